@@ -13,9 +13,9 @@ fn protect_universal_impl(args: TokenStream, input: TokenStream, http_method: &s
     let args = parse_macro_input!(args with Punctuated::<Expr, Token![,]>::parse_terminated);
     let input_fn = parse_macro_input!(input as ItemFn);
 
-    // Parse arguments: path and permission, with optional route attributes
-    let (path, permission, route_attrs) = match parse_protect_args_extended(&args) {
-        Ok((p, perm, attrs)) => (p, perm, attrs),
+    // Parse arguments: path and permission requirement, with optional rate limit and route attributes
+    let (path, permission, rate_limit, route_attrs) = match parse_protect_args_extended(&args) {
+        Ok((p, perm, limit, attrs)) => (p, perm, limit, attrs),
         Err(err) => {
             return syn::Error::new_spanned(&input_fn, err)
                 .to_compile_error()
@@ -30,6 +30,17 @@ fn protect_universal_impl(args: TokenStream, input: TokenStream, http_method: &s
     let fn_output = &input_fn.sig.output;
     let fn_attrs = &input_fn.attrs;
     let fn_asyncness = &input_fn.sig.asyncness;
+    let permission_check = permission.check();
+    let granted_binding = permission.granted_binding();
+    let rate_limit_guard = rate_limit_guard(rate_limit.unwrap_or(0));
+    // Handlers declaring their own bearer only get a rate limit guard when asked for one
+    let bearer_fn_inputs = match rate_limit {
+        Some(_) => {
+            let inputs = fn_inputs.iter();
+            quote! { #(#inputs,)* #rate_limit_guard }
+        }
+        None => quote! { #fn_inputs },
+    };
 
     // Extract the return type from the function signature
     let return_type = match fn_output {
@@ -105,12 +116,14 @@ fn protect_universal_impl(args: TokenStream, input: TokenStream, http_method: &s
         quote! {
             #(#fn_attrs)*
             #rocket_attr
-            #fn_vis #fn_asyncness fn #fn_name(#fn_inputs) -> rocket::Either<rocket::response::status::Forbidden<&'static str>, #return_type> {
+            #fn_vis #fn_asyncness fn #fn_name(#bearer_fn_inputs) -> rocket::Either<rocket::response::status::Forbidden<&'static str>, #return_type> {
                 // Check permission first
-                if !bearer.has_permission(#permission) {
+                if !#permission_check {
                     return rocket::Either::Left(rocket::response::status::Forbidden("Permission denied"));
                 }
 
+                #granted_binding
+
                 // Call original function and wrap in Either::Right
                 rocket::Either::Right(#fn_block)
             }
@@ -123,13 +136,16 @@ fn protect_universal_impl(args: TokenStream, input: TokenStream, http_method: &s
             #rocket_attr
             #fn_vis #fn_asyncness fn #fn_name(
                 bearer: crate::visualization::auth::guards::OAuthBearer,
+                #rate_limit_guard,
                 #fn_inputs
             ) -> rocket::Either<rocket::response::status::Forbidden<&'static str>, #return_type> {
                 // Check permission
-                if !bearer.has_permission(#permission) {
+                if !#permission_check {
                     return rocket::Either::Left(rocket::response::status::Forbidden("Permission denied"));
                 }
 
+                #granted_binding
+
                 // Call original function and wrap in Either::Right - the bearer variable is now available in scope
                 rocket::Either::Right(#fn_block)
             }
@@ -160,8 +176,47 @@ fn protect_universal_impl(args: TokenStream, input: TokenStream, http_method: &s
 /// fn handler_name() -> SomeResponse {
 ///     // Your handler code here
 /// }
+///
+/// // Accessible with any one of several permissions
+/// #[protect_get("/path", any = ["read:api", "admin:api"])]
+/// fn handler_name() -> SomeResponse {
+///     // Your handler code here
+/// }
+///
+/// // Requires every listed permission
+/// #[protect_get("/path", all = ["read:api", "write:api"])]
+/// fn handler_name() -> SomeResponse {
+///     // Your handler code here
+/// }
+///
+/// // At most 10 requests per configured rate limit window for each client
+/// #[protect_get("/path", "permission:scope", rate_limit = 10)]
+/// fn handler_name() -> SomeResponse {
+///     // Your handler code here
+/// }
 /// ```
 ///
+/// ### Rate Limiting
+///
+/// A `RateLimit` request guard is added after the bearer. Without
+/// `rate_limit = N` it applies the limit configured in
+/// `visualization.rate_limit` (when enabled); with it, `N` requests per window
+/// are allowed regardless of the global switch. Exceeding the limit returns
+/// HTTP 429 with a `Retry-After` header. Handlers declaring their own
+/// `OAuthBearer` only get the guard when `rate_limit` is given.
+///
+/// ### Credentials
+///
+/// The bearer accepts either an `Authorization: Bearer <token>` header or,
+/// for machine clients, an `X-API-Key` header holding a key configured in
+/// `access.api_keys`. The permission check is the same for both.
+///
+/// ### Variables Available in the Handler
+///
+/// - `bearer`: the validated `OAuthBearer`
+/// - `granted_permissions`: a `Vec<&'static str>` listing the required
+///   permissions held by the bearer, e.g. the ones that matched an `any` list
+///
 /// ### Supported Route Grammar
 ///
 /// The macro supports the full Rocket route grammar including:
@@ -269,6 +324,147 @@ pub fn protect_patch(args: TokenStream, input: TokenStream) -> TokenStream {
     protect_universal_impl(args, input, "patch")
 }
 
+/// Permission requirement checked by the protection macros
+#[derive(Debug, PartialEq)]
+enum PermissionRequirement {
+    /// A single permission: `"read:api"`
+    Single(String),
+    /// At least one of the permissions: `any = ["read:api", "admin:api"]`
+    Any(Vec<String>),
+    /// Every one of the permissions: `all = ["read:api", "write:api"]`
+    All(Vec<String>),
+}
+
+impl PermissionRequirement {
+    /// Generate the boolean expression checking the requirement against `bearer`
+    fn check(&self) -> proc_macro2::TokenStream {
+        match self {
+            PermissionRequirement::Single(permission) => {
+                quote! { bearer.has_permission(#permission) }
+            }
+            PermissionRequirement::Any(permissions) => {
+                quote! { bearer.has_any_permission(&[#(#permissions),*]) }
+            }
+            PermissionRequirement::All(permissions) => {
+                quote! { bearer.has_all_permissions(&[#(#permissions),*]) }
+            }
+        }
+    }
+
+    /// Generate the `granted_permissions` binding exposed to the handler body
+    ///
+    /// It lists the required permissions held by the bearer, in declaration order.
+    fn granted_binding(&self) -> proc_macro2::TokenStream {
+        let permissions = match self {
+            PermissionRequirement::Single(permission) => std::slice::from_ref(permission),
+            PermissionRequirement::Any(permissions) | PermissionRequirement::All(permissions) => {
+                permissions.as_slice()
+            }
+        };
+        quote! {
+            #[allow(unused_variables)]
+            let granted_permissions: Vec<&'static str> = [#(#permissions),*]
+                .into_iter()
+                .filter(|permission| bearer.has_permission(permission))
+                .collect();
+        }
+    }
+}
+
+/// Parse the permission argument of the protection macros
+///
+/// Accepts either a string literal, `any = ["permission", ...]` or
+/// `all = ["permission", ...]`.
+fn parse_permission_arg(arg: &Expr) -> Result<PermissionRequirement, String> {
+    const EXPECTED: &str =
+        "Second argument (permission) must be a string literal, `any = [...]` or `all = [...]`";
+
+    match arg {
+        Expr::Lit(expr_lit) => match &expr_lit.lit {
+            Lit::Str(lit_str) => Ok(PermissionRequirement::Single(lit_str.value())),
+            _ => Err(EXPECTED.to_string()),
+        },
+        Expr::Assign(assign) => {
+            let key = match &*assign.left {
+                Expr::Path(path) if path.path.segments.len() == 1 => {
+                    path.path.segments[0].ident.to_string()
+                }
+                _ => return Err(EXPECTED.to_string()),
+            };
+            let permissions = parse_permission_list(&assign.right)?;
+            match key.as_str() {
+                "any" => Ok(PermissionRequirement::Any(permissions)),
+                "all" => Ok(PermissionRequirement::All(permissions)),
+                _ => Err(format!(
+                    "Unknown permission requirement `{}`, expected `any` or `all`",
+                    key
+                )),
+            }
+        }
+        _ => Err(EXPECTED.to_string()),
+    }
+}
+
+/// Parse a non-empty array of permission string literals
+fn parse_permission_list(expr: &Expr) -> Result<Vec<String>, String> {
+    let elems = match expr {
+        Expr::Array(array) => &array.elems,
+        _ => return Err("Permission list must be an array of string literals".to_string()),
+    };
+
+    let permissions = elems
+        .iter()
+        .map(|elem| match elem {
+            Expr::Lit(syn::ExprLit {
+                lit: Lit::Str(lit_str),
+                ..
+            }) => Ok(lit_str.value()),
+            _ => Err("Permission list must only contain string literals".to_string()),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if permissions.is_empty() {
+        return Err("Permission list must not be empty".to_string());
+    }
+    Ok(permissions)
+}
+
+/// Parse a `rate_limit = N` argument
+///
+/// Returns `Ok(None)` when `arg` is not a `rate_limit` assignment.
+fn parse_rate_limit_arg(arg: &Expr) -> Result<Option<u32>, String> {
+    let assign = match arg {
+        Expr::Assign(assign) => assign,
+        _ => return Ok(None),
+    };
+    match &*assign.left {
+        Expr::Path(path) if path.path.is_ident("rate_limit") => {}
+        _ => return Ok(None),
+    }
+
+    let limit = match &*assign.right {
+        Expr::Lit(syn::ExprLit {
+            lit: Lit::Int(lit_int),
+            ..
+        }) => lit_int
+            .base10_parse::<u32>()
+            .map_err(|_| "rate_limit value must be a u32 integer literal".to_string())?,
+        _ => return Err("rate_limit value must be an integer literal".to_string()),
+    };
+    if limit == 0 {
+        return Err("rate_limit value must be greater than 0".to_string());
+    }
+    Ok(Some(limit))
+}
+
+/// Generate the rate limit request guard parameter
+///
+/// A `requests` of 0 uses the globally configured limit.
+fn rate_limit_guard(requests: u32) -> proc_macro2::TokenStream {
+    let requests = proc_macro2::Literal::u32_unsuffixed(requests);
+    quote! { _rate_limit: crate::visualization::auth::guards::RateLimit<#requests> }
+}
+
 /// Parse the arguments for protection macros
 #[allow(dead_code)]
 fn parse_protect_args(args: &Punctuated<Expr, Token![,]>) -> Result<(String, String), String> {
@@ -304,10 +500,19 @@ fn parse_protect_args(args: &Punctuated<Expr, Token![,]>) -> Result<(String, Str
 }
 
 /// Parse the arguments for protection macros with extended route attributes support
-/// Supports: path, permission, and optional route attributes like rank, format, data
+/// Supports: path, permission, an optional `rate_limit = N` and optional route
+/// attributes like rank, format, data
 fn parse_protect_args_extended(
     args: &Punctuated<Expr, Token![,]>,
-) -> Result<(String, String, proc_macro2::TokenStream), String> {
+) -> Result<
+    (
+        String,
+        PermissionRequirement,
+        Option<u32>,
+        proc_macro2::TokenStream,
+    ),
+    String,
+> {
     if args.len() < 2 {
         return Err(
             "Protection macros require at least 2 arguments: path and permission".to_string(),
@@ -325,32 +530,39 @@ fn parse_protect_args_extended(
         _ => return Err("First argument (path) must be a string literal".to_string()),
     };
 
-    let permission = match &args[1] {
-        Expr::Lit(expr_lit) => {
-            if let Lit::Str(lit_str) = &expr_lit.lit {
-                lit_str.value()
-            } else {
-                return Err("Second argument (permission) must be a string literal".to_string());
-            }
-        }
-        _ => return Err("Second argument (permission) must be a string literal".to_string()),
-    };
+    let permission = parse_permission_arg(&args[1])?;
 
     // Collect remaining arguments as route attributes (rank, format, data, etc.)
-    let route_attrs = if args.len() > 2 {
-        let remaining_args: Vec<_> = args.iter().skip(2).collect();
+    let mut rate_limit = None;
+    let mut remaining_args = Vec::new();
+    for arg in args.iter().skip(2) {
+        match parse_rate_limit_arg(arg)? {
+            Some(limit) => rate_limit = Some(limit),
+            None => remaining_args.push(arg),
+        }
+    }
+    let route_attrs = if !remaining_args.is_empty() {
         quote::quote! { #(#remaining_args),* }
     } else {
         proc_macro2::TokenStream::new()
     };
 
-    Ok((path, permission, route_attrs))
+    Ok((path, permission, rate_limit, route_attrs))
 }
 
 /// Parse the arguments for OpenAPI protection macros with optional tag and route attributes
 fn parse_openapi_protect_args(
     args: &Punctuated<Expr, Token![,]>,
-) -> Result<(String, String, Option<String>, proc_macro2::TokenStream), String> {
+) -> Result<
+    (
+        String,
+        PermissionRequirement,
+        Option<String>,
+        Option<u32>,
+        proc_macro2::TokenStream,
+    ),
+    String,
+> {
     if args.len() < 2 {
         return Err(
             format!("OpenAPI protection macros require at least 2 arguments: path and permission. Got {} arguments.", args.len())
@@ -368,22 +580,18 @@ fn parse_openapi_protect_args(
         _ => return Err("First argument (path) must be a string literal".to_string()),
     };
 
-    let permission = match &args[1] {
-        Expr::Lit(expr_lit) => {
-            if let Lit::Str(lit_str) = &expr_lit.lit {
-                lit_str.value()
-            } else {
-                return Err("Second argument (permission) must be a string literal".to_string());
-            }
-        }
-        _ => return Err("Second argument (permission) must be a string literal".to_string()),
-    };
+    let permission = parse_permission_arg(&args[1])?;
 
-    // Look for tag assignment and collect other route attributes
+    // Look for tag and rate_limit assignments and collect other route attributes
     let mut tag = None;
+    let mut rate_limit = None;
     let mut route_attrs = Vec::new();
 
     for arg in args.iter().skip(2) {
+        if let Some(limit) = parse_rate_limit_arg(arg)? {
+            rate_limit = Some(limit);
+            continue;
+        }
         match arg {
             Expr::Assign(assign) => {
                 // Check if left side is specifically "tag"
@@ -418,7 +626,7 @@ fn parse_openapi_protect_args(
         proc_macro2::TokenStream::new()
     };
 
-    Ok((path, permission, tag, route_attrs_tokens))
+    Ok((path, permission, tag, rate_limit, route_attrs_tokens))
 }
 
 /// Internal function that implements the combined OpenAPI + protection logic for all HTTP methods
@@ -430,9 +638,9 @@ fn openapi_protect_universal_impl(
     let args = parse_macro_input!(args with Punctuated::<Expr, Token![,]>::parse_terminated);
     let input_fn = parse_macro_input!(input as ItemFn);
 
-    // Parse arguments: path, permission, optional tag, and route attributes
-    let (path, permission, tag, route_attrs) = match parse_openapi_protect_args(&args) {
-        Ok((p, perm, t, attrs)) => (p, perm, t, attrs),
+    // Parse arguments: path, permission requirement, optional tag, rate limit and route attributes
+    let (path, permission, tag, rate_limit, route_attrs) = match parse_openapi_protect_args(&args) {
+        Ok((p, perm, t, limit, attrs)) => (p, perm, t, limit, attrs),
         Err(err) => {
             return syn::Error::new_spanned(&input_fn, err)
                 .to_compile_error()
@@ -447,6 +655,17 @@ fn openapi_protect_universal_impl(
     let fn_output = &input_fn.sig.output;
     let fn_attrs = &input_fn.attrs;
     let fn_asyncness = &input_fn.sig.asyncness;
+    let permission_check = permission.check();
+    let granted_binding = permission.granted_binding();
+    let rate_limit_guard = rate_limit_guard(rate_limit.unwrap_or(0));
+    // Handlers declaring their own bearer only get a rate limit guard when asked for one
+    let bearer_fn_inputs = match rate_limit {
+        Some(_) => {
+            let inputs = fn_inputs.iter();
+            quote! { #(#inputs,)* #rate_limit_guard }
+        }
+        None => quote! { #fn_inputs },
+    };
 
     // Extract the return type from the function signature
     let return_type = match fn_output {
@@ -531,12 +750,14 @@ fn openapi_protect_universal_impl(
             #(#fn_attrs)*
             #openapi_attr
             #rocket_attr
-            #fn_vis #fn_asyncness fn #fn_name(#fn_inputs) -> rocket::Either<rocket::response::status::Forbidden<&'static str>, #return_type> {
+            #fn_vis #fn_asyncness fn #fn_name(#bearer_fn_inputs) -> rocket::Either<rocket::response::status::Forbidden<&'static str>, #return_type> {
                 // Check permission first
-                if !bearer.has_permission(#permission) {
+                if !#permission_check {
                     return rocket::Either::Left(rocket::response::status::Forbidden("Permission denied"));
                 }
 
+                #granted_binding
+
                 // Call original function and wrap in Either::Right
                 rocket::Either::Right(#fn_block)
             }
@@ -551,13 +772,16 @@ fn openapi_protect_universal_impl(
             #rocket_attr
             #fn_vis #fn_asyncness fn #fn_name(
                 bearer: crate::visualization::auth::guards::OAuthBearer,
+                #rate_limit_guard,
                 #fn_inputs
             ) -> rocket::Either<rocket::response::status::Forbidden<&'static str>, #return_type> {
                 // Check permission
-                if !bearer.has_permission(#permission) {
+                if !#permission_check {
                     return rocket::Either::Left(rocket::response::status::Forbidden("Permission denied"));
                 }
 
+                #granted_binding
+
                 // Call original function and wrap in Either::Right - the bearer variable is now available in scope
                 rocket::Either::Right(#fn_block)
             }
@@ -594,13 +818,30 @@ fn openapi_protect_universal_impl(
 /// fn get_user(id: u32) -> Json<User> {
 ///     // Full route grammar support
 /// }
+///
+/// // Accessible with any one of several permissions
+/// #[openapi_protect_get("/path", any = ["read:api", "admin:api"], tag = "Custom Tag")]
+/// fn handler_name() -> SomeResponse {
+///     // The 'granted_permissions' variable lists the matched permissions
+///     log::debug!("Authorized by {:?}", granted_permissions);
+/// }
+///
+/// // Requires every listed permission
+/// #[openapi_protect_get("/path", all = ["read:api", "write:api"])]
+/// fn handler_name() -> SomeResponse {
+///     // Your handler code here
+/// }
 /// ```
 ///
 /// ### Parameters
 ///
 /// - `path`: The route path (required) - supports full Rocket route grammar
-/// - `permission`: The required permission string (required)
+/// - `permission`: The required permission string, `any = [...]` to accept
+///   any one of several permissions, or `all = [...]` to require every one
+///   of them (required)
 /// - `tag`: Optional OpenAPI tag for grouping endpoints in documentation
+/// - `rate_limit`: Optional number of requests allowed per rate limit window,
+///   overriding `visualization.rate_limit.requests`
 /// - Additional route attributes: `rank`, `format`, `data`, etc.
 ///
 /// ### Supported Route Grammar
@@ -620,6 +861,8 @@ fn openapi_protect_universal_impl(
 /// - Supports optional tag parameter for OpenAPI documentation organization
 /// - Supports all Rocket route attributes for advanced routing
 /// - Adds permission checking logic
+/// - Exposes the held required permissions as `granted_permissions`
+/// - Adds a `RateLimit` guard returning HTTP 429 with `Retry-After` when exceeded
 /// - Returns HTTP 403 Forbidden if permission is denied
 /// - Uses `rocket::Either` for proper response type handling
 ///
@@ -680,7 +923,7 @@ pub fn openapi_protect_get(args: TokenStream, input: TokenStream) -> TokenStream
 /// ### Parameters
 ///
 /// - `path`: The route path (required)
-/// - `permission`: The required permission string (required)
+/// - `permission`: The required permission string, `any = [...]` or `all = [...]` (required)
 /// - `tag`: Optional OpenAPI tag for grouping endpoints in documentation
 ///
 /// ### Features
@@ -722,7 +965,7 @@ pub fn openapi_protect_post(args: TokenStream, input: TokenStream) -> TokenStrea
 /// ### Parameters
 ///
 /// - `path`: The route path (required)
-/// - `permission`: The required permission string (required)
+/// - `permission`: The required permission string, `any = [...]` or `all = [...]` (required)
 /// - `tag`: Optional OpenAPI tag for grouping endpoints in documentation
 ///
 /// ### Features
@@ -764,7 +1007,7 @@ pub fn openapi_protect_put(args: TokenStream, input: TokenStream) -> TokenStream
 /// ### Parameters
 ///
 /// - `path`: The route path (required)
-/// - `permission`: The required permission string (required)
+/// - `permission`: The required permission string, `any = [...]` or `all = [...]` (required)
 /// - `tag`: Optional OpenAPI tag for grouping endpoints in documentation
 ///
 /// ### Features
@@ -806,7 +1049,7 @@ pub fn openapi_protect_delete(args: TokenStream, input: TokenStream) -> TokenStr
 /// ### Parameters
 ///
 /// - `path`: The route path (required)
-/// - `permission`: The required permission string (required)
+/// - `permission`: The required permission string, `any = [...]` or `all = [...]` (required)
 /// - `tag`: Optional OpenAPI tag for grouping endpoints in documentation
 ///
 /// ### Features
@@ -821,3 +1064,147 @@ pub fn openapi_protect_delete(args: TokenStream, input: TokenStream) -> TokenStr
 pub fn openapi_protect_patch(args: TokenStream, input: TokenStream) -> TokenStream {
     openapi_protect_universal_impl(args, input, "patch")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &str) -> Punctuated<Expr, Token![,]> {
+        syn::parse::Parser::parse_str(Punctuated::<Expr, Token![,]>::parse_terminated, args)
+            .expect("valid macro arguments")
+    }
+
+    #[test]
+    fn test_single_permission() {
+        let args = parse_args(r#""/api/data", "read:api""#);
+        let (path, permission, rate_limit, route_attrs) =
+            parse_protect_args_extended(&args).unwrap();
+
+        assert_eq!(path, "/api/data");
+        assert_eq!(rate_limit, None);
+        assert_eq!(
+            permission,
+            PermissionRequirement::Single("read:api".to_string())
+        );
+        assert!(route_attrs.is_empty());
+        assert_eq!(
+            permission.check().to_string(),
+            quote! { bearer.has_permission("read:api") }.to_string()
+        );
+    }
+
+    #[test]
+    fn test_any_permission() {
+        let args = parse_args(r#""/api/data", any = ["read:api", "admin:all"], rank = 2"#);
+        let (_, permission, _, route_attrs) = parse_protect_args_extended(&args).unwrap();
+
+        assert_eq!(
+            permission,
+            PermissionRequirement::Any(vec!["read:api".to_string(), "admin:all".to_string()])
+        );
+        assert_eq!(route_attrs.to_string(), quote! { rank = 2 }.to_string());
+        assert_eq!(
+            permission.check().to_string(),
+            quote! { bearer.has_any_permission(&["read:api", "admin:all"]) }.to_string()
+        );
+    }
+
+    #[test]
+    fn test_openapi_any_permission_with_tag() {
+        let args = parse_args(r#""/api/data", any = ["read:api", "admin:all"], tag = "Data""#);
+        let (_, permission, tag, _, route_attrs) = parse_openapi_protect_args(&args).unwrap();
+
+        assert!(matches!(permission, PermissionRequirement::Any(ref p) if p.len() == 2));
+        assert_eq!(tag.as_deref(), Some("Data"));
+        assert!(route_attrs.is_empty());
+    }
+
+    #[test]
+    fn test_all_permissions() {
+        let args = parse_args(r#""/api/data", all = ["read:api", "write:api"]"#);
+        let (_, permission, _, _) = parse_protect_args_extended(&args).unwrap();
+
+        assert_eq!(
+            permission,
+            PermissionRequirement::All(vec!["read:api".to_string(), "write:api".to_string()])
+        );
+        assert_eq!(
+            permission.check().to_string(),
+            quote! { bearer.has_all_permissions(&["read:api", "write:api"]) }.to_string()
+        );
+    }
+
+    #[test]
+    fn test_granted_permissions_binding() {
+        let permission = PermissionRequirement::Single("read:api".to_string());
+        assert_eq!(
+            permission.granted_binding().to_string(),
+            quote! {
+                #[allow(unused_variables)]
+                let granted_permissions: Vec<&'static str> = ["read:api"]
+                    .into_iter()
+                    .filter(|permission| bearer.has_permission(permission))
+                    .collect();
+            }
+            .to_string()
+        );
+
+        let permission =
+            PermissionRequirement::All(vec!["read:api".to_string(), "write:api".to_string()]);
+        assert!(permission
+            .granted_binding()
+            .to_string()
+            .contains(&quote! { ["read:api", "write:api"] }.to_string()));
+    }
+
+    #[test]
+    fn test_rate_limit_argument() {
+        let args = parse_args(r#""/api/data", "read:api", rate_limit = 5, rank = 2"#);
+        let (_, _, rate_limit, route_attrs) = parse_protect_args_extended(&args).unwrap();
+        assert_eq!(rate_limit, Some(5));
+        assert_eq!(route_attrs.to_string(), quote! { rank = 2 }.to_string());
+
+        let args = parse_args(r#""/api/data", "read:api", tag = "Data", rate_limit = 5"#);
+        let (_, _, tag, rate_limit, route_attrs) = parse_openapi_protect_args(&args).unwrap();
+        assert_eq!(tag.as_deref(), Some("Data"));
+        assert_eq!(rate_limit, Some(5));
+        assert!(route_attrs.is_empty());
+
+        assert_eq!(
+            rate_limit_guard(5).to_string(),
+            quote! { _rate_limit: crate::visualization::auth::guards::RateLimit<5> }.to_string()
+        );
+    }
+
+    #[test]
+    fn test_invalid_rate_limit() {
+        for args in [
+            r#""/api/data", "read:api", rate_limit = 0"#,
+            r#""/api/data", "read:api", rate_limit = "5""#,
+            r#""/api/data", "read:api", rate_limit = -1"#,
+        ] {
+            assert!(
+                parse_protect_args_extended(&parse_args(args)).is_err(),
+                "{} must be rejected",
+                args
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_permission_lists() {
+        for args in [
+            r#""/api/data", any = []"#,
+            r#""/api/data", any = [read_api]"#,
+            r#""/api/data", any = "read:api""#,
+            r#""/api/data", some = ["read:api"]"#,
+            r#""/api/data", all = []"#,
+        ] {
+            assert!(
+                parse_protect_args_extended(&parse_args(args)).is_err(),
+                "{} must be rejected",
+                args
+            );
+        }
+    }
+}
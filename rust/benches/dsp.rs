@@ -0,0 +1,597 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Criterion benchmarks for the DSP hot paths
+//!
+//! | Group | What it measures |
+//! |---|---|
+//! | `filter/<type>/order_<n>` | [`Filter::apply`] over blocks of 256, 1024 and 4096 samples |
+//! | `fft/analyze` | [`FFTAnalyzer::analyze`] for windows of 512 to 8192 points, with a warm plan cache |
+//! | `fft/analyze_cold` | The same analysis on a fresh analyzer, which builds its plan and buffers |
+//! | `fft/sliding_dft` | [`SlidingDftAnalyzer::analyze`] on 441-sample blocks of a 4096-point window, all bins against a 400 Hz band |
+//! | `filter_simd/<type>/order_<n>` | `apply_scalar` against the SIMD `apply` on 4096 samples, with the `simd` feature |
+//! | `graph/execute` | [`ProcessingGraph::execute`] on a mixer → bandpass → peak finder → output graph |
+//! | `graph/execute_fan_out` | Serial against parallel execution of four Butterworth branches on 16384 samples |
+//! | `graph/buffer_reuse` | Allocating against pooled execution of a fan-out graph of standard filters, printing the allocations per frame |
+//!
+//! Benchmark ids are stable so that runs can be compared against a saved
+//! baseline:
+//!
+//! ```text
+//! cargo bench --bench dsp -- --save-baseline main
+//! cargo bench --bench dsp -- --baseline main
+//! ```
+//!
+//! Every benchmark first checks that its function produces finite output of
+//! the expected size, so `cargo bench --bench dsp -- --test` doubles as a
+//! smoke test.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rust_photoacoustic::acquisition::AudioFrame;
+use rust_photoacoustic::config::processing::{ConnectionConfig, NodeConfig, ProcessingGraphConfig};
+use rust_photoacoustic::preprocessing::filter::{
+    BandpassFilter, ButterBandpassFilter, Filter, LowpassFilter,
+};
+use rust_photoacoustic::processing::{ProcessingData, ProcessingGraph};
+use rust_photoacoustic::spectral::fft::{FFTAnalyzer, SpectralAnalyzer};
+use rust_photoacoustic::spectral::sliding_dft::SlidingDftAnalyzer;
+use rust_photoacoustic::utility::noise_generator::NoiseGenerator;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::f32::consts::TAU;
+use std::hint::black_box;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Sample rate of the graph built by [`ProcessingGraph::from_config`]
+const SAMPLE_RATE: u32 = 44100;
+const BLOCK_SIZES: [usize; 3] = [256, 1024, 4096];
+const WINDOW_SIZES: [usize; 5] = [512, 1024, 2048, 4096, 8192];
+/// Frame size of the graph benchmark, matching the default FFT size
+const GRAPH_FRAME_SIZE: usize = 4096;
+
+/// Global allocator counting the allocations, for the buffer reuse benchmark
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size as u64, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Number of allocations and allocated bytes so far
+fn allocation_counters() -> (u64, u64) {
+    (
+        ALLOCATIONS.load(Ordering::Relaxed),
+        ALLOCATED_BYTES.load(Ordering::Relaxed),
+    )
+}
+
+/// A 2 kHz tone of amplitude 0.5 in 0.01 RMS Gaussian noise
+fn test_signal(len: usize, seed: u32) -> Vec<f32> {
+    let mut noise = NoiseGenerator::new(seed);
+    (0..len)
+        .map(|n| {
+            let t = n as f32 / SAMPLE_RATE as f32;
+            0.5 * (TAU * 2000.0 * t).sin() + 0.01 * noise.random_gaussian()
+        })
+        .collect()
+}
+
+fn assert_finite(what: &str, samples: &[f32]) {
+    assert!(
+        samples.iter().all(|s| s.is_finite()),
+        "{} produced non-finite output",
+        what
+    );
+}
+
+fn bench_filter_group(
+    c: &mut Criterion,
+    name: &str,
+    orders: &[usize],
+    build: impl Fn(usize) -> Box<dyn Filter>,
+) {
+    for &order in orders {
+        let mut group = c.benchmark_group(format!("filter/{}/order_{}", name, order));
+        for &block_size in &BLOCK_SIZES {
+            let filter = build(order);
+            let signal = test_signal(block_size, 1);
+
+            let output = filter.apply(&signal);
+            assert_eq!(output.len(), block_size);
+            assert_finite(&format!("{} order {}", name, order), &output);
+
+            group.throughput(Throughput::Elements(block_size as u64));
+            group.bench_with_input(
+                BenchmarkId::from_parameter(block_size),
+                &signal,
+                |b, signal| b.iter(|| filter.apply(black_box(signal))),
+            );
+        }
+        group.finish();
+    }
+}
+
+fn bench_filters(c: &mut Criterion) {
+    bench_filter_group(c, "bandpass", &[2, 4, 8], |order| {
+        Box::new(
+            BandpassFilter::new(2000.0, 200.0)
+                .with_sample_rate(SAMPLE_RATE)
+                .with_order(order),
+        )
+    });
+    bench_filter_group(c, "lowpass", &[1, 2, 4], |order| {
+        Box::new(
+            LowpassFilter::new(5000.0)
+                .with_sample_rate(SAMPLE_RATE)
+                .with_order(order),
+        )
+    });
+    bench_filter_group(c, "butter_bandpass", &[2, 4, 8], |order| {
+        Box::new(ButterBandpassFilter::new(
+            1900.0,
+            2100.0,
+            SAMPLE_RATE as f64,
+            order,
+        ))
+    });
+}
+
+/// Compare the scalar loop with the SIMD lanes of the standard filters
+///
+/// Run with `cargo bench --bench dsp --features simd -- filter_simd`.
+#[cfg(feature = "simd")]
+fn bench_filters_simd(c: &mut Criterion) {
+    use rust_photoacoustic::preprocessing::filter::HighpassFilter;
+
+    const BLOCK_SIZE: usize = 4096;
+    let signal = test_signal(BLOCK_SIZE, 1);
+
+    for order in [4, 8] {
+        let bandpass = BandpassFilter::new(2000.0, 200.0)
+            .with_sample_rate(SAMPLE_RATE)
+            .with_order(order);
+        let lowpass = LowpassFilter::new(5000.0)
+            .with_sample_rate(SAMPLE_RATE)
+            .with_order(order);
+        let highpass = HighpassFilter::new(500.0)
+            .with_sample_rate(SAMPLE_RATE)
+            .with_order(order);
+
+        assert_finite("SIMD bandpass", &bandpass.apply(&signal));
+        assert_finite("SIMD lowpass", &lowpass.apply(&signal));
+        assert_finite("SIMD highpass", &highpass.apply(&signal));
+
+        let mut group = c.benchmark_group(format!("filter_simd/bandpass/order_{}", order));
+        group.throughput(Throughput::Elements(BLOCK_SIZE as u64));
+        group.bench_function("scalar", |b| {
+            b.iter(|| bandpass.apply_scalar(black_box(&signal)))
+        });
+        group.bench_function("simd", |b| b.iter(|| bandpass.apply(black_box(&signal))));
+        group.finish();
+
+        let mut group = c.benchmark_group(format!("filter_simd/lowpass/order_{}", order));
+        group.throughput(Throughput::Elements(BLOCK_SIZE as u64));
+        group.bench_function("scalar", |b| {
+            b.iter(|| lowpass.apply_scalar(black_box(&signal)))
+        });
+        group.bench_function("simd", |b| b.iter(|| lowpass.apply(black_box(&signal))));
+        group.finish();
+
+        let mut group = c.benchmark_group(format!("filter_simd/highpass/order_{}", order));
+        group.throughput(Throughput::Elements(BLOCK_SIZE as u64));
+        group.bench_function("scalar", |b| {
+            b.iter(|| highpass.apply_scalar(black_box(&signal)))
+        });
+        group.bench_function("simd", |b| b.iter(|| highpass.apply(black_box(&signal))));
+        group.finish();
+    }
+}
+
+#[cfg(not(feature = "simd"))]
+fn bench_filters_simd(_c: &mut Criterion) {}
+
+fn bench_fft(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fft/analyze");
+    for &window_size in &WINDOW_SIZES {
+        let mut analyzer = FFTAnalyzer::new(window_size, 1);
+        let signal = test_signal(window_size, 2);
+
+        let spectrum = analyzer
+            .analyze(&signal, SAMPLE_RATE)
+            .expect("spectrum analyzed");
+        assert_eq!(spectrum.amplitudes.len(), spectrum.frequencies.len());
+        assert!(!spectrum.amplitudes.is_empty());
+        assert_finite("FFT amplitudes", &spectrum.amplitudes);
+        assert_finite("FFT phases", &spectrum.phases);
+
+        group.throughput(Throughput::Elements(window_size as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(window_size),
+            &signal,
+            |b, signal| b.iter(|| analyzer.analyze(black_box(signal), SAMPLE_RATE)),
+        );
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("fft/analyze_cold");
+    for &window_size in &WINDOW_SIZES {
+        let signal = test_signal(window_size, 2);
+        group.throughput(Throughput::Elements(window_size as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(window_size),
+            &signal,
+            |b, signal| {
+                b.iter(|| FFTAnalyzer::new(window_size, 1).analyze(black_box(signal), SAMPLE_RATE))
+            },
+        );
+    }
+    group.finish();
+
+    // Streaming cost of a 10 ms block on a 4096-point sliding window
+    const STREAM_BLOCK: usize = 441;
+    let signal = test_signal(STREAM_BLOCK, 2);
+    let mut group = c.benchmark_group("fft/sliding_dft");
+    group.throughput(Throughput::Elements(STREAM_BLOCK as u64));
+    for (name, analyzer) in [
+        ("all_bins", SlidingDftAnalyzer::new(4096, 1)),
+        (
+            "band_1800_2200",
+            SlidingDftAnalyzer::new(4096, 1).with_frequency_range(1800.0, 2200.0),
+        ),
+    ] {
+        let mut analyzer = analyzer;
+        let spectrum = analyzer
+            .analyze(&signal, SAMPLE_RATE)
+            .expect("spectrum analyzed");
+        assert_finite("sliding DFT amplitudes", &spectrum.amplitudes);
+        group.bench_function(name, |b| {
+            b.iter(|| analyzer.analyze(black_box(&signal), SAMPLE_RATE))
+        });
+    }
+    group.finish();
+}
+
+/// The acquisition path of the default graph, without recording or streaming
+fn representative_graph() -> ProcessingGraphConfig {
+    let node = |id: &str, node_type: &str, parameters: serde_json::Value| NodeConfig {
+        id: id.to_string(),
+        node_type: node_type.to_string(),
+        parameters,
+    };
+    let connection = |from: &str, to: &str| ConnectionConfig {
+        from: from.to_string(),
+        to: to.to_string(),
+    };
+    ProcessingGraphConfig {
+        id: "bench".to_string(),
+        nodes: vec![
+            node("input", "input", serde_json::Value::Null),
+            node(
+                "mixer",
+                "channel_mixer",
+                serde_json::json!({"strategy": "add"}),
+            ),
+            node(
+                "bandpass",
+                "filter",
+                serde_json::json!({
+                    "type": "butter_bandpass",
+                    "center_frequency": 2000.0,
+                    "bandwidth": 200.0,
+                    "order": 4
+                }),
+            ),
+            node(
+                "peak_finder",
+                "computing_peak_finder",
+                serde_json::json!({
+                    "detection_threshold": 0.1,
+                    "frequency_min": 1800.0,
+                    "frequency_max": 2200.0
+                }),
+            ),
+            node(
+                "output",
+                "photoacoustic_output",
+                serde_json::json!({"detection_threshold": 0.05}),
+            ),
+        ],
+        connections: vec![
+            connection("input", "mixer"),
+            connection("mixer", "bandpass"),
+            connection("bandpass", "peak_finder"),
+            connection("peak_finder", "output"),
+        ],
+        output_node: Some("output".to_string()),
+    }
+}
+
+fn bench_graph(c: &mut Criterion) {
+    let mut graph = ProcessingGraph::from_config(&representative_graph()).expect("graph built");
+    let channel_a = test_signal(GRAPH_FRAME_SIZE, 3);
+    let channel_b = test_signal(GRAPH_FRAME_SIZE, 4);
+    let frame = |frame_number: u64| {
+        ProcessingData::AudioFrame(AudioFrame {
+            channel_a: channel_a.clone(),
+            channel_b: channel_b.clone(),
+            sample_rate: SAMPLE_RATE,
+            timestamp: 1000 + frame_number * 93,
+            frame_number,
+        })
+    };
+
+    let outputs = graph.execute(frame(0)).expect("graph executed");
+    match &outputs[..] {
+        [ProcessingData::PhotoacousticResult { signal, .. }] => {
+            assert_eq!(signal.len(), GRAPH_FRAME_SIZE);
+            assert_finite("graph output", signal);
+        }
+        _ => panic!("unexpected graph output"),
+    }
+
+    let mut group = c.benchmark_group("graph/execute");
+    group.throughput(Throughput::Elements(GRAPH_FRAME_SIZE as u64));
+    let mut frame_number = 0;
+    group.bench_function(BenchmarkId::from_parameter(GRAPH_FRAME_SIZE), |b| {
+        b.iter_batched(
+            || {
+                frame_number += 1;
+                frame(frame_number)
+            },
+            |input| graph.execute(input),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+/// Two channel selectors feeding two Butterworth bandpass branches each
+fn fan_out_graph() -> ProcessingGraphConfig {
+    let node = |id: &str, node_type: &str, parameters: serde_json::Value| NodeConfig {
+        id: id.to_string(),
+        node_type: node_type.to_string(),
+        parameters,
+    };
+    let connection = |from: &str, to: &str| ConnectionConfig {
+        from: from.to_string(),
+        to: to.to_string(),
+    };
+    let mut nodes = vec![node("input", "input", serde_json::Value::Null)];
+    let mut connections = Vec::new();
+    for channel in ["A", "B"] {
+        let select = format!("select_{}", channel);
+        nodes.push(node(
+            &select,
+            "channel_selector",
+            serde_json::json!({"target_channel": format!("Channel{}", channel)}),
+        ));
+        connections.push(connection("input", &select));
+        for center_frequency in [2000.0, 4000.0] {
+            let branch = format!("bandpass_{}_{}", channel, center_frequency);
+            nodes.push(node(
+                &branch,
+                "filter",
+                serde_json::json!({
+                    "type": "butter_bandpass",
+                    "center_frequency": center_frequency,
+                    "bandwidth": 200.0,
+                    "order": 8
+                }),
+            ));
+            connections.push(connection(&select, &branch));
+        }
+    }
+    ProcessingGraphConfig {
+        id: "bench_fan_out".to_string(),
+        nodes,
+        connections,
+        output_node: Some("bandpass_A_2000".to_string()),
+    }
+}
+
+fn bench_graph_fan_out(c: &mut Criterion) {
+    const FRAME_SIZE: usize = 4 * GRAPH_FRAME_SIZE;
+    let frame = ProcessingData::AudioFrame(AudioFrame {
+        channel_a: test_signal(FRAME_SIZE, 5),
+        channel_b: test_signal(FRAME_SIZE, 6),
+        sample_rate: SAMPLE_RATE,
+        timestamp: 1000,
+        frame_number: 0,
+    });
+
+    let mut group = c.benchmark_group("graph/execute_fan_out");
+    group.throughput(Throughput::Elements(FRAME_SIZE as u64));
+    for (name, parallel) in [("serial", false), ("parallel", true)] {
+        let mut graph = ProcessingGraph::from_config(&fan_out_graph()).expect("graph built");
+        for branch in ["bandpass_A_4000", "bandpass_B_2000", "bandpass_B_4000"] {
+            graph.set_output_node(branch).expect("output node");
+        }
+        graph.set_parallel_execution(parallel);
+
+        let outputs = graph.execute(frame.clone()).expect("graph executed");
+        assert_eq!(outputs.len(), 4);
+        for output in &outputs {
+            match output {
+                ProcessingData::SingleChannel { samples, .. } => {
+                    assert_eq!(samples.len(), FRAME_SIZE);
+                    assert_finite("fan-out branch", samples);
+                }
+                _ => panic!("unexpected fan-out output"),
+            }
+        }
+
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || frame.clone(),
+                |input| graph.execute(input),
+                criterion::BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+/// Channel selectors feeding standard filters, one selector read by two branches
+fn buffer_reuse_graph() -> ProcessingGraphConfig {
+    let node = |id: &str, node_type: &str, parameters: serde_json::Value| NodeConfig {
+        id: id.to_string(),
+        node_type: node_type.to_string(),
+        parameters,
+    };
+    let connection = |from: &str, to: &str| ConnectionConfig {
+        from: from.to_string(),
+        to: to.to_string(),
+    };
+    ProcessingGraphConfig {
+        id: "bench_buffer_reuse".to_string(),
+        nodes: vec![
+            node("input", "input", serde_json::Value::Null),
+            node(
+                "select_a",
+                "channel_selector",
+                serde_json::json!({"target_channel": "ChannelA"}),
+            ),
+            node(
+                "select_b",
+                "channel_selector",
+                serde_json::json!({"target_channel": "ChannelB"}),
+            ),
+            node(
+                "bandpass_a",
+                "filter",
+                serde_json::json!({
+                    "type": "bandpass",
+                    "center_frequency": 2000.0,
+                    "bandwidth": 200.0,
+                    "order": 4
+                }),
+            ),
+            node("gain_a", "gain", serde_json::json!({"gain_db": 6.0})),
+            node(
+                "highpass_a",
+                "filter",
+                serde_json::json!({"type": "highpass", "cutoff_frequency": 500.0, "order": 2}),
+            ),
+            node(
+                "lowpass_b",
+                "filter",
+                serde_json::json!({"type": "lowpass", "cutoff_frequency": 1000.0, "order": 4}),
+            ),
+        ],
+        connections: vec![
+            connection("input", "select_a"),
+            connection("input", "select_b"),
+            connection("select_a", "bandpass_a"),
+            connection("bandpass_a", "gain_a"),
+            connection("select_a", "highpass_a"),
+            connection("select_b", "lowpass_b"),
+        ],
+        output_node: Some("gain_a".to_string()),
+    }
+}
+
+fn bench_graph_buffer_reuse(c: &mut Criterion) {
+    const COUNTED_FRAMES: u64 = 16;
+    let frame = ProcessingData::AudioFrame(AudioFrame {
+        channel_a: test_signal(GRAPH_FRAME_SIZE, 7),
+        channel_b: test_signal(GRAPH_FRAME_SIZE, 8),
+        sample_rate: SAMPLE_RATE,
+        timestamp: 1000,
+        frame_number: 0,
+    });
+
+    let mut group = c.benchmark_group("graph/buffer_reuse");
+    group.throughput(Throughput::Elements(GRAPH_FRAME_SIZE as u64));
+    let mut allocated_bytes = Vec::new();
+    for (name, reuse) in [("allocating", false), ("pooled", true)] {
+        let mut graph = ProcessingGraph::from_config(&buffer_reuse_graph()).expect("graph built");
+        graph.set_output_node("highpass_a").expect("output node");
+        graph.set_output_node("lowpass_b").expect("output node");
+        graph.set_buffer_reuse(reuse);
+
+        // Fill the pool with a few frames, then count the allocations of
+        // execute and recycle, leaving out the copy of the input frame
+        let mut counted = (0, 0);
+        for frame_number in 0..4 + COUNTED_FRAMES {
+            let input = frame.clone();
+            let (allocations, bytes) = allocation_counters();
+            let outputs = graph.execute(input).expect("graph executed");
+            graph.recycle(black_box(outputs));
+            if frame_number >= 4 {
+                let (end_allocations, end_bytes) = allocation_counters();
+                counted.0 += end_allocations - allocations;
+                counted.1 += end_bytes - bytes;
+            }
+        }
+        println!(
+            "graph/buffer_reuse/{}: {} allocations, {} bytes allocated per frame",
+            name,
+            counted.0 / COUNTED_FRAMES,
+            counted.1 / COUNTED_FRAMES
+        );
+        allocated_bytes.push(counted.1);
+
+        let outputs = graph.execute(frame.clone()).expect("graph executed");
+        assert_eq!(outputs.len(), 3);
+        for output in &outputs {
+            match output {
+                ProcessingData::SingleChannel { samples, .. } => {
+                    assert_eq!(samples.len(), GRAPH_FRAME_SIZE);
+                    assert_finite("buffer reuse branch", samples);
+                }
+                _ => panic!("unexpected buffer reuse output"),
+            }
+        }
+        graph.recycle(outputs);
+
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || frame.clone(),
+                |input| {
+                    let outputs = graph.execute(input).expect("graph executed");
+                    graph.recycle(black_box(outputs));
+                },
+                criterion::BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+
+    // The sample buffers dominate the allocated bytes of the allocating path
+    assert!(
+        allocated_bytes[1] * 4 < allocated_bytes[0],
+        "pooled execution allocated {} bytes against {}",
+        allocated_bytes[1],
+        allocated_bytes[0]
+    );
+}
+
+criterion_group!(
+    benches,
+    bench_filters,
+    bench_filters_simd,
+    bench_fft,
+    bench_graph,
+    bench_graph_fan_out,
+    bench_graph_buffer_reuse
+);
+criterion_main!(benches);
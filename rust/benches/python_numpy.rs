@@ -0,0 +1,93 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Criterion benchmarks of the Python node with lists and with NumPy arrays
+//!
+//! | Group | What it measures |
+//! |---|---|
+//! | `python/list` | [`PythonNode::process`] on dual channel frames of 1024 to 65536 samples passed as Python lists |
+//! | `python/numpy` | The same frames and script with `numpy_arrays: true` |
+//!
+//! Both groups run the same gain script, only the transport of the samples
+//! differs. The throughput is reported in samples per second, both channels
+//! included. NumPy must be importable by the embedded interpreter:
+//!
+//! ```text
+//! cargo bench --bench python_numpy
+//! cargo bench --bench python_numpy -- --save-baseline main
+//! ```
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use rust_photoacoustic::processing::nodes::{
+    ProcessingData, ProcessingNode, PythonNode, PythonNodeConfig,
+};
+use std::hint::black_box;
+
+const FRAME_SIZES: [usize; 4] = [1024, 4096, 16384, 65536];
+
+/// Same computation on both paths, only the transport of the samples differs
+const GAIN_SCRIPT: &str = r#"
+import numpy as np
+
+def process_data(data):
+    as_list = isinstance(data["channel_a"], list)
+    for key in ("channel_a", "channel_b"):
+        result = np.asarray(data[key], dtype=np.float32) * 2.0
+        data[key] = result.tolist() if as_list else result
+    return data
+"#;
+
+fn dual_channel(frame_size: usize) -> ProcessingData {
+    ProcessingData::DualChannel {
+        channel_a: (0..frame_size).map(|i| (i as f32 * 0.01).sin()).collect(),
+        channel_b: (0..frame_size).map(|i| (i as f32 * 0.01).cos()).collect(),
+        sample_rate: 48000,
+        timestamp: 0,
+        frame_number: 0,
+    }
+}
+
+fn bench_python(c: &mut Criterion) {
+    let dir = tempfile::tempdir().expect("temporary directory");
+    let script_path = dir.path().join("gain.py");
+    std::fs::write(&script_path, GAIN_SCRIPT).expect("script written");
+
+    for numpy_arrays in [false, true] {
+        let path = if numpy_arrays { "numpy" } else { "list" };
+        let mut group = c.benchmark_group(format!("python/{}", path));
+        for frame_size in FRAME_SIZES {
+            let mut node = PythonNode::new(
+                format!("python_gain_{}", path),
+                PythonNodeConfig {
+                    script_path: script_path.clone(),
+                    numpy_arrays,
+                    ..Default::default()
+                },
+            );
+            let input = dual_channel(frame_size);
+
+            // Loads the script and NumPy, and checks the gain
+            match node.process(input.clone()).expect("frame processed") {
+                ProcessingData::DualChannel { channel_a, .. } => {
+                    assert_eq!(channel_a.len(), frame_size);
+                    assert!((channel_a[100] - 2.0 * 1.0f32.sin()).abs() < 1e-5);
+                }
+                other => panic!("unexpected output {:?}", other),
+            }
+
+            group.throughput(Throughput::Elements(2 * frame_size as u64));
+            group.bench_function(BenchmarkId::from_parameter(frame_size), |b| {
+                b.iter_batched(
+                    || input.clone(),
+                    |input| node.process(black_box(input)).expect("frame processed"),
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_python);
+criterion_main!(benches);
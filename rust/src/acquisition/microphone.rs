@@ -7,6 +7,7 @@
 //! This module handles the acquisition of audio data from microphones using CPAL
 
 use crate::acquisition::{AudioFrame, RealTimeAudioSource, SharedAudioStream};
+use crate::config::photoacoustic::ChannelMappingConfig;
 use crate::config::PhotoacousticConfig;
 
 use super::AudioSource;
@@ -37,6 +38,81 @@ pub enum MicrophoneError {
     UnsupportedFormat(SampleFormat),
     #[error("Audio stream error: {0}")]
     StreamError(String),
+    #[error("Input channel {channel} mapped to channel {target} is not available: the device has channels 1 to {available}")]
+    ChannelOutOfRange {
+        target: char,
+        channel: u16,
+        available: u16,
+    },
+}
+
+/// Device channels read as channel A and channel B
+///
+/// Indices are zero-based positions in an interleaved device frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelMap {
+    channel_a: usize,
+    channel_b: usize,
+}
+
+impl ChannelMap {
+    /// Resolve the configured mapping against the channels of a device
+    ///
+    /// Without a mapping, the first two channels are used and a mono device
+    /// feeds both channels.
+    ///
+    /// ### Arguments
+    ///
+    /// * `mapping` - Configured device channels, numbered from 1
+    /// * `device_channels` - Number of input channels reported by the device
+    ///
+    /// ### Returns
+    ///
+    /// The channel map, or [`MicrophoneError::ChannelOutOfRange`] if a mapped channel does not exist on the device
+    pub fn resolve(
+        mapping: Option<&ChannelMappingConfig>,
+        device_channels: u16,
+    ) -> Result<Self, MicrophoneError> {
+        let Some(mapping) = mapping else {
+            return Ok(Self {
+                channel_a: 0,
+                channel_b: if device_channels >= 2 { 1 } else { 0 },
+            });
+        };
+
+        let index = |target: char, channel: u16| {
+            if channel == 0 || channel > device_channels {
+                Err(MicrophoneError::ChannelOutOfRange {
+                    target,
+                    channel,
+                    available: device_channels,
+                })
+            } else {
+                Ok(channel as usize - 1)
+            }
+        };
+        Ok(Self {
+            channel_a: index('A', mapping.channel_a)?,
+            channel_b: index('B', mapping.channel_b)?,
+        })
+    }
+
+    /// Number of device channels the stream must capture
+    pub fn channels_needed(&self) -> u16 {
+        (self.channel_a.max(self.channel_b) + 1) as u16
+    }
+
+    /// Extract channel A and channel B from interleaved device samples
+    ///
+    /// ### Arguments
+    ///
+    /// * `data` - Interleaved samples, a trailing partial device frame is ignored
+    /// * `channels` - Number of channels of the device stream
+    pub fn split(&self, data: &[f32], channels: usize) -> (Vec<f32>, Vec<f32>) {
+        data.chunks_exact(channels)
+            .map(|frame| (frame[self.channel_a], frame[self.channel_b]))
+            .unzip()
+    }
 }
 
 /// Audio source that reads from a microphone device using CPAL
@@ -167,6 +243,21 @@ impl MicrophoneSource {
             .default_input_config()
             .context("Failed to get default input configuration")?;
 
+        // Map the configured device channels to channel A and channel B
+        let device_channels = Self::max_input_channels(&device).max(supported_config.channels());
+        let channel_map = ChannelMap::resolve(config.channel_mapping.as_ref(), device_channels)?;
+        let supported_config = if channel_map.channels_needed() > supported_config.channels() {
+            Self::config_with_channels(&device, &supported_config, channel_map.channels_needed())?
+        } else {
+            supported_config
+        };
+        if let Some(ref mapping) = config.channel_mapping {
+            info!(
+                "Channel mapping: device channel {} -> A, device channel {} -> B",
+                mapping.channel_a, mapping.channel_b
+            );
+        }
+
         // Use the device's native configuration
         let stream_config: StreamConfig = supported_config.clone().into();
         let sample_rate = stream_config.sample_rate;
@@ -202,6 +293,7 @@ impl MicrophoneSource {
                 &device_clone,
                 &stream_config_clone,
                 sample_format,
+                channel_map,
                 sender,
                 target_chunk_size, // Use smaller chunks for the stream
             ) {
@@ -252,10 +344,17 @@ impl MicrophoneSource {
         }
 
         let device = if let Some(name) = device_name {
-            // Find device by name
-            devices
-                .into_iter()
-                .find(|d| d.name().map(|n| n.contains(name)).unwrap_or(false))
+            // Find device by name, an exact match is preferred over a partial one
+            // so that "hw:CARD=Audio" does not select "plughw:CARD=Audio"
+            let names: Vec<String> = devices
+                .iter()
+                .map(|d| d.name().unwrap_or_default())
+                .collect();
+            names
+                .iter()
+                .position(|n| n == name)
+                .or_else(|| names.iter().position(|n| n.contains(name)))
+                .map(|index| devices[index].clone())
                 .ok_or_else(|| {
                     Self::list_available_devices(host);
                     MicrophoneError::DeviceNotFound(name.to_string())
@@ -271,6 +370,45 @@ impl MicrophoneSource {
         Ok(device)
     }
 
+    /// Largest number of input channels supported by a device
+    fn max_input_channels(device: &Device) -> u16 {
+        device
+            .supported_input_configs()
+            .map(|configs| configs.map(|config| config.channels()).max().unwrap_or(0))
+            .unwrap_or(0)
+    }
+
+    /// Find an input configuration capturing at least `channels` channels
+    ///
+    /// The sample rate and format of the default configuration are kept.
+    fn config_with_channels(
+        device: &Device,
+        default_config: &cpal::SupportedStreamConfig,
+        channels: u16,
+    ) -> Result<cpal::SupportedStreamConfig> {
+        let sample_rate = default_config.sample_rate();
+        device
+            .supported_input_configs()
+            .context("Failed to get supported input configurations")?
+            .filter(|range| {
+                range.channels() >= channels
+                    && range.sample_format() == default_config.sample_format()
+                    && range.min_sample_rate() <= sample_rate
+                    && sample_rate <= range.max_sample_rate()
+            })
+            .min_by_key(|range| range.channels())
+            .map(|range| range.with_sample_rate(sample_rate))
+            .ok_or_else(|| {
+                MicrophoneError::ConfigurationError(format!(
+                    "no {} Hz {:?} configuration with {} channels",
+                    sample_rate,
+                    default_config.sample_format(),
+                    channels
+                ))
+                .into()
+            })
+    }
+
     /// List all available audio input devices
     fn list_available_devices(host: &Host) {
         error!("Available audio input devices:");
@@ -295,6 +433,7 @@ impl MicrophoneSource {
         device: &Device,
         config: &StreamConfig,
         sample_format: SampleFormat,
+        channel_map: ChannelMap,
         sender: Sender<(Vec<f32>, Vec<f32>)>,
         chunk_size: usize, // Now using smaller chunks
     ) -> Result<Stream> {
@@ -309,7 +448,14 @@ impl MicrophoneSource {
                 device.build_input_stream(
                     config,
                     move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                        Self::process_audio_data(data, &buffer, &sender, channels, chunk_size);
+                        Self::process_audio_data(
+                            data,
+                            &buffer,
+                            &sender,
+                            channels,
+                            channel_map,
+                            chunk_size,
+                        );
                     },
                     |err| error!("Audio stream error: {}", err),
                     None,
@@ -331,6 +477,7 @@ impl MicrophoneSource {
                             &buffer,
                             &sender,
                             channels,
+                            channel_map,
                             chunk_size,
                         );
                     },
@@ -354,6 +501,7 @@ impl MicrophoneSource {
                             &buffer,
                             &sender,
                             channels,
+                            channel_map,
                             chunk_size,
                         );
                     },
@@ -371,6 +519,7 @@ impl MicrophoneSource {
         buffer: &Arc<Mutex<Vec<f32>>>,
         sender: &Arc<Mutex<Sender<(Vec<f32>, Vec<f32>)>>>,
         channels: usize,
+        channel_map: ChannelMap,
         chunk_size: usize, // Now using smaller chunks instead of full frames
     ) {
         let mut buffer = buffer.lock().unwrap();
@@ -384,22 +533,8 @@ impl MicrophoneSource {
         while buffer.len() >= samples_per_chunk {
             let chunk_data: Vec<f32> = buffer.drain(..samples_per_chunk).collect();
 
-            // Separate channels
-            let (channel_a, channel_b) = if channels >= 2 {
-                // Stereo: separate left and right channels
-                let mut ch_a = Vec::with_capacity(chunk_size);
-                let mut ch_b = Vec::with_capacity(chunk_size);
-
-                for chunk in chunk_data.chunks_exact(channels) {
-                    ch_a.push(chunk[0]);
-                    ch_b.push(chunk[1]);
-                }
-                (ch_a, ch_b)
-            } else {
-                // Mono: duplicate channel
-                let mono_data: Vec<f32> = chunk_data;
-                (mono_data.clone(), mono_data)
-            };
+            // Separate the mapped channels, a mono device feeds both
+            let (channel_a, channel_b) = channel_map.split(&chunk_data, channels);
 
             // Send the chunk
             if let Ok(sender) = sender.lock() {
@@ -475,3 +610,74 @@ impl AudioSource for MicrophoneSource {
         self.sample_rate
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(channel_a: u16, channel_b: u16) -> ChannelMappingConfig {
+        ChannelMappingConfig {
+            channel_a,
+            channel_b,
+        }
+    }
+
+    #[test]
+    fn test_default_channel_map() {
+        let stereo = ChannelMap::resolve(None, 2).unwrap();
+        assert_eq!(
+            stereo.split(&[0.1, 0.2, 0.3, 0.4], 2),
+            (vec![0.1, 0.3], vec![0.2, 0.4])
+        );
+
+        // A mono device feeds both channels
+        let mono = ChannelMap::resolve(None, 1).unwrap();
+        assert_eq!(mono.channels_needed(), 1);
+        assert_eq!(mono.split(&[0.1, 0.2], 1), (vec![0.1, 0.2], vec![0.1, 0.2]));
+    }
+
+    #[test]
+    fn test_multichannel_mapping() {
+        // Channels 3 and 5 of an 8-channel interface
+        let map = ChannelMap::resolve(Some(&mapping(3, 5)), 8).unwrap();
+        assert_eq!(map.channels_needed(), 5);
+
+        let data: Vec<f32> = (0..16).map(|sample| sample as f32).collect();
+        assert_eq!(map.split(&data, 8), (vec![2.0, 10.0], vec![4.0, 12.0]));
+
+        // Channels may be swapped or shared
+        let swapped = ChannelMap::resolve(Some(&mapping(2, 1)), 2).unwrap();
+        assert_eq!(swapped.split(&[0.1, 0.2], 2), (vec![0.2], vec![0.1]));
+        let shared = ChannelMap::resolve(Some(&mapping(4, 4)), 4).unwrap();
+        assert_eq!(shared.channels_needed(), 4);
+    }
+
+    #[test]
+    fn test_mapping_exceeds_device_channels() {
+        let error = ChannelMap::resolve(Some(&mapping(3, 9)), 8).unwrap_err();
+        assert!(matches!(
+            error,
+            MicrophoneError::ChannelOutOfRange {
+                target: 'B',
+                channel: 9,
+                available: 8
+            }
+        ));
+        assert_eq!(
+            error.to_string(),
+            "Input channel 9 mapped to channel B is not available: the device has channels 1 to 8"
+        );
+
+        // Channels are numbered from 1
+        assert!(matches!(
+            ChannelMap::resolve(Some(&mapping(0, 1)), 2),
+            Err(MicrophoneError::ChannelOutOfRange {
+                target: 'A',
+                channel: 0,
+                ..
+            })
+        ));
+        // A stereo mapping on a mono device
+        assert!(ChannelMap::resolve(Some(&mapping(1, 2)), 1).is_err());
+    }
+}
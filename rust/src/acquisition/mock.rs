@@ -6,16 +6,25 @@
 //!
 //! This module provides a mock audio source that generates synthetic photoacoustic signals
 //! using the NoiseGenerator for testing and simulation purposes.
+//!
+//! When the simulated source configures a [`ChirpConfig`], the mock source
+//! generates this frequency sweep instead, to measure the frequency response
+//! of the acquisition and filter chain. A [`MultiToneConfig`] likewise
+//! replaces the signal with a sum of sinusoids set independently per channel,
+//! to test intermodulation and filter selectivity, and a [`TestSignalConfig`]
+//! with an impulse or a step to measure the responses of the filters.
 
 use super::AudioSource;
 use crate::acquisition::{AudioFrame, RealTimeAudioSource, SharedAudioStream};
-use crate::config::PhotoacousticConfig;
+use crate::config::{
+    ChirpConfig, MultiToneConfig, PhotoacousticConfig, TestSignalConfig, ToneComponent,
+};
 use crate::utility::noise_generator::NoiseGenerator;
 use anyhow::Result;
 use async_trait::async_trait;
 use log::{debug, error, info};
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
+    atomic::{AtomicBool, AtomicU64, Ordering},
     Arc,
 };
 use std::time::{Duration, Instant};
@@ -32,6 +41,10 @@ pub struct MockSource {
     min_pulse_amplitude: f32,
     max_pulse_amplitude: f32,
     correlation: f32,
+    // Test signal replacing the photoacoustic signal, and its sample clock
+    // shared with the streaming task, so that the signal continues where it stopped
+    synthetic: Option<SyntheticSignal>,
+    synthetic_sample: Arc<AtomicU64>,
     // Timing control for real-time simulation
     last_frame_time: Option<Instant>,
     frame_duration: Duration,
@@ -62,9 +75,13 @@ impl RealTimeAudioSource for MockSource {
         let min_pulse_amplitude = self.min_pulse_amplitude;
         let max_pulse_amplitude = self.max_pulse_amplitude;
         let correlation = self.correlation;
+        let synthetic = self.synthetic.clone();
+        let synthetic_sample = self.synthetic_sample.clone();
+
+        // Start from the source's generator so that a configured seed is honored
+        let mut generator = self.generator;
 
         let handle = tokio::spawn(async move {
-            let mut generator = NoiseGenerator::new_from_system_time();
             let mut frame_number = 0u64;
             let mut last_frame_time = Instant::now();
 
@@ -78,36 +95,42 @@ impl RealTimeAudioSource for MockSource {
                 }
                 last_frame_time = Instant::now();
 
-                // Generate correlated stereo mock photoacoustic signal
-                let samples = generator.generate_mock_photoacoustic_correlated(
-                    frame_size as u32,
-                    sample_rate,
-                    noise_amplitude,
-                    frequency,
-                    pulse_width,
-                    min_pulse_amplitude,
-                    max_pulse_amplitude,
-                    correlation,
-                );
-
-                // Convert interleaved i16 samples to separate f32 channels
-                let mut channel_a = Vec::with_capacity(frame_size);
-                let mut channel_b = Vec::with_capacity(frame_size);
-
-                fn i16_to_f32(sample: i16) -> f32 {
-                    if sample >= 0 {
-                        sample as f32 / i16::MAX as f32
-                    } else {
-                        sample as f32 / -(i16::MIN as f32)
+                let (channel_a, channel_b) = if let Some(ref synthetic) = synthetic {
+                    let start = synthetic_sample.fetch_add(frame_size as u64, Ordering::Relaxed);
+                    synthetic.frame(start, frame_size, sample_rate)
+                } else {
+                    // Generate correlated stereo mock photoacoustic signal
+                    let samples = generator.generate_mock_photoacoustic_correlated(
+                        frame_size as u32,
+                        sample_rate,
+                        noise_amplitude,
+                        frequency,
+                        pulse_width,
+                        min_pulse_amplitude,
+                        max_pulse_amplitude,
+                        correlation,
+                    );
+
+                    // Convert interleaved i16 samples to separate f32 channels
+                    let mut channel_a = Vec::with_capacity(frame_size);
+                    let mut channel_b = Vec::with_capacity(frame_size);
+
+                    fn i16_to_f32(sample: i16) -> f32 {
+                        if sample >= 0 {
+                            sample as f32 / i16::MAX as f32
+                        } else {
+                            sample as f32 / -(i16::MIN as f32)
+                        }
                     }
-                }
 
-                for chunk in samples.chunks_exact(2) {
-                    let left = i16_to_f32(chunk[0]);
-                    let right = i16_to_f32(chunk[1]);
-                    channel_a.push(left);
-                    channel_b.push(right);
-                }
+                    for chunk in samples.chunks_exact(2) {
+                        let left = i16_to_f32(chunk[0]);
+                        let right = i16_to_f32(chunk[1]);
+                        channel_a.push(left);
+                        channel_b.push(right);
+                    }
+                    (channel_a, channel_b)
+                };
 
                 frame_number += 1;
                 let audio_frame = AudioFrame::new(channel_a, channel_b, sample_rate, frame_number);
@@ -163,10 +186,25 @@ impl MockSource {
     /// let mock_source = MockSource::new(config);
     /// ```
     pub fn new(config: PhotoacousticConfig) -> Result<Self> {
-        let generator = NoiseGenerator::new_from_system_time();
+        let rng_seed = config
+            .simulated_source
+            .as_ref()
+            .and_then(|simulated_config| simulated_config.rng_seed);
+        let generator = NoiseGenerator::from_seed_or_system_time(rng_seed);
         let sample_rate = config.sample_rate as u32;
         let frame_size = config.frame_size as usize;
 
+        let synthetic = config
+            .simulated_source
+            .as_ref()
+            .and_then(|simulated_config| {
+                simulated_config
+                    .chirp
+                    .map(SyntheticSignal::Chirp)
+                    .or_else(|| simulated_config.tones.clone().map(SyntheticSignal::Tones))
+                    .or_else(|| simulated_config.test_signal.map(SyntheticSignal::Test))
+            });
+
         let correlation = if let Some(ref simulated_config) = config.simulated_source {
             simulated_config.correlation.clamp(-1.0, 1.0)
         } else {
@@ -188,6 +226,34 @@ impl MockSource {
         );
         info!("  Expected FPS: {:.1}", 1.0 / frame_duration.as_secs_f64());
         info!("  Correlation: {}", correlation);
+        if let Some(seed) = rng_seed {
+            info!("  RNG seed: {}", seed);
+        }
+        match synthetic {
+            Some(SyntheticSignal::Chirp(ref chirp)) => info!(
+                "  Chirp: {:?} sweep from {} Hz to {} Hz in {} s{}",
+                chirp.sweep,
+                chirp.start_frequency_hz,
+                chirp.stop_frequency_hz,
+                chirp.duration_seconds,
+                if chirp.repeat { ", repeated" } else { "" }
+            ),
+            Some(SyntheticSignal::Tones(ref tones)) => info!(
+                "  Tones: {} on channel A, {} on channel B",
+                tones.channel_a.len(),
+                tones.channel_b.len()
+            ),
+            Some(SyntheticSignal::Test(ref test_signal)) => info!(
+                "  Test signal: {} of {} from sample {}",
+                match test_signal {
+                    TestSignalConfig::Impulse { .. } => "impulse",
+                    TestSignalConfig::Step { .. } => "step",
+                },
+                test_signal.amplitude(),
+                test_signal.start_sample(sample_rate)
+            ),
+            None => {}
+        }
 
         Ok(Self {
             generator,
@@ -200,6 +266,8 @@ impl MockSource {
             min_pulse_amplitude: 0.8, // Minimum 80% pulse amplitude
             max_pulse_amplitude: 1.0, // Maximum 100% pulse amplitude
             correlation,
+            synthetic,
+            synthetic_sample: Arc::new(AtomicU64::new(0)),
             last_frame_time: None,
             frame_duration,
             real_time_mode: true, // Enable real-time simulation by default
@@ -227,8 +295,8 @@ impl MockSource {
         max_pulse_amplitude: f32,
     ) -> Result<Self> {
         let mut config = config.clone();
-        // Create a temporary SimulatedSourceConfig with the provided correlation
-        let mut simulated_config = crate::config::SimulatedSourceConfig::default();
+        // Override the correlation of the SimulatedSourceConfig, keeping its seed
+        let mut simulated_config = config.simulated_source.clone().unwrap_or_default();
         simulated_config.correlation = correlation.clamp(-1.0, 1.0);
         config.simulated_source = Some(simulated_config);
         let mut mock_source = Self::new(config)?;
@@ -284,6 +352,13 @@ impl AudioSource for MockSource {
             self.last_frame_time = Some(Instant::now());
         }
 
+        if let Some(ref synthetic) = self.synthetic {
+            let start = self
+                .synthetic_sample
+                .fetch_add(self.frame_size as u64, Ordering::Relaxed);
+            return Ok(synthetic.frame(start, self.frame_size, self.sample_rate));
+        }
+
         // Generate correlated stereo mock photoacoustic signal
         let samples = self.generator.generate_mock_photoacoustic_correlated(
             self.frame_size as u32,
@@ -323,6 +398,62 @@ impl AudioSource for MockSource {
     }
 }
 
+/// Deterministic test signal generated instead of the photoacoustic signal
+#[derive(Debug, Clone)]
+enum SyntheticSignal {
+    /// Frequency sweep, both channels carrying the same signal
+    Chirp(ChirpConfig),
+    /// Sums of sinusoids set per channel
+    Tones(MultiToneConfig),
+    /// Impulse or step, both channels carrying the same signal
+    Test(TestSignalConfig),
+}
+
+impl SyntheticSignal {
+    /// Generate a frame of the signal
+    ///
+    /// ### Arguments
+    ///
+    /// * `first_sample` - Index of the first sample of the frame since the start of the signal
+    /// * `frame_size` - Number of samples per channel
+    /// * `sample_rate` - Sample rate in Hz
+    fn frame(
+        &self,
+        first_sample: u64,
+        frame_size: usize,
+        sample_rate: u32,
+    ) -> (Vec<f32>, Vec<f32>) {
+        let times = (first_sample..first_sample + frame_size as u64)
+            .map(|sample| sample as f64 / sample_rate as f64);
+        match self {
+            Self::Chirp(chirp) => {
+                let channel: Vec<f32> = times
+                    .map(|time| {
+                        chirp.phase_at(time).map_or(0.0, |cycles| {
+                            chirp.amplitude * (std::f64::consts::TAU * cycles.fract()).sin() as f32
+                        })
+                    })
+                    .collect();
+                (channel.clone(), channel)
+            }
+            Self::Tones(tones) => {
+                let sum = |components: &[ToneComponent], time: f64| -> f32 {
+                    components.iter().map(|tone| tone.value_at(time)).sum()
+                };
+                times
+                    .map(|time| (sum(&tones.channel_a, time), sum(&tones.channel_b, time)))
+                    .unzip()
+            }
+            Self::Test(test_signal) => {
+                let channel: Vec<f32> = (first_sample..first_sample + frame_size as u64)
+                    .map(|sample| test_signal.value_at(sample, sample_rate))
+                    .collect();
+                (channel.clone(), channel)
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
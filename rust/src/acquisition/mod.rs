@@ -18,17 +18,23 @@ pub mod daemon;
 mod file;
 mod microphone;
 mod mock;
+mod network;
 pub mod realtime_daemon;
+mod session;
 mod simulated_photoacoustic;
 pub mod stream;
+mod tee;
 
 pub use daemon::AcquisitionDaemon;
 use file::FileSource;
 pub use microphone::MicrophoneSource;
 pub use mock::MockSource;
+pub use network::{NetworkAudioSource, NetworkStats};
 pub use realtime_daemon::RealTimeAcquisitionDaemon;
+pub use session::{ReplaySource, ReplayTiming, SessionRecorder};
 pub use simulated_photoacoustic::SimulatedPhotoacousticRealtimeAudioSource;
 pub use stream::{AudioFrame, AudioStreamConsumer, SharedAudioStream, StreamStats};
+pub use tee::{TeeSink, TeeSource, WavRecorder};
 
 use crate::config::PhotoacousticConfig;
 
@@ -42,6 +48,16 @@ pub trait AudioSource: Send {
     fn sample_rate(&self) -> u32;
 }
 
+impl<T: AudioSource + ?Sized> AudioSource for Box<T> {
+    fn read_frame(&mut self) -> Result<(Vec<f32>, Vec<f32>)> {
+        (**self).read_frame()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        (**self).sample_rate()
+    }
+}
+
 /// Trait for real-time audio sources that can stream directly to SharedAudioStream
 #[async_trait]
 pub trait RealTimeAudioSource: Send + Sync {
@@ -58,6 +74,25 @@ pub trait RealTimeAudioSource: Send + Sync {
     fn sample_rate(&self) -> u32;
 }
 
+#[async_trait]
+impl<T: RealTimeAudioSource + ?Sized> RealTimeAudioSource for Box<T> {
+    async fn start_streaming(&mut self, stream: Arc<SharedAudioStream>) -> Result<()> {
+        (**self).start_streaming(stream).await
+    }
+
+    async fn stop_streaming(&mut self) -> Result<()> {
+        (**self).stop_streaming().await
+    }
+
+    fn is_streaming(&self) -> bool {
+        (**self).is_streaming()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        (**self).sample_rate()
+    }
+}
+
 /// Get an audio source from the specified device
 pub fn get_audio_source_from_device(config: PhotoacousticConfig) -> Result<Box<dyn AudioSource>> {
     Ok(Box::new(MicrophoneSource::new(config)?))
@@ -127,6 +162,13 @@ pub fn get_realtime_mock_audio_source(
     Ok(Box::new(MockSource::new(config)?))
 }
 
+/// Get a real-time audio source receiving PCM packets over UDP
+pub fn get_realtime_network_audio_source(
+    config: PhotoacousticConfig,
+) -> Result<Box<dyn RealTimeAudioSource>> {
+    Ok(Box::new(NetworkAudioSource::new(config)?))
+}
+
 /// Get a real-time simulated photoacoustic audio source
 ///
 /// This function creates either a simple MockSource or an advanced SimulatedPhotoacousticRealtimeAudioSource
@@ -0,0 +1,472 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Network audio source module
+//!
+//! This module provides [`NetworkAudioSource`], receiving stereo PCM over UDP
+//! from acquisition hardware which streams over the network instead of
+//! exposing a local audio device.
+//!
+//! Each packet carries a sequence number, in an RTP header
+//! ([`NetworkProtocol::Rtp`]) or a 4-byte big-endian prefix
+//! ([`NetworkProtocol::Raw`]), followed by the interleaved samples of channel A
+//! and channel B. Packets are put back in order in a reorder buffer:
+//!
+//! - a packet arriving after a later one is held back and emitted in order;
+//! - a packet still missing once more than `reorder_window` packets are pending
+//!   is declared lost and replaced by silence of the length of the previous
+//!   packet, which keeps the timing of the following samples;
+//! - a packet arriving after being declared lost, or twice, is dropped;
+//! - a forward jump of the sequence number larger than
+//!   [`MAX_CONCEALED_PACKETS`] resynchronizes the buffer without silence;
+//! - a packet more than [`MAX_CONCEALED_PACKETS`] behind is dropped as well,
+//!   unless [`MAX_CONCEALED_PACKETS`] such packets arrive in a row, as from a
+//!   sender restarted at a lower sequence number, which resynchronizes the buffer.
+//!
+//! The samples are then cut into frames of `frame_size` samples published to
+//! the [`SharedAudioStream`].
+
+use super::{AudioFrame, RealTimeAudioSource, SharedAudioStream};
+use crate::config::photoacoustic::{NetworkProtocol, NetworkSampleFormat, NetworkSourceConfig};
+use crate::config::PhotoacousticConfig;
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use log::{debug, error, info, warn};
+use std::collections::BTreeMap;
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc,
+};
+
+/// Largest UDP payload
+const MAX_PACKET_SIZE: usize = 65536;
+
+/// Size of the fixed RTP header
+const RTP_HEADER_SIZE: usize = 12;
+
+/// Largest gap of sequence numbers concealed with silence
+pub const MAX_CONCEALED_PACKETS: i64 = 64;
+
+/// Packet statistics of a [`NetworkAudioSource`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NetworkStats {
+    /// Packets decoded
+    pub packets_received: u64,
+    /// Packets declared lost and replaced by silence
+    pub packets_lost: u64,
+    /// Packets which arrived after a later packet and were put back in order
+    pub packets_reordered: u64,
+    /// Packets dropped because they arrived after being declared lost, or twice
+    pub packets_late: u64,
+    /// Packets dropped because they could not be decoded
+    pub packets_invalid: u64,
+}
+
+/// Packet counters shared with the receiving task
+#[derive(Debug, Default)]
+struct NetworkCounters {
+    received: AtomicU64,
+    lost: AtomicU64,
+    reordered: AtomicU64,
+    late: AtomicU64,
+    invalid: AtomicU64,
+}
+
+impl NetworkCounters {
+    fn snapshot(&self) -> NetworkStats {
+        NetworkStats {
+            packets_received: self.received.load(Ordering::Relaxed),
+            packets_lost: self.lost.load(Ordering::Relaxed),
+            packets_reordered: self.reordered.load(Ordering::Relaxed),
+            packets_late: self.late.load(Ordering::Relaxed),
+            packets_invalid: self.invalid.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Audio source receiving stereo PCM packets over UDP
+pub struct NetworkAudioSource {
+    socket: UdpSocket,
+    config: NetworkSourceConfig,
+    sample_rate: u32,
+    frame_size: usize,
+    counters: Arc<NetworkCounters>,
+    // Real-time streaming support
+    streaming: Arc<AtomicBool>,
+    stream_handle: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl NetworkAudioSource {
+    /// Create a network source bound to the configured UDP address
+    ///
+    /// The socket is bound immediately, so that an address already in use is
+    /// reported at startup; packets received before streaming starts are
+    /// queued by the system.
+    ///
+    /// ### Arguments
+    ///
+    /// * `config` - PhotoacousticConfig with a `network_source`, the `sample_rate` of the stream and the `frame_size`
+    ///
+    /// ### Returns
+    ///
+    /// A new NetworkAudioSource, or an error if `network_source` is missing or the address cannot be bound
+    pub fn new(config: PhotoacousticConfig) -> Result<Self> {
+        let network_config = config
+            .network_source
+            .clone()
+            .ok_or_else(|| anyhow!("No network_source configured"))?;
+        let socket = UdpSocket::bind(&network_config.bind_address).with_context(|| {
+            format!(
+                "Failed to bind network audio source to {}",
+                network_config.bind_address
+            )
+        })?;
+        socket.set_nonblocking(true)?;
+
+        info!("Creating NetworkAudioSource with config:");
+        info!("  Address: {}", socket.local_addr()?);
+        info!("  Protocol: {:?}", network_config.protocol);
+        info!("  Sample format: {:?}", network_config.sample_format);
+        info!("  Sample rate: {} Hz", config.sample_rate);
+        info!(
+            "  Reorder window: {} packets",
+            network_config.reorder_window
+        );
+
+        Ok(Self {
+            socket,
+            config: network_config,
+            sample_rate: config.sample_rate as u32,
+            frame_size: config.frame_size as usize,
+            counters: Arc::new(NetworkCounters::default()),
+            streaming: Arc::new(AtomicBool::new(false)),
+            stream_handle: None,
+        })
+    }
+
+    /// Local address of the UDP socket
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.socket.local_addr()?)
+    }
+
+    /// Packet statistics since the source was created
+    pub fn stats(&self) -> NetworkStats {
+        self.counters.snapshot()
+    }
+}
+
+#[async_trait]
+impl RealTimeAudioSource for NetworkAudioSource {
+    async fn start_streaming(&mut self, stream: Arc<SharedAudioStream>) -> Result<()> {
+        if self.streaming.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let socket = tokio::net::UdpSocket::from_std(self.socket.try_clone()?)?;
+        self.streaming.store(true, Ordering::Relaxed);
+
+        let streaming = self.streaming.clone();
+        let counters = self.counters.clone();
+        let protocol = self.config.protocol;
+        let sample_format = self.config.sample_format;
+        let reorder_window = self.config.reorder_window;
+        let sample_rate = self.sample_rate;
+        let frame_size = self.frame_size;
+
+        let handle = tokio::spawn(async move {
+            let mut buffer = vec![0u8; MAX_PACKET_SIZE];
+            let mut sequences = SequenceTracker::new(protocol);
+            let mut reorder_buffer = ReorderBuffer::new(reorder_window);
+            let mut channel_a = Vec::new();
+            let mut channel_b = Vec::new();
+            let mut frame_number = 0u64;
+
+            while streaming.load(Ordering::Relaxed) {
+                let len = match socket.recv(&mut buffer).await {
+                    Ok(len) => len,
+                    Err(e) => {
+                        error!("Failed to receive network audio packet: {}", e);
+                        break;
+                    }
+                };
+
+                let packet =
+                    parse_packet(protocol, &buffer[..len]).and_then(|(sequence, payload)| {
+                        Ok((sequence, decode_samples(sample_format, payload)?))
+                    });
+                let (sequence, (samples_a, samples_b)) = match packet {
+                    Ok(packet) => packet,
+                    Err(e) => {
+                        counters.invalid.fetch_add(1, Ordering::Relaxed);
+                        debug!("Dropping invalid network audio packet: {}", e);
+                        continue;
+                    }
+                };
+                counters.received.fetch_add(1, Ordering::Relaxed);
+
+                reorder_buffer.push(
+                    sequences.extend(sequence),
+                    samples_a,
+                    samples_b,
+                    &mut channel_a,
+                    &mut channel_b,
+                    &counters,
+                );
+
+                while channel_a.len() >= frame_size {
+                    frame_number += 1;
+                    let audio_frame = AudioFrame::new(
+                        channel_a.drain(..frame_size).collect(),
+                        channel_b.drain(..frame_size).collect(),
+                        sample_rate,
+                        frame_number,
+                    );
+                    if let Err(e) = stream.publish(audio_frame).await {
+                        error!("Failed to publish network frame: {}", e);
+                        return;
+                    }
+                }
+            }
+        });
+
+        self.stream_handle = Some(handle);
+        Ok(())
+    }
+
+    async fn stop_streaming(&mut self) -> Result<()> {
+        self.streaming.store(false, Ordering::Relaxed);
+
+        if let Some(handle) = self.stream_handle.take() {
+            handle.abort();
+        }
+
+        Ok(())
+    }
+
+    fn is_streaming(&self) -> bool {
+        self.streaming.load(Ordering::Relaxed)
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+}
+
+/// Split a packet into its sequence number and its sample payload
+fn parse_packet(protocol: NetworkProtocol, packet: &[u8]) -> Result<(u32, &[u8])> {
+    match protocol {
+        NetworkProtocol::Raw => {
+            if packet.len() < 4 {
+                anyhow::bail!("Packet of {} bytes has no sequence number", packet.len());
+            }
+            let sequence = u32::from_be_bytes([packet[0], packet[1], packet[2], packet[3]]);
+            Ok((sequence, &packet[4..]))
+        }
+        NetworkProtocol::Rtp => {
+            if packet.len() < RTP_HEADER_SIZE {
+                anyhow::bail!(
+                    "RTP packet of {} bytes is shorter than its header",
+                    packet.len()
+                );
+            }
+            let version = packet[0] >> 6;
+            if version != 2 {
+                anyhow::bail!("Unsupported RTP version {}", version);
+            }
+
+            let csrc_count = (packet[0] & 0x0F) as usize;
+            let mut offset = RTP_HEADER_SIZE + 4 * csrc_count;
+            if packet[0] & 0x10 != 0 {
+                // Header extension: profile and length in 32-bit words
+                let extension = packet
+                    .get(offset..offset + 4)
+                    .ok_or_else(|| anyhow!("Truncated RTP header extension"))?;
+                offset += 4 + 4 * u16::from_be_bytes([extension[2], extension[3]]) as usize;
+            }
+            let mut end = packet.len();
+            if packet[0] & 0x20 != 0 {
+                // The last byte counts the padding bytes, itself included
+                end = end.saturating_sub(packet[packet.len() - 1] as usize);
+            }
+
+            let payload = packet
+                .get(offset..end)
+                .ok_or_else(|| anyhow!("Truncated RTP packet of {} bytes", packet.len()))?;
+            Ok((u16::from_be_bytes([packet[2], packet[3]]) as u32, payload))
+        }
+    }
+}
+
+/// Deinterleave a payload into the samples of channel A and channel B
+fn decode_samples(format: NetworkSampleFormat, payload: &[u8]) -> Result<(Vec<f32>, Vec<f32>)> {
+    let sample_size = format.sample_size();
+    if payload.len() % (2 * sample_size) != 0 {
+        anyhow::bail!(
+            "Payload of {} bytes is not a whole number of stereo samples",
+            payload.len()
+        );
+    }
+
+    let decode = |bytes: &[u8]| match format {
+        NetworkSampleFormat::S16be => i16_to_f32(i16::from_be_bytes([bytes[0], bytes[1]])),
+        NetworkSampleFormat::S16le => i16_to_f32(i16::from_le_bytes([bytes[0], bytes[1]])),
+        NetworkSampleFormat::F32le => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+    };
+
+    let samples = payload.len() / (2 * sample_size);
+    let mut channel_a = Vec::with_capacity(samples);
+    let mut channel_b = Vec::with_capacity(samples);
+    for chunk in payload.chunks_exact(2 * sample_size) {
+        channel_a.push(decode(&chunk[..sample_size]));
+        channel_b.push(decode(&chunk[sample_size..]));
+    }
+    Ok((channel_a, channel_b))
+}
+
+fn i16_to_f32(sample: i16) -> f32 {
+    if sample >= 0 {
+        sample as f32 / i16::MAX as f32
+    } else {
+        sample as f32 / -(i16::MIN as f32)
+    }
+}
+
+/// Extends the wrapping sequence numbers of a protocol to 64 bits
+struct SequenceTracker {
+    modulus: i64,
+    highest: Option<i64>,
+}
+
+impl SequenceTracker {
+    fn new(protocol: NetworkProtocol) -> Self {
+        let bits = match protocol {
+            NetworkProtocol::Rtp => 16,
+            NetworkProtocol::Raw => 32,
+        };
+        Self {
+            modulus: 1 << bits,
+            highest: None,
+        }
+    }
+
+    /// Extended sequence number of a packet, the nearest to the highest one seen
+    fn extend(&mut self, sequence: u32) -> i64 {
+        let Some(highest) = self.highest else {
+            self.highest = Some(sequence as i64);
+            return sequence as i64;
+        };
+
+        let mut delta = (sequence as i64 - highest).rem_euclid(self.modulus);
+        if delta >= self.modulus / 2 {
+            delta -= self.modulus;
+        }
+        let extended = highest + delta;
+        if delta > 0 {
+            self.highest = Some(extended);
+        }
+        extended
+    }
+}
+
+/// Puts the packets back in sequence order and conceals the lost ones
+struct ReorderBuffer {
+    window: usize,
+    next: Option<i64>,
+    pending: BTreeMap<i64, (Vec<f32>, Vec<f32>)>,
+    last_packet_len: usize,
+    /// Consecutive packets more than [`MAX_CONCEALED_PACKETS`] behind
+    stale_packets: i64,
+}
+
+impl ReorderBuffer {
+    fn new(window: usize) -> Self {
+        Self {
+            window,
+            next: None,
+            pending: BTreeMap::new(),
+            last_packet_len: 0,
+            stale_packets: 0,
+        }
+    }
+
+    /// Insert a packet and append the samples now in order to the channels
+    fn push(
+        &mut self,
+        sequence: i64,
+        samples_a: Vec<f32>,
+        samples_b: Vec<f32>,
+        channel_a: &mut Vec<f32>,
+        channel_b: &mut Vec<f32>,
+        counters: &NetworkCounters,
+    ) {
+        let mut next = *self.next.get_or_insert(sequence);
+
+        // A single straggler far behind is late, a run of them is a restarted sender
+        if next - sequence > MAX_CONCEALED_PACKETS {
+            self.stale_packets += 1;
+        } else {
+            self.stale_packets = 0;
+        }
+        if sequence - next > MAX_CONCEALED_PACKETS || self.stale_packets >= MAX_CONCEALED_PACKETS {
+            warn!(
+                "Network audio sequence jumped from {} to {}, resynchronizing",
+                next, sequence
+            );
+            self.pending.clear();
+            self.stale_packets = 0;
+            next = sequence;
+        }
+
+        if sequence < next || self.pending.contains_key(&sequence) {
+            counters.late.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        if sequence > next {
+            self.pending.insert(sequence, (samples_a, samples_b));
+        } else {
+            if !self.pending.is_empty() {
+                counters.reordered.fetch_add(1, Ordering::Relaxed);
+            }
+            self.emit(samples_a, samples_b, channel_a, channel_b);
+            next += 1;
+        }
+
+        loop {
+            while let Some((samples_a, samples_b)) = self.pending.remove(&next) {
+                self.emit(samples_a, samples_b, channel_a, channel_b);
+                next += 1;
+            }
+            if self.pending.len() <= self.window {
+                break;
+            }
+
+            // Stop waiting for the missing packets
+            let Some(&first_pending) = self.pending.keys().next() else {
+                break;
+            };
+            let lost = first_pending - next;
+            counters.lost.fetch_add(lost as u64, Ordering::Relaxed);
+            debug!("Concealing {} lost network audio packets", lost);
+            let silence = self.last_packet_len * lost as usize;
+            channel_a.resize(channel_a.len() + silence, 0.0);
+            channel_b.resize(channel_b.len() + silence, 0.0);
+            next = first_pending;
+        }
+
+        self.next = Some(next);
+    }
+
+    fn emit(
+        &mut self,
+        samples_a: Vec<f32>,
+        samples_b: Vec<f32>,
+        channel_a: &mut Vec<f32>,
+        channel_b: &mut Vec<f32>,
+    ) {
+        self.last_packet_len = samples_a.len();
+        channel_a.extend(samples_a);
+        channel_b.extend(samples_b);
+    }
+}
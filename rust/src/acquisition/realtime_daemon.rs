@@ -41,6 +41,22 @@ impl RealTimeAcquisitionDaemon {
         }
     }
 
+    /// Create a real-time acquisition daemon publishing to an existing stream
+    ///
+    /// Used to restart the acquisition without losing the subscribers of the
+    /// stream, such as the processing consumer and the web clients.
+    pub fn with_stream(
+        source: Box<dyn RealTimeAudioSource>,
+        stream: Arc<SharedAudioStream>,
+    ) -> Self {
+        Self {
+            source,
+            stream,
+            running: Arc::new(AtomicBool::new(false)),
+            stats_handle: None,
+        }
+    }
+
     /// Get a reference to the shared audio stream
     pub fn get_shared_stream(&self) -> Arc<SharedAudioStream> {
         self.stream.clone()
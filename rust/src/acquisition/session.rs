@@ -0,0 +1,515 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Session recording and replay
+//!
+//! A session file captures the exact input of a processing graph during a run:
+//! the [`AudioFrame`]s it executed, with their frame numbers and timestamps, and
+//! the [`ProcessingGraphConfig`] of the graph. [`SessionRecorder`] writes it,
+//! usually through [`ProcessingConsumer::record_session`](crate::processing::ProcessingConsumer::record_session),
+//! and [`ReplaySource`] reads it back so that the run can be reproduced offline:
+//! a graph built from the recorded configuration and fed the replayed frames
+//! produces the same results, bit for bit.
+//!
+//! ### File Format
+//!
+//! The file is made of JSON lines: a header with the graph configuration, then
+//! one line per frame. Samples are stored as base64-encoded little-endian `f32`,
+//! which keeps them exact and compact.
+//!
+//! ```text
+//! {"format":"rust-photoacoustic-session","version":1,"recorded_at":1718000000000,"graph":{"id":"default",...}}
+//! {"frame_number":1,"timestamp":1718000000021,"sample_rate":48000,"channel_a":"AAB...","channel_b":"AAC..."}
+//! ```
+//!
+//! ### Replay Timing
+//!
+//! Streaming with [`RealTimeAudioSource`], the frames are published either at
+//! the pace given by their timestamps ([`ReplayTiming::Original`]) or as fast as
+//! the consumers of the stream keep up ([`ReplayTiming::AsFastAsPossible`]). In
+//! both cases no frame is dropped and the frames keep their recorded numbers
+//! and timestamps. Read with [`ReplaySource::next_frame`] or [`AudioSource`],
+//! the frames come without pacing.
+//!
+//! ### Example
+//!
+//! ```no_run
+//! use rust_photoacoustic::acquisition::{ReplaySource, ReplayTiming};
+//! use rust_photoacoustic::processing::{ProcessingData, ProcessingGraph};
+//!
+//! let mut replay = ReplaySource::open("session.jsonl", ReplayTiming::AsFastAsPossible)?;
+//! let mut graph = ProcessingGraph::from_config(replay.graph_config())?;
+//! while let Some(frame) = replay.next_frame()? {
+//!     let results = graph.execute(ProcessingData::AudioFrame(frame))?;
+//!     println!("{} results", results.len());
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use super::{AudioFrame, AudioSource, RealTimeAudioSource, SharedAudioStream};
+use crate::config::processing::ProcessingGraphConfig;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use base64::prelude::{Engine as _, BASE64_STANDARD};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Lines, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc, Arc,
+};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Format identifier of the session header
+const SESSION_FORMAT: &str = "rust-photoacoustic-session";
+
+/// Version of the session file format
+const SESSION_VERSION: u32 = 1;
+
+/// Period at which an as-fast-as-possible replay checks whether the stream has room
+const STREAM_FULL_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// First line of a session file
+#[derive(Debug, Serialize, Deserialize)]
+struct SessionHeader {
+    format: String,
+    version: u32,
+    /// Start of the recording, in milliseconds since the Unix epoch
+    recorded_at: u64,
+    graph: ProcessingGraphConfig,
+}
+
+/// A recorded frame, one line of a session file
+#[derive(Debug, Serialize, Deserialize)]
+struct SessionFrame {
+    frame_number: u64,
+    timestamp: u64,
+    sample_rate: u32,
+    channel_a: String,
+    channel_b: String,
+}
+
+impl SessionFrame {
+    fn from_frame(frame: &AudioFrame) -> Self {
+        Self {
+            frame_number: frame.frame_number,
+            timestamp: frame.timestamp,
+            sample_rate: frame.sample_rate,
+            channel_a: encode_samples(&frame.channel_a),
+            channel_b: encode_samples(&frame.channel_b),
+        }
+    }
+
+    fn into_frame(self) -> Result<AudioFrame> {
+        Ok(AudioFrame {
+            channel_a: decode_samples(&self.channel_a)?,
+            channel_b: decode_samples(&self.channel_b)?,
+            sample_rate: self.sample_rate,
+            timestamp: self.timestamp,
+            frame_number: self.frame_number,
+        })
+    }
+}
+
+/// Encode samples as base64 little-endian `f32`
+fn encode_samples(samples: &[f32]) -> String {
+    let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+    BASE64_STANDARD.encode(bytes)
+}
+
+/// Decode samples encoded by [`encode_samples`]
+fn decode_samples(encoded: &str) -> Result<Vec<f32>> {
+    let bytes = BASE64_STANDARD.decode(encoded)?;
+    if bytes.len() % 4 != 0 {
+        return Err(anyhow!("Truncated samples in session frame"));
+    }
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect())
+}
+
+/// Writer of a session file
+///
+/// The header is written on creation, each recorded frame is appended as one
+/// line. Frames are queued to a writer thread so that recording never blocks
+/// the processing loop on file IO: call [`finish`](Self::finish) to wait for
+/// them to be written and flushed.
+pub struct SessionRecorder {
+    path: PathBuf,
+    /// Queue of the writer thread, closed to stop it
+    frames: Option<mpsc::Sender<AudioFrame>>,
+    /// Writer thread, returning the number of frames written
+    writer: Option<JoinHandle<Result<u64>>>,
+    frames_recorded: u64,
+}
+
+impl SessionRecorder {
+    /// Create, or overwrite, a session file
+    ///
+    /// ### Arguments
+    ///
+    /// * `path` - Path of the session file
+    /// * `graph` - Configuration of the graph executing the recorded frames
+    ///
+    /// ### Returns
+    ///
+    /// The recorder, or an error if the file cannot be written
+    pub fn create(path: impl AsRef<Path>, graph: &ProcessingGraphConfig) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = File::create(&path).map_err(|e| {
+            anyhow!(
+                "Failed to create session recording {}: {}",
+                path.display(),
+                e
+            )
+        })?;
+        let mut writer = BufWriter::new(file);
+
+        let header = SessionHeader {
+            format: SESSION_FORMAT.to_string(),
+            version: SESSION_VERSION,
+            recorded_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            graph: graph.clone(),
+        };
+        serde_json::to_writer(&mut writer, &header)?;
+        writer.write_all(b"\n")?;
+
+        let (frames, queue) = mpsc::channel::<AudioFrame>();
+        let writer = thread::Builder::new()
+            .name("session-recorder".to_string())
+            .spawn(move || -> Result<u64> {
+                let mut frames_written = 0;
+                for frame in queue {
+                    serde_json::to_writer(&mut writer, &SessionFrame::from_frame(&frame))?;
+                    writer.write_all(b"\n")?;
+                    frames_written += 1;
+                }
+                writer.flush()?;
+                Ok(frames_written)
+            })?;
+
+        info!(
+            "Recording session of graph '{}' to {}",
+            graph.id,
+            path.display()
+        );
+        Ok(Self {
+            path,
+            frames: Some(frames),
+            writer: Some(writer),
+            frames_recorded: 0,
+        })
+    }
+
+    /// Path of the session file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of frames recorded so far, including the ones not written yet
+    pub fn frames_recorded(&self) -> u64 {
+        self.frames_recorded
+    }
+
+    /// Queue a frame to be appended to the session
+    ///
+    /// ### Returns
+    ///
+    /// The error of the writer thread if it stopped on a write failure, the
+    /// recorder is unusable afterwards
+    pub fn record(&mut self, frame: &AudioFrame) -> Result<()> {
+        let queued = self
+            .frames
+            .as_ref()
+            .is_some_and(|frames| frames.send(frame.clone()).is_ok());
+        if !queued {
+            return Err(self
+                .stop_writer()
+                .err()
+                .unwrap_or_else(|| anyhow!("Session writer stopped")));
+        }
+        self.frames_recorded += 1;
+        Ok(())
+    }
+
+    /// Wait for the queued frames to be written, then flush the session file
+    ///
+    /// ### Returns
+    ///
+    /// The number of recorded frames
+    pub fn finish(mut self) -> Result<u64> {
+        let frames_written = self.stop_writer()?;
+        info!(
+            "Session recording {} closed after {} frames",
+            self.path.display(),
+            frames_written
+        );
+        Ok(frames_written)
+    }
+
+    /// Close the queue and wait for the writer thread to end
+    fn stop_writer(&mut self) -> Result<u64> {
+        self.frames.take();
+        let writer = self
+            .writer
+            .take()
+            .ok_or_else(|| anyhow!("Session writer already stopped"))?;
+        writer
+            .join()
+            .map_err(|_| anyhow!("Session writer of {} panicked", self.path.display()))?
+    }
+}
+
+/// Pace of the frames streamed by a [`ReplaySource`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplayTiming {
+    /// Publish the frames at the intervals of their recorded timestamps
+    #[default]
+    Original,
+    /// Publish the frames as soon as the stream has room for them
+    AsFastAsPossible,
+}
+
+/// Reader of the frames of a session file
+struct SessionReader {
+    lines: Lines<BufReader<File>>,
+    line_number: usize,
+}
+
+impl SessionReader {
+    /// Open a session file and read its header
+    fn open(path: &Path) -> Result<(Self, SessionHeader)> {
+        let file = File::open(path)
+            .map_err(|e| anyhow!("Failed to open session {}: {}", path.display(), e))?;
+        let mut lines = BufReader::new(file).lines();
+        let header: SessionHeader = match lines.next() {
+            Some(line) => serde_json::from_str(&line?)
+                .map_err(|e| anyhow!("Invalid session header in {}: {}", path.display(), e))?,
+            None => return Err(anyhow!("Empty session file {}", path.display())),
+        };
+        if header.format != SESSION_FORMAT {
+            return Err(anyhow!(
+                "{} is not a session file (format '{}')",
+                path.display(),
+                header.format
+            ));
+        }
+        if header.version != SESSION_VERSION {
+            return Err(anyhow!(
+                "Unsupported session version {} in {}",
+                header.version,
+                path.display()
+            ));
+        }
+
+        Ok((
+            Self {
+                lines,
+                line_number: 1,
+            },
+            header,
+        ))
+    }
+
+    /// Read the next frame, `None` at the end of the session
+    fn next_frame(&mut self) -> Result<Option<AudioFrame>> {
+        for line in self.lines.by_ref() {
+            self.line_number += 1;
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let frame: SessionFrame = serde_json::from_str(&line).map_err(|e| {
+                anyhow!("Invalid session frame at line {}: {}", self.line_number, e)
+            })?;
+            return frame.into_frame().map(Some);
+        }
+        Ok(None)
+    }
+}
+
+/// Audio source replaying the frames of a session file
+///
+/// The frames keep their recorded channels, sample rate, frame numbers and
+/// timestamps. See the [module documentation](self) for the timing of the
+/// replay.
+pub struct ReplaySource {
+    path: PathBuf,
+    timing: ReplayTiming,
+    graph: ProcessingGraphConfig,
+    recorded_at: u64,
+    reader: SessionReader,
+    // First frame, read on opening for the sample rate
+    pending: Option<AudioFrame>,
+    sample_rate: u32,
+    // Real-time streaming support
+    streaming: Arc<AtomicBool>,
+    stream_handle: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl ReplaySource {
+    /// Open a session file
+    ///
+    /// ### Arguments
+    ///
+    /// * `path` - Path of the session file
+    /// * `timing` - Pace of the streamed frames
+    ///
+    /// ### Returns
+    ///
+    /// The replay source, or an error if the file is not a valid session
+    pub fn open(path: impl AsRef<Path>, timing: ReplayTiming) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let (mut reader, header) = SessionReader::open(&path)?;
+        let pending = reader.next_frame()?;
+        let sample_rate = pending.as_ref().map_or(0, |frame| frame.sample_rate);
+
+        Ok(Self {
+            path,
+            timing,
+            graph: header.graph,
+            recorded_at: header.recorded_at,
+            reader,
+            pending,
+            sample_rate,
+            streaming: Arc::new(AtomicBool::new(false)),
+            stream_handle: None,
+        })
+    }
+
+    /// Configuration of the graph the session was recorded with
+    pub fn graph_config(&self) -> &ProcessingGraphConfig {
+        &self.graph
+    }
+
+    /// Start of the recording, in milliseconds since the Unix epoch
+    pub fn recorded_at(&self) -> u64 {
+        self.recorded_at
+    }
+
+    /// Pace of the streamed frames
+    pub fn timing(&self) -> ReplayTiming {
+        self.timing
+    }
+
+    /// Read the next recorded frame, without pacing
+    ///
+    /// ### Returns
+    ///
+    /// The frame, `None` once every frame was read
+    pub fn next_frame(&mut self) -> Result<Option<AudioFrame>> {
+        match self.pending.take() {
+            Some(frame) => Ok(Some(frame)),
+            None => self.reader.next_frame(),
+        }
+    }
+}
+
+impl AudioSource for ReplaySource {
+    fn read_frame(&mut self) -> Result<(Vec<f32>, Vec<f32>)> {
+        match self.next_frame()? {
+            Some(frame) => Ok((frame.channel_a, frame.channel_b)),
+            None => Err(anyhow!("End of session {}", self.path.display())),
+        }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+}
+
+#[async_trait]
+impl RealTimeAudioSource for ReplaySource {
+    async fn start_streaming(&mut self, stream: Arc<SharedAudioStream>) -> Result<()> {
+        if self.streaming.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        // Replay from the first frame, independently of the frames already read
+        let (mut reader, _) = SessionReader::open(&self.path)?;
+        self.streaming.store(true, Ordering::Relaxed);
+
+        let streaming = self.streaming.clone();
+        let timing = self.timing;
+        let path = self.path.clone();
+
+        let handle = tokio::spawn(async move {
+            let start = Instant::now();
+            let mut first_timestamp = None;
+            let mut frames_published = 0u64;
+
+            while streaming.load(Ordering::Relaxed) {
+                let frame = match reader.next_frame() {
+                    Ok(Some(frame)) => frame,
+                    Ok(None) => {
+                        info!(
+                            "Replayed {} frames of session {}",
+                            frames_published,
+                            path.display()
+                        );
+                        break;
+                    }
+                    Err(e) => {
+                        error!("Error reading session {}: {}", path.display(), e);
+                        break;
+                    }
+                };
+
+                match timing {
+                    ReplayTiming::Original => {
+                        let first = *first_timestamp.get_or_insert(frame.timestamp);
+                        let due =
+                            start + Duration::from_millis(frame.timestamp.saturating_sub(first));
+                        tokio::time::sleep_until(due.into()).await;
+                    }
+                    ReplayTiming::AsFastAsPossible => {
+                        // A full stream would overwrite frames the consumers have not read
+                        while stream.queued_frames() >= stream.capacity()
+                            && streaming.load(Ordering::Relaxed)
+                        {
+                            tokio::time::sleep(STREAM_FULL_POLL_INTERVAL).await;
+                        }
+                    }
+                }
+
+                if let Err(e) = stream.publish(frame).await {
+                    error!("Failed to publish replayed frame: {}", e);
+                    break;
+                }
+                frames_published += 1;
+            }
+
+            streaming.store(false, Ordering::Relaxed);
+        });
+
+        self.stream_handle = Some(handle);
+        Ok(())
+    }
+
+    async fn stop_streaming(&mut self) -> Result<()> {
+        self.streaming.store(false, Ordering::Relaxed);
+
+        if let Some(handle) = self.stream_handle.take() {
+            handle.abort();
+        }
+
+        Ok(())
+    }
+
+    /// Whether frames are being replayed, `false` once the session is over
+    fn is_streaming(&self) -> bool {
+        self.streaming.load(Ordering::Relaxed)
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+}
@@ -7,6 +7,12 @@
 //! This module provides a comprehensive simulated photoacoustic audio source that uses
 //! the `generate_universal_photoacoustic_stereo` function to create realistic synthetic
 //! photoacoustic signals for testing and development purposes.
+//!
+//! The signal amplitude follows the `gas_events` schedule of the
+//! [`SimulatedSourceConfig`], evaluated on the simulated time of each frame,
+//! so that concentration changes can be scripted deterministically. White
+//! and pink noise, a narrowband interferer and mains hum can be mixed into
+//! the output at configured levels to control the signal-to-noise ratio.
 
 use super::{AudioFrame, RealTimeAudioSource, SharedAudioStream};
 use crate::config::{PhotoacousticConfig, SimulatedSourceConfig};
@@ -32,6 +38,7 @@ use std::time::{Duration, Instant};
 /// - Gas flow noise with 1/f characteristics from turbulent flow
 /// - Thermal drift effects on frequency and phase stability
 /// - Molecular concentration variations (random walk simulation)
+/// - Scheduled concentration changes (`gas_events` ramps)
 /// - Laser modulation (both amplitude and pulsed modes)
 /// - Environmental perturbations and system noise
 ///
@@ -52,6 +59,10 @@ pub struct SimulatedPhotoacousticRealtimeAudioSource {
     simulation_config: SimulatedSourceConfig,
     /// Timing control for real-time simulation
     last_frame_time: Option<Instant>,
+    /// Number of frames generated by [`Self::next_frame`]
+    frames_generated: u64,
+    /// Noise and interference state of [`Self::next_frame`]
+    interference: InterferenceMixer,
     /// Duration of each frame for timing control
     frame_duration: Duration,
     /// Whether to simulate real-time timing
@@ -89,7 +100,7 @@ impl SimulatedPhotoacousticRealtimeAudioSource {
         config: PhotoacousticConfig,
         simulation_config: SimulatedSourceConfig,
     ) -> Result<Self> {
-        let generator = NoiseGenerator::new_from_system_time();
+        let generator = NoiseGenerator::from_seed_or_system_time(simulation_config.rng_seed);
         let sample_rate = config.sample_rate as u32;
         let frame_size = config.frame_size as usize;
 
@@ -122,6 +133,9 @@ impl SimulatedPhotoacousticRealtimeAudioSource {
         );
         info!("  SNR factor: {} dB", simulation_config.snr_factor);
         info!("  Modulation mode: {}", simulation_config.modulation_mode);
+        if let Some(seed) = simulation_config.rng_seed {
+            info!("  RNG seed: {}", seed);
+        }
 
         Ok(Self {
             generator,
@@ -130,6 +144,8 @@ impl SimulatedPhotoacousticRealtimeAudioSource {
             config,
             simulation_config,
             last_frame_time: None,
+            frames_generated: 0,
+            interference: InterferenceMixer::default(),
             frame_duration,
             real_time_mode: true, // Enable real-time simulation by default
             streaming: Arc::new(AtomicBool::new(false)),
@@ -150,6 +166,9 @@ impl SimulatedPhotoacousticRealtimeAudioSource {
     /// This allows runtime modification of simulation parameters without
     /// recreating the entire source.
     pub fn update_simulation_config(&mut self, new_config: SimulatedSourceConfig) {
+        if new_config.rng_seed.is_some() && new_config.rng_seed != self.simulation_config.rng_seed {
+            self.generator = NoiseGenerator::from_seed_or_system_time(new_config.rng_seed);
+        }
         self.simulation_config = new_config;
         debug!("Updated simulation configuration");
         debug!(
@@ -167,29 +186,168 @@ impl SimulatedPhotoacousticRealtimeAudioSource {
         debug!("  SNR factor: {} dB", self.simulation_config.snr_factor);
     }
 
-    /// Generate a single frame of simulated photoacoustic data
+    /// Generate the next frame of simulated photoacoustic data
     ///
-    /// Uses the `generate_universal_photoacoustic_stereo` function to create
-    /// realistic photoacoustic signals with comprehensive physics modeling.
-    fn generate_frame(&mut self) -> Vec<i16> {
-        self.generator.generate_universal_photoacoustic_stereo(
-            self.frame_size as u32,
+    /// Frames are numbered from 1 and generated without real-time pacing, the
+    /// same way the streaming task does. Useful to inspect the simulated
+    /// signal without a running stream.
+    pub fn next_frame(&mut self) -> AudioFrame {
+        self.frames_generated += 1;
+        generate_simulated_frame(
+            &mut self.generator,
+            &mut self.interference,
+            self.frame_size,
             self.sample_rate,
-            self.simulation_config.background_noise_amplitude,
-            self.simulation_config.resonance_frequency,
-            self.simulation_config.laser_modulation_depth,
-            self.simulation_config.signal_amplitude,
-            self.simulation_config.phase_opposition_degrees,
-            self.simulation_config.temperature_drift_factor,
-            self.simulation_config.gas_flow_noise_factor,
-            self.simulation_config.snr_factor,
-            &self.simulation_config.modulation_mode,
-            self.simulation_config.pulse_width_seconds,
-            self.simulation_config.pulse_frequency_hz,
+            &self.simulation_config,
+            self.frames_generated,
         )
     }
 }
 
+/// Generate frame `frame_number` (counted from 1) of the simulated signal
+///
+/// Uses the `generate_universal_photoacoustic_stereo` function to create
+/// realistic photoacoustic signals with comprehensive physics modeling. The
+/// signal amplitude is taken from the gas event schedule at the simulated
+/// start time of the frame, then the configured noise and interference are
+/// mixed in.
+fn generate_simulated_frame(
+    generator: &mut NoiseGenerator,
+    interference: &mut InterferenceMixer,
+    frame_size: usize,
+    sample_rate: u32,
+    simulation_config: &SimulatedSourceConfig,
+    frame_number: u64,
+) -> AudioFrame {
+    let elapsed_seconds = (frame_number - 1) as f64 * frame_size as f64 / sample_rate as f64;
+
+    // Generate comprehensive photoacoustic simulation data
+    let samples = generator.generate_universal_photoacoustic_stereo(
+        frame_size as u32,
+        sample_rate,
+        simulation_config.background_noise_amplitude,
+        simulation_config.resonance_frequency,
+        simulation_config.laser_modulation_depth,
+        simulation_config.signal_amplitude_at(elapsed_seconds),
+        simulation_config.phase_opposition_degrees,
+        simulation_config.temperature_drift_factor,
+        simulation_config.gas_flow_noise_factor,
+        simulation_config.snr_factor,
+        &simulation_config.modulation_mode,
+        simulation_config.pulse_width_seconds,
+        simulation_config.pulse_frequency_hz,
+    );
+
+    // Convert interleaved stereo i16 samples to separate f32 channels
+    let mut channel_a = Vec::with_capacity(frame_size);
+    let mut channel_b = Vec::with_capacity(frame_size);
+
+    // Conversion function from i16 to f32 in range [-1.0, 1.0]
+    let i16_to_f32 = |sample: i16| -> f32 {
+        if sample >= 0 {
+            sample as f32 / i16::MAX as f32
+        } else {
+            sample as f32 / -(i16::MIN as f32)
+        }
+    };
+
+    // Deinterleave stereo samples into separate channels
+    for chunk in samples.chunks_exact(2) {
+        channel_a.push(i16_to_f32(chunk[0]));
+        channel_b.push(i16_to_f32(chunk[1]));
+    }
+
+    interference.mix(
+        generator,
+        simulation_config,
+        sample_rate,
+        (frame_number - 1) * frame_size as u64,
+        [&mut channel_a[..], &mut channel_b[..]],
+    );
+
+    AudioFrame::new(channel_a, channel_b, sample_rate, frame_number)
+}
+
+/// Normalization of the pink noise filter to a unit RMS output
+///
+/// Square root of the energy of the filter impulse response.
+const PINK_NOISE_RMS_GAIN: f32 = 3.052_527_5;
+
+/// Noise and interference mixed into the final simulated output
+///
+/// Keeps the pink noise filters of both channels across frames. Tones are
+/// computed from the absolute sample index, so their phase is continuous
+/// between frames.
+#[derive(Debug, Default)]
+struct InterferenceMixer {
+    /// Paul Kellet's pink noise filter state, one per channel
+    pink_state: [[f32; 7]; 2],
+}
+
+impl InterferenceMixer {
+    /// Add the configured noise and tones to `channels`
+    ///
+    /// Nothing is drawn from `generator` when no noise is configured, so the
+    /// default configuration produces the same signal as without the mixer.
+    ///
+    /// ### Parameters
+    ///
+    /// * `first_sample` - Absolute index of the first sample of the frame
+    /// * `channels` - Channels A and B, clamped to [-1.0, 1.0] after mixing
+    fn mix(
+        &mut self,
+        generator: &mut NoiseGenerator,
+        config: &SimulatedSourceConfig,
+        sample_rate: u32,
+        first_sample: u64,
+        channels: [&mut [f32]; 2],
+    ) {
+        let tones: Vec<_> = [config.interferer, config.mains_hum]
+            .into_iter()
+            .flatten()
+            .filter(|tone| tone.amplitude > 0.0)
+            .collect();
+        if config.white_noise_level <= 0.0 && config.pink_noise_level <= 0.0 && tones.is_empty() {
+            return;
+        }
+
+        for (channel, pink_state) in channels.into_iter().zip(self.pink_state.iter_mut()) {
+            for (index, sample) in channel.iter_mut().enumerate() {
+                let mut value = *sample;
+                if config.white_noise_level > 0.0 {
+                    value += generator.random_gaussian() * config.white_noise_level;
+                }
+                if config.pink_noise_level > 0.0 {
+                    let pink = pink_noise(pink_state, generator.random_gaussian());
+                    value += pink / PINK_NOISE_RMS_GAIN * config.pink_noise_level;
+                }
+                if !tones.is_empty() {
+                    // Phase computed in f64 to stay accurate over long runs
+                    let t = (first_sample + index as u64) as f64 / sample_rate as f64;
+                    for tone in &tones {
+                        let phase = 2.0 * std::f64::consts::PI * tone.frequency_hz as f64 * t;
+                        value += tone.amplitude * phase.sin() as f32;
+                    }
+                }
+                *sample = value.clamp(-1.0, 1.0);
+            }
+        }
+    }
+}
+
+/// Filter `white` into pink noise with Paul Kellet's refined method
+fn pink_noise(state: &mut [f32; 7], white: f32) -> f32 {
+    state[0] = 0.99886 * state[0] + white * 0.0555179;
+    state[1] = 0.99332 * state[1] + white * 0.0750759;
+    state[2] = 0.96900 * state[2] + white * 0.1538520;
+    state[3] = 0.86650 * state[3] + white * 0.3104856;
+    state[4] = 0.55000 * state[4] + white * 0.5329522;
+    state[5] = -0.7616 * state[5] - white * 0.0168980;
+    let pink = state[..6].iter().sum::<f32>() + state[6] + white * 0.5362;
+    state[6] = white * 0.115926;
+    pink
+}
+
 #[async_trait]
 impl RealTimeAudioSource for SimulatedPhotoacousticRealtimeAudioSource {
     async fn start_streaming(&mut self, stream: Arc<SharedAudioStream>) -> Result<()> {
@@ -208,9 +366,11 @@ impl RealTimeAudioSource for SimulatedPhotoacousticRealtimeAudioSource {
 
         // Clone simulation config for the async task
         let simulation_config = self.simulation_config.clone();
+        // Start from the source's generator so that a configured seed is honored
+        let mut generator = self.generator;
 
         let handle = tokio::spawn(async move {
-            let mut generator = NoiseGenerator::new_from_system_time();
+            let mut interference = InterferenceMixer::default();
             let mut frame_number = 0u64;
             let mut last_time = Instant::now();
 
@@ -224,47 +384,16 @@ impl RealTimeAudioSource for SimulatedPhotoacousticRealtimeAudioSource {
                     last_time = Instant::now();
                 }
 
-                // Generate comprehensive photoacoustic simulation data
-                let samples = generator.generate_universal_photoacoustic_stereo(
-                    frame_size as u32,
+                frame_number += 1;
+                let audio_frame = generate_simulated_frame(
+                    &mut generator,
+                    &mut interference,
+                    frame_size,
                     sample_rate,
-                    simulation_config.background_noise_amplitude,
-                    simulation_config.resonance_frequency,
-                    simulation_config.laser_modulation_depth,
-                    simulation_config.signal_amplitude,
-                    simulation_config.phase_opposition_degrees,
-                    simulation_config.temperature_drift_factor,
-                    simulation_config.gas_flow_noise_factor,
-                    simulation_config.snr_factor,
-                    &simulation_config.modulation_mode,
-                    simulation_config.pulse_width_seconds,
-                    simulation_config.pulse_frequency_hz,
+                    &simulation_config,
+                    frame_number,
                 );
 
-                // Convert interleaved stereo i16 samples to separate f32 channels
-                let mut channel_a = Vec::with_capacity(frame_size);
-                let mut channel_b = Vec::with_capacity(frame_size);
-
-                // Conversion function from i16 to f32 in range [-1.0, 1.0]
-                let i16_to_f32 = |sample: i16| -> f32 {
-                    if sample >= 0 {
-                        sample as f32 / i16::MAX as f32
-                    } else {
-                        sample as f32 / -(i16::MIN as f32)
-                    }
-                };
-
-                // Deinterleave stereo samples into separate channels
-                for chunk in samples.chunks_exact(2) {
-                    let left = i16_to_f32(chunk[0]);
-                    let right = i16_to_f32(chunk[1]);
-                    channel_a.push(left);
-                    channel_b.push(right);
-                }
-
-                frame_number += 1;
-                let audio_frame = AudioFrame::new(channel_a, channel_b, sample_rate, frame_number);
-
                 if let Err(e) = stream.publish(audio_frame).await {
                     error!("Failed to publish simulated photoacoustic frame: {}", e);
                     break;
@@ -6,13 +6,38 @@
 //!
 //! This module provides a shared data structure for streaming audio frames
 //! between the acquisition daemon and web clients in real-time.
+//!
+//! The stream buffers a bounded number of frames for its consumers. The
+//! [`StreamStats`] report the fill level of this buffer and the latency it
+//! adds, and flag a buffer chronically near full (the producer outpaces the
+//! slowest consumer, which will lose frames) or consumers chronically waiting
+//! for frames (underruns: the producer is late).
 
 use anyhow::Result;
+use log::warn;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::{broadcast, RwLock};
 
+/// Weight of the latest frame in the averaged fill level and underrun ratio
+const AVERAGE_WEIGHT: f64 = 0.1;
+
+/// Averaged fill level above which the buffer is reported near full
+const NEAR_FULL_RATIO: f64 = 0.9;
+
+/// Averaged fill level below which a near full buffer is reported recovered
+const NEAR_FULL_CLEAR_RATIO: f64 = 0.5;
+
+/// Share of underruns among the recent reads above which consumers are reported starved
+const NEAR_EMPTY_RATIO: f64 = 0.5;
+
+/// Share of underruns below which starved consumers are reported recovered
+const NEAR_EMPTY_CLEAR_RATIO: f64 = 0.1;
+
+/// A read is an underrun when the consumer waited longer than this number of frame durations
+const UNDERRUN_FRAME_DURATIONS: f64 = 2.0;
+
 /// Represents a frame of audio data with metadata
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct AudioFrame {
@@ -81,6 +106,8 @@ pub struct SharedAudioStream {
     latest_frame: Arc<RwLock<Option<AudioFrame>>>,
     /// Stream statistics
     stats: Arc<RwLock<StreamStats>>,
+    /// Number of frames buffered for the consumers
+    capacity: usize,
 }
 
 /// Statistics about the audio stream
@@ -102,6 +129,36 @@ pub struct StreamStats {
     pub sample_rate: u32,
     /// Whether the stream has dual channels (true) or is mono (false)
     pub dual_channel: bool,
+    /// Number of frames the stream buffers for its consumers
+    #[serde(default)]
+    pub buffer_capacity: usize,
+    /// Frames queued for the slowest consumer after the last publish
+    #[serde(default)]
+    pub buffer_fill: usize,
+    /// Fill level of the buffer averaged over the recent frames, from 0.0 to 1.0
+    #[serde(default)]
+    pub average_fill_ratio: f64,
+    /// Estimated delay in milliseconds between the capture of a frame and its
+    /// read by the slowest consumer: the duration of the frames queued ahead of
+    /// it and of the frame itself
+    #[serde(default)]
+    pub estimated_latency_ms: f64,
+    /// Frames published while the buffer was full, each overwriting a frame
+    /// the slowest consumer had not read
+    #[serde(default)]
+    pub overruns: u64,
+    /// Reads for which a consumer waited longer than two frame durations
+    #[serde(default)]
+    pub underruns: u64,
+    /// Share of underruns among the recent reads, from 0.0 to 1.0
+    #[serde(default)]
+    pub underrun_ratio: f64,
+    /// The buffer is chronically near full: the producer outpaces the slowest consumer
+    #[serde(default)]
+    pub near_full: bool,
+    /// The consumers chronically wait for frames: the producer is late
+    #[serde(default)]
+    pub near_empty: bool,
 }
 
 impl Default for StreamStats {
@@ -118,6 +175,15 @@ impl Default for StreamStats {
             frames_since_last_update: 0,
             sample_rate: 0,
             dual_channel: false,
+            buffer_capacity: 0,
+            buffer_fill: 0,
+            average_fill_ratio: 0.0,
+            estimated_latency_ms: 0.0,
+            overruns: 0,
+            underruns: 0,
+            underrun_ratio: 0.0,
+            near_full: false,
+            near_empty: false,
         }
     }
 }
@@ -126,17 +192,32 @@ impl SharedAudioStream {
     /// Create a new shared audio stream
     ///
     /// ### Parameters
-    /// * `buffer_size` - Size of the broadcast channel buffer
+    /// * `buffer_size` - Number of frames buffered for the consumers, rounded up to a power of two
     pub fn new(buffer_size: usize) -> Self {
         let (sender, _) = broadcast::channel(buffer_size);
+        let capacity = buffer_size.next_power_of_two();
 
         Self {
             sender,
             latest_frame: Arc::new(RwLock::new(None)),
-            stats: Arc::new(RwLock::new(StreamStats::default())),
+            stats: Arc::new(RwLock::new(StreamStats {
+                buffer_capacity: capacity,
+                ..StreamStats::default()
+            })),
+            capacity,
         }
     }
 
+    /// Number of frames buffered for the consumers
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of frames published but not yet read by the slowest consumer
+    pub fn queued_frames(&self) -> usize {
+        self.sender.len()
+    }
+
     /// Get a receiver for subscribing to the stream
     pub fn subscribe(&self) -> broadcast::Receiver<AudioFrame> {
         self.sender.subscribe()
@@ -160,6 +241,30 @@ impl SharedAudioStream {
             stats.sample_rate = frame.sample_rate;
             stats.dual_channel = frame.is_dual_channel();
 
+            // Fill level once the frame is queued for the current subscribers
+            let queued = self.sender.len();
+            if stats.active_subscribers > 0 {
+                if queued >= self.capacity {
+                    stats.overruns += 1;
+                }
+                stats.buffer_fill = (queued + 1).min(self.capacity);
+            } else {
+                stats.buffer_fill = 0;
+            }
+            stats.estimated_latency_ms = stats.buffer_fill as f64 * frame.duration_ms();
+
+            let fill_ratio = stats.buffer_fill as f64 / self.capacity as f64;
+            stats.average_fill_ratio += AVERAGE_WEIGHT * (fill_ratio - stats.average_fill_ratio);
+            if !stats.near_full && stats.average_fill_ratio > NEAR_FULL_RATIO {
+                stats.near_full = true;
+                warn!(
+                    "Audio stream buffer near full ({} of {} frames): the producer outpaces the slowest consumer",
+                    stats.buffer_fill, self.capacity
+                );
+            } else if stats.near_full && stats.average_fill_ratio < NEAR_FULL_CLEAR_RATIO {
+                stats.near_full = false;
+            }
+
             let now = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .unwrap_or_default()
@@ -198,12 +303,37 @@ impl SharedAudioStream {
     pub fn subscriber_count(&self) -> usize {
         self.sender.receiver_count()
     }
+
+    /// Record how long a consumer waited for a frame
+    async fn record_read(&self, waited: Duration, frame: &AudioFrame) {
+        let underrun =
+            waited.as_secs_f64() * 1000.0 > UNDERRUN_FRAME_DURATIONS * frame.duration_ms();
+
+        let mut stats = self.stats.write().await;
+        if underrun {
+            stats.underruns += 1;
+        }
+        let sample = if underrun { 1.0 } else { 0.0 };
+        stats.underrun_ratio += AVERAGE_WEIGHT * (sample - stats.underrun_ratio);
+        if !stats.near_empty && stats.underrun_ratio > NEAR_EMPTY_RATIO {
+            stats.near_empty = true;
+            warn!(
+                "Audio stream underruns: consumers wait {:.1} ms for frames of {:.1} ms, the producer is late",
+                waited.as_secs_f64() * 1000.0,
+                frame.duration_ms()
+            );
+        } else if stats.near_empty && stats.underrun_ratio < NEAR_EMPTY_CLEAR_RATIO {
+            stats.near_empty = false;
+        }
+    }
 }
 
 /// Consumer interface for reading from the shared stream
 pub struct AudioStreamConsumer {
     receiver: broadcast::Receiver<AudioFrame>,
     stream: SharedAudioStream,
+    /// Whether a frame was read, the wait for the first frame is not an underrun
+    started: bool,
 }
 
 impl AudioStreamConsumer {
@@ -214,12 +344,28 @@ impl AudioStreamConsumer {
         Self {
             receiver,
             stream: stream.clone(),
+            started: false,
         }
     }
 
     /// Get the next frame from the stream
     /// Returns None if the stream is closed or on timeout
+    ///
+    /// A wait longer than two frame durations is recorded as an underrun in the
+    /// stream statistics.
     pub async fn next_frame(&mut self) -> Option<AudioFrame> {
+        let waiting_since = Instant::now();
+        let frame = self.receive().await?;
+        if self.started {
+            self.stream
+                .record_read(waiting_since.elapsed(), &frame)
+                .await;
+        }
+        self.started = true;
+        Some(frame)
+    }
+
+    async fn receive(&mut self) -> Option<AudioFrame> {
         match self.receiver.recv().await {
             Ok(frame) => Some(frame),
             Err(broadcast::error::RecvError::Closed) => None,
@@ -237,6 +383,23 @@ impl AudioStreamConsumer {
         }
     }
 
+    /// Get the next frame already queued in the stream without waiting
+    /// Returns None if no frame is queued or the stream is closed
+    pub fn try_next_frame(&mut self) -> Option<AudioFrame> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(frame) => return Some(frame),
+                Err(broadcast::error::TryRecvError::Lagged(skipped)) => {
+                    log::warn!(
+                        "Audio stream consumer lagged behind, skipped {} frames",
+                        skipped
+                    );
+                }
+                Err(_) => return None,
+            }
+        }
+    }
+
     /// Get the latest available frame without waiting
     pub async fn get_latest_frame(&self) -> Option<AudioFrame> {
         self.stream.get_latest_frame().await
@@ -287,4 +450,88 @@ mod tests {
         assert_eq!(frame1.frame_number, 42);
         assert_eq!(frame2.frame_number, 42);
     }
+
+    /// A frame of 1 ms at 48 kHz
+    fn short_frame(frame_number: u64) -> AudioFrame {
+        AudioFrame::new(vec![0.1; 48], vec![0.2; 48], 48000, frame_number)
+    }
+
+    #[tokio::test]
+    async fn test_buffer_fill_and_overruns() {
+        let stream = SharedAudioStream::new(8);
+        let mut consumer = AudioStreamConsumer::new(&stream);
+        assert_eq!(stream.get_stats().await.buffer_capacity, 8);
+
+        // The producer outpaces a consumer which does not read
+        for frame_number in 1..=4 {
+            stream.publish(short_frame(frame_number)).await.unwrap();
+        }
+        let stats = stream.get_stats().await;
+        assert_eq!(stats.buffer_fill, 4);
+        assert!((stats.estimated_latency_ms - 4.0).abs() < 1e-9);
+        assert_eq!(stats.overruns, 0);
+        assert!(!stats.near_full);
+
+        for frame_number in 5..=40 {
+            stream.publish(short_frame(frame_number)).await.unwrap();
+        }
+        let stats = stream.get_stats().await;
+        assert_eq!(stats.buffer_fill, 8);
+        assert!((stats.estimated_latency_ms - 8.0).abs() < 1e-9);
+        assert_eq!(stats.overruns, 32);
+        assert!(stats.near_full, "average fill {}", stats.average_fill_ratio);
+
+        // The consumer catches up and then keeps up with the producer
+        while consumer.try_next_frame().is_some() {}
+        for frame_number in 41..=50 {
+            stream.publish(short_frame(frame_number)).await.unwrap();
+            assert_eq!(
+                consumer.try_next_frame().unwrap().frame_number,
+                frame_number
+            );
+        }
+        let stats = stream.get_stats().await;
+        assert_eq!(stats.buffer_fill, 1);
+        assert_eq!(stats.overruns, 32);
+        assert!(
+            !stats.near_full,
+            "average fill {}",
+            stats.average_fill_ratio
+        );
+    }
+
+    #[tokio::test]
+    async fn test_underruns() {
+        let stream = SharedAudioStream::new(32);
+        let mut consumer = AudioStreamConsumer::new(&stream);
+
+        // The producer publishes 1 ms frames every 20 ms
+        let producer = stream.clone();
+        let handle = tokio::spawn(async move {
+            for frame_number in 1..=12 {
+                sleep(Duration::from_millis(20)).await;
+                producer.publish(short_frame(frame_number)).await.unwrap();
+            }
+        });
+        for _ in 0..12 {
+            consumer.next_frame().await.unwrap();
+        }
+        handle.await.unwrap();
+
+        // The wait for the first frame is not an underrun
+        let stats = stream.get_stats().await;
+        assert_eq!(stats.underruns, 11);
+        assert!(stats.near_empty, "underrun ratio {}", stats.underrun_ratio);
+
+        // Frames already queued are read without waiting
+        for frame_number in 13..=40 {
+            stream.publish(short_frame(frame_number)).await.unwrap();
+        }
+        for _ in 13..=40 {
+            consumer.next_frame().await.unwrap();
+        }
+        let stats = stream.get_stats().await;
+        assert_eq!(stats.underruns, 11);
+        assert!(!stats.near_empty, "underrun ratio {}", stats.underrun_ratio);
+    }
 }
@@ -0,0 +1,330 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Tee audio source module
+//!
+//! This module provides [`TeeSource`], a wrapper passing the frames of an audio
+//! source through unchanged while copying them to a [`TeeSink`]: a WAV file
+//! recording the raw input for later re-analysis, or a second
+//! [`SharedAudioStream`] for another consumer. The processing graph is left
+//! untouched.
+//!
+//! As an [`AudioSource`], each frame is copied when it is read and a recording
+//! error is returned by `read_frame`. As a [`RealTimeAudioSource`], the wrapped
+//! source streams to an internal stream and a forwarding task copies each frame
+//! to the sink before publishing it to the output stream; a recording error is
+//! logged without interrupting the acquisition.
+
+use super::{AudioFrame, AudioSource, AudioStreamConsumer, RealTimeAudioSource, SharedAudioStream};
+use crate::config::photoacoustic::{InputRecordingConfig, RecordingSampleFormat};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use hound::{SampleFormat, WavSpec, WavWriter};
+use log::{error, info};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// Number of frames buffered between the wrapped source and the forwarding task
+const TEE_BUFFER_SIZE: usize = 64;
+
+/// Period at which the forwarding task checks whether streaming was stopped
+const FORWARD_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// WAV file recording the frames of a [`TeeSource`]
+///
+/// The file is created, or overwritten, with the sample rate of the first
+/// frame. Channel A and channel B are interleaved as left and right.
+pub struct WavRecorder {
+    path: PathBuf,
+    format: RecordingSampleFormat,
+    writer: Option<WavWriter<BufWriter<File>>>,
+}
+
+impl WavRecorder {
+    /// Create a recorder writing to the configured WAV file
+    ///
+    /// ### Arguments
+    ///
+    /// * `config` - Output path and sample format of the recording
+    pub fn new(config: &InputRecordingConfig) -> Self {
+        Self {
+            path: PathBuf::from(&config.path),
+            format: config.format,
+            writer: None,
+        }
+    }
+
+    /// Path of the WAV file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append the samples of a frame to the recording
+    pub fn write(&mut self, frame: &AudioFrame) -> Result<()> {
+        let format = self.format;
+        let writer = match self.writer {
+            Some(ref mut writer) => writer,
+            None => {
+                let (bits_per_sample, sample_format) = match format {
+                    RecordingSampleFormat::Float32 => (32, SampleFormat::Float),
+                    RecordingSampleFormat::Int16 => (16, SampleFormat::Int),
+                };
+                let spec = WavSpec {
+                    channels: 2,
+                    sample_rate: frame.sample_rate,
+                    bits_per_sample,
+                    sample_format,
+                };
+                let writer = WavWriter::create(&self.path, spec).map_err(|e| {
+                    anyhow!(
+                        "Failed to create WAV recording {}: {}",
+                        self.path.display(),
+                        e
+                    )
+                })?;
+                info!(
+                    "Recording input frames to {} ({}Hz, {:?})",
+                    self.path.display(),
+                    frame.sample_rate,
+                    format
+                );
+                self.writer.insert(writer)
+            }
+        };
+
+        for (sample_a, sample_b) in frame.channel_a.iter().zip(&frame.channel_b) {
+            match format {
+                RecordingSampleFormat::Float32 => {
+                    writer.write_sample(*sample_a)?;
+                    writer.write_sample(*sample_b)?;
+                }
+                RecordingSampleFormat::Int16 => {
+                    writer.write_sample(f32_to_i16(*sample_a))?;
+                    writer.write_sample(f32_to_i16(*sample_b))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the WAV header and close the file
+    ///
+    /// The next frame starts a new recording at the same path. Dropping the
+    /// recorder finalizes the file as well, ignoring errors.
+    pub fn finalize(&mut self) -> Result<()> {
+        if let Some(writer) = self.writer.take() {
+            writer.finalize()?;
+        }
+        Ok(())
+    }
+}
+
+/// Convert a normalized sample to 16-bit PCM
+///
+/// Inverse of the conversion of the audio sources, so that 16-bit input is
+/// recorded without loss.
+fn f32_to_i16(sample: f32) -> i16 {
+    let sample = sample.clamp(-1.0, 1.0);
+    if sample >= 0.0 {
+        (sample * i16::MAX as f32).round() as i16
+    } else {
+        (sample * -(i16::MIN as f32)).round() as i16
+    }
+}
+
+/// Destination of the frames copied by a [`TeeSource`]
+pub enum TeeSink {
+    /// Record the frames to a WAV file
+    Wav(WavRecorder),
+    /// Publish the frames to a second stream, for another consumer
+    Stream(Arc<SharedAudioStream>),
+}
+
+impl TeeSink {
+    /// Create a sink recording to the configured WAV file
+    pub fn wav(config: &InputRecordingConfig) -> Self {
+        Self::Wav(WavRecorder::new(config))
+    }
+
+    /// Copy a frame to the sink
+    async fn record(&mut self, frame: &AudioFrame) -> Result<()> {
+        match self {
+            Self::Wav(recorder) => recorder.write(frame),
+            Self::Stream(stream) => stream.publish(frame.clone()).await,
+        }
+    }
+
+    /// Finalize the WAV recording, if any
+    fn finalize(&mut self) -> Result<()> {
+        match self {
+            Self::Wav(recorder) => recorder.finalize(),
+            Self::Stream(_) => Ok(()),
+        }
+    }
+}
+
+/// Audio source copying the frames of a wrapped source to a [`TeeSink`]
+pub struct TeeSource<S> {
+    source: S,
+    sink: Arc<Mutex<TeeSink>>,
+    // Frame numbering of the frames read through AudioSource
+    frame_number: u64,
+    // Real-time streaming support
+    streaming: Arc<AtomicBool>,
+    forward_handle: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl<S> TeeSource<S> {
+    /// Wrap an audio source
+    ///
+    /// ### Arguments
+    ///
+    /// * `source` - Audio source whose frames are passed through
+    /// * `sink` - Destination of the copied frames
+    pub fn new(source: S, sink: TeeSink) -> Self {
+        Self {
+            source,
+            sink: Arc::new(Mutex::new(sink)),
+            frame_number: 0,
+            streaming: Arc::new(AtomicBool::new(false)),
+            forward_handle: None,
+        }
+    }
+
+    /// Wrapped audio source
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    /// Finalize the WAV recording of the frames read so far
+    ///
+    /// Streaming sources finalize the recording when they are stopped.
+    pub fn finish(&mut self) -> Result<()> {
+        futures::executor::block_on(self.sink.lock()).finalize()
+    }
+}
+
+impl<S: AudioSource> AudioSource for TeeSource<S> {
+    fn read_frame(&mut self) -> Result<(Vec<f32>, Vec<f32>)> {
+        let (channel_a, channel_b) = self.source.read_frame()?;
+
+        self.frame_number += 1;
+        let frame = AudioFrame::new(
+            channel_a,
+            channel_b,
+            AudioSource::sample_rate(&self.source),
+            self.frame_number,
+        );
+        futures::executor::block_on(async { self.sink.lock().await.record(&frame).await })?;
+
+        Ok((frame.channel_a, frame.channel_b))
+    }
+
+    fn sample_rate(&self) -> u32 {
+        AudioSource::sample_rate(&self.source)
+    }
+}
+
+#[async_trait]
+impl<S: RealTimeAudioSource> RealTimeAudioSource for TeeSource<S> {
+    async fn start_streaming(&mut self, stream: Arc<SharedAudioStream>) -> Result<()> {
+        if self.streaming.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        // Subscribe before the wrapped source starts so that no frame is missed
+        let source_stream = Arc::new(SharedAudioStream::new(TEE_BUFFER_SIZE));
+        let mut consumer = AudioStreamConsumer::new(&source_stream);
+        self.source.start_streaming(source_stream).await?;
+        self.streaming.store(true, Ordering::Relaxed);
+
+        let streaming = self.streaming.clone();
+        let sink = self.sink.clone();
+
+        let handle = tokio::spawn(async move {
+            let mut recording_failed = false;
+
+            while streaming.load(Ordering::Relaxed) {
+                match tokio::time::timeout(FORWARD_POLL_INTERVAL, consumer.next_frame()).await {
+                    Ok(Some(frame)) => {
+                        if !tee_frame(&sink, &stream, frame, &mut recording_failed).await {
+                            return;
+                        }
+                    }
+                    Ok(None) => return,
+                    Err(_) => {} // No frame yet, check the streaming flag
+                }
+            }
+
+            // The wrapped source is stopped: forward the frames still queued
+            while let Some(frame) = consumer.try_next_frame() {
+                if !tee_frame(&sink, &stream, frame, &mut recording_failed).await {
+                    return;
+                }
+            }
+        });
+
+        self.forward_handle = Some(handle);
+        Ok(())
+    }
+
+    async fn stop_streaming(&mut self) -> Result<()> {
+        // Stop the wrapped source first, so that its last frames are forwarded
+        self.source.stop_streaming().await?;
+        self.streaming.store(false, Ordering::Relaxed);
+
+        if let Some(handle) = self.forward_handle.take() {
+            handle
+                .await
+                .map_err(|e| anyhow!("Tee forwarding task failed: {}", e))?;
+        }
+
+        self.sink.lock().await.finalize()
+    }
+
+    fn is_streaming(&self) -> bool {
+        self.streaming.load(Ordering::Relaxed)
+    }
+
+    fn sample_rate(&self) -> u32 {
+        RealTimeAudioSource::sample_rate(&self.source)
+    }
+}
+
+/// Copy a frame to the sink and publish it to the output stream
+///
+/// A recording error is logged once and does not stop the forwarding.
+///
+/// ### Returns
+///
+/// `false` if the output stream rejected the frame
+async fn tee_frame(
+    sink: &Mutex<TeeSink>,
+    stream: &SharedAudioStream,
+    frame: AudioFrame,
+    recording_failed: &mut bool,
+) -> bool {
+    if let Err(e) = sink.lock().await.record(&frame).await {
+        if !*recording_failed {
+            error!(
+                "Failed to record frame {}, further errors are not logged: {}",
+                frame.frame_number, e
+            );
+            *recording_failed = true;
+        }
+    }
+
+    if let Err(e) = stream.publish(frame).await {
+        error!("Failed to publish tee frame: {}", e);
+        return false;
+    }
+    true
+}
@@ -5,15 +5,29 @@
 use clap::{Arg, ArgMatches, Command};
 use std::path::PathBuf;
 
+/// Arguments replacing the single token creation, which make `--user` and `--client` optional
+const NO_SINGLE_TOKEN_ARGS: [&str; 4] = ["list-users", "list-clients", "list-permissions", "batch"];
+
 /// Structure for handling command-line arguments
 #[derive(Debug, Clone)]
 pub struct CliArgs {
     pub config_path: PathBuf,
     pub algorithm: String,
-    pub user: String,
-    pub client: String,
+    /// Username, present unless only listings are requested
+    pub user: Option<String>,
+    /// Client identifier, present unless only listings are requested
+    pub client: Option<String>,
     pub duration_override: Option<u64>,
     pub quiet: bool,
+    pub list_users: bool,
+    pub list_clients: bool,
+    pub list_permissions: bool,
+    /// Token to inspect instead of creating one (`inspect <TOKEN>` subcommand)
+    pub inspect_token: Option<String>,
+    /// CSV file of the tokens to create in batch
+    pub batch_path: Option<PathBuf>,
+    /// Output format of the batch results, `csv` or `json`
+    pub batch_format: String,
 }
 
 impl CliArgs {
@@ -28,6 +42,17 @@ impl CliArgs {
         Command::new("create_token")
             .version("1.0")
             .about("Create JWT access tokens manually")
+            .subcommand_negates_reqs(true)
+            .subcommand(
+                Command::new("inspect")
+                    .about("Decode a JWT token and validate it against the configured keys")
+                    .arg(
+                        Arg::new("token")
+                            .value_name("TOKEN")
+                            .help("The JWT token to inspect")
+                            .required(true),
+                    ),
+            )
             .arg(
                 Arg::new("config")
                     .short('c')
@@ -42,7 +67,7 @@ impl CliArgs {
                     .long("algorithm")
                     .value_name("ALGORITHM")
                     .help("JWT signing algorithm")
-                    .value_parser(["HS256", "RS256"])
+                    .value_parser(["HS256", "RS256", "EdDSA"])
                     .default_value("RS256"),
             )
             .arg(
@@ -59,7 +84,7 @@ impl CliArgs {
                     .long("user")
                     .value_name("USERNAME")
                     .help("Username (must exist in config)")
-                    .required(true),
+                    .required_unless_present_any(NO_SINGLE_TOKEN_ARGS),
             )
             .arg(
                 Arg::new("client")
@@ -67,7 +92,7 @@ impl CliArgs {
                     .long("client")
                     .value_name("CLIENT")
                     .help("Client (must exist in config)")
-                    .required(true),
+                    .required_unless_present_any(NO_SINGLE_TOKEN_ARGS),
             )
             .arg(
                 Arg::new("quiet")
@@ -77,6 +102,44 @@ impl CliArgs {
                     .help("Suppress output messages, only token is printed")
                     .action(clap::ArgAction::SetTrue),
             )
+            .arg(
+                Arg::new("batch")
+                    .short('b')
+                    .long("batch")
+                    .value_name("CSV_FILE")
+                    .help(
+                        "Create a token for each user,client,algorithm,duration row of a CSV file",
+                    )
+                    .conflicts_with_all(["user", "client"]),
+            )
+            .arg(
+                Arg::new("format")
+                    .short('f')
+                    .long("format")
+                    .value_name("FORMAT")
+                    .help("Output format of the batch results")
+                    .value_parser(["csv", "json"])
+                    .default_value("csv")
+                    .requires("batch"),
+            )
+            .arg(
+                Arg::new("list-users")
+                    .long("list-users")
+                    .help("List the configured users and their permissions, then exit")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("list-clients")
+                    .long("list-clients")
+                    .help("List the configured clients and their default scopes, then exit")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("list-permissions")
+                    .long("list-permissions")
+                    .help("List the granted permissions and the users holding them, then exit")
+                    .action(clap::ArgAction::SetTrue),
+            )
     }
 
     /// Extract arguments from matches
@@ -84,10 +147,23 @@ impl CliArgs {
         Self {
             config_path: PathBuf::from(matches.get_one::<String>("config").unwrap()),
             algorithm: matches.get_one::<String>("algorithm").unwrap().clone(),
-            user: matches.get_one::<String>("user").unwrap().clone(),
-            client: matches.get_one::<String>("client").unwrap().clone(),
+            user: matches.get_one::<String>("user").cloned(),
+            client: matches.get_one::<String>("client").cloned(),
             duration_override: matches.get_one::<u64>("duration").copied(),
             quiet: matches.get_one::<bool>("quiet").copied().unwrap_or(false),
+            list_users: matches.get_flag("list-users"),
+            list_clients: matches.get_flag("list-clients"),
+            list_permissions: matches.get_flag("list-permissions"),
+            inspect_token: matches
+                .subcommand_matches("inspect")
+                .and_then(|inspect| inspect.get_one::<String>("token").cloned()),
+            batch_path: matches.get_one::<String>("batch").map(PathBuf::from),
+            batch_format: matches.get_one::<String>("format").unwrap().clone(),
         }
     }
+
+    /// Whether any listing was requested instead of a token
+    pub fn wants_listing(&self) -> bool {
+        self.list_users || self.list_clients || self.list_permissions
+    }
 }
@@ -7,7 +7,8 @@ mod cli;
 use cli::CliArgs;
 use rust_photoacoustic::config::Config;
 use rust_photoacoustic::utility::jwt_token::{
-    ConfigLoader, JwtAlgorithm, TokenCreationError, TokenCreationParams, TokenCreator,
+    batch_results_to_csv, BatchTokenResult, ConfigLoader, JwtAlgorithm, TokenCreationError,
+    TokenCreationParams, TokenCreator, TokenInspection, TokenInspector, TokenStatus,
 };
 use std::process;
 use std::str::FromStr;
@@ -30,17 +31,52 @@ fn run() -> Result<(), TokenCreationError> {
 
     let config_loader = ConfigLoader::from_config(&config)?;
 
-    // Validate user and client
-    let _user = config_loader.find_user(&args.user)?;
-    let _client = config_loader.find_client(&args.client)?;
+    // Inspect a token instead of creating one
+    if let Some(token) = &args.inspect_token {
+        let inspection = TokenInspector::new(&config_loader)?.inspect(token)?;
+        print_inspection(&inspection);
+        if !inspection.status.is_valid() {
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // List the configured entities instead of creating a token
+    if args.wants_listing() {
+        print_listings(&args, &config_loader);
+        return Ok(());
+    }
 
     // Prepare creation parameters
     let algorithm = JwtAlgorithm::from_str(&args.algorithm)?;
     let duration = args.duration_override.unwrap_or(86400); // Default 24 hours
 
+    // Create a token for each row of a CSV file
+    if let Some(batch_path) = &args.batch_path {
+        let batch =
+            std::fs::File::open(batch_path).map_err(|e| TokenCreationError::BatchFileError {
+                reason: format!("{}: {}", batch_path.display(), e),
+            })?;
+        let results =
+            TokenCreator::new(&config_loader)?.create_batch(batch, &algorithm, duration)?;
+        print_batch_results(&results, &args.batch_format)?;
+        if results.iter().any(|result| result.error.is_some()) {
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Both are required by the CLI unless a listing or a batch is requested
+    let user_id = args.user.clone().unwrap_or_default();
+    let client_id = args.client.clone().unwrap_or_default();
+
+    // Validate user and client
+    let _user = config_loader.find_user(&user_id)?;
+    let _client = config_loader.find_client(&client_id)?;
+
     let params = TokenCreationParams {
-        user_id: args.user.clone(),
-        client_id: args.client.clone(),
+        user_id,
+        client_id,
         algorithm,
         duration_seconds: duration,
     };
@@ -59,6 +95,75 @@ fn run() -> Result<(), TokenCreationError> {
     Ok(())
 }
 
+fn print_listings(args: &CliArgs, config_loader: &ConfigLoader) {
+    if args.list_users {
+        println!("👤 Users:");
+        for user in config_loader.list_users() {
+            println!("  {}: {}", user.name, user.permissions.join(", "));
+        }
+    }
+    if args.list_clients {
+        println!("🖥️  Clients:");
+        for client in config_loader.list_clients() {
+            println!("  {}: {}", client.name, client.permissions.join(", "));
+        }
+    }
+    if args.list_permissions {
+        println!("🔑 Permissions:");
+        for permission in config_loader.list_permissions() {
+            println!(
+                "  {}: {}",
+                permission.permission,
+                permission.users.join(", ")
+            );
+        }
+    }
+}
+
+fn print_inspection(inspection: &TokenInspection) {
+    let pretty = |value: &serde_json::Value| {
+        serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+    };
+    match &inspection.status {
+        TokenStatus::Valid => println!("✅ Token is valid"),
+        TokenStatus::Expired => println!("⌛ Token has EXPIRED"),
+        TokenStatus::InvalidSignature { reason } => {
+            println!("❌ Token signature is INVALID: {}", reason)
+        }
+        TokenStatus::Invalid { reason } => println!("❌ Token is INVALID: {}", reason),
+    }
+    println!("📄 Header: {}", pretty(&inspection.header));
+    println!("📋 Claims: {}", pretty(&inspection.claims));
+    println!("🔑 Permissions: {}", inspection.permissions.join(", "));
+    match inspection.expires_at {
+        Some(expires_at) => println!("⏱️  Expires: {}", expires_at.to_rfc2822()),
+        None => println!("⏱️  Expires: never"),
+    }
+}
+
+fn print_batch_results(
+    results: &[BatchTokenResult],
+    format: &str,
+) -> Result<(), TokenCreationError> {
+    let output = match format {
+        "json" => serde_json::to_string_pretty(results).map_err(|e| {
+            TokenCreationError::BatchFileError {
+                reason: e.to_string(),
+            }
+        })?,
+        _ => batch_results_to_csv(results)?,
+    };
+    println!("{}", output);
+
+    // Report the failed rows on stderr so that the output stays parseable
+    for result in results {
+        if let Some(error) = &result.error {
+            eprintln!("❌ Line {}: {}", result.line, error);
+        }
+    }
+    Ok(())
+}
+
 fn print_full_results(result: &rust_photoacoustic::utility::jwt_token::TokenCreationResult) {
     println!("✅ Token created successfully!");
     println!("👤 User: {}", result.user_id);
@@ -42,5 +42,17 @@ fn main() -> Result<()> {
         Err(e) => println!("Validation failed: {}", e),
     }
 
+    // Keys that do not match any setting are silently ignored when loading
+    match Config::unknown_keys_in_file(path) {
+        Ok(keys) if keys.is_empty() => println!("No unknown keys"),
+        Ok(keys) => {
+            println!("Unknown keys (ignored, check their spelling):");
+            for key in keys {
+                println!("  - {}", key);
+            }
+        }
+        Err(e) => println!("Unknown key check failed: {}", e),
+    }
+
     Ok(())
 }
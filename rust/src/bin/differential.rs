@@ -12,6 +12,7 @@
 //! * Process stereo files and output the difference between channels (L-R or R-L)
 //! * Process two mono files and output their difference (file1-file2)
 //! * Apply gain adjustment to the resulting differential signal
+//! * Print the noise level and SNR of the inputs and of the differential signal
 //!
 //! ## Usage
 //!
@@ -36,6 +37,7 @@
 use clap::{Parser, ValueEnum};
 use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
 use rust_photoacoustic::preprocessing::differential;
+use rust_photoacoustic::utility::noise_stats::noise_stats;
 use std::path::PathBuf;
 
 /// Defines the different modes of differential signal processing.
@@ -138,6 +140,19 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Prints the noise characteristics of a signal.
+///
+/// ### Arguments
+///
+/// * `label` - Name of the signal in the summary
+/// * `samples` - The 16-bit samples of the signal
+/// * `sample_rate` - The sample rate of the signal in Hz
+fn print_noise_summary(label: &str, samples: &[i16], sample_rate: u32) {
+    let normalized: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+    println!("Noise summary of {}:", label);
+    println!("{}", noise_stats(&normalized, sample_rate));
+}
+
 /// Processes a stereo WAV file to create a differential signal.
 ///
 /// This function handles the LeftMinusRight and RightMinusLeft modes.
@@ -205,6 +220,14 @@ fn process_stereo_file(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
         })
         .collect();
 
+    print_noise_summary("the left channel", &left_channel, spec.sample_rate);
+    print_noise_summary("the right channel", &right_channel, spec.sample_rate);
+    print_noise_summary(
+        "the differential signal",
+        &diff_signal_with_gain,
+        spec.sample_rate,
+    );
+
     // Create mono output spec
     let out_spec = WavSpec {
         channels: 1,
@@ -301,6 +324,14 @@ fn process_two_mono_files(args: &Args) -> Result<(), Box<dyn std::error::Error>>
         })
         .collect();
 
+    print_noise_summary("the first file", file1, spec1.sample_rate);
+    print_noise_summary("the second file", file2, spec1.sample_rate);
+    print_noise_summary(
+        "the differential signal",
+        &diff_signal_with_gain,
+        spec1.sample_rate,
+    );
+
     // Create output spec
     let out_spec = WavSpec {
         channels: 1,
@@ -0,0 +1,120 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! # Ed25519 Key Generator
+//!
+//! This binary utility generates Ed25519 key pairs for JWT token signing with EdDSA.
+//! It creates both private and public keys in PEM format and writes them to specified files.
+//!
+//! ## Usage
+//!
+//! ```
+//! ed25519keygen [OPTIONS]
+//! ```
+//!
+//! ## Options
+//!
+//! - `--out-pub-key <PATH>`: Path for the public key PEM file (default: "./ed25519_pub.key")
+//! - `--out-private-key <PATH>`: Path for the private key PEM file (default: "./ed25519_private.key")
+//!
+//! ## Examples
+//!
+//! Generate keys in the current directory:
+//! ```
+//! ed25519keygen
+//! ```
+//!
+//! Generate keys with custom paths:
+//! ```
+//! ed25519keygen --out-pub-key /path/to/public.pem --out-private-key /path/to/private.pem
+//! ```
+//!
+//! ## Integration with Photoacoustic Configuration
+//!
+//! After generating the keys, they need to be Base64 encoded and added to the `config.yaml` file
+//! under the `visualization.ed25519_private_key` and `visualization.ed25519_public_key` fields.
+//! Tokens are then minted with `create_token --algorithm EdDSA`.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use rcgen::KeyPair;
+
+/// Command line arguments for the Ed25519 key generation utility.
+#[derive(Parser, Debug)]
+#[clap(
+    author,
+    version,
+    about = "Generate Ed25519 key pairs for JWT token signing with EdDSA"
+)]
+struct Args {
+    /// Output path for the public key PEM file.
+    ///
+    /// This file will contain the public key in SPKI PEM format, which can be
+    /// used for JWT token verification. In the photoacoustic application, this key
+    /// should be base64-encoded and added to the config.yaml file.
+    #[clap(long, default_value = "./ed25519_pub.key")]
+    out_pub_key: PathBuf,
+
+    /// Output path for the private key PEM file.
+    ///
+    /// This file will contain the private key in PKCS#8 PEM format, which can be
+    /// used for JWT token signing. In the photoacoustic application, this key
+    /// should be base64-encoded and added to the config.yaml file.
+    #[clap(long, default_value = "./ed25519_private.key")]
+    out_private_key: PathBuf,
+}
+
+/// Main entry point for the Ed25519 key generation utility.
+///
+/// This function:
+/// 1. Parses command line arguments
+/// 2. Generates an Ed25519 key pair
+/// 3. Writes the keys in PEM format to the specified output files
+/// 4. Prints instructions for using the keys with the photoacoustic application
+///
+/// ### Errors
+///
+/// Returns an error if:
+/// - Key generation fails
+/// - File creation or writing fails
+fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let key_pair = KeyPair::generate_for(&rcgen::PKCS_ED25519)
+        .context("Failed to generate Ed25519 key pair")?;
+    let private_pem = key_pair.serialize_pem();
+    let public_pem = key_pair.public_key_pem();
+
+    // Write private key to file
+    let mut private_file = File::create(&args.out_private_key).with_context(|| {
+        format!(
+            "Failed to create private key file at {:?}",
+            args.out_private_key
+        )
+    })?;
+    private_file
+        .write_all(private_pem.as_bytes())
+        .context("Failed to write private key to file")?;
+
+    // Write public key to file
+    let mut public_file = File::create(&args.out_pub_key)
+        .with_context(|| format!("Failed to create public key file at {:?}", args.out_pub_key))?;
+    public_file
+        .write_all(public_pem.as_bytes())
+        .context("Failed to write public key to file")?;
+
+    println!("Private key written to: {:?}", args.out_private_key);
+    println!("Public key written to: {:?}", args.out_pub_key);
+    println!();
+    println!("You can use these keys for JWT EdDSA signing in your application.");
+    println!("To use them in the config.yaml, you can Base64 encode them with:");
+    println!("cat {} | base64", args.out_private_key.display());
+    println!("cat {} | base64", args.out_pub_key.display());
+
+    Ok(())
+}
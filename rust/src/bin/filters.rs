@@ -30,6 +30,14 @@
 //! filters --input stereo.wav --output filtered.wav --filter-type Bandpass --channel 0
 //! ```
 //!
+//! Measure the impulse response of a 2nd order lowpass filter, written as CSV for plotting:
+//! ```
+//! filters --test-signal impulse --position 100 --output impulse.wav --response-csv impulse.csv --filter-type Lowpass --order 2
+//! ```
+//!
+//! The noise level, SNR and spectral flatness of each channel are printed
+//! before and after filtering, to check the improvement brought by the filter.
+//!
 //! ## Photoacoustic Applications
 //!
 //! In photoacoustic spectroscopy, filtering is essential for:
@@ -45,6 +53,9 @@ use rust_photoacoustic::preprocessing::filter::{
     BandpassFilter, ButterBandpassFilter, ButterHighpassFilter, ButterLowpassFilter, Filter,
     HighpassFilter, LowpassFilter,
 };
+use rust_photoacoustic::utility::noise_stats::noise_stats;
+use rust_photoacoustic::utility::test_signal;
+use std::io::Write;
 use std::path::PathBuf;
 
 /// Types of audio filters available in this utility.
@@ -102,6 +113,17 @@ enum FilterType {
     ButterHighpass,
 }
 
+/// Deterministic signals filtered instead of a WAV file.
+///
+/// The output of the filter is then its impulse or step response.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum TestSignal {
+    /// Unit impulse at `--position`
+    Impulse,
+    /// Unit step from `--position` on
+    Step,
+}
+
 /// Command line arguments for the audio filter utility.
 ///
 /// This structure defines the parameters that control how filters are applied
@@ -117,8 +139,9 @@ struct Args {
     ///
     /// The path to the WAV file that will be processed. The file must be a valid WAV file
     /// with PCM encoding. Both mono and multi-channel files are supported.
-    #[arg(short = 'i', long)]
-    input: PathBuf,
+    /// Not needed when a test signal is generated instead.
+    #[arg(short = 'i', long, required_unless_present = "test_signal")]
+    input: Option<PathBuf>,
 
     /// Output WAV file path.
     ///
@@ -183,6 +206,32 @@ struct Args {
     /// The result is clamped to prevent digital clipping.
     #[arg(short = 'g', long, default_value_t = 1.0)]
     gain: f32,
+
+    /// Generate a mono test signal instead of reading the input file.
+    ///
+    /// Filtering a unit impulse or a unit step measures the impulse or step
+    /// response of the filter.
+    #[arg(long, value_enum, conflicts_with = "input")]
+    test_signal: Option<TestSignal>,
+
+    /// Sample index of the impulse or of the step in the test signal.
+    #[arg(long, default_value_t = 0)]
+    position: usize,
+
+    /// Number of samples of the test signal.
+    #[arg(long, default_value_t = 4800)]
+    length: usize,
+
+    /// Sample rate in Hz of the test signal.
+    #[arg(long, default_value_t = 48000)]
+    sample_rate: u32,
+
+    /// CSV file receiving the input and filtered samples of each channel.
+    ///
+    /// Without the 16-bit quantization of the output WAV file, for plotting
+    /// the measured responses.
+    #[arg(long)]
+    response_csv: Option<PathBuf>,
 }
 
 /// Main entry point for the audio filter utility.
@@ -204,32 +253,55 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Parse command line arguments
     let args = Args::parse();
 
-    println!("Reading WAV file: {:?}", args.input);
-    let mut reader = WavReader::open(&args.input)?;
-    let spec = reader.spec();
+    let (spec, channel_samples) = match (args.test_signal, &args.input) {
+        (Some(signal), _) => {
+            println!(
+                "Generating {:?} test signal at sample {} of {}, {} Hz",
+                signal, args.position, args.length, args.sample_rate
+            );
+            let samples = match signal {
+                TestSignal::Impulse => test_signal::impulse(args.length, args.position),
+                TestSignal::Step => test_signal::step(args.length, args.position),
+            };
+            let spec = hound::WavSpec {
+                channels: 1,
+                sample_rate: args.sample_rate,
+                bits_per_sample: 16,
+                sample_format: hound::SampleFormat::Int,
+            };
+            (spec, vec![samples])
+        }
+        (None, Some(input)) => {
+            println!("Reading WAV file: {:?}", input);
+            let mut reader = WavReader::open(input)?;
+            let spec = reader.spec();
+
+            // Display input file information
+            println!("Input WAV specifications:");
+            println!("- Sample rate: {} Hz", spec.sample_rate);
+            println!("- Bits per sample: {}", spec.bits_per_sample);
+            println!("- Channels: {}", spec.channels);
+
+            // Read all samples from input file
+            let samples: Vec<i16> = reader.samples::<i16>().collect::<Result<Vec<i16>, _>>()?;
+
+            // Convert samples to f32 for filtering (normalized to range [-1.0, 1.0])
+            let mut channel_samples = vec![Vec::new(); spec.channels as usize];
+
+            // Split interleaved samples into separate channel vectors
+            for (i, &sample) in samples.iter().enumerate() {
+                channel_samples[i % spec.channels as usize].push(sample as f32 / 32768.0);
+            }
+            (spec, channel_samples)
+        }
+        (None, None) => return Err("Either --input or --test-signal is required".into()),
+    };
     let sample_rate = spec.sample_rate;
-
-    // Display input file information
-    println!("Input WAV specifications:");
-    println!("- Sample rate: {} Hz", spec.sample_rate);
-    println!("- Bits per sample: {}", spec.bits_per_sample);
-    println!("- Channels: {}", spec.channels);
-
-    // Read all samples from input file
-    let samples: Vec<i16> = reader.samples::<i16>().collect::<Result<Vec<i16>, _>>()?;
+    let channels = spec.channels as usize;
 
     // Process based on filter type
     println!("Processing with filter: {:?}", args.filter_type);
 
-    // Convert samples to f32 for filtering (normalized to range [-1.0, 1.0])
-    let channels = spec.channels as usize;
-    let mut channel_samples = vec![Vec::new(); channels];
-
-    // Split interleaved samples into separate channel vectors
-    for (i, &sample) in samples.iter().enumerate() {
-        channel_samples[i % channels].push(sample as f32 / 32768.0);
-    }
-
     // Create the appropriate filter based on user selection
     let filter: Box<dyn Filter> = match args.filter_type {
         FilterType::Bandpass => {
@@ -323,8 +395,42 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
+    // Noise summary of each channel, before and after filtering
+    for (ch, (input, output)) in channel_samples
+        .iter()
+        .zip(filtered_channels.iter())
+        .enumerate()
+    {
+        println!("Noise summary of channel {} before filtering:", ch);
+        println!("{}", noise_stats(input, sample_rate));
+        println!("Noise summary of channel {} after filtering:", ch);
+        println!("{}", noise_stats(output, sample_rate));
+    }
+
+    if let Some(ref path) = args.response_csv {
+        println!("Writing responses to {:?}", path);
+        let mut csv = std::io::BufWriter::new(std::fs::File::create(path)?);
+        write!(csv, "sample,time_s")?;
+        for ch in 0..channels {
+            write!(csv, ",input_{},output_{}", ch, ch)?;
+        }
+        writeln!(csv)?;
+        for i in 0..filtered_channels[0].len() {
+            write!(csv, "{},{}", i, i as f64 / sample_rate as f64)?;
+            for ch in 0..channels {
+                write!(
+                    csv,
+                    ",{},{}",
+                    channel_samples[ch][i], filtered_channels[ch][i]
+                )?;
+            }
+            writeln!(csv)?;
+        }
+        csv.flush()?;
+    }
+
     // Interleave channels and convert back to i16 samples
-    let mut output_samples = Vec::with_capacity(samples.len());
+    let mut output_samples = Vec::with_capacity(channels * filtered_channels[0].len());
 
     for i in 0..filtered_channels[0].len() {
         for ch in 0..channels {
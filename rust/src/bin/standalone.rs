@@ -40,6 +40,10 @@ pub struct Args {
     /// Number of spectra to average
     #[arg(long, default_value_t = 10)]
     averages: usize,
+
+    /// Track the spectrum with a sliding DFT instead of a block FFT
+    #[arg(long)]
+    sliding_dft: bool,
 }
 
 #[rocket::main]
@@ -57,6 +61,7 @@ async fn main() -> Result<()> {
 
     let config = PhotoacousticConfig {
         input_device: args.input_device.clone(),
+        channel_mapping: None,
         input_file: args
             .input_file
             .clone()
@@ -70,6 +75,8 @@ async fn main() -> Result<()> {
         simulated_source: None,     // No simulated source in standalone mode
         record_consumer: false,     // No record consumer in standalone mode
         record_file: String::new(), // No record file in standalone mode
+        input_recording: None,      // No input recording in standalone mode
+        network_source: None,       // No network source in standalone mode
     };
     // Determine input source (device or file)
     let source = if let Some(device) = &args.input_device {
@@ -85,7 +92,13 @@ async fn main() -> Result<()> {
 
     // Set up processing pipeline
     let filter = preprocessing::create_bandpass_filter(args.frequency, args.bandwidth);
-    let analyzer = spectral::create_spectral_analyzer(args.frame_size, args.averages);
+    let spectral_mode = if args.sliding_dft {
+        spectral::SpectralMode::SlidingDft
+    } else {
+        spectral::SpectralMode::Fft
+    };
+    let analyzer =
+        spectral::create_spectral_analyzer(args.frame_size, args.averages, spectral_mode);
 
     // Process audio data
     println!("Processing audio data...");
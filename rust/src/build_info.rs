@@ -7,7 +7,7 @@
 //! This module provides access to build-time information including Git commit hashes,
 //! compilation date, and other metadata useful for maintenance and debugging.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDateTime, Utc};
 
 /// Build information structure containing all relevant metadata
 #[derive(Debug, Clone)]
@@ -96,6 +96,12 @@ impl BuildInfo {
         DateTime::parse_from_str(self.git_commit_date, "%Y-%m-%d %H:%M:%S %z")
             .map(|dt| dt.with_timezone(&Utc))
     }
+
+    /// Parse the build timestamp as a DateTime
+    pub fn build_datetime(&self) -> Result<DateTime<Utc>, chrono::ParseError> {
+        NaiveDateTime::parse_from_str(self.build_timestamp, "%Y-%m-%d %H:%M:%S UTC")
+            .map(|dt| dt.and_utc())
+    }
 }
 
 impl std::fmt::Display for BuildInfo {
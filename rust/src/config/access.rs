@@ -17,6 +17,7 @@ use rocket::{
 };
 use rocket_okapi::JsonSchema;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 /// OAuth2 client configuration for authorization code flow
 ///
@@ -27,11 +28,15 @@ use serde::{Deserialize, Serialize};
 ///
 /// * `client_id` - The unique identifier for the OAuth2 client
 /// * `allowed_callbacks` - List of URLs that this client is allowed to redirect to
+/// * `refresh_token_lifetime` - Optional lifetime of the refresh tokens, in seconds
+/// * `client_type` - Whether the client is public or confidential
+/// * `client_secret` - Secret of a confidential client
+/// * `require_pkce` - Whether authorization requests must carry a PKCE challenge
 ///
 /// ### Example
 ///
 /// ```
-/// use rust_photoacoustic::config::access::Client;
+/// use rust_photoacoustic::config::access::{Client, ClientType};
 ///
 /// let client = Client {
 ///     client_id: "LaserSmartClient".to_string(),
@@ -40,6 +45,10 @@ use serde::{Deserialize, Serialize};
 ///         "http://localhost:8080/client/".to_string(),
 ///         "https://localhost:8080/client/".to_string(),
 ///     ],
+///     refresh_token_lifetime: Some(604800),
+///     client_type: ClientType::Public,
+///     client_secret: None,
+///     require_pkce: true,
 /// };
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -58,12 +67,178 @@ pub struct Client {
     /// This is a space-separated list of scopes that the client can request.
     /// The default scope is used if the client does not specify a scope during the authorization request.
     pub default_scope: String,
+
+    /// Lifetime of the refresh tokens issued to this client, in seconds
+    ///
+    /// Refresh tokens are rotated on every use and each new refresh token gets
+    /// a fresh lifetime. Defaults to 7 days; when set to `null`, refresh tokens
+    /// expire with the access token they were issued with.
+    #[serde(default = "default_refresh_token_lifetime")]
+    pub refresh_token_lifetime: Option<i64>,
+
+    /// Whether the client is public or confidential
+    ///
+    /// Public clients (such as the SPA web client) cannot keep a secret and
+    /// should protect their authorization codes with PKCE.
+    #[serde(default)]
+    pub client_type: ClientType,
+
+    /// Secret used by a confidential client to authenticate at the token endpoint
+    ///
+    /// Ignored for public clients.
+    #[serde(default)]
+    pub client_secret: Option<String>,
+
+    /// Reject authorization requests from this client that carry no PKCE
+    /// `code_challenge`
+    #[serde(default)]
+    pub require_pkce: bool,
+}
+
+/// OAuth2 client type as defined in RFC 6749 section 2.1
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ClientType {
+    /// Client unable to keep its credentials confidential (browser or native app)
+    #[default]
+    Public,
+    /// Client able to authenticate with a secret
+    Confidential,
+}
+
+/// Crypt algorithm of the password hashes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PasswordHashAlgorithm {
+    /// SHA-256 crypt (`$5$`), as created by `openssl passwd -5`
+    #[default]
+    Sha256,
+    /// SHA-512 crypt (`$6$`), as created by `openssl passwd -6`
+    Sha512,
+}
+
+/// Cost of the password hashes
+///
+/// Passwords hashed with a weaker algorithm or fewer rounds are rehashed
+/// with these parameters on the next successful login. Stronger hashes are
+/// kept. The new hash is saved to the configuration file the daemon was
+/// started from, otherwise it only lasts until the next restart.
+///
+/// ### Example
+///
+/// ```
+/// use rust_photoacoustic::config::access::{PasswordHashAlgorithm, PasswordHashConfig};
+///
+/// let password_hash = PasswordHashConfig {
+///     algorithm: PasswordHashAlgorithm::Sha512,
+///     rounds: 100_000,
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PasswordHashConfig {
+    /// Crypt algorithm of new hashes. Default is `sha256`.
+    #[serde(default)]
+    pub algorithm: PasswordHashAlgorithm,
+
+    /// Number of rounds of new hashes, between 1000 and 999999999.
+    /// More rounds slow down brute force attacks, and logins. Default is 5000.
+    #[serde(default = "default_password_hash_rounds")]
+    pub rounds: u32,
+}
+
+/// Default number of rounds of the SHA crypt algorithms
+fn default_password_hash_rounds() -> u32 {
+    5000
+}
+
+impl Default for PasswordHashConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: PasswordHashAlgorithm::default(),
+            rounds: default_password_hash_rounds(),
+        }
+    }
+}
+
+/// Temporary lockout of a user after repeated failed logins
+///
+/// Failures are counted per user and source IP address. Once `max_failures`
+/// failures happened within `window_secs` seconds, every login attempt for
+/// this user from this address is refused for `lockout_secs` seconds, even
+/// with the right password. A successful login resets the count.
+///
+/// ### Example
+///
+/// ```
+/// use rust_photoacoustic::config::access::LoginLockoutConfig;
+///
+/// let lockout = LoginLockoutConfig {
+///     enabled: true,
+///     max_failures: 3,
+///     window_secs: 60,
+///     lockout_secs: 600,
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct LoginLockoutConfig {
+    /// Lock users out after repeated failed logins. Default is `true`.
+    #[serde(default = "default_login_lockout_enabled")]
+    pub enabled: bool,
+
+    /// Number of failed logins triggering the lockout. Default is 5.
+    #[serde(default = "default_login_lockout_max_failures")]
+    pub max_failures: u32,
+
+    /// Time in seconds over which failed logins are counted. Default is 300.
+    #[serde(default = "default_login_lockout_window_secs")]
+    pub window_secs: u64,
+
+    /// Duration of the lockout in seconds. Default is 900.
+    #[serde(default = "default_login_lockout_secs")]
+    pub lockout_secs: u64,
+}
+
+fn default_login_lockout_enabled() -> bool {
+    true
+}
+
+fn default_login_lockout_max_failures() -> u32 {
+    5
 }
 
-fn default_duration() -> Option<i64> {
+fn default_login_lockout_window_secs() -> u64 {
+    300
+}
+
+fn default_login_lockout_secs() -> u64 {
+    900
+}
+
+impl Default for LoginLockoutConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_login_lockout_enabled(),
+            max_failures: default_login_lockout_max_failures(),
+            window_secs: default_login_lockout_window_secs(),
+            lockout_secs: default_login_lockout_secs(),
+        }
+    }
+}
+
+fn default_access_token_ttl() -> Option<i64> {
     Some(86400)
 }
 
+/// Default lifetime of the refresh tokens of a client: 7 days
+fn default_refresh_token_lifetime() -> Option<i64> {
+    Some(604800)
+}
+
+/// Default tolerance in seconds for the time claims of the tokens
+fn default_clock_skew_seconds() -> u64 {
+    60
+}
+
 /// User definition for authentication and authorization
 ///
 /// This structure represents a user with authentication credentials and
@@ -74,6 +249,7 @@ fn default_duration() -> Option<i64> {
 /// * `user` - The username used for authentication
 /// * `pass` - Base64-encoded password hash (created with openssl passwd -5 | base64 -w0)
 /// * `permissions` - List of permission strings that define what actions the user can perform
+/// * `roles` - Names of the [`Role`]s whose permissions are granted to the user
 ///
 /// ### Example
 ///
@@ -86,6 +262,7 @@ fn default_duration() -> Option<i64> {
 ///     email: None,
 ///     name: None,
 ///     permissions: vec!["read:api".to_string(), "write:api".to_string(), "admin:api".to_string()],
+///     roles: vec![],
 /// };
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -106,10 +283,128 @@ pub struct User {
     /// * "admin:api" - Allows administrative operations
     pub permissions: Vec<String>,
 
+    /// Names of the roles assigned to the user
+    ///
+    /// The permissions of each role are added to `permissions` when tokens
+    /// are issued, see [`AccessConfig::effective_permissions`].
+    #[serde(default)]
+    pub roles: Vec<String>,
+
     pub email: Option<String>,
     pub name: Option<String>,
 }
 
+/// Named set of permissions that can be assigned to users
+///
+/// ### Example
+///
+/// ```
+/// use rust_photoacoustic::config::access::Role;
+///
+/// let role = Role {
+///     name: "operator".to_string(),
+///     permissions: vec!["read:api".to_string(), "write:api".to_string()],
+/// };
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Role {
+    /// The role name referenced by [`User::roles`]
+    pub name: String,
+
+    /// Permissions granted to every user holding this role
+    pub permissions: Vec<String>,
+}
+
+/// Static API key granting a set of permissions to a machine client
+///
+/// API keys are an alternative to OAuth2 for clients that cannot run an
+/// interactive flow (scripts, PLCs, monitoring probes). The key is sent in the
+/// `X-API-Key` header and only its SHA-256 hash is stored in the configuration.
+///
+/// ### Example
+///
+/// ```
+/// use rust_photoacoustic::config::access::ApiKey;
+///
+/// let api_key = ApiKey {
+///     name: "monitoring".to_string(),
+///     key_hash: ApiKey::hash_key("my-secret-key"),
+///     permissions: vec!["read:api".to_string()],
+/// };
+/// assert!(api_key.matches("my-secret-key"));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ApiKey {
+    /// Name identifying the client owning the key, used as token subject
+    pub name: String,
+
+    /// Hex-encoded SHA-256 hash of the key
+    ///
+    /// This should be created using: `printf '%s' <key> | sha256sum`
+    pub key_hash: String,
+
+    /// Permissions granted to requests carrying this key
+    pub permissions: Vec<String>,
+}
+
+impl ApiKey {
+    /// Hash a key in the format stored in [`ApiKey::key_hash`]
+    ///
+    /// ### Parameters
+    ///
+    /// * `key` - The clear-text API key
+    ///
+    /// ### Returns
+    ///
+    /// The lowercase hex-encoded SHA-256 hash of the key
+    pub fn hash_key(key: &str) -> String {
+        Sha256::digest(key.as_bytes())
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Check whether a clear-text key matches this API key
+    ///
+    /// The hashes are compared in constant time.
+    pub fn matches(&self, key: &str) -> bool {
+        let expected = self.key_hash.trim().to_ascii_lowercase();
+        let actual = Self::hash_key(key);
+        expected.len() == actual.len()
+            && expected
+                .bytes()
+                .zip(actual.bytes())
+                .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+                == 0
+    }
+}
+
+/// Permissions granted to the holder of a client certificate
+///
+/// Used with mutual TLS (see
+/// [`MutualTlsConfig`](crate::config::MutualTlsConfig)): the certificate is
+/// verified during the TLS handshake, then its subject common name selects
+/// the permissions.
+///
+/// ### Example
+///
+/// ```
+/// use rust_photoacoustic::config::access::ClientCertificate;
+///
+/// let device = ClientCertificate {
+///     common_name: "analyzer-0042".to_string(),
+///     permissions: vec!["read:api".to_string(), "write:api".to_string()],
+/// };
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ClientCertificate {
+    /// Common name (CN) of the certificate subject
+    pub common_name: String,
+
+    /// Permissions granted to requests presenting this certificate
+    pub permissions: Vec<String>,
+}
+
 /// Configuration for user access and permissions
 ///
 /// This structure defines both users who can access the application directly
@@ -120,16 +415,20 @@ pub struct User {
 /// ### Example
 ///
 /// ```rust
-/// use rust_photoacoustic::config::access::{AccessConfig, User, Client};
+/// use rust_photoacoustic::config::access::{
+///     AccessConfig, Client, ClientType, LoginLockoutConfig, PasswordHashConfig, Role, User,
+/// };
 ///
 /// let access_config = AccessConfig {
-///     duration: Some(86400), // Token duration in seconds
+///     access_token_ttl: Some(86400), // Token lifetime in seconds
+///     clock_skew_seconds: 60,
 ///     iss: Some("LaserSmartServer".to_string()),
 ///     users: vec![
 ///          User {
 ///              user: "admin".to_string(),
 ///              pass: "JDEkYTRuMy5jZmUkRU93djlOYXBKYjFNTXRTMHA1UzN1MQo=".to_string(),
-///              permissions: vec!["read:api".to_string(), "write:api".to_string(), "admin:api".to_string()],
+///              permissions: vec!["admin:api".to_string()],
+///              roles: vec!["operator".to_string()],
 ///              email: None,
 ///              name: None,
 ///          },
@@ -137,6 +436,7 @@ pub struct User {
 ///              user: "reader".to_string(),
 ///              pass: "JDEkUTJoSGZWU3ckT3NIVTUzamhCY3pYVmRHTGlTazg4Lwo=".to_string(),
 ///              permissions: vec!["read:api".to_string()],
+///              roles: vec![],
 ///              email: None,
 ///              name: None,
 ///          }],
@@ -148,7 +448,20 @@ pub struct User {
 ///                  "http://localhost:8080/client/".to_string(),
 ///                  "https://localhost:8080/client/".to_string(),
 ///              ],
+///              refresh_token_lifetime: None,
+///              client_type: ClientType::Public,
+///              client_secret: None,
+///              require_pkce: false,
 ///          }],
+///      roles: vec![
+///          Role {
+///              name: "operator".to_string(),
+///              permissions: vec!["read:api".to_string(), "write:api".to_string()],
+///          }],
+///      api_keys: vec![],
+///      client_certificates: vec![],
+///      password_hash: PasswordHashConfig::default(),
+///      login_lockout: LoginLockoutConfig::default(),
 ///     };
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -159,11 +472,42 @@ pub struct AccessConfig {
     /// List of OAuth2 clients with their identifiers and allowed callback URLs
     pub clients: Vec<Client>,
 
-    /// Duration of the issued token
-    #[serde(default = "default_duration")]
-    pub duration: Option<i64>,
+    /// Roles that can be assigned to users
+    #[serde(default)]
+    pub roles: Vec<Role>,
+
+    /// Static API keys accepted in the `X-API-Key` header
+    #[serde(default)]
+    pub api_keys: Vec<ApiKey>,
+
+    /// Client certificate subjects accepted with mutual TLS
+    #[serde(default)]
+    pub client_certificates: Vec<ClientCertificate>,
+
+    /// Algorithm and cost of the password hashes
+    #[serde(default)]
+    pub password_hash: PasswordHashConfig,
+
+    /// Lockout after repeated failed logins
+    #[serde(default)]
+    pub login_lockout: LoginLockoutConfig,
+
+    /// Lifetime in seconds of the issued access tokens
+    ///
+    /// Also accepted under its former name `duration`.
+    #[serde(default = "default_access_token_ttl", alias = "duration")]
+    pub access_token_ttl: Option<i64>,
+
+    /// Tolerated clock difference in seconds with the token issuer
+    ///
+    /// Applied to the `exp`, `nbf` and `iat` claims when validating tokens, so
+    /// that hosts with drifting clocks do not reject valid tokens. Default is 60.
+    #[serde(default = "default_clock_skew_seconds")]
+    pub clock_skew_seconds: u64,
 
     /// Issuer for the access tokens
+    ///
+    /// Also the issuer required by the validator when none is set explicitly.
     #[serde(default = "default_iss")]
     pub iss: Option<String>,
 }
@@ -172,6 +516,71 @@ fn default_iss() -> Option<String> {
     Some("LaserSmartServer".to_string())
 }
 
+impl AccessConfig {
+    /// Compute the effective permissions of a user
+    ///
+    /// The user's own permissions come first, followed by the permissions of
+    /// each of its roles in order. Duplicates are removed and unknown role
+    /// names are ignored.
+    ///
+    /// ### Parameters
+    ///
+    /// * `user` - The user whose permissions are expanded
+    ///
+    /// ### Returns
+    ///
+    /// The deduplicated list of permissions granted to the user
+    pub fn effective_permissions(&self, user: &User) -> Vec<String> {
+        let role_permissions = user.roles.iter().flat_map(|role_name| {
+            self.roles
+                .iter()
+                .filter(move |role| &role.name == role_name)
+                .flat_map(|role| role.permissions.iter())
+        });
+
+        let mut permissions: Vec<String> = Vec::new();
+        for permission in user.permissions.iter().chain(role_permissions) {
+            if !permissions.contains(permission) {
+                permissions.push(permission.clone());
+            }
+        }
+        permissions
+    }
+
+    /// Find the API key matching a clear-text key
+    ///
+    /// ### Parameters
+    ///
+    /// * `key` - The key received in the `X-API-Key` header
+    ///
+    /// ### Returns
+    ///
+    /// The matching API key, or `None` if the key is unknown
+    pub fn find_api_key(&self, key: &str) -> Option<&ApiKey> {
+        self.api_keys.iter().find(|api_key| api_key.matches(key))
+    }
+
+    /// Find the client certificate entry of a certificate subject
+    ///
+    /// ### Parameters
+    ///
+    /// * `common_name` - Common name of the verified client certificate
+    ///
+    /// ### Returns
+    ///
+    /// The matching entry, or `None` if the subject is not configured
+    pub fn find_client_certificate(&self, common_name: &str) -> Option<&ClientCertificate> {
+        self.client_certificates
+            .iter()
+            .find(|certificate| certificate.common_name == common_name)
+    }
+
+    /// Lifetime of the issued access tokens, 24 hours when not configured
+    pub fn access_token_lifetime(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.access_token_ttl.unwrap_or(86400))
+    }
+}
+
 impl Default for User {
     fn default() -> Self {
         Self {
@@ -187,6 +596,7 @@ impl Default for User {
                 "email".to_string(),
                 "offline_access".to_string(),
             ],
+            roles: vec![],
             email: Some("email@example.org".to_string()),
             name: Some("Admin User".to_string()),
         }
@@ -202,6 +612,10 @@ impl Default for Client {
                 "http://localhost:8080/client/".to_string(),
                 "https://localhost:8080/client/".to_string(),
             ],
+            refresh_token_lifetime: default_refresh_token_lifetime(),
+            client_type: ClientType::Public,
+            client_secret: None,
+            require_pkce: false,
         }
     }
 }
@@ -211,7 +625,13 @@ impl Default for AccessConfig {
         Self {
             users: vec![User::default()],
             clients: vec![Client::default()],
-            duration: default_duration(),
+            roles: vec![],
+            api_keys: vec![],
+            client_certificates: vec![],
+            password_hash: PasswordHashConfig::default(),
+            login_lockout: LoginLockoutConfig::default(),
+            access_token_ttl: default_access_token_ttl(),
+            clock_skew_seconds: default_clock_skew_seconds(),
             iss: default_iss(),
         }
     }
@@ -29,6 +29,15 @@ pub struct AcquisitionConfig {
     /// Lower values provide more frequent updates but may increase system load.
     /// Must be greater than zero.
     pub interval_ms: u64,
+
+    /// Number of audio frames the acquisition stream buffers for its consumers.
+    ///
+    /// Rounded up to a power of two. A larger buffer absorbs longer stalls of
+    /// the consumers at the cost of latency; the fill level and the latency
+    /// it adds are reported in the stream statistics. Defaults to the
+    /// `frame_size` of the photoacoustic configuration.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_buffer_frames: Option<usize>,
 }
 
 // implement Default for AcquisitionConfig
@@ -37,6 +46,7 @@ impl Default for AcquisitionConfig {
         Self {
             enabled: true,
             interval_ms: 1000, // Default to 1 second (1000ms) between acquisitions
+            stream_buffer_frames: None, // Default to the frame size
         }
     }
 }
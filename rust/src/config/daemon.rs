@@ -0,0 +1,136 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Daemon lifecycle configuration
+//!
+//! This module defines the settings controlling how the daemon manages its
+//! background tasks and its logs: the deadline of the graceful shutdown, the
+//! restart policy of the supervised tasks, the threads of the real-time tasks
+//! and the rotated log file.
+
+use rocket_okapi::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Configuration of the daemon lifecycle.
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
+pub struct DaemonConfig {
+    /// Deadline of the graceful shutdown in milliseconds.
+    ///
+    /// On shutdown the daemon stops the acquisition, drains the processing
+    /// graph, flushes the action drivers and the recordings, then stops the
+    /// servers. Tasks still running when this deadline expires are aborted.
+    /// Must be greater than zero.
+    #[serde(default = "default_shutdown_timeout_ms")]
+    pub shutdown_timeout_ms: u64,
+
+    /// Consecutive failures after which a supervised task is no longer restarted.
+    ///
+    /// A task that fails (returns an error or panics) is restarted after a
+    /// backoff delay. Once it failed more than this number of times in a row,
+    /// it is marked failed and `/readyz` reports the daemon as not ready.
+    #[serde(default = "default_task_max_restarts")]
+    pub task_max_restarts: u32,
+
+    /// Delay before the first restart of a failed task, in milliseconds.
+    ///
+    /// The delay doubles after each consecutive failure, up to
+    /// `task_restart_backoff_max_ms`. Must be greater than zero.
+    #[serde(default = "default_task_restart_backoff_ms")]
+    pub task_restart_backoff_ms: u64,
+
+    /// Upper bound of the restart delay, in milliseconds.
+    ///
+    /// A task that ran at least this long before failing starts a new series
+    /// of consecutive failures. Must not be lower than `task_restart_backoff_ms`.
+    #[serde(default = "default_task_restart_backoff_max_ms")]
+    pub task_restart_backoff_max_ms: u64,
+
+    /// Worker threads of the runtime running the acquisition and processing tasks.
+    ///
+    /// When this or `realtime_cpu_affinity` is set, the real-time audio
+    /// acquisition and the processing consumer run on a dedicated runtime
+    /// instead of sharing the threads of the web server. The parallel branches
+    /// of the processing graph run on a thread pool of the same size. Defaults
+    /// to the number of CPUs in `realtime_cpu_affinity`. Must be greater than zero.
+    #[serde(default)]
+    pub realtime_threads: Option<usize>,
+
+    /// CPUs the threads of the real-time runtime are pinned to.
+    ///
+    /// Applied on Linux only; other platforms ignore it with a warning. Every
+    /// CPU must be available to the daemon process and the list must not be
+    /// empty.
+    #[serde(default)]
+    pub realtime_cpu_affinity: Option<Vec<usize>>,
+
+    /// Path of the log file written by the daemon.
+    ///
+    /// When set, every log line is also appended to this file. Once the file
+    /// would exceed `log_file_max_size` bytes it is renamed `<path>.1`, older
+    /// files are shifted to `<path>.2`, `<path>.3`... and a new file is started.
+    #[serde(default)]
+    pub log_file: Option<String>,
+
+    /// Size in bytes above which the log file is rotated.
+    ///
+    /// Must be greater than zero.
+    #[serde(default = "default_log_file_max_size")]
+    pub log_file_max_size: u64,
+
+    /// Number of rotated log files kept next to the current one.
+    ///
+    /// The oldest file is deleted when a rotation would exceed this count.
+    /// Must be greater than zero.
+    #[serde(default = "default_log_file_max_files")]
+    pub log_file_max_files: usize,
+
+    /// Whether log lines are still written to the console when `log_file` is set.
+    #[serde(default = "default_log_to_console")]
+    pub log_to_console: bool,
+}
+
+fn default_shutdown_timeout_ms() -> u64 {
+    10_000
+}
+
+fn default_task_max_restarts() -> u32 {
+    5
+}
+
+fn default_task_restart_backoff_ms() -> u64 {
+    1_000
+}
+
+fn default_task_restart_backoff_max_ms() -> u64 {
+    60_000
+}
+
+fn default_log_file_max_size() -> u64 {
+    10 * 1024 * 1024
+}
+
+fn default_log_file_max_files() -> usize {
+    5
+}
+
+fn default_log_to_console() -> bool {
+    true
+}
+
+impl Default for DaemonConfig {
+    fn default() -> Self {
+        Self {
+            shutdown_timeout_ms: default_shutdown_timeout_ms(),
+            task_max_restarts: default_task_max_restarts(),
+            task_restart_backoff_ms: default_task_restart_backoff_ms(),
+            task_restart_backoff_max_ms: default_task_restart_backoff_max_ms(),
+            realtime_threads: None,
+            realtime_cpu_affinity: None,
+            log_file: None,
+            log_file_max_size: default_log_file_max_size(),
+            log_file_max_files: default_log_file_max_files(),
+            log_to_console: default_log_to_console(),
+        }
+    }
+}
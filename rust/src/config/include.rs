@@ -0,0 +1,137 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Configuration layering with `include:` directives
+//!
+//! A configuration file can list one or more base files under a top-level
+//! `include` key. The base files are loaded (recursively resolving their own
+//! includes), deep-merged in order and the including file is merged on top:
+//!
+//! ```yaml
+//! # site-a.yaml
+//! include:
+//!   - base.yaml
+//!   - lab-hardware.yaml
+//! visualization:
+//!   name: "Site A"
+//! ```
+//!
+//! ### Merge rules
+//!
+//! - Mappings are merged key by key, recursively
+//! - Scalars and sequences from the including file replace the base value
+//! - Later entries of `include` win over earlier ones
+//!
+//! Relative include paths are resolved from the directory of the including
+//! file. A file that includes itself, directly or through other files, is
+//! rejected. The directive is removed from the merged document, before the
+//! schema validation.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use log::debug;
+use serde_yml::Value;
+
+/// Top-level key listing the base files of a configuration file
+pub const INCLUDE_KEY: &str = "include";
+
+/// Load the YAML document at `path` with its includes resolved
+///
+/// ### Parameters
+///
+/// * `path` - Path of the configuration file
+///
+/// ### Returns
+///
+/// The merged document without `include` directives, or an error if a file
+/// cannot be read or parsed, an `include` entry is not a path, or the includes
+/// form a cycle
+pub fn load_yaml_with_includes<P: AsRef<Path>>(path: P) -> Result<Value> {
+    load_layered(path.as_ref(), &mut Vec::new())
+}
+
+fn load_layered(path: &Path, chain: &mut Vec<PathBuf>) -> Result<Value> {
+    let canonical = path
+        .canonicalize()
+        .with_context(|| format!("Failed to read configuration file at {:?}", path))?;
+    if chain.contains(&canonical) {
+        let cycle: Vec<String> = chain
+            .iter()
+            .chain(std::iter::once(&canonical))
+            .map(|file| file.display().to_string())
+            .collect();
+        bail!(
+            "Configuration include cycle detected: {}",
+            cycle.join(" -> ")
+        );
+    }
+
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read configuration file at {:?}", path))?;
+    let mut document: Value = serde_yml::from_str(&contents)
+        .with_context(|| format!("Failed to parse YAML configuration from {:?}", path))?;
+
+    let includes = match document.as_mapping_mut() {
+        Some(mapping) => include_paths(mapping.remove(INCLUDE_KEY), path)?,
+        None => Vec::new(),
+    };
+    if includes.is_empty() {
+        return Ok(document);
+    }
+
+    chain.push(canonical);
+    let mut merged = Value::Null;
+    for include in includes {
+        debug!("Including {:?} into {:?}", include, path);
+        deep_merge(&mut merged, load_layered(&include, chain)?);
+    }
+    chain.pop();
+
+    deep_merge(&mut merged, document);
+    Ok(merged)
+}
+
+/// Resolve the paths listed by an `include` directive of the file at `path`
+fn include_paths(directive: Option<Value>, path: &Path) -> Result<Vec<PathBuf>> {
+    let entries = match directive {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::Sequence(entries)) => entries,
+        Some(entry) => vec![entry],
+    };
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+    entries
+        .into_iter()
+        .map(|entry| match entry {
+            Value::String(include) => Ok(base_dir.join(include)),
+            other => bail!(
+                "Invalid '{}' entry in {:?}: expected a file path, found {:?}",
+                INCLUDE_KEY,
+                path,
+                other
+            ),
+        })
+        .collect()
+}
+
+/// Merge `overlay` into `base`, with `overlay` winning on conflicts
+///
+/// Mappings are merged recursively; any other value of `overlay` replaces the
+/// value of `base`.
+pub fn deep_merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Mapping(base), Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => deep_merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
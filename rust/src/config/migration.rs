@@ -0,0 +1,146 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Migration of configuration files written for older schema versions
+//!
+//! Every configuration document carries a top-level `schema_version`; documents
+//! without it are version 1. Before schema validation, the loader upgrades the
+//! document one version at a time through [`MIGRATIONS`] and logs each applied
+//! step, so that an old file keeps loading instead of failing validation with
+//! errors about fields it never had.
+//!
+//! | From | To | Changes |
+//! |---|---|---|
+//! | 1 | 2 | `photoacoustic.window_size` renamed to `frame_size`; missing required `photoacoustic` fields filled with their defaults |
+//!
+//! Documents declaring a version newer than [`CURRENT_SCHEMA_VERSION`] are
+//! rejected: they were written for a newer release of the application.
+
+use anyhow::{bail, Result};
+use log::{info, warn};
+use serde_yml::{Mapping, Value};
+
+use super::PhotoacousticConfig;
+
+/// Schema version of the configuration files written by this release
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+/// Top-level key holding the schema version of a configuration document
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// One step of the migration chain
+pub struct Migration {
+    /// Version of the documents this step applies to
+    pub from: u32,
+    /// Human-readable summary, logged when the step is applied
+    pub description: &'static str,
+    /// Rewrite the document in place to version `from + 1`
+    pub apply: fn(&mut Mapping) -> Result<()>,
+}
+
+/// Migration chain, ordered by source version
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    from: 1,
+    description:
+        "renamed photoacoustic.window_size to frame_size and filled missing photoacoustic defaults",
+    apply: migrate_v1_to_v2,
+}];
+
+/// Default schema version of a [`Config`](super::Config)
+pub fn default_schema_version() -> u32 {
+    CURRENT_SCHEMA_VERSION
+}
+
+/// Upgrade a configuration document to [`CURRENT_SCHEMA_VERSION`]
+///
+/// Documents that are not a mapping are left untouched for the schema
+/// validation to report.
+///
+/// ### Parameters
+///
+/// * `document` - The configuration document, rewritten in place
+///
+/// ### Returns
+///
+/// The descriptions of the applied migrations, or an error if the declared
+/// version is invalid, newer than the current one, or has no migration step
+pub fn migrate(document: &mut Value) -> Result<Vec<&'static str>> {
+    let Some(mapping) = document.as_mapping_mut() else {
+        return Ok(Vec::new());
+    };
+
+    let mut version = match mapping.get(SCHEMA_VERSION_KEY) {
+        None => 1,
+        Some(value) => match value.as_u64().and_then(|v| u32::try_from(v).ok()) {
+            Some(version) if version >= 1 => version,
+            _ => bail!(
+                "Invalid '{}' {:?}: expected a positive integer",
+                SCHEMA_VERSION_KEY,
+                value
+            ),
+        },
+    };
+    if version > CURRENT_SCHEMA_VERSION {
+        bail!(
+            "Configuration schema version {} is newer than the version {} supported by this \
+             release; upgrade rust-photoacoustic or use a configuration written for it",
+            version,
+            CURRENT_SCHEMA_VERSION
+        );
+    }
+
+    let mut applied = Vec::new();
+    while version < CURRENT_SCHEMA_VERSION {
+        let Some(migration) = MIGRATIONS.iter().find(|m| m.from == version) else {
+            bail!("No migration from configuration schema version {}", version);
+        };
+        (migration.apply)(mapping)?;
+        info!(
+            "Migrated configuration from schema version {} to {}: {}",
+            version,
+            version + 1,
+            migration.description
+        );
+        applied.push(migration.description);
+        version += 1;
+    }
+
+    mapping.insert(
+        Value::from(SCHEMA_VERSION_KEY),
+        Value::from(CURRENT_SCHEMA_VERSION),
+    );
+    Ok(applied)
+}
+
+/// Version 1 to 2: consistent `frame_size` naming in the `photoacoustic` section
+fn migrate_v1_to_v2(document: &mut Mapping) -> Result<()> {
+    let Some(photoacoustic) = document
+        .get_mut("photoacoustic")
+        .and_then(Value::as_mapping_mut)
+    else {
+        return Ok(());
+    };
+
+    if let Some(window_size) = photoacoustic.remove("window_size") {
+        if photoacoustic.contains_key("frame_size") {
+            warn!("Both photoacoustic.window_size and frame_size are set; keeping frame_size");
+        } else {
+            photoacoustic.insert(Value::from("frame_size"), window_size);
+        }
+    }
+
+    let defaults = PhotoacousticConfig::default();
+    let required = [
+        ("frequency", Value::from(defaults.frequency)),
+        ("bandwidth", Value::from(defaults.bandwidth)),
+        ("frame_size", Value::from(defaults.frame_size)),
+        ("averages", Value::from(defaults.averages)),
+    ];
+    for (key, default) in required {
+        if !photoacoustic.contains_key(key) {
+            photoacoustic.insert(Value::from(key), default);
+        }
+    }
+    Ok(())
+}
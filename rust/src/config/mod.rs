@@ -16,6 +16,11 @@
 //! - `modbus`: Settings for Modbus TCP server functionality
 //! - `photoacoustic`: Settings for photoacoustic measurements
 //! - `access`: Settings for user access and permissions
+//! - `daemon`: Settings for the daemon lifecycle, such as the shutdown deadline
+//!
+//! A configuration file can be layered on top of shared base files with a
+//! top-level `include:` directive, see [`include`]. Files written for an older
+//! `schema_version` are upgraded on load, see [`migration`].
 //!
 //! ## Security Features
 //!
@@ -42,7 +47,7 @@
 //!     None,                           // Input file
 //!     Some(1000.0),                   // Frequency
 //!     Some(50.0),                     // Bandwidth
-//!     Some(2048),                     // Window size
+//!     Some(2048),                     // Frame size
 //!     Some(5),                        // Averages
 //!     Some(true),                     // Enable Modbus
 //!     Some("0.0.0.0".to_string()),    // Modbus address
@@ -56,21 +61,26 @@
 
 pub mod access;
 pub mod acquisition;
+pub mod daemon;
 pub mod generix;
+pub mod include;
+pub mod migration;
 pub mod modbus;
 pub mod photoacoustic;
 pub mod processing;
+pub mod reload;
 pub mod simulated_source;
 pub mod thermal_regulation;
+pub mod unknown_keys;
 pub mod utils;
 pub mod visualization;
 
-use std::fs::{self, File};
+use std::fs::File;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use log::{debug, error};
+use log::{debug, error, warn};
 use rocket::request::{FromRequest, Outcome};
 use rocket::{Request, State};
 use rocket_okapi::r#gen::OpenApiGenerator;
@@ -78,16 +88,28 @@ use rocket_okapi::request::OpenApiFromRequest;
 use rocket_okapi::JsonSchema;
 use serde::{Deserialize, Serialize};
 // Re-export all types for public API
-pub use access::{AccessConfig, User};
+pub use access::{
+    AccessConfig, ApiKey, ClientCertificate, LoginLockoutConfig, PasswordHashAlgorithm,
+    PasswordHashConfig, Role, User,
+};
 pub use acquisition::AcquisitionConfig;
+pub use daemon::DaemonConfig;
 pub use generix::GenerixConfig;
-pub use modbus::ModbusConfig;
+pub use modbus::{
+    ModbusConfig, ModbusParity, ModbusTransport, ModbusValueEncoding, ModbusWordOrder,
+};
 pub use photoacoustic::PhotoacousticConfig;
 pub use processing::ProcessingConfig;
-pub use simulated_source::SimulatedSourceConfig;
+pub use reload::{ConfigFieldChange, ConfigFilePath, ConfigPreview, ConfigReloadReport};
+pub use simulated_source::{
+    ChirpConfig, ChirpSweep, GasEvent, MultiToneConfig, SimulatedSourceConfig, TestSignalConfig,
+    ToneComponent, ToneInterference,
+};
 pub use thermal_regulation::ThermalRegulationConfig;
-pub use utils::output_config_schema;
-pub use visualization::VisualizationConfig;
+pub use utils::{editor_config_schema, output_config_schema, yaml_language_server_header};
+pub use visualization::{
+    CorsConfig, MutualTlsConfig, RateLimitConfig, RemoteJwksConfig, VisualizationConfig,
+};
 
 /// Separator character used in user session identifiers
 pub const USER_SESSION_SEPARATOR: char = '⛷';
@@ -110,6 +132,13 @@ pub const USER_SESSION_SEPARATOR: char = '⛷';
 /// file, allowing for minimal configuration when custom settings are not required.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Config {
+    /// Version of the configuration schema the file was written for.
+    ///
+    /// Files written for an older version are upgraded by the [`migration`]
+    /// chain when they are loaded. Files without this field are version 1.
+    #[serde(default = "migration::default_schema_version")]
+    pub schema_version: u32,
+
     /// Settings for the visualization web server component.
     ///
     /// These settings control how the visualization server behaves, including
@@ -169,11 +198,20 @@ pub struct Config {
 
     #[serde(default)]
     pub generix: GenerixConfig,
+
+    /// Daemon lifecycle settings for the photoacoustic application.
+    ///
+    /// This section controls how the daemon manages its background tasks,
+    /// such as the deadline of the graceful shutdown.
+    /// If not specified, default values will be used.
+    #[serde(default)]
+    pub daemon: DaemonConfig,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            schema_version: migration::CURRENT_SCHEMA_VERSION,
             visualization: VisualizationConfig::default(),
             acquisition: AcquisitionConfig::default(),
             modbus: ModbusConfig::default(),
@@ -182,6 +220,7 @@ impl Default for Config {
             processing: ProcessingConfig::default(),
             thermal_regulation: ThermalRegulationConfig::default(),
             generix: GenerixConfig::default(),
+            daemon: DaemonConfig::default(),
         }
     }
 }
@@ -246,12 +285,13 @@ impl Config {
         }
 
         debug!("Loading configuration from {:?}", path);
-        let contents = fs::read_to_string(path)
-            .with_context(|| format!("Failed to read configuration file at {:?}", path))?;
 
-        // First step: convert YAML to a generic Value
-        let yaml_value: serde_yml::Value = serde_yml::from_str(&contents)
-            .with_context(|| format!("Failed to parse YAML configuration from {:?}", path))?;
+        // First step: convert YAML to a generic Value, merging included base files
+        let mut yaml_value = include::load_yaml_with_includes(path)?;
+
+        // Upgrade files written for an older schema version before validating them
+        migration::migrate(&mut yaml_value)
+            .with_context(|| format!("Failed to migrate configuration from {:?}", path))?;
 
         // Convert to JSON Value for validation
         let json_value = serde_json::to_value(&yaml_value).with_context(|| {
@@ -265,6 +305,15 @@ impl Config {
             "Failed to parse JSON schema"
         })?;
 
+        // Misspelled keys are silently dropped by serde defaults, report them
+        for key in unknown_keys::find_unknown_keys(&json_value, &schema) {
+            warn!(
+                "Unknown configuration key '{}' in {} does not match any setting",
+                key,
+                path.display()
+            );
+        }
+
         // Create the validator
         let validator = jsonschema::draft202012::options()
             .should_validate_formats(true)
@@ -282,7 +331,7 @@ impl Config {
 
         // Now that YAML has been validated, deserializing to Config
         debug!("Schema validation passed, deserializing into Config structure");
-        let config: Config = match serde_yml::from_str(&contents) {
+        let config: Config = match serde_yml::from_value(yaml_value) {
             Ok(config) => config,
             Err(err) => {
                 error!("Configuration deserialization error: {}", err);
@@ -317,6 +366,34 @@ impl Config {
         Ok(config)
     }
 
+    /// List the keys of a configuration file that do not match any setting
+    ///
+    /// The file goes through the same include resolution and schema version
+    /// migration as in [`Config::from_file`], then its keys are compared with
+    /// the JSON schema. See [`unknown_keys`] for the path format.
+    ///
+    /// ### Parameters
+    ///
+    /// * `path` - Path of the configuration file
+    ///
+    /// ### Returns
+    ///
+    /// The paths of the unknown keys, or an error when the file cannot be read
+    /// or parsed
+    pub fn unknown_keys_in_file<P: AsRef<Path>>(path: P) -> Result<Vec<String>> {
+        let path = path.as_ref();
+        let mut yaml_value = include::load_yaml_with_includes(path)?;
+        migration::migrate(&mut yaml_value)
+            .with_context(|| format!("Failed to migrate configuration from {:?}", path))?;
+        let json_value = serde_json::to_value(&yaml_value).with_context(|| {
+            format!("Failed to convert YAML to JSON for validation: {:?}", path)
+        })?;
+        let schema: serde_json::Value =
+            serde_json::from_str(include_str!("../../resources/config.schema.json"))
+                .context("Failed to parse JSON schema")?;
+        Ok(unknown_keys::find_unknown_keys(&json_value, &schema))
+    }
+
     /// Save the configuration to a file
     pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let yaml =
@@ -339,19 +416,25 @@ impl Config {
     ///
     /// ### Parameters
     ///
-    /// * `web_port` - TCP port for the visualization server
-    /// * `web_address` - Network address for the visualization server to bind to
-    /// * `hmac_secret` - Optional HMAC secret for JWT token signing
-    /// * `daemon_mode` - If true, ensures visualization server is enabled
-    /// * `input_device` - Optional audio input device for photoacoustic analysis
-    /// * `input_file` - Optional input file path for photoacoustic analysis
-    /// * `frequency` - Optional excitation frequency in Hz
-    /// * `bandwidth` - Optional filter bandwidth in Hz
-    /// * `frame_size` - Optional FFT window size
-    /// * `averages` - Optional number of spectra to average
-    /// * `modbus_enabled` - Optional flag to enable/disable Modbus server
-    /// * `modbus_port` - Optional TCP port for Modbus server
-    /// * `modbus_address` - Optional network address for Modbus server
+    /// Each parameter maps to a single configuration field:
+    ///
+    /// * `web_port` - `visualization.port`, TCP port of the visualization server
+    /// * `web_address` - `visualization.address`, network address the visualization server binds to
+    /// * `hmac_secret` - `visualization.hmac_secret`, HMAC secret for JWT token signing
+    /// * `daemon_mode` - `visualization.enabled`, set to `true` in daemon mode
+    /// * `input_device` - `photoacoustic.input_device`, audio input device
+    /// * `input_file` - `photoacoustic.input_file`, input audio file path
+    /// * `frequency` - `photoacoustic.frequency`, excitation frequency in Hz
+    /// * `bandwidth` - `photoacoustic.bandwidth`, filter bandwidth in Hz
+    /// * `frame_size` - `photoacoustic.frame_size`, frame size in samples. The same value
+    ///   sizes the acquired audio frames and the FFT analysis window; the command line
+    ///   flag is `--frame-size` (`--window-size` is a deprecated alias)
+    /// * `averages` - `photoacoustic.averages`, number of spectra to average
+    /// * `modbus_enabled` - `modbus.enabled`, enable or disable the Modbus server
+    /// * `modbus_address` - `modbus.address`, network address of the Modbus server
+    /// * `modbus_port` - `modbus.port`, TCP port of the Modbus server
+    /// * `enable_local_visualization` - `visualization.enable_local_visualization`,
+    ///   unauthenticated visualization access for loopback clients
     ///
     /// ### Example
     ///
@@ -367,7 +450,7 @@ impl Config {
     ///     None,                           // Input file
     ///     Some(1000.0),                   // Frequency
     ///     Some(50.0),                     // Bandwidth
-    ///     Some(2048),                     // Window size
+    ///     Some(2048),                     // Frame size
     ///     Some(5),                        // Averages
     ///     Some(true),                     // Enable Modbus
     ///     Some("0.0.0.0".to_string()),    // Modbus address
@@ -439,7 +522,7 @@ impl Config {
             self.photoacoustic.bandwidth = band;
         }
         if let Some(size) = frame_size {
-            debug!("Overriding window size from command line: {}", size);
+            debug!("Overriding frame size from command line: {}", size);
             self.photoacoustic.frame_size = size;
         }
         if let Some(avg) = averages {
@@ -2,33 +2,105 @@
 // This file is part of the rust-photoacoustic project and is licensed under the
 // SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
 
-//! Modbus TCP server configuration
+//! Modbus server configuration
 //!
-//! This module defines the structures for configuring the Modbus TCP server
-//! component of the photoacoustic application.
+//! This module defines the structures for configuring the Modbus server
+//! component of the photoacoustic application, served either over TCP or
+//! as RTU over a serial line (RS-485/RS-232).
 
 use rocket_okapi::JsonSchema;
 use serde::{Deserialize, Serialize};
-/// Configuration for the Modbus TCP server component.
+
+/// Transport used by the Modbus server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ModbusTransport {
+    /// Modbus TCP, bound to `address`:`port`
+    #[default]
+    Tcp,
+    /// Modbus RTU over the serial line `serial_port`
+    Rtu,
+}
+
+/// Parity of the serial line used by the Modbus RTU transport
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ModbusParity {
+    /// No parity bit, the default of the configuration
+    #[default]
+    None,
+    /// Even parity, recommended by the Modbus serial line specification
+    Even,
+    /// Odd parity
+    Odd,
+}
+
+/// Encoding of the measurement input registers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ModbusValueEncoding {
+    /// One 16-bit register per value, multiplied by a fixed scale factor
+    #[default]
+    Scaled,
+    /// 32-bit IEEE-754 floats spread over two consecutive registers
+    Float32,
+}
+
+/// Order of the two 16-bit words of a 32-bit value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ModbusWordOrder {
+    /// Most significant word first (ABCD)
+    #[default]
+    BigEndian,
+    /// Least significant word first, also known as word swap (CDAB)
+    LittleEndian,
+}
+
+fn default_serial_port() -> String {
+    "/dev/ttyUSB0".to_string()
+}
+
+fn default_baud_rate() -> u32 {
+    9600
+}
+
+fn default_slave_id() -> u8 {
+    1
+}
+
+/// Configuration for the Modbus server component.
 ///
-/// This structure contains settings that control the Modbus TCP server functionality,
-/// including network binding parameters and whether the server is enabled.
+/// This structure contains settings that control the Modbus server functionality,
+/// including the transport, network binding parameters, serial line parameters
+/// and whether the server is enabled.
 ///
 /// ### Fields
 ///
 /// * `enabled` - Flag to enable or disable the Modbus server
+/// * `transport` - `tcp` (default) or `rtu`
 /// * `port` - TCP port number for the Modbus server (default: 502)
 /// * `address` - Network address for the Modbus server to bind to (default: 127.0.0.1)
+/// * `serial_port` - Serial device used by the RTU transport (default: /dev/ttyUSB0)
+/// * `baud_rate` - Serial line speed used by the RTU transport (default: 9600)
+/// * `parity` - Serial line parity used by the RTU transport (default: none)
+/// * `slave_id` - Unit identifier answered by the RTU transport (default: 1)
+/// * `value_encoding` - Encoding of the measurement registers (default: scaled)
+/// * `word_order` - Word order of 32-bit values in float32 encoding (default: big_endian)
 ///
 /// ### Example
 ///
 /// ```
-/// use rust_photoacoustic::config::ModbusConfig;
+/// use rust_photoacoustic::config::modbus::{ModbusConfig, ModbusParity, ModbusTransport};
 ///
 /// let modbus_config = ModbusConfig {
 ///     enabled: true,
-///     port: 503,
-///     address: "0.0.0.0".to_string(),
+///     transport: ModbusTransport::Rtu,
+///     serial_port: "/dev/ttyUSB1".to_string(),
+///     baud_rate: 19200,
+///     parity: ModbusParity::Even,
+///     slave_id: 17,
+///     ..Default::default()
 /// };
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -49,6 +121,41 @@ pub struct ModbusConfig {
     /// Can be an IPv4/IPv6 address or a hostname. Default is "127.0.0.1".
     /// Use "0.0.0.0" to bind to all IPv4 interfaces.
     pub address: String,
+
+    /// Transport used to serve the Modbus registers.
+    ///
+    /// Both transports serve the same register map.
+    #[serde(default)]
+    pub transport: ModbusTransport,
+
+    /// Serial device used by the RTU transport (e.g. "/dev/ttyUSB0" or "COM3").
+    #[serde(default = "default_serial_port")]
+    pub serial_port: String,
+
+    /// Serial line speed in bauds used by the RTU transport.
+    #[serde(default = "default_baud_rate")]
+    pub baud_rate: u32,
+
+    /// Serial line parity used by the RTU transport.
+    #[serde(default)]
+    pub parity: ModbusParity,
+
+    /// Unit identifier the RTU transport answers to (1-247).
+    ///
+    /// Requests addressed to other units on the bus are ignored.
+    #[serde(default = "default_slave_id")]
+    pub slave_id: u8,
+
+    /// Encoding of the measurement input registers.
+    ///
+    /// `scaled` keeps the historical fixed-point registers; `float32` serves
+    /// every measurement as an IEEE-754 float over two registers.
+    #[serde(default)]
+    pub value_encoding: ModbusValueEncoding,
+
+    /// Word order of the 32-bit values served in `float32` encoding.
+    #[serde(default)]
+    pub word_order: ModbusWordOrder,
 }
 
 impl Default for ModbusConfig {
@@ -57,6 +164,13 @@ impl Default for ModbusConfig {
             enabled: false,                   // Disabled by default for safety
             port: 502,                        // Standard Modbus TCP port
             address: "127.0.0.1".to_string(), // Localhost for security
+            transport: ModbusTransport::Tcp,  // TCP unless configured otherwise
+            serial_port: default_serial_port(),
+            baud_rate: default_baud_rate(),
+            parity: ModbusParity::None,
+            slave_id: default_slave_id(),
+            value_encoding: ModbusValueEncoding::Scaled, // Backward compatible layout
+            word_order: ModbusWordOrder::BigEndian,
         }
     }
 }
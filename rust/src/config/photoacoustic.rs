@@ -38,6 +38,7 @@ use serde::{Deserialize, Serialize};
 ///
 /// let pa_config = PhotoacousticConfig {
 ///     input_device: Some("first".to_string()),
+///     channel_mapping: None,
 ///     input_file: None,
 ///     frequency: 1000.0,
 ///     sample_rate: 48000,
@@ -48,14 +49,28 @@ use serde::{Deserialize, Serialize};
 ///     simulated_source: Some(SimulatedSourceConfig::default()),
 ///     record_consumer: false,
 ///     record_file: "recorded_audio.wav".to_string(),
+///     input_recording: None,
+///     network_source: None,
 /// };
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PhotoacousticConfig {
     /// The input device to use for data acquisition
+    ///
+    /// A device whose name is exactly this hint is preferred, otherwise the
+    /// first device whose name contains it is used. On Linux the ALSA names
+    /// select the path to the hardware, e.g. `pulse` for the PulseAudio server
+    /// or `hw:CARD=Audio,DEV=0` for direct access to a card.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub input_device: Option<String>,
 
+    /// Device channels read as channel A and channel B
+    ///
+    /// Selects two channels of a multichannel input device. Without it, the
+    /// first two channels are used, and a mono device feeds both channels.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel_mapping: Option<ChannelMappingConfig>,
+
     /// The input file to use for data acquisition mutually exclusive with input_device
     #[serde(skip_serializing_if = "Option::is_none")]
     pub input_file: Option<String>,
@@ -90,7 +105,7 @@ pub struct PhotoacousticConfig {
     /// Number of spectra to average
     pub averages: u16,
 
-    /// Sample rate of the input data (default is 48000 Hz)
+    /// Sample rate of the input data (default is 44100 Hz)
     #[serde(default = "default_sample_rate")]
     pub sample_rate: u16,
 
@@ -108,6 +123,116 @@ pub struct PhotoacousticConfig {
     /// Optional output file for recording audio frames
     #[serde(default)]
     pub record_file: String,
+
+    /// Recording of the raw input frames alongside the live processing
+    ///
+    /// When present, the real-time audio source is wrapped in a
+    /// [`TeeSource`](crate::acquisition::TeeSource) which writes every frame
+    /// to a WAV file before passing it on unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_recording: Option<InputRecordingConfig>,
+
+    /// PCM stream received over the network
+    ///
+    /// When present, the acquisition receives interleaved stereo PCM packets
+    /// over UDP instead of reading a local device or file. The stream must
+    /// have the configured `sample_rate`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network_source: Option<NetworkSourceConfig>,
+}
+
+/// Channels of a multichannel input device read as channel A and channel B
+///
+/// Channels are numbered from 1, as on the front panel of audio interfaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ChannelMappingConfig {
+    /// Device channel read as channel A
+    pub channel_a: u16,
+
+    /// Device channel read as channel B
+    pub channel_b: u16,
+}
+
+/// Configuration of the network PCM source
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NetworkSourceConfig {
+    /// Local UDP address the packets are received on, e.g. "0.0.0.0:5004"
+    pub bind_address: String,
+
+    /// Packet framing
+    #[serde(default)]
+    pub protocol: NetworkProtocol,
+
+    /// Encoding of the interleaved samples
+    #[serde(default)]
+    pub sample_format: NetworkSampleFormat,
+
+    /// Number of packets held back waiting for a missing packet
+    ///
+    /// Once more packets are pending, the missing ones are declared lost and
+    /// replaced by silence.
+    #[serde(default = "default_reorder_window")]
+    pub reorder_window: usize,
+}
+
+/// Framing of the PCM packets of a network source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkProtocol {
+    /// RTP packets (RFC 3550) with a 16-bit sequence number
+    #[default]
+    Rtp,
+    /// 32-bit big-endian sequence number followed by the samples
+    Raw,
+}
+
+/// Encoding of the samples of a network source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NetworkSampleFormat {
+    /// 16-bit signed big-endian, the RTP L16 payload
+    #[default]
+    S16be,
+    /// 16-bit signed little-endian
+    S16le,
+    /// 32-bit float little-endian
+    F32le,
+}
+
+impl NetworkSampleFormat {
+    /// Size of a sample in bytes
+    pub fn sample_size(&self) -> usize {
+        match self {
+            Self::S16be | Self::S16le => 2,
+            Self::F32le => 4,
+        }
+    }
+}
+
+fn default_reorder_window() -> usize {
+    4
+}
+
+/// Configuration of the raw input recording
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InputRecordingConfig {
+    /// Output WAV file, overwritten when the acquisition starts
+    pub path: String,
+
+    /// Sample format of the WAV file
+    #[serde(default)]
+    pub format: RecordingSampleFormat,
+}
+
+/// Sample format of a WAV recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingSampleFormat {
+    /// 32-bit float samples, recording the frames without loss
+    #[default]
+    Float32,
+    /// 16-bit integer PCM samples, half the size of a float recording
+    Int16,
 }
 
 fn default_sample_rate() -> u16 {
@@ -122,6 +247,7 @@ impl Default for PhotoacousticConfig {
     fn default() -> Self {
         Self {
             input_device: Some("first".to_string()), // Default to the first CPAL device
+            channel_mapping: None,                   // First two channels of the device
             input_file: None,                        // No file by default
             simulated_source: None,                  // No simulation by default (use real hardware)
             frequency: 1000.0,                       // 1kHz default frequency
@@ -132,6 +258,8 @@ impl Default for PhotoacousticConfig {
             precision: 16,
             record_consumer: false, // record consumer disabled by default
             record_file: "recorded_audio.wav".to_string(), // Default output file
+            input_recording: None,  // No input recording by default
+            network_source: None,   // No network source by default
         }
     }
 }
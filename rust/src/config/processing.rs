@@ -83,6 +83,14 @@ pub struct ProcessingPerformanceConfig {
     /// Statistics update interval (milliseconds)
     #[serde(default = "default_stats_interval_ms")]
     pub stats_interval_ms: u64,
+
+    /// Run the independent branches of the processing graph concurrently
+    #[serde(default)]
+    pub parallel_execution: bool,
+
+    /// Reuse the sample buffers of the processing graph across frames
+    #[serde(default)]
+    pub reuse_buffers: bool,
 }
 
 // Default value functions
@@ -155,6 +163,8 @@ impl Default for ProcessingPerformanceConfig {
             max_processing_time_us: default_max_processing_time_us(),
             enable_stats: default_enable_stats(),
             stats_interval_ms: default_stats_interval_ms(),
+            parallel_execution: false,
+            reuse_buffers: false,
         }
     }
 }
@@ -0,0 +1,310 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Runtime reload of the configuration file
+//!
+//! This module re-reads the configuration file of a running instance, validates
+//! it exactly like [`Config::from_file`] does, and swaps the shared configuration
+//! only when the new file is valid. Unlike [`Config::from_file`], a failed reload
+//! has no side effect: no sample file is written and the running configuration
+//! is left untouched. A candidate configuration can also be compared field by
+//! field with the running one before it is applied, see [`diff_configs`].
+//!
+//! Changed sections are classified by how they take effect:
+//!
+//! | Section | Effect |
+//! |---|---|
+//! | `access` | Hot-applied (users and OAuth2 clients are pushed to `OxideState`) |
+//! | `processing` | Hot-applied (node parameters such as filter settings and thresholds are picked up by the processing consumer) |
+//! | Any other section | Pending restart |
+
+use std::path::{Path, PathBuf};
+
+use log::warn;
+use rocket_okapi::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use super::include::load_yaml_with_includes;
+use super::migration::migrate;
+use super::unknown_keys::find_unknown_keys;
+use super::{utils, Config};
+
+/// Configuration sections that take effect without restarting the daemon
+pub const HOT_RELOADABLE_SECTIONS: &[&str] = &["access", "processing"];
+
+/// Path of the configuration file the running instance was loaded from
+///
+/// Managed by Rocket when the daemon is started with a configuration file, so
+/// that the reload endpoint knows which file to re-read.
+#[derive(Debug, Clone)]
+pub struct ConfigFilePath(pub PathBuf);
+
+/// Outcome of a configuration reload
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ConfigReloadReport {
+    /// Whether the new configuration replaced the running one
+    pub applied: bool,
+    /// Changed sections that are already in effect
+    pub hot_applied: Vec<String>,
+    /// Changed sections that only take effect after a restart
+    pub pending_restart: Vec<String>,
+    /// Validation errors that prevented the reload
+    pub errors: Vec<String>,
+}
+
+impl ConfigReloadReport {
+    /// Report of a reload rejected because of `errors`
+    pub fn rejected(errors: Vec<String>) -> Self {
+        Self {
+            errors,
+            ..Default::default()
+        }
+    }
+}
+
+/// One field that differs between the running and a candidate configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ConfigFieldChange {
+    /// Dotted path of the field, with array indices in brackets
+    /// (e.g. `processing.default_graph.nodes[1].parameters.target_channel`)
+    pub path: String,
+    /// Value in the running configuration, absent if the field is added
+    pub current: Option<serde_json::Value>,
+    /// Value in the candidate configuration, absent if the field is removed
+    pub candidate: Option<serde_json::Value>,
+    /// Whether the change only takes effect after a restart
+    pub requires_restart: bool,
+}
+
+/// Preview of a candidate configuration against the running one
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ConfigPreview {
+    /// Whether the candidate passed validation
+    pub valid: bool,
+    /// Fields that would change if the candidate were applied
+    pub changes: Vec<ConfigFieldChange>,
+    /// Whether at least one of the changes requires a restart
+    pub requires_restart: bool,
+    /// Validation errors of the candidate
+    pub errors: Vec<String>,
+}
+
+impl ConfigPreview {
+    /// Preview of a valid candidate that would apply `changes`
+    pub fn new(changes: Vec<ConfigFieldChange>) -> Self {
+        Self {
+            valid: true,
+            requires_restart: changes.iter().any(|change| change.requires_restart),
+            changes,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Preview of a candidate rejected because of `errors`
+    pub fn rejected(errors: Vec<String>) -> Self {
+        Self {
+            errors,
+            ..Default::default()
+        }
+    }
+}
+
+/// Whether changes to the top-level `section` need a restart to take effect
+pub fn section_requires_restart(section: &str) -> bool {
+    !HOT_RELOADABLE_SECTIONS.contains(&section)
+}
+
+/// Read and validate the configuration file at `path`
+///
+/// The `include` directives of the file are resolved, then the merged document
+/// goes through the checks of [`parse_config`].
+///
+/// ### Parameters
+///
+/// * `path` - Path of the configuration file
+///
+/// ### Returns
+///
+/// The validated configuration, or the list of errors found in the file
+pub fn load_config_file<P: AsRef<Path>>(path: P) -> Result<Config, Vec<String>> {
+    let yaml_value = load_yaml_with_includes(path).map_err(|e| vec![format!("{:#}", e)])?;
+    validate_document(yaml_value)
+}
+
+/// Parse and validate a configuration document
+///
+/// The document goes through the same steps as [`Config::from_file`]: schema
+/// version migration, the warnings about unknown keys, the JSON schema,
+/// deserialization and [`utils::validate_specific_rules`]. All schema
+/// violations are reported at once. JSON documents are accepted as well, since JSON is valid YAML.
+/// `include` directives are only resolved for files, see [`load_config_file`].
+///
+/// ### Parameters
+///
+/// * `contents` - The YAML or JSON configuration document
+///
+/// ### Returns
+///
+/// The validated configuration, or the list of errors found in the document
+pub fn parse_config(contents: &str) -> Result<Config, Vec<String>> {
+    let yaml_value: serde_yml::Value =
+        serde_yml::from_str(contents).map_err(|e| vec![format!("Failed to parse YAML: {}", e)])?;
+    validate_document(yaml_value)
+}
+
+fn validate_document(mut yaml_value: serde_yml::Value) -> Result<Config, Vec<String>> {
+    migrate(&mut yaml_value).map_err(|e| vec![format!("{:#}", e)])?;
+    let json_value = serde_json::to_value(&yaml_value)
+        .map_err(|e| vec![format!("Failed to convert YAML to JSON: {}", e)])?;
+
+    let schema: serde_json::Value =
+        serde_json::from_str(include_str!("../../resources/config.schema.json"))
+            .map_err(|e| vec![format!("Failed to parse JSON schema: {}", e)])?;
+    for key in find_unknown_keys(&json_value, &schema) {
+        warn!(
+            "Unknown configuration key '{}' does not match any setting",
+            key
+        );
+    }
+    let validator = jsonschema::draft202012::options()
+        .should_validate_formats(true)
+        .build(&schema)
+        .map_err(|e| vec![format!("Failed to build JSON schema validator: {}", e)])?;
+    let schema_errors: Vec<String> = validator
+        .iter_errors(&json_value)
+        .map(|error| format!("Configuration validation failed: {}", error))
+        .collect();
+    if !schema_errors.is_empty() {
+        return Err(schema_errors);
+    }
+
+    let config: Config = serde_yml::from_value(yaml_value)
+        .map_err(|e| vec![format!("Failed to deserialize configuration: {}", e)])?;
+    utils::validate_specific_rules(&config).map_err(|e| vec![e.to_string()])?;
+
+    Ok(config)
+}
+
+/// List the top-level sections that differ between `current` and `new`
+///
+/// Sections are compared through their JSON representation and returned sorted
+/// by name.
+pub fn changed_sections(current: &Config, new: &Config) -> Vec<String> {
+    let (Ok(serde_json::Value::Object(current)), Ok(serde_json::Value::Object(new))) =
+        (serde_json::to_value(current), serde_json::to_value(new))
+    else {
+        return Vec::new();
+    };
+
+    let mut changed: Vec<String> = new
+        .iter()
+        .filter(|(section, value)| current.get(*section) != Some(*value))
+        .map(|(section, _)| section.clone())
+        .collect();
+    changed.sort();
+    changed
+}
+
+/// Replace the shared configuration with `new_config`
+///
+/// The sections are compared and swapped under a single write lock, so readers
+/// observe either the old or the new configuration as a whole.
+///
+/// ### Parameters
+///
+/// * `config` - The shared configuration of the running instance
+/// * `new_config` - The validated configuration to apply
+///
+/// ### Returns
+///
+/// The report of the changed sections, classified with
+/// [`HOT_RELOADABLE_SECTIONS`]
+pub async fn swap_config(config: &RwLock<Config>, new_config: Config) -> ConfigReloadReport {
+    let mut current = config.write().await;
+    let changed = changed_sections(&current, &new_config);
+    *current = new_config;
+
+    let (hot_applied, pending_restart) = changed
+        .into_iter()
+        .partition(|section| !section_requires_restart(section));
+    ConfigReloadReport {
+        applied: true,
+        hot_applied,
+        pending_restart,
+        errors: Vec::new(),
+    }
+}
+
+/// List the fields that differ between `current` and `candidate`
+///
+/// Both configurations are compared through their JSON representation. Objects
+/// are compared key by key and arrays index by index, so only the leaves that
+/// actually changed are reported, ordered by key and then by index.
+///
+/// ### Parameters
+///
+/// * `current` - The running configuration
+/// * `candidate` - The configuration that would replace it
+///
+/// ### Returns
+///
+/// The changed fields, each classified with [`section_requires_restart`]
+pub fn diff_configs(current: &Config, candidate: &Config) -> Vec<ConfigFieldChange> {
+    let current = serde_json::to_value(current).unwrap_or_default();
+    let candidate = serde_json::to_value(candidate).unwrap_or_default();
+    let mut changes = Vec::new();
+    diff_values(
+        String::new(),
+        Some(&current),
+        Some(&candidate),
+        &mut changes,
+    );
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    changes
+}
+
+fn diff_values(
+    path: String,
+    current: Option<&serde_json::Value>,
+    candidate: Option<&serde_json::Value>,
+    changes: &mut Vec<ConfigFieldChange>,
+) {
+    use serde_json::Value;
+
+    match (current, candidate) {
+        (Some(Value::Object(current)), Some(Value::Object(candidate))) => {
+            let keys: std::collections::BTreeSet<&String> =
+                current.keys().chain(candidate.keys()).collect();
+            for key in keys {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                diff_values(child, current.get(key), candidate.get(key), changes);
+            }
+        }
+        (Some(Value::Array(current)), Some(Value::Array(candidate))) => {
+            for index in 0..current.len().max(candidate.len()) {
+                diff_values(
+                    format!("{}[{}]", path, index),
+                    current.get(index),
+                    candidate.get(index),
+                    changes,
+                );
+            }
+        }
+        (current, candidate) if current != candidate => {
+            let section = path.split(['.', '[']).next().unwrap_or_default();
+            changes.push(ConfigFieldChange {
+                requires_restart: section_requires_restart(section),
+                current: current.cloned(),
+                candidate: candidate.cloned(),
+                path,
+            });
+        }
+        _ => {}
+    }
+}
@@ -188,6 +188,383 @@ pub struct SimulatedSourceConfig {
     /// Typical values: 10-1000 Hz
     #[serde(default = "default_pulse_frequency_hz")]
     pub pulse_frequency_hz: f32,
+
+    /// Gas concentration in ppm producing `signal_amplitude`
+    ///
+    /// The simulated concentration starts at this value. When `gas_events`
+    /// change it, the photoacoustic signal amplitude is scaled proportionally:
+    /// `signal_amplitude * concentration / reference_concentration_ppm`.
+    /// Only used when source_type is "universal". Must be greater than zero.
+    #[serde(default = "default_reference_concentration_ppm")]
+    pub reference_concentration_ppm: f32,
+
+    /// Scheduled concentration changes, in any order
+    ///
+    /// Each event starts a linear ramp from the concentration at its
+    /// `time_offset_seconds` to its `target_concentration_ppm`, over
+    /// `ramp_seconds`. The time base is the simulated time (frames generated
+    /// × frame duration), so a schedule always produces the same profile.
+    /// An empty list keeps a static concentration.
+    /// Only used when source_type is "universal".
+    #[serde(default)]
+    pub gas_events: Vec<GasEvent>,
+
+    /// RMS level of the white noise added to each channel (0.0 to 1.0 of full scale)
+    ///
+    /// Unlike `background_noise_amplitude`, which is shaped by `snr_factor`,
+    /// this noise is added to the final output at exactly this level. It is
+    /// independent between channels, like the electronic noise of two
+    /// microphone preamplifiers. Only used when source_type is "universal".
+    #[serde(default)]
+    pub white_noise_level: f32,
+
+    /// RMS level of the pink (1/f) noise added to each channel (0.0 to 1.0 of full scale)
+    ///
+    /// Independent between channels and added to the final output.
+    /// Only used when source_type is "universal".
+    #[serde(default)]
+    pub pink_noise_level: f32,
+
+    /// Narrowband acoustic interferer added to both channels
+    ///
+    /// Models a pump, fan or any tonal source picked up by both microphones.
+    /// Only used when source_type is "universal".
+    #[serde(default)]
+    pub interferer: Option<ToneInterference>,
+
+    /// Mains hum added to both channels, at 50 Hz or 60 Hz
+    ///
+    /// Only used when source_type is "universal".
+    #[serde(default)]
+    pub mains_hum: Option<ToneInterference>,
+
+    /// Frequency sweep generated instead of the photoacoustic pulses
+    ///
+    /// Drives the acquisition and filter chain with a swept sine to measure
+    /// its frequency response. Both channels carry the same sweep, without
+    /// noise. Only used when source_type is "mock".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chirp: Option<ChirpConfig>,
+
+    /// Sums of sinusoids generated instead of the photoacoustic pulses
+    ///
+    /// Each channel carries its own components, to test intermodulation and
+    /// filter selectivity with several simultaneous tones. Cannot be combined
+    /// with `chirp`. Only used when source_type is "mock".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tones: Option<MultiToneConfig>,
+
+    /// Impulse or step generated instead of the photoacoustic pulses
+    ///
+    /// Both channels carry the same noiseless signal, so that the output of a
+    /// filter is directly its impulse or step response. Cannot be combined
+    /// with `chirp` or `tones`. Only used when source_type is "mock".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_signal: Option<TestSignalConfig>,
+
+    /// Seed of the random components of the simulated signal
+    ///
+    /// When set, both the "mock" and "universal" sources produce the same
+    /// output on every run, which makes tests and demos reproducible. When
+    /// absent, the seed is derived from the system time and the noise differs
+    /// between runs. Must be greater than zero.
+    #[serde(default)]
+    pub rng_seed: Option<u32>,
+}
+
+/// Sinusoidal interference mixed into the simulated signal
+///
+/// ### Examples
+///
+/// ```no_run
+/// use rust_photoacoustic::config::ToneInterference;
+///
+/// // 50 Hz mains hum at 1% of full scale
+/// let hum = ToneInterference {
+///     frequency_hz: 50.0,
+///     amplitude: 0.01,
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ToneInterference {
+    /// Frequency of the tone in Hz
+    pub frequency_hz: f32,
+    /// Peak amplitude of the tone (0.0 to 1.0 of full scale)
+    pub amplitude: f32,
+}
+
+/// Swept sine generated by the mock source
+///
+/// ### Examples
+///
+/// ```no_run
+/// use rust_photoacoustic::config::{ChirpConfig, ChirpSweep};
+///
+/// // Logarithmic sweep from 20 Hz to 20 kHz in 10 s, repeated
+/// let chirp = ChirpConfig {
+///     start_frequency_hz: 20.0,
+///     stop_frequency_hz: 20000.0,
+///     duration_seconds: 10.0,
+///     sweep: ChirpSweep::Logarithmic,
+///     repeat: true,
+///     amplitude: 0.5,
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ChirpConfig {
+    /// Frequency at the start of the sweep in Hz
+    pub start_frequency_hz: f64,
+    /// Frequency at the end of the sweep in Hz
+    pub stop_frequency_hz: f64,
+    /// Duration of one sweep in seconds
+    pub duration_seconds: f64,
+    /// Progression of the frequency over the sweep
+    #[serde(default)]
+    pub sweep: ChirpSweep,
+    /// Restart the sweep once it ends, otherwise the sweep is followed by silence
+    #[serde(default)]
+    pub repeat: bool,
+    /// Peak amplitude of the sweep (0.0 to 1.0 of full scale)
+    #[serde(default = "default_chirp_amplitude")]
+    pub amplitude: f32,
+}
+
+/// Sinusoids summed into each channel of the mock source
+///
+/// ### Examples
+///
+/// ```no_run
+/// use rust_photoacoustic::config::{MultiToneConfig, ToneComponent};
+///
+/// // Two-tone intermodulation test on channel A, a reference tone on channel B
+/// let tones = MultiToneConfig {
+///     channel_a: vec![
+///         ToneComponent { frequency_hz: 1000.0, amplitude: 0.4, phase_degrees: 0.0 },
+///         ToneComponent { frequency_hz: 1200.0, amplitude: 0.4, phase_degrees: 0.0 },
+///     ],
+///     channel_b: vec![ToneComponent { frequency_hz: 1000.0, amplitude: 0.5, phase_degrees: 90.0 }],
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct MultiToneConfig {
+    /// Components summed into channel A, silence if empty
+    #[serde(default)]
+    pub channel_a: Vec<ToneComponent>,
+    /// Components summed into channel B, silence if empty
+    #[serde(default)]
+    pub channel_b: Vec<ToneComponent>,
+}
+
+/// Sinusoidal component of a [`MultiToneConfig`]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ToneComponent {
+    /// Frequency in Hz
+    pub frequency_hz: f64,
+    /// Peak amplitude (0.0 to 1.0 of full scale)
+    pub amplitude: f32,
+    /// Phase at the first sample in degrees, 0 for a sine starting at zero
+    #[serde(default)]
+    pub phase_degrees: f64,
+}
+
+impl ToneComponent {
+    /// Value of the component `elapsed_seconds` after the first sample
+    pub fn value_at(&self, elapsed_seconds: f64) -> f32 {
+        let cycles = self.frequency_hz * elapsed_seconds + self.phase_degrees / 360.0;
+        self.amplitude * (std::f64::consts::TAU * cycles.fract()).sin() as f32
+    }
+}
+
+/// Deterministic signal to measure the impulse or step response of a filter
+///
+/// ### Examples
+///
+/// ```no_run
+/// use rust_photoacoustic::config::TestSignalConfig;
+///
+/// // Unit impulse at the 100th sample, then a step 0.5 s into the signal
+/// let impulse = TestSignalConfig::Impulse { sample: 100, amplitude: 1.0 };
+/// let step = TestSignalConfig::Step { time_seconds: 0.5, amplitude: 1.0 };
+/// assert_eq!(step.start_sample(48000), 24000);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum TestSignalConfig {
+    /// Single non-zero sample, silence everywhere else
+    Impulse {
+        /// Index of the impulse sample from the start of the acquisition
+        sample: u64,
+        /// Value of the impulse sample (1.0 for a unit impulse)
+        #[serde(default = "default_test_signal_amplitude")]
+        amplitude: f32,
+    },
+    /// Silence, then a constant level from the given time on
+    Step {
+        /// Time of the step in seconds from the start of the acquisition
+        time_seconds: f64,
+        /// Level after the step (1.0 for a unit step)
+        #[serde(default = "default_test_signal_amplitude")]
+        amplitude: f32,
+    },
+}
+
+impl TestSignalConfig {
+    /// Index of the first non-zero sample at the given sample rate
+    pub fn start_sample(&self, sample_rate: u32) -> u64 {
+        match *self {
+            Self::Impulse { sample, .. } => sample,
+            Self::Step { time_seconds, .. } => (time_seconds * sample_rate as f64).round() as u64,
+        }
+    }
+
+    /// Value of the signal at the given sample index
+    pub fn value_at(&self, sample: u64, sample_rate: u32) -> f32 {
+        let start = self.start_sample(sample_rate);
+        match *self {
+            Self::Impulse { amplitude, .. } if sample == start => amplitude,
+            Self::Step { amplitude, .. } if sample >= start => amplitude,
+            _ => 0.0,
+        }
+    }
+
+    /// Level of the non-zero samples
+    pub fn amplitude(&self) -> f32 {
+        match *self {
+            Self::Impulse { amplitude, .. } | Self::Step { amplitude, .. } => amplitude,
+        }
+    }
+}
+
+/// Progression of the frequency of a [`ChirpConfig`] sweep
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChirpSweep {
+    /// The frequency changes by the same number of Hz every second
+    #[default]
+    Linear,
+    /// The frequency changes by the same ratio every second, spending the
+    /// same time in every octave
+    Logarithmic,
+}
+
+impl ChirpConfig {
+    /// Time into the current sweep, or `None` once a single sweep has ended
+    fn sweep_time(&self, elapsed_seconds: f64) -> Option<f64> {
+        if self.repeat {
+            Some(elapsed_seconds.rem_euclid(self.duration_seconds))
+        } else if elapsed_seconds < self.duration_seconds {
+            Some(elapsed_seconds)
+        } else {
+            None
+        }
+    }
+
+    /// Instantaneous frequency in Hz at `elapsed_seconds` since the start of
+    /// the first sweep, or `None` once a single sweep has ended
+    pub fn frequency_at(&self, elapsed_seconds: f64) -> Option<f64> {
+        let t = self.sweep_time(elapsed_seconds)?;
+        let progress = t / self.duration_seconds;
+        Some(match self.sweep {
+            ChirpSweep::Linear => {
+                self.start_frequency_hz
+                    + (self.stop_frequency_hz - self.start_frequency_hz) * progress
+            }
+            ChirpSweep::Logarithmic => {
+                self.start_frequency_hz
+                    * (self.stop_frequency_hz / self.start_frequency_hz).powf(progress)
+            }
+        })
+    }
+
+    /// Phase in cycles at `elapsed_seconds`, the integral of [`Self::frequency_at`]
+    /// from the start of the current sweep, or `None` once a single sweep has ended
+    pub fn phase_at(&self, elapsed_seconds: f64) -> Option<f64> {
+        let t = self.sweep_time(elapsed_seconds)?;
+        let ratio = self.stop_frequency_hz / self.start_frequency_hz;
+        Some(match self.sweep {
+            ChirpSweep::Logarithmic if ratio != 1.0 => {
+                self.start_frequency_hz
+                    * self.duration_seconds
+                    * (ratio.powf(t / self.duration_seconds) - 1.0)
+                    / ratio.ln()
+            }
+            _ => {
+                self.start_frequency_hz * t
+                    + (self.stop_frequency_hz - self.start_frequency_hz) * t * t
+                        / (2.0 * self.duration_seconds)
+            }
+        })
+    }
+}
+
+/// Scheduled change of the simulated gas concentration
+///
+/// ### Examples
+///
+/// ```no_run
+/// use rust_photoacoustic::config::GasEvent;
+///
+/// // Rise to 1500 ppm over 10 s, starting 30 s into the simulation
+/// let event = GasEvent {
+///     time_offset_seconds: 30.0,
+///     target_concentration_ppm: 1500.0,
+///     ramp_seconds: 10.0,
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct GasEvent {
+    /// Simulated time at which the ramp starts, in seconds
+    pub time_offset_seconds: f64,
+    /// Concentration reached at the end of the ramp, in ppm
+    pub target_concentration_ppm: f32,
+    /// Duration of the linear ramp in seconds, 0 for a step change
+    #[serde(default)]
+    pub ramp_seconds: f64,
+}
+
+impl SimulatedSourceConfig {
+    /// Scheduled gas concentration in ppm at `elapsed_seconds` of simulated time
+    ///
+    /// Events are applied in `time_offset_seconds` order. An event starting
+    /// while the previous ramp is still running ramps from the concentration
+    /// reached at that moment.
+    pub fn concentration_at(&self, elapsed_seconds: f64) -> f32 {
+        let mut events = self.gas_events.clone();
+        events.sort_by(|a, b| a.time_offset_seconds.total_cmp(&b.time_offset_seconds));
+
+        let mut concentration = self.reference_concentration_ppm;
+        for (index, event) in events.iter().enumerate() {
+            if elapsed_seconds < event.time_offset_seconds {
+                break;
+            }
+            // The ramp is interrupted by the next event
+            let ramp_end = match events.get(index + 1) {
+                Some(next) if next.time_offset_seconds <= elapsed_seconds => {
+                    next.time_offset_seconds
+                }
+                _ => elapsed_seconds,
+            };
+            let progress = if event.ramp_seconds > 0.0 {
+                ((ramp_end - event.time_offset_seconds) / event.ramp_seconds).min(1.0)
+            } else {
+                1.0
+            };
+            concentration += (event.target_concentration_ppm - concentration) * progress as f32;
+        }
+        concentration
+    }
+
+    /// Photoacoustic signal amplitude at `elapsed_seconds` of simulated time
+    ///
+    /// `signal_amplitude` scaled by the scheduled concentration relative to
+    /// `reference_concentration_ppm`.
+    pub fn signal_amplitude_at(&self, elapsed_seconds: f64) -> f32 {
+        if self.gas_events.is_empty() {
+            return self.signal_amplitude;
+        }
+        self.signal_amplitude * self.concentration_at(elapsed_seconds)
+            / self.reference_concentration_ppm
+    }
 }
 
 impl Default for SimulatedSourceConfig {
@@ -206,11 +583,29 @@ impl Default for SimulatedSourceConfig {
             modulation_mode: default_modulation_mode(),
             pulse_width_seconds: default_pulse_width_seconds(),
             pulse_frequency_hz: default_pulse_frequency_hz(),
+            reference_concentration_ppm: default_reference_concentration_ppm(),
+            gas_events: Vec::new(),
+            white_noise_level: 0.0,
+            pink_noise_level: 0.0,
+            interferer: None,
+            mains_hum: None,
+            chirp: None,
+            tones: None,
+            test_signal: None,
+            rng_seed: None,
         }
     }
 }
 
 // Default value functions for serde
+fn default_chirp_amplitude() -> f32 {
+    0.5
+}
+
+fn default_test_signal_amplitude() -> f32 {
+    1.0
+}
+
 fn default_source_type() -> String {
     "mock".to_string() // Default to simple mock for backward compatibility
 }
@@ -262,3 +657,7 @@ fn default_pulse_width_seconds() -> f32 {
 fn default_pulse_frequency_hz() -> f32 {
     100.0 // 100 Hz pulse frequency
 }
+
+fn default_reference_concentration_ppm() -> f32 {
+    1000.0 // 1000 ppm produces the configured signal amplitude
+}
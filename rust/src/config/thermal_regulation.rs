@@ -28,6 +28,53 @@ pub struct ThermalRegulationConfig {
     /// Global thermal regulation parameters
     #[serde(default)]
     pub global_settings: GlobalThermalSettings,
+
+    /// Groups of adjacent regulators whose control is coordinated
+    #[serde(default)]
+    pub zone_groups: Vec<ThermalZoneGroupConfig>,
+}
+
+impl ThermalRegulationConfig {
+    /// Zone group containing a regulator, if any
+    pub fn zone_group(&self, regulator_id: &str) -> Option<&ThermalZoneGroupConfig> {
+        self.zone_groups
+            .iter()
+            .find(|group| group.zones.iter().any(|zone| zone == regulator_id))
+    }
+}
+
+/// Group of adjacent regulators controlled together
+///
+/// Adjacent cells exchange heat, so with independent control the oscillation
+/// of one zone drives its neighbours. At every cycle the regulators of a group
+/// publish their readings in the shared thermal state and read the readings of
+/// the other zones to apply the relationship of the group.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ThermalZoneGroupConfig {
+    /// Unique identifier of the group
+    pub id: String,
+
+    /// Relationship enforced between the zones
+    pub mode: ZoneCoordinationMode,
+
+    /// Identifiers of the regulators of the group
+    pub zones: Vec<String>,
+
+    /// Regulator driving the group, required in `master_slave` mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub master: Option<String>,
+}
+
+/// Relationship between the zones of a [`ThermalZoneGroupConfig`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ZoneCoordinationMode {
+    /// The master regulates its own temperature and the other zones apply its
+    /// control output
+    MasterSlave,
+    /// Every zone regulates the average temperature of the group towards the
+    /// average setpoint of the group
+    AveragedSetpoint,
 }
 
 /// I2C bus configuration for hardware controllers
@@ -179,6 +226,106 @@ pub struct ThermalRegulatorConfig {
 
     /// Safety limits and protections
     pub safety_limits: SafetyLimits,
+
+    /// Digital inputs read from the CAT9555 input registers
+    /// (over-temperature comparators, end-stops, enable switches)
+    #[serde(default)]
+    pub digital_inputs: Vec<DigitalInputConfig>,
+
+    /// H-Bridge current sensing and overcurrent protection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_sense: Option<CurrentSenseConfig>,
+
+    /// Time series log of the regulator telemetry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_log: Option<ThermalDataLogConfig>,
+}
+
+/// Log file receiving one row per regulation cycle
+///
+/// Each row holds the timestamp, regulator id, temperature, setpoint,
+/// control output and active safety fault of the cycle. Once the file would
+/// exceed `max_size` bytes it is renamed `<path>.1`, older files are shifted
+/// to `<path>.2`, `<path>.3`... and a new file is started.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ThermalDataLogConfig {
+    /// Path of the log file
+    pub path: String,
+
+    /// Row format of the log file
+    #[serde(default)]
+    pub format: ThermalDataLogFormat,
+
+    /// Size in bytes above which the log file is rotated
+    #[serde(default = "default_data_log_max_size")]
+    pub max_size: u64,
+
+    /// Number of rotated log files kept next to the current one
+    #[serde(default = "default_data_log_max_files")]
+    pub max_files: usize,
+}
+
+/// Row format of a thermal data log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ThermalDataLogFormat {
+    /// Comma-separated values with a header row at the top of each file
+    #[default]
+    Csv,
+    /// One JSON object per line
+    Json,
+}
+
+/// Current-sense channel measuring the H-Bridge current through a shunt
+///
+/// The ADC reading is converted to a voltage with the `adc_resolution` and
+/// `voltage_reference` of the temperature conversion, then to amps:
+/// `current = voltage / (shunt_resistance_ohm * amplifier_gain)`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CurrentSenseConfig {
+    /// ADC controller I2C address
+    pub adc_address: u8,
+
+    /// ADC channel number (0-3)
+    pub adc_channel: u8,
+
+    /// Shunt resistance in ohms
+    pub shunt_resistance_ohm: f64,
+
+    /// Gain of the current-sense amplifier between the shunt and the ADC
+    #[serde(default = "default_amplifier_gain")]
+    pub amplifier_gain: f64,
+
+    /// Current in amps above which the actuators are shut down
+    pub overcurrent_threshold_a: f64,
+}
+
+impl CurrentSenseConfig {
+    /// Convert the voltage measured by the ADC to a current in amps
+    pub fn current_from_voltage(&self, voltage: f64) -> f64 {
+        voltage / (self.shunt_resistance_ohm * self.amplifier_gain)
+    }
+}
+
+/// Digital input read from a CAT9555 GPIO controller
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DigitalInputConfig {
+    /// Name of the input, used in logs and fault messages
+    pub name: String,
+
+    /// CAT9555 I2C address
+    pub address: u8,
+
+    /// GPIO pin number (0-15, pins 8-15 being on port 1)
+    pub pin: u8,
+
+    /// The input is active when the pin is low
+    #[serde(default)]
+    pub active_low: bool,
+
+    /// Shut the regulator down when the input is active
+    #[serde(default)]
+    pub fault: bool,
 }
 
 /// Temperature sensor configuration
@@ -281,18 +428,113 @@ pub struct ThermalModeConfig {
 /// Temperature conversion configuration
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TemperatureConversionConfig {
-    /// Conversion formula (polynomial or lookup table)
+    /// Conversion formula: an expression of `voltage` returning Kelvin, or the
+    /// name of a built-in formula (`NTC_10K_3977`, `STEINHART_HART`,
+    /// `PT100_LINEAR`, `PT1000_CVD`, `LOOKUP_TABLE`, ...)
     pub formula: String,
 
     /// ADC resolution in bits
     pub adc_resolution: u8,
 
-    /// Voltage reference in volts
+    /// Voltage reference in volts, also the supply of the sensor voltage divider
     pub voltage_reference: f32,
 
     /// Conversion type
     #[serde(default)]
     pub conversion_type: ConversionType,
+
+    /// Pull-up resistance of the sensor voltage divider in ohms
+    ///
+    /// Used by the built-in resistive sensor formulas, the sensor being
+    /// connected between the ADC input and ground.
+    #[serde(default = "default_pullup_resistance")]
+    pub pullup_resistance: f64,
+
+    /// Coefficients of the `STEINHART_HART` formula
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub steinhart_hart: Option<SteinhartHartCoefficients>,
+
+    /// Points of the `LOOKUP_TABLE` formula, sorted by increasing voltage
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lookup_table: Vec<LookupTablePoint>,
+
+    /// Field calibration of the sensor, applied to the converted temperature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calibration: Option<TemperatureCalibration>,
+}
+
+/// Field calibration of a temperature sensor
+///
+/// The two-point correction, if any, maps the measured temperatures of its
+/// two points onto their reference temperatures, linearly between and beyond
+/// them. The offset is then added.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+pub struct TemperatureCalibration {
+    /// Offset added to the temperature in Kelvin
+    #[serde(default)]
+    pub offset_k: f64,
+
+    /// Two-point correction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub two_point: Option<TwoPointCalibration>,
+}
+
+impl TemperatureCalibration {
+    /// Apply the calibration to a converted temperature in Kelvin
+    pub fn apply(&self, temperature_k: f64) -> f64 {
+        let corrected = match &self.two_point {
+            Some(TwoPointCalibration { low, high }) => {
+                low.reference_k
+                    + (temperature_k - low.measured_k) * (high.reference_k - low.reference_k)
+                        / (high.measured_k - low.measured_k)
+            }
+            None => temperature_k,
+        };
+        corrected + self.offset_k
+    }
+}
+
+/// Two-point calibration of a temperature sensor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TwoPointCalibration {
+    /// Low temperature calibration point
+    pub low: CalibrationPoint,
+
+    /// High temperature calibration point
+    pub high: CalibrationPoint,
+}
+
+/// Temperature measured by the sensor against a reference thermometer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CalibrationPoint {
+    /// Temperature converted from the sensor in Kelvin
+    pub measured_k: f64,
+
+    /// Temperature of the reference thermometer in Kelvin
+    pub reference_k: f64,
+}
+
+/// Steinhart-Hart equation coefficients: 1/T = A + B·ln(R) + C·ln(R)³
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SteinhartHartCoefficients {
+    /// Coefficient A
+    pub a: f64,
+
+    /// Coefficient B
+    pub b: f64,
+
+    /// Coefficient C
+    pub c: f64,
+}
+
+/// Point of a voltage to temperature lookup table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LookupTablePoint {
+    /// ADC input voltage in volts
+    pub voltage: f64,
+
+    /// Temperature at this voltage in Kelvin
+    pub temperature_k: f64,
 }
 
 /// PID controller parameters
@@ -322,6 +564,38 @@ pub struct PidParameters {
     /// PID controller settings
     #[serde(default)]
     pub settings: PidSettings,
+
+    /// Feedforward term added to the PID output
+    #[serde(default)]
+    pub feedforward: FeedforwardParameters,
+}
+
+/// Feedforward term of the thermal controller
+///
+/// The feedforward output is added to the PID output before clamping:
+/// `setpoint_gain * (setpoint - reference_temperature_k) + disturbance_gain * disturbance`,
+/// the disturbance being 1.0 while `disturbance_input` is active and 0.0
+/// otherwise. It provides the base output needed to hold the setpoint, so the
+/// integral term only corrects the residual error. With the default zero gains
+/// the controller is a pure PID.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FeedforwardParameters {
+    /// Output percent per Kelvin of setpoint above the reference temperature
+    #[serde(default)]
+    pub setpoint_gain: f32,
+
+    /// Temperature in Kelvin at which the setpoint feedforward is zero,
+    /// typically the ambient temperature
+    #[serde(default = "default_feedforward_reference_temperature")]
+    pub reference_temperature_k: f32,
+
+    /// Output percent added while the disturbance input is active
+    #[serde(default)]
+    pub disturbance_gain: f32,
+
+    /// Name of the digital input signaling the disturbance (e.g. gas flow on)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disturbance_input: Option<String>,
 }
 
 /// Control system parameters
@@ -539,15 +813,34 @@ pub struct ControlSettings {
     #[serde(default)]
     pub adaptive_control: bool,
 
-    /// Deadband around setpoint
+    /// Half-width in Kelvin of the dead-band around the setpoint, 0 to disable
+    ///
+    /// While the error stays within the dead-band the PID is not updated and
+    /// the output is set according to `deadband_output`, which stops the
+    /// actuator from dithering around the setpoint.
     #[serde(default)]
     pub deadband_k: f32,
 
+    /// Output applied while the error is within the dead-band
+    #[serde(default)]
+    pub deadband_output: DeadbandOutput,
+
     /// Minimum control action
     #[serde(default)]
     pub min_control_action: f32,
 }
 
+/// Output of the thermal controller within the dead-band
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeadbandOutput {
+    /// Switch the actuator off
+    #[default]
+    Zero,
+    /// Keep the last output computed before entering the dead-band
+    Hold,
+}
+
 /// Emergency settings
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EmergencySettings {
@@ -636,6 +929,9 @@ fn default_adc_resolution() -> u8 {
 fn default_voltage_ref() -> f32 {
     3.3
 }
+fn default_pullup_resistance() -> f64 {
+    10000.0
+}
 fn default_gpio_channels() -> u8 {
     16
 }
@@ -675,6 +971,18 @@ fn default_metrics_interval() -> f32 {
 fn default_history_buffer_size() -> usize {
     1000
 }
+fn default_amplifier_gain() -> f64 {
+    1.0
+}
+fn default_data_log_max_size() -> u64 {
+    10 * 1024 * 1024
+}
+fn default_data_log_max_files() -> usize {
+    5
+}
+fn default_feedforward_reference_temperature() -> f32 {
+    298.15
+}
 fn default_emergency_temp() -> f32 {
     373.15
 }
@@ -690,6 +998,7 @@ impl Default for ThermalRegulationConfig {
             i2c_buses: HashMap::new(),
             regulators: Vec::new(),
             global_settings: GlobalThermalSettings::default(),
+            zone_groups: Vec::new(),
         }
     }
 }
@@ -762,6 +1071,17 @@ impl Default for InterruptConfig {
     }
 }
 
+impl Default for FeedforwardParameters {
+    fn default() -> Self {
+        Self {
+            setpoint_gain: 0.0,
+            reference_temperature_k: default_feedforward_reference_temperature(),
+            disturbance_gain: 0.0,
+            disturbance_input: None,
+        }
+    }
+}
+
 impl Default for PidSettings {
     fn default() -> Self {
         Self {
@@ -776,7 +1096,8 @@ impl Default for ControlSettings {
     fn default() -> Self {
         Self {
             adaptive_control: false,
-            deadband_k: 0.1,
+            deadband_k: 0.0,
+            deadband_output: DeadbandOutput::default(),
             min_control_action: 0.1,
         }
     }
@@ -0,0 +1,209 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Detection of configuration keys that do not match any setting
+//!
+//! Most sections of the configuration are deserialized with serde defaults, so
+//! a misspelled key such as `reuse_bufers` is silently dropped and the setting
+//! keeps its default value. The loader compares the document with the JSON
+//! schema and warns about every key the schema does not describe.
+//!
+//! Keys are reported by their path in the document, with array elements
+//! written as `[index]`, for instance
+//! `processing.default_graph.nodes[2].parameters.cutof_frequency`.
+//!
+//! The schema is followed through `allOf`, `anyOf` and `oneOf`, and through
+//! `if`/`then`/`else` when the condition only uses `const`, `enum` and
+//! `required`, which covers the node parameters selected by `node_type`.
+//! Objects without declared properties, such as the configuration of the
+//! action drivers, are free-form and never reported.
+
+use serde_json::Value;
+
+/// List the keys of `document` that do not match any property of `schema`
+///
+/// ### Parameters
+///
+/// * `document` - Configuration document, converted to JSON
+/// * `schema` - JSON schema describing the document
+///
+/// ### Returns
+///
+/// The paths of the unknown keys, in document order
+///
+/// ### Examples
+///
+/// ```
+/// use rust_photoacoustic::config::unknown_keys::find_unknown_keys;
+/// use serde_json::json;
+///
+/// let schema = json!({
+///     "type": "object",
+///     "properties": {"daemon": {"type": "object", "properties": {"log_file": {}}}}
+/// });
+/// let document = json!({"daemon": {"log_fille": "daemon.log"}});
+/// assert_eq!(find_unknown_keys(&document, &schema), vec!["daemon.log_fille"]);
+/// ```
+pub fn find_unknown_keys(document: &Value, schema: &Value) -> Vec<String> {
+    let mut unknown = Vec::new();
+    collect_unknown_keys(document, &[schema], "", &mut unknown);
+    unknown
+}
+
+/// Find the unknown keys of `value`, described by all of `schemas`
+fn collect_unknown_keys(value: &Value, schemas: &[&Value], path: &str, unknown: &mut Vec<String>) {
+    let mut applicable = Vec::new();
+    for schema in schemas {
+        applicable_schemas(schema, value, &mut applicable);
+    }
+
+    match value {
+        Value::Object(object) => {
+            let describes_keys = applicable.iter().any(|schema| {
+                schema.get("properties").is_some()
+                    || schema.get("patternProperties").is_some()
+                    || schema
+                        .get("additionalProperties")
+                        .is_some_and(Value::is_object)
+            });
+            if !describes_keys {
+                // Free-form object
+                return;
+            }
+            let open = applicable
+                .iter()
+                .any(|schema| schema.get("additionalProperties") == Some(&Value::Bool(true)));
+
+            for (key, child) in object {
+                let child_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                let child_schemas = property_schemas(&applicable, key);
+                if child_schemas.is_empty() {
+                    if !open {
+                        unknown.push(child_path);
+                    }
+                    continue;
+                }
+                collect_unknown_keys(child, &child_schemas, &child_path, unknown);
+            }
+        }
+        Value::Array(elements) => {
+            let item_schemas: Vec<&Value> = applicable
+                .iter()
+                .filter_map(|schema| schema.get("items"))
+                .filter(|items| items.is_object())
+                .collect();
+            if item_schemas.is_empty() {
+                return;
+            }
+            for (index, element) in elements.iter().enumerate() {
+                let element_path = format!("{}[{}]", path, index);
+                collect_unknown_keys(element, &item_schemas, &element_path, unknown);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collect `schema` and the subschemas that apply to `value`
+fn applicable_schemas<'a>(schema: &'a Value, value: &Value, applicable: &mut Vec<&'a Value>) {
+    if !schema.is_object() {
+        return;
+    }
+    applicable.push(schema);
+
+    for keyword in ["allOf", "anyOf", "oneOf"] {
+        if let Some(branches) = schema.get(keyword).and_then(Value::as_array) {
+            for branch in branches {
+                applicable_schemas(branch, value, applicable);
+            }
+        }
+    }
+
+    if let Some(condition) = schema.get("if") {
+        let branch = if condition_holds(condition, value) {
+            schema.get("then")
+        } else {
+            schema.get("else")
+        };
+        if let Some(branch) = branch {
+            applicable_schemas(branch, value, applicable);
+        }
+    }
+}
+
+/// Evaluate the `const`, `enum` and `required` keywords of an `if` condition
+fn condition_holds(condition: &Value, value: &Value) -> bool {
+    let Some(object) = value.as_object() else {
+        return true;
+    };
+
+    let properties_hold = condition
+        .get("properties")
+        .and_then(Value::as_object)
+        .is_none_or(|properties| {
+            properties.iter().all(|(key, property)| {
+                let Some(actual) = object.get(key) else {
+                    return true;
+                };
+                property
+                    .get("const")
+                    .is_none_or(|expected| actual == expected)
+                    && property
+                        .get("enum")
+                        .and_then(Value::as_array)
+                        .is_none_or(|allowed| allowed.contains(actual))
+            })
+        });
+
+    let required_present = condition
+        .get("required")
+        .and_then(Value::as_array)
+        .is_none_or(|required| {
+            required
+                .iter()
+                .filter_map(Value::as_str)
+                .all(|key| object.contains_key(key))
+        });
+
+    properties_hold && required_present
+}
+
+/// Schemas describing the value of `key` in an object described by `schemas`
+///
+/// Declared properties take precedence over `patternProperties`, which take
+/// precedence over an `additionalProperties` schema.
+fn property_schemas<'a>(schemas: &[&'a Value], key: &str) -> Vec<&'a Value> {
+    let declared: Vec<&Value> = schemas
+        .iter()
+        .filter_map(|schema| schema.get("properties")?.get(key))
+        .collect();
+    if !declared.is_empty() {
+        return declared;
+    }
+
+    let key_value = Value::String(key.to_string());
+    let patterned: Vec<&Value> = schemas
+        .iter()
+        .filter_map(|schema| schema.get("patternProperties")?.as_object())
+        .flat_map(|patterns| patterns.iter())
+        .filter(|(pattern, _)| {
+            jsonschema::validator_for(&serde_json::json!({ "pattern": pattern }))
+                .is_ok_and(|validator| validator.is_valid(&key_value))
+        })
+        .map(|(_, schema)| schema)
+        .collect();
+    if !patterned.is_empty() {
+        return patterned;
+    }
+
+    schemas
+        .iter()
+        .filter_map(|schema| schema.get("additionalProperties"))
+        .filter(|additional| additional.is_object())
+        .collect()
+}
@@ -9,16 +9,19 @@
 
 use anyhow::{Context, Result};
 use base64::Engine;
-use log::debug;
+use log::{debug, info};
 
-use super::{Config, USER_SESSION_SEPARATOR};
-use crate::utility::temperature_conversion::convert_voltage_to_temperature;
+use super::migration::CURRENT_SCHEMA_VERSION;
+use super::thermal_regulation::ZoneCoordinationMode;
+use super::{Config, TestSignalConfig, USER_SESSION_SEPARATOR};
+use crate::utility::temperature_conversion::validate_temperature_conversion;
 
-/// Output the embedded JSON schema to the console.
+/// Output the configuration schema to the console.
 ///
 /// This function is called when the `--show-config-schema` flag is provided
-/// on the command line. It outputs the full JSON schema for the configuration
-/// to stdout, formatted for readability.
+/// on the command line. It outputs the editor schema built by
+/// [`editor_config_schema`] to stdout, formatted for readability, and logs
+/// the header that makes YAML editors use it.
 ///
 /// ### Example
 ///
@@ -26,23 +29,175 @@ use crate::utility::temperature_conversion::convert_voltage_to_temperature;
 /// ./rust_photoacoustic --show-config-schema > config_schema.json
 /// ```
 pub fn output_config_schema() -> Result<()> {
-    // Load the schema from the embedded string
-    let schema_str = include_str!("../../resources/config.schema.json");
-
-    // Parse the schema to a JSON Value to pretty-format it
-    let schema: serde_json::Value =
-        serde_json::from_str(schema_str).context("Failed to parse JSON schema")?;
+    let schema = editor_config_schema()?;
 
     // Pretty-print the schema
     let formatted_schema =
         serde_json::to_string_pretty(&schema).context("Failed to format JSON schema")?;
 
-    // Output to stdout
+    // Output to stdout, the header hint is logged to keep the output valid JSON
     println!("{}", formatted_schema);
+    info!(
+        "Add this line at the top of config.yaml for completion in editors using yaml-language-server:\n{}",
+        yaml_language_server_header("config_schema.json")
+    );
 
     Ok(())
 }
 
+/// Build the configuration schema for editors
+///
+/// Starts from the embedded JSON schema used for validation and fills in, for
+/// every property the Rust configuration types also describe, the missing
+/// descriptions (from the doc comments), default values and enum values.
+/// Annotations already present in the embedded schema are kept. Node
+/// parameters are free-form in the Rust types and keep the annotations of the
+/// embedded schema only.
+///
+/// ### Returns
+///
+/// The annotated JSON schema, or an error if the embedded schema is invalid
+pub fn editor_config_schema() -> Result<serde_json::Value> {
+    let mut schema: serde_json::Value =
+        serde_json::from_str(include_str!("../../resources/config.schema.json"))
+            .context("Failed to parse JSON schema")?;
+
+    let generated = schemars::schema_for!(Config).to_value();
+    let definitions = generated
+        .get("$defs")
+        .cloned()
+        .unwrap_or(serde_json::Value::Null);
+    annotate_schema(&mut schema, &generated, &definitions);
+
+    Ok(schema)
+}
+
+/// Header line pointing yaml-language-server based editors to a schema
+///
+/// ### Parameters
+///
+/// * `schema_location` - Path or URL of the schema, relative paths are resolved
+///   from the directory of the YAML file
+///
+/// ### Returns
+///
+/// The comment line to put at the top of the configuration file
+pub fn yaml_language_server_header(schema_location: &str) -> String {
+    format!("# yaml-language-server: $schema={}", schema_location)
+}
+
+/// Copy the missing annotations of `generated` into `target`, recursively
+fn annotate_schema(
+    target: &mut serde_json::Value,
+    generated: &serde_json::Value,
+    definitions: &serde_json::Value,
+) {
+    let resolved = resolve_generated(generated, definitions);
+    let Some(target) = target.as_object_mut() else {
+        return;
+    };
+
+    if !target.contains_key("description") {
+        if let Some(doc) = doc_text(generated).or_else(|| doc_text(resolved)) {
+            target.insert("description".to_string(), doc.into());
+        }
+    }
+    if !target.contains_key("default") {
+        let default = generated.get("default").or_else(|| resolved.get("default"));
+        // Whole sections are described by the defaults of their properties
+        if let Some(default) = default.filter(|default| !default.is_null() && !default.is_object())
+        {
+            target.insert("default".to_string(), default.clone());
+        }
+    }
+    let constrained = ["enum", "const", "oneOf", "anyOf"]
+        .iter()
+        .any(|keyword| target.contains_key(*keyword));
+    if !constrained && target.get("type") == Some(&serde_json::json!("string")) {
+        if let Some(values) = enum_values(resolved) {
+            target.insert("enum".to_string(), values.into());
+        }
+    }
+
+    if let Some(properties) = target
+        .get_mut("properties")
+        .and_then(serde_json::Value::as_object_mut)
+    {
+        for (key, property) in properties.iter_mut() {
+            if let Some(generated_property) = resolved.get("properties").and_then(|p| p.get(key)) {
+                annotate_schema(property, generated_property, definitions);
+            }
+        }
+    }
+    if let (Some(items), Some(generated_items)) = (target.get_mut("items"), resolved.get("items")) {
+        annotate_schema(items, generated_items, definitions);
+    }
+    // Maps such as the I2C buses, keyed by name
+    if let Some(generated_values) = resolved
+        .get("additionalProperties")
+        .filter(|values| values.is_object())
+    {
+        if let Some(patterns) = target
+            .get_mut("patternProperties")
+            .and_then(serde_json::Value::as_object_mut)
+        {
+            for values in patterns.values_mut() {
+                annotate_schema(values, generated_values, definitions);
+            }
+        }
+    }
+}
+
+/// Follow the `$ref` of a generated schema and unwrap `Option` types
+fn resolve_generated<'a>(
+    schema: &'a serde_json::Value,
+    definitions: &'a serde_json::Value,
+) -> &'a serde_json::Value {
+    if let Some(definition) = schema
+        .get("$ref")
+        .and_then(serde_json::Value::as_str)
+        .and_then(|reference| reference.strip_prefix("#/$defs/"))
+        .and_then(|name| definitions.get(name))
+    {
+        return resolve_generated(definition, definitions);
+    }
+
+    // `Option<T>` is generated as `anyOf: [T, {"type": "null"}]`
+    if let Some(branches) = schema.get("anyOf").and_then(serde_json::Value::as_array) {
+        let mut not_null = branches
+            .iter()
+            .filter(|branch| branch.get("type") != Some(&serde_json::json!("null")));
+        if let (Some(branch), None) = (not_null.next(), not_null.next()) {
+            return resolve_generated(branch, definitions);
+        }
+    }
+
+    schema
+}
+
+/// Doc comment of a generated schema, split by schemars into title and description
+fn doc_text(schema: &serde_json::Value) -> Option<String> {
+    let parts: Vec<&str> = ["title", "description"]
+        .iter()
+        .filter_map(|keyword| schema.get(*keyword)?.as_str())
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("\n\n"))
+}
+
+/// Values of a generated enum, documented variants are generated as `oneOf` constants
+fn enum_values(schema: &serde_json::Value) -> Option<Vec<serde_json::Value>> {
+    if let Some(values) = schema.get("enum").and_then(serde_json::Value::as_array) {
+        return Some(values.clone());
+    }
+    schema
+        .get("oneOf")?
+        .as_array()?
+        .iter()
+        .map(|variant| variant.get("const").cloned())
+        .collect::<Option<Vec<_>>>()
+        .filter(|values| !values.is_empty())
+}
+
 /// Check if a string is a valid IP address
 ///
 /// Validates that a string represents a valid IPv4 or IPv6 address,
@@ -83,6 +238,7 @@ pub fn is_valid_ip_address(addr: &str) -> bool {
 ///
 /// This function validates:
 ///
+/// - **Schema Version**: Ensures the configuration was migrated to the current schema version
 /// - **SSL Configuration**: Ensures that if a certificate is provided, a key is also provided (and vice versa)
 /// - **Base64 Encoding**: Validates that certificates, keys, and RS256 keys are valid base64-encoded strings
 /// - **Port Range**: Ensures the visualization port is within a valid range (1-65534)
@@ -94,6 +250,15 @@ pub fn is_valid_ip_address(addr: &str) -> bool {
 pub fn validate_specific_rules(config: &Config) -> Result<()> {
     debug!("Performing additional validation checks");
 
+    // Validate schema version
+    if config.schema_version != CURRENT_SCHEMA_VERSION {
+        anyhow::bail!(
+            "Configuration schema version {} does not match the current version {}",
+            config.schema_version,
+            CURRENT_SCHEMA_VERSION
+        );
+    }
+
     // Validate SSL certificates
     if let Some(cert) = &config.visualization.cert {
         if config.visualization.key.is_none() {
@@ -122,6 +287,240 @@ pub fn validate_specific_rules(config: &Config) -> Result<()> {
         anyhow::bail!("Invalid port number: {}", config.visualization.port);
     }
 
+    let rate_limit = &config.visualization.rate_limit;
+    if rate_limit.requests == 0 || rate_limit.window_secs == 0 {
+        anyhow::bail!(
+            "Invalid rate limit: {} requests per {} seconds",
+            rate_limit.requests,
+            rate_limit.window_secs
+        );
+    }
+
+    for origin in &config.visualization.cors.allowed_origins {
+        let valid = match origin.split_once("://") {
+            Some((scheme, authority)) => {
+                (scheme == "http" || scheme == "https")
+                    && !authority.is_empty()
+                    && !authority.trim_end_matches('/').contains('/')
+            }
+            None => false,
+        };
+        if !valid {
+            anyhow::bail!(
+                "Invalid CORS origin '{}': expected scheme://host[:port]",
+                origin
+            );
+        }
+    }
+
+    if config.visualization.measurement_stream_interval_ms == 0 {
+        anyhow::bail!("Invalid measurement stream interval: must be greater than 0 ms");
+    }
+
+    if config.visualization.thermal_stream_interval_ms == 0 {
+        anyhow::bail!("Invalid thermal stream interval: must be greater than 0 ms");
+    }
+
+    if let Some(ref simulated_source) = config.photoacoustic.simulated_source {
+        if simulated_source.reference_concentration_ppm <= 0.0 {
+            anyhow::bail!(
+                "Invalid simulated source reference concentration: {} ppm must be greater than 0",
+                simulated_source.reference_concentration_ppm
+            );
+        }
+        for event in &simulated_source.gas_events {
+            let valid = [event.time_offset_seconds, event.ramp_seconds]
+                .iter()
+                .all(|value| value.is_finite() && *value >= 0.0)
+                && event.target_concentration_ppm.is_finite()
+                && event.target_concentration_ppm >= 0.0;
+            if !valid {
+                anyhow::bail!(
+                    "Invalid simulated gas event at {} s: offset, ramp and target concentration must be positive",
+                    event.time_offset_seconds
+                );
+            }
+        }
+        for (name, level) in [
+            ("white noise level", simulated_source.white_noise_level),
+            ("pink noise level", simulated_source.pink_noise_level),
+        ] {
+            if !(0.0..=1.0).contains(&level) {
+                anyhow::bail!(
+                    "Invalid simulated source {}: {} must be between 0.0 and 1.0",
+                    name,
+                    level
+                );
+            }
+        }
+        if simulated_source.rng_seed == Some(0) {
+            anyhow::bail!("Invalid simulated source rng_seed: must be greater than 0");
+        }
+        let nyquist = config.photoacoustic.sample_rate as f32 / 2.0;
+        if let Some(ref interferer) = simulated_source.interferer {
+            if !(interferer.frequency_hz > 0.0 && interferer.frequency_hz < nyquist)
+                || !(0.0..=1.0).contains(&interferer.amplitude)
+            {
+                anyhow::bail!(
+                    "Invalid simulated interferer: {} Hz must be between 0 and {} Hz, amplitude {} between 0.0 and 1.0",
+                    interferer.frequency_hz,
+                    nyquist,
+                    interferer.amplitude
+                );
+            }
+        }
+        if let Some(ref chirp) = simulated_source.chirp {
+            let valid_frequency = |frequency: f64| frequency > 0.0 && frequency < nyquist as f64;
+            if !valid_frequency(chirp.start_frequency_hz)
+                || !valid_frequency(chirp.stop_frequency_hz)
+            {
+                anyhow::bail!(
+                    "Invalid chirp: {} Hz to {} Hz must be between 0 and {} Hz",
+                    chirp.start_frequency_hz,
+                    chirp.stop_frequency_hz,
+                    nyquist
+                );
+            }
+            if !(chirp.duration_seconds.is_finite() && chirp.duration_seconds > 0.0)
+                || !(0.0..=1.0).contains(&chirp.amplitude)
+            {
+                anyhow::bail!(
+                    "Invalid chirp: duration {} s must be greater than 0, amplitude {} between 0.0 and 1.0",
+                    chirp.duration_seconds,
+                    chirp.amplitude
+                );
+            }
+        }
+        let synthetic_signals = [
+            simulated_source.chirp.is_some(),
+            simulated_source.tones.is_some(),
+            simulated_source.test_signal.is_some(),
+        ];
+        if synthetic_signals.iter().filter(|set| **set).count() > 1 {
+            anyhow::bail!(
+                "Invalid simulated_source: only one of chirp, tones and test_signal can be set"
+            );
+        }
+        if let Some(ref test_signal) = simulated_source.test_signal {
+            let valid_time = match test_signal {
+                TestSignalConfig::Impulse { .. } => true,
+                TestSignalConfig::Step { time_seconds, .. } => {
+                    time_seconds.is_finite() && *time_seconds >= 0.0
+                }
+            };
+            if !valid_time || !(0.0..=1.0).contains(&test_signal.amplitude()) {
+                anyhow::bail!(
+                    "Invalid test_signal: step time must be 0 s or later, amplitude {} between 0.0 and 1.0",
+                    test_signal.amplitude()
+                );
+            }
+        }
+        if let Some(ref tones) = simulated_source.tones {
+            for (channel, components) in [("A", &tones.channel_a), ("B", &tones.channel_b)] {
+                for tone in components {
+                    if !(tone.frequency_hz > 0.0 && tone.frequency_hz < nyquist as f64)
+                        || !(0.0..=1.0).contains(&tone.amplitude)
+                        || !tone.phase_degrees.is_finite()
+                    {
+                        anyhow::bail!(
+                            "Invalid tone on channel {}: {} Hz must be between 0 and {} Hz, amplitude {} between 0.0 and 1.0, phase {} degrees finite",
+                            channel,
+                            tone.frequency_hz,
+                            nyquist,
+                            tone.amplitude,
+                            tone.phase_degrees
+                        );
+                    }
+                }
+                let total_amplitude: f32 = components.iter().map(|tone| tone.amplitude).sum();
+                if total_amplitude > 1.0 {
+                    anyhow::bail!(
+                        "Invalid tones on channel {}: amplitudes sum to {}, which would clip above 1.0",
+                        channel,
+                        total_amplitude
+                    );
+                }
+            }
+        }
+        if let Some(ref mains_hum) = simulated_source.mains_hum {
+            if (mains_hum.frequency_hz != 50.0 && mains_hum.frequency_hz != 60.0)
+                || !(0.0..=1.0).contains(&mains_hum.amplitude)
+            {
+                anyhow::bail!(
+                    "Invalid simulated mains hum: frequency {} Hz must be 50 or 60, amplitude {} between 0.0 and 1.0",
+                    mains_hum.frequency_hz,
+                    mains_hum.amplitude
+                );
+            }
+        }
+    }
+
+    if config.acquisition.stream_buffer_frames == Some(0) {
+        anyhow::bail!("Invalid acquisition stream buffer: must hold at least 1 frame");
+    }
+
+    if let Some(ref mapping) = config.photoacoustic.channel_mapping {
+        if mapping.channel_a == 0 || mapping.channel_b == 0 {
+            anyhow::bail!(
+                "Invalid channel mapping {} -> A, {} -> B: device channels are numbered from 1",
+                mapping.channel_a,
+                mapping.channel_b
+            );
+        }
+    }
+
+    if let Some(ref network_source) = config.photoacoustic.network_source {
+        if network_source
+            .bind_address
+            .parse::<std::net::SocketAddr>()
+            .is_err()
+        {
+            anyhow::bail!(
+                "Invalid network source bind address '{}': expected IP:port",
+                network_source.bind_address
+            );
+        }
+    }
+
+    if config.daemon.shutdown_timeout_ms == 0 {
+        anyhow::bail!("Invalid shutdown timeout: must be greater than 0 ms");
+    }
+
+    if config.daemon.task_restart_backoff_ms == 0 {
+        anyhow::bail!("Invalid task restart backoff: must be greater than 0 ms");
+    }
+    if config.daemon.task_restart_backoff_max_ms < config.daemon.task_restart_backoff_ms {
+        anyhow::bail!(
+            "Invalid task restart backoff: maximum {} ms is lower than the initial {} ms",
+            config.daemon.task_restart_backoff_max_ms,
+            config.daemon.task_restart_backoff_ms
+        );
+    }
+
+    if config.daemon.realtime_threads == Some(0) {
+        anyhow::bail!("Invalid real-time thread count: must be greater than 0");
+    }
+    if config
+        .daemon
+        .realtime_cpu_affinity
+        .as_ref()
+        .is_some_and(Vec::is_empty)
+    {
+        anyhow::bail!("Invalid real-time CPU affinity: the CPU list is empty");
+    }
+
+    if let Some(ref log_file) = config.daemon.log_file {
+        if log_file.trim().is_empty() {
+            anyhow::bail!("Invalid log file: path must not be empty");
+        }
+        if config.daemon.log_file_max_size == 0 {
+            anyhow::bail!("Invalid log file max size: must be greater than 0 bytes");
+        }
+        if config.daemon.log_file_max_files == 0 {
+            anyhow::bail!("Invalid log file max files: must be greater than 0");
+        }
+    }
+
     // Check if the address is in a valid format
     if !is_valid_ip_address(&config.visualization.address) {
         debug!(
@@ -138,6 +537,61 @@ pub fn validate_specific_rules(config: &Config) -> Result<()> {
     let _ = base64::engine::general_purpose::STANDARD
         .decode(&config.visualization.rs256_public_key)
         .context("RS256 public key is not valid base64")?;
+    for previous_key in &config.visualization.rs256_previous_public_keys {
+        let _ = base64::engine::general_purpose::STANDARD
+            .decode(previous_key)
+            .context("Previous RS256 public key is not valid base64")?;
+    }
+    if let Some(private_key) = &config.visualization.ed25519_private_key {
+        let _ = base64::engine::general_purpose::STANDARD
+            .decode(private_key)
+            .context("Ed25519 private key is not valid base64")?;
+        if config.visualization.ed25519_public_key.is_none() {
+            anyhow::bail!("Ed25519 private key requires the matching ed25519_public_key");
+        }
+    }
+    if let Some(public_key) = &config.visualization.ed25519_public_key {
+        let _ = base64::engine::general_purpose::STANDARD
+            .decode(public_key)
+            .context("Ed25519 public key is not valid base64")?;
+    }
+    if let Some(remote_jwks) = &config.visualization.remote_jwks {
+        if !remote_jwks.url.starts_with("http://") && !remote_jwks.url.starts_with("https://") {
+            anyhow::bail!(
+                "Remote JWKS URL must use http or https: {}",
+                remote_jwks.url
+            );
+        }
+        if remote_jwks.refresh_interval_secs == 0 {
+            anyhow::bail!("Remote JWKS refresh_interval_secs must be greater than 0");
+        }
+    }
+
+    if let Some(mutual_tls) = &config.visualization.mutual_tls {
+        if config.visualization.cert.is_none() || config.visualization.key.is_none() {
+            anyhow::bail!("Mutual TLS requires the visualization cert and key");
+        }
+        base64::engine::general_purpose::STANDARD
+            .decode(&mutual_tls.ca_certs)
+            .context("Mutual TLS CA certificates are not valid base64")?;
+    }
+
+    let rounds = config.access.password_hash.rounds;
+    if !(1000..=999_999_999).contains(&rounds) {
+        anyhow::bail!(
+            "Password hash rounds must be between 1000 and 999999999, got {}",
+            rounds
+        );
+    }
+
+    let lockout = &config.access.login_lockout;
+    if lockout.enabled
+        && (lockout.max_failures == 0 || lockout.window_secs == 0 || lockout.lockout_secs == 0)
+    {
+        anyhow::bail!(
+            "Login lockout max_failures, window_secs and lockout_secs must be greater than 0"
+        );
+    }
 
     // if AccessConfig contains users, validate their credentials
     // User password should be a valid base64 string
@@ -170,6 +624,69 @@ pub fn validate_specific_rules(config: &Config) -> Result<()> {
         }
     }
 
+    // Validate roles: permissions must be valid and every assigned role must exist
+    for role in &config.access.roles {
+        for permission in &role.permissions {
+            if permission.contains(USER_SESSION_SEPARATOR) {
+                anyhow::bail!(
+                    "Role {} permission contains invalid character: {}",
+                    role.name,
+                    USER_SESSION_SEPARATOR
+                );
+            }
+        }
+    }
+    for user in &config.access.users {
+        for role_name in &user.roles {
+            if !config
+                .access
+                .roles
+                .iter()
+                .any(|role| &role.name == role_name)
+            {
+                anyhow::bail!("User {} references unknown role: {}", user.user, role_name);
+            }
+        }
+    }
+
+    // Validate API keys: names must be unique and hashes hex-encoded SHA-256
+    for (index, api_key) in config.access.api_keys.iter().enumerate() {
+        let hash = api_key.key_hash.trim();
+        if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            anyhow::bail!(
+                "API key {} hash is not a hex-encoded SHA-256 hash, you should use printf '%s' <key> | sha256sum",
+                api_key.name
+            );
+        }
+        if config.access.api_keys[..index]
+            .iter()
+            .any(|other| other.name == api_key.name)
+        {
+            anyhow::bail!("Duplicate API key name: {}", api_key.name);
+        }
+        for permission in &api_key.permissions {
+            if permission.contains(USER_SESSION_SEPARATOR) {
+                anyhow::bail!(
+                    "API key {} permission contains invalid character: {}",
+                    api_key.name,
+                    USER_SESSION_SEPARATOR
+                );
+            }
+        }
+    }
+
+    for certificate in &config.access.client_certificates {
+        for permission in &certificate.permissions {
+            if permission.contains(USER_SESSION_SEPARATOR) {
+                anyhow::bail!(
+                    "Client certificate {} permission contains invalid character: {}",
+                    certificate.common_name,
+                    USER_SESSION_SEPARATOR
+                );
+            }
+        }
+    }
+
     // Validate temperature conversion formulas
     debug!("Validating temperature conversion formulas");
 
@@ -180,31 +697,99 @@ pub fn validate_specific_rules(config: &Config) -> Result<()> {
             regulator.name, formula
         );
 
-        // Test the formula with a few test voltages to ensure it works correctly
-        let test_voltages = [1.0, 2.5, 4.0]; // Test voltages in volts
-
-        for &test_voltage in &test_voltages {
-            match convert_voltage_to_temperature(formula.clone(), test_voltage) {
-                Ok(temperature_k) => {
-                    debug!(
-                        "Formula validation for '{}': {}V -> {:.2}K ({:.2}°C)",
-                        regulator.name,
-                        test_voltage,
-                        temperature_k,
-                        temperature_k - 273.15
-                    );
-                }
-                Err(e) => {
-                    anyhow::bail!(
-                        "Temperature conversion formula validation failed for regulator '{}' at {}V: {}. Formula: '{}'",
-                        regulator.name,
-                        test_voltage,
-                        e,
-                        formula
-                    );
-                }
+        if let Err(e) = validate_temperature_conversion(&regulator.temperature_conversion) {
+            anyhow::bail!(
+                "Temperature conversion formula validation failed for regulator '{}': {}. Formula: '{}'",
+                regulator.name,
+                e,
+                formula
+            );
+        }
+
+        if let Some(current_sense) = &regulator.current_sense {
+            if current_sense.shunt_resistance_ohm <= 0.0 || current_sense.amplifier_gain <= 0.0 {
+                anyhow::bail!(
+                    "Current sense of regulator '{}' needs a positive shunt resistance and amplifier gain",
+                    regulator.name
+                );
+            }
+            if current_sense.overcurrent_threshold_a <= 0.0 {
+                anyhow::bail!(
+                    "Overcurrent threshold of regulator '{}' must be positive",
+                    regulator.name
+                );
+            }
+        }
+
+        let deadband_k = regulator.control_parameters.settings.deadband_k;
+        if !deadband_k.is_finite() || deadband_k < 0.0 {
+            anyhow::bail!(
+                "Dead-band of regulator '{}' must be zero or positive, got {} K",
+                regulator.name,
+                deadband_k
+            );
+        }
+
+        if let Some(input_name) = &regulator.pid_parameters.feedforward.disturbance_input {
+            if !regulator
+                .digital_inputs
+                .iter()
+                .any(|input| &input.name == input_name)
+            {
+                anyhow::bail!(
+                    "Feedforward disturbance input '{}' of regulator '{}' is not a configured digital input",
+                    input_name,
+                    regulator.name
+                );
+            }
+        }
+
+        if let Some(data_log) = &regulator.data_log {
+            if data_log.path.trim().is_empty() {
+                anyhow::bail!("Data log path of regulator '{}' is empty", regulator.name);
+            }
+            if data_log.max_size == 0 || data_log.max_files == 0 {
+                anyhow::bail!(
+                    "Data log of regulator '{}' needs a positive max_size and max_files",
+                    regulator.name
+                );
+            }
+        }
+    }
+
+    let mut grouped_zones = std::collections::HashSet::new();
+    for group in &config.thermal_regulation.zone_groups {
+        if group.zones.len() < 2 {
+            anyhow::bail!("Zone group '{}' needs at least two zones", group.id);
+        }
+        for zone in &group.zones {
+            if !config
+                .thermal_regulation
+                .regulators
+                .iter()
+                .any(|regulator| &regulator.id == zone)
+            {
+                anyhow::bail!(
+                    "Zone '{}' of zone group '{}' is not a configured regulator",
+                    zone,
+                    group.id
+                );
+            }
+            if !grouped_zones.insert(zone) {
+                anyhow::bail!("Zone '{}' belongs to several zone groups", zone);
             }
         }
+        if group.mode == ZoneCoordinationMode::MasterSlave
+            && !group
+                .master
+                .as_ref()
+                .is_some_and(|master| group.zones.contains(master))
+        {
+            anyhow::bail!(
+                "Zone group '{}' in master_slave mode needs a master among its zones",
+                group.id
+            );
+        }
     }
 
     // If processing is enabled and default_graph exists, validate the graph
@@ -262,6 +847,10 @@ mod tests {
             adc_resolution: 16,
             voltage_reference: 5.0,
             conversion_type: ConversionType::NtcThermistor,
+            pullup_resistance: 10000.0,
+            steinhart_hart: None,
+            lookup_table: vec![],
+            calibration: None,
         };
 
         let temp_sensor = TemperatureSensorConfig {
@@ -323,6 +912,7 @@ mod tests {
                 output_max: 100.0,
                 integral_max: 1000.0,
                 settings: PidSettings::default(),
+                feedforward: Default::default(),
             },
             control_parameters: ControlParameters {
                 sampling_frequency_hz: 1.0,
@@ -336,6 +926,9 @@ mod tests {
                 max_cooling_duty: 80.0,
                 emergency_settings: EmergencySettings::default(),
             },
+            digital_inputs: vec![],
+            current_sense: None,
+            data_log: None,
         };
 
         let mut i2c_buses = HashMap::new();
@@ -358,6 +951,7 @@ mod tests {
             i2c_buses,
             regulators: vec![regulator],
             global_settings: GlobalThermalSettings::default(),
+            zone_groups: vec![],
         };
 
         config
@@ -416,6 +1010,44 @@ mod tests {
         assert!(validate_specific_rules(&config).is_ok());
     }
 
+    #[test]
+    fn test_validate_zone_groups() {
+        let formula = "273.15 + voltage * 10.0";
+        let mut config = create_test_config_with_formula(formula);
+        let mut second = config.thermal_regulation.regulators[0].clone();
+        second.id = "second_regulator".to_string();
+        config.thermal_regulation.regulators.push(second);
+
+        let group = ThermalZoneGroupConfig {
+            id: "cells".to_string(),
+            mode: ZoneCoordinationMode::MasterSlave,
+            zones: vec!["test_regulator".to_string(), "second_regulator".to_string()],
+            master: Some("test_regulator".to_string()),
+        };
+        config.thermal_regulation.zone_groups = vec![group.clone()];
+        assert!(validate_specific_rules(&config).is_ok());
+
+        // The master must be one of the zones
+        config.thermal_regulation.zone_groups[0].master = Some("other".to_string());
+        assert!(validate_specific_rules(&config).is_err());
+        config.thermal_regulation.zone_groups[0].master = None;
+        config.thermal_regulation.zone_groups[0].mode = ZoneCoordinationMode::AveragedSetpoint;
+        assert!(validate_specific_rules(&config).is_ok());
+
+        // Unknown zone
+        let mut unknown = group.clone();
+        unknown.zones.push("missing".to_string());
+        config.thermal_regulation.zone_groups = vec![unknown];
+        assert!(validate_specific_rules(&config).is_err());
+
+        // A zone in two groups
+        let mut other = group.clone();
+        other.id = "other_cells".to_string();
+        config.thermal_regulation.zone_groups = vec![group, other];
+        let error = validate_specific_rules(&config).unwrap_err().to_string();
+        assert!(error.contains("several zone groups"), "{}", error);
+    }
+
     #[test]
     fn test_validate_processing_graph_with_streaming_nodes() {
         use crate::config::processing::{NodeConfig, ProcessingConfig, ProcessingGraphConfig};
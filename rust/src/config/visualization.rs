@@ -43,6 +43,126 @@ pub struct VisualizationOutputItem {
     pub concentration_max: f64,
 }
 
+/// Rate limiting applied to the protected API endpoints
+///
+/// Each client gets a token bucket holding `requests` tokens which refills
+/// completely over `window_secs` seconds. Clients are identified both by the
+/// subject of their bearer token and by their IP address; a request is only
+/// accepted when both buckets still hold a token.
+///
+/// Routes can override `requests` with the `rate_limit = N` argument of the
+/// protection macros. Such per-route limits apply even when `enabled` is `false`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct RateLimitConfig {
+    /// Apply the global limit to every protected route. Default is `false`.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Number of requests allowed per window (bucket capacity). Default is 120.
+    #[serde(default = "default_rate_limit_requests")]
+    pub requests: u32,
+
+    /// Time in seconds needed to refill an empty bucket. Default is 60.
+    #[serde(default = "default_rate_limit_window_secs")]
+    pub window_secs: u64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            requests: default_rate_limit_requests(),
+            window_secs: default_rate_limit_window_secs(),
+        }
+    }
+}
+
+/// Default number of requests allowed per rate limiting window
+fn default_rate_limit_requests() -> u32 {
+    120
+}
+
+/// Default rate limiting window in seconds
+fn default_rate_limit_window_secs() -> u64 {
+    60
+}
+
+/// Cross-Origin Resource Sharing policy of the visualization server
+///
+/// With the default configuration only same-origin requests are possible: no
+/// CORS header is sent and preflight requests from other origins are refused.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct CorsConfig {
+    /// Origins allowed to make cross-origin requests, as `scheme://host[:port]`
+    /// (e.g. `https://dashboard.example.com`). Default is empty.
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+
+    /// Allow requests from any origin. Default is `false`.
+    #[serde(default)]
+    pub allow_any_origin: bool,
+
+    /// Allow cross-origin requests to include credentials (cookies,
+    /// `Authorization` headers). Default is `false`.
+    #[serde(default)]
+    pub allow_credentials: bool,
+}
+
+impl CorsConfig {
+    /// Check whether `origin` may make cross-origin requests
+    pub fn is_origin_allowed(&self, origin: &str) -> bool {
+        self.allow_any_origin
+            || self
+                .allowed_origins
+                .iter()
+                .any(|allowed| allowed.trim_end_matches('/').eq_ignore_ascii_case(origin))
+    }
+}
+
+/// JWKS of an external identity provider
+///
+/// RS256 tokens whose key ID (`kid`) is not one of the local keys are verified
+/// with the key of this JWKS, which is fetched on demand and cached.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct RemoteJwksConfig {
+    /// URL of the JWKS document (e.g. `https://idp.example.com/.well-known/jwks.json`)
+    pub url: String,
+
+    /// Maximum age in seconds of the cached keys before they are fetched
+    /// again. Default is 300.
+    #[serde(default = "default_remote_jwks_refresh_interval_secs")]
+    pub refresh_interval_secs: u64,
+
+    /// Expected issuer (`iss`) of the tokens signed with these keys.
+    /// Default is the issuer of the local tokens.
+    #[serde(default)]
+    pub issuer: Option<String>,
+}
+
+/// Mutual TLS authentication of clients by certificate
+///
+/// Requires TLS (`cert` and `key`). Client certificates are verified against
+/// the CA bundle during the TLS handshake; the common name of a verified
+/// certificate is mapped to permissions by
+/// [`AccessConfig::client_certificates`](crate::config::AccessConfig::client_certificates).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct MutualTlsConfig {
+    /// CA certificates in PEM format, Base64 encoded, that client certificates
+    /// must chain to
+    pub ca_certs: String,
+
+    /// Reject TLS connections without a valid client certificate.
+    /// When `false`, clients without certificate may still authenticate with
+    /// a token or an API key. Default is `true`.
+    #[serde(default = "default_enabled")]
+    pub mandatory: bool,
+}
+
+/// Default maximum age of the cached remote keys in seconds
+fn default_remote_jwks_refresh_interval_secs() -> u64 {
+    300
+}
+
 /// Configuration for the visualization web server.
 ///
 /// This structure contains all settings required for the visualization server component,
@@ -120,6 +240,41 @@ pub struct VisualizationConfig {
     #[serde(default = "default_rs256_public_key")]
     pub rs256_public_key: String,
 
+    /// Public keys of the previous RS256 signing keys, in PEM format, Base64 encoded.
+    ///
+    /// When the signing key is rotated, moving the previous `rs256_public_key`
+    /// here keeps the tokens it signed valid until they expire. The keys are
+    /// also published in the JWKS.
+    #[serde(default)]
+    pub rs256_previous_public_keys: Vec<String>,
+
+    /// Ed25519 private key in PKCS#8 PEM format, Base64 encoded.
+    ///
+    /// Used for signing JWT tokens with the EdDSA algorithm, whose tokens are
+    /// smaller and faster to verify than RS256 ones. Disabled by default.
+    /// Can be generated with the `ed25519keygen` binary.
+    #[serde(default)]
+    pub ed25519_private_key: Option<String>,
+
+    /// Ed25519 public key in PEM format, Base64 encoded.
+    ///
+    /// Used for verifying JWT tokens signed with the EdDSA algorithm.
+    /// Can be generated with the `ed25519keygen` binary.
+    #[serde(default)]
+    pub ed25519_public_key: Option<String>,
+
+    /// JWKS of an external identity provider whose tokens are accepted.
+    ///
+    /// Disabled by default, see [`RemoteJwksConfig`].
+    #[serde(default)]
+    pub remote_jwks: Option<RemoteJwksConfig>,
+
+    /// Client certificate authentication.
+    ///
+    /// Disabled by default, see [`MutualTlsConfig`].
+    #[serde(default)]
+    pub mutual_tls: Option<MutualTlsConfig>,
+
     /// Enable or disable the visualization server.
     ///
     /// This flag can be used to easily enable or disable the server
@@ -144,6 +299,34 @@ pub struct VisualizationConfig {
     #[serde(default = "default_enable_local_visualization")]
     pub enable_local_visualization: bool,
 
+    /// Rate limiting of the protected API endpoints.
+    ///
+    /// Disabled by default, see [`RateLimitConfig`].
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+
+    /// Cross-Origin Resource Sharing policy.
+    ///
+    /// Same-origin only by default, see [`CorsConfig`].
+    #[serde(default)]
+    pub cors: CorsConfig,
+
+    /// Minimum interval in milliseconds between two events of the
+    /// `/api/stream/measurements` Server-Sent Events stream.
+    ///
+    /// Updates of the computing state arriving faster are coalesced into the
+    /// next event. Default is 250 ms.
+    #[serde(default = "default_measurement_stream_interval_ms")]
+    pub measurement_stream_interval_ms: u64,
+
+    /// Minimum interval in milliseconds between two updates of a regulator
+    /// on the `/api/stream/thermal` Server-Sent Events stream.
+    ///
+    /// Regulation cycles arriving faster are coalesced into the next event.
+    /// Default is 100 ms.
+    #[serde(default = "default_thermal_stream_interval_ms")]
+    pub thermal_stream_interval_ms: u64,
+
     /// List of output items to be displayed in the visualization interface.
     ///
     /// Each item represents a specific measurement with customizable display properties.
@@ -255,6 +438,16 @@ fn default_enable_local_visualization() -> bool {
     false
 }
 
+/// Default minimum interval between measurement stream events (250 ms).
+fn default_measurement_stream_interval_ms() -> u64 {
+    250
+}
+
+/// Default minimum interval between thermal stream updates (100 ms).
+fn default_thermal_stream_interval_ms() -> u64 {
+    100
+}
+
 /// Generate a random session secret key for cookie-based authentication.
 fn default_session_secret() -> String {
     use rand::Rng;
@@ -284,10 +477,19 @@ impl Default for VisualizationConfig {
             hmac_secret: default_hmac_secret(),
             rs256_private_key: default_rs256_private_key(),
             rs256_public_key: default_rs256_public_key(),
+            rs256_previous_public_keys: Vec::new(),
+            ed25519_private_key: None,
+            ed25519_public_key: None,
+            remote_jwks: None,
+            mutual_tls: None,
             enabled: default_enabled(),
             session_secret: default_session_secret(),
             enable_compression: default_enabled(),
             enable_local_visualization: default_enable_local_visualization(),
+            rate_limit: RateLimitConfig::default(),
+            cors: CorsConfig::default(),
+            measurement_stream_interval_ms: default_measurement_stream_interval_ms(),
+            thermal_stream_interval_ms: default_thermal_stream_interval_ms(),
             output: default_output_items(),
         }
     }
@@ -20,20 +20,26 @@ use tokio::time;
 use crate::acquisition::record_consumer::RecordConsumer;
 use crate::acquisition::{
     get_default_realtime_audio_source, get_realtime_audio_source_from_device,
-    get_realtime_audio_source_from_file, get_realtime_simulated_photoacoustic_source,
-    RealTimeAcquisitionDaemon, SharedAudioStream,
+    get_realtime_audio_source_from_file, get_realtime_network_audio_source,
+    get_realtime_simulated_photoacoustic_source, RealTimeAcquisitionDaemon, RealTimeAudioSource,
+    SharedAudioStream, TeeSink, TeeSource,
 };
+use crate::config::reload::swap_config;
+use crate::config::{Config, ConfigFilePath, ModbusConfig, ModbusTransport, PhotoacousticConfig};
+use crate::daemon::realtime_runtime::RealtimeRuntime;
+use crate::daemon::supervisor::{sleep_while_running, RestartPolicy, TaskSupervisor};
+use crate::modbus::rtu::{open_serial_port, serve_rtu};
+use crate::modbus::{MeasurementEncoding, PhotoacousticModbusServer, RtuSlaveServer};
 use crate::processing::computing_nodes::SharedComputingState;
 use crate::processing::nodes::StreamingNodeRegistry;
-use crate::processing::{ProcessingConsumer, ProcessingGraph};
+use crate::processing::{ProcessingConsumer, ProcessingConsumerHandle, ProcessingGraph};
 use crate::thermal_regulation::{
     create_shared_thermal_state, SharedThermalState, ThermalRegulationSystemDaemon,
 };
 use crate::utility::PhotoacousticDataSource;
 use crate::visualization::auth::OxideState;
-use crate::visualization::server::{build_rocket, build_rocket_for_daemon};
+use crate::visualization::server::{build_rocket, build_rocket_for_daemon, configure_mutual_tls};
 use crate::visualization::shared_state::SharedVisualizationState;
-use crate::{config::Config, modbus::PhotoacousticModbusServer};
 use base64::prelude::*;
 use rocket::{
     config::LogLevel,
@@ -76,10 +82,22 @@ pub struct Daemon {
     /// Real-time acquisition daemon for audio processing
     #[allow(dead_code)]
     realtime_acquisition_daemon: Option<RealTimeAcquisitionDaemon>,
+    /// Stops the real-time acquisition task ahead of the other tasks
+    acquisition_running: Arc<AtomicBool>,
+    /// Real-time acquisition task, stopped first by [`Daemon::graceful_shutdown`]
+    acquisition_task: Option<JoinHandle<Result<()>>>,
     /// record consumer daemon for testing and validation
     record_consumer_daemon: Option<RecordConsumer>,
     /// processing consumer daemon for audio processing pipeline
     processing_consumer_daemon: Option<ProcessingConsumer>,
+    /// Handle draining the processing consumer running in its own task
+    processing_consumer_handle: Option<ProcessingConsumerHandle>,
+    /// Handle stopping the Rocket web server
+    web_shutdown: Option<rocket::Shutdown>,
+    /// Restarts the acquisition and Modbus tasks when they fail
+    supervisor: TaskSupervisor,
+    /// Dedicated runtime of the acquisition and processing tasks, when configured
+    realtime_runtime: Option<RealtimeRuntime>,
     /// Shared visualization state for statistics and runtime data
     visualization_state: Arc<SharedVisualizationState>,
     /// Streaming node registry for managing real-time audio streams
@@ -136,8 +154,14 @@ impl Daemon {
             modbus_server: None,
             audio_stream: None,
             realtime_acquisition_daemon: None,
+            acquisition_running: Arc::new(AtomicBool::new(true)),
+            acquisition_task: None,
             record_consumer_daemon: None,
             processing_consumer_daemon: None,
+            processing_consumer_handle: None,
+            web_shutdown: None,
+            supervisor: TaskSupervisor::default(),
+            realtime_runtime: None,
             visualization_state: Arc::new(SharedVisualizationState::new()),
             streaming_registry: Arc::new(StreamingNodeRegistry::new()),
             config: Arc::new(RwLock::new(crate::config::Config::default())),
@@ -218,6 +242,9 @@ impl Daemon {
     pub async fn launch(&mut self, config: Arc<RwLock<Config>>) -> Result<()> {
         // Store the config as a shared Arc<RwLock<Config>> for dynamic configuration support
         self.config = config;
+        self.supervisor =
+            TaskSupervisor::new(RestartPolicy::from(&self.config.read().await.daemon));
+        self.realtime_runtime = RealtimeRuntime::from_config(&self.config.read().await.daemon)?;
 
         // Démarrer l'acquisition audio AVANT le serveur web
         self.start_audio_acquisition().await?;
@@ -263,6 +290,17 @@ impl Daemon {
         Ok(())
     }
 
+    /// Handle of the runtime running the acquisition and processing tasks
+    ///
+    /// The dedicated real-time runtime when `daemon.realtime_threads` or
+    /// `daemon.realtime_cpu_affinity` is set, the current runtime otherwise.
+    fn realtime_handle(&self) -> tokio::runtime::Handle {
+        match &self.realtime_runtime {
+            Some(runtime) => runtime.handle().clone(),
+            None => tokio::runtime::Handle::current(),
+        }
+    }
+
     /// Start the Rocket web server for visualization
     ///
     /// Initializes and launches a Rocket web server for the visualization interface.
@@ -299,6 +337,7 @@ impl Daemon {
             visualization_key,
             hmac_secret,
             enable_compression,
+            mutual_tls,
         ) = {
             let config_read = config.read().await;
             (
@@ -312,6 +351,7 @@ impl Daemon {
                 config_read.visualization.key.clone(),
                 config_read.visualization.hmac_secret.clone(),
                 config_read.visualization.enable_compression,
+                config_read.visualization.mutual_tls.clone(),
             )
         };
 
@@ -348,6 +388,7 @@ impl Daemon {
             figment = figment
                 .merge(("tls.certs", cert_data))
                 .merge(("tls.key", key_data));
+            figment = configure_mutual_tls(figment, mutual_tls.as_ref())?;
 
             // Add the hmac secret to the figment
             figment = figment.merge(("hmac_secret", hmac_secret));
@@ -355,7 +396,7 @@ impl Daemon {
             info!("TLS enabled for web server");
         }
 
-        let (rocket, oxide_state) = build_rocket_for_daemon(
+        let (mut rocket, oxide_state) = build_rocket_for_daemon(
             figment,
             Arc::clone(&config),
             self.audio_stream.clone(),
@@ -366,14 +407,22 @@ impl Daemon {
         )
         .await;
 
+        // Let the readiness probe report the supervised tasks
+        rocket = rocket.manage(self.supervisor.clone());
+
+        // Let the reload endpoint know which file to re-read
+        if let Some(ref config_path) = self.config_path {
+            rocket = rocket.manage(ConfigFilePath(config_path.clone()));
+        }
+
         // Store the shared OxideState clone for hot-reloading access configuration.
         // Since OxideState::clone() shares the inner Arcs (registrar, issuer, access_config),
         // calling update_access_config() on this clone will update the live Rocket instance.
         self.oxide_state = Some(oxide_state);
 
-        let _running = self.running.clone();
+        let ignited = rocket.ignite().await?;
+        self.web_shutdown = Some(ignited.shutdown());
         let task = tokio::spawn(async move {
-            let ignited = rocket.ignite().await?;
             ignited.launch().await?;
             Ok(())
         });
@@ -414,7 +463,7 @@ impl Daemon {
                 // Perform data acquisition
                 // This would integrate with our acquisition module
                 debug!("Acquiring data... currently nothing");
-                time::sleep(Duration::from_millis(1000 * 60)).await;
+                sleep_while_running(&running, Duration::from_millis(1000 * 60)).await;
             }
             Ok(())
         });
@@ -495,7 +544,7 @@ impl Daemon {
         let task = tokio::spawn(async move {
             while running.load(Ordering::SeqCst) {
                 debug!("Daemon heartbeat: running");
-                time::sleep(Duration::from_secs(60)).await;
+                sleep_while_running(&running, Duration::from_secs(60)).await;
             }
             Ok(())
         });
@@ -513,9 +562,12 @@ impl Daemon {
     ///   (users, passwords, OAuth2 clients) without restart.
     /// - **`processing` nodes** — picked up automatically by the
     ///   `ProcessingConsumer` config monitor already running.
-    /// - **Other sections** (`visualization`, `acquisition`, `modbus`) — a
+    /// - **Other sections** (`visualization`, `acquisition`, `modbus`, …) — a
     ///   warning is logged indicating a restart is required.
     ///
+    /// The same path is managed by the web server as [`ConfigFilePath`] so that
+    /// `POST /api/config/reload` can trigger the reload on demand.
+    ///
     /// This method is a no-op if [`set_config_path`] was not called before
     /// [`launch`].
     fn start_config_file_watcher(&mut self) {
@@ -555,34 +607,12 @@ impl Daemon {
 
                     match crate::config::Config::from_file(&config_path) {
                         Ok(new_config) => {
-                            // Compare sections via JSON to detect which changed.
-                            let access_changed = {
-                                let current = config.read().await;
-                                serde_json::to_value(&current.access).ok()
-                                    != serde_json::to_value(&new_config.access).ok()
-                            };
-                            let visualization_changed = {
-                                let current = config.read().await;
-                                serde_json::to_value(&current.visualization).ok()
-                                    != serde_json::to_value(&new_config.visualization).ok()
-                            };
-                            let acquisition_changed = {
-                                let current = config.read().await;
-                                serde_json::to_value(&current.acquisition).ok()
-                                    != serde_json::to_value(&new_config.acquisition).ok()
-                            };
-                            let modbus_changed = {
-                                let current = config.read().await;
-                                serde_json::to_value(&current.modbus).ok()
-                                    != serde_json::to_value(&new_config.modbus).ok()
-                            };
-
                             // Atomically replace the shared configuration.
-                            *config.write().await = new_config;
+                            let report = swap_config(&config, new_config).await;
                             info!("Configuration reloaded successfully from disk");
 
                             // Apply hot-reload for each changed section.
-                            if access_changed {
+                            if report.hot_applied.iter().any(|section| section == "access") {
                                 info!("Section 'access' changed — applying live hot-reload…");
                                 let new_access = config.read().await.access.clone();
                                 if let Some(ref oxide) = oxide_state {
@@ -600,16 +630,8 @@ impl Daemon {
                                     );
                                 }
                             }
-                            if visualization_changed {
-                                warn!(
-                                    "Section 'visualization' changed — restart required to apply"
-                                );
-                            }
-                            if acquisition_changed {
-                                warn!("Section 'acquisition' changed — restart required to apply");
-                            }
-                            if modbus_changed {
-                                warn!("Section 'modbus' changed — restart required to apply");
+                            for section in &report.pending_restart {
+                                warn!("Section '{}' changed — restart required to apply", section);
                             }
                             // Note: 'processing' changes are picked up automatically by
                             // ProcessingConsumer::start_config_monitoring().
@@ -634,10 +656,11 @@ impl Daemon {
 
     /// Launch the modbus server daemon
     ///
-    /// Initializes and launches a Modbus TCP server that allows external systems
+    /// Initializes and launches a Modbus server that allows external systems
     /// to access photoacoustic data using the Modbus protocol. The server is
-    /// configured according to the shared `Arc<Config>` stored in the daemon,
-    /// including address and port settings.
+    /// configured according to the shared `Arc<Config>` stored in the daemon:
+    /// a TCP server bound to the configured address and port, or an RTU slave
+    /// on the configured serial line when `modbus.transport` is `rtu`.
     ///
     /// This method spawns an asynchronous task that runs the Modbus server in the background.
     /// The server will continue running until the daemon's `running` flag is set to `false`.
@@ -651,104 +674,211 @@ impl Daemon {
     /// This function can fail if:
     /// * The server fails to bind to the specified address/port
     /// * The socket address is invalid
+    /// * The serial port cannot be opened (RTU transport)
     /// * The Modbus server fails to initialize for any other reason
     async fn start_modbus_server(&mut self) -> Result<()> {
         // Use the shared config from the daemon
         let config = Arc::clone(&self.config);
         let config_read = config.read().await;
 
+        if config_read.modbus.transport == ModbusTransport::Rtu {
+            let modbus_config = config_read.modbus.clone();
+            drop(config_read);
+            return self.start_modbus_rtu_server(&modbus_config);
+        }
+
         info!(
             "Starting modbus server on {}:{}",
             config_read.modbus.address, config_read.modbus.port
         );
 
         let socket_addr_str = format!("{}:{}", config_read.modbus.address, config_read.modbus.port);
+        let encoding = MeasurementEncoding::from(&config_read.modbus);
         drop(config_read); // Release the read lock
 
         let running = self.running.clone();
         // Get a reference to the shared computing state
         let computing_state = Arc::clone(&self.computing_state);
+        // Get a reference to the shared thermal regulation state
+        let thermal_state = Arc::clone(&self.thermal_regulation_state);
+
+        let supervisor_running = self.running.clone();
+        let task = self
+            .supervisor
+            .spawn("modbus", supervisor_running, move || {
+                let socket_addr_str = socket_addr_str.clone();
+                let running = running.clone();
+                let computing_state = computing_state.clone();
+                let thermal_state = thermal_state.clone();
 
-        let task = tokio::spawn(async move {
-            let socket_addr: SocketAddr = socket_addr_str.parse().expect("Invalid socket address");
-            let listener = TcpListener::bind(socket_addr).await?;
-
-            let server = Server::new(listener);
-
-            // Use a single shared service instance for all connections
-            // This might be sufficient because on modbus specifications only one
-            // Modbus master can connect to a Modbus slave at a time
-
-            // Create a new Modbus server instance
-            let on_connected = move |stream, socket_addr| {
-                // Clone the Arc to avoid moving the original
-                let computing_state_clone = computing_state.clone();
-
-                // Log current data from computing state
-                if let Ok(state) = computing_state_clone.try_read() {
-                    if let (Some(freq), Some(amp), Some(conc)) = (
-                        state.peak_frequency,
-                        state.peak_amplitude,
-                        state.concentration_ppm,
-                    ) {
-                        debug!(
+                async move {
+                    let socket_addr: SocketAddr =
+                        socket_addr_str.parse().expect("Invalid socket address");
+                    let listener = TcpListener::bind(socket_addr).await?;
+
+                    let server = Server::new(listener);
+
+                    // Use a single shared service instance for all connections
+                    // This might be sufficient because on modbus specifications only one
+                    // Modbus master can connect to a Modbus slave at a time
+
+                    // Create a new Modbus server instance
+                    let on_connected = move |stream, socket_addr| {
+                        // Clone the Arc to avoid moving the original
+                        let computing_state_clone = computing_state.clone();
+                        let thermal_state_clone = thermal_state.clone();
+
+                        // Log current data from computing state
+                        if let Ok(state) = computing_state_clone.try_read() {
+                            if let (Some(freq), Some(amp), Some(conc)) = (
+                                state.peak_frequency,
+                                state.peak_amplitude,
+                                state.concentration_ppm,
+                            ) {
+                                debug!(
                             "Computing state contains - frequency:{} amplitude:{} concentration:{}",
                             freq, amp, conc
                         );
-                    } else {
-                        debug!("Computing state contains no measurement data yet");
+                            } else {
+                                debug!("Computing state contains no measurement data yet");
+                            }
+                        } else {
+                            debug!("Could not read computing state");
+                        }
+
+                        async move {
+                            accept_tcp_connection(stream, socket_addr, move |_socket_addr| {
+                                // Use the cloned Arc in this inner closure
+                                Ok(Some(
+                                    PhotoacousticModbusServer::with_computing_state(
+                                        &computing_state_clone,
+                                    )
+                                    .with_thermal_state(&thermal_state_clone)
+                                    .with_measurement_encoding(encoding),
+                                ))
+                            })
+                        }
+                    };
+
+                    let on_process_error = |err| {
+                        error!("Modbus server error: {err}");
+                    };
+
+                    // Start the server in a separate task
+                    let server_handle = tokio::spawn(async move {
+                        if let Err(e) = server.serve(&on_connected, on_process_error).await {
+                            error!("Modbus server error: {}", e);
+                        }
+                    });
+
+                    // Monitor the running flag and shutdown when requested
+                    while running.load(Ordering::SeqCst) {
+                        // A server stopping on its own is restarted by the supervisor
+                        if server_handle.is_finished() {
+                            anyhow::bail!("Modbus server stopped unexpectedly");
+                        }
+
+                        // Check every second if we should continue running
+                        time::sleep(Duration::from_secs(1)).await;
                     }
-                } else {
-                    debug!("Could not read computing state");
-                }
 
-                async move {
-                    accept_tcp_connection(stream, socket_addr, move |_socket_addr| {
-                        // Use the cloned Arc in this inner closure
-                        Ok(Some(PhotoacousticModbusServer::with_computing_state(
-                            &computing_state_clone,
-                        )))
-                    })
-                }
-            };
+                    // The running flag is now false, which means we need to shut down
+                    info!("Shutting down Modbus server...");
 
-            let on_process_error = |err| {
-                error!("Modbus server error: {err}");
-            };
+                    // Explicitly abort the server task if it's still running
+                    server_handle.abort();
 
-            // Start the server in a separate task
-            let server_handle = tokio::spawn(async move {
-                if let Err(e) = server.serve(&on_connected, on_process_error).await {
-                    error!("Modbus server error: {}", e);
+                    // Wait for the server to shut down with a timeout
+                    match tokio::time::timeout(Duration::from_secs(5), server_handle).await {
+                        Ok(_) => info!("Modbus server shut down successfully"),
+                        Err(_) => {
+                            // If it times out, just log and continue - we don't want to block shutdown
+                            warn!("Modbus server shutdown timed out, forcing termination");
+                        }
+                    }
+
+                    Ok(())
                 }
             });
 
-            // Monitor the running flag and shutdown when requested
-            while running.load(Ordering::SeqCst) {
-                // Check every second if we should continue running
-                time::sleep(Duration::from_secs(1)).await;
-            }
+        self.tasks.push(task);
+        info!("Modbus server started");
+        Ok(())
+    }
 
-            // The running flag is now false, which means we need to shut down
-            info!("Shutting down Modbus server...");
+    /// Launch the modbus server as an RTU slave on a serial line
+    ///
+    /// Serves the same registers as the TCP server, answering only the
+    /// configured `slave_id`. The serial port is opened before the task is
+    /// spawned so that a missing device is reported at startup.
+    ///
+    /// ### Parameters
+    ///
+    /// * `modbus_config` - Modbus configuration with the serial line settings
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if the serial port cannot be opened
+    fn start_modbus_rtu_server(&mut self, modbus_config: &ModbusConfig) -> Result<()> {
+        info!(
+            "Starting modbus RTU server on {} ({} bauds, parity {:?}, slave {})",
+            modbus_config.serial_port,
+            modbus_config.baud_rate,
+            modbus_config.parity,
+            modbus_config.slave_id
+        );
 
-            // Explicitly abort the server task if it's still running
-            server_handle.abort();
+        let mut first_serial = Some(open_serial_port(modbus_config)?);
+        let modbus_config = modbus_config.clone();
+        let computing_state = Arc::clone(&self.computing_state);
+        let thermal_state = Arc::clone(&self.thermal_regulation_state);
 
-            // Wait for the server to shut down with a timeout
-            match tokio::time::timeout(Duration::from_secs(5), server_handle).await {
-                Ok(_) => info!("Modbus server shut down successfully"),
-                Err(_) => {
-                    // If it times out, just log and continue - we don't want to block shutdown
-                    warn!("Modbus server shutdown timed out, forcing termination");
-                }
-            }
+        let running = self.running.clone();
+        let task = self
+            .supervisor
+            .spawn("modbus", self.running.clone(), move || {
+                // The first attempt uses the port opened at startup, restarts reopen it
+                let serial = match first_serial.take() {
+                    Some(serial) => Ok(serial),
+                    None => open_serial_port(&modbus_config),
+                };
+                let server = RtuSlaveServer::new(
+                    modbus_config.slave_id,
+                    PhotoacousticModbusServer::with_computing_state(&computing_state)
+                        .with_thermal_state(&thermal_state)
+                        .with_measurement_encoding(MeasurementEncoding::from(&modbus_config)),
+                );
+                let running = running.clone();
 
-            Ok(())
-        });
+                async move {
+                    let serial = serial?;
+                    let server_handle = tokio::spawn(async move {
+                        if let Err(e) = serve_rtu(serial, server).await {
+                            error!("Modbus RTU server error: {:?}", e);
+                        }
+                    });
+
+                    // Monitor the running flag and shutdown when requested
+                    while running.load(Ordering::SeqCst) && !server_handle.is_finished() {
+                        time::sleep(Duration::from_secs(1)).await;
+                    }
+                    // A server stopping on its own is restarted by the supervisor
+                    let stopped_unexpectedly = server_handle.is_finished();
+
+                    info!("Shutting down Modbus RTU server...");
+                    server_handle.abort();
+                    let _ = server_handle.await;
+                    info!("Modbus RTU server shut down");
+
+                    if stopped_unexpectedly && running.load(Ordering::SeqCst) {
+                        anyhow::bail!("Modbus RTU server stopped unexpectedly");
+                    }
+                    Ok(())
+                }
+            });
 
         self.tasks.push(task);
-        info!("Modbus server started");
+        info!("Modbus RTU server started");
         Ok(())
     }
 
@@ -806,85 +936,84 @@ impl Daemon {
         // === PHASE 1: Real-Time Audio Source Selection ===
         // Clone the necessary data from config before dropping the read lock
         let photoacoustic_config = config_read.photoacoustic.clone();
-        let buffer_size: usize = config_read.photoacoustic.frame_size.into();
+        let buffer_size: usize = config_read
+            .acquisition
+            .stream_buffer_frames
+            .unwrap_or(config_read.photoacoustic.frame_size.into());
         drop(config_read);
 
-        // Select and initialize the appropriate real-time audio source based on configuration
-        let audio_source = if let Some(ref simulated_config) = photoacoustic_config.simulated_source
-        {
-            // Simulated photoacoustic source for testing and advanced simulation
-            info!(
-                "Using simulated photoacoustic source with type: {}",
-                simulated_config.source_type
-            );
-            get_realtime_simulated_photoacoustic_source(photoacoustic_config.clone())?
-        } else if let Some(ref file_path) = photoacoustic_config.input_file {
-            // File-based real-time audio source for testing and playback scenarios
-            info!("Using real-time file audio source: {}", file_path);
-            get_realtime_audio_source_from_file(photoacoustic_config.clone())?
-        } else if let Some(ref device_name) = photoacoustic_config.input_device {
-            // Named device source for specific hardware targeting
-            info!("Using real-time device audio source: {}", device_name);
-            get_realtime_audio_source_from_device(photoacoustic_config.clone())?
-        } else {
-            // Default system audio input as fallback
-            info!("Using default real-time audio source");
-            get_default_realtime_audio_source(photoacoustic_config.clone())?
-        };
+        // Select and initialize the configured source now, so that a missing
+        // device or file is reported at startup
+        let audio_source = select_realtime_audio_source(&photoacoustic_config)?;
 
-        // === PHASE 2: Real-Time Acquisition Daemon Creation ===
-        // Create the real-time acquisition daemon with the selected source
-        let mut realtime_daemon = RealTimeAcquisitionDaemon::new(audio_source, buffer_size);
+        // === PHASE 2: Stream Creation ===
+        // The stream outlives the acquisition attempts: a restarted acquisition
+        // keeps publishing to the subscribers of the first one
+        let audio_stream = Arc::new(SharedAudioStream::new(buffer_size));
 
-        // === PHASE 3: Stream Connection ===
-        // Get a reference to the daemon's internal stream for web server use
-        let audio_stream = realtime_daemon.get_shared_stream();
-
-        // === PHASE 4: State Management ===
-        // Store the acquisition daemon's stream for access by web server components
+        // === PHASE 3: State Management ===
+        // Store the stream for access by the processing consumer and web server components
         self.audio_stream = Some(audio_stream.clone());
 
-        // === PHASE 5: Background Task Spawning ===
-        // Start the real-time acquisition daemon in a dedicated async task
+        // === PHASE 4: Supervised Task Spawning ===
+        // Each attempt runs a real-time acquisition daemon until shutdown is
+        // signaled; failed attempts are restarted by the supervisor
         let running = self.running.clone();
-        let task = tokio::spawn(async move {
-            info!("Real-time audio acquisition task started");
+        let acquisition_running = self.acquisition_running.clone();
+        let mut first_source = Some(audio_source);
+        let task = self.supervisor.spawn_on(
+            &self.realtime_handle(),
+            "acquisition",
+            self.acquisition_running.clone(),
+            move || {
+                let audio_source = match first_source.take() {
+                    Some(audio_source) => Ok(audio_source),
+                    None => select_realtime_audio_source(&photoacoustic_config),
+                };
+                let audio_stream = audio_stream.clone();
+                let running = running.clone();
+                let acquisition_running = acquisition_running.clone();
 
-            // Start the real-time acquisition daemon
-            match realtime_daemon.start().await {
-                Ok(_) => {
+                async move {
+                    info!("Real-time audio acquisition task started");
+                    let mut realtime_daemon =
+                        RealTimeAcquisitionDaemon::with_stream(audio_source?, audio_stream);
+                    realtime_daemon.start().await.map_err(|e| {
+                        anyhow::anyhow!("Failed to start real-time acquisition daemon: {}", e)
+                    })?;
                     info!("Real-time audio acquisition daemon started successfully");
-                }
-                Err(e) => {
-                    error!("Failed to start real-time audio acquisition daemon: {}", e);
-                    return Ok(());
-                }
-            }
 
-            // Keep the daemon running until shutdown is signaled
-            while running.load(Ordering::Relaxed) {
-                // Check daemon status
-                if !realtime_daemon.is_running() {
-                    warn!("Real-time acquisition daemon stopped unexpectedly");
-                    break;
-                }
+                    // Keep the daemon running until shutdown is signaled
+                    let mut stopped_unexpectedly = false;
+                    while running.load(Ordering::Relaxed)
+                        && acquisition_running.load(Ordering::Relaxed)
+                    {
+                        // Check daemon status
+                        if !realtime_daemon.is_running() {
+                            stopped_unexpectedly = true;
+                            break;
+                        }
 
-                // Wait a bit before checking again
-                tokio::time::sleep(Duration::from_millis(1000)).await;
-            }
+                        // Wait a bit before checking again
+                        tokio::time::sleep(Duration::from_millis(1000)).await;
+                    }
 
-            // Graceful shutdown
-            info!("Stopping real-time audio acquisition daemon");
-            if let Err(e) = realtime_daemon.stop().await {
-                error!("Error stopping real-time acquisition daemon: {}", e);
-            }
+                    info!("Stopping real-time audio acquisition daemon");
+                    if let Err(e) = realtime_daemon.stop().await {
+                        error!("Error stopping real-time acquisition daemon: {}", e);
+                    }
 
-            info!("Real-time audio acquisition task stopped");
-            Ok(())
-        });
+                    if stopped_unexpectedly {
+                        anyhow::bail!("Real-time acquisition daemon stopped unexpectedly");
+                    }
+                    info!("Real-time audio acquisition task stopped");
+                    Ok(())
+                }
+            },
+        );
 
-        // Register the task for lifecycle management and graceful shutdown
-        self.tasks.push(task);
+        // Keep the task apart, the graceful shutdown stops it before the others
+        self.acquisition_task = Some(task);
         info!("Real-time audio acquisition system started successfully");
         Ok(())
     }
@@ -1019,13 +1148,18 @@ impl Daemon {
             .map_err(|e| anyhow::anyhow!("Invalid processing configuration: {}", e))?;
 
         // Create processing graph from configuration with streaming registry, photoacoustic parameters, and computing state
-        let processing_graph = ProcessingGraph::from_config_with_all_params(
+        let mut processing_graph = ProcessingGraph::from_config_with_all_params(
             &default_graph,
             Some((*self.streaming_registry).clone()),
             &photoacoustic_config,
             Some(self.computing_state.clone()),
         )
         .map_err(|e| anyhow::anyhow!("Failed to create processing graph: {}", e))?;
+        processing_graph.set_parallel_execution(processing_config.performance.parallel_execution);
+        processing_graph.set_buffer_reuse(processing_config.performance.reuse_buffers);
+        if let Some(runtime) = &self.realtime_runtime {
+            processing_graph.set_thread_pool(Some(runtime.thread_pool().clone()));
+        }
 
         // Create processing consumer daemon with shared visualization state and config
         let processing_consumer = ProcessingConsumer::new_with_visualization_state_and_config(
@@ -1035,10 +1169,13 @@ impl Daemon {
             Arc::clone(&self.config),
         );
 
+        // Keep a handle to drain the consumer once it is moved into its task
+        self.processing_consumer_handle = Some(processing_consumer.handle());
+
         // Start the processing consumer in a background task
         let mut processing_consumer_for_task = processing_consumer;
 
-        let task = tokio::spawn(async move {
+        let task = self.realtime_handle().spawn(async move {
             info!("Processing consumer task started");
 
             // Start the processing consumer daemon
@@ -1275,6 +1412,10 @@ impl Daemon {
     /// This method only signals the tasks to stop; it does not wait for them to complete.
     /// To wait for all tasks to finish, call `join()` after this method.
     ///
+    /// All tasks stop at once, so frames still queued in the processing graph
+    /// may be lost. Use [`graceful_shutdown`](Self::graceful_shutdown) to stop
+    /// the components in order and flush in-flight work.
+    ///
     /// ### Examples
     ///
     /// ```no_run
@@ -1293,6 +1434,7 @@ impl Daemon {
     pub fn shutdown(&self) {
         info!("Shutting down daemon tasks");
         self.running.store(false, Ordering::SeqCst);
+        self.acquisition_running.store(false, Ordering::SeqCst);
         // Tasks should check the running flag and terminate gracefully
     }
 
@@ -1357,7 +1499,7 @@ impl Daemon {
         }
 
         // Wait for all tasks to complete
-        for task in self.tasks {
+        for task in self.acquisition_task.into_iter().chain(self.tasks) {
             match tokio::time::timeout(Duration::from_secs(5), task).await {
                 Ok(result) => {
                     if let Err(e) = result {
@@ -1373,6 +1515,111 @@ impl Daemon {
         Ok(())
     }
 
+    /// Stop all components in order, flushing in-flight work, then wait for the tasks
+    ///
+    /// Consumes the daemon and performs an ordered shutdown:
+    ///
+    /// 1. Stop the audio acquisition, so that no new frame enters the pipeline
+    /// 2. Let the processing graph drain the frames still queued in the audio stream
+    /// 3. Flush the graph nodes: action drivers deliver their queued sends and
+    ///    have their `shutdown` called, record nodes finalize their files
+    /// 4. Stop the thermal regulation system
+    /// 5. Stop the web and Modbus servers and the remaining background tasks
+    ///
+    /// The whole sequence is bounded by `daemon.shutdown_timeout_ms`; tasks
+    /// still running when the deadline expires are aborted.
+    ///
+    /// ### Returns
+    ///
+    /// * `Result<()>` - Always `Ok`, task failures and an expired deadline are logged
+    ///
+    /// ### Examples
+    ///
+    /// ```no_run
+    /// use rust_photoacoustic::{config::Config, daemon::launch_daemon::Daemon};
+    /// use std::sync::Arc;
+    /// use tokio::sync::RwLock;
+    ///
+    /// async fn example() -> anyhow::Result<()> {
+    ///     let config = Config::from_file("config.yaml")?;
+    ///     let mut daemon = Daemon::new();
+    ///     daemon.launch(Arc::new(RwLock::new(config))).await?;
+    ///
+    ///     tokio::signal::ctrl_c().await?;
+    ///     daemon.graceful_shutdown().await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn graceful_shutdown(mut self) -> Result<()> {
+        let timeout = Duration::from_millis(self.config.read().await.daemon.shutdown_timeout_ms);
+        info!(
+            "Starting graceful shutdown (deadline {} ms)",
+            timeout.as_millis()
+        );
+
+        if time::timeout(timeout, self.shutdown_in_order())
+            .await
+            .is_err()
+        {
+            warn!(
+                "Graceful shutdown did not complete within {} ms, aborting the remaining tasks",
+                timeout.as_millis()
+            );
+        }
+
+        // Force the tasks that did not stop in time
+        self.running.store(false, Ordering::SeqCst);
+        for task in self
+            .acquisition_task
+            .take()
+            .into_iter()
+            .chain(self.tasks.drain(..))
+        {
+            if !task.is_finished() {
+                task.abort();
+            }
+        }
+
+        info!("Daemon stopped");
+        Ok(())
+    }
+
+    /// Ordered steps of [`graceful_shutdown`](Self::graceful_shutdown), without the deadline
+    async fn shutdown_in_order(&mut self) {
+        // 1. No new frames
+        self.acquisition_running.store(false, Ordering::SeqCst);
+        if let Some(task) = self.acquisition_task.as_mut() {
+            info!("Graceful shutdown: stopping audio acquisition");
+            log_task_result("audio acquisition", task.await);
+            self.acquisition_task = None;
+        }
+
+        // 2-3. Drain the queued frames, then flush drivers and recordings
+        if let Some(handle) = self.processing_consumer_handle.clone() {
+            info!("Graceful shutdown: draining the processing graph");
+            handle.drain_and_shutdown().await;
+        }
+
+        // 4. Thermal regulation
+        if let Some(ref mut thermal_daemon) = self.thermal_regulation_daemon {
+            info!("Graceful shutdown: stopping thermal regulation system");
+            if let Err(e) = thermal_daemon.stop().await {
+                error!("Failed to stop thermal regulation system: {}", e);
+            }
+        }
+
+        // 5. Servers and background tasks
+        info!("Graceful shutdown: stopping the web and Modbus servers");
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(web_shutdown) = self.web_shutdown.take() {
+            web_shutdown.notify();
+        }
+        for task in self.tasks.iter_mut() {
+            log_task_result("daemon", task.await);
+        }
+        self.tasks.clear();
+    }
+
     /// Update configuration for processing graph nodes dynamically
     ///
     /// This method enables dynamic configuration updates for processing nodes without
@@ -1610,3 +1857,60 @@ impl Daemon {
         Ok(())
     }
 }
+
+/// Log the outcome of a daemon task awaited during the shutdown
+fn log_task_result(name: &str, result: std::result::Result<Result<()>, tokio::task::JoinError>) {
+    match result {
+        Ok(Ok(())) => debug!("{} task stopped", name),
+        Ok(Err(e)) => error!("{} task failed: {}", name, e),
+        Err(e) => error!("{} task panicked: {}", name, e),
+    }
+}
+
+/// Create the real-time audio source selected by the configuration
+///
+/// Sources are tried in priority order: simulated source, network source,
+/// input file, named input device, then the default system input. When an input recording is
+/// configured, the source is wrapped in a [`TeeSource`] recording its frames.
+fn select_realtime_audio_source(
+    photoacoustic_config: &PhotoacousticConfig,
+) -> Result<Box<dyn RealTimeAudioSource>> {
+    let audio_source = if let Some(ref simulated_config) = photoacoustic_config.simulated_source {
+        // Simulated photoacoustic source for testing and advanced simulation
+        info!(
+            "Using simulated photoacoustic source with type: {}",
+            simulated_config.source_type
+        );
+        get_realtime_simulated_photoacoustic_source(photoacoustic_config.clone())?
+    } else if let Some(ref network_config) = photoacoustic_config.network_source {
+        // PCM packets streamed over the network by the acquisition hardware
+        info!(
+            "Using network audio source on {}",
+            network_config.bind_address
+        );
+        get_realtime_network_audio_source(photoacoustic_config.clone())?
+    } else if let Some(ref file_path) = photoacoustic_config.input_file {
+        // File-based real-time audio source for testing and playback scenarios
+        info!("Using real-time file audio source: {}", file_path);
+        get_realtime_audio_source_from_file(photoacoustic_config.clone())?
+    } else if let Some(ref device_name) = photoacoustic_config.input_device {
+        // Named device source for specific hardware targeting
+        info!("Using real-time device audio source: {}", device_name);
+        get_realtime_audio_source_from_device(photoacoustic_config.clone())?
+    } else {
+        // Default system audio input as fallback
+        info!("Using default real-time audio source");
+        get_default_realtime_audio_source(photoacoustic_config.clone())?
+    };
+
+    match photoacoustic_config.input_recording {
+        Some(ref recording) => {
+            info!("Recording raw input frames to {}", recording.path);
+            Ok(Box::new(TeeSource::new(
+                audio_source,
+                TeeSink::wav(recording),
+            )))
+        }
+        None => Ok(audio_source),
+    }
+}
@@ -8,6 +8,11 @@
 //!
 //! * **Launch Daemon**: Core implementation for starting, monitoring, and gracefully
 //!   shutting down background tasks
+//! * **Supervisor**: Restarts failed background tasks with exponential backoff
+//! * **Real-time runtime**: Optional dedicated runtime, pinned to chosen CPUs, for
+//!   the acquisition and processing tasks
+//! * **Self-test**: Validates the processing chain, action drivers and thermal
+//!   sensors of a configuration for the `--self-test` mode
 //!
 //! ## Usage
 //!
@@ -27,9 +32,8 @@
 //!     // Wait for shutdown signal (e.g., Ctrl+C)
 //!     tokio::signal::ctrl_c().await?;
 //!     
-//!     // Clean shutdown
-//!     daemon.shutdown();
-//!     daemon.join().await?;
+//!     // Ordered shutdown flushing in-flight work
+//!     daemon.graceful_shutdown().await?;
 //!     
 //!     Ok(())
 //! }
@@ -38,3 +42,6 @@
 // Re-export the Daemon struct for convenience
 
 pub mod launch_daemon;
+pub mod realtime_runtime;
+pub mod self_test;
+pub mod supervisor;
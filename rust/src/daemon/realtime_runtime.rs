@@ -0,0 +1,307 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Dedicated runtime for the real-time tasks
+//!
+//! By default every daemon task runs on the Tokio runtime of `main`, so a busy
+//! web server competes with the audio acquisition and the processing graph
+//! for the same worker threads. When `daemon.realtime_threads` or
+//! `daemon.realtime_cpu_affinity` is set, the daemon runs the acquisition and
+//! processing tasks on a [`RealtimeRuntime`] instead: a separate
+//! multi-threaded runtime with its own worker count, whose threads can be
+//! pinned to dedicated CPUs. The runtime comes with a rayon thread pool of the
+//! same size and affinity, running the branches of the processing graph when
+//! `processing.performance.parallel_execution` is enabled.
+//!
+//! ### Platform support
+//!
+//! | Platform | Thread count | CPU affinity |
+//! |---|---|---|
+//! | Linux | Applied | Applied with `sched_setaffinity` to the worker, blocking and thread pool threads |
+//! | Other | Applied | Ignored, a warning is logged at startup |
+//!
+//! Threads created outside Tokio, such as the audio callback thread of the
+//! microphone source, are not pinned.
+
+use std::io;
+
+use anyhow::{bail, Result};
+use log::{info, warn};
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::sync::Arc;
+use tokio::runtime::{Handle, Runtime};
+
+use crate::config::DaemonConfig;
+
+/// Name of the threads of the real-time runtime
+pub const REALTIME_THREAD_NAME: &str = "photoacoustic-rt";
+
+/// Name of the threads of the real-time thread pool, followed by their index
+pub const REALTIME_POOL_THREAD_NAME: &str = "photoacoustic-rt-pool";
+
+/// Tokio runtime running the acquisition and processing tasks
+///
+/// The runtime is shut down in the background when dropped, so it can be
+/// dropped from an asynchronous context.
+///
+/// ### Examples
+///
+/// ```
+/// use rust_photoacoustic::daemon::realtime_runtime::RealtimeRuntime;
+///
+/// let runtime = RealtimeRuntime::new(2, None).unwrap();
+/// let answer = runtime.handle().block_on(async { 42 });
+/// assert_eq!(answer, 42);
+/// assert_eq!(runtime.worker_threads(), 2);
+/// ```
+#[derive(Debug)]
+pub struct RealtimeRuntime {
+    /// Always present until the runtime is dropped
+    runtime: Option<Runtime>,
+    /// Pool running the parallel branches of the processing graph
+    thread_pool: Arc<ThreadPool>,
+    /// Number of worker threads
+    worker_threads: usize,
+    /// CPUs the threads are pinned to, when applied on this platform
+    cpu_affinity: Option<Vec<usize>>,
+}
+
+impl RealtimeRuntime {
+    /// Build a runtime with `worker_threads` threads, optionally pinned to `cpu_affinity`
+    ///
+    /// On platforms without CPU affinity support, `cpu_affinity` is ignored
+    /// with a warning.
+    ///
+    /// ### Arguments
+    ///
+    /// * `worker_threads` - Number of worker threads, greater than zero
+    /// * `cpu_affinity` - CPUs the worker and blocking threads may run on
+    ///
+    /// ### Returns
+    ///
+    /// The runtime, or an error when the thread count or the CPU list is
+    /// invalid, or when a CPU is not available to the process
+    pub fn new(worker_threads: usize, cpu_affinity: Option<Vec<usize>>) -> Result<Self> {
+        if cpu_affinity.as_ref().is_some_and(Vec::is_empty) {
+            bail!("Invalid real-time CPU affinity: the CPU list is empty");
+        }
+        if worker_threads == 0 {
+            bail!("Invalid real-time thread count: must be greater than 0");
+        }
+
+        let cpu_affinity = match cpu_affinity {
+            Some(cpus) if !affinity_supported() => {
+                warn!(
+                    "CPU affinity is not supported on this platform, ignoring real-time CPUs {:?}",
+                    cpus
+                );
+                None
+            }
+            Some(cpus) => {
+                let allowed = current_thread_affinity()?;
+                if let Some(cpu) = cpus.iter().find(|cpu| !allowed.contains(cpu)) {
+                    bail!(
+                        "Invalid real-time CPU affinity: CPU {} is not available (allowed CPUs: {:?})",
+                        cpu,
+                        allowed
+                    );
+                }
+                Some(cpus)
+            }
+            None => None,
+        };
+
+        let pinned_cpus = cpu_affinity.clone();
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(worker_threads)
+            .thread_name(REALTIME_THREAD_NAME)
+            .enable_all()
+            .on_thread_start(move || {
+                if let Some(cpus) = &pinned_cpus {
+                    if let Err(e) = set_current_thread_affinity(cpus) {
+                        warn!("Failed to pin a real-time thread to CPUs {:?}: {}", cpus, e);
+                    }
+                }
+            })
+            .build()?;
+
+        let pinned_cpus = cpu_affinity.clone();
+        let thread_pool = ThreadPoolBuilder::new()
+            .num_threads(worker_threads)
+            .thread_name(|index| format!("{}-{}", REALTIME_POOL_THREAD_NAME, index))
+            .start_handler(move |_| {
+                if let Some(cpus) = &pinned_cpus {
+                    if let Err(e) = set_current_thread_affinity(cpus) {
+                        warn!("Failed to pin a real-time thread to CPUs {:?}: {}", cpus, e);
+                    }
+                }
+            })
+            .build()?;
+
+        info!(
+            "Real-time runtime started with {} worker threads{}",
+            worker_threads,
+            match &cpu_affinity {
+                Some(cpus) => format!(" pinned to CPUs {:?}", cpus),
+                None => String::new(),
+            }
+        );
+        Ok(Self {
+            runtime: Some(runtime),
+            thread_pool: Arc::new(thread_pool),
+            worker_threads,
+            cpu_affinity,
+        })
+    }
+
+    /// Build the runtime described by the daemon configuration
+    ///
+    /// The thread count defaults to the number of configured CPUs.
+    ///
+    /// ### Arguments
+    ///
+    /// * `config` - Daemon configuration
+    ///
+    /// ### Returns
+    ///
+    /// `None` when neither `realtime_threads` nor `realtime_cpu_affinity` is
+    /// set, so that the real-time tasks share the main runtime
+    pub fn from_config(config: &DaemonConfig) -> Result<Option<Self>> {
+        let worker_threads = match (config.realtime_threads, &config.realtime_cpu_affinity) {
+            (Some(threads), _) => threads,
+            (None, Some(cpus)) => cpus.len(),
+            (None, None) => return Ok(None),
+        };
+        Self::new(worker_threads, config.realtime_cpu_affinity.clone()).map(Some)
+    }
+
+    /// Handle spawning tasks on this runtime
+    pub fn handle(&self) -> &Handle {
+        self.runtime
+            .as_ref()
+            .expect("runtime present until dropped")
+            .handle()
+    }
+
+    /// Thread pool of the parallel graph execution, with the runtime's thread
+    /// count and CPU affinity
+    ///
+    /// See [`ProcessingGraph::set_thread_pool`](crate::processing::ProcessingGraph::set_thread_pool).
+    pub fn thread_pool(&self) -> &Arc<ThreadPool> {
+        &self.thread_pool
+    }
+
+    /// Number of worker threads
+    pub fn worker_threads(&self) -> usize {
+        self.worker_threads
+    }
+
+    /// CPUs the threads are pinned to, `None` when they are not pinned
+    pub fn cpu_affinity(&self) -> Option<&[usize]> {
+        self.cpu_affinity.as_deref()
+    }
+}
+
+impl Drop for RealtimeRuntime {
+    fn drop(&mut self) {
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// Whether thread CPU affinity is supported on this platform
+pub fn affinity_supported() -> bool {
+    cfg!(target_os = "linux")
+}
+
+/// Restrict the calling thread to `cpus`
+///
+/// ### Arguments
+///
+/// * `cpus` - Indices of the CPUs the thread may run on
+///
+/// ### Returns
+///
+/// An error of kind [`io::ErrorKind::Unsupported`] on platforms without
+/// affinity support, or the error reported by the system
+#[cfg(target_os = "linux")]
+pub fn set_current_thread_affinity(cpus: &[usize]) -> io::Result<()> {
+    if let Some(cpu) = cpus.iter().find(|&&cpu| cpu >= libc::CPU_SETSIZE as usize) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("CPU {} is out of range", cpu),
+        ));
+    }
+
+    // SAFETY: cpu_set_t is a plain bit mask, every index was checked against
+    // CPU_SETSIZE and the mask outlives the call
+    let result = unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        for &cpu in cpus {
+            libc::CPU_SET(cpu, &mut set);
+        }
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set)
+    };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Restrict the calling thread to `cpus`
+///
+/// ### Arguments
+///
+/// * `cpus` - Indices of the CPUs the thread may run on
+///
+/// ### Returns
+///
+/// An error of kind [`io::ErrorKind::Unsupported`] on platforms without
+/// affinity support, or the error reported by the system
+#[cfg(not(target_os = "linux"))]
+pub fn set_current_thread_affinity(_cpus: &[usize]) -> io::Result<()> {
+    Err(unsupported())
+}
+
+/// CPUs the calling thread may run on
+///
+/// ### Returns
+///
+/// The sorted CPU indices, or an error of kind
+/// [`io::ErrorKind::Unsupported`] on platforms without affinity support
+#[cfg(target_os = "linux")]
+pub fn current_thread_affinity() -> io::Result<Vec<usize>> {
+    // SAFETY: the mask is written by sched_getaffinity before being read and
+    // only indices below CPU_SETSIZE are tested
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        if libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok((0..libc::CPU_SETSIZE as usize)
+            .filter(|&cpu| libc::CPU_ISSET(cpu, &set))
+            .collect())
+    }
+}
+
+/// CPUs the calling thread may run on
+///
+/// ### Returns
+///
+/// The sorted CPU indices, or an error of kind
+/// [`io::ErrorKind::Unsupported`] on platforms without affinity support
+#[cfg(not(target_os = "linux"))]
+pub fn current_thread_affinity() -> io::Result<Vec<usize>> {
+    Err(unsupported())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "CPU affinity is not supported on this platform",
+    )
+}
@@ -0,0 +1,500 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Self-test of the measurement chain
+//!
+//! The `--self-test` mode validates a configuration on the target hardware
+//! without starting the daemon:
+//!
+//! | Check | Pass condition |
+//! |---|---|
+//! | Signal chain | A synthetic tone at `photoacoustic.frequency`, fed through `processing.default_graph`, is located by every `computing_peak_finder` node within [`frequency_tolerance`] |
+//! | Action drivers | The driver of every `action_universal` node reaches its endpoint within [`DRIVER_CHECK_TIMEOUT`] |
+//! | Thermal sensors | Every enabled regulator reads a temperature within [`PLAUSIBLE_TEMPERATURE_RANGE_C`] |
+//!
+//! A check is skipped when there is nothing to test: processing disabled, no
+//! action driver configured or thermal regulation disabled.
+//!
+//! The synthetic frames never reach the action endpoints: the graph under test
+//! is built without the drivers of its action nodes, and its record nodes write
+//! to a temporary directory removed after the check. Python nodes and drivers
+//! do run their scripts.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+use tokio::sync::RwLock;
+
+use crate::acquisition::AudioFrame;
+use crate::config::processing::ProcessingGraphConfig;
+use crate::config::Config;
+use crate::processing::computing_nodes::action_drivers::create_action_driver;
+use crate::processing::computing_nodes::ComputingSharedData;
+use crate::processing::nodes::StreamingNodeRegistry;
+use crate::processing::{ProcessingData, ProcessingGraph};
+use crate::thermal_regulation::create_thermal_regulation_driver;
+use crate::utility::noise_generator::NoiseGenerator;
+
+/// Number of synthetic frames fed through the processing graph
+pub const SELF_TEST_FRAMES: u64 = 20;
+
+/// Amplitude of the synthetic tone on channel A
+pub const SELF_TEST_TONE_AMPLITUDE: f32 = 0.5;
+
+/// Standard deviation of the noise added to both channels
+pub const SELF_TEST_NOISE_LEVEL: f32 = 0.01;
+
+/// Time allowed for an action driver to reach its endpoint
+pub const DRIVER_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Temperatures a working sensor can report, in degrees Celsius
+pub const PLAUSIBLE_TEMPERATURE_RANGE_C: RangeInclusive<f64> = -40.0..=150.0;
+
+/// Part of the measurement chain validated by a check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckKind {
+    /// Synthetic signal located by a peak finder
+    SignalChain,
+    /// Connection of an action driver
+    ActionDriver,
+    /// Reading of a thermal sensor
+    ThermalSensor,
+}
+
+/// Outcome of a check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    /// The check succeeded
+    Pass,
+    /// The check failed
+    Fail,
+    /// Nothing to check in this configuration
+    Skip,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Fail => "FAIL",
+            CheckStatus::Skip => "SKIP",
+        })
+    }
+}
+
+/// Result of a single check
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckResult {
+    /// Part of the chain the check validates
+    pub kind: CheckKind,
+    /// Name of the checked item, such as a node or regulator ID
+    pub name: String,
+    /// Outcome of the check
+    pub status: CheckStatus,
+    /// Measured value or reason of the outcome
+    pub detail: String,
+}
+
+impl CheckResult {
+    fn new(
+        kind: CheckKind,
+        name: impl Into<String>,
+        status: CheckStatus,
+        detail: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind,
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        }
+    }
+
+    /// Pass when `outcome` is `Ok`, fail with its error otherwise
+    fn from_outcome(kind: CheckKind, name: impl Into<String>, outcome: Result<String>) -> Self {
+        match outcome {
+            Ok(detail) => Self::new(kind, name, CheckStatus::Pass, detail),
+            Err(e) => Self::new(kind, name, CheckStatus::Fail, format!("{:#}", e)),
+        }
+    }
+}
+
+/// Results of all the checks of a self-test
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SelfTestReport {
+    /// Checks in the order they ran
+    pub checks: Vec<CheckResult>,
+}
+
+impl SelfTestReport {
+    /// Whether no check failed
+    pub fn passed(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// Checks that failed
+    pub fn failures(&self) -> impl Iterator<Item = &CheckResult> {
+        self.checks
+            .iter()
+            .filter(|check| check.status == CheckStatus::Fail)
+    }
+
+    /// Checks of the given kind
+    pub fn checks_of(&self, kind: CheckKind) -> impl Iterator<Item = &CheckResult> {
+        self.checks.iter().filter(move |check| check.kind == kind)
+    }
+}
+
+impl fmt::Display for SelfTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Self-test report")?;
+        for check in &self.checks {
+            let kind = match check.kind {
+                CheckKind::SignalChain => "signal chain",
+                CheckKind::ActionDriver => "action driver",
+                CheckKind::ThermalSensor => "thermal sensor",
+            };
+            writeln!(
+                f,
+                "  [{}] {} '{}': {}",
+                check.status, kind, check.name, check.detail
+            )?;
+        }
+        let failed = self.failures().count();
+        if failed == 0 {
+            write!(f, "Result: PASSED ({} checks)", self.checks.len())
+        } else {
+            write!(
+                f,
+                "Result: FAILED ({} of {} checks failed)",
+                failed,
+                self.checks.len()
+            )
+        }
+    }
+}
+
+/// Run every check against `config`
+///
+/// ### Arguments
+///
+/// * `config` - Configuration to validate, with the command line overrides applied
+///
+/// ### Returns
+///
+/// The report of the signal chain, action driver and thermal sensor checks
+pub async fn run_self_test(config: &Config) -> SelfTestReport {
+    info!("Running self-test");
+    let mut checks = check_signal_chain(config).await;
+    checks.extend(check_action_drivers(config).await);
+    checks.extend(check_thermal_sensors(config).await);
+    SelfTestReport { checks }
+}
+
+/// Largest distance between the detected and the expected peak frequency
+///
+/// Two FFT bins of the peak finders, which analyze frames of
+/// `photoacoustic.frame_size` samples.
+///
+/// ### Arguments
+///
+/// * `config` - Configuration under test
+///
+/// ### Returns
+///
+/// The tolerance in Hz
+pub fn frequency_tolerance(config: &Config) -> f32 {
+    2.0 * config.photoacoustic.sample_rate as f32 / config.photoacoustic.frame_size as f32
+}
+
+/// Feed a synthetic tone through the processing graph and check the peak finders
+///
+/// ### Arguments
+///
+/// * `config` - Configuration under test
+///
+/// ### Returns
+///
+/// One result per peak finder node, or a single result when processing is
+/// disabled, the graph has no peak finder or the graph fails
+pub async fn check_signal_chain(config: &Config) -> Vec<CheckResult> {
+    let graph_id = config.processing.default_graph.id.clone();
+    if !config.processing.enabled {
+        return vec![CheckResult::new(
+            CheckKind::SignalChain,
+            graph_id,
+            CheckStatus::Skip,
+            "processing is disabled",
+        )];
+    }
+
+    let peak_finders: Vec<String> = config
+        .processing
+        .default_graph
+        .nodes
+        .iter()
+        .filter(|node| node.node_type == "computing_peak_finder")
+        .map(|node| node.id.clone())
+        .collect();
+    if peak_finders.is_empty() {
+        return vec![CheckResult::new(
+            CheckKind::SignalChain,
+            graph_id,
+            CheckStatus::Fail,
+            "the processing graph has no computing_peak_finder node",
+        )];
+    }
+
+    let computing_state = Arc::new(RwLock::new(ComputingSharedData::default()));
+    if let Err(e) = feed_synthetic_signal(config, &computing_state) {
+        return vec![CheckResult::new(
+            CheckKind::SignalChain,
+            graph_id,
+            CheckStatus::Fail,
+            format!("{:#}", e),
+        )];
+    }
+
+    let expected = config.photoacoustic.frequency;
+    let tolerance = frequency_tolerance(config);
+    let state = computing_state.read().await;
+    peak_finders
+        .into_iter()
+        .map(|node_id| {
+            let outcome = match state.get_peak_result(&node_id) {
+                None => Err(anyhow!(
+                    "no peak found (expected {:.1} Hz ± {:.1} Hz)",
+                    expected,
+                    tolerance
+                )),
+                Some(peak) if (peak.frequency - expected).abs() > tolerance => Err(anyhow!(
+                    "peak at {:.1} Hz, expected {:.1} Hz ± {:.1} Hz",
+                    peak.frequency,
+                    expected,
+                    tolerance
+                )),
+                Some(peak) => Ok(format!(
+                    "peak at {:.1} Hz, expected {:.1} Hz ± {:.1} Hz",
+                    peak.frequency, expected, tolerance
+                )),
+            };
+            CheckResult::from_outcome(CheckKind::SignalChain, node_id, outcome)
+        })
+        .collect()
+}
+
+/// Build the graph under test and process [`SELF_TEST_FRAMES`] synthetic frames
+fn feed_synthetic_signal(
+    config: &Config,
+    computing_state: &Arc<RwLock<ComputingSharedData>>,
+) -> Result<()> {
+    let record_dir = tempfile::tempdir().context("Failed to create the recording directory")?;
+    let graph_config = isolated_graph_config(&config.processing.default_graph, record_dir.path());
+    let mut graph = ProcessingGraph::from_config_with_all_params(
+        &graph_config,
+        Some(StreamingNodeRegistry::new()),
+        &config.photoacoustic,
+        Some(computing_state.clone()),
+    )
+    .context("Failed to create the processing graph")?;
+
+    let photoacoustic = &config.photoacoustic;
+    let sample_rate = photoacoustic.sample_rate as u32;
+    let frame_size = photoacoustic.frame_size as usize;
+    let omega = std::f32::consts::TAU * photoacoustic.frequency / sample_rate as f32;
+    let mut noise = NoiseGenerator::new(0x5e1f);
+    for frame_number in 0..SELF_TEST_FRAMES {
+        let first_sample = frame_number as usize * frame_size;
+        let (channel_a, channel_b) = (first_sample..first_sample + frame_size)
+            .map(|n| {
+                (
+                    SELF_TEST_TONE_AMPLITUDE * (omega * n as f32).sin()
+                        + SELF_TEST_NOISE_LEVEL * noise.random_gaussian(),
+                    SELF_TEST_NOISE_LEVEL * noise.random_gaussian(),
+                )
+            })
+            .unzip();
+        let frame = ProcessingData::AudioFrame(AudioFrame {
+            channel_a,
+            channel_b,
+            sample_rate,
+            timestamp: first_sample as u64 * 1000 / sample_rate as u64,
+            frame_number,
+        });
+        graph
+            .execute(frame)
+            .with_context(|| format!("Processing graph failed on frame {}", frame_number))?;
+    }
+    Ok(())
+}
+
+/// Copy of `graph` that cannot act outside the self-test
+///
+/// Action nodes lose their driver and dead-letter queue, and record nodes
+/// write into `record_dir`.
+fn isolated_graph_config(
+    graph: &ProcessingGraphConfig,
+    record_dir: &std::path::Path,
+) -> ProcessingGraphConfig {
+    let mut graph = graph.clone();
+    for node in &mut graph.nodes {
+        let Some(params) = node.parameters.as_object_mut() else {
+            continue;
+        };
+        match node.node_type.as_str() {
+            "action_universal" => {
+                params.remove("driver");
+                params.remove("dead_letter");
+            }
+            "record" => {
+                let record_file = record_dir.join(format!("{}.wav", node.id));
+                params.insert(
+                    "record_file".to_string(),
+                    record_file.to_string_lossy().into_owned().into(),
+                );
+            }
+            _ => {}
+        }
+    }
+    graph
+}
+
+/// Check that the driver of every action node reaches its endpoint
+///
+/// ### Arguments
+///
+/// * `config` - Configuration under test
+///
+/// ### Returns
+///
+/// One result per action node with a driver, or a skipped result when no
+/// driver is configured
+pub async fn check_action_drivers(config: &Config) -> Vec<CheckResult> {
+    let mut checks = Vec::new();
+    for node in &config.processing.default_graph.nodes {
+        if node.node_type != "action_universal" {
+            continue;
+        }
+        let Some(driver) = node.parameters.get("driver").and_then(|v| v.as_object()) else {
+            continue;
+        };
+        let driver_type = driver
+            .get("type")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        let name = format!("{} ({})", node.id, driver_type);
+        let outcome = match driver.get("config").and_then(|v| v.as_object()) {
+            Some(driver_config) => check_action_driver(driver_type, driver_config).await,
+            None => Err(anyhow!("the driver has no config section")),
+        };
+        checks.push(CheckResult::from_outcome(
+            CheckKind::ActionDriver,
+            name,
+            outcome,
+        ));
+    }
+
+    if checks.is_empty() {
+        checks.push(CheckResult::new(
+            CheckKind::ActionDriver,
+            config.processing.default_graph.id.clone(),
+            CheckStatus::Skip,
+            "no action driver configured",
+        ));
+    }
+    checks
+}
+
+/// Connect a driver, then shut it down
+async fn check_action_driver(
+    driver_type: &str,
+    driver_config: &serde_json::Map<String, serde_json::Value>,
+) -> Result<String> {
+    let mut driver = create_action_driver(driver_type, driver_config)?;
+    let outcome = match tokio::time::timeout(DRIVER_CHECK_TIMEOUT, driver.check_connection()).await
+    {
+        Ok(result) => result,
+        Err(_) => Err(anyhow!(
+            "no answer within {} s",
+            DRIVER_CHECK_TIMEOUT.as_secs()
+        )),
+    };
+    let _ = driver.shutdown().await;
+    outcome.map(|_| "connected".to_string())
+}
+
+/// Read the sensor of every enabled thermal regulator
+///
+/// ### Arguments
+///
+/// * `config` - Configuration under test
+///
+/// ### Returns
+///
+/// One result per enabled regulator, or a skipped result when thermal
+/// regulation is disabled or has no enabled regulator
+pub async fn check_thermal_sensors(config: &Config) -> Vec<CheckResult> {
+    let thermal = &config.thermal_regulation;
+    if !thermal.enabled {
+        return vec![CheckResult::new(
+            CheckKind::ThermalSensor,
+            "thermal_regulation",
+            CheckStatus::Skip,
+            "thermal regulation is disabled",
+        )];
+    }
+
+    let mut checks = Vec::new();
+    for regulator in thermal.regulators.iter().filter(|r| r.enabled) {
+        let outcome = match thermal.i2c_buses.get(&regulator.i2c_bus) {
+            Some(bus_config) => read_regulator_temperature(bus_config, regulator).await,
+            None => Err(anyhow!("unknown I2C bus '{}'", regulator.i2c_bus)),
+        };
+        checks.push(CheckResult::from_outcome(
+            CheckKind::ThermalSensor,
+            regulator.id.clone(),
+            outcome,
+        ));
+    }
+
+    if checks.is_empty() {
+        checks.push(CheckResult::new(
+            CheckKind::ThermalSensor,
+            "thermal_regulation",
+            CheckStatus::Skip,
+            "no enabled regulator",
+        ));
+    }
+    checks
+}
+
+/// Read the temperature of a regulator and check that it is plausible
+async fn read_regulator_temperature(
+    bus_config: &crate::config::thermal_regulation::I2CBusConfig,
+    regulator: &crate::config::thermal_regulation::ThermalRegulatorConfig,
+) -> Result<String> {
+    let mut driver = create_thermal_regulation_driver(bus_config, regulator)?;
+    driver
+        .initialize()
+        .await
+        .context("Failed to initialize the driver")?;
+    let temperature = driver
+        .read_temperature()
+        .await
+        .context("Failed to read the temperature")?;
+
+    if !PLAUSIBLE_TEMPERATURE_RANGE_C.contains(&temperature) {
+        return Err(anyhow!(
+            "implausible temperature {:.2} °C (expected {:.0} to {:.0} °C)",
+            temperature,
+            PLAUSIBLE_TEMPERATURE_RANGE_C.start(),
+            PLAUSIBLE_TEMPERATURE_RANGE_C.end()
+        ));
+    }
+    Ok(format!("{:.2} °C", temperature))
+}
@@ -0,0 +1,309 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Supervision of the daemon background tasks
+//!
+//! A task spawned through [`TaskSupervisor::spawn`] is restarted when it
+//! returns an error or panics, instead of silently disappearing:
+//!
+//! 1. The failure is logged and the task's restart counter is incremented
+//! 2. The task is restarted after a backoff delay, doubled after each
+//!    consecutive failure up to [`RestartPolicy::max_backoff`]
+//! 3. After more than [`RestartPolicy::max_restarts`] consecutive failures the
+//!    task is marked [`TaskState::Failed`] and no longer restarted
+//!
+//! A task returning `Ok(())` has stopped on purpose and is not restarted.
+//! The current state of every supervised task is available from
+//! [`TaskSupervisor::statuses`]; the `/readyz` probe reports the daemon as not
+//! ready while a task is restarting or failed.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use futures::FutureExt;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+
+use crate::config::DaemonConfig;
+use crate::utility::panic_message;
+
+/// Restart limits applied to the supervised tasks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestartPolicy {
+    /// Consecutive failures after which a task is marked failed
+    pub max_restarts: u32,
+    /// Delay before the first restart
+    pub initial_backoff: Duration,
+    /// Upper bound of the restart delay
+    pub max_backoff: Duration,
+}
+
+impl RestartPolicy {
+    /// Delay before restarting a task that failed `failures` times in a row
+    ///
+    /// ### Parameters
+    ///
+    /// * `failures` - Number of consecutive failures, starting at 1
+    pub fn backoff(&self, failures: u32) -> Duration {
+        let factor = 2u32.saturating_pow(failures.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+impl From<&DaemonConfig> for RestartPolicy {
+    fn from(config: &DaemonConfig) -> Self {
+        Self {
+            max_restarts: config.task_max_restarts,
+            initial_backoff: Duration::from_millis(config.task_restart_backoff_ms),
+            max_backoff: Duration::from_millis(config.task_restart_backoff_max_ms),
+        }
+    }
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self::from(&DaemonConfig::default())
+    }
+}
+
+/// Lifecycle state of a supervised task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskState {
+    /// The task is running
+    Running,
+    /// The task failed and waits for its restart
+    Restarting,
+    /// The task returned on purpose, usually because the daemon is stopping
+    Stopped,
+    /// The task failed too many times in a row and is no longer restarted
+    Failed,
+}
+
+/// State and restart history of a supervised task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskStatus {
+    pub state: TaskState,
+    /// Number of restarts since the daemon started
+    pub restarts: u32,
+    /// Error or panic message of the last failure
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// Spawns the daemon tasks and restarts them when they fail
+///
+/// Cloning the supervisor shares the task statuses, so that a clone managed
+/// by the web server reports the tasks spawned by the daemon.
+#[derive(Debug, Clone, Default)]
+pub struct TaskSupervisor {
+    policy: RestartPolicy,
+    statuses: Arc<RwLock<BTreeMap<String, TaskStatus>>>,
+}
+
+impl TaskSupervisor {
+    /// Create a supervisor applying `policy` to the tasks it spawns
+    pub fn new(policy: RestartPolicy) -> Self {
+        Self {
+            policy,
+            statuses: Arc::default(),
+        }
+    }
+
+    /// Restart limits applied by this supervisor
+    pub fn policy(&self) -> RestartPolicy {
+        self.policy
+    }
+
+    /// Snapshot of the status of every supervised task, by task name
+    pub fn statuses(&self) -> BTreeMap<String, TaskStatus> {
+        self.statuses
+            .read()
+            .map(|statuses| statuses.clone())
+            .unwrap_or_default()
+    }
+
+    /// Status of the supervised task `name`, if it was spawned
+    pub fn status(&self, name: &str) -> Option<TaskStatus> {
+        self.statuses
+            .read()
+            .ok()
+            .and_then(|statuses| statuses.get(name).cloned())
+    }
+
+    /// Spawn a supervised task
+    ///
+    /// `factory` is called once per attempt and returns the future running the
+    /// task. Attempts returning an error or panicking are restarted according
+    /// to the [`RestartPolicy`], unless `running` was cleared meanwhile.
+    ///
+    /// ### Parameters
+    ///
+    /// * `name` - Task name, used in logs and in the statuses
+    /// * `running` - Flag cleared when the task must stop; no restart happens afterwards
+    /// * `factory` - Creates the future of one attempt
+    ///
+    /// ### Returns
+    ///
+    /// The handle of the supervising task, resolving to an error once the
+    /// task is marked failed
+    pub fn spawn<F, Fut>(
+        &self,
+        name: &str,
+        running: Arc<AtomicBool>,
+        factory: F,
+    ) -> JoinHandle<Result<()>>
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.spawn_on(&Handle::current(), name, running, factory)
+    }
+
+    /// Spawn a supervised task on the runtime of `handle`
+    ///
+    /// Same as [`spawn`](Self::spawn), with every attempt running on the
+    /// given runtime, such as the real-time runtime of the daemon.
+    ///
+    /// ### Parameters
+    ///
+    /// * `handle` - Runtime running the supervising task and its attempts
+    /// * `name` - Task name, used in logs and in the statuses
+    /// * `running` - Flag cleared when the task must stop; no restart happens afterwards
+    /// * `factory` - Creates the future of one attempt
+    ///
+    /// ### Returns
+    ///
+    /// The handle of the supervising task, resolving to an error once the
+    /// task is marked failed
+    pub fn spawn_on<F, Fut>(
+        &self,
+        handle: &Handle,
+        name: &str,
+        running: Arc<AtomicBool>,
+        mut factory: F,
+    ) -> JoinHandle<Result<()>>
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let supervisor = self.clone();
+        let name = name.to_string();
+        supervisor.update(&name, |status| status.state = TaskState::Running);
+
+        handle.spawn(async move {
+            let policy = supervisor.policy;
+            let mut failures = 0u32;
+
+            loop {
+                let started = Instant::now();
+                let outcome = AssertUnwindSafe(async { factory().await })
+                    .catch_unwind()
+                    .await;
+                let failure = match outcome {
+                    Ok(Ok(())) => {
+                        supervisor.update(&name, |status| status.state = TaskState::Stopped);
+                        return Ok(());
+                    }
+                    Ok(Err(e)) => format!("{:#}", e),
+                    Err(panic) => format!("panicked: {}", panic_message(panic.as_ref())),
+                };
+
+                if !running.load(Ordering::SeqCst) {
+                    warn!("Task '{}' failed while stopping: {}", name, failure);
+                    supervisor.update(&name, |status| {
+                        status.state = TaskState::Stopped;
+                        status.last_error = Some(failure);
+                    });
+                    return Ok(());
+                }
+
+                // A task that ran for a while before failing starts a new series
+                if started.elapsed() >= policy.max_backoff {
+                    failures = 0;
+                }
+                failures += 1;
+
+                if failures > policy.max_restarts {
+                    error!(
+                        "Task '{}' failed {} times in a row, giving up: {}",
+                        name, failures, failure
+                    );
+                    supervisor.update(&name, |status| {
+                        status.state = TaskState::Failed;
+                        status.last_error = Some(failure.clone());
+                    });
+                    return Err(anyhow!("Task '{}' failed: {}", name, failure));
+                }
+
+                let delay = policy.backoff(failures);
+                warn!(
+                    "Task '{}' failed ({}), restart {}/{} in {} ms",
+                    name,
+                    failure,
+                    failures,
+                    policy.max_restarts,
+                    delay.as_millis()
+                );
+                supervisor.update(&name, |status| {
+                    status.state = TaskState::Restarting;
+                    status.restarts += 1;
+                    status.last_error = Some(failure);
+                });
+
+                if !sleep_while_running(&running, delay).await {
+                    supervisor.update(&name, |status| status.state = TaskState::Stopped);
+                    return Ok(());
+                }
+                info!("Restarting task '{}'", name);
+                supervisor.update(&name, |status| status.state = TaskState::Running);
+            }
+        })
+    }
+
+    /// Apply `change` to the status of `name`, registering it if needed
+    fn update(&self, name: &str, change: impl FnOnce(&mut TaskStatus)) {
+        if let Ok(mut statuses) = self.statuses.write() {
+            let status = statuses
+                .entry(name.to_string())
+                .or_insert_with(|| TaskStatus {
+                    state: TaskState::Running,
+                    restarts: 0,
+                    last_error: None,
+                });
+            change(status);
+        }
+    }
+}
+
+/// Sleep for `duration` unless `running` is cleared meanwhile
+///
+/// Long-period tasks use it so that they do not hold the shutdown for their
+/// whole period.
+///
+/// ### Returns
+///
+/// `true` if the whole delay elapsed while `running` stayed set
+pub(crate) async fn sleep_while_running(running: &AtomicBool, duration: Duration) -> bool {
+    let deadline = tokio::time::Instant::now() + duration;
+    loop {
+        if !running.load(Ordering::SeqCst) {
+            return false;
+        }
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        if remaining.is_zero() {
+            return true;
+        }
+        tokio::time::sleep(remaining.min(Duration::from_millis(100))).await;
+    }
+}
@@ -16,10 +16,10 @@ mod thermal_regulation;
 mod utility;
 mod visualization;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
 use config::Config;
-use log::info;
+use log::{info, warn};
 
 use std::env;
 use std::path::PathBuf;
@@ -57,10 +57,16 @@ pub struct Args {
     #[arg(long)]
     output: Option<PathBuf>,
 
-    /// Window size for FFT analysis
+    /// Frame size in samples (`photoacoustic.frame_size`)
+    ///
+    /// The same value sizes the acquired audio frames and the FFT analysis window.
     #[arg(long)]
     frame_size: Option<u16>,
 
+    /// Deprecated alias of --frame-size
+    #[arg(long = "window-size", hide = true, conflicts_with = "frame_size")]
+    window_size: Option<u16>,
+
     /// Number of spectra to average
     #[arg(long)]
     averages: Option<u16>,
@@ -89,7 +95,7 @@ pub struct Args {
     #[arg(long)]
     validate_config: Option<PathBuf>,
 
-    /// Output the configuration schema as JSON and exit
+    /// Output the configuration schema, annotated for editors, as JSON and exit
     #[arg(long)]
     show_config_schema: bool,
 
@@ -154,6 +160,62 @@ pub struct Args {
     /// This generates and prints the complete OpenAPI v3.0.0 specification for all API endpoints
     #[arg(long = "get-openapi-json")]
     get_openapi_json: bool,
+
+    /// Write the OpenAPI specification to the given file and exit
+    /// The file is written as YAML when its extension is .yaml or .yml, as JSON otherwise
+    #[arg(long = "dump-openapi", value_name = "PATH")]
+    dump_openapi: Option<PathBuf>,
+
+    /// Output the effective configuration as YAML and exit
+    /// This is the configuration file with the command line overrides applied, i.e. the
+    /// configuration the daemon would run with
+    #[arg(long = "dump-effective-config")]
+    dump_effective_config: bool,
+
+    /// Validate the measurement chain and exit, with a non-zero status on failure
+    /// A synthetic signal is fed through the processing graph, the action drivers are
+    /// connected and the thermal sensors are read
+    #[arg(long = "self-test")]
+    self_test: bool,
+}
+
+impl Args {
+    /// Frame size requested on the command line
+    ///
+    /// Accepts the deprecated `--window-size` spelling, with a warning.
+    fn frame_size(&self) -> Option<u16> {
+        if self.window_size.is_some() {
+            warn!("--window-size is deprecated, use --frame-size instead");
+        }
+        self.frame_size.or(self.window_size)
+    }
+
+    /// Path of the configuration file, `config.yaml` unless --config is set
+    fn config_path(&self) -> PathBuf {
+        self.config
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("config.yaml"))
+    }
+
+    /// Apply the command line overrides to `config`
+    fn apply_overrides(&self, config: &mut Config) {
+        config.apply_args(
+            self.web_port,
+            self.web_address.clone(),
+            self.hmac_secret.clone(),
+            self.server,
+            self.input_device.clone(),
+            self.input_file.clone(),
+            self.frequency,
+            self.bandwidth,
+            self.frame_size(),
+            self.averages,
+            self.modbus_enabled,
+            self.modbus_address.clone(),
+            self.modbus_port,
+            Some(self.enable_local_visualization),
+        );
+    }
 }
 
 #[rocket::main]
@@ -238,9 +300,7 @@ async fn main() -> Result<()> {
         log::LevelFilter::Info
     };
 
-    env_logger::Builder::from_default_env()
-        .filter_level(log_level)
-        .init();
+    let logger = utility::log_file::DaemonLogger::init(log_level)?;
 
     // Check if --show-config-schema flag is set
     if args.show_config_schema {
@@ -273,6 +333,37 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Handle --dump-openapi to write the specification for build/release pipelines
+    if let Some(output_path) = &args.dump_openapi {
+        let config_path = args
+            .config
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("config.yaml"));
+        let config = Config::from_file(&config_path)?;
+        let config_arc = Arc::new(RwLock::new(config));
+
+        let is_yaml = output_path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml"));
+        let openapi_spec = if is_yaml {
+            visualization::server::generate_openapi_yaml(&config_arc, true, true, true, true)
+                .await?
+        } else {
+            visualization::server::generate_openapi_json(&config_arc, true, true, true, true)
+                .await?
+        };
+
+        std::fs::write(output_path, openapi_spec).with_context(|| {
+            format!(
+                "Failed to write OpenAPI specification to {}",
+                output_path.display()
+            )
+        })?;
+        println!("OpenAPI specification written to {}", output_path.display());
+        return Ok(());
+    }
+
     // Validate configuration file if --validate-config is set
     if let Some(validate_path) = args.validate_config {
         if !validate_path.exists() {
@@ -290,32 +381,34 @@ async fn main() -> Result<()> {
     }
 
     // Load configuration
-    let config_path = args
-        .config
-        .clone()
-        .unwrap_or_else(|| PathBuf::from("config.yaml"));
+    let config_path = args.config_path();
     let mut config = Config::from_file(&config_path)?;
 
     // Apply command line overrides
-    config.apply_args(
-        args.web_port,
-        args.web_address.clone(),
-        args.hmac_secret.clone(),
-        args.server,
-        args.input_device.clone(),
-        args.input_file.clone(),
-        args.frequency,
-        args.bandwidth,
-        args.frame_size,
-        args.averages,
-        args.modbus_enabled,
-        args.modbus_address.clone(),
-        args.modbus_port,
-        Some(args.enable_local_visualization),
-    );
+    args.apply_overrides(&mut config);
+
+    // If --dump-effective-config is set, output the resolved configuration and exit
+    if args.dump_effective_config {
+        let yaml = serde_yml::to_string(&config)
+            .context("Failed to serialize the effective configuration to YAML")?;
+        print!("{}", yaml);
+        return Ok(());
+    }
+
+    // If --self-test is set, validate the measurement chain and exit with its outcome
+    if args.self_test {
+        let report = daemon::self_test::run_self_test(&config).await;
+        println!("{}", report);
+        if !report.passed() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
 
     // Configure Rocket
     if args.server {
+        // Attach the rotated log file before the daemon starts logging
+        logger.configure(&config.daemon)?;
         info!("Starting in daemon mode");
         let mut daemon = daemon::launch_daemon::Daemon::new();
 
@@ -332,8 +425,7 @@ async fn main() -> Result<()> {
         match signal::ctrl_c().await {
             Ok(()) => {
                 info!("Received shutdown signal, terminating daemon");
-                daemon.shutdown();
-                daemon.join().await?;
+                daemon.graceful_shutdown().await?;
             }
             Err(err) => {
                 eprintln!("Error waiting for shutdown signal: {}", err);
@@ -355,3 +447,81 @@ struct AnalysisResult {
     concentration: f32,
     timestamp: chrono::DateTime<chrono::Utc>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Apply the command line `argv` to a default configuration
+    fn config_from_args(argv: &[&str]) -> Config {
+        let args = Args::try_parse_from(argv).unwrap();
+        let mut config = Config::default();
+        args.apply_overrides(&mut config);
+        config
+    }
+
+    #[test]
+    fn test_frame_size_flag_sets_frame_size() {
+        let config = config_from_args(&["rust_photoacoustic", "--frame-size", "2048"]);
+        assert_eq!(config.photoacoustic.frame_size, 2048);
+    }
+
+    #[test]
+    fn test_deprecated_window_size_flag_sets_frame_size() {
+        let config = config_from_args(&["rust_photoacoustic", "--window-size", "1024"]);
+        assert_eq!(config.photoacoustic.frame_size, 1024);
+    }
+
+    #[test]
+    fn test_frame_size_defaults_to_config() {
+        let config = config_from_args(&["rust_photoacoustic"]);
+        assert_eq!(
+            config.photoacoustic.frame_size,
+            Config::default().photoacoustic.frame_size
+        );
+    }
+
+    #[test]
+    fn test_both_frame_size_spellings_conflict() {
+        let result = Args::try_parse_from([
+            "rust_photoacoustic",
+            "--frame-size",
+            "2048",
+            "--window-size",
+            "1024",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_dump_effective_config_reflects_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.yaml");
+        let mut file_config = Config::default();
+        file_config.photoacoustic.frequency = 2000.0;
+        file_config.modbus.port = 5020;
+        file_config.save_to_file(&config_path).unwrap();
+
+        let args = Args::try_parse_from([
+            "rust_photoacoustic",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--frequency",
+            "1234.5",
+            "--dump-effective-config",
+        ])
+        .unwrap();
+        assert!(args.dump_effective_config);
+        assert_eq!(args.config_path(), config_path);
+
+        let mut config = Config::from_file(args.config_path()).unwrap();
+        args.apply_overrides(&mut config);
+        let dumped: serde_yml::Value =
+            serde_yml::from_str(&serde_yml::to_string(&config).unwrap()).unwrap();
+
+        // Overridden on the command line
+        assert_eq!(dumped["photoacoustic"]["frequency"].as_f64(), Some(1234.5));
+        // Taken from the configuration file
+        assert_eq!(dumped["modbus"]["port"].as_u64(), Some(5020));
+    }
+}
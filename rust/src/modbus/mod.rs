@@ -4,14 +4,18 @@
 
 //! Modbus communication module
 //!
-//! This module provides Modbus TCP server functionality for the photoacoustic
-//! water vapor analyzer, allowing external systems to read measurement data
+//! This module provides Modbus server functionality, over TCP or RTU (serial
+//! line), for the photoacoustic water vapor analyzer, allowing external systems to read measurement data
 //! and configure the analyzer via the Modbus protocol.
 //!
 //! ## Key Components
 //!
 //! - `PhotoacousticModbusServer`: The main server implementation that handles
 //!   Modbus requests and provides access to measurement data.
+//! - `register_map`: The register layout shared by the server and the
+//!   `GET /api/modbus/map` endpoint.
+//! - `rtu`: Modbus RTU transport, serving the same registers on a serial line
+//!   when `modbus.transport` is `rtu`.
 //!
 //! ## Usage
 //!
@@ -43,6 +47,23 @@
 //! - Register 4: Timestamp high word (UNIX epoch seconds)
 //! - Register 5: Status code (0=normal, 1=warning, 2=error)
 //!
+//! With `modbus.value_encoding: float32`, registers 0-7 instead hold the
+//! frequency, amplitude, concentration (IEEE-754 floats) and timestamp
+//! (32-bit integer) over two registers each, in the configured
+//! `modbus.word_order`, and register 8 holds the status code.
+//!
+//! ### Thermal Regulation Input Registers (Read-Only)
+//!
+//! Available when thermal regulation is enabled. Regulators are sorted by
+//! identifier; signed values use two's complement and `0x8000` means
+//! "no reading available yet".
+//!
+//! - Register 100: Number of thermal regulators exposed
+//! - Register 110 + 10·n: Regulator n temperature (°C × 100)
+//! - Register 111 + 10·n: Regulator n setpoint (°C × 100)
+//! - Register 112 + 10·n: Regulator n control output (% × 100)
+//! - Register 113 + 10·n: Regulator n fault bit (0=ok, 1=fault)
+//!
 //! ### Holding Registers (Read/Write)
 //!
 //! - Register 0: Measurement interval (seconds), default: 10
@@ -51,4 +72,8 @@
 //! - Register 3: Filter strength, default: 40
 
 pub mod modbus_server;
+pub mod register_map;
+pub mod rtu;
 pub use modbus_server::PhotoacousticModbusServer;
+pub use register_map::{register_map, MeasurementEncoding, RegisterDescriptor};
+pub use rtu::RtuSlaveServer;
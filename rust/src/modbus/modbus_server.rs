@@ -27,6 +27,40 @@
 //! | 4 | Measurement Timestamp (High Word) | epoch seconds | 1 |
 //! | 5 | Status Code | - | 0=normal, 1=warning, 2=error |
 //!
+//! ### Float32 Input Registers (Read Only)
+//!
+//! When `modbus.value_encoding` is `float32` (see
+//! [`PhotoacousticModbusServer::with_measurement_encoding`]), the measurement
+//! block above is replaced by IEEE-754 floats spanning two registers. The order
+//! of the two words follows `modbus.word_order` (`big_endian`: high word first).
+//!
+//! | Register Address | Description | Unit | Type |
+//! |-----------------|-------------|------|------|
+//! | 0-1 | Resonance Frequency | Hz | float32 |
+//! | 2-3 | Signal Amplitude | - | float32 |
+//! | 4-5 | Gas Concentration | ppm | float32 (NaN when not computed) |
+//! | 6-7 | Measurement Timestamp | epoch seconds | uint32 |
+//! | 8 | Status Code | - | 0=normal, 1=warning, 2=error |
+//!
+//! ### Thermal Regulation Input Registers (Read Only)
+//!
+//! When the server is attached to a thermal regulation state (see
+//! [`PhotoacousticModbusServer::with_thermal_state`]), a block of registers
+//! starting at address 100 exposes the state of every thermal regulator.
+//! Regulators are ordered by their identifier. Signed values are encoded as
+//! 16-bit two's complement; `0x8000` means "no reading available yet".
+//!
+//! | Register Address | Description | Unit | Scaling |
+//! |-----------------|-------------|------|---------|
+//! | 100 | Number of thermal regulators exposed | - | 1 |
+//! | 110 + 10·n | Regulator n current temperature | °C | ×100 (0.01 °C resolution, signed) |
+//! | 111 + 10·n | Regulator n setpoint | °C | ×100 (0.01 °C resolution, signed) |
+//! | 112 + 10·n | Regulator n control output | % | ×100 (0.01 % resolution, signed) |
+//! | 113 + 10·n | Regulator n fault bit | - | 0=ok, 1=fault |
+//!
+//! The setpoint register reports the regulator's current target, including
+//! setpoints written through the holding registers below.
+//!
 //! ### Holding Registers (Read/Write)
 //!
 //! | Register Address | Description | Unit | Default | Range |
@@ -36,6 +70,19 @@
 //! | 2 | Gain Setting | - | 30 | 0-100 |
 //! | 3 | Filter Strength | - | 40 | 0-100 |
 //!
+//! ### Thermal Regulation Holding Registers (Read/Write)
+//!
+//! | Register Address | Description | Unit | Scaling | Range |
+//! |-----------------|-------------|------|---------|-------|
+//! | 111 + 10·n | Regulator n setpoint | °C | ×100 (signed) | Regulator safety limits |
+//!
+//! Writing a setpoint forwards it to the regulator through the shared thermal
+//! state. Values outside the regulator's configured safety limits are refused
+//! with an `IllegalDataValue` exception and no register is modified.
+//!
+//! The register layout is defined in [`super::register_map`], which is also
+//! published by the `GET /api/modbus/map` endpoint.
+//!
 //! ## Usage Example
 //!
 //! See the `examples/modbus_client.rs` file for a complete example of how to use
@@ -47,13 +94,29 @@ use std::{
     sync::{Arc, Mutex},
 };
 
-use log::{debug, error};
+use log::{debug, error, info};
 
 use tokio_modbus::prelude::*;
 
+use crate::config::modbus::ModbusValueEncoding;
 use crate::processing::computing_nodes::SharedComputingState;
+use crate::thermal_regulation::shared_state::RegulatorStatus;
+use crate::thermal_regulation::SharedThermalState;
 use crate::utility::PhotoacousticDataSource;
 
+use super::register_map::{
+    decode_thermal_value, encode_f32, encode_thermal_value, encode_u32, exposed_regulator_ids,
+    register_map, thermal_block_base, thermal_block_index, thermal_setpoint_index,
+    MeasurementEncoding, RegisterType, AMPLITUDE_REGISTER, AMPLITUDE_SCALE,
+    AVERAGING_COUNT_REGISTER, CONCENTRATION_REGISTER, CONCENTRATION_SCALE,
+    FILTER_STRENGTH_REGISTER, FLOAT32_AMPLITUDE_REGISTER, FLOAT32_CONCENTRATION_REGISTER,
+    FLOAT32_FREQUENCY_REGISTER, FLOAT32_STATUS_REGISTER, FLOAT32_TIMESTAMP_REGISTER,
+    FREQUENCY_REGISTER, FREQUENCY_SCALE, GAIN_REGISTER, MEASUREMENT_INTERVAL_REGISTER,
+    STATUS_REGISTER, THERMAL_CONTROL_OUTPUT_OFFSET, THERMAL_FAULT_OFFSET,
+    THERMAL_REGULATOR_COUNT_REGISTER, THERMAL_SETPOINT_OFFSET, THERMAL_TEMPERATURE_OFFSET,
+    THERMAL_VALUE_UNAVAILABLE, TIMESTAMP_HIGH_REGISTER, TIMESTAMP_LOW_REGISTER,
+};
+
 /// A Modbus TCP server implementation specific to the photoacoustic water vapor analyzer.
 ///
 /// This server exposes input registers for read-only sensor values (like frequency,
@@ -97,6 +160,12 @@ pub struct PhotoacousticModbusServer {
 
     /// Reference to shared computing state for real-time data updates
     computing_state: Option<SharedComputingState>,
+
+    /// Reference to shared thermal regulation state for regulator monitoring
+    thermal_state: Option<SharedThermalState>,
+
+    /// Encoding of the measurement input registers
+    encoding: MeasurementEncoding,
 }
 
 impl tokio_modbus::server::Service for PhotoacousticModbusServer {
@@ -117,9 +186,15 @@ impl tokio_modbus::server::Service for PhotoacousticModbusServer {
     fn call(&self, req: Self::Request) -> Self::Future {
         debug!("Received Modbus request: {:?}", req);
 
-        // Refresh input registers from computing state before processing read requests
+        // Refresh input registers from shared states before processing read requests
         if matches!(req, Request::ReadInputRegisters(_, _)) {
             self.refresh_from_computing_state();
+            self.refresh_from_thermal_state();
+        }
+
+        // Thermal setpoint holding registers mirror the regulators' current targets
+        if matches!(req, Request::ReadHoldingRegisters(_, _)) {
+            self.refresh_from_thermal_state();
         }
 
         let res = match req {
@@ -145,17 +220,13 @@ impl tokio_modbus::server::Service for PhotoacousticModbusServer {
                     values.len(),
                     addr
                 );
-                register_write(&mut self.holding_registers.lock().unwrap(), addr, &values)
+                self.write_holding_registers(addr, &values)
                     .map(|_| Response::WriteMultipleRegisters(addr, values.len() as u16))
             }
             Request::WriteSingleRegister(addr, value) => {
                 debug!("Writing value {} to holding register {}", value, addr);
-                register_write(
-                    &mut self.holding_registers.lock().unwrap(),
-                    addr,
-                    std::slice::from_ref(&value),
-                )
-                .map(|_| Response::WriteSingleRegister(addr, value))
+                self.write_holding_registers(addr, std::slice::from_ref(&value))
+                    .map(|_| Response::WriteSingleRegister(addr, value))
             }
             _ => {
                 error!(
@@ -187,10 +258,13 @@ impl PhotoacousticModbusServer {
     ///
     /// ### Input Registers (Read-Only)
     /// - 0: 1234 (Resonance frequency in Hz)
-    /// - 1: 5678 (Signal amplitude)
+    /// - 1: 5.678 (Signal amplitude)
     /// - 2: 1000 (Water vapor concentration in ppm)
     /// - 3 & 4: Current UNIX timestamp
     ///
+    /// The measurement registers use the scaled encoding until
+    /// [`Self::with_measurement_encoding`] selects another one.
+    ///
     /// ### Holding Registers (Read-Write)
     /// - 0: 10 (Measurement interval in seconds)
     /// - 1: 20 (Averaging count in samples)
@@ -201,36 +275,27 @@ impl PhotoacousticModbusServer {
     ///
     /// A new `PhotoacousticModbusServer` instance ready to be used with a TCP server.
     pub fn new() -> Self {
-        // Initialize input registers with measurement values (with proper scaling)
-        let mut input_registers = HashMap::new();
-        input_registers.insert(0, 1234 * 10); // Frequency in Hz × 10
-        input_registers.insert(1, 5678); // Amplitude × 1000
-        input_registers.insert(2, 1000 * 10); // Concentration in ppm × 10
-
-        // Store current timestamp in two 16-bit registers
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs() as u32;
-
-        input_registers.insert(3, (now & 0xFFFF) as u16); // Low word
-        input_registers.insert(4, ((now >> 16) & 0xFFFF) as u16); // High word
-
-        // Status register - 0 means normal operation
-        input_registers.insert(5, 0);
+        let encoding = MeasurementEncoding::default();
 
-        // Initialize holding registers with configuration values
-        let mut holding_registers = HashMap::new();
-        holding_registers.insert(0, 10); // Measurement interval (seconds)
-        holding_registers.insert(1, 20); // Averaging count (samples)
-        holding_registers.insert(2, 30); // Gain setting
-        holding_registers.insert(3, 40); // Filter strength
+        // Initialize holding registers with the defaults declared in the register map
+        let holding_registers: HashMap<u16, u16> = register_map(encoding, &[])
+            .into_iter()
+            .filter(|register| register.register_type == RegisterType::Holding)
+            .filter_map(|register| Some((register.address, register.default_value?)))
+            .collect();
 
-        Self {
-            input_registers: Arc::new(Mutex::new(input_registers)),
+        let server = Self {
+            input_registers: Arc::new(Mutex::new(HashMap::new())),
             holding_registers: Arc::new(Mutex::new(holding_registers)),
             computing_state: None,
-        }
+            thermal_state: None,
+            encoding,
+        };
+
+        // Initialize input registers with test measurement values
+        server.reset_measurement_registers();
+
+        server
     }
 
     /// Create a new Modbus server instance with a computing state
@@ -257,11 +322,66 @@ impl PhotoacousticModbusServer {
         server
     }
 
+    /// Attach a thermal regulation state to the server
+    ///
+    /// Once attached, the thermal regulation input registers (starting at
+    /// [`THERMAL_REGULATOR_COUNT_REGISTER`]) are refreshed from the shared
+    /// thermal state before every input register read.
+    ///
+    /// ### Parameters
+    ///
+    /// * `thermal_state` - The shared thermal regulation state to expose
+    ///
+    /// ### Returns
+    ///
+    /// The server instance with thermal regulation registers enabled.
+    pub fn with_thermal_state(mut self, thermal_state: &SharedThermalState) -> Self {
+        self.thermal_state = Some(Arc::clone(thermal_state));
+        self.refresh_from_thermal_state();
+        self
+    }
+
+    /// Select the encoding of the measurement input registers
+    ///
+    /// The measurement block is rebuilt with the new layout, from the attached
+    /// computing state if any, or from the test values used by [`Self::new`].
+    ///
+    /// ### Parameters
+    ///
+    /// * `encoding` - Scaled integers or IEEE-754 floats, and their word order
+    ///
+    /// ### Returns
+    ///
+    /// The server instance serving measurements with the given encoding.
+    pub fn with_measurement_encoding(mut self, encoding: MeasurementEncoding) -> Self {
+        self.encoding = encoding;
+        self.reset_measurement_registers();
+        self.refresh_from_computing_state();
+        self
+    }
+
+    /// Encoding of the measurement input registers
+    pub fn measurement_encoding(&self) -> MeasurementEncoding {
+        self.encoding
+    }
+
+    /// Replace the measurement block with the test values of the current encoding
+    fn reset_measurement_registers(&self) {
+        let last_measurement_register = FLOAT32_STATUS_REGISTER.max(STATUS_REGISTER);
+        self.input_registers
+            .lock()
+            .unwrap()
+            .retain(|addr, _| *addr > last_measurement_register);
+        self.update_measurement_data(1234.0, 5.678, 1000.0);
+    }
+
     /// Update the measurement data in the input registers
     ///
     /// This method allows updating the sensor measurement values that are
-    /// exposed through the input registers. The floating-point values are
-    /// scaled to fit into 16-bit registers with appropriate precision.
+    /// exposed through the input registers. In the scaled encoding, the
+    /// floating-point values are scaled to fit into 16-bit registers with
+    /// appropriate precision; in the float32 encoding they are stored as
+    /// IEEE-754 floats over two registers.
     ///
     /// ### Parameters
     ///
@@ -276,41 +396,73 @@ impl PhotoacousticModbusServer {
     ///
     /// ### Value Scaling
     ///
-    /// The values are scaled as follows:
+    /// In the scaled encoding, the values are scaled as follows:
     /// * Frequency: multiplied by 10 (0.1 Hz resolution)
     /// * Amplitude: multiplied by 1000 (0.001 resolution)
     /// * Concentration: multiplied by 10 (0.1 ppm resolution)
     pub fn update_measurement_data(&self, frequency: f32, amplitude: f32, concentration: f32) {
         let mut input_regs = self.input_registers.lock().unwrap();
 
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs() as u32;
+
+        // Status code - 2 (error) if any value is NaN, 0 for normal operation
+        let status = if frequency.is_nan() || amplitude.is_nan() || concentration.is_nan() {
+            2
+        } else {
+            0
+        };
+
+        if self.encoding.value_encoding == ModbusValueEncoding::Float32 {
+            let word_order = self.encoding.word_order;
+            let wide_values = [
+                (
+                    FLOAT32_FREQUENCY_REGISTER,
+                    encode_f32(frequency, word_order),
+                ),
+                (
+                    FLOAT32_AMPLITUDE_REGISTER,
+                    encode_f32(amplitude, word_order),
+                ),
+                (
+                    FLOAT32_CONCENTRATION_REGISTER,
+                    encode_f32(concentration, word_order),
+                ),
+                (FLOAT32_TIMESTAMP_REGISTER, encode_u32(now, word_order)),
+            ];
+            for (addr, words) in wide_values {
+                input_regs.insert(addr, words[0]);
+                input_regs.insert(addr + 1, words[1]);
+            }
+            input_regs.insert(FLOAT32_STATUS_REGISTER, status);
+
+            debug!(
+                "Updated Modbus float32 input registers with new measurement data: freq={}, amp={}, conc={}",
+                frequency, amplitude, concentration
+            );
+            return;
+        }
+
         // Scale and update the registers with the new data
         // For frequency, we want 0.1 Hz resolution, so multiply by 10
-        let freq_scaled = (frequency * 10.0).round() as u16;
-        input_regs.insert(0, freq_scaled);
+        let freq_scaled = (frequency as f64 * FREQUENCY_SCALE).round() as u16;
+        input_regs.insert(FREQUENCY_REGISTER, freq_scaled);
 
         // For amplitude, we want 0.001 resolution, so multiply by 1000
-        let amp_scaled = (amplitude * 1000.0).round() as u16;
-        input_regs.insert(1, amp_scaled);
+        let amp_scaled = (amplitude as f64 * AMPLITUDE_SCALE).round() as u16;
+        input_regs.insert(AMPLITUDE_REGISTER, amp_scaled);
 
         // For concentration, we want 0.1 ppm resolution, so multiply by 10
-        let conc_scaled = (concentration * 10.0).round() as u16;
-        input_regs.insert(2, conc_scaled);
+        let conc_scaled = (concentration as f64 * CONCENTRATION_SCALE).round() as u16;
+        input_regs.insert(CONCENTRATION_REGISTER, conc_scaled);
 
         // Update the timestamp
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs() as u32;
+        input_regs.insert(TIMESTAMP_LOW_REGISTER, (now & 0xFFFF) as u16); // Low word
+        input_regs.insert(TIMESTAMP_HIGH_REGISTER, ((now >> 16) & 0xFFFF) as u16); // High word
 
-        input_regs.insert(3, (now & 0xFFFF) as u16); // Low word
-        input_regs.insert(4, ((now >> 16) & 0xFFFF) as u16); // High word
-
-        // Add status register - 0 means normal operation
-        if frequency.is_nan() || amplitude.is_nan() || concentration.is_nan() {
-            input_regs.insert(5, 2); // Error status if any value is NaN
-        } else {
-            input_regs.insert(5, 0); // Normal operation
-        }
+        input_regs.insert(STATUS_REGISTER, status);
 
         debug!(
             "Updated Modbus input registers with new measurement data: freq={}, amp={}, conc={}",
@@ -427,6 +579,155 @@ impl PhotoacousticModbusServer {
         }
     }
 
+    /// Update the thermal regulation input registers from a thermal state
+    ///
+    /// Regulators are sorted by identifier and laid out in consecutive blocks of
+    /// [`THERMAL_REGULATOR_BLOCK_SIZE`](super::register_map::THERMAL_REGULATOR_BLOCK_SIZE)
+    /// registers starting at
+    /// [`THERMAL_REGULATOR_BLOCK_BASE`](super::register_map::THERMAL_REGULATOR_BLOCK_BASE).
+    /// At most [`MAX_THERMAL_REGULATORS`](super::register_map::MAX_THERMAL_REGULATORS)
+    /// regulators are exposed.
+    ///
+    /// ### Parameters
+    ///
+    /// * `thermal_state` - The shared thermal regulation state to read from
+    ///
+    /// ### Returns
+    ///
+    /// * `true` if the registers were updated
+    /// * `false` if the thermal state could not be read
+    pub fn update_from_thermal_state(&self, thermal_state: &SharedThermalState) -> bool {
+        let Ok(state) = thermal_state.try_read() else {
+            debug!("Could not read thermal state for Modbus update");
+            return false;
+        };
+
+        let regulator_ids = exposed_regulator_ids(&state);
+
+        let mut input_regs = self.input_registers.lock().unwrap();
+        let mut holding_regs = self.holding_registers.lock().unwrap();
+
+        // Drop blocks of regulators that may have disappeared since the last refresh
+        input_regs.retain(|addr, _| thermal_block_index(*addr).is_none());
+        holding_regs.retain(|addr, _| thermal_block_index(*addr).is_none());
+
+        input_regs.insert(THERMAL_REGULATOR_COUNT_REGISTER, regulator_ids.len() as u16);
+
+        for (index, regulator_id) in regulator_ids.iter().enumerate() {
+            let Some(regulator) = state.get_regulator_history(regulator_id) else {
+                continue;
+            };
+            let base = thermal_block_base(index);
+
+            // The setpoint reflects the current target, which may have been
+            // changed remotely after the last recorded data point
+            let setpoint = encode_thermal_value(regulator.current_pid_params.setpoint_celsius);
+            let (temperature, control_output) = match regulator.history.back() {
+                Some(point) => (
+                    encode_thermal_value(point.temperature_celsius),
+                    encode_thermal_value(point.control_output_percent),
+                ),
+                None => (THERMAL_VALUE_UNAVAILABLE, THERMAL_VALUE_UNAVAILABLE),
+            };
+            let fault = matches!(regulator.status, RegulatorStatus::Error { .. }) as u16;
+
+            input_regs.insert(base + THERMAL_TEMPERATURE_OFFSET, temperature);
+            input_regs.insert(base + THERMAL_SETPOINT_OFFSET, setpoint);
+            input_regs.insert(base + THERMAL_CONTROL_OUTPUT_OFFSET, control_output);
+            input_regs.insert(base + THERMAL_FAULT_OFFSET, fault);
+
+            holding_regs.insert(base + THERMAL_SETPOINT_OFFSET, setpoint);
+        }
+
+        debug!(
+            "Updated Modbus thermal registers for {} regulator(s)",
+            regulator_ids.len()
+        );
+        true
+    }
+
+    /// Refresh thermal regulation registers from the stored thermal state
+    fn refresh_from_thermal_state(&self) {
+        if let Some(ref thermal_state) = self.thermal_state {
+            self.update_from_thermal_state(thermal_state);
+        }
+    }
+
+    /// Write values to the holding registers
+    ///
+    /// Values targeting thermal setpoint registers are validated against the
+    /// regulator's safety limits before anything is written. If any setpoint is
+    /// refused, no register is modified. Accepted setpoints are forwarded to the
+    /// regulators through the shared thermal state.
+    ///
+    /// ### Errors
+    ///
+    /// * `ExceptionCode::IllegalDataAddress` - A target register does not exist
+    /// * `ExceptionCode::IllegalDataValue` - A setpoint is outside the safe range
+    /// * `ExceptionCode::ServerDeviceBusy` - The thermal state is currently locked
+    fn write_holding_registers(&self, addr: u16, values: &[u16]) -> Result<(), ExceptionCode> {
+        let setpoint_writes: Vec<(usize, u16)> = values
+            .iter()
+            .enumerate()
+            .filter_map(|(i, value)| {
+                let reg_addr = addr.checked_add(i as u16)?;
+                thermal_setpoint_index(reg_addr).map(|index| (index, *value))
+            })
+            .collect();
+
+        if setpoint_writes.is_empty() {
+            return register_write(&mut self.holding_registers.lock().unwrap(), addr, values);
+        }
+
+        let Some(ref thermal_state) = self.thermal_state else {
+            error!("Exception::IllegalDataAddress - Thermal registers are not available");
+            return Err(ExceptionCode::IllegalDataAddress);
+        };
+        let Ok(mut state) = thermal_state.try_write() else {
+            error!("Exception::ServerDeviceBusy - Thermal state is locked");
+            return Err(ExceptionCode::ServerDeviceBusy);
+        };
+
+        // Validate every setpoint before touching any register
+        let regulator_ids = exposed_regulator_ids(&state);
+        let mut requests = Vec::with_capacity(setpoint_writes.len());
+        for (index, raw) in setpoint_writes {
+            let Some(regulator_id) = regulator_ids.get(index) else {
+                error!(
+                    "Exception::IllegalDataAddress - No thermal regulator at index {}",
+                    index
+                );
+                return Err(ExceptionCode::IllegalDataAddress);
+            };
+            let Some(setpoint_celsius) = decode_thermal_value(raw) else {
+                error!("Exception::IllegalDataValue - Setpoint value is unavailable marker");
+                return Err(ExceptionCode::IllegalDataValue);
+            };
+            if let Err(e) = state.validate_setpoint(regulator_id, setpoint_celsius) {
+                error!("Exception::IllegalDataValue - {}", e);
+                return Err(ExceptionCode::IllegalDataValue);
+            }
+            requests.push((regulator_id.clone(), setpoint_celsius));
+        }
+
+        register_write(&mut self.holding_registers.lock().unwrap(), addr, values)?;
+
+        for (regulator_id, setpoint_celsius) in requests {
+            state
+                .request_setpoint(&regulator_id, setpoint_celsius)
+                .map_err(|e| {
+                    error!("Exception::ServerDeviceFailure - {}", e);
+                    ExceptionCode::ServerDeviceFailure
+                })?;
+            info!(
+                "Modbus requested setpoint {:.2} °C for thermal regulator '{}'",
+                setpoint_celsius, regulator_id
+            );
+        }
+
+        Ok(())
+    }
+
     /// Get the current configuration from holding registers
     ///
     /// ### Returns
@@ -439,10 +740,10 @@ impl PhotoacousticModbusServer {
     pub fn get_configuration(&self) -> (u16, u16, u16, u16) {
         let regs = self.holding_registers.lock().unwrap();
 
-        let interval = *regs.get(&0).unwrap_or(&10);
-        let averaging = *regs.get(&1).unwrap_or(&20);
-        let gain = *regs.get(&2).unwrap_or(&30);
-        let filter = *regs.get(&3).unwrap_or(&40);
+        let interval = *regs.get(&MEASUREMENT_INTERVAL_REGISTER).unwrap_or(&10);
+        let averaging = *regs.get(&AVERAGING_COUNT_REGISTER).unwrap_or(&20);
+        let gain = *regs.get(&GAIN_REGISTER).unwrap_or(&30);
+        let filter = *regs.get(&FILTER_STRENGTH_REGISTER).unwrap_or(&40);
 
         (interval, averaging, gain, filter)
     }
@@ -0,0 +1,567 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Modbus register map
+//!
+//! This module is the single source of truth for the Modbus register layout.
+//! The [`PhotoacousticModbusServer`](super::PhotoacousticModbusServer) uses the
+//! addresses, scale factors and default values defined here, and the
+//! `GET /api/modbus/map` endpoint publishes the descriptors returned by
+//! [`register_map`], so the documentation served to integrators cannot drift
+//! from the implementation.
+//!
+//! Measurements are served either as scaled 16-bit integers (the historical
+//! layout) or as IEEE-754 32-bit floats spread over two consecutive registers,
+//! depending on the [`MeasurementEncoding`].
+
+use rocket_okapi::JsonSchema;
+use serde::Serialize;
+
+use crate::config::modbus::{ModbusConfig, ModbusValueEncoding, ModbusWordOrder};
+use crate::thermal_regulation::shared_state::SharedThermalRegulationState;
+
+/// Input register holding the resonance frequency
+pub const FREQUENCY_REGISTER: u16 = 0;
+/// Scale factor of the resonance frequency register (0.1 Hz resolution)
+pub const FREQUENCY_SCALE: f64 = 10.0;
+
+/// Input register holding the signal amplitude
+pub const AMPLITUDE_REGISTER: u16 = 1;
+/// Scale factor of the signal amplitude register (0.001 resolution)
+pub const AMPLITUDE_SCALE: f64 = 1000.0;
+
+/// Input register holding the gas concentration
+pub const CONCENTRATION_REGISTER: u16 = 2;
+/// Scale factor of the gas concentration register (0.1 ppm resolution)
+pub const CONCENTRATION_SCALE: f64 = 10.0;
+
+/// Input register holding the low word of the measurement timestamp
+pub const TIMESTAMP_LOW_REGISTER: u16 = 3;
+/// Input register holding the high word of the measurement timestamp
+pub const TIMESTAMP_HIGH_REGISTER: u16 = 4;
+
+/// Input register holding the measurement status code
+pub const STATUS_REGISTER: u16 = 5;
+
+/// First of the two input registers holding the resonance frequency in float32 encoding
+pub const FLOAT32_FREQUENCY_REGISTER: u16 = 0;
+/// First of the two input registers holding the signal amplitude in float32 encoding
+pub const FLOAT32_AMPLITUDE_REGISTER: u16 = 2;
+/// First of the two input registers holding the gas concentration in float32 encoding
+pub const FLOAT32_CONCENTRATION_REGISTER: u16 = 4;
+/// First of the two input registers holding the measurement timestamp in float32 encoding
+pub const FLOAT32_TIMESTAMP_REGISTER: u16 = 6;
+/// Input register holding the measurement status code in float32 encoding
+pub const FLOAT32_STATUS_REGISTER: u16 = 8;
+
+/// Holding register for the measurement interval (seconds)
+pub const MEASUREMENT_INTERVAL_REGISTER: u16 = 0;
+/// Holding register for the averaging count (samples)
+pub const AVERAGING_COUNT_REGISTER: u16 = 1;
+/// Holding register for the gain setting
+pub const GAIN_REGISTER: u16 = 2;
+/// Holding register for the filter strength
+pub const FILTER_STRENGTH_REGISTER: u16 = 3;
+
+/// Input register holding the number of thermal regulators exposed over Modbus
+pub const THERMAL_REGULATOR_COUNT_REGISTER: u16 = 100;
+
+/// First input register of the per-regulator thermal blocks
+pub const THERMAL_REGULATOR_BLOCK_BASE: u16 = 110;
+
+/// Number of input registers reserved for each thermal regulator block
+pub const THERMAL_REGULATOR_BLOCK_SIZE: u16 = 10;
+
+/// Maximum number of thermal regulators exposed over Modbus
+pub const MAX_THERMAL_REGULATORS: u16 = 16;
+
+/// Offset of the temperature register (°C × 100) inside a regulator block
+pub const THERMAL_TEMPERATURE_OFFSET: u16 = 0;
+
+/// Offset of the setpoint register (°C × 100) inside a regulator block
+pub const THERMAL_SETPOINT_OFFSET: u16 = 1;
+
+/// Offset of the control output register (% × 100) inside a regulator block
+pub const THERMAL_CONTROL_OUTPUT_OFFSET: u16 = 2;
+
+/// Offset of the fault bit register inside a regulator block
+pub const THERMAL_FAULT_OFFSET: u16 = 3;
+
+/// Scale factor applied to thermal values (0.01 resolution)
+pub const THERMAL_SCALE_FACTOR: f64 = 100.0;
+
+/// Raw register value used when a signed thermal reading is not available
+pub const THERMAL_VALUE_UNAVAILABLE: u16 = 0x8000;
+
+/// Encoding of the measurement input registers
+///
+/// Thermal regulation and holding registers always use 16-bit values; only the
+/// measurement block is affected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MeasurementEncoding {
+    /// Scaled integers or IEEE-754 floats
+    pub value_encoding: ModbusValueEncoding,
+    /// Word order of the 32-bit values
+    pub word_order: ModbusWordOrder,
+}
+
+impl From<&ModbusConfig> for MeasurementEncoding {
+    fn from(config: &ModbusConfig) -> Self {
+        Self {
+            value_encoding: config.value_encoding,
+            word_order: config.word_order,
+        }
+    }
+}
+
+/// Type of the value stored in a register
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RegisterDataType {
+    /// Unsigned 16-bit integer
+    Uint16,
+    /// Signed 16-bit integer (two's complement)
+    Int16,
+    /// Unsigned 32-bit integer over two registers
+    Uint32,
+    /// IEEE-754 32-bit float over two registers
+    Float32,
+}
+
+/// Modbus table a register belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RegisterType {
+    /// Input register (function code 0x04)
+    Input,
+    /// Holding register (function codes 0x03, 0x06 and 0x10)
+    Holding,
+}
+
+/// Access mode of a register
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RegisterAccess {
+    /// The register can only be read
+    Read,
+    /// The register can be read and written
+    ReadWrite,
+}
+
+/// Description of a single Modbus register
+///
+/// The engineering value of a register is `raw / scale_factor`, where `raw`
+/// is interpreted according to `data_type`. 32-bit values span
+/// `register_count` consecutive registers starting at `address`, ordered
+/// according to `word_order`.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RegisterDescriptor {
+    /// Register address (first register for 32-bit values)
+    pub address: u16,
+    /// Number of consecutive registers holding the value
+    pub register_count: u16,
+    /// Type of the raw value
+    pub data_type: RegisterDataType,
+    /// Word order of 32-bit values
+    pub word_order: Option<ModbusWordOrder>,
+    /// Modbus table the register belongs to
+    pub register_type: RegisterType,
+    /// Machine-readable name of the value
+    pub name: String,
+    /// Divide the raw value by this factor to obtain the engineering value
+    pub scale_factor: f64,
+    /// Engineering unit, if any
+    pub unit: Option<String>,
+    /// Whether the raw value is a signed 16-bit integer
+    pub signed: bool,
+    /// Access mode
+    pub access: RegisterAccess,
+    /// Value loaded at server start, for holding registers
+    pub default_value: Option<u16>,
+    /// Human-readable description
+    pub description: String,
+}
+
+impl RegisterDescriptor {
+    fn new(
+        address: u16,
+        register_type: RegisterType,
+        name: &str,
+        scale_factor: f64,
+        unit: Option<&str>,
+        description: &str,
+    ) -> Self {
+        Self {
+            address,
+            register_count: 1,
+            data_type: RegisterDataType::Uint16,
+            word_order: None,
+            register_type,
+            name: name.to_string(),
+            scale_factor,
+            unit: unit.map(str::to_string),
+            signed: false,
+            access: match register_type {
+                RegisterType::Input => RegisterAccess::Read,
+                RegisterType::Holding => RegisterAccess::ReadWrite,
+            },
+            default_value: None,
+            description: description.to_string(),
+        }
+    }
+
+    fn signed(mut self) -> Self {
+        self.signed = true;
+        self.data_type = RegisterDataType::Int16;
+        self
+    }
+
+    fn wide(mut self, data_type: RegisterDataType, word_order: ModbusWordOrder) -> Self {
+        self.register_count = 2;
+        self.data_type = data_type;
+        self.word_order = Some(word_order);
+        self
+    }
+
+    fn with_default(mut self, default_value: u16) -> Self {
+        self.default_value = Some(default_value);
+        self
+    }
+}
+
+/// Build the complete register map
+///
+/// ### Parameters
+///
+/// * `encoding` - Encoding of the measurement input registers
+/// * `thermal_regulator_ids` - Identifiers of the thermal regulators exposed
+///   over Modbus. They are sorted and truncated with [`sorted_regulator_ids`]
+///   so that the layout matches the one served by the Modbus server.
+///
+/// ### Returns
+///
+/// The descriptors of every register, input registers first.
+pub fn register_map(
+    encoding: MeasurementEncoding,
+    thermal_regulator_ids: &[String],
+) -> Vec<RegisterDescriptor> {
+    use RegisterType::{Holding, Input};
+
+    let mut registers = measurement_registers(encoding);
+
+    let thermal_regulator_ids = sorted_regulator_ids(thermal_regulator_ids);
+    if !thermal_regulator_ids.is_empty() {
+        registers.push(RegisterDescriptor::new(
+            THERMAL_REGULATOR_COUNT_REGISTER,
+            Input,
+            "thermal_regulator_count",
+            1.0,
+            None,
+            "Number of thermal regulators exposed",
+        ));
+    }
+    for (index, regulator_id) in thermal_regulator_ids.iter().enumerate() {
+        let base = thermal_block_base(index);
+        registers.extend([
+            RegisterDescriptor::new(
+                base + THERMAL_TEMPERATURE_OFFSET,
+                Input,
+                &format!("thermal.{}.temperature", regulator_id),
+                THERMAL_SCALE_FACTOR,
+                Some("°C"),
+                &format!("Current temperature of regulator '{}'", regulator_id),
+            )
+            .signed(),
+            RegisterDescriptor::new(
+                base + THERMAL_SETPOINT_OFFSET,
+                Input,
+                &format!("thermal.{}.setpoint", regulator_id),
+                THERMAL_SCALE_FACTOR,
+                Some("°C"),
+                &format!("Current setpoint of regulator '{}'", regulator_id),
+            )
+            .signed(),
+            RegisterDescriptor::new(
+                base + THERMAL_CONTROL_OUTPUT_OFFSET,
+                Input,
+                &format!("thermal.{}.control_output", regulator_id),
+                THERMAL_SCALE_FACTOR,
+                Some("%"),
+                &format!("Control output of regulator '{}'", regulator_id),
+            )
+            .signed(),
+            RegisterDescriptor::new(
+                base + THERMAL_FAULT_OFFSET,
+                Input,
+                &format!("thermal.{}.fault", regulator_id),
+                1.0,
+                None,
+                &format!("Fault bit of regulator '{}' (0=ok, 1=fault)", regulator_id),
+            ),
+        ]);
+    }
+
+    registers.extend([
+        RegisterDescriptor::new(
+            MEASUREMENT_INTERVAL_REGISTER,
+            Holding,
+            "measurement_interval",
+            1.0,
+            Some("s"),
+            "Measurement interval (1-3600)",
+        )
+        .with_default(10),
+        RegisterDescriptor::new(
+            AVERAGING_COUNT_REGISTER,
+            Holding,
+            "averaging_count",
+            1.0,
+            Some("samples"),
+            "Averaging count (1-100)",
+        )
+        .with_default(20),
+        RegisterDescriptor::new(
+            GAIN_REGISTER,
+            Holding,
+            "gain",
+            1.0,
+            None,
+            "Gain setting (0-100)",
+        )
+        .with_default(30),
+        RegisterDescriptor::new(
+            FILTER_STRENGTH_REGISTER,
+            Holding,
+            "filter_strength",
+            1.0,
+            None,
+            "Filter strength (0-100)",
+        )
+        .with_default(40),
+    ]);
+
+    for (index, regulator_id) in thermal_regulator_ids.iter().enumerate() {
+        registers.push(
+            RegisterDescriptor::new(
+                thermal_block_base(index) + THERMAL_SETPOINT_OFFSET,
+                Holding,
+                &format!("thermal.{}.setpoint", regulator_id),
+                THERMAL_SCALE_FACTOR,
+                Some("°C"),
+                &format!(
+                    "Setpoint command for regulator '{}', limited to its safety range",
+                    regulator_id
+                ),
+            )
+            .signed(),
+        );
+    }
+
+    registers
+}
+
+/// Descriptors of the measurement input registers for the given encoding
+fn measurement_registers(encoding: MeasurementEncoding) -> Vec<RegisterDescriptor> {
+    use RegisterDataType::{Float32, Uint32};
+    use RegisterType::Input;
+
+    if encoding.value_encoding == ModbusValueEncoding::Float32 {
+        let word_order = encoding.word_order;
+        return vec![
+            RegisterDescriptor::new(
+                FLOAT32_FREQUENCY_REGISTER,
+                Input,
+                "resonance_frequency",
+                1.0,
+                Some("Hz"),
+                "Resonance frequency",
+            )
+            .wide(Float32, word_order),
+            RegisterDescriptor::new(
+                FLOAT32_AMPLITUDE_REGISTER,
+                Input,
+                "signal_amplitude",
+                1.0,
+                None,
+                "Signal amplitude",
+            )
+            .wide(Float32, word_order),
+            RegisterDescriptor::new(
+                FLOAT32_CONCENTRATION_REGISTER,
+                Input,
+                "concentration",
+                1.0,
+                Some("ppm"),
+                "Gas concentration (NaN when not computed)",
+            )
+            .wide(Float32, word_order),
+            RegisterDescriptor::new(
+                FLOAT32_TIMESTAMP_REGISTER,
+                Input,
+                "timestamp",
+                1.0,
+                Some("s"),
+                "Measurement timestamp, UNIX epoch seconds",
+            )
+            .wide(Uint32, word_order),
+            RegisterDescriptor::new(
+                FLOAT32_STATUS_REGISTER,
+                Input,
+                "status",
+                1.0,
+                None,
+                "Status code (0=normal, 1=warning, 2=error)",
+            ),
+        ];
+    }
+
+    vec![
+        RegisterDescriptor::new(
+            FREQUENCY_REGISTER,
+            Input,
+            "resonance_frequency",
+            FREQUENCY_SCALE,
+            Some("Hz"),
+            "Resonance frequency (0.1 Hz resolution)",
+        ),
+        RegisterDescriptor::new(
+            AMPLITUDE_REGISTER,
+            Input,
+            "signal_amplitude",
+            AMPLITUDE_SCALE,
+            None,
+            "Signal amplitude (0.001 resolution)",
+        ),
+        RegisterDescriptor::new(
+            CONCENTRATION_REGISTER,
+            Input,
+            "concentration",
+            CONCENTRATION_SCALE,
+            Some("ppm"),
+            "Gas concentration (0.1 ppm resolution)",
+        ),
+        RegisterDescriptor::new(
+            TIMESTAMP_LOW_REGISTER,
+            Input,
+            "timestamp_low",
+            1.0,
+            Some("s"),
+            "Measurement timestamp, low word of the UNIX epoch seconds",
+        ),
+        RegisterDescriptor::new(
+            TIMESTAMP_HIGH_REGISTER,
+            Input,
+            "timestamp_high",
+            1.0,
+            Some("s"),
+            "Measurement timestamp, high word of the UNIX epoch seconds",
+        ),
+        RegisterDescriptor::new(
+            STATUS_REGISTER,
+            Input,
+            "status",
+            1.0,
+            None,
+            "Status code (0=normal, 1=warning, 2=error)",
+        ),
+    ]
+}
+
+/// Order regulator identifiers the way they are laid out in the register map
+///
+/// Identifiers are sorted alphabetically and truncated to
+/// [`MAX_THERMAL_REGULATORS`].
+pub fn sorted_regulator_ids(regulator_ids: &[String]) -> Vec<String> {
+    let mut regulator_ids = regulator_ids.to_vec();
+    regulator_ids.sort();
+    regulator_ids.truncate(MAX_THERMAL_REGULATORS as usize);
+    regulator_ids
+}
+
+/// Regulators exposed by the Modbus server, in register block order
+///
+/// This is the single source of the thermal block layout: the Modbus server
+/// fills the blocks and the register map API describes them from the same
+/// shared thermal state.
+pub fn exposed_regulator_ids(thermal_state: &SharedThermalRegulationState) -> Vec<String> {
+    sorted_regulator_ids(&thermal_state.get_regulator_ids())
+}
+
+/// First register of the block of the regulator at `index`
+pub fn thermal_block_base(index: usize) -> u16 {
+    THERMAL_REGULATOR_BLOCK_BASE + index as u16 * THERMAL_REGULATOR_BLOCK_SIZE
+}
+
+/// Return the regulator index of a register inside the thermal block range
+pub fn thermal_block_index(addr: u16) -> Option<usize> {
+    let block_end =
+        THERMAL_REGULATOR_BLOCK_BASE + MAX_THERMAL_REGULATORS * THERMAL_REGULATOR_BLOCK_SIZE;
+    (THERMAL_REGULATOR_BLOCK_BASE..block_end)
+        .contains(&addr)
+        .then(|| ((addr - THERMAL_REGULATOR_BLOCK_BASE) / THERMAL_REGULATOR_BLOCK_SIZE) as usize)
+}
+
+/// Return the regulator index if `addr` is a thermal setpoint register
+pub fn thermal_setpoint_index(addr: u16) -> Option<usize> {
+    thermal_block_index(addr).filter(|_| {
+        (addr - THERMAL_REGULATOR_BLOCK_BASE) % THERMAL_REGULATOR_BLOCK_SIZE
+            == THERMAL_SETPOINT_OFFSET
+    })
+}
+
+/// Split a 32-bit value into two registers in the given word order
+pub fn encode_u32(value: u32, word_order: ModbusWordOrder) -> [u16; 2] {
+    let high = (value >> 16) as u16;
+    let low = value as u16;
+    match word_order {
+        ModbusWordOrder::BigEndian => [high, low],
+        ModbusWordOrder::LittleEndian => [low, high],
+    }
+}
+
+/// Rebuild a 32-bit value from two registers in the given word order
+pub fn decode_u32(registers: [u16; 2], word_order: ModbusWordOrder) -> u32 {
+    let (high, low) = match word_order {
+        ModbusWordOrder::BigEndian => (registers[0], registers[1]),
+        ModbusWordOrder::LittleEndian => (registers[1], registers[0]),
+    };
+    ((high as u32) << 16) | low as u32
+}
+
+/// Encode an IEEE-754 32-bit float into two registers in the given word order
+///
+/// Bytes inside each register are always big-endian, as mandated by Modbus.
+pub fn encode_f32(value: f32, word_order: ModbusWordOrder) -> [u16; 2] {
+    encode_u32(value.to_bits(), word_order)
+}
+
+/// Decode an IEEE-754 32-bit float from two registers in the given word order
+pub fn decode_f32(registers: [u16; 2], word_order: ModbusWordOrder) -> f32 {
+    f32::from_bits(decode_u32(registers, word_order))
+}
+
+/// Encode a signed thermal value as a 16-bit two's complement register
+///
+/// The value is multiplied by [`THERMAL_SCALE_FACTOR`] and saturated to the
+/// `i16` range (excluding `i16::MIN`, reserved for
+/// [`THERMAL_VALUE_UNAVAILABLE`]). NaN values are encoded as unavailable.
+pub fn encode_thermal_value(value: f64) -> u16 {
+    if value.is_nan() {
+        return THERMAL_VALUE_UNAVAILABLE;
+    }
+    let scaled = (value * THERMAL_SCALE_FACTOR)
+        .round()
+        .clamp(i16::MIN as f64 + 1.0, i16::MAX as f64);
+    scaled as i16 as u16
+}
+
+/// Decode a 16-bit thermal register back into a floating-point value
+///
+/// Returns `None` when the register holds [`THERMAL_VALUE_UNAVAILABLE`].
+pub fn decode_thermal_value(raw: u16) -> Option<f64> {
+    if raw == THERMAL_VALUE_UNAVAILABLE {
+        return None;
+    }
+    Some(raw as i16 as f64 / THERMAL_SCALE_FACTOR)
+}
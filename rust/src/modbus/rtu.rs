@@ -0,0 +1,143 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Modbus RTU transport
+//!
+//! Serves the [`PhotoacousticModbusServer`] register map as a Modbus RTU slave
+//! on a serial line (typically RS-485). Unlike Modbus TCP, an RTU bus is shared
+//! by several slaves, so every request carries a unit identifier and the
+//! analyzer must stay silent for requests addressed to other devices.
+//!
+//! The register map, scaling and write validation are identical for both
+//! transports: the same [`PhotoacousticModbusServer`] handles the requests.
+
+use std::future;
+
+use anyhow::{Context, Result};
+use log::debug;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_modbus::prelude::*;
+use tokio_modbus::server::rtu::Server;
+use tokio_serial::{SerialPortBuilderExt, SerialStream};
+
+use crate::config::modbus::{ModbusConfig, ModbusParity};
+
+use super::PhotoacousticModbusServer;
+
+/// Unit identifier used by Modbus masters to address every slave at once
+pub const BROADCAST_SLAVE_ID: u8 = 0;
+
+/// A [`PhotoacousticModbusServer`] answering a single unit identifier of an RTU bus.
+///
+/// Requests addressed to another slave are dropped without response.
+/// Broadcast requests (unit identifier 0) are processed but never answered,
+/// as mandated by the Modbus serial line specification.
+pub struct RtuSlaveServer {
+    slave_id: u8,
+    server: PhotoacousticModbusServer,
+}
+
+impl RtuSlaveServer {
+    /// Wrap a register server so that it answers the given unit identifier
+    ///
+    /// ### Parameters
+    ///
+    /// * `slave_id` - Unit identifier of the analyzer on the bus (1-247)
+    /// * `server` - Server holding the register map
+    pub fn new(slave_id: u8, server: PhotoacousticModbusServer) -> Self {
+        Self { slave_id, server }
+    }
+
+    /// Unit identifier answered by this server
+    pub fn slave_id(&self) -> u8 {
+        self.slave_id
+    }
+}
+
+impl tokio_modbus::server::Service for RtuSlaveServer {
+    type Request = SlaveRequest<'static>;
+    type Response = Option<Response>;
+    type Exception = ExceptionCode;
+    type Future = future::Ready<Result<Self::Response, Self::Exception>>;
+
+    fn call(&self, req: Self::Request) -> Self::Future {
+        let SlaveRequest { slave, request } = req;
+
+        if slave == BROADCAST_SLAVE_ID {
+            // Broadcast writes are applied, but the master expects no reply
+            let _ = tokio_modbus::server::Service::call(&self.server, request).into_inner();
+            return future::ready(Ok(None));
+        }
+
+        if slave != self.slave_id {
+            debug!(
+                "Ignoring Modbus RTU request for slave {} (we are slave {})",
+                slave, self.slave_id
+            );
+            return future::ready(Ok(None));
+        }
+
+        future::ready(
+            tokio_modbus::server::Service::call(&self.server, request)
+                .into_inner()
+                .map(Some),
+        )
+    }
+}
+
+/// Open the serial line described by the Modbus configuration
+///
+/// The line is opened with 8 data bits and one stop bit, the parity and
+/// speed being taken from the configuration.
+///
+/// ### Parameters
+///
+/// * `config` - Modbus configuration (`serial_port`, `baud_rate`, `parity`)
+///
+/// ### Returns
+///
+/// The opened serial stream, or an error if the device cannot be opened
+pub fn open_serial_port(config: &ModbusConfig) -> Result<SerialStream> {
+    let parity = match config.parity {
+        ModbusParity::None => tokio_serial::Parity::None,
+        ModbusParity::Even => tokio_serial::Parity::Even,
+        ModbusParity::Odd => tokio_serial::Parity::Odd,
+    };
+
+    tokio_serial::new(&config.serial_port, config.baud_rate)
+        .data_bits(tokio_serial::DataBits::Eight)
+        .stop_bits(tokio_serial::StopBits::One)
+        .parity(parity)
+        .open_native_async()
+        .with_context(|| {
+            format!(
+                "Failed to open Modbus RTU serial port {} at {} bauds",
+                config.serial_port, config.baud_rate
+            )
+        })
+}
+
+/// Serve the register map as a Modbus RTU slave until the transport fails
+///
+/// The transport is usually a [`SerialStream`] returned by [`open_serial_port`],
+/// but any byte stream can be used (e.g. an in-memory duplex in tests).
+///
+/// ### Parameters
+///
+/// * `transport` - Byte stream carrying the RTU frames
+/// * `server` - Server answering the analyzer's unit identifier
+///
+/// ### Returns
+///
+/// `Ok(())` when the transport is closed, or the I/O error that stopped the server
+pub async fn serve_rtu<T>(transport: T, server: RtuSlaveServer) -> Result<()>
+where
+    T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    Server::new(transport)
+        .serve_forever(server)
+        .await
+        .context("Modbus RTU server stopped")?;
+    Ok(())
+}
@@ -34,6 +34,10 @@
 //! - Thread-safe operation
 //! - Configurable sample rates
 //!
+//! With the `simd` feature, the standard filters run the sections of their
+//! cascade in parallel SIMD lanes. `apply_scalar` keeps the scalar loop
+//! available for comparison; both paths produce the same output.
+//!
 //! # Examples
 //!
 //! ## Basic Usage
@@ -53,6 +57,8 @@
 pub mod scipy_butter_filter;
 pub mod scipy_cauer_filter;
 pub mod scipy_cheby_filter;
+#[cfg(feature = "simd")]
+mod simd;
 pub mod standard_filters;
 
 /// Trait for implementing digital filters
@@ -93,6 +99,34 @@ pub trait Filter: Send + Sync {
     /// ```
     fn apply(&self, signal: &[f32]) -> Vec<f32>;
 
+    /// Apply the filter to a signal, writing the filtered samples into `output`
+    ///
+    /// `output` is cleared first and its allocation is reused when it is large
+    /// enough. The processing graph uses it with buffers drawn from its buffer
+    /// pool. The default implementation copies the result of
+    /// [`apply`](Self::apply); the standard filters write directly into
+    /// `output` on their scalar path.
+    ///
+    /// ### Arguments
+    ///
+    /// * `signal` - Input signal samples as a slice of f32 values
+    /// * `output` - Buffer receiving the filtered samples
+    ///
+    /// ### Examples
+    ///
+    /// ```no_run
+    /// use rust_photoacoustic::preprocessing::filter::{Filter, standard_filters::LowpassFilter};
+    ///
+    /// let filter = LowpassFilter::new(1000.0);
+    /// let mut output = Vec::with_capacity(1024);
+    /// filter.apply_into(&[1.0, 0.5, -0.3, 0.8, -0.2], &mut output);
+    /// assert_eq!(output.len(), 5);
+    /// ```
+    fn apply_into(&self, signal: &[f32], output: &mut Vec<f32>) {
+        output.clear();
+        output.extend_from_slice(&self.apply(signal));
+    }
+
     /// Update filter configuration with new parameters
     ///
     /// This method allows dynamic reconfiguration of filter parameters without
@@ -0,0 +1,214 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! SIMD inner loops of the standard IIR filters
+//!
+//! An IIR section depends on its own previous output, so the samples of one
+//! section cannot be computed in parallel. The sections of a cascade can: the
+//! kernels below run up to four cascaded sections in the four lanes of an
+//! [`f32x4`], lane `k` processing sample `n - k` while lane 0 processes sample
+//! `n`. Each lane receives the output of the previous lane from the previous
+//! step, so a cascade of four sections costs one vector step per sample
+//! instead of four scalar ones. Longer cascades are processed four sections at
+//! a time.
+//!
+//! Each lane performs the same operations in the same order as the scalar
+//! loop of its section, so the results match the scalar path. During the
+//! first and last steps of a block, the lanes that have no sample to process
+//! keep their state unchanged.
+//!
+//! On targets without SIMD instructions, [`wide`] falls back to plain arrays
+//! and the kernels still produce the same output.
+
+use super::standard_filters::{BiquadCoeffs, BiquadState};
+use wide::f32x4;
+
+/// Number of sections processed in parallel
+const LANES: usize = 4;
+
+/// Mask of the lanes holding a sample at step `step` of a block of `len` samples
+///
+/// Lane `k` processes sample `step - k`.
+fn active_lanes(step: usize, len: usize) -> f32x4 {
+    let all = f32::from_bits(u32::MAX);
+    let mut mask = [0.0; LANES];
+    for (lane, value) in mask.iter_mut().enumerate() {
+        if lane <= step && step - lane < len {
+            *value = all;
+        }
+    }
+    f32x4::from(mask)
+}
+
+/// Run `sections` pipelined sections over `signal`
+///
+/// `step` receives the lane inputs and, at the edges of the block, the mask of
+/// the active lanes. It returns the lane outputs.
+///
+/// ### Arguments
+///
+/// * `signal` - Input of the first section
+/// * `sections` - Number of sections in use, from 1 to 4
+/// * `step` - Computes one step of all lanes
+///
+/// ### Returns
+///
+/// The output of the last section
+fn run_pipeline<F>(signal: &[f32], sections: usize, mut step: F) -> Vec<f32>
+where
+    F: FnMut(f32x4, Option<f32x4>) -> f32x4,
+{
+    let len = signal.len();
+    let mut output = Vec::with_capacity(len);
+    if len == 0 {
+        return output;
+    }
+
+    let latency = sections - 1;
+    let mut lanes = [0.0; LANES];
+    for n in 0..len + latency {
+        let x = signal.get(n).copied().unwrap_or(0.0);
+        let input = f32x4::from([x, lanes[0], lanes[1], lanes[2]]);
+        let mask = if n >= latency && n < len {
+            None
+        } else {
+            Some(active_lanes(n, len))
+        };
+        lanes = step(input, mask).to_array();
+        if n >= latency {
+            output.push(lanes[latency]);
+        }
+    }
+    output
+}
+
+/// Keep the lanes of `old` outside of `mask`
+fn select(mask: Option<f32x4>, new: f32x4, old: f32x4) -> f32x4 {
+    match mask {
+        Some(mask) => mask.blend(new, old),
+        None => new,
+    }
+}
+
+/// Replace non-finite lanes with zero
+fn finite_or_zero(value: f32x4) -> f32x4 {
+    value.is_finite().blend(value, f32x4::ZERO)
+}
+
+/// Gather one field of up to four sections into a vector
+fn gather<T>(items: &[T], field: impl Fn(&T) -> f32) -> f32x4 {
+    let mut lanes = [0.0; LANES];
+    for (lane, item) in lanes.iter_mut().zip(items) {
+        *lane = field(item);
+    }
+    f32x4::from(lanes)
+}
+
+/// Apply a cascade of Direct Form II Transposed biquad sections
+///
+/// ### Arguments
+///
+/// * `coeffs` - Coefficients of each section
+/// * `states` - State of each section, updated in place
+/// * `signal` - Input samples
+///
+/// ### Returns
+///
+/// The output of the last section
+pub(crate) fn biquad_cascade(
+    coeffs: &[BiquadCoeffs],
+    states: &mut [BiquadState],
+    signal: &[f32],
+) -> Vec<f32> {
+    let mut samples = signal.to_vec();
+    for (coeffs, states) in coeffs.chunks(LANES).zip(states.chunks_mut(LANES)) {
+        let b0 = gather(coeffs, |c| c.b0);
+        let b1 = gather(coeffs, |c| c.b1);
+        let b2 = gather(coeffs, |c| c.b2);
+        let a1 = gather(coeffs, |c| c.a1);
+        let a2 = gather(coeffs, |c| c.a2);
+        let mut z1 = gather(states, |s| s.z1);
+        let mut z2 = gather(states, |s| s.z2);
+
+        samples = run_pipeline(&samples, coeffs.len(), |x, mask| {
+            let y = b0 * x + z1;
+            z1 = select(mask, b1 * x - a1 * y + z2, z1);
+            z2 = select(mask, b2 * x - a2 * y, z2);
+            y
+        });
+
+        let (z1, z2) = (z1.to_array(), z2.to_array());
+        for (lane, state) in states.iter_mut().enumerate() {
+            state.z1 = z1[lane];
+            state.z2 = z2[lane];
+        }
+    }
+    samples
+}
+
+/// Apply `order` cascaded first-order lowpass stages `y = αx + (1 - α)y[n-1]`
+///
+/// The stages start from a zero state and non-finite outputs are replaced
+/// with zero.
+///
+/// ### Arguments
+///
+/// * `alpha` - Smoothing coefficient of each stage
+/// * `order` - Number of stages
+/// * `signal` - Input samples, already clamped
+///
+/// ### Returns
+///
+/// The output of the last stage
+pub(crate) fn lowpass_cascade(alpha: f32, order: usize, signal: &[f32]) -> Vec<f32> {
+    let gain = f32x4::splat(alpha);
+    let feedback = f32x4::splat(1.0 - alpha);
+    let mut samples = signal.to_vec();
+    let mut remaining = order;
+    while remaining > 0 {
+        let sections = remaining.min(LANES);
+        let mut prev = f32x4::ZERO;
+        samples = run_pipeline(&samples, sections, |x, mask| {
+            let y = finite_or_zero(gain * x + feedback * prev);
+            prev = select(mask, y, prev);
+            y
+        });
+        remaining -= sections;
+    }
+    samples
+}
+
+/// Apply `order` cascaded first-order highpass stages `y = αy[n-1] + (x - x[n-1])`
+///
+/// Every stage starts from the state `x[n-1] = y[n-1] = initial` and
+/// non-finite outputs are replaced with zero.
+///
+/// ### Arguments
+///
+/// * `alpha` - Pole of each stage
+/// * `order` - Number of stages
+/// * `initial` - Initial input and output of every stage
+/// * `signal` - Input samples, already clamped
+///
+/// ### Returns
+///
+/// The output of the last stage
+pub(crate) fn highpass_cascade(alpha: f32, order: usize, initial: f32, signal: &[f32]) -> Vec<f32> {
+    let pole = f32x4::splat(alpha);
+    let mut samples = signal.to_vec();
+    let mut remaining = order;
+    while remaining > 0 {
+        let sections = remaining.min(LANES);
+        let mut x_prev = f32x4::splat(initial);
+        let mut y_prev = f32x4::splat(initial);
+        samples = run_pipeline(&samples, sections, |x, mask| {
+            let y = finite_or_zero(pole * y_prev + (x - x_prev));
+            x_prev = select(mask, x, x_prev);
+            y_prev = select(mask, y, y_prev);
+            y
+        });
+        remaining -= sections;
+    }
+    samples
+}
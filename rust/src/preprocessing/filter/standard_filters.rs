@@ -55,19 +55,19 @@ pub struct BandpassFilter {
 
 /// Coefficients for a single biquad section
 #[derive(Clone, Debug)]
-struct BiquadCoeffs {
-    b0: f32,
-    b1: f32,
-    b2: f32, // Feedforward coefficients
-    a1: f32,
-    a2: f32, // Feedback coefficients (a0 normalized to 1)
+pub(super) struct BiquadCoeffs {
+    pub(super) b0: f32,
+    pub(super) b1: f32,
+    pub(super) b2: f32, // Feedforward coefficients
+    pub(super) a1: f32,
+    pub(super) a2: f32, // Feedback coefficients (a0 normalized to 1)
 }
 
 /// State variables for a single biquad section (Direct Form II Transposed)
 #[derive(Clone, Debug)]
-struct BiquadState {
-    z1: f32, // First delay element
-    z2: f32, // Second delay element
+pub(super) struct BiquadState {
+    pub(super) z1: f32, // First delay element
+    pub(super) z2: f32, // Second delay element
 }
 
 impl BandpassFilter {
@@ -398,14 +398,12 @@ impl BandpassFilter {
             }
         }
     }
-}
 
-impl Filter for BandpassFilter {
-    /// Apply the bandpass filter to a signal
+    /// Apply the filter with the scalar sample loop
     ///
-    /// Processes the input signal through cascaded biquad sections using the
-    /// Direct Form II Transposed structure. This implementation provides good
-    /// numerical stability and low coefficient sensitivity.
+    /// This is the reference implementation of [`Filter::apply`], used when the
+    /// `simd` feature is disabled or the filter has a single biquad section. It
+    /// shares the section states with `apply`.
     ///
     /// ### Arguments
     ///
@@ -414,32 +412,21 @@ impl Filter for BandpassFilter {
     /// ### Returns
     ///
     /// A new vector containing the filtered signal samples with the same length as input
-    ///
-    /// ### Examples
-    ///
-    /// ```no_run
-    /// use rust_photoacoustic::preprocessing::filter::{Filter, standard_filters::BandpassFilter};
-    /// use std::f32::consts::PI;
-    ///
-    /// let filter = BandpassFilter::new(1000.0, 200.0);
-    ///
-    /// // Generate a test signal
-    /// let mut input = Vec::new();
-    /// for i in 0..100 {
-    ///     let t = i as f32 / 48000.0;
-    ///     input.push((2.0 * PI * 1000.0 * t).sin());
-    /// }
-    ///
-    /// let output = filter.apply(&input);
-    /// assert_eq!(output.len(), input.len());
-    /// ```
-    fn apply(&self, signal: &[f32]) -> Vec<f32> {
+    pub fn apply_scalar(&self, signal: &[f32]) -> Vec<f32> {
         let mut filtered = Vec::with_capacity(signal.len());
+        self.apply_scalar_into(signal, &mut filtered);
+        filtered
+    }
+
+    /// Apply the filter with the scalar sample loop, writing into `filtered`
+    fn apply_scalar_into(&self, signal: &[f32], filtered: &mut Vec<f32>) {
+        filtered.clear();
 
         // Ensure we have calculated coefficients
         if self.biquad_coeffs.is_empty() {
             // Return the original signal if no coefficients are available
-            return signal.to_vec();
+            filtered.extend_from_slice(signal);
+            return;
         }
 
         // Acquire write lock on states
@@ -467,8 +454,61 @@ impl Filter for BandpassFilter {
 
             filtered.push(y);
         }
+    }
+}
 
-        filtered
+impl Filter for BandpassFilter {
+    /// Apply the bandpass filter to a signal
+    ///
+    /// Processes the input signal through cascaded biquad sections using the
+    /// Direct Form II Transposed structure. This implementation provides good
+    /// numerical stability and low coefficient sensitivity.
+    ///
+    /// ### Arguments
+    ///
+    /// * `signal` - Input signal samples as a slice of f32 values
+    ///
+    /// ### Returns
+    ///
+    /// A new vector containing the filtered signal samples with the same length as input
+    ///
+    /// ### Examples
+    ///
+    /// ```no_run
+    /// use rust_photoacoustic::preprocessing::filter::{Filter, standard_filters::BandpassFilter};
+    /// use std::f32::consts::PI;
+    ///
+    /// let filter = BandpassFilter::new(1000.0, 200.0);
+    ///
+    /// // Generate a test signal
+    /// let mut input = Vec::new();
+    /// for i in 0..100 {
+    ///     let t = i as f32 / 48000.0;
+    ///     input.push((2.0 * PI * 1000.0 * t).sin());
+    /// }
+    ///
+    /// let output = filter.apply(&input);
+    /// assert_eq!(output.len(), input.len());
+    /// ```
+    fn apply(&self, signal: &[f32]) -> Vec<f32> {
+        #[cfg(feature = "simd")]
+        if self.biquad_coeffs.len() > 1 {
+            let mut states = self.biquad_states.write().unwrap();
+            return super::simd::biquad_cascade(&self.biquad_coeffs, &mut states, signal);
+        }
+
+        self.apply_scalar(signal)
+    }
+
+    fn apply_into(&self, signal: &[f32], output: &mut Vec<f32>) {
+        #[cfg(feature = "simd")]
+        if self.biquad_coeffs.len() > 1 {
+            // The SIMD kernel allocates its own output
+            *output = self.apply(signal);
+            return;
+        }
+
+        self.apply_scalar_into(signal, output);
     }
 
     fn update_config(&mut self, parameters: &serde_json::Value) -> anyhow::Result<bool> {
@@ -714,6 +754,59 @@ impl LowpassFilter {
 
         Ok(updated)
     }
+
+    /// Apply the filter with the scalar sample loop
+    ///
+    /// This is the reference implementation of [`Filter::apply`], used when the
+    /// `simd` feature is disabled or the filter has a single stage.
+    ///
+    /// ### Arguments
+    ///
+    /// * `signal` - Input signal samples as a slice of f32 values
+    ///
+    /// ### Returns
+    ///
+    /// A new vector containing the filtered signal samples with high frequencies attenuated
+    pub fn apply_scalar(&self, signal: &[f32]) -> Vec<f32> {
+        let mut filtered = Vec::with_capacity(signal.len());
+        self.apply_scalar_into(signal, &mut filtered);
+        filtered
+    }
+
+    /// Apply the filter with the scalar sample loop, writing into `filtered`
+    ///
+    /// The stages run one after the other over the whole block, in place in
+    /// `filtered`. Each sample goes through the same operations as in a
+    /// sample-by-sample loop, so the output does not depend on the loop order.
+    fn apply_scalar_into(&self, signal: &[f32], filtered: &mut Vec<f32>) {
+        // Cascaded first-order IIR lowpass filter implementation
+        filtered.clear();
+
+        // Calculate filter coefficient based on cutoff frequency
+        let omega_c = 2.0 * std::f32::consts::PI * self.cutoff_freq / self.sample_rate as f32;
+        let alpha = omega_c / (omega_c + 1.0); // More stable coefficient calculation
+
+        // Clamp input to prevent overflow
+        filtered.extend(signal.iter().map(|sample| sample.clamp(-1e6, 1e6)));
+
+        // Process through each cascade stage, starting from a zero state
+        for _ in 0..self.order {
+            let mut prev_sample = 0.0;
+            for sample in filtered.iter_mut() {
+                let filtered_sample = alpha * *sample + (1.0 - alpha) * prev_sample;
+
+                // Ensure output is finite
+                let final_sample = if filtered_sample.is_finite() {
+                    filtered_sample
+                } else {
+                    0.0
+                };
+
+                prev_sample = final_sample;
+                *sample = final_sample;
+            }
+        }
+    }
 }
 
 impl Filter for LowpassFilter {
@@ -759,43 +852,26 @@ impl Filter for LowpassFilter {
     /// assert_eq!(output.len(), input.len());
     /// ```
     fn apply(&self, signal: &[f32]) -> Vec<f32> {
-        // Cascaded first-order IIR lowpass filter implementation
-        let mut filtered = Vec::with_capacity(signal.len());
-
-        if signal.is_empty() {
-            return filtered;
+        #[cfg(feature = "simd")]
+        if self.order > 1 {
+            let omega_c = 2.0 * std::f32::consts::PI * self.cutoff_freq / self.sample_rate as f32;
+            let alpha = omega_c / (omega_c + 1.0);
+            let clamped: Vec<f32> = signal.iter().map(|s| s.clamp(-1e6, 1e6)).collect();
+            return super::simd::lowpass_cascade(alpha, self.order, &clamped);
         }
 
-        // Calculate filter coefficient based on cutoff frequency
-        let omega_c = 2.0 * std::f32::consts::PI * self.cutoff_freq / self.sample_rate as f32;
-        let alpha = omega_c / (omega_c + 1.0); // More stable coefficient calculation
-
-        // Initialize state variables for each cascade stage
-        let mut prev_samples = vec![0.0; self.order];
-
-        for &sample in signal {
-            // Clamp input to prevent overflow
-            let mut current_sample = sample.clamp(-1e6, 1e6);
-
-            // Process through each cascade stage
-            for stage in 0..self.order {
-                let filtered_sample = alpha * current_sample + (1.0 - alpha) * prev_samples[stage];
-
-                // Ensure output is finite
-                let final_sample = if filtered_sample.is_finite() {
-                    filtered_sample
-                } else {
-                    0.0
-                };
-
-                prev_samples[stage] = final_sample;
-                current_sample = final_sample;
-            }
+        self.apply_scalar(signal)
+    }
 
-            filtered.push(current_sample);
+    fn apply_into(&self, signal: &[f32], output: &mut Vec<f32>) {
+        #[cfg(feature = "simd")]
+        if self.order > 1 {
+            // The SIMD kernel allocates its own output
+            *output = self.apply(signal);
+            return;
         }
 
-        filtered
+        self.apply_scalar_into(signal, output);
     }
 
     fn update_config(&mut self, parameters: &serde_json::Value) -> anyhow::Result<bool> {
@@ -1044,6 +1120,69 @@ impl HighpassFilter {
 
         Ok(updated)
     }
+
+    /// Apply the filter with the scalar sample loop
+    ///
+    /// This is the reference implementation of [`Filter::apply`], used when the
+    /// `simd` feature is disabled or the filter has a single stage.
+    ///
+    /// ### Arguments
+    ///
+    /// * `signal` - Input signal samples as a slice of f32 values
+    ///
+    /// ### Returns
+    ///
+    /// A new vector containing the filtered signal samples with low frequencies and DC removed
+    pub fn apply_scalar(&self, signal: &[f32]) -> Vec<f32> {
+        let mut filtered = Vec::with_capacity(signal.len());
+        self.apply_scalar_into(signal, &mut filtered);
+        filtered
+    }
+
+    /// Apply the filter with the scalar sample loop, writing into `filtered`
+    ///
+    /// The stages run one after the other over the whole block, in place in
+    /// `filtered`. Each sample goes through the same operations as in a
+    /// sample-by-sample loop, so the output does not depend on the loop order.
+    fn apply_scalar_into(&self, signal: &[f32], filtered: &mut Vec<f32>) {
+        // Cascaded first-order RC highpass filter implementation
+        // Each stage: H(z) = (1 - z^-1) / (1 - α*z^-1)
+        filtered.clear();
+
+        if signal.is_empty() {
+            return;
+        }
+
+        // Calculate filter coefficient
+        let omega_c = 2.0 * std::f32::consts::PI * self.cutoff_freq / self.sample_rate as f32;
+        let alpha = (-omega_c).exp(); // Pole location
+
+        // Clamp input to prevent overflow
+        filtered.extend(signal.iter().map(|sample| sample.clamp(-1e6, 1e6)));
+
+        // Every stage starts from the first sample (no previous state), which
+        // passes through unchanged
+        let first_sample = filtered[0];
+
+        // Process the remaining samples through each cascade stage using the
+        // difference equation y[n] = α*y[n-1] + (x[n] - x[n-1])
+        for _ in 0..self.order {
+            let mut x_prev = first_sample; // Previous input sample of this stage
+            let mut y_prev = first_sample; // Previous output sample of this stage
+            for sample in filtered[1..].iter_mut() {
+                let current_sample = *sample;
+                let y_curr = alpha * y_prev + (current_sample - x_prev);
+
+                // Ensure output is finite
+                let final_sample = if y_curr.is_finite() { y_curr } else { 0.0 };
+
+                // Update state variables for this stage
+                x_prev = current_sample;
+                y_prev = final_sample;
+                *sample = final_sample;
+            }
+        }
+    }
 }
 
 impl Filter for HighpassFilter {
@@ -1091,57 +1230,34 @@ impl Filter for HighpassFilter {
     /// assert_eq!(output.len(), input.len());
     /// ```
     fn apply(&self, signal: &[f32]) -> Vec<f32> {
-        // Cascaded first-order RC highpass filter implementation
-        // Each stage: H(z) = (1 - z^-1) / (1 - α*z^-1)
-
-        let mut filtered = Vec::with_capacity(signal.len());
-
-        if signal.is_empty() {
+        #[cfg(feature = "simd")]
+        if self.order > 1 && !signal.is_empty() {
+            let omega_c = 2.0 * std::f32::consts::PI * self.cutoff_freq / self.sample_rate as f32;
+            let alpha = (-omega_c).exp();
+            let clamped: Vec<f32> = signal.iter().map(|s| s.clamp(-1e6, 1e6)).collect();
+            let mut filtered = Vec::with_capacity(signal.len());
+            filtered.push(clamped[0]);
+            filtered.extend(super::simd::highpass_cascade(
+                alpha,
+                self.order,
+                clamped[0],
+                &clamped[1..],
+            ));
             return filtered;
         }
 
-        // Calculate filter coefficient
-        let omega_c = 2.0 * std::f32::consts::PI * self.cutoff_freq / self.sample_rate as f32;
-        let alpha = (-omega_c).exp(); // Pole location
-
-        // Initialize state variables for each cascade stage
-        let mut x_prev = vec![0.0; self.order]; // Previous input sample for each stage
-        let mut y_prev = vec![0.0; self.order]; // Previous output sample for each stage
-
-        // Process first sample (no previous state)
-        let first_sample = signal[0].clamp(-1e6, 1e6);
-
-        // Initialize all stages with the first sample
-        for stage in 0..self.order {
-            x_prev[stage] = first_sample;
-            y_prev[stage] = first_sample;
-        }
-        filtered.push(first_sample);
-
-        // Process remaining samples using difference equation for each stage:
-        // y[n] = α*y[n-1] + (x[n] - x[n-1])
-        for &x_curr in &signal[1..] {
-            let mut current_sample = x_curr.clamp(-1e6, 1e6);
-
-            // Process through each cascade stage
-            for stage in 0..self.order {
-                let y_curr = alpha * y_prev[stage] + (current_sample - x_prev[stage]);
-
-                // Ensure output is finite
-                let final_sample = if y_curr.is_finite() { y_curr } else { 0.0 };
-
-                // Update state variables for this stage
-                x_prev[stage] = current_sample;
-                y_prev[stage] = final_sample;
-
-                // Output of this stage becomes input to next stage
-                current_sample = final_sample;
-            }
+        self.apply_scalar(signal)
+    }
 
-            filtered.push(current_sample);
+    fn apply_into(&self, signal: &[f32], output: &mut Vec<f32>) {
+        #[cfg(feature = "simd")]
+        if self.order > 1 && !signal.is_empty() {
+            // The SIMD kernel allocates its own output
+            *output = self.apply(signal);
+            return;
         }
 
-        filtered
+        self.apply_scalar_into(signal, output);
     }
 
     fn update_config(&mut self, parameters: &serde_json::Value) -> anyhow::Result<bool> {
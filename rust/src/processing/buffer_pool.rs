@@ -0,0 +1,247 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Reusable sample buffers for the processing graph
+//!
+//! Every node of the allocating path returns new `Vec<f32>` buffers and the
+//! graph clones each output for the nodes that read it, so a frame costs
+//! several allocations per node. With buffer reuse enabled (see
+//! [`ProcessingGraph::set_buffer_reuse`](crate::processing::ProcessingGraph::set_buffer_reuse)),
+//! the graph and the nodes draw their buffers from a [`FrameBufferPool`] and
+//! give back the buffers they no longer need. Once the pool holds the buffers
+//! of one frame, the following frames of the same size are processed without
+//! allocating sample buffers.
+//!
+//! A buffer taken from the pool is always empty: its previous content is
+//! cleared, so a buffer reused for a shorter frame cannot leak samples of an
+//! older frame.
+
+use super::nodes::ProcessingData;
+use crate::acquisition::AudioFrame;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Default number of buffers kept by a pool
+pub const DEFAULT_MAX_BUFFERS: usize = 64;
+
+/// Counters of a [`FrameBufferPool`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct BufferPoolStatistics {
+    /// Buffers allocated or grown because the pool had no large enough buffer
+    pub allocations: u64,
+    /// Buffers served from the pool without allocating
+    pub reuses: u64,
+    /// Buffers currently held by the pool
+    pub pooled_buffers: usize,
+}
+
+/// Thread-safe pool of `Vec<f32>` sample buffers
+///
+/// The pool is shared by the nodes of a level executed in parallel, so its
+/// methods take `&self`. It holds at most `max_buffers` buffers; buffers
+/// given back to a full pool are dropped.
+///
+/// ### Examples
+///
+/// ```
+/// use rust_photoacoustic::processing::buffer_pool::FrameBufferPool;
+///
+/// let pool = FrameBufferPool::new(8);
+/// let mut buffer = pool.take(1024);
+/// buffer.extend_from_slice(&[0.5; 1024]);
+/// pool.give(buffer);
+///
+/// // The next buffer of the same size reuses the allocation
+/// let buffer = pool.take(1024);
+/// assert!(buffer.is_empty() && buffer.capacity() >= 1024);
+/// assert_eq!(pool.statistics().allocations, 1);
+/// assert_eq!(pool.statistics().reuses, 1);
+/// ```
+#[derive(Debug)]
+pub struct FrameBufferPool {
+    /// Buffers available for reuse
+    buffers: Mutex<Vec<Vec<f32>>>,
+    /// Maximum number of buffers kept
+    max_buffers: usize,
+    /// Number of allocations made by `take`
+    allocations: AtomicU64,
+    /// Number of buffers served without allocating
+    reuses: AtomicU64,
+}
+
+impl Default for FrameBufferPool {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_BUFFERS)
+    }
+}
+
+impl FrameBufferPool {
+    /// Create an empty pool keeping at most `max_buffers` buffers
+    ///
+    /// ### Arguments
+    ///
+    /// * `max_buffers` - Maximum number of buffers held by the pool
+    pub fn new(max_buffers: usize) -> Self {
+        Self {
+            buffers: Mutex::new(Vec::with_capacity(max_buffers)),
+            max_buffers,
+            allocations: AtomicU64::new(0),
+            reuses: AtomicU64::new(0),
+        }
+    }
+
+    /// Take an empty buffer able to hold `len` samples without reallocating
+    ///
+    /// ### Arguments
+    ///
+    /// * `len` - Number of samples the caller will push
+    ///
+    /// ### Returns
+    ///
+    /// An empty buffer with a capacity of at least `len`
+    pub fn take(&self, len: usize) -> Vec<f32> {
+        let buffer = {
+            let mut buffers = self.buffers.lock().unwrap();
+            // Prefer a buffer that is already large enough
+            match buffers.iter().rposition(|buffer| buffer.capacity() >= len) {
+                Some(index) => Some(buffers.swap_remove(index)),
+                None => buffers.pop(),
+            }
+        };
+
+        match buffer {
+            Some(mut buffer) => {
+                buffer.clear();
+                if buffer.capacity() >= len {
+                    self.reuses.fetch_add(1, Ordering::Relaxed);
+                } else {
+                    buffer.reserve_exact(len);
+                    self.allocations.fetch_add(1, Ordering::Relaxed);
+                }
+                buffer
+            }
+            None => {
+                self.allocations.fetch_add(1, Ordering::Relaxed);
+                Vec::with_capacity(len)
+            }
+        }
+    }
+
+    /// Take a buffer holding a copy of `samples`
+    ///
+    /// ### Arguments
+    ///
+    /// * `samples` - Samples to copy
+    ///
+    /// ### Returns
+    ///
+    /// A pooled buffer with the same content as `samples`
+    pub fn take_copy(&self, samples: &[f32]) -> Vec<f32> {
+        let mut buffer = self.take(samples.len());
+        buffer.extend_from_slice(samples);
+        buffer
+    }
+
+    /// Give a buffer back to the pool
+    ///
+    /// Buffers without capacity and buffers given to a full pool are dropped.
+    ///
+    /// ### Arguments
+    ///
+    /// * `buffer` - Buffer the caller no longer needs
+    pub fn give(&self, buffer: Vec<f32>) {
+        if buffer.capacity() == 0 {
+            return;
+        }
+        let mut buffers = self.buffers.lock().unwrap();
+        if buffers.len() < self.max_buffers {
+            buffers.push(buffer);
+        }
+    }
+
+    /// Copy `data` into pooled buffers
+    ///
+    /// ### Arguments
+    ///
+    /// * `data` - Data to copy
+    ///
+    /// ### Returns
+    ///
+    /// A copy of `data` whose sample buffers come from the pool
+    pub fn clone_data(&self, data: &ProcessingData) -> ProcessingData {
+        match data {
+            ProcessingData::AudioFrame(frame) => ProcessingData::AudioFrame(AudioFrame {
+                channel_a: self.take_copy(&frame.channel_a),
+                channel_b: self.take_copy(&frame.channel_b),
+                sample_rate: frame.sample_rate,
+                timestamp: frame.timestamp,
+                frame_number: frame.frame_number,
+            }),
+            ProcessingData::SingleChannel {
+                samples,
+                sample_rate,
+                timestamp,
+                frame_number,
+            } => ProcessingData::SingleChannel {
+                samples: self.take_copy(samples),
+                sample_rate: *sample_rate,
+                timestamp: *timestamp,
+                frame_number: *frame_number,
+            },
+            ProcessingData::DualChannel {
+                channel_a,
+                channel_b,
+                sample_rate,
+                timestamp,
+                frame_number,
+            } => ProcessingData::DualChannel {
+                channel_a: self.take_copy(channel_a),
+                channel_b: self.take_copy(channel_b),
+                sample_rate: *sample_rate,
+                timestamp: *timestamp,
+                frame_number: *frame_number,
+            },
+            ProcessingData::PhotoacousticResult { signal, metadata } => {
+                ProcessingData::PhotoacousticResult {
+                    signal: self.take_copy(signal),
+                    metadata: metadata.clone(),
+                }
+            }
+        }
+    }
+
+    /// Give the sample buffers of `data` back to the pool
+    ///
+    /// ### Arguments
+    ///
+    /// * `data` - Data the caller no longer needs
+    pub fn recycle(&self, data: ProcessingData) {
+        match data {
+            ProcessingData::AudioFrame(frame) => {
+                self.give(frame.channel_a);
+                self.give(frame.channel_b);
+            }
+            ProcessingData::SingleChannel { samples, .. } => self.give(samples),
+            ProcessingData::DualChannel {
+                channel_a,
+                channel_b,
+                ..
+            } => {
+                self.give(channel_a);
+                self.give(channel_b);
+            }
+            ProcessingData::PhotoacousticResult { signal, .. } => self.give(signal),
+        }
+    }
+
+    /// Get the counters of the pool
+    pub fn statistics(&self) -> BufferPoolStatistics {
+        BufferPoolStatistics {
+            allocations: self.allocations.load(Ordering::Relaxed),
+            reuses: self.reuses.load(Ordering::Relaxed),
+            pooled_buffers: self.buffers.lock().unwrap().len(),
+        }
+    }
+}
@@ -2,17 +2,110 @@
 //!
 //! This module implements a driver for sending display data to external HTTP endpoints via webhooks.
 //! It's useful for integration with web applications, dashboards, or cloud services.
+//!
+//! Requests can be signed with HMAC-SHA256 so the endpoint can authenticate the
+//! sender. The signature covers the request timestamp and body, joined by a dot
+//! (`<timestamp>.<body>`), and is sent as `sha256=<hex digest>` along with the
+//! timestamp in Unix seconds. Endpoints recompute it with the shared secret and
+//! reject stale timestamps to prevent replays.
+//!
+//! With a retry queue (see [`with_retry_queue`](HttpsCallbackActionDriver::with_retry_queue)),
+//! callbacks are written to disk before being sent and kept until the endpoint
+//! accepts them, so an outage spanning a restart loses no callback.
 
 use anyhow::Result;
 use async_trait::async_trait;
+use hmac::{Hmac, KeyInit, Mac};
 use log::{info, warn};
-use reqwest::header::HeaderMap;
+use reqwest::header::{HeaderMap, HeaderName, CONTENT_TYPE};
 use serde_json::{json, Value};
+use sha2::Sha256;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::SystemTime;
 
+use super::http_queue::WebhookQueue;
+use super::http_template::PayloadTemplate;
 use super::{ActionDriver, AlertData, MeasurementData};
 
+/// Default header carrying the HMAC signature of the requests
+pub const DEFAULT_SIGNATURE_HEADER: &str = "X-Signature";
+/// Default header carrying the timestamp covered by the signature
+pub const DEFAULT_SIGNATURE_TIMESTAMP_HEADER: &str = "X-Signature-Timestamp";
+
+/// Error status returned by the endpoint on the last attempt of a request
+#[derive(thiserror::Error, Debug)]
+#[error("HTTP request failed after {attempts} attempts: {status} - {body}")]
+struct HttpStatusError {
+    attempts: u32,
+    status: reqwest::StatusCode,
+    body: String,
+}
+
+impl HttpStatusError {
+    /// Whether sending the same request again cannot succeed
+    fn is_permanent(&self) -> bool {
+        self.status.is_client_error()
+            && self.status != reqwest::StatusCode::REQUEST_TIMEOUT
+            && self.status != reqwest::StatusCode::TOO_MANY_REQUESTS
+    }
+}
+
+/// HMAC-SHA256 signing of the requests
+#[derive(Clone)]
+struct HmacSigning {
+    /// Shared secret
+    secret: String,
+    /// Header carrying the signature
+    signature_header: String,
+    /// Header carrying the signed timestamp
+    timestamp_header: String,
+}
+
+impl std::fmt::Debug for HmacSigning {
+    // Keep the secret out of the logs
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HmacSigning")
+            .field("signature_header", &self.signature_header)
+            .field("timestamp_header", &self.timestamp_header)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HmacSigning {
+    /// Check the secret and the header names
+    fn validate(&self) -> Result<()> {
+        anyhow::ensure!(!self.secret.is_empty(), "HMAC secret must not be empty");
+        for header in [&self.signature_header, &self.timestamp_header] {
+            HeaderName::from_bytes(header.as_bytes())
+                .map_err(|e| anyhow::anyhow!("Invalid signature header '{}': {}", header, e))?;
+        }
+        anyhow::ensure!(
+            !self
+                .signature_header
+                .eq_ignore_ascii_case(&self.timestamp_header),
+            "Signature and timestamp headers must differ"
+        );
+        Ok(())
+    }
+
+    /// Signature header value of a body sent at `timestamp` (Unix seconds)
+    fn sign(&self, timestamp: u64, body: &[u8]) -> Result<String> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret.as_bytes())
+            .map_err(|e| anyhow::anyhow!("Invalid HMAC secret: {}", e))?;
+        mac.update(timestamp.to_string().as_bytes());
+        mac.update(b".");
+        mac.update(body);
+        let digest = mac
+            .finalize()
+            .into_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+        Ok(format!("sha256={}", digest))
+    }
+}
+
 /// HTTP/HTTPS callback display driver
 ///
 /// Sends display data to external HTTP endpoints via webhooks.
@@ -33,6 +126,14 @@ pub struct HttpsCallbackActionDriver {
     headers: HashMap<String, String>,
     /// Last known connection status
     connection_status: String,
+    /// Template shaping the measurement payloads, instead of the default structure
+    payload_template: Option<PayloadTemplate>,
+    /// HMAC signing of the requests
+    signing: Option<HmacSigning>,
+    /// Path and capacity of the durable retry queue
+    retry_queue_settings: Option<(PathBuf, usize)>,
+    /// Durable retry queue, opened by `initialize`
+    retry_queue: Option<WebhookQueue>,
 }
 
 impl HttpsCallbackActionDriver {
@@ -49,6 +150,10 @@ impl HttpsCallbackActionDriver {
             timeout_seconds: 10,
             headers: HashMap::new(),
             connection_status: "Initializing".to_string(),
+            payload_template: None,
+            signing: None,
+            retry_queue_settings: None,
+            retry_queue: None,
         }
     }
 
@@ -89,8 +194,182 @@ impl HttpsCallbackActionDriver {
         self
     }
 
+    /// Shape the measurement payloads with a template
+    ///
+    /// The template is a JSON object whose strings may contain `{{field}}`
+    /// placeholders (see [`PayloadTemplate`]). It is validated by `initialize`.
+    /// Alerts and clear requests keep the default structure.
+    ///
+    /// # Arguments
+    /// * `template` - JSON object with `{{field}}` placeholders
+    pub fn with_payload_template(mut self, template: Value) -> Self {
+        self.payload_template = Some(PayloadTemplate::new(template));
+        self
+    }
+
+    /// Sign the requests with HMAC-SHA256
+    ///
+    /// The signature is sent in the `X-Signature` header and the signed
+    /// timestamp in `X-Signature-Timestamp`, unless other headers are set with
+    /// [`with_signature_headers`](Self::with_signature_headers).
+    ///
+    /// # Arguments
+    /// * `secret` - Secret shared with the endpoint
+    pub fn with_hmac_secret(mut self, secret: impl Into<String>) -> Self {
+        let (signature_header, timestamp_header) = match self.signing.take() {
+            Some(signing) => (signing.signature_header, signing.timestamp_header),
+            None => (
+                DEFAULT_SIGNATURE_HEADER.to_string(),
+                DEFAULT_SIGNATURE_TIMESTAMP_HEADER.to_string(),
+            ),
+        };
+        self.signing = Some(HmacSigning {
+            secret: secret.into(),
+            signature_header,
+            timestamp_header,
+        });
+        self
+    }
+
+    /// Set the headers carrying the HMAC signature and its timestamp
+    ///
+    /// Only used when a secret is set with [`with_hmac_secret`](Self::with_hmac_secret).
+    ///
+    /// # Arguments
+    /// * `signature_header` - Header carrying the signature (e.g., "X-Hub-Signature-256")
+    /// * `timestamp_header` - Header carrying the signed timestamp
+    pub fn with_signature_headers(
+        mut self,
+        signature_header: impl Into<String>,
+        timestamp_header: impl Into<String>,
+    ) -> Self {
+        let signing = self.signing.get_or_insert_with(|| HmacSigning {
+            secret: String::new(),
+            signature_header: String::new(),
+            timestamp_header: String::new(),
+        });
+        signing.signature_header = signature_header.into();
+        signing.timestamp_header = timestamp_header.into();
+        self
+    }
+
+    /// Keep the callbacks in a durable queue until the endpoint accepts them
+    ///
+    /// Each callback is written to the queue file before it is sent, and
+    /// removed once the endpoint accepts it. Callbacks the endpoint could not
+    /// receive stay queued and are sent again, oldest first, before the next
+    /// callback and when the driver is initialized, including after a restart.
+    /// Callbacks refused with a client error (4xx other than 408 and 429) are
+    /// discarded so they do not block the queue. The callback methods succeed
+    /// once the callback is queued, even if it could not be sent yet.
+    ///
+    /// # Arguments
+    /// * `path` - Queue file (e.g., "/var/lib/photoacoustic/callbacks.jsonl")
+    /// * `capacity` - Maximum number of pending callbacks, the oldest being dropped beyond
+    pub fn with_retry_queue(mut self, path: impl Into<PathBuf>, capacity: usize) -> Self {
+        self.retry_queue_settings = Some((path.into(), capacity));
+        self
+    }
+
+    /// Open the retry queue if it is configured and not open yet
+    ///
+    /// # Returns
+    /// * `Ok(true)` - The queue has just been opened
+    /// * `Ok(false)` - No queue is configured, or it is already open
+    /// * `Err(anyhow::Error)` - The queue file cannot be read or written
+    fn open_retry_queue(&mut self) -> Result<bool> {
+        match self.retry_queue_settings {
+            Some((ref path, capacity)) if self.retry_queue.is_none() => {
+                let queue = WebhookQueue::open(path.clone(), capacity)?;
+                if queue.stats().pending > 0 {
+                    info!(
+                        "HttpsCallbackActionDriver: {} callbacks pending in {}",
+                        queue.stats().pending,
+                        path.display()
+                    );
+                }
+                self.retry_queue = Some(queue);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    // Send a callback, through the retry queue when one is configured
+    async fn deliver(&mut self, payload: Value, annotate_retries: bool) -> Result<()> {
+        if self.retry_queue_settings.is_none() {
+            return self.send_with_retry(&payload, annotate_retries).await;
+        }
+
+        self.open_retry_queue()?;
+        let queued = match self.retry_queue.as_mut() {
+            Some(queue) => queue.push(payload.clone(), annotate_retries),
+            None => Err(anyhow::anyhow!("Retry queue is not open")),
+        };
+        if let Err(e) = queued {
+            // Without a durable copy, the callback is only sent once
+            warn!(
+                "HttpsCallbackActionDriver: Cannot queue callback, sending it directly: {}",
+                e
+            );
+            return self.send_with_retry(&payload, annotate_retries).await;
+        }
+        self.drain_retry_queue().await
+    }
+
+    // Send the queued callbacks, oldest first, until the queue is empty or
+    // the endpoint cannot be reached
+    async fn drain_retry_queue(&mut self) -> Result<()> {
+        while let Some(callback) = self
+            .retry_queue
+            .as_ref()
+            .and_then(|queue| queue.front().cloned())
+        {
+            match self
+                .send_with_retry(&callback.payload, callback.annotate_retries)
+                .await
+            {
+                Ok(()) => {
+                    if let Some(queue) = self.retry_queue.as_mut() {
+                        queue.delivered(callback.id)?;
+                    }
+                }
+                Err(e)
+                    if e.downcast_ref::<HttpStatusError>()
+                        .is_some_and(HttpStatusError::is_permanent) =>
+                {
+                    warn!(
+                        "HttpsCallbackActionDriver: Discarding callback {} refused by {}: {}",
+                        callback.id, self.url, e
+                    );
+                    if let Some(queue) = self.retry_queue.as_mut() {
+                        queue.rejected(callback.id)?;
+                    }
+                }
+                Err(e) => {
+                    warn!(
+                        "HttpsCallbackActionDriver: {} callbacks kept in the retry queue: {}",
+                        self.retry_queue
+                            .as_ref()
+                            .map_or(0, |queue| queue.stats().pending),
+                        e
+                    );
+                    return Ok(());
+                }
+            }
+        }
+        Ok(())
+    }
+
     // Helper to send a payload with retry logic
-    async fn send_with_retry(&mut self, payload: &serde_json::Value) -> Result<()> {
+    //
+    // `annotate_retries` adds a `retry_attempt` field to the retried payloads,
+    // which is left out of templated payloads so they keep the configured shape
+    async fn send_with_retry(
+        &mut self,
+        payload: &serde_json::Value,
+        annotate_retries: bool,
+    ) -> Result<()> {
         let mut attempts = 0;
         let max_attempts = self.retry_count + 1;
 
@@ -106,12 +385,10 @@ impl HttpsCallbackActionDriver {
             headers.insert(reqwest::header::AUTHORIZATION, auth_value.parse()?);
         }
 
-        // Add custom headers
+        // Add custom headers, which may override the content type
+        headers.insert(CONTENT_TYPE, "application/json".parse()?);
         for (key, value) in &self.headers {
-            headers.insert(
-                reqwest::header::HeaderName::from_bytes(key.as_bytes())?,
-                value.parse()?,
-            );
+            headers.insert(HeaderName::from_bytes(key.as_bytes())?, value.parse()?);
         }
 
         loop {
@@ -119,15 +396,32 @@ impl HttpsCallbackActionDriver {
 
             // Add retry info to payload
             let mut payload_with_retry = payload.as_object().unwrap().clone();
-            if attempts > 1 {
+            if annotate_retries && attempts > 1 {
                 payload_with_retry.insert("retry_attempt".into(), attempts.into());
             }
+            let body = serde_json::to_vec(&payload_with_retry)?;
+
+            // Sign each attempt, with a fresh timestamp
+            let mut request_headers = headers.clone();
+            if let Some(ref signing) = self.signing {
+                let timestamp = SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)?
+                    .as_secs();
+                request_headers.insert(
+                    HeaderName::from_bytes(signing.signature_header.as_bytes())?,
+                    signing.sign(timestamp, &body)?.parse()?,
+                );
+                request_headers.insert(
+                    HeaderName::from_bytes(signing.timestamp_header.as_bytes())?,
+                    timestamp.into(),
+                );
+            }
 
             let result = self
                 .client
                 .post(&self.url)
-                .headers(headers.clone())
-                .json(&payload_with_retry)
+                .headers(request_headers)
+                .body(body)
                 .timeout(std::time::Duration::from_secs(self.timeout_seconds))
                 .send()
                 .await;
@@ -146,12 +440,12 @@ impl HttpsCallbackActionDriver {
                         self.connection_status = format!("Error: HTTP {}", status);
 
                         if attempts >= max_attempts {
-                            return Err(anyhow::anyhow!(
-                                "HTTP request failed after {} attempts: {} - {}",
+                            return Err(HttpStatusError {
                                 attempts,
                                 status,
-                                error_text
-                            ));
+                                body: error_text,
+                            }
+                            .into());
                         }
 
                         warn!(
@@ -195,6 +489,15 @@ impl ActionDriver for HttpsCallbackActionDriver {
             ));
         }
 
+        // Validate the payload template and the signing before any request is sent
+        if let Some(ref template) = self.payload_template {
+            template.validate()?;
+        }
+        if let Some(ref signing) = self.signing {
+            signing.validate()?;
+        }
+        let queue_opened = self.open_retry_queue()?;
+
         // Test connection with a health check
         let response = self
             .client
@@ -211,7 +514,6 @@ impl ActionDriver for HttpsCallbackActionDriver {
                     self.url
                 );
                 self.connection_status = "Connected".to_string();
-                Ok(())
             }
             Err(e) => {
                 warn!(
@@ -220,12 +522,36 @@ impl ActionDriver for HttpsCallbackActionDriver {
                 );
                 // Don't fail initialization - the endpoint might not support GET requests
                 self.connection_status = format!("Warning: Initial connection test failed: {}", e);
-                Ok(())
             }
         }
+
+        // Replay the callbacks left pending by a previous run
+        if queue_opened {
+            self.drain_retry_queue().await?;
+        }
+        Ok(())
+    }
+
+    async fn check_connection(&mut self) -> Result<()> {
+        // Any HTTP response, even an error status, proves the endpoint is reachable
+        self.initialize().await?;
+        if self.connection_status == "Connected" {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "Cannot reach {}: {}",
+                self.url,
+                self.connection_status
+            ))
+        }
     }
 
     async fn update_action(&mut self, data: &MeasurementData) -> Result<()> {
+        if let Some(ref template) = self.payload_template {
+            let payload = template.render(data)?;
+            return self.deliver(payload, false).await;
+        }
+
         let payload = json!({
             "type": "display_update",
             "concentration_ppm": data.concentration_ppm,
@@ -236,7 +562,7 @@ impl ActionDriver for HttpsCallbackActionDriver {
             "metadata": data.metadata
         });
 
-        self.send_with_retry(&payload).await
+        self.deliver(payload, true).await
     }
 
     async fn show_alert(&mut self, alert: &AlertData) -> Result<()> {
@@ -249,7 +575,7 @@ impl ActionDriver for HttpsCallbackActionDriver {
             "timestamp": alert.timestamp.duration_since(std::time::UNIX_EPOCH)?.as_secs()
         });
 
-        self.send_with_retry(&payload).await
+        self.deliver(payload, true).await
     }
 
     async fn clear_action(&mut self) -> Result<()> {
@@ -258,7 +584,7 @@ impl ActionDriver for HttpsCallbackActionDriver {
             "timestamp": SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_secs()
         });
 
-        self.send_with_retry(&payload).await
+        self.deliver(payload, true).await
     }
 
     async fn get_status(&self) -> Result<Value> {
@@ -269,10 +595,25 @@ impl ActionDriver for HttpsCallbackActionDriver {
             "retry_count": self.retry_count,
             "connection_status": self.connection_status,
             "has_auth_token": self.auth_token.is_some(),
-            "custom_headers": self.headers.keys().collect::<Vec<_>>()
+            "custom_headers": self.headers.keys().collect::<Vec<_>>(),
+            "payload_template": self.payload_template.as_ref().map(PayloadTemplate::template),
+            "hmac_signing": self.signing.as_ref().map(|signing| json!({
+                "signature_header": signing.signature_header,
+                "timestamp_header": signing.timestamp_header
+            })),
+            "retry_queue": self.retry_queue_settings.as_ref().map(|(path, capacity)| json!({
+                "path": path,
+                "capacity": capacity,
+                "stats": self.retry_queue.as_ref().map(WebhookQueue::stats)
+            }))
         }))
     }
 
+    async fn is_ready(&self) -> bool {
+        // A failed initial GET is tolerated, only failed callbacks count
+        !self.connection_status.starts_with("Error")
+    }
+
     fn driver_type(&self) -> &str {
         "https_callback"
     }
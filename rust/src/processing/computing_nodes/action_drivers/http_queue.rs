@@ -0,0 +1,297 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Durable outbound queue of the HTTPS callback driver
+//!
+//! With a retry queue, a [`HttpsCallbackActionDriver`](super::HttpsCallbackActionDriver)
+//! stores each callback before sending it and removes it once the endpoint
+//! accepted it, so callbacks that could not be delivered before a restart are
+//! sent by the next run.
+//!
+//! The queue file is an append-only log of JSON lines, one record per change:
+//!
+//! ```json
+//! {"op":"enqueue","id":7,"annotate_retries":true,"payload":{"type":"display_update"}}
+//! {"op":"ack","id":7}
+//! ```
+//!
+//! Opening the queue replays the log. Unreadable lines, such as a line cut
+//! short by a crash, are skipped with a warning. Once the log holds more
+//! records of delivered callbacks than pending callbacks, and at least 64 of
+//! them, it is compacted: rewritten with the pending callbacks only, through a
+//! temporary file.
+
+use anyhow::{Context, Result};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Default maximum number of pending callbacks
+pub const DEFAULT_WEBHOOK_QUEUE_CAPACITY: usize = 10_000;
+
+/// Minimum number of delivered records in the log before it is compacted
+const COMPACTION_THRESHOLD: usize = 64;
+
+/// Record of the queue log
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum LogRecord {
+    /// A callback was queued
+    Enqueue {
+        id: u64,
+        annotate_retries: bool,
+        payload: Value,
+    },
+    /// A callback was delivered, rejected or dropped
+    Ack { id: u64 },
+}
+
+/// Callback waiting for delivery
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct QueuedCallback {
+    /// Sequence number of the callback in the queue
+    pub id: u64,
+    /// Whether retried attempts get a `retry_attempt` field
+    pub annotate_retries: bool,
+    /// JSON payload of the callback
+    pub payload: Value,
+}
+
+/// Counters of the queue
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub(super) struct WebhookQueueStats {
+    /// Callbacks waiting for delivery
+    pub pending: usize,
+    /// Callbacks queued since the queue was opened
+    pub enqueued: u64,
+    /// Queued callbacks delivered since the queue was opened
+    pub delivered: u64,
+    /// Queued callbacks refused by the endpoint and discarded
+    pub rejected: u64,
+    /// Callbacks dropped because the queue was full
+    pub dropped: u64,
+    /// Rewrites of the log
+    pub compactions: u64,
+}
+
+/// Bounded append-only queue of callbacks
+#[derive(Debug)]
+pub(super) struct WebhookQueue {
+    path: PathBuf,
+    capacity: usize,
+    file: File,
+    entries: VecDeque<QueuedCallback>,
+    next_id: u64,
+    /// Records in the log, pending or not
+    log_records: usize,
+    stats: WebhookQueueStats,
+}
+
+impl WebhookQueue {
+    /// Open the queue, replaying the log left by a previous run
+    ///
+    /// # Arguments
+    /// * `path` - Queue file, created with its directory if needed
+    /// * `capacity` - Maximum number of pending callbacks, at least 1
+    ///
+    /// # Returns
+    /// * `Ok(WebhookQueue)` - The queue, holding the callbacks pending in the log
+    /// * `Err(anyhow::Error)` - The capacity is 0 or the file cannot be read or written
+    pub fn open(path: impl Into<PathBuf>, capacity: usize) -> Result<Self> {
+        anyhow::ensure!(capacity > 0, "Retry queue capacity must be at least 1");
+        let path = path.into();
+        if let Some(directory) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(directory).with_context(|| {
+                format!(
+                    "Failed to create retry queue directory {}",
+                    directory.display()
+                )
+            })?;
+        }
+
+        let mut entries = VecDeque::new();
+        let mut next_id = 0;
+        if path.exists() {
+            let contents = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read retry queue {}", path.display()))?;
+            for (index, line) in contents.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<LogRecord>(line) {
+                    Ok(LogRecord::Enqueue {
+                        id,
+                        annotate_retries,
+                        payload,
+                    }) => {
+                        next_id = next_id.max(id + 1);
+                        entries.push_back(QueuedCallback {
+                            id,
+                            annotate_retries,
+                            payload,
+                        });
+                    }
+                    Ok(LogRecord::Ack { id }) => entries.retain(|entry| entry.id != id),
+                    Err(e) => warn!(
+                        "Skipping unreadable record at line {} of retry queue {}: {}",
+                        index + 1,
+                        path.display(),
+                        e
+                    ),
+                }
+            }
+        }
+
+        let mut stats = WebhookQueueStats::default();
+        if entries.len() > capacity {
+            let excess = entries.len() - capacity;
+            entries.drain(..excess);
+            stats.dropped = excess as u64;
+        }
+
+        // Start from a compact log holding the pending callbacks only
+        let file = Self::rewrite(&path, &entries)?;
+        stats.pending = entries.len();
+        Ok(Self {
+            path,
+            capacity,
+            file,
+            log_records: entries.len(),
+            entries,
+            next_id,
+            stats,
+        })
+    }
+
+    /// Counters of the queue
+    pub fn stats(&self) -> WebhookQueueStats {
+        self.stats
+    }
+
+    /// Oldest pending callback, the next one to deliver
+    pub fn front(&self) -> Option<&QueuedCallback> {
+        self.entries.front()
+    }
+
+    /// Queue a callback, dropping the oldest one when the queue is full
+    ///
+    /// # Arguments
+    /// * `payload` - JSON payload of the callback
+    /// * `annotate_retries` - Whether retried attempts get a `retry_attempt` field
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - Id of the queued callback, written to disk
+    /// * `Err(anyhow::Error)` - The queue file cannot be written
+    pub fn push(&mut self, payload: Value, annotate_retries: bool) -> Result<u64> {
+        if self.entries.len() == self.capacity {
+            if let Some(oldest) = self.entries.pop_front() {
+                warn!(
+                    "Retry queue {} is full, dropping callback {}",
+                    self.path.display(),
+                    oldest.id
+                );
+                self.append(&LogRecord::Ack { id: oldest.id })?;
+                self.stats.dropped += 1;
+            }
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.append(&LogRecord::Enqueue {
+            id,
+            annotate_retries,
+            payload: payload.clone(),
+        })?;
+        self.entries.push_back(QueuedCallback {
+            id,
+            annotate_retries,
+            payload,
+        });
+        self.stats.enqueued += 1;
+        self.stats.pending = self.entries.len();
+        Ok(id)
+    }
+
+    /// Remove a callback the endpoint accepted
+    pub fn delivered(&mut self, id: u64) -> Result<()> {
+        if self.remove(id)? {
+            self.stats.delivered += 1;
+        }
+        Ok(())
+    }
+
+    /// Remove a callback the endpoint refused, so it does not block the queue
+    pub fn rejected(&mut self, id: u64) -> Result<()> {
+        if self.remove(id)? {
+            self.stats.rejected += 1;
+        }
+        Ok(())
+    }
+
+    fn remove(&mut self, id: u64) -> Result<bool> {
+        let Some(position) = self.entries.iter().position(|entry| entry.id == id) else {
+            return Ok(false);
+        };
+        self.entries.remove(position);
+        self.stats.pending = self.entries.len();
+        self.append(&LogRecord::Ack { id })?;
+
+        let stale_records = self.log_records - self.entries.len();
+        if stale_records >= COMPACTION_THRESHOLD.max(self.entries.len()) {
+            self.compact()?;
+        }
+        Ok(true)
+    }
+
+    /// Append a record to the log and wait until it is on disk
+    fn append(&mut self, record: &LogRecord) -> Result<()> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+        self.file
+            .write_all(&line)
+            .and_then(|()| self.file.sync_data())
+            .with_context(|| format!("Failed to write retry queue {}", self.path.display()))?;
+        self.log_records += 1;
+        Ok(())
+    }
+
+    /// Rewrite the log with the pending callbacks only
+    fn compact(&mut self) -> Result<()> {
+        self.file = Self::rewrite(&self.path, &self.entries)?;
+        self.log_records = self.entries.len();
+        self.stats.compactions += 1;
+        Ok(())
+    }
+
+    /// Replace the log by the enqueue records of `entries`, returning it open for appending
+    fn rewrite(path: &Path, entries: &VecDeque<QueuedCallback>) -> Result<File> {
+        let temporary_path = path.with_extension("tmp");
+        let mut file = File::create(&temporary_path).with_context(|| {
+            format!("Failed to create retry queue {}", temporary_path.display())
+        })?;
+        for entry in entries {
+            serde_json::to_writer(
+                &mut file,
+                &LogRecord::Enqueue {
+                    id: entry.id,
+                    annotate_retries: entry.annotate_retries,
+                    payload: entry.payload.clone(),
+                },
+            )?;
+            file.write_all(b"\n")?;
+        }
+        file.sync_all()?;
+        fs::rename(&temporary_path, path)
+            .with_context(|| format!("Failed to write retry queue {}", path.display()))?;
+
+        OpenOptions::new()
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open retry queue {}", path.display()))
+    }
+}
@@ -0,0 +1,214 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Payload templates of the HTTPS callback driver
+//!
+//! A template is a JSON object shaped like the payload the receiving API
+//! expects. Its strings may contain `{{field}}` placeholders, replaced by the
+//! fields of the measurement:
+//!
+//! | Placeholder | Value |
+//! |---|---|
+//! | `concentration_ppm` | Concentration in ppm |
+//! | `source_node_id` | Node that computed the concentration |
+//! | `peak_amplitude` | Peak amplitude (0.0-1.0) |
+//! | `peak_frequency` | Peak frequency in Hz |
+//! | `timestamp` | Unix timestamp in seconds |
+//! | `timestamp_ms` | Unix timestamp in milliseconds |
+//! | `timestamp_rfc3339` | Timestamp as an RFC 3339 string (UTC) |
+//! | `metadata.<key>` | Metadata value, `null` when absent |
+//!
+//! A string made of a single placeholder is replaced by the value itself,
+//! keeping its JSON type:
+//!
+//! ```json
+//! {"sensor": "{{source_node_id}}", "value": "{{concentration_ppm}}", "unit": "ppm"}
+//! ```
+//!
+//! renders as `{"sensor": "concentration", "value": 12.5, "unit": "ppm"}`.
+//! Placeholders within a longer string are replaced by their text, e.g.
+//! `"{{concentration_ppm}} ppm"` renders as `"12.5 ppm"`.
+
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::time::UNIX_EPOCH;
+
+use super::MeasurementData;
+
+/// Fields of a measurement available as placeholders, besides `metadata.<key>`
+const TEMPLATE_FIELDS: &[&str] = &[
+    "concentration_ppm",
+    "source_node_id",
+    "peak_amplitude",
+    "peak_frequency",
+    "timestamp",
+    "timestamp_ms",
+    "timestamp_rfc3339",
+];
+
+/// Part of a template string
+enum Segment<'a> {
+    /// Literal text
+    Text(&'a str),
+    /// Placeholder name, without the braces
+    Field(&'a str),
+}
+
+/// Template of the measurement payloads sent by a
+/// [`HttpsCallbackActionDriver`](super::HttpsCallbackActionDriver)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PayloadTemplate {
+    template: Value,
+}
+
+impl PayloadTemplate {
+    /// Create a template, checked by [`PayloadTemplate::validate`]
+    ///
+    /// # Arguments
+    /// * `template` - JSON object with `{{field}}` placeholders
+    pub fn new(template: Value) -> Self {
+        Self { template }
+    }
+
+    /// The template as configured
+    pub fn template(&self) -> &Value {
+        &self.template
+    }
+
+    /// Check that the template is an object with well-formed, known placeholders
+    ///
+    /// # Returns
+    /// * `Ok(())` - The template can be rendered
+    /// * `Err(anyhow::Error)` - The template is not an object, a placeholder is
+    ///   not closed or names an unknown field
+    pub fn validate(&self) -> Result<()> {
+        anyhow::ensure!(
+            self.template.is_object(),
+            "Payload template must be a JSON object, got: {}",
+            self.template
+        );
+        validate_value(&self.template, "$")
+    }
+
+    /// Render the payload of a measurement
+    ///
+    /// # Arguments
+    /// * `data` - The measurement
+    ///
+    /// # Returns
+    /// * `Ok(Value)` - The payload
+    /// * `Err(anyhow::Error)` - The template is invalid
+    pub fn render(&self, data: &MeasurementData) -> Result<Value> {
+        render_value(&self.template, data)
+    }
+}
+
+fn validate_value(value: &Value, path: &str) -> Result<()> {
+    match value {
+        Value::String(text) => {
+            for segment in parse(text).map_err(|e| anyhow::anyhow!("{} at {}", e, path))? {
+                if let Segment::Field(name) = segment {
+                    anyhow::ensure!(
+                        is_known_field(name),
+                        "Unknown placeholder '{{{{{}}}}}' at {} (available: {}, metadata.<key>)",
+                        name,
+                        path,
+                        TEMPLATE_FIELDS.join(", ")
+                    );
+                }
+            }
+            Ok(())
+        }
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .try_for_each(|(index, item)| validate_value(item, &format!("{}[{}]", path, index))),
+        Value::Object(entries) => entries
+            .iter()
+            .try_for_each(|(key, item)| validate_value(item, &format!("{}.{}", path, key))),
+        _ => Ok(()),
+    }
+}
+
+fn render_value(value: &Value, data: &MeasurementData) -> Result<Value> {
+    Ok(match value {
+        Value::String(text) => {
+            let segments = parse(text)?;
+            match segments.as_slice() {
+                [Segment::Field(name)] => field_value(name, data)?,
+                _ => {
+                    let mut rendered = String::with_capacity(text.len());
+                    for segment in &segments {
+                        match segment {
+                            Segment::Text(text) => rendered.push_str(text),
+                            Segment::Field(name) => match field_value(name, data)? {
+                                Value::String(text) => rendered.push_str(&text),
+                                other => rendered.push_str(&other.to_string()),
+                            },
+                        }
+                    }
+                    Value::String(rendered)
+                }
+            }
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| render_value(item, data))
+                .collect::<Result<_>>()?,
+        ),
+        Value::Object(entries) => Value::Object(
+            entries
+                .iter()
+                .map(|(key, item)| Ok((key.clone(), render_value(item, data)?)))
+                .collect::<Result<_>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+/// Split a template string into literal text and placeholders
+fn parse(text: &str) -> Result<Vec<Segment<'_>>> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            segments.push(Segment::Text(&rest[..start]));
+        }
+        let end = rest[start + 2..]
+            .find("}}")
+            .ok_or_else(|| anyhow::anyhow!("Unclosed placeholder in '{}'", text))?;
+        segments.push(Segment::Field(rest[start + 2..start + 2 + end].trim()));
+        rest = &rest[start + 2 + end + 2..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    Ok(segments)
+}
+
+fn is_known_field(name: &str) -> bool {
+    TEMPLATE_FIELDS.contains(&name)
+        || name
+            .strip_prefix("metadata.")
+            .is_some_and(|key| !key.is_empty())
+}
+
+fn field_value(name: &str, data: &MeasurementData) -> Result<Value> {
+    let since_epoch = || data.timestamp.duration_since(UNIX_EPOCH);
+    Ok(match name {
+        "concentration_ppm" => json!(data.concentration_ppm),
+        "source_node_id" => json!(data.source_node_id),
+        "peak_amplitude" => json!(data.peak_amplitude),
+        "peak_frequency" => json!(data.peak_frequency),
+        "timestamp" => json!(since_epoch()?.as_secs()),
+        "timestamp_ms" => json!(since_epoch()?.as_millis() as u64),
+        "timestamp_rfc3339" => json!(chrono::DateTime::<chrono::Utc>::from(data.timestamp)
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)),
+        _ => match name.strip_prefix("metadata.") {
+            Some(key) => data.metadata.get(key).cloned().unwrap_or(Value::Null),
+            None => anyhow::bail!("Unknown placeholder '{{{{{}}}}}'", name),
+        },
+    })
+}
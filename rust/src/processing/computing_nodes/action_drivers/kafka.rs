@@ -5,24 +5,48 @@
 //! Kafka display driver implementation
 //!
 //! This module implements a driver for sending display data to Apache Kafka.
-//! It allows publishing concentration and alert data to Kafka topics.
+//! It allows publishing concentration and alert data to Kafka topics, and
+//! optionally consuming control commands from a command topic (see
+//! [`kafka_commands`](super::kafka_commands)).
+//!
+//! Measurements are sent as JSON by default. With
+//! [`KafkaActionDriver::with_avro`], they are Avro records registered in a
+//! Confluent Schema Registry (see [`kafka_avro`](super::kafka_avro)).
 
 use anyhow::Result;
+use apache_avro::Schema;
 use async_trait::async_trait;
 use log::{error, info};
+use rdkafka::consumer::{Consumer, StreamConsumer};
 use rdkafka::message::OwnedMessage;
 use rdkafka::{
-    producer::{FutureProducer, FutureRecord},
+    producer::{FutureProducer, FutureRecord, Producer},
     util::Timeout,
     ClientConfig,
 };
 use serde_json::{json, Value};
 use std::fmt;
-use std::sync::Arc;
+use std::sync::{mpsc, Arc, Mutex};
 use std::time::{Duration, SystemTime};
+use tokio::task::JoinHandle;
 
+use super::kafka_avro::{
+    encode_measurement, measurement_schema, SchemaRegistryClient, MEASUREMENT_AVRO_SCHEMA,
+};
+use super::kafka_commands::{CommandDispatcher, ConsumerLike, KafkaCommandStats, RealConsumer};
 use super::{ActionDriver, AlertData, MeasurementData};
 
+/// Avro encoding settings of a Kafka driver
+#[derive(Debug)]
+struct AvroSettings {
+    /// Registry holding the measurement schema
+    registry: SchemaRegistryClient,
+    /// Registry subject of the measurement schema
+    subject: String,
+    /// Id of the measurement schema and the parsed schema, once registered
+    registered: Option<(u32, Schema)>,
+}
+
 /// Kafka display driver
 ///
 /// Sends display data to Apache Kafka topics.
@@ -43,6 +67,20 @@ pub struct KafkaActionDriver {
     timeout_ms: u64,
     /// Connection status
     connection_status: String,
+    /// Topic to consume control commands from
+    command_topic: Option<String>,
+    /// Consumer group of the command consumer
+    command_group_id: String,
+    /// Kafka consumer wrapper for receiving commands
+    consumer: Option<Arc<dyn ConsumerLike>>,
+    /// Configuration updates for the action node owning the driver
+    config_updates: Option<mpsc::Sender<Value>>,
+    /// Counters of the command consumer
+    command_stats: Arc<Mutex<KafkaCommandStats>>,
+    /// Avro encoding of the measurements, JSON when not set
+    avro: Option<AvroSettings>,
+    /// Task dispatching the received commands
+    command_task: Option<JoinHandle<()>>,
 }
 
 // Manually implement Debug for KafkaActionDriver since FutureProducer doesn't implement Debug
@@ -56,6 +94,10 @@ impl fmt::Debug for KafkaActionDriver {
             .field("client_id", &self.client_id)
             .field("timeout_ms", &self.timeout_ms)
             .field("connection_status", &self.connection_status)
+            .field("command_topic", &self.command_topic)
+            .field("command_group_id", &self.command_group_id)
+            .field("consumer", &self.consumer.is_some())
+            .field("avro", &self.avro)
             .finish()
     }
 }
@@ -80,6 +122,13 @@ impl KafkaActionDriver {
             client_id: format!("photoacoustic-driver-{}", uuid::Uuid::new_v4()),
             timeout_ms: 5000, // Default 5 seconds
             connection_status: "Initializing".to_string(),
+            command_topic: None,
+            command_group_id: "photoacoustic-commands".to_string(),
+            consumer: None,
+            config_updates: None,
+            command_stats: Arc::new(Mutex::new(KafkaCommandStats::default())),
+            command_task: None,
+            avro: None,
         }
     }
 
@@ -101,6 +150,79 @@ impl KafkaActionDriver {
         self
     }
 
+    /// Consume control commands from a topic
+    ///
+    /// # Arguments
+    /// * `topic` - Topic carrying the commands
+    /// * `group_id` - Consumer group committing the processed offsets
+    pub fn with_command_topic(
+        mut self,
+        topic: impl Into<String>,
+        group_id: impl Into<String>,
+    ) -> Self {
+        self.command_topic = Some(topic.into());
+        self.command_group_id = group_id.into();
+        self
+    }
+
+    /// Send the measurements as Avro records instead of JSON
+    ///
+    /// The measurement schema is registered in the registry when the first
+    /// measurement is sent. Alerts stay JSON, and `clear_action` sends nothing
+    /// since the display topic only carries measurements.
+    ///
+    /// # Arguments
+    /// * `registry_url` - Confluent Schema Registry URL (e.g., "http://localhost:8081")
+    /// * `subject` - Registry subject, `<display_topic>-value` if `None`
+    pub fn with_avro(mut self, registry_url: impl Into<String>, subject: Option<String>) -> Self {
+        let subject = subject.unwrap_or_else(|| format!("{}-value", self.display_topic));
+        self.avro = Some(AvroSettings {
+            registry: SchemaRegistryClient::new(registry_url),
+            subject,
+            registered: None,
+        });
+        self
+    }
+
+    // Helper to encode a measurement as an Avro record, registering the schema if needed
+    async fn avro_payload(&mut self, data: &MeasurementData) -> Result<Option<Vec<u8>>> {
+        let Some(avro) = self.avro.as_mut() else {
+            return Ok(None);
+        };
+        if avro.registered.is_none() {
+            let schema = measurement_schema()?;
+            let schema_id = avro
+                .registry
+                .register(&avro.subject, MEASUREMENT_AVRO_SCHEMA)
+                .await?;
+            info!(
+                "KafkaActionDriver: Measurement schema registered as id {} of subject '{}'",
+                schema_id, avro.subject
+            );
+            avro.registered = Some((schema_id, schema));
+        }
+
+        let (schema_id, schema) = avro.registered.as_ref().unwrap();
+        Ok(Some(encode_measurement(schema, *schema_id, data)?))
+    }
+
+    // Helper method to create the command consumer if it doesn't exist
+    fn ensure_consumer(&mut self, topic: &str) -> Result<Arc<dyn ConsumerLike>> {
+        if self.consumer.is_none() {
+            let consumer: StreamConsumer = ClientConfig::new()
+                .set("bootstrap.servers", &self.brokers)
+                .set("client.id", &self.client_id)
+                .set("group.id", &self.command_group_id)
+                .set("enable.auto.commit", "false")
+                .create()?;
+            consumer.subscribe(&[topic])?;
+
+            self.consumer = Some(Arc::new(RealConsumer::new(consumer)));
+        }
+
+        Ok(self.consumer.as_ref().unwrap().clone())
+    }
+
     // Helper method to create a producer if it doesn't exist
     fn ensure_producer(&mut self) -> Result<Arc<dyn ProducerLike>> {
         if self.producer.is_none() {
@@ -120,7 +242,7 @@ impl KafkaActionDriver {
     }
 
     // Helper to send a message to a topic
-    async fn send_to_topic(&mut self, topic: &str, key: &str, payload: &str) -> Result<()> {
+    async fn send_to_topic(&mut self, topic: &str, key: &str, payload: &[u8]) -> Result<()> {
         // Store timeout_ms in a local variable to avoid borrowing self later
         let timeout_ms = self.timeout_ms;
         let producer = self.ensure_producer()?;
@@ -163,13 +285,13 @@ mod tests {
             &self,
             topic: &str,
             key: &str,
-            payload: &str,
+            payload: &[u8],
             _timeout_ms: u64,
         ) -> Result<(), (KafkaError, OwnedMessage)> {
             self.calls.lock().unwrap().push((
                 topic.to_string(),
                 key.to_string(),
-                payload.to_string(),
+                String::from_utf8_lossy(payload).to_string(),
             ));
             Ok(())
         }
@@ -245,9 +367,16 @@ pub trait ProducerLike: Send + Sync {
         &self,
         topic: &str,
         key: &str,
-        payload: &str,
+        payload: &[u8],
         timeout_ms: u64,
     ) -> Result<(), (rdkafka::error::KafkaError, OwnedMessage)>;
+
+    /// Check that the brokers answer a metadata request
+    ///
+    /// Mocks are always reachable by default.
+    async fn check_brokers(&self, _timeout_ms: u64) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// Real producer wrapper for actual rdkafka FutureProducer
@@ -267,7 +396,7 @@ impl ProducerLike for RealProducer {
         &self,
         topic: &str,
         key: &str,
-        payload: &str,
+        payload: &[u8],
         timeout_ms: u64,
     ) -> Result<(), (rdkafka::error::KafkaError, OwnedMessage)> {
         let record = FutureRecord::to(topic).key(key).payload(payload);
@@ -280,6 +409,19 @@ impl ProducerLike for RealProducer {
             Err((kafka_error, owned_msg)) => Err((kafka_error, owned_msg)),
         }
     }
+
+    async fn check_brokers(&self, timeout_ms: u64) -> Result<()> {
+        // fetch_metadata blocks until the brokers answer or the timeout expires
+        let producer = self.inner.clone();
+        tokio::task::spawn_blocking(move || {
+            producer
+                .client()
+                .fetch_metadata(None, Timeout::After(Duration::from_millis(timeout_ms)))
+                .map(|_| ())
+        })
+        .await??;
+        Ok(())
+    }
 }
 
 impl KafkaActionDriver {
@@ -287,6 +429,11 @@ impl KafkaActionDriver {
     pub fn set_producer_for_test(&mut self, producer: Arc<dyn ProducerLike>) {
         self.producer = Some(producer);
     }
+
+    /// Set a custom command consumer (used for tests/mocks)
+    pub fn set_consumer_for_test(&mut self, consumer: Arc<dyn ConsumerLike>) {
+        self.consumer = Some(consumer);
+    }
 }
 
 #[async_trait]
@@ -301,12 +448,55 @@ impl ActionDriver for KafkaActionDriver {
         );
         self.connection_status = "Producer initialized".to_string();
 
+        if let Some(topic) = self.command_topic.clone() {
+            let consumer = self.ensure_consumer(&topic)?;
+            let dispatcher = CommandDispatcher {
+                config_updates: self.config_updates.clone(),
+                stats: Arc::clone(&self.command_stats),
+            };
+            if let Some(task) = self.command_task.take() {
+                task.abort();
+            }
+            self.command_task = Some(tokio::spawn(dispatcher.run(consumer)));
+            info!(
+                "KafkaActionDriver: Consuming commands from topic '{}' in group '{}'",
+                topic, self.command_group_id
+            );
+        }
+
         Ok(())
     }
 
+    async fn check_connection(&mut self) -> Result<()> {
+        // Creating the producer does not contact the brokers
+        let timeout_ms = self.timeout_ms;
+        let producer = self.ensure_producer()?;
+        match producer.check_brokers(timeout_ms).await {
+            Ok(()) => {
+                self.connection_status = "Connected".to_string();
+                Ok(())
+            }
+            Err(e) => {
+                self.connection_status = format!("Error: {}", e);
+                Err(anyhow::anyhow!(
+                    "Cannot reach Kafka brokers {}: {}",
+                    self.brokers,
+                    e
+                ))
+            }
+        }
+    }
+
     async fn update_action(&mut self, data: &MeasurementData) -> Result<()> {
         // Clone the data we need to avoid borrowing self
         let display_topic = self.display_topic.clone();
+        let key = data.source_node_id.clone();
+
+        if let Some(avro_payload) = self.avro_payload(data).await? {
+            return self
+                .send_to_topic(&display_topic, &key, &avro_payload)
+                .await;
+        }
 
         let payload = json!({
             "type": "display_update",
@@ -319,9 +509,9 @@ impl ActionDriver for KafkaActionDriver {
         });
 
         let json_str = serde_json::to_string(&payload)?;
-        let key = data.source_node_id.clone();
 
-        self.send_to_topic(&display_topic, &key, &json_str).await
+        self.send_to_topic(&display_topic, &key, json_str.as_bytes())
+            .await
     }
 
     async fn show_alert(&mut self, alert: &AlertData) -> Result<()> {
@@ -340,10 +530,16 @@ impl ActionDriver for KafkaActionDriver {
         let json_str = serde_json::to_string(&payload)?;
         let key = alert.alert_type.clone();
 
-        self.send_to_topic(&alert_topic, &key, &json_str).await
+        self.send_to_topic(&alert_topic, &key, json_str.as_bytes())
+            .await
     }
 
     async fn clear_action(&mut self) -> Result<()> {
+        // An Avro display topic only carries measurements
+        if self.avro.is_some() {
+            return Ok(());
+        }
+
         // Clone the data we need to avoid borrowing self
         let display_topic = self.display_topic.clone();
 
@@ -354,7 +550,8 @@ impl ActionDriver for KafkaActionDriver {
 
         let json_str = serde_json::to_string(&payload)?;
 
-        self.send_to_topic(&display_topic, "clear", &json_str).await
+        self.send_to_topic(&display_topic, "clear", json_str.as_bytes())
+            .await
     }
 
     async fn get_status(&self) -> Result<Value> {
@@ -367,16 +564,49 @@ impl ActionDriver for KafkaActionDriver {
             "timeout_ms": self.timeout_ms,
             "connection_status": self.connection_status,
             "is_connected": self.producer.is_some(),
+            "serialization": if self.avro.is_some() { "avro" } else { "json" },
+            "avro": self.avro.as_ref().map(|avro| json!({
+                "registry_url": avro.registry.url(),
+                "subject": avro.subject,
+                "schema_id": avro.registered.as_ref().map(|(schema_id, _)| schema_id),
+            })),
+            "commands": self.command_topic.as_ref().map(|topic| json!({
+                "topic": topic,
+                "group_id": self.command_group_id,
+                "stats": self.command_stats.lock().ok().map(|stats| stats.clone()),
+            })),
         }))
     }
 
+    async fn is_ready(&self) -> bool {
+        self.producer.is_some()
+    }
+
     fn driver_type(&self) -> &str {
         "kafka"
     }
 
+    fn set_config_update_sender(&mut self, sender: mpsc::Sender<Value>) {
+        self.config_updates = Some(sender);
+    }
+
     async fn shutdown(&mut self) -> Result<()> {
+        // Stop consuming commands, the consumer leaves its group when dropped
+        if let Some(task) = self.command_task.take() {
+            task.abort();
+        }
+        self.consumer = None;
+
         // Kafka producer is dropped automatically
         self.producer = None;
         Ok(())
     }
 }
+
+impl Drop for KafkaActionDriver {
+    fn drop(&mut self) {
+        if let Some(task) = self.command_task.take() {
+            task.abort();
+        }
+    }
+}
@@ -0,0 +1,210 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Avro encoding of the measurements sent to Kafka
+//!
+//! In Avro mode, a [`KafkaActionDriver`](super::KafkaActionDriver) registers
+//! the [`MEASUREMENT_AVRO_SCHEMA`] in a Confluent Schema Registry and sends
+//! each measurement in the Confluent wire format:
+//!
+//! | Bytes | Content |
+//! |---|---|
+//! | 0 | Magic byte `0` |
+//! | 1-4 | Schema id, big-endian |
+//! | 5- | Avro binary encoding of the measurement |
+//!
+//! The metadata values are JSON documents, stored as Avro strings.
+
+use anyhow::{Context, Result};
+use apache_avro::types::Value as AvroValue;
+use apache_avro::{from_avro_datum, to_avro_datum, Schema};
+use std::collections::HashMap;
+use std::time::{Duration, UNIX_EPOCH};
+
+use super::MeasurementData;
+
+/// First byte of the Confluent wire format
+pub const AVRO_MAGIC_BYTE: u8 = 0;
+
+/// Avro schema of the measurements
+pub const MEASUREMENT_AVRO_SCHEMA: &str = r#"{
+  "type": "record",
+  "name": "MeasurementData",
+  "namespace": "com.sctg.photoacoustic",
+  "doc": "Measurement sent by a photoacoustic action node",
+  "fields": [
+    {"name": "concentration_ppm", "type": "double", "doc": "Concentration in ppm"},
+    {"name": "source_node_id", "type": "string", "doc": "Node that computed the concentration"},
+    {"name": "peak_amplitude", "type": "float", "doc": "Peak amplitude (0.0-1.0)"},
+    {"name": "peak_frequency", "type": "float", "doc": "Peak frequency in Hz"},
+    {"name": "timestamp", "type": {"type": "long", "logicalType": "timestamp-millis"}},
+    {"name": "metadata", "type": {"type": "map", "values": "string"}, "default": {},
+     "doc": "Additional metadata, each value as a JSON document"}
+  ]
+}"#;
+
+/// Parse the [`MEASUREMENT_AVRO_SCHEMA`]
+pub fn measurement_schema() -> Result<Schema> {
+    Ok(Schema::parse_str(MEASUREMENT_AVRO_SCHEMA)?)
+}
+
+/// Encode a measurement in the Confluent wire format
+///
+/// # Arguments
+/// * `schema` - The measurement schema
+/// * `schema_id` - Id of the schema in the registry
+/// * `data` - The measurement
+pub fn encode_measurement(
+    schema: &Schema,
+    schema_id: u32,
+    data: &MeasurementData,
+) -> Result<Vec<u8>> {
+    let metadata = data
+        .metadata
+        .iter()
+        .map(|(key, value)| {
+            Ok((
+                key.clone(),
+                AvroValue::String(serde_json::to_string(value)?),
+            ))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+    let record = AvroValue::Record(vec![
+        (
+            "concentration_ppm".to_string(),
+            AvroValue::Double(data.concentration_ppm),
+        ),
+        (
+            "source_node_id".to_string(),
+            AvroValue::String(data.source_node_id.clone()),
+        ),
+        (
+            "peak_amplitude".to_string(),
+            AvroValue::Float(data.peak_amplitude),
+        ),
+        (
+            "peak_frequency".to_string(),
+            AvroValue::Float(data.peak_frequency),
+        ),
+        (
+            "timestamp".to_string(),
+            AvroValue::TimestampMillis(
+                data.timestamp.duration_since(UNIX_EPOCH)?.as_millis() as i64
+            ),
+        ),
+        ("metadata".to_string(), AvroValue::Map(metadata)),
+    ]);
+
+    let mut bytes = vec![AVRO_MAGIC_BYTE];
+    bytes.extend_from_slice(&schema_id.to_be_bytes());
+    bytes.extend(to_avro_datum(schema, record)?);
+    Ok(bytes)
+}
+
+/// Decode a measurement encoded by [`encode_measurement`]
+///
+/// # Returns
+/// * `Ok((u32, MeasurementData))` - The schema id and the measurement
+/// * `Err(anyhow::Error)` - The bytes are not a measurement in the Confluent wire format
+pub fn decode_measurement(schema: &Schema, bytes: &[u8]) -> Result<(u32, MeasurementData)> {
+    anyhow::ensure!(
+        bytes.len() > 5 && bytes[0] == AVRO_MAGIC_BYTE,
+        "Not a Confluent Avro message"
+    );
+    let schema_id = u32::from_be_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]);
+    let AvroValue::Record(fields) = from_avro_datum(schema, &mut &bytes[5..], None)? else {
+        anyhow::bail!("Avro measurement is not a record");
+    };
+
+    let mut data = MeasurementData {
+        concentration_ppm: 0.0,
+        source_node_id: String::new(),
+        peak_amplitude: 0.0,
+        peak_frequency: 0.0,
+        timestamp: UNIX_EPOCH,
+        metadata: HashMap::new(),
+    };
+    for (name, value) in fields {
+        match (name.as_str(), value) {
+            ("concentration_ppm", AvroValue::Double(v)) => data.concentration_ppm = v,
+            ("source_node_id", AvroValue::String(v)) => data.source_node_id = v,
+            ("peak_amplitude", AvroValue::Float(v)) => data.peak_amplitude = v,
+            ("peak_frequency", AvroValue::Float(v)) => data.peak_frequency = v,
+            ("timestamp", AvroValue::TimestampMillis(v)) => {
+                data.timestamp = UNIX_EPOCH + Duration::from_millis(v as u64)
+            }
+            ("metadata", AvroValue::Map(entries)) => {
+                for (key, value) in entries {
+                    let AvroValue::String(json) = value else {
+                        anyhow::bail!("Avro metadata '{}' is not a string", key);
+                    };
+                    let value = serde_json::from_str(&json)
+                        .with_context(|| format!("Invalid JSON in Avro metadata '{}'", key))?;
+                    data.metadata.insert(key, value);
+                }
+            }
+            (name, value) => anyhow::bail!("Unexpected Avro field '{}': {:?}", name, value),
+        }
+    }
+    Ok((schema_id, data))
+}
+
+/// Client of a Confluent Schema Registry
+#[derive(Debug, Clone)]
+pub struct SchemaRegistryClient {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl SchemaRegistryClient {
+    /// Create a client of the registry at `url` (e.g., "http://localhost:8081")
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into().trim_end_matches('/').to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Registry URL
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Register a schema under a subject, or look up its id if already registered
+    ///
+    /// # Arguments
+    /// * `subject` - Registry subject (e.g., "<topic>-value")
+    /// * `schema` - Schema definition, as JSON
+    ///
+    /// # Returns
+    /// * `Ok(u32)` - Id of the schema
+    /// * `Err(anyhow::Error)` - The registry could not be reached or refused the schema
+    pub async fn register(&self, subject: &str, schema: &str) -> Result<u32> {
+        let url = format!("{}/subjects/{}/versions", self.url, subject);
+        let response = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/vnd.schemaregistry.v1+json")
+            .body(serde_json::to_string(
+                &serde_json::json!({ "schema": schema }),
+            )?)
+            .send()
+            .await
+            .with_context(|| format!("Cannot reach schema registry {}", self.url))?;
+
+        let status = response.status();
+        let body: serde_json::Value = response.json().await.unwrap_or_default();
+        anyhow::ensure!(
+            status.is_success(),
+            "Schema registry refused subject '{}' ({}): {}",
+            subject,
+            status,
+            body
+        );
+        body.get("id")
+            .and_then(|id| id.as_u64())
+            .and_then(|id| u32::try_from(id).ok())
+            .ok_or_else(|| anyhow::anyhow!("Schema registry response without id: {}", body))
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Control commands consumed from Kafka
+//!
+//! A [`KafkaActionDriver`](super::KafkaActionDriver) with a command topic
+//! subscribes to it as a member of its consumer group, and dispatches the
+//! commands it receives. Each message is a JSON object whose `command` field
+//! selects the command:
+//!
+//! ```json
+//! {"command": "update_config", "parameters": {"concentration_threshold": 500.0}}
+//! {"command": "acknowledge", "alert_type": "concentration_threshold"}
+//! ```
+//!
+//! - `update_config` changes the configuration of the action node owning the
+//!   driver, with the parameters of a hot reload: `concentration_threshold`,
+//!   `amplitude_threshold`, `update_interval_ms` or `monitored_nodes`.
+//! - `acknowledge` records that an operator acknowledged an alert.
+//!
+//! Automatic offset commits are disabled: the offset of a message is
+//! committed once it has been dispatched. Messages that are not valid
+//! commands are logged, counted as rejected and committed too, so they are
+//! not delivered again.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{info, warn};
+use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
+use rdkafka::{Message, Offset, TopicPartitionList};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::{mpsc, Arc, Mutex};
+
+/// Command received on the command topic
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum KafkaCommand {
+    /// Change the configuration of the action node
+    UpdateConfig {
+        /// Hot reload parameters of the node
+        parameters: Value,
+    },
+    /// Acknowledge an alert
+    Acknowledge {
+        /// Type of the acknowledged alert
+        alert_type: String,
+    },
+}
+
+/// Message read from the command topic
+#[derive(Debug, Clone, PartialEq)]
+pub struct KafkaCommandMessage {
+    /// Topic of the message
+    pub topic: String,
+    /// Partition of the message
+    pub partition: i32,
+    /// Offset of the message in its partition
+    pub offset: i64,
+    /// Payload of the message, if it is valid UTF-8
+    pub payload: Option<String>,
+}
+
+/// Counters of the command consumer
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct KafkaCommandStats {
+    /// Messages received on the command topic
+    pub received: u64,
+    /// Commands dispatched
+    pub applied: u64,
+    /// Messages that were not valid commands
+    pub rejected: u64,
+    /// Offsets committed
+    pub committed: u64,
+    /// Type of the last acknowledged alert
+    pub last_acknowledged_alert: Option<String>,
+}
+
+/// Lightweight abstraction over a consumer to allow test mocks
+#[async_trait]
+pub trait ConsumerLike: Send + Sync {
+    /// Wait for the next message of the subscribed topic
+    async fn recv(&self) -> Result<KafkaCommandMessage>;
+
+    /// Commit the offset following a processed message
+    fn commit(&self, message: &KafkaCommandMessage) -> Result<()>;
+}
+
+/// Real consumer wrapper for an rdkafka StreamConsumer
+pub struct RealConsumer {
+    inner: StreamConsumer,
+}
+
+impl RealConsumer {
+    pub fn new(consumer: StreamConsumer) -> Self {
+        Self { inner: consumer }
+    }
+}
+
+#[async_trait]
+impl ConsumerLike for RealConsumer {
+    async fn recv(&self) -> Result<KafkaCommandMessage> {
+        let message = self.inner.recv().await?;
+        Ok(KafkaCommandMessage {
+            topic: message.topic().to_string(),
+            partition: message.partition(),
+            offset: message.offset(),
+            payload: message
+                .payload_view::<str>()
+                .and_then(|payload| payload.ok())
+                .map(str::to_string),
+        })
+    }
+
+    fn commit(&self, message: &KafkaCommandMessage) -> Result<()> {
+        let mut offsets = TopicPartitionList::new();
+        offsets.add_partition_offset(
+            &message.topic,
+            message.partition,
+            Offset::Offset(message.offset + 1),
+        )?;
+        self.inner.commit(&offsets, CommitMode::Async)?;
+        Ok(())
+    }
+}
+
+/// Where the commands are dispatched
+#[derive(Debug, Clone)]
+pub(super) struct CommandDispatcher {
+    /// Configuration updates for the action node
+    pub config_updates: Option<mpsc::Sender<Value>>,
+    /// Counters reported in the driver status
+    pub stats: Arc<Mutex<KafkaCommandStats>>,
+}
+
+impl CommandDispatcher {
+    /// Receive, dispatch and commit the commands until the consumer fails
+    pub async fn run(self, consumer: Arc<dyn ConsumerLike>) {
+        loop {
+            let message = match consumer.recv().await {
+                Ok(message) => message,
+                Err(e) => {
+                    warn!("KafkaActionDriver: Command consumer error: {}", e);
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                    continue;
+                }
+            };
+
+            self.update_stats(|stats| stats.received += 1);
+            match self.dispatch(&message) {
+                Ok(()) => self.update_stats(|stats| stats.applied += 1),
+                Err(e) => {
+                    warn!(
+                        "KafkaActionDriver: Rejected command at {}[{}]@{}: {}",
+                        message.topic, message.partition, message.offset, e
+                    );
+                    self.update_stats(|stats| stats.rejected += 1);
+                }
+            }
+
+            match consumer.commit(&message) {
+                Ok(()) => self.update_stats(|stats| stats.committed += 1),
+                Err(e) => warn!(
+                    "KafkaActionDriver: Failed to commit offset {} of {}[{}]: {}",
+                    message.offset, message.topic, message.partition, e
+                ),
+            }
+        }
+    }
+
+    /// Parse a message and apply its command
+    fn dispatch(&self, message: &KafkaCommandMessage) -> Result<()> {
+        let payload = message
+            .payload
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("Empty or non UTF-8 payload"))?;
+        match serde_json::from_str::<KafkaCommand>(payload)? {
+            KafkaCommand::UpdateConfig { parameters } => {
+                anyhow::ensure!(
+                    parameters.is_object(),
+                    "update_config parameters must be an object"
+                );
+                let sender = self
+                    .config_updates
+                    .as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("The driver is not attached to a node"))?;
+                info!(
+                    "KafkaActionDriver: Received configuration update {}",
+                    parameters
+                );
+                sender
+                    .send(parameters)
+                    .map_err(|_| anyhow::anyhow!("The action node has been dropped"))
+            }
+            KafkaCommand::Acknowledge { alert_type } => {
+                info!("KafkaActionDriver: Alert '{}' acknowledged", alert_type);
+                self.update_stats(|stats| stats.last_acknowledged_alert = Some(alert_type));
+                Ok(())
+            }
+        }
+    }
+
+    fn update_stats(&self, update: impl FnOnce(&mut KafkaCommandStats)) {
+        if let Ok(mut stats) = self.stats.lock() {
+            update(&mut stats);
+        }
+    }
+}
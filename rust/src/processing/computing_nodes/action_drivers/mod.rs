@@ -24,16 +24,33 @@
 
 // Core modules containing driver implementations
 mod http;
+mod http_queue;
+mod http_template;
 mod kafka;
+mod kafka_avro;
+mod kafka_commands;
 mod redis;
 // Python driver (feature-gated)
 #[cfg(feature = "python-driver")]
 mod python;
 
 // Re-export driver implementations
-pub use self::http::HttpsCallbackActionDriver;
-pub use self::kafka::KafkaActionDriver;
-pub use self::redis::{RedisActionDriver, RedisDriverMode};
+pub use self::http::{
+    HttpsCallbackActionDriver, DEFAULT_SIGNATURE_HEADER, DEFAULT_SIGNATURE_TIMESTAMP_HEADER,
+};
+pub use self::http_queue::DEFAULT_WEBHOOK_QUEUE_CAPACITY;
+pub use self::http_template::PayloadTemplate;
+pub use self::kafka::{KafkaActionDriver, ProducerLike};
+pub use self::kafka_avro::{
+    decode_measurement, encode_measurement, measurement_schema, SchemaRegistryClient,
+    AVRO_MAGIC_BYTE, MEASUREMENT_AVRO_SCHEMA,
+};
+pub use self::kafka_commands::{
+    ConsumerLike, KafkaCommand, KafkaCommandMessage, KafkaCommandStats,
+};
+pub use self::redis::{
+    RedisActionDriver, RedisDriverMode, RedisTlsFiles, DEFAULT_REDIS_BATCH_MAX_LATENCY_MS,
+};
 
 #[cfg(feature = "python-driver")]
 pub use self::python::{PythonActionDriver, PythonDriverConfig};
@@ -44,7 +61,9 @@ use rocket_okapi::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
+
+use super::SharedComputingState;
 
 /// Core action data passed to drivers
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -131,6 +150,38 @@ pub trait ActionDriver: Send + Sync + std::fmt::Debug {
     /// * `Err(anyhow::Error)` - Clear operation failed
     async fn clear_action(&mut self) -> Result<()>;
 
+    /// Check that the driver can reach its endpoint
+    ///
+    /// Used by the self-test to validate the configured drivers without
+    /// sending measurement data. Unlike [`initialize`](Self::initialize), the
+    /// check fails when the endpoint cannot be reached.
+    ///
+    /// # Returns
+    /// * `Ok(())` - The endpoint is reachable
+    /// * `Err(anyhow::Error)` - The endpoint could not be reached
+    ///
+    /// # Default Implementation
+    /// Initializes the driver - drivers whose initialization tolerates an
+    /// unreachable endpoint should override it
+    async fn check_connection(&mut self) -> Result<()> {
+        self.initialize().await
+    }
+
+    /// Check that the initialized driver can deliver measurements now
+    ///
+    /// Used by the readiness probe, without any network round trip: drivers
+    /// report the state of their connection as seen by their last operation.
+    ///
+    /// # Returns
+    /// * `true` - The driver is connected, or does not need a connection
+    /// * `false` - The driver lost its connection
+    ///
+    /// # Default Implementation
+    /// Ready once initialized - drivers holding a connection should override it
+    async fn is_ready(&self) -> bool {
+        true
+    }
+
     /// Get driver status and health information
     ///
     /// Returns diagnostic information about the driver's current state.
@@ -161,6 +212,35 @@ pub trait ActionDriver: Send + Sync + std::fmt::Debug {
         true // Most drivers support real-time by default
     }
 
+    /// Give the driver read access to the results of the computing nodes
+    ///
+    /// Called by the UniversalActionNode before the driver is initialized.
+    ///
+    /// # Arguments
+    /// * `shared_state` - Shared computing state of the processing graph
+    ///
+    /// # Default Implementation
+    /// Ignores the state - drivers should override if they use computed values
+    fn set_shared_computing_state(&mut self, _shared_state: Option<SharedComputingState>) {
+        // Default implementation: the driver only uses the measurement data
+    }
+
+    /// Give the driver a way to change the configuration of its node
+    ///
+    /// Called by the UniversalActionNode before the driver is initialized.
+    /// The node applies the parameters sent on the channel like a hot reload
+    /// (`concentration_threshold`, `amplitude_threshold`, ...) before it
+    /// handles the next computing data.
+    ///
+    /// # Arguments
+    /// * `sender` - Channel of configuration updates for the node
+    ///
+    /// # Default Implementation
+    /// Ignores the channel - drivers receiving remote commands should override
+    fn set_config_update_sender(&mut self, _sender: std::sync::mpsc::Sender<Value>) {
+        // Default implementation: the driver does not receive commands
+    }
+
     /// Shutdown the driver gracefully
     ///
     /// Called when the ActionNode is being destroyed or reconfigured.
@@ -215,3 +295,278 @@ pub trait ActionDriver: Send + Sync + std::fmt::Debug {
         }))
     }
 }
+
+/// Create an action driver from its configuration
+///
+/// This is the `driver` section of an `action_universal` node: the driver
+/// `type` and its `config` object.
+///
+/// # Arguments
+/// * `driver_type` - Driver type ("https_callback", "redis", "kafka" or "python")
+/// * `config` - Driver configuration
+///
+/// # Returns
+/// * `Ok(Box<dyn ActionDriver>)` - The driver, not yet initialized
+/// * `Err(anyhow::Error)` - Unknown driver type or missing required parameter
+pub fn create_action_driver(
+    driver_type: &str,
+    config: &serde_json::Map<String, Value>,
+) -> Result<Box<dyn ActionDriver>> {
+    let driver: Box<dyn ActionDriver> = match driver_type {
+        "https_callback" => {
+            let url = config
+                .get("callback_url")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("Missing callback_url for https_callback driver"))?;
+
+            let mut http_driver = HttpsCallbackActionDriver::new(url);
+
+            // Optional auth token
+            if let Some(auth_token) = config.get("auth_token").and_then(|v| v.as_str()) {
+                http_driver = http_driver.with_auth_token(auth_token);
+            }
+
+            // Optional timeout
+            if let Some(timeout_ms) = config.get("timeout_ms").and_then(|v| v.as_u64()) {
+                http_driver = http_driver.with_timeout_seconds(timeout_ms / 1000);
+            }
+
+            // Optional retry count
+            if let Some(retry_count) = config.get("retry_count").and_then(|v| v.as_u64()) {
+                http_driver = http_driver.with_retry_count(retry_count as u32);
+            }
+
+            // Optional payload template, validated when the driver is initialized
+            if let Some(template) = config.get("payload_template") {
+                http_driver = http_driver.with_payload_template(template.clone());
+            }
+
+            // Optional HMAC signing, with the default headers unless overridden
+            if let Some(secret) = config.get("hmac_secret").and_then(|v| v.as_str()) {
+                http_driver = http_driver.with_hmac_secret(secret);
+            }
+            let signature_header = config.get("signature_header").and_then(|v| v.as_str());
+            let timestamp_header = config
+                .get("signature_timestamp_header")
+                .and_then(|v| v.as_str());
+            if signature_header.is_some() || timestamp_header.is_some() {
+                http_driver = http_driver.with_signature_headers(
+                    signature_header.unwrap_or(DEFAULT_SIGNATURE_HEADER),
+                    timestamp_header.unwrap_or(DEFAULT_SIGNATURE_TIMESTAMP_HEADER),
+                );
+            }
+
+            // Optional durable retry queue
+            if let Some(path) = config.get("retry_queue_path").and_then(|v| v.as_str()) {
+                let capacity = config
+                    .get("retry_queue_capacity")
+                    .and_then(|v| v.as_u64())
+                    .map_or(DEFAULT_WEBHOOK_QUEUE_CAPACITY, |capacity| capacity as usize);
+                http_driver = http_driver.with_retry_queue(path, capacity);
+            }
+
+            Box::new(http_driver)
+        }
+        "redis" => {
+            let connection_string = config
+                .get("connection_string")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("Missing connection_string for redis driver"))?;
+
+            // Get mode (default to key_value for backward compatibility)
+            let mode = config
+                .get("mode")
+                .and_then(|v| v.as_str())
+                .unwrap_or("key_value");
+
+            // Get channel or prefix (support both 'channel' and 'channel_or_prefix')
+            let channel_or_prefix = config
+                .get("channel_or_prefix")
+                .and_then(|v| v.as_str())
+                .or_else(|| config.get("channel").and_then(|v| v.as_str()))
+                .unwrap_or("photoacoustic");
+
+            let mut redis_driver = match mode {
+                "pub_sub" | "pubsub" => {
+                    RedisActionDriver::new_pubsub(connection_string, channel_or_prefix)
+                }
+                "key_value" | "keyvalue" => {
+                    RedisActionDriver::new_key_value(connection_string, channel_or_prefix)
+                }
+                _ => {
+                    log::warn!("Unknown Redis mode '{}', defaulting to key_value", mode);
+                    RedisActionDriver::new_key_value(connection_string, channel_or_prefix)
+                }
+            };
+
+            // Optional expiration (support both 'expiration_seconds' and 'expiry_seconds')
+            if let Some(expiration_seconds) = config
+                .get("expiration_seconds")
+                .and_then(|v| v.as_u64())
+                .or_else(|| config.get("expiry_seconds").and_then(|v| v.as_u64()))
+            {
+                redis_driver = redis_driver.with_expiration_seconds(expiration_seconds);
+            }
+
+            // Optional TLS certificates, checked when the driver is initialized
+            let tls_path = |key: &str| {
+                config
+                    .get(key)
+                    .and_then(|v| v.as_str())
+                    .map(std::path::PathBuf::from)
+            };
+            let tls_files = RedisTlsFiles {
+                client_cert: tls_path("tls_client_cert"),
+                client_key: tls_path("tls_client_key"),
+                ca_cert: tls_path("tls_ca_cert"),
+            };
+            if tls_files.client_cert.is_some()
+                || tls_files.client_key.is_some()
+                || tls_files.ca_cert.is_some()
+            {
+                redis_driver = redis_driver.with_tls(tls_files);
+            }
+
+            // Optional batching
+            if let Some(batch_size) = config.get("batch_size").and_then(|v| v.as_u64()) {
+                let max_latency_ms = config
+                    .get("batch_max_latency_ms")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(DEFAULT_REDIS_BATCH_MAX_LATENCY_MS);
+                if batch_size > 1 && max_latency_ms == 0 {
+                    return Err(anyhow::anyhow!(
+                        "batch_max_latency_ms must be greater than 0 for the redis driver"
+                    ));
+                }
+                redis_driver = redis_driver
+                    .with_batching(batch_size as usize, Duration::from_millis(max_latency_ms));
+            }
+
+            Box::new(redis_driver)
+        }
+        "kafka" => {
+            let bootstrap_servers = config
+                .get("bootstrap_servers")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("Missing bootstrap_servers for kafka driver"))?;
+
+            let topic = config
+                .get("topic")
+                .and_then(|v| v.as_str())
+                .unwrap_or("photoacoustic.display");
+
+            let alert_topic = config
+                .get("alert_topic")
+                .and_then(|v| v.as_str())
+                .unwrap_or("photoacoustic.alerts");
+
+            let mut kafka_driver = KafkaActionDriver::new(bootstrap_servers, topic, alert_topic);
+
+            // Optional command consumer
+            if let Some(command_topic) = config.get("command_topic").and_then(|v| v.as_str()) {
+                let group_id = config
+                    .get("command_group_id")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("photoacoustic-commands");
+                kafka_driver = kafka_driver.with_command_topic(command_topic, group_id);
+            }
+
+            // Optional Avro serialization (default: JSON)
+            match config.get("serialization").and_then(|v| v.as_str()) {
+                None | Some("json") => {}
+                Some("avro") => {
+                    let registry_url = config
+                        .get("schema_registry_url")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Missing schema_registry_url for kafka driver with avro serialization"
+                        )
+                    })?;
+                    let subject = config
+                        .get("schema_subject")
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string);
+                    kafka_driver = kafka_driver.with_avro(registry_url, subject);
+                }
+                Some(other) => {
+                    anyhow::bail!(
+                        "Unknown kafka serialization '{}', expected json or avro",
+                        other
+                    )
+                }
+            }
+
+            Box::new(kafka_driver)
+        }
+        #[cfg(feature = "python-driver")]
+        "python" => {
+            // Extract required script_path
+            let script_path = config
+                .get("script_path")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("Missing script_path for python driver"))?;
+
+            // Create configuration with required script_path
+            let mut python_config = PythonDriverConfig {
+                script_path: script_path.into(),
+                ..Default::default()
+            };
+
+            // Configure optional parameters
+            if let Some(auto_reload) = config.get("auto_reload").and_then(|v| v.as_bool()) {
+                python_config.auto_reload = auto_reload;
+            }
+
+            if let Some(timeout_seconds) = config.get("timeout_seconds").and_then(|v| v.as_u64()) {
+                python_config.timeout_seconds = timeout_seconds;
+            }
+
+            if let Some(update_function) = config.get("update_function").and_then(|v| v.as_str()) {
+                python_config.update_function = update_function.to_string();
+            }
+
+            if let Some(alert_function) = config.get("alert_function").and_then(|v| v.as_str()) {
+                python_config.alert_function = alert_function.to_string();
+            }
+
+            if let Some(init_function) = config.get("init_function").and_then(|v| v.as_str()) {
+                python_config.init_function = init_function.to_string();
+            }
+
+            if let Some(shutdown_function) =
+                config.get("shutdown_function").and_then(|v| v.as_str())
+            {
+                python_config.shutdown_function = shutdown_function.to_string();
+            }
+
+            if let Some(status_function) = config.get("status_function").and_then(|v| v.as_str()) {
+                python_config.status_function = status_function.to_string();
+            }
+
+            if let Some(venv_path) = config.get("venv_path").and_then(|v| v.as_str()) {
+                python_config.venv_path = Some(venv_path.into());
+            }
+
+            // Handle python_paths array
+            if let Some(python_paths_arr) = config.get("python_paths").and_then(|v| v.as_array()) {
+                python_config.python_paths = python_paths_arr
+                    .iter()
+                    .filter_map(|v| v.as_str())
+                    .map(|s| s.into())
+                    .collect();
+            }
+
+            Box::new(PythonActionDriver::new(python_config))
+        }
+        #[cfg(not(feature = "python-driver"))]
+        "python" => {
+            return Err(anyhow::anyhow!(
+                "Python driver requested but not compiled (missing python-driver feature)"
+            ))
+        }
+        _ => return Err(anyhow::anyhow!("Unsupported driver type: {}", driver_type)),
+    };
+
+    Ok(driver)
+}
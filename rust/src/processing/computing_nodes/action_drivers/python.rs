@@ -18,10 +18,14 @@
 //! # Features
 //!
 //! - **Script Hot-reloading**: Automatically reload Python scripts when they change
-//! - **Timeout Protection**: Configurable timeouts prevent hanging Python scripts
+//! - **Timeout Protection**: Calls running longer than `timeout_seconds` are
+//!   interrupted with a `TimeoutError` raised in the script, and counted in the
+//!   `timeouts` field of the driver status
 //! - **Error Handling**: Robust error handling with detailed error messages
 //! - **Measurement History**: Automatic tracking of measurement data
 //! - **Async/Await Support**: Full async support for non-blocking operation
+//! - **Computed Values**: The latest peak and concentration results are readable
+//!   from the `computing_state` module global
 //!
 //! # Example Python Script
 //!
@@ -73,6 +77,23 @@
 //!     return {"status": "shutdown"}
 //! ```
 //!
+//! # Computed Values
+//!
+//! Before each call the driver sets the `computing_state` module global to a
+//! read-only snapshot of the shared computing state, or `None` when the driver
+//! is not attached to a processing graph. See
+//! [`ComputingSharedData::script_context`](crate::processing::computing_nodes::ComputingSharedData::script_context)
+//! for the schema:
+//!
+//! ```python
+//! def on_measurement(data):
+//!     state = computing_state or {}
+//!     for node_id, result in state.get("concentration_results", {}).items():
+//!         if result["concentration_ppm"] > 2 * data["concentration_ppm"]:
+//!             print(f"{node_id} disagrees with {data['source_node_id']}")
+//!     return {"processed": True}
+//! ```
+//!
 //!```yaml
 //! # Python Action Driver - For custom Python processing
 //! # This driver allows executing custom Python code for advanced processing
@@ -174,10 +195,12 @@ use log::{debug, error, info, warn};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime};
 
 use super::{ActionDriver, AlertData, MeasurementData};
+use crate::processing::computing_nodes::SharedComputingState;
 
 /// Python action driver configuration
 ///
@@ -236,6 +259,10 @@ pub struct PythonDriverConfig {
     /// Function name to call for status (default: "get_status")
     pub status_function: String,
     /// Maximum execution time for Python calls (seconds)
+    ///
+    /// A call blocked in C code, such as `time.sleep(60)`, is not interrupted:
+    /// the driver fails it one second after the limit and lets it finish in
+    /// the background.
     pub timeout_seconds: u64,
     /// Whether to reload script on each call (development mode)
     pub auto_reload: bool,
@@ -329,6 +356,10 @@ pub struct PythonActionDriver {
     history: Arc<Mutex<Vec<MeasurementData>>>,
     status: Arc<Mutex<String>>,
     max_history: usize,
+    shared_computing_state: Option<SharedComputingState>,
+    timeouts: Arc<AtomicU64>,
+    #[cfg(feature = "python-driver")]
+    watchdog: Arc<crate::processing::nodes::python::PythonWatchdog>,
 }
 
 impl std::fmt::Debug for PythonActionDriver {
@@ -374,9 +405,18 @@ impl PythonActionDriver {
             history: Arc::new(Mutex::new(Vec::new())),
             status: Arc::new(Mutex::new("Not initialized".to_string())),
             max_history: 1000,
+            shared_computing_state: None,
+            timeouts: Arc::new(AtomicU64::new(0)),
+            #[cfg(feature = "python-driver")]
+            watchdog: Arc::new(crate::processing::nodes::python::PythonWatchdog::new()),
         }
     }
 
+    /// Number of script calls interrupted by the timeout since the driver was created
+    pub fn timeout_count(&self) -> u64 {
+        self.timeouts.load(Ordering::Relaxed)
+    }
+
     /// Create a Python action driver from configuration
     ///
     /// Creates a driver from a configuration hash map, typically loaded from JSON
@@ -646,7 +686,11 @@ impl PythonActionDriver {
     /// # Timeout Behavior
     ///
     /// Function calls are executed in a separate thread with a configurable timeout.
-    /// If the timeout is exceeded, the call is cancelled and an error is returned.
+    /// If the timeout is exceeded, a `TimeoutError` is raised in the script and a
+    /// [`PythonCallTimeout`](crate::processing::nodes::PythonCallTimeout) error
+    /// is returned. The exception is delivered between two Python bytecodes:
+    /// when the script is blocked in a C call, the driver stops waiting one
+    /// second after the timeout and lets the call finish in the background.
     ///
     /// # Type Conversion
     ///
@@ -675,20 +719,30 @@ impl PythonActionDriver {
     /// Without it, calls will return an error indicating the feature is missing.
     #[cfg(feature = "python-driver")]
     async fn call_python_function(&self, func_name: &str, args: &[Value]) -> Result<Value> {
+        use crate::processing::nodes::python::PythonCallTimeout;
         use pyo3::prelude::*;
         use pyo3::types::{PyDict, PyList, PyModule, PyTuple};
         use std::ffi::CString;
 
         let script_path = self.config.script_path.clone();
+        let watchdog = Arc::clone(&self.watchdog);
         let timeout = Duration::from_secs(self.config.timeout_seconds);
+        let function = func_name.to_string();
         let func_name = func_name.to_string();
         let args = args.to_vec();
+        let computing_state = match &self.shared_computing_state {
+            Some(shared_state) => shared_state.read().await.script_context(),
+            None => Value::Null,
+        };
 
-        // Execute Python code in a blocking task with timeout
+        // Execute Python code in a blocking task with timeout, leaving the
+        // watchdog time to interrupt the script first
         let result = tokio::time::timeout(
-            timeout,
+            timeout + Duration::from_secs(1),
             tokio::task::spawn_blocking(move || {
                 Python::with_gil(|py| -> Result<Value> {
+                    let mut timed_out = false;
+
                     // Capture Python stdout/stderr
                     let sys = py.import("sys")?;
                     let io = py.import("io")?;
@@ -738,6 +792,13 @@ impl PythonActionDriver {
                             module_name.as_c_str(),
                         )?;
 
+                        // Read-only snapshot of the computed results
+                        let computing_state =
+                            pythonize::pythonize(py, &computing_state).map_err(|e| {
+                                PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string())
+                            })?;
+                        module.setattr("computing_state", computing_state)?;
+
                         // Check if the function exists
                         if !module.hasattr(func_name.as_str())? {
                             debug!("Python function '{}' not found, skipping", func_name);
@@ -747,27 +808,26 @@ impl PythonActionDriver {
                         // Get the function
                         let func = module.getattr(func_name.as_str())?;
 
-                        // Call the function
-                        if args.is_empty() {
-                            func.call0().map(|v| v.into())
-                        } else {
-                            // Convert each argument to Python objects individually using pythonize
-                            let py_args: Result<Vec<PyObject>, _> = args
-                                .iter()
-                                .map(|arg| {
-                                    pythonize::pythonize(py, arg)
-                                        .map_err(|e| {
-                                            PyErr::new::<pyo3::exceptions::PyValueError, _>(
-                                                e.to_string(),
-                                            )
-                                        })
-                                        .map(|bound| bound.into())
-                                })
-                                .collect();
-                            let py_args = py_args?;
-                            let args_tuple = PyTuple::new(py, py_args)?;
-                            func.call1(&args_tuple).map(|v| v.into())
-                        }
+                        // Convert each argument to Python objects individually using pythonize
+                        let py_args: Result<Vec<PyObject>, _> = args
+                            .iter()
+                            .map(|arg| {
+                                pythonize::pythonize(py, arg)
+                                    .map_err(|e| {
+                                        PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                                            e.to_string(),
+                                        )
+                                    })
+                                    .map(|bound| bound.into())
+                            })
+                            .collect();
+                        let args_tuple = PyTuple::new(py, py_args?)?;
+
+                        // Call the function, interrupted past the timeout
+                        let guard = watchdog.arm(py, timeout)?;
+                        let result = func.call1(&args_tuple).map(|v| v.into());
+                        timed_out = guard.disarm(py);
+                        result
                     });
 
                     // Restore original stdout/stderr
@@ -792,6 +852,14 @@ impl PythonActionDriver {
                         warn!("[Python:{}] stderr: {}", func_name, stderr_output.trim());
                     }
 
+                    if timed_out {
+                        return Err(PythonCallTimeout {
+                            function: func_name,
+                            timeout,
+                        }
+                        .into());
+                    }
+
                     // Handle the result
                     let result = result.map_err(|e| anyhow!("Python execution error: {}", e))?;
 
@@ -818,13 +886,17 @@ impl PythonActionDriver {
         )
         .await;
 
-        match result {
+        let result = match result {
             Ok(task_result) => task_result.map_err(|e| anyhow!("Task error: {}", e))?,
-            Err(_) => Err(anyhow!(
-                "Python function call timed out after {} seconds",
-                self.config.timeout_seconds
-            )),
+            Err(_) => Err(PythonCallTimeout { function, timeout }.into()),
+        };
+        if let Err(e) = &result {
+            if e.is::<PythonCallTimeout>() {
+                self.timeouts.fetch_add(1, Ordering::Relaxed);
+                warn!("Python driver: {}", e);
+            }
         }
+        result
     }
 
     /// Call a Python function without the python-driver feature
@@ -971,6 +1043,10 @@ impl ActionDriver for PythonActionDriver {
         "python"
     }
 
+    fn set_shared_computing_state(&mut self, shared_state: Option<SharedComputingState>) {
+        self.shared_computing_state = shared_state;
+    }
+
     /// Initialize the Python action driver
     ///
     /// Performs the following initialization steps:
@@ -1455,7 +1531,8 @@ impl ActionDriver for PythonActionDriver {
                     "driver_status": status,
                     "python_status": py_status,
                     "auto_reload": self.config.auto_reload,
-                    "history_size": self.history.lock().unwrap().len()
+                    "history_size": self.history.lock().unwrap().len(),
+                    "timeouts": self.timeout_count()
                 })),
                 Err(_) => Ok(json!({
                     "type": "python",
@@ -1463,7 +1540,8 @@ impl ActionDriver for PythonActionDriver {
                     "driver_status": status,
                     "python_status": "function not available",
                     "auto_reload": self.config.auto_reload,
-                    "history_size": self.history.lock().unwrap().len()
+                    "history_size": self.history.lock().unwrap().len(),
+                    "timeouts": self.timeout_count()
                 })),
             }
         }
@@ -6,14 +6,42 @@
 //!
 //! This module implements a driver for sending display data to Redis.
 //! It supports both publishing to channels and storing key-value pairs.
+//!
+//! # Batching
+//!
+//! By default every measurement is sent in its own round trip. With
+//! [`RedisActionDriver::with_batching`], measurements are queued and sent as a
+//! single pipeline once `batch_size` of them are waiting, or once the oldest
+//! has waited `max_latency`. Batches are sent in order, and the measurements
+//! still waiting are flushed by [`ActionDriver::shutdown`].
+//!
+//! When a batch cannot be sent, it stays queued for the next flush, except the
+//! measurement whose [`ActionDriver::update_action`] call triggered the flush:
+//! that call fails and the measurement is left to the caller, for instance the
+//! dead-letter queue of the action node. At most ten batches are kept waiting,
+//! further measurements are refused until Redis is reachable again.
+//!
+//! # Mutual TLS
+//!
+//! For a `rediss://` endpoint requiring client certificates,
+//! [`RedisActionDriver::with_tls`] sets the client certificate, its private
+//! key and the CA bundle verifying the server, as PEM files. They are read
+//! and checked by [`ActionDriver::initialize`], before connecting.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use async_trait::async_trait;
-use log::{error, info, warn};
+use log::{debug, error, info, warn};
 use redis::AsyncCommands;
 use redis::{aio::MultiplexedConnection, Client};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
 use serde_json::{json, Value};
-use std::time::SystemTime;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
 
 use super::{ActionDriver, AlertData, MeasurementData};
 
@@ -26,6 +54,212 @@ pub enum RedisDriverMode {
     KeyValue,
 }
 
+/// Default longest time a measurement waits for its batch to fill
+pub const DEFAULT_REDIS_BATCH_MAX_LATENCY_MS: u64 = 1000;
+
+/// Maximum number of unsent batches kept waiting while Redis is unreachable
+const MAX_PENDING_BATCHES: usize = 10;
+
+/// Longest wait of the latency flush task between two failed flushes
+const MAX_FLUSH_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Shortest maximum latency of a batch, which paces the latency flush task
+const MIN_BATCH_MAX_LATENCY: Duration = Duration::from_millis(1);
+
+/// Measurements waiting to be sent in a batch, shared with the latency flush task
+#[derive(Debug, Default)]
+struct BatchState {
+    /// Serialized measurements, oldest first
+    pending: VecDeque<PendingMeasurement>,
+    /// When the oldest pending measurement was queued
+    oldest_queued_at: Option<Instant>,
+    /// Number of batches sent
+    batches_sent: u64,
+    /// Number of measurements sent in batches
+    measurements_sent: u64,
+    /// Size of the last batch sent
+    last_batch_size: usize,
+    /// Number of flushes that failed to send a batch
+    failed_flushes: u64,
+}
+
+/// A measurement serialized for Redis
+#[derive(Debug, Clone)]
+struct PendingMeasurement {
+    source_node_id: String,
+    timestamp_secs: u64,
+    payload: String,
+}
+
+/// Settings shared by the driver and its latency flush task
+#[derive(Debug, Clone)]
+struct BatchTarget {
+    mode: RedisDriverMode,
+    channel_or_prefix: String,
+    expiration_seconds: Option<u64>,
+    batch_size: usize,
+}
+
+impl BatchTarget {
+    /// Send the pending measurements in batches of `batch_size`, stopping at the first failure
+    async fn flush(
+        &self,
+        state: &mut BatchState,
+        conn: &mut MultiplexedConnection,
+    ) -> redis::RedisResult<()> {
+        while !state.pending.is_empty() {
+            let size = state.pending.len().min(self.batch_size);
+            let mut pipe = redis::pipe();
+            for measurement in state.pending.iter().take(size) {
+                self.add_commands(&mut pipe, measurement);
+            }
+            if let Err(e) = pipe.query_async::<()>(conn).await {
+                state.failed_flushes += 1;
+                return Err(e);
+            }
+
+            state.pending.drain(..size);
+            state.batches_sent += 1;
+            state.measurements_sent += size as u64;
+            state.last_batch_size = size;
+            debug!("RedisActionDriver: Sent a batch of {} measurements", size);
+        }
+        state.oldest_queued_at = None;
+        Ok(())
+    }
+
+    /// Add the commands sending `measurement` to a pipeline, as `update_action` does
+    fn add_commands(&self, pipe: &mut redis::Pipeline, measurement: &PendingMeasurement) {
+        match self.mode {
+            RedisDriverMode::PubSub => {
+                pipe.publish(&self.channel_or_prefix, &measurement.payload)
+                    .ignore();
+            }
+            RedisDriverMode::KeyValue => {
+                let key = format!(
+                    "{}:display:{}:{}",
+                    self.channel_or_prefix, measurement.source_node_id, measurement.timestamp_secs
+                );
+                let latest_key = format!(
+                    "{}:latest:{}",
+                    self.channel_or_prefix, measurement.source_node_id
+                );
+                for key in [key, latest_key] {
+                    match self.expiration_seconds {
+                        Some(exp_secs) => pipe.set_ex(key, &measurement.payload, exp_secs).ignore(),
+                        None => pipe.set(key, &measurement.payload).ignore(),
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// Build the JSON payload of a measurement
+fn measurement_payload(data: &MeasurementData) -> Result<String> {
+    let payload = json!({
+        "type": "display_update",
+        "concentration_ppm": data.concentration_ppm,
+        "source_node_id": data.source_node_id,
+        "peak_amplitude": data.peak_amplitude,
+        "peak_frequency": data.peak_frequency,
+        "timestamp": data.timestamp.duration_since(std::time::UNIX_EPOCH)?.as_secs(),
+        "metadata": data.metadata
+    });
+    Ok(serde_json::to_string(&payload)?)
+}
+
+/// PEM files authenticating the driver to a TLS Redis endpoint
+#[derive(Debug, Clone, Default)]
+pub struct RedisTlsFiles {
+    /// Client certificate chain, presented to the server (mutual TLS)
+    pub client_cert: Option<PathBuf>,
+    /// Private key of the client certificate
+    pub client_key: Option<PathBuf>,
+    /// CA bundle verifying the server certificate, instead of the default roots
+    pub ca_cert: Option<PathBuf>,
+}
+
+impl RedisTlsFiles {
+    /// Read and check the TLS files
+    ///
+    /// The client certificate and key must be given together, contain PEM
+    /// data, and the certificate must match the key.
+    ///
+    /// # Returns
+    /// * `Ok(redis::TlsCertificates)` - The certificates for `Client::build_with_tls`
+    /// * `Err(anyhow::Error)` - A file is missing or invalid, or the pair does not match
+    pub fn load(&self) -> Result<redis::TlsCertificates> {
+        let client_tls = match (&self.client_cert, &self.client_key) {
+            (Some(cert_path), Some(key_path)) => {
+                let client_cert = read_pem_file(cert_path, "client certificate")?;
+                let client_key = read_pem_file(key_path, "client key")?;
+                check_key_pair(&client_cert, &client_key).with_context(|| {
+                    format!(
+                        "Invalid Redis client certificate {} or key {}",
+                        cert_path.display(),
+                        key_path.display()
+                    )
+                })?;
+                Some(redis::ClientTlsConfig {
+                    client_cert,
+                    client_key,
+                })
+            }
+            (None, None) => None,
+            _ => anyhow::bail!("Redis TLS client certificate and key must be configured together"),
+        };
+
+        let root_cert = match &self.ca_cert {
+            Some(ca_path) => {
+                let ca_cert = read_pem_file(ca_path, "CA bundle")?;
+                let certificates = CertificateDer::pem_slice_iter(&ca_cert)
+                    .collect::<Result<Vec<_>, _>>()
+                    .with_context(|| format!("Invalid Redis CA bundle {}", ca_path.display()))?;
+                anyhow::ensure!(
+                    !certificates.is_empty(),
+                    "Redis CA bundle {} contains no certificate",
+                    ca_path.display()
+                );
+                Some(ca_cert)
+            }
+            None => None,
+        };
+
+        Ok(redis::TlsCertificates {
+            client_tls,
+            root_cert,
+        })
+    }
+}
+
+/// Read a PEM file of the TLS configuration
+fn read_pem_file(path: &Path, description: &str) -> Result<Vec<u8>> {
+    std::fs::read(path)
+        .with_context(|| format!("Cannot read Redis {} {}", description, path.display()))
+}
+
+/// Check that a PEM certificate chain matches a PEM private key
+fn check_key_pair(cert_pem: &[u8], key_pem: &[u8]) -> Result<()> {
+    let chain = CertificateDer::pem_slice_iter(cert_pem)
+        .collect::<Result<Vec<_>, _>>()
+        .context("Client certificate is not valid PEM")?;
+    anyhow::ensure!(
+        !chain.is_empty(),
+        "Client certificate file contains no certificate"
+    );
+    let key = PrivateKeyDer::from_pem_slice(key_pem)
+        .context("Client key is not a valid PEM private key")?;
+
+    let signing_key = rustls::crypto::ring::default_provider()
+        .key_provider
+        .load_private_key(key)
+        .map_err(|e| anyhow::anyhow!("Unsupported client key: {}", e))?;
+    rustls::sign::CertifiedKey::new(chain, signing_key)
+        .keys_match()
+        .map_err(|e| anyhow::anyhow!("Client certificate does not match the private key: {}", e))
+}
+
 /// Redis display driver
 ///
 /// Sends display data to Redis using either pub/sub channels or key-value storage.
@@ -46,6 +280,16 @@ pub struct RedisActionDriver {
     expiration_seconds: Option<u64>,
     /// Connection status
     connection_status: String,
+    /// Number of measurements sent per batch, 1 to send each one immediately
+    batch_size: usize,
+    /// Longest time a measurement waits for its batch to fill
+    batch_max_latency: Duration,
+    /// Measurements waiting for their batch
+    batch: Arc<Mutex<BatchState>>,
+    /// Task flushing the batches that waited `batch_max_latency`
+    flush_task: Option<JoinHandle<()>>,
+    /// Certificates of a TLS endpoint
+    tls: Option<RedisTlsFiles>,
 }
 
 impl RedisActionDriver {
@@ -63,6 +307,11 @@ impl RedisActionDriver {
             connection: None,
             expiration_seconds: None,
             connection_status: "Initializing".to_string(),
+            batch_size: 1,
+            batch_max_latency: Duration::ZERO,
+            batch: Arc::new(Mutex::new(BatchState::default())),
+            flush_task: None,
+            tls: None,
         }
     }
 
@@ -80,6 +329,11 @@ impl RedisActionDriver {
             connection: None,
             expiration_seconds: None,
             connection_status: "Initializing".to_string(),
+            batch_size: 1,
+            batch_max_latency: Duration::ZERO,
+            batch: Arc::new(Mutex::new(BatchState::default())),
+            flush_task: None,
+            tls: None,
         }
     }
 
@@ -96,6 +350,170 @@ impl RedisActionDriver {
         self
     }
 
+    /// Send measurements in batches instead of one round trip each
+    ///
+    /// # Arguments
+    /// * `batch_size` - Number of measurements sent together (1 = no batching)
+    /// * `max_latency` - Longest time a measurement waits for its batch to fill,
+    ///   at least 1 ms
+    pub fn with_batching(mut self, batch_size: usize, max_latency: Duration) -> Self {
+        self.batch_size = batch_size.max(1);
+        self.batch_max_latency = max_latency.max(MIN_BATCH_MAX_LATENCY);
+        self
+    }
+
+    /// Authenticate to a `rediss://` endpoint with certificates
+    ///
+    /// # Arguments
+    /// * `files` - Client certificate, key and CA bundle, checked by `initialize`
+    pub fn with_tls(mut self, files: RedisTlsFiles) -> Self {
+        self.tls = Some(files);
+        self
+    }
+
+    /// Create the Redis client, with the TLS certificates if configured
+    fn create_client(&self) -> Result<Client> {
+        let Some(tls) = &self.tls else {
+            return Ok(Client::open(self.url.clone())?);
+        };
+        anyhow::ensure!(
+            self.url.starts_with("rediss://"),
+            "Redis TLS certificates require a rediss:// URL, got {}",
+            self.url
+        );
+        let certificates = tls.load()?;
+        Ok(Client::build_with_tls(self.url.clone(), certificates)?)
+    }
+
+    fn batch_target(&self) -> BatchTarget {
+        BatchTarget {
+            mode: self.mode.clone(),
+            channel_or_prefix: self.channel_or_prefix.clone(),
+            expiration_seconds: self.expiration_seconds,
+            batch_size: self.batch_size,
+        }
+    }
+
+    /// Start the task flushing the batches that waited `batch_max_latency`
+    ///
+    /// The task uses its own connection, opened from the driver's client,
+    /// without holding the batch. While Redis is unreachable, the task waits
+    /// twice as long after each failed flush, up to [`MAX_FLUSH_RETRY_INTERVAL`],
+    /// and warns only about the first failure.
+    fn start_flush_task(&mut self) {
+        let Some(client) = self.client.clone() else {
+            return;
+        };
+        if let Some(task) = self.flush_task.take() {
+            task.abort();
+        }
+
+        let target = self.batch_target();
+        let batch = Arc::clone(&self.batch);
+        let max_latency = self.batch_max_latency;
+        let poll_interval = (max_latency / 4).max(Duration::from_millis(1));
+        self.flush_task = Some(tokio::spawn(async move {
+            let mut connection: Option<MultiplexedConnection> = None;
+            let mut retry_interval = poll_interval;
+            loop {
+                tokio::time::sleep(retry_interval).await;
+                let due = batch
+                    .lock()
+                    .await
+                    .oldest_queued_at
+                    .is_some_and(|queued_at| queued_at.elapsed() >= max_latency);
+                if !due {
+                    continue;
+                }
+
+                // A connection is kept only while its flushes succeed
+                let result = match connection.take() {
+                    Some(conn) => Ok(conn),
+                    None => client
+                        .get_multiplexed_async_connection()
+                        .await
+                        .map_err(|e| format!("cannot connect: {}", e)),
+                };
+                let result = match result {
+                    Ok(mut conn) => {
+                        let mut state = batch.lock().await;
+                        let flushed = target
+                            .flush(&mut state, &mut conn)
+                            .await
+                            .map_err(|e| format!("failed: {}", e));
+                        if flushed.is_ok() {
+                            connection = Some(conn);
+                        }
+                        flushed
+                    }
+                    Err(e) => Err(e),
+                };
+
+                match result {
+                    Ok(()) => {
+                        if retry_interval > poll_interval {
+                            info!("RedisActionDriver: Batch flush recovered");
+                        }
+                        retry_interval = poll_interval;
+                    }
+                    Err(e) => {
+                        if retry_interval == poll_interval {
+                            warn!("RedisActionDriver: Batch flush {}, retrying", e);
+                        } else {
+                            debug!("RedisActionDriver: Batch flush {}", e);
+                        }
+                        retry_interval =
+                            (retry_interval * 2).min(MAX_FLUSH_RETRY_INTERVAL.max(poll_interval));
+                    }
+                }
+            }
+        }));
+    }
+
+    /// Queue a measurement, flushing when its batch is full
+    async fn queue_measurement(&mut self, data: &MeasurementData) -> Result<()> {
+        let measurement = PendingMeasurement {
+            source_node_id: data.source_node_id.clone(),
+            timestamp_secs: data
+                .timestamp
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_secs(),
+            payload: measurement_payload(data)?,
+        };
+
+        let batch = Arc::clone(&self.batch);
+        let mut state = batch.lock().await;
+        if state.pending.len() >= self.batch_size * MAX_PENDING_BATCHES {
+            return Err(anyhow::anyhow!(
+                "Redis batch queue full ({} measurements waiting)",
+                state.pending.len()
+            ));
+        }
+        state.pending.push_back(measurement);
+        state.oldest_queued_at.get_or_insert_with(Instant::now);
+        if state.pending.len() < self.batch_size {
+            return Ok(());
+        }
+
+        let target = self.batch_target();
+        let result = match self.get_connection().await {
+            Ok(conn) => target
+                .flush(&mut state, conn)
+                .await
+                .map_err(|e| anyhow::anyhow!("Redis batch operation failed: {}", e)),
+            Err(e) => Err(e),
+        };
+        if result.is_err() {
+            // The caller keeps this measurement, the older ones wait for the next flush
+            state.pending.pop_back();
+            if state.pending.is_empty() {
+                state.oldest_queued_at = None;
+            }
+            self.connection = None;
+        }
+        result
+    }
+
     // Helper method to get a valid Redis connection with reconnection logic
     async fn get_connection(&mut self) -> Result<&mut MultiplexedConnection> {
         // First, check if we have a connection and if it's still valid
@@ -123,7 +541,7 @@ impl RedisActionDriver {
 
         // Create client if needed
         if self.client.is_none() {
-            self.client = Some(redis::Client::open(self.url.clone())?);
+            self.client = Some(self.create_client()?);
             self.connection_status = "Client created".to_string();
         }
 
@@ -151,6 +569,11 @@ impl RedisActionDriver {
 #[async_trait]
 impl ActionDriver for RedisActionDriver {
     async fn initialize(&mut self) -> Result<()> {
+        // Check the TLS files before connecting, so that errors name them
+        if self.tls.is_some() {
+            self.client = Some(self.create_client()?);
+        }
+
         // Test Redis connection
         let conn = self.get_connection().await?;
 
@@ -164,6 +587,9 @@ impl ActionDriver for RedisActionDriver {
             Ok(_) => {
                 info!("RedisActionDriver: Successfully connected to Redis");
                 self.connection_status = "Connected and verified".to_string();
+                if self.batch_size > 1 {
+                    self.start_flush_task();
+                }
                 Ok(())
             }
             Err(e) => {
@@ -175,23 +601,17 @@ impl ActionDriver for RedisActionDriver {
     }
 
     async fn update_action(&mut self, data: &MeasurementData) -> Result<()> {
+        if self.batch_size > 1 {
+            return self.queue_measurement(data).await;
+        }
+
         // Clone values that we'll need after borrowing self
         let mode = self.mode.clone();
         let channel_or_prefix = self.channel_or_prefix.clone();
         let expiration_seconds = self.expiration_seconds;
 
         // Create the payload first
-        let payload = json!({
-            "type": "display_update",
-            "concentration_ppm": data.concentration_ppm,
-            "source_node_id": data.source_node_id,
-            "peak_amplitude": data.peak_amplitude,
-            "peak_frequency": data.peak_frequency,
-            "timestamp": data.timestamp.duration_since(std::time::UNIX_EPOCH)?.as_secs(),
-            "metadata": data.metadata
-        });
-
-        let json_str = serde_json::to_string(&payload)?;
+        let json_str = measurement_payload(data)?;
 
         // Try to send the data with automatic reconnection
         let mut retry_count = 0;
@@ -478,6 +898,18 @@ impl ActionDriver for RedisActionDriver {
     }
 
     async fn get_status(&self) -> Result<Value> {
+        let batching = {
+            let state = self.batch.lock().await;
+            json!({
+                "batch_size": self.batch_size,
+                "max_latency_ms": self.batch_max_latency.as_millis() as u64,
+                "pending": state.pending.len(),
+                "batches_sent": state.batches_sent,
+                "measurements_sent": state.measurements_sent,
+                "last_batch_size": state.last_batch_size,
+                "failed_flushes": state.failed_flushes,
+            })
+        };
         Ok(json!({
             "driver_type": self.driver_type(),
             "url": self.url,
@@ -489,16 +921,57 @@ impl ActionDriver for RedisActionDriver {
             "expiration_seconds": self.expiration_seconds,
             "connection_status": self.connection_status,
             "is_connected": self.connection.is_some(),
+            "tls": self.tls.as_ref().map(|tls| json!({
+                "client_cert": tls.client_cert,
+                "client_key": tls.client_key,
+                "ca_cert": tls.ca_cert,
+            })),
+            "batching": batching,
         }))
     }
 
+    async fn is_ready(&self) -> bool {
+        // Dropped after a failed command, until the next reconnection
+        self.connection.is_some()
+    }
+
     fn driver_type(&self) -> &str {
         "redis"
     }
 
     async fn shutdown(&mut self) -> Result<()> {
+        // Send the partial batch before closing the connection
+        if let Some(task) = self.flush_task.take() {
+            task.abort();
+        }
+        let batch = Arc::clone(&self.batch);
+        let mut state = batch.lock().await;
+        let result = if state.pending.is_empty() {
+            Ok(())
+        } else {
+            let target = self.batch_target();
+            match self.get_connection().await {
+                Ok(conn) => target.flush(&mut state, conn).await.map_err(|e| {
+                    anyhow::anyhow!(
+                        "Redis batch flush failed, {} measurements not sent: {}",
+                        state.pending.len(),
+                        e
+                    )
+                }),
+                Err(e) => Err(e),
+            }
+        };
+
         // Redis connections are automatically closed when dropped
         self.connection = None;
-        Ok(())
+        result
+    }
+}
+
+impl Drop for RedisActionDriver {
+    fn drop(&mut self) {
+        if let Some(task) = self.flush_task.take() {
+            task.abort();
+        }
     }
 }
@@ -63,7 +63,7 @@
 //! ```
 
 use crate::processing::computing_nodes::{ComputingSharedData, ConcentrationResult, PeakResult};
-use crate::processing::nodes::ProcessingNode;
+use crate::processing::nodes::{DetectionEvent, ProcessingNode};
 use anyhow::Result;
 use std::collections::VecDeque;
 use std::time::SystemTime;
@@ -188,6 +188,13 @@ pub enum ActionTrigger {
         /// Source node ID that timed out
         source_node_id: String,
     },
+    /// Triggered by a new onset or release event of a photoacoustic output node
+    Detection {
+        /// The detection event
+        event: DetectionEvent,
+        /// Source output node ID that emitted the event
+        source_node_id: String,
+    },
     /// Custom trigger with arbitrary data
     Custom {
         /// Trigger identifier
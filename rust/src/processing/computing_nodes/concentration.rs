@@ -18,6 +18,7 @@
 //! - **Shared state updates**: Concentration results are stored in global shared state
 //! - **Temperature compensation**: Optional temperature correction for improved accuracy
 //! - **Multi-spectral analysis**: Support for different spectral lines/harmonics
+//! - **Outlier rejection**: Optional Hampel filter discarding isolated concentration spikes
 //!
 //! # Configuration
 //!
@@ -27,21 +28,33 @@
 //! - `polynomial_coefficients`: 5-element array for 4th-degree polynomial [a₀, a₁, a₂, a₃, a₄]
 //! - `temperature_compensation`: Enable/disable temperature correction
 //! - `spectral_line_id`: Optional identifier for the spectral line being analyzed
+//! - `outlier_rejection`: Optional [`HampelFilter`] configuration (`window_size`, `threshold_mads`,
+//!   `min_deviation_ppm`)
+//!
+//! # Outlier rejection
+//!
+//! When outlier rejection is enabled, each new peak reading is checked by a Hampel
+//! filter before its concentration is published. A rejected reading leaves the
+//! previous concentration in the shared state, so action nodes never see it, and
+//! increments the `outliers_rejected` counter published in the result metadata.
+//! A genuine sustained change is published once it fills more than half of the window.
 //!
 //! # Usage
 //!
 //! ```rust
 //! use rust_photoacoustic::processing::computing_nodes::concentration::ConcentrationNode;
+//! use rust_photoacoustic::processing::computing_nodes::HampelFilter;
 //! use rust_photoacoustic::processing::{ProcessingNode, ProcessingData};
 //!
 //! let mut concentration_node = ConcentrationNode::new("concentration_calc".to_string())
 //!     .with_peak_finder_source("primary_peak_finder".to_string())
 //!     .with_polynomial_coefficients([0.0, 0.45, -0.002, 0.0001, 0.0])
-//!     .with_temperature_compensation(true);
+//!     .with_temperature_compensation(true)
+//!     .with_outlier_rejection(HampelFilter::new(11, 3.0).unwrap());
 //! ```
 
 use crate::processing::computing_nodes::{
-    ComputingSharedData, ConcentrationResult, PeakResult, SharedComputingState,
+    ComputingSharedData, ConcentrationResult, HampelFilter, PeakResult, SharedComputingState,
 };
 use crate::processing::nodes::ProcessingMetadata;
 use crate::processing::{ProcessingData, ProcessingNode};
@@ -82,6 +95,15 @@ pub struct ConcentrationNode {
     /// Maximum concentration limit for safety/validation
     max_concentration_ppm: f32,
 
+    /// Optional outlier rejection applied to new readings before publication
+    outlier_filter: Option<HampelFilter>,
+
+    /// Timestamp of the last peak reading checked for outliers
+    last_peak_timestamp: Option<SystemTime>,
+
+    /// Whether the last peak reading checked was rejected as an outlier
+    last_reading_rejected: bool,
+
     /// Shared state for communicating results to other nodes
     shared_state: Arc<RwLock<ComputingSharedData>>,
 
@@ -100,6 +122,7 @@ impl ConcentrationNode {
     /// - Temperature compensation disabled
     /// - Minimum amplitude threshold: 0.001
     /// - Maximum concentration: 10000.0 ppm
+    /// - No outlier rejection
     ///
     /// # Arguments
     ///
@@ -117,6 +140,9 @@ impl ConcentrationNode {
             spectral_line_id: None,
             min_amplitude_threshold: 0.001,
             max_concentration_ppm: 10000.0,
+            outlier_filter: None,
+            last_peak_timestamp: None,
+            last_reading_rejected: false,
             shared_state: Arc::new(RwLock::new(ComputingSharedData::default())),
             processing_count: 0,
             calculation_count: 0,
@@ -149,6 +175,9 @@ impl ConcentrationNode {
             spectral_line_id: None,
             min_amplitude_threshold: 0.001,
             max_concentration_ppm: 10000.0,
+            outlier_filter: None,
+            last_peak_timestamp: None,
+            last_reading_rejected: false,
             shared_state,
             processing_count: 0,
             calculation_count: 0,
@@ -240,6 +269,31 @@ impl ConcentrationNode {
         self
     }
 
+    /// Enable outlier rejection of new readings
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - Hampel filter checking each new concentration before publication
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_outlier_rejection(mut self, filter: HampelFilter) -> Self {
+        self.outlier_filter = Some(filter);
+        self
+    }
+
+    /// Get the number of readings rejected as outliers
+    ///
+    /// # Returns
+    ///
+    /// The number of rejected readings, 0 when outlier rejection is disabled
+    pub fn get_rejected_count(&self) -> u64 {
+        self.outlier_filter
+            .as_ref()
+            .map_or(0, |filter| filter.rejected_count())
+    }
+
     /// Get the shared computing state
     ///
     /// # Returns
@@ -277,6 +331,39 @@ impl ConcentrationNode {
             .min(self.max_concentration_ppm as f64)
     }
 
+    /// Check whether the concentration of a peak reading is an outlier
+    ///
+    /// Each peak reading is checked once, when its timestamp is first seen; later frames
+    /// reuse the decision until the peak finder publishes a new reading.
+    ///
+    /// # Arguments
+    ///
+    /// * `peak_data` - The source peak reading
+    /// * `concentration` - Concentration calculated from the reading in ppm
+    ///
+    /// # Returns
+    ///
+    /// `true` if the reading must not be published
+    fn is_outlier(&mut self, peak_data: &PeakResult, concentration: f64) -> bool {
+        let Some(filter) = self.outlier_filter.as_mut() else {
+            return false;
+        };
+
+        if self.last_peak_timestamp != Some(peak_data.timestamp) {
+            self.last_peak_timestamp = Some(peak_data.timestamp);
+            self.last_reading_rejected = !filter.accept(concentration);
+            if self.last_reading_rejected {
+                debug!(
+                    "Concentration node '{}': Rejected outlier {:.2} ppm ({} rejected so far)",
+                    self.id,
+                    concentration,
+                    filter.rejected_count()
+                );
+            }
+        }
+        self.last_reading_rejected
+    }
+
     /// Update the concentration result in the shared state
     ///
     /// This method stores the concentration result under this node's ID in the shared state
@@ -307,6 +394,7 @@ impl ConcentrationNode {
                 // Create concentration result
                 let concentration_result = ConcentrationResult {
                     concentration_ppm: concentration,
+                    concentration_variance: None,
                     source_peak_finder_id: self
                         .computing_peak_finder_id
                         .as_deref()
@@ -318,7 +406,16 @@ impl ConcentrationNode {
                     source_frequency: source_peak_result.frequency,
                     temperature_compensated: self.temperature_compensation,
                     timestamp: SystemTime::now(),
-                    processing_metadata: std::collections::HashMap::new(),
+                    processing_metadata: self
+                        .outlier_filter
+                        .iter()
+                        .map(|filter| {
+                            (
+                                "outliers_rejected".to_string(),
+                                filter.rejected_count().to_string(),
+                            )
+                        })
+                        .collect(),
                 };
 
                 // Store concentration result under this node's ID
@@ -413,7 +510,9 @@ impl ProcessingNode for ConcentrationNode {
         if let Some(peak_data) = peak_result {
             if peak_data.amplitude >= self.min_amplitude_threshold {
                 let concentration = self.calculate_concentration(peak_data.amplitude);
-                self.update_shared_state(&peak_data, concentration);
+                if !self.is_outlier(&peak_data, concentration) {
+                    self.update_shared_state(&peak_data, concentration);
+                }
             } else {
                 // Amplitude too low for reliable calculation
                 if self.processing_count % 1000 == 0 {
@@ -482,6 +581,11 @@ impl ProcessingNode for ConcentrationNode {
         self.processing_count = 0;
         self.calculation_count = 0;
         self.last_calculation_time = None;
+        self.last_peak_timestamp = None;
+        self.last_reading_rejected = false;
+        if let Some(filter) = self.outlier_filter.as_mut() {
+            filter.reset();
+        }
 
         // Note: We don't reset shared state as other nodes might depend on it
         info!("Concentration node '{}': State reset", self.id);
@@ -503,6 +607,7 @@ impl ProcessingNode for ConcentrationNode {
 
         cloned.min_amplitude_threshold = self.min_amplitude_threshold;
         cloned.max_concentration_ppm = self.max_concentration_ppm;
+        cloned.outlier_filter = self.outlier_filter.clone();
 
         Box::new(cloned)
     }
@@ -598,6 +703,27 @@ impl ProcessingNode for ConcentrationNode {
             }
         }
 
+        // Update outlier rejection, null disables it
+        if let Some(outlier_rejection) = parameters.get("outlier_rejection") {
+            self.outlier_filter = if outlier_rejection.is_null() {
+                None
+            } else {
+                Some(HampelFilter::from_config(outlier_rejection)?)
+            };
+            self.last_peak_timestamp = None;
+            self.last_reading_rejected = false;
+            updated = true;
+            info!(
+                "Concentration node '{}': Outlier rejection {}",
+                self.id,
+                if self.outlier_filter.is_some() {
+                    "enabled"
+                } else {
+                    "disabled"
+                }
+            );
+        }
+
         // Update PeakFinder source binding
         if let Some(source_id) = parameters.get("computing_peak_finder_id") {
             if let Some(id_str) = source_id.as_str() {
@@ -0,0 +1,503 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! This module implements the CrossCorrelation node, which estimates the time delay
+//! between the two channels of a signal.
+//!
+//! The CrossCorrelationNode is a pass-through ComputingNode: it keeps the last `window_size`
+//! samples of channel A and channel B, computes their cross-correlation and stores the lag
+//! of the correlation peak in the shared computing state. In a photoacoustic cell with two
+//! microphones, this lag is the difference of acoustic time-of-flight to the microphones.
+//!
+//! # Algorithm
+//!
+//! - The mean of each channel is removed over the window
+//! - The cross-correlation `r[k] = Σ a[n]·b[n + k]` is computed with zero-padded FFTs
+//! - The peak is searched for lags in `[-max_lag, max_lag]`
+//! - A parabola through the peak and its two neighbours refines the lag to a fraction
+//!   of a sample
+//!
+//! A positive lag means that channel B lags behind channel A: `b[n] = a[n - lag]`.
+//!
+//! # Configuration
+//!
+//! - `sample_rate` is automatically set from `photoacoustic.sample_rate` (global config)
+//! - `window_size` defaults to `photoacoustic.frame_size` (global config)
+//! - Node-specific parameters:
+//!   - `window_size`: Number of samples of each channel correlated
+//!   - `max_lag_samples`: Largest lag searched, in samples (default: half the window)
+//!   - `interpolation`: Parabolic sub-sample interpolation of the lag (default: true)
+//!
+//! # Usage
+//!
+//! ```rust
+//! use rust_photoacoustic::processing::computing_nodes::CrossCorrelationNode;
+//! use rust_photoacoustic::processing::{ProcessingData, ProcessingNode};
+//!
+//! let mut node = CrossCorrelationNode::new("time_delay".to_string())
+//!     .with_window_size(256)
+//!     .with_max_lag(16)
+//!     .with_interpolation(false);
+//!
+//! // Channel B is channel A delayed by 3 samples
+//! let channel_a: Vec<f32> = (0..256).map(|i| ((i * i) % 17) as f32 - 8.0).collect();
+//! let mut channel_b = vec![0.0; 3];
+//! channel_b.extend_from_slice(&channel_a[..253]);
+//!
+//! let input = ProcessingData::DualChannel {
+//!     channel_a,
+//!     channel_b,
+//!     sample_rate: 48000,
+//!     timestamp: 0,
+//!     frame_number: 1,
+//! };
+//! node.process(input).unwrap();
+//!
+//! let state = node.get_shared_state();
+//! let result = state.try_read().unwrap().get_time_delay_result("time_delay").cloned().unwrap();
+//! assert_eq!(result.lag_samples, 3.0);
+//! ```
+
+use crate::processing::computing_nodes::{
+    ComputingSharedData, SharedComputingState, TimeDelayResult,
+};
+use crate::processing::{ProcessingData, ProcessingNode};
+use anyhow::{anyhow, Result};
+use log::{debug, warn};
+use realfft::{ComplexToReal, RealFftPlanner, RealToComplex};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::sync::RwLock;
+
+/// Smallest supported correlation window
+const MIN_WINDOW_SIZE: usize = 16;
+
+/// A computing node estimating the time delay between channel A and channel B
+///
+/// The node passes its input through unchanged and writes a [`TimeDelayResult`] to the
+/// shared computing state each time a full window of both channels is available.
+pub struct CrossCorrelationNode {
+    /// Unique identifier for this node
+    id: String,
+
+    /// Number of samples of each channel correlated
+    window_size: usize,
+
+    /// Largest lag searched (samples), `None` for half the window
+    max_lag: Option<usize>,
+
+    /// Whether the lag is refined by parabolic interpolation
+    interpolation: bool,
+
+    /// Sample rate used to convert the lag to seconds
+    sample_rate: u32,
+
+    /// Shared state for communicating results to other nodes
+    shared_state: SharedComputingState,
+
+    /// FFT planner for efficient computation
+    fft_planner: RealFftPlanner<f32>,
+
+    /// Forward and inverse FFTs of the zero-padded window
+    forward: Arc<dyn RealToComplex<f32>>,
+    inverse: Arc<dyn ComplexToReal<f32>>,
+
+    /// Last samples of each channel
+    buffer_a: VecDeque<f32>,
+    buffer_b: VecDeque<f32>,
+
+    /// Statistics for monitoring performance
+    processing_count: u64,
+}
+
+impl CrossCorrelationNode {
+    /// Create a new CrossCorrelation node with default parameters
+    ///
+    /// Default configuration:
+    /// - Window size: 2048 samples
+    /// - Maximum lag: half the window
+    /// - Parabolic interpolation enabled
+    /// - Sample rate: 48 kHz
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Unique identifier for this node
+    ///
+    /// # Returns
+    ///
+    /// A new CrossCorrelationNode instance with its own shared state
+    pub fn new(id: String) -> Self {
+        Self::new_with_shared_state(id, None)
+    }
+
+    /// Create a new CrossCorrelation node with an external shared computing state
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Unique identifier for this node
+    /// * `shared_state` - Optional external shared computing state, a new one if `None`
+    ///
+    /// # Returns
+    ///
+    /// A new CrossCorrelationNode instance with the provided or new shared state
+    pub fn new_with_shared_state(id: String, shared_state: Option<SharedComputingState>) -> Self {
+        let window_size = 2048;
+        let mut fft_planner = RealFftPlanner::<f32>::new();
+        let fft_size = Self::fft_size(window_size);
+        let forward = fft_planner.plan_fft_forward(fft_size);
+        let inverse = fft_planner.plan_fft_inverse(fft_size);
+
+        Self {
+            id,
+            window_size,
+            max_lag: None,
+            interpolation: true,
+            sample_rate: 48000,
+            shared_state: shared_state
+                .unwrap_or_else(|| Arc::new(RwLock::new(ComputingSharedData::default()))),
+            fft_planner,
+            forward,
+            inverse,
+            buffer_a: VecDeque::with_capacity(window_size),
+            buffer_b: VecDeque::with_capacity(window_size),
+            processing_count: 0,
+        }
+    }
+
+    /// Set the number of samples of each channel correlated
+    ///
+    /// Sizes below 16 samples are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - Window size in samples
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_window_size(mut self, size: usize) -> Self {
+        self.set_window_size(size);
+        self
+    }
+
+    /// Set the largest lag searched
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - Largest lag in samples, limited to the window size minus one
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_max_lag(mut self, samples: usize) -> Self {
+        self.max_lag = Some(samples);
+        self
+    }
+
+    /// Enable or disable the sub-sample interpolation of the lag
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Refine the lag with a parabola through the correlation peak
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_interpolation(mut self, enabled: bool) -> Self {
+        self.interpolation = enabled;
+        self
+    }
+
+    /// Set the sample rate used to convert the lag to seconds
+    ///
+    /// # Arguments
+    ///
+    /// * `rate` - Sample rate in Hz
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_sample_rate(mut self, rate: u32) -> Self {
+        self.sample_rate = rate;
+        self
+    }
+
+    /// Get access to the shared state for reading results
+    pub fn get_shared_state(&self) -> SharedComputingState {
+        Arc::clone(&self.shared_state)
+    }
+
+    /// Zero-padded FFT size avoiding the circular wrap-around of the correlation
+    fn fft_size(window_size: usize) -> usize {
+        (2 * window_size).next_power_of_two()
+    }
+
+    fn set_window_size(&mut self, size: usize) -> bool {
+        if size < MIN_WINDOW_SIZE || size == self.window_size {
+            return false;
+        }
+        self.window_size = size;
+        let fft_size = Self::fft_size(size);
+        self.forward = self.fft_planner.plan_fft_forward(fft_size);
+        self.inverse = self.fft_planner.plan_fft_inverse(fft_size);
+        self.buffer_a = VecDeque::with_capacity(size);
+        self.buffer_b = VecDeque::with_capacity(size);
+        true
+    }
+
+    /// Largest lag searched, in samples
+    fn effective_max_lag(&self) -> usize {
+        self.max_lag
+            .unwrap_or(self.window_size / 2)
+            .min(self.window_size - 1)
+    }
+
+    /// Cross-correlate the buffered windows and locate the correlation peak
+    ///
+    /// # Returns
+    ///
+    /// The lag of the peak in samples and the normalized correlation at the peak,
+    /// or `None` if a channel is silent
+    fn estimate_lag(&self) -> Result<Option<(f64, f32)>> {
+        let fft_size = Self::fft_size(self.window_size);
+        let padded = |buffer: &VecDeque<f32>| {
+            let mean = buffer.iter().sum::<f32>() / buffer.len() as f32;
+            let mut samples: Vec<f32> = buffer.iter().map(|sample| sample - mean).collect();
+            let energy: f32 = samples.iter().map(|sample| sample * sample).sum();
+            samples.resize(fft_size, 0.0);
+            (samples, energy)
+        };
+        let (mut samples_a, energy_a) = padded(&self.buffer_a);
+        let (mut samples_b, energy_b) = padded(&self.buffer_b);
+        if energy_a <= f32::EPSILON || energy_b <= f32::EPSILON {
+            return Ok(None);
+        }
+
+        let mut spectrum_a = self.forward.make_output_vec();
+        let mut spectrum_b = self.forward.make_output_vec();
+        self.forward
+            .process(&mut samples_a, &mut spectrum_a)
+            .map_err(|e| anyhow!("FFT processing failed: {:?}", e))?;
+        self.forward
+            .process(&mut samples_b, &mut spectrum_b)
+            .map_err(|e| anyhow!("FFT processing failed: {:?}", e))?;
+
+        // R[f] = conj(A[f])·B[f] is the spectrum of r[k] = Σ a[n]·b[n + k]
+        let mut cross_spectrum: Vec<_> = spectrum_a
+            .iter()
+            .zip(&spectrum_b)
+            .map(|(a, b)| a.conj() * b)
+            .collect();
+        // The DC and Nyquist bins of a real signal are real
+        let last = cross_spectrum.len() - 1;
+        cross_spectrum[0].im = 0.0;
+        cross_spectrum[last].im = 0.0;
+        let mut correlation = self.inverse.make_output_vec();
+        self.inverse
+            .process(&mut cross_spectrum, &mut correlation)
+            .map_err(|e| anyhow!("Inverse FFT processing failed: {:?}", e))?;
+
+        // Negative lags wrap around to the end of the inverse FFT
+        let max_lag = self.effective_max_lag() as i64;
+        let at = |lag: i64| correlation[lag.rem_euclid(fft_size as i64) as usize];
+        let peak_lag = (-max_lag..=max_lag)
+            .max_by(|a, b| at(*a).total_cmp(&at(*b)))
+            .unwrap_or(0);
+
+        let mut lag = peak_lag as f64;
+        if self.interpolation && peak_lag.abs() < max_lag {
+            let (before, peak, after) = (
+                at(peak_lag - 1) as f64,
+                at(peak_lag) as f64,
+                at(peak_lag + 1) as f64,
+            );
+            let curvature = before - 2.0 * peak + after;
+            if curvature < 0.0 {
+                lag += (0.5 * (before - after) / curvature).clamp(-0.5, 0.5);
+            }
+        }
+
+        // The inverse FFT is not normalized
+        let peak_correlation = at(peak_lag) / fft_size as f32 / (energy_a * energy_b).sqrt();
+        Ok(Some((lag, peak_correlation)))
+    }
+
+    fn update_shared_state(&self, lag_samples: f64, peak_correlation: f32) {
+        let result = TimeDelayResult {
+            lag_samples,
+            lag_seconds: lag_samples / self.sample_rate as f64,
+            peak_correlation,
+            window_size: self.window_size,
+            timestamp: SystemTime::now(),
+        };
+
+        match self.shared_state.try_write() {
+            Ok(mut state) => state.update_time_delay_result(self.id.clone(), result),
+            Err(_) => warn!(
+                "Cross-correlation '{}': Failed to acquire write lock for shared state - lag={:.3} samples",
+                self.id, lag_samples
+            ),
+        }
+    }
+}
+
+impl ProcessingNode for CrossCorrelationNode {
+    /// Buffer both channels and estimate their time delay, passing the input through
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Dual-channel audio data
+    ///
+    /// # Returns
+    ///
+    /// The same input data unchanged
+    fn process(&mut self, input: ProcessingData) -> Result<ProcessingData> {
+        self.processing_count += 1;
+
+        let (channel_a, channel_b, sample_rate) = match &input {
+            ProcessingData::AudioFrame(frame) => {
+                (&frame.channel_a, &frame.channel_b, frame.sample_rate)
+            }
+            ProcessingData::DualChannel {
+                channel_a,
+                channel_b,
+                sample_rate,
+                ..
+            } => (channel_a, channel_b, *sample_rate),
+            _ => return Ok(input),
+        };
+        self.sample_rate = sample_rate;
+
+        self.buffer_a.extend(channel_a);
+        self.buffer_b.extend(channel_b);
+        while self.buffer_a.len() > self.window_size {
+            self.buffer_a.pop_front();
+        }
+        while self.buffer_b.len() > self.window_size {
+            self.buffer_b.pop_front();
+        }
+
+        if self.buffer_a.len() == self.window_size && self.buffer_b.len() == self.window_size {
+            match self.estimate_lag()? {
+                Some((lag_samples, peak_correlation)) => {
+                    if self.processing_count % 100 == 0 {
+                        debug!(
+                            "Cross-correlation '{}': lag {:.3} samples ({:.2} µs), correlation {:.3}",
+                            self.id,
+                            lag_samples,
+                            lag_samples / self.sample_rate as f64 * 1e6,
+                            peak_correlation
+                        );
+                    }
+                    self.update_shared_state(lag_samples, peak_correlation);
+                }
+                None => debug!(
+                    "Cross-correlation '{}': Silent channel, no lag estimated",
+                    self.id
+                ),
+            }
+        }
+
+        Ok(input)
+    }
+
+    fn node_id(&self) -> &str {
+        &self.id
+    }
+
+    fn node_type(&self) -> &str {
+        "computing_cross_correlation"
+    }
+
+    /// CrossCorrelationNode needs both channels
+    fn accepts_input(&self, input: &ProcessingData) -> bool {
+        matches!(
+            input,
+            ProcessingData::AudioFrame(_) | ProcessingData::DualChannel { .. }
+        )
+    }
+
+    /// CrossCorrelationNode is a pass-through node, so output type matches input type
+    fn output_type(&self, input: &ProcessingData) -> Option<String> {
+        match input {
+            ProcessingData::AudioFrame(_) => Some("AudioFrame".to_string()),
+            ProcessingData::DualChannel { .. } => Some("DualChannel".to_string()),
+            _ => None,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.buffer_a.clear();
+        self.buffer_b.clear();
+        self.processing_count = 0;
+    }
+
+    fn clone_node(&self) -> Box<dyn ProcessingNode> {
+        let mut node = CrossCorrelationNode::new_with_shared_state(
+            self.id.clone(),
+            Some(self.shared_state.clone()),
+        )
+        .with_window_size(self.window_size)
+        .with_interpolation(self.interpolation)
+        .with_sample_rate(self.sample_rate);
+        node.max_lag = self.max_lag;
+        Box::new(node)
+    }
+
+    fn supports_hot_reload(&self) -> bool {
+        true
+    }
+
+    /// Update configuration parameters dynamically
+    ///
+    /// Supports updating `window_size`, `max_lag_samples` and `interpolation`.
+    /// Changing the window size clears the buffered samples.
+    ///
+    /// # Arguments
+    ///
+    /// * `parameters` - JSON object containing parameter updates
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success and whether any parameters were changed
+    fn update_config(&mut self, parameters: &serde_json::Value) -> Result<bool> {
+        let mut updated = false;
+
+        if let Some(size) = parameters.get("window_size").and_then(|v| v.as_u64()) {
+            updated |= self.set_window_size(size as usize);
+        }
+
+        if let Some(max_lag) = parameters.get("max_lag_samples").and_then(|v| v.as_u64()) {
+            let max_lag = Some(max_lag as usize);
+            if max_lag != self.max_lag {
+                self.max_lag = max_lag;
+                updated = true;
+            }
+        }
+
+        if let Some(interpolation) = parameters.get("interpolation").and_then(|v| v.as_bool()) {
+            if interpolation != self.interpolation {
+                self.interpolation = interpolation;
+                updated = true;
+            }
+        }
+
+        Ok(updated)
+    }
+
+    fn set_shared_computing_state(&mut self, shared_state: Option<SharedComputingState>) {
+        if let Some(state) = shared_state {
+            self.shared_state = state;
+        }
+    }
+
+    fn get_shared_computing_state(&self) -> Option<SharedComputingState> {
+        Some(self.shared_state.clone())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
@@ -0,0 +1,240 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Dead-letter queue for measurements an action driver could not deliver
+//!
+//! When the driver of a [`UniversalActionNode`](super::UniversalActionNode)
+//! fails to deliver a measurement, after the retries of the driver itself, the
+//! measurement is stored in the dead-letter queue of the node instead of being
+//! lost. The action thread re-delivers the queued measurements, oldest first,
+//! after each successful delivery and every retry interval, so they reach the
+//! endpoint once it recovers.
+//!
+//! The queue is bounded: when it is full, the oldest measurement is dropped to
+//! make room for the new one. It is persisted as JSON lines in
+//! `<directory>/<node_id>.jsonl`, rewritten on every change, so measurements
+//! queued before a restart are re-delivered by the next run.
+//!
+//! Alerts are not queued, only measurements.
+//!
+//! ### Configuration
+//!
+//! ```yaml
+//! - id: dashboard
+//!   node_type: action_universal
+//!   parameters:
+//!     dead_letter:
+//!       directory: /var/lib/photoacoustic/dead_letter
+//!       capacity: 1000
+//!       retry_interval_ms: 5000
+//!     driver:
+//!       type: https_callback
+//!       config:
+//!         callback_url: https://dashboard.example.com/api/measurements
+//! ```
+
+use super::action_drivers::MeasurementData;
+use anyhow::{Context, Result};
+use log::warn;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Default maximum number of queued measurements
+pub const DEFAULT_DEAD_LETTER_CAPACITY: usize = 1000;
+
+/// Default interval between re-delivery attempts while the driver is idle
+pub const DEFAULT_DEAD_LETTER_RETRY_INTERVAL_MS: u64 = 5000;
+
+/// Counters of a dead-letter queue
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct DeadLetterStats {
+    /// Measurements currently waiting for re-delivery
+    pub queued: usize,
+    /// Measurements added to the queue since it was opened
+    pub dead_lettered: u64,
+    /// Queued measurements delivered since the queue was opened
+    pub redelivered: u64,
+    /// Measurements dropped because the queue was full
+    pub dropped: u64,
+}
+
+/// Bounded on-disk queue of undelivered measurements
+#[derive(Debug)]
+pub struct DeadLetterQueue {
+    path: PathBuf,
+    capacity: usize,
+    retry_interval: Duration,
+    entries: VecDeque<MeasurementData>,
+    stats: DeadLetterStats,
+}
+
+impl DeadLetterQueue {
+    /// Open the dead-letter queue of an action node
+    ///
+    /// Creates `directory` if needed and loads the measurements left in the
+    /// queue file by a previous run. Unreadable lines are skipped with a
+    /// warning, and only the newest `capacity` measurements are kept.
+    ///
+    /// ### Parameters
+    ///
+    /// * `directory` - Directory holding the queue files
+    /// * `node_id` - ID of the action node, used as the file name
+    /// * `capacity` - Maximum number of queued measurements, at least 1
+    /// * `retry_interval` - Interval between re-delivery attempts while the driver is idle
+    ///
+    /// ### Returns
+    ///
+    /// The queue, or an error if the capacity is 0 or the directory or file
+    /// cannot be read
+    pub fn open<P: AsRef<Path>>(
+        directory: P,
+        node_id: &str,
+        capacity: usize,
+        retry_interval: Duration,
+    ) -> Result<Self> {
+        anyhow::ensure!(
+            capacity > 0,
+            "Dead-letter queue capacity must be at least 1"
+        );
+        let directory = directory.as_ref();
+        fs::create_dir_all(directory).with_context(|| {
+            format!(
+                "Failed to create dead-letter directory {}",
+                directory.display()
+            )
+        })?;
+        let path = directory.join(format!("{}.jsonl", node_id));
+
+        let mut entries = VecDeque::new();
+        if path.exists() {
+            let contents = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read dead-letter queue {}", path.display()))?;
+            for (index, line) in contents.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<MeasurementData>(line) {
+                    Ok(data) => entries.push_back(data),
+                    Err(e) => warn!(
+                        "Skipping unreadable entry at line {} of dead-letter queue {}: {}",
+                        index + 1,
+                        path.display(),
+                        e
+                    ),
+                }
+            }
+        }
+
+        let mut queue = Self {
+            path,
+            capacity,
+            retry_interval,
+            entries,
+            stats: DeadLetterStats::default(),
+        };
+        if queue.entries.len() > capacity {
+            let excess = queue.entries.len() - capacity;
+            queue.entries.drain(..excess);
+            queue.stats.dropped = excess as u64;
+            queue.persist()?;
+        }
+        queue.stats.queued = queue.entries.len();
+        Ok(queue)
+    }
+
+    /// Path of the queue file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Maximum number of queued measurements
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Interval between re-delivery attempts while the driver is idle
+    pub fn retry_interval(&self) -> Duration {
+        self.retry_interval
+    }
+
+    /// Number of queued measurements
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no measurement is waiting for re-delivery
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Counters of the queue
+    pub fn stats(&self) -> DeadLetterStats {
+        self.stats
+    }
+
+    /// Oldest queued measurement, the next one to re-deliver
+    pub fn front(&self) -> Option<&MeasurementData> {
+        self.entries.front()
+    }
+
+    /// Queue a measurement the driver could not deliver
+    ///
+    /// When the queue is full, the oldest measurement is dropped.
+    ///
+    /// ### Parameters
+    ///
+    /// * `data` - The undelivered measurement
+    ///
+    /// ### Returns
+    ///
+    /// An error if the queue file cannot be written, the measurement stays
+    /// queued in memory
+    pub fn push(&mut self, data: MeasurementData) -> Result<()> {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+            self.stats.dropped += 1;
+        }
+        self.entries.push_back(data);
+        self.stats.dead_lettered += 1;
+        self.stats.queued = self.entries.len();
+        self.persist()
+    }
+
+    /// Remove the oldest measurement once it has been re-delivered
+    ///
+    /// ### Returns
+    ///
+    /// The removed measurement, or an error if the queue file cannot be written
+    pub fn pop_front(&mut self) -> Result<Option<MeasurementData>> {
+        let data = self.entries.pop_front();
+        if data.is_some() {
+            self.stats.redelivered += 1;
+            self.stats.queued = self.entries.len();
+            self.persist()?;
+        }
+        Ok(data)
+    }
+
+    /// Rewrite the queue file, through a temporary file so it is never left half written
+    fn persist(&self) -> Result<()> {
+        let temporary_path = self.path.with_extension("jsonl.tmp");
+        let mut file = fs::File::create(&temporary_path).with_context(|| {
+            format!(
+                "Failed to create dead-letter queue {}",
+                temporary_path.display()
+            )
+        })?;
+        for data in &self.entries {
+            serde_json::to_writer(&mut file, data)?;
+            file.write_all(b"\n")?;
+        }
+        file.sync_all()?;
+        fs::rename(&temporary_path, &self.path)
+            .with_context(|| format!("Failed to write dead-letter queue {}", self.path.display()))
+    }
+}
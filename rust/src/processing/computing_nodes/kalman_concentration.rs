@@ -0,0 +1,429 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! This module implements the KalmanConcentrationNode, which smooths the concentration
+//! computed by a ConcentrationNode over time.
+//!
+//! The KalmanConcentrationNode is a pass-through ComputingNode: each time its source
+//! ConcentrationNode publishes a new concentration, it runs one step of a scalar Kalman
+//! filter and stores the filtered concentration, with the variance of the estimate, in
+//! the shared computing state under its own ID.
+//!
+//! # Model
+//!
+//! The concentration is a random walk: between two measurements `dt` seconds apart, its
+//! variance grows by `q·dt`, where `q` is the process noise in ppm²/s. Each measurement
+//! has the variance `r`, the measurement noise in ppm². For a measurement `z`:
+//!
+//! ```text
+//! P⁻ = P + q·dt
+//! K  = P⁻ / (P⁻ + r)
+//! x  = x + K·(z − x)
+//! P  = (1 − K)·P⁻
+//! ```
+//!
+//! The first measurement initializes `x = z` and `P = r`. A small `q` relative to `r`
+//! gives a smooth estimate that follows slow changes; a larger `q` follows steps faster.
+//!
+//! # Configuration
+//!
+//! - `computing_concentration_id`: ID of the ConcentrationNode to filter (required)
+//! - `process_noise`: `q`, variance growth of the concentration in ppm²/s (default: 1.0)
+//! - `measurement_noise`: `r`, variance of a single measurement in ppm² (default: 100.0)
+//!
+//! The node must come after its source in the processing graph, so that each frame
+//! filters the concentration computed for the same frame.
+//!
+//! # Usage
+//!
+//! ```rust
+//! use rust_photoacoustic::processing::computing_nodes::KalmanConcentrationNode;
+//!
+//! let node = KalmanConcentrationNode::new("smoothed_co2".to_string())
+//!     .with_concentration_source("co2_concentration".to_string())
+//!     .with_process_noise(0.5)
+//!     .with_measurement_noise(25.0);
+//! assert!(node.get_estimate().is_none());
+//! ```
+
+use crate::processing::computing_nodes::{
+    ComputingSharedData, ConcentrationResult, SharedComputingState,
+};
+use crate::processing::{ProcessingData, ProcessingNode};
+use anyhow::Result;
+use log::{debug, warn};
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::sync::RwLock;
+
+/// A computing node filtering the concentration of a ConcentrationNode with a Kalman filter
+///
+/// The node passes its input through unchanged and writes a [`ConcentrationResult`] with
+/// the filtered concentration and its `concentration_variance` to the shared computing
+/// state, so that action nodes can monitor it like any concentration node.
+pub struct KalmanConcentrationNode {
+    /// Unique identifier for this node
+    id: String,
+
+    /// ID of the ConcentrationNode whose results are filtered
+    computing_concentration_id: Option<String>,
+
+    /// Variance growth of the concentration (ppm²/s)
+    process_noise: f64,
+
+    /// Variance of a single measurement (ppm²)
+    measurement_noise: f64,
+
+    /// Filtered concentration (ppm) and its variance (ppm²)
+    estimate: Option<(f64, f64)>,
+
+    /// Timestamp of the last measurement filtered
+    last_measurement: Option<SystemTime>,
+
+    /// Shared state for communicating results to other nodes
+    shared_state: SharedComputingState,
+
+    /// Statistics for monitoring performance
+    processing_count: u64,
+    update_count: u64,
+}
+
+impl KalmanConcentrationNode {
+    /// Create a new KalmanConcentration node with default parameters
+    ///
+    /// Default configuration:
+    /// - No source ConcentrationNode
+    /// - Process noise: 1.0 ppm²/s
+    /// - Measurement noise: 100.0 ppm² (10 ppm standard deviation)
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Unique identifier for this node
+    ///
+    /// # Returns
+    ///
+    /// A new KalmanConcentrationNode instance with its own shared state
+    pub fn new(id: String) -> Self {
+        Self::new_with_shared_state(id, None)
+    }
+
+    /// Create a new KalmanConcentration node with an external shared computing state
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - Unique identifier for this node
+    /// * `shared_state` - Optional external shared computing state, a new one if `None`
+    ///
+    /// # Returns
+    ///
+    /// A new KalmanConcentrationNode instance with the provided or new shared state
+    pub fn new_with_shared_state(id: String, shared_state: Option<SharedComputingState>) -> Self {
+        Self {
+            id,
+            computing_concentration_id: None,
+            process_noise: 1.0,
+            measurement_noise: 100.0,
+            estimate: None,
+            last_measurement: None,
+            shared_state: shared_state
+                .unwrap_or_else(|| Arc::new(RwLock::new(ComputingSharedData::default()))),
+            processing_count: 0,
+            update_count: 0,
+        }
+    }
+
+    /// Set the ConcentrationNode ID to filter
+    ///
+    /// # Arguments
+    ///
+    /// * `concentration_id` - ID of the ConcentrationNode to bind to
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_concentration_source(mut self, concentration_id: String) -> Self {
+        self.computing_concentration_id = Some(concentration_id);
+        self
+    }
+
+    /// Set the process noise
+    ///
+    /// Negative values are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `process_noise` - Variance growth of the concentration in ppm²/s
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_process_noise(mut self, process_noise: f64) -> Self {
+        if process_noise >= 0.0 {
+            self.process_noise = process_noise;
+        }
+        self
+    }
+
+    /// Set the measurement noise
+    ///
+    /// Values that are not strictly positive are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `measurement_noise` - Variance of a single measurement in ppm²
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_measurement_noise(mut self, measurement_noise: f64) -> Self {
+        if measurement_noise > 0.0 {
+            self.measurement_noise = measurement_noise;
+        }
+        self
+    }
+
+    /// Get access to the shared state for reading results
+    pub fn get_shared_state(&self) -> SharedComputingState {
+        Arc::clone(&self.shared_state)
+    }
+
+    /// Current filtered concentration (ppm) and its variance (ppm²)
+    ///
+    /// # Returns
+    ///
+    /// `None` until the first measurement
+    pub fn get_estimate(&self) -> Option<(f64, f64)> {
+        self.estimate
+    }
+
+    /// Get processing statistics
+    ///
+    /// # Returns
+    ///
+    /// Tuple of (processing_count, update_count)
+    pub fn get_statistics(&self) -> (u64, u64) {
+        (self.processing_count, self.update_count)
+    }
+
+    /// Run one step of the filter
+    ///
+    /// # Arguments
+    ///
+    /// * `measurement` - Measured concentration in ppm
+    /// * `timestamp` - Time of the measurement, giving the elapsed time since the previous one
+    ///
+    /// # Returns
+    ///
+    /// The filtered concentration (ppm) and its variance (ppm²)
+    fn step(&mut self, measurement: f64, timestamp: SystemTime) -> (f64, f64) {
+        let estimate = match (self.estimate, self.last_measurement) {
+            (Some((concentration, variance)), Some(previous)) => {
+                let elapsed = timestamp
+                    .duration_since(previous)
+                    .map(|elapsed| elapsed.as_secs_f64())
+                    .unwrap_or(0.0);
+                let predicted_variance = variance + self.process_noise * elapsed;
+                let gain = predicted_variance / (predicted_variance + self.measurement_noise);
+                (
+                    concentration + gain * (measurement - concentration),
+                    (1.0 - gain) * predicted_variance,
+                )
+            }
+            _ => (measurement, self.measurement_noise),
+        };
+
+        self.estimate = Some(estimate);
+        self.last_measurement = Some(timestamp);
+        self.update_count += 1;
+        estimate
+    }
+
+    /// Store the filtered concentration under this node's ID in the shared state
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The measurement filtered
+    /// * `concentration` - Filtered concentration in ppm
+    /// * `variance` - Variance of the filtered concentration in ppm²
+    fn update_shared_state(&self, source: ConcentrationResult, concentration: f64, variance: f64) {
+        let mut processing_metadata = source.processing_metadata;
+        if let Some(source_id) = &self.computing_concentration_id {
+            processing_metadata.insert("source_concentration_id".to_string(), source_id.clone());
+        }
+        let result = ConcentrationResult {
+            concentration_ppm: concentration,
+            concentration_variance: Some(variance),
+            processing_metadata,
+            ..source
+        };
+
+        match self.shared_state.try_write() {
+            Ok(mut state) => state.update_concentration_result(self.id.clone(), result),
+            Err(_) => warn!(
+                "Kalman concentration '{}': Failed to acquire write lock for shared state - concentration={:.2} ppm",
+                self.id, concentration
+            ),
+        }
+    }
+}
+
+impl ProcessingNode for KalmanConcentrationNode {
+    /// Filter the latest concentration of the source node, passing the input through
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Input data to pass through (unchanged)
+    ///
+    /// # Returns
+    ///
+    /// The same input data unchanged
+    fn process(&mut self, input: ProcessingData) -> Result<ProcessingData> {
+        self.processing_count += 1;
+
+        let measurement = match (
+            &self.computing_concentration_id,
+            self.shared_state.try_read(),
+        ) {
+            (Some(source_id), Ok(state)) => state.get_concentration_result(source_id).cloned(),
+            (None, _) => None,
+            (_, Err(_)) => {
+                if self.processing_count % 1000 == 0 {
+                    warn!(
+                        "Kalman concentration '{}': Failed to read shared state",
+                        self.id
+                    );
+                }
+                None
+            }
+        };
+
+        // Only new measurements are filtered
+        if let Some(measurement) =
+            measurement.filter(|result| Some(result.timestamp) != self.last_measurement)
+        {
+            let (concentration, variance) =
+                self.step(measurement.concentration_ppm, measurement.timestamp);
+            if self.update_count % 100 == 0 {
+                debug!(
+                    "Kalman concentration '{}': {:.2} ppm measured, {:.2} ± {:.2} ppm filtered",
+                    self.id,
+                    measurement.concentration_ppm,
+                    concentration,
+                    variance.sqrt()
+                );
+            }
+            self.update_shared_state(measurement, concentration, variance);
+        }
+
+        Ok(input)
+    }
+
+    fn node_id(&self) -> &str {
+        &self.id
+    }
+
+    fn node_type(&self) -> &str {
+        "computing_kalman_concentration"
+    }
+
+    /// KalmanConcentrationNode can process any data type (pass-through)
+    fn accepts_input(&self, _input: &ProcessingData) -> bool {
+        true
+    }
+
+    /// KalmanConcentrationNode is a pass-through node, so output type matches input type
+    fn output_type(&self, input: &ProcessingData) -> Option<String> {
+        match input {
+            ProcessingData::AudioFrame(_) => Some("AudioFrame".to_string()),
+            ProcessingData::SingleChannel { .. } => Some("SingleChannel".to_string()),
+            ProcessingData::DualChannel { .. } => Some("DualChannel".to_string()),
+            ProcessingData::PhotoacousticResult { .. } => Some("PhotoacousticResult".to_string()),
+        }
+    }
+
+    /// Forget the estimate, the next measurement initializes the filter again
+    fn reset(&mut self) {
+        self.estimate = None;
+        self.last_measurement = None;
+        self.processing_count = 0;
+        self.update_count = 0;
+    }
+
+    fn clone_node(&self) -> Box<dyn ProcessingNode> {
+        let mut node = KalmanConcentrationNode::new_with_shared_state(
+            self.id.clone(),
+            Some(self.shared_state.clone()),
+        )
+        .with_process_noise(self.process_noise)
+        .with_measurement_noise(self.measurement_noise);
+        node.computing_concentration_id = self.computing_concentration_id.clone();
+        Box::new(node)
+    }
+
+    fn supports_hot_reload(&self) -> bool {
+        true
+    }
+
+    /// Update configuration parameters dynamically
+    ///
+    /// Supports updating `process_noise`, `measurement_noise` and
+    /// `computing_concentration_id`. The estimate is kept, except when the source changes.
+    ///
+    /// # Arguments
+    ///
+    /// * `parameters` - JSON object containing parameter updates
+    ///
+    /// # Returns
+    ///
+    /// Result indicating success and whether any parameters were changed
+    fn update_config(&mut self, parameters: &serde_json::Value) -> Result<bool> {
+        let mut updated = false;
+
+        if let Some(process_noise) = parameters.get("process_noise").and_then(|v| v.as_f64()) {
+            if process_noise < 0.0 {
+                anyhow::bail!("process_noise must not be negative");
+            }
+            self.process_noise = process_noise;
+            updated = true;
+        }
+
+        if let Some(measurement_noise) =
+            parameters.get("measurement_noise").and_then(|v| v.as_f64())
+        {
+            if measurement_noise <= 0.0 {
+                anyhow::bail!("measurement_noise must be strictly positive");
+            }
+            self.measurement_noise = measurement_noise;
+            updated = true;
+        }
+
+        if let Some(source_id) = parameters
+            .get("computing_concentration_id")
+            .and_then(|v| v.as_str())
+        {
+            if self.computing_concentration_id.as_deref() != Some(source_id) {
+                self.computing_concentration_id = Some(source_id.to_string());
+                self.estimate = None;
+                self.last_measurement = None;
+                updated = true;
+            }
+        }
+
+        Ok(updated)
+    }
+
+    fn set_shared_computing_state(&mut self, shared_state: Option<SharedComputingState>) {
+        if let Some(state) = shared_state {
+            self.shared_state = state;
+        }
+    }
+
+    fn get_shared_computing_state(&self) -> Option<SharedComputingState> {
+        Some(self.shared_state.clone())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
@@ -2,6 +2,7 @@
 // This file is part of the rust-photoacoustic project and is licensed under the
 // SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
 
+use crate::processing::nodes::{DetectionEvent, DetectionState};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::SystemTime;
@@ -10,6 +11,10 @@ use tokio::sync::RwLock;
 pub mod action_drivers;
 pub mod action_trait;
 pub mod concentration;
+pub mod cross_correlation;
+pub mod dead_letter;
+pub mod kalman_concentration;
+pub mod outlier_rejection;
 pub mod peak_finder;
 pub mod universal_action;
 
@@ -35,6 +40,8 @@ pub struct PeakResult {
 pub struct ConcentrationResult {
     /// Calculated concentration in parts per million (ppm)
     pub concentration_ppm: f64,
+    /// Variance of the concentration estimate in ppm², for filtered concentrations
+    pub concentration_variance: Option<f64>,
     /// Source PeakFinderNode ID that provided the amplitude data
     pub source_peak_finder_id: String,
     /// Spectral line identifier (e.g., "CO2_line", "CH4_line")
@@ -53,6 +60,32 @@ pub struct ConcentrationResult {
     pub processing_metadata: HashMap<String, String>,
 }
 
+/// Result data from a cross-correlation node
+#[derive(Debug, Clone)]
+pub struct TimeDelayResult {
+    /// Lag of channel B behind channel A in samples, fractional when interpolated
+    pub lag_samples: f64,
+    /// Lag of channel B behind channel A in seconds
+    pub lag_seconds: f64,
+    /// Normalized cross-correlation at the peak (-1.0 to 1.0)
+    pub peak_correlation: f32,
+    /// Number of samples of each channel correlated
+    pub window_size: usize,
+    /// Timestamp of when this delay was estimated
+    pub timestamp: SystemTime,
+}
+
+/// Detection state published by a photoacoustic output node
+#[derive(Debug, Clone)]
+pub struct DetectionResult {
+    /// Current state of the detection state machine
+    pub state: DetectionState,
+    /// Most recent detection events, oldest first, with increasing sequence numbers
+    pub events: Vec<DetectionEvent>,
+    /// Timestamp of when this state was published
+    pub timestamp: SystemTime,
+}
+
 /// Shared data structure for computing nodes
 ///
 /// This structure holds the results of analytical computations performed by computing nodes.
@@ -63,6 +96,8 @@ pub struct ConcentrationResult {
 ///
 /// - `peak_results`: HashMap of peak detection results from multiple nodes, keyed by node ID
 /// - `concentration_results`: HashMap of concentration calculation results from multiple nodes, keyed by node ID
+/// - `time_delay_results`: HashMap of time delays between the channels from multiple nodes, keyed by node ID
+/// - `detection_results`: HashMap of detection states and events from output nodes, keyed by node ID
 /// - `peak_frequency`: Detected resonance frequency in Hz (legacy, use peak_results)
 /// - `peak_amplitude`: Normalized amplitude of the detected peak (legacy, use peak_results)
 /// - `concentration_ppm`: Calculated gas concentration in ppm (legacy, use concentration_results)
@@ -76,6 +111,12 @@ pub struct ComputingSharedData {
     /// Concentration calculation results from multiple nodes, keyed by node ID
     pub concentration_results: HashMap<String, ConcentrationResult>,
 
+    /// Time delays between the channels from multiple nodes, keyed by node ID
+    pub time_delay_results: HashMap<String, TimeDelayResult>,
+
+    /// Detection states and events from output nodes, keyed by node ID
+    pub detection_results: HashMap<String, DetectionResult>,
+
     // Legacy fields for backward compatibility
     pub peak_frequency: Option<f32>,
     pub peak_amplitude: Option<f32>,
@@ -89,6 +130,8 @@ impl Default for ComputingSharedData {
         Self {
             peak_results: HashMap::new(),
             concentration_results: HashMap::new(),
+            time_delay_results: HashMap::new(),
+            detection_results: HashMap::new(),
             peak_frequency: None,
             peak_amplitude: None,
             concentration_ppm: None,
@@ -135,6 +178,28 @@ impl ComputingSharedData {
         self.last_update = result.timestamp;
     }
 
+    /// Get time delay result for a specific node ID
+    pub fn get_time_delay_result(&self, node_id: &str) -> Option<&TimeDelayResult> {
+        self.time_delay_results.get(node_id)
+    }
+
+    /// Update time delay result for a specific node ID
+    pub fn update_time_delay_result(&mut self, node_id: String, result: TimeDelayResult) {
+        self.last_update = result.timestamp;
+        self.time_delay_results.insert(node_id, result);
+    }
+
+    /// Get detection result for a specific node ID
+    pub fn get_detection_result(&self, node_id: &str) -> Option<&DetectionResult> {
+        self.detection_results.get(node_id)
+    }
+
+    /// Update detection result for a specific node ID
+    pub fn update_detection_result(&mut self, node_id: String, result: DetectionResult) {
+        self.last_update = result.timestamp;
+        self.detection_results.insert(node_id, result);
+    }
+
     /// Get the most recent peak result across all nodes
     pub fn get_latest_peak_result(&self) -> Option<&PeakResult> {
         self.peak_results
@@ -159,6 +224,144 @@ impl ComputingSharedData {
         self.concentration_results.keys().cloned().collect()
     }
 
+    /// Read-only snapshot of the results passed to Python scripts
+    ///
+    /// `PythonNode` and `PythonActionDriver` expose this snapshot to their
+    /// scripts as the `computing_state` module global. Timestamps are seconds
+    /// since the Unix epoch and absent values are `None`:
+    ///
+    /// ```text
+    /// {
+    ///     "peak_frequency": float | None,       # latest peak, any node (Hz)
+    ///     "peak_amplitude": float | None,       # latest peak, any node
+    ///     "concentration_ppm": float | None,    # latest concentration, any node
+    ///     "polynomial_coefficients": [a0, a1, a2, a3, a4],
+    ///     "last_update": float,
+    ///     "peak_results": {
+    ///         "<node_id>": {"frequency", "amplitude", "concentration_ppm",
+    ///                       "coherence_score", "timestamp"}
+    ///     },
+    ///     "concentration_results": {
+    ///         "<node_id>": {"concentration_ppm", "concentration_variance",
+    ///                       "source_peak_finder_id",
+    ///                       "spectral_line_id", "polynomial_coefficients",
+    ///                       "source_amplitude", "source_frequency",
+    ///                       "temperature_compensated", "timestamp"}
+    ///     },
+    ///     "time_delay_results": {
+    ///         "<node_id>": {"lag_samples", "lag_seconds", "peak_correlation",
+    ///                       "window_size", "timestamp"}
+    ///     },
+    ///     "detection_results": {
+    ///         "<node_id>": {"state", "detected", "timestamp",
+    ///                       "events": [{"sequence", "kind", "level", "frame_number",
+    ///                                   "frame_timestamp", "duration_ms", "timestamp"}]}
+    ///     }
+    /// }
+    /// ```
+    pub fn script_context(&self) -> serde_json::Value {
+        fn epoch_seconds(time: SystemTime) -> f64 {
+            time.duration_since(SystemTime::UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs_f64())
+                .unwrap_or(0.0)
+        }
+
+        let peak_results: serde_json::Map<String, serde_json::Value> = self
+            .peak_results
+            .iter()
+            .map(|(node_id, result)| {
+                (
+                    node_id.clone(),
+                    serde_json::json!({
+                        "frequency": result.frequency,
+                        "amplitude": result.amplitude,
+                        "concentration_ppm": result.concentration_ppm,
+                        "coherence_score": result.coherence_score,
+                        "timestamp": epoch_seconds(result.timestamp),
+                    }),
+                )
+            })
+            .collect();
+        let concentration_results: serde_json::Map<String, serde_json::Value> = self
+            .concentration_results
+            .iter()
+            .map(|(node_id, result)| {
+                (
+                    node_id.clone(),
+                    serde_json::json!({
+                        "concentration_ppm": result.concentration_ppm,
+                        "concentration_variance": result.concentration_variance,
+                        "source_peak_finder_id": result.source_peak_finder_id,
+                        "spectral_line_id": result.spectral_line_id,
+                        "polynomial_coefficients": result.polynomial_coefficients,
+                        "source_amplitude": result.source_amplitude,
+                        "source_frequency": result.source_frequency,
+                        "temperature_compensated": result.temperature_compensated,
+                        "timestamp": epoch_seconds(result.timestamp),
+                    }),
+                )
+            })
+            .collect();
+        let time_delay_results: serde_json::Map<String, serde_json::Value> = self
+            .time_delay_results
+            .iter()
+            .map(|(node_id, result)| {
+                (
+                    node_id.clone(),
+                    serde_json::json!({
+                        "lag_samples": result.lag_samples,
+                        "lag_seconds": result.lag_seconds,
+                        "peak_correlation": result.peak_correlation,
+                        "window_size": result.window_size,
+                        "timestamp": epoch_seconds(result.timestamp),
+                    }),
+                )
+            })
+            .collect();
+        let detection_results: serde_json::Map<String, serde_json::Value> = self
+            .detection_results
+            .iter()
+            .map(|(node_id, result)| {
+                let events: Vec<serde_json::Value> = result
+                    .events
+                    .iter()
+                    .map(|event| {
+                        serde_json::json!({
+                            "sequence": event.sequence,
+                            "kind": event.kind.as_str(),
+                            "level": event.level,
+                            "frame_number": event.frame_number,
+                            "frame_timestamp": event.frame_timestamp,
+                            "duration_ms": event.duration_ms,
+                            "timestamp": epoch_seconds(event.timestamp),
+                        })
+                    })
+                    .collect();
+                (
+                    node_id.clone(),
+                    serde_json::json!({
+                        "state": result.state.as_str(),
+                        "detected": result.state.is_detected(),
+                        "events": events,
+                        "timestamp": epoch_seconds(result.timestamp),
+                    }),
+                )
+            })
+            .collect();
+
+        serde_json::json!({
+            "peak_frequency": self.peak_frequency,
+            "peak_amplitude": self.peak_amplitude,
+            "concentration_ppm": self.concentration_ppm,
+            "polynomial_coefficients": self.polynomial_coefficients,
+            "last_update": epoch_seconds(self.last_update),
+            "peak_results": peak_results,
+            "concentration_results": concentration_results,
+            "time_delay_results": time_delay_results,
+            "detection_results": detection_results,
+        })
+    }
+
     /// Check if a node has recent peak data (within last 30 seconds)
     pub fn has_recent_peak_data(&self, node_id: &str) -> bool {
         if let Some(result) = self.peak_results.get(node_id) {
@@ -194,5 +397,9 @@ pub use action_trait::{
     ActionHistoryEntry, ActionNode, ActionNodeHelper, ActionTrigger, CircularBuffer,
 };
 pub use concentration::ConcentrationNode;
+pub use cross_correlation::CrossCorrelationNode;
+pub use dead_letter::{DeadLetterQueue, DeadLetterStats};
+pub use kalman_concentration::KalmanConcentrationNode;
+pub use outlier_rejection::HampelFilter;
 pub use peak_finder::PeakFinderNode;
 pub use universal_action::UniversalActionNode;
@@ -0,0 +1,220 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Outlier rejection for concentration readings
+//!
+//! This module implements a causal Hampel filter. Each reading is compared with the
+//! median of the previous readings: it is rejected when it lies more than `threshold`
+//! scaled median absolute deviations (MAD) away from it. The MAD is scaled by 1.4826,
+//! so that it estimates the standard deviation of Gaussian noise. Deviations below an
+//! absolute floor are always accepted: with a small window the MAD of pure noise is
+//! sometimes tiny, and normal readings would otherwise be rejected. Without a floor,
+//! a window of identical readings (MAD of zero) says nothing about the noise and
+//! rejects nothing, otherwise the slightest change would be an outlier.
+//!
+//! Rejected readings still enter the window. An isolated spike is rejected, while a
+//! sustained change is accepted once it fills more than half of the window.
+//!
+//! # Configuration
+//!
+//! - `window_size`: Number of previous readings the median is computed on (default: 11)
+//! - `threshold_mads`: Rejection threshold in scaled MADs (default: 3.0)
+//! - `min_deviation_ppm`: Deviation from the median always accepted (default: 0.0)
+//!
+//! # Usage
+//!
+//! ```rust
+//! use rust_photoacoustic::processing::computing_nodes::HampelFilter;
+//!
+//! let mut filter = HampelFilter::new(5, 3.0).unwrap();
+//! for reading in [400.0, 402.0, 399.0, 401.0, 400.5] {
+//!     assert!(filter.accept(reading));
+//! }
+//! assert!(!filter.accept(900.0));
+//! assert!(filter.accept(401.5));
+//! assert_eq!(filter.rejected_count(), 1);
+//! ```
+
+use anyhow::Result;
+use std::collections::VecDeque;
+
+/// Scale factor turning the MAD into a standard deviation estimate for Gaussian noise
+const MAD_SCALE: f64 = 1.4826;
+
+/// A causal Hampel filter rejecting isolated outliers in a series of readings
+#[derive(Debug, Clone)]
+pub struct HampelFilter {
+    /// Number of previous readings the median is computed on
+    window_size: usize,
+
+    /// Rejection threshold in scaled MADs
+    threshold: f64,
+
+    /// Deviation from the median always accepted
+    min_deviation: f64,
+
+    /// Previous readings, oldest first, including rejected ones
+    history: VecDeque<f64>,
+
+    /// Number of readings rejected since creation or reset
+    rejected_count: u64,
+}
+
+impl HampelFilter {
+    /// Default number of previous readings in the window
+    pub const DEFAULT_WINDOW_SIZE: usize = 11;
+
+    /// Default rejection threshold in scaled MADs
+    pub const DEFAULT_THRESHOLD: f64 = 3.0;
+
+    /// Create a new Hampel filter
+    ///
+    /// # Arguments
+    ///
+    /// * `window_size` - Number of previous readings the median is computed on (at least 3)
+    /// * `threshold` - Rejection threshold in scaled MADs (strictly positive)
+    ///
+    /// # Returns
+    ///
+    /// The filter, or an error if a parameter is out of range
+    pub fn new(window_size: usize, threshold: f64) -> Result<Self> {
+        if window_size < 3 {
+            anyhow::bail!("Outlier rejection window_size must be at least 3");
+        }
+        if !threshold.is_finite() || threshold <= 0.0 {
+            anyhow::bail!("Outlier rejection threshold_mads must be strictly positive");
+        }
+
+        Ok(Self {
+            window_size,
+            threshold,
+            min_deviation: 0.0,
+            history: VecDeque::with_capacity(window_size + 1),
+            rejected_count: 0,
+        })
+    }
+
+    /// Set the deviation from the median that is always accepted
+    ///
+    /// Negative values are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_deviation` - Absolute deviation floor, in the unit of the readings
+    ///
+    /// # Returns
+    ///
+    /// Self for method chaining
+    pub fn with_min_deviation(mut self, min_deviation: f64) -> Self {
+        if min_deviation >= 0.0 {
+            self.min_deviation = min_deviation;
+        }
+        self
+    }
+
+    /// Create a Hampel filter from its JSON configuration
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Object with optional `window_size`, `threshold_mads` and
+    ///   `min_deviation_ppm` fields
+    ///
+    /// # Returns
+    ///
+    /// The filter, or an error if the configuration is invalid
+    pub fn from_config(config: &serde_json::Value) -> Result<Self> {
+        let config = config
+            .as_object()
+            .ok_or_else(|| anyhow::anyhow!("Outlier rejection configuration must be an object"))?;
+        let window_size = match config.get("window_size") {
+            Some(value) => value.as_u64().ok_or_else(|| {
+                anyhow::anyhow!("Outlier rejection window_size must be a positive integer")
+            })? as usize,
+            None => Self::DEFAULT_WINDOW_SIZE,
+        };
+        let threshold = match config.get("threshold_mads") {
+            Some(value) => value.as_f64().ok_or_else(|| {
+                anyhow::anyhow!("Outlier rejection threshold_mads must be a number")
+            })?,
+            None => Self::DEFAULT_THRESHOLD,
+        };
+        let min_deviation = match config.get("min_deviation_ppm") {
+            Some(value) => match value.as_f64() {
+                Some(min_deviation) if min_deviation >= 0.0 => min_deviation,
+                _ => anyhow::bail!("Outlier rejection min_deviation_ppm must not be negative"),
+            },
+            None => 0.0,
+        };
+        Ok(Self::new(window_size, threshold)?.with_min_deviation(min_deviation))
+    }
+
+    /// Check a new reading and add it to the window
+    ///
+    /// Readings are accepted until the window is full, and while its MAD is zero
+    /// unless a deviation floor is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The new reading
+    ///
+    /// # Returns
+    ///
+    /// `true` if the reading is accepted, `false` if it is an outlier
+    pub fn accept(&mut self, value: f64) -> bool {
+        let accepted = self.history.len() < self.window_size || {
+            let mut window: Vec<f64> = self.history.iter().copied().collect();
+            let center = median(&mut window);
+            let mut deviations: Vec<f64> = window.iter().map(|x| (x - center).abs()).collect();
+            let scale = MAD_SCALE * median(&mut deviations);
+            (scale == 0.0 && self.min_deviation == 0.0)
+                || (value - center).abs() <= (self.threshold * scale).max(self.min_deviation)
+        };
+
+        self.history.push_back(value);
+        if self.history.len() > self.window_size {
+            self.history.pop_front();
+        }
+        if !accepted {
+            self.rejected_count += 1;
+        }
+        accepted
+    }
+
+    /// Number of readings rejected since creation or the last reset
+    pub fn rejected_count(&self) -> u64 {
+        self.rejected_count
+    }
+
+    /// Number of previous readings the median is computed on
+    pub fn window_size(&self) -> usize {
+        self.window_size
+    }
+
+    /// Rejection threshold in scaled MADs
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Deviation from the median always accepted
+    pub fn min_deviation(&self) -> f64 {
+        self.min_deviation
+    }
+
+    /// Clear the window and the rejection counter
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.rejected_count = 0;
+    }
+}
+
+/// Median of a non-empty slice, reordering it
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let middle = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
@@ -1239,6 +1239,7 @@ mod tests {
                 sample_rate: 48000,
                 processing_steps: vec!["test".to_string()],
                 processing_latency_us: 100,
+                snr_db: None,
             },
         };
         let output_data = peak_finder.process(input_data.clone()).unwrap();
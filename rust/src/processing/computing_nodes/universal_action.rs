@@ -223,24 +223,28 @@
 
 use crate::processing::computing_nodes::{
     action_drivers::{ActionDriver, AlertData, MeasurementData},
+    dead_letter::{DeadLetterQueue, DeadLetterStats},
     ActionHistoryEntry, ActionNode, ActionNodeHelper, ActionTrigger, CircularBuffer,
     ComputingSharedData, SharedComputingState,
 };
-use crate::processing::nodes::{ProcessingData, ProcessingNode};
+use crate::processing::nodes::{DetectionEventKind, ProcessingData, ProcessingNode};
 use anyhow::{anyhow, Result};
 use log::{debug, error, info, warn};
 use serde_json::json;
 use std::collections::HashMap;
-use std::sync::mpsc;
+use std::sync::mpsc::{self, RecvTimeoutError};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::SystemTime;
+use tokio::sync::oneshot;
 
 /// Messages sent to the action processing thread
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 enum ActionMessage {
     Update(MeasurementData),
     Alert(AlertData),
+    /// Ask the driver whether it is ready, see [`ActionDriver::is_ready`]
+    Readiness(oneshot::Sender<bool>),
     Shutdown,
 }
 
@@ -312,6 +316,10 @@ pub struct UniversalActionNode {
     action_sender: Option<mpsc::Sender<ActionMessage>>,
     /// Handle to the action processing thread
     action_thread_handle: Option<thread::JoinHandle<()>>,
+    /// Measurements the driver could not deliver, shared with the action processing thread
+    dead_letter: Arc<Mutex<Option<DeadLetterQueue>>>,
+    /// Configuration updates sent by the driver, for instance from remote commands
+    config_updates: Option<Mutex<mpsc::Receiver<serde_json::Value>>>,
     /// Unique identifier for this action node
     /// REQUIRED: Every ActionNode must have a unique ID for monitoring and debugging
     id: String,
@@ -346,6 +354,8 @@ pub struct UniversalActionNode {
     /// These fields demonstrate how to track ActionNode performance
     /// Useful for debugging and system monitoring
     processing_count: u64, // Total number of process() calls
+    /// Sequence number of the last detection event handled, keyed by output node ID
+    last_detection_sequences: HashMap<String, u64>,
     actions_triggered: u64,                 // Total number of actions executed
     last_update_time: Option<SystemTime>,   // When computing data was last processed
     last_action_update: Option<SystemTime>, // When action was last updated (hardware-specific)
@@ -376,8 +386,8 @@ impl UniversalActionNode {
     pub fn new(id: String) -> Self {
         Self {
             id,
-            action_sender: None,                    // No thread started yet
-            action_thread_handle: None,             // No thread started yet
+            action_sender: None,                      // No thread started yet
+            action_thread_handle: None,               // No thread started yet
             history_buffer: CircularBuffer::new(1), // Minimal buffer - MUST configure with with_history_buffer_capacity()
             monitored_nodes: Vec::new(),            // Empty: add nodes via with_monitored_node()
             shared_computing_state: None,           // Set later by ProcessingGraph
@@ -385,9 +395,12 @@ impl UniversalActionNode {
             amplitude_threshold: Some(0.8),         // Default: 80% amplitude alarm
             action_update_interval_ms: 1000,        // Default: update every second
             processing_count: 0,                    // Performance counter
+            last_detection_sequences: HashMap::new(), // No detection events handled yet
             actions_triggered: 0,                   // Action counter
             last_update_time: None,                 // No updates yet
             last_action_update: None,               // No action updates yet
+            dead_letter: Arc::new(Mutex::new(None)),
+            config_updates: None,
         }
     }
 
@@ -415,8 +428,8 @@ impl UniversalActionNode {
     pub fn new_with_shared_state(id: String, shared_state: Option<SharedComputingState>) -> Self {
         Self {
             id,
-            action_sender: None,                    // No thread started yet
-            action_thread_handle: None,             // No thread started yet
+            action_sender: None,                      // No thread started yet
+            action_thread_handle: None,               // No thread started yet
             history_buffer: CircularBuffer::new(1), // Minimal buffer - MUST configure with with_history_buffer_capacity()
             monitored_nodes: Vec::new(),            // Empty: add nodes via with_monitored_node()
             shared_computing_state: shared_state,   // Use provided shared state
@@ -424,9 +437,12 @@ impl UniversalActionNode {
             amplitude_threshold: Some(0.8),         // Default: 80% amplitude alarm
             action_update_interval_ms: 1000,        // Default: update every second
             processing_count: 0,                    // Performance counter
+            last_detection_sequences: HashMap::new(), // No detection events handled yet
             actions_triggered: 0,                   // Action counter
             last_update_time: None,                 // No updates yet
             last_action_update: None,               // No action updates yet
+            dead_letter: Arc::new(Mutex::new(None)),
+            config_updates: None,
         }
     }
 
@@ -578,11 +594,17 @@ impl UniversalActionNode {
     ///     .with_driver(Box::new(http_driver));
     /// ```
     pub fn with_driver(mut self, mut driver: Box<dyn ActionDriver>) -> Self {
+        driver.set_shared_computing_state(self.shared_computing_state.clone());
+        let (config_sender, config_receiver) = mpsc::channel::<serde_json::Value>();
+        driver.set_config_update_sender(config_sender);
+        self.config_updates = Some(Mutex::new(config_receiver));
+
         // Create channel for communicating with the action thread
         let (sender, receiver) = mpsc::channel::<ActionMessage>();
 
         // Start the action processing thread
         let node_id = self.id.clone();
+        let dead_letter = self.dead_letter.clone();
         let handle = thread::spawn(move || {
             let rt = match tokio::runtime::Runtime::new() {
                 Ok(rt) => rt,
@@ -609,23 +631,46 @@ impl UniversalActionNode {
                 node_id
             );
 
-            // Process messages
-            while let Ok(message) = receiver.recv() {
+            // Deliver the measurements left in the dead-letter queue by a previous run
+            Self::redeliver_dead_letters(&rt, &mut driver, &dead_letter, &node_id);
+
+            // Process messages, waking up every retry interval while a
+            // dead-letter queue is configured
+            loop {
+                let retry_interval = dead_letter
+                    .lock()
+                    .ok()
+                    .and_then(|queue| queue.as_ref().map(DeadLetterQueue::retry_interval));
+                let message = match retry_interval {
+                    Some(interval) => match receiver.recv_timeout(interval) {
+                        Ok(message) => Some(message),
+                        Err(RecvTimeoutError::Timeout) => None,
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    },
+                    None => match receiver.recv() {
+                        Ok(message) => Some(message),
+                        Err(_) => break,
+                    },
+                };
+
                 match message {
-                    ActionMessage::Update(data) => {
+                    Some(ActionMessage::Update(data)) => {
                         if let Err(e) = rt.block_on(driver.update_action(&data)) {
                             error!(
                                 "Display thread [{}]: Failed to update action: {}",
                                 node_id, e
                             );
+                            Self::dead_letter_measurement(&dead_letter, &node_id, data);
                         } else {
                             debug!(
                                 "Display thread [{}]: Successfully updated action with {:.2} ppm",
                                 node_id, data.concentration_ppm
                             );
+                            // The driver has recovered, deliver the backlog
+                            Self::redeliver_dead_letters(&rt, &mut driver, &dead_letter, &node_id);
                         }
                     }
-                    ActionMessage::Alert(alert) => {
+                    Some(ActionMessage::Alert(alert)) => {
                         if let Err(e) = rt.block_on(driver.show_alert(&alert)) {
                             error!("Display thread [{}]: Failed to show alert: {}", node_id, e);
                         } else {
@@ -635,13 +680,25 @@ impl UniversalActionNode {
                             );
                         }
                     }
-                    ActionMessage::Shutdown => {
+                    Some(ActionMessage::Readiness(reply)) => {
+                        let _ = reply.send(rt.block_on(driver.is_ready()));
+                    }
+                    Some(ActionMessage::Shutdown) => {
                         info!("Display thread [{}]: Shutting down", node_id);
                         break;
                     }
+                    None => Self::redeliver_dead_letters(&rt, &mut driver, &dead_letter, &node_id),
                 }
             }
 
+            // Every queued message has been delivered, release the driver
+            if let Err(e) = rt.block_on(driver.shutdown()) {
+                error!(
+                    "Display thread [{}]: Failed to shut down driver: {}",
+                    node_id, e
+                );
+            }
+
             info!("Display thread [{}]: Thread terminated", node_id);
         });
 
@@ -650,6 +707,144 @@ impl UniversalActionNode {
         self
     }
 
+    /// Apply the configuration updates sent by the driver since the last call
+    ///
+    /// The parameters are those of a hot reload, see
+    /// [`ActionDriver::set_config_update_sender`].
+    fn apply_config_updates(&mut self) {
+        let updates: Vec<serde_json::Value> = match &self.config_updates {
+            Some(receiver) => match receiver.lock() {
+                Ok(receiver) => receiver.try_iter().collect(),
+                Err(_) => return,
+            },
+            None => return,
+        };
+        for parameters in updates {
+            match self.update_config(&parameters) {
+                Ok(true) => info!(
+                    "ActionNode '{}': Configuration updated by its driver: {}",
+                    self.id, parameters
+                ),
+                Ok(false) => warn!(
+                    "ActionNode '{}': Ignored configuration update without known parameter: {}",
+                    self.id, parameters
+                ),
+                Err(e) => error!(
+                    "ActionNode '{}': Failed to apply configuration update: {}",
+                    self.id, e
+                ),
+            }
+        }
+    }
+
+    /// Keep the measurements the driver could not deliver in a dead-letter queue
+    ///
+    /// Undelivered measurements are re-delivered, oldest first, after the
+    /// next successful delivery and every retry interval of the queue. See
+    /// [`dead_letter`](crate::processing::computing_nodes::dead_letter).
+    ///
+    /// Call it before [`with_driver`](Self::with_driver): the action thread
+    /// only starts its periodic re-delivery attempts when the queue is
+    /// configured by the time it waits for the next message.
+    ///
+    /// # Arguments
+    /// * `queue` - The queue opened for this node
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let queue = DeadLetterQueue::open("dead_letter", "action", 1000, Duration::from_secs(5))?;
+    /// let node = UniversalActionNode::new("action".to_string())
+    ///     .with_history_buffer_capacity(100)
+    ///     .with_dead_letter_queue(queue)
+    ///     .with_driver(Box::new(http_driver));
+    /// ```
+    pub fn with_dead_letter_queue(self, queue: DeadLetterQueue) -> Self {
+        match self.dead_letter.lock() {
+            Ok(mut dead_letter) => *dead_letter = Some(queue),
+            Err(e) => error!("Dead-letter queue of [{}] unavailable: {}", self.id, e),
+        }
+        self
+    }
+
+    /// Counters of the dead-letter queue, `None` when no queue is configured
+    pub fn dead_letter_stats(&self) -> Option<DeadLetterStats> {
+        self.dead_letter
+            .lock()
+            .ok()
+            .and_then(|queue| queue.as_ref().map(DeadLetterQueue::stats))
+    }
+
+    /// Store an undelivered measurement in the dead-letter queue, if configured
+    fn dead_letter_measurement(
+        dead_letter: &Mutex<Option<DeadLetterQueue>>,
+        node_id: &str,
+        data: MeasurementData,
+    ) {
+        let Ok(mut dead_letter) = dead_letter.lock() else {
+            return;
+        };
+        let Some(queue) = dead_letter.as_mut() else {
+            return;
+        };
+        if let Err(e) = queue.push(data) {
+            error!(
+                "Display thread [{}]: Failed to persist dead-letter queue: {}",
+                node_id, e
+            );
+        }
+        warn!(
+            "Display thread [{}]: Measurement kept in dead-letter queue ({} queued)",
+            node_id,
+            queue.len()
+        );
+    }
+
+    /// Re-deliver the queued measurements, oldest first, until the driver fails again
+    fn redeliver_dead_letters(
+        rt: &tokio::runtime::Runtime,
+        driver: &mut Box<dyn ActionDriver>,
+        dead_letter: &Mutex<Option<DeadLetterQueue>>,
+        node_id: &str,
+    ) {
+        loop {
+            // The lock is not held while the driver sends, so that the
+            // statistics can be read meanwhile
+            let next = match dead_letter.lock() {
+                Ok(queue) => queue.as_ref().and_then(|queue| queue.front().cloned()),
+                Err(_) => None,
+            };
+            let Some(data) = next else {
+                return;
+            };
+
+            if let Err(e) = rt.block_on(driver.update_action(&data)) {
+                debug!(
+                    "Display thread [{}]: Dead-letter re-delivery failed: {}",
+                    node_id, e
+                );
+                return;
+            }
+
+            if let Ok(mut queue) = dead_letter.lock() {
+                if let Some(queue) = queue.as_mut() {
+                    if let Err(e) = queue.pop_front() {
+                        error!(
+                            "Display thread [{}]: Failed to persist dead-letter queue: {}",
+                            node_id, e
+                        );
+                    }
+                    if queue.is_empty() {
+                        info!(
+                            "Display thread [{}]: Dead-letter queue delivered ({} measurements re-delivered)",
+                            node_id,
+                            queue.stats().redelivered
+                        );
+                    }
+                }
+            }
+        }
+    }
+
     /// Initialize the configured driver
     ///
     /// # PATTERN: Driver initialization method
@@ -674,6 +869,25 @@ impl UniversalActionNode {
         self.action_sender.is_some() && self.action_thread_handle.is_some()
     }
 
+    /// Ask the driver whether it can deliver measurements
+    ///
+    /// The action processing thread answers with [`ActionDriver::is_ready`]
+    /// once the driver is initialized and the messages queued before are
+    /// handled. The answer never comes when the initialization failed or the
+    /// thread stopped, so callers should wait for it with a timeout.
+    ///
+    /// # Returns
+    /// The receiver of the answer, `None` when no driver is configured or its
+    /// thread has stopped
+    pub fn driver_readiness(&self) -> Option<oneshot::Receiver<bool>> {
+        let (reply, answer) = oneshot::channel();
+        self.action_sender
+            .as_ref()?
+            .send(ActionMessage::Readiness(reply))
+            .ok()?;
+        Some(answer)
+    }
+
     /// Send a action update message to the processing thread
     fn send_action_update(&self, data: MeasurementData) {
         if let Some(ref sender) = self.action_sender {
@@ -965,6 +1179,7 @@ impl UniversalActionNode {
                 "has_driver": self.has_driver(),
                 "driver_type": if self.has_driver() { "configured" } else { "none" }
             },
+            "dead_letter": self.dead_letter_stats(),
             "performance": {
                 "processing_count": self.processing_count,
                 "actions_triggered": self.actions_triggered,
@@ -1147,6 +1362,21 @@ impl ProcessingNode for UniversalActionNode {
         self.shared_computing_state.clone()
     }
 
+    fn shutdown(&mut self) {
+        // The action thread delivers the queued messages in order, then calls
+        // the driver's `shutdown` before terminating
+        if let Some(sender) = self.action_sender.take() {
+            if let Err(e) = sender.send(ActionMessage::Shutdown) {
+                debug!("Action thread [{}] already terminated: {}", self.id, e);
+            }
+        }
+        if let Some(handle) = self.action_thread_handle.take() {
+            if handle.join().is_err() {
+                error!("Action thread [{}] panicked during shutdown", self.id);
+            }
+        }
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -1228,6 +1458,7 @@ impl ActionNode for UniversalActionNode {
     /// and related peak data without tight coupling to specific peak finder IDs.
     fn update_from_computing_data(&mut self, computing_data: &ComputingSharedData) -> Result<()> {
         self.last_update_time = Some(SystemTime::now());
+        self.apply_config_updates();
 
         // Update history buffer with data from monitored concentration nodes
         for node_id in &self.monitored_nodes.clone() {
@@ -1292,6 +1523,33 @@ impl ActionNode for UniversalActionNode {
             }
         }
 
+        // Check for new detection events from monitored output nodes
+        for node_id in &self.monitored_nodes {
+            let Some(detection) = computing_data.get_detection_result(node_id) else {
+                continue;
+            };
+            let Some(last_event) = detection.events.last() else {
+                continue;
+            };
+            let mut last_seen = self
+                .last_detection_sequences
+                .get(node_id)
+                .copied()
+                .unwrap_or(0);
+            if last_event.sequence < last_seen {
+                // The output node was recreated and numbers its events from 1 again
+                last_seen = 0;
+            }
+            for event in detection.events.iter().filter(|e| e.sequence > last_seen) {
+                triggers.push(ActionTrigger::Detection {
+                    event: event.clone(),
+                    source_node_id: node_id.clone(),
+                });
+            }
+            self.last_detection_sequences
+                .insert(node_id.clone(), last_event.sequence);
+        }
+
         // Check for data timeouts (30 seconds default) for concentration nodes
         let timeout_seconds = 30;
 
@@ -1321,6 +1579,11 @@ impl ActionNode for UniversalActionNode {
 
         // Loop through all monitored nodes to check for timeouts
         'node_loop: for node_id in &self.monitored_nodes {
+            // Output nodes publish detections on state changes only, they never time out
+            if computing_data.get_detection_result(node_id).is_some() {
+                continue 'node_loop;
+            }
+
             // Check if we have any data in the history buffer
             if let Some(last_timestamp) = last_timestamp_map.get(node_id) {
                 if let Ok(elapsed) = last_timestamp.elapsed() {
@@ -1596,6 +1859,36 @@ impl ActionNode for UniversalActionNode {
                     Ok(false)
                 }
             }
+            ActionTrigger::Detection {
+                event,
+                source_node_id,
+            } => {
+                let (severity, verb) = match event.kind {
+                    DetectionEventKind::Onset => ("warning", "started"),
+                    DetectionEventKind::Release => ("info", "ended"),
+                };
+                let message = format!(
+                    "Detection {} on node '{}': level {:.4} after {} ms",
+                    verb, source_node_id, event.level, event.duration_ms
+                );
+                info!("Detection Alert Queued [{}]: {}", self.id, message);
+
+                let mut data = HashMap::new();
+                data.insert("source_node_id".to_string(), json!(source_node_id));
+                data.insert("sequence".to_string(), json!(event.sequence));
+                data.insert("level".to_string(), json!(event.level));
+                data.insert("frame_number".to_string(), json!(event.frame_number));
+                data.insert("duration_ms".to_string(), json!(event.duration_ms));
+                self.send_alert(AlertData {
+                    alert_type: format!("detection_{}", event.kind.as_str()),
+                    severity: severity.to_string(),
+                    message,
+                    data,
+                    timestamp: SystemTime::now(),
+                });
+                self.actions_triggered += 1;
+                Ok(true)
+            }
             ActionTrigger::Custom {
                 trigger_id,
                 data: _,
@@ -1669,7 +1962,8 @@ impl ActionNode for UniversalActionNode {
             },
             "configuration": {
                 "action_update_interval_ms": self.action_update_interval_ms
-            }
+            },
+            "dead_letter": self.dead_letter_stats()
         }))
     }
 
@@ -1677,6 +1971,7 @@ impl ActionNode for UniversalActionNode {
         self.history_buffer.clear();
         self.processing_count = 0;
         self.actions_triggered = 0;
+        self.last_detection_sequences.clear();
         self.last_update_time = None;
         self.last_action_update = None;
 
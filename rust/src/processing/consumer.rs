@@ -7,16 +7,17 @@
 //! This module provides the main processing consumer that reads from the SharedAudioStream
 //! and processes frames through the configurable processing graph.
 
-use crate::acquisition::{AudioStreamConsumer, SharedAudioStream};
+use crate::acquisition::{AudioFrame, AudioStreamConsumer, SessionRecorder, SharedAudioStream};
 use crate::processing::result::{FrameInfo, ProcessingMetadata};
 use crate::processing::{PhotoacousticAnalysis, ProcessingData, ProcessingGraph, ProcessingResult};
 use crate::visualization::shared_state::SharedVisualizationState;
 use anyhow::Result;
 use log::{debug, error, info, warn};
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::{
     atomic::{AtomicBool, AtomicU64, Ordering},
-    Arc,
+    Arc, Mutex,
 };
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::{broadcast, RwLock};
@@ -31,6 +32,8 @@ pub struct ProcessingConsumer {
     consumer: Option<AudioStreamConsumer>,
     /// Control flag for the consumer
     running: Arc<AtomicBool>,
+    /// Set to process the queued frames and stop, see [`ProcessingConsumerHandle::drain`]
+    draining: Arc<AtomicBool>,
     /// Counter of processed frames
     frames_processed: Arc<AtomicU64>,
     /// Counter of failed processing attempts
@@ -49,6 +52,118 @@ pub struct ProcessingConsumer {
     last_config_version: Arc<AtomicU64>,
     /// Last known node parameters for fine-grained change detection
     last_node_parameters: Arc<RwLock<HashMap<String, serde_json::Value>>>,
+    /// Recorder of the graph input, see [`record_session`](Self::record_session)
+    session_recorder: Arc<Mutex<Option<SessionRecorder>>>,
+}
+
+/// Maximum time the processing loop waits for a frame before checking its
+/// stop and drain flags again
+const FRAME_WAIT_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Handle controlling a [`ProcessingConsumer`] moved into its own task
+///
+/// Obtained with [`ProcessingConsumer::handle`] before the consumer is started.
+/// The daemon uses it during the graceful shutdown to drain the frames still
+/// queued in the audio stream and to flush the processing graph.
+#[derive(Clone)]
+pub struct ProcessingConsumerHandle {
+    consumer_id: String,
+    running: Arc<AtomicBool>,
+    draining: Arc<AtomicBool>,
+    processing_graph: Arc<RwLock<ProcessingGraph>>,
+    session_recorder: Arc<Mutex<Option<SessionRecorder>>>,
+}
+
+impl ProcessingConsumerHandle {
+    /// Ask the consumer to process the frames already queued, then stop
+    ///
+    /// The processing loop no longer waits for new frames: it stops as soon as
+    /// the audio stream has no queued frame left. Poll [`is_running`](Self::is_running)
+    /// to know when the drain is complete.
+    pub fn drain(&self) {
+        info!("Draining ProcessingConsumer '{}'", self.consumer_id);
+        self.draining.store(true, Ordering::Relaxed);
+    }
+
+    /// Check if the consumer processing loop is still running
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    /// Get the processing graph executed by the consumer
+    pub fn processing_graph(&self) -> Arc<RwLock<ProcessingGraph>> {
+        Arc::clone(&self.processing_graph)
+    }
+
+    /// Record the input of the processing graph to a session file
+    ///
+    /// Every frame processed from now on is appended to the file, after the
+    /// configuration of the graph, until [`stop_session_recording`](Self::stop_session_recording).
+    /// A [`ReplaySource`](crate::acquisition::ReplaySource) reading the file reproduces
+    /// the run. A recording already in progress is closed first. Parameters
+    /// changed while recording are not recorded.
+    ///
+    /// ### Arguments
+    ///
+    /// * `path` - Path of the session file, overwritten if it exists
+    ///
+    /// ### Returns
+    ///
+    /// An error if the graph configuration is not available or the file cannot
+    /// be written
+    pub async fn record_session(&self, path: impl AsRef<Path>) -> Result<()> {
+        let graph_config = self.processing_graph.read().await.to_config()?;
+        let mut recorder = self
+            .session_recorder
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Session recorder lock poisoned"))?;
+        // Close the previous recording first, it may be at the same path
+        if let Some(previous) = recorder.take() {
+            previous.finish()?;
+        }
+        *recorder = Some(SessionRecorder::create(path, &graph_config)?);
+        Ok(())
+    }
+
+    /// Stop recording the input of the processing graph
+    ///
+    /// ### Returns
+    ///
+    /// The number of frames recorded, `None` if no recording was in progress
+    pub fn stop_session_recording(&self) -> Result<Option<u64>> {
+        let recorder = self
+            .session_recorder
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Session recorder lock poisoned"))?
+            .take();
+        recorder.map(SessionRecorder::finish).transpose()
+    }
+
+    /// Drain the queued frames, then flush the nodes of the processing graph
+    ///
+    /// Waits for the processing loop to stop, then calls
+    /// [`ProcessingGraph::shutdown`] so that action drivers deliver their
+    /// queued sends and record nodes finalize their files. The wait is not
+    /// bounded: callers apply their own deadline.
+    pub async fn drain_and_shutdown(&self) {
+        self.drain();
+        while self.is_running() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        // Node shutdown joins the driver threads, keep it off the async workers
+        let graph = Arc::clone(&self.processing_graph);
+        match tokio::task::spawn_blocking(move || graph.blocking_write().shutdown()).await {
+            Ok(()) => info!(
+                "ProcessingConsumer '{}': Processing graph flushed",
+                self.consumer_id
+            ),
+            Err(e) => error!(
+                "ProcessingConsumer '{}': Processing graph shutdown failed: {}",
+                self.consumer_id, e
+            ),
+        }
+    }
 }
 
 /// Processing statistics
@@ -81,6 +196,7 @@ impl ProcessingConsumer {
             processing_graph: Arc::new(RwLock::new(processing_graph)),
             consumer: None,
             running: Arc::new(AtomicBool::new(false)),
+            draining: Arc::new(AtomicBool::new(false)),
             frames_processed: Arc::new(AtomicU64::new(0)),
             processing_failures: Arc::new(AtomicU64::new(0)),
             consumer_id,
@@ -90,6 +206,7 @@ impl ProcessingConsumer {
             config: None,
             last_config_version: Arc::new(AtomicU64::new(0)),
             last_node_parameters: Arc::new(RwLock::new(HashMap::new())),
+            session_recorder: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -112,6 +229,7 @@ impl ProcessingConsumer {
             processing_graph: Arc::new(RwLock::new(processing_graph)),
             consumer: None,
             running: Arc::new(AtomicBool::new(false)),
+            draining: Arc::new(AtomicBool::new(false)),
             frames_processed: Arc::new(AtomicU64::new(0)),
             processing_failures: Arc::new(AtomicU64::new(0)),
             consumer_id,
@@ -121,6 +239,7 @@ impl ProcessingConsumer {
             config: None,
             last_config_version: Arc::new(AtomicU64::new(0)),
             last_node_parameters: Arc::new(RwLock::new(HashMap::new())),
+            session_recorder: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -147,6 +266,7 @@ impl ProcessingConsumer {
             processing_graph: Arc::new(RwLock::new(processing_graph)),
             consumer: None,
             running: Arc::new(AtomicBool::new(false)),
+            draining: Arc::new(AtomicBool::new(false)),
             frames_processed: Arc::new(AtomicU64::new(0)),
             processing_failures: Arc::new(AtomicU64::new(0)),
             consumer_id,
@@ -156,6 +276,7 @@ impl ProcessingConsumer {
             config: Some(config),
             last_config_version: Arc::new(AtomicU64::new(initial_hash)),
             last_node_parameters: Arc::new(RwLock::new(HashMap::new())),
+            session_recorder: Arc::new(Mutex::new(None)),
         }
     }
 
@@ -250,6 +371,17 @@ impl ProcessingConsumer {
         }
     }
 
+    /// Get a handle to drain the consumer once it has been moved into its task
+    pub fn handle(&self) -> ProcessingConsumerHandle {
+        ProcessingConsumerHandle {
+            consumer_id: self.consumer_id.clone(),
+            running: Arc::clone(&self.running),
+            draining: Arc::clone(&self.draining),
+            processing_graph: Arc::clone(&self.processing_graph),
+            session_recorder: Arc::clone(&self.session_recorder),
+        }
+    }
+
     /// Check if the consumer is running
     pub fn is_running(&self) -> bool {
         self.running.load(Ordering::Relaxed)
@@ -265,13 +397,29 @@ impl ProcessingConsumer {
         self.processing_failures.load(Ordering::Relaxed)
     }
 
+    /// Record the input of the processing graph to a session file
+    ///
+    /// See [`ProcessingConsumerHandle::record_session`].
+    pub async fn record_session(&self, path: impl AsRef<Path>) -> Result<()> {
+        self.handle().record_session(path).await
+    }
+
+    /// Stop recording the input of the processing graph
+    ///
+    /// See [`ProcessingConsumerHandle::stop_session_recording`].
+    pub fn stop_session_recording(&self) -> Result<Option<u64>> {
+        self.handle().stop_session_recording()
+    }
+
     /// Get processing statistics
     pub async fn get_stats(&self) -> ProcessingStats {
         self.stats.read().await.clone()
     }
 
     /// Update the processing graph at runtime
-    pub async fn update_graph(&self, new_graph: ProcessingGraph) -> Result<()> {
+    ///
+    /// A graph without thread pool takes the pool of the current graph.
+    pub async fn update_graph(&self, mut new_graph: ProcessingGraph) -> Result<()> {
         info!(
             "Updating processing graph for consumer '{}'",
             self.consumer_id
@@ -283,6 +431,9 @@ impl ProcessingConsumer {
         // Update the graph
         {
             let mut graph = self.processing_graph.write().await;
+            if new_graph.thread_pool().is_none() {
+                new_graph.set_thread_pool(graph.thread_pool().cloned());
+            }
             *graph = new_graph;
         }
 
@@ -305,7 +456,27 @@ impl ProcessingConsumer {
         while self.running.load(Ordering::Relaxed) {
             // Get the next frame from the audio stream
             if let Some(ref mut consumer) = self.consumer {
-                match consumer.next_frame().await {
+                let next_frame = if self.draining.load(Ordering::Relaxed) {
+                    // Process the frames already queued, then stop
+                    match consumer.try_next_frame() {
+                        Some(frame) => Some(frame),
+                        None => {
+                            info!(
+                                "ProcessingConsumer '{}': Queued frames drained",
+                                self.consumer_id
+                            );
+                            break;
+                        }
+                    }
+                } else {
+                    // Wake up regularly to observe stop and drain requests
+                    match tokio::time::timeout(FRAME_WAIT_TIMEOUT, consumer.next_frame()).await {
+                        Ok(frame) => frame,
+                        Err(_) => continue,
+                    }
+                };
+
+                match next_frame {
                     Some(frame) => {
                         let start_time = Instant::now();
 
@@ -374,6 +545,7 @@ impl ProcessingConsumer {
             }
         }
 
+        self.running.store(false, Ordering::Relaxed);
         info!(
             "ProcessingConsumer '{}': Processing loop stopped",
             self.consumer_id
@@ -381,12 +553,31 @@ impl ProcessingConsumer {
         Ok(())
     }
 
+    /// Append a frame to the session recording, if any
+    ///
+    /// A recording error is logged and closes the recording.
+    fn record_session_frame(&self, frame: &AudioFrame) {
+        let Ok(mut recorder) = self.session_recorder.lock() else {
+            return;
+        };
+        if let Some(session) = recorder.as_mut() {
+            if let Err(e) = session.record(frame) {
+                error!(
+                    "ProcessingConsumer '{}': Failed to record frame {} to {}, recording stopped: {}",
+                    self.consumer_id,
+                    frame.frame_number,
+                    session.path().display(),
+                    e
+                );
+                *recorder = None;
+            }
+        }
+    }
+
     /// Process a single audio frame through the processing graph
-    async fn process_frame(
-        &self,
-        frame: crate::acquisition::AudioFrame,
-    ) -> Result<Option<ProcessingResult>> {
+    async fn process_frame(&self, frame: AudioFrame) -> Result<Option<ProcessingResult>> {
         let start_time = Instant::now();
+        self.record_session_frame(&frame);
 
         // Create frame info for the result
         let frame_info = FrameInfo {
@@ -409,12 +600,14 @@ impl ProcessingConsumer {
         let total_processing_time = start_time.elapsed().as_micros() as u64;
 
         // If we got results, create a ProcessingResult
-        if let Some(final_data) = processing_results.first() {
+        let result = if let Some(final_data) = processing_results.first() {
             match final_data {
                 ProcessingData::PhotoacousticResult { signal, metadata } => {
                     // We already have a photoacoustic result
-                    let analysis =
+                    let mut analysis =
                         PhotoacousticAnalysis::from_signal(signal.clone(), frame_info.sample_rate);
+                    // The SNR measured by the output node, when its gate is enabled
+                    analysis.characteristics.snr_db = metadata.snr_db;
 
                     // Convert nodes::ProcessingMetadata to result::ProcessingMetadata
                     let result_metadata = ProcessingMetadata {
@@ -446,7 +639,7 @@ impl ProcessingConsumer {
                         result_metadata,
                     );
 
-                    Ok(Some(result))
+                    Some(result)
                 }
                 ProcessingData::SingleChannel {
                     samples,
@@ -476,7 +669,7 @@ impl ProcessingConsumer {
                         metadata,
                     );
 
-                    Ok(Some(result))
+                    Some(result)
                 }
                 _ => {
                     // Other data types - convert to basic result
@@ -503,13 +696,21 @@ impl ProcessingConsumer {
                         metadata,
                     );
 
-                    Ok(Some(result))
+                    Some(result)
                 }
             }
         } else {
             // No results from processing graph
-            Ok(None)
-        }
+            None
+        };
+
+        // Hand the sample buffers back to the graph for the next frame
+        self.processing_graph
+            .read()
+            .await
+            .recycle(processing_results);
+
+        Ok(result)
     }
 
     /// Update processing statistics
@@ -837,7 +1038,7 @@ impl ProcessingConsumer {
         last_node_parameters: &Arc<RwLock<HashMap<String, serde_json::Value>>>,
         consumer_id: &str,
     ) -> Result<bool> {
-        let (current_hash, node_configs, graph_config, photoacoustic_config) = {
+        let (current_hash, node_configs, graph_config, photoacoustic_config, performance) = {
             let config_read = config.read().await;
             let hash = Self::calculate_config_hash(&config_read.processing);
             let node_configs = config_read
@@ -849,7 +1050,14 @@ impl ProcessingConsumer {
                 .collect::<HashMap<String, _>>();
             let graph_config = config_read.processing.default_graph.clone();
             let photoacoustic_config = config_read.photoacoustic.clone();
-            (hash, node_configs, graph_config, photoacoustic_config)
+            let performance = config_read.processing.performance.clone();
+            (
+                hash,
+                node_configs,
+                graph_config,
+                photoacoustic_config,
+                performance,
+            )
         };
 
         let last_hash = last_config_version.load(Ordering::Relaxed);
@@ -860,6 +1068,13 @@ impl ProcessingConsumer {
                 consumer_id, last_hash, current_hash
             );
 
+            // The execution mode applies to the current graph without a rebuild
+            {
+                let mut graph_write = processing_graph.write().await;
+                graph_write.set_parallel_execution(performance.parallel_execution);
+                graph_write.set_buffer_reuse(performance.reuse_buffers);
+            }
+
             // Detect which specific nodes have changed by comparing individual parameters
             let changed_nodes = {
                 let mut last_params = last_node_parameters.write().await;
@@ -963,10 +1178,14 @@ impl ProcessingConsumer {
                     &graph_config,
                     &photoacoustic_config,
                 ) {
-                    Ok(new_graph) => {
-                        // Update the processing graph
+                    Ok(mut new_graph) => {
+                        new_graph.set_parallel_execution(performance.parallel_execution);
+                        new_graph.set_buffer_reuse(performance.reuse_buffers);
+
+                        // Update the processing graph, keeping the thread pool of the daemon
                         {
                             let mut graph_write = processing_graph.write().await;
+                            new_graph.set_thread_pool(graph_write.thread_pool().cloned());
                             *graph_write = new_graph;
                         }
 
@@ -1034,6 +1253,10 @@ impl ProcessingConsumer {
         // Hash output node
         config.default_graph.output_node.hash(&mut hasher);
 
+        // Hash the execution mode
+        config.performance.parallel_execution.hash(&mut hasher);
+        config.performance.reuse_buffers.hash(&mut hasher);
+
         hasher.finish()
     }
 }
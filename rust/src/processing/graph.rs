@@ -7,30 +7,30 @@
 //! This module manages the processing graph structure, connections between nodes,
 //! and graph execution logic.
 
-use crate::config::processing::{NodeConfig, ProcessingGraphConfig};
+use crate::config::processing::{ConnectionConfig, NodeConfig, ProcessingGraphConfig};
 use crate::preprocessing::differential::SimpleDifferential;
 use crate::preprocessing::filter::{
     BandpassFilter, ButterBandpassFilter, ButterHighpassFilter, ButterLowpassFilter,
     CauerBandpassFilter, CauerHighpassFilter, CauerLowpassFilter, ChebyBandpassFilter,
     ChebyHighpassFilter, ChebyLowpassFilter, HighpassFilter, LowpassFilter,
 };
+use crate::processing::buffer_pool::{BufferPoolStatistics, FrameBufferPool};
 use crate::processing::computing_nodes::{
-    action_drivers::{
-        ActionDriver, HttpsCallbackActionDriver, KafkaActionDriver, RedisActionDriver,
-    },
-    ConcentrationNode, PeakFinderNode, SharedComputingState, UniversalActionNode,
+    action_drivers::create_action_driver,
+    dead_letter::{DEFAULT_DEAD_LETTER_CAPACITY, DEFAULT_DEAD_LETTER_RETRY_INTERVAL_MS},
+    ConcentrationNode, CrossCorrelationNode, DeadLetterQueue, HampelFilter,
+    KalmanConcentrationNode, PeakFinderNode, SharedComputingState, UniversalActionNode,
 };
-
-// Import PythonActionDriver when feature is enabled
-#[cfg(feature = "python-driver")]
-use crate::processing::computing_nodes::action_drivers::{PythonActionDriver, PythonDriverConfig};
 use crate::processing::nodes::{
-    ChannelMixerNode, ChannelSelectorNode, ChannelTarget, DifferentialNode, FilterNode, GainNode,
-    InputNode, MixStrategy, NodeId, PhotoacousticOutputNode, ProcessingData, ProcessingNode,
-    RecordNode, StreamingNode, StreamingNodeRegistry,
+    AdaptiveFilterNode, ChannelMixerNode, ChannelSelectorNode, ChannelTarget, DecimationNode,
+    DetectionStateMachine, DifferentialNode, FilterNode, GainNode, InputNode, InterpolationNode,
+    MixStrategy, NodeId, PhotoacousticOutputNode, ProcessingData, ProcessingNode, RecordNode,
+    SpectralSubtractionNode, StreamingNode, StreamingNodeRegistry,
 };
+use crate::utility::panic_message;
 use anyhow::Result;
 use log::debug;
+use rayon::prelude::*;
 use rocket_okapi::JsonSchema;
 use schemars::{generate::SchemaGenerator, Schema};
 use serde::{Deserialize, Serialize};
@@ -38,6 +38,8 @@ use serde_json::Value;
 use std::borrow::Cow;
 use std::collections::{HashMap, VecDeque};
 use std::fmt;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use thiserror::Error;
 
@@ -75,6 +77,20 @@ pub enum ProcessingGraphError {
     NoInputNode,
     #[error("Graph execution failed: {0}")]
     ExecutionFailed(String),
+    #[error("Node '{0}' panicked: {1}")]
+    NodePanicked(String, String),
+}
+
+/// State of one frame while the graph executes
+struct FrameState<'a> {
+    /// Node receiving the graph input
+    input_node_id: &'a str,
+    /// Graph input, until the input node takes it
+    input_data: Option<ProcessingData>,
+    /// Outputs of the nodes already executed
+    node_outputs: HashMap<NodeId, ProcessingData>,
+    /// Reads left on each output, with buffer reuse
+    pending_reads: HashMap<NodeId, usize>,
 }
 
 /// Represents a connection between two nodes
@@ -105,6 +121,12 @@ pub struct NodeStatistics {
     /// Maximum processing time observed
     #[serde(with = "duration_serde")]
     pub worst_processing_time: Duration,
+    /// Number of calls interrupted by the node's execution timeout
+    #[serde(default)]
+    pub timeouts: u64,
+    /// Node timeout total at the last update, so that a reset restarts the count
+    #[serde(skip)]
+    timeouts_seen: u64,
     /// Last update timestamp (not serialized)
     #[serde(skip)]
     pub last_update: Option<Instant>,
@@ -162,6 +184,10 @@ impl JsonSchema for NodeStatistics {
             "worst_processing_time".to_string(),
             duration_schema.to_value(),
         );
+        properties.insert(
+            "timeouts".to_string(),
+            gen.subschema_for::<u64>().to_value(),
+        );
 
         let mut object_schema = serde_json::Map::new();
         object_schema.insert("type".to_string(), serde_json::json!("object"));
@@ -198,6 +224,8 @@ impl NodeStatistics {
             average_processing_time: Duration::ZERO,
             fastest_processing_time: Duration::MAX,
             worst_processing_time: Duration::ZERO,
+            timeouts: 0,
+            timeouts_seen: 0,
             last_update: None,
         }
     }
@@ -218,12 +246,23 @@ impl NodeStatistics {
         self.last_update = Some(Instant::now());
     }
 
+    /// Count the timeouts reported by the node since the last update
+    ///
+    /// ### Parameters
+    ///
+    /// * `total` - Cumulative count from [`ProcessingNode::timeout_count`]
+    pub fn record_timeouts(&mut self, total: u64) {
+        self.timeouts += total.saturating_sub(self.timeouts_seen);
+        self.timeouts_seen = total;
+    }
+
     pub fn reset(&mut self) {
         self.frames_processed = 0;
         self.total_processing_time = Duration::ZERO;
         self.average_processing_time = Duration::ZERO;
         self.fastest_processing_time = Duration::MAX;
         self.worst_processing_time = Duration::ZERO;
+        self.timeouts = 0;
         self.last_update = None;
     }
 }
@@ -239,7 +278,11 @@ impl fmt::Display for NodeStatistics {
             self.average_processing_time.as_secs_f64() * 1000.0,
             self.fastest_processing_time.as_secs_f64() * 1000.0,
             self.worst_processing_time.as_secs_f64() * 1000.0
-        )
+        )?;
+        if self.timeouts > 0 {
+            write!(f, ", {} timeouts", self.timeouts)?;
+        }
+        Ok(())
     }
 }
 
@@ -425,6 +468,12 @@ impl ProcessingGraphStatistics {
         }
     }
 
+    pub fn record_node_timeouts(&mut self, node_id: &str, total: u64) {
+        if let Some(stats) = self.node_statistics.get_mut(node_id) {
+            stats.record_timeouts(total);
+        }
+    }
+
     pub fn reset_all_statistics(&mut self) {
         for stats in self.node_statistics.values_mut() {
             stats.reset();
@@ -545,6 +594,14 @@ pub struct ProcessingGraph {
     connections: Vec<Connection>,
     /// Cached execution order (topologically sorted)
     execution_order: Option<Vec<NodeId>>,
+    /// Cached execution levels: nodes of a level only depend on earlier levels
+    execution_levels: Option<Vec<Vec<NodeId>>>,
+    /// Run the nodes of a level concurrently
+    parallel_execution: bool,
+    /// Pool running the parallel levels, the global rayon pool when `None`
+    thread_pool: Option<Arc<rayon::ThreadPool>>,
+    /// Pool of sample buffers, when buffer reuse is enabled
+    buffer_pool: Option<FrameBufferPool>,
     /// Input node ID
     input_node: Option<NodeId>,
     /// Output node ID(s)
@@ -555,6 +612,8 @@ pub struct ProcessingGraph {
     node_parameters: HashMap<NodeId, HashMap<String, serde_json::Value>>,
     /// Shared computing state for all nodes
     shared_computing_state: Option<SharedComputingState>,
+    /// Configuration the graph was created from, if any
+    config: Option<ProcessingGraphConfig>,
 }
 
 impl ProcessingGraph {
@@ -564,11 +623,16 @@ impl ProcessingGraph {
             nodes: HashMap::new(),
             connections: Vec::new(),
             execution_order: None,
+            execution_levels: None,
+            parallel_execution: false,
+            thread_pool: None,
+            buffer_pool: None,
             input_node: None,
             output_nodes: Vec::new(),
             statistics: ProcessingGraphStatistics::new(),
             node_parameters: HashMap::new(),
             shared_computing_state: None,
+            config: None,
         }
     }
 
@@ -736,7 +800,111 @@ impl ProcessingGraph {
         Ok(())
     }
 
+    /// Enable or disable the parallel execution of independent branches
+    ///
+    /// When enabled, [`execute`](Self::execute) groups the nodes into levels
+    /// whose nodes only depend on earlier levels, and runs the nodes of a level
+    /// on the global rayon thread pool, or the one given to
+    /// [`set_thread_pool`](Self::set_thread_pool). Node outputs and statistics
+    /// are merged in execution order, so the results are the same as with
+    /// serial execution as long as the nodes of a branch do not depend on side
+    /// effects of another branch (for example a value that one branch writes to
+    /// the shared computing state and another one reads within the same frame).
+    ///
+    /// Parallel execution only pays off when the branches cost more than
+    /// dispatching their nodes to the pool on every frame. It is disabled by
+    /// default.
+    ///
+    /// ### Arguments
+    ///
+    /// * `enabled` - Run the nodes of a level concurrently
+    pub fn set_parallel_execution(&mut self, enabled: bool) {
+        self.parallel_execution = enabled;
+    }
+
+    /// Whether independent branches run concurrently
+    pub fn parallel_execution(&self) -> bool {
+        self.parallel_execution
+    }
+
+    /// Set the thread pool running the levels of a parallel execution
+    ///
+    /// The daemon passes the pool of its real-time runtime, so that parallel
+    /// branches follow `daemon.realtime_threads` and `daemon.realtime_cpu_affinity`.
+    ///
+    /// ### Arguments
+    ///
+    /// * `thread_pool` - The pool, or `None` for the global rayon pool
+    pub fn set_thread_pool(&mut self, thread_pool: Option<Arc<rayon::ThreadPool>>) {
+        self.thread_pool = thread_pool;
+    }
+
+    /// Thread pool running the parallel levels, `None` for the global rayon pool
+    pub fn thread_pool(&self) -> Option<&Arc<rayon::ThreadPool>> {
+        self.thread_pool.as_ref()
+    }
+
+    /// Enable or disable the reuse of sample buffers across frames
+    ///
+    /// When enabled, [`execute`](Self::execute) runs the nodes through
+    /// [`ProcessingNode::process_pooled`] with a [`FrameBufferPool`] owned by
+    /// the graph. The output of a node is moved to the last node reading it
+    /// instead of being cloned, the other readers get a copy in a pooled
+    /// buffer, and the intermediate outputs are given back to the pool at the
+    /// end of the frame. Callers hand the returned outputs back with
+    /// [`recycle`](Self::recycle) once they are done with them, so that the
+    /// next frame reuses their buffers too.
+    ///
+    /// The outputs are the same as with the allocating path. Disabling buffer
+    /// reuse drops the pool. It is disabled by default.
+    ///
+    /// ### Arguments
+    ///
+    /// * `enabled` - Draw the sample buffers from a pool
+    pub fn set_buffer_reuse(&mut self, enabled: bool) {
+        if !enabled {
+            self.buffer_pool = None;
+        } else if self.buffer_pool.is_none() {
+            self.buffer_pool = Some(FrameBufferPool::default());
+        }
+    }
+
+    /// Whether sample buffers are reused across frames
+    pub fn buffer_reuse(&self) -> bool {
+        self.buffer_pool.is_some()
+    }
+
+    /// Get the counters of the buffer pool, when buffer reuse is enabled
+    pub fn buffer_pool_statistics(&self) -> Option<BufferPoolStatistics> {
+        self.buffer_pool.as_ref().map(FrameBufferPool::statistics)
+    }
+
+    /// Give the sample buffers of outputs returned by `execute` back to the pool
+    ///
+    /// Does nothing when buffer reuse is disabled.
+    ///
+    /// ### Arguments
+    ///
+    /// * `outputs` - Outputs the caller no longer needs
+    pub fn recycle(&self, outputs: Vec<ProcessingData>) {
+        if let Some(pool) = &self.buffer_pool {
+            for output in outputs {
+                pool.recycle(output);
+            }
+        }
+    }
+
     /// Execute the processing graph with the given input data
+    ///
+    /// Nodes run in topological order. With
+    /// [`set_parallel_execution`](Self::set_parallel_execution), the nodes of
+    /// each execution level run concurrently instead. With
+    /// [`set_buffer_reuse`](Self::set_buffer_reuse), the sample buffers come
+    /// from the graph's buffer pool.
+    ///
+    /// A node that panics fails the frame with
+    /// [`ProcessingGraphError::NodePanicked`]; the node is kept in the graph and
+    /// receives the next frames.
     pub fn execute(&mut self, input_data: ProcessingData) -> Result<Vec<ProcessingData>> {
         let graph_start_time = Instant::now();
 
@@ -751,79 +919,58 @@ impl ProcessingGraph {
         let execution_order = self.get_execution_order()?.clone();
 
         // Store intermediate results
-        let mut node_outputs: HashMap<NodeId, ProcessingData> = HashMap::new();
-
-        // Execute nodes in topological order
-        for node_id in &execution_order {
-            let node_start_time = Instant::now();
-
-            let node = self.nodes.get_mut(node_id).unwrap();
-
-            let input_for_node = if node_id == &input_node_id {
-                // Input node gets the original input data
-                input_data.clone()
-            } else {
-                // Find the input for this node from connected predecessors
-                let predecessors: Vec<&str> = self
-                    .connections
-                    .iter()
-                    .filter(|conn| &conn.to == node_id)
-                    .map(|conn| conn.from.as_str())
-                    .collect();
-
-                if predecessors.is_empty() {
-                    // This shouldn't happen in a well-formed graph
-                    return Err(ProcessingGraphError::ExecutionFailed(format!(
-                        "Node '{}' has no input connections",
-                        node_id
-                    ))
-                    .into());
-                }
-
-                // For now, we assume single input per node
-                // In a more complex system, we'd need to handle multiple inputs
-                let predecessor_id = predecessors[0];
-                node_outputs
-                    .get(predecessor_id)
-                    .ok_or_else(|| {
-                        ProcessingGraphError::ExecutionFailed(format!(
-                            "No output from predecessor '{}'",
-                            predecessor_id
-                        ))
-                    })?
-                    .clone()
-            };
-
-            // Process the data through this node
-            let output = node.process(input_for_node).map_err(|e| {
-                ProcessingGraphError::ExecutionFailed(format!("Node '{}' failed: {}", node_id, e))
-            })?;
-
-            // Record node processing time
-            let node_duration = node_start_time.elapsed();
-            self.statistics
-                .record_node_processing(node_id, node_duration);
+        let mut frame = FrameState {
+            input_node_id: &input_node_id,
+            input_data: Some(input_data),
+            node_outputs: HashMap::new(),
+            pending_reads: self.pending_reads(&execution_order),
+        };
 
-            node_outputs.insert(node_id.clone(), output);
+        if self.parallel_execution {
+            for level in self.get_execution_levels()? {
+                self.execute_level(&level, &mut frame)?;
+            }
+        } else {
+            // Execute nodes in topological order
+            for node_id in &execution_order {
+                self.execute_level(std::slice::from_ref(node_id), &mut frame)?;
+            }
         }
 
         // Record total graph execution time
         let graph_duration = graph_start_time.elapsed();
         self.statistics.record_graph_execution(graph_duration);
 
+        // With buffer reuse, move the outputs out and recycle the other buffers
+        if let Some(pool) = &self.buffer_pool {
+            let output_nodes = if self.output_nodes.is_empty() {
+                execution_order.last().into_iter().collect::<Vec<_>>()
+            } else {
+                self.output_nodes.iter().collect()
+            };
+            let results = output_nodes
+                .into_iter()
+                .filter_map(|node_id| frame.node_outputs.remove(node_id))
+                .collect();
+            for (_, output) in frame.node_outputs.drain() {
+                pool.recycle(output);
+            }
+            return Ok(results);
+        }
+
         // Collect outputs from designated output nodes
         let mut results = Vec::new();
         if self.output_nodes.is_empty() {
             // If no specific output nodes, return the last node's output
             if let Some(last_node_id) = execution_order.last() {
-                if let Some(output) = node_outputs.get(last_node_id) {
+                if let Some(output) = frame.node_outputs.get(last_node_id) {
                     results.push(output.clone());
                 }
             }
         } else {
             // Return outputs from all designated output nodes
             for output_node_id in &self.output_nodes {
-                if let Some(output) = node_outputs.get(output_node_id) {
+                if let Some(output) = frame.node_outputs.get(output_node_id) {
                     results.push(output.clone());
                 }
             }
@@ -832,6 +979,185 @@ impl ProcessingGraph {
         Ok(results)
     }
 
+    /// Number of nodes reading the output of each node, with buffer reuse
+    ///
+    /// Output nodes count one extra read for the returned results, so that
+    /// their output is never moved to a successor.
+    fn pending_reads(&self, execution_order: &[NodeId]) -> HashMap<NodeId, usize> {
+        let mut pending_reads = HashMap::new();
+        if self.buffer_pool.is_none() {
+            return pending_reads;
+        }
+
+        for node_id in self.nodes.keys() {
+            if let Some(conn) = self.connections.iter().find(|conn| &conn.to == node_id) {
+                *pending_reads.entry(conn.from.clone()).or_insert(0) += 1;
+            }
+        }
+        let output_nodes = if self.output_nodes.is_empty() {
+            execution_order.last().into_iter().collect::<Vec<_>>()
+        } else {
+            self.output_nodes.iter().collect()
+        };
+        for node_id in output_nodes {
+            *pending_reads.entry(node_id.clone()).or_insert(0) += 1;
+        }
+        pending_reads
+    }
+
+    /// Get the input of a node from the graph input or its first predecessor
+    ///
+    /// The graph input is moved to the input node. With buffer reuse, the
+    /// output of a predecessor is moved to its last reader and copied into
+    /// pooled buffers for the others; otherwise it is cloned.
+    fn node_input(&self, node_id: &str, frame: &mut FrameState<'_>) -> Result<ProcessingData> {
+        if node_id == frame.input_node_id {
+            // Input node gets the original input data
+            return Ok(frame.input_data.take().ok_or_else(|| {
+                ProcessingGraphError::ExecutionFailed(format!(
+                    "Input node '{}' executed twice",
+                    node_id
+                ))
+            })?);
+        }
+
+        // Find the input for this node from connected predecessors. For now,
+        // we assume single input per node; in a more complex system, we'd need
+        // to handle multiple inputs
+        let predecessor_id = self
+            .connections
+            .iter()
+            .find(|conn| conn.to == node_id)
+            .map(|conn| conn.from.as_str())
+            .ok_or_else(|| {
+                // This shouldn't happen in a well-formed graph
+                ProcessingGraphError::ExecutionFailed(format!(
+                    "Node '{}' has no input connections",
+                    node_id
+                ))
+            })?;
+
+        let missing_output = || {
+            ProcessingGraphError::ExecutionFailed(format!(
+                "No output from predecessor '{}'",
+                predecessor_id
+            ))
+        };
+        let Some(pool) = &self.buffer_pool else {
+            return Ok(frame
+                .node_outputs
+                .get(predecessor_id)
+                .ok_or_else(missing_output)?
+                .clone());
+        };
+
+        let pending_reads = frame
+            .pending_reads
+            .get_mut(predecessor_id)
+            .ok_or_else(missing_output)?;
+        *pending_reads -= 1;
+        if *pending_reads == 0 {
+            Ok(frame
+                .node_outputs
+                .remove(predecessor_id)
+                .ok_or_else(missing_output)?)
+        } else {
+            Ok(pool.clone_data(
+                frame
+                    .node_outputs
+                    .get(predecessor_id)
+                    .ok_or_else(missing_output)?,
+            ))
+        }
+    }
+
+    /// Execute the nodes of one execution level
+    ///
+    /// A level of several nodes runs on the graph's thread pool, see
+    /// [`set_thread_pool`](Self::set_thread_pool), a single node on the calling
+    /// thread. Outputs, statistics and errors are then handled
+    /// in the order of `level`.
+    ///
+    /// ### Arguments
+    ///
+    /// * `level` - Nodes that only depend on nodes already executed
+    /// * `frame` - State of the frame, whose node outputs are extended with this level
+    ///
+    /// ### Returns
+    ///
+    /// An error naming the first node of the level that failed or panicked
+    fn execute_level(&mut self, level: &[NodeId], frame: &mut FrameState<'_>) -> Result<()> {
+        let inputs = level
+            .iter()
+            .map(|node_id| self.node_input(node_id, frame))
+            .collect::<Result<Vec<_>>>()?;
+
+        // Process the data through each node, counting the timeouts even when
+        // the frame is dropped
+        let pool = self.buffer_pool.as_ref();
+        let run = move |node: &mut Box<dyn ProcessingNode>, input: ProcessingData| {
+            let node_start_time = Instant::now();
+            let output = std::panic::catch_unwind(AssertUnwindSafe(|| match pool {
+                Some(pool) => node.process_pooled(input, pool),
+                None => node.process(input),
+            }));
+            (output, node.timeout_count(), node_start_time.elapsed())
+        };
+
+        let results = if let [node_id] = level {
+            let node = self.nodes.get_mut(node_id).unwrap();
+            let input = inputs.into_iter().next().unwrap();
+            vec![run(node, input)]
+        } else {
+            let mut nodes: HashMap<&NodeId, &mut Box<dyn ProcessingNode>> = self
+                .nodes
+                .iter_mut()
+                .filter(|(node_id, _)| level.contains(*node_id))
+                .collect();
+            let work: Vec<_> = level
+                .iter()
+                .map(|node_id| nodes.remove(node_id).unwrap())
+                .zip(inputs)
+                .collect();
+
+            // Collected in the order of `level`
+            let run_level = || -> Vec<_> {
+                work.into_par_iter()
+                    .map(|(node, input)| run(node, input))
+                    .collect()
+            };
+            match &self.thread_pool {
+                Some(thread_pool) => thread_pool.install(run_level),
+                None => run_level(),
+            }
+        };
+
+        for (node_id, (output, timeouts, node_duration)) in level.iter().zip(results) {
+            self.statistics.record_node_timeouts(node_id, timeouts);
+            let output = output
+                .map_err(|panic| {
+                    ProcessingGraphError::NodePanicked(
+                        node_id.clone(),
+                        panic_message(panic.as_ref()),
+                    )
+                })?
+                .map_err(|e| {
+                    ProcessingGraphError::ExecutionFailed(format!(
+                        "Node '{}' failed: {}",
+                        node_id, e
+                    ))
+                })?;
+
+            // Record node processing time
+            self.statistics
+                .record_node_processing(node_id, node_duration);
+
+            frame.node_outputs.insert(node_id.clone(), output);
+        }
+
+        Ok(())
+    }
+
     /// Create a new processing graph from configuration
     pub fn from_config(config: &ProcessingGraphConfig) -> Result<Self> {
         Self::from_config_with_registry(config, None)
@@ -948,6 +1274,7 @@ impl ProcessingGraph {
             let _ = graph.set_output_node(output_id);
         }
 
+        graph.config = Some(config.clone());
         debug!("Processing graph created successfully");
         Ok(graph)
     }
@@ -1402,15 +1729,77 @@ impl ProcessingGraph {
                     Box::new(differential),
                 )))
             }
+            "adaptive_filter" => {
+                // Extract adaptive filter parameters (all optional)
+                let params = config.parameters.as_object();
+                let filter_length = params
+                    .and_then(|p| p.get("filter_length"))
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(32) as usize;
+                let step_size = params
+                    .and_then(|p| p.get("step_size"))
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(0.1) as f32;
+
+                let mut node =
+                    AdaptiveFilterNode::new(config.id.clone(), filter_length, step_size)?;
+                if let Some(regularization) = params
+                    .and_then(|p| p.get("regularization"))
+                    .and_then(|v| v.as_f64())
+                {
+                    node = node.with_regularization(regularization as f32);
+                }
+
+                Ok(Box::new(node))
+            }
             "photoacoustic_output" => {
                 // Extract photoacoustic output parameters
-                let mut node = PhotoacousticOutputNode::new(config.id.clone());
+                let mut node = PhotoacousticOutputNode::new_with_shared_state(
+                    config.id.clone(),
+                    computing_state.clone(),
+                );
 
                 if let Some(params) = config.parameters.as_object() {
-                    if let Some(threshold_value) = params.get("detection_threshold") {
-                        if let Some(threshold) = threshold_value.as_f64() {
-                            node = node.with_detection_threshold(threshold as f32);
-                        }
+                    let detection_threshold = params
+                        .get("detection_threshold")
+                        .and_then(|v| v.as_f64())
+                        .unwrap_or(0.01) as f32;
+                    let release_threshold = params
+                        .get("release_threshold")
+                        .and_then(|v| v.as_f64())
+                        .map(|v| v as f32)
+                        .unwrap_or(detection_threshold);
+                    let detection_dwell_ms = params
+                        .get("detection_dwell_ms")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0);
+                    let release_dwell_ms = params
+                        .get("release_dwell_ms")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0);
+                    node = node.with_detection(
+                        DetectionStateMachine::new(
+                            detection_threshold,
+                            release_threshold,
+                            detection_dwell_ms,
+                            release_dwell_ms,
+                        )
+                        .map_err(|e| {
+                            anyhow::anyhow!("Photoacoustic output node '{}': {}", config.id, e)
+                        })?,
+                    );
+
+                    if let Some(value) = params.get("snr_threshold_db").filter(|v| !v.is_null()) {
+                        let snr_threshold_db = value
+                            .as_f64()
+                            .filter(|v| v.is_finite())
+                            .ok_or_else(|| {
+                                anyhow::anyhow!(
+                                    "Photoacoustic output node '{}': snr_threshold_db must be a number",
+                                    config.id
+                                )
+                            })?;
+                        node = node.with_snr_threshold_db(Some(snr_threshold_db as f32));
                     }
 
                     if let Some(window_size_value) = params.get("analysis_window_size") {
@@ -1538,6 +1927,32 @@ impl ProcessingGraph {
 
                 Ok(Box::new(peak_finder))
             }
+            "computing_cross_correlation" => {
+                // Use global photoacoustic parameters for sample_rate and the default window size
+                let mut cross_correlation = CrossCorrelationNode::new_with_shared_state(
+                    config.id.clone(),
+                    computing_state.clone(),
+                )
+                .with_sample_rate(photoacoustic_config.sample_rate as u32)
+                .with_window_size(photoacoustic_config.frame_size as usize);
+
+                if let Some(params) = config.parameters.as_object() {
+                    if let Some(window_size) = params.get("window_size").and_then(|v| v.as_u64()) {
+                        cross_correlation =
+                            cross_correlation.with_window_size(window_size as usize);
+                    }
+                    if let Some(max_lag) = params.get("max_lag_samples").and_then(|v| v.as_u64()) {
+                        cross_correlation = cross_correlation.with_max_lag(max_lag as usize);
+                    }
+                    if let Some(interpolation) =
+                        params.get("interpolation").and_then(|v| v.as_bool())
+                    {
+                        cross_correlation = cross_correlation.with_interpolation(interpolation);
+                    }
+                }
+
+                Ok(Box::new(cross_correlation))
+            }
             "computing_concentration" => {
                 // Extract concentration calculator parameters
                 let params = config
@@ -1620,8 +2035,49 @@ impl ProcessingGraph {
                     }
                 }
 
+                if let Some(outlier_rejection) = params
+                    .get("outlier_rejection")
+                    .filter(|value| !value.is_null())
+                {
+                    concentration_node = concentration_node
+                        .with_outlier_rejection(HampelFilter::from_config(outlier_rejection)?);
+                }
+
                 Ok(Box::new(concentration_node))
             }
+            "computing_kalman_concentration" => {
+                // Extract Kalman filter parameters
+                let params = config.parameters.as_object().ok_or_else(|| {
+                    anyhow::anyhow!("Kalman concentration node requires parameters")
+                })?;
+
+                // Extract computing_concentration_id (required)
+                let concentration_id = params
+                    .get("computing_concentration_id")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Kalman concentration node requires 'computing_concentration_id' parameter"
+                        )
+                    })?;
+
+                let mut kalman_node = KalmanConcentrationNode::new_with_shared_state(
+                    config.id.clone(),
+                    computing_state.clone(),
+                )
+                .with_concentration_source(concentration_id.to_string());
+
+                if let Some(process_noise) = params.get("process_noise").and_then(|v| v.as_f64()) {
+                    kalman_node = kalman_node.with_process_noise(process_noise);
+                }
+                if let Some(measurement_noise) =
+                    params.get("measurement_noise").and_then(|v| v.as_f64())
+                {
+                    kalman_node = kalman_node.with_measurement_noise(measurement_noise);
+                }
+
+                Ok(Box::new(kalman_node))
+            }
             "gain" => {
                 // Extract gain parameters
                 let params = config
@@ -1638,8 +2094,86 @@ impl ProcessingGraph {
 
                 Ok(Box::new(GainNode::new(config.id.clone(), gain_db)))
             }
+            "spectral_subtraction" => {
+                // Extract spectral subtraction parameters (all optional)
+                let mut node = SpectralSubtractionNode::new(config.id.clone());
+
+                if let Some(params) = config.parameters.as_object() {
+                    if let Some(fft_size) = params.get("fft_size").and_then(|v| v.as_u64()) {
+                        node = node.with_fft_size(fft_size as usize);
+                    }
+
+                    if let Some(factor) = params.get("over_subtraction").and_then(|v| v.as_f64()) {
+                        node = node.with_over_subtraction(factor as f32);
+                    }
+
+                    if let Some(floor) = params.get("spectral_floor").and_then(|v| v.as_f64()) {
+                        node = node.with_spectral_floor(floor as f32);
+                    }
+
+                    if let Some(frames) = params.get("learn_frames").and_then(|v| v.as_u64()) {
+                        node = node.with_learn_frames(frames as usize);
+                    }
+                }
+
+                Ok(Box::new(node))
+            }
+            "decimation" => {
+                // Extract decimation parameters
+                let params = config
+                    .parameters
+                    .as_object()
+                    .ok_or_else(|| anyhow::anyhow!("Decimation node requires parameters"))?;
+
+                // Extract factor (required)
+                let factor = params
+                    .get("factor")
+                    .and_then(|v| v.as_u64())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Decimation node requires 'factor' parameter")
+                    })?;
+
+                let mut node = DecimationNode::new(config.id.clone(), factor as usize)?;
+
+                if let Some(cutoff_ratio) = params.get("cutoff_ratio").and_then(|v| v.as_f64()) {
+                    node = node.with_cutoff_ratio(cutoff_ratio)?;
+                }
+
+                if let Some(filter_length) = params.get("filter_length").and_then(|v| v.as_u64()) {
+                    node = node.with_filter_length(filter_length as usize)?;
+                }
+
+                Ok(Box::new(node))
+            }
+            "interpolation" => {
+                // Extract interpolation parameters
+                let params = config
+                    .parameters
+                    .as_object()
+                    .ok_or_else(|| anyhow::anyhow!("Interpolation node requires parameters"))?;
+
+                // Extract factor (required)
+                let factor = params
+                    .get("factor")
+                    .and_then(|v| v.as_u64())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Interpolation node requires 'factor' parameter")
+                    })?;
+
+                let mut node = InterpolationNode::new(config.id.clone(), factor as usize)?;
+
+                if let Some(cutoff_ratio) = params.get("cutoff_ratio").and_then(|v| v.as_f64()) {
+                    node = node.with_cutoff_ratio(cutoff_ratio)?;
+                }
+
+                if let Some(filter_length) = params.get("filter_length").and_then(|v| v.as_u64()) {
+                    node = node.with_filter_length(filter_length as usize)?;
+                }
+
+                Ok(Box::new(node))
+            }
             "python" => {
-                use crate::processing::nodes::{PythonNode, PythonNodeConfig};
+                use crate::processing::nodes::{PythonNode, PythonNodeConfig, PythonTimeoutPolicy};
 
                 // Extract python node parameters
                 let params = config
@@ -1693,6 +2227,14 @@ impl ProcessingGraph {
                     python_config.timeout_seconds = timeout_seconds;
                 }
 
+                if let Some(timeout_policy) = params.get("timeout_policy").and_then(|v| v.as_str())
+                {
+                    python_config.timeout_policy = match timeout_policy {
+                        "pass_through" => PythonTimeoutPolicy::PassThrough,
+                        _ => PythonTimeoutPolicy::Drop, // Default
+                    };
+                }
+
                 if let Some(auto_reload) = params.get("auto_reload").and_then(|v| v.as_bool()) {
                     python_config.auto_reload = auto_reload;
                 }
@@ -1717,8 +2259,72 @@ impl ProcessingGraph {
                     python_config.output_type = Some(output_type.to_string());
                 }
 
+                if let Some(numpy_arrays) = params.get("numpy_arrays").and_then(|v| v.as_bool()) {
+                    python_config.numpy_arrays = numpy_arrays;
+                }
+
                 Ok(Box::new(PythonNode::new(config.id.clone(), python_config)))
             }
+            #[cfg(feature = "lua-node")]
+            "lua" => {
+                use crate::processing::nodes::LuaNode;
+
+                let params = config
+                    .parameters
+                    .as_object()
+                    .ok_or_else(|| anyhow::anyhow!("Lua node requires parameters"))?;
+                let params = params
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+
+                Ok(Box::new(LuaNode::from_config(config.id.clone(), params)?))
+            }
+            #[cfg(not(feature = "lua-node"))]
+            "lua" => Err(anyhow::anyhow!(
+                "Lua node requested but not compiled (missing lua-node feature)"
+            )),
+            #[cfg(feature = "wasm-node")]
+            "wasm" => {
+                use crate::processing::nodes::WasmNode;
+
+                let params = config
+                    .parameters
+                    .as_object()
+                    .ok_or_else(|| anyhow::anyhow!("WebAssembly node requires parameters"))?;
+                let params = params
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+
+                Ok(Box::new(WasmNode::from_config(config.id.clone(), params)?))
+            }
+            #[cfg(not(feature = "wasm-node"))]
+            "wasm" => Err(anyhow::anyhow!(
+                "WebAssembly node requested but not compiled (missing wasm-node feature)"
+            )),
+            #[cfg(feature = "plugin-node")]
+            "plugin" => {
+                use crate::processing::nodes::PluginNode;
+
+                let params = config
+                    .parameters
+                    .as_object()
+                    .ok_or_else(|| anyhow::anyhow!("Plugin node requires parameters"))?;
+                let params = params
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+
+                Ok(Box::new(PluginNode::from_config(
+                    config.id.clone(),
+                    params,
+                )?))
+            }
+            #[cfg(not(feature = "plugin-node"))]
+            "plugin" => Err(anyhow::anyhow!(
+                "Plugin node requested but not compiled (missing plugin-node feature)"
+            )),
             "action_universal" => {
                 // Extract example display action parameters
                 let mut action_node = UniversalActionNode::new_with_shared_state(
@@ -1768,6 +2374,35 @@ impl ProcessingGraph {
                         }
                     }
 
+                    // Extract dead_letter parameter (optional queue of undelivered measurements)
+                    if let Some(dead_letter) = params.get("dead_letter").and_then(|v| v.as_object())
+                    {
+                        let directory = dead_letter
+                            .get("directory")
+                            .and_then(|v| v.as_str())
+                            .ok_or_else(|| {
+                                anyhow::anyhow!(
+                                    "Action node '{}': dead_letter requires a directory",
+                                    config.id
+                                )
+                            })?;
+                        let capacity = dead_letter
+                            .get("capacity")
+                            .and_then(|v| v.as_u64())
+                            .map_or(DEFAULT_DEAD_LETTER_CAPACITY, |capacity| capacity as usize);
+                        let retry_interval_ms = dead_letter
+                            .get("retry_interval_ms")
+                            .and_then(|v| v.as_u64())
+                            .unwrap_or(DEFAULT_DEAD_LETTER_RETRY_INTERVAL_MS);
+                        let queue = DeadLetterQueue::open(
+                            directory,
+                            &config.id,
+                            capacity,
+                            Duration::from_millis(retry_interval_ms),
+                        )?;
+                        action_node = action_node.with_dead_letter_queue(queue);
+                    }
+
                     // Extract driver configuration
                     if let Some(driver_config) = params.get("driver") {
                         if let Some(driver_obj) = driver_config.as_object() {
@@ -1777,224 +2412,8 @@ impl ProcessingGraph {
                                 if let Some(driver_config_obj) =
                                     driver_obj.get("config").and_then(|v| v.as_object())
                                 {
-                                    let driver: Box<dyn ActionDriver> = match driver_type {
-                                        "https_callback" => {
-                                            let url = driver_config_obj.get("callback_url")
-                                                .and_then(|v| v.as_str())
-                                                .ok_or_else(|| anyhow::anyhow!("Missing callback_url for https_callback driver"))?;
-
-                                            let mut http_driver =
-                                                HttpsCallbackActionDriver::new(url);
-
-                                            // Optional auth token
-                                            if let Some(auth_token) = driver_config_obj
-                                                .get("auth_token")
-                                                .and_then(|v| v.as_str())
-                                            {
-                                                http_driver =
-                                                    http_driver.with_auth_token(auth_token);
-                                            }
-
-                                            // Optional timeout
-                                            if let Some(timeout_ms) = driver_config_obj
-                                                .get("timeout_ms")
-                                                .and_then(|v| v.as_u64())
-                                            {
-                                                http_driver = http_driver
-                                                    .with_timeout_seconds(timeout_ms / 1000);
-                                            }
-
-                                            // Optional retry count
-                                            if let Some(retry_count) = driver_config_obj
-                                                .get("retry_count")
-                                                .and_then(|v| v.as_u64())
-                                            {
-                                                http_driver = http_driver
-                                                    .with_retry_count(retry_count as u32);
-                                            }
-
-                                            Box::new(http_driver)
-                                        }
-                                        "redis" => {
-                                            let connection_string = driver_config_obj.get("connection_string")
-                                                .and_then(|v| v.as_str())
-                                                .ok_or_else(|| anyhow::anyhow!("Missing connection_string for redis driver"))?;
-
-                                            // Get mode (default to key_value for backward compatibility)
-                                            let mode = driver_config_obj
-                                                .get("mode")
-                                                .and_then(|v| v.as_str())
-                                                .unwrap_or("key_value");
-
-                                            // Get channel or prefix (support both 'channel' and 'channel_or_prefix')
-                                            let channel_or_prefix = driver_config_obj
-                                                .get("channel_or_prefix")
-                                                .and_then(|v| v.as_str())
-                                                .or_else(|| {
-                                                    driver_config_obj
-                                                        .get("channel")
-                                                        .and_then(|v| v.as_str())
-                                                })
-                                                .unwrap_or("photoacoustic");
-
-                                            let mut redis_driver = match mode {
-                                                "pub_sub" | "pubsub" => {
-                                                    RedisActionDriver::new_pubsub(
-                                                        connection_string,
-                                                        channel_or_prefix,
-                                                    )
-                                                }
-                                                "key_value" | "keyvalue" => {
-                                                    RedisActionDriver::new_key_value(
-                                                        connection_string,
-                                                        channel_or_prefix,
-                                                    )
-                                                }
-                                                _ => {
-                                                    log::warn!("Unknown Redis mode '{}', defaulting to key_value", mode);
-                                                    RedisActionDriver::new_key_value(
-                                                        connection_string,
-                                                        channel_or_prefix,
-                                                    )
-                                                }
-                                            };
-
-                                            // Optional expiration (support both 'expiration_seconds' and 'expiry_seconds')
-                                            if let Some(expiration_seconds) = driver_config_obj
-                                                .get("expiration_seconds")
-                                                .and_then(|v| v.as_u64())
-                                                .or_else(|| {
-                                                    driver_config_obj
-                                                        .get("expiry_seconds")
-                                                        .and_then(|v| v.as_u64())
-                                                })
-                                            {
-                                                redis_driver = redis_driver
-                                                    .with_expiration_seconds(expiration_seconds);
-                                            }
-
-                                            Box::new(redis_driver)
-                                        }
-                                        "kafka" => {
-                                            let bootstrap_servers = driver_config_obj.get("bootstrap_servers")
-                                                .and_then(|v| v.as_str())
-                                                .ok_or_else(|| anyhow::anyhow!("Missing bootstrap_servers for kafka driver"))?;
-
-                                            let topic = driver_config_obj
-                                                .get("topic")
-                                                .and_then(|v| v.as_str())
-                                                .unwrap_or("photoacoustic.display");
-
-                                            let alert_topic = driver_config_obj
-                                                .get("alert_topic")
-                                                .and_then(|v| v.as_str())
-                                                .unwrap_or("photoacoustic.alerts");
-
-                                            Box::new(KafkaActionDriver::new(
-                                                bootstrap_servers,
-                                                topic,
-                                                alert_topic,
-                                            ))
-                                        }
-                                        #[cfg(feature = "python-driver")]
-                                        "python" => {
-                                            // Extract required script_path
-
-                                            use crate::processing::{computing_nodes::action_drivers::PythonDriverConfig, PythonActionDriver};
-                                            let script_path = driver_config_obj.get("script_path")
-                                                .and_then(|v| v.as_str())
-                                                .ok_or_else(|| anyhow::anyhow!("Missing script_path for python driver"))?;
-
-                                            // Create configuration with required script_path
-                                            let mut config = PythonDriverConfig {
-                                                script_path: script_path.into(),
-                                                ..Default::default()
-                                            };
-
-                                            // Configure optional parameters
-                                            if let Some(auto_reload) = driver_config_obj
-                                                .get("auto_reload")
-                                                .and_then(|v| v.as_bool())
-                                            {
-                                                config.auto_reload = auto_reload;
-                                            }
-
-                                            if let Some(timeout_seconds) = driver_config_obj
-                                                .get("timeout_seconds")
-                                                .and_then(|v| v.as_u64())
-                                            {
-                                                config.timeout_seconds = timeout_seconds;
-                                            }
-
-                                            if let Some(update_function) = driver_config_obj
-                                                .get("update_function")
-                                                .and_then(|v| v.as_str())
-                                            {
-                                                config.update_function = update_function.to_string();
-                                            }
-
-                                            if let Some(alert_function) = driver_config_obj
-                                                .get("alert_function")
-                                                .and_then(|v| v.as_str())
-                                            {
-                                                config.alert_function = alert_function.to_string();
-                                            }
-
-                                            if let Some(init_function) = driver_config_obj
-                                                .get("init_function")
-                                                .and_then(|v| v.as_str())
-                                            {
-                                                config.init_function = init_function.to_string();
-                                            }
-
-                                            if let Some(shutdown_function) = driver_config_obj
-                                                .get("shutdown_function")
-                                                .and_then(|v| v.as_str())
-                                            {
-                                                config.shutdown_function = shutdown_function.to_string();
-                                            }
-
-                                            if let Some(status_function) = driver_config_obj
-                                                .get("status_function")
-                                                .and_then(|v| v.as_str())
-                                            {
-                                                config.status_function = status_function.to_string();
-                                            }
-
-                                            if let Some(venv_path) = driver_config_obj
-                                                .get("venv_path")
-                                                .and_then(|v| v.as_str())
-                                            {
-                                                config.venv_path = Some(venv_path.into());
-                                            }
-
-                                            // Handle python_paths array
-                                            if let Some(python_paths_arr) = driver_config_obj
-                                                .get("python_paths")
-                                                .and_then(|v| v.as_array())
-                                            {
-                                                config.python_paths = python_paths_arr
-                                                    .iter()
-                                                    .filter_map(|v| v.as_str())
-                                                    .map(|s| s.into())
-                                                    .collect();
-                                            }
-
-                                            Box::new(PythonActionDriver::new(config))
-                                        }
-                                        #[cfg(not(feature = "python-driver"))]
-                                        "python" => {
-                                            return Err(anyhow::anyhow!(
-                                                "Python driver requested but not compiled (missing python-driver feature)"
-                                            ))
-                                        }
-                                        _ => {
-                                            return Err(anyhow::anyhow!(
-                                                "Unsupported driver type: {}",
-                                                driver_type
-                                            ))
-                                        }
-                                    };
+                                    let driver =
+                                        create_action_driver(driver_type, driver_config_obj)?;
 
                                     action_node = action_node.with_driver(driver);
                                 }
@@ -2019,6 +2438,40 @@ impl ProcessingGraph {
         Ok(order)
     }
 
+    /// Get the execution levels of the graph
+    ///
+    /// The level of a node is the length of the longest path from a source
+    /// node, so the nodes of a level only depend on nodes of earlier levels
+    /// and can run concurrently. Within a level, nodes keep their relative
+    /// execution order.
+    fn get_execution_levels(&mut self) -> Result<Vec<Vec<NodeId>>> {
+        if let Some(ref levels) = self.execution_levels {
+            return Ok(levels.clone());
+        }
+
+        let order = self.get_execution_order()?;
+        let mut depths: HashMap<&str, usize> = HashMap::new();
+        let mut levels: Vec<Vec<NodeId>> = Vec::new();
+        for node_id in &order {
+            let depth = self
+                .connections
+                .iter()
+                .filter(|conn| &conn.to == node_id)
+                .filter_map(|conn| depths.get(conn.from.as_str()))
+                .map(|depth| depth + 1)
+                .max()
+                .unwrap_or(0);
+            depths.insert(node_id, depth);
+            if levels.len() <= depth {
+                levels.resize(depth + 1, Vec::new());
+            }
+            levels[depth].push(node_id.clone());
+        }
+
+        self.execution_levels = Some(levels.clone());
+        Ok(levels)
+    }
+
     /// Get the execution order (topologically sorted) without caching
     ///
     /// This method computes the execution order without modifying the graph state,
@@ -2138,6 +2591,7 @@ impl ProcessingGraph {
     /// Invalidate cached execution order
     fn invalidate_execution_order(&mut self) {
         self.execution_order = None;
+        self.execution_levels = None;
     }
 
     /// Get a list of all node IDs
@@ -2160,6 +2614,50 @@ impl ProcessingGraph {
         &self.connections
     }
 
+    /// Get the configuration reproducing the graph
+    ///
+    /// Starts from the configuration the graph was created from and applies the
+    /// node parameters updated since with [`update_node_config`](Self::update_node_config),
+    /// and the current connections.
+    ///
+    /// ### Returns
+    ///
+    /// The configuration, or an error if the graph was not created from a
+    /// configuration or if nodes were added or removed since
+    pub fn to_config(&self) -> Result<ProcessingGraphConfig> {
+        let mut config = self.config.clone().ok_or_else(|| {
+            anyhow::anyhow!("The processing graph was not created from a configuration")
+        })?;
+        if config.nodes.len() != self.nodes.len()
+            || config
+                .nodes
+                .iter()
+                .any(|node| !self.nodes.contains_key(&node.id))
+        {
+            anyhow::bail!(
+                "The nodes of processing graph '{}' changed since its creation",
+                config.id
+            );
+        }
+
+        for node in &mut config.nodes {
+            if let Some(parameters) = self.node_parameters.get(&node.id) {
+                if !parameters.is_empty() {
+                    node.parameters = Value::Object(parameters.clone().into_iter().collect());
+                }
+            }
+        }
+        config.connections = self
+            .connections
+            .iter()
+            .map(|connection| ConnectionConfig {
+                from: connection.from.clone(),
+                to: connection.to.clone(),
+            })
+            .collect();
+        Ok(config)
+    }
+
     /// Reset all nodes in the graph
     pub fn reset(&mut self) {
         for node in self.nodes.values_mut() {
@@ -2168,6 +2666,24 @@ impl ProcessingGraph {
         self.invalidate_execution_order();
     }
 
+    /// Flush the pending work of all nodes before the application exits
+    ///
+    /// Calls [`ProcessingNode::shutdown`] on every node, in execution order
+    /// when the graph is valid, so that action drivers deliver their queued
+    /// sends and record nodes finalize their files. The graph must not be
+    /// executed afterwards.
+    pub fn shutdown(&mut self) {
+        let order = self
+            .get_execution_order_immutable()
+            .unwrap_or_else(|_| self.node_ids());
+        for node_id in order {
+            if let Some(node) = self.nodes.get_mut(&node_id) {
+                debug!("Shutting down node '{}'", node_id);
+                node.shutdown();
+            }
+        }
+    }
+
     /// Validate the graph structure
     pub fn validate(&self) -> Result<()> {
         // Check if we have an input node
@@ -2505,6 +3021,29 @@ impl ProcessingGraph {
             })
             .collect()
     }
+
+    /// Clear the history buffer of a UniversalActionNode
+    ///
+    /// Resets the action state of the node: its history buffer is emptied and
+    /// its processing and action counters are zeroed. The node keeps running
+    /// with its configuration unchanged.
+    ///
+    /// # Arguments
+    /// * `node_id` - The ID of the UniversalActionNode to clear
+    ///
+    /// # Returns
+    /// * `Some(count)` - Number of history entries removed
+    /// * `None` - Node not found or not a UniversalActionNode
+    pub fn clear_universal_action_node_history(&mut self, node_id: &str) -> Option<usize> {
+        use crate::processing::computing_nodes::action_trait::ActionNode;
+
+        let removed = self
+            .get_universal_action_node(node_id)?
+            .get_history_buffer()
+            .len();
+        self.nodes.get_mut(node_id)?.reset();
+        Some(removed)
+    }
 }
 
 /// Represents a connection between two nodes in serializable format
@@ -2622,6 +3161,7 @@ impl SerializableProcessingGraph {
                 sample_rate: 44100,
                 processing_steps: vec![],
                 processing_latency_us: 0,
+                snr_db: None,
             },
         };
 
@@ -58,13 +58,25 @@
 //! - Order 3: 18dB/octave roll-off (steep)
 //! - Order 4: 24dB/octave roll-off (very steep)
 //!
+//! ### Sample Rate Conversion
+//! - `decimation`: Anti-alias FIR lowpass then keeps every `factor`-th sample, reporting the
+//!   reduced sample rate downstream
+//! - `interpolation`: Upsamples by `factor` with a polyphase lowpass filter removing the
+//!   spectral images, reporting the increased sample rate downstream
+//!
+//! ### Denoising Nodes
+//! - `spectral_subtraction`: Subtracts a noise spectrum learnt on signal-absent frames, with
+//!   configurable block size, over-subtraction factor and spectral floor
+//!
 //! ### Channel Operations
 //! - `channel_selector`: Selects ChannelA, ChannelB, or Both channels
 //! - `channel_mixer`: Mixes channels using Add, Subtract, Average, or Weighted strategies
 //! - `differential`: Calculates differential between channels
+//! - `adaptive_filter`: Cancels the noise of channel A correlated with channel B with a normalized LMS filter
 //!
 //! ### Output Nodes
-//! - `photoacoustic_output`: Final analysis node with configurable detection threshold
+//! - `photoacoustic_output`: Final analysis node with a debounced detection state machine and an optional SNR gate
+//!   (hysteresis thresholds and dwell times) emitting onset and release events to action nodes
 //! - `record`: Records audio data with configurable duration, path, and format
 //!
 //! ### Action Nodes
@@ -455,13 +467,15 @@
 //! - ProcessingGraph integration with action nodes
 //! - Error handling and fallback mechanisms
 
+pub mod buffer_pool;
 pub mod computing_nodes;
 pub mod consumer;
 pub mod graph;
 pub mod nodes;
 pub mod result;
 
-pub use consumer::ProcessingConsumer;
+pub use buffer_pool::{BufferPoolStatistics, FrameBufferPool};
+pub use consumer::{ProcessingConsumer, ProcessingConsumerHandle};
 pub use graph::{
     PerformanceSummary, ProcessingGraph, ProcessingGraphError, SerializableConnection,
     SerializableNode, SerializableProcessingGraph,
@@ -480,5 +494,6 @@ pub use computing_nodes::{
         ActionDriver, AlertData, HttpsCallbackActionDriver, KafkaActionDriver, MeasurementData,
         RedisActionDriver,
     },
+    dead_letter::{DeadLetterQueue, DeadLetterStats},
     universal_action::UniversalActionNode,
 };
@@ -0,0 +1,321 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Adaptive noise-cancellation node implementation
+//!
+//! This module provides the `AdaptiveFilterNode` which cancels the noise of
+//! channel A that is correlated with channel B. Where the differential node
+//! subtracts channel B as is, the adaptive filter learns the transfer function
+//! from the reference channel to the noise in the signal channel, so that
+//! differences of gain and delay between the two microphones are compensated.
+//!
+//! ### Algorithm
+//!
+//! A normalized least-mean-squares (NLMS) FIR filter of `filter_length` taps
+//! estimates the noise of channel A from the last samples `x` of channel B:
+//!
+//! ```text
+//! e[n] = a[n] - wᵀ·x[n]
+//! w ← w + μ·e[n]·x[n] / (ε + xᵀ·x)
+//! ```
+//!
+//! The error `e` is the output: the part of channel A that cannot be predicted
+//! from channel B, which is the photoacoustic signal when it is only present in
+//! channel A. The step size `μ`, between 0 and 2, trades the convergence speed
+//! for the residual noise once converged.
+
+use super::data::ProcessingData;
+use super::traits::ProcessingNode;
+use anyhow::Result;
+use log::debug;
+use std::collections::VecDeque;
+
+/// A processing node cancelling the noise of channel A correlated with channel B.
+///
+/// The filter weights adapt continuously across frames, so that slow changes
+/// of the acoustic paths are tracked. The node converts dual-channel input to
+/// a single-channel output, like the differential node.
+///
+/// ### Input/Output
+///
+/// - **Input**: [`ProcessingData::DualChannel`] or [`ProcessingData::AudioFrame`],
+///   channel A carrying the signal and channel B the noise reference
+/// - **Output**: [`ProcessingData::SingleChannel`] with the denoised channel A
+///
+/// ### Examples
+///
+/// ```no_run
+/// use rust_photoacoustic::processing::nodes::{
+///     AdaptiveFilterNode, ProcessingData, ProcessingNode,
+/// };
+///
+/// // 32 taps, step size 0.1
+/// let mut node = AdaptiveFilterNode::new("noise_canceller".to_string(), 32, 0.1)?;
+///
+/// let input = ProcessingData::DualChannel {
+///     channel_a: vec![0.5, 0.3, 0.8, 0.1],
+///     channel_b: vec![0.4, 0.2, 0.9, 0.0],
+///     sample_rate: 48000,
+///     timestamp: 1000,
+///     frame_number: 1,
+/// };
+///
+/// match node.process(input)? {
+///     ProcessingData::SingleChannel { samples, .. } => assert_eq!(samples.len(), 4),
+///     _ => panic!("Expected SingleChannel output"),
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct AdaptiveFilterNode {
+    /// Unique identifier for this node
+    id: String,
+    /// Normalized step size μ
+    step_size: f32,
+    /// Regularization ε of the reference energy
+    regularization: f32,
+    /// FIR filter weights, for the most recent reference sample first
+    weights: Vec<f32>,
+    /// Last reference samples, most recent first
+    reference: VecDeque<f32>,
+    /// Energy of the reference samples in `reference`
+    reference_energy: f64,
+}
+
+impl AdaptiveFilterNode {
+    /// Create a new adaptive filter node with zero initial weights.
+    ///
+    /// ### Arguments
+    ///
+    /// * `id` - Unique identifier for this node
+    /// * `filter_length` - Number of taps, at least 1, covering the largest delay between the channels
+    /// * `step_size` - Normalized step size, strictly between 0 and 2
+    ///
+    /// ### Returns
+    ///
+    /// The node, or an error if a parameter is out of range
+    pub fn new(id: String, filter_length: usize, step_size: f32) -> Result<Self> {
+        Self::validate_filter_length(filter_length)?;
+        Self::validate_step_size(step_size)?;
+        Ok(Self {
+            id,
+            step_size,
+            regularization: 1e-6,
+            weights: vec![0.0; filter_length],
+            reference: VecDeque::from(vec![0.0; filter_length]),
+            reference_energy: 0.0,
+        })
+    }
+
+    /// Set the regularization added to the reference energy.
+    ///
+    /// It avoids large updates while the reference is silent.
+    ///
+    /// ### Arguments
+    ///
+    /// * `regularization` - Regularization ε, negative values are ignored
+    pub fn with_regularization(mut self, regularization: f32) -> Self {
+        if regularization >= 0.0 {
+            self.regularization = regularization;
+        }
+        self
+    }
+
+    /// Get the number of taps
+    pub fn get_filter_length(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Get the normalized step size
+    pub fn get_step_size(&self) -> f32 {
+        self.step_size
+    }
+
+    /// Current filter weights, for the most recent reference sample first
+    ///
+    /// Once converged, they are the impulse response from channel B to the
+    /// noise in channel A.
+    pub fn weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    fn validate_filter_length(filter_length: usize) -> Result<()> {
+        if filter_length == 0 {
+            anyhow::bail!("filter_length must be at least 1");
+        }
+        Ok(())
+    }
+
+    fn validate_step_size(step_size: f32) -> Result<()> {
+        if !(step_size > 0.0 && step_size < 2.0) {
+            anyhow::bail!(
+                "step_size must be strictly between 0 and 2, got {}",
+                step_size
+            );
+        }
+        Ok(())
+    }
+
+    /// Cancel the noise of `signal` predicted from `reference`
+    ///
+    /// ### Arguments
+    ///
+    /// * `signal` - Channel A samples
+    /// * `reference` - Channel B samples, of the same length
+    ///
+    /// ### Returns
+    ///
+    /// The prediction error for each sample
+    fn cancel(&mut self, signal: &[f32], reference: &[f32]) -> Result<Vec<f32>> {
+        if signal.len() != reference.len() {
+            anyhow::bail!(
+                "AdaptiveFilterNode requires channels of the same length, got {} and {}",
+                signal.len(),
+                reference.len()
+            );
+        }
+
+        let mut output = Vec::with_capacity(signal.len());
+        for (&desired, &sample) in signal.iter().zip(reference) {
+            if let Some(oldest) = self.reference.pop_back() {
+                self.reference_energy -= (oldest as f64).powi(2);
+            }
+            self.reference.push_front(sample);
+            self.reference_energy = (self.reference_energy + (sample as f64).powi(2)).max(0.0);
+
+            let estimate: f32 = self
+                .weights
+                .iter()
+                .zip(&self.reference)
+                .map(|(weight, x)| weight * x)
+                .sum();
+            let error = desired - estimate;
+
+            let gain =
+                self.step_size * error / (self.regularization + self.reference_energy as f32);
+            for (weight, x) in self.weights.iter_mut().zip(&self.reference) {
+                *weight += gain * x;
+            }
+            output.push(error);
+        }
+        Ok(output)
+    }
+}
+
+impl ProcessingNode for AdaptiveFilterNode {
+    fn process(&mut self, input: ProcessingData) -> Result<ProcessingData> {
+        match input {
+            ProcessingData::DualChannel {
+                channel_a,
+                channel_b,
+                sample_rate,
+                timestamp,
+                frame_number,
+            } => Ok(ProcessingData::SingleChannel {
+                samples: self.cancel(&channel_a, &channel_b)?,
+                sample_rate,
+                timestamp,
+                frame_number,
+            }),
+            ProcessingData::AudioFrame(frame) => Ok(ProcessingData::SingleChannel {
+                samples: self.cancel(&frame.channel_a, &frame.channel_b)?,
+                sample_rate: frame.sample_rate,
+                timestamp: frame.timestamp,
+                frame_number: frame.frame_number,
+            }),
+            _ => anyhow::bail!("AdaptiveFilterNode requires DualChannel or AudioFrame input data"),
+        }
+    }
+
+    fn node_id(&self) -> &str {
+        &self.id
+    }
+
+    fn node_type(&self) -> &str {
+        "adaptive_filter"
+    }
+
+    fn accepts_input(&self, input: &ProcessingData) -> bool {
+        matches!(
+            input,
+            ProcessingData::DualChannel { .. } | ProcessingData::AudioFrame(_)
+        )
+    }
+
+    fn output_type(&self, input: &ProcessingData) -> Option<String> {
+        match input {
+            ProcessingData::DualChannel { .. } | ProcessingData::AudioFrame(_) => {
+                Some("SingleChannel".to_string())
+            }
+            _ => None,
+        }
+    }
+
+    fn reset(&mut self) {
+        let filter_length = self.weights.len();
+        self.weights = vec![0.0; filter_length];
+        self.reference = VecDeque::from(vec![0.0; filter_length]);
+        self.reference_energy = 0.0;
+    }
+
+    fn clone_node(&self) -> Box<dyn ProcessingNode> {
+        Box::new(self.clone())
+    }
+
+    fn supports_hot_reload(&self) -> bool {
+        true // step_size and regularization apply immediately, filter_length restarts adaptation
+    }
+
+    fn update_config(&mut self, parameters: &serde_json::Value) -> Result<bool> {
+        let mut updated = false;
+
+        if let Some(value) = parameters.get("step_size") {
+            let step_size = value
+                .as_f64()
+                .ok_or_else(|| anyhow::anyhow!("step_size must be a number"))?
+                as f32;
+            Self::validate_step_size(step_size)?;
+            self.step_size = step_size;
+            updated = true;
+        }
+
+        if let Some(value) = parameters.get("regularization") {
+            let regularization = value
+                .as_f64()
+                .ok_or_else(|| anyhow::anyhow!("regularization must be a number"))?;
+            if regularization < 0.0 {
+                anyhow::bail!("regularization must not be negative");
+            }
+            self.regularization = regularization as f32;
+            updated = true;
+        }
+
+        if let Some(value) = parameters.get("filter_length") {
+            let filter_length = value
+                .as_u64()
+                .ok_or_else(|| anyhow::anyhow!("filter_length must be a positive integer"))?
+                as usize;
+            Self::validate_filter_length(filter_length)?;
+            if filter_length != self.weights.len() {
+                self.weights.resize(filter_length, 0.0);
+                self.reset();
+            }
+            updated = true;
+        }
+
+        if updated {
+            debug!(
+                "AdaptiveFilterNode '{}': Configuration updated (filter_length: {}, step_size: {})",
+                self.id,
+                self.weights.len(),
+                self.step_size
+            );
+        }
+        Ok(updated)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
@@ -10,6 +10,7 @@
 use super::data::ProcessingData;
 use super::filter::ChannelTarget;
 use super::traits::ProcessingNode;
+use crate::processing::buffer_pool::FrameBufferPool;
 use anyhow::Result;
 
 /// Channel selector node that extracts a specific channel from dual-channel data
@@ -108,10 +109,13 @@ impl ChannelSelectorNode {
     pub fn new(id: String, target_channel: ChannelTarget) -> Self {
         Self { id, target_channel }
     }
-}
 
-impl ProcessingNode for ChannelSelectorNode {
-    fn process(&mut self, input: ProcessingData) -> Result<ProcessingData> {
+    /// Select the target channel, giving the other one back to `pool` when given
+    fn select(
+        &self,
+        input: ProcessingData,
+        pool: Option<&FrameBufferPool>,
+    ) -> Result<ProcessingData> {
         match input {
             ProcessingData::DualChannel {
                 channel_a,
@@ -120,13 +124,16 @@ impl ProcessingNode for ChannelSelectorNode {
                 timestamp,
                 frame_number,
             } => {
-                let samples = match self.target_channel {
-                    ChannelTarget::ChannelA => channel_a,
-                    ChannelTarget::ChannelB => channel_b,
+                let (samples, other) = match self.target_channel {
+                    ChannelTarget::ChannelA => (channel_a, channel_b),
+                    ChannelTarget::ChannelB => (channel_b, channel_a),
                     ChannelTarget::Both => {
                         anyhow::bail!("ChannelSelectorNode cannot select 'Both' channels for SingleChannel output")
                     }
                 };
+                if let Some(pool) = pool {
+                    pool.give(other);
+                }
 
                 Ok(ProcessingData::SingleChannel {
                     samples,
@@ -138,6 +145,20 @@ impl ProcessingNode for ChannelSelectorNode {
             _ => anyhow::bail!("ChannelSelectorNode requires DualChannel input data"),
         }
     }
+}
+
+impl ProcessingNode for ChannelSelectorNode {
+    fn process(&mut self, input: ProcessingData) -> Result<ProcessingData> {
+        self.select(input, None)
+    }
+
+    fn process_pooled(
+        &mut self,
+        input: ProcessingData,
+        pool: &FrameBufferPool,
+    ) -> Result<ProcessingData> {
+        self.select(input, Some(pool))
+    }
 
     fn node_id(&self) -> &str {
         &self.id
@@ -363,13 +384,14 @@ impl ChannelMixerNode {
     pub fn new(id: String, mix_strategy: MixStrategy) -> Self {
         Self { id, mix_strategy }
     }
-}
 
-impl ProcessingNode for ChannelMixerNode {
-    fn process(&mut self, input: ProcessingData) -> Result<ProcessingData> {
+    /// Mix channel B into channel A in place
+    ///
+    /// Channel B is given back to `pool` when given.
+    fn mix(&self, input: ProcessingData, pool: Option<&FrameBufferPool>) -> Result<ProcessingData> {
         match input {
             ProcessingData::DualChannel {
-                channel_a,
+                mut channel_a,
                 channel_b,
                 sample_rate,
                 timestamp,
@@ -379,19 +401,22 @@ impl ProcessingNode for ChannelMixerNode {
                     anyhow::bail!("Channel lengths must match for mixing");
                 }
 
-                let mixed_samples: Vec<f32> = channel_a
-                    .iter()
-                    .zip(channel_b.iter())
-                    .map(|(a, b)| match self.mix_strategy {
-                        MixStrategy::Add => a + b,
-                        MixStrategy::Subtract => a - b,
-                        MixStrategy::Average => (a + b) / 2.0,
-                        MixStrategy::Weighted { a_weight, b_weight } => a * a_weight + b * b_weight,
-                    })
-                    .collect();
+                for (a, &b) in channel_a.iter_mut().zip(channel_b.iter()) {
+                    *a = match self.mix_strategy {
+                        MixStrategy::Add => *a + b,
+                        MixStrategy::Subtract => *a - b,
+                        MixStrategy::Average => (*a + b) / 2.0,
+                        MixStrategy::Weighted { a_weight, b_weight } => {
+                            *a * a_weight + b * b_weight
+                        }
+                    };
+                }
+                if let Some(pool) = pool {
+                    pool.give(channel_b);
+                }
 
                 Ok(ProcessingData::SingleChannel {
-                    samples: mixed_samples,
+                    samples: channel_a,
                     sample_rate,
                     timestamp,
                     frame_number,
@@ -400,6 +425,20 @@ impl ProcessingNode for ChannelMixerNode {
             _ => anyhow::bail!("ChannelMixerNode requires DualChannel input data"),
         }
     }
+}
+
+impl ProcessingNode for ChannelMixerNode {
+    fn process(&mut self, input: ProcessingData) -> Result<ProcessingData> {
+        self.mix(input, None)
+    }
+
+    fn process_pooled(
+        &mut self,
+        input: ProcessingData,
+        pool: &FrameBufferPool,
+    ) -> Result<ProcessingData> {
+        self.mix(input, Some(pool))
+    }
 
     fn node_id(&self) -> &str {
         &self.id
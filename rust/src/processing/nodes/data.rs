@@ -72,6 +72,7 @@ pub type NodeId = String;
 ///         sample_rate: 44100,
 ///         processing_steps: vec!["filter".to_string(), "differential".to_string()],
 ///         processing_latency_us: 1500,
+///         snr_db: None,
 ///     },
 /// };
 /// ```
@@ -113,6 +114,7 @@ pub enum ProcessingData {
 /// - `sample_rate` - Sample rate of the processed audio
 /// - `processing_steps` - List of processing operations applied
 /// - `processing_latency_us` - Total processing time in microseconds
+/// - `snr_db` - Peak-to-noise-floor ratio of the signal in dB, when measured
 ///
 /// ### Examples
 ///
@@ -130,6 +132,7 @@ pub enum ProcessingData {
 ///         "photoacoustic_analysis".to_string(),
 ///     ],
 ///     processing_latency_us: 2500,
+///     snr_db: Some(32.5),
 /// };
 ///
 /// println!("Processing took {} steps", metadata.processing_steps.len());
@@ -141,6 +144,9 @@ pub struct ProcessingMetadata {
     pub sample_rate: u32,
     pub processing_steps: Vec<String>,
     pub processing_latency_us: u64,
+    /// Peak-to-noise-floor ratio in dB, set by the nodes that measure it
+    #[serde(default)]
+    pub snr_db: Option<f32>,
 }
 
 impl ProcessingData {
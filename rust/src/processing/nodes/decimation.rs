@@ -0,0 +1,425 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Decimation node implementation
+//!
+//! This module provides the `DecimationNode` which reduces the sample rate of
+//! audio signals by an integer factor, for analyses that only need a lower rate.
+//!
+//! ### Algorithm
+//!
+//! Each channel goes through a linear-phase FIR lowpass, a Blackman-windowed
+//! sinc, then every `factor`-th sample is kept. Only the kept samples are
+//! filtered. The cutoff, where the gain is -6 dB, is `cutoff_ratio` times the
+//! output Nyquist frequency. With the default length of `16·factor + 1` taps,
+//! everything that would alias below the cutoff is attenuated by more than 70 dB.
+//!
+//! The filter history and the position of the next kept sample carry over from
+//! one frame to the next, so the output is the decimation of the continuous
+//! stream whatever the frame sizes. Frames whose length is a multiple of the
+//! factor give `length / factor` samples; other frames give one sample more or
+//! less from time to time. The output is delayed by `(filter_length - 1) / 2`
+//! input samples. Frame numbers and timestamps are kept.
+
+use super::data::ProcessingData;
+use super::traits::ProcessingNode;
+use anyhow::Result;
+use log::debug;
+use std::f64::consts::PI;
+
+/// Blackman-windowed sinc lowpass coefficients with unity gain at DC
+///
+/// ### Arguments
+///
+/// * `filter_length` - Number of taps, at least 2
+/// * `cutoff` - Cutoff frequency (-6 dB) in cycles per sample
+///
+/// ### Returns
+///
+/// The `filter_length` symmetric coefficients
+pub(super) fn lowpass_taps(filter_length: usize, cutoff: f64) -> Vec<f64> {
+    let order = (filter_length - 1) as f64;
+    let taps: Vec<f64> = (0..filter_length)
+        .map(|k| {
+            let t = k as f64 - order / 2.0;
+            let sinc = if t == 0.0 {
+                2.0 * cutoff
+            } else {
+                (2.0 * PI * cutoff * t).sin() / (PI * t)
+            };
+            let phase = 2.0 * PI * k as f64 / order;
+            sinc * (0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos())
+        })
+        .collect();
+    let gain: f64 = taps.iter().sum();
+    taps.into_iter().map(|tap| tap / gain).collect()
+}
+
+/// Streaming state of one channel
+#[derive(Debug, Clone)]
+struct ChannelState {
+    /// Last `filter_length - 1` input samples
+    history: Vec<f32>,
+    /// Index, in the next frame, of the next sample to keep
+    phase: usize,
+}
+
+impl ChannelState {
+    fn new(filter_length: usize) -> Self {
+        Self {
+            history: vec![0.0; filter_length - 1],
+            phase: 0,
+        }
+    }
+}
+
+/// A processing node that lowpass filters and downsamples audio signals.
+///
+/// The output data carries the reduced sample rate. The input sample rate must
+/// be a multiple of the decimation factor. It supports single-channel,
+/// dual-channel and audio frame data.
+///
+/// ### Examples
+///
+/// ```no_run
+/// use rust_photoacoustic::processing::nodes::{DecimationNode, ProcessingData, ProcessingNode};
+///
+/// let mut node = DecimationNode::new("decimate".to_string(), 4)?.with_cutoff_ratio(0.8)?;
+///
+/// let input = ProcessingData::SingleChannel {
+///     samples: vec![0.0; 4800],
+///     sample_rate: 48000,
+///     timestamp: 1000,
+///     frame_number: 1,
+/// };
+///
+/// match node.process(input)? {
+///     ProcessingData::SingleChannel { samples, sample_rate, .. } => {
+///         assert_eq!(samples.len(), 1200);
+///         assert_eq!(sample_rate, 12000);
+///     }
+///     _ => panic!("Expected SingleChannel output"),
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct DecimationNode {
+    /// Unique identifier for this node
+    id: String,
+    /// Ratio between the input and output sample rates
+    factor: usize,
+    /// Cutoff frequency as a fraction of the output Nyquist frequency
+    cutoff_ratio: f64,
+    /// Anti-alias FIR coefficients, with unity gain at DC
+    coefficients: Vec<f32>,
+    /// Channel A, or the single channel, then channel B
+    channels: [ChannelState; 2],
+}
+
+impl DecimationNode {
+    /// Create a new decimation node.
+    ///
+    /// The anti-alias filter has `16·factor + 1` taps and a cutoff at 0.8 times
+    /// the output Nyquist frequency.
+    ///
+    /// ### Arguments
+    ///
+    /// * `id` - Unique identifier for this node
+    /// * `factor` - Ratio between the input and output sample rates, at least 2
+    ///
+    /// ### Returns
+    ///
+    /// The node, or an error if the factor is out of range
+    pub fn new(id: String, factor: usize) -> Result<Self> {
+        Self::validate_factor(factor)?;
+        let mut node = Self {
+            id,
+            factor,
+            cutoff_ratio: 0.8,
+            coefficients: Vec::new(),
+            channels: [ChannelState::new(1), ChannelState::new(1)],
+        };
+        node.design(Self::default_filter_length(factor));
+        Ok(node)
+    }
+
+    /// Set the cutoff frequency of the anti-alias filter
+    ///
+    /// ### Arguments
+    ///
+    /// * `cutoff_ratio` - Cutoff as a fraction of the output Nyquist frequency, in (0, 1]
+    ///
+    /// ### Returns
+    ///
+    /// The node, or an error if the ratio is out of range
+    pub fn with_cutoff_ratio(mut self, cutoff_ratio: f64) -> Result<Self> {
+        Self::validate_cutoff_ratio(cutoff_ratio)?;
+        self.cutoff_ratio = cutoff_ratio;
+        self.design(self.coefficients.len());
+        Ok(self)
+    }
+
+    /// Set the number of taps of the anti-alias filter
+    ///
+    /// Longer filters have a sharper transition and a longer delay.
+    ///
+    /// ### Arguments
+    ///
+    /// * `filter_length` - Number of taps, odd and at least 3
+    ///
+    /// ### Returns
+    ///
+    /// The node, or an error if the length is invalid
+    pub fn with_filter_length(mut self, filter_length: usize) -> Result<Self> {
+        Self::validate_filter_length(filter_length)?;
+        self.design(filter_length);
+        Ok(self)
+    }
+
+    /// Ratio between the input and output sample rates
+    pub fn get_factor(&self) -> usize {
+        self.factor
+    }
+
+    /// Cutoff frequency as a fraction of the output Nyquist frequency
+    pub fn get_cutoff_ratio(&self) -> f64 {
+        self.cutoff_ratio
+    }
+
+    /// Number of taps of the anti-alias filter
+    pub fn get_filter_length(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    /// Delay of the output in input samples
+    pub fn latency_samples(&self) -> usize {
+        (self.coefficients.len() - 1) / 2
+    }
+
+    fn default_filter_length(factor: usize) -> usize {
+        16 * factor + 1
+    }
+
+    fn validate_factor(factor: usize) -> Result<()> {
+        if factor < 2 {
+            anyhow::bail!("Decimation factor must be at least 2, got {}", factor);
+        }
+        Ok(())
+    }
+
+    fn validate_cutoff_ratio(cutoff_ratio: f64) -> Result<()> {
+        if cutoff_ratio.is_nan() || cutoff_ratio <= 0.0 || cutoff_ratio > 1.0 {
+            anyhow::bail!("cutoff_ratio must be in (0, 1], got {}", cutoff_ratio);
+        }
+        Ok(())
+    }
+
+    fn validate_filter_length(filter_length: usize) -> Result<()> {
+        if filter_length < 3 || filter_length % 2 == 0 {
+            anyhow::bail!(
+                "filter_length must be odd and at least 3, got {}",
+                filter_length
+            );
+        }
+        Ok(())
+    }
+
+    /// Compute the Blackman-windowed sinc coefficients and restart the stream
+    ///
+    /// ### Arguments
+    ///
+    /// * `filter_length` - Number of taps
+    fn design(&mut self, filter_length: usize) {
+        // Cutoff in cycles per input sample
+        let cutoff = self.cutoff_ratio * 0.5 / self.factor as f64;
+        self.coefficients = lowpass_taps(filter_length, cutoff)
+            .into_iter()
+            .map(|tap| tap as f32)
+            .collect();
+        self.reset();
+    }
+
+    /// Output sample rate for an input sample rate
+    ///
+    /// ### Arguments
+    ///
+    /// * `sample_rate` - Input sample rate in Hz
+    ///
+    /// ### Returns
+    ///
+    /// The reduced sample rate, or an error if it is not an integer
+    fn output_sample_rate(&self, sample_rate: u32) -> Result<u32> {
+        if sample_rate % self.factor as u32 != 0 {
+            anyhow::bail!(
+                "DecimationNode '{}': Sample rate {} Hz is not a multiple of the factor {}",
+                self.id,
+                sample_rate,
+                self.factor
+            );
+        }
+        Ok(sample_rate / self.factor as u32)
+    }
+
+    /// Filter and decimate the next `samples` of a channel
+    ///
+    /// ### Arguments
+    ///
+    /// * `channel` - 0 for channel A or a single channel, 1 for channel B
+    /// * `samples` - Input samples
+    ///
+    /// ### Returns
+    ///
+    /// The kept samples of the filtered signal
+    fn process_channel(&mut self, channel: usize, samples: &[f32]) -> Vec<f32> {
+        let state = &mut self.channels[channel];
+        let delay = self.coefficients.len() - 1;
+
+        // Input sample n is at index n + delay
+        let mut buffer = std::mem::take(&mut state.history);
+        buffer.extend_from_slice(samples);
+
+        let output: Vec<f32> = (state.phase..samples.len())
+            .step_by(self.factor)
+            .map(|n| {
+                buffer[n..=n + delay]
+                    .iter()
+                    .rev()
+                    .zip(&self.coefficients)
+                    .map(|(sample, coefficient)| sample * coefficient)
+                    .sum()
+            })
+            .collect();
+
+        state.phase = state.phase + output.len() * self.factor - samples.len();
+        buffer.drain(..buffer.len() - delay);
+        state.history = buffer;
+        output
+    }
+}
+
+impl ProcessingNode for DecimationNode {
+    fn process(&mut self, input: ProcessingData) -> Result<ProcessingData> {
+        match input {
+            ProcessingData::SingleChannel {
+                samples,
+                sample_rate,
+                timestamp,
+                frame_number,
+            } => Ok(ProcessingData::SingleChannel {
+                sample_rate: self.output_sample_rate(sample_rate)?,
+                samples: self.process_channel(0, &samples),
+                timestamp,
+                frame_number,
+            }),
+            ProcessingData::DualChannel {
+                channel_a,
+                channel_b,
+                sample_rate,
+                timestamp,
+                frame_number,
+            } => Ok(ProcessingData::DualChannel {
+                sample_rate: self.output_sample_rate(sample_rate)?,
+                channel_a: self.process_channel(0, &channel_a),
+                channel_b: self.process_channel(1, &channel_b),
+                timestamp,
+                frame_number,
+            }),
+            ProcessingData::AudioFrame(mut frame) => {
+                frame.sample_rate = self.output_sample_rate(frame.sample_rate)?;
+                frame.channel_a = self.process_channel(0, &frame.channel_a);
+                frame.channel_b = self.process_channel(1, &frame.channel_b);
+                Ok(ProcessingData::AudioFrame(frame))
+            }
+            ProcessingData::PhotoacousticResult { .. } => {
+                anyhow::bail!("DecimationNode cannot process PhotoacousticResult data")
+            }
+        }
+    }
+
+    fn node_id(&self) -> &str {
+        &self.id
+    }
+
+    fn node_type(&self) -> &str {
+        "decimation"
+    }
+
+    fn accepts_input(&self, input: &ProcessingData) -> bool {
+        matches!(
+            input,
+            ProcessingData::SingleChannel { .. }
+                | ProcessingData::DualChannel { .. }
+                | ProcessingData::AudioFrame(_)
+        )
+    }
+
+    fn output_type(&self, input: &ProcessingData) -> Option<String> {
+        match input {
+            ProcessingData::SingleChannel { .. } => Some("SingleChannel".to_string()),
+            ProcessingData::DualChannel { .. } => Some("DualChannel".to_string()),
+            ProcessingData::AudioFrame(_) => Some("AudioFrame".to_string()),
+            ProcessingData::PhotoacousticResult { .. } => None,
+        }
+    }
+
+    fn reset(&mut self) {
+        let filter_length = self.coefficients.len();
+        self.channels = [
+            ChannelState::new(filter_length),
+            ChannelState::new(filter_length),
+        ];
+    }
+
+    fn clone_node(&self) -> Box<dyn ProcessingNode> {
+        Box::new(self.clone())
+    }
+
+    fn supports_hot_reload(&self) -> bool {
+        true
+    }
+
+    /// Update the decimation parameters
+    ///
+    /// Supports `factor`, `cutoff_ratio` and `filter_length`. A new factor without
+    /// a `filter_length` uses the default length for that factor. The filter is
+    /// designed again and the stream restarts.
+    fn update_config(&mut self, parameters: &serde_json::Value) -> Result<bool> {
+        let factor = match parameters.get("factor").and_then(|v| v.as_u64()) {
+            Some(factor) => factor as usize,
+            None => self.factor,
+        };
+        let cutoff_ratio = parameters
+            .get("cutoff_ratio")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(self.cutoff_ratio);
+        let filter_length = match parameters.get("filter_length").and_then(|v| v.as_u64()) {
+            Some(filter_length) => filter_length as usize,
+            None if factor != self.factor => Self::default_filter_length(factor),
+            None => self.coefficients.len(),
+        };
+
+        if factor == self.factor
+            && cutoff_ratio == self.cutoff_ratio
+            && filter_length == self.coefficients.len()
+        {
+            return Ok(false);
+        }
+
+        Self::validate_factor(factor)?;
+        Self::validate_cutoff_ratio(cutoff_ratio)?;
+        Self::validate_filter_length(filter_length)?;
+        self.factor = factor;
+        self.cutoff_ratio = cutoff_ratio;
+        self.design(filter_length);
+        debug!(
+            "DecimationNode '{}': Configuration updated (factor: {}, cutoff_ratio: {}, filter_length: {})",
+            self.id, self.factor, self.cutoff_ratio, filter_length
+        );
+        Ok(true)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
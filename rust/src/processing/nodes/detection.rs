@@ -0,0 +1,309 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Detection state machine
+//!
+//! This module provides the `DetectionStateMachine` used by the
+//! [`PhotoacousticOutputNode`](super::PhotoacousticOutputNode) to turn a per-frame
+//! signal level into discrete detection events. A level flickering around the
+//! threshold does not produce a burst of detections: it must stay above the
+//! detection threshold for a dwell time before the detection starts, and below
+//! the release threshold for another dwell time before it ends.
+//!
+//! ### States
+//!
+//! ```text
+//!            level > detection              dwell elapsed
+//!   Idle ─────────────────────────▶ Armed ─────────────────▶ Detected ◀─┐
+//!    ▲                                │     (Onset event)       │       │ level > release
+//!    │      level <= detection        │                         │       │
+//!    ├────────────────────────────────┘     level <= release    ▼       │
+//!    │                                      ┌─────────────── Holding ───┘
+//!    └──────────────────────────────────────┘  dwell elapsed
+//!                 (Release event)
+//! ```
+//!
+//! Dwell times are measured with the frame timestamps, in milliseconds. With the
+//! default zero dwell times and equal thresholds, the detection follows the
+//! per-frame comparison of the level with the detection threshold.
+
+use anyhow::Result;
+use std::time::SystemTime;
+
+/// State of a [`DetectionStateMachine`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectionState {
+    /// No detection, the level is below the detection threshold
+    Idle,
+    /// The level crossed the detection threshold, waiting for the detection dwell time
+    Armed,
+    /// Detection in progress, the level is above the release threshold
+    Detected,
+    /// Detection in progress, the level fell below the release threshold and the
+    /// release dwell time is running
+    Holding,
+}
+
+impl DetectionState {
+    /// Name of the state, as published to scripts and logs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DetectionState::Idle => "idle",
+            DetectionState::Armed => "armed",
+            DetectionState::Detected => "detected",
+            DetectionState::Holding => "holding",
+        }
+    }
+
+    /// Whether a detection is in progress, between its onset and its release
+    pub fn is_detected(&self) -> bool {
+        matches!(self, DetectionState::Detected | DetectionState::Holding)
+    }
+}
+
+/// Kind of a [`DetectionEvent`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectionEventKind {
+    /// The level stayed above the detection threshold for the detection dwell time
+    Onset,
+    /// The level stayed below the release threshold for the release dwell time
+    Release,
+}
+
+impl DetectionEventKind {
+    /// Name of the event kind, as published to scripts and logs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DetectionEventKind::Onset => "onset",
+            DetectionEventKind::Release => "release",
+        }
+    }
+}
+
+/// A discrete event emitted by a [`DetectionStateMachine`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectionEvent {
+    /// Sequence number, starting at 1 and increasing with each event of a machine
+    pub sequence: u64,
+    /// Onset or release of the detection
+    pub kind: DetectionEventKind,
+    /// Signal level of the frame that fired the event
+    pub level: f32,
+    /// Number of the frame that fired the event
+    pub frame_number: u64,
+    /// Timestamp of the frame that fired the event, in milliseconds
+    pub frame_timestamp: u64,
+    /// For an onset, time since the level crossed the detection threshold; for a
+    /// release, time since the onset. In milliseconds of frame timestamps
+    pub duration_ms: u64,
+    /// Time at which the event was emitted
+    pub timestamp: SystemTime,
+}
+
+/// Debounced detection of a signal level with hysteresis and dwell times
+#[derive(Debug, Clone)]
+pub struct DetectionStateMachine {
+    /// Level above which a detection is armed
+    detection_threshold: f32,
+    /// Level at or below which a detection is released
+    release_threshold: f32,
+    /// Time the level must stay above the detection threshold, in milliseconds
+    detection_dwell_ms: u64,
+    /// Time the level must stay at or below the release threshold, in milliseconds
+    release_dwell_ms: u64,
+    /// Current state
+    state: DetectionState,
+    /// Frame timestamp at which the current state was entered
+    state_since: u64,
+    /// Frame timestamp of the last onset
+    onset_at: u64,
+    /// Sequence number of the last event
+    last_sequence: u64,
+}
+
+impl DetectionStateMachine {
+    /// Create a new detection state machine
+    ///
+    /// ### Arguments
+    ///
+    /// * `detection_threshold` - Level above which a detection is armed
+    /// * `release_threshold` - Level at or below which a detection is released, not
+    ///   above the detection threshold
+    /// * `detection_dwell_ms` - Time the level must stay above the detection threshold
+    ///   before the onset
+    /// * `release_dwell_ms` - Time the level must stay at or below the release threshold
+    ///   before the release
+    ///
+    /// ### Returns
+    ///
+    /// The state machine, or an error if the thresholds are invalid
+    pub fn new(
+        detection_threshold: f32,
+        release_threshold: f32,
+        detection_dwell_ms: u64,
+        release_dwell_ms: u64,
+    ) -> Result<Self> {
+        if !detection_threshold.is_finite() || !release_threshold.is_finite() {
+            anyhow::bail!("Detection thresholds must be finite numbers");
+        }
+        if release_threshold > detection_threshold {
+            anyhow::bail!(
+                "release_threshold ({}) must not be above detection_threshold ({})",
+                release_threshold,
+                detection_threshold
+            );
+        }
+
+        Ok(Self {
+            detection_threshold,
+            release_threshold,
+            detection_dwell_ms,
+            release_dwell_ms,
+            state: DetectionState::Idle,
+            state_since: 0,
+            onset_at: 0,
+            last_sequence: 0,
+        })
+    }
+
+    /// Feed the level of a new frame
+    ///
+    /// ### Arguments
+    ///
+    /// * `level` - Signal level of the frame
+    /// * `frame_timestamp` - Timestamp of the frame, in milliseconds
+    /// * `frame_number` - Number of the frame
+    ///
+    /// ### Returns
+    ///
+    /// The onset or release event fired by this frame, if any
+    pub fn update(
+        &mut self,
+        level: f32,
+        frame_timestamp: u64,
+        frame_number: u64,
+    ) -> Option<DetectionEvent> {
+        if self.state == DetectionState::Idle && level > self.detection_threshold {
+            self.enter(DetectionState::Armed, frame_timestamp);
+        }
+
+        match self.state {
+            DetectionState::Armed if level <= self.detection_threshold => {
+                self.enter(DetectionState::Idle, frame_timestamp);
+                None
+            }
+            DetectionState::Armed if self.elapsed(frame_timestamp) >= self.detection_dwell_ms => {
+                let armed_for = self.elapsed(frame_timestamp);
+                self.enter(DetectionState::Detected, frame_timestamp);
+                self.onset_at = frame_timestamp;
+                Some(self.event(
+                    DetectionEventKind::Onset,
+                    level,
+                    frame_number,
+                    frame_timestamp,
+                    armed_for,
+                ))
+            }
+            DetectionState::Detected | DetectionState::Holding
+                if level > self.release_threshold =>
+            {
+                if self.state == DetectionState::Holding {
+                    self.enter(DetectionState::Detected, frame_timestamp);
+                }
+                None
+            }
+            DetectionState::Detected | DetectionState::Holding => {
+                if self.state == DetectionState::Detected {
+                    self.enter(DetectionState::Holding, frame_timestamp);
+                }
+                if self.elapsed(frame_timestamp) < self.release_dwell_ms {
+                    return None;
+                }
+                let detected_for = frame_timestamp.saturating_sub(self.onset_at);
+                self.enter(DetectionState::Idle, frame_timestamp);
+                Some(self.event(
+                    DetectionEventKind::Release,
+                    level,
+                    frame_number,
+                    frame_timestamp,
+                    detected_for,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Current state
+    pub fn state(&self) -> DetectionState {
+        self.state
+    }
+
+    /// Level above which a detection is armed
+    pub fn detection_threshold(&self) -> f32 {
+        self.detection_threshold
+    }
+
+    /// Level at or below which a detection is released
+    pub fn release_threshold(&self) -> f32 {
+        self.release_threshold
+    }
+
+    /// Time the level must stay above the detection threshold, in milliseconds
+    pub fn detection_dwell_ms(&self) -> u64 {
+        self.detection_dwell_ms
+    }
+
+    /// Time the level must stay at or below the release threshold, in milliseconds
+    pub fn release_dwell_ms(&self) -> u64 {
+        self.release_dwell_ms
+    }
+
+    /// Return to the idle state
+    ///
+    /// Sequence numbers keep increasing, so that consumers never see an event
+    /// number twice.
+    pub fn reset(&mut self) {
+        self.state = DetectionState::Idle;
+        self.state_since = 0;
+        self.onset_at = 0;
+    }
+
+    /// Number the next events after the last event of another state machine
+    ///
+    /// ### Arguments
+    ///
+    /// * `previous` - State machine replaced by this one
+    pub fn continue_sequence_from(&mut self, previous: &DetectionStateMachine) {
+        self.last_sequence = previous.last_sequence;
+    }
+
+    fn enter(&mut self, state: DetectionState, frame_timestamp: u64) {
+        self.state = state;
+        self.state_since = frame_timestamp;
+    }
+
+    fn elapsed(&self, frame_timestamp: u64) -> u64 {
+        frame_timestamp.saturating_sub(self.state_since)
+    }
+
+    fn event(
+        &mut self,
+        kind: DetectionEventKind,
+        level: f32,
+        frame_number: u64,
+        frame_timestamp: u64,
+        duration_ms: u64,
+    ) -> DetectionEvent {
+        self.last_sequence += 1;
+        DetectionEvent {
+            sequence: self.last_sequence,
+            kind,
+            level,
+            frame_number,
+            frame_timestamp,
+            duration_ms,
+            timestamp: SystemTime::now(),
+        }
+    }
+}
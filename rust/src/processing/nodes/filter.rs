@@ -10,6 +10,7 @@
 use super::data::ProcessingData;
 use super::traits::ProcessingNode;
 use crate::preprocessing::Filter;
+use crate::processing::buffer_pool::FrameBufferPool;
 use anyhow::Result;
 use log;
 
@@ -149,10 +150,28 @@ impl FilterNode {
             target_channel,
         }
     }
-}
 
-impl ProcessingNode for FilterNode {
-    fn process(&mut self, input: ProcessingData) -> Result<ProcessingData> {
+    /// Filter one channel, with an output buffer from `pool` when given
+    ///
+    /// The input buffer is given back to the pool once filtered.
+    fn filter_channel(&self, samples: Vec<f32>, pool: Option<&FrameBufferPool>) -> Vec<f32> {
+        match pool {
+            Some(pool) => {
+                let mut filtered = pool.take(samples.len());
+                self.filter.apply_into(&samples, &mut filtered);
+                pool.give(samples);
+                filtered
+            }
+            None => self.filter.apply(&samples),
+        }
+    }
+
+    /// Filter the target channels of `input`
+    fn filter_data(
+        &self,
+        input: ProcessingData,
+        pool: Option<&FrameBufferPool>,
+    ) -> Result<ProcessingData> {
         match input {
             ProcessingData::DualChannel {
                 mut channel_a,
@@ -163,14 +182,14 @@ impl ProcessingNode for FilterNode {
             } => {
                 match self.target_channel {
                     ChannelTarget::ChannelA => {
-                        channel_a = self.filter.apply(&channel_a);
+                        channel_a = self.filter_channel(channel_a, pool);
                     }
                     ChannelTarget::ChannelB => {
-                        channel_b = self.filter.apply(&channel_b);
+                        channel_b = self.filter_channel(channel_b, pool);
                     }
                     ChannelTarget::Both => {
-                        channel_a = self.filter.apply(&channel_a);
-                        channel_b = self.filter.apply(&channel_b);
+                        channel_a = self.filter_channel(channel_a, pool);
+                        channel_b = self.filter_channel(channel_b, pool);
                     }
                 }
 
@@ -188,7 +207,7 @@ impl ProcessingNode for FilterNode {
                 timestamp,
                 frame_number,
             } => {
-                let filtered_samples = self.filter.apply(&samples);
+                let filtered_samples = self.filter_channel(samples, pool);
                 Ok(ProcessingData::SingleChannel {
                     samples: filtered_samples,
                     sample_rate,
@@ -199,6 +218,20 @@ impl ProcessingNode for FilterNode {
             _ => anyhow::bail!("FilterNode can only process DualChannel or SingleChannel data"),
         }
     }
+}
+
+impl ProcessingNode for FilterNode {
+    fn process(&mut self, input: ProcessingData) -> Result<ProcessingData> {
+        self.filter_data(input, None)
+    }
+
+    fn process_pooled(
+        &mut self,
+        input: ProcessingData,
+        pool: &FrameBufferPool,
+    ) -> Result<ProcessingData> {
+        self.filter_data(input, Some(pool))
+    }
 
     fn node_id(&self) -> &str {
         &self.id
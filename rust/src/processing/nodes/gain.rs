@@ -238,20 +238,18 @@ impl GainNode {
         }
     }
 
-    /// Apply gain to a vector of samples.
+    /// Apply gain to a vector of samples in place.
+    ///
+    /// The input buffers are owned by the node, so the gain is applied
+    /// without allocating new buffers.
     ///
     /// ### Arguments
     ///
     /// * `samples` - Audio samples to process
-    ///
-    /// ### Returns
-    ///
-    /// Vector of samples with gain applied
-    fn apply_gain(&self, samples: &[f32]) -> Vec<f32> {
-        samples
-            .iter()
-            .map(|&sample| sample * self.linear_gain)
-            .collect()
+    fn apply_gain(&self, samples: &mut [f32]) {
+        for sample in samples {
+            *sample *= self.linear_gain;
+        }
     }
 }
 
@@ -259,43 +257,40 @@ impl ProcessingNode for GainNode {
     fn process(&mut self, input: ProcessingData) -> Result<ProcessingData> {
         match input {
             ProcessingData::SingleChannel {
-                samples,
+                mut samples,
                 sample_rate,
                 timestamp,
                 frame_number,
             } => {
-                let processed_samples = self.apply_gain(&samples);
+                self.apply_gain(&mut samples);
                 Ok(ProcessingData::SingleChannel {
-                    samples: processed_samples,
+                    samples,
                     sample_rate,
                     timestamp,
                     frame_number,
                 })
             }
             ProcessingData::DualChannel {
-                channel_a,
-                channel_b,
+                mut channel_a,
+                mut channel_b,
                 sample_rate,
                 timestamp,
                 frame_number,
             } => {
-                let processed_channel_a = self.apply_gain(&channel_a);
-                let processed_channel_b = self.apply_gain(&channel_b);
+                self.apply_gain(&mut channel_a);
+                self.apply_gain(&mut channel_b);
                 Ok(ProcessingData::DualChannel {
-                    channel_a: processed_channel_a,
-                    channel_b: processed_channel_b,
+                    channel_a,
+                    channel_b,
                     sample_rate,
                     timestamp,
                     frame_number,
                 })
             }
-            ProcessingData::AudioFrame(frame) => {
-                let processed_channel_a = self.apply_gain(&frame.channel_a);
-                let processed_channel_b = self.apply_gain(&frame.channel_b);
-                let mut processed_frame = frame;
-                processed_frame.channel_a = processed_channel_a;
-                processed_frame.channel_b = processed_channel_b;
-                Ok(ProcessingData::AudioFrame(processed_frame))
+            ProcessingData::AudioFrame(mut frame) => {
+                self.apply_gain(&mut frame.channel_a);
+                self.apply_gain(&mut frame.channel_b);
+                Ok(ProcessingData::AudioFrame(frame))
             }
             ProcessingData::PhotoacousticResult { .. } => {
                 anyhow::bail!("GainNode cannot process PhotoacousticResult data")
@@ -565,6 +560,7 @@ mod tests {
                 sample_rate: 44100,
                 processing_steps: vec!["test".to_string()],
                 processing_latency_us: 100,
+                snr_db: None,
             },
         };
 
@@ -610,6 +606,7 @@ mod tests {
                 sample_rate: 44100,
                 processing_steps: vec!["test".to_string()],
                 processing_latency_us: 100,
+                snr_db: None,
             },
         };
         assert!(!gain_node.accepts_input(&photoacoustic));
@@ -0,0 +1,389 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Interpolation node implementation
+//!
+//! This module provides the `InterpolationNode` which increases the sample rate
+//! of audio signals by an integer factor, to match the rates of graph branches
+//! running at different rates. It is the inverse of the
+//! [`DecimationNode`](super::DecimationNode).
+//!
+//! ### Algorithm
+//!
+//! Upsampling inserts `factor - 1` zeros between the input samples, which
+//! repeats the spectrum of the signal around the multiples of the input sample
+//! rate. A Blackman-windowed sinc lowpass with a gain of `factor` removes these
+//! images. The cutoff, where the gain is -6 dB, is `cutoff_ratio` times the
+//! input Nyquist frequency.
+//!
+//! The filter is split into `factor` polyphase branches: output sample
+//! `n·factor + p` is the input filtered by the taps `p`, `p + factor`,
+//! `p + 2·factor`..., so the inserted zeros are never multiplied. Every input
+//! frame gives `factor` times as many samples, the filter history carries over
+//! between frames, and the output is delayed by `(filter_length - 1) / 2` output
+//! samples. Frame numbers and timestamps are kept.
+
+use super::data::ProcessingData;
+use super::decimation::lowpass_taps;
+use super::traits::ProcessingNode;
+use anyhow::Result;
+use log::debug;
+
+/// A processing node that upsamples audio signals with a polyphase filter.
+///
+/// The output data carries the increased sample rate. It supports
+/// single-channel, dual-channel and audio frame data.
+///
+/// ### Examples
+///
+/// ```no_run
+/// use rust_photoacoustic::processing::nodes::{InterpolationNode, ProcessingData, ProcessingNode};
+///
+/// let mut node = InterpolationNode::new("upsample".to_string(), 4)?.with_cutoff_ratio(0.8)?;
+///
+/// let input = ProcessingData::SingleChannel {
+///     samples: vec![0.0; 1200],
+///     sample_rate: 12000,
+///     timestamp: 1000,
+///     frame_number: 1,
+/// };
+///
+/// match node.process(input)? {
+///     ProcessingData::SingleChannel { samples, sample_rate, .. } => {
+///         assert_eq!(samples.len(), 4800);
+///         assert_eq!(sample_rate, 48000);
+///     }
+///     _ => panic!("Expected SingleChannel output"),
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct InterpolationNode {
+    /// Unique identifier for this node
+    id: String,
+    /// Ratio between the output and input sample rates
+    factor: usize,
+    /// Cutoff frequency as a fraction of the input Nyquist frequency
+    cutoff_ratio: f64,
+    /// Number of taps of the interpolation filter
+    filter_length: usize,
+    /// Taps of each polyphase branch, scaled by the factor, padded with zeros
+    /// to the same length
+    phases: Vec<Vec<f32>>,
+    /// Last input samples needed by the branches, for channel A, or the
+    /// single channel, then channel B
+    history: [Vec<f32>; 2],
+}
+
+impl InterpolationNode {
+    /// Create a new interpolation node.
+    ///
+    /// The interpolation filter has `16·factor + 1` taps and a cutoff at 0.8
+    /// times the input Nyquist frequency.
+    ///
+    /// ### Arguments
+    ///
+    /// * `id` - Unique identifier for this node
+    /// * `factor` - Ratio between the output and input sample rates, at least 2
+    ///
+    /// ### Returns
+    ///
+    /// The node, or an error if the factor is out of range
+    pub fn new(id: String, factor: usize) -> Result<Self> {
+        Self::validate_factor(factor)?;
+        let mut node = Self {
+            id,
+            factor,
+            cutoff_ratio: 0.8,
+            filter_length: Self::default_filter_length(factor),
+            phases: Vec::new(),
+            history: [Vec::new(), Vec::new()],
+        };
+        node.design();
+        Ok(node)
+    }
+
+    /// Set the cutoff frequency of the interpolation filter
+    ///
+    /// ### Arguments
+    ///
+    /// * `cutoff_ratio` - Cutoff as a fraction of the input Nyquist frequency, in (0, 1]
+    ///
+    /// ### Returns
+    ///
+    /// The node, or an error if the ratio is out of range
+    pub fn with_cutoff_ratio(mut self, cutoff_ratio: f64) -> Result<Self> {
+        Self::validate_cutoff_ratio(cutoff_ratio)?;
+        self.cutoff_ratio = cutoff_ratio;
+        self.design();
+        Ok(self)
+    }
+
+    /// Set the number of taps of the interpolation filter
+    ///
+    /// Longer filters have a sharper transition and a longer delay.
+    ///
+    /// ### Arguments
+    ///
+    /// * `filter_length` - Number of taps, odd and at least 3
+    ///
+    /// ### Returns
+    ///
+    /// The node, or an error if the length is invalid
+    pub fn with_filter_length(mut self, filter_length: usize) -> Result<Self> {
+        Self::validate_filter_length(filter_length)?;
+        self.filter_length = filter_length;
+        self.design();
+        Ok(self)
+    }
+
+    /// Ratio between the output and input sample rates
+    pub fn get_factor(&self) -> usize {
+        self.factor
+    }
+
+    /// Cutoff frequency as a fraction of the input Nyquist frequency
+    pub fn get_cutoff_ratio(&self) -> f64 {
+        self.cutoff_ratio
+    }
+
+    /// Number of taps of the interpolation filter
+    pub fn get_filter_length(&self) -> usize {
+        self.filter_length
+    }
+
+    /// Delay of the output in output samples
+    pub fn latency_samples(&self) -> usize {
+        (self.filter_length - 1) / 2
+    }
+
+    fn default_filter_length(factor: usize) -> usize {
+        16 * factor + 1
+    }
+
+    fn validate_factor(factor: usize) -> Result<()> {
+        if factor < 2 {
+            anyhow::bail!("Interpolation factor must be at least 2, got {}", factor);
+        }
+        Ok(())
+    }
+
+    fn validate_cutoff_ratio(cutoff_ratio: f64) -> Result<()> {
+        if cutoff_ratio.is_nan() || cutoff_ratio <= 0.0 || cutoff_ratio > 1.0 {
+            anyhow::bail!("cutoff_ratio must be in (0, 1], got {}", cutoff_ratio);
+        }
+        Ok(())
+    }
+
+    fn validate_filter_length(filter_length: usize) -> Result<()> {
+        if filter_length < 3 || filter_length % 2 == 0 {
+            anyhow::bail!(
+                "filter_length must be odd and at least 3, got {}",
+                filter_length
+            );
+        }
+        Ok(())
+    }
+
+    /// Split the interpolation filter into its polyphase branches and restart the stream
+    fn design(&mut self) {
+        // Cutoff in cycles per output sample
+        let cutoff = self.cutoff_ratio * 0.5 / self.factor as f64;
+        let taps = lowpass_taps(self.filter_length, cutoff);
+        let branch_length = self.filter_length.div_ceil(self.factor);
+        self.phases = (0..self.factor)
+            .map(|phase| {
+                let mut branch: Vec<f32> = taps
+                    .iter()
+                    .skip(phase)
+                    .step_by(self.factor)
+                    .map(|tap| (tap * self.factor as f64) as f32)
+                    .collect();
+                branch.resize(branch_length, 0.0);
+                branch
+            })
+            .collect();
+        self.reset();
+    }
+
+    /// Output sample rate for an input sample rate
+    ///
+    /// ### Arguments
+    ///
+    /// * `sample_rate` - Input sample rate in Hz
+    ///
+    /// ### Returns
+    ///
+    /// The increased sample rate, or an error if it does not fit in a `u32`
+    fn output_sample_rate(&self, sample_rate: u32) -> Result<u32> {
+        sample_rate.checked_mul(self.factor as u32).ok_or_else(|| {
+            anyhow::anyhow!(
+                "InterpolationNode '{}': Sample rate {} Hz is too high to interpolate by {}",
+                self.id,
+                sample_rate,
+                self.factor
+            )
+        })
+    }
+
+    /// Upsample the next `samples` of a channel
+    ///
+    /// ### Arguments
+    ///
+    /// * `channel` - 0 for channel A or a single channel, 1 for channel B
+    /// * `samples` - Input samples
+    ///
+    /// ### Returns
+    ///
+    /// `factor` output samples per input sample
+    fn process_channel(&mut self, channel: usize, samples: &[f32]) -> Vec<f32> {
+        let delay = self.phases[0].len() - 1;
+
+        // Input sample n is at index n + delay
+        let mut buffer = std::mem::take(&mut self.history[channel]);
+        buffer.extend_from_slice(samples);
+
+        let mut output = Vec::with_capacity(samples.len() * self.factor);
+        for window in buffer.windows(delay + 1) {
+            for branch in &self.phases {
+                output.push(
+                    window
+                        .iter()
+                        .rev()
+                        .zip(branch)
+                        .map(|(sample, tap)| sample * tap)
+                        .sum(),
+                );
+            }
+        }
+
+        buffer.drain(..buffer.len() - delay);
+        self.history[channel] = buffer;
+        output
+    }
+}
+
+impl ProcessingNode for InterpolationNode {
+    fn process(&mut self, input: ProcessingData) -> Result<ProcessingData> {
+        match input {
+            ProcessingData::SingleChannel {
+                samples,
+                sample_rate,
+                timestamp,
+                frame_number,
+            } => Ok(ProcessingData::SingleChannel {
+                sample_rate: self.output_sample_rate(sample_rate)?,
+                samples: self.process_channel(0, &samples),
+                timestamp,
+                frame_number,
+            }),
+            ProcessingData::DualChannel {
+                channel_a,
+                channel_b,
+                sample_rate,
+                timestamp,
+                frame_number,
+            } => Ok(ProcessingData::DualChannel {
+                sample_rate: self.output_sample_rate(sample_rate)?,
+                channel_a: self.process_channel(0, &channel_a),
+                channel_b: self.process_channel(1, &channel_b),
+                timestamp,
+                frame_number,
+            }),
+            ProcessingData::AudioFrame(mut frame) => {
+                frame.sample_rate = self.output_sample_rate(frame.sample_rate)?;
+                frame.channel_a = self.process_channel(0, &frame.channel_a);
+                frame.channel_b = self.process_channel(1, &frame.channel_b);
+                Ok(ProcessingData::AudioFrame(frame))
+            }
+            ProcessingData::PhotoacousticResult { .. } => {
+                anyhow::bail!("InterpolationNode cannot process PhotoacousticResult data")
+            }
+        }
+    }
+
+    fn node_id(&self) -> &str {
+        &self.id
+    }
+
+    fn node_type(&self) -> &str {
+        "interpolation"
+    }
+
+    fn accepts_input(&self, input: &ProcessingData) -> bool {
+        matches!(
+            input,
+            ProcessingData::SingleChannel { .. }
+                | ProcessingData::DualChannel { .. }
+                | ProcessingData::AudioFrame(_)
+        )
+    }
+
+    fn output_type(&self, input: &ProcessingData) -> Option<String> {
+        match input {
+            ProcessingData::SingleChannel { .. } => Some("SingleChannel".to_string()),
+            ProcessingData::DualChannel { .. } => Some("DualChannel".to_string()),
+            ProcessingData::AudioFrame(_) => Some("AudioFrame".to_string()),
+            ProcessingData::PhotoacousticResult { .. } => None,
+        }
+    }
+
+    fn reset(&mut self) {
+        let delay = self.phases[0].len() - 1;
+        self.history = [vec![0.0; delay], vec![0.0; delay]];
+    }
+
+    fn clone_node(&self) -> Box<dyn ProcessingNode> {
+        Box::new(self.clone())
+    }
+
+    fn supports_hot_reload(&self) -> bool {
+        true
+    }
+
+    /// Update the interpolation parameters
+    ///
+    /// Supports `factor`, `cutoff_ratio` and `filter_length`. A new factor without
+    /// a `filter_length` uses the default length for that factor. The filter is
+    /// designed again and the stream restarts.
+    fn update_config(&mut self, parameters: &serde_json::Value) -> Result<bool> {
+        let factor = match parameters.get("factor").and_then(|v| v.as_u64()) {
+            Some(factor) => factor as usize,
+            None => self.factor,
+        };
+        let cutoff_ratio = parameters
+            .get("cutoff_ratio")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(self.cutoff_ratio);
+        let filter_length = match parameters.get("filter_length").and_then(|v| v.as_u64()) {
+            Some(filter_length) => filter_length as usize,
+            None if factor != self.factor => Self::default_filter_length(factor),
+            None => self.filter_length,
+        };
+
+        if factor == self.factor
+            && cutoff_ratio == self.cutoff_ratio
+            && filter_length == self.filter_length
+        {
+            return Ok(false);
+        }
+
+        Self::validate_factor(factor)?;
+        Self::validate_cutoff_ratio(cutoff_ratio)?;
+        Self::validate_filter_length(filter_length)?;
+        self.factor = factor;
+        self.cutoff_ratio = cutoff_ratio;
+        self.filter_length = filter_length;
+        self.design();
+        debug!(
+            "InterpolationNode '{}': Configuration updated (factor: {}, cutoff_ratio: {}, filter_length: {})",
+            self.id, self.factor, self.cutoff_ratio, self.filter_length
+        );
+        Ok(true)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
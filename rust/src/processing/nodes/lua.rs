@@ -0,0 +1,530 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Lua processing node implementation
+//!
+//! This module implements a processing node that executes a Lua script on each
+//! frame, using an interpreter embedded with [`mlua`]. It is a lighter
+//! alternative to the [`PythonNode`](super::PythonNode): the Lua 5.4
+//! interpreter is compiled into the binary, so the node needs no system
+//! runtime and builds statically. It is available with the `lua-node` feature.
+//!
+//! # Script Interface
+//!
+//! The script receives the same table as the dictionary passed to a Python
+//! script: a `type` field (`AudioFrame`, `SingleChannel`, `DualChannel` or
+//! `PhotoacousticResult`), the sample buffers as arrays (`channel_a`,
+//! `channel_b`, `samples` or `signal`), and `sample_rate`, `timestamp` and
+//! `frame_number` (or `metadata` for a photoacoustic result). It returns the
+//! table of the output frame.
+//!
+//! ```lua
+//! local gain = 2.0
+//!
+//! function initialize()
+//!     print("Lua processing node initialized")
+//! end
+//!
+//! function process_data(data)
+//!     for _, key in ipairs({"channel_a", "channel_b", "samples", "signal"}) do
+//!         local buffer = data[key]
+//!         if buffer then
+//!             for i = 1, #buffer do
+//!                 buffer[i] = buffer[i] * gain
+//!             end
+//!         end
+//!     end
+//!     return data
+//! end
+//! ```
+//!
+//! Unlike the Python node, the script is loaded once in an interpreter kept by
+//! the node: global variables persist from one frame to the next, until the
+//! script is reloaded or the node is reset. The `initialize` function, when
+//! defined, runs after each load.
+//!
+//! # Computed Values
+//!
+//! Before each call the node sets the `computing_state` global to a snapshot
+//! of the shared computing state of the graph, or `nil` when no state is
+//! attached. See
+//! [`ComputingSharedData::script_context`](crate::processing::computing_nodes::ComputingSharedData::script_context)
+//! for the schema; values without a result are `nil`.
+//!
+//! ```lua
+//! function process_data(data)
+//!     local concentration = computing_state and computing_state.concentration_ppm
+//!     if concentration and concentration > 1000.0 then
+//!         for i = 1, #data.samples do
+//!             data.samples[i] = 0.0
+//!         end
+//!     end
+//!     return data
+//! end
+//! ```
+//!
+//! # Timeouts
+//!
+//! A call running longer than `timeout_seconds` (`0` disables the limit) is
+//! interrupted by an instruction count hook. The frame fails, the graph drops
+//! it, and the timeout is counted in the `timeouts` field of the node
+//! statistics.
+//!
+//! # Configuration
+//!
+//! ```yaml
+//! - id: "lua_gain"
+//!   node_type: "lua"
+//!   parameters:
+//!     script_path: gain.lua
+//!     timeout_seconds: 5
+//! ```
+
+use anyhow::{anyhow, Result};
+use log::{debug, info, warn};
+use mlua::{Function, HookTriggers, Lua, LuaSerdeExt, SerializeOptions, Table, VmState};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::data::ProcessingData;
+use super::python::PythonNode;
+use super::traits::ProcessingNode;
+use crate::processing::computing_nodes::SharedComputingState;
+
+/// Number of Lua instructions between two checks of the call deadline
+const TIMEOUT_CHECK_INSTRUCTIONS: u32 = 10_000;
+
+/// Lua processing node configuration
+///
+/// # Example
+///
+/// ```rust
+/// use rust_photoacoustic::processing::nodes::LuaNodeConfig;
+/// use std::path::PathBuf;
+///
+/// let config = LuaNodeConfig {
+///     script_path: PathBuf::from("gain.lua"),
+///     timeout_seconds: 5,
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(config.process_function, "process_data");
+/// assert_eq!(config.init_function, "initialize");
+/// ```
+#[derive(Debug, Clone)]
+pub struct LuaNodeConfig {
+    /// Path to the Lua script file
+    pub script_path: PathBuf,
+    /// Function name to call for processing (default: "process_data")
+    pub process_function: String,
+    /// Optional function called after the script is loaded (default: "initialize")
+    pub init_function: String,
+    /// Maximum execution time for Lua calls (seconds), `0` disables the limit
+    pub timeout_seconds: u64,
+}
+
+impl Default for LuaNodeConfig {
+    fn default() -> Self {
+        Self {
+            script_path: PathBuf::from("processor.lua"),
+            process_function: "process_data".to_string(),
+            init_function: "initialize".to_string(),
+            timeout_seconds: 30,
+        }
+    }
+}
+
+/// Processing node running a Lua script on each frame
+///
+/// The script is loaded on the first frame, or by [`LuaNode::reload`]. See
+/// the [module documentation](self) for the script interface.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rust_photoacoustic::processing::nodes::{
+///     LuaNode, LuaNodeConfig, ProcessingData, ProcessingNode,
+/// };
+/// use std::path::PathBuf;
+///
+/// # fn example() -> anyhow::Result<()> {
+/// let mut node = LuaNode::new(
+///     "lua_gain".to_string(),
+///     LuaNodeConfig {
+///         script_path: PathBuf::from("gain.lua"),
+///         ..Default::default()
+///     },
+/// );
+///
+/// let output = node.process(ProcessingData::SingleChannel {
+///     samples: vec![0.1, 0.2, 0.3],
+///     sample_rate: 48000,
+///     timestamp: 0,
+///     frame_number: 1,
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct LuaNode {
+    id: String,
+    config: LuaNodeConfig,
+    /// Interpreter with the script loaded, `None` until the first frame
+    lua: Option<Lua>,
+    shared_computing_state: Option<SharedComputingState>,
+    /// Last snapshot of the shared computing state given to the script
+    computing_state_snapshot: Value,
+    timeouts: u64,
+}
+
+impl std::fmt::Debug for LuaNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LuaNode")
+            .field("id", &self.id)
+            .field("config", &self.config)
+            .field("loaded", &self.lua.is_some())
+            .finish()
+    }
+}
+
+impl LuaNode {
+    /// Create a new Lua processing node
+    ///
+    /// ### Parameters
+    ///
+    /// * `id` - Unique identifier for this node
+    /// * `config` - Script and functions to run
+    pub fn new(id: String, config: LuaNodeConfig) -> Self {
+        Self {
+            id,
+            config,
+            lua: None,
+            shared_computing_state: None,
+            computing_state_snapshot: Value::Null,
+            timeouts: 0,
+        }
+    }
+
+    /// Create a Lua node from the parameters of its graph configuration
+    ///
+    /// ### Parameters
+    ///
+    /// * `id` - Unique identifier for this node
+    /// * `config` - `script_path` (required), `process_function`,
+    ///   `init_function` and `timeout_seconds`
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if `script_path` is missing
+    pub fn from_config(id: String, config: HashMap<String, Value>) -> Result<Self> {
+        let mut node_config = LuaNodeConfig {
+            script_path: config
+                .get("script_path")
+                .and_then(|v| v.as_str())
+                .map(PathBuf::from)
+                .ok_or_else(|| anyhow!("Lua node requires script_path parameter"))?,
+            ..Default::default()
+        };
+
+        if let Some(process_function) = config.get("process_function").and_then(|v| v.as_str()) {
+            node_config.process_function = process_function.to_string();
+        }
+
+        if let Some(init_function) = config.get("init_function").and_then(|v| v.as_str()) {
+            node_config.init_function = init_function.to_string();
+        }
+
+        if let Some(timeout_seconds) = config.get("timeout_seconds").and_then(|v| v.as_u64()) {
+            node_config.timeout_seconds = timeout_seconds;
+        }
+
+        Ok(Self::new(id, node_config))
+    }
+
+    /// Get the current configuration
+    pub fn config(&self) -> &LuaNodeConfig {
+        &self.config
+    }
+
+    /// Number of script calls interrupted by the timeout since the node was created
+    pub fn timeout_count(&self) -> u64 {
+        self.timeouts
+    }
+
+    /// Load the script again in a new interpreter
+    ///
+    /// The new script must load, define the processing function and pass its
+    /// `initialize` function; otherwise the previous interpreter, with its
+    /// global variables, keeps running.
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if the script cannot be read, loaded or initialized
+    pub fn reload(&mut self) -> Result<()> {
+        let lua = self.load_script()?;
+        self.lua = Some(lua);
+        info!(
+            "Lua node '{}' reloaded {:?}",
+            self.id, self.config.script_path
+        );
+        Ok(())
+    }
+
+    /// Load the script in a new interpreter and run its `initialize` function
+    fn load_script(&mut self) -> Result<Lua> {
+        let code = std::fs::read_to_string(&self.config.script_path).map_err(|e| {
+            anyhow!(
+                "Failed to read Lua script {:?}: {}",
+                self.config.script_path,
+                e
+            )
+        })?;
+
+        let lua = Lua::new();
+        lua.load(code.as_str())
+            .set_name(format!("@{}", self.config.script_path.display()))
+            .exec()
+            .map_err(|e| {
+                anyhow!(
+                    "Failed to load Lua script {:?}: {}",
+                    self.config.script_path,
+                    e
+                )
+            })?;
+
+        lua.globals()
+            .get::<Function>(self.config.process_function.as_str())
+            .map_err(|_| {
+                anyhow!(
+                    "Function '{}' not found in Lua script {:?}",
+                    self.config.process_function,
+                    self.config.script_path
+                )
+            })?;
+
+        // The initialization function is optional
+        let init_function = self.config.init_function.clone();
+        if let Ok(initialize) = lua.globals().get::<Function>(init_function.as_str()) {
+            self.call_with_timeout::<()>(&lua, &init_function, initialize, ())?;
+        }
+
+        debug!(
+            "Lua node '{}' loaded {:?}",
+            self.id, self.config.script_path
+        );
+        Ok(lua)
+    }
+
+    /// Call `function`, interrupted when it runs longer than the timeout
+    fn call_with_timeout<R: mlua::FromLuaMulti>(
+        &mut self,
+        lua: &Lua,
+        function_name: &str,
+        function: Function,
+        args: impl mlua::IntoLuaMulti,
+    ) -> Result<R> {
+        let timeout = Duration::from_secs(self.config.timeout_seconds);
+        let timed_out = Arc::new(AtomicBool::new(false));
+        if !timeout.is_zero() {
+            let deadline = Instant::now() + timeout;
+            let timed_out = Arc::clone(&timed_out);
+            lua.set_hook(
+                HookTriggers::new().every_nth_instruction(TIMEOUT_CHECK_INSTRUCTIONS),
+                move |_, _| {
+                    if Instant::now() < deadline {
+                        return Ok(VmState::Continue);
+                    }
+                    timed_out.store(true, Ordering::Relaxed);
+                    Err(mlua::Error::runtime("execution timeout"))
+                },
+            );
+        }
+
+        let result = function.call::<R>(args);
+        lua.remove_hook();
+
+        if timed_out.load(Ordering::Relaxed) {
+            self.timeouts += 1;
+            warn!(
+                "Lua node '{}': function '{}' interrupted, exceeding its timeout of {} seconds",
+                self.id, function_name, self.config.timeout_seconds
+            );
+            return Err(anyhow!(
+                "Lua function '{}' interrupted after exceeding its timeout of {} seconds",
+                function_name,
+                self.config.timeout_seconds
+            ));
+        }
+        result.map_err(|e| anyhow!("Lua function '{}' failed: {}", function_name, e))
+    }
+
+    /// Run the processing function of the loaded script on `input`
+    fn call_process_function(
+        &mut self,
+        lua: &Lua,
+        input: &ProcessingData,
+    ) -> Result<ProcessingData> {
+        let computing_state = lua.to_value_with(self.computing_state_context(), Self::options())?;
+        lua.globals().set("computing_state", computing_state)?;
+
+        let function_name = self.config.process_function.clone();
+        let function = lua.globals().get::<Function>(function_name.as_str())?;
+        let data = Self::data_to_table(lua, input)?;
+        let output: Table = self.call_with_timeout(lua, &function_name, function, data)?;
+        Self::table_to_data(lua, output)
+    }
+
+    /// Snapshot of the shared computing state exposed to the script
+    ///
+    /// `Null` when the node is not attached to a graph. The processing thread
+    /// does not wait for the lock: while a computing node is writing the state,
+    /// the script gets the previous snapshot.
+    fn computing_state_context(&mut self) -> &Value {
+        if let Some(state) = self
+            .shared_computing_state
+            .as_ref()
+            .and_then(|shared_state| shared_state.try_read().ok())
+        {
+            self.computing_state_snapshot = state.script_context();
+        }
+        &self.computing_state_snapshot
+    }
+
+    /// Table passed to the processing function
+    fn data_to_table(lua: &Lua, input: &ProcessingData) -> mlua::Result<Table> {
+        let header =
+            lua.to_value_with(&PythonNode::processing_data_header(input), Self::options())?;
+        let mlua::Value::Table(table) = header else {
+            return Err(mlua::Error::runtime("frame header is not a table"));
+        };
+        for (key, samples) in PythonNode::sample_buffers(input) {
+            table.set(key, lua.create_sequence_from(samples.iter().copied())?)?;
+        }
+        Ok(table)
+    }
+
+    /// Convert the table returned by the processing function to a frame
+    fn table_to_data(lua: &Lua, table: Table) -> Result<ProcessingData> {
+        // Buffers are read directly, the remaining fields through JSON
+        let mut arrays = HashMap::new();
+        for key in ["samples", "channel_a", "channel_b", "signal"] {
+            if let Some(samples) = table.get::<Option<Vec<f32>>>(key)? {
+                arrays.insert(key.to_string(), samples);
+                table.set(key, mlua::Value::Nil)?;
+            }
+        }
+        let json: Value = lua.from_value(mlua::Value::Table(table))?;
+        PythonNode::json_to_processing_data_with_arrays(json, arrays)
+    }
+
+    /// `null` values become `nil`, so that scripts test them with `if value then`
+    fn options() -> SerializeOptions {
+        SerializeOptions::new()
+            .serialize_none_to_null(false)
+            .serialize_unit_to_null(false)
+    }
+}
+
+impl ProcessingNode for LuaNode {
+    fn process(&mut self, input: ProcessingData) -> Result<ProcessingData> {
+        let lua = match self.lua.take() {
+            Some(lua) => lua,
+            None => self.load_script()?,
+        };
+
+        // The interpreter is kept even when the call fails
+        let result = self.call_process_function(&lua, &input);
+        self.lua = Some(lua);
+
+        let output = result?;
+        debug!(
+            "Lua node '{}' processed {} data",
+            self.id,
+            PythonNode::data_type_name(&input)
+        );
+        Ok(output)
+    }
+
+    fn node_id(&self) -> &str {
+        &self.id
+    }
+
+    fn node_type(&self) -> &str {
+        "lua"
+    }
+
+    fn accepts_input(&self, _input: &ProcessingData) -> bool {
+        true
+    }
+
+    fn output_type(&self, input: &ProcessingData) -> Option<String> {
+        Some(PythonNode::data_type_name(input).to_string())
+    }
+
+    fn reset(&mut self) {
+        // The script is loaded again, with fresh globals, on the next frame
+        self.lua = None;
+        debug!("Lua node '{}' reset", self.id);
+    }
+
+    fn clone_node(&self) -> Box<dyn ProcessingNode> {
+        let mut node = LuaNode::new(self.id.clone(), self.config.clone());
+        node.shared_computing_state = self.shared_computing_state.clone();
+        Box::new(node)
+    }
+
+    fn set_shared_computing_state(&mut self, shared_state: Option<SharedComputingState>) {
+        self.shared_computing_state = shared_state;
+        self.computing_state_snapshot = Value::Null;
+    }
+
+    fn get_shared_computing_state(&self) -> Option<SharedComputingState> {
+        self.shared_computing_state.clone()
+    }
+
+    fn timeout_count(&self) -> u64 {
+        self.timeouts
+    }
+
+    fn supports_hot_reload(&self) -> bool {
+        true // The script can be replaced in place
+    }
+
+    fn update_config(&mut self, parameters: &Value) -> Result<bool> {
+        let Value::Object(params) = parameters else {
+            anyhow::bail!("Lua node parameters must be an object");
+        };
+
+        if let Some(timeout_seconds) = params.get("timeout_seconds") {
+            self.config.timeout_seconds = timeout_seconds
+                .as_u64()
+                .ok_or_else(|| anyhow!("timeout_seconds parameter must be an integer"))?;
+        }
+
+        // Setting script_path, even to its current value, reloads the script
+        if let Some(script_path) = params.get("script_path") {
+            let script_path = script_path
+                .as_str()
+                .ok_or_else(|| anyhow!("script_path parameter must be a string"))?;
+            let previous = std::mem::replace(&mut self.config.script_path, script_path.into());
+            if let Err(e) = self.reload() {
+                self.config.script_path = previous;
+                return Err(e);
+            }
+        }
+
+        // Changing the functions requires reconstruction
+        let requires_reconstruction = params
+            .keys()
+            .any(|key| !matches!(key.as_str(), "timeout_seconds" | "script_path"));
+        Ok(!requires_reconstruction)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
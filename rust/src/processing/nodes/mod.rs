@@ -15,7 +15,15 @@
 //! - [`filter`] - Filter nodes (`FilterNode`, `ChannelTarget`)
 //! - [`channel`] - Channel operation nodes (`ChannelSelectorNode`, `ChannelMixerNode`, `MixStrategy`)
 //! - [`differential`] - Differential calculation nodes (`DifferentialNode`)
+//! - [`adaptive_filter`] - Adaptive noise-cancellation nodes (`AdaptiveFilterNode`)
+//! - [`spectral_subtraction`] - Spectral subtraction denoising nodes (`SpectralSubtractionNode`)
+//! - [`decimation`] - Anti-aliased downsampling nodes (`DecimationNode`)
+//! - [`interpolation`] - Polyphase upsampling nodes (`InterpolationNode`)
+//! - `lua` - Lua scripting node (`LuaNode`), with the `lua-node` feature
+//! - `wasm` - Sandboxed WebAssembly plugin node (`WasmNode`), with the `wasm-node` feature
+//! - `plugin` - Native shared library plugin node (`PluginNode`), with the `plugin-node` feature
 //! - [`output`] - Output nodes (`PhotoacousticOutputNode`)
+//! - [`detection`] - Debounced detection state machine of the output node (`DetectionStateMachine`)
 //! - [`record`] - Recording nodes (`RecordNode`)
 //! - [`streaming`] - Real-time streaming nodes (`StreamingNode`)
 //! - [`streaming_registry`] - Centralized registry for managing streaming nodes (`StreamingNodeRegistry`)
@@ -47,29 +55,51 @@
 //! assert!(result.is_ok());
 //! ```
 
+pub mod adaptive_filter;
 pub mod channel;
 pub mod data;
+pub mod decimation;
+pub mod detection;
 pub mod differential;
 pub mod filter;
 pub mod gain;
 pub mod input;
+pub mod interpolation;
+#[cfg(feature = "lua-node")]
+pub mod lua;
 pub mod output;
+#[cfg(feature = "plugin-node")]
+pub mod plugin;
 pub mod python;
 pub mod record;
+pub mod spectral_subtraction;
 pub mod streaming;
 pub mod streaming_registry;
 pub mod traits;
+#[cfg(feature = "wasm-node")]
+pub mod wasm;
 
 // Re-export all public types for backward compatibility
+pub use adaptive_filter::AdaptiveFilterNode;
 pub use channel::{ChannelMixerNode, ChannelSelectorNode, MixStrategy};
 pub use data::{NodeId, ProcessingData, ProcessingMetadata};
+pub use decimation::DecimationNode;
+pub use detection::{DetectionEvent, DetectionEventKind, DetectionState, DetectionStateMachine};
 pub use differential::DifferentialNode;
 pub use filter::{ChannelTarget, FilterNode};
 pub use gain::GainNode;
 pub use input::InputNode;
+pub use interpolation::InterpolationNode;
+#[cfg(feature = "lua-node")]
+pub use lua::{LuaNode, LuaNodeConfig};
 pub use output::PhotoacousticOutputNode;
-pub use python::{PythonNode, PythonNodeConfig};
+#[cfg(feature = "plugin-node")]
+pub use plugin::{PluginNode, PluginNodeConfig, PLUGIN_ABI_VERSION};
+pub use python::{PythonCallTimeout, PythonNode, PythonNodeConfig, PythonTimeoutPolicy};
 pub use record::RecordNode;
+pub use spectral_subtraction::SpectralSubtractionNode;
 pub use streaming::StreamingNode;
 pub use streaming_registry::StreamingNodeRegistry;
 pub use traits::ProcessingNode;
+#[cfg(feature = "wasm-node")]
+pub use wasm::{WasmNode, WasmNodeConfig};
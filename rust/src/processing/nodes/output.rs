@@ -11,10 +11,12 @@
 //! ## Features
 //!
 //! - Signal amplitude analysis (peak and RMS)
-//! - Detection threshold comparison
+//! - Debounced detection with hysteresis and dwell times, emitting onset and release events
+//! - Optional SNR gate rejecting loud broadband noise
 //! - Processing metadata generation
 //! - Configurable detection thresholds and analysis windows
 //! - Converts processed signals to photoacoustic results
+//! - Publishes the detection state and events to the shared computing state, for action nodes
 //!
 //! ## Examples
 //!
@@ -36,8 +38,17 @@
 //! # Ok::<(), anyhow::Error>(())
 //! ```
 
+use super::detection::{DetectionEvent, DetectionEventKind, DetectionStateMachine};
 use super::{ProcessingData, ProcessingMetadata, ProcessingNode};
+use crate::processing::computing_nodes::{DetectionResult, SharedComputingState};
+use crate::utility::noise_stats::peak_to_noise_floor_db;
 use anyhow::Result;
+use log::{debug, info, warn};
+use std::collections::VecDeque;
+use std::time::SystemTime;
+
+/// Number of recent detection events kept by the node and published to the shared state
+const MAX_RECENT_EVENTS: usize = 32;
 
 /// Photoacoustic output node that converts processed signal to final photoacoustic result
 ///
@@ -54,16 +65,43 @@ use anyhow::Result;
 ///
 /// The node performs several analysis operations:
 /// - Signal amplitude analysis (peak and RMS)
-/// - Detection threshold comparison
+/// - Detection of the peak amplitude with a [`DetectionStateMachine`]
+/// - Optional peak-to-noise-floor ratio (SNR) gate
 /// - Basic signal characterization
 /// - Processing metadata generation
 ///
+/// ### Detection
+///
+/// The peak amplitude of each frame feeds a [`DetectionStateMachine`]. A detection
+/// starts once the amplitude stays above the detection threshold for the detection
+/// dwell time, and ends once it stays at or below the release threshold for the
+/// release dwell time. Each start and end is a [`DetectionEvent`]: the processing
+/// steps of the frame get `detection_onset` or `detection_release`, and every frame
+/// of a detection gets `detection_confirmed`. With a shared computing state, the
+/// state and the recent events are published as a [`DetectionResult`] under the
+/// node ID, where action nodes monitoring this node pick them up. When the shared
+/// state is locked, the publication is retried at the following frames with all
+/// the events since the last one, up to the 32 most recent events.
+///
+/// ### SNR Gate
+///
+/// A loud signal is not always a photoacoustic signal: broadband noise of high
+/// amplitude crosses the detection threshold as well. With an SNR threshold, the
+/// node measures the ratio of the spectral peak to the noise floor over the last
+/// `analysis_window_size` samples of each frame (see
+/// [`peak_to_noise_floor_db`]), and reports it in the `snr_db` field of the
+/// metadata. Frames below the SNR threshold feed a zero level to the detection,
+/// and get the `snr_gated` processing step when their amplitude is above the
+/// detection threshold. Broadband noise measures about 10 dB whatever its level,
+/// so thresholds around 20 dB keep only the frames dominated by a tone.
+///
 /// ### Configuration
 ///
 /// The node can be configured with:
-/// - Detection threshold for signal presence
+/// - Detection and release thresholds for signal presence
+/// - Detection and release dwell times
+/// - SNR threshold of the detection gate
 /// - Analysis window size for signal processing
-/// - Custom analysis parameters
 ///
 /// ### Examples
 ///
@@ -120,10 +158,18 @@ use anyhow::Result;
 /// ```
 pub struct PhotoacousticOutputNode {
     id: String,
-    /// Minimum signal threshold for detection
-    detection_threshold: f32,
+    /// Debounced detection of the peak amplitude
+    detector: DetectionStateMachine,
     /// Signal analysis window size (samples)
     analysis_window_size: usize,
+    /// Minimum peak-to-noise-floor ratio in dB for a detection, `None` to disable the gate
+    snr_threshold_db: Option<f32>,
+    /// Most recent detection events, oldest first
+    recent_events: VecDeque<DetectionEvent>,
+    /// Shared computing state the detection is published to
+    shared_computing_state: Option<SharedComputingState>,
+    /// The detection changed since it was last published
+    publication_pending: bool,
 }
 
 impl PhotoacousticOutputNode {
@@ -135,7 +181,9 @@ impl PhotoacousticOutputNode {
     ///
     /// ### Default Settings
     ///
-    /// - Detection threshold: 0.01 (1%)
+    /// - Detection and release thresholds: 0.01 (1%)
+    /// - Detection and release dwell times: 0 ms
+    /// - SNR gate: disabled
     /// - Analysis window size: 1024 samples
     ///
     /// ### Examples
@@ -147,17 +195,36 @@ impl PhotoacousticOutputNode {
     /// assert_eq!(output_node.node_id(), "output");
     /// ```
     pub fn new(id: String) -> Self {
+        Self::new_with_shared_state(id, None)
+    }
+
+    /// Create a new photoacoustic output node publishing its detections
+    ///
+    /// ### Arguments
+    ///
+    /// * `id` - Unique identifier for this node
+    /// * `shared_state` - Shared computing state the detection state and events are
+    ///   published to
+    pub fn new_with_shared_state(id: String, shared_state: Option<SharedComputingState>) -> Self {
         Self {
             id,
-            detection_threshold: 0.01,  // Default threshold
+            detector: DetectionStateMachine::new(0.01, 0.01, 0, 0) // Default threshold
+                .expect("default detection thresholds are valid"),
             analysis_window_size: 1024, // Default window size
+            snr_threshold_db: None,
+            recent_events: VecDeque::with_capacity(MAX_RECENT_EVENTS),
+            shared_computing_state: shared_state,
+            publication_pending: false,
         }
     }
 
     /// Set the detection threshold for signal presence
     ///
     /// The detection threshold is used to determine whether a significant
-    /// photoacoustic signal is present in the processed audio data.
+    /// photoacoustic signal is present in the processed audio data. It sets both
+    /// the detection and the release thresholds and keeps the dwell times; use
+    /// [`with_detection`](Self::with_detection) for hysteresis. Non-finite
+    /// thresholds are ignored.
     ///
     /// ### Arguments
     ///
@@ -172,7 +239,36 @@ impl PhotoacousticOutputNode {
     ///     .with_detection_threshold(0.05); // 5% threshold
     /// ```
     pub fn with_detection_threshold(mut self, threshold: f32) -> Self {
-        self.detection_threshold = threshold;
+        match DetectionStateMachine::new(
+            threshold,
+            threshold,
+            self.detector.detection_dwell_ms(),
+            self.detector.release_dwell_ms(),
+        ) {
+            Ok(detector) => self.detector = detector,
+            Err(e) => warn!("PhotoacousticOutputNode '{}': {}", self.id, e),
+        }
+        self
+    }
+
+    /// Set the detection state machine
+    ///
+    /// ### Arguments
+    ///
+    /// * `detector` - State machine with the thresholds and dwell times
+    ///
+    /// ### Examples
+    ///
+    /// ```no_run
+    /// use rust_photoacoustic::processing::nodes::{DetectionStateMachine, PhotoacousticOutputNode};
+    ///
+    /// // Detect above 5% for 500 ms, release at or below 4% for 1 s
+    /// let node = PhotoacousticOutputNode::new("output".to_string())
+    ///     .with_detection(DetectionStateMachine::new(0.05, 0.04, 500, 1000)?);
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn with_detection(mut self, detector: DetectionStateMachine) -> Self {
+        self.detector = detector;
         self
     }
 
@@ -198,8 +294,59 @@ impl PhotoacousticOutputNode {
         self
     }
 
+    /// Set the SNR threshold of the detection gate
+    ///
+    /// Frames whose peak-to-noise-floor ratio is below the threshold are not
+    /// detected, whatever their amplitude. `None` disables the gate and the SNR
+    /// measurement. Non-finite thresholds are ignored.
+    ///
+    /// ### Arguments
+    ///
+    /// * `threshold_db` - Minimum peak-to-noise-floor ratio in dB
+    ///
+    /// ### Examples
+    ///
+    /// ```no_run
+    /// use rust_photoacoustic::processing::PhotoacousticOutputNode;
+    ///
+    /// let node = PhotoacousticOutputNode::new("output".to_string())
+    ///     .with_detection_threshold(0.05)
+    ///     .with_snr_threshold_db(Some(20.0)); // Tone at least 20 dB above the noise floor
+    /// ```
+    pub fn with_snr_threshold_db(mut self, threshold_db: Option<f32>) -> Self {
+        match threshold_db {
+            Some(threshold) if !threshold.is_finite() => warn!(
+                "PhotoacousticOutputNode '{}': SNR threshold must be a finite number",
+                self.id
+            ),
+            _ => self.snr_threshold_db = threshold_db,
+        }
+        self
+    }
+
+    /// Get the SNR threshold of the detection gate in dB, `None` when disabled
+    pub fn get_snr_threshold_db(&self) -> Option<f32> {
+        self.snr_threshold_db
+    }
+
+    /// Get the detection state machine
+    pub fn get_detection(&self) -> &DetectionStateMachine {
+        &self.detector
+    }
+
+    /// Get the most recent detection events, oldest first
+    pub fn get_recent_events(&self) -> Vec<DetectionEvent> {
+        self.recent_events.iter().cloned().collect()
+    }
+
     /// Perform basic photoacoustic analysis on the signal
-    fn analyze_signal(&self, signal: &[f32], sample_rate: u32) -> ProcessingMetadata {
+    fn analyze_signal(
+        &mut self,
+        signal: &[f32],
+        sample_rate: u32,
+        timestamp: u64,
+        frame_number: u64,
+    ) -> ProcessingMetadata {
         let mut processing_steps = Vec::new();
         processing_steps.push("photoacoustic_analysis".to_string());
 
@@ -207,12 +354,65 @@ impl PhotoacousticOutputNode {
         let max_amplitude = signal.iter().fold(0.0f32, |acc, &x| acc.max(x.abs()));
         let rms = (signal.iter().map(|&x| x * x).sum::<f32>() / signal.len() as f32).sqrt();
 
-        // Simple detection logic
-        let is_detection = max_amplitude > self.detection_threshold;
+        // SNR gate: broadband noise does not count as a detection, however loud
+        let snr_db = self.snr_threshold_db.map(|threshold| {
+            let window = &signal[signal.len().saturating_sub(self.analysis_window_size)..];
+            (peak_to_noise_floor_db(window), threshold)
+        });
+        let level = match snr_db {
+            Some((snr, threshold)) if snr < threshold => {
+                if max_amplitude > self.detector.detection_threshold() {
+                    processing_steps.push("snr_gated".to_string());
+                    debug!(
+                        "PhotoacousticOutputNode '{}': Frame {} gated (level {:.4}, SNR {:.1} dB < {:.1} dB)",
+                        self.id, frame_number, max_amplitude, snr, threshold
+                    );
+                }
+                0.0
+            }
+            _ => max_amplitude,
+        };
 
-        if is_detection {
+        // Debounced detection logic
+        let previous_state = self.detector.state();
+        let event = self.detector.update(level, timestamp, frame_number);
+
+        if let Some(event) = event {
+            let step = match event.kind {
+                DetectionEventKind::Onset => "detection_onset",
+                DetectionEventKind::Release => "detection_release",
+            };
+            processing_steps.push(step.to_string());
+            info!(
+                "PhotoacousticOutputNode '{}': Detection {} at frame {} (level {:.4}, after {} ms)",
+                self.id,
+                event.kind.as_str(),
+                frame_number,
+                event.level,
+                event.duration_ms
+            );
+            if self.recent_events.len() == MAX_RECENT_EVENTS {
+                self.recent_events.pop_front();
+            }
+            self.recent_events.push_back(event);
+        }
+        if self.detector.state().is_detected() {
             processing_steps.push("detection_confirmed".to_string());
         }
+        if self.detector.state() != previous_state {
+            debug!(
+                "PhotoacousticOutputNode '{}': {} -> {} (level {:.4}, rms {:.4})",
+                self.id,
+                previous_state.as_str(),
+                self.detector.state().as_str(),
+                max_amplitude,
+                rms
+            );
+            self.publication_pending = true;
+        }
+        if self.publication_pending {
+            self.publication_pending = !self.publish_detection();
+        }
 
         ProcessingMetadata {
             original_frame_number: 0, // Will be set by caller
@@ -220,6 +420,41 @@ impl PhotoacousticOutputNode {
             sample_rate,
             processing_steps,
             processing_latency_us: 0, // Will be calculated by caller
+            snr_db: snr_db.map(|(snr, _)| snr),
+        }
+    }
+
+    /// Publish the detection state and the recent events to the shared computing state
+    ///
+    /// The processing thread never waits for the lock: when the shared state is
+    /// busy, the caller retries at the next frame.
+    ///
+    /// ### Returns
+    ///
+    /// `false` if the shared state was locked and the detection not published
+    fn publish_detection(&self) -> bool {
+        let Some(shared_state) = &self.shared_computing_state else {
+            return true;
+        };
+        match shared_state.try_write() {
+            Ok(mut state) => {
+                state.update_detection_result(
+                    self.id.clone(),
+                    DetectionResult {
+                        state: self.detector.state(),
+                        events: self.get_recent_events(),
+                        timestamp: SystemTime::now(),
+                    },
+                );
+                true
+            }
+            Err(_) => {
+                debug!(
+                    "PhotoacousticOutputNode '{}': Shared computing state busy, detection published at the next frame",
+                    self.id
+                );
+                false
+            }
         }
     }
 }
@@ -234,7 +469,8 @@ impl ProcessingNode for PhotoacousticOutputNode {
                 frame_number,
             } => {
                 // Perform photoacoustic analysis
-                let mut metadata = self.analyze_signal(&samples, sample_rate);
+                let mut metadata =
+                    self.analyze_signal(&samples, sample_rate, timestamp, frame_number);
                 metadata.original_frame_number = frame_number;
                 metadata.original_timestamp = timestamp;
 
@@ -275,19 +511,115 @@ impl ProcessingNode for PhotoacousticOutputNode {
     }
 
     fn reset(&mut self) {
-        // No state to reset
+        self.detector.reset();
+        self.recent_events.clear();
+        self.publication_pending = false;
     }
 
     fn clone_node(&self) -> Box<dyn ProcessingNode> {
+        let mut detector = self.detector.clone();
+        detector.reset();
         Box::new(
-            PhotoacousticOutputNode::new(self.id.clone())
-                .with_detection_threshold(self.detection_threshold)
-                .with_analysis_window_size(self.analysis_window_size),
+            PhotoacousticOutputNode::new_with_shared_state(
+                self.id.clone(),
+                self.shared_computing_state.clone(),
+            )
+            .with_detection(detector)
+            .with_snr_threshold_db(self.snr_threshold_db)
+            .with_analysis_window_size(self.analysis_window_size),
         )
     }
 
     fn supports_hot_reload(&self) -> bool {
-        false // PhotoacousticOutputNode doesn't implement hot-reload yet
+        true
+    }
+
+    /// Update the detection and analysis parameters
+    ///
+    /// Supports `detection_threshold`, `release_threshold`, `detection_dwell_ms`,
+    /// `release_dwell_ms`, `snr_threshold_db` and `analysis_window_size`. A new
+    /// `detection_threshold` without a `release_threshold` sets both. A new
+    /// detection configuration restarts the state machine from the idle state. A
+    /// null `snr_threshold_db` disables the SNR gate.
+    fn update_config(&mut self, parameters: &serde_json::Value) -> Result<bool> {
+        let mut updated = false;
+
+        // Validated before any change, so that an invalid update leaves the node unchanged
+        let snr_threshold_db = match parameters.get("snr_threshold_db") {
+            None => self.snr_threshold_db,
+            Some(serde_json::Value::Null) => None,
+            Some(value) => Some(
+                value
+                    .as_f64()
+                    .map(|v| v as f32)
+                    .filter(|v| v.is_finite())
+                    .ok_or_else(|| anyhow::anyhow!("snr_threshold_db must be a number"))?,
+            ),
+        };
+
+        let detection_threshold = parameters
+            .get("detection_threshold")
+            .and_then(|v| v.as_f64())
+            .map(|v| v as f32);
+        let release_threshold = parameters
+            .get("release_threshold")
+            .and_then(|v| v.as_f64())
+            .map(|v| v as f32)
+            .or(detection_threshold)
+            .unwrap_or(self.detector.release_threshold());
+        let detection_dwell_ms = parameters
+            .get("detection_dwell_ms")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(self.detector.detection_dwell_ms());
+        let release_dwell_ms = parameters
+            .get("release_dwell_ms")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(self.detector.release_dwell_ms());
+        let detection_threshold =
+            detection_threshold.unwrap_or(self.detector.detection_threshold());
+
+        if detection_threshold != self.detector.detection_threshold()
+            || release_threshold != self.detector.release_threshold()
+            || detection_dwell_ms != self.detector.detection_dwell_ms()
+            || release_dwell_ms != self.detector.release_dwell_ms()
+        {
+            let mut detector = DetectionStateMachine::new(
+                detection_threshold,
+                release_threshold,
+                detection_dwell_ms,
+                release_dwell_ms,
+            )?;
+            // Keep the event numbering, so that action nodes see the next events as new
+            detector.continue_sequence_from(&self.detector);
+            self.detector = detector;
+            self.publish_detection();
+            updated = true;
+        }
+
+        if snr_threshold_db != self.snr_threshold_db {
+            self.snr_threshold_db = snr_threshold_db;
+            updated = true;
+        }
+
+        if let Some(window_size) = parameters
+            .get("analysis_window_size")
+            .and_then(|v| v.as_u64())
+        {
+            if window_size as usize != self.analysis_window_size {
+                self.analysis_window_size = window_size as usize;
+                updated = true;
+            }
+        }
+
+        Ok(updated)
+    }
+
+    fn set_shared_computing_state(&mut self, shared_state: Option<SharedComputingState>) {
+        self.shared_computing_state = shared_state;
+    }
+
+    fn get_shared_computing_state(&self) -> Option<SharedComputingState> {
+        self.shared_computing_state.clone()
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
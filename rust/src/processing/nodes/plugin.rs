@@ -0,0 +1,537 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Native plugin node implementation
+//!
+//! This module implements a processing node delegating the processing to a
+//! shared library (`.so`, `.dll` or `.dylib`) loaded at runtime, so that
+//! existing DSP written in C, C++ or Rust can be used in the graph without
+//! being rewritten as a node. It is available with the `plugin-node` feature.
+//!
+//! Unlike the WebAssembly node, a native plugin runs in the process with no
+//! sandbox: a faulty library can crash the daemon. Only load trusted plugins.
+//!
+//! # C ABI
+//!
+//! The library exports the following functions, version
+//! [`PLUGIN_ABI_VERSION`] of the ABI:
+//!
+//! ```c
+//! #include <stddef.h>
+//! #include <stdint.h>
+//!
+//! /* Must return the ABI version the plugin implements (1) */
+//! uint32_t abi_version(void);
+//!
+//! /* Create an instance from its JSON configuration, NULL on failure */
+//! void *create(const char *config_json);
+//!
+//! /* Process a frame of `len` samples per channel, writing at most `len`
+//!  * samples to `out` and their number to `out_len`.
+//!  * Returns 0 on success, or a plugin specific error code. */
+//! int32_t process(void *instance, const float *channel_a, const float *channel_b,
+//!                 size_t len, float *out, size_t *out_len);
+//!
+//! /* Apply a new JSON configuration.
+//!  * Returns 0 when applied, 1 when the instance must be created again,
+//!  * or a negative error code. */
+//! int32_t update_config(void *instance, const char *config_json);
+//!
+//! /* Release an instance */
+//! void destroy(void *instance);
+//! ```
+//!
+//! The configuration is the `config` parameter of the node, serialized as a
+//! NUL-terminated UTF-8 JSON string, valid only for the duration of the call.
+//! A single channel frame is passed as both channel A and channel B, and the
+//! node outputs a single channel frame with the timing of its input.
+//!
+//! An instance is only used by one thread at a time, but not always the same
+//! one. Each clone of the node creates its own instance.
+//!
+//! # Configuration
+//!
+//! ```yaml
+//! - id: "vendor_dsp"
+//!   node_type: "plugin"
+//!   parameters:
+//!     library_path: /opt/vendor/libdsp.so
+//!     config:              # Passed to create() and update_config()
+//!       gain: 2.0
+//! ```
+
+use anyhow::{anyhow, Result};
+use libloading::Library;
+use log::{debug, info};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::ffi::{c_char, c_void, CString};
+use std::path::{Path, PathBuf};
+use std::ptr::NonNull;
+use std::sync::Arc;
+
+use super::data::ProcessingData;
+use super::traits::ProcessingNode;
+
+/// Version of the C ABI implemented by the plugins this node loads
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+type AbiVersionFn = unsafe extern "C" fn() -> u32;
+type CreateFn = unsafe extern "C" fn(config_json: *const c_char) -> *mut c_void;
+type ProcessFn = unsafe extern "C" fn(
+    instance: *mut c_void,
+    channel_a: *const f32,
+    channel_b: *const f32,
+    len: usize,
+    out: *mut f32,
+    out_len: *mut usize,
+) -> i32;
+type UpdateConfigFn =
+    unsafe extern "C" fn(instance: *mut c_void, config_json: *const c_char) -> i32;
+type DestroyFn = unsafe extern "C" fn(instance: *mut c_void);
+
+/// Native plugin node configuration
+///
+/// # Example
+///
+/// ```rust
+/// use rust_photoacoustic::processing::nodes::PluginNodeConfig;
+/// use serde_json::json;
+/// use std::path::PathBuf;
+///
+/// let config = PluginNodeConfig {
+///     library_path: PathBuf::from("libdsp.so"),
+///     config: json!({ "gain": 2.0 }),
+/// };
+/// ```
+#[derive(Debug, Clone)]
+pub struct PluginNodeConfig {
+    /// Path to the shared library
+    pub library_path: PathBuf,
+    /// Configuration passed to the plugin as JSON
+    pub config: Value,
+}
+
+impl Default for PluginNodeConfig {
+    fn default() -> Self {
+        Self {
+            library_path: PathBuf::from("plugin.so"),
+            config: Value::Object(Default::default()),
+        }
+    }
+}
+
+/// Functions resolved from a loaded plugin library
+struct PluginApi {
+    create: CreateFn,
+    process: ProcessFn,
+    update_config: UpdateConfigFn,
+    destroy: DestroyFn,
+    /// Keeps the functions above valid, dropped last
+    _library: Library,
+}
+
+impl PluginApi {
+    /// Load a library and resolve the functions of the ABI
+    fn load(library_path: &Path) -> Result<Self> {
+        // SAFETY: loading a library runs its initialization routines, the
+        // plugins are trusted code
+        let library = unsafe { Library::new(library_path) }
+            .map_err(|e| anyhow!("Failed to load plugin library {:?}: {}", library_path, e))?;
+
+        // SAFETY: the symbol types are those documented by the ABI, the
+        // version check below rejects libraries implementing another one
+        let abi_version: AbiVersionFn =
+            unsafe { Self::symbol(&library, library_path, "abi_version")? };
+        let version = unsafe { abi_version() };
+        if version != PLUGIN_ABI_VERSION {
+            return Err(anyhow!(
+                "Plugin library {:?} implements ABI version {}, expected version {}",
+                library_path,
+                version,
+                PLUGIN_ABI_VERSION
+            ));
+        }
+
+        unsafe {
+            Ok(Self {
+                create: Self::symbol(&library, library_path, "create")?,
+                process: Self::symbol(&library, library_path, "process")?,
+                update_config: Self::symbol(&library, library_path, "update_config")?,
+                destroy: Self::symbol(&library, library_path, "destroy")?,
+                _library: library,
+            })
+        }
+    }
+
+    /// Resolve a function of the library
+    ///
+    /// # Safety
+    ///
+    /// `T` must be the type of the exported function, and the returned
+    /// pointer must not outlive `library`.
+    unsafe fn symbol<T: Copy>(library: &Library, library_path: &Path, name: &str) -> Result<T> {
+        let symbol_name = format!("{}\0", name);
+        library
+            .get::<T>(symbol_name.as_bytes())
+            .map(|symbol| *symbol)
+            .map_err(|e| {
+                anyhow!(
+                    "Plugin library {:?} does not export '{}': {}",
+                    library_path,
+                    name,
+                    e
+                )
+            })
+    }
+}
+
+/// Instance created by a plugin, destroyed when dropped
+struct PluginInstance {
+    api: Arc<PluginApi>,
+    handle: NonNull<c_void>,
+}
+
+// SAFETY: the ABI requires instances to be usable from any thread, one at a
+// time; the node only calls the plugin through `&mut self`
+unsafe impl Send for PluginInstance {}
+unsafe impl Sync for PluginInstance {}
+
+impl PluginInstance {
+    /// Create an instance from its configuration
+    fn create(api: Arc<PluginApi>, config: &Value) -> Result<Self> {
+        let config_json = config_json(config)?;
+        // SAFETY: the string is NUL-terminated and outlives the call
+        let handle = unsafe { (api.create)(config_json.as_ptr()) };
+        let handle = NonNull::new(handle)
+            .ok_or_else(|| anyhow!("Plugin create() failed with configuration {}", config))?;
+        Ok(Self { api, handle })
+    }
+}
+
+impl Drop for PluginInstance {
+    fn drop(&mut self) {
+        // SAFETY: the handle was returned by create() and is released once
+        unsafe { (self.api.destroy)(self.handle.as_ptr()) }
+    }
+}
+
+/// Serialize a configuration for the plugin
+fn config_json(config: &Value) -> Result<CString> {
+    CString::new(config.to_string())
+        .map_err(|_| anyhow!("Plugin configuration cannot contain NUL characters"))
+}
+
+/// Processing node running a native plugin
+///
+/// The library is loaded and an instance created by [`PluginNode::new`], so
+/// that a missing symbol or an incompatible ABI is reported when the graph is
+/// built. See the [module documentation](self) for the ABI.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rust_photoacoustic::processing::nodes::{
+///     PluginNode, PluginNodeConfig, ProcessingData, ProcessingNode,
+/// };
+/// use serde_json::json;
+/// use std::path::PathBuf;
+///
+/// # fn example() -> anyhow::Result<()> {
+/// let mut node = PluginNode::new(
+///     "vendor_dsp".to_string(),
+///     PluginNodeConfig {
+///         library_path: PathBuf::from("/opt/vendor/libdsp.so"),
+///         config: json!({ "gain": 2.0 }),
+///     },
+/// )?;
+///
+/// let output = node.process(ProcessingData::SingleChannel {
+///     samples: vec![0.1, 0.2],
+///     sample_rate: 48000,
+///     timestamp: 0,
+///     frame_number: 1,
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct PluginNode {
+    id: String,
+    config: PluginNodeConfig,
+    api: Arc<PluginApi>,
+    /// Plugin instance, `None` after a reset until the next frame
+    instance: Option<PluginInstance>,
+}
+
+impl std::fmt::Debug for PluginNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PluginNode")
+            .field("id", &self.id)
+            .field("config", &self.config)
+            .field("instantiated", &self.instance.is_some())
+            .finish()
+    }
+}
+
+impl PluginNode {
+    /// Load a plugin library and create an instance
+    ///
+    /// ### Parameters
+    ///
+    /// * `id` - Unique identifier for this node
+    /// * `config` - Library path and plugin configuration
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if the library cannot be loaded, does not export a
+    /// function of the ABI, implements another ABI version, or if `create()`
+    /// fails
+    pub fn new(id: String, config: PluginNodeConfig) -> Result<Self> {
+        let api = Arc::new(PluginApi::load(&config.library_path)?);
+        let instance = PluginInstance::create(Arc::clone(&api), &config.config)?;
+        info!("Plugin node '{}' loaded {:?}", id, config.library_path);
+
+        Ok(Self {
+            id,
+            config,
+            api,
+            instance: Some(instance),
+        })
+    }
+
+    /// Create a plugin node from the parameters of its graph configuration
+    ///
+    /// ### Parameters
+    ///
+    /// * `id` - Unique identifier for this node
+    /// * `config` - `library_path` (required) and `config` passed to the plugin
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if `library_path` is missing, or if the plugin cannot
+    /// be loaded (see [`PluginNode::new`])
+    pub fn from_config(id: String, config: HashMap<String, Value>) -> Result<Self> {
+        let library_path = config
+            .get("library_path")
+            .and_then(|v| v.as_str())
+            .map(PathBuf::from)
+            .ok_or_else(|| anyhow!("Plugin node requires library_path parameter"))?;
+
+        Self::new(
+            id,
+            PluginNodeConfig {
+                library_path,
+                config: config
+                    .get("config")
+                    .cloned()
+                    .unwrap_or_else(|| Value::Object(Default::default())),
+            },
+        )
+    }
+
+    /// Get the current configuration
+    pub fn config(&self) -> &PluginNodeConfig {
+        &self.config
+    }
+
+    /// Run the plugin on one frame
+    fn process_samples(&mut self, channel_a: &[f32], channel_b: &[f32]) -> Result<Vec<f32>> {
+        if channel_a.len() != channel_b.len() {
+            return Err(anyhow!(
+                "Channel lengths differ: {} and {} samples",
+                channel_a.len(),
+                channel_b.len()
+            ));
+        }
+
+        let instance = match self.instance.take() {
+            Some(instance) => instance,
+            None => PluginInstance::create(Arc::clone(&self.api), &self.config.config)?,
+        };
+        let instance = self.instance.insert(instance);
+
+        let len = channel_a.len();
+        let mut out = vec![0.0f32; len];
+        let mut out_len = 0usize;
+        // SAFETY: the input slices hold `len` samples and the output buffer
+        // has room for `len` samples, as the ABI requires
+        let code = unsafe {
+            (instance.api.process)(
+                instance.handle.as_ptr(),
+                channel_a.as_ptr(),
+                channel_b.as_ptr(),
+                len,
+                out.as_mut_ptr(),
+                &mut out_len,
+            )
+        };
+        if code != 0 {
+            return Err(anyhow!("Plugin process() returned error code {}", code));
+        }
+        if out_len > len {
+            return Err(anyhow!(
+                "Plugin process() reported {} samples for a frame of {}",
+                out_len,
+                len
+            ));
+        }
+
+        out.truncate(out_len);
+        Ok(out)
+    }
+}
+
+impl ProcessingNode for PluginNode {
+    fn process(&mut self, input: ProcessingData) -> Result<ProcessingData> {
+        let (channel_a, channel_b, sample_rate, timestamp, frame_number) = match &input {
+            ProcessingData::AudioFrame(frame) => (
+                frame.channel_a.as_slice(),
+                frame.channel_b.as_slice(),
+                frame.sample_rate,
+                frame.timestamp,
+                frame.frame_number,
+            ),
+            ProcessingData::DualChannel {
+                channel_a,
+                channel_b,
+                sample_rate,
+                timestamp,
+                frame_number,
+            } => (
+                channel_a.as_slice(),
+                channel_b.as_slice(),
+                *sample_rate,
+                *timestamp,
+                *frame_number,
+            ),
+            ProcessingData::SingleChannel {
+                samples,
+                sample_rate,
+                timestamp,
+                frame_number,
+            } => (
+                samples.as_slice(),
+                samples.as_slice(),
+                *sample_rate,
+                *timestamp,
+                *frame_number,
+            ),
+            ProcessingData::PhotoacousticResult { .. } => {
+                return Err(anyhow!(
+                    "Plugin node '{}' does not accept PhotoacousticResult data",
+                    self.id
+                ))
+            }
+        };
+
+        let samples = self.process_samples(channel_a, channel_b)?;
+        Ok(ProcessingData::SingleChannel {
+            samples,
+            sample_rate,
+            timestamp,
+            frame_number,
+        })
+    }
+
+    fn node_id(&self) -> &str {
+        &self.id
+    }
+
+    fn node_type(&self) -> &str {
+        "plugin"
+    }
+
+    fn accepts_input(&self, input: &ProcessingData) -> bool {
+        !matches!(input, ProcessingData::PhotoacousticResult { .. })
+    }
+
+    fn output_type(&self, input: &ProcessingData) -> Option<String> {
+        if self.accepts_input(input) {
+            Some("SingleChannel".to_string())
+        } else {
+            None
+        }
+    }
+
+    fn reset(&mut self) {
+        // A new instance is created on the next frame
+        self.instance = None;
+        debug!("Plugin node '{}' reset", self.id);
+    }
+
+    fn clone_node(&self) -> Box<dyn ProcessingNode> {
+        // The clone shares the library and creates its own instance on its first frame
+        Box::new(Self {
+            id: self.id.clone(),
+            config: self.config.clone(),
+            api: Arc::clone(&self.api),
+            instance: None,
+        })
+    }
+
+    fn supports_hot_reload(&self) -> bool {
+        true // The plugin configuration and library can be replaced in place
+    }
+
+    fn update_config(&mut self, parameters: &Value) -> Result<bool> {
+        let Value::Object(params) = parameters else {
+            anyhow::bail!("Plugin node parameters must be an object");
+        };
+
+        // A new library replaces the instance, with the new configuration if any
+        if let Some(library_path) = params.get("library_path") {
+            let library_path = PathBuf::from(
+                library_path
+                    .as_str()
+                    .ok_or_else(|| anyhow!("library_path parameter must be a string"))?,
+            );
+            let config = params
+                .get("config")
+                .cloned()
+                .unwrap_or_else(|| self.config.config.clone());
+
+            let api = Arc::new(PluginApi::load(&library_path)?);
+            let instance = PluginInstance::create(Arc::clone(&api), &config)?;
+            self.instance = Some(instance);
+            self.api = api;
+            self.config = PluginNodeConfig {
+                library_path,
+                config,
+            };
+            info!(
+                "Plugin node '{}' reloaded {:?}",
+                self.id, self.config.library_path
+            );
+        } else if let Some(config) = params.get("config") {
+            if let Some(instance) = &self.instance {
+                let config_json = config_json(config)?;
+                // SAFETY: the handle is live and the string outlives the call
+                let code = unsafe {
+                    (instance.api.update_config)(instance.handle.as_ptr(), config_json.as_ptr())
+                };
+                match code {
+                    0 => {}
+                    1 => return Ok(false),
+                    code => {
+                        return Err(anyhow!(
+                            "Plugin update_config() returned error code {}",
+                            code
+                        ))
+                    }
+                }
+            }
+            // Without an instance, the next one is created with this configuration
+            self.config.config = config.clone();
+        }
+
+        let requires_reconstruction = params
+            .keys()
+            .any(|key| !matches!(key.as_str(), "library_path" | "config"));
+        Ok(!requires_reconstruction)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
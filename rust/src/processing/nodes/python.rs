@@ -18,11 +18,17 @@
 //!
 //! # Features
 //!
-//! - **Script Hot-reloading**: Automatically reload Python scripts when they change
-//! - **Timeout Protection**: Configurable timeouts prevent hanging Python scripts
+//! - **Script Hot-reloading**: Automatically reload Python scripts when they change,
+//!   or on demand with [`PythonNode::reload`] or by posting the node `script_path`
+//!   to `/api/graph/config`. A script that fails to import or lacks the processing
+//!   function is rejected and the previous one keeps running
+//! - **Timeout Protection**: Calls running longer than `timeout_seconds` are
+//!   interrupted, and the frame is dropped or passed through unmodified
 //! - **Error Handling**: Robust error handling with detailed error messages
 //! - **Multiple Data Types**: Support for all ProcessingData variants
 //! - **Sync Operation**: Synchronous processing for integration with the processing graph
+//! - **Computed Values**: The latest peak and concentration results are readable
+//!   from the `computing_state` module global
 //!
 //! # Example Python Script
 //!
@@ -107,6 +113,62 @@
 //!     return {"status": "shutdown"}
 //! ```
 //!
+//! # NumPy Arrays
+//!
+//! By default the sample buffers (`samples`, `channel_a`, `channel_b`,
+//! `signal`) are passed as Python lists: every sample becomes a JSON number,
+//! then a Python `float`, and the way back repeats both conversions. With
+//! `numpy_arrays: true` they are passed as writable NumPy `float32` arrays
+//! instead, filled with a single copy of the Rust buffer, and the returned
+//! buffers (arrays or lists) are read back with a single copy of their
+//! `float32` memory. The scalar fields still go through JSON. When NumPy
+//! cannot be imported the node falls back to lists.
+//!
+//! The `python_numpy` benchmark measures both paths for frames of 1024 to
+//! 65536 samples:
+//!
+//! ```text
+//! cargo bench --bench python_numpy
+//! ```
+//!
+//! A script written for the NumPy path can process a whole frame at once:
+//!
+//! ```python
+//! def process_data(data):
+//!     data["channel_a"] *= 2.0
+//!     data["channel_b"] = data["channel_b"] - data["channel_b"].mean()
+//!     return data
+//! ```
+//!
+//! # Computed Values
+//!
+//! Before each call the node sets the `computing_state` module global to a
+//! read-only snapshot of the shared computing state of the graph, or `None`
+//! when no state is attached. See
+//! [`ComputingSharedData::script_context`](crate::processing::computing_nodes::ComputingSharedData::script_context)
+//! for the schema:
+//!
+//! ```python
+//! def process_data(data):
+//!     concentration = (computing_state or {}).get("concentration_ppm")
+//!     if concentration is not None and concentration > 1000.0:
+//!         data["samples"] = [0.0 for _ in data["samples"]]
+//!     return data
+//! ```
+//!
+//! # Timeouts
+//!
+//! A watchdog raises `TimeoutError` in the script when a call runs longer than
+//! `timeout_seconds` (`0` disables it). The exception is delivered between two
+//! Python bytecodes, so a blocking C call such as a long `time.sleep` or a
+//! NumPy operation completes first; a loop of short calls is interrupted on
+//! time. Depending on `timeout_policy`, the node then fails the frame, which
+//! the graph drops (`drop`, the default), or returns its input unmodified
+//! (`pass_through`). Either way the next frame is processed normally, and the
+//! timeout is counted in [`PythonNode::timeout_count`] and in the `timeouts`
+//! field of the node statistics. A script catching `TimeoutError` is not
+//! interrupted, but its call still counts as timed out.
+//!
 //! # Usage Example
 //!
 //! ```rust,no_run
@@ -152,12 +214,14 @@ use log::{debug, error, info, warn};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime};
 
 use super::data::ProcessingData;
 use super::traits::ProcessingNode;
 use crate::acquisition::AudioFrame;
+use crate::processing::computing_nodes::SharedComputingState;
 
 #[cfg(feature = "python-driver")]
 use pyo3::prelude::*;
@@ -169,6 +233,222 @@ struct CachedPythonModule {
     last_modified: SystemTime,
 }
 
+/// Error returned when a Python call is interrupted by its timeout
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Python function '{function}' interrupted after exceeding its {timeout:?} timeout")]
+pub struct PythonCallTimeout {
+    /// Name of the interrupted function
+    pub function: String,
+    /// Time limit of the call
+    pub timeout: Duration,
+}
+
+/// Interrupts Python calls running longer than their time limit
+///
+/// Each node or driver owns one watchdog. Its thread is started by the first
+/// guarded call and sleeps until the earliest deadline of the calls in
+/// progress, then raises `TimeoutError` in the thread running the late call;
+/// the interpreter delivers it at the next bytecode. Also used by the Python
+/// action driver.
+///
+/// Dropping the watchdog stops its thread.
+#[cfg(feature = "python-driver")]
+#[derive(Default)]
+pub(crate) struct PythonWatchdog {
+    shared: Arc<WatchdogShared>,
+    started: std::sync::Once,
+}
+
+#[cfg(feature = "python-driver")]
+#[derive(Default)]
+struct WatchdogShared {
+    state: Mutex<WatchdogState>,
+    /// Signalled when a call is armed or the watchdog is dropped
+    wake: std::sync::Condvar,
+}
+
+#[cfg(feature = "python-driver")]
+#[derive(Default)]
+struct WatchdogState {
+    /// Guarded calls in progress by call number, only changed with the GIL held
+    calls: HashMap<u64, WatchedCall>,
+    next_call: u64,
+    shutdown: bool,
+}
+
+/// A guarded call in progress
+#[cfg(feature = "python-driver")]
+struct WatchedCall {
+    /// Python identifier of the calling thread
+    thread_id: std::os::raw::c_ulong,
+    deadline: std::time::Instant,
+    /// `TimeoutError` was raised in the calling thread
+    fired: bool,
+}
+
+#[cfg(feature = "python-driver")]
+impl PythonWatchdog {
+    /// Create a watchdog, its thread is started by the first call to [`Self::arm`]
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start watching a call made by the current thread
+    ///
+    /// Must be called while holding the GIL, just before calling into Python.
+    ///
+    /// ### Parameters
+    ///
+    /// * `py` - GIL token of the calling thread
+    /// * `timeout` - Time limit of the call
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if the Python thread identifier cannot be read
+    pub(crate) fn arm(&self, py: Python<'_>, timeout: Duration) -> PyResult<PythonCallGuard<'_>> {
+        let thread_id = py
+            .import("threading")?
+            .call_method0("get_ident")?
+            .extract::<std::os::raw::c_ulong>()?;
+
+        self.started.call_once(|| {
+            let shared = Arc::clone(&self.shared);
+            std::thread::spawn(move || Self::run(&shared));
+        });
+
+        let mut state = self.shared.state.lock().unwrap();
+        let call = state.next_call;
+        state.next_call += 1;
+        state.calls.insert(
+            call,
+            WatchedCall {
+                thread_id,
+                deadline: std::time::Instant::now() + timeout,
+                fired: false,
+            },
+        );
+        drop(state);
+        self.shared.wake.notify_one();
+
+        Ok(PythonCallGuard {
+            watchdog: self,
+            call,
+        })
+    }
+
+    /// Watchdog thread: wait for the earliest deadline and interrupt late calls
+    fn run(shared: &WatchdogShared) {
+        let mut state = shared.state.lock().unwrap();
+        loop {
+            if state.shutdown {
+                return;
+            }
+            let now = std::time::Instant::now();
+            let next_deadline = state
+                .calls
+                .values()
+                .filter(|call| !call.fired)
+                .map(|call| call.deadline)
+                .min();
+            state = match next_deadline {
+                None => shared.wake.wait(state).unwrap(),
+                Some(deadline) if deadline > now => {
+                    shared.wake.wait_timeout(state, deadline - now).unwrap().0
+                }
+                Some(_) => {
+                    // Take the GIL before the state, in the order of the
+                    // guarded calls, which arm and disarm while holding it
+                    drop(state);
+                    Python::with_gil(|_py| {
+                        let mut state = shared.state.lock().unwrap();
+                        let now = std::time::Instant::now();
+                        for call in state
+                            .calls
+                            .values_mut()
+                            .filter(|call| !call.fired && call.deadline <= now)
+                        {
+                            // SAFETY: the GIL is held, and the target thread
+                            // is still inside the call since it is not disarmed
+                            unsafe {
+                                pyo3::ffi::PyThreadState_SetAsyncExc(
+                                    call.thread_id,
+                                    pyo3::ffi::PyExc_TimeoutError,
+                                );
+                            }
+                            call.fired = true;
+                        }
+                    });
+                    shared.state.lock().unwrap()
+                }
+            };
+        }
+    }
+}
+
+#[cfg(feature = "python-driver")]
+impl Drop for PythonWatchdog {
+    fn drop(&mut self) {
+        // Does not join the thread, which may be waiting for the GIL held by
+        // the caller
+        self.shared.state.lock().unwrap().shutdown = true;
+        self.shared.wake.notify_one();
+    }
+}
+
+/// A call watched by a [`PythonWatchdog`]
+///
+/// The guard must be disarmed or dropped while the GIL is still held.
+#[cfg(feature = "python-driver")]
+pub(crate) struct PythonCallGuard<'a> {
+    watchdog: &'a PythonWatchdog,
+    call: u64,
+}
+
+#[cfg(feature = "python-driver")]
+impl PythonCallGuard<'_> {
+    /// Stop watching the call once it has returned
+    ///
+    /// ### Returns
+    ///
+    /// `true` if the call was interrupted
+    pub(crate) fn disarm(self, _py: Python<'_>) -> bool {
+        self.stop()
+    }
+
+    fn stop(&self) -> bool {
+        let mut state = self.watchdog.shared.state.lock().unwrap();
+        match state.calls.remove(&self.call) {
+            Some(call) if call.fired => {
+                // SAFETY: the GIL is held; a null exception clears a
+                // `TimeoutError` still pending if the call returned before it
+                unsafe {
+                    pyo3::ffi::PyThreadState_SetAsyncExc(call.thread_id, std::ptr::null_mut());
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(feature = "python-driver")]
+impl Drop for PythonCallGuard<'_> {
+    fn drop(&mut self) {
+        // Covers an early return between arming and disarming
+        self.stop();
+    }
+}
+
+/// What the Python node returns when its processing function times out
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PythonTimeoutPolicy {
+    /// Fail the call, the graph drops the frame
+    #[default]
+    Drop,
+    /// Return the input frame unmodified
+    PassThrough,
+}
+
 /// Python processing node configuration
 ///
 /// This structure defines all the configuration options for the Python processing node.
@@ -197,7 +477,7 @@ struct CachedPythonModule {
 /// # Default Configuration
 ///
 /// ```rust
-/// use rust_photoacoustic::processing::nodes::PythonNodeConfig;
+/// use rust_photoacoustic::processing::nodes::{PythonNodeConfig, PythonTimeoutPolicy};
 /// use std::path::PathBuf;
 ///
 /// let default_config = PythonNodeConfig::default();
@@ -205,6 +485,7 @@ struct CachedPythonModule {
 /// assert_eq!(default_config.script_path, PathBuf::from("processor.py"));
 /// assert_eq!(default_config.process_function, "process_data");
 /// assert_eq!(default_config.timeout_seconds, 30);
+/// assert_eq!(default_config.timeout_policy, PythonTimeoutPolicy::Drop);
 /// assert_eq!(default_config.auto_reload, false);
 /// ```
 #[derive(Debug, Clone)]
@@ -221,8 +502,14 @@ pub struct PythonNodeConfig {
     pub shutdown_function: String,
     /// Function name to call for status (default: "get_status")
     pub status_function: String,
-    /// Maximum execution time for Python calls (seconds)
+    /// Maximum execution time for Python calls (seconds), `0` disables the limit
+    ///
+    /// The limit is only enforced between Python bytecodes: a call blocked in C
+    /// code, such as `time.sleep(60)`, is not interrupted before it returns and
+    /// holds up the graph until then.
     pub timeout_seconds: u64,
+    /// Outcome of a frame whose processing call timed out
+    pub timeout_policy: PythonTimeoutPolicy,
     /// Whether to reload script on each call (development mode)
     pub auto_reload: bool,
     /// Additional Python path directories
@@ -231,6 +518,9 @@ pub struct PythonNodeConfig {
     pub accepted_types: Vec<String>,
     /// Expected output data type (None means same as input)
     pub output_type: Option<String>,
+    /// Pass the sample buffers as NumPy `float32` arrays instead of lists
+    /// (falls back to lists when NumPy is not installed)
+    pub numpy_arrays: bool,
 }
 
 impl Default for PythonNodeConfig {
@@ -243,10 +533,12 @@ impl Default for PythonNodeConfig {
             shutdown_function: "shutdown".to_string(),
             status_function: "get_status".to_string(),
             timeout_seconds: 30,
+            timeout_policy: PythonTimeoutPolicy::Drop,
             auto_reload: false,
             python_paths: Vec::new(),
             accepted_types: Vec::new(),
             output_type: None,
+            numpy_arrays: false,
         }
     }
 }
@@ -265,7 +557,8 @@ impl Default for PythonNodeConfig {
 /// # Performance Considerations
 ///
 /// - Python scripts are reloaded from disk when `auto_reload` is enabled
-/// - Function calls have configurable timeouts to prevent hanging
+/// - Function calls have configurable timeouts to prevent hanging, enforced
+///   between Python bytecodes only: a blocking C call runs to completion
 /// - Python GIL acquisition has minimal overhead in typical usage
 ///
 /// # Example
@@ -312,6 +605,12 @@ pub struct PythonNode {
     status: Arc<Mutex<String>>,
     #[cfg(feature = "python-driver")]
     cached_module: Arc<Mutex<Option<CachedPythonModule>>>,
+    shared_computing_state: Option<SharedComputingState>,
+    /// Last snapshot of the shared computing state given to the script
+    computing_state_snapshot: Arc<Mutex<Value>>,
+    timeouts: Arc<AtomicU64>,
+    #[cfg(feature = "python-driver")]
+    watchdog: Arc<PythonWatchdog>,
 }
 
 impl std::fmt::Debug for PythonNode {
@@ -358,6 +657,11 @@ impl PythonNode {
             status: Arc::new(Mutex::new("created".to_string())),
             #[cfg(feature = "python-driver")]
             cached_module: Arc::new(Mutex::new(None)),
+            shared_computing_state: None,
+            computing_state_snapshot: Arc::new(Mutex::new(Value::Null)),
+            timeouts: Arc::new(AtomicU64::new(0)),
+            #[cfg(feature = "python-driver")]
+            watchdog: Arc::new(PythonWatchdog::new()),
         }
     }
 
@@ -442,6 +746,21 @@ impl PythonNode {
             }
         }
 
+        if let Some(timeout_policy) = config.get("timeout_policy") {
+            if let Some(policy_str) = timeout_policy.as_str() {
+                node_config.timeout_policy = match policy_str {
+                    "drop" => PythonTimeoutPolicy::Drop,
+                    "pass_through" => PythonTimeoutPolicy::PassThrough,
+                    other => {
+                        return Err(anyhow!(
+                            "Invalid timeout_policy '{}', expected 'drop' or 'pass_through'",
+                            other
+                        ))
+                    }
+                };
+            }
+        }
+
         if let Some(auto_reload) = config.get("auto_reload") {
             if let Some(reload) = auto_reload.as_bool() {
                 node_config.auto_reload = reload;
@@ -472,9 +791,20 @@ impl PythonNode {
             }
         }
 
+        if let Some(numpy_arrays) = config.get("numpy_arrays") {
+            if let Some(enabled) = numpy_arrays.as_bool() {
+                node_config.numpy_arrays = enabled;
+            }
+        }
+
         Ok(Self::new(id, node_config))
     }
 
+    /// Number of script calls interrupted by the timeout since the node was created
+    pub fn timeout_count(&self) -> u64 {
+        self.timeouts.load(Ordering::Relaxed)
+    }
+
     /// Get the current configuration
     pub fn config(&self) -> &PythonNodeConfig {
         &self.config
@@ -489,6 +819,120 @@ impl PythonNode {
         Ok(())
     }
 
+    /// Reload the Python script from disk in place
+    ///
+    /// The new script is imported and checked for the processing function
+    /// before it replaces the current one: on failure the node keeps running
+    /// the previous script. When the node was already initialized, the
+    /// initialization function of the new script is called.
+    ///
+    /// The node id and its position in the processing graph are unchanged.
+    ///
+    /// ### Errors
+    ///
+    /// Fails if the script cannot be read, does not import or does not define
+    /// the processing function
+    pub fn reload(&self) -> Result<()> {
+        #[cfg(feature = "python-driver")]
+        {
+            let (module_code, modified) = Python::with_gil(|py| self.load_validated_script(py))
+                .inspect_err(|e| {
+                    *self.status.lock().unwrap() = format!("reload failed: {}", e);
+                })?;
+
+            *self
+                .cached_module
+                .lock()
+                .map_err(|e| anyhow!("Failed to lock cached_module: {}", e))? =
+                Some(CachedPythonModule {
+                    module_code,
+                    last_modified: modified,
+                });
+            *self
+                .last_modified
+                .lock()
+                .map_err(|e| anyhow!("Failed to lock last_modified: {}", e))? = Some(modified);
+            info!(
+                "Python node '{}' reloaded script {:?}",
+                self.id, self.config.script_path
+            );
+
+            let initialized = *self
+                .initialized
+                .lock()
+                .map_err(|e| anyhow!("Failed to lock initialized: {}", e))?;
+            if initialized {
+                self.call_python_function(&self.config.init_function, json!({}))?;
+            }
+            *self.status.lock().unwrap() = "reloaded".to_string();
+            Ok(())
+        }
+        #[cfg(not(feature = "python-driver"))]
+        {
+            Err(anyhow!(
+                "Python driver feature not enabled. Enable with --features python-driver"
+            ))
+        }
+    }
+
+    /// Read the script and check that it imports and defines the processing function
+    ///
+    /// ### Returns
+    ///
+    /// The script source and its modification time
+    #[cfg(feature = "python-driver")]
+    fn load_validated_script(&self, py: Python<'_>) -> Result<(String, SystemTime)> {
+        let script_content = std::fs::read_to_string(&self.config.script_path).map_err(|e| {
+            anyhow!(
+                "Failed to read script file {:?}: {}",
+                self.config.script_path,
+                e
+            )
+        })?;
+        let modified = std::fs::metadata(&self.config.script_path)?.modified()?;
+
+        let module = Self::compile_module(py, &script_content).map_err(|e| {
+            anyhow!(
+                "Python script {:?} failed to load: {}",
+                self.config.script_path,
+                e
+            )
+        })?;
+        if !module.hasattr(self.config.process_function.as_str())? {
+            return Err(anyhow!(
+                "Python script {:?} does not define '{}'",
+                self.config.script_path,
+                self.config.process_function
+            ));
+        }
+        Ok((script_content, modified))
+    }
+
+    /// Execute the script source as the `processor` module
+    #[cfg(feature = "python-driver")]
+    fn compile_module<'py>(
+        py: Python<'py>,
+        module_code: &str,
+    ) -> Result<Bound<'py, pyo3::types::PyModule>> {
+        use std::ffi::CString;
+
+        let module_code_cstr =
+            CString::new(module_code).map_err(|e| anyhow!("Invalid module code: {}", e))?;
+
+        // Convert filename and module name to CString for PyO3 0.25+
+        let filename =
+            CString::new("processor.py").map_err(|e| anyhow!("Invalid filename: {}", e))?;
+        let module_name =
+            CString::new("processor").map_err(|e| anyhow!("Invalid module name: {}", e))?;
+
+        Ok(pyo3::types::PyModule::from_code(
+            py,
+            module_code_cstr.as_c_str(),
+            filename.as_c_str(),
+            module_name.as_c_str(),
+        )?)
+    }
+
     /// Check if the script file has been modified since last load
     fn script_modified(&self) -> Result<bool> {
         let metadata = std::fs::metadata(&self.config.script_path)?;
@@ -538,32 +982,124 @@ impl PythonNode {
     /// Call a Python function with the given arguments (optimized with module caching)
     #[cfg(feature = "python-driver")]
     fn call_python_function(&self, function_name: &str, args: Value) -> Result<Value> {
-        use pyo3::prelude::*;
-        use pyo3::types::{PyDict, PyModule};
-        use std::ffi::CString;
+        self.with_script_function(function_name, |py, function| {
+            // Call the function based on its name and expected arguments
+            let result = if function_name == "initialize" {
+                // Initialize function takes no arguments
+                function.call0()?
+            } else {
+                // Convert arguments to Python
+                let py_args = pythonize::pythonize(py, &args)?;
+                // Call the function with arguments
+                function.call1((py_args,))?
+            };
+
+            // Convert result back to JSON
+            Ok(pythonize::depythonize(&result)?)
+        })
+    }
+
+    /// Call the processing function with the sample buffers as NumPy arrays
+    ///
+    /// Each buffer is copied once into a `bytearray` viewed as a writable
+    /// `float32` array, and each returned buffer is copied once back from the
+    /// array memory. The returned buffers may be NumPy arrays or lists.
+    ///
+    /// ### Returns
+    ///
+    /// `None` when NumPy cannot be imported, the caller then uses the list path
+    #[cfg(feature = "python-driver")]
+    fn call_process_function_numpy(
+        &self,
+        input: &ProcessingData,
+    ) -> Result<Option<ProcessingData>> {
+        use pyo3::types::PyByteArray;
+        use std::borrow::Cow;
+
+        let numpy = match Python::with_gil(|py| py.import("numpy").map(|numpy| numpy.unbind())) {
+            Ok(numpy) => numpy,
+            Err(e) => {
+                debug!(
+                    "Python node '{}': NumPy unavailable, passing lists: {}",
+                    self.id, e
+                );
+                return Ok(None);
+            }
+        };
+        let header = Self::processing_data_header(input);
+
+        let (json_result, arrays) =
+            self.with_script_function(&self.config.process_function, |py, function| {
+                let numpy = numpy.bind(py);
+                let float32 = numpy.getattr("float32")?;
+
+                let data = pythonize::pythonize(py, &header)?;
+                for (key, samples) in Self::sample_buffers(input) {
+                    let buffer =
+                        PyByteArray::new_with(py, std::mem::size_of_val(samples), |bytes| {
+                            for (chunk, sample) in bytes.chunks_exact_mut(4).zip(samples) {
+                                chunk.copy_from_slice(&sample.to_ne_bytes());
+                            }
+                            Ok(())
+                        })?;
+                    data.set_item(key, numpy.call_method1("frombuffer", (buffer, &float32))?)?;
+                }
+
+                let result = function.call1((data,))?;
+
+                let mut arrays = HashMap::new();
+                for key in ["samples", "channel_a", "channel_b", "signal"] {
+                    if !result.contains(key)? {
+                        continue;
+                    }
+                    let array = numpy
+                        .call_method1("ascontiguousarray", (result.get_item(key)?, &float32))?;
+                    let bytes = array.call_method0("tobytes")?;
+                    let bytes: Cow<[u8]> = bytes.extract()?;
+                    let samples = bytes
+                        .chunks_exact(4)
+                        .map(|chunk| f32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                        .collect::<Vec<f32>>();
+                    arrays.insert(key.to_string(), samples);
+                    result.del_item(key)?;
+                }
+
+                let json_result: Value = pythonize::depythonize(&result)?;
+                Ok((json_result, arrays))
+            })?;
+
+        Self::json_to_processing_data_with_arrays(json_result, arrays).map(Some)
+    }
+
+    /// Run `call` with `function_name` from the loaded script
+    ///
+    /// Loads the module, sets its `computing_state` global and the Python
+    /// paths, and interrupts the call when it exceeds the configured timeout.
+    ///
+    /// ### Errors
+    ///
+    /// Returns a [`PythonCallTimeout`] error when the call was interrupted
+    #[cfg(feature = "python-driver")]
+    fn with_script_function<T>(
+        &self,
+        function_name: &str,
+        call: impl for<'py> FnOnce(Python<'py>, &Bound<'py, PyAny>) -> Result<T>,
+    ) -> Result<T> {
         use std::time::Instant;
 
         let start_time = Instant::now();
 
-        Python::with_gil(|py| -> Result<Value> {
+        Python::with_gil(|py| -> Result<T> {
             // Get or create cached module
-            let module_code = self.get_or_load_module_code()?;
-
-            let module_code_cstr = CString::new(module_code.as_str())
-                .map_err(|e| anyhow!("Invalid module code: {}", e))?;
-
-            // Convert filename and module name to CString for PyO3 0.25+
-            let filename =
-                CString::new("processor.py").map_err(|e| anyhow!("Invalid filename: {}", e))?;
-            let module_name =
-                CString::new("processor").map_err(|e| anyhow!("Invalid module name: {}", e))?;
+            let module_code = self.get_or_load_module_code(py)?;
 
             // Create module from cached code (this is much faster than file I/O)
-            let module = PyModule::from_code(
-                py,
-                module_code_cstr.as_c_str(),
-                filename.as_c_str(),
-                module_name.as_c_str(),
+            let module = Self::compile_module(py, &module_code)?;
+
+            // Read-only snapshot of the computed results
+            module.setattr(
+                "computing_state",
+                pythonize::pythonize(py, &self.computing_state_context())?,
             )?;
 
             // Set up Python path if specified (only do this once during initialization ideally)
@@ -585,67 +1121,76 @@ impl PythonNode {
                 ));
             }
 
-            // Call the function based on its name and expected arguments
-            let result = if function_name == "initialize" {
-                // Initialize function takes no arguments
-                module.getattr(function_name)?.call0()?
+            let function = module.getattr(function_name)?;
+            let timeout = Duration::from_secs(self.config.timeout_seconds);
+            let guard = if timeout.is_zero() {
+                None
             } else {
-                // Convert arguments to Python
-                let py_args = pythonize::pythonize(py, &args)?;
-                // Call the function with arguments
-                module.getattr(function_name)?.call1((py_args,))?
+                Some(self.watchdog.arm(py, timeout)?)
             };
-
-            // Convert result back to JSON
-            let json_result: Value = pythonize::depythonize(&result)?;
-
-            // Check for timeout (simple approach)
-            if start_time.elapsed() > Duration::from_secs(self.config.timeout_seconds) {
+            let result = call(py, &function);
+            if guard.is_some_and(|guard| guard.disarm(py)) {
+                self.timeouts.fetch_add(1, Ordering::Relaxed);
                 warn!(
-                    "Python function '{}' execution took {:?}, which exceeds timeout of {} seconds",
+                    "Python node '{}': function '{}' interrupted after {:?}, exceeding its timeout of {} seconds",
+                    self.id,
                     function_name,
                     start_time.elapsed(),
                     self.config.timeout_seconds
                 );
+                return Err(PythonCallTimeout {
+                    function: function_name.to_string(),
+                    timeout,
+                }
+                .into());
             }
+            let result = result?;
 
             debug!(
                 "Python function '{}' completed in {:?}",
                 function_name,
                 start_time.elapsed()
             );
-            Ok(json_result)
+            Ok(result)
         })
     }
 
     /// Get module code, using cache when possible
     #[cfg(feature = "python-driver")]
-    fn get_or_load_module_code(&self) -> Result<String> {
-        // Check if we need to reload the script
-        let should_reload = if self.config.auto_reload {
-            self.script_modified()?
-        } else {
-            // If auto_reload is false, only reload if we haven't loaded yet
-            let cached = self
-                .cached_module
-                .lock()
-                .map_err(|e| anyhow!("Failed to lock cached_module: {}", e))?;
-            cached.is_none()
-        };
+    fn get_or_load_module_code(&self, py: Python<'_>) -> Result<String> {
+        // Load the script once, then again on change if auto_reload is enabled
+        let loaded = self
+            .cached_module
+            .lock()
+            .map_err(|e| anyhow!("Failed to lock cached_module: {}", e))?
+            .is_some();
+        let should_reload = !loaded || (self.config.auto_reload && self.script_modified()?);
 
         if should_reload {
             // Load script from disk
-            let script_content =
-                std::fs::read_to_string(&self.config.script_path).map_err(|e| {
-                    anyhow!(
-                        "Failed to read script file {:?}: {}",
-                        self.config.script_path,
-                        e
-                    )
-                })?;
-
-            let metadata = std::fs::metadata(&self.config.script_path)?;
-            let modified = metadata.modified()?;
+            let (script_content, modified) = match self.load_validated_script(py) {
+                Ok(loaded) => loaded,
+                Err(e) => {
+                    let cached = self
+                        .cached_module
+                        .lock()
+                        .map_err(|e| anyhow!("Failed to lock cached_module: {}", e))?;
+                    let Some(cached_module) = &*cached else {
+                        return Err(e);
+                    };
+                    // Keep running the previous script until the file changes again
+                    warn!("Python node '{}' keeps its previous script: {}", self.id, e);
+                    let mut last_modified = self
+                        .last_modified
+                        .lock()
+                        .map_err(|e| anyhow!("Failed to lock last_modified: {}", e))?;
+                    *last_modified = std::fs::metadata(&self.config.script_path)
+                        .and_then(|metadata| metadata.modified())
+                        .ok()
+                        .or(*last_modified);
+                    return Ok(cached_module.module_code.clone());
+                }
+            };
 
             // Cache the module
             let mut cached = self
@@ -691,6 +1236,14 @@ impl PythonNode {
         }
     }
 
+    #[cfg(not(feature = "python-driver"))]
+    fn call_process_function_numpy(
+        &self,
+        _input: &ProcessingData,
+    ) -> Result<Option<ProcessingData>> {
+        Ok(None)
+    }
+
     #[cfg(not(feature = "python-driver"))]
     fn call_python_function(&self, _function_name: &str, _args: Value) -> Result<Value> {
         Err(anyhow!(
@@ -698,83 +1251,141 @@ impl PythonNode {
         ))
     }
 
+    /// Run the processing function on `input`, with NumPy arrays when enabled
+    fn call_process_function(&self, input: &ProcessingData) -> Result<ProcessingData> {
+        if self.config.numpy_arrays {
+            if let Some(output) = self.call_process_function_numpy(input)? {
+                return Ok(output);
+            }
+        }
+
+        // Convert input to JSON
+        let input_json = self.processing_data_to_json(input)?;
+
+        // Call Python processing function
+        let result_json = self.call_python_function(&self.config.process_function, input_json)?;
+
+        // Convert result back to ProcessingData
+        self.json_to_processing_data(result_json)
+    }
+
+    /// Snapshot of the shared computing state exposed to the script
+    ///
+    /// `Null` when the node is not attached to a graph. The processing thread
+    /// does not wait for the lock: while a computing node is writing the state,
+    /// the script gets the previous snapshot.
+    fn computing_state_context(&self) -> Value {
+        let Some(shared_state) = &self.shared_computing_state else {
+            return Value::Null;
+        };
+        let mut snapshot = self.computing_state_snapshot.lock().unwrap();
+        if let Ok(state) = shared_state.try_read() {
+            *snapshot = state.script_context();
+        }
+        snapshot.clone()
+    }
+
     /// Convert ProcessingData to JSON for Python consumption
     fn processing_data_to_json(&self, data: &ProcessingData) -> Result<Value> {
+        let mut json = Self::processing_data_header(data);
+        for (key, samples) in Self::sample_buffers(data) {
+            json[key] = json!(samples);
+        }
+        Ok(json)
+    }
+
+    /// Fields of the data passed to Python other than the sample buffers
+    ///
+    /// Also used by the Lua node, so that scripts see the same fields.
+    pub(crate) fn processing_data_header(data: &ProcessingData) -> Value {
         match data {
-            ProcessingData::AudioFrame(frame) => Ok(json!({
+            ProcessingData::AudioFrame(frame) => json!({
                 "type": "AudioFrame",
-                "channel_a": frame.channel_a,
-                "channel_b": frame.channel_b,
                 "sample_rate": frame.sample_rate,
                 "timestamp": frame.timestamp,
                 "frame_number": frame.frame_number
-            })),
+            }),
             ProcessingData::SingleChannel {
-                samples,
                 sample_rate,
                 timestamp,
                 frame_number,
-            } => Ok(json!({
+                ..
+            } => json!({
                 "type": "SingleChannel",
-                "samples": samples,
                 "sample_rate": sample_rate,
                 "timestamp": timestamp,
                 "frame_number": frame_number
-            })),
+            }),
             ProcessingData::DualChannel {
-                channel_a,
-                channel_b,
                 sample_rate,
                 timestamp,
                 frame_number,
-            } => Ok(json!({
+                ..
+            } => json!({
                 "type": "DualChannel",
-                "channel_a": channel_a,
-                "channel_b": channel_b,
                 "sample_rate": sample_rate,
                 "timestamp": timestamp,
                 "frame_number": frame_number
-            })),
-            ProcessingData::PhotoacousticResult { signal, metadata } => Ok(json!({
+            }),
+            ProcessingData::PhotoacousticResult { metadata, .. } => json!({
                 "type": "PhotoacousticResult",
-                "signal": signal,
                 "metadata": {
                     "original_frame_number": metadata.original_frame_number,
                     "original_timestamp": metadata.original_timestamp,
                     "sample_rate": metadata.sample_rate,
                     "processing_steps": metadata.processing_steps,
-                    "processing_latency_us": metadata.processing_latency_us
+                    "processing_latency_us": metadata.processing_latency_us,
+                    "snr_db": metadata.snr_db
                 }
-            })),
+            }),
+        }
+    }
+
+    /// Sample buffers of the data with the key they are passed under
+    pub(crate) fn sample_buffers(data: &ProcessingData) -> Vec<(&'static str, &[f32])> {
+        match data {
+            ProcessingData::AudioFrame(frame) => vec![
+                ("channel_a", frame.channel_a.as_slice()),
+                ("channel_b", frame.channel_b.as_slice()),
+            ],
+            ProcessingData::SingleChannel { samples, .. } => vec![("samples", samples.as_slice())],
+            ProcessingData::DualChannel {
+                channel_a,
+                channel_b,
+                ..
+            } => vec![
+                ("channel_a", channel_a.as_slice()),
+                ("channel_b", channel_b.as_slice()),
+            ],
+            ProcessingData::PhotoacousticResult { signal, .. } => {
+                vec![("signal", signal.as_slice())]
+            }
         }
     }
 
     /// Convert JSON result back to ProcessingData
     fn json_to_processing_data(&self, json: Value) -> Result<ProcessingData> {
+        Self::json_to_processing_data_with_arrays(json, HashMap::new())
+    }
+
+    /// Convert JSON result back to ProcessingData, taking the sample buffers
+    /// from `arrays` when the NumPy path or the Lua node already extracted them
+    pub(crate) fn json_to_processing_data_with_arrays(
+        json: Value,
+        mut arrays: HashMap<String, Vec<f32>>,
+    ) -> Result<ProcessingData> {
         let data_type = json
             .get("type")
             .and_then(|t| t.as_str())
-            .ok_or_else(|| anyhow!("Missing or invalid 'type' field in Python result"))?;
+            .ok_or_else(|| anyhow!("Missing or invalid 'type' field in script result"))?;
 
         match data_type {
             "AudioFrame" => {
-                let channel_a = json
-                    .get("channel_a")
-                    .and_then(|v| v.as_array())
-                    .ok_or_else(|| anyhow!("Missing channel_a in AudioFrame"))?
-                    .iter()
-                    .map(|v| v.as_f64().map(|f| f as f32))
-                    .collect::<Option<Vec<f32>>>()
-                    .ok_or_else(|| anyhow!("Invalid channel_a data"))?;
+                let channel_a =
+                    Self::channel_samples(&json, &mut arrays, "channel_a", "AudioFrame")?;
 
-                let channel_b = json
-                    .get("channel_b")
-                    .and_then(|v| v.as_array())
-                    .ok_or_else(|| anyhow!("Missing channel_b in AudioFrame"))?
-                    .iter()
-                    .map(|v| v.as_f64().map(|f| f as f32))
-                    .collect::<Option<Vec<f32>>>()
-                    .ok_or_else(|| anyhow!("Invalid channel_b data"))?;
+                let channel_b =
+                    Self::channel_samples(&json, &mut arrays, "channel_b", "AudioFrame")?;
 
                 let sample_rate =
                     json.get("sample_rate")
@@ -800,14 +1411,8 @@ impl PythonNode {
                 }))
             }
             "SingleChannel" => {
-                let samples = json
-                    .get("samples")
-                    .and_then(|v| v.as_array())
-                    .ok_or_else(|| anyhow!("Missing samples in SingleChannel"))?
-                    .iter()
-                    .map(|v| v.as_f64().map(|f| f as f32))
-                    .collect::<Option<Vec<f32>>>()
-                    .ok_or_else(|| anyhow!("Invalid samples data"))?;
+                let samples =
+                    Self::channel_samples(&json, &mut arrays, "samples", "SingleChannel")?;
 
                 let sample_rate =
                     json.get("sample_rate")
@@ -832,23 +1437,11 @@ impl PythonNode {
                 })
             }
             "DualChannel" => {
-                let channel_a = json
-                    .get("channel_a")
-                    .and_then(|v| v.as_array())
-                    .ok_or_else(|| anyhow!("Missing channel_a in DualChannel"))?
-                    .iter()
-                    .map(|v| v.as_f64().map(|f| f as f32))
-                    .collect::<Option<Vec<f32>>>()
-                    .ok_or_else(|| anyhow!("Invalid channel_a data"))?;
+                let channel_a =
+                    Self::channel_samples(&json, &mut arrays, "channel_a", "DualChannel")?;
 
-                let channel_b = json
-                    .get("channel_b")
-                    .and_then(|v| v.as_array())
-                    .ok_or_else(|| anyhow!("Missing channel_b in DualChannel"))?
-                    .iter()
-                    .map(|v| v.as_f64().map(|f| f as f32))
-                    .collect::<Option<Vec<f32>>>()
-                    .ok_or_else(|| anyhow!("Invalid channel_b data"))?;
+                let channel_b =
+                    Self::channel_samples(&json, &mut arrays, "channel_b", "DualChannel")?;
 
                 let sample_rate =
                     json.get("sample_rate")
@@ -874,14 +1467,8 @@ impl PythonNode {
                 })
             }
             "PhotoacousticResult" => {
-                let signal = json
-                    .get("signal")
-                    .and_then(|v| v.as_array())
-                    .ok_or_else(|| anyhow!("Missing signal in PhotoacousticResult"))?
-                    .iter()
-                    .map(|v| v.as_f64().map(|f| f as f32))
-                    .collect::<Option<Vec<f32>>>()
-                    .ok_or_else(|| anyhow!("Invalid signal data"))?;
+                let signal =
+                    Self::channel_samples(&json, &mut arrays, "signal", "PhotoacousticResult")?;
 
                 let metadata_json = json
                     .get("metadata")
@@ -917,6 +1504,11 @@ impl PythonNode {
                     .and_then(|v| v.as_u64())
                     .ok_or_else(|| anyhow!("Missing processing_latency_us"))?;
 
+                let snr_db = metadata_json
+                    .get("snr_db")
+                    .and_then(|v| v.as_f64())
+                    .map(|v| v as f32);
+
                 Ok(ProcessingData::PhotoacousticResult {
                     signal,
                     metadata: super::data::ProcessingMetadata {
@@ -925,6 +1517,7 @@ impl PythonNode {
                         sample_rate,
                         processing_steps,
                         processing_latency_us,
+                        snr_db,
                     },
                 })
             }
@@ -932,8 +1525,27 @@ impl PythonNode {
         }
     }
 
+    /// Samples stored under `key`, either extracted from a NumPy array or read from a JSON list
+    fn channel_samples(
+        json: &Value,
+        arrays: &mut HashMap<String, Vec<f32>>,
+        key: &str,
+        data_type: &str,
+    ) -> Result<Vec<f32>> {
+        if let Some(samples) = arrays.remove(key) {
+            return Ok(samples);
+        }
+        json.get(key)
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| anyhow!("Missing {} in {}", key, data_type))?
+            .iter()
+            .map(|v| v.as_f64().map(|f| f as f32))
+            .collect::<Option<Vec<f32>>>()
+            .ok_or_else(|| anyhow!("Invalid {} data", key))
+    }
+
     /// Get the data type name for ProcessingData
-    fn data_type_name(data: &ProcessingData) -> &'static str {
+    pub(crate) fn data_type_name(data: &ProcessingData) -> &'static str {
         match data {
             ProcessingData::AudioFrame(_) => "AudioFrame",
             ProcessingData::SingleChannel { .. } => "SingleChannel",
@@ -970,14 +1582,20 @@ impl ProcessingNode for PythonNode {
             }
         }
 
-        // Convert input to JSON
-        let input_json = self.processing_data_to_json(&input)?;
-
-        // Call Python processing function
-        let result_json = self.call_python_function(&self.config.process_function, input_json)?;
-
-        // Convert result back to ProcessingData
-        let output = self.json_to_processing_data(result_json)?;
+        let output = match self.call_process_function(&input) {
+            Ok(output) => output,
+            Err(e)
+                if e.is::<PythonCallTimeout>()
+                    && self.config.timeout_policy == PythonTimeoutPolicy::PassThrough =>
+            {
+                warn!(
+                    "Python node '{}' passes frame through unmodified: {}",
+                    self.id, e
+                );
+                return Ok(input);
+            }
+            Err(e) => return Err(e),
+        };
 
         // Validate output type if specified
         if let Some(expected_output) = &self.config.output_type {
@@ -1050,7 +1668,56 @@ impl ProcessingNode for PythonNode {
     }
 
     fn clone_node(&self) -> Box<dyn ProcessingNode> {
-        Box::new(PythonNode::new(self.id.clone(), self.config.clone()))
+        let mut node = PythonNode::new(self.id.clone(), self.config.clone());
+        node.shared_computing_state = self.shared_computing_state.clone();
+        Box::new(node)
+    }
+
+    fn set_shared_computing_state(&mut self, shared_state: Option<SharedComputingState>) {
+        self.shared_computing_state = shared_state;
+        *self.computing_state_snapshot.lock().unwrap() = Value::Null;
+    }
+
+    fn get_shared_computing_state(&self) -> Option<SharedComputingState> {
+        self.shared_computing_state.clone()
+    }
+
+    fn timeout_count(&self) -> u64 {
+        PythonNode::timeout_count(self)
+    }
+
+    fn supports_hot_reload(&self) -> bool {
+        true // The script can be reloaded or replaced in place
+    }
+
+    fn update_config(&mut self, parameters: &serde_json::Value) -> Result<bool> {
+        let Value::Object(params) = parameters else {
+            anyhow::bail!("Python node parameters must be an object");
+        };
+
+        if let Some(auto_reload) = params.get("auto_reload") {
+            self.config.auto_reload = auto_reload
+                .as_bool()
+                .ok_or_else(|| anyhow!("auto_reload parameter must be a boolean"))?;
+        }
+
+        // Setting script_path, even to its current value, reloads the script
+        if let Some(script_path) = params.get("script_path") {
+            let script_path = script_path
+                .as_str()
+                .ok_or_else(|| anyhow!("script_path parameter must be a string"))?;
+            let previous = std::mem::replace(&mut self.config.script_path, script_path.into());
+            if let Err(e) = self.reload() {
+                self.config.script_path = previous;
+                return Err(e);
+            }
+        }
+
+        // Changing the functions or the environment requires reconstruction
+        let requires_reconstruction = params
+            .keys()
+            .any(|key| !matches!(key.as_str(), "auto_reload" | "script_path"));
+        Ok(!requires_reconstruction)
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
@@ -425,6 +425,35 @@ impl RecordNode {
 
         Ok(())
     }
+
+    /// Finalize the open WAV file, if any, and hand it to the rolling file management
+    ///
+    /// ### Parameters
+    ///
+    /// * `context` - Where the finalization happens, used in log messages
+    fn finalize_recording(&mut self, context: &str) {
+        if let Some(writer) = self.wav_writer.take() {
+            if let Err(e) = writer.finalize() {
+                error!("Failed to finalize WAV file in {}: {}", context, e);
+            } else {
+                debug!("WAV file finalized in {} for node '{}'", context, self.id);
+
+                // Add the final file to rolling management
+                if self.file_index > 0 {
+                    let final_file = self.get_current_file_path();
+                    let final_size_kb = self.current_size_bytes / 1024;
+
+                    if self.auto_delete && final_file.exists() {
+                        if let Err(e) = fs::remove_file(&final_file) {
+                            warn!("Failed to auto-delete final file {:?}: {}", final_file, e);
+                        }
+                    } else if let Err(e) = self.manage_rolling_files(final_file, final_size_kb) {
+                        error!("Failed to manage rolling files in {}: {}", context, e);
+                    }
+                }
+            }
+        }
+    }
 }
 
 impl ProcessingNode for RecordNode {
@@ -496,6 +525,10 @@ impl ProcessingNode for RecordNode {
         false // RecordNode doesn't implement hot-reload yet (would require file management)
     }
 
+    fn shutdown(&mut self) {
+        self.finalize_recording("shutdown");
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -504,27 +537,7 @@ impl ProcessingNode for RecordNode {
 impl Drop for RecordNode {
     fn drop(&mut self) {
         // Ensure the WAV file is properly finalized when the node is dropped
-        if let Some(writer) = self.wav_writer.take() {
-            if let Err(e) = writer.finalize() {
-                error!("Failed to finalize WAV file in Drop: {}", e);
-            } else {
-                debug!("WAV file finalized in Drop for node '{}'", self.id);
-
-                // Add the final file to rolling management
-                if self.file_index > 0 {
-                    let final_file = self.get_current_file_path();
-                    let final_size_kb = self.current_size_bytes / 1024;
-
-                    if self.auto_delete && final_file.exists() {
-                        if let Err(e) = fs::remove_file(&final_file) {
-                            warn!("Failed to auto-delete final file {:?}: {}", final_file, e);
-                        }
-                    } else if let Err(e) = self.manage_rolling_files(final_file, final_size_kb) {
-                        error!("Failed to manage rolling files in Drop: {}", e);
-                    }
-                }
-            }
-        }
+        self.finalize_recording("Drop");
     }
 }
 
@@ -0,0 +1,487 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Spectral subtraction denoising node implementation
+//!
+//! This module provides the `SpectralSubtractionNode` which removes a stationary
+//! noise floor from audio signals. The noise power spectrum is learnt while the
+//! signal is known to be absent, then subtracted from the short-time spectra of
+//! the following frames.
+//!
+//! ### Algorithm
+//!
+//! Each channel is cut into blocks of `fft_size` samples overlapping by half,
+//! weighted by a square-root Hann window and transformed with a real FFT. For
+//! every bin of power `P` and learnt noise power `N`, the cleaned power is:
+//!
+//! ```text
+//! P' = max(P - α·N, β·N)
+//! ```
+//!
+//! where `α` is the over-subtraction factor and `β` the spectral floor. The bin
+//! is scaled by `sqrt(P'/P)`, keeping its phase, and the blocks are recombined
+//! by overlap-add with the same window. Over-subtraction removes the noise peaks
+//! that would otherwise remain as isolated tones ("musical noise"), and the floor
+//! keeps a little of the noise to mask the remaining ones.
+//!
+//! The output is delayed by `fft_size` samples and keeps the length of the input
+//! frames. While the noise is learnt, the signal goes through unchanged.
+
+use super::data::ProcessingData;
+use super::traits::ProcessingNode;
+use anyhow::{anyhow, Result};
+use log::debug;
+use realfft::{ComplexToReal, RealFftPlanner, RealToComplex};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// Streaming state of one channel
+#[derive(Clone)]
+struct ChannelState {
+    /// Samples not yet consumed by a full block
+    pending: Vec<f32>,
+    /// Overlap-add accumulator of `fft_size` samples
+    overlap: Vec<f32>,
+    /// Reconstructed samples waiting to be output
+    output: VecDeque<f32>,
+    /// Learnt noise power per bin
+    noise_power: Vec<f32>,
+    /// Number of blocks averaged into `noise_power`
+    noise_blocks: usize,
+    /// Number of blocks processed since the last reset
+    blocks: u64,
+}
+
+impl ChannelState {
+    /// Primed with half a block of silence before the signal, so that every
+    /// sample is covered by two blocks, and half a block of silence in the
+    /// output, so that each frame can be output in full
+    fn new(fft_size: usize) -> Self {
+        let hop = fft_size / 2;
+        Self {
+            pending: vec![0.0; hop],
+            overlap: vec![0.0; fft_size],
+            output: VecDeque::from(vec![0.0; hop]),
+            noise_power: vec![0.0; hop + 1],
+            noise_blocks: 0,
+            blocks: 0,
+        }
+    }
+}
+
+/// A processing node that removes a learnt noise spectrum from audio signals.
+///
+/// The node learns the noise power spectrum of each channel during its first
+/// `learn_frames` frames, which must be free of signal. Learning can be started
+/// again at any time with [`learn_noise`](SpectralSubtractionNode::learn_noise),
+/// or the `learn_frames` parameter, when the following frames are known to be
+/// signal-absent. It supports single-channel, dual-channel and audio frame data.
+///
+/// ### Examples
+///
+/// ```no_run
+/// use rust_photoacoustic::processing::nodes::{
+///     ProcessingData, ProcessingNode, SpectralSubtractionNode,
+/// };
+///
+/// let mut node = SpectralSubtractionNode::new("denoise".to_string())
+///     .with_fft_size(1024)
+///     .with_over_subtraction(2.0)
+///     .with_spectral_floor(0.02)
+///     .with_learn_frames(10);
+///
+/// let input = ProcessingData::SingleChannel {
+///     samples: vec![0.0; 4800],
+///     sample_rate: 48000,
+///     timestamp: 1000,
+///     frame_number: 1,
+/// };
+///
+/// let result = node.process(input)?;
+/// match result {
+///     ProcessingData::SingleChannel { samples, .. } => assert_eq!(samples.len(), 4800),
+///     _ => panic!("Expected SingleChannel output"),
+/// }
+/// assert!(node.is_learning());
+/// assert_eq!(node.latency_samples(), 1024);
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct SpectralSubtractionNode {
+    /// Unique identifier for this node
+    id: String,
+    /// Number of samples of each block
+    fft_size: usize,
+    /// Multiple of the noise power subtracted from each bin
+    over_subtraction: f32,
+    /// Fraction of the noise power kept in each bin
+    spectral_floor: f32,
+    /// Number of frames learnt after a reset
+    learn_frames: usize,
+    /// Number of frames still to learn
+    remaining_learn_frames: usize,
+    /// Square-root periodic Hann window
+    window: Vec<f32>,
+    forward: Arc<dyn RealToComplex<f32>>,
+    inverse: Arc<dyn ComplexToReal<f32>>,
+    /// Channel A, or the single channel, then channel B
+    channels: [ChannelState; 2],
+}
+
+impl SpectralSubtractionNode {
+    /// Create a new spectral subtraction node with default parameters.
+    ///
+    /// The defaults are 1024-sample blocks, an over-subtraction factor of 2,
+    /// a spectral floor of 0.02 and 10 learnt frames.
+    ///
+    /// ### Arguments
+    ///
+    /// * `id` - Unique identifier for this node
+    pub fn new(id: String) -> Self {
+        let fft_size = 1024;
+        let (window, forward, inverse) = Self::plan(fft_size);
+        Self {
+            id,
+            fft_size,
+            over_subtraction: 2.0,
+            spectral_floor: 0.02,
+            learn_frames: 10,
+            remaining_learn_frames: 10,
+            window,
+            forward,
+            inverse,
+            channels: [ChannelState::new(fft_size), ChannelState::new(fft_size)],
+        }
+    }
+
+    /// Set the number of samples of each block.
+    ///
+    /// Odd sizes are rounded down and sizes below 16 samples are ignored.
+    /// Changing the size discards the learnt noise and starts learning again.
+    ///
+    /// ### Arguments
+    ///
+    /// * `fft_size` - Block size in samples, the frequency resolution is `sample_rate / fft_size`
+    pub fn with_fft_size(mut self, fft_size: usize) -> Self {
+        self.set_fft_size(fft_size);
+        self
+    }
+
+    /// Set the multiple of the noise power subtracted from each bin.
+    ///
+    /// ### Arguments
+    ///
+    /// * `over_subtraction` - Over-subtraction factor, 1.0 subtracts the noise estimate as is
+    pub fn with_over_subtraction(mut self, over_subtraction: f32) -> Self {
+        self.over_subtraction = over_subtraction.max(0.0);
+        self
+    }
+
+    /// Set the fraction of the noise power kept in each bin.
+    ///
+    /// ### Arguments
+    ///
+    /// * `spectral_floor` - Spectral floor between 0.0 and 1.0
+    pub fn with_spectral_floor(mut self, spectral_floor: f32) -> Self {
+        self.spectral_floor = spectral_floor.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the number of frames learnt after creation or a reset.
+    ///
+    /// ### Arguments
+    ///
+    /// * `learn_frames` - Number of signal-absent frames at the start of the stream
+    pub fn with_learn_frames(mut self, learn_frames: usize) -> Self {
+        self.learn_frames = learn_frames;
+        self.remaining_learn_frames = learn_frames;
+        self
+    }
+
+    /// Discard the learnt noise and learn it again from the next frames.
+    ///
+    /// The frames are passed through unchanged until learning is over.
+    ///
+    /// ### Arguments
+    ///
+    /// * `frames` - Number of following frames known to be signal-absent
+    pub fn learn_noise(&mut self, frames: usize) {
+        for channel in &mut self.channels {
+            channel.noise_power.fill(0.0);
+            channel.noise_blocks = 0;
+        }
+        self.remaining_learn_frames = frames;
+    }
+
+    /// Whether the node is learning the noise spectrum
+    pub fn is_learning(&self) -> bool {
+        self.remaining_learn_frames > 0
+    }
+
+    /// Delay between the input and the output, in samples
+    pub fn latency_samples(&self) -> usize {
+        self.fft_size
+    }
+
+    /// Get the number of samples of each block
+    pub fn get_fft_size(&self) -> usize {
+        self.fft_size
+    }
+
+    /// Get the over-subtraction factor
+    pub fn get_over_subtraction(&self) -> f32 {
+        self.over_subtraction
+    }
+
+    /// Get the spectral floor
+    pub fn get_spectral_floor(&self) -> f32 {
+        self.spectral_floor
+    }
+
+    /// Window and FFT plans for blocks of `fft_size` samples
+    #[allow(clippy::type_complexity)]
+    fn plan(
+        fft_size: usize,
+    ) -> (
+        Vec<f32>,
+        Arc<dyn RealToComplex<f32>>,
+        Arc<dyn ComplexToReal<f32>>,
+    ) {
+        let window = (0..fft_size)
+            .map(|n| {
+                let phase = 2.0 * std::f32::consts::PI * n as f32 / fft_size as f32;
+                (0.5 - 0.5 * phase.cos()).sqrt()
+            })
+            .collect();
+        let mut planner = RealFftPlanner::<f32>::new();
+        (
+            window,
+            planner.plan_fft_forward(fft_size),
+            planner.plan_fft_inverse(fft_size),
+        )
+    }
+
+    /// Change the block size, returning whether it changed
+    fn set_fft_size(&mut self, fft_size: usize) -> bool {
+        let fft_size = fft_size & !1;
+        if fft_size < 16 || fft_size == self.fft_size {
+            return false;
+        }
+        let (window, forward, inverse) = Self::plan(fft_size);
+        self.fft_size = fft_size;
+        self.window = window;
+        self.forward = forward;
+        self.inverse = inverse;
+        self.reset();
+        true
+    }
+
+    /// Denoise the next `samples` of a channel
+    ///
+    /// ### Arguments
+    ///
+    /// * `channel` - 0 for channel A or a single channel, 1 for channel B
+    /// * `samples` - Input samples
+    /// * `learning` - Whether the samples are signal-absent
+    ///
+    /// ### Returns
+    ///
+    /// As many samples as the input, delayed by `fft_size` samples
+    fn process_channel(
+        &mut self,
+        channel: usize,
+        samples: &[f32],
+        learning: bool,
+    ) -> Result<Vec<f32>> {
+        let fft_size = self.fft_size;
+        let hop = fft_size / 2;
+        let scale = 1.0 / fft_size as f32;
+        let state = &mut self.channels[channel];
+
+        state.pending.extend_from_slice(samples);
+        let mut block = vec![0.0; fft_size];
+        let mut spectrum = self.forward.make_output_vec();
+        let mut start = 0;
+        while state.pending.len() - start >= fft_size {
+            for ((windowed, sample), weight) in block
+                .iter_mut()
+                .zip(&state.pending[start..start + fft_size])
+                .zip(&self.window)
+            {
+                *windowed = sample * weight;
+            }
+            self.forward
+                .process(&mut block, &mut spectrum)
+                .map_err(|e| anyhow!("FFT processing failed: {:?}", e))?;
+
+            // The first block is half priming silence
+            let priming = state.blocks == 0;
+            state.blocks += 1;
+            if learning {
+                if !priming {
+                    state.noise_blocks += 1;
+                    let count = state.noise_blocks as f32;
+                    for (noise, bin) in state.noise_power.iter_mut().zip(&spectrum) {
+                        *noise += (bin.norm_sqr() - *noise) / count;
+                    }
+                }
+            } else if state.noise_blocks > 0 {
+                for (bin, noise) in spectrum.iter_mut().zip(&state.noise_power) {
+                    let power = bin.norm_sqr();
+                    if power > 0.0 {
+                        let cleaned = (power - self.over_subtraction * noise)
+                            .max(self.spectral_floor * noise);
+                        *bin *= (cleaned / power).sqrt().min(1.0);
+                    }
+                }
+            }
+
+            self.inverse
+                .process(&mut spectrum, &mut block)
+                .map_err(|e| anyhow!("Inverse FFT processing failed: {:?}", e))?;
+            for ((sum, sample), weight) in state.overlap.iter_mut().zip(&block).zip(&self.window) {
+                *sum += sample * weight * scale;
+            }
+            state.output.extend(state.overlap.drain(..hop));
+            state.overlap.resize(fft_size, 0.0);
+            start += hop;
+        }
+        state.pending.drain(..start);
+
+        let missing = samples.len().saturating_sub(state.output.len());
+        let mut output = vec![0.0; missing];
+        output.extend(state.output.drain(..samples.len() - missing));
+        Ok(output)
+    }
+}
+
+impl ProcessingNode for SpectralSubtractionNode {
+    fn process(&mut self, input: ProcessingData) -> Result<ProcessingData> {
+        let learning = self.is_learning();
+        let output = match input {
+            ProcessingData::SingleChannel {
+                samples,
+                sample_rate,
+                timestamp,
+                frame_number,
+            } => ProcessingData::SingleChannel {
+                samples: self.process_channel(0, &samples, learning)?,
+                sample_rate,
+                timestamp,
+                frame_number,
+            },
+            ProcessingData::DualChannel {
+                channel_a,
+                channel_b,
+                sample_rate,
+                timestamp,
+                frame_number,
+            } => ProcessingData::DualChannel {
+                channel_a: self.process_channel(0, &channel_a, learning)?,
+                channel_b: self.process_channel(1, &channel_b, learning)?,
+                sample_rate,
+                timestamp,
+                frame_number,
+            },
+            ProcessingData::AudioFrame(mut frame) => {
+                frame.channel_a = self.process_channel(0, &frame.channel_a, learning)?;
+                frame.channel_b = self.process_channel(1, &frame.channel_b, learning)?;
+                ProcessingData::AudioFrame(frame)
+            }
+            ProcessingData::PhotoacousticResult { .. } => {
+                anyhow::bail!("SpectralSubtractionNode cannot process PhotoacousticResult data")
+            }
+        };
+
+        if learning {
+            self.remaining_learn_frames -= 1;
+            if !self.is_learning() {
+                debug!(
+                    "SpectralSubtractionNode '{}': Noise spectrum learnt",
+                    self.id
+                );
+            }
+        }
+        Ok(output)
+    }
+
+    fn node_id(&self) -> &str {
+        &self.id
+    }
+
+    fn node_type(&self) -> &str {
+        "spectral_subtraction"
+    }
+
+    fn accepts_input(&self, input: &ProcessingData) -> bool {
+        matches!(
+            input,
+            ProcessingData::SingleChannel { .. }
+                | ProcessingData::DualChannel { .. }
+                | ProcessingData::AudioFrame(_)
+        )
+    }
+
+    fn output_type(&self, input: &ProcessingData) -> Option<String> {
+        match input {
+            ProcessingData::SingleChannel { .. } => Some("SingleChannel".to_string()),
+            ProcessingData::DualChannel { .. } => Some("DualChannel".to_string()),
+            ProcessingData::AudioFrame(_) => Some("AudioFrame".to_string()),
+            ProcessingData::PhotoacousticResult { .. } => None,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.channels = [
+            ChannelState::new(self.fft_size),
+            ChannelState::new(self.fft_size),
+        ];
+        self.remaining_learn_frames = self.learn_frames;
+    }
+
+    fn clone_node(&self) -> Box<dyn ProcessingNode> {
+        Box::new(self.clone())
+    }
+
+    fn supports_hot_reload(&self) -> bool {
+        true
+    }
+
+    fn update_config(&mut self, parameters: &serde_json::Value) -> Result<bool> {
+        let mut updated = false;
+
+        if let Some(fft_size) = parameters.get("fft_size").and_then(|v| v.as_u64()) {
+            updated |= self.set_fft_size(fft_size as usize);
+        }
+
+        if let Some(factor) = parameters.get("over_subtraction").and_then(|v| v.as_f64()) {
+            self.over_subtraction = (factor as f32).max(0.0);
+            updated = true;
+        }
+
+        if let Some(floor) = parameters.get("spectral_floor").and_then(|v| v.as_f64()) {
+            self.spectral_floor = (floor as f32).clamp(0.0, 1.0);
+            updated = true;
+        }
+
+        // Flags the next frames as signal-absent
+        if let Some(frames) = parameters.get("learn_frames").and_then(|v| v.as_u64()) {
+            self.learn_frames = frames as usize;
+            self.learn_noise(frames as usize);
+            updated = true;
+        }
+
+        if updated {
+            debug!(
+                "SpectralSubtractionNode '{}': Configuration updated (fft_size: {}, over_subtraction: {}, spectral_floor: {})",
+                self.id, self.fft_size, self.over_subtraction, self.spectral_floor
+            );
+        }
+        Ok(updated)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
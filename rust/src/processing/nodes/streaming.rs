@@ -396,6 +396,7 @@ mod tests {
                 sample_rate: 44100,
                 processing_steps: vec!["test".to_string()],
                 processing_latency_us: 100,
+                snr_db: None,
             },
         };
         assert!(!node.accepts_input(&photoacoustic));
@@ -492,6 +493,7 @@ mod tests {
                 sample_rate: 44100,
                 processing_steps: vec!["test".to_string()],
                 processing_latency_us: 100,
+                snr_db: None,
             },
         };
         let converted = node.convert_to_audio_frame(&photoacoustic);
@@ -8,6 +8,7 @@
 //! to participate in the audio processing graph.
 
 use super::data::ProcessingData;
+use crate::processing::buffer_pool::FrameBufferPool;
 use crate::processing::computing_nodes::SharedComputingState;
 use anyhow::Result;
 
@@ -110,6 +111,31 @@ pub trait ProcessingNode: Send + Sync {
     /// ```
     fn process(&mut self, input: ProcessingData) -> Result<ProcessingData>;
 
+    /// Process input data using sample buffers drawn from a pool
+    ///
+    /// Called instead of [`process`](Self::process) when the graph reuses its
+    /// buffers. Nodes that produce new sample buffers override it to take them
+    /// from `pool` and to give back the input buffers they no longer need.
+    /// The output must be identical to the one of `process`.
+    ///
+    /// ### Arguments
+    ///
+    /// * `input` - The input data to process, owned by the node
+    /// * `pool` - Pool providing and receiving sample buffers
+    ///
+    /// ### Returns
+    ///
+    /// * `Ok(ProcessingData)` - Successfully processed output data
+    /// * `Err(anyhow::Error)` - Processing error with details
+    fn process_pooled(
+        &mut self,
+        input: ProcessingData,
+        _pool: &FrameBufferPool,
+    ) -> Result<ProcessingData> {
+        // Default implementation: the allocating path
+        self.process(input)
+    }
+
     /// Get the node's unique identifier
     ///
     /// Returns the unique ID assigned to this node instance.
@@ -301,6 +327,30 @@ pub trait ProcessingNode: Send + Sync {
         None
     }
 
+    /// Number of calls interrupted by the node's execution timeout
+    ///
+    /// Nodes running user code under a time limit, such as
+    /// [`PythonNode`](crate::processing::nodes::PythonNode), report here the
+    /// total since their creation. The graph adds it to the node statistics.
+    ///
+    /// ### Returns
+    ///
+    /// The cumulative number of timeouts, `0` for nodes without a time limit
+    fn timeout_count(&self) -> u64 {
+        0
+    }
+
+    /// Flush pending work before the application exits
+    ///
+    /// Called once by [`ProcessingGraph::shutdown`](crate::processing::ProcessingGraph::shutdown)
+    /// after the last frame has been processed. Nodes holding external
+    /// resources (open files, driver connections, queued sends) finalize
+    /// them here instead of relying on `Drop`, so that the work completes
+    /// within the daemon's shutdown deadline. The node is not used afterwards.
+    fn shutdown(&mut self) {
+        // Default implementation: nothing to flush
+    }
+
     /// Get a reference to this node as Any for downcasting
     ///
     /// This method allows safe downcasting of ProcessingNode trait objects
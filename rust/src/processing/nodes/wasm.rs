@@ -0,0 +1,704 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! WebAssembly plugin node implementation
+//!
+//! This module implements a processing node running third-party processing
+//! logic compiled to WebAssembly, with the [`wasmtime`] runtime. Unlike the
+//! Python and Lua nodes, the plugin runs in a sandbox: it only sees its own
+//! linear memory, it is given no imports (no file system, network or clock),
+//! its memory is capped and each call is interrupted past a deadline. It is
+//! available with the `wasm-node` feature.
+//!
+//! # Host ABI
+//!
+//! The module must export:
+//!
+//! | Export | Signature | Role |
+//! |---|---|---|
+//! | `memory` | memory | Linear memory holding the sample buffers |
+//! | `alloc` | `(size: i32) -> i32` | Reserve `size` bytes, return their offset or `0` when out of memory |
+//! | `process` | `(channel_a: i32, channel_b: i32, len: i32, channel_out: i32) -> i32` | Process one frame |
+//!
+//! Samples are little-endian `f32`. The host calls `alloc` for three buffers of
+//! `len` samples each, writes channel A and channel B into the first two,
+//! then calls `process` with their offsets and the offset of the output
+//! buffer. `process` writes its output samples, and returns their number
+//! (at most `len`), or a negative error code. The buffers are reused for the
+//! following frames, and `alloc` is only called again when a frame is longer
+//! than the previous ones, so a bump allocator that never frees is enough.
+//!
+//! A single channel frame is passed as both channel A and channel B. The node
+//! outputs a single channel frame with the timing of its input.
+//!
+//! A plugin doubling channel A, in the WebAssembly text format:
+//!
+//! ```wat
+//! (module
+//!   (memory (export "memory") 1)
+//!   (global $next (mut i32) (i32.const 1024))
+//!   (func (export "alloc") (param $size i32) (result i32)
+//!     (local $ptr i32)
+//!     (local.set $ptr (global.get $next))
+//!     (global.set $next (i32.add (global.get $next) (local.get $size)))
+//!     (block $done
+//!       (loop $grow
+//!         (br_if $done (i32.le_u (global.get $next)
+//!                                (i32.mul (memory.size) (i32.const 65536))))
+//!         (if (i32.eq (memory.grow (i32.const 1)) (i32.const -1))
+//!           (then (return (i32.const 0))))
+//!         (br $grow)))
+//!     (local.get $ptr))
+//!   (func (export "process")
+//!     (param $a i32) (param $b i32) (param $len i32) (param $out i32) (result i32)
+//!     (local $i i32)
+//!     (block $end
+//!       (loop $next_sample
+//!         (br_if $end (i32.ge_u (local.get $i) (local.get $len)))
+//!         (f32.store
+//!           (i32.add (local.get $out) (i32.shl (local.get $i) (i32.const 2)))
+//!           (f32.mul
+//!             (f32.load (i32.add (local.get $a) (i32.shl (local.get $i) (i32.const 2))))
+//!             (f32.const 2)))
+//!         (local.set $i (i32.add (local.get $i) (i32.const 1)))
+//!         (br $next_sample)))
+//!     (local.get $len)))
+//! ```
+//!
+//! # Limits
+//!
+//! - `memory_limit_mb`: maximum size of the plugin memory; `memory.grow`
+//!   beyond it fails, and the plugin reports it by returning `0` from `alloc`
+//! - `timeout_ms`: maximum duration of a call (`0` disables the limit); the
+//!   call is trapped, the frame fails and the graph drops it. Timeouts are
+//!   counted in the `timeouts` field of the node statistics
+//!
+//! # Configuration
+//!
+//! ```yaml
+//! - id: "wasm_plugin"
+//!   node_type: "wasm"
+//!   parameters:
+//!     module_path: plugins/doubler.wasm  # Binary (.wasm) or text (.wat) module
+//!     memory_limit_mb: 64
+//!     timeout_ms: 100
+//! ```
+
+use anyhow::{anyhow, Result};
+use log::{debug, info, warn};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex, Once};
+use std::time::{Duration, Instant};
+use wasmtime::{
+    Config, Engine, Instance, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, Trap,
+    TypedFunc,
+};
+
+use super::data::ProcessingData;
+use super::traits::ProcessingNode;
+
+/// WebAssembly plugin node configuration
+///
+/// # Example
+///
+/// ```rust
+/// use rust_photoacoustic::processing::nodes::WasmNodeConfig;
+/// use std::path::PathBuf;
+///
+/// let config = WasmNodeConfig {
+///     module_path: PathBuf::from("doubler.wasm"),
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(config.memory_limit_mb, 64);
+/// assert_eq!(config.timeout_ms, 1000);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WasmNodeConfig {
+    /// Path to the WebAssembly module, binary or text format
+    pub module_path: PathBuf,
+    /// Maximum size of the plugin linear memory (MiB)
+    pub memory_limit_mb: u64,
+    /// Maximum execution time of a plugin call (milliseconds), `0` disables the limit
+    pub timeout_ms: u64,
+}
+
+impl Default for WasmNodeConfig {
+    fn default() -> Self {
+        Self {
+            module_path: PathBuf::from("plugin.wasm"),
+            memory_limit_mb: 64,
+            timeout_ms: 1000,
+        }
+    }
+}
+
+/// Data owned by the store of a plugin
+struct PluginState {
+    limits: StoreLimits,
+}
+
+/// Instantiated plugin with its sandbox
+struct WasmPlugin {
+    store: Store<PluginState>,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    process: TypedFunc<(i32, i32, i32, i32), i32>,
+    /// Offsets of the channel A, channel B and output buffers, and their capacity in samples
+    buffers: Option<([i32; 3], usize)>,
+}
+
+/// Processing node running a sandboxed WebAssembly plugin
+///
+/// The module is instantiated on the first frame, or by
+/// [`WasmNode::reload`]. See the [module documentation](self) for the ABI.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use rust_photoacoustic::processing::nodes::{
+///     ProcessingData, ProcessingNode, WasmNode, WasmNodeConfig,
+/// };
+/// use std::path::PathBuf;
+///
+/// # fn example() -> anyhow::Result<()> {
+/// let mut node = WasmNode::new(
+///     "wasm_plugin".to_string(),
+///     WasmNodeConfig {
+///         module_path: PathBuf::from("doubler.wasm"),
+///         ..Default::default()
+///     },
+/// )?;
+///
+/// let output = node.process(ProcessingData::DualChannel {
+///     channel_a: vec![0.1, 0.2],
+///     channel_b: vec![0.3, 0.4],
+///     sample_rate: 48000,
+///     timestamp: 0,
+///     frame_number: 1,
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct WasmNode {
+    id: String,
+    config: WasmNodeConfig,
+    engine: Engine,
+    /// Plugin instance, `None` until the first frame
+    plugin: Option<WasmPlugin>,
+    watchdog: WasmWatchdog,
+    timeouts: u64,
+}
+
+impl std::fmt::Debug for WasmNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmNode")
+            .field("id", &self.id)
+            .field("config", &self.config)
+            .field("loaded", &self.plugin.is_some())
+            .finish()
+    }
+}
+
+/// Interrupts plugin calls running longer than their time limit
+///
+/// Each node owns one watchdog. Its thread is started by the first guarded
+/// call and sleeps until the deadline of the call in progress, then advances
+/// the engine epoch, which traps the call. Calls are numbered, and the epoch
+/// only advances while the late call is still armed, under the lock taken to
+/// disarm it, so a deadline reached as a call returns never interrupts the
+/// next one.
+///
+/// Dropping the watchdog stops its thread.
+#[derive(Default)]
+struct WasmWatchdog {
+    shared: Arc<WatchdogShared>,
+    started: Once,
+}
+
+#[derive(Default)]
+struct WatchdogShared {
+    state: Mutex<WatchdogState>,
+    /// Signalled when a call is armed or the watchdog is dropped
+    wake: Condvar,
+}
+
+#[derive(Default)]
+struct WatchdogState {
+    /// Number and deadline of the call in progress
+    armed: Option<(u64, Instant)>,
+    next_call: u64,
+    shutdown: bool,
+}
+
+impl WasmWatchdog {
+    /// Start watching a call, just before making it
+    ///
+    /// ### Parameters
+    ///
+    /// * `engine` - Engine of the store running the call
+    /// * `timeout` - Time limit of the call
+    ///
+    /// ### Returns
+    ///
+    /// The number of the call, to pass to [`Self::disarm`]
+    fn arm(&self, engine: &Engine, timeout: Duration) -> u64 {
+        self.started.call_once(|| {
+            let shared = Arc::clone(&self.shared);
+            let engine = engine.clone();
+            std::thread::spawn(move || Self::run(&shared, &engine));
+        });
+
+        let mut state = self.shared.state.lock().unwrap();
+        let call = state.next_call;
+        state.next_call += 1;
+        state.armed = Some((call, Instant::now() + timeout));
+        drop(state);
+        self.shared.wake.notify_one();
+        call
+    }
+
+    /// Stop watching call number `call` once it has returned
+    fn disarm(&self, call: u64) {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.armed.is_some_and(|(armed, _)| armed == call) {
+            state.armed = None;
+        }
+    }
+
+    /// Watchdog thread: wait for the deadline and interrupt the late call
+    fn run(shared: &WatchdogShared, engine: &Engine) {
+        let mut state = shared.state.lock().unwrap();
+        loop {
+            if state.shutdown {
+                return;
+            }
+            let now = Instant::now();
+            state = match state.armed {
+                None => shared.wake.wait(state).unwrap(),
+                Some((_, deadline)) if deadline > now => {
+                    shared.wake.wait_timeout(state, deadline - now).unwrap().0
+                }
+                Some(_) => {
+                    // Still armed: the call has not returned yet
+                    engine.increment_epoch();
+                    state.armed = None;
+                    state
+                }
+            };
+        }
+    }
+}
+
+impl Drop for WasmWatchdog {
+    fn drop(&mut self) {
+        self.shared.state.lock().unwrap().shutdown = true;
+        self.shared.wake.notify_one();
+    }
+}
+
+impl WasmNode {
+    /// Create a new WebAssembly plugin node
+    ///
+    /// ### Parameters
+    ///
+    /// * `id` - Unique identifier for this node
+    /// * `config` - Module and sandbox limits
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if the WebAssembly engine cannot be created
+    pub fn new(id: String, config: WasmNodeConfig) -> Result<Self> {
+        Ok(Self {
+            id,
+            config,
+            engine: Self::create_engine()?,
+            plugin: None,
+            watchdog: WasmWatchdog::default(),
+            timeouts: 0,
+        })
+    }
+
+    /// Create a WebAssembly node from the parameters of its graph configuration
+    ///
+    /// ### Parameters
+    ///
+    /// * `id` - Unique identifier for this node
+    /// * `config` - `module_path` (required), `memory_limit_mb` and `timeout_ms`
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if `module_path` is missing
+    pub fn from_config(id: String, config: HashMap<String, Value>) -> Result<Self> {
+        let mut node_config = WasmNodeConfig {
+            module_path: config
+                .get("module_path")
+                .and_then(|v| v.as_str())
+                .map(PathBuf::from)
+                .ok_or_else(|| anyhow!("WebAssembly node requires module_path parameter"))?,
+            ..Default::default()
+        };
+
+        if let Some(memory_limit_mb) = config.get("memory_limit_mb").and_then(|v| v.as_u64()) {
+            node_config.memory_limit_mb = memory_limit_mb;
+        }
+
+        if let Some(timeout_ms) = config.get("timeout_ms").and_then(|v| v.as_u64()) {
+            node_config.timeout_ms = timeout_ms;
+        }
+
+        Self::new(id, node_config)
+    }
+
+    /// Get the current configuration
+    pub fn config(&self) -> &WasmNodeConfig {
+        &self.config
+    }
+
+    /// Number of plugin calls interrupted by the timeout since the node was created
+    pub fn timeout_count(&self) -> u64 {
+        self.timeouts
+    }
+
+    /// Instantiate the module again, with a fresh memory
+    ///
+    /// The previous instance keeps running if the new module cannot be
+    /// compiled, lacks an export of the ABI or fails to instantiate.
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if the module cannot be loaded or instantiated
+    pub fn reload(&mut self) -> Result<()> {
+        let plugin = self.instantiate()?;
+        self.plugin = Some(plugin);
+        info!(
+            "WebAssembly node '{}' reloaded {:?}",
+            self.id, self.config.module_path
+        );
+        Ok(())
+    }
+
+    /// Create an engine interrupting calls when its epoch advances
+    ///
+    /// Each node owns its engine, so that a timeout in one node does not
+    /// interrupt the calls of another.
+    fn create_engine() -> Result<Engine> {
+        let mut engine_config = Config::new();
+        engine_config.epoch_interruption(true);
+        Engine::new(&engine_config)
+            .map_err(|e| anyhow!("Failed to create WebAssembly engine: {}", e))
+    }
+
+    /// Compile the module and instantiate it in a new store
+    fn instantiate(&mut self) -> Result<WasmPlugin> {
+        let module = Module::from_file(&self.engine, &self.config.module_path).map_err(|e| {
+            anyhow!(
+                "Failed to load WebAssembly module {:?}: {}",
+                self.config.module_path,
+                e
+            )
+        })?;
+
+        let memory_limit = usize::try_from(self.config.memory_limit_mb)
+            .ok()
+            .and_then(|mb| mb.checked_mul(1024 * 1024))
+            .unwrap_or(usize::MAX);
+        let mut store = Store::new(
+            &self.engine,
+            PluginState {
+                limits: StoreLimitsBuilder::new()
+                    .memory_size(memory_limit)
+                    .instances(1)
+                    .build(),
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+
+        // No imports: the plugin can only compute on its own memory
+        let instance = self.with_deadline(&mut store, "instantiate", |store| {
+            Instance::new(store, &module, &[])
+        })?;
+
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| anyhow!("WebAssembly module does not export 'memory'"))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&mut store, "alloc")
+            .map_err(|e| anyhow!("WebAssembly module export 'alloc': {}", e))?;
+        let process = instance
+            .get_typed_func::<(i32, i32, i32, i32), i32>(&mut store, "process")
+            .map_err(|e| anyhow!("WebAssembly module export 'process': {}", e))?;
+
+        debug!(
+            "WebAssembly node '{}' instantiated {:?}",
+            self.id, self.config.module_path
+        );
+        Ok(WasmPlugin {
+            store,
+            memory,
+            alloc,
+            process,
+            buffers: None,
+        })
+    }
+
+    /// Run `call` in `store`, trapping it once `timeout_ms` has elapsed
+    fn with_deadline<T>(
+        &mut self,
+        store: &mut Store<PluginState>,
+        function_name: &str,
+        call: impl FnOnce(&mut Store<PluginState>) -> wasmtime::Result<T>,
+    ) -> Result<T> {
+        // The call traps as soon as the engine epoch advances
+        store.set_epoch_deadline(1);
+        let watched = (self.config.timeout_ms > 0).then(|| {
+            self.watchdog
+                .arm(&self.engine, Duration::from_millis(self.config.timeout_ms))
+        });
+
+        let result = call(store);
+        if let Some(watched) = watched {
+            self.watchdog.disarm(watched);
+        }
+
+        result.map_err(|e| {
+            if matches!(e.downcast_ref::<Trap>(), Some(Trap::Interrupt)) {
+                self.timeouts += 1;
+                warn!(
+                    "WebAssembly node '{}': '{}' interrupted, exceeding its timeout of {} ms",
+                    self.id, function_name, self.config.timeout_ms
+                );
+                anyhow!(
+                    "WebAssembly '{}' interrupted after exceeding its timeout of {} ms",
+                    function_name,
+                    self.config.timeout_ms
+                )
+            } else {
+                anyhow!("WebAssembly '{}' failed: {}", function_name, e)
+            }
+        })
+    }
+
+    /// Run the plugin on one frame
+    fn process_samples(
+        &mut self,
+        plugin: &mut WasmPlugin,
+        channel_a: &[f32],
+        channel_b: &[f32],
+    ) -> Result<Vec<f32>> {
+        if channel_a.len() != channel_b.len() {
+            return Err(anyhow!(
+                "Channel lengths differ: {} and {} samples",
+                channel_a.len(),
+                channel_b.len()
+            ));
+        }
+        let len = channel_a.len();
+        let len_i32 =
+            i32::try_from(len).map_err(|_| anyhow!("Frame of {} samples is too long", len))?;
+        let size = len_i32
+            .checked_mul(4)
+            .ok_or_else(|| anyhow!("Frame of {} samples is too long", len))?;
+
+        // Allocate the buffers the first time, and for longer frames
+        let offsets = match plugin.buffers {
+            Some((offsets, capacity)) if capacity >= len => offsets,
+            _ => {
+                let alloc = plugin.alloc.clone();
+                let mut offsets = [0; 3];
+                for offset in offsets.iter_mut() {
+                    *offset = self.with_deadline(&mut plugin.store, "alloc", |store| {
+                        alloc.call(store, size)
+                    })?;
+                    if *offset <= 0 {
+                        return Err(anyhow!(
+                            "WebAssembly plugin could not allocate {} bytes (memory limit {} MiB)",
+                            size,
+                            self.config.memory_limit_mb
+                        ));
+                    }
+                }
+                plugin.buffers = Some((offsets, len));
+                offsets
+            }
+        };
+
+        for (offset, samples) in offsets.iter().zip([channel_a, channel_b]) {
+            let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+            plugin
+                .memory
+                .write(&mut plugin.store, *offset as usize, &bytes)
+                .map_err(|e| anyhow!("Invalid buffer offset {} from alloc: {}", offset, e))?;
+        }
+
+        let process = plugin.process.clone();
+        let [a, b, out] = offsets;
+        let written = self.with_deadline(&mut plugin.store, "process", |store| {
+            process.call(store, (a, b, len_i32, out))
+        })?;
+        if written < 0 {
+            return Err(anyhow!(
+                "WebAssembly plugin returned error code {}",
+                written
+            ));
+        }
+        if written > len_i32 {
+            return Err(anyhow!(
+                "WebAssembly plugin wrote {} samples for a frame of {}",
+                written,
+                len
+            ));
+        }
+
+        let mut bytes = vec![0u8; written as usize * 4];
+        plugin
+            .memory
+            .read(&plugin.store, out as usize, &mut bytes)
+            .map_err(|e| anyhow!("Invalid output offset {}: {}", out, e))?;
+        Ok(bytes
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect())
+    }
+}
+
+impl ProcessingNode for WasmNode {
+    fn process(&mut self, input: ProcessingData) -> Result<ProcessingData> {
+        let (channel_a, channel_b, sample_rate, timestamp, frame_number) = match &input {
+            ProcessingData::AudioFrame(frame) => (
+                frame.channel_a.as_slice(),
+                frame.channel_b.as_slice(),
+                frame.sample_rate,
+                frame.timestamp,
+                frame.frame_number,
+            ),
+            ProcessingData::DualChannel {
+                channel_a,
+                channel_b,
+                sample_rate,
+                timestamp,
+                frame_number,
+            } => (
+                channel_a.as_slice(),
+                channel_b.as_slice(),
+                *sample_rate,
+                *timestamp,
+                *frame_number,
+            ),
+            ProcessingData::SingleChannel {
+                samples,
+                sample_rate,
+                timestamp,
+                frame_number,
+            } => (
+                samples.as_slice(),
+                samples.as_slice(),
+                *sample_rate,
+                *timestamp,
+                *frame_number,
+            ),
+            ProcessingData::PhotoacousticResult { .. } => {
+                return Err(anyhow!(
+                    "WebAssembly node '{}' does not accept PhotoacousticResult data",
+                    self.id
+                ))
+            }
+        };
+
+        let mut plugin = match self.plugin.take() {
+            Some(plugin) => plugin,
+            None => self.instantiate()?,
+        };
+        // The instance is kept even when the call fails
+        let result = self.process_samples(&mut plugin, channel_a, channel_b);
+        self.plugin = Some(plugin);
+
+        Ok(ProcessingData::SingleChannel {
+            samples: result?,
+            sample_rate,
+            timestamp,
+            frame_number,
+        })
+    }
+
+    fn node_id(&self) -> &str {
+        &self.id
+    }
+
+    fn node_type(&self) -> &str {
+        "wasm"
+    }
+
+    fn accepts_input(&self, input: &ProcessingData) -> bool {
+        !matches!(input, ProcessingData::PhotoacousticResult { .. })
+    }
+
+    fn output_type(&self, input: &ProcessingData) -> Option<String> {
+        if self.accepts_input(input) {
+            Some("SingleChannel".to_string())
+        } else {
+            None
+        }
+    }
+
+    fn reset(&mut self) {
+        // The module is instantiated again, with a fresh memory, on the next frame
+        self.plugin = None;
+        debug!("WebAssembly node '{}' reset", self.id);
+    }
+
+    fn clone_node(&self) -> Box<dyn ProcessingNode> {
+        Box::new(Self {
+            id: self.id.clone(),
+            config: self.config.clone(),
+            engine: Self::create_engine().unwrap_or_else(|_| self.engine.clone()),
+            plugin: None,
+            timeouts: 0,
+        })
+    }
+
+    fn timeout_count(&self) -> u64 {
+        self.timeouts
+    }
+
+    fn supports_hot_reload(&self) -> bool {
+        true // The module can be replaced in place
+    }
+
+    fn update_config(&mut self, parameters: &Value) -> Result<bool> {
+        let Value::Object(params) = parameters else {
+            anyhow::bail!("WebAssembly node parameters must be an object");
+        };
+
+        if let Some(timeout_ms) = params.get("timeout_ms") {
+            self.config.timeout_ms = timeout_ms
+                .as_u64()
+                .ok_or_else(|| anyhow!("timeout_ms parameter must be an integer"))?;
+        }
+
+        // Setting module_path, even to its current value, instantiates the module again
+        if let Some(module_path) = params.get("module_path") {
+            let module_path = module_path
+                .as_str()
+                .ok_or_else(|| anyhow!("module_path parameter must be a string"))?;
+            let previous = std::mem::replace(&mut self.config.module_path, module_path.into());
+            if let Err(e) = self.reload() {
+                self.config.module_path = previous;
+                return Err(e);
+            }
+        }
+
+        // The memory limit is fixed when the store is created
+        let requires_reconstruction = params
+            .keys()
+            .any(|key| !matches!(key.as_str(), "timeout_ms" | "module_path"));
+        Ok(!requires_reconstruction)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
@@ -55,9 +55,19 @@
 //! 3. Average multiple FFTs if enabled (to reduce noise)
 //! 4. Extract amplitude and phase information
 //! 5. Return the results as a `SpectrumData` structure
+//!
+//! # Buffer Reuse
+//!
+//! `FFTAnalyzer` keeps its FFT plan, window coefficients, complex buffers and
+//! the spectra of its averaging history between calls. They are rebuilt only
+//! when the window size or window function changes, so after the first
+//! `averages` calls an analysis only allocates the vectors of the returned
+//! `SpectrumData`.
 
 use anyhow::Result;
-use rustfft::{num_complex::Complex32, FftPlanner};
+use rustfft::{num_complex::Complex32, Fft, FftPlanner, Length};
+use std::collections::VecDeque;
+use std::sync::Arc;
 
 /// Trait for implementing spectral analysis algorithms
 ///
@@ -236,9 +246,25 @@ pub struct FFTAnalyzer {
 
     /// Storage for previous FFT outputs for averaging
     ///
-    /// This vector stores the complex FFT outputs from previous frames
-    /// to enable spectral averaging.
-    previous_spectra: Vec<Vec<Complex32>>,
+    /// This queue stores the complex FFT outputs from previous frames
+    /// to enable spectral averaging. The buffer of the oldest frame is
+    /// reused for the newest one.
+    previous_spectra: VecDeque<Vec<Complex32>>,
+
+    /// FFT plan, rebuilt when the window size changes
+    ///
+    /// The plan is shared through an `Arc` and is `Send + Sync`, so the
+    /// analyzer stays usable as a `SpectralAnalyzer` across threads.
+    fft_plan: Option<Arc<dyn Fft<f32>>>,
+
+    /// Window coefficients for the current window function and size
+    window: Option<(WindowFunction, Vec<f32>)>,
+
+    /// Scratch buffer of the FFT plan
+    fft_scratch: Vec<Complex32>,
+
+    /// Averaged spectrum of the history
+    average: Vec<Complex32>,
 }
 
 impl FFTAnalyzer {
@@ -274,7 +300,89 @@ impl FFTAnalyzer {
             averages,
             window_function: WindowFunction::Hann, // Default to Hann window
             spectrum_data: None,
-            previous_spectra: Vec::with_capacity(averages),
+            previous_spectra: VecDeque::with_capacity(averages + 1),
+            fft_plan: None,
+            window: None,
+            fft_scratch: Vec::new(),
+            average: Vec::new(),
+        }
+    }
+
+    /// Set the window function applied before the FFT (builder pattern)
+    ///
+    /// ### Parameters
+    ///
+    /// * `window_function` - The window function to apply
+    ///
+    /// ### Returns
+    ///
+    /// The analyzer with the new window function
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use rust_photoacoustic::spectral::fft::{FFTAnalyzer, WindowFunction};
+    ///
+    /// let analyzer = FFTAnalyzer::new(2048, 1).with_window_function(WindowFunction::Blackman);
+    /// assert_eq!(analyzer.window_function(), WindowFunction::Blackman);
+    /// ```
+    pub fn with_window_function(mut self, window_function: WindowFunction) -> Self {
+        self.window_function = window_function;
+        self
+    }
+
+    /// Get the window function applied before the FFT
+    pub fn window_function(&self) -> WindowFunction {
+        self.window_function
+    }
+
+    /// Get the size of the FFT window in samples
+    pub fn frame_size(&self) -> usize {
+        self.frame_size
+    }
+
+    /// Change the size of the FFT window
+    ///
+    /// The averaging history is cleared because its spectra have the old size.
+    /// The cached FFT plan and window coefficients are rebuilt on the next
+    /// call to `analyze`.
+    ///
+    /// ### Parameters
+    ///
+    /// * `frame_size` - The new size of the FFT window in samples
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use rust_photoacoustic::spectral::fft::{FFTAnalyzer, SpectralAnalyzer};
+    ///
+    /// let mut analyzer = FFTAnalyzer::new(1024, 1);
+    /// analyzer.set_frame_size(2048);
+    /// let spectrum = analyzer.analyze(&vec![0.0f32; 2048], 44100).unwrap();
+    /// assert_eq!(spectrum.frequencies.len(), 1024);
+    /// ```
+    pub fn set_frame_size(&mut self, frame_size: usize) {
+        if frame_size != self.frame_size {
+            self.frame_size = frame_size;
+            self.previous_spectra.clear();
+        }
+    }
+
+    /// Coefficient of `window_function` at sample `i` of a window of `len` samples
+    fn window_factor(window_function: WindowFunction, i: usize, len: usize) -> f32 {
+        match window_function {
+            WindowFunction::Rectangular => 1.0,
+            WindowFunction::Hann => {
+                0.5 * (1.0 - (2.0 * std::f32::consts::PI * i as f32 / (len - 1) as f32).cos())
+            }
+            WindowFunction::Blackman => {
+                let a0 = 0.42;
+                let a1 = 0.5;
+                let a2 = 0.08;
+                let x = i as f32 / (len - 1) as f32;
+                a0 - a1 * (2.0 * std::f32::consts::PI * x).cos()
+                    + a2 * (4.0 * std::f32::consts::PI * x).cos()
+            }
         }
     }
 
@@ -313,69 +421,70 @@ impl FFTAnalyzer {
     /// assert!(windowed_signal[signal.len() - 1] < signal[signal.len() - 1]);
     /// ```
     pub fn apply_window(&self, signal: &[f32]) -> Vec<f32> {
-        let mut windowed = Vec::with_capacity(signal.len());
-
-        for (i, &sample) in signal.iter().enumerate() {
-            let window_factor = match self.window_function {
-                WindowFunction::Rectangular => 1.0,
-                WindowFunction::Hann => {
-                    0.5 * (1.0
-                        - (2.0 * std::f32::consts::PI * i as f32 / (signal.len() - 1) as f32).cos())
-                }
-                WindowFunction::Blackman => {
-                    let a0 = 0.42;
-                    let a1 = 0.5;
-                    let a2 = 0.08;
-                    let x = i as f32 / (signal.len() - 1) as f32;
-                    a0 - a1 * (2.0 * std::f32::consts::PI * x).cos()
-                        + a2 * (4.0 * std::f32::consts::PI * x).cos()
-                }
-            };
-
-            windowed.push(sample * window_factor);
+        signal
+            .iter()
+            .enumerate()
+            .map(|(i, &sample)| sample * Self::window_factor(self.window_function, i, signal.len()))
+            .collect()
+    }
+
+    /// Rebuild the cached FFT plan and window coefficients if the window size
+    /// or window function changed since the last call
+    fn prepare(&mut self) {
+        let n = self.frame_size;
+        if self.fft_plan.as_ref().map(|plan| plan.len()) != Some(n) {
+            let plan = FftPlanner::new().plan_fft_forward(n);
+            self.fft_scratch = vec![Complex32::new(0.0, 0.0); plan.get_inplace_scratch_len()];
+            self.fft_plan = Some(plan);
         }
 
-        windowed
+        let window_function = self.window_function;
+        let stale = match &self.window {
+            Some((function, coefficients)) => {
+                *function != window_function || coefficients.len() != n
+            }
+            None => true,
+        };
+        if stale {
+            let coefficients = (0..n)
+                .map(|i| Self::window_factor(window_function, i, n))
+                .collect();
+            self.window = Some((window_function, coefficients));
+        }
     }
 
-    /// Compute FFT of the input signal
+    /// Compute the FFT of the windowed input signal into `output`
     ///
-    /// This method applies the Fast Fourier Transform algorithm to convert
-    /// a time-domain signal into the frequency domain. It uses the rustfft
-    /// library for efficient FFT computation.
+    /// This method applies the cached window coefficients to the input signal
+    /// and runs the cached FFT plan in place, using the cached scratch buffer.
+    /// `prepare()` must have been called for the current window size.
     ///
     /// ### Parameters
     ///
-    /// * `signal` - The time-domain signal to transform
-    ///
-    /// ### Returns
-    ///
-    /// A vector of complex numbers representing the frequency-domain signal
+    /// * `signal` - The time-domain signal to transform, `frame_size` samples long
+    /// * `output` - Buffer receiving the frequency-domain signal, resized to `frame_size`
     ///
     /// ### Implementation Details
     ///
-    /// The method:
-    /// 1. Converts the real input signal to complex numbers with zero imaginary part
-    /// 2. Creates an FFT plan using the rustfft library
-    /// 3. Executes the FFT in-place on the complex array
-    /// 4. Returns the transformed data for further processing
-    ///
     /// The output contains both positive and negative frequency components,
     /// with the DC (0 Hz) component at index 0 and the Nyquist frequency
     /// component at index N/2 (where N is the signal length).
-    fn compute_fft(&mut self, signal: &[f32]) -> Vec<Complex32> {
-        // Convert input to complex numbers
-        let mut complex_input: Vec<Complex32> =
-            signal.iter().map(|&x| Complex32::new(x, 0.0)).collect();
+    fn compute_fft(&mut self, signal: &[f32], output: &mut Vec<Complex32>) {
+        let (Some(plan), Some((_, window))) = (&self.fft_plan, &self.window) else {
+            unreachable!("FFT plan prepared before computing the FFT");
+        };
 
-        // Create FFT planner and get FFT algorithm
-        let mut planner = FftPlanner::new();
-        let fft = planner.plan_fft_forward(signal.len());
+        // Convert the windowed input to complex numbers
+        output.clear();
+        output.extend(
+            signal
+                .iter()
+                .zip(window)
+                .map(|(&x, &factor)| Complex32::new(x * factor, 0.0)),
+        );
 
         // Execute FFT in-place
-        fft.process(&mut complex_input);
-
-        complex_input
+        plan.process_with_scratch(output, &mut self.fft_scratch);
     }
 
     /// Convert FFT output to meaningful spectrum data
@@ -487,20 +596,27 @@ impl SpectralAnalyzer for FFTAnalyzer {
             ));
         }
 
-        // Apply window function
-        let windowed = self.apply_window(&signal[0..self.frame_size]);
+        self.prepare();
 
-        // Compute FFT
-        let fft_result = self.compute_fft(&windowed);
+        // Window the signal and compute its FFT, reusing the buffer of the
+        // oldest spectrum once the averaging history is full
+        let mut fft_result = if self.previous_spectra.len() >= self.averages {
+            self.previous_spectra.pop_front().unwrap_or_default()
+        } else {
+            Vec::with_capacity(self.frame_size)
+        };
+        self.compute_fft(&signal[0..self.frame_size], &mut fft_result);
 
         // Add to previous spectra for averaging
-        self.previous_spectra.push(fft_result.clone());
-        if self.previous_spectra.len() > self.averages {
-            self.previous_spectra.remove(0);
+        self.previous_spectra.push_back(fft_result);
+        while self.previous_spectra.len() > self.averages.max(1) {
+            self.previous_spectra.pop_front();
         }
 
         // Average spectra
-        let mut avg_spectrum = vec![Complex32::new(0.0, 0.0); self.frame_size];
+        let mut avg_spectrum = std::mem::take(&mut self.average);
+        avg_spectrum.clear();
+        avg_spectrum.resize(self.frame_size, Complex32::new(0.0, 0.0));
 
         for spectrum in &self.previous_spectra {
             for (i, &complex_val) in spectrum.iter().enumerate() {
@@ -514,9 +630,18 @@ impl SpectralAnalyzer for FFTAnalyzer {
 
         // Convert to spectrum data
         let spectrum = self.fft_to_spectrum(&avg_spectrum, sample_rate);
-
-        // Store for later reference
-        self.spectrum_data = Some(spectrum.clone());
+        self.average = avg_spectrum;
+
+        // Store for later reference, reusing the vectors of the previous result
+        match &mut self.spectrum_data {
+            Some(stored) => {
+                stored.frequencies.clone_from(&spectrum.frequencies);
+                stored.amplitudes.clone_from(&spectrum.amplitudes);
+                stored.phases.clone_from(&spectrum.phases);
+                stored.sample_rate = spectrum.sample_rate;
+            }
+            None => self.spectrum_data = Some(spectrum.clone()),
+        }
 
         Ok(spectrum)
     }
@@ -607,7 +732,7 @@ impl SpectralAnalyzer for FFTAnalyzer {
 /// // You can access the window functions directly from the enum
 /// println!("Available window functions: Rectangular, Hann, Blackman");
 /// ```
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WindowFunction {
     /// Rectangular window (no windowing)
     Rectangular,
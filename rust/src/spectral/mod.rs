@@ -16,6 +16,7 @@
 //! - Window functions to reduce spectral leakage
 //! - Spectral averaging for improved signal-to-noise ratio
 //! - Frequency-specific amplitude extraction
+//! - Sliding DFT for continuous tracking of a frequency band
 //!
 //! ## Architecture
 //!
@@ -23,7 +24,8 @@
 //!
 //! - `SpectralAnalyzer` trait defines the interface for all analyzers
 //! - `FFTAnalyzer` provides a concrete implementation using FFT
-//! - Factory function `create_spectral_analyzer()` instantiates a suitable analyzer
+//! - `SlidingDftAnalyzer` updates the spectrum sample by sample with a sliding DFT
+//! - Factory function `create_spectral_analyzer()` instantiates the analyzer of a `SpectralMode`
 //!
 //! This design allows for easy extension with alternative spectral analysis methods
 //! while maintaining a consistent API for application code.
@@ -31,10 +33,10 @@
 //! ## Usage
 //!
 //! ```
-//! use rust_photoacoustic::spectral;
+//! use rust_photoacoustic::spectral::{self, SpectralMode};
 //!
 //! // Create an analyzer with 2048-point FFT and 4x averaging
-//! let mut analyzer = spectral::create_spectral_analyzer(2048, 4);
+//! let mut analyzer = spectral::create_spectral_analyzer(2048, 4, SpectralMode::Fft);
 //!
 //! // Generate a simple test signal (replace with your actual signal)
 //! let sample_rate = 44100;
@@ -51,10 +53,24 @@
 
 // Make the fft module public for documentation examples
 pub mod fft;
+pub mod sliding_dft;
 
 // Re-export key types and functions for public use at the top level
 pub use fft::SpectralAnalyzer;
 
+/// Spectral analysis method selected by `create_spectral_analyzer`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpectralMode {
+    /// Block FFT of the first `frame_size` samples of each signal
+    #[default]
+    Fft,
+    /// Sliding DFT over the last `frame_size` samples of a continuous stream
+    ///
+    /// Each call to `analyze` appends its samples to the window instead of
+    /// analyzing them on their own, see [`sliding_dft::SlidingDftAnalyzer`].
+    SlidingDft,
+}
+
 /// Create a new spectral analyzer with the given window size and averaging
 ///
 /// This factory function creates and returns a new spectral analyzer that
@@ -72,6 +88,9 @@ pub use fft::SpectralAnalyzer;
 ///   Higher values improve the signal-to-noise ratio but increase latency
 ///   and computational cost. Set to 1 for no averaging.
 ///
+/// * `mode` - The analysis method: a block FFT per call, or a sliding DFT
+///   updated with every sample of a continuous stream.
+///
 /// ### Returns
 ///
 /// A boxed trait object implementing the `SpectralAnalyzer` trait
@@ -79,13 +98,23 @@ pub use fft::SpectralAnalyzer;
 /// ### Example
 ///
 /// ```
-/// use rust_photoacoustic::spectral;
+/// use rust_photoacoustic::spectral::{self, SpectralMode};
 ///
 /// // Create an analyzer with a 4096-point window and 5x averaging
-/// let analyzer = spectral::create_spectral_analyzer(4096, 5);
+/// let analyzer = spectral::create_spectral_analyzer(4096, 5, SpectralMode::Fft);
 ///
-/// // Now you can use this analyzer with any compatible signal
+/// // Or track a continuous stream with a sliding DFT
+/// let tracker = spectral::create_spectral_analyzer(4096, 1, SpectralMode::SlidingDft);
 /// ```
-pub fn create_spectral_analyzer(frame_size: usize, averages: usize) -> Box<dyn SpectralAnalyzer> {
-    Box::new(fft::FFTAnalyzer::new(frame_size, averages))
+pub fn create_spectral_analyzer(
+    frame_size: usize,
+    averages: usize,
+    mode: SpectralMode,
+) -> Box<dyn SpectralAnalyzer> {
+    match mode {
+        SpectralMode::Fft => Box::new(fft::FFTAnalyzer::new(frame_size, averages)),
+        SpectralMode::SlidingDft => {
+            Box::new(sliding_dft::SlidingDftAnalyzer::new(frame_size, averages))
+        }
+    }
 }
@@ -0,0 +1,456 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Sliding DFT for continuous spectral tracking
+//!
+//! `SlidingDftAnalyzer` keeps the DFT of the last `frame_size` samples it has
+//! received and updates it sample by sample with the recurrence
+//!
+//! ```text
+//! X[k] ← (X[k] + x[n] - x[n - N]) · e^(j2πk/N)
+//! ```
+//!
+//! so each new sample costs one complex multiply-add per tracked bin instead
+//! of a full FFT per frame. Restricting the tracked bins to a frequency range
+//! around a resonance makes the cost independent of the window size, which
+//! suits tracking a slowly-moving peak.
+//!
+//! # Bounded State
+//!
+//! The analyzer stores the last `frame_size` samples, the `frame_size / 2 + 1`
+//! bins of the positive spectrum and the spectra of its averaging history,
+//! whatever the length of the stream. The bins are accumulated in `f64` and
+//! recomputed from the stored samples with an FFT every
+//! [`RESYNC_INTERVAL`] samples, so rounding errors cannot build up over a
+//! long-running acquisition.
+//!
+//! # Windowing
+//!
+//! The recurrence produces the spectrum of a rectangular window. The Hann and
+//! Blackman windows are applied in the frequency domain by combining
+//! neighbouring bins, which corresponds to their periodic form
+//! `w[n] = 0.5 - 0.5 cos(2πn/N)` (and likewise for Blackman). `FFTAnalyzer`
+//! uses the symmetric form with `N - 1` in the denominator, so the two
+//! analyzers only agree exactly with a rectangular window.
+//!
+//! # Example
+//!
+//! ```
+//! use rust_photoacoustic::spectral::fft::SpectralAnalyzer;
+//! use rust_photoacoustic::spectral::sliding_dft::SlidingDftAnalyzer;
+//!
+//! let sample_rate = 48000;
+//! let mut analyzer = SlidingDftAnalyzer::new(4096, 1).with_frequency_range(1500.0, 2500.0);
+//!
+//! // Feed the stream in blocks of any size
+//! for block in 0..10 {
+//!     let samples: Vec<f32> = (0..480)
+//!         .map(|n| {
+//!             let t = (block * 480 + n) as f32 / sample_rate as f32;
+//!             (2.0 * std::f32::consts::PI * 2000.0 * t).sin()
+//!         })
+//!         .collect();
+//!     analyzer.analyze(&samples, sample_rate).unwrap();
+//! }
+//!
+//! let amplitude = analyzer.get_amplitude_at(2000.0).unwrap();
+//! assert!(amplitude > 0.3);
+//! ```
+
+use super::fft::{SpectralAnalyzer, SpectrumData, WindowFunction};
+use anyhow::Result;
+use rustfft::num_complex::Complex64;
+use rustfft::{Fft, FftPlanner};
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// Number of samples after which the bins are recomputed with an FFT
+pub const RESYNC_INTERVAL: usize = 1 << 22;
+
+/// Sliding DFT spectral analyzer
+///
+/// Unlike `FFTAnalyzer`, which analyzes the first `frame_size` samples of each
+/// signal it is given, this analyzer treats successive calls to `analyze` as
+/// one continuous stream. Each call appends its samples to the sliding window
+/// and returns the spectrum of the last `frame_size` samples received. Until
+/// `frame_size` samples have been received, the missing samples are zeros.
+///
+/// The returned spectrum has the same layout and normalization as the one of
+/// `FFTAnalyzer`. When a frequency range is set, the bins outside of it have
+/// a zero amplitude and phase.
+///
+/// ### Example
+///
+/// ```
+/// use rust_photoacoustic::spectral::fft::{SpectralAnalyzer, WindowFunction};
+/// use rust_photoacoustic::spectral::sliding_dft::SlidingDftAnalyzer;
+///
+/// let mut analyzer =
+///     SlidingDftAnalyzer::new(1024, 1).with_window_function(WindowFunction::Rectangular);
+/// let spectrum = analyzer.analyze(&vec![0.0f32; 256], 44100).unwrap();
+/// assert_eq!(spectrum.frequencies.len(), 512);
+/// ```
+pub struct SlidingDftAnalyzer {
+    /// Size of the sliding window in samples
+    frame_size: usize,
+
+    /// Number of spectra to average
+    averages: usize,
+
+    /// Window function applied in the frequency domain
+    window_function: WindowFunction,
+
+    /// Frequency range of the tracked bins in Hz, all bins when `None`
+    frequency_range: Option<(f32, f32)>,
+
+    /// Sample rate of the stream, `None` until the first call to `analyze`
+    sample_rate: Option<u32>,
+
+    /// Last `frame_size` samples, in a circular buffer
+    samples: Vec<f32>,
+
+    /// Index of the oldest sample in `samples`
+    position: usize,
+
+    /// Rectangular-window DFT of the sliding window, bins 0 to `frame_size / 2`
+    bins: Vec<Complex64>,
+
+    /// Rotation `e^(j2πk/N)` applied to bin `k` for each new sample
+    twiddles: Vec<Complex64>,
+
+    /// Bins reported in the spectrum
+    reported: RangeInclusive<usize>,
+
+    /// Bins updated by the recurrence, the reported bins and two neighbours
+    /// on each side for the window
+    tracked: RangeInclusive<usize>,
+
+    /// Samples received since the bins were last recomputed
+    samples_since_resync: usize,
+
+    /// FFT plan used to recompute the bins
+    fft_plan: Option<Arc<dyn Fft<f64>>>,
+
+    /// Differences `x[n] - x[n - N]` of the current block
+    deltas: Vec<f64>,
+
+    /// Windowed spectra of the previous calls, for averaging
+    previous_spectra: VecDeque<Vec<Complex64>>,
+
+    /// Most recent spectrum, for `get_amplitude_at`
+    spectrum_data: Option<SpectrumData>,
+}
+
+impl SlidingDftAnalyzer {
+    /// Create a new sliding DFT analyzer
+    ///
+    /// The analyzer tracks all bins with a Hann window, like `FFTAnalyzer`.
+    ///
+    /// ### Parameters
+    ///
+    /// * `frame_size` - The size of the sliding window in samples, at least 2.
+    ///   It does not need to be a power of 2.
+    /// * `averages` - The number of consecutive spectra to average. Set to 1
+    ///   for no averaging.
+    ///
+    /// ### Returns
+    ///
+    /// A new `SlidingDftAnalyzer` with an empty (all zero) window
+    ///
+    /// ### Example
+    ///
+    /// ```
+    /// use rust_photoacoustic::spectral::sliding_dft::SlidingDftAnalyzer;
+    ///
+    /// let analyzer = SlidingDftAnalyzer::new(4096, 1);
+    /// assert_eq!(analyzer.frame_size(), 4096);
+    /// ```
+    pub fn new(frame_size: usize, averages: usize) -> Self {
+        Self {
+            frame_size,
+            averages,
+            window_function: WindowFunction::Hann,
+            frequency_range: None,
+            sample_rate: None,
+            samples: Vec::new(),
+            position: 0,
+            bins: Vec::new(),
+            twiddles: Vec::new(),
+            reported: 0..=0,
+            tracked: 0..=0,
+            samples_since_resync: 0,
+            fft_plan: None,
+            deltas: Vec::new(),
+            previous_spectra: VecDeque::with_capacity(averages + 1),
+            spectrum_data: None,
+        }
+    }
+
+    /// Set the window function (builder pattern)
+    ///
+    /// ### Parameters
+    ///
+    /// * `window_function` - The window function, applied in its periodic form
+    ///
+    /// ### Returns
+    ///
+    /// The analyzer with the new window function
+    pub fn with_window_function(mut self, window_function: WindowFunction) -> Self {
+        self.window_function = window_function;
+        self
+    }
+
+    /// Only track the bins between `min_frequency` and `max_frequency` (builder pattern)
+    ///
+    /// The cost of each sample is proportional to the number of tracked bins.
+    /// Two extra bins are tracked on each side of the range for the window.
+    ///
+    /// ### Parameters
+    ///
+    /// * `min_frequency` - Lower bound of the range in Hz
+    /// * `max_frequency` - Upper bound of the range in Hz
+    ///
+    /// ### Returns
+    ///
+    /// The analyzer tracking the given range
+    pub fn with_frequency_range(mut self, min_frequency: f32, max_frequency: f32) -> Self {
+        self.frequency_range = Some((
+            min_frequency.min(max_frequency),
+            min_frequency.max(max_frequency),
+        ));
+        self
+    }
+
+    /// Get the window function
+    pub fn window_function(&self) -> WindowFunction {
+        self.window_function
+    }
+
+    /// Get the size of the sliding window in samples
+    pub fn frame_size(&self) -> usize {
+        self.frame_size
+    }
+
+    /// Clear the sliding window, the bins and the averaging history
+    pub fn reset(&mut self) {
+        self.samples.clear();
+        self.bins.clear();
+        self.previous_spectra.clear();
+        self.spectrum_data = None;
+    }
+
+    /// Allocate the state for `sample_rate`, or reset it if the sample rate changed
+    fn prepare(&mut self, sample_rate: u32) {
+        if self.sample_rate != Some(sample_rate) {
+            self.reset();
+            self.sample_rate = Some(sample_rate);
+        }
+        if !self.samples.is_empty() {
+            return;
+        }
+
+        let n = self.frame_size;
+        let nyquist_bin = n / 2;
+        self.samples = vec![0.0; n];
+        self.position = 0;
+        self.bins = vec![Complex64::new(0.0, 0.0); nyquist_bin + 1];
+        self.twiddles = (0..=nyquist_bin)
+            .map(|k| Complex64::from_polar(1.0, 2.0 * std::f64::consts::PI * k as f64 / n as f64))
+            .collect();
+        self.samples_since_resync = 0;
+
+        let last_bin = n / 2 - 1;
+        self.reported = match self.frequency_range {
+            Some((min_frequency, max_frequency)) => {
+                let df = sample_rate as f32 / n as f32;
+                let low = ((min_frequency / df).floor().max(0.0) as usize).min(last_bin);
+                let high = ((max_frequency / df).ceil().max(0.0) as usize).min(last_bin);
+                low..=high
+            }
+            None => 0..=last_bin,
+        };
+        self.tracked =
+            self.reported.start().saturating_sub(2)..=(self.reported.end() + 2).min(nyquist_bin);
+    }
+
+    /// Slide the window over `signal`, updating the tracked bins
+    fn push_samples(&mut self, signal: &[f32]) {
+        let n = self.frame_size;
+        self.deltas.clear();
+        for &x in signal {
+            let oldest = std::mem::replace(&mut self.samples[self.position], x);
+            self.deltas.push(x as f64 - oldest as f64);
+            self.position = (self.position + 1) % n;
+        }
+
+        // One bin at a time over the whole block keeps the state in registers
+        for k in self.tracked.clone() {
+            let twiddle = self.twiddles[k];
+            let mut bin = self.bins[k];
+            for &delta in &self.deltas {
+                bin = (bin + delta) * twiddle;
+            }
+            self.bins[k] = bin;
+        }
+
+        self.samples_since_resync += signal.len();
+        if self.samples_since_resync >= RESYNC_INTERVAL {
+            self.resync();
+        }
+    }
+
+    /// Recompute the tracked bins from the sliding window with an FFT
+    fn resync(&mut self) {
+        let n = self.frame_size;
+        let plan = self
+            .fft_plan
+            .get_or_insert_with(|| FftPlanner::new().plan_fft_forward(n))
+            .clone();
+
+        // Oldest sample first, as in the recurrence
+        let mut buffer: Vec<Complex64> = self.samples[self.position..]
+            .iter()
+            .chain(&self.samples[..self.position])
+            .map(|&x| Complex64::new(x as f64, 0.0))
+            .collect();
+        plan.process(&mut buffer);
+
+        for k in self.tracked.clone() {
+            self.bins[k] = buffer[k];
+        }
+        self.samples_since_resync = 0;
+    }
+
+    /// Rectangular-window bin `k`, for any `k`
+    ///
+    /// Bins outside of `0..=N/2` are obtained from the periodicity and the
+    /// conjugate symmetry of the DFT of a real signal.
+    fn bin(&self, k: isize) -> Complex64 {
+        let n = self.frame_size as isize;
+        let k = k.rem_euclid(n);
+        if k as usize <= self.frame_size / 2 {
+            self.bins[k as usize]
+        } else {
+            self.bins[(n - k) as usize].conj()
+        }
+    }
+
+    /// Windowed spectrum of the positive frequencies
+    fn windowed_spectrum(&self) -> Vec<Complex64> {
+        let mut spectrum = vec![Complex64::new(0.0, 0.0); self.frame_size / 2];
+        for k in self.reported.clone() {
+            let value = &mut spectrum[k];
+            let k = k as isize;
+            *value = match self.window_function {
+                WindowFunction::Rectangular => self.bin(k),
+                WindowFunction::Hann => {
+                    self.bin(k) * 0.5 - (self.bin(k - 1) + self.bin(k + 1)) * 0.25
+                }
+                WindowFunction::Blackman => {
+                    self.bin(k) * 0.42 - (self.bin(k - 1) + self.bin(k + 1)) * 0.25
+                        + (self.bin(k - 2) + self.bin(k + 2)) * 0.04
+                }
+            };
+        }
+        spectrum
+    }
+}
+
+impl SpectralAnalyzer for SlidingDftAnalyzer {
+    /// Append `signal` to the sliding window and return its spectrum
+    ///
+    /// ### Parameters
+    ///
+    /// * `signal` - The next samples of the stream, of any length
+    /// * `sample_rate` - The sample rate of the stream in Hz. A change of
+    ///   sample rate restarts the analysis from an empty window.
+    ///
+    /// ### Returns
+    ///
+    /// The spectrum of the last `frame_size` samples received, averaged over
+    /// the last `averages` calls
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if the sample rate is zero or the window is shorter
+    /// than 2 samples.
+    fn analyze(&mut self, signal: &[f32], sample_rate: u32) -> Result<SpectrumData> {
+        if sample_rate == 0 {
+            return Err(anyhow::anyhow!("Invalid sample rate: 0 Hz"));
+        }
+        if self.frame_size < 2 {
+            return Err(anyhow::anyhow!(
+                "Window too short: {} samples (need at least 2)",
+                self.frame_size
+            ));
+        }
+
+        self.prepare(sample_rate);
+        self.push_samples(signal);
+
+        // Average the windowed spectra of the last calls
+        self.previous_spectra.push_back(self.windowed_spectrum());
+        while self.previous_spectra.len() > self.averages.max(1) {
+            self.previous_spectra.pop_front();
+        }
+
+        let n = self.frame_size;
+        let df = sample_rate as f32 / n as f32;
+        let count = self.previous_spectra.len() as f64;
+        let useful_bins = n / 2;
+        let mut frequencies = Vec::with_capacity(useful_bins);
+        let mut amplitudes = Vec::with_capacity(useful_bins);
+        let mut phases = Vec::with_capacity(useful_bins);
+        for k in 0..useful_bins {
+            let sum: Complex64 = self.previous_spectra.iter().map(|s| s[k]).sum();
+            let value = sum / count;
+            frequencies.push(k as f32 * df);
+            amplitudes.push((value.norm() / n as f64 * 2.0) as f32);
+            phases.push(value.arg() as f32);
+        }
+
+        let spectrum = SpectrumData {
+            frequencies,
+            amplitudes,
+            phases,
+            sample_rate,
+        };
+        self.spectrum_data = Some(spectrum.clone());
+        Ok(spectrum)
+    }
+
+    /// Get the amplitude at the specified frequency from the latest spectrum
+    ///
+    /// ### Parameters
+    ///
+    /// * `frequency` - The frequency in Hz
+    ///
+    /// ### Returns
+    ///
+    /// The amplitude of the closest bin
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if `analyze()` has not been called or the frequency is
+    /// beyond the Nyquist frequency.
+    fn get_amplitude_at(&self, frequency: f32) -> Result<f32> {
+        let spectrum = self
+            .spectrum_data
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No spectrum data available. Call analyze() first."))?;
+
+        let df = spectrum.sample_rate as f32 / (self.frame_size as f32);
+        let bin = (frequency / df).round() as usize;
+
+        if bin >= spectrum.frequencies.len() {
+            return Err(anyhow::anyhow!(
+                "Frequency {} Hz is outside the analyzed spectrum",
+                frequency
+            ));
+        }
+
+        Ok(spectrum.amplitudes[bin])
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Coordinated control of adjacent thermal zones
+//!
+//! Adjacent cells exchange heat: with independent PID loops, a zone which
+//! oscillates drives the temperature of its neighbours, whose own loops then
+//! react to a disturbance they cannot remove. The regulators of a
+//! [`ThermalZoneGroupConfig`] publish their readings in the shared thermal
+//! state and, at every cycle, [`coordinate`] computes how a zone is controlled
+//! from the readings of the whole group:
+//!
+//! - **`master_slave`**: the master regulates its own temperature and the other
+//!   zones apply its control output, so that the group is heated and cooled as
+//!   a single body.
+//! - **`averaged_setpoint`**: every zone regulates the average temperature of
+//!   the group towards the average setpoint of the group, so that all zones
+//!   react to the same error instead of fighting each other.
+//!
+//! A zone whose partners have not published a reading yet, or are not running,
+//! falls back to the readings available, down to regulating its own
+//! temperature alone.
+
+use std::collections::HashMap;
+
+use crate::config::thermal_regulation::{ThermalZoneGroupConfig, ZoneCoordinationMode};
+
+/// Latest reading of a zone, as published in the shared thermal state
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZoneReading {
+    /// Measured temperature in Celsius
+    pub temperature_celsius: f64,
+    /// Setpoint in Celsius
+    pub setpoint_celsius: f64,
+    /// Applied control output in percent
+    pub control_output_percent: f64,
+}
+
+/// How a zone is controlled for the current cycle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ZoneControl {
+    /// Run the PID of the zone on this process variable
+    ///
+    /// The PID compares it with the setpoint of the zone; the coordinated
+    /// error is therefore `setpoint - process_variable`.
+    Regulate { process_variable: f64 },
+    /// Apply the control output of another zone without running the PID
+    Follow { control_output_percent: f64 },
+}
+
+/// Compute the control of a zone from the readings of its group
+///
+/// # Arguments
+/// * `group` - Zone group of the regulator
+/// * `zone_id` - Identifier of the regulator
+/// * `temperature_celsius` - Temperature measured by the regulator in this cycle
+/// * `setpoint_celsius` - Setpoint of the regulator
+/// * `readings` - Latest readings of the running zones of the group
+///
+/// # Returns
+/// * The control to apply in this cycle
+pub fn coordinate(
+    group: &ThermalZoneGroupConfig,
+    zone_id: &str,
+    temperature_celsius: f64,
+    setpoint_celsius: f64,
+    readings: &HashMap<String, ZoneReading>,
+) -> ZoneControl {
+    let independent = ZoneControl::Regulate {
+        process_variable: temperature_celsius,
+    };
+
+    match group.mode {
+        ZoneCoordinationMode::MasterSlave => {
+            match group.master.as_deref().filter(|master| *master != zone_id) {
+                Some(master) => {
+                    readings
+                        .get(master)
+                        .map_or(independent, |reading| ZoneControl::Follow {
+                            control_output_percent: reading.control_output_percent,
+                        })
+                }
+                None => independent,
+            }
+        }
+        ZoneCoordinationMode::AveragedSetpoint => {
+            // The reading of this cycle replaces the one published earlier
+            let mut temperature_sum = temperature_celsius;
+            let mut setpoint_sum = setpoint_celsius;
+            let mut count = 1.0;
+            for (id, reading) in readings {
+                if id != zone_id && group.zones.contains(id) {
+                    temperature_sum += reading.temperature_celsius;
+                    setpoint_sum += reading.setpoint_celsius;
+                    count += 1.0;
+                }
+            }
+            let group_error = (setpoint_sum - temperature_sum) / count;
+            ZoneControl::Regulate {
+                process_variable: setpoint_celsius - group_error,
+            }
+        }
+    }
+}
@@ -8,7 +8,7 @@
 //! thermal regulators, each running in its own thread with individual PID control loops.
 
 use anyhow::Result;
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc,
@@ -18,11 +18,18 @@ use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 use tokio::time;
 
-use crate::config::thermal_regulation::{ThermalRegulationConfig, ThermalRegulatorConfig};
+use crate::config::thermal_regulation::{
+    DeadbandOutput, PidParameters, ThermalRegulationConfig, ThermalRegulatorConfig,
+    ThermalZoneGroupConfig,
+};
+use crate::thermal_regulation::coordination::{coordinate, ZoneControl};
+use crate::thermal_regulation::data_log::{ThermalDataLogger, ThermalLogRecord};
 use crate::thermal_regulation::shared_state::{
-    CurrentPidParams, PidComponents, RegulatorStatus, SharedThermalState,
+    CurrentPidParams, PidComponents, RegulatorStatus, SetpointLimits, SharedThermalState,
+};
+use crate::thermal_regulation::{
+    create_thermal_regulation_driver, DigitalInputState, ThermalRegulationDriver,
 };
-use crate::thermal_regulation::{create_thermal_regulation_driver, ThermalRegulationDriver};
 
 /// Commands that can be sent to a thermal regulator thread
 #[derive(Debug, Clone)]
@@ -70,6 +77,8 @@ pub struct ThermalRegulatorDaemon {
     thread_handle: Option<JoinHandle<Result<()>>>,
     /// Command sender to communicate with the regulator thread
     command_sender: Option<mpsc::UnboundedSender<ThermalRegulatorCommand>>,
+    /// Zone group coordinating this regulator with adjacent zones
+    zone_group: Option<ThermalZoneGroupConfig>,
 }
 
 /// PID controller implementation for thermal regulation
@@ -94,6 +103,22 @@ pub struct PidController {
     output_max: f64,
     /// Last update time for dt calculation
     last_update: Option<Instant>,
+    /// Feedforward output percent per °C of setpoint above the reference
+    feedforward_gain: f64,
+    /// Temperature in Celsius at which the setpoint feedforward is zero
+    feedforward_reference_celsius: f64,
+    /// Feedforward output percent per unit of measured disturbance
+    disturbance_gain: f64,
+    /// Last measured disturbance (1.0 while a disturbance input is active)
+    disturbance: f64,
+    /// Half-width in °C of the dead-band around the setpoint, 0 when disabled
+    dead_band_celsius: f64,
+    /// Output applied within the dead-band
+    dead_band_output: DeadbandOutput,
+    /// Whether the last update was within the dead-band
+    in_dead_band: bool,
+    /// Last output computed outside the dead-band
+    last_output: f64,
 }
 
 /// Thermal regulation system daemon managing multiple regulators
@@ -132,9 +157,87 @@ impl PidController {
             output_min,
             output_max,
             last_update: None,
+            feedforward_gain: 0.0,
+            feedforward_reference_celsius: 0.0,
+            disturbance_gain: 0.0,
+            disturbance: 0.0,
+            dead_band_celsius: 0.0,
+            dead_band_output: DeadbandOutput::Zero,
+            in_dead_band: false,
+            last_output: 0.0,
         }
     }
 
+    /// Create a PID controller from the regulator configuration
+    ///
+    /// # Arguments
+    /// * `parameters` - PID parameters, setpoint and feedforward temperatures in Kelvin
+    pub fn from_parameters(parameters: &PidParameters) -> Self {
+        let mut pid = Self::new(
+            parameters.kp as f64,
+            parameters.ki as f64,
+            parameters.kd as f64,
+            (parameters.setpoint - 273.15) as f64, // Convert K to C
+            parameters.integral_max as f64,
+            parameters.output_min as f64,
+            parameters.output_max as f64,
+        );
+        let feedforward = &parameters.feedforward;
+        pid.set_feedforward(
+            feedforward.setpoint_gain as f64,
+            (feedforward.reference_temperature_k - 273.15) as f64, // Convert K to C
+            feedforward.disturbance_gain as f64,
+        );
+        pid
+    }
+
+    /// Configure the feedforward term added to the PID output
+    ///
+    /// # Arguments
+    /// * `setpoint_gain` - Output percent per °C of setpoint above `reference_celsius`
+    /// * `reference_celsius` - Temperature at which the setpoint feedforward is zero
+    /// * `disturbance_gain` - Output percent per unit of measured disturbance
+    pub fn set_feedforward(
+        &mut self,
+        setpoint_gain: f64,
+        reference_celsius: f64,
+        disturbance_gain: f64,
+    ) {
+        self.feedforward_gain = setpoint_gain;
+        self.feedforward_reference_celsius = reference_celsius;
+        self.disturbance_gain = disturbance_gain;
+    }
+
+    /// Configure the dead-band around the setpoint
+    ///
+    /// While `|setpoint - temperature| <= dead_band_celsius` the PID terms are
+    /// not updated and the output is zero or the last output, according to
+    /// `output`. A zero width disables the dead-band.
+    ///
+    /// # Arguments
+    /// * `dead_band_celsius` - Half-width of the dead-band in °C
+    /// * `output` - Output applied within the dead-band
+    pub fn set_dead_band(&mut self, dead_band_celsius: f64, output: DeadbandOutput) {
+        self.dead_band_celsius = dead_band_celsius.max(0.0);
+        self.dead_band_output = output;
+    }
+
+    /// Whether the last update was within the dead-band
+    pub fn in_dead_band(&self) -> bool {
+        self.in_dead_band
+    }
+
+    /// Set the measured disturbance used by the feedforward term
+    pub fn set_disturbance(&mut self, disturbance: f64) {
+        self.disturbance = disturbance;
+    }
+
+    /// Current feedforward output percentage
+    pub fn feedforward(&self) -> f64 {
+        self.feedforward_gain * (self.setpoint_celsius - self.feedforward_reference_celsius)
+            + self.disturbance_gain * self.disturbance
+    }
+
     /// Update PID controller and return control output
     ///
     /// # Arguments
@@ -154,9 +257,46 @@ impl PidController {
         };
         self.last_update = Some(now);
 
+        self.update_with_dt(process_variable, dt)
+    }
+
+    /// Update PID controller with an explicit time step
+    ///
+    /// Used by [`update`](Self::update) with the wall-clock time since the
+    /// previous update, and by simulations running faster than real time.
+    ///
+    /// # Arguments
+    /// * `process_variable` - Current temperature in Celsius
+    /// * `dt` - Time since the previous update in seconds
+    ///
+    /// # Returns
+    /// * Control output percentage (-100.0 to +100.0)
+    pub fn update_with_dt(&mut self, process_variable: f64, dt: f64) -> PidOutput {
         // Calculate error
         let error = self.setpoint_celsius - process_variable;
 
+        // Within the dead-band the integral is frozen, so that it does not
+        // wind up while the output is held
+        self.in_dead_band = self.dead_band_celsius > 0.0 && error.abs() <= self.dead_band_celsius;
+        if self.in_dead_band {
+            // Avoid a derivative kick when leaving the dead-band
+            self.previous_error = error;
+            let control_output = match self.dead_band_output {
+                DeadbandOutput::Zero => 0.0,
+                DeadbandOutput::Hold => self.last_output,
+            };
+            return PidOutput {
+                control_output,
+                components: PidComponents {
+                    proportional: 0.0,
+                    integral: 0.0,
+                    derivative: 0.0,
+                    feedforward: 0.0,
+                    error,
+                },
+            };
+        }
+
         // Proportional term
         let proportional = self.kp * error;
 
@@ -174,8 +314,11 @@ impl PidController {
         };
         self.previous_error = error;
 
+        // Feedforward term
+        let feedforward = self.feedforward();
+
         // Calculate total output
-        let output = proportional + integral + derivative;
+        let output = proportional + integral + derivative + feedforward;
         let clamped_output = output.clamp(self.output_min, self.output_max);
 
         // Reset integral if output is saturated (additional anti-windup)
@@ -184,6 +327,7 @@ impl PidController {
         {
             self.integral *= 0.9; // Gradually reduce integral when saturated
         }
+        self.last_output = clamped_output;
 
         PidOutput {
             control_output: clamped_output,
@@ -191,6 +335,7 @@ impl PidController {
                 proportional,
                 integral,
                 derivative,
+                feedforward,
                 error,
             },
         }
@@ -213,6 +358,29 @@ impl PidController {
         self.integral = 0.0;
         self.previous_error = 0.0;
         self.last_update = None;
+        self.in_dead_band = false;
+        self.last_output = 0.0;
+    }
+
+    /// Output applying the control output of another zone
+    ///
+    /// The output is clamped to the limits of this controller; only the error
+    /// is reported in the components.
+    ///
+    /// # Arguments
+    /// * `control_output` - Control output of the followed zone in percent
+    /// * `process_variable` - Current temperature in Celsius
+    pub fn follow(&self, control_output: f64, process_variable: f64) -> PidOutput {
+        PidOutput {
+            control_output: control_output.clamp(self.output_min, self.output_max),
+            components: PidComponents {
+                proportional: 0.0,
+                integral: 0.0,
+                derivative: 0.0,
+                feedforward: 0.0,
+                error: self.setpoint_celsius - process_variable,
+            },
+        }
     }
 
     /// Get current PID parameters
@@ -237,6 +405,72 @@ pub struct PidOutput {
     pub components: PidComponents,
 }
 
+/// Shut the actuators down if a fault input is active
+///
+/// Reads the configured digital inputs and, when an input flagged as a fault
+/// is active, forces the control output to zero.
+///
+/// # Arguments
+/// * `driver` - Thermal regulation driver of the regulator
+///
+/// # Returns
+/// * The active fault input, if any
+pub async fn check_fault_inputs(
+    driver: &mut dyn ThermalRegulationDriver,
+) -> Result<Option<DigitalInputState>> {
+    let fault = driver
+        .read_digital_inputs()
+        .await?
+        .into_iter()
+        .find(|input| input.fault && input.active);
+
+    if fault.is_some() {
+        driver.apply_control_output(0.0).await?;
+    }
+    Ok(fault)
+}
+
+/// Shut the actuators down if the H-Bridge current exceeds a threshold
+///
+/// Reads the current-sense channel and, when the current is above
+/// `threshold_a`, forces the control output to zero.
+///
+/// # Arguments
+/// * `driver` - Thermal regulation driver of the regulator
+/// * `threshold_a` - Overcurrent threshold in amps
+///
+/// # Returns
+/// * The measured current if it exceeds the threshold
+pub async fn check_overcurrent(
+    driver: &mut dyn ThermalRegulationDriver,
+    threshold_a: f64,
+) -> Result<Option<f64>> {
+    let overcurrent = driver
+        .read_current()
+        .await?
+        .filter(|&current| current > threshold_a);
+
+    if overcurrent.is_some() {
+        driver.apply_control_output(0.0).await?;
+    }
+    Ok(overcurrent)
+}
+
+/// Append a row to the data log of a regulator, if any
+///
+/// A write failure is only reported, it must not stop the regulation.
+fn append_data_log(data_logger: &mut Option<ThermalDataLogger>, record: &ThermalLogRecord) {
+    if let Some(logger) = data_logger {
+        if let Err(e) = logger.log(record) {
+            warn!(
+                "Failed to write thermal data log {}: {}",
+                logger.path().display(),
+                e
+            );
+        }
+    }
+}
+
 impl ThermalRegulatorDaemon {
     /// Create a new thermal regulator daemon
     pub async fn new(
@@ -256,15 +490,7 @@ impl ThermalRegulatorDaemon {
         );
 
         // Initialize regulator in shared state
-        let pid_controller = PidController::new(
-            config.pid_parameters.kp as f64,
-            config.pid_parameters.ki as f64,
-            config.pid_parameters.kd as f64,
-            (config.pid_parameters.setpoint - 273.15) as f64, // Convert K to C
-            config.pid_parameters.integral_max as f64,
-            config.pid_parameters.output_min as f64,
-            config.pid_parameters.output_max as f64,
-        );
+        let pid_controller = PidController::from_parameters(&config.pid_parameters);
 
         {
             let mut state = shared_state.write().await;
@@ -273,6 +499,13 @@ impl ThermalRegulatorDaemon {
                 config.name.clone(),
                 pid_controller.get_current_params(),
             )?;
+            state.set_setpoint_limits(
+                &config.id,
+                SetpointLimits {
+                    min_celsius: (config.safety_limits.min_temperature_k - 273.15) as f64,
+                    max_celsius: (config.safety_limits.max_temperature_k - 273.15) as f64,
+                },
+            )?;
         }
 
         Ok(Self {
@@ -282,9 +515,18 @@ impl ThermalRegulatorDaemon {
             running,
             thread_handle: None,
             command_sender: None,
+            zone_group: None,
         })
     }
 
+    /// Coordinate this regulator with the other zones of `zone_group`
+    ///
+    /// Must be called before [`start`](Self::start); `None` restores
+    /// independent control.
+    pub fn set_zone_group(&mut self, zone_group: Option<ThermalZoneGroupConfig>) {
+        self.zone_group = zone_group;
+    }
+
     /// Start the thermal regulation loop in a separate thread
     pub fn start(&mut self) -> Result<()> {
         let regulator_id = self.config.id.clone();
@@ -305,6 +547,7 @@ impl ThermalRegulatorDaemon {
         let bus_config = self.bus_config.clone();
         let shared_state = self.shared_state.clone();
         let running = self.running.clone();
+        let zone_group = self.zone_group.clone();
 
         let handle = tokio::spawn(async move {
             info!("Thermal regulator '{}' thread started", regulator_id);
@@ -331,14 +574,11 @@ impl ThermalRegulatorDaemon {
             }
 
             // Create PID controller
-            let mut pid_controller = PidController::new(
-                config.pid_parameters.kp as f64,
-                config.pid_parameters.ki as f64,
-                config.pid_parameters.kd as f64,
-                (config.pid_parameters.setpoint - 273.15) as f64, // Convert K to C
-                config.pid_parameters.integral_max as f64,
-                config.pid_parameters.output_min as f64,
-                config.pid_parameters.output_max as f64,
+            let mut pid_controller = PidController::from_parameters(&config.pid_parameters);
+            let control_settings = &config.control_parameters.settings;
+            pid_controller.set_dead_band(
+                control_settings.deadband_k as f64, // Kelvin and Celsius widths are equal
+                control_settings.deadband_output,
             );
 
             // Update status to running
@@ -351,6 +591,23 @@ impl ThermalRegulatorDaemon {
 
             let mut interval = time::interval(interval_duration);
             let mut iteration_count = 0u64;
+            // Latched safety shutdown, cleared only by restarting the regulator
+            let mut safety_fault: Option<String> = None;
+
+            // Time series log of the regulator telemetry
+            let mut data_logger = match &config.data_log {
+                Some(data_log) => match ThermalDataLogger::open(data_log) {
+                    Ok(logger) => Some(logger),
+                    Err(e) => {
+                        error!(
+                            "Failed to open thermal data log {} for '{}': {}",
+                            data_log.path, regulator_id, e
+                        );
+                        None
+                    }
+                },
+                None => None,
+            };
 
             while running.load(Ordering::Relaxed) {
                 tokio::select! {
@@ -405,11 +662,119 @@ impl ThermalRegulatorDaemon {
 
                         // Execute regulation cycle inline to avoid Send issues
                         if let Err(e) = async {
+                            // Apply setpoints requested through the shared state (e.g. Modbus)
+                            let pending_setpoint = {
+                                let mut state = shared_state.write().await;
+                                state.take_pending_setpoint(&regulator_id)
+                            };
+                            if let Some(setpoint_celsius) = pending_setpoint {
+                                pid_controller.set_setpoint(setpoint_celsius);
+                                {
+                                    let mut state = shared_state.write().await;
+                                    state.update_regulator_pid_params(
+                                        &regulator_id,
+                                        pid_controller.get_current_params(),
+                                    )?;
+                                }
+                                info!("Applied requested setpoint {} °C to regulator '{}'",
+                                      setpoint_celsius, regulator_id);
+                            }
+
+                            // Shut down on an active fault input or an overcurrent,
+                            // the current reflecting the output of the previous cycle
+                            if safety_fault.is_none() {
+                                let mut shutdown_reason = check_fault_inputs(driver.as_mut())
+                                    .await?
+                                    .map(|input| format!("fault input '{}' active", input.name));
+                                if shutdown_reason.is_none() {
+                                    if let Some(current_sense) = &config.current_sense {
+                                        let threshold_a = current_sense.overcurrent_threshold_a;
+                                        shutdown_reason = check_overcurrent(driver.as_mut(), threshold_a)
+                                            .await?
+                                            .map(|current| {
+                                                format!(
+                                                    "overcurrent {:.2} A above {:.2} A",
+                                                    current, threshold_a
+                                                )
+                                            });
+                                    }
+                                }
+                                if let Some(reason) = shutdown_reason {
+                                    let message = format!("Safety shutdown: {}", reason);
+                                    error!("Thermal regulator '{}': {}", regulator_id, message);
+                                    {
+                                        let mut state = shared_state.write().await;
+                                        state.update_regulator_status(
+                                            &regulator_id,
+                                            RegulatorStatus::Error {
+                                                message: message.clone(),
+                                            },
+                                        )?;
+                                    }
+                                    safety_fault = Some(message);
+                                }
+                            }
+
+                            // Keep the actuators off after a safety shutdown
+                            if let Some(message) = &safety_fault {
+                                driver.apply_control_output(0.0).await?;
+                                if data_logger.is_some() {
+                                    let temperature_celsius = driver.read_temperature().await?;
+                                    append_data_log(
+                                        &mut data_logger,
+                                        &ThermalLogRecord::now(
+                                            &regulator_id,
+                                            temperature_celsius,
+                                            pid_controller.setpoint_celsius,
+                                            0.0,
+                                            Some(message.clone()),
+                                        ),
+                                    );
+                                }
+                                return Ok(());
+                            }
+
+                            // Measure the feedforward disturbance
+                            if let Some(input_name) = &config.pid_parameters.feedforward.disturbance_input {
+                                let active = driver
+                                    .read_digital_inputs()
+                                    .await?
+                                    .iter()
+                                    .any(|input| &input.name == input_name && input.active);
+                                pid_controller.set_disturbance(if active { 1.0 } else { 0.0 });
+                            }
+
                             // Read current temperature
                             let temperature_celsius = driver.read_temperature().await?;
 
+                            // Coordinate with the zones published by the group in the shared state
+                            let zone_control = match &zone_group {
+                                Some(group) => {
+                                    let readings = shared_state.read().await.zone_readings(&group.zones);
+                                    coordinate(
+                                        group,
+                                        &regulator_id,
+                                        temperature_celsius,
+                                        pid_controller.setpoint_celsius,
+                                        &readings,
+                                    )
+                                }
+                                None => ZoneControl::Regulate {
+                                    process_variable: temperature_celsius,
+                                },
+                            };
+
                             // Calculate PID output
-                            let pid_output = pid_controller.update(temperature_celsius);
+                            let pid_output = match zone_control {
+                                ZoneControl::Regulate { process_variable } => {
+                                    pid_controller.update(process_variable)
+                                }
+                                ZoneControl::Follow { control_output_percent } => {
+                                    // Restart the PID from scratch if the master stops
+                                    pid_controller.reset();
+                                    pid_controller.follow(control_output_percent, temperature_celsius)
+                                }
+                            };
 
                             // Apply control output to hardware
                             driver.apply_control_output(pid_output.control_output).await?;
@@ -426,6 +791,17 @@ impl ThermalRegulatorDaemon {
                                 )?;
                             }
 
+                            append_data_log(
+                                &mut data_logger,
+                                &ThermalLogRecord::now(
+                                    &regulator_id,
+                                    temperature_celsius,
+                                    pid_controller.setpoint_celsius,
+                                    pid_output.control_output,
+                                    None,
+                                ),
+                            );
+
                             Ok::<(), anyhow::Error>(())
                         }.await {
                             error!(
@@ -570,6 +946,7 @@ impl ThermalRegulationSystemDaemon {
             )
             .await?;
 
+            regulator_daemon.set_zone_group(self.config.zone_group(&regulator_config.id).cloned());
             regulator_daemon.start()?;
             self.regulator_daemons.push(regulator_daemon);
 
@@ -768,4 +1145,182 @@ mod tests {
             "Integral should be limited by anti-windup"
         );
     }
+
+    /// Time for a PID regulated plant to first come within 0.2 °C of a 25 → 35 °C setpoint step
+    ///
+    /// The plant is a 500 J/K body losing 2 W/K to a 25 °C ambient, driven by
+    /// a 60 W heater after a 2 s deadtime: holding 35 °C takes 20 W, a 33.3 %
+    /// output, reached by the feedforward alone with a gain of 3.33 %/°C.
+    fn time_to_setpoint(feedforward_gain: f64) -> Option<f64> {
+        use crate::thermal_regulation::simulation::{ThermalPlant, ThermalPlantParameters};
+
+        let mut plant = ThermalPlant::new(ThermalPlantParameters {
+            thermal_mass_j_per_k: 500.0,
+            heat_transfer_w_per_k: 2.0,
+            ambient_temperature_c: 25.0,
+            actuator_deadtime_s: 2.0,
+            sensor_time_constant_s: None,
+            heating_efficiency: 1.0,
+            cooling_efficiency: 1.0,
+            max_cooling_below_ambient_k: None,
+        });
+        let mut pid = PidController::new(5.0, 0.005, 0.0, 35.0, 10_000.0, -100.0, 100.0);
+        pid.set_feedforward(feedforward_gain, 25.0, 0.0);
+
+        for _ in 0..6000 {
+            let output = pid.update_with_dt(plant.temperature(), 1.0);
+            plant.set_power(output.control_output / 100.0 * 60.0);
+            plant.step(1.0);
+            if (plant.temperature() - 35.0).abs() <= 0.2 {
+                return Some(plant.elapsed());
+            }
+        }
+        None
+    }
+
+    /// Test the feedforward term reduces the time to reach a new setpoint
+    #[test]
+    fn test_feedforward_reaches_setpoint_faster() {
+        let with_feedforward = time_to_setpoint(100.0 * 2.0 / 60.0).expect("setpoint reached");
+        // Without feedforward the slow integral term has to build the 33.3 % output
+        let without_feedforward = time_to_setpoint(0.0).unwrap_or(f64::INFINITY);
+
+        assert!(
+            with_feedforward < without_feedforward / 2.0,
+            "with feedforward {} s, without {} s",
+            with_feedforward,
+            without_feedforward
+        );
+    }
+
+    /// Test a zero feedforward leaves the PID output unchanged
+    #[test]
+    fn test_zero_feedforward_unchanged() {
+        let mut pid = PidController::new(1.0, 0.1, 0.01, 30.0, 100.0, -100.0, 100.0);
+        let mut pid_feedforward = pid.clone();
+        pid_feedforward.set_feedforward(0.0, 25.0, 0.0);
+        pid_feedforward.set_disturbance(1.0);
+
+        for temperature in [20.0, 24.0, 29.0, 31.0, 30.0] {
+            let output = pid.update_with_dt(temperature, 0.2);
+            let output_feedforward = pid_feedforward.update_with_dt(temperature, 0.2);
+            assert_eq!(output.control_output, output_feedforward.control_output);
+            assert_eq!(output_feedforward.components.feedforward, 0.0);
+        }
+    }
+
+    /// Test the feedforward follows the setpoint and the measured disturbance
+    #[test]
+    fn test_feedforward_setpoint_and_disturbance() {
+        let mut pid = PidController::new(0.0, 0.0, 0.0, 35.0, 100.0, -100.0, 100.0);
+        pid.set_feedforward(2.0, 25.0, 15.0);
+        assert_eq!(pid.update_with_dt(35.0, 1.0).control_output, 20.0);
+
+        // Gas flow turning on adds its base load
+        pid.set_disturbance(1.0);
+        let output = pid.update_with_dt(35.0, 1.0);
+        assert_eq!(output.control_output, 35.0);
+        assert_eq!(output.components.feedforward, 35.0);
+
+        // Below the reference temperature the feedforward cools
+        pid.set_disturbance(0.0);
+        pid.set_setpoint(15.0);
+        assert_eq!(pid.update_with_dt(15.0, 1.0).control_output, -20.0);
+    }
+
+    /// Test the output and the integral are frozen within the dead-band
+    #[test]
+    fn test_dead_band_freezes_output_and_integral() {
+        let mut pid = PidController::new(2.0, 0.5, 0.0, 30.0, 100.0, -100.0, 100.0);
+        pid.set_dead_band(0.5, DeadbandOutput::Zero);
+
+        for _ in 0..3 {
+            assert!(pid.update_with_dt(28.0, 1.0).control_output > 0.0);
+        }
+        assert!(!pid.in_dead_band());
+        let integral = pid.integral;
+
+        for temperature in [29.6, 29.8, 30.0, 30.5, 29.5] {
+            let output = pid.update_with_dt(temperature, 1.0);
+            assert_eq!(output.control_output, 0.0);
+            assert!(pid.in_dead_band());
+        }
+        assert_eq!(pid.integral, integral);
+
+        // Drifting out of the dead-band resumes control from the frozen integral
+        let output = pid.update_with_dt(29.0, 1.0);
+        assert!(!pid.in_dead_band());
+        assert!((output.control_output - (2.0 + 0.5 * (integral + 1.0))).abs() < 1e-9);
+    }
+
+    /// Test the hold mode keeps the last output within the dead-band
+    #[test]
+    fn test_dead_band_holds_last_output() {
+        let mut pid = PidController::new(2.0, 0.5, 0.0, 30.0, 100.0, -100.0, 100.0);
+        pid.set_dead_band(0.5, DeadbandOutput::Hold);
+
+        let last_output = pid.update_with_dt(29.0, 1.0).control_output;
+        for temperature in [29.6, 30.4, 30.0] {
+            assert_eq!(
+                pid.update_with_dt(temperature, 1.0).control_output,
+                last_output
+            );
+        }
+    }
+
+    /// Outputs of a PID regulated plant whose ambient rises from 30.2 to 33 °C after 600 s
+    ///
+    /// The plant is a 500 J/K body losing 2 W/K to the ambient, driven by a
+    /// 60 W Peltier after a 2 s deadtime, regulated at 30 °C.
+    fn dead_band_run(dead_band_celsius: f64) -> (Vec<f64>, Vec<f64>) {
+        use crate::thermal_regulation::simulation::{ThermalPlant, ThermalPlantParameters};
+
+        let mut plant = ThermalPlant::new(ThermalPlantParameters {
+            thermal_mass_j_per_k: 500.0,
+            heat_transfer_w_per_k: 2.0,
+            ambient_temperature_c: 30.2,
+            actuator_deadtime_s: 2.0,
+            sensor_time_constant_s: None,
+            heating_efficiency: 1.0,
+            cooling_efficiency: 1.0,
+            max_cooling_below_ambient_k: None,
+        });
+        let mut pid = PidController::new(5.0, 0.05, 0.0, 30.0, 10_000.0, -100.0, 100.0);
+        pid.set_dead_band(dead_band_celsius, DeadbandOutput::Zero);
+
+        let mut outputs = Vec::new();
+        let mut temperatures = Vec::new();
+        for step in 0..6000 {
+            if step == 600 {
+                plant.set_ambient_temperature(33.0);
+            }
+            let output = pid.update_with_dt(plant.temperature(), 1.0);
+            plant.set_power(output.control_output / 100.0 * 60.0);
+            plant.step(1.0);
+            outputs.push(output.control_output);
+            temperatures.push(plant.temperature());
+        }
+        (outputs, temperatures)
+    }
+
+    /// Test the output settles to zero within the dead-band and control resumes outside
+    #[test]
+    fn test_dead_band_settles_and_resumes() {
+        // Without dead-band the PID keeps cooling 0.2 °C away
+        let (outputs, _) = dead_band_run(0.0);
+        assert!(outputs[..600].iter().any(|output| *output != 0.0));
+
+        let (outputs, temperatures) = dead_band_run(0.5);
+        assert!(outputs[..600].iter().all(|output| *output == 0.0));
+
+        // The warmer ambient pushes the temperature out and the PID cools again
+        assert!(outputs[600..].iter().any(|output| *output < 0.0));
+        for temperature in &temperatures[3000..] {
+            assert!(
+                (temperature - 30.0).abs() < 0.6,
+                "temperature {} °C",
+                temperature
+            );
+        }
+    }
 }
@@ -0,0 +1,129 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Thermal regulation data log
+//!
+//! [`ThermalDataLogger`] appends one [`ThermalLogRecord`] per regulation cycle
+//! to a CSV or JSON lines file, giving the time series needed to tune the PID
+//! gains or to analyze a safety shutdown afterwards.
+//!
+//! The file is rotated by a [`RollingFileWriter`], like the daemon log. Each
+//! row is written in a single call, and in CSV format the header row is
+//! written along with the first row of every new file, so that each rotated
+//! file can be read on its own.
+
+use std::io::{self, Write};
+use std::path::Path;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::thermal_regulation::{ThermalDataLogConfig, ThermalDataLogFormat};
+use crate::utility::log_file::RollingFileWriter;
+
+/// Header row of the CSV data log, matching the fields of [`ThermalLogRecord`]
+pub const CSV_HEADER: &str =
+    "timestamp,regulator_id,temperature_celsius,setpoint_celsius,control_output,fault\n";
+
+/// Telemetry of a thermal regulator for one regulation cycle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThermalLogRecord {
+    /// Time of the regulation cycle
+    pub timestamp: DateTime<Utc>,
+    /// Identifier of the regulator
+    pub regulator_id: String,
+    /// Measured temperature in Celsius
+    pub temperature_celsius: f64,
+    /// Setpoint in Celsius
+    pub setpoint_celsius: f64,
+    /// Control output applied to the actuators (-100.0 to 100.0%)
+    pub control_output: f64,
+    /// Safety shutdown message, while the regulator is shut down
+    pub fault: Option<String>,
+}
+
+impl ThermalLogRecord {
+    /// Create a record of the current regulation cycle
+    ///
+    /// # Arguments
+    /// * `regulator_id` - Identifier of the regulator
+    /// * `temperature_celsius` - Measured temperature in Celsius
+    /// * `setpoint_celsius` - Setpoint in Celsius
+    /// * `control_output` - Applied control output in percent
+    /// * `fault` - Active safety shutdown message, if any
+    pub fn now(
+        regulator_id: &str,
+        temperature_celsius: f64,
+        setpoint_celsius: f64,
+        control_output: f64,
+        fault: Option<String>,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            regulator_id: regulator_id.to_string(),
+            temperature_celsius,
+            setpoint_celsius,
+            control_output,
+            fault,
+        }
+    }
+}
+
+/// Rotated CSV or JSON lines log of the telemetry of a regulator
+#[derive(Debug)]
+pub struct ThermalDataLogger {
+    writer: RollingFileWriter,
+    format: ThermalDataLogFormat,
+}
+
+impl ThermalDataLogger {
+    /// Open the data log described by `config` for appending
+    ///
+    /// The file and its directory are created if needed.
+    pub fn open(config: &ThermalDataLogConfig) -> io::Result<Self> {
+        Ok(Self {
+            writer: RollingFileWriter::open(&config.path, config.max_size, config.max_files)?,
+            format: config.format,
+        })
+    }
+
+    /// Path of the current log file
+    pub fn path(&self) -> &Path {
+        self.writer.path()
+    }
+
+    /// Append a row to the log
+    ///
+    /// # Arguments
+    /// * `record` - Telemetry of the regulation cycle
+    pub fn log(&mut self, record: &ThermalLogRecord) -> Result<()> {
+        let mut row = match self.format {
+            ThermalDataLogFormat::Csv => {
+                let mut writer = csv::WriterBuilder::new()
+                    .has_headers(false)
+                    .from_writer(Vec::new());
+                writer.serialize(record)?;
+                writer.into_inner()?
+            }
+            ThermalDataLogFormat::Json => {
+                let mut row = serde_json::to_vec(record)?;
+                row.push(b'\n');
+                row
+            }
+        };
+
+        if self.format == ThermalDataLogFormat::Csv
+            && self
+                .writer
+                .starts_new_file((CSV_HEADER.len() + row.len()) as u64)
+        {
+            row.splice(0..0, CSV_HEADER.bytes());
+        }
+
+        self.writer.write_all(&row)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
@@ -13,6 +13,7 @@
 //! - Realistic thermal time constants and responses
 
 use crate::config::thermal_regulation::I2CBusConfig;
+use crate::thermal_regulation::simulation::{ThermalPlant, ThermalPlantParameters};
 use crate::thermal_regulation::I2CBusDriver;
 use anyhow::{anyhow, Result};
 use log::{debug, info};
@@ -39,6 +40,8 @@ const PELTIER_WIDTH_MM: f64 = 30.0;
 const PELTIER_MAX_POWER_W: f64 = 32.0;
 /// Heating resistor maximum power in Watts (DBK HPG-1/10-60x35-12-24V)
 const HEATER_MAX_POWER_W: f64 = 60.0;
+/// H-Bridge supply voltage in Volts (heating resistor rated for 24V)
+const HBRIDGE_SUPPLY_VOLTAGE_V: f64 = 24.0;
 
 /// Mock I2C driver for thermal regulation simulation with L298N H-Bridge control
 ///
@@ -84,6 +87,10 @@ pub struct MockI2CL298NDriver {
     /// In real hardware: KEEP THIS - essential for proper L298N control coordination
     h_bridge_state: Arc<Mutex<HBridgeState>>,
 
+    /// H-Bridge load current simulation feeding the current-sense ADC channel
+    /// In real hardware: Remove this, the current is measured on the shunt
+    current_sense: Arc<Mutex<CurrentSenseSimulation>>,
+
     /// Driver initialization timestamp for debugging and diagnostics
     start_time: Instant,
 }
@@ -110,18 +117,18 @@ pub enum MockDeviceType {
 }
 
 /// Thermal simulation of the photoacoustic cell
+///
+/// The actuator powers drive a [`ThermalPlant`] built from the cell properties.
 #[derive(Debug)]
 pub struct ThermalCellSimulation {
-    /// Current temperature of the cell in Celsius
-    temperature: f64,
+    /// Thermal plant model of the cell
+    plant: ThermalPlant,
     /// Target temperature for regulation
     target_temperature: f64,
     /// Current Peltier power (-100 to +100%)
     peltier_power: f64,
     /// Current heating resistor power (0 to 100%)
     heater_power: f64,
-    /// Ambient temperature in Celsius
-    ambient_temperature: f64,
     /// Last simulation update time
     last_update: Instant,
     /// Last logging time for periodic status messages
@@ -151,8 +158,16 @@ pub struct ThermalProperties {
     peltier_dimensions_mm: (f64, f64),
     /// Heating resistor maximum power (W) - DBK HPG-1/10-60x35-12-24V
     heater_max_power: f64,
-    /// Thermal time constant (seconds)
-    thermal_time_constant: f64,
+    /// Delay for the actuator heat to reach the cell body (seconds)
+    actuator_deadtime: f64,
+    /// Time constant of the sensor coupling to the cell body (seconds)
+    sensor_time_constant: f64,
+    /// Fraction of the heater power reaching the cell body
+    heater_efficiency: f64,
+    /// Heat pumped out of the cell per Watt of Peltier power (coefficient of performance)
+    peltier_cooling_efficiency: f64,
+    /// Maximum temperature difference of the Peltier module below ambient (K)
+    peltier_max_delta_t: f64,
 }
 
 impl Default for ThermalProperties {
@@ -174,7 +189,28 @@ impl Default for ThermalProperties {
             peltier_max_power: PELTIER_MAX_POWER_W,
             peltier_dimensions_mm: (PELTIER_LENGTH_MM, PELTIER_WIDTH_MM),
             heater_max_power: HEATER_MAX_POWER_W,
-            thermal_time_constant: 90.0, // seconds (reduced for faster response with 60W heater)
+            actuator_deadtime: 2.0, // seconds through the actuator mounting
+            sensor_time_constant: 20.0, // seconds for a thermistor bonded to the cell
+            heater_efficiency: 0.95, // resistor losses through its leads and mounting
+            peltier_cooling_efficiency: 0.5, // typical COP with the hot side at ambient
+            peltier_max_delta_t: 40.0, // K with a heatsink on the hot side
+        }
+    }
+}
+
+impl ThermalProperties {
+    /// Parameters of the thermal plant model of the cell
+    pub fn plant_parameters(&self, ambient_temperature: f64) -> ThermalPlantParameters {
+        ThermalPlantParameters {
+            // J/K (mass converted from g to kg)
+            thermal_mass_j_per_k: (self.mass_g / 1000.0) * self.specific_heat,
+            heat_transfer_w_per_k: self.heat_transfer_coefficient * self.surface_area_m2,
+            ambient_temperature_c: ambient_temperature,
+            actuator_deadtime_s: self.actuator_deadtime,
+            sensor_time_constant_s: Some(self.sensor_time_constant),
+            heating_efficiency: self.heater_efficiency,
+            cooling_efficiency: self.peltier_cooling_efficiency,
+            max_cooling_below_ambient_k: Some(self.peltier_max_delta_t),
         }
     }
 }
@@ -265,6 +301,7 @@ impl MockI2CL298NDriver {
             devices: Arc::new(Mutex::new(devices)),
             thermal_simulation: Arc::new(Mutex::new(thermal_simulation)),
             h_bridge_state: Arc::new(Mutex::new(HBridgeState::default())), // Safe initial state
+            current_sense: Arc::new(Mutex::new(CurrentSenseSimulation::default())),
             start_time: Instant::now(),
         })
     }
@@ -276,9 +313,9 @@ impl MockI2CL298NDriver {
             .lock()
             .map_err(|_| anyhow!("Failed to lock thermal simulation"))?;
 
-        let old_temp = simulation.temperature;
+        let old_temp = simulation.get_temperature();
         simulation.update();
-        let new_temp = simulation.temperature;
+        let new_temp = simulation.get_temperature();
 
         // Always show debug output for temperature changes
         debug!(
@@ -290,7 +327,9 @@ impl MockI2CL298NDriver {
         if simulation.last_log_time.elapsed() >= Duration::from_secs(60) {
             info!(
                 "Thermal simulation status: {:.2}°C, Peltier power: {:.1}%, Heater power: {:.1}%",
-                simulation.temperature, simulation.peltier_power, simulation.heater_power
+                simulation.get_temperature(),
+                simulation.peltier_power,
+                simulation.heater_power
             );
             simulation.last_log_time = Instant::now();
         }
@@ -303,7 +342,7 @@ impl MockI2CL298NDriver {
             .thermal_simulation
             .lock()
             .map_err(|_| anyhow!("Failed to lock thermal simulation"))?;
-        Ok(simulation.temperature)
+        Ok(simulation.get_temperature())
     }
 
     /// Set Peltier power for simulation
@@ -325,6 +364,85 @@ impl MockI2CL298NDriver {
         simulation.set_heater_power(power_percent);
         Ok(())
     }
+
+    /// Drive an emulated input pin (0-15) of a GPIO controller (CAT9555)
+    ///
+    /// Pins 0-7 are on input port 0 (register 0x00), pins 8-15 on input
+    /// port 1 (register 0x01).
+    pub fn set_input_pin(&self, address: u8, pin: u8, high: bool) -> Result<()> {
+        if pin > 15 {
+            return Err(anyhow!("Invalid GPIO pin {}", pin));
+        }
+        let mut devices = self
+            .devices
+            .lock()
+            .map_err(|_| anyhow!("Failed to lock devices"))?;
+        let device = devices
+            .get_mut(&address)
+            .filter(|device| matches!(device.device_type, MockDeviceType::GpioController))
+            .ok_or_else(|| anyhow!("GPIO controller not found at address 0x{:02X}", address))?;
+
+        let input_port = device.registers.entry(pin / 8).or_insert(0xFF);
+        let mask = 1u8 << (pin % 8);
+        if high {
+            *input_port |= mask;
+        } else {
+            *input_port &= !mask;
+        }
+        Ok(())
+    }
+
+    /// Route the simulated H-Bridge current to an ADC controller channel
+    ///
+    /// `volts_per_amp` is the transimpedance of the current-sense circuit,
+    /// the shunt resistance times the amplifier gain.
+    pub fn set_current_sense_input(
+        &self,
+        address: u8,
+        channel: u8,
+        volts_per_amp: f64,
+    ) -> Result<()> {
+        let mut current_sense = self
+            .current_sense
+            .lock()
+            .map_err(|_| anyhow!("Failed to lock current sense simulation"))?;
+        current_sense.adc_input = Some((address, channel));
+        current_sense.volts_per_amp = volts_per_amp;
+        Ok(())
+    }
+
+    /// Set the resistance of the simulated H-Bridge load in ohms
+    pub fn set_load_resistance(&self, resistance_ohm: f64) -> Result<()> {
+        if resistance_ohm <= 0.0 {
+            return Err(anyhow!("Invalid load resistance {} ohm", resistance_ohm));
+        }
+        let mut current_sense = self
+            .current_sense
+            .lock()
+            .map_err(|_| anyhow!("Failed to lock current sense simulation"))?;
+        current_sense.load_resistance = resistance_ohm;
+        Ok(())
+    }
+
+    /// Simulated H-Bridge 1 load current in amps
+    ///
+    /// The PWM chops the supply voltage across the load, so the average
+    /// current is proportional to the duty cycle.
+    fn simulated_load_current(&self) -> Result<f64> {
+        let state = self
+            .h_bridge_state
+            .lock()
+            .map_err(|_| anyhow!("Failed to lock H-Bridge state"))?;
+        if matches!(state.h1_direction, HBridgeDirection::Disabled) {
+            return Ok(0.0);
+        }
+        let current_sense = self
+            .current_sense
+            .lock()
+            .map_err(|_| anyhow!("Failed to lock current sense simulation"))?;
+        Ok(state.h1_duty_cycle / 100.0 * current_sense.supply_voltage
+            / current_sense.load_resistance)
+    }
 }
 
 #[async_trait::async_trait]
@@ -375,9 +493,9 @@ impl I2CBusDriver for MockI2CL298NDriver {
 
         match device.device_type {
             MockDeviceType::TemperatureSensor => self.read_temperature_sensor(register, length),
-            MockDeviceType::AdcController => self.read_adc_controller(register, length),
+            MockDeviceType::AdcController => self.read_adc_controller(device, register, length),
             MockDeviceType::PwmController => self.read_pwm_controller(register, length),
-            MockDeviceType::GpioController => self.read_gpio_controller(register, length),
+            MockDeviceType::GpioController => self.read_gpio_controller(device, register, length),
         }
     }
 
@@ -523,7 +641,7 @@ impl MockI2CL298NDriver {
                     .map_err(|_| anyhow!("Failed to lock thermal simulation"))?;
 
                 // Convert temperature to MCP9808 format (16-bit, 0.0625°C resolution)
-                let temp_c = simulation.temperature;
+                let temp_c = simulation.get_temperature();
                 // MCP9808 uses 16-bit signed format: temp = register_value / 16.0
                 let temp_raw = (temp_c * 16.0) as i16;
 
@@ -593,7 +711,26 @@ impl MockI2CL298NDriver {
     }
 
     /// Read from ADC controller (ADS1115)
-    fn read_adc_controller(&self, register: u8, length: usize) -> Result<Vec<u8>> {
+    fn read_adc_controller(
+        &self,
+        device: &MockDevice,
+        register: u8,
+        length: usize,
+    ) -> Result<Vec<u8>> {
+        let (current_sense_input, volts_per_amp) = {
+            let current_sense = self
+                .current_sense
+                .lock()
+                .map_err(|_| anyhow!("Failed to lock current sense simulation"))?;
+            (current_sense.adc_input, current_sense.volts_per_amp)
+        };
+        if current_sense_input == Some((device.address, register)) {
+            // Current-sense channel: shunt voltage amplified, saturating at 5V
+            let v_adc = (self.simulated_load_current()? * volts_per_amp).clamp(0.0, 5.0);
+            let adc_raw = ((v_adc / 5.0) * 65535.0) as u16;
+            return Ok(vec![(adc_raw >> 8) as u8, (adc_raw & 0xFF) as u8]);
+        }
+
         match register {
             0x00 => {
                 // Conversion register
@@ -607,7 +744,7 @@ impl MockI2CL298NDriver {
                 // Circuit: 5V --- 10kΩ resistor --- ADC input --- NTC --- GND
                 // ADC voltage = 5V * R_ntc / (10000 + R_ntc)
 
-                let temp_c = simulation.temperature;
+                let temp_c = simulation.get_temperature();
                 let temp_k = temp_c + 273.15;
 
                 // NTC resistance using β formula: R = R0 * exp(β * (1/T - 1/T0))
@@ -816,11 +953,21 @@ impl MockI2CL298NDriver {
     }
 
     /// Read from GPIO controller (CAT9555)
-    fn read_gpio_controller(&self, register: u8, length: usize) -> Result<Vec<u8>> {
+    ///
+    /// The input ports return the emulated input pins, all high (pulled up)
+    /// until driven low with [`MockI2CL298NDriver::set_input_pin`].
+    fn read_gpio_controller(
+        &self,
+        device: &MockDevice,
+        register: u8,
+        length: usize,
+    ) -> Result<Vec<u8>> {
         match register {
             0x00 | 0x01 => {
-                // Input port registers
-                Ok(vec![0xFF, 0xFF]) // All inputs high
+                // Input port registers, read in pairs like the real device
+                let input_port =
+                    |register: u8| device.registers.get(&register).copied().unwrap_or(0xFF);
+                Ok(vec![input_port(register), input_port(register ^ 0x01)])
             }
             0x02 | 0x03 => {
                 // Output port registers
@@ -965,19 +1112,20 @@ impl ThermalCellSimulation {
     /// Create a new thermal cell simulation
     pub fn new() -> Self {
         let now = Instant::now();
+        let properties = ThermalProperties::default();
         Self {
-            temperature: AMBIENT_ROOM_TEMP_C, // Start at room temperature
+            // Start at room temperature
+            plant: ThermalPlant::new(properties.plant_parameters(AMBIENT_ROOM_TEMP_C)),
             target_temperature: 41.0,
             peltier_power: 0.0,
             heater_power: 0.0,
-            ambient_temperature: AMBIENT_ROOM_TEMP_C,
             last_update: now,
             last_log_time: now,
-            properties: ThermalProperties::default(),
+            properties,
         }
     }
 
-    /// Update thermal simulation
+    /// Update thermal simulation with the wall-clock time since the last update
     pub fn update(&mut self) {
         let now = Instant::now();
         let dt = now.duration_since(self.last_update).as_secs_f64();
@@ -985,55 +1133,46 @@ impl ThermalCellSimulation {
 
         if dt > 0.0 && dt < 10.0 {
             // Sanity check on time step
-            self.temperature = self.calculate_next_temperature(dt);
+            self.advance(dt);
         }
     }
 
-    /// Calculate next temperature based on thermal dynamics
-    fn calculate_next_temperature(&self, dt: f64) -> f64 {
+    /// Advance the simulation by `dt` seconds of simulated time
+    pub fn advance(&mut self, dt: f64) {
+        self.plant.step(dt);
+    }
+
+    /// Command the plant with the total actuator power
+    fn command_plant(&mut self) {
         // Heat input from Peltier (positive = heating, negative = cooling)
         let peltier_heat = self.peltier_power / 100.0 * self.properties.peltier_max_power;
 
         // Heat input from resistive heater (always positive)
         let heater_heat = self.heater_power / 100.0 * self.properties.heater_max_power;
 
-        // Heat loss to ambient (convective cooling)
-        let temp_diff = self.temperature - self.ambient_temperature;
-        let ambient_heat_loss =
-            self.properties.heat_transfer_coefficient * self.properties.surface_area_m2 * temp_diff;
-
-        // Total heat rate (Watts)
-        let total_heat_rate = peltier_heat + heater_heat - ambient_heat_loss;
-
-        // Temperature change using thermal mass
-        let thermal_mass = (self.properties.mass_g / 1000.0) * self.properties.specific_heat; // J/K (mass converted from g to kg)
-        let temp_change = total_heat_rate * dt / thermal_mass; // K
-
-        // Apply first-order thermal lag using time constant
-        let thermal_lag_factor = 1.0 - (-dt / self.properties.thermal_time_constant).exp();
-        let effective_temp_change = temp_change * thermal_lag_factor;
-
-        self.temperature + effective_temp_change
+        self.plant.set_power(peltier_heat + heater_heat);
     }
 
     /// Set Peltier power (-100 to +100%)
     pub fn set_peltier_power(&mut self, power: f64) {
         self.peltier_power = power.clamp(-100.0, 100.0);
+        self.command_plant();
     }
 
     /// Set heating resistor power (0 to 100%)
     pub fn set_heater_power(&mut self, power: f64) {
         self.heater_power = power.clamp(0.0, 100.0);
+        self.command_plant();
     }
 
     /// Set ambient temperature
     pub fn set_ambient_temperature(&mut self, temp: f64) {
-        self.ambient_temperature = temp;
+        self.plant.set_ambient_temperature(temp);
     }
 
-    /// Get current temperature
+    /// Get current temperature, as measured by the sensor
     pub fn get_temperature(&self) -> f64 {
-        self.temperature
+        self.plant.temperature()
     }
 
     /// Get thermal properties
@@ -1156,6 +1295,35 @@ impl Default for HBridgeState {
     }
 }
 
+/// Simulated current-sense circuit of H-Bridge 1
+///
+/// The load is modeled as a resistor across the H-Bridge supply; lowering
+/// its resistance simulates a shorted heater or Peltier module.
+#[derive(Debug)]
+struct CurrentSenseSimulation {
+    /// H-Bridge supply voltage (V)
+    supply_voltage: f64,
+    /// Load resistance (Ω)
+    load_resistance: f64,
+    /// ADC controller address and channel wired to the amplifier output
+    adc_input: Option<(u8, u8)>,
+    /// Amplifier output voltage per amp of load current (V/A)
+    volts_per_amp: f64,
+}
+
+impl Default for CurrentSenseSimulation {
+    fn default() -> Self {
+        Self {
+            supply_voltage: HBRIDGE_SUPPLY_VOLTAGE_V,
+            // Resistance of the heating resistor at its rated power
+            load_resistance: HBRIDGE_SUPPLY_VOLTAGE_V * HBRIDGE_SUPPLY_VOLTAGE_V
+                / HEATER_MAX_POWER_W,
+            adc_input: None,
+            volts_per_amp: 0.0,
+        }
+    }
+}
+
 impl MockI2CL298NDriver {
     /// Apply thermal power based on H-Bridge state and PWM duty cycle
     ///
@@ -1397,8 +1565,7 @@ mod tests {
 
         // Simulate 60 seconds of heating
         for _ in 0..600 {
-            std::thread::sleep(Duration::from_millis(1));
-            sim.update();
+            sim.advance(0.1);
         }
 
         let final_temp = sim.get_temperature();
@@ -1415,11 +1582,88 @@ mod tests {
 
         // Simulate 60 seconds of cooling
         for _ in 0..600 {
-            std::thread::sleep(Duration::from_millis(1));
-            sim.update();
+            sim.advance(0.1);
         }
 
         let cooled_temp = sim.get_temperature();
         assert!(cooled_temp < heated_temp);
     }
+
+    #[test]
+    fn test_thermal_lag_and_deadtime() {
+        let mut sim = ThermalCellSimulation::new();
+        sim.set_heater_power(100.0);
+
+        // No response within the actuator deadtime
+        sim.advance(1.5);
+        assert_eq!(sim.get_temperature(), AMBIENT_ROOM_TEMP_C);
+
+        // The sensor keeps rising after the heater is switched off
+        sim.advance(120.0);
+        sim.set_heater_power(0.0);
+        let switch_off_temp = sim.get_temperature();
+        sim.advance(10.0);
+        assert!(sim.get_temperature() > switch_off_temp);
+
+        // The plant time constant follows the cell properties
+        let parameters = sim.get_properties().plant_parameters(AMBIENT_ROOM_TEMP_C);
+        let tau = parameters.time_constant_s();
+        assert!(tau > 600.0 && tau < 1200.0, "time constant {} s", tau);
+    }
+
+    #[test]
+    fn test_heating_cooling_asymmetry() {
+        // Equal heater and Peltier power commands
+        let mut heating = ThermalCellSimulation::new();
+        heating.set_heater_power(PELTIER_MAX_POWER_W / HEATER_MAX_POWER_W * 100.0);
+        let mut cooling = ThermalCellSimulation::new();
+        cooling.set_peltier_power(-100.0);
+
+        heating.advance(600.0);
+        cooling.advance(600.0);
+        let rise = heating.get_temperature() - AMBIENT_ROOM_TEMP_C;
+        let drop = AMBIENT_ROOM_TEMP_C - cooling.get_temperature();
+        assert!(drop > 0.0);
+        assert!(rise > 1.5 * drop, "rise {} K, drop {} K", rise, drop);
+
+        // The Peltier module cannot cool further than its maximum temperature difference
+        cooling.advance(20000.0);
+        let floor = AMBIENT_ROOM_TEMP_C - cooling.get_temperature();
+        assert!(floor > 0.0 && floor < 40.0, "{} K below ambient", floor);
+    }
+
+    #[tokio::test]
+    async fn test_gpio_input_pins() {
+        let config = I2CBusConfig {
+            bus_type: crate::config::thermal_regulation::I2CBusType::Mock,
+            device: "mock".to_string(),
+            usb_vendor_id: None,
+            usb_product_id: None,
+            pwm_controllers: vec![],
+            adc_controllers: vec![],
+            gpio_controllers: vec![crate::config::thermal_regulation::GpioControllerConfig {
+                address: 0x20,
+                channels: 16,
+                controller_type: "CAT9555".to_string(),
+                function: Default::default(),
+                settings: Default::default(),
+            }],
+            bus_settings: Default::default(),
+        };
+        let mut driver = MockI2CL298NDriver::new(&config).unwrap();
+
+        // Inputs are pulled up
+        assert_eq!(driver.read_gpio(0x20).await.unwrap(), 0xFFFF);
+
+        driver.set_input_pin(0x20, 3, false).unwrap();
+        driver.set_input_pin(0x20, 9, false).unwrap();
+        assert_eq!(driver.read_gpio(0x20).await.unwrap(), 0xFDF7);
+        assert_eq!(driver.read(0x20, 0x01, 2).await.unwrap(), vec![0xFD, 0xF7]);
+
+        driver.set_input_pin(0x20, 3, true).unwrap();
+        assert_eq!(driver.read_gpio(0x20).await.unwrap(), 0xFDFF);
+
+        assert!(driver.set_input_pin(0x20, 16, false).is_err());
+        assert!(driver.set_input_pin(0x21, 0, false).is_err());
+    }
 }
@@ -8,10 +8,13 @@
 //! - I2C device communication (native, CP2112, and mock drivers)
 //! - PID controller implementation for precise temperature control
 //! - Thermal cell simulation for testing and development
+//! - Coordinated control of adjacent thermal zones
 //! - Hardware abstraction for different thermal control systems
 
 pub mod controller;
+pub mod coordination;
 pub mod daemon;
+pub mod data_log;
 pub mod drivers;
 pub mod shared_state;
 pub mod simulation;
@@ -25,7 +28,12 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use crate::config::thermal_regulation::{I2CBusConfig, I2CBusType, ThermalRegulationConfig};
+use crate::config::thermal_regulation::{
+    DigitalInputConfig, I2CBusConfig, I2CBusType, ThermalRegulationConfig, ThermalRegulatorConfig,
+};
+
+/// CAT9555 input port 0 register, followed by input port 1
+pub const CAT9555_INPUT_PORT_REGISTER: u8 = 0x00;
 
 /// Main thermal regulation manager
 pub struct ThermalRegulationManager {
@@ -45,6 +53,107 @@ pub trait I2CBusDriver {
 
     /// Check if device is present on the bus
     async fn device_present(&mut self, address: u8) -> Result<bool>;
+
+    /// Read the 16 input pins of a CAT9555 GPIO controller
+    ///
+    /// Bit `n` of the result is the level of GPIO `n`: input port 0 holds
+    /// pins 0-7 and input port 1 pins 8-15.
+    async fn read_gpio(&mut self, address: u8) -> Result<u16> {
+        let data = self.read(address, CAT9555_INPUT_PORT_REGISTER, 2).await?;
+        if data.len() < 2 {
+            return Err(anyhow::anyhow!("Insufficient GPIO data"));
+        }
+        Ok(u16::from_le_bytes([data[0], data[1]]))
+    }
+}
+
+/// State of a configured digital input
+#[derive(Debug, Clone, PartialEq)]
+pub struct DigitalInputState {
+    /// Name of the input from the configuration
+    pub name: String,
+    /// The input is active, taking its polarity into account
+    pub active: bool,
+    /// The input is a fault input shutting the regulator down
+    pub fault: bool,
+}
+
+/// Read configured digital inputs through an I2C bus driver
+///
+/// Each CAT9555 controller is read once, whatever the number of its inputs.
+async fn read_digital_inputs_with<D>(
+    i2c_driver: &mut D,
+    inputs: &[DigitalInputConfig],
+) -> Result<Vec<DigitalInputState>>
+where
+    D: I2CBusDriver + Send,
+{
+    let mut levels: HashMap<u8, u16> = HashMap::new();
+    let mut states = Vec::with_capacity(inputs.len());
+
+    for input in inputs {
+        let level = match levels.get(&input.address) {
+            Some(&level) => level,
+            None => {
+                let level = i2c_driver.read_gpio(input.address).await.map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to read GPIO inputs at 0x{:02X}: {}",
+                        input.address,
+                        e
+                    )
+                })?;
+                levels.insert(input.address, level);
+                level
+            }
+        };
+        let mask = 1u16.checked_shl(input.pin as u32).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Invalid GPIO pin {} for digital input '{}'",
+                input.pin,
+                input.name
+            )
+        })?;
+        let high = level & mask != 0;
+
+        states.push(DigitalInputState {
+            name: input.name.clone(),
+            active: high != input.active_low,
+            fault: input.fault,
+        });
+    }
+
+    Ok(states)
+}
+
+/// Read the H-Bridge current through an I2C bus driver
+///
+/// Returns `None` when the regulator has no current-sense channel.
+async fn read_current_with<D>(
+    i2c_driver: &mut D,
+    regulator_config: &ThermalRegulatorConfig,
+) -> Result<Option<f64>>
+where
+    D: I2CBusDriver + Send,
+{
+    let Some(current_sense) = &regulator_config.current_sense else {
+        return Ok(None);
+    };
+
+    let adc_data = i2c_driver
+        .read(current_sense.adc_address, current_sense.adc_channel, 2)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to read current-sense ADC: {}", e))?;
+    if adc_data.len() < 2 {
+        return Err(anyhow::anyhow!("Insufficient ADC data"));
+    }
+
+    let raw_value = ((adc_data[0] as u16) << 8) | (adc_data[1] as u16);
+    let temp_conversion = &regulator_config.temperature_conversion;
+    let max_adc_value = (1_u32 << temp_conversion.adc_resolution) - 1;
+    let voltage =
+        (raw_value as f64 / max_adc_value as f64) * temp_conversion.voltage_reference as f64;
+
+    Ok(Some(current_sense.current_from_voltage(voltage)))
 }
 
 /// High-level thermal regulation driver trait for complete hardware abstraction
@@ -82,6 +191,18 @@ pub trait ThermalRegulationDriver: Send + Sync {
     ///
     /// Returns a status string with hardware-specific information.
     async fn get_status(&mut self) -> Result<String>;
+
+    /// Read the configured digital inputs
+    ///
+    /// Returns the state of each input listed in the `digital_inputs` of the
+    /// regulator configuration, read from the CAT9555 input registers.
+    async fn read_digital_inputs(&mut self) -> Result<Vec<DigitalInputState>>;
+
+    /// Read the H-Bridge current from the current-sense ADC channel
+    ///
+    /// Returns the current in amps, or `None` when the regulator has no
+    /// `current_sense` configuration.
+    async fn read_current(&mut self) -> Result<Option<f64>>;
 }
 
 /// Thermal controller for managing individual regulators
@@ -357,6 +478,13 @@ impl MockL298NThermalRegulationDriver {
         regulator_config: &crate::config::thermal_regulation::ThermalRegulatorConfig,
     ) -> Result<Self> {
         let i2c_driver = drivers::mock::MockI2CL298NDriver::new(bus_config)?;
+        if let Some(current_sense) = &regulator_config.current_sense {
+            i2c_driver.set_current_sense_input(
+                current_sense.adc_address,
+                current_sense.adc_channel,
+                current_sense.shunt_resistance_ohm * current_sense.amplifier_gain,
+            )?;
+        }
 
         Ok(Self {
             i2c_driver,
@@ -364,6 +492,20 @@ impl MockL298NThermalRegulationDriver {
             current_control_output: 0.0,
         })
     }
+
+    /// Drive an emulated CAT9555 input pin (0-15) high or low
+    ///
+    /// Simulates external signals such as an over-temperature comparator.
+    pub fn set_input_pin(&self, address: u8, pin: u8, high: bool) -> Result<()> {
+        self.i2c_driver.set_input_pin(address, pin, high)
+    }
+
+    /// Set the resistance of the simulated thermal actuator load in ohms
+    ///
+    /// A low resistance simulates a shorted heater or a stalled Peltier module.
+    pub fn set_load_resistance(&self, resistance_ohm: f64) -> Result<()> {
+        self.i2c_driver.set_load_resistance(resistance_ohm)
+    }
 }
 
 #[async_trait::async_trait]
@@ -391,13 +533,15 @@ impl ThermalRegulationDriver for MockL298NThermalRegulationDriver {
     /// - **ADC Controller**: ADS1115 configured for appropriate resolution and reference
     /// - **Thermistor Circuit**: NTC thermistor in voltage divider configuration
     /// - **Conversion Formula**: Steinhart-Hart or NTC Beta formula in configuration
-    /// - **Calibration**: Optional offset and scaling factors in configuration
+    /// - **Calibration**: Optional two-point correction and offset in
+    ///   `temperature_conversion.calibration`, applied by the conversion
     ///
     /// ## Real Hardware Adaptation:
     /// - **Keep Exact Logic**: This conversion chain works for real thermistor circuits
     /// - **ADC Configuration**: Ensure ADC resolution and reference match configuration
     /// - **Formula Validation**: Verify thermistor formula matches actual component
-    /// - **Calibration**: Add temperature calibration offset correction if needed
+    /// - **Calibration**: Measure the sensor against a reference thermometer at two
+    ///   temperatures and configure them in `temperature_conversion.calibration`
     /// - **Error Handling**: Add ADC communication retry logic and fault detection
     ///
     /// ## Critical Implementation Notes:
@@ -417,7 +561,7 @@ impl ThermalRegulationDriver for MockL298NThermalRegulationDriver {
     /// - Implement temperature sensor redundancy for safety-critical applications
     /// - Add temperature reading timeout and fallback mechanisms
     async fn read_temperature(&mut self) -> Result<f64> {
-        use crate::utility::convert_voltage_to_temperature;
+        use crate::utility::convert_voltage_to_temperature_with;
         use anyhow::anyhow;
         use log::debug;
 
@@ -455,8 +599,8 @@ impl ThermalRegulationDriver for MockL298NThermalRegulationDriver {
             raw_value, voltage, formula
         );
 
-        // Step 5: Convert voltage to temperature using configured formula
-        let temperature_k = convert_voltage_to_temperature(formula.clone(), voltage as f32)?;
+        // Step 5: Convert voltage to temperature using configured formula and calibration
+        let temperature_k = convert_voltage_to_temperature_with(temp_conversion, voltage as f32)?;
 
         // Step 6: Convert from Kelvin to Celsius for PID controller compatibility
         let temperature_c = temperature_k - 273.15;
@@ -742,6 +886,22 @@ impl ThermalRegulationDriver for MockL298NThermalRegulationDriver {
             temp, self.current_control_output
         ))
     }
+
+    /// Read the configured digital inputs from the emulated CAT9555 input pins
+    ///
+    /// The emulated pins are high (pulled up) until driven with
+    /// [`MockL298NThermalRegulationDriver::set_input_pin`].
+    async fn read_digital_inputs(&mut self) -> Result<Vec<DigitalInputState>> {
+        read_digital_inputs_with(&mut self.i2c_driver, &self.regulator_config.digital_inputs).await
+    }
+
+    /// Read the H-Bridge current from the emulated current-sense amplifier
+    ///
+    /// The mock models the current of a resistive load driven at the PWM
+    /// duty cycle, see [`MockL298NThermalRegulationDriver::set_load_resistance`].
+    async fn read_current(&mut self) -> Result<Option<f64>> {
+        read_current_with(&mut self.i2c_driver, &self.regulator_config).await
+    }
 }
 
 /// Native thermal regulation driver for Raspberry Pi
@@ -770,7 +930,7 @@ impl NativeThermalRegulationDriver {
 #[async_trait::async_trait]
 impl ThermalRegulationDriver for NativeThermalRegulationDriver {
     async fn read_temperature(&mut self) -> Result<f64> {
-        use crate::utility::convert_voltage_to_temperature;
+        use crate::utility::convert_voltage_to_temperature_with;
         use anyhow::anyhow;
         use log::debug;
 
@@ -799,7 +959,7 @@ impl ThermalRegulationDriver for NativeThermalRegulationDriver {
         let max_adc_value = (1_u32 << adc_resolution) - 1;
         let voltage = (raw_value as f64 / max_adc_value as f64) * voltage_reference as f64;
 
-        let temperature_k = convert_voltage_to_temperature(formula.clone(), voltage as f32)?;
+        let temperature_k = convert_voltage_to_temperature_with(temp_conversion, voltage as f32)?;
         let temperature_c = temperature_k - 273.15;
 
         debug!(
@@ -900,6 +1060,14 @@ impl ThermalRegulationDriver for NativeThermalRegulationDriver {
             self.current_control_output
         ))
     }
+
+    async fn read_digital_inputs(&mut self) -> Result<Vec<DigitalInputState>> {
+        read_digital_inputs_with(&mut self.i2c_driver, &self.regulator_config.digital_inputs).await
+    }
+
+    async fn read_current(&mut self) -> Result<Option<f64>> {
+        read_current_with(&mut self.i2c_driver, &self.regulator_config).await
+    }
 }
 
 /// CP2112 thermal regulation driver for USB-based I2C
@@ -931,7 +1099,7 @@ impl Cp2112ThermalRegulationDriver {
 #[async_trait::async_trait]
 impl ThermalRegulationDriver for Cp2112ThermalRegulationDriver {
     async fn read_temperature(&mut self) -> Result<f64> {
-        use crate::utility::convert_voltage_to_temperature;
+        use crate::utility::convert_voltage_to_temperature_with;
         use anyhow::anyhow;
         use log::debug;
 
@@ -959,7 +1127,7 @@ impl ThermalRegulationDriver for Cp2112ThermalRegulationDriver {
         let max_adc_value = (1_u32 << adc_resolution) - 1;
         let voltage = (raw_value as f64 / max_adc_value as f64) * voltage_reference as f64;
 
-        let temperature_k = convert_voltage_to_temperature(formula.clone(), voltage as f32)?;
+        let temperature_k = convert_voltage_to_temperature_with(temp_conversion, voltage as f32)?;
         let temperature_c = temperature_k - 273.15;
 
         debug!(
@@ -1059,6 +1227,14 @@ impl ThermalRegulationDriver for Cp2112ThermalRegulationDriver {
             self.current_control_output
         ))
     }
+
+    async fn read_digital_inputs(&mut self) -> Result<Vec<DigitalInputState>> {
+        read_digital_inputs_with(&mut self.i2c_driver, &self.regulator_config.digital_inputs).await
+    }
+
+    async fn read_current(&mut self) -> Result<Option<f64>> {
+        read_current_with(&mut self.i2c_driver, &self.regulator_config).await
+    }
 }
 
 /// Factory function to create appropriate thermal regulation driver
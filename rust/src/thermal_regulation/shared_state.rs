@@ -13,6 +13,9 @@ use rocket_okapi::JsonSchema;
 use std::collections::{HashMap, VecDeque};
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
+
+use crate::thermal_regulation::coordination::ZoneReading;
+
 /// Maximum number of historical data points per regulator (1 day at 1Hz)
 pub const MAX_HISTORY_SIZE: usize = 86400;
 
@@ -40,6 +43,9 @@ pub struct PidComponents {
     pub integral: f64,
     /// Derivative term value
     pub derivative: f64,
+    /// Feedforward term value
+    #[serde(default)]
+    pub feedforward: f64,
     /// Error value (setpoint - process_variable)
     pub error: f64,
 }
@@ -57,10 +63,25 @@ pub struct ThermalRegulatorHistory {
     pub status: RegulatorStatus,
     /// Rolling history of temperature and control data
     pub history: VecDeque<ThermalDataPoint>,
+    /// Number of data points recorded since the regulator was initialized
+    #[serde(default)]
+    pub sample_count: u64,
     /// Last update timestamp
     pub last_update: u64,
     /// Current PID parameters
     pub current_pid_params: CurrentPidParams,
+    /// Allowed range for remotely requested setpoints
+    #[serde(default)]
+    pub setpoint_limits: Option<SetpointLimits>,
+}
+
+/// Allowed setpoint range for a regulator
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+pub struct SetpointLimits {
+    /// Lowest accepted setpoint in degrees Celsius
+    pub min_celsius: f64,
+    /// Highest accepted setpoint in degrees Celsius
+    pub max_celsius: f64,
 }
 
 /// Current status of a thermal regulator
@@ -103,6 +124,9 @@ pub struct SharedThermalRegulationState {
     system_status: ThermalSystemStatus,
     /// Last global update timestamp
     last_system_update: u64,
+    /// Setpoints requested by external systems, waiting to be applied by the regulator loops
+    #[serde(skip)]
+    pending_setpoints: HashMap<String, f64>,
 }
 
 /// Global thermal regulation system status
@@ -133,6 +157,7 @@ impl SharedThermalRegulationState {
                 system_enabled: false,
             },
             last_system_update: current_timestamp(),
+            pending_setpoints: HashMap::new(),
         }
     }
 
@@ -149,8 +174,10 @@ impl SharedThermalRegulationState {
             enabled: true,
             status: RegulatorStatus::Initializing,
             history: VecDeque::with_capacity(MAX_HISTORY_SIZE),
+            sample_count: 0,
             last_update: current_timestamp(),
             current_pid_params: pid_params,
+            setpoint_limits: None,
         };
 
         self.regulators.insert(id, regulator_history);
@@ -185,6 +212,7 @@ impl SharedThermalRegulationState {
         if regulator.history.len() > MAX_HISTORY_SIZE {
             regulator.history.pop_front();
         }
+        regulator.sample_count += 1;
 
         regulator.last_update = current_timestamp();
         regulator.status = RegulatorStatus::Running;
@@ -227,6 +255,73 @@ impl SharedThermalRegulationState {
         Ok(())
     }
 
+    /// Set the allowed setpoint range for a regulator
+    pub fn set_setpoint_limits(
+        &mut self,
+        regulator_id: &str,
+        limits: SetpointLimits,
+    ) -> Result<()> {
+        let regulator = self
+            .regulators
+            .get_mut(regulator_id)
+            .ok_or_else(|| anyhow::anyhow!("Regulator '{}' not found", regulator_id))?;
+
+        regulator.setpoint_limits = Some(limits);
+        Ok(())
+    }
+
+    /// Check that a setpoint is acceptable for a regulator
+    ///
+    /// The setpoint must be finite and, when limits are configured, fall within
+    /// the regulator's [`SetpointLimits`].
+    pub fn validate_setpoint(&self, regulator_id: &str, setpoint_celsius: f64) -> Result<()> {
+        let regulator = self
+            .regulators
+            .get(regulator_id)
+            .ok_or_else(|| anyhow::anyhow!("Regulator '{}' not found", regulator_id))?;
+
+        if !setpoint_celsius.is_finite() {
+            anyhow::bail!("Setpoint for regulator '{}' must be finite", regulator_id);
+        }
+
+        if let Some(limits) = regulator.setpoint_limits {
+            if setpoint_celsius < limits.min_celsius || setpoint_celsius > limits.max_celsius {
+                anyhow::bail!(
+                    "Setpoint {:.2} °C for regulator '{}' is outside the safe range [{:.2}, {:.2}] °C",
+                    setpoint_celsius,
+                    regulator_id,
+                    limits.min_celsius,
+                    limits.max_celsius
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Request a new setpoint for a regulator
+    ///
+    /// The setpoint is validated, published immediately in the regulator's
+    /// current PID parameters and queued until the regulator loop picks it up
+    /// with [`take_pending_setpoint`](Self::take_pending_setpoint).
+    pub fn request_setpoint(&mut self, regulator_id: &str, setpoint_celsius: f64) -> Result<()> {
+        self.validate_setpoint(regulator_id, setpoint_celsius)?;
+
+        if let Some(regulator) = self.regulators.get_mut(regulator_id) {
+            regulator.current_pid_params.setpoint_celsius = setpoint_celsius;
+            regulator.last_update = current_timestamp();
+        }
+        self.pending_setpoints
+            .insert(regulator_id.to_string(), setpoint_celsius);
+
+        Ok(())
+    }
+
+    /// Take the pending setpoint request for a regulator, if any
+    pub fn take_pending_setpoint(&mut self, regulator_id: &str) -> Option<f64> {
+        self.pending_setpoints.remove(regulator_id)
+    }
+
     /// Get historical data for a specific regulator
     pub fn get_regulator_history(&self, regulator_id: &str) -> Option<&ThermalRegulatorHistory> {
         self.regulators.get(regulator_id)
@@ -258,6 +353,32 @@ impl SharedThermalRegulationState {
         self.regulators.keys().cloned().collect()
     }
 
+    /// Get the latest readings of the running regulators among `zone_ids`
+    ///
+    /// Regulators which are not running or have no data point yet are left
+    /// out, so that a zone group never follows a stopped or faulty zone.
+    pub fn zone_readings(&self, zone_ids: &[String]) -> HashMap<String, ZoneReading> {
+        zone_ids
+            .iter()
+            .filter_map(|id| {
+                let regulator = self.regulators.get(id)?;
+                if !matches!(regulator.status, RegulatorStatus::Running) {
+                    return None;
+                }
+                regulator.history.back().map(|point| {
+                    (
+                        id.clone(),
+                        ZoneReading {
+                            temperature_celsius: point.temperature_celsius,
+                            setpoint_celsius: point.setpoint_celsius,
+                            control_output_percent: point.control_output_percent,
+                        },
+                    )
+                })
+            })
+            .collect()
+    }
+
     /// Get current status for all regulators
     pub fn get_all_regulator_status(&self) -> HashMap<String, (RegulatorStatus, u64)> {
         self.regulators
@@ -387,6 +508,7 @@ mod tests {
             proportional: 1.0,
             integral: 0.5,
             derivative: 0.1,
+            feedforward: 0.0,
             error: -1.0,
         };
 
@@ -428,6 +550,7 @@ mod tests {
             proportional: 1.0,
             integral: 0.5,
             derivative: 0.1,
+            feedforward: 0.0,
             error: -1.0,
         };
 
@@ -476,6 +599,7 @@ mod tests {
             proportional: 1.0,
             integral: 0.5,
             derivative: 0.1,
+            feedforward: 0.0,
             error: -1.0,
         };
 
@@ -537,6 +661,7 @@ mod tests {
                     proportional: i as f64,
                     integral: 0.5,
                     derivative: 0.1,
+                    feedforward: 0.0,
                     error: -1.0,
                 };
 
@@ -564,4 +689,49 @@ mod tests {
         let regulator = state.regulators.get("test_reg").unwrap();
         assert_eq!(regulator.history.len(), 10);
     }
+
+    #[test]
+    fn test_setpoint_request_within_limits() {
+        let mut state = SharedThermalRegulationState::new();
+        let pid_params = CurrentPidParams {
+            kp: 1.0,
+            ki: 0.1,
+            kd: 0.01,
+            setpoint_celsius: 25.0,
+            output_min: -100.0,
+            output_max: 100.0,
+        };
+
+        state
+            .initialize_regulator(
+                "test_reg".to_string(),
+                "Test Regulator".to_string(),
+                pid_params,
+            )
+            .unwrap();
+        state
+            .set_setpoint_limits(
+                "test_reg",
+                SetpointLimits {
+                    min_celsius: 10.0,
+                    max_celsius: 60.0,
+                },
+            )
+            .unwrap();
+
+        state.request_setpoint("test_reg", 42.0).unwrap();
+        let regulator = state.regulators.get("test_reg").unwrap();
+        assert_eq!(regulator.current_pid_params.setpoint_celsius, 42.0);
+        assert_eq!(state.take_pending_setpoint("test_reg"), Some(42.0));
+        assert_eq!(state.take_pending_setpoint("test_reg"), None);
+
+        // Out-of-range and non-finite requests are refused and leave the target unchanged
+        assert!(state.request_setpoint("test_reg", 75.0).is_err());
+        assert!(state.request_setpoint("test_reg", 5.0).is_err());
+        assert!(state.request_setpoint("test_reg", f64::NAN).is_err());
+        assert!(state.request_setpoint("unknown", 20.0).is_err());
+        let regulator = state.regulators.get("test_reg").unwrap();
+        assert_eq!(regulator.current_pid_params.setpoint_celsius, 42.0);
+        assert_eq!(state.take_pending_setpoint("test_reg"), None);
+    }
 }
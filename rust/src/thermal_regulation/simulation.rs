@@ -4,23 +4,427 @@
 
 //! Thermal simulation module for photoacoustic applications
 //!
-//! This module provides advanced thermal simulation capabilities
-//! for modeling complex thermal behaviors in photoacoustic systems.
+//! This module provides a lumped thermal plant model used by the mock driver,
+//! so that PID tuning against the simulation shows the lag and overshoot of
+//! the real cell.
+//!
+//! The regulated body is a single thermal mass `C` (J/K) exchanging heat with
+//! the ambient through a heat-transfer coefficient `G` (W/K):
+//!
+//! ```text
+//! C · dT/dt = P(t - L) - G · (T - T_ambient)
+//! ```
+//!
+//! where `P` is the actuator power and `L` the actuator deadtime. This is a
+//! first-order-plus-deadtime (FOPDT) plant with time constant `τ = C / G` and
+//! static gain `1 / G` K/W. An optional sensor time constant `τs` adds a
+//! second pole between the body and the measured temperature:
+//!
+//! ```text
+//! τs · dTs/dt = T - Ts
+//! ```
+//!
+//! Heating and cooling are asymmetric: the commanded power is scaled by a
+//! heating efficiency when positive and by a cooling efficiency when negative.
+//! A Peltier module pumps less heat as the cell gets colder than its hot side,
+//! held at ambient; with a maximum temperature difference `ΔTmax` the cooling
+//! power becomes:
+//!
+//! ```text
+//! Pc = Pc0 · (1 - (T_ambient - T) / ΔTmax)
+//! ```
+//!
+//! so the cell never gets colder than `ΔTmax` below ambient.
+//!
+//! The power is held constant between two commands, so the model is advanced
+//! with its exact solution: the result does not depend on the step size.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use rust_photoacoustic::thermal_regulation::simulation::{
+//!     ThermalPlant, ThermalPlantParameters,
+//! };
+//!
+//! let mut plant = ThermalPlant::new(ThermalPlantParameters {
+//!     thermal_mass_j_per_k: 500.0,
+//!     heat_transfer_w_per_k: 0.5,
+//!     ambient_temperature_c: 25.0,
+//!     actuator_deadtime_s: 2.0,
+//!     sensor_time_constant_s: None,
+//!     heating_efficiency: 1.0,
+//!     cooling_efficiency: 0.5,
+//!     max_cooling_below_ambient_k: Some(40.0),
+//! });
+//!
+//! plant.set_power(10.0);
+//! plant.step(1.0);
+//! assert_eq!(plant.temperature(), 25.0); // Still within the deadtime
+//! plant.step(1000.0);
+//! assert!(plant.temperature() > 25.0);
+//! ```
+
+use std::collections::VecDeque;
+
+/// Parameters of the [`ThermalPlant`] model
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThermalPlantParameters {
+    /// Heat capacity of the regulated body in J/K (mass × specific heat)
+    pub thermal_mass_j_per_k: f64,
+    /// Heat-transfer coefficient to the ambient in W/K (convection coefficient × surface)
+    pub heat_transfer_w_per_k: f64,
+    /// Ambient temperature in °C
+    pub ambient_temperature_c: f64,
+    /// Delay between a power command and its effect on the body in seconds
+    pub actuator_deadtime_s: f64,
+    /// Time constant of the sensor coupling in seconds
+    ///
+    /// `None` gives a first-order plant where the sensor reads the body
+    /// temperature directly.
+    pub sensor_time_constant_s: Option<f64>,
+    /// Fraction of a positive (heating) power command delivered to the body
+    pub heating_efficiency: f64,
+    /// Fraction of a negative (cooling) power command extracted from the body
+    pub cooling_efficiency: f64,
+    /// Maximum temperature difference the cooler can hold below ambient in K
+    ///
+    /// `None` gives a cooling power independent of the temperature.
+    pub max_cooling_below_ambient_k: Option<f64>,
+}
+
+impl ThermalPlantParameters {
+    /// Time constant `C / G` of the body without actuator in seconds
+    pub fn time_constant_s(&self) -> f64 {
+        self.thermal_mass_j_per_k / self.heat_transfer_w_per_k
+    }
 
-/// Advanced thermal simulation (placeholder for future implementation)
-pub struct ThermalSimulation {
-    // Future implementation for more complex thermal modeling
+    /// Steady-state temperature rise above ambient for a constant power in Watts
+    ///
+    /// The rise is negative for a cooling power.
+    pub fn steady_state_rise(&self, power_w: f64) -> f64 {
+        let (heat_w, conductance) = self.linearized_input(power_w);
+        heat_w / conductance
+    }
+
+    /// Heat input at ambient and total conductance for a commanded power
+    ///
+    /// The cooling power drops linearly with the temperature below ambient,
+    /// which amounts to an extra conductance of `Pc0 / ΔTmax` towards ambient.
+    fn linearized_input(&self, power_w: f64) -> (f64, f64) {
+        if power_w >= 0.0 {
+            return (
+                power_w * self.heating_efficiency,
+                self.heat_transfer_w_per_k,
+            );
+        }
+        let cooling_w = -power_w * self.cooling_efficiency;
+        match self.max_cooling_below_ambient_k {
+            Some(max_delta) if max_delta > 0.0 => (
+                -cooling_w,
+                self.heat_transfer_w_per_k + cooling_w / max_delta,
+            ),
+            _ => (-cooling_w, self.heat_transfer_w_per_k),
+        }
+    }
+}
+
+/// First-order-plus-deadtime thermal plant with an optional sensor lag
+///
+/// Power commands given with [`set_power`](Self::set_power) reach the body
+/// after the actuator deadtime; [`step`](Self::step) advances the simulated
+/// time.
+#[derive(Debug, Clone)]
+pub struct ThermalPlant {
+    parameters: ThermalPlantParameters,
+    /// Temperature of the thermal mass in °C
+    body_temperature: f64,
+    /// Temperature seen by the sensor in °C
+    sensor_temperature: f64,
+    /// Power currently heating the body in Watts
+    applied_power: f64,
+    /// Power commands waiting for the deadtime, as (application time, power)
+    pending_commands: VecDeque<(f64, f64)>,
+    /// Simulated time in seconds
+    elapsed: f64,
 }
 
-impl ThermalSimulation {
-    /// Create a new thermal simulation
-    pub fn new() -> Self {
-        Self {}
+impl ThermalPlant {
+    /// Create a plant at thermal equilibrium with the ambient
+    pub fn new(parameters: ThermalPlantParameters) -> Self {
+        Self {
+            parameters,
+            body_temperature: parameters.ambient_temperature_c,
+            sensor_temperature: parameters.ambient_temperature_c,
+            applied_power: 0.0,
+            pending_commands: VecDeque::new(),
+            elapsed: 0.0,
+        }
+    }
+
+    /// Command the actuator power in Watts, positive heating the body
+    ///
+    /// The command takes effect after the actuator deadtime.
+    pub fn set_power(&mut self, power_w: f64) {
+        let last_power = self
+            .pending_commands
+            .back()
+            .map_or(self.applied_power, |&(_, power)| power);
+        if power_w == last_power {
+            return;
+        }
+
+        if self.parameters.actuator_deadtime_s > 0.0 {
+            self.pending_commands
+                .push_back((self.elapsed + self.parameters.actuator_deadtime_s, power_w));
+        } else {
+            self.applied_power = power_w;
+        }
+    }
+
+    /// Advance the simulation by `dt` seconds
+    pub fn step(&mut self, dt: f64) {
+        if dt <= 0.0 {
+            return;
+        }
+        let end = self.elapsed + dt;
+
+        while let Some(&(apply_at, power)) = self.pending_commands.front() {
+            if apply_at > end {
+                break;
+            }
+            self.advance(apply_at - self.elapsed);
+            self.applied_power = power;
+            self.pending_commands.pop_front();
+        }
+        self.advance(end - self.elapsed);
+    }
+
+    /// Advance by `dt` seconds at constant power with the exact solution
+    fn advance(&mut self, dt: f64) {
+        if dt > 0.0 {
+            let (heat_w, conductance) = self.parameters.linearized_input(self.applied_power);
+            let tau = self.parameters.thermal_mass_j_per_k / conductance;
+            let steady_state = self.parameters.ambient_temperature_c + heat_w / conductance;
+            let body_error = self.body_temperature - steady_state;
+            let body_decay = (-dt / tau).exp();
+
+            self.sensor_temperature = match self.parameters.sensor_time_constant_s {
+                Some(tau_s) if tau_s > 0.0 => {
+                    let sensor_decay = (-dt / tau_s).exp();
+                    // Response of the sensor pole to the decaying body error
+                    let forced = if (tau - tau_s).abs() < 1e-9 * tau {
+                        dt / tau * body_decay
+                    } else {
+                        tau / (tau - tau_s) * (body_decay - sensor_decay)
+                    };
+                    steady_state
+                        + (self.sensor_temperature - steady_state) * sensor_decay
+                        + body_error * forced
+                }
+                _ => steady_state + body_error * body_decay,
+            };
+            self.body_temperature = steady_state + body_error * body_decay;
+        }
+        self.elapsed += dt.max(0.0);
+    }
+
+    /// Temperature measured by the sensor in °C
+    pub fn temperature(&self) -> f64 {
+        self.sensor_temperature
+    }
+
+    /// Temperature of the thermal mass in °C
+    pub fn body_temperature(&self) -> f64 {
+        self.body_temperature
+    }
+
+    /// Power currently heating the body in Watts, after the deadtime
+    pub fn applied_power(&self) -> f64 {
+        self.applied_power
+    }
+
+    /// Simulated time in seconds
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    /// Change the ambient temperature
+    pub fn set_ambient_temperature(&mut self, temperature_c: f64) {
+        self.parameters.ambient_temperature_c = temperature_c;
+    }
+
+    /// Parameters of the model
+    pub fn parameters(&self) -> &ThermalPlantParameters {
+        &self.parameters
     }
 }
 
-impl Default for ThermalSimulation {
-    fn default() -> Self {
-        Self::new()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AMBIENT: f64 = 25.0;
+    const POWER: f64 = 20.0;
+
+    fn parameters(deadtime: f64, sensor_time_constant: Option<f64>) -> ThermalPlantParameters {
+        ThermalPlantParameters {
+            thermal_mass_j_per_k: 500.0,
+            heat_transfer_w_per_k: 0.5,
+            ambient_temperature_c: AMBIENT,
+            actuator_deadtime_s: deadtime,
+            sensor_time_constant_s: sensor_time_constant,
+            heating_efficiency: 1.0,
+            cooling_efficiency: 1.0,
+            max_cooling_below_ambient_k: None,
+        }
+    }
+
+    /// Analytic step response of the first-order plant `t` seconds after the step
+    fn first_order_response(parameters: &ThermalPlantParameters, t: f64) -> f64 {
+        let tau = parameters.time_constant_s();
+        AMBIENT + parameters.steady_state_rise(POWER) * (1.0 - (-t.max(0.0) / tau).exp())
+    }
+
+    #[test]
+    fn test_first_order_step_response() {
+        let parameters = parameters(0.0, None);
+        assert_eq!(parameters.time_constant_s(), 1000.0);
+
+        for dt in [0.1, 1.0, 7.5] {
+            let mut plant = ThermalPlant::new(parameters);
+            plant.set_power(POWER);
+            while plant.elapsed() < 3000.0 {
+                plant.step(dt);
+                let expected = first_order_response(&parameters, plant.elapsed());
+                assert!(
+                    (plant.temperature() - expected).abs() < 1e-6,
+                    "dt {}: {} °C at {} s, expected {} °C",
+                    dt,
+                    plant.temperature(),
+                    plant.elapsed(),
+                    expected
+                );
+            }
+        }
+
+        // One time constant reaches 63.2 % of the 40 K rise
+        let mut plant = ThermalPlant::new(parameters);
+        plant.set_power(POWER);
+        plant.step(1000.0);
+        assert!((plant.temperature() - (AMBIENT + 40.0 * 0.632_120_56)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_deadtime_delays_response() {
+        let parameters = parameters(5.0, None);
+        let mut plant = ThermalPlant::new(parameters);
+        plant.set_power(POWER);
+
+        for _ in 0..49 {
+            plant.step(0.1);
+            assert_eq!(plant.temperature(), AMBIENT, "at {} s", plant.elapsed());
+        }
+        for _ in 0..1000 {
+            // Steps straddling the application time of the command
+            plant.step(0.3);
+            let expected = first_order_response(&parameters, plant.elapsed() - 5.0);
+            assert!((plant.temperature() - expected).abs() < 1e-6);
+        }
+
+        // Switching off is delayed as well
+        plant.set_power(0.0);
+        let before = plant.temperature();
+        plant.step(4.9);
+        assert!(plant.temperature() > before);
+        assert_eq!(plant.applied_power(), POWER);
+        plant.step(0.2);
+        assert_eq!(plant.applied_power(), 0.0);
+    }
+
+    #[test]
+    fn test_second_order_step_response() {
+        let tau_s = 50.0;
+        let parameters = parameters(0.0, Some(tau_s));
+        let tau = parameters.time_constant_s();
+        let rise = parameters.steady_state_rise(POWER);
+
+        let mut plant = ThermalPlant::new(parameters);
+        plant.set_power(POWER);
+        for _ in 0..1000 {
+            plant.step(10.0);
+            let t = plant.elapsed();
+            let expected = AMBIENT
+                + rise
+                    * (1.0 - (tau * (-t / tau).exp() - tau_s * (-t / tau_s).exp()) / (tau - tau_s));
+            assert!((plant.temperature() - expected).abs() < 1e-6);
+            // The sensor lags behind the body
+            assert!(plant.temperature() < plant.body_temperature());
+        }
+        assert!((plant.temperature() - (AMBIENT + rise)).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_equal_time_constants() {
+        let parameters = parameters(0.0, Some(1000.0));
+        let mut plant = ThermalPlant::new(parameters);
+        plant.set_power(POWER);
+        plant.step(1000.0);
+
+        // Critically damped response: 1 - (1 + t/τ)·exp(-t/τ)
+        let expected = AMBIENT + 40.0 * (1.0 - 2.0 * (-1.0f64).exp());
+        assert!((plant.temperature() - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_heating_cooling_asymmetry() {
+        let parameters = ThermalPlantParameters {
+            heating_efficiency: 0.9,
+            cooling_efficiency: 0.45,
+            ..parameters(0.0, None)
+        };
+        let mut heating = ThermalPlant::new(parameters);
+        let mut cooling = ThermalPlant::new(parameters);
+        heating.set_power(POWER);
+        cooling.set_power(-POWER);
+
+        for _ in 0..100 {
+            heating.step(10.0);
+            cooling.step(10.0);
+            let rise = heating.temperature() - AMBIENT;
+            let drop = AMBIENT - cooling.temperature();
+            assert!(rise > drop);
+            // Same dynamics, scaled by the efficiencies
+            assert!((drop / rise - 0.5).abs() < 1e-9);
+            let expected = first_order_response(&parameters, heating.elapsed()) - AMBIENT;
+            assert!((rise - 0.9 * expected).abs() < 1e-6);
+        }
+        assert!((parameters.steady_state_rise(POWER) - 36.0).abs() < 1e-9);
+        assert!((parameters.steady_state_rise(-POWER) - -18.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_max_cooling_below_ambient() {
+        let parameters = ThermalPlantParameters {
+            max_cooling_below_ambient_k: Some(10.0),
+            ..parameters(0.0, Some(20.0))
+        };
+        // 20 W of cooling would hold 40 K below ambient without the limit;
+        // the cooling power vanishes 10 K below ambient
+        let floor = parameters.steady_state_rise(-POWER);
+        assert!((floor - -20.0 / (0.5 + 2.0)).abs() < 1e-9);
+
+        let mut plant = ThermalPlant::new(parameters);
+        plant.set_power(-POWER);
+        for _ in 0..2000 {
+            plant.step(5.0);
+            assert!(plant.temperature() > AMBIENT - 10.0);
+        }
+        assert!((plant.temperature() - (AMBIENT + floor)).abs() < 1e-3);
+
+        // The limit slows cooling down as the cell gets colder, heating is unaffected
+        let mut heating = ThermalPlant::new(parameters);
+        heating.set_power(POWER);
+        heating.step(20000.0);
+        assert!((heating.temperature() - (AMBIENT + 40.0)).abs() < 1e-3);
     }
 }
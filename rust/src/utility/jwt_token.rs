@@ -7,17 +7,19 @@
 //! This module provides utilities for creating JWT tokens programmatically,
 //! extracted from the create_token binary for reuse across the codebase.
 
-use base64::prelude::BASE64_STANDARD;
+use base64::prelude::{BASE64_STANDARD, BASE64_URL_SAFE_NO_PAD};
 use base64::Engine;
+use chrono::{DateTime, TimeZone, Utc};
 use oxide_auth::endpoint::{Issuer, Scope};
 use oxide_auth::primitives::grant::{Extensions, Grant};
+use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use thiserror::Error;
 use url::Url;
 
 use crate::config::access::{Client, User};
 use crate::config::Config;
-use crate::visualization::auth::jwt::JwtIssuer;
+use crate::visualization::auth::jwt::{JwtIssuer, JwtValidator};
 
 /// Specific errors for token creation
 #[derive(Error, Debug)]
@@ -40,7 +42,7 @@ pub enum TokenCreationError {
     #[error("Failed to decode RS256 key from base64: {reason}")]
     KeyDecodingError { reason: String },
 
-    #[error("Failed to create JwtIssuer with RS256 keys: {reason}")]
+    #[error("Failed to create JwtIssuer with asymmetric keys: {reason}")]
     JwtIssuerCreationError { reason: String },
 
     #[error("Invalid scope: {scope}")]
@@ -54,6 +56,12 @@ pub enum TokenCreationError {
 
     #[error("JWT token creation failed: {reason}")]
     TokenIssuingError { reason: String },
+
+    #[error("Malformed JWT token: {reason}")]
+    MalformedToken { reason: String },
+
+    #[error("Batch file error: {reason}")]
+    BatchFileError { reason: String },
 }
 
 /// Supported JWT algorithms for token signing
@@ -82,6 +90,8 @@ pub enum JwtAlgorithm {
     HS256,
     /// RSA signature with SHA-256 hash algorithm (asymmetric key)
     RS256,
+    /// Edwards-curve signature with Ed25519 (asymmetric key, small and fast to verify)
+    EdDSA,
 }
 
 impl FromStr for JwtAlgorithm {
@@ -91,6 +101,7 @@ impl FromStr for JwtAlgorithm {
         match s {
             "HS256" => Ok(JwtAlgorithm::HS256),
             "RS256" => Ok(JwtAlgorithm::RS256),
+            "EdDSA" | "Ed25519" => Ok(JwtAlgorithm::EdDSA),
             _ => Err(TokenCreationError::TokenIssuingError {
                 reason: format!("Unsupported algorithm: {}", s),
             }),
@@ -103,6 +114,7 @@ impl JwtAlgorithm {
         match self {
             JwtAlgorithm::HS256 => jsonwebtoken::Algorithm::HS256,
             JwtAlgorithm::RS256 => jsonwebtoken::Algorithm::RS256,
+            JwtAlgorithm::EdDSA => jsonwebtoken::Algorithm::EdDSA,
         }
     }
 
@@ -110,6 +122,7 @@ impl JwtAlgorithm {
         match self {
             JwtAlgorithm::HS256 => "HS256",
             JwtAlgorithm::RS256 => "RS256",
+            JwtAlgorithm::EdDSA => "EdDSA",
         }
     }
 }
@@ -183,6 +196,29 @@ pub struct TokenCreationResult {
     pub permissions: Vec<String>,
 }
 
+/// Configured user or client with the permissions it holds
+///
+/// Returned by [`ConfigLoader::list_users`] and [`ConfigLoader::list_clients`]
+/// so that operators can discover valid token inputs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityListing {
+    /// The username or client identifier
+    pub name: String,
+    /// The effective permissions of a user, or the default scopes of a client
+    pub permissions: Vec<String>,
+}
+
+/// Permission with the users it is granted to
+///
+/// Returned by [`ConfigLoader::list_permissions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionListing {
+    /// The permission name
+    pub permission: String,
+    /// The users holding the permission, directly or through a role
+    pub users: Vec<String>,
+}
+
 /// Configuration loader with validation for JWT token creation
 ///
 /// This structure wraps the application configuration and provides
@@ -273,6 +309,71 @@ impl ConfigLoader {
                 client: client_id.to_string(),
             })
     }
+
+    /// List the configured users with their effective permissions
+    ///
+    /// Role permissions are expanded, as they are in the issued tokens.
+    ///
+    /// ### Returns
+    ///
+    /// The users in configuration order
+    pub fn list_users(&self) -> Vec<EntityListing> {
+        let access = &self.config.access;
+        access
+            .users
+            .iter()
+            .map(|user| EntityListing {
+                name: user.user.clone(),
+                permissions: access.effective_permissions(user),
+            })
+            .collect()
+    }
+
+    /// List the configured clients with their default scopes
+    ///
+    /// ### Returns
+    ///
+    /// The clients in configuration order
+    pub fn list_clients(&self) -> Vec<EntityListing> {
+        self.config
+            .access
+            .clients
+            .iter()
+            .map(|client| EntityListing {
+                name: client.client_id.clone(),
+                permissions: client
+                    .default_scope
+                    .split_whitespace()
+                    .map(str::to_string)
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// List the permissions granted to the configured users
+    ///
+    /// ### Returns
+    ///
+    /// The permissions sorted by name, each with the users holding it
+    pub fn list_permissions(&self) -> Vec<PermissionListing> {
+        let mut permissions: Vec<PermissionListing> = Vec::new();
+        for user in self.list_users() {
+            for permission in user.permissions {
+                match permissions
+                    .iter_mut()
+                    .find(|listing| listing.permission == permission)
+                {
+                    Some(listing) => listing.users.push(user.name.clone()),
+                    None => permissions.push(PermissionListing {
+                        permission,
+                        users: vec![user.name.clone()],
+                    }),
+                }
+            }
+        }
+        permissions.sort_by(|a, b| a.permission.cmp(&b.permission));
+        permissions
+    }
 }
 
 /// JWT token creator for generating authenticated tokens
@@ -406,7 +507,11 @@ impl TokenCreator {
             user_id: params.user_id.clone(),
             algorithm: params.algorithm.as_str().to_string(),
             duration_seconds: params.duration_seconds,
-            permissions: user.permissions.clone(),
+            permissions: self
+                .config_loader
+                .config()
+                .access
+                .effective_permissions(user),
         })
     }
 
@@ -442,6 +547,31 @@ impl TokenCreator {
                     }
                 })
             }
+            JwtAlgorithm::EdDSA => {
+                let config = self.config_loader.config();
+                let decode_key = |key: &Option<String>, name: &str| {
+                    let key =
+                        key.as_deref()
+                            .ok_or_else(|| TokenCreationError::KeyDecodingError {
+                                reason: format!("Ed25519 {} key not configured", name),
+                            })?;
+                    BASE64_STANDARD
+                        .decode(key)
+                        .map_err(|e| TokenCreationError::KeyDecodingError {
+                            reason: format!("Ed25519 {} key: {}", name, e),
+                        })
+                };
+                let ed25519_private_key =
+                    decode_key(&config.visualization.ed25519_private_key, "private")?;
+                let ed25519_public_key =
+                    decode_key(&config.visualization.ed25519_public_key, "public")?;
+
+                JwtIssuer::with_ed25519_pem(&ed25519_private_key, &ed25519_public_key).map_err(
+                    |e| TokenCreationError::JwtIssuerCreationError {
+                        reason: format!("Failed to create EdDSA issuer: {:?}", e),
+                    },
+                )
+            }
         }
     }
 
@@ -489,7 +619,7 @@ impl TokenCreator {
             .with_issuer(issuer_name)
             .valid_for(chrono::TimeDelta::seconds(params.duration_seconds as i64))
             .with_algorithm(params.algorithm.to_jsonwebtoken_algorithm())
-            .add_user_claims(&params.user_id, &user.permissions)
+            .add_user_claims(&params.user_id, &config.access.effective_permissions(user))
             .issue(grant)
             .map_err(|e| TokenCreationError::TokenIssuingError {
                 reason: format!("Failed to issue JWT token: {:?}", e),
@@ -498,3 +628,405 @@ impl TokenCreator {
         Ok(token.token)
     }
 }
+
+/// Verdict of a token inspection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenStatus {
+    /// The token passes every check of the server
+    Valid,
+    /// The signature is valid but the token has expired
+    Expired,
+    /// The signature does not match any configured key
+    InvalidSignature { reason: String },
+    /// The signature is valid but a claim is rejected (issuer, audience, ...)
+    Invalid { reason: String },
+}
+
+impl TokenStatus {
+    /// Whether the server would accept the token
+    pub fn is_valid(&self) -> bool {
+        matches!(self, TokenStatus::Valid)
+    }
+}
+
+/// Decoded contents of a JWT token and its validation verdict
+///
+/// Returned by [`TokenInspector::inspect`]. The header and claims are decoded
+/// even when the token is rejected, so that operators can see why.
+#[derive(Debug, Clone)]
+pub struct TokenInspection {
+    /// The decoded JOSE header
+    pub header: serde_json::Value,
+    /// The decoded claims
+    pub claims: serde_json::Value,
+    /// The user permissions carried by the token, its scopes when it has none
+    pub permissions: Vec<String>,
+    /// The expiry time, if the token has a valid `exp` claim
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Whether the server would accept the token, and why not
+    pub status: TokenStatus,
+}
+
+/// JWT token inspector validating tokens against the configured keys
+///
+/// The tokens are checked by the [`JwtValidator`] the server uses, built from
+/// the HS256 secret, the RS256 public key, the previous RS256 public keys and
+/// the Ed25519 public key of the configuration.
+///
+/// # Examples
+///
+/// ```
+/// use rust_photoacoustic::config::Config;
+/// use rust_photoacoustic::utility::jwt_token::{
+///     ConfigLoader, JwtAlgorithm, TokenCreationParams, TokenCreator, TokenInspector,
+/// };
+///
+/// let mut config = Config::default();
+/// config.visualization.hmac_secret = "test-secret-for-hmac".to_string();
+/// let config_loader = ConfigLoader::from_config(&config).unwrap();
+///
+/// let token = TokenCreator::new(&config_loader)
+///     .unwrap()
+///     .create_token(&TokenCreationParams {
+///         user_id: "admin".to_string(),
+///         client_id: "LaserSmartClient".to_string(),
+///         algorithm: JwtAlgorithm::HS256,
+///         duration_seconds: 3600,
+///     })
+///     .unwrap()
+///     .token;
+///
+/// let inspection = TokenInspector::new(&config_loader).unwrap().inspect(&token).unwrap();
+/// assert!(inspection.status.is_valid());
+/// assert_eq!(inspection.claims["sub"], "admin");
+/// ```
+pub struct TokenInspector {
+    validator: JwtValidator,
+}
+
+impl TokenInspector {
+    /// Creates a token inspector from a configuration loader
+    ///
+    /// # Arguments
+    ///
+    /// * `config_loader` - The configuration loader holding the keys and access configuration
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TokenCreationError::KeyDecodingError`] if a configured RS256
+    /// public key cannot be decoded
+    pub fn new(config_loader: &ConfigLoader) -> Result<Self, TokenCreationError> {
+        let config = config_loader.config();
+        let decode_public_key = |key: &str| {
+            BASE64_STANDARD
+                .decode(key)
+                .map_err(|e| TokenCreationError::KeyDecodingError {
+                    reason: format!("RS256 public key: {}", e),
+                })
+        };
+
+        let hmac_secret = config.visualization.hmac_secret.as_bytes();
+        let rs256_public_key = match config.visualization.rs256_public_key.as_str() {
+            "" => None,
+            key => Some(decode_public_key(key)?),
+        };
+        let mut validator = JwtValidator::new(
+            (!hmac_secret.is_empty()).then_some(hmac_secret),
+            rs256_public_key.as_deref(),
+            config.access.clone(),
+        )
+        .map_err(|e| TokenCreationError::KeyDecodingError {
+            reason: format!("RS256 public key: {}", e),
+        })?;
+        for previous_key in &config.visualization.rs256_previous_public_keys {
+            validator = validator
+                .with_rs256_public_key(&decode_public_key(previous_key)?)
+                .map_err(|e| TokenCreationError::KeyDecodingError {
+                    reason: format!("Previous RS256 public key: {}", e),
+                })?;
+        }
+        if let Some(ed25519_public_key) = &config.visualization.ed25519_public_key {
+            let ed25519_public_key = BASE64_STANDARD.decode(ed25519_public_key).map_err(|e| {
+                TokenCreationError::KeyDecodingError {
+                    reason: format!("Ed25519 public key: {}", e),
+                }
+            })?;
+            validator = validator
+                .with_ed25519_public_key(&ed25519_public_key)
+                .map_err(|e| TokenCreationError::KeyDecodingError {
+                    reason: e.to_string(),
+                })?;
+        }
+
+        Ok(Self { validator })
+    }
+
+    /// Decodes a JWT token and validates it as the server would
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The JWT token string to inspect
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(TokenInspection)` - The decoded token and its verdict, valid or not
+    /// * `Err(TokenCreationError::MalformedToken)` - If the token cannot be decoded at all
+    pub fn inspect(&self, token: &str) -> Result<TokenInspection, TokenCreationError> {
+        let malformed = |reason: String| TokenCreationError::MalformedToken { reason };
+
+        let header = jsonwebtoken::decode_header(token).map_err(|e| malformed(e.to_string()))?;
+        let header = serde_json::to_value(header).map_err(|e| malformed(e.to_string()))?;
+        let payload = token
+            .split('.')
+            .nth(1)
+            .ok_or_else(|| malformed("missing claims".to_string()))?;
+        let payload = BASE64_URL_SAFE_NO_PAD
+            .decode(payload.trim_end_matches('='))
+            .map_err(|e| malformed(format!("claims are not base64url encoded: {}", e)))?;
+        let claims: serde_json::Value = serde_json::from_slice(&payload)
+            .map_err(|e| malformed(format!("claims are not JSON: {}", e)))?;
+
+        let permissions = match claims["permissions"].as_array() {
+            Some(permissions) => permissions
+                .iter()
+                .filter_map(|permission| permission.as_str().map(str::to_string))
+                .collect(),
+            None => claims["scope"]
+                .as_str()
+                .unwrap_or("")
+                .split_whitespace()
+                .map(str::to_string)
+                .collect(),
+        };
+        let expires_at = claims["exp"]
+            .as_i64()
+            .and_then(|exp| Utc.timestamp_opt(exp, 0).single());
+
+        let status = match self.validator.verify_signature(token) {
+            Err(e) => TokenStatus::InvalidSignature {
+                reason: e.to_string(),
+            },
+            Ok(()) => match self.validator.validate(token) {
+                Ok(_) => TokenStatus::Valid,
+                Err(_) if expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) => {
+                    TokenStatus::Expired
+                }
+                Err(e) => TokenStatus::Invalid {
+                    reason: e.to_string(),
+                },
+            },
+        };
+
+        Ok(TokenInspection {
+            header,
+            claims,
+            permissions,
+            expires_at,
+            status,
+        })
+    }
+}
+
+/// Row of a batch token creation CSV file
+///
+/// The CSV file has a header row naming the `user`, `client`, `algorithm`
+/// and `duration` columns. The algorithm and duration may be left empty to
+/// use the defaults of the batch.
+#[derive(Debug, Clone, Deserialize)]
+struct BatchTokenRequest {
+    user: String,
+    client: String,
+    #[serde(default)]
+    algorithm: Option<String>,
+    #[serde(default)]
+    duration: Option<u64>,
+}
+
+/// Outcome of one row of a batch token creation
+///
+/// Exactly one of `token` and `error` is set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BatchTokenResult {
+    /// Line of the row in the CSV file, the header being line 1
+    pub line: u64,
+    /// The user of the row
+    pub user: String,
+    /// The client of the row
+    pub client: String,
+    /// The signing algorithm of the token
+    pub algorithm: String,
+    /// The token duration in seconds
+    pub duration: Option<u64>,
+    /// The created token
+    pub token: Option<String>,
+    /// Why no token was created for this row
+    pub error: Option<String>,
+}
+
+impl TokenCreator {
+    /// Creates a token for every row of a CSV file
+    ///
+    /// Rows are validated and created independently: an invalid row (unknown
+    /// user or client, unsupported algorithm, malformed values) gets an error
+    /// in its result and the batch goes on.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The CSV document, with a `user,client,algorithm,duration` header row
+    /// * `default_algorithm` - The algorithm of the rows without one
+    /// * `default_duration` - The duration in seconds of the rows without one
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<BatchTokenResult>)` - One result per row, in file order
+    /// * `Err(TokenCreationError::BatchFileError)` - If the header row cannot be read
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_photoacoustic::config::Config;
+    /// use rust_photoacoustic::utility::jwt_token::{ConfigLoader, JwtAlgorithm, TokenCreator};
+    ///
+    /// let mut config = Config::default();
+    /// config.visualization.hmac_secret = "test-secret-for-hmac".to_string();
+    /// let config_loader = ConfigLoader::from_config(&config).unwrap();
+    /// let token_creator = TokenCreator::new(&config_loader).unwrap();
+    ///
+    /// let csv = "user,client,algorithm,duration\nadmin,LaserSmartClient,HS256,60\nnobody,LaserSmartClient,,\n";
+    /// let results = token_creator
+    ///     .create_batch(csv.as_bytes(), &JwtAlgorithm::HS256, 3600)
+    ///     .unwrap();
+    /// assert!(results[0].token.is_some());
+    /// assert!(results[1].error.is_some());
+    /// ```
+    pub fn create_batch<R: std::io::Read>(
+        &self,
+        reader: R,
+        default_algorithm: &JwtAlgorithm,
+        default_duration: u64,
+    ) -> Result<Vec<BatchTokenResult>, TokenCreationError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .trim(csv::Trim::All)
+            .flexible(true)
+            .from_reader(reader);
+        let headers = reader
+            .headers()
+            .map_err(|e| TokenCreationError::BatchFileError {
+                reason: e.to_string(),
+            })?
+            .clone();
+
+        let mut results = Vec::new();
+        for record in reader.records() {
+            let record = match record {
+                Ok(record) => record,
+                Err(e) => {
+                    results.push(BatchTokenResult {
+                        line: e.position().map_or(0, |position| position.line()),
+                        user: String::new(),
+                        client: String::new(),
+                        algorithm: String::new(),
+                        duration: None,
+                        token: None,
+                        error: Some(e.to_string()),
+                    });
+                    continue;
+                }
+            };
+            let line = record.position().map_or(0, |position| position.line());
+            let field = |name: &str| {
+                headers
+                    .iter()
+                    .position(|header| header == name)
+                    .and_then(|index| record.get(index))
+                    .unwrap_or("")
+                    .to_string()
+            };
+            let mut result = BatchTokenResult {
+                line,
+                user: field("user"),
+                client: field("client"),
+                algorithm: field("algorithm"),
+                duration: None,
+                token: None,
+                error: None,
+            };
+
+            let created = record
+                .deserialize::<BatchTokenRequest>(Some(&headers))
+                .map_err(|e| e.to_string())
+                .and_then(|request| {
+                    let algorithm = match request.algorithm.as_deref() {
+                        Some(algorithm) => {
+                            JwtAlgorithm::from_str(algorithm).map_err(|e| e.to_string())?
+                        }
+                        None => default_algorithm.clone(),
+                    };
+                    result.algorithm = algorithm.as_str().to_string();
+                    result.duration = Some(request.duration.unwrap_or(default_duration));
+                    self.create_token(&TokenCreationParams {
+                        user_id: request.user,
+                        client_id: request.client,
+                        algorithm,
+                        duration_seconds: request.duration.unwrap_or(default_duration),
+                    })
+                    .map_err(|e| e.to_string())
+                });
+            match created {
+                Ok(created) => result.token = Some(created.token),
+                Err(error) => result.error = Some(error),
+            }
+            results.push(result);
+        }
+        Ok(results)
+    }
+}
+
+/// Columns of the CSV results of a batch token creation
+pub const BATCH_RESULT_COLUMNS: [&str; 7] = [
+    "line",
+    "user",
+    "client",
+    "algorithm",
+    "duration",
+    "token",
+    "error",
+];
+
+/// Serializes the results of a batch token creation to CSV
+///
+/// The header row holds the [`BATCH_RESULT_COLUMNS`], empty cells stand for
+/// missing values.
+///
+/// # Arguments
+///
+/// * `results` - The results of [`TokenCreator::create_batch`]
+///
+/// # Returns
+///
+/// The CSV document, or [`TokenCreationError::BatchFileError`] if it could not be written
+pub fn batch_results_to_csv(results: &[BatchTokenResult]) -> Result<String, TokenCreationError> {
+    let batch_error = |reason: String| TokenCreationError::BatchFileError { reason };
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(BATCH_RESULT_COLUMNS)
+        .map_err(|e| batch_error(e.to_string()))?;
+    for result in results {
+        writer
+            .write_record([
+                result.line.to_string(),
+                result.user.clone(),
+                result.client.clone(),
+                result.algorithm.clone(),
+                result.duration.map(|d| d.to_string()).unwrap_or_default(),
+                result.token.clone().unwrap_or_default(),
+                result.error.clone().unwrap_or_default(),
+            ])
+            .map_err(|e| batch_error(e.to_string()))?;
+    }
+    let csv = writer
+        .into_inner()
+        .map_err(|e| batch_error(e.to_string()))?;
+    String::from_utf8(csv).map_err(|e| batch_error(e.to_string()))
+}
@@ -0,0 +1,233 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Daemon logger writing to the console and to a rotated log file
+//!
+//! [`DaemonLogger`] wraps the `env_logger` console logger and optionally
+//! appends every log line to a [`RollingFileWriter`]. The logger is installed
+//! before the configuration is loaded, so the log file is attached afterwards
+//! with [`DaemonLogger::configure`].
+//!
+//! Each log line is formatted first and then written under the file lock in a
+//! single call, and a rotation only happens between two lines: lines logged
+//! concurrently from several threads are neither interleaved nor lost.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use anyhow::{Context, Result};
+use log::{LevelFilter, Log, Metadata, Record};
+
+use crate::config::DaemonConfig;
+
+/// Log file renamed `<path>.1`, `<path>.2`... once it reaches its maximum size
+#[derive(Debug)]
+pub struct RollingFileWriter {
+    path: PathBuf,
+    max_size: u64,
+    max_files: usize,
+    file: File,
+    size: u64,
+}
+
+impl RollingFileWriter {
+    /// Open `path` for appending, creating it and its directory if needed
+    ///
+    /// ### Parameters
+    ///
+    /// * `path` - Path of the current log file
+    /// * `max_size` - Size in bytes above which the file is rotated
+    /// * `max_files` - Number of rotated files kept next to the current one
+    pub fn open(path: impl Into<PathBuf>, max_size: u64, max_files: usize) -> io::Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            max_size,
+            max_files,
+            file,
+            size,
+        })
+    }
+
+    /// Path of the current log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Path of the rotated file `index`, `1` being the most recent one
+    pub fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
+    /// Whether a write of `len` bytes lands at the top of a file
+    ///
+    /// True when the current file is empty or would be rotated first.
+    pub fn starts_new_file(&self, len: u64) -> bool {
+        self.size == 0 || self.size + len > self.max_size
+    }
+
+    /// Shift the rotated files and start a new current file
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        let oldest = self.rotated_path(self.max_files);
+        if oldest.exists() {
+            fs::remove_file(&oldest)?;
+        }
+        for index in (1..self.max_files).rev() {
+            let rotated = self.rotated_path(index);
+            if rotated.exists() {
+                fs::rename(&rotated, self.rotated_path(index + 1))?;
+            }
+        }
+        if self.max_files > 0 {
+            fs::rename(&self.path, self.rotated_path(1))?;
+        }
+
+        self.file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RollingFileWriter {
+    /// Write the whole buffer, rotating first if it would overflow the file
+    ///
+    /// A buffer larger than the maximum size goes alone into a new file.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+        self.file.write_all(buf)?;
+        self.size += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Logger writing to the console and to an optional rotated log file
+pub struct DaemonLogger {
+    console: env_logger::Logger,
+    console_enabled: AtomicBool,
+    file: Mutex<Option<RollingFileWriter>>,
+}
+
+impl DaemonLogger {
+    /// Create a logger filtering and printing the console output with `console`
+    pub fn new(console: env_logger::Logger) -> Self {
+        Self {
+            console,
+            console_enabled: AtomicBool::new(true),
+            file: Mutex::new(None),
+        }
+    }
+
+    /// Install a logger with `level` as default filter, overridable with `RUST_LOG`
+    ///
+    /// ### Returns
+    ///
+    /// The installed logger, used to attach the log file once the
+    /// configuration is loaded
+    pub fn init(level: LevelFilter) -> Result<&'static Self> {
+        let console = env_logger::Builder::from_default_env()
+            .filter_level(level)
+            .build();
+        let max_level = console.filter();
+        let logger: &'static Self = Box::leak(Box::new(Self::new(console)));
+        log::set_logger(logger).context("A logger is already installed")?;
+        log::set_max_level(max_level);
+        Ok(logger)
+    }
+
+    /// Apply the log file settings of the daemon configuration
+    ///
+    /// ### Errors
+    ///
+    /// Fails if the log file cannot be opened
+    pub fn configure(&self, config: &DaemonConfig) -> Result<()> {
+        let file = match config.log_file {
+            Some(ref path) => Some(
+                RollingFileWriter::open(path, config.log_file_max_size, config.log_file_max_files)
+                    .with_context(|| format!("Failed to open log file {}", path))?,
+            ),
+            None => None,
+        };
+        let has_file = file.is_some();
+        self.set_file(file);
+        // Never silence every output
+        self.set_console_enabled(config.log_to_console || !has_file);
+        Ok(())
+    }
+
+    /// Replace the log file, `None` to stop logging to a file
+    pub fn set_file(&self, file: Option<RollingFileWriter>) {
+        if let Ok(mut current) = self.file.lock() {
+            *current = file;
+        }
+    }
+
+    /// Enable or disable the console output
+    pub fn set_console_enabled(&self, enabled: bool) {
+        self.console_enabled.store(enabled, Ordering::Relaxed);
+    }
+}
+
+impl Log for DaemonLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.console.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.console.matches(record) {
+            return;
+        }
+        if self.console_enabled.load(Ordering::Relaxed) {
+            self.console.log(record);
+        }
+
+        let Ok(mut file) = self.file.lock() else {
+            return;
+        };
+        if let Some(file) = file.as_mut() {
+            let line = format!(
+                "[{} {:<5} {}] {}\n",
+                chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                record.level(),
+                record.target(),
+                record.args()
+            );
+            // Logging must never fail the caller
+            let _ = file.write_all(line.as_bytes());
+        }
+    }
+
+    fn flush(&self) {
+        self.console.flush();
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(file) = file.as_mut() {
+                let _ = file.flush();
+            }
+        }
+    }
+}
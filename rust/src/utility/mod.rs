@@ -8,18 +8,34 @@ pub mod certificate_utilities;
 pub mod cpal;
 pub mod data_source;
 pub mod jwt_token;
+pub mod log_file;
 pub mod noise_generator;
 #[cfg(test)]
 pub mod noise_generator_test;
+pub mod noise_stats;
 /// System statistics collection module.
 /// This module provides cross-platform monitoring of CPU usage, memory consumption,
 /// and thread count for performance analysis and system health monitoring.
 pub mod system_stats;
 pub mod temperature_conversion;
+pub mod test_signal;
 
 // Re-exports for use in other modules
 pub use data_source::PhotoacousticDataSource;
-pub use temperature_conversion::convert_voltage_to_temperature;
+pub use temperature_conversion::{
+    convert_voltage_to_temperature, convert_voltage_to_temperature_with,
+};
+
+/// Human readable message of a panic payload
+pub fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
 
 /// Macro to include a PNG file as a base64-encoded string
 /// This macro reads a PNG file at compile time and encodes it in base64 format.
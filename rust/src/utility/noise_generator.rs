@@ -171,6 +171,25 @@ impl NoiseGenerator {
         Self::new(seed)
     }
 
+    /// Creates a new noise generator from `seed`, or from the system time without seed.
+    ///
+    /// Sources use it so that a configured seed makes their output reproducible,
+    /// while keeping a different noise on each run by default.
+    ///
+    /// ### Examples
+    ///
+    /// ```
+    /// use rust_photoacoustic::utility::noise_generator::NoiseGenerator;
+    ///
+    /// let mut first = NoiseGenerator::from_seed_or_system_time(Some(42));
+    /// let mut second = NoiseGenerator::from_seed_or_system_time(Some(42));
+    /// assert_eq!(first.random_float(), second.random_float());
+    /// ```
+    pub fn from_seed_or_system_time(seed: Option<u32>) -> Self {
+        seed.map(Self::new)
+            .unwrap_or_else(Self::new_from_system_time)
+    }
+
     /// Generates a random floating-point number between -1.0 and 1.0.
     ///
     /// This method uses the XORShift algorithm to update the internal state
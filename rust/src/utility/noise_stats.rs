@@ -0,0 +1,239 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! # Noise Characterization
+//!
+//! This module is the counterpart of the [`noise_generator`](super::noise_generator)
+//! module: instead of synthesizing noise, it measures the noise of a recorded
+//! signal, typically during the calibration of a photoacoustic cell.
+//!
+//! [`noise_stats`] computes the power spectrum of the signal (Hann window,
+//! DC removed) and splits it between the dominant tone, made of the bins
+//! around the spectral peak, and the noise, made of all the other bins. It
+//! reports:
+//!
+//! * The RMS level of the noise
+//! * The signal-to-noise ratio of the dominant tone
+//! * The spectral flatness (Wiener entropy) of the spectrum, close to 1 for
+//!   white noise and close to 0 for a pure tone
+//!
+//! [`peak_to_noise_floor_db`] compares the spectral peak with the median of the
+//! other bins instead. It tells a tone apart from broadband noise regardless of
+//! their level, and gates the detection of the photoacoustic output node.
+//!
+//! ## Example
+//!
+//! ```rust
+//! use rust_photoacoustic::utility::noise_stats::noise_stats;
+//!
+//! // 1 kHz tone sampled at 48 kHz, 48 samples per period
+//! let samples: Vec<f32> = (0..48000)
+//!     .map(|i| (2.0 * std::f32::consts::PI * (i % 48) as f32 / 48.0).sin())
+//!     .collect();
+//!
+//! let stats = noise_stats(&samples, 48000);
+//! assert!((stats.dominant_frequency - 1000.0).abs() < 2.0);
+//! assert!(stats.snr_db > 60.0);
+//! ```
+
+use realfft::RealFftPlanner;
+use std::fmt;
+
+/// Number of bins on each side of the spectral peak attributed to the tone
+///
+/// The main lobe of the Hann window spans two bins on each side of the tone;
+/// one more bin keeps the leakage of an off-bin tone out of the noise.
+const TONE_HALF_WIDTH_BINS: usize = 3;
+
+/// Noise characteristics of a signal, computed by [`noise_stats`]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct NoiseStats {
+    /// RMS level of the whole signal, DC removed
+    pub signal_rms: f32,
+    /// RMS level of the noise, the signal without its dominant tone
+    pub noise_rms: f32,
+    /// Frequency of the dominant tone in Hz
+    pub dominant_frequency: f32,
+    /// Ratio of the dominant tone power to the noise power in dB
+    ///
+    /// Infinite for a noiseless tone, 0 for a silent signal.
+    pub snr_db: f32,
+    /// Spectral flatness between 0 (pure tone) and 1 (white noise)
+    pub spectral_flatness: f32,
+}
+
+impl fmt::Display for NoiseStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "- Signal RMS: {:.6}", self.signal_rms)?;
+        writeln!(
+            f,
+            "- Noise RMS: {:.6} ({:.1} dBFS)",
+            self.noise_rms,
+            20.0 * self.noise_rms.max(f32::MIN_POSITIVE).log10()
+        )?;
+        writeln!(f, "- Dominant tone: {:.1} Hz", self.dominant_frequency)?;
+        writeln!(f, "- SNR: {:.1} dB", self.snr_db)?;
+        write!(f, "- Spectral flatness: {:.3}", self.spectral_flatness)
+    }
+}
+
+/// Compute the noise floor and SNR of a signal
+///
+/// The dominant tone is the highest bin of the power spectrum, DC excluded.
+/// Its power is the sum of the bins within three bins of the peak; the noise
+/// power is the sum of the other bins, scaled up to the whole band to account
+/// for the noise hidden under the tone.
+///
+/// ### Parameters
+///
+/// * `samples` - The signal, normalized to [-1.0, 1.0]
+/// * `sample_rate` - The sample rate of the signal in Hz
+///
+/// ### Returns
+///
+/// The noise characteristics of the signal. Signals shorter than 16 samples
+/// are too short to analyze and return all-zero statistics.
+pub fn noise_stats(samples: &[f32], sample_rate: u32) -> NoiseStats {
+    let n = samples.len();
+    let Some((power, mean_square)) = power_spectrum(samples) else {
+        return NoiseStats::default();
+    };
+    let total_power: f64 = power.iter().sum();
+    if total_power <= 0.0 {
+        return NoiseStats::default();
+    }
+
+    let peak = peak_bin(&power);
+    let tone_bins = tone_bins(peak, power.len());
+    let tone_bin_count = tone_bins.clone().count();
+    let measured_noise: f64 = total_power - power[tone_bins].iter().sum::<f64>();
+    let noise_bin_count = power.len() - tone_bin_count;
+
+    // Noise under the tone, assuming a flat noise floor
+    let noise_power = if noise_bin_count > 0 {
+        measured_noise * power.len() as f64 / noise_bin_count as f64
+    } else {
+        0.0
+    };
+    let tone_power = (total_power - noise_power).max(0.0);
+
+    let snr_db = if noise_power > 0.0 {
+        10.0 * (tone_power / noise_power).max(f64::MIN_POSITIVE).log10()
+    } else {
+        f64::INFINITY
+    };
+
+    // Geometric over arithmetic mean, floored to keep empty bins finite
+    let floor = total_power * 1e-20;
+    let log_mean = power.iter().map(|&p| (p + floor).ln()).sum::<f64>() / power.len() as f64;
+    let spectral_flatness = log_mean.exp() / (total_power / power.len() as f64);
+
+    NoiseStats {
+        signal_rms: mean_square.sqrt() as f32,
+        noise_rms: (mean_square * (noise_power / total_power).min(1.0)).sqrt() as f32,
+        dominant_frequency: ((peak + 1) as f64 * sample_rate as f64 / n as f64) as f32,
+        snr_db: snr_db as f32,
+        spectral_flatness: spectral_flatness.clamp(0.0, 1.0) as f32,
+    }
+}
+
+/// Compute the ratio of the spectral peak to the noise floor of a signal
+///
+/// The spectral peak is the highest bin of the power spectrum, DC excluded.
+/// The noise floor is the median power of the bins more than three bins away
+/// from the peak: unlike the mean, the median ignores the other tones and the
+/// few strong bins of a noise burst. White noise of any level gives about 10 dB,
+/// a tone standing out of the noise gives much more.
+///
+/// ### Parameters
+///
+/// * `samples` - The signal, normalized to [-1.0, 1.0]
+///
+/// ### Returns
+///
+/// The peak-to-noise-floor ratio in dB, infinite for a noiseless tone. Silent
+/// signals and signals shorter than 16 samples return 0.
+pub fn peak_to_noise_floor_db(samples: &[f32]) -> f32 {
+    let Some((power, _)) = power_spectrum(samples) else {
+        return 0.0;
+    };
+    let peak = peak_bin(&power);
+    if power[peak] <= 0.0 {
+        return 0.0;
+    }
+
+    let tone_bins = tone_bins(peak, power.len());
+    let mut off_peak: Vec<f64> = power
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !tone_bins.contains(index))
+        .map(|(_, &p)| p)
+        .collect();
+    if off_peak.is_empty() {
+        return f32::INFINITY;
+    }
+    off_peak.sort_by(|a, b| a.total_cmp(b));
+    let middle = off_peak.len() / 2;
+    let floor = if off_peak.len() % 2 == 1 {
+        off_peak[middle]
+    } else {
+        0.5 * (off_peak[middle - 1] + off_peak[middle])
+    };
+
+    if floor > 0.0 {
+        (10.0 * (power[peak] / floor).log10()) as f32
+    } else {
+        f32::INFINITY
+    }
+}
+
+/// Hann-windowed power spectrum of a signal, DC removed
+///
+/// ### Returns
+///
+/// The power of the bins above DC and the mean square of the signal, or `None`
+/// for signals shorter than 16 samples
+fn power_spectrum(samples: &[f32]) -> Option<(Vec<f64>, f64)> {
+    let n = samples.len();
+    if n < 16 {
+        return None;
+    }
+
+    let mean = samples.iter().map(|&s| s as f64).sum::<f64>() / n as f64;
+    let mean_square = samples
+        .iter()
+        .map(|&s| (s as f64 - mean).powi(2))
+        .sum::<f64>()
+        / n as f64;
+
+    let mut input: Vec<f64> = samples
+        .iter()
+        .enumerate()
+        .map(|(i, &s)| {
+            let window = 0.5 - 0.5 * (2.0 * std::f64::consts::PI * i as f64 / (n - 1) as f64).cos();
+            (s as f64 - mean) * window
+        })
+        .collect();
+    let fft = RealFftPlanner::<f64>::new().plan_fft_forward(n);
+    let mut spectrum = fft.make_output_vec();
+    fft.process(&mut input, &mut spectrum).ok()?;
+    // Bin 0 is DC, removed with the mean
+    let power = spectrum.iter().skip(1).map(|c| c.norm_sqr()).collect();
+    Some((power, mean_square))
+}
+
+/// Index of the highest bin of a power spectrum
+fn peak_bin(power: &[f64]) -> usize {
+    power
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map(|(index, _)| index)
+        .unwrap_or(0)
+}
+
+/// Bins attributed to the tone at `peak`, in a spectrum of `len` bins
+fn tone_bins(peak: usize, len: usize) -> std::ops::RangeInclusive<usize> {
+    peak.saturating_sub(TONE_HALF_WIDTH_BINS)..=(peak + TONE_HALF_WIDTH_BINS).min(len - 1)
+}
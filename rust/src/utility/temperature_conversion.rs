@@ -9,38 +9,217 @@ use evalexpr::{
     eval_with_context, Context, ContextWithMutableVariables, DefaultNumericTypes, HashMapContext,
     Value,
 };
+use log::debug;
 
-/// Convert voltage to temperature using a configurable mathematical formula
-///
-/// This function evaluates a mathematical expression where 'voltage' is available as a variable.
-/// The formula should return temperature in Kelvin.
-/// The formula use [evalexpr syntax](https://docs.rs/evalexpr/12.0.2/evalexpr/index.html) for evaluation.
-///
-/// # Arguments
-/// * `formula` - Mathematical expression as string (e.g., "1.0 / (1.0 / 298.15 + math::ln(10000.0 * voltage / (5.0 - voltage) / 10000.0) / 3977.0)")
-/// * `voltage` - Input voltage in volts
-///
-/// # Returns
-/// * `Result<f64>` - Temperature in Kelvin, or error if formula evaluation fails
-///
-/// # Example
-/// ```rust
-/// use rust_photoacoustic::utility::temperature_conversion::convert_voltage_to_temperature;
+use crate::config::thermal_regulation::{
+    ConversionType, LookupTablePoint, TemperatureConversionConfig, TwoPointCalibration,
+};
+
+/// Offset between the Celsius and Kelvin scales
+const KELVIN_OFFSET: f64 = 273.15;
+
+/// Reference temperature of the NTC β equation (25°C) in Kelvin
+const NTC_REFERENCE_K: f64 = 298.15;
+
+/// Mean temperature coefficient α of platinum RTDs between 0°C and 100°C (IEC 60751)
+const RTD_ALPHA: f64 = 0.00385;
+
+/// Callendar-Van Dusen coefficients of platinum RTDs (IEC 60751)
+const CVD_A: f64 = 3.9083e-3;
+const CVD_B: f64 = -5.775e-7;
+const CVD_C: f64 = -4.183e-12;
+
+/// Names of the built-in formulas, listed in the unknown formula error
+const SUPPORTED_FORMULAS: &str = "NTC_<R25>_<BETA> (e.g. NTC_10K_3977), STEINHART_HART, \
+     PT100_LINEAR, PT1000_LINEAR, PT100_CVD, PT1000_CVD, LOOKUP_TABLE";
+
+/// Built-in sensor formula, selected by name instead of an expression
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NamedFormula {
+    /// `NTC_<R25>_<BETA>`: NTC thermistor β equation
+    Ntc { r25: f64, beta: f64 },
+    /// `STEINHART_HART`: Steinhart-Hart equation with configured coefficients
+    SteinhartHart,
+    /// `PT100_LINEAR` / `PT1000_LINEAR`: platinum RTD with a constant α
+    RtdLinear { r0: f64 },
+    /// `PT100_CVD` / `PT1000_CVD`: platinum RTD Callendar-Van Dusen equation
+    RtdCallendarVanDusen { r0: f64 },
+    /// `LOOKUP_TABLE`: interpolation in the configured voltage to temperature table
+    LookupTable,
+}
+
+impl NamedFormula {
+    /// Parse a formula name
+    ///
+    /// Formulas starting with an uppercase letter and made of uppercase
+    /// letters, digits and underscores are formula names, other formulas are
+    /// expressions.
+    ///
+    /// ### Returns
+    ///
+    /// `Ok(None)` for an expression
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error listing the supported formulas for an unknown name
+    fn parse(formula: &str) -> Result<Option<Self>> {
+        let name = formula.trim();
+        let is_name = name.starts_with(|c: char| c.is_ascii_uppercase())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+        if !is_name {
+            return Ok(None);
+        }
+
+        let named = match name {
+            "STEINHART_HART" => Some(Self::SteinhartHart),
+            "PT100_LINEAR" => Some(Self::RtdLinear { r0: 100.0 }),
+            "PT1000_LINEAR" => Some(Self::RtdLinear { r0: 1000.0 }),
+            "PT100_CVD" => Some(Self::RtdCallendarVanDusen { r0: 100.0 }),
+            "PT1000_CVD" => Some(Self::RtdCallendarVanDusen { r0: 1000.0 }),
+            "LOOKUP_TABLE" => Some(Self::LookupTable),
+            _ => name.strip_prefix("NTC_").and_then(|parameters| {
+                let (r25, beta) = parameters.split_once('_')?;
+                Some(Self::Ntc {
+                    r25: parse_resistance(r25)?,
+                    beta: beta.parse::<f64>().ok().filter(|beta| *beta > 0.0)?,
+                })
+            }),
+        };
+        named.map(Some).ok_or_else(|| {
+            anyhow!(
+                "Unknown temperature conversion formula '{}'. Supported formulas: {}, \
+                 or an expression of 'voltage'",
+                name,
+                SUPPORTED_FORMULAS
+            )
+        })
+    }
+
+    /// Convert a voltage to a temperature in Kelvin
+    fn convert(self, conversion: &TemperatureConversionConfig, voltage: f64) -> Result<f64> {
+        let resistance = || divider_resistance(conversion, voltage);
+        match self {
+            Self::Ntc { r25, beta } => {
+                Ok(1.0 / (1.0 / NTC_REFERENCE_K + (resistance()? / r25).ln() / beta))
+            }
+            Self::SteinhartHart => {
+                let coefficients = conversion.steinhart_hart.as_ref().ok_or_else(|| {
+                    anyhow!("The STEINHART_HART formula requires the steinhart_hart coefficients")
+                })?;
+                let ln_r = resistance()?.ln();
+                Ok(1.0 / (coefficients.a + coefficients.b * ln_r + coefficients.c * ln_r.powi(3)))
+            }
+            Self::RtdLinear { r0 } => Ok(KELVIN_OFFSET + (resistance()? / r0 - 1.0) / RTD_ALPHA),
+            Self::RtdCallendarVanDusen { r0 } => {
+                Ok(KELVIN_OFFSET + callendar_van_dusen_celsius(resistance()? / r0)?)
+            }
+            Self::LookupTable => interpolate(&conversion.lookup_table, voltage),
+        }
+    }
+}
+
+/// Parse a resistance such as `10000`, `10K`, `4K7` or `1M`
+fn parse_resistance(text: &str) -> Option<f64> {
+    let value: f64 = match text.find(['K', 'M']) {
+        Some(index) => {
+            let multiplier = if text[index..].starts_with('K') {
+                1e3
+            } else {
+                1e6
+            };
+            format!("{}.{}", &text[..index], &text[index + 1..])
+                .parse::<f64>()
+                .ok()?
+                * multiplier
+        }
+        None => text.parse().ok()?,
+    };
+    (value > 0.0).then_some(value)
+}
+
+/// Resistance of the sensor at the bottom of the voltage divider
+fn divider_resistance(conversion: &TemperatureConversionConfig, voltage: f64) -> Result<f64> {
+    let supply = conversion.voltage_reference as f64;
+    if voltage <= 0.0 || voltage >= supply {
+        return Err(anyhow!(
+            "Voltage {:.3}V is outside the divider range (0V to {:.3}V): sensor shorted or disconnected",
+            voltage,
+            supply
+        ));
+    }
+    Ok(conversion.pullup_resistance * voltage / (supply - voltage))
+}
+
+/// Temperature in °C of a platinum RTD from its resistance ratio R/R0
 ///
-/// // NTC formula for 10kΩ NTC with β=3977, 10kΩ voltage divider, 5V supply
-///        let formula = "1.0 / (1.0 / 298.15 + math::ln(10000.0 * voltage / (5.0 - voltage) / 10000.0) / 3977.0)".to_string();
-/// let temp_k = convert_voltage_to_temperature(formula, 2.5).unwrap();
-/// assert!((temp_k - 298.15).abs() < 1.0); // Should be close to 25°C (298.15K)
-/// ```
-pub fn convert_voltage_to_temperature(formula: String, voltage: f32) -> Result<f64> {
-    // Validate input voltage
-    if voltage < 0.0 || voltage > 10.0 {
+/// Above 0°C the Callendar-Van Dusen equation R = R0·(1 + A·T + B·T²) is
+/// solved directly; below, the C·(T - 100)·T³ term is added and the equation
+/// is solved by Newton's method from the quadratic solution.
+fn callendar_van_dusen_celsius(ratio: f64) -> Result<f64> {
+    let discriminant = CVD_A * CVD_A - 4.0 * CVD_B * (1.0 - ratio);
+    if discriminant < 0.0 {
         return Err(anyhow!(
-            "Invalid voltage: {:.3}V (must be between 0V and 10V)",
-            voltage
+            "RTD resistance ratio {:.4} is outside the Callendar-Van Dusen range",
+            ratio
+        ));
+    }
+    let mut temperature = (-CVD_A + discriminant.sqrt()) / (2.0 * CVD_B);
+    if ratio < 1.0 {
+        for _ in 0..10 {
+            let t = temperature;
+            let f = 1.0 + CVD_A * t + CVD_B * t * t + CVD_C * (t - 100.0) * t.powi(3) - ratio;
+            let derivative = CVD_A + 2.0 * CVD_B * t + CVD_C * (4.0 * t.powi(3) - 300.0 * t * t);
+            temperature = t - f / derivative;
+        }
+    }
+    Ok(temperature)
+}
+
+/// Linear interpolation in a voltage to temperature lookup table
+fn interpolate(table: &[LookupTablePoint], voltage: f64) -> Result<f64> {
+    check_lookup_table(table)?;
+    let (first, last) = (&table[0], &table[table.len() - 1]);
+    if voltage < first.voltage || voltage > last.voltage {
+        return Err(anyhow!(
+            "Voltage {:.3}V is outside the lookup table range ({:.3}V to {:.3}V)",
+            voltage,
+            first.voltage,
+            last.voltage
         ));
     }
+    let upper = table
+        .partition_point(|point| point.voltage < voltage)
+        .max(1);
+    let (low, high) = (&table[upper - 1], &table[upper]);
+    let fraction = (voltage - low.voltage) / (high.voltage - low.voltage);
+    Ok(low.temperature_k + fraction * (high.temperature_k - low.temperature_k))
+}
 
+/// Check that a lookup table has at least two points with increasing voltages
+fn check_lookup_table(table: &[LookupTablePoint]) -> Result<()> {
+    if table.len() < 2 {
+        return Err(anyhow!(
+            "The LOOKUP_TABLE formula requires a lookup_table of at least 2 points, got {}",
+            table.len()
+        ));
+    }
+    if let Some(pair) = table
+        .windows(2)
+        .find(|pair| pair[1].voltage.is_nan() || pair[1].voltage <= pair[0].voltage)
+    {
+        return Err(anyhow!(
+            "Lookup table voltages must be strictly increasing, got {}V then {}V",
+            pair[0].voltage,
+            pair[1].voltage
+        ));
+    }
+    Ok(())
+}
+
+/// Evaluate an expression formula where `voltage` is a variable
+fn evaluate_expression(formula: &str, voltage: f32) -> Result<f64> {
     // Validate formula contains 'voltage' variable
     if !formula.contains("voltage") {
         return Err(anyhow!(
@@ -55,7 +234,7 @@ pub fn convert_voltage_to_temperature(formula: String, voltage: f32) -> Result<f
     context.set_value("voltage".into(), Value::Float(voltage as f64))?;
 
     // Evaluate the formula
-    let result = eval_with_context(&formula, &context).map_err(|e| {
+    let result = eval_with_context(formula, &context).map_err(|e| {
         anyhow!(
             "Failed to evaluate temperature formula '{}': {}",
             formula,
@@ -64,15 +243,135 @@ pub fn convert_voltage_to_temperature(formula: String, voltage: f32) -> Result<f
     })?;
 
     // Convert result to f64
-    let temperature_k = result.as_float().or_else(|_| {
+    result.as_float().or_else(|_| {
         Err(anyhow!(
             "Formula did not return a numeric value: '{}'",
             formula
         ))
-    })?;
+    })
+}
+
+/// Convert voltage to temperature using a configurable mathematical formula
+///
+/// This function evaluates a mathematical expression where 'voltage' is available as a variable.
+/// The formula should return temperature in Kelvin.
+/// The formula use [evalexpr syntax](https://docs.rs/evalexpr/12.0.2/evalexpr/index.html) for evaluation.
+///
+/// The built-in formulas of [`convert_voltage_to_temperature_with`] are also
+/// accepted, with a 10 kΩ pull-up and a 5 V supply. Formulas requiring
+/// configured parameters (`STEINHART_HART`, `LOOKUP_TABLE`) fail here.
+///
+/// # Arguments
+/// * `formula` - Mathematical expression as string (e.g., "1.0 / (1.0 / 298.15 + math::ln(10000.0 * voltage / (5.0 - voltage) / 10000.0) / 3977.0)")
+/// * `voltage` - Input voltage in volts
+///
+/// # Returns
+/// * `Result<f64>` - Temperature in Kelvin, or error if formula evaluation fails
+///
+/// # Example
+/// ```rust
+/// use rust_photoacoustic::utility::temperature_conversion::convert_voltage_to_temperature;
+///
+/// // NTC formula for 10kΩ NTC with β=3977, 10kΩ voltage divider, 5V supply
+///        let formula = "1.0 / (1.0 / 298.15 + math::ln(10000.0 * voltage / (5.0 - voltage) / 10000.0) / 3977.0)".to_string();
+/// let temp_k = convert_voltage_to_temperature(formula, 2.5).unwrap();
+/// assert!((temp_k - 298.15).abs() < 1.0); // Should be close to 25°C (298.15K)
+/// ```
+pub fn convert_voltage_to_temperature(formula: String, voltage: f32) -> Result<f64> {
+    convert_voltage_to_temperature_with(
+        &TemperatureConversionConfig {
+            formula,
+            adc_resolution: 16,
+            voltage_reference: 5.0,
+            conversion_type: ConversionType::default(),
+            pullup_resistance: 10000.0,
+            steinhart_hart: None,
+            lookup_table: Vec::new(),
+            calibration: None,
+        },
+        voltage,
+    )
+}
+
+/// Convert voltage to temperature with the formula and parameters of a regulator
+///
+/// The formula is either an expression of `voltage` (see
+/// [`convert_voltage_to_temperature`]) or one of the built-in formulas:
+///
+/// | Formula | Sensor | Parameters |
+/// |---|---|---|
+/// | `NTC_<R25>_<BETA>` | NTC thermistor, β equation (e.g. `NTC_10K_3977`) | resistance at 25°C and β in the name |
+/// | `STEINHART_HART` | NTC thermistor, 1/T = A + B·ln(R) + C·ln(R)³ | `steinhart_hart` coefficients |
+/// | `PT100_LINEAR`, `PT1000_LINEAR` | Platinum RTD, α = 0.00385 | |
+/// | `PT100_CVD`, `PT1000_CVD` | Platinum RTD, Callendar-Van Dusen equation (IEC 60751) | |
+/// | `LOOKUP_TABLE` | Any sensor, linear interpolation between points | `lookup_table` |
+///
+/// The resistive sensor formulas compute the resistance of the sensor from a
+/// voltage divider: the sensor between the ADC input and ground, a
+/// `pullup_resistance` resistor to the `voltage_reference` supply.
+///
+/// The `calibration` of the configuration, if any, is applied to the
+/// converted temperature before its range is checked.
+///
+/// # Arguments
+/// * `conversion` - Temperature conversion configuration of the regulator
+/// * `voltage` - Input voltage in volts
+///
+/// # Returns
+/// * `Result<f64>` - Temperature in Kelvin, or an error for an unknown
+///   formula, missing parameters or a voltage outside the formula range
+///
+/// # Example
+/// ```rust
+/// use rust_photoacoustic::config::thermal_regulation::{
+///     ConversionType, TemperatureConversionConfig,
+/// };
+/// use rust_photoacoustic::utility::temperature_conversion::convert_voltage_to_temperature_with;
+///
+/// // PT1000 with a 1kΩ pull-up on a 3.3V supply: 1000Ω at half the supply is 0°C
+/// let conversion = TemperatureConversionConfig {
+///     formula: "PT1000_CVD".to_string(),
+///     adc_resolution: 16,
+///     voltage_reference: 3.3,
+///     conversion_type: ConversionType::Linear,
+///     pullup_resistance: 1000.0,
+///     steinhart_hart: None,
+///     lookup_table: vec![],
+///     calibration: None,
+/// };
+/// let temp_k = convert_voltage_to_temperature_with(&conversion, 1.65).unwrap();
+/// assert!((temp_k - 273.15).abs() < 0.01);
+/// ```
+pub fn convert_voltage_to_temperature_with(
+    conversion: &TemperatureConversionConfig,
+    voltage: f32,
+) -> Result<f64> {
+    // Validate input voltage
+    if voltage < 0.0 || voltage > 10.0 {
+        return Err(anyhow!(
+            "Invalid voltage: {:.3}V (must be between 0V and 10V)",
+            voltage
+        ));
+    }
+
+    let temperature_k = match NamedFormula::parse(&conversion.formula)? {
+        Some(named) => named.convert(conversion, voltage as f64)?,
+        None => evaluate_expression(&conversion.formula, voltage)?,
+    };
+    let temperature_k = match &conversion.calibration {
+        Some(calibration) => {
+            let calibrated_k = calibration.apply(temperature_k);
+            debug!(
+                "Temperature calibration: {:.3}K -> {:.3}K",
+                temperature_k, calibrated_k
+            );
+            calibrated_k
+        }
+        None => temperature_k,
+    };
 
     // Validate result (reasonable temperature range in Kelvin: -50°C to 100°C)
-    if temperature_k < 223.15 || temperature_k > 373.15 {
+    if !(223.15..=373.15).contains(&temperature_k) {
         return Err(anyhow!(
             "Calculated temperature {:.2}K ({:.2}°C) is outside reasonable range (-50°C to 100°C)",
             temperature_k,
@@ -83,9 +382,70 @@ pub fn convert_voltage_to_temperature(formula: String, voltage: f32) -> Result<f
     Ok(temperature_k)
 }
 
+/// Check the temperature conversion configuration of a regulator
+///
+/// The calibration points must differ. Built-in formulas are checked for
+/// their parameters. Expressions are evaluated at 1V, 2.5V and 4V, which must
+/// give reasonable temperatures.
+///
+/// # Arguments
+/// * `conversion` - Temperature conversion configuration of the regulator
+///
+/// # Returns
+/// * `Result<()>` - An error describing the first problem found
+pub fn validate_temperature_conversion(conversion: &TemperatureConversionConfig) -> Result<()> {
+    if let Some(TwoPointCalibration { low, high }) = conversion
+        .calibration
+        .as_ref()
+        .and_then(|calibration| calibration.two_point.as_ref())
+    {
+        if low.measured_k == high.measured_k || low.reference_k == high.reference_k {
+            return Err(anyhow!(
+                "The two calibration points must have different measured and reference temperatures"
+            ));
+        }
+    }
+
+    match NamedFormula::parse(&conversion.formula)? {
+        Some(NamedFormula::LookupTable) => check_lookup_table(&conversion.lookup_table),
+        Some(named) => {
+            if conversion.pullup_resistance.is_nan() || conversion.pullup_resistance <= 0.0 {
+                return Err(anyhow!(
+                    "The {} formula requires a positive pullup_resistance, got {}",
+                    conversion.formula,
+                    conversion.pullup_resistance
+                ));
+            }
+            if named == NamedFormula::SteinhartHart && conversion.steinhart_hart.is_none() {
+                return Err(anyhow!(
+                    "The STEINHART_HART formula requires the steinhart_hart coefficients"
+                ));
+            }
+            Ok(())
+        }
+        None => {
+            // Test the formula with a few test voltages to ensure it works correctly
+            for test_voltage in [1.0, 2.5, 4.0] {
+                let temperature_k = convert_voltage_to_temperature_with(conversion, test_voltage)
+                    .map_err(|e| anyhow!("at {}V: {}", test_voltage, e))?;
+                debug!(
+                    "Formula validation: {}V -> {:.2}K ({:.2}°C)",
+                    test_voltage,
+                    temperature_k,
+                    temperature_k - 273.15
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::thermal_regulation::{
+        CalibrationPoint, SteinhartHartCoefficients, TemperatureCalibration,
+    };
     use approx::assert_relative_eq;
 
     #[test]
@@ -154,4 +514,304 @@ mod tests {
         let formula = "\"not a number\"".to_string();
         assert!(convert_voltage_to_temperature(formula, 2.5).is_err());
     }
+
+    /// Conversion configuration of a built-in formula on a voltage divider
+    fn conversion(formula: &str, supply: f32, pullup: f64) -> TemperatureConversionConfig {
+        TemperatureConversionConfig {
+            formula: formula.to_string(),
+            adc_resolution: 16,
+            voltage_reference: supply,
+            conversion_type: ConversionType::default(),
+            pullup_resistance: pullup,
+            steinhart_hart: None,
+            lookup_table: vec![],
+            calibration: None,
+        }
+    }
+
+    /// Check the conversion of sensor resistances against reference temperatures in °C
+    fn assert_resistances(
+        conversion: &TemperatureConversionConfig,
+        references: &[(f64, f64)],
+        tolerance: f64,
+    ) {
+        let supply = conversion.voltage_reference as f64;
+        for &(resistance, temperature_c) in references {
+            let voltage = supply * resistance / (resistance + conversion.pullup_resistance);
+            let temp_k = convert_voltage_to_temperature_with(conversion, voltage as f32).unwrap();
+            assert_relative_eq!(temp_k - 273.15, temperature_c, epsilon = tolerance);
+        }
+    }
+
+    #[test]
+    fn test_ntc_beta_formula() {
+        // R(T) = 10kΩ·exp(3977·(1/T - 1/298.15))
+        let ntc = conversion("NTC_10K_3977", 5.0, 10000.0);
+        assert_resistances(
+            &ntc,
+            &[
+                (33900.42, 0.0),
+                (10000.0, 25.0),
+                (3563.13, 50.0),
+                (1070.31, 85.0),
+            ],
+            0.01,
+        );
+
+        // Same result as the equivalent expression
+        let expression = "1.0 / (1.0 / 298.15 + math::ln(10000.0 * voltage / (5.0 - voltage) / 10000.0) / 3977.0)";
+        for voltage in [1.0, 2.5, 4.0] {
+            assert_relative_eq!(
+                convert_voltage_to_temperature_with(&ntc, voltage).unwrap(),
+                convert_voltage_to_temperature(expression.to_string(), voltage).unwrap(),
+                epsilon = 1e-6
+            );
+        }
+
+        // Other resistance notations
+        let ntc_4k7 = conversion("NTC_4K7_3950", 5.0, 4700.0);
+        assert_relative_eq!(
+            convert_voltage_to_temperature_with(&ntc_4k7, 2.5).unwrap(),
+            298.15,
+            epsilon = 1e-6
+        );
+        let ntc_ohms = conversion("NTC_100000_4250", 5.0, 100000.0);
+        assert_relative_eq!(
+            convert_voltage_to_temperature_with(&ntc_ohms, 2.5).unwrap(),
+            298.15,
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn test_steinhart_hart_formula() {
+        // Coefficients and resistance table of a common 10kΩ NTC thermistor
+        let mut steinhart_hart = conversion("STEINHART_HART", 3.3, 10000.0);
+        assert!(convert_voltage_to_temperature_with(&steinhart_hart, 1.65).is_err());
+
+        steinhart_hart.steinhart_hart = Some(SteinhartHartCoefficients {
+            a: 1.129148e-3,
+            b: 2.34125e-4,
+            c: 8.76741e-8,
+        });
+        assert_resistances(
+            &steinhart_hart,
+            &[
+                (97070.0, -20.0),
+                (32650.0, 0.0),
+                (10000.0, 25.0),
+                (3602.0, 50.0),
+                (1070.0, 85.0),
+            ],
+            0.05,
+        );
+    }
+
+    #[test]
+    fn test_rtd_callendar_van_dusen_formula() {
+        // IEC 60751 resistance table of the PT100
+        let references = [
+            (84.27, -40.0),
+            (92.16, -20.0),
+            (100.0, 0.0),
+            (119.40, 50.0),
+            (132.80, 85.0),
+        ];
+        assert_resistances(&conversion("PT100_CVD", 3.3, 100.0), &references, 0.02);
+
+        let pt1000_references: Vec<(f64, f64)> = references
+            .iter()
+            .map(|&(resistance, temperature)| (resistance * 10.0, temperature))
+            .collect();
+        assert_resistances(
+            &conversion("PT1000_CVD", 3.3, 1000.0),
+            &pt1000_references,
+            0.02,
+        );
+    }
+
+    #[test]
+    fn test_rtd_linear_formula() {
+        // Exact on the constant α line
+        let pt100 = conversion("PT100_LINEAR", 3.3, 100.0);
+        assert_resistances(&pt100, &[(100.0, 0.0), (119.25, 50.0)], 1e-3);
+
+        // Within 1°C of the IEC 60751 table between -40°C and 85°C
+        let pt1000 = conversion("PT1000_LINEAR", 3.3, 1000.0);
+        assert_resistances(
+            &pt1000,
+            &[
+                (842.7, -40.0),
+                (1000.0, 0.0),
+                (1194.0, 50.0),
+                (1328.0, 85.0),
+            ],
+            1.0,
+        );
+    }
+
+    #[test]
+    fn test_lookup_table_formula() {
+        let mut table = conversion("LOOKUP_TABLE", 5.0, 10000.0);
+        assert!(convert_voltage_to_temperature_with(&table, 2.5).is_err());
+
+        table.lookup_table = vec![
+            LookupTablePoint {
+                voltage: 1.0,
+                temperature_k: 333.15,
+            },
+            LookupTablePoint {
+                voltage: 2.5,
+                temperature_k: 298.15,
+            },
+            LookupTablePoint {
+                voltage: 4.0,
+                temperature_k: 268.15,
+            },
+        ];
+        for (voltage, expected) in [
+            (1.0, 333.15),
+            (1.75, 315.65),
+            (2.5, 298.15),
+            (3.5, 278.15),
+            (4.0, 268.15),
+        ] {
+            assert_relative_eq!(
+                convert_voltage_to_temperature_with(&table, voltage).unwrap(),
+                expected,
+                epsilon = 1e-4
+            );
+        }
+
+        // No extrapolation outside the table
+        assert!(convert_voltage_to_temperature_with(&table, 0.5).is_err());
+        assert!(convert_voltage_to_temperature_with(&table, 4.5).is_err());
+
+        // Voltages must increase
+        table.lookup_table.swap(0, 1);
+        assert!(validate_temperature_conversion(&table).is_err());
+        assert!(convert_voltage_to_temperature_with(&table, 2.0).is_err());
+    }
+
+    #[test]
+    fn test_unknown_formula_name() {
+        for name in ["NTC_10K", "NTC_XK_3977", "PT500_CVD", "THERMOCOUPLE_K"] {
+            let error = convert_voltage_to_temperature_with(&conversion(name, 5.0, 10000.0), 2.5)
+                .unwrap_err()
+                .to_string();
+            assert!(
+                error.contains("Unknown temperature conversion formula")
+                    && error.contains(name)
+                    && error.contains("PT100_CVD"),
+                "{}",
+                error
+            );
+            assert!(validate_temperature_conversion(&conversion(name, 5.0, 10000.0)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_named_formula_divider_range() {
+        // A shorted or disconnected sensor is reported, not converted
+        let ntc = conversion("NTC_10K_3977", 5.0, 10000.0);
+        assert!(convert_voltage_to_temperature_with(&ntc, 0.0).is_err());
+        assert!(convert_voltage_to_temperature_with(&ntc, 5.0).is_err());
+        assert!(validate_temperature_conversion(&ntc).is_ok());
+        assert!(validate_temperature_conversion(&conversion("NTC_10K_3977", 5.0, 0.0)).is_err());
+    }
+    /// Calibration measured 1K too high at 0°C and 0.5K too low at 50°C
+    fn two_point_calibration(offset_k: f64) -> TemperatureCalibration {
+        TemperatureCalibration {
+            offset_k,
+            two_point: Some(TwoPointCalibration {
+                low: CalibrationPoint {
+                    measured_k: 274.15,
+                    reference_k: 273.15,
+                },
+                high: CalibrationPoint {
+                    measured_k: 322.65,
+                    reference_k: 323.15,
+                },
+            }),
+        }
+    }
+
+    #[test]
+    fn test_two_point_calibration() {
+        let calibration = two_point_calibration(0.0);
+
+        // The calibration points are mapped exactly
+        assert_relative_eq!(calibration.apply(274.15), 273.15, epsilon = 1e-9);
+        assert_relative_eq!(calibration.apply(322.65), 323.15, epsilon = 1e-9);
+
+        // Linear in between: the midpoint maps to the midpoint
+        assert_relative_eq!(calibration.apply(298.4), 298.15, epsilon = 1e-9);
+        let gain = 50.0 / 48.5;
+        for measured in [280.0, 290.0, 300.0, 310.0] {
+            assert_relative_eq!(
+                calibration.apply(measured),
+                273.15 + (measured - 274.15) * gain,
+                epsilon = 1e-9
+            );
+        }
+
+        // The offset is added after the correction
+        assert_relative_eq!(
+            two_point_calibration(0.25).apply(274.15),
+            273.4,
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn test_calibrated_conversion() {
+        // 273.15 + voltage * 10, the calibration points at 0.1V and 4.95V
+        let mut linear = conversion("273.15 + voltage * 10.0", 5.0, 10000.0);
+        let uncalibrated: Vec<f64> = [1.0, 2.5, 4.0]
+            .iter()
+            .map(|&voltage| convert_voltage_to_temperature_with(&linear, voltage).unwrap())
+            .collect();
+
+        // An empty calibration leaves the output unchanged
+        linear.calibration = Some(TemperatureCalibration::default());
+        for (voltage, expected) in [1.0, 2.5, 4.0].iter().zip(&uncalibrated) {
+            assert_eq!(
+                convert_voltage_to_temperature_with(&linear, *voltage).unwrap(),
+                *expected
+            );
+        }
+
+        linear.calibration = Some(TemperatureCalibration {
+            offset_k: -0.5,
+            two_point: None,
+        });
+        assert_relative_eq!(
+            convert_voltage_to_temperature_with(&linear, 2.5).unwrap(),
+            297.65,
+            epsilon = 1e-4
+        );
+
+        linear.calibration = Some(two_point_calibration(0.0));
+        assert_relative_eq!(
+            convert_voltage_to_temperature_with(&linear, 0.1).unwrap(),
+            273.15,
+            epsilon = 1e-4
+        );
+        assert_relative_eq!(
+            convert_voltage_to_temperature_with(&linear, 4.95).unwrap(),
+            323.15,
+            epsilon = 1e-4
+        );
+        assert!(validate_temperature_conversion(&linear).is_ok());
+
+        // Identical calibration points are rejected
+        if let Some(TwoPointCalibration { high, .. }) = linear
+            .calibration
+            .as_mut()
+            .and_then(|calibration| calibration.two_point.as_mut())
+        {
+            high.measured_k = 274.15;
+        }
+        assert!(validate_temperature_conversion(&linear).is_err());
+    }
 }
@@ -0,0 +1,50 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! # Test Signals
+//!
+//! Deterministic signals used to characterize filters: the output of a
+//! linear filter fed with a unit impulse is its impulse response, and with a
+//! unit step its step response. The same signals can be streamed by the mock
+//! source through [`TestSignalConfig`](crate::config::TestSignalConfig).
+//!
+//! ## Example
+//!
+//! ```rust
+//! use rust_photoacoustic::preprocessing::filter::{Filter, LowpassFilter};
+//! use rust_photoacoustic::utility::test_signal::impulse;
+//!
+//! let filter = LowpassFilter::new(1000.0).with_sample_rate(48000);
+//! let response = filter.apply(&impulse(1024, 0));
+//!
+//! // The response starts with the impulse at sample 0, then decays
+//! assert!(response[0] > 0.0);
+//! assert!(response.windows(2).all(|pair| pair[1] < pair[0]));
+//! ```
+
+/// Unit impulse: `length` samples of silence except 1.0 at `position`
+///
+/// ### Arguments
+///
+/// * `length` - Number of samples
+/// * `position` - Index of the impulse, no impulse if beyond `length`
+pub fn impulse(length: usize, position: usize) -> Vec<f32> {
+    let mut signal = vec![0.0; length];
+    if let Some(sample) = signal.get_mut(position) {
+        *sample = 1.0;
+    }
+    signal
+}
+
+/// Unit step: `length` samples, silent before `position` and 1.0 from it on
+///
+/// ### Arguments
+///
+/// * `length` - Number of samples
+/// * `position` - Index of the first sample at 1.0
+pub fn step(length: usize, position: usize) -> Vec<f32> {
+    (0..length)
+        .map(|sample| if sample >= position { 1.0 } else { 0.0 })
+        .collect()
+}
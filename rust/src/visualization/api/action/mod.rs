@@ -12,12 +12,15 @@
 //! # Available Endpoints
 //!
 //! - `GET /api/action/{node_id}/history` - Get historical measurement data
+//! - `GET /api/action/history?nodes=a,b,c` - Get the history of several nodes at once
+//! - `DELETE /api/action/{node_id}/history` - Clear the history buffer
 //! - `GET /api/action/{node_id}/history/stats` - Get buffer statistics
 //! - `GET /api/action` - List all action nodes
 //!
 //! # Security
 //!
-//! All endpoints require `read:api` permission and valid JWT authentication.
+//! All endpoints require valid JWT authentication, with the `read:api`
+//! permission, or `write:api` for clearing a history buffer.
 //!
 //! # Usage Examples
 //!
@@ -26,6 +29,15 @@
 //! curl -H "Authorization: Bearer $TOKEN" \
 //!      "https://localhost:8080/api/action/redis_stream_action/history?limit=50"
 //!
+//! # Get the measurements of one hour, 100 at a time: repeat with
+//! # before=<next_cursor of the previous response> until it is absent
+//! curl -i -H "Authorization: Bearer $TOKEN" \
+//!      "https://localhost:8080/api/action/redis_stream_action/history?since=1640995200&until=1640998800&limit=100"
+//!
+//! # Download the history as CSV (same as sending "Accept: text/csv")
+//! curl -H "Authorization: Bearer $TOKEN" -o history.csv \
+//!      "https://localhost:8080/api/action/redis_stream_action/history?format=csv"
+//!
 //! # Get buffer statistics for web_dashboard_action node
 //! curl -H "Authorization: Bearer $TOKEN" \
 //!      "https://localhost:8080/api/action/web_dashboard_action/history/stats"
@@ -36,15 +48,25 @@
 //! ```
 
 use anyhow::{anyhow, Result};
-use auth_macros::openapi_protect_get;
-use rocket::http::Status;
+use auth_macros::{openapi_protect_delete, openapi_protect_get};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rocket::http::{ContentType, Status};
+use rocket::request::{FromRequest, Outcome};
+use rocket::response::{self, Responder};
 use rocket::serde::json::Json;
-use rocket::{get, State};
-use rocket_okapi::okapi::openapi3::OpenApi;
+use rocket::{get, Request, State};
+use rocket_okapi::gen::OpenApiGenerator;
+use rocket_okapi::okapi::openapi3::{MediaType, OpenApi, RefOr, Responses};
+use rocket_okapi::request::{OpenApiFromRequest, RequestHeaderInput};
+use rocket_okapi::response::OpenApiResponderInner;
 use rocket_okapi::{openapi_get_routes_spec, JsonSchema};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+use std::time::UNIX_EPOCH;
 
 use crate::processing::computing_nodes::action_drivers::MeasurementData;
 use crate::processing::computing_nodes::action_trait::ActionNode;
@@ -52,10 +74,362 @@ use crate::processing::computing_nodes::UniversalActionNode;
 use crate::visualization::shared_state::SharedVisualizationState;
 
 /// Query parameters for history endpoint
-#[derive(Deserialize, JsonSchema)]
+///
+/// Time bounds are Unix timestamps in seconds and are inclusive. Cursors are
+/// taken from the `next_cursor` of a previous response.
+#[derive(Debug, Clone, Default)]
 pub struct HistoryQuery {
     /// Maximum number of entries to return (default: all)
     pub limit: Option<usize>,
+    /// Only return entries recorded at or after this time
+    pub since: Option<u64>,
+    /// Only return entries recorded at or before this time
+    pub until: Option<u64>,
+    /// Only return entries older than this cursor (next page of older entries)
+    pub before: Option<HistoryCursor>,
+    /// Only return entries newer than this cursor (next page of newer entries)
+    pub after: Option<HistoryCursor>,
+}
+
+/// One page of action history
+#[derive(Debug, Clone)]
+pub struct HistoryPage {
+    /// Entries of the page, newest first
+    pub items: Vec<MeasurementData>,
+    /// Cursor to pass as `before` (or `after` when paging forward) to get the
+    /// next page, `None` when the page is the last one
+    pub next_cursor: Option<HistoryCursor>,
+}
+
+/// Position of an entry in the action history
+///
+/// Entries recorded at the same timestamp are told apart by their sequence,
+/// their rank among the entries of that timestamp starting from the oldest,
+/// so that a page boundary never skips or repeats an entry. Cursors are
+/// ordered like the entries, from the oldest to the newest, and written as
+/// `<nanoseconds since the Unix epoch>-<sequence>`.
+///
+/// ### Example
+///
+/// ```
+/// use rust_photoacoustic::visualization::api::action::HistoryCursor;
+///
+/// let cursor: HistoryCursor = "1700000000000000000-1".parse().unwrap();
+/// assert_eq!(cursor.timestamp_ns, 1_700_000_000_000_000_000);
+/// assert_eq!(cursor.sequence, 1);
+/// assert_eq!(cursor.to_string(), "1700000000000000000-1");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HistoryCursor {
+    /// Timestamp of the entry in nanoseconds since the Unix epoch
+    pub timestamp_ns: u64,
+    /// Rank of the entry among the entries recorded at the same timestamp
+    pub sequence: u32,
+}
+
+impl fmt::Display for HistoryCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.timestamp_ns, self.sequence)
+    }
+}
+
+impl FromStr for HistoryCursor {
+    type Err = anyhow::Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let (timestamp_ns, sequence) = value
+            .split_once('-')
+            .ok_or_else(|| anyhow!("Invalid history cursor '{}'", value))?;
+        Ok(Self {
+            timestamp_ns: timestamp_ns.parse()?,
+            sequence: sequence.parse()?,
+        })
+    }
+}
+
+/// Cursors of the history entries
+///
+/// ### Parameters
+///
+/// * `history` - The history entries, newest first
+///
+/// ### Returns
+///
+/// The cursor of each entry, in the same order as `history`
+pub fn history_cursors(history: &[MeasurementData]) -> Vec<HistoryCursor> {
+    let mut cursors: Vec<HistoryCursor> = Vec::with_capacity(history.len());
+    // Number the entries sharing a timestamp from the oldest one
+    for entry in history.iter().rev() {
+        let timestamp_ns = entry
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64;
+        let sequence = match cursors.last() {
+            Some(previous) if previous.timestamp_ns == timestamp_ns => previous.sequence + 1,
+            _ => 0,
+        };
+        cursors.push(HistoryCursor {
+            timestamp_ns,
+            sequence,
+        });
+    }
+    cursors.reverse();
+    cursors
+}
+
+/// Select a page of history entries
+///
+/// Entries are filtered by the `since`/`until` range and the `before`/`after`
+/// cursors, then `limit` entries are kept. Pages walk towards older entries,
+/// unless only `after` is set, in which case they walk towards newer entries
+/// and keep the entries closest to the cursor.
+///
+/// ### Parameters
+///
+/// * `history` - The history entries, newest first
+/// * `query` - The filtering and pagination parameters
+///
+/// ### Returns
+///
+/// The page, with entries newest first
+pub fn paginate_history(history: Vec<MeasurementData>, query: &HistoryQuery) -> HistoryPage {
+    let cursors = history_cursors(&history);
+    let (mut matching, mut matching_cursors): (Vec<MeasurementData>, Vec<HistoryCursor>) = history
+        .into_iter()
+        .zip(cursors)
+        .filter(|(entry, cursor)| {
+            let seconds = entry
+                .timestamp
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            query.since.map_or(true, |since| seconds >= since)
+                && query.until.map_or(true, |until| seconds <= until)
+                && query.before.map_or(true, |before| *cursor < before)
+                && query.after.map_or(true, |after| *cursor > after)
+        })
+        .unzip();
+
+    let limit = query.limit.unwrap_or(usize::MAX);
+    if matching.len() <= limit {
+        return HistoryPage {
+            items: matching,
+            next_cursor: None,
+        };
+    }
+
+    if query.after.is_some() && query.before.is_none() {
+        // Paging forward: keep the oldest entries, the cursor is the newest one kept
+        let items = matching.split_off(matching.len() - limit);
+        let next_cursor = matching_cursors.get(matching.len()).copied();
+        HistoryPage { items, next_cursor }
+    } else {
+        // Paging backward: keep the newest entries, the cursor is the oldest one kept
+        matching.truncate(limit);
+        matching_cursors.truncate(limit);
+        HistoryPage {
+            items: matching,
+            next_cursor: matching_cursors.last().copied(),
+        }
+    }
+}
+
+/// Representation of the history returned to the client
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HistoryFormat {
+    /// JSON array of [`MeasurementData`] (default)
+    #[default]
+    Json,
+    /// CSV with a header row, see [`history_to_csv`]
+    Csv,
+}
+
+impl HistoryFormat {
+    /// Parse the value of the `format` query parameter (`json` or `csv`)
+    pub fn from_param(value: &str) -> Option<Self> {
+        if value.eq_ignore_ascii_case("json") {
+            Some(Self::Json)
+        } else if value.eq_ignore_ascii_case("csv") {
+            Some(Self::Csv)
+        } else {
+            None
+        }
+    }
+}
+
+/// Format negotiated from the `Accept` header: CSV when `text/csv` is preferred
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for HistoryFormat {
+    type Error = Infallible;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let prefers_csv = request.accept().is_some_and(|accept| {
+            let media_type = accept.preferred().media_type();
+            media_type.top() == "text" && media_type.sub() == "csv"
+        });
+        Outcome::Success(if prefers_csv {
+            HistoryFormat::Csv
+        } else {
+            HistoryFormat::Json
+        })
+    }
+}
+
+impl<'r> OpenApiFromRequest<'r> for HistoryFormat {
+    fn from_request_input(
+        _gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        Ok(RequestHeaderInput::None)
+    }
+}
+
+/// Fixed leading columns of the CSV export
+const CSV_COLUMNS: [&str; 5] = [
+    "timestamp",
+    "source_node_id",
+    "concentration_ppm",
+    "peak_amplitude",
+    "peak_frequency",
+];
+
+/// Serialize history entries to CSV
+///
+/// The header row holds the [`CSV_COLUMNS`] followed by one `metadata.<key>`
+/// column per metadata key found in any entry, sorted by key. Timestamps are
+/// RFC 3339 UTC dates, string metadata values are written as is and other
+/// values as JSON. Missing metadata values are left empty.
+///
+/// ### Parameters
+///
+/// * `items` - The entries to serialize, one row each
+///
+/// ### Returns
+///
+/// The CSV document, or an error if it could not be written
+pub fn history_to_csv(items: &[MeasurementData]) -> Result<String> {
+    let metadata_keys: BTreeSet<&str> = items
+        .iter()
+        .flat_map(|entry| entry.metadata.keys().map(String::as_str))
+        .collect();
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(
+        CSV_COLUMNS
+            .iter()
+            .map(|column| column.to_string())
+            .chain(metadata_keys.iter().map(|key| format!("metadata.{}", key))),
+    )?;
+
+    for entry in items {
+        let timestamp =
+            DateTime::<Utc>::from(entry.timestamp).to_rfc3339_opts(SecondsFormat::Millis, true);
+        let fixed = [
+            timestamp,
+            entry.source_node_id.clone(),
+            entry.concentration_ppm.to_string(),
+            entry.peak_amplitude.to_string(),
+            entry.peak_frequency.to_string(),
+        ];
+        let metadata = metadata_keys
+            .iter()
+            .map(|key| match entry.metadata.get(*key) {
+                Some(Value::String(value)) => value.clone(),
+                Some(Value::Null) | None => String::new(),
+                Some(value) => value.to_string(),
+            });
+        writer.write_record(fixed.into_iter().chain(metadata))?;
+    }
+
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// JSON body of the history endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HistoryPageBody {
+    /// Measurement history, newest first
+    pub items: Vec<MeasurementData>,
+    /// Cursor of the next page, absent on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// History entries in the negotiated format
+///
+/// The JSON body holds the next page cursor in `next_cursor`. It is also sent
+/// in the `X-Next-Cursor` header, which is the only place for it in CSV
+/// documents.
+pub struct HistoryResponse {
+    pub page: HistoryPage,
+    pub format: HistoryFormat,
+}
+
+impl<'r> Responder<'r, 'static> for HistoryResponse {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = match self.format {
+            HistoryFormat::Json => Json(HistoryPageBody {
+                items: self.page.items,
+                next_cursor: self.page.next_cursor.map(|cursor| cursor.to_string()),
+            })
+            .respond_to(request)?,
+            HistoryFormat::Csv => {
+                let csv = history_to_csv(&self.page.items).map_err(|e| {
+                    log::error!("Failed to export action history as CSV: {:#}", e);
+                    Status::InternalServerError
+                })?;
+                (ContentType::CSV, csv).respond_to(request)?
+            }
+        };
+        if let Some(cursor) = self.page.next_cursor {
+            response.set_raw_header("X-Next-Cursor", cursor.to_string());
+        }
+        response.set_raw_header("Vary", "Accept");
+        Ok(response)
+    }
+}
+
+impl OpenApiResponderInner for HistoryResponse {
+    fn responses(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        let mut responses = Json::<HistoryPageBody>::responses(gen)?;
+        if let Some(RefOr::Object(ok)) = responses.responses.get_mut("200") {
+            ok.content.insert(
+                "text/csv".to_owned(),
+                MediaType {
+                    example: Some(Value::String(
+                        "timestamp,source_node_id,concentration_ppm,peak_amplitude,peak_frequency\n\
+                         2025-01-01T00:00:00.000Z,concentration_calculator,456.78,0.85,2000.5\n"
+                            .to_owned(),
+                    )),
+                    ..Default::default()
+                },
+            );
+        }
+        Ok(responses)
+    }
+}
+
+/// History of one node in a combined history response
+///
+/// Exactly one of `history` and `error` is present.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct NodeHistoryResult {
+    /// Measurement history of the node, newest first
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub history: Option<Vec<MeasurementData>>,
+    /// Why the history of the node could not be retrieved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response of the history clearing endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ClearHistoryResponse {
+    /// ID of the cleared action node
+    pub node_id: String,
+    /// Number of history entries removed
+    pub removed_entries: usize,
 }
 
 /// Response structure for action node list
@@ -85,39 +459,86 @@ pub struct ActionNodeInfo {
 ///
 /// ### Query Parameters
 /// - `limit`: Maximum number of entries to return (optional)
+/// - `since`: Only entries recorded at or after this Unix time in seconds (optional)
+/// - `until`: Only entries recorded at or before this Unix time in seconds (optional)
+/// - `before`: Only entries older than this cursor (optional)
+/// - `after`: Only entries newer than this cursor (optional)
+/// - `format`: `json` (default) or `csv`, takes precedence over the `Accept` header
+///
+/// ### Formats
+/// JSON is returned by default. CSV is returned with `format=csv` or when the
+/// preferred type of the `Accept` header is `text/csv`, see [`history_to_csv`]
+/// for its columns.
+///
+/// ### Pagination
+/// When `limit` truncates the result, `next_cursor` holds the cursor of the
+/// next page: pass it as `before` to get older entries, or as `after` when
+/// paging forward from an `after` cursor. It is absent on the last page. CSV
+/// documents get it in the `X-Next-Cursor` header, also set for JSON.
 ///
 /// ### Returns
-/// - `200 OK`: Array of measurement data
+/// - `200 OK`: Page of measurement data
+/// - `400 Bad Request`: `since` is after `until`, invalid cursor or unknown `format`
 /// - `404 Not Found`: Action node with the specified ID not found
 /// - `500 Internal Server Error`: Failed to access processing graph
 ///
 /// ### Example Response
 /// ```json
-/// [
-///   {
-///     "concentration_ppm": 456.78,
-///     "source_node_id": "concentration_calculator",
-///     "peak_amplitude": 0.85,
-///     "peak_frequency": 2000.5,
-///     "timestamp": 1640995200,
-///     "metadata": {
-///       "trigger_type": "concentration_threshold",
-///       "alert_message": "High concentration detected"
+/// {
+///   "items": [
+///     {
+///       "concentration_ppm": 456.78,
+///       "source_node_id": "concentration_calculator",
+///       "peak_amplitude": 0.85,
+///       "peak_frequency": 2000.5,
+///       "timestamp": 1640995200,
+///       "metadata": {
+///         "trigger_type": "concentration_threshold",
+///         "alert_message": "High concentration detected"
+///       }
 ///     }
-///   }
-/// ]
+///   ],
+///   "next_cursor": "1640995200000000000-0"
+/// }
 /// ```
 #[openapi_protect_get(
-    "/api/action/<node_id>/history?<limit>",
+    "/api/action/<node_id>/history?<limit>&<since>&<until>&<before>&<after>&<format>",
     "read:api",
     tag = "Action History"
 )]
 pub async fn get_action_history(
     node_id: &str,
     limit: Option<usize>,
+    since: Option<u64>,
+    until: Option<u64>,
+    before: Option<&str>,
+    after: Option<&str>,
+    format: Option<&str>,
+    accepted_format: HistoryFormat,
     state: &State<SharedVisualizationState>,
-) -> Result<Json<Vec<MeasurementData>>, Status> {
-    let result = if let Some(live_graph) = state.get_live_processing_graph().await {
+) -> Result<HistoryResponse, Status> {
+    let before = before.map(str::parse::<HistoryCursor>).transpose();
+    let after = after.map(str::parse::<HistoryCursor>).transpose();
+    let format = match format {
+        Some(format) => HistoryFormat::from_param(format),
+        None => Some(accepted_format),
+    };
+
+    let invalid_range = matches!((since, until), (Some(since), Some(until)) if since > until);
+    let (Ok(before), Ok(after)) = (before, after) else {
+        return Err(Status::BadRequest);
+    };
+    let query = HistoryQuery {
+        limit,
+        since,
+        until,
+        before,
+        after,
+    };
+
+    let history = if invalid_range || format.is_none() {
+        Err(Status::BadRequest)
+    } else if let Some(live_graph) = state.get_live_processing_graph().await {
         // Try to access the live processing graph
         if let Ok(graph_lock) =
             tokio::time::timeout(std::time::Duration::from_millis(100), live_graph.read()).await
@@ -125,8 +546,7 @@ pub async fn get_action_history(
             // Get the specific UniversalActionNode
             if let Some(action_node) = graph_lock.get_universal_action_node(node_id) {
                 // Get measurement history from the action node
-                let history = action_node.get_measurement_history(limit);
-                Ok(Json(history))
+                Ok(action_node.get_measurement_history(None))
             } else {
                 // Node not found
                 Err(Status::NotFound)
@@ -145,7 +565,165 @@ pub async fn get_action_history(
             timestamp: std::time::SystemTime::now(),
             metadata: HashMap::new(),
         }];
-        Ok(Json(mock_data))
+        Ok(mock_data)
+    };
+
+    history.map(|history| HistoryResponse {
+        page: paginate_history(history, &query),
+        format: format.unwrap_or_default(),
+    })
+}
+
+/// Get the history of several action nodes in one request
+///
+/// Returns a map from node ID to the history of that node, so that measurements
+/// of several action nodes can be correlated without one request per node.
+/// Unknown node IDs get an `error` entry instead of failing the whole request.
+///
+/// ### Query Parameters
+/// - `nodes`: Comma-separated list of action node IDs
+/// - `limit`: Maximum number of entries to return per node (optional)
+///
+/// ### Returns
+/// - `200 OK`: Map from node ID to its history or error
+/// - `500 Internal Server Error`: Failed to access processing graph
+///
+/// ### Example Response
+/// ```json
+/// {
+///   "redis_stream_action": {
+///     "history": [
+///       {
+///         "concentration_ppm": 456.78,
+///         "source_node_id": "concentration_calculator",
+///         "peak_amplitude": 0.85,
+///         "peak_frequency": 2000.5,
+///         "timestamp": 1640995200,
+///         "metadata": {}
+///       }
+///     ]
+///   },
+///   "unknown_node": {
+///     "error": "Action node 'unknown_node' not found"
+///   }
+/// }
+/// ```
+#[openapi_protect_get(
+    "/api/action/history?<nodes>&<limit>",
+    "read:api",
+    tag = "Action History"
+)]
+pub async fn get_combined_action_history(
+    nodes: &str,
+    limit: Option<usize>,
+    state: &State<SharedVisualizationState>,
+) -> Result<Json<BTreeMap<String, NodeHistoryResult>>, Status> {
+    let node_ids: BTreeSet<&str> = nodes
+        .split(',')
+        .map(str::trim)
+        .filter(|node_id| !node_id.is_empty())
+        .collect();
+
+    let result = if let Some(live_graph) = state.get_live_processing_graph().await {
+        // Try to access the live processing graph
+        if let Ok(graph_lock) =
+            tokio::time::timeout(std::time::Duration::from_millis(100), live_graph.read()).await
+        {
+            let histories = node_ids
+                .into_iter()
+                .map(|node_id| {
+                    let result = match graph_lock.get_universal_action_node(node_id) {
+                        Some(action_node) => NodeHistoryResult {
+                            history: Some(action_node.get_measurement_history(limit)),
+                            error: None,
+                        },
+                        None => NodeHistoryResult {
+                            history: None,
+                            error: Some(format!("Action node '{}' not found", node_id)),
+                        },
+                    };
+                    (node_id.to_string(), result)
+                })
+                .collect();
+            Ok(Json(histories))
+        } else {
+            // Timeout occurred
+            Err(Status::InternalServerError)
+        }
+    } else {
+        // Fallback to mock data if live graph is not available
+        let histories = node_ids
+            .into_iter()
+            .map(|node_id| {
+                let mock_data = vec![MeasurementData {
+                    concentration_ppm: 123.45,
+                    source_node_id: "concentration_calculator".to_string(),
+                    peak_amplitude: 0.75,
+                    peak_frequency: 2000.0,
+                    timestamp: std::time::SystemTime::now(),
+                    metadata: HashMap::new(),
+                }];
+                (
+                    node_id.to_string(),
+                    NodeHistoryResult {
+                        history: Some(mock_data),
+                        error: None,
+                    },
+                )
+            })
+            .collect();
+        Ok(Json(histories))
+    };
+
+    result
+}
+
+/// Clear the history buffer of an action node
+///
+/// Empties the history buffer of the action node and resets its processing
+/// and action counters, without restarting the daemon. The node keeps its
+/// configuration and driver, and records new measurements right away.
+///
+/// ### Path Parameters
+/// - `node_id`: The ID of the action node to clear
+///
+/// ### Returns
+/// - `200 OK`: Number of entries removed
+/// - `404 Not Found`: Action node with the specified ID not found
+/// - `500 Internal Server Error`: Failed to access processing graph
+///
+/// ### Example Response
+/// ```json
+/// {
+///   "node_id": "redis_stream_action",
+///   "removed_entries": 85
+/// }
+/// ```
+#[openapi_protect_delete("/api/action/<node_id>/history", "write:api", tag = "Action History")]
+pub async fn clear_action_history(
+    node_id: &str,
+    state: &State<SharedVisualizationState>,
+) -> Result<Json<ClearHistoryResponse>, Status> {
+    let result = if let Some(live_graph) = state.get_live_processing_graph().await {
+        // Try to access the live processing graph for writing
+        if let Ok(mut graph_lock) =
+            tokio::time::timeout(std::time::Duration::from_millis(100), live_graph.write()).await
+        {
+            match graph_lock.clear_universal_action_node_history(node_id) {
+                Some(removed_entries) => Ok(Json(ClearHistoryResponse {
+                    node_id: node_id.to_string(),
+                    removed_entries,
+                })),
+                // Node not found
+                None => Err(Status::NotFound),
+            }
+        } else {
+            // Timeout occurred
+            Err(Status::InternalServerError)
+        }
+    } else {
+        // Without live graph there is no action node to clear
+        Err(Status::NotFound)
     };
 
     result
@@ -327,6 +905,8 @@ pub async fn list_action_nodes(
 pub fn get_action_routes() -> (Vec<rocket::Route>, OpenApi) {
     openapi_get_routes_spec![
         get_action_history,
+        get_combined_action_history,
+        clear_action_history,
         get_action_history_stats,
         list_action_nodes
     ]
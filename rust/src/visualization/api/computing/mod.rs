@@ -3,16 +3,25 @@
 // SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
 
 //! routes for computing nodes
-use crate::processing::computing_nodes::{PeakResult, SharedComputingState};
-use auth_macros::openapi_protect_get;
+use crate::processing::computing_nodes::{
+    ConcentrationResult, PeakResult, SharedComputingState, TimeDelayResult,
+};
+use crate::visualization::api::get::config::ConfigState;
+use auth_macros::{openapi_protect_get, protect_get};
+use rocket::futures::stream::Stream;
+use rocket::response::stream::{Event, EventStream};
 use rocket::serde::json::Json;
 use rocket::serde::{Deserialize, Serialize};
 use rocket::{get, response::status, State};
 use rocket_okapi::okapi::openapi3::OpenApi;
-use rocket_okapi::openapi_get_routes_spec;
 use rocket_okapi::JsonSchema;
+use rocket_okapi::{openapi, openapi_get_routes_spec};
 use std::collections::HashMap;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
+use tokio::time::MissedTickBehavior;
+
+/// Interval without measurement updates after which a heartbeat comment is sent
+const MEASUREMENT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
 
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 pub struct PeakResultResponse {
@@ -22,11 +31,61 @@ pub struct PeakResultResponse {
     pub timestamp: SystemTime,
 }
 
+/// Time delay between the channels estimated by a cross-correlation node
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct TimeDelayResultResponse {
+    /// Lag of channel B behind channel A in samples
+    pub lag_samples: f64,
+    /// Lag of channel B behind channel A in seconds
+    pub lag_seconds: f64,
+    /// Normalized cross-correlation at the peak
+    pub peak_correlation: f32,
+    pub timestamp: SystemTime,
+}
+
+impl From<&TimeDelayResult> for TimeDelayResultResponse {
+    fn from(result: &TimeDelayResult) -> Self {
+        Self {
+            lag_samples: result.lag_samples,
+            lag_seconds: result.lag_seconds,
+            peak_correlation: result.peak_correlation,
+            timestamp: result.timestamp,
+        }
+    }
+}
+
+/// Concentration computed by a concentration or Kalman concentration node
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct ConcentrationResultResponse {
+    pub concentration_ppm: f64,
+    /// Variance of the concentration in ppm², set by filtering nodes
+    pub concentration_variance: Option<f64>,
+    pub timestamp: SystemTime,
+}
+
+impl From<&ConcentrationResult> for ConcentrationResultResponse {
+    fn from(result: &ConcentrationResult) -> Self {
+        Self {
+            concentration_ppm: result.concentration_ppm,
+            concentration_variance: result.concentration_variance,
+            timestamp: result.timestamp,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 pub struct ComputingResponse {
     /// Peak results from multiple nodes, keyed by node ID
     pub peak_results: HashMap<String, PeakResultResponse>,
 
+    /// Time delays between the channels from cross-correlation nodes, keyed by node ID
+    #[serde(default)]
+    pub time_delay_results: HashMap<String, TimeDelayResultResponse>,
+
+    /// Concentrations from concentration nodes, keyed by node ID
+    #[serde(default)]
+    pub concentration_results: HashMap<String, ConcentrationResultResponse>,
+
     /// Legacy fields for backward compatibility
     pub peak_frequency: Option<f32>,
     pub peak_amplitude: Option<f32>,
@@ -83,8 +142,22 @@ pub async fn computing_api(
         .cloned()
         .collect();
 
+    let time_delay_results = shared_data
+        .time_delay_results
+        .iter()
+        .map(|(node_id, result)| (node_id.clone(), result.into()))
+        .collect();
+
+    let concentration_results = shared_data
+        .concentration_results
+        .iter()
+        .map(|(node_id, result)| (node_id.clone(), result.into()))
+        .collect();
+
     let response = ComputingResponse {
         peak_results,
+        time_delay_results,
+        concentration_results,
         // Legacy fields for backward compatibility
         peak_frequency: shared_data.peak_frequency,
         peak_amplitude: shared_data.peak_amplitude,
@@ -97,7 +170,151 @@ pub async fn computing_api(
     Json(response)
 }
 
+/// Peak detection update sent as a `peak` event on the measurement stream
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct PeakMeasurementEvent {
+    /// ID of the peak finder node that produced the result
+    pub node_id: String,
+    pub frequency: f32,
+    pub amplitude: f32,
+    pub concentration_ppm: Option<f32>,
+    pub coherence_score: f32,
+    pub timestamp: SystemTime,
+}
+
+impl PeakMeasurementEvent {
+    fn new(node_id: &str, result: &PeakResult) -> Self {
+        Self {
+            node_id: node_id.to_string(),
+            frequency: result.frequency,
+            amplitude: result.amplitude,
+            concentration_ppm: result.concentration_ppm,
+            coherence_score: result.coherence_score,
+            timestamp: result.timestamp,
+        }
+    }
+}
+
+/// Concentration update sent as a `concentration` event on the measurement stream
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct ConcentrationMeasurementEvent {
+    /// ID of the concentration node that produced the result
+    pub node_id: String,
+    pub concentration_ppm: f64,
+    /// Variance of the concentration in ppm², set by filtering nodes
+    #[serde(default)]
+    pub concentration_variance: Option<f64>,
+    pub source_peak_finder_id: String,
+    pub spectral_line_id: Option<String>,
+    pub source_amplitude: f32,
+    pub source_frequency: f32,
+    pub temperature_compensated: bool,
+    pub timestamp: SystemTime,
+}
+
+impl ConcentrationMeasurementEvent {
+    fn new(node_id: &str, result: &ConcentrationResult) -> Self {
+        Self {
+            node_id: node_id.to_string(),
+            concentration_ppm: result.concentration_ppm,
+            concentration_variance: result.concentration_variance,
+            source_peak_finder_id: result.source_peak_finder_id.clone(),
+            spectral_line_id: result.spectral_line_id.clone(),
+            source_amplitude: result.source_amplitude,
+            source_frequency: result.source_frequency,
+            temperature_compensated: result.temperature_compensated,
+            timestamp: result.timestamp,
+        }
+    }
+}
+
+/// Stream peak and concentration updates as Server-Sent Events
+///
+/// The shared computing state is checked every
+/// `visualization.measurement_stream_interval_ms`; every result whose timestamp
+/// changed since the previous check is sent, so faster updates are coalesced
+/// into the latest value. The current results are sent on connection.
+///
+/// ### Authentication
+/// Requires a valid JWT token with `read:api` permission.
+///
+/// ### Response Format
+/// Peak results are sent as `peak` events and concentration results as
+/// `concentration` events:
+/// ```text
+/// event: peak
+/// data: {"node_id": "peak_finder", "frequency": 2000.0, "amplitude": 0.5, ...}
+///
+/// event: concentration
+/// data: {"node_id": "concentration", "concentration_ppm": 412.5, ...}
+/// ```
+/// A `heartbeat` comment is sent when no update happened for 5 seconds.
+#[openapi(tag = "Computing")]
+#[protect_get("/api/stream/measurements", "read:api")]
+pub async fn stream_measurements(
+    computing_state: &State<SharedComputingState>,
+    config: &ConfigState,
+) -> EventStream<impl Stream<Item = Event>> {
+    let computing_state = computing_state.inner().clone();
+    let interval = Duration::from_millis(
+        config
+            .read()
+            .await
+            .visualization
+            .measurement_stream_interval_ms
+            .max(1),
+    );
+
+    EventStream! {
+        let mut sent_peaks: HashMap<String, SystemTime> = HashMap::new();
+        let mut sent_concentrations: HashMap<String, SystemTime> = HashMap::new();
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut idle = Duration::ZERO;
+
+        loop {
+            ticker.tick().await;
+
+            let mut events = Vec::new();
+            {
+                let shared_data = computing_state.read().await;
+                for (node_id, result) in &shared_data.peak_results {
+                    if sent_peaks.get(node_id) != Some(&result.timestamp) {
+                        sent_peaks.insert(node_id.clone(), result.timestamp);
+                        events.push(
+                            Event::json(&PeakMeasurementEvent::new(node_id, result)).event("peak"),
+                        );
+                    }
+                }
+                for (node_id, result) in &shared_data.concentration_results {
+                    if sent_concentrations.get(node_id) != Some(&result.timestamp) {
+                        sent_concentrations.insert(node_id.clone(), result.timestamp);
+                        events.push(
+                            Event::json(&ConcentrationMeasurementEvent::new(node_id, result))
+                                .event("concentration"),
+                        );
+                    }
+                }
+            }
+
+            if events.is_empty() {
+                idle += interval;
+                if idle >= MEASUREMENT_HEARTBEAT_INTERVAL {
+                    idle = Duration::ZERO;
+                    yield Event::comment("heartbeat");
+                }
+                continue;
+            }
+
+            idle = Duration::ZERO;
+            for event in events {
+                yield event;
+            }
+        }
+    }
+}
+
 /// Centralized function to get all computing routes with OpenAPI documentation
 pub fn get_computing_routes() -> (Vec<rocket::Route>, OpenApi) {
-    openapi_get_routes_spec![computing_api]
+    openapi_get_routes_spec![computing_api, stream_measurements]
 }
@@ -2,17 +2,21 @@
 // This file is part of the rust-photoacoustic project and is licensed under the
 // SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
 
+use crate::config::reload::{diff_configs, load_config_file, parse_config, swap_config};
 use crate::config::visualization::VisualizationOutputItem;
-use crate::config::Config;
-use rocket::get;
+use crate::config::{Config, ConfigFilePath, ConfigPreview, ConfigReloadReport};
+use crate::visualization::auth::OxideState;
+use log::{info, warn};
+use rocket::data::{ByteUnit, Data};
+use rocket::http::Status;
 use rocket::serde::json::Json;
-use rocket::State;
+use rocket::{get, post, response::status, State};
 use rocket_okapi::okapi::openapi3::OpenApi;
 use rocket_okapi::openapi_get_routes_spec;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use auth_macros::openapi_protect_get;
+use auth_macros::{openapi_protect_get, openapi_protect_post};
 
 pub type ConfigState = State<Arc<RwLock<Config>>>;
 
@@ -150,7 +154,164 @@ pub async fn get_visualization_output(config: &ConfigState) -> Json<Vec<Visualiz
     Json(config.visualization.output.clone())
 }
 
+/// Reload the configuration file
+///
+/// **Endpoint:** `POST /api/config/reload`
+///
+/// Re-reads the configuration file the daemon was started with and validates it
+/// against the JSON schema and the additional configuration rules. When the file
+/// is valid, the shared configuration is replaced atomically and the changed
+/// sections are reported:
+///
+/// - `hot_applied`: sections already in effect (`access`, and `processing` whose
+///   node parameters such as filter settings and thresholds are picked up by the
+///   processing consumer)
+/// - `pending_restart`: sections that take effect on the next restart
+///
+/// ### Authentication
+///
+/// Requires a valid JWT bearer token with the `admin:api` permission.
+///
+/// ### Response Structure
+///
+/// ```json
+/// {
+///   "applied": true,
+///   "hot_applied": ["processing"],
+///   "pending_restart": ["modbus"],
+///   "errors": []
+/// }
+/// ```
+///
+/// ### Error Responses
+///
+/// - `401 Unauthorized`: Missing or invalid JWT token
+/// - `403 Forbidden`: Token lacks required `admin:api` permission
+/// - `404 Not Found`: The server was not started from a configuration file
+/// - `422 Unprocessable Entity`: The file is invalid; `errors` lists the
+///   validation errors and the running configuration is left unchanged
+#[openapi_protect_post("/api/config/reload", "admin:api", tag = "Configuration")]
+pub async fn reload_config(
+    config: &ConfigState,
+    config_path: Option<&State<ConfigFilePath>>,
+    oxide_state: &State<OxideState>,
+) -> Result<Json<ConfigReloadReport>, status::Custom<Json<ConfigReloadReport>>> {
+    match config_path.map(|path| (path, load_config_file(&path.0))) {
+        None => Err(status::Custom(
+            Status::NotFound,
+            Json(ConfigReloadReport::rejected(vec![
+                "The server was not started from a configuration file".to_string(),
+            ])),
+        )),
+        Some((config_path, Ok(new_config))) => {
+            let report = swap_config(config.inner(), new_config).await;
+            if report.hot_applied.iter().any(|section| section == "access") {
+                let access = config.inner().read().await.access.clone();
+                oxide_state.update_access_config(access).await;
+            }
+            info!(
+                "Configuration reloaded from {}: hot-applied {:?}, pending restart {:?}",
+                config_path.0.display(),
+                report.hot_applied,
+                report.pending_restart
+            );
+            Ok(Json(report))
+        }
+        Some((config_path, Err(errors))) => {
+            warn!(
+                "Configuration reload from {} rejected: {:?}",
+                config_path.0.display(),
+                errors
+            );
+            Err(status::Custom(
+                Status::UnprocessableEntity,
+                Json(ConfigReloadReport::rejected(errors)),
+            ))
+        }
+    }
+}
+
+/// Maximum size of a configuration submitted to [`preview_config`]
+const CONFIG_PREVIEW_LIMIT: ByteUnit = ByteUnit::Mebibyte(2);
+
+/// Preview the changes of a candidate configuration
+///
+/// **Endpoint:** `POST /api/config/preview`
+///
+/// Validates the complete configuration sent in the request body, as JSON or
+/// YAML, with the same checks as a reload, and compares it field by field with
+/// the running configuration. Nothing is applied.
+///
+/// Each changed field is reported with its value in the running and in the
+/// candidate configuration, and whether it requires a restart or is
+/// hot-applied (`access` and `processing` sections).
+///
+/// ### Authentication
+///
+/// Requires a valid JWT bearer token with the `admin:api` permission.
+///
+/// ### Response Structure
+///
+/// ```json
+/// {
+///   "valid": true,
+///   "changes": [
+///     {
+///       "path": "modbus.port",
+///       "current": 502,
+///       "candidate": 503,
+///       "requires_restart": true
+///     }
+///   ],
+///   "requires_restart": true,
+///   "errors": []
+/// }
+/// ```
+///
+/// ### Error Responses
+///
+/// - `401 Unauthorized`: Missing or invalid JWT token
+/// - `403 Forbidden`: Token lacks required `admin:api` permission
+/// - `422 Unprocessable Entity`: The candidate is invalid or larger than 2 MiB;
+///   `errors` lists the validation errors
+#[openapi_protect_post(
+    "/api/config/preview",
+    "admin:api",
+    tag = "Configuration",
+    data = "<candidate>"
+)]
+pub async fn preview_config(
+    config: &ConfigState,
+    candidate: Data<'_>,
+) -> Result<Json<ConfigPreview>, status::Custom<Json<ConfigPreview>>> {
+    let candidate = match candidate.open(CONFIG_PREVIEW_LIMIT).into_string().await {
+        Ok(candidate) if candidate.is_complete() => parse_config(&candidate),
+        Ok(_) => Err(vec![format!(
+            "Configuration exceeds the {} limit",
+            CONFIG_PREVIEW_LIMIT
+        )]),
+        Err(e) => Err(vec![format!("Failed to read the configuration: {}", e)]),
+    };
+
+    match candidate {
+        Ok(candidate) => {
+            let current = config.inner().read().await;
+            Ok(Json(ConfigPreview::new(diff_configs(&current, &candidate))))
+        }
+        Err(errors) => Err(status::Custom(
+            Status::UnprocessableEntity,
+            Json(ConfigPreview::rejected(errors)),
+        )),
+    }
+}
+
 /// Centralized function to get all config routes with OpenAPI documentation
 pub fn get_config_routes() -> (Vec<rocket::Route>, OpenApi) {
-    openapi_get_routes_spec![get_config, get_config_schema, get_visualization_output]
+    openapi_get_routes_spec![
+        get_config,
+        get_config_schema,
+        get_visualization_output,
+        reload_config,
+        preview_config
+    ]
 }
@@ -4,19 +4,31 @@
 
 //! Thermal data retrieval API for photoacoustic applications
 //! This module provides an API for retrieving thermal data from the SharedThermalRegulationState
+//! and for changing the setpoint of a running regulator. Live updates are
+//! pushed by the `/api/stream/thermal` Server-Sent Events stream.
 
 use crate::thermal_regulation::shared_state::{
-    RegulatorStatus, SharedThermalRegulationState, SharedThermalState, ThermalDataPoint,
-    ThermalRegulatorHistory,
+    RegulatorStatus, SetpointLimits, SharedThermalRegulationState, SharedThermalState,
+    ThermalDataPoint, ThermalRegulatorHistory,
 };
-use auth_macros::openapi_protect_get;
-use rocket::get;
+use crate::visualization::api::get::config::ConfigState;
+use auth_macros::{openapi_protect_get, openapi_protect_put, protect_get};
+use rocket::futures::stream::Stream;
+use rocket::http::Status;
 use rocket::response::status;
+use rocket::response::stream::{Event, EventStream};
+use rocket::serde::json::Json;
 use rocket::serde::{Deserialize, Serialize};
+use rocket::{get, put};
 use rocket_okapi::okapi::openapi3::OpenApi;
-use rocket_okapi::openapi_get_routes_spec;
+use rocket_okapi::{openapi, openapi_get_routes_spec};
 use schemars::JsonSchema;
 use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+
+/// Interval without thermal updates after which a heartbeat comment is sent
+const THERMAL_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
 
 /// Current temperature information for a thermal regulator
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -74,6 +86,95 @@ pub struct FilterSummary {
     pub to_timestamp: Option<u64>,
 }
 
+/// Current state of a thermal regulator
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RegulatorState {
+    /// Regulator identifier
+    pub id: String,
+    /// Human-readable name of the regulator
+    pub name: String,
+    /// Whether the regulator is enabled in the configuration
+    pub enabled: bool,
+    /// Current status of the regulator
+    pub status: String,
+    /// Last measured temperature in degrees Celsius, `None` before the first cycle
+    pub temperature_celsius: Option<f64>,
+    /// Current setpoint in degrees Celsius
+    pub setpoint_celsius: f64,
+    /// Last control output in percent, `None` before the first cycle
+    pub control_output_percent: Option<f64>,
+    /// Error message while the regulator is in error, safety shutdowns included,
+    /// `None` once it is stopped
+    pub fault: Option<String>,
+    /// Allowed range for the setpoint
+    pub setpoint_limits: Option<SetpointLimits>,
+    /// Last update timestamp (Unix seconds)
+    pub last_update: u64,
+}
+
+impl From<&ThermalRegulatorHistory> for RegulatorState {
+    fn from(regulator: &ThermalRegulatorHistory) -> Self {
+        let latest = regulator.history.back();
+        Self {
+            id: regulator.id.clone(),
+            name: regulator.name.clone(),
+            enabled: regulator.enabled,
+            status: regulator_status_to_string(&regulator.status),
+            temperature_celsius: latest.map(|point| point.temperature_celsius),
+            setpoint_celsius: regulator.current_pid_params.setpoint_celsius,
+            control_output_percent: latest.map(|point| point.control_output_percent),
+            fault: match &regulator.status {
+                RegulatorStatus::Error { message } => Some(message.clone()),
+                _ => None,
+            },
+            setpoint_limits: regulator.setpoint_limits,
+            last_update: regulator.last_update,
+        }
+    }
+}
+
+/// Telemetry of a regulator pushed by the thermal stream
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ThermalTelemetryEvent {
+    /// Regulator identifier
+    pub regulator_id: String,
+    /// Number of regulation cycles recorded so far; a gap between two events
+    /// means that cycles were coalesced
+    pub sample: u64,
+    /// Timestamp of the regulation cycle (Unix seconds)
+    pub timestamp: u64,
+    /// Measured temperature in degrees Celsius
+    pub temperature_celsius: f64,
+    /// Setpoint in degrees Celsius
+    pub setpoint_celsius: f64,
+    /// Control output in percent
+    pub control_output_percent: f64,
+    /// Current status of the regulator
+    pub status: String,
+}
+
+impl ThermalTelemetryEvent {
+    /// Build the event for the latest data point of `regulator`, if any
+    fn latest(regulator: &ThermalRegulatorHistory) -> Option<Self> {
+        regulator.history.back().map(|point| Self {
+            regulator_id: regulator.id.clone(),
+            sample: regulator.sample_count,
+            timestamp: point.timestamp,
+            temperature_celsius: point.temperature_celsius,
+            setpoint_celsius: point.setpoint_celsius,
+            control_output_percent: point.control_output_percent,
+            status: regulator_status_to_string(&regulator.status),
+        })
+    }
+}
+
+/// Setpoint change request
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SetpointRequest {
+    /// New setpoint in degrees Celsius
+    pub setpoint_celsius: f64,
+}
+
 /// Get the list of available thermal regulators
 ///
 /// **Endpoint:** `GET /api/thermal/regulators`
@@ -267,6 +368,7 @@ pub async fn get_last_temperatures(
 ///           "proportional": 1.5,
 ///           "integral": 0.3,
 ///           "derivative": -0.1,
+///           "feedforward": 0.0,
 ///           "error": 1.5
 ///         }
 ///       }
@@ -576,12 +678,246 @@ fn build_thermal_response(
     }
 }
 
+/// Get the current state of all thermal regulators
+///
+/// **Endpoint:** `GET /api/thermal/status`
+///
+/// Returns the current temperature, setpoint, control output and fault of
+/// every configured regulator, sorted by identifier. `GET /api/thermal`
+/// returns the historical data, so the current state is served under
+/// `/status`.
+///
+/// ### Authentication
+///
+/// This endpoint requires a valid JWT bearer token in the Authorization header
+/// with read access privileges. The token must have the `read:api` scope.
+///
+/// ### Response Structure
+///
+/// ```json
+/// [
+///   {
+///     "id": "sample_cell",
+///     "name": "Sample cell",
+///     "enabled": true,
+///     "status": "Running",
+///     "temperature_celsius": 29.84,
+///     "setpoint_celsius": 30.0,
+///     "control_output_percent": 12.5,
+///     "fault": null,
+///     "setpoint_limits": { "min_celsius": 0.0, "max_celsius": 80.0 },
+///     "last_update": 1672531200
+///   }
+/// ]
+/// ```
+///
+/// ### Error Responses
+///
+/// - `401 Unauthorized`: Missing or invalid JWT token
+/// - `403 Forbidden`: Token lacks required `read:api` scope
+#[openapi_protect_get("/api/thermal/status", "read:api", tag = "Thermal Regulation")]
+pub async fn get_thermal_status(
+    state: &rocket::State<SharedThermalState>,
+) -> Json<Vec<RegulatorState>> {
+    let thermal_state = state.read().await;
+
+    let mut regulators: Vec<RegulatorState> = thermal_state
+        .regulators
+        .values()
+        .map(RegulatorState::from)
+        .collect();
+    regulators.sort_by(|a, b| a.id.cmp(&b.id));
+
+    Json(regulators)
+}
+
+/// Get the current state of a thermal regulator
+///
+/// **Endpoint:** `GET /api/thermal/<id>`
+///
+/// Returns the same information as one entry of `GET /api/thermal/status`.
+///
+/// ### Parameters
+///
+/// - `id`: Identifier of the regulator
+///
+/// ### Authentication
+///
+/// This endpoint requires a valid JWT bearer token in the Authorization header
+/// with read access privileges. The token must have the `read:api` scope.
+///
+/// ### Error Responses
+///
+/// - `401 Unauthorized`: Missing or invalid JWT token
+/// - `403 Forbidden`: Token lacks required `read:api` scope
+/// - `404 Not Found`: No regulator with this identifier
+#[openapi_protect_get("/api/thermal/<id>", "read:api", tag = "Thermal Regulation")]
+pub async fn get_thermal_regulator(
+    id: &str,
+    state: &rocket::State<SharedThermalState>,
+) -> Result<Json<RegulatorState>, status::NotFound<String>> {
+    let thermal_state = state.read().await;
+
+    thermal_state
+        .get_regulator_history(id)
+        .map(|regulator| Json(RegulatorState::from(regulator)))
+        .ok_or_else(|| status::NotFound(format!("Regulator '{}' not found", id)))
+}
+
+/// Change the setpoint of a thermal regulator
+///
+/// **Endpoint:** `PUT /api/thermal/<id>/setpoint`
+///
+/// The setpoint is validated against the safe range of the regulator, then
+/// applied by the regulation loop at its next cycle.
+///
+/// ### Parameters
+///
+/// - `id`: Identifier of the regulator
+///
+/// ### Request Body
+///
+/// ```json
+/// { "setpoint_celsius": 35.0 }
+/// ```
+///
+/// ### Authentication
+///
+/// This endpoint requires a valid JWT bearer token in the Authorization header
+/// with write access privileges. The token must have the `write:api` scope.
+///
+/// ### Returns
+///
+/// The state of the regulator with the new setpoint
+///
+/// ### Error Responses
+///
+/// - `401 Unauthorized`: Missing or invalid JWT token
+/// - `403 Forbidden`: Token lacks required `write:api` scope
+/// - `404 Not Found`: No regulator with this identifier
+/// - `422 Unprocessable Entity`: The setpoint is not finite or outside the
+///   safe range of the regulator
+#[openapi_protect_put(
+    "/api/thermal/<id>/setpoint",
+    "write:api",
+    tag = "Thermal Regulation",
+    data = "<request>"
+)]
+pub async fn put_thermal_setpoint(
+    id: &str,
+    request: Json<SetpointRequest>,
+    state: &rocket::State<SharedThermalState>,
+) -> Result<Json<RegulatorState>, status::Custom<String>> {
+    let mut thermal_state = state.write().await;
+
+    match thermal_state.get_regulator_history(id) {
+        None => Err(status::Custom(
+            Status::NotFound,
+            format!("Regulator '{}' not found", id),
+        )),
+        Some(_) => match thermal_state.request_setpoint(id, request.setpoint_celsius) {
+            Ok(()) => {
+                log::info!(
+                    "Setpoint of thermal regulator '{}' set to {:.2} °C",
+                    id,
+                    request.setpoint_celsius
+                );
+                Ok(Json(RegulatorState::from(&thermal_state.regulators[id])))
+            }
+            Err(e) => Err(status::Custom(Status::UnprocessableEntity, e.to_string())),
+        },
+    }
+}
+
+/// Stream thermal regulation telemetry as Server-Sent Events
+///
+/// **Endpoint:** `GET /api/stream/thermal`
+///
+/// The shared thermal state is checked every
+/// `visualization.thermal_stream_interval_ms`; the latest data point of every
+/// regulator which ran a regulation cycle since the previous check is sent, so
+/// cycles faster than the interval are coalesced into the latest value. The
+/// current telemetry is sent on connection.
+///
+/// ### Authentication
+/// Requires a valid JWT token with `read:api` permission.
+///
+/// ### Response Format
+/// Each update is sent as a `thermal` event:
+/// ```text
+/// event: thermal
+/// data: {"regulator_id": "sample_cell", "sample": 1250, "timestamp": 1672531200, "temperature_celsius": 29.84, "setpoint_celsius": 30.0, "control_output_percent": 12.5, "status": "Running"}
+/// ```
+/// A `heartbeat` comment is sent when no update happened for 5 seconds.
+#[openapi(tag = "Thermal Regulation")]
+#[protect_get("/api/stream/thermal", "read:api")]
+pub async fn stream_thermal(
+    state: &rocket::State<SharedThermalState>,
+    config: &ConfigState,
+) -> EventStream<impl Stream<Item = Event>> {
+    let thermal_state = state.inner().clone();
+    let interval = Duration::from_millis(
+        config
+            .read()
+            .await
+            .visualization
+            .thermal_stream_interval_ms
+            .max(1),
+    );
+
+    EventStream! {
+        let mut sent_samples: HashMap<String, u64> = HashMap::new();
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut idle = Duration::ZERO;
+
+        loop {
+            ticker.tick().await;
+
+            let mut events = Vec::new();
+            {
+                let thermal_state = thermal_state.read().await;
+                let mut regulators: Vec<&ThermalRegulatorHistory> =
+                    thermal_state.regulators.values().collect();
+                regulators.sort_by(|a, b| a.id.cmp(&b.id));
+                for regulator in regulators {
+                    if sent_samples.get(&regulator.id) == Some(&regulator.sample_count) {
+                        continue;
+                    }
+                    if let Some(event) = ThermalTelemetryEvent::latest(regulator) {
+                        sent_samples.insert(regulator.id.clone(), regulator.sample_count);
+                        events.push(Event::json(&event).event("thermal"));
+                    }
+                }
+            }
+
+            if events.is_empty() {
+                idle += interval;
+                if idle >= THERMAL_HEARTBEAT_INTERVAL {
+                    idle = Duration::ZERO;
+                    yield Event::comment("heartbeat");
+                }
+                continue;
+            }
+
+            idle = Duration::ZERO;
+            for event in events {
+                yield event;
+            }
+        }
+    }
+}
+
 /// Centralized function to get all thermal routes with OpenAPI documentation
 pub fn get_thermal_routes() -> (Vec<rocket::Route>, OpenApi) {
     openapi_get_routes_spec![
         get_thermal_regulators,
         get_thermal_data,
-        get_last_temperatures
+        get_last_temperatures,
+        get_thermal_status,
+        get_thermal_regulator,
+        put_thermal_setpoint,
+        stream_thermal
     ]
 }
 
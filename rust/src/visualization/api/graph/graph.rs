@@ -7,8 +7,10 @@
 //! This module provides a protected endpoint for serving ProcessingGraphStatistics as JSON.
 //! The endpoint uses JWT token protection via the protect_get macro and accesses real-time
 //! statistics from the running ProcessingConsumer via SharedVisualizationState.
+//! The statistics can be reset with `POST /api/processing/stats/reset`.
 
 use log::info;
+use rocket::http::Status;
 use rocket::serde::json::Json;
 use rocket::{get, post, response::status, State};
 use rocket_okapi::okapi::openapi3::OpenApi;
@@ -16,7 +18,7 @@ use rocket_okapi::openapi_get_routes_spec;
 
 use crate::config::processing::NodeConfig;
 use crate::processing::graph::ProcessingGraphStatistics;
-use crate::processing::SerializableProcessingGraph;
+use crate::processing::{PerformanceSummary, SerializableProcessingGraph};
 use crate::visualization::api::ConfigState;
 use crate::visualization::shared_state::SharedVisualizationState;
 use auth_macros::{openapi_protect_get, openapi_protect_post};
@@ -381,6 +383,59 @@ fn are_json_types_compatible(existing: &serde_json::Value, new: &serde_json::Val
     }
 }
 
+/// Reset the processing graph performance statistics
+///
+/// **Endpoint:** `POST /api/processing/stats/reset`
+///
+/// Zeroes the execution counters and times of every node and of the whole
+/// graph, so that the statistics only cover the activity after the reset
+/// (for instance a specific experiment window). Processing is not interrupted.
+///
+/// ### Authentication
+///
+/// This endpoint requires a valid JWT bearer token with the `write:api` permission.
+///
+/// ### Returns
+///
+/// Returns the `PerformanceSummary` taken just before the reset, so that the
+/// accumulated figures are not lost.
+///
+/// ### Error Responses
+///
+/// - `401 Unauthorized`: Missing or invalid JWT token
+/// - `403 Forbidden`: Token lacks the `write:api` permission
+/// - `404 Not Found`: No processing graph is currently running
+/// - `500 Internal Server Error`: The processing graph could not be locked in time
+#[openapi_protect_post("/api/processing/stats/reset", "write:api", tag = "Processing")]
+pub async fn reset_processing_statistics(
+    state: &State<SharedVisualizationState>,
+) -> Result<Json<PerformanceSummary>, Status> {
+    match state.get_live_processing_graph().await {
+        Some(live_graph) => {
+            match tokio::time::timeout(std::time::Duration::from_millis(100), live_graph.write())
+                .await
+            {
+                Ok(mut graph) => {
+                    let summary = graph.get_performance_summary();
+                    graph.reset_statistics();
+                    let statistics = graph.get_statistics().clone();
+                    drop(graph);
+
+                    // Publish the reset statistics right away rather than at the next consumer update
+                    state.update_processing_statistics(statistics).await;
+                    info!(
+                        "Processing statistics reset after {} executions",
+                        summary.total_executions
+                    );
+                    Ok(Json(summary))
+                }
+                Err(_) => Err(Status::InternalServerError),
+            }
+        }
+        None => Err(Status::NotFound),
+    }
+}
+
 /// Get a human-readable name for a JSON value type
 fn get_json_type_name(value: &serde_json::Value) -> &'static str {
     use serde_json::Value::*;
@@ -396,5 +451,10 @@ fn get_json_type_name(value: &serde_json::Value) -> &'static str {
 
 /// Centralized function to get all graph routes with OpenAPI documentation
 pub fn get_graph_routes() -> (Vec<rocket::Route>, OpenApi) {
-    openapi_get_routes_spec![get_graph_statistics, get_graph, post_node_config]
+    openapi_get_routes_spec![
+        get_graph_statistics,
+        get_graph,
+        post_node_config,
+        reset_processing_statistics
+    ]
 }
@@ -0,0 +1,336 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Liveness and readiness probes for container orchestration
+//!
+//! Two unauthenticated endpoints meant for Kubernetes-style probes:
+//!
+//! - `GET /healthz` (liveness) answers `200 OK` as long as the web server runs.
+//! - `GET /readyz` (readiness) answers `200 OK` when every subsystem is ready and
+//!   `503 Service Unavailable` otherwise. The JSON body lists each subsystem:
+//!
+//! | Subsystem | Ready when |
+//! |---|---|
+//! | `acquisition` | A frame was published on the audio stream within [`ACQUISITION_STALE_AFTER`] |
+//! | `processing` | The live processing graph is registered and passes validation |
+//! | `action_drivers` | Every action node with a driver has it initialized and reporting ready through [`ActionDriver::is_ready`](crate::processing::computing_nodes::action_drivers::ActionDriver::is_ready) within [`DRIVER_READINESS_TIMEOUT`] |
+//! | `tasks` | No supervised daemon task is restarting or failed |
+//!
+//! Subsystems disabled in the configuration are reported as `disabled` and do
+//! not prevent readiness.
+
+use crate::acquisition::AudioFrame;
+use crate::config::Config;
+use crate::daemon::supervisor::{TaskState, TaskSupervisor};
+use crate::visualization::shared_state::SharedVisualizationState;
+use crate::visualization::streaming::AudioStreamState;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::{get, routes, State};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+
+/// Age of the latest audio frame above which acquisition is considered stalled
+pub const ACQUISITION_STALE_AFTER: Duration = Duration::from_secs(5);
+
+/// Maximum time spent waiting for the processing graph lock
+const GRAPH_LOCK_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Maximum time an action driver may take to report its readiness
+pub const DRIVER_READINESS_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// State of a single subsystem in a readiness report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProbeStatus {
+    /// The subsystem is working
+    Ok,
+    /// The subsystem is not working yet or anymore
+    Unavailable,
+    /// The subsystem is disabled in the configuration
+    Disabled,
+}
+
+/// Status of one subsystem with an optional human readable detail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubsystemStatus {
+    pub status: ProbeStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl SubsystemStatus {
+    fn ok() -> Self {
+        Self {
+            status: ProbeStatus::Ok,
+            detail: None,
+        }
+    }
+
+    fn disabled() -> Self {
+        Self {
+            status: ProbeStatus::Disabled,
+            detail: None,
+        }
+    }
+
+    fn unavailable(detail: impl Into<String>) -> Self {
+        Self {
+            status: ProbeStatus::Unavailable,
+            detail: Some(detail.into()),
+        }
+    }
+
+    /// Whether this subsystem allows the service to be ready
+    pub fn is_ready(&self) -> bool {
+        self.status != ProbeStatus::Unavailable
+    }
+}
+
+/// Status of every subsystem checked by the readiness probe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Subsystems {
+    pub acquisition: SubsystemStatus,
+    pub processing: SubsystemStatus,
+    pub action_drivers: SubsystemStatus,
+    pub tasks: SubsystemStatus,
+}
+
+impl Subsystems {
+    /// Whether no subsystem is unavailable
+    pub fn is_ready(&self) -> bool {
+        self.acquisition.is_ready()
+            && self.processing.is_ready()
+            && self.action_drivers.is_ready()
+            && self.tasks.is_ready()
+    }
+}
+
+/// Body of the `/healthz` and `/readyz` responses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProbeReport {
+    /// `ok` when the probe succeeds, `unavailable` otherwise
+    pub status: ProbeStatus,
+    /// Per-subsystem details, absent from the liveness probe
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub subsystems: Option<Subsystems>,
+}
+
+/// Liveness probe
+///
+/// **Endpoint:** `GET /healthz`
+///
+/// Always answers `200 OK` with `{"status": "ok"}` while the server is running.
+#[get("/healthz")]
+pub fn healthz() -> Json<ProbeReport> {
+    Json(ProbeReport {
+        status: ProbeStatus::Ok,
+        subsystems: None,
+    })
+}
+
+/// Readiness probe
+///
+/// **Endpoint:** `GET /readyz`
+///
+/// ### Returns
+///
+/// - `200 OK` when acquisition, processing, action drivers and daemon tasks are ready
+/// - `503 Service Unavailable` when at least one of them is not
+///
+/// ### Example Response
+///
+/// ```json
+/// {
+///   "status": "unavailable",
+///   "subsystems": {
+///     "acquisition": {"status": "ok"},
+///     "processing": {"status": "unavailable", "detail": "Invalid processing graph: ..."},
+///     "action_drivers": {"status": "ok"},
+///     "tasks": {"status": "ok"}
+///   }
+/// }
+/// ```
+#[get("/readyz")]
+pub async fn readyz(
+    config: &State<Arc<RwLock<Config>>>,
+    audio_state: Option<&State<AudioStreamState>>,
+    visualization_state: Option<&State<SharedVisualizationState>>,
+    supervisor: Option<&State<TaskSupervisor>>,
+) -> (Status, Json<ProbeReport>) {
+    let (acquisition_enabled, processing_enabled) = {
+        let config = config.read().await;
+        (config.acquisition.enabled, config.processing.enabled)
+    };
+
+    let acquisition = if acquisition_enabled {
+        acquisition_status(audio_state).await
+    } else {
+        SubsystemStatus::disabled()
+    };
+    let (processing, action_drivers) = if processing_enabled {
+        processing_status(visualization_state).await
+    } else {
+        (SubsystemStatus::disabled(), SubsystemStatus::disabled())
+    };
+
+    let subsystems = Subsystems {
+        acquisition,
+        processing,
+        action_drivers,
+        tasks: tasks_status(supervisor),
+    };
+    let ready = subsystems.is_ready();
+
+    let report = ProbeReport {
+        status: if ready {
+            ProbeStatus::Ok
+        } else {
+            ProbeStatus::Unavailable
+        },
+        subsystems: Some(subsystems),
+    };
+    let status = if ready {
+        Status::Ok
+    } else {
+        Status::ServiceUnavailable
+    };
+    (status, Json(report))
+}
+
+/// Check that audio frames are flowing on the realtime stream
+async fn acquisition_status(audio_state: Option<&State<AudioStreamState>>) -> SubsystemStatus {
+    let Some(audio_state) = audio_state else {
+        return SubsystemStatus::unavailable("Audio stream not registered");
+    };
+    match audio_state.stream.get_latest_frame().await {
+        Some(frame) if frame_age(&frame) <= ACQUISITION_STALE_AFTER => SubsystemStatus::ok(),
+        Some(frame) => SubsystemStatus::unavailable(format!(
+            "No audio frame for {} ms",
+            frame_age(&frame).as_millis()
+        )),
+        None => SubsystemStatus::unavailable("No audio frame received yet"),
+    }
+}
+
+/// Time elapsed since `frame` was captured
+fn frame_age(frame: &AudioFrame) -> Duration {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    Duration::from_millis(now.saturating_sub(frame.timestamp))
+}
+
+/// Check the live processing graph and the drivers of its action nodes
+///
+/// ### Returns
+///
+/// The `(processing, action_drivers)` statuses. Drivers cannot be checked
+/// without a graph and are then reported unavailable as well.
+async fn processing_status(
+    visualization_state: Option<&State<SharedVisualizationState>>,
+) -> (SubsystemStatus, SubsystemStatus) {
+    let live_graph = match visualization_state {
+        Some(state) => state.get_live_processing_graph().await,
+        None => None,
+    };
+    let Some(live_graph) = live_graph else {
+        return (
+            SubsystemStatus::unavailable("Processing graph not registered"),
+            SubsystemStatus::unavailable("Processing graph not registered"),
+        );
+    };
+    let Ok(graph) = tokio::time::timeout(GRAPH_LOCK_TIMEOUT, live_graph.read()).await else {
+        return (
+            SubsystemStatus::unavailable("Processing graph is busy"),
+            SubsystemStatus::unavailable("Processing graph is busy"),
+        );
+    };
+
+    let processing = match graph.validate() {
+        Ok(()) => SubsystemStatus::ok(),
+        Err(e) => SubsystemStatus::unavailable(format!("Invalid processing graph: {}", e)),
+    };
+
+    // Ask every driver at once, then wait for the answers without the graph lock
+    let readiness: Vec<(String, Option<_>)> = graph
+        .get_universal_action_node_ids()
+        .into_iter()
+        .filter_map(|node_id| {
+            let node = graph.get_universal_action_node(&node_id)?;
+            node.has_driver()
+                .then(|| (node_id, node.driver_readiness()))
+        })
+        .collect();
+    drop(graph);
+
+    let answers = readiness.into_iter().map(|(node_id, answer)| async move {
+        let ready = match answer {
+            Some(answer) => matches!(
+                tokio::time::timeout(DRIVER_READINESS_TIMEOUT, answer).await,
+                Ok(Ok(true))
+            ),
+            None => false,
+        };
+        (!ready).then_some(node_id)
+    });
+    let mut disconnected: Vec<String> = futures::future::join_all(answers)
+        .await
+        .into_iter()
+        .flatten()
+        .collect();
+    disconnected.sort();
+    let action_drivers = if disconnected.is_empty() {
+        SubsystemStatus::ok()
+    } else {
+        SubsystemStatus::unavailable(format!(
+            "Drivers not connected: {}",
+            disconnected.join(", ")
+        ))
+    };
+
+    (processing, action_drivers)
+}
+
+/// Check that no supervised daemon task is restarting or has given up
+///
+/// Reported `disabled` when the daemon does not manage a supervisor.
+fn tasks_status(supervisor: Option<&State<TaskSupervisor>>) -> SubsystemStatus {
+    let Some(supervisor) = supervisor else {
+        return SubsystemStatus::disabled();
+    };
+    let unhealthy: Vec<String> = supervisor
+        .statuses()
+        .into_iter()
+        .filter(|(_, status)| matches!(status.state, TaskState::Restarting | TaskState::Failed))
+        .map(|(name, status)| {
+            let state = match status.state {
+                TaskState::Failed => "failed",
+                _ => "restarting",
+            };
+            match status.last_error {
+                Some(error) => format!(
+                    "{} {} after {} restarts ({})",
+                    name, state, status.restarts, error
+                ),
+                None => format!("{} {} after {} restarts", name, state, status.restarts),
+            }
+        })
+        .collect();
+    if unhealthy.is_empty() {
+        SubsystemStatus::ok()
+    } else {
+        SubsystemStatus::unavailable(format!("Unhealthy tasks: {}", unhealthy.join(", ")))
+    }
+}
+
+/// Get the probe routes
+///
+/// These routes are unauthenticated and not part of the OpenAPI specification.
+pub fn get_health_routes() -> Vec<rocket::Route> {
+    routes![healthz, readyz]
+}
@@ -5,6 +5,9 @@ pub mod action;
 pub mod computing;
 pub mod get;
 pub mod graph;
+pub mod health;
+pub mod modbus;
+pub mod openapi;
 pub mod post;
 pub mod system;
 pub mod test;
@@ -12,6 +15,9 @@ pub use action::*;
 pub use computing::*;
 pub use get::config::*;
 pub use get::thermal::*;
+pub use health::get_health_routes;
+pub use modbus::*;
+pub use openapi::{get_openapi_export_routes, stamp_openapi_info, OpenApiDocument};
 pub use post::test::*;
 pub use system::*;
 pub use test::*;
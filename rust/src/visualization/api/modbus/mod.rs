@@ -0,0 +1,74 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Modbus API routes
+//!
+//! Exposes the Modbus register map so that integrators can discover which
+//! register holds which value without reading the source code.
+
+use crate::modbus::register_map::{
+    exposed_regulator_ids, register_map, MeasurementEncoding, RegisterDescriptor,
+};
+use crate::thermal_regulation::SharedThermalState;
+use crate::visualization::api::get::config::ConfigState;
+use auth_macros::openapi_protect_get;
+use rocket::get;
+use rocket::serde::json::Json;
+use rocket::serde::Serialize;
+use rocket::State;
+use rocket_okapi::okapi::openapi3::OpenApi;
+use rocket_okapi::openapi_get_routes_spec;
+use rocket_okapi::JsonSchema;
+
+/// Modbus register map response
+#[derive(Serialize, Debug, Clone, JsonSchema)]
+pub struct ModbusRegisterMapResponse {
+    /// Whether the Modbus server is enabled in the configuration
+    pub enabled: bool,
+    /// Thermal regulator identifiers, in register block order
+    pub thermal_regulators: Vec<String>,
+    /// Every register served by the Modbus server
+    pub registers: Vec<RegisterDescriptor>,
+}
+
+/// Get the Modbus register map
+///
+/// **Endpoint:** `GET /api/modbus/map`
+///
+/// Returns the full Modbus register map: address, register type, data type,
+/// register count, word order, scale factor, unit, access mode and description
+/// of every register. The measurement registers follow the configured
+/// `modbus.value_encoding`. The map is
+/// generated from the same definitions the Modbus server uses, so it always
+/// matches the running implementation.
+///
+/// Thermal regulation blocks are listed for the regulators of the shared
+/// thermal regulation state, in the order the Modbus server lays them out.
+/// They are empty when the server runs without thermal regulation.
+///
+/// ### Authentication
+///
+/// Requires a valid JWT bearer token with the `read:api` permission.
+#[openapi_protect_get("/api/modbus/map", "read:api", tag = "Modbus")]
+pub async fn get_modbus_map(
+    config: &ConfigState,
+    thermal_state: Option<&State<SharedThermalState>>,
+) -> Json<ModbusRegisterMapResponse> {
+    let regulator_ids = match thermal_state {
+        Some(thermal_state) => exposed_regulator_ids(&*thermal_state.read().await),
+        None => Vec::new(),
+    };
+    let config = config.inner().read().await;
+
+    Json(ModbusRegisterMapResponse {
+        enabled: config.modbus.enabled,
+        registers: register_map(MeasurementEncoding::from(&config.modbus), &regulator_ids),
+        thermal_regulators: regulator_ids,
+    })
+}
+
+/// Centralized function to get all Modbus routes with OpenAPI documentation
+pub fn get_modbus_routes() -> (Vec<rocket::Route>, OpenApi) {
+    openapi_get_routes_spec![get_modbus_map]
+}
@@ -0,0 +1,105 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Downloadable export of the OpenAPI specification
+//!
+//! The specification assembled when the server is built is serialized once and
+//! served by two unauthenticated endpoints, so that SDK generators and API
+//! gateways can pull it from a stable location:
+//!
+//! | Endpoint | Format |
+//! |---|---|
+//! | `GET /api/openapi.json` | JSON (`application/json`) |
+//! | `GET /api/openapi.yaml` | YAML (`application/yaml`) |
+//!
+//! The `info.version` field of the specification is the crate version from
+//! [`BuildInfo`], so clients can tell which server release they were generated
+//! against.
+
+use crate::build_info::BuildInfo;
+use anyhow::{Context, Result};
+use rocket::http::ContentType;
+use rocket::{get, routes, State};
+use rocket_okapi::okapi::openapi3::OpenApi;
+
+/// Title of the published specification
+pub const OPENAPI_TITLE: &str = "SCTG rust-photoacoustic API";
+
+/// Description of the published specification
+pub const OPENAPI_DESCRIPTION: &str =
+    "Flexible Gas Analyzer using Laser Photoacoustic Spectroscopy";
+
+/// Set the title, description and version of `spec`
+///
+/// The version is the crate version of the running binary.
+pub fn stamp_openapi_info(spec: &mut OpenApi) {
+    spec.info.title = OPENAPI_TITLE.to_string();
+    spec.info.description = Some(OPENAPI_DESCRIPTION.to_string());
+    spec.info.version = BuildInfo::get().version.to_string();
+}
+
+/// Serialize `spec` to pretty-printed JSON
+pub fn openapi_to_json(spec: &OpenApi) -> Result<String> {
+    serde_json::to_string_pretty(spec).context("Failed to serialize OpenAPI specification to JSON")
+}
+
+/// Serialize `spec` to YAML
+pub fn openapi_to_yaml(spec: &OpenApi) -> Result<String> {
+    serde_yml::to_string(spec).context("Failed to serialize OpenAPI specification to YAML")
+}
+
+/// Pre-serialized OpenAPI specification managed by Rocket
+///
+/// The specification does not change once the server is built, so both
+/// representations are computed once instead of on every request.
+#[derive(Debug, Clone)]
+pub struct OpenApiDocument {
+    json: String,
+    yaml: String,
+}
+
+impl OpenApiDocument {
+    /// Serialize `spec` in both formats
+    ///
+    /// ### Parameters
+    ///
+    /// * `spec` - The specification served by the server
+    ///
+    /// ### Returns
+    ///
+    /// The document, or an error if `spec` cannot be serialized
+    pub fn new(spec: &OpenApi) -> Result<Self> {
+        Ok(Self {
+            json: openapi_to_json(spec)?,
+            yaml: openapi_to_yaml(spec)?,
+        })
+    }
+}
+
+/// Download the OpenAPI specification as JSON
+///
+/// **Endpoint:** `GET /api/openapi.json`
+#[get("/api/openapi.json")]
+pub fn openapi_json(document: &State<OpenApiDocument>) -> (ContentType, String) {
+    (ContentType::JSON, document.json.clone())
+}
+
+/// Download the OpenAPI specification as YAML
+///
+/// **Endpoint:** `GET /api/openapi.yaml`
+#[get("/api/openapi.yaml")]
+pub fn openapi_yaml(document: &State<OpenApiDocument>) -> (ContentType, String) {
+    (
+        ContentType::new("application", "yaml"),
+        document.yaml.clone(),
+    )
+}
+
+/// Get the specification export routes
+///
+/// These routes require an [`OpenApiDocument`] in the managed state and are not
+/// part of the specification themselves.
+pub fn get_openapi_export_routes() -> Vec<rocket::Route> {
+    routes![openapi_json, openapi_yaml]
+}
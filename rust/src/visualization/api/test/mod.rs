@@ -187,6 +187,25 @@ pub async fn test_post_api(path: PathBuf, test_data: Json<TestData>) -> Json<Tes
     })
 }
 
+/// Test API endpoint with a per-route rate limit
+///
+/// Allows two requests per configured rate limit window for each client,
+/// whether or not global rate limiting is enabled.
+#[openapi_protect_get("/api/test_rate_limit", "read:api", tag = "Test", rate_limit = 2)]
+pub async fn test_api_rate_limited() -> Json<TestResponse> {
+    Json(TestResponse {
+        description: "Rate limited test API called".to_string(),
+        token: bearer.token.clone(),
+        user: bearer.user_info.user_id.clone(),
+        message: None,
+    })
+}
+
 pub fn get_test_routes() -> (Vec<rocket::Route>, OpenApi) {
-    openapi_get_routes_spec![test_api, test_post_api, test_api_post_web_dashboard_display]
+    openapi_get_routes_spec![
+        test_api,
+        test_post_api,
+        test_api_post_web_dashboard_display,
+        test_api_rate_limited
+    ]
 }
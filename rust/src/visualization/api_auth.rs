@@ -26,7 +26,7 @@
 //! #[launch]
 //! fn rocket() -> _ {
 //!     // Initialize JWT validator with a secret
-//!     let jwt_validator = Arc::new(init_jwt_validator("your-hmac-secret",None, AccessConfig::default()).expect("JWT init failed"));
+//!     let jwt_validator = Arc::new(init_jwt_validator("your-hmac-secret", None, None, AccessConfig::default()).expect("JWT init failed"));
 //!
 //!     rocket::build()
 //!         .manage(jwt_validator)
@@ -196,6 +196,7 @@ impl<'r> FromRequest<'r> for AuthenticatedUser {
             .expect("JwtValidator not configured");
 
         // Validate token
+        state.fetch_remote_key(&token).await;
         let user_info = match state.get_user_info(&token, access_config) {
             Ok(info) => info,
             Err(e) => {
@@ -334,13 +335,15 @@ pub fn get_data(_user: AuthenticatedUser, _scope: RequireScope) -> Json<serde_js
 /// Initialize the JWT validator for the API
 ///
 /// This function creates and configures a `JwtValidator` instance with the provided
-/// HMAC secret or RS256 public key. The validator is configured with a fixed issuer
-/// and audience.
+/// HMAC secret, RS256 public key or Ed25519 public key. The validator is configured
+/// with the issuer of the access configuration and a fixed audience.
 ///
 /// ### Arguments
 ///
 /// * `hmac_secret` - The HMAC secret key used to verify JWT signatures
 /// * `rs256_public_key` - Optional RS256 public key in PEM format for verifying signatures
+/// * `ed25519_public_key` - Optional Ed25519 public key in PEM format for verifying EdDSA signatures
+/// * `access_config` - Users and permissions the token subjects are resolved against
 ///
 /// ### Returns
 ///
@@ -359,11 +362,11 @@ pub fn get_data(_user: AuthenticatedUser, _scope: RequireScope) -> Json<serde_js
 ///
 /// let public_key_bytes = b"-----BEGIN PUBLIC KEY-----\n...\n-----END PUBLIC KEY-----";
 /// // Using HMAC
-/// let jwt_validator = init_jwt_validator("your-secret-key", None, AccessConfig::default())
+/// let jwt_validator = init_jwt_validator("your-secret-key", None, None, AccessConfig::default())
 ///     .expect("Failed to initialize JWT validator");
 ///     
 /// // Using RS256
-/// let jwt_validator = init_jwt_validator("fallback-secret", Some(&public_key_bytes.as_ref()), AccessConfig::default())
+/// let jwt_validator = init_jwt_validator("fallback-secret", Some(&public_key_bytes.as_ref()), None, AccessConfig::default())
 ///     .expect("Failed to initialize JWT validator");
 ///     
 /// let validator_arc = Arc::new(jwt_validator);
@@ -371,16 +374,20 @@ pub fn get_data(_user: AuthenticatedUser, _scope: RequireScope) -> Json<serde_js
 pub fn init_jwt_validator(
     hmac_secret: &str,
     rs256_public_key: Option<&[u8]>,
+    ed25519_public_key: Option<&[u8]>,
     access_config: AccessConfig,
 ) -> Result<JwtValidator> {
-    // Support both keys if both are provided
+    // Support all the keys that are provided
     let hmac_opt = if !hmac_secret.is_empty() {
         Some(hmac_secret.as_bytes())
     } else {
         None
     };
-    let validator = JwtValidator::new(hmac_opt, rs256_public_key, access_config.clone())
+    let mut validator = JwtValidator::new(hmac_opt, rs256_public_key, access_config.clone())
         .map_err(|e| anyhow::anyhow!("Failed to create JWT validator: {}", e))?;
+    if let Some(ed25519_public_key) = ed25519_public_key {
+        validator = validator.with_ed25519_public_key(ed25519_public_key)?;
+    }
     Ok(validator
         .with_issuer(
             access_config
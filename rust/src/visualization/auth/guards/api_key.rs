@@ -0,0 +1,148 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Rocket request guard for static API key authentication
+//!
+//! Machine clients that cannot run an OAuth2 flow authenticate with a key sent
+//! in the `X-API-Key` header. The keys are configured in
+//! [`AccessConfig::api_keys`](crate::config::AccessConfig::api_keys), hashed
+//! with SHA-256, each one mapping to a set of permissions.
+//!
+//! [`OAuthBearer`] falls back to this guard when a request carries an
+//! `X-API-Key` header and no `Authorization` header, so the routes generated
+//! by the protection macros accept either credential.
+
+use crate::config::Config;
+use crate::visualization::auth::guards::bearer::OAuthBearer;
+use crate::visualization::auth::guards::rate_limit::AuthenticatedSubject;
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::State;
+use rocket_okapi::okapi::openapi3::{SecurityRequirement, SecurityScheme, SecuritySchemeData};
+use rocket_okapi::{
+    gen::OpenApiGenerator,
+    request::{OpenApiFromRequest, RequestHeaderInput},
+};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Name of the header carrying the API key
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+/// Request guard authenticating a machine client by its API key
+///
+/// ### Error Responses
+///
+/// | Condition | HTTP Status | Description |
+/// |-----------|-------------|-------------|
+/// | Missing `X-API-Key` header | 401 Unauthorized | No authentication provided |
+/// | Unknown key | 401 Unauthorized | The key matches no configured API key |
+/// | Server configuration error | 500 Internal Server Error | Missing config state |
+///
+/// ### Examples
+///
+/// ```rust,no_run
+/// use rocket::get;
+/// use rust_photoacoustic::visualization::auth::guards::ApiKeyAuth;
+///
+/// #[get("/probe")]
+/// fn probe(api_key: ApiKeyAuth) -> String {
+///     format!("Hello {}", api_key.name)
+/// }
+/// ```
+pub struct ApiKeyAuth {
+    /// Name of the matching API key
+    pub name: String,
+    /// Permissions granted by the key
+    pub permissions: Vec<String>,
+}
+
+impl ApiKeyAuth {
+    /// Subject identifying the key in logs and rate limit buckets
+    pub fn subject(&self) -> String {
+        format!("api-key:{}", self.name)
+    }
+
+    /// Convert the authenticated key into a bearer carrying its permissions
+    ///
+    /// The bearer has no token, its subject is [`ApiKeyAuth::subject`].
+    pub fn into_bearer(self) -> OAuthBearer {
+        OAuthBearer::for_client(self.subject(), self.name, self.permissions)
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ApiKeyAuth {
+    type Error = (Status, &'static str);
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let Some(key) = request.headers().get_one(API_KEY_HEADER) else {
+            return Outcome::Error((
+                Status::Unauthorized,
+                (Status::Unauthorized, "Missing X-API-Key header"),
+            ));
+        };
+
+        let config_state = match request.guard::<&State<Arc<RwLock<Config>>>>().await {
+            Outcome::Success(config) => config,
+            _ => {
+                return Outcome::Error((
+                    Status::InternalServerError,
+                    (Status::InternalServerError, "Missing config state"),
+                ))
+            }
+        };
+
+        let api_key = config_state
+            .read()
+            .await
+            .access
+            .find_api_key(key)
+            .map(|api_key| ApiKeyAuth {
+                name: api_key.name.clone(),
+                permissions: api_key.permissions.clone(),
+            });
+        match api_key {
+            Some(api_key) => {
+                // Lets the rate limit guard key its buckets by API key
+                request.local_cache(|| AuthenticatedSubject(Some(api_key.subject())));
+                Outcome::Success(api_key)
+            }
+            None => Outcome::Error((
+                Status::Unauthorized,
+                (Status::Unauthorized, "Invalid API key"),
+            )),
+        }
+    }
+}
+
+impl<'r> OpenApiFromRequest<'r> for ApiKeyAuth {
+    fn from_request_input(
+        _gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        let security_scheme = SecurityScheme {
+            description: Some(
+                "Requires a static API key configured in access.api_keys, \
+                provided in the X-API-Key header."
+                    .to_owned(),
+            ),
+            data: SecuritySchemeData::ApiKey {
+                name: API_KEY_HEADER.to_owned(),
+                location: "header".to_owned(),
+            },
+            extensions: Default::default(),
+        };
+
+        let mut security_req = SecurityRequirement::new();
+        security_req.insert("ApiKeyAuth".to_owned(), vec![]);
+
+        Ok(RequestHeaderInput::Security(
+            "ApiKeyAuth".to_owned(),
+            security_scheme,
+            security_req,
+        ))
+    }
+}
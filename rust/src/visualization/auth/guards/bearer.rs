@@ -6,7 +6,8 @@
 //!
 //! This module provides request guards that automatically validate OAuth2 Bearer tokens
 //! and check user permissions for API endpoints. The guards integrate with the JWT
-//! validation system and support both HMAC (HS256) and RSA (RS256) token validation.
+//! validation system and support HMAC (HS256), RSA (RS256) and Ed25519 (EdDSA)
+//! token validation.
 //!
 //! # Request Guards
 //!
@@ -15,10 +16,16 @@
 //!
 //! # Token Validation
 //!
-//! The guards support both symmetric (HMAC-SHA256) and asymmetric (RSA-SHA256) JWT validation:
+//! The guards support symmetric (HMAC-SHA256) and asymmetric (RSA-SHA256, Ed25519) JWT validation:
 //!
 //! - **HS256**: Uses a shared secret key for token signing and verification
-//! - **RS256**: Uses RSA public/private key pairs for enhanced security
+//! - **RS256**: Uses RSA public/private key pairs for enhanced security, the
+//!   public keys of previous signing keys remaining valid after a rotation
+//! - **EdDSA**: Uses the Ed25519 public key of `visualization.ed25519_public_key`
+//!
+//! Requests without an `Authorization` header but with an `X-API-Key` header are
+//! authenticated by [`ApiKeyAuth`] instead, and requests with neither by their
+//! TLS client certificate through [`ClientCertAuth`], see [`OAuthBearer`].
 //!
 //! The validation process includes:
 //! 1. Extracting the Bearer token from the Authorization header
@@ -26,7 +33,10 @@
 //! 3. Extracting user information and permissions from the token
 //! 4. Optionally checking for specific permissions
 
-use crate::config::Config;
+use crate::config::{AccessConfig, Config};
+use crate::visualization::auth::guards::api_key::{ApiKeyAuth, API_KEY_HEADER};
+use crate::visualization::auth::guards::client_cert::ClientCertAuth;
+use crate::visualization::auth::guards::rate_limit::AuthenticatedSubject;
 use crate::visualization::auth::jwt::{JwtValidator, UserSysInfo};
 use crate::visualization::auth::oauth2::OxideState;
 use base64::Engine;
@@ -46,8 +56,8 @@ use tokio::sync::RwLock;
 /// Request guard for extracting and validating a Bearer JWT from the Authorization header
 ///
 /// This guard automatically validates OAuth2 Bearer tokens and extracts user information
-/// from JWT claims. It supports both HMAC (HS256) and RSA (RS256) token validation
-/// depending on the server configuration.
+/// from JWT claims. It supports HMAC (HS256), RSA (RS256) and Ed25519 (EdDSA) token
+/// validation depending on the server configuration.
 ///
 /// ### Authentication Process
 ///
@@ -56,6 +66,19 @@ use tokio::sync::RwLock;
 /// 3. **User Resolution**: Extracts user information from token claims
 /// 4. **Permission Loading**: Loads user permissions from the token or configuration
 ///
+/// ### API Keys
+///
+/// When the request has no `Authorization` header but an `X-API-Key` header,
+/// the key is checked by [`ApiKeyAuth`] and the bearer carries the permissions
+/// of the key, without token. Routes taking an `OAuthBearer`, including those
+/// generated by the protection macros, thus accept either credential.
+///
+/// ### Client Certificates
+///
+/// With mutual TLS enabled, a request carrying neither header is authenticated
+/// by its verified client certificate through [`ClientCertAuth`], when the
+/// certificate subject is configured.
+///
 /// ### Success Conditions
 ///
 /// The guard succeeds if:
@@ -70,6 +93,7 @@ use tokio::sync::RwLock;
 /// | Condition | HTTP Status | Description |
 /// |-----------|-------------|-------------|
 /// | Missing Authorization header | 401 Unauthorized | No authentication provided |
+/// | Unknown API key | 401 Unauthorized | The `X-API-Key` matches no configured key |
 /// | Malformed Bearer token | 401 Unauthorized | Invalid token format |
 /// | Invalid JWT signature | 401 Unauthorized | Token tampered with or wrong key |
 /// | Expired token | 401 Unauthorized | Token past expiration time |
@@ -144,6 +168,7 @@ impl<'r> FromRequest<'r> for OAuthBearer {
                 expiry: Utc::now() + chrono::Duration::hours(24),
                 permissions: Some(vec!["read:api".to_string(), "admin:api".to_string()]),
             };
+            request.local_cache(|| AuthenticatedSubject(Some(user_info.user_id.clone())));
 
             return Outcome::Success(OAuthBearer {
                 user_info,
@@ -152,53 +177,25 @@ impl<'r> FromRequest<'r> for OAuthBearer {
             });
         }
 
+        // Machine clients may authenticate with a static API key instead
+        if auth_header.is_none() && request.headers().contains(API_KEY_HEADER) {
+            return ApiKeyAuth::from_request(request)
+                .await
+                .map(ApiKeyAuth::into_bearer);
+        }
+
+        // Devices may authenticate with their TLS client certificate
+        if auth_header.is_none() && config.visualization.mutual_tls.is_some() {
+            if let Outcome::Success(client) = ClientCertAuth::from_request(request).await {
+                return Outcome::Success(client.into_bearer());
+            }
+        }
+
         let access_config = config.access.clone();
 
         if let Some(header) = auth_header {
             if let Some(token) = header.strip_prefix("Bearer ") {
-                // Get the OxideState from Rocket state
-                let state = match request.guard::<&State<OxideState>>().await {
-                    Outcome::Success(state) => state,
-                    _ => {
-                        return Outcome::Error((
-                            Status::InternalServerError,
-                            (Status::InternalServerError, "Missing state"),
-                        ))
-                    }
-                };
-                // Build JwtValidator from state (supporting both HS256 and RS256)
-                let hmac_secret = state.hmac_secret.as_bytes();
-                let rs256_public_key = if !state.rs256_public_key.is_empty() {
-                    base64::engine::general_purpose::STANDARD
-                        .decode(&state.rs256_public_key)
-                        .ok()
-                } else {
-                    None
-                };
-
-                let validator = match rs256_public_key {
-                    Some(ref pem) => {
-                        JwtValidator::new(Some(hmac_secret), Some(&pem), access_config.clone())
-                    }
-                    None => JwtValidator::new(Some(hmac_secret), None, access_config.clone()),
-                };
-                match validator {
-                    Ok(validator) => match validator.get_user_info(token, access_config.clone()) {
-                        Ok(user_info) => Outcome::Success(OAuthBearer {
-                            user_info: user_info.clone(),
-                            token: token.to_string(),
-                            permissions: user_info.permissions.clone(),
-                        }),
-                        Err(_) => Outcome::Error((
-                            Status::Unauthorized,
-                            (Status::Unauthorized, "Invalid token"),
-                        )),
-                    },
-                    Err(_) => Outcome::Error((
-                        Status::InternalServerError,
-                        (Status::InternalServerError, "Validator error"),
-                    )),
-                }
+                Self::from_token(request, token, &access_config).await
             } else {
                 Outcome::Error((
                     Status::Unauthorized,
@@ -215,6 +212,126 @@ impl<'r> FromRequest<'r> for OAuthBearer {
 }
 
 impl OAuthBearer {
+    /// Build a bearer without token for a client authenticated by other means
+    ///
+    /// Used for API keys and client certificates, which do not expire: the
+    /// bearer is reported valid for an hour.
+    ///
+    /// ### Parameters
+    ///
+    /// * `user_id` - Subject of the bearer, also keying the rate limit buckets
+    /// * `client_id` - Name of the client
+    /// * `permissions` - Permissions granted to the client
+    pub(crate) fn for_client(user_id: String, client_id: String, permissions: Vec<String>) -> Self {
+        let now = Utc::now();
+        let user_info = UserSysInfo {
+            user_id,
+            name: Some(client_id.clone()),
+            client_id,
+            scopes: permissions.clone(),
+            email: None,
+            token_id: String::new(),
+            issued_at: now,
+            expiry: now + chrono::Duration::hours(1),
+            permissions: Some(permissions.clone()),
+        };
+        OAuthBearer {
+            user_info,
+            token: String::new(),
+            permissions: Some(permissions),
+        }
+    }
+
+    /// Validate a raw bearer token and build the guard from its claims
+    ///
+    /// Shared by [`OAuthBearer::from_request`] and guards reading the token from
+    /// another place than the `Authorization` header.
+    ///
+    /// ### Parameters
+    ///
+    /// * `request` - The request being authenticated, used to reach the managed [`OxideState`]
+    /// * `token` - The JWT, without the `Bearer ` prefix
+    /// * `access_config` - Users and permissions the token subject is resolved against
+    ///
+    /// ### Returns
+    ///
+    /// `Outcome::Success` with the bearer, or `Outcome::Error` with 401 for an
+    /// invalid token and 500 when the server state is missing.
+    pub(crate) async fn from_token(
+        request: &Request<'_>,
+        token: &str,
+        access_config: &AccessConfig,
+    ) -> Outcome<Self, (Status, &'static str)> {
+        // Get the OxideState from Rocket state
+        let state = match request.guard::<&State<OxideState>>().await {
+            Outcome::Success(state) => state,
+            _ => {
+                return Outcome::Error((
+                    Status::InternalServerError,
+                    (Status::InternalServerError, "Missing state"),
+                ))
+            }
+        };
+        // Build JwtValidator from state (supporting HS256, RS256 and EdDSA)
+        let hmac_secret = state.hmac_secret.as_bytes();
+        let rs256_public_key = if !state.rs256_public_key.is_empty() {
+            base64::engine::general_purpose::STANDARD
+                .decode(&state.rs256_public_key)
+                .ok()
+        } else {
+            None
+        };
+
+        let validator = match rs256_public_key {
+            Some(ref pem) => {
+                JwtValidator::new(Some(hmac_secret), Some(&pem), access_config.clone())
+            }
+            None => JwtValidator::new(Some(hmac_secret), None, access_config.clone()),
+        }
+        .map_err(anyhow::Error::from)
+        .and_then(|validator| {
+            // Tokens signed before the last key rotation remain valid
+            state
+                .rs256_previous_public_keys_pem()
+                .iter()
+                .try_fold(validator, |validator, previous_key| {
+                    validator.with_rs256_public_key(previous_key)
+                })
+        })
+        .and_then(|validator| match state.ed25519_public_key_pem() {
+            Some(ed25519_public_key) => validator.with_ed25519_public_key(&ed25519_public_key),
+            None => Ok(validator),
+        })
+        .map(|validator| match &state.remote_jwks {
+            Some(remote_jwks) => validator.with_remote_jwks(remote_jwks.clone()),
+            None => validator,
+        });
+        if let Ok(validator) = &validator {
+            validator.fetch_remote_key(token).await;
+        }
+        match validator {
+            Ok(validator) => match validator.get_user_info(token, access_config.clone()) {
+                Ok(user_info) => {
+                    // Lets the rate limit guard key its buckets by token subject
+                    request.local_cache(|| AuthenticatedSubject(Some(user_info.user_id.clone())));
+                    Outcome::Success(OAuthBearer {
+                        user_info: user_info.clone(),
+                        token: token.to_string(),
+                        permissions: user_info.permissions.clone(),
+                    })
+                }
+                Err(_) => Outcome::Error((
+                    Status::Unauthorized,
+                    (Status::Unauthorized, "Invalid token"),
+                )),
+            },
+            Err(_) => Outcome::Error((
+                Status::InternalServerError,
+                (Status::InternalServerError, "Validator error"),
+            )),
+        }
+    }
+
     /// Check if the authenticated user has the specified permission
     ///
     /// ### Arguments
@@ -248,6 +365,70 @@ impl OAuthBearer {
             .map(|permissions| permissions.contains(&permission.to_string()))
             .unwrap_or(false)
     }
+
+    /// Check if the authenticated user has at least one of the specified permissions
+    ///
+    /// ### Arguments
+    ///
+    /// * `permissions` - The permission strings to check for
+    ///
+    /// ### Returns
+    ///
+    /// Returns `true` if the user has any of the permissions, `false` otherwise.
+    /// An empty `permissions` slice never matches.
+    ///
+    /// ### Examples
+    ///
+    /// ```rust,no_run
+    /// use rocket::get;
+    /// use rust_photoacoustic::visualization::auth::OAuthBearer;
+    ///
+    /// #[get("/measurements")]
+    /// fn get_measurements(bearer: OAuthBearer) -> Result<&'static str, rocket::http::Status> {
+    ///     if bearer.has_any_permission(&["read:api", "admin:api"]) {
+    ///         Ok("Measurements")
+    ///     } else {
+    ///         Err(rocket::http::Status::Forbidden)
+    ///     }
+    /// }
+    /// ```
+    pub fn has_any_permission(&self, permissions: &[&str]) -> bool {
+        permissions
+            .iter()
+            .any(|permission| self.has_permission(permission))
+    }
+
+    /// Check if the authenticated user has every one of the specified permissions
+    ///
+    /// ### Arguments
+    ///
+    /// * `permissions` - The permission strings to check for
+    ///
+    /// ### Returns
+    ///
+    /// Returns `true` if the user has all of the permissions, `false` otherwise.
+    /// An empty `permissions` slice always matches.
+    ///
+    /// ### Examples
+    ///
+    /// ```rust,no_run
+    /// use rocket::post;
+    /// use rust_photoacoustic::visualization::auth::OAuthBearer;
+    ///
+    /// #[post("/calibration")]
+    /// fn calibrate(bearer: OAuthBearer) -> Result<&'static str, rocket::http::Status> {
+    ///     if bearer.has_all_permissions(&["write:api", "admin:api"]) {
+    ///         Ok("Calibration started")
+    ///     } else {
+    ///         Err(rocket::http::Status::Forbidden)
+    ///     }
+    /// }
+    /// ```
+    pub fn has_all_permissions(&self, permissions: &[&str]) -> bool {
+        permissions
+            .iter()
+            .all(|permission| self.has_permission(permission))
+    }
 }
 
 impl<'r> OpenApiFromRequest<'r> for OAuthBearer {
@@ -261,7 +442,7 @@ impl<'r> OpenApiFromRequest<'r> for OAuthBearer {
             description: Some(
                 "Requires a valid JWT Bearer token for authentication. \
                 The token must be provided in the Authorization header as 'Bearer <token>'. \
-                Supports both HMAC (HS256) and RSA (RS256) signed tokens."
+                Supports HMAC (HS256), RSA (RS256) and Ed25519 (EdDSA) signed tokens."
                     .to_owned(),
             ),
             // Setup data requirements for Bearer JWT
@@ -0,0 +1,141 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Rocket request guard for client certificate authentication
+//!
+//! With mutual TLS enabled (see [`MutualTlsConfig`](crate::config::MutualTlsConfig)),
+//! client certificates are verified against the configured CA bundle during
+//! the TLS handshake. This guard maps the subject common name of the verified
+//! certificate to the permissions configured in
+//! [`AccessConfig::client_certificates`](crate::config::AccessConfig::client_certificates).
+//!
+//! [`OAuthBearer`] falls back to this guard when a request carries neither an
+//! `Authorization` nor an `X-API-Key` header, so the routes generated by the
+//! protection macros accept client certificates as well.
+
+use crate::config::Config;
+use crate::visualization::auth::guards::bearer::OAuthBearer;
+use crate::visualization::auth::guards::rate_limit::AuthenticatedSubject;
+use rocket::http::Status;
+use rocket::mtls::Certificate;
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::State;
+use rocket_okapi::{
+    gen::OpenApiGenerator,
+    request::{OpenApiFromRequest, RequestHeaderInput},
+};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Request guard authenticating a device by its TLS client certificate
+///
+/// ### Error Responses
+///
+/// | Condition | HTTP Status | Description |
+/// |-----------|-------------|-------------|
+/// | No verified client certificate | 401 Unauthorized | Mutual TLS disabled or no certificate presented |
+/// | Unknown subject | 401 Unauthorized | The common name matches no configured client certificate |
+/// | Server configuration error | 500 Internal Server Error | Missing config state |
+///
+/// ### Examples
+///
+/// ```rust,no_run
+/// use rocket::get;
+/// use rust_photoacoustic::visualization::auth::guards::ClientCertAuth;
+///
+/// #[get("/device")]
+/// fn device(client: ClientCertAuth) -> String {
+///     format!("Hello {}", client.common_name)
+/// }
+/// ```
+pub struct ClientCertAuth {
+    /// Subject common name of the verified certificate
+    pub common_name: String,
+    /// Permissions granted to the certificate
+    pub permissions: Vec<String>,
+}
+
+impl ClientCertAuth {
+    /// Subject identifying the certificate in logs and rate limit buckets
+    pub fn subject(&self) -> String {
+        format!("cert:{}", self.common_name)
+    }
+
+    /// Convert the authenticated certificate into a bearer carrying its permissions
+    ///
+    /// The bearer has no token, its subject is [`ClientCertAuth::subject`].
+    pub fn into_bearer(self) -> OAuthBearer {
+        OAuthBearer::for_client(self.subject(), self.common_name, self.permissions)
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ClientCertAuth {
+    type Error = (Status, &'static str);
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        // Only certificates verified during the TLS handshake are returned
+        let certificate = match request.guard::<Certificate<'r>>().await {
+            Outcome::Success(certificate) => certificate,
+            _ => {
+                return Outcome::Error((
+                    Status::Unauthorized,
+                    (Status::Unauthorized, "Missing client certificate"),
+                ))
+            }
+        };
+        let Some(common_name) = certificate.subject().common_name().map(str::to_string) else {
+            return Outcome::Error((
+                Status::Unauthorized,
+                (
+                    Status::Unauthorized,
+                    "Client certificate without common name",
+                ),
+            ));
+        };
+
+        let config_state = match request.guard::<&State<Arc<RwLock<Config>>>>().await {
+            Outcome::Success(config) => config,
+            _ => {
+                return Outcome::Error((
+                    Status::InternalServerError,
+                    (Status::InternalServerError, "Missing config state"),
+                ))
+            }
+        };
+
+        let client = config_state
+            .read()
+            .await
+            .access
+            .find_client_certificate(&common_name)
+            .map(|certificate| ClientCertAuth {
+                common_name: certificate.common_name.clone(),
+                permissions: certificate.permissions.clone(),
+            });
+        match client {
+            Some(client) => {
+                // Lets the rate limit guard key its buckets by certificate subject
+                request.local_cache(|| AuthenticatedSubject(Some(client.subject())));
+                Outcome::Success(client)
+            }
+            None => Outcome::Error((
+                Status::Unauthorized,
+                (Status::Unauthorized, "Unknown client certificate"),
+            )),
+        }
+    }
+}
+
+impl<'r> OpenApiFromRequest<'r> for ClientCertAuth {
+    fn from_request_input(
+        _gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        // The certificate is part of the TLS handshake, not of the request,
+        // and OpenAPI 3.0 has no security scheme for it
+        Ok(RequestHeaderInput::None)
+    }
+}
@@ -7,7 +7,10 @@
 //! This module provides Rocket request guards for validating authentication
 //! and checking permissions in API endpoints.
 
+pub mod api_key;
 pub mod bearer;
+pub mod client_cert;
+pub mod rate_limit;
 
 #[cfg(test)]
 mod test_macro;
@@ -17,5 +20,8 @@ mod test_macro;
 mod macro_test_example;
 
 // Re-export main guards
+pub use api_key::{ApiKeyAuth, API_KEY_HEADER};
 pub use bearer::OAuthBearer;
+pub use client_cert::ClientCertAuth;
+pub use rate_limit::{too_many_requests, AuthenticatedSubject, RateLimit, RateLimiter};
 //pub use macros::{protect_get, protected_route_mounts, protected_routes};
@@ -0,0 +1,287 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Token bucket rate limiting for protected API endpoints
+//!
+//! The [`RateLimit`] request guard is added to every route generated by the
+//! protection macros (`protect_get`, `openapi_protect_get`, ...). It consumes one
+//! token from two buckets per request:
+//!
+//! - one keyed by the subject of the bearer token (set by [`OAuthBearer`](super::OAuthBearer))
+//! - one keyed by the client IP address
+//!
+//! Buckets are scoped to the route, so hammering `/api/computing` does not
+//! starve other endpoints. When a bucket is empty the guard fails with
+//! `429 Too Many Requests`; the [`too_many_requests`] catcher then answers with
+//! a `Retry-After` header giving the number of seconds until a token is available.
+//!
+//! The limits come from [`RateLimitConfig`](crate::config::RateLimitConfig) and are
+//! read on each request, so configuration hot-reloads apply immediately. A route
+//! can override the number of requests per window with `RateLimit<N>`, which the
+//! protection macros generate from their `rate_limit = N` argument.
+
+use crate::config::Config;
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::response::{self, Responder, Response};
+use rocket::serde::json::{json, Json};
+use rocket::{catch, State};
+use rocket_okapi::okapi;
+use rocket_okapi::{
+    gen::OpenApiGenerator,
+    request::{OpenApiFromRequest, RequestHeaderInput},
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// Number of tracked buckets above which idle buckets are pruned
+const MAX_TRACKED_BUCKETS: usize = 10_000;
+
+/// Subject of the bearer token authenticated for the current request
+///
+/// Stored in the request-local cache by [`OAuthBearer`](super::OAuthBearer) so
+/// that [`RateLimit`] can key its bucket by token subject.
+#[derive(Debug, Clone, Default)]
+pub struct AuthenticatedSubject(pub Option<String>);
+
+/// Seconds to wait before retrying, stored in the request-local cache by [`RateLimit`]
+#[derive(Debug, Clone, Copy, Default)]
+struct RetryAfter(u64);
+
+/// A single token bucket
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    /// Tokens currently available, refilled continuously
+    tokens: f64,
+    /// Last time the bucket was refilled
+    updated: Instant,
+}
+
+impl TokenBucket {
+    /// Refill the bucket for the time elapsed since its last update
+    fn refill(&mut self, now: Instant, capacity: f64, window: Duration) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * capacity / window.as_secs_f64()).min(capacity);
+        self.updated = now;
+    }
+
+    /// Time until the bucket holds one full token
+    fn wait_time(&self, capacity: f64, window: Duration) -> Duration {
+        let missing = (1.0 - self.tokens).max(0.0);
+        Duration::from_secs_f64(missing * window.as_secs_f64() / capacity)
+    }
+}
+
+/// Shared token bucket store, managed as Rocket state
+///
+/// The server builder manages one instance; without it the [`RateLimit`] guard
+/// lets every request through.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl RateLimiter {
+    /// Create an empty rate limiter
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Consume one token from each of the buckets identified by `keys`
+    ///
+    /// Tokens are only consumed when every bucket holds one, so a rejected
+    /// request does not count against the client.
+    ///
+    /// ### Parameters
+    ///
+    /// * `keys` - Bucket identifiers, created full on first use
+    /// * `capacity` - Number of requests allowed per window
+    /// * `window` - Time needed to refill an empty bucket
+    ///
+    /// ### Returns
+    ///
+    /// `Ok(())` when the request is allowed, otherwise `Err` with the time
+    /// until every bucket holds a token again.
+    pub fn check(&self, keys: &[String], capacity: u32, window: Duration) -> Result<(), Duration> {
+        let capacity = f64::from(capacity.max(1));
+        let window = window.max(Duration::from_millis(1));
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() > MAX_TRACKED_BUCKETS {
+            // A bucket idle for a whole window is full and can be recreated on demand
+            buckets.retain(|_, bucket| now.saturating_duration_since(bucket.updated) < window);
+        }
+
+        let mut wait = Duration::ZERO;
+        for key in keys {
+            let bucket = buckets.entry(key.clone()).or_insert(TokenBucket {
+                tokens: capacity,
+                updated: now,
+            });
+            bucket.refill(now, capacity, window);
+            wait = wait.max(bucket.wait_time(capacity, window));
+        }
+
+        if wait > Duration::ZERO {
+            return Err(wait);
+        }
+        for key in keys {
+            if let Some(bucket) = buckets.get_mut(key) {
+                bucket.tokens -= 1.0;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Request guard enforcing the token bucket rate limit of a route
+///
+/// `REQUESTS` overrides the number of requests per window configured in
+/// [`RateLimitConfig`](crate::config::RateLimitConfig); `0` uses the global
+/// setting, which only applies when rate limiting is enabled. The refill window
+/// always comes from the configuration.
+///
+/// The guard must come after [`OAuthBearer`](super::OAuthBearer) in the handler
+/// signature to be keyed by token subject. The protection macros take care of this.
+///
+/// ### Error Responses
+///
+/// | Condition | HTTP Status | Description |
+/// |-----------|-------------|-------------|
+/// | Bucket empty | 429 Too Many Requests | `Retry-After` gives the seconds to wait |
+///
+/// ### Examples
+///
+/// ```rust,no_run
+/// use rocket::get;
+/// use rust_photoacoustic::visualization::auth::guards::RateLimit;
+/// use rust_photoacoustic::visualization::auth::OAuthBearer;
+///
+/// // At most 10 requests per configured window for each client
+/// #[get("/expensive")]
+/// fn expensive(bearer: OAuthBearer, _rate_limit: RateLimit<10>) -> &'static str {
+///     "Expensive computation"
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit<const REQUESTS: u32 = 0>;
+
+#[rocket::async_trait]
+impl<'r, const REQUESTS: u32> FromRequest<'r> for RateLimit<REQUESTS> {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let limiter = match request.guard::<&State<RateLimiter>>().await {
+            Outcome::Success(limiter) => limiter,
+            _ => return Outcome::Success(RateLimit),
+        };
+        let settings = match request.guard::<&State<Arc<RwLock<Config>>>>().await {
+            Outcome::Success(config) => config.read().await.visualization.rate_limit.clone(),
+            _ => return Outcome::Success(RateLimit),
+        };
+
+        let capacity = if REQUESTS > 0 {
+            REQUESTS
+        } else if settings.enabled {
+            settings.requests
+        } else {
+            return Outcome::Success(RateLimit);
+        };
+
+        let route = request
+            .route()
+            .map(|route| route.uri.to_string())
+            .unwrap_or_else(|| request.uri().path().to_string());
+        let scope = format!("{} {}", request.method(), route);
+
+        let mut keys = Vec::with_capacity(2);
+        if let Some(subject) = &request.local_cache(AuthenticatedSubject::default).0 {
+            keys.push(format!("{}|sub:{}", scope, subject));
+        }
+        if let Some(ip) = request.client_ip() {
+            keys.push(format!("{}|ip:{}", scope, ip));
+        }
+
+        match limiter.check(&keys, capacity, Duration::from_secs(settings.window_secs)) {
+            Ok(()) => Outcome::Success(RateLimit),
+            Err(wait) => {
+                let seconds = wait.as_secs_f64().ceil().max(1.0) as u64;
+                log::debug!("Rate limit exceeded for {}, retry in {}s", scope, seconds);
+                request.local_cache(|| RetryAfter(seconds));
+                Outcome::Error((Status::TooManyRequests, ()))
+            }
+        }
+    }
+}
+
+impl<'r, const REQUESTS: u32> OpenApiFromRequest<'r> for RateLimit<REQUESTS> {
+    fn from_request_input(
+        _gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        Ok(RequestHeaderInput::None)
+    }
+
+    fn get_responses(
+        _gen: &mut OpenApiGenerator,
+    ) -> rocket_okapi::Result<okapi::openapi3::Responses> {
+        use okapi::openapi3::*;
+
+        let mut responses = Responses::default();
+        responses.responses.insert(
+            "429".to_owned(),
+            RefOr::Object(Response {
+                description: "Too Many Requests - Retry after the number of seconds given \
+                    in the Retry-After header"
+                    .to_owned(),
+                content: okapi::map! {
+                    "application/json".to_owned() => MediaType {
+                        example: Some(json!({
+                            "error": "Too many requests",
+                            "retry_after": 1
+                        })),
+                        ..Default::default()
+                    }
+                },
+                ..Default::default()
+            }),
+        );
+        Ok(responses)
+    }
+}
+
+/// `429 Too Many Requests` response carrying a `Retry-After` header
+#[derive(Debug, Clone, Copy)]
+pub struct TooManyRequests {
+    /// Seconds the client should wait before retrying
+    pub retry_after: u64,
+}
+
+impl<'r> Responder<'r, 'static> for TooManyRequests {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let body = Json(json!({
+            "error": "Too many requests",
+            "retry_after": self.retry_after
+        }));
+        Response::build_from(body.respond_to(request)?)
+            .status(Status::TooManyRequests)
+            .raw_header("Retry-After", self.retry_after.to_string())
+            .ok()
+    }
+}
+
+/// Catcher turning rate limit rejections into responses with a `Retry-After` header
+///
+/// Registered by the server builder for status 429.
+#[catch(429)]
+pub fn too_many_requests(request: &Request) -> TooManyRequests {
+    let RetryAfter(seconds) = *request.local_cache(|| RetryAfter(1));
+    TooManyRequests {
+        retry_after: seconds.max(1),
+    }
+}
@@ -11,6 +11,7 @@ use jsonwebtoken::{Algorithm, Validation};
 use log;
 use oxide_auth::primitives::grant::Grant;
 use oxide_auth::primitives::issuer::{IssuedToken, Issuer, RefreshedToken};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 use super::claims::JwtClaims;
@@ -40,6 +41,15 @@ impl JwtIssuer {
         Ok(JwtIssuer(Arc::new(Mutex::new(token_map))))
     }
 
+    /// Create a new JwtIssuer with EdDSA algorithm using PEM encoded Ed25519 keys
+    pub fn with_ed25519_pem(
+        private_key_pem: &[u8],
+        public_key_pem: &[u8],
+    ) -> Result<Self, jsonwebtoken::errors::Error> {
+        let token_map = JwtTokenMap::with_ed25519_pem(private_key_pem, public_key_pem)?;
+        Ok(JwtIssuer(Arc::new(Mutex::new(token_map))))
+    }
+
     /// Sets the JWT signing algorithm
     pub fn with_algorithm(&mut self, algorithm: Algorithm) -> &mut Self {
         {
@@ -67,6 +77,53 @@ impl JwtIssuer {
         self
     }
 
+    /// Set the refresh token lifetime of each client, replacing the previous ones
+    pub fn with_refresh_token_lifetimes(
+        &mut self,
+        lifetimes: HashMap<String, Duration>,
+    ) -> &mut Self {
+        {
+            let mut map = self.0.lock().unwrap();
+            map.set_refresh_token_lifetimes(lifetimes);
+        }
+        self
+    }
+
+    /// Replace the RS256 signing key, keeping the current one for verification
+    ///
+    /// See [`JwtTokenMap::rotate_rs256_key`].
+    pub fn rotate_rs256_key(
+        &self,
+        private_key_pem: &[u8],
+        public_key_pem: &[u8],
+    ) -> Result<(), jsonwebtoken::errors::Error> {
+        self.map().rotate_rs256_key(private_key_pem, public_key_pem)
+    }
+
+    /// Accept the tokens signed with another RS256 key, returning its key ID
+    ///
+    /// See [`JwtTokenMap::add_verification_key`].
+    pub fn add_verification_key(&self, public_key_pem: &[u8]) -> anyhow::Result<String> {
+        self.map().add_verification_key(public_key_pem)
+    }
+
+    /// Stop accepting the tokens signed with a previous key
+    pub fn remove_verification_key(&self, kid: &str) -> bool {
+        self.map().remove_verification_key(kid)
+    }
+
+    /// Key ID (`kid`) set in the header of the issued tokens, if any
+    pub fn signing_kid(&self) -> Option<String> {
+        self.map().signing_kid.clone()
+    }
+
+    /// Revoke the refresh token family if `refresh_token` was already rotated
+    ///
+    /// See [`JwtTokenMap::revoke_replayed_refresh_token`].
+    pub fn revoke_replayed_refresh_token(&self, refresh_token: &str) -> bool {
+        self.map().revoke_replayed_refresh_token(refresh_token)
+    }
+
     /// Add user information to token claims
     pub fn add_user_claims(&mut self, username: &str, permissions: &[String]) -> &mut Self {
         {
@@ -92,7 +149,10 @@ impl JwtIssuer {
 
         // Try to decode as JWT
         let validation = Validation::new(map.algorithm);
-        match jsonwebtoken::decode::<JwtClaims>(token, &map.verification_key, &validation) {
+        let verification_key = map
+            .verification_key_for(token)
+            .ok_or("JWT signed with an unknown key")?;
+        match jsonwebtoken::decode::<JwtClaims>(token, verification_key, &validation) {
             Ok(token_data) => {
                 log::debug!("JWT Claims: {:?}", token_data.claims);
 
@@ -65,6 +65,7 @@ use base64::prelude::*;
 use jsonwebtoken::jwk::{Jwk, PublicKeyUse};
 use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey};
 use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::pkcs8::DecodePublicKey;
 use rsa::sha2::Digest;
 use rsa::sha2::Sha256;
 use rsa::traits::PublicKeyParts;
@@ -543,13 +544,65 @@ impl JwkKeySet {
     /// A JWK representing the RSA public key, or an error if parsing fails
     pub fn create_jwk_from_pem(pem_data: &[u8]) -> Result<Jwk> {
         // Parse the PEM key
-        let public_key = DecodeRsaPublicKey::from_pkcs1_pem(std::str::from_utf8(pem_data)?)
-            .context("Failed to parse RSA public key from PEM")?;
+        let public_key = Self::parse_rsa_public_pem(pem_data)?;
 
         // Convert to JWK
         Self::create_jwk_from_public_key(&public_key)
     }
 
+    /// Create a JWK set from PEM encoded RSA public keys
+    ///
+    /// Keys that cannot be parsed are skipped with a warning, so that a
+    /// misconfigured key does not hide the others.
+    ///
+    /// ### Parameters
+    ///
+    /// * `pems` - The PEM encoded RSA public keys, the current signing key first
+    ///
+    /// ### Returns
+    ///
+    /// The set of the JWKs of the valid keys, in the same order
+    pub fn from_public_pems<'a>(pems: impl IntoIterator<Item = &'a [u8]>) -> Self {
+        let keys = pems
+            .into_iter()
+            .filter_map(|pem| match Self::create_jwk_from_pem(pem) {
+                Ok(jwk) => Some(jwk),
+                Err(e) => {
+                    log::warn!("Skipping RSA public key from the JWKS: {}", e);
+                    None
+                }
+            })
+            .collect();
+        Self { keys }
+    }
+
+    /// Compute the key ID (kid) of a PEM encoded RSA public key
+    ///
+    /// The key ID is the RFC 7638 thumbprint of the key, the one published in
+    /// the JWKS and set in the header of the tokens signed with the key.
+    ///
+    /// ### Parameters
+    ///
+    /// * `pem_data` - The PEM encoded RSA public key as bytes
+    ///
+    /// ### Returns
+    ///
+    /// The Base64URL encoded thumbprint, or an error if parsing fails
+    pub fn kid_from_pem(pem_data: &[u8]) -> Result<String> {
+        let public_key = Self::parse_rsa_public_pem(pem_data)?;
+        let n = BASE64_URL_SAFE_NO_PAD.encode(public_key.n().to_bytes_be());
+        let e = BASE64_URL_SAFE_NO_PAD.encode(public_key.e().to_bytes_be());
+        Self::calculate_jwk_thumbprint(&n, &e)
+    }
+
+    /// Parse an RSA public key in PKCS#1 (`RSA PUBLIC KEY`) or SPKI (`PUBLIC KEY`) PEM format
+    fn parse_rsa_public_pem(pem_data: &[u8]) -> Result<RsaPublicKey> {
+        let pem = std::str::from_utf8(pem_data)?;
+        DecodeRsaPublicKey::from_pkcs1_pem(pem)
+            .or_else(|_| DecodePublicKey::from_public_key_pem(pem))
+            .context("Failed to parse RSA public key from PEM")
+    }
+
     /// Create a JWK from an RSA public key
     ///
     /// Converts an RSA public key to a JWK representation with the necessary
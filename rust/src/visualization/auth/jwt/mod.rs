@@ -5,6 +5,7 @@
 mod claims;
 mod issuer;
 mod keys;
+mod remote_jwks;
 mod token_entry;
 mod token_map;
 mod validator;
@@ -13,6 +14,7 @@ mod validator;
 pub use claims::JwtClaims;
 pub use issuer::JwtIssuer;
 pub use keys::{JwkKeySet, JwtKeyConfig};
+pub use remote_jwks::RemoteJwks;
 pub use validator::{JwtValidator, UserSysInfo};
 
 use crate::config::AccessConfig;
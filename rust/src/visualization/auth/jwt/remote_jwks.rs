@@ -0,0 +1,242 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Remote JSON Web Key Set
+//!
+//! This module fetches and caches the JWKS published by an external identity
+//! provider, so that [`JwtValidator`](super::JwtValidator) can verify the RS256
+//! tokens it issues. The keys are selected by the key ID (`kid`) of the token
+//! header.
+//!
+//! Validation is synchronous, so the keys are never fetched while a token is
+//! validated: [`RemoteJwks::ensure_key`] is awaited beforehand by the request
+//! guards and refreshes the cache when it is stale or lacks the token's key.
+
+use anyhow::{anyhow, Context, Result};
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::DecodingKey;
+use log::{debug, warn};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// Minimum delay between two fetches triggered by token validations
+///
+/// Prevents tokens carrying random key IDs, or an unreachable identity
+/// provider, from triggering a fetch for every request.
+const MIN_REFETCH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Timeout of a JWKS request
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Keys fetched from the remote JWKS
+#[derive(Default)]
+struct JwksCache {
+    /// Decoding keys by key ID
+    keys: HashMap<String, DecodingKey>,
+    /// Time of the last successful fetch
+    fetched_at: Option<Instant>,
+    /// Time of the last fetch attempt, successful or not
+    attempted_at: Option<Instant>,
+}
+
+/// JWKS of an external identity provider, fetched and cached
+///
+/// The cache is refreshed when it is older than the refresh interval, or when
+/// a token names a key it does not hold, at most every 10 seconds. When a
+/// refresh fails, the keys already fetched are kept.
+///
+/// ### Example
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use rust_photoacoustic::config::AccessConfig;
+/// use rust_photoacoustic::visualization::auth::jwt::{JwtValidator, RemoteJwks};
+///
+/// async fn example(token: &str) {
+///     let remote = Arc::new(RemoteJwks::new(
+///         "https://idp.example.com/.well-known/jwks.json",
+///         Duration::from_secs(300),
+///     ));
+///     let validator = JwtValidator::new(None, None, AccessConfig::default())
+///         .unwrap()
+///         .with_remote_jwks(remote);
+///     validator.fetch_remote_key(token).await;
+///     let claims = validator.validate(token);
+/// }
+/// ```
+pub struct RemoteJwks {
+    /// URL of the JWKS document
+    url: String,
+    /// Maximum age of the cached keys
+    refresh_interval: Duration,
+    /// Expected issuer (`iss`) of the tokens verified with these keys, if any
+    issuer: Option<String>,
+    /// HTTP client used to fetch the JWKS
+    client: reqwest::Client,
+    /// Cached keys
+    cache: RwLock<JwksCache>,
+}
+
+impl RemoteJwks {
+    /// Create a remote JWKS, without fetching it
+    ///
+    /// ### Parameters
+    ///
+    /// * `url` - URL of the JWKS document (e.g. `https://idp.example.com/.well-known/jwks.json`)
+    /// * `refresh_interval` - Maximum age of the cached keys
+    pub fn new(url: impl Into<String>, refresh_interval: Duration) -> Self {
+        Self {
+            url: url.into(),
+            refresh_interval,
+            issuer: None,
+            client: reqwest::Client::builder()
+                .timeout(FETCH_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            cache: RwLock::new(JwksCache::default()),
+        }
+    }
+
+    /// Set the issuer of the tokens signed with these keys
+    ///
+    /// The tokens verified with a remote key must carry this issuer instead of
+    /// the one expected by the validator for local tokens.
+    ///
+    /// ### Parameters
+    ///
+    /// * `issuer` - The expected `iss` claim of the identity provider's tokens
+    ///
+    /// ### Returns
+    ///
+    /// Self with the issuer, allowing for method chaining
+    pub fn with_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuer = Some(issuer.into());
+        self
+    }
+
+    /// Expected issuer of the tokens signed with these keys, if any
+    pub fn issuer(&self) -> Option<&str> {
+        self.issuer.as_deref()
+    }
+
+    /// URL of the JWKS document
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Get the cached key with the given key ID
+    ///
+    /// ### Parameters
+    ///
+    /// * `kid` - Key ID from the token header
+    ///
+    /// ### Returns
+    ///
+    /// The decoding key, or `None` if the cache does not hold it
+    pub fn key(&self, kid: &str) -> Option<DecodingKey> {
+        self.cache.read().ok()?.keys.get(kid).cloned()
+    }
+
+    /// Key IDs of the cached keys, sorted
+    pub fn key_ids(&self) -> Vec<String> {
+        let mut kids: Vec<String> = self
+            .cache
+            .read()
+            .map(|cache| cache.keys.keys().cloned().collect())
+            .unwrap_or_default();
+        kids.sort();
+        kids
+    }
+
+    /// Check whether the cached keys are older than the refresh interval
+    ///
+    /// A JWKS never fetched successfully is stale.
+    pub fn is_stale(&self) -> bool {
+        self.cache
+            .read()
+            .ok()
+            .and_then(|cache| cache.fetched_at)
+            .is_none_or(|fetched_at| fetched_at.elapsed() >= self.refresh_interval)
+    }
+
+    /// Fetch the JWKS and replace the cached keys
+    ///
+    /// Keys that cannot be used for verification (e.g. encryption keys or
+    /// keys without `kid`) are skipped.
+    ///
+    /// ### Returns
+    ///
+    /// The number of keys cached
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if the document cannot be fetched or is not a JWKS.
+    /// The previously cached keys are kept.
+    pub async fn refresh(&self) -> Result<usize> {
+        if let Ok(mut cache) = self.cache.write() {
+            cache.attempted_at = Some(Instant::now());
+        }
+        let response = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .with_context(|| format!("Failed to fetch the JWKS from {}", self.url))?;
+        let jwks: JwkSet = response
+            .json()
+            .await
+            .with_context(|| format!("Invalid JWKS document at {}", self.url))?;
+
+        let mut keys = HashMap::new();
+        for jwk in &jwks.keys {
+            let Some(kid) = jwk.common.key_id.clone() else {
+                debug!("Skipping remote JWK without key ID");
+                continue;
+            };
+            match DecodingKey::from_jwk(jwk) {
+                Ok(key) => {
+                    keys.insert(kid, key);
+                }
+                Err(e) => debug!("Skipping remote JWK {}: {}", kid, e),
+            }
+        }
+
+        let count = keys.len();
+        let mut cache = self
+            .cache
+            .write()
+            .map_err(|_| anyhow!("Remote JWKS cache lock poisoned"))?;
+        cache.keys = keys;
+        cache.fetched_at = Some(Instant::now());
+        debug!("Fetched {} keys from the JWKS at {}", count, self.url);
+        Ok(count)
+    }
+
+    /// Make sure the cache is fresh and holds the key of a token, if possible
+    ///
+    /// Refreshes the cache when it is stale or lacks `kid`, unless a fetch was
+    /// attempted within the last 10 seconds. Fetch errors are logged: the token
+    /// is then validated against the keys already cached.
+    ///
+    /// ### Parameters
+    ///
+    /// * `kid` - Key ID from the token header
+    pub async fn ensure_key(&self, kid: &str) {
+        let recently_attempted = self
+            .cache
+            .read()
+            .ok()
+            .and_then(|cache| cache.attempted_at)
+            .is_some_and(|attempted_at| attempted_at.elapsed() < MIN_REFETCH_INTERVAL);
+        if recently_attempted || !(self.is_stale() || self.key(kid).is_none()) {
+            return;
+        }
+        if let Err(e) = self.refresh().await {
+            warn!("{:#}", e);
+        }
+    }
+}
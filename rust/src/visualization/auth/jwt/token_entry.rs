@@ -49,10 +49,14 @@ pub struct TokenEntry {
 
     /// Expiration time for the token
     ///
-    /// The time at which this token will expire and no longer be valid for use.
-    /// Both access and refresh tokens share the same expiration time in this implementation.
+    /// The time at which the access token will expire and no longer be valid for use.
     pub expiry: DateTime<Utc>,
 
+    /// Expiration time for the refresh token
+    ///
+    /// Equal to `expiry` unless the client has its own refresh token lifetime.
+    pub refresh_expiry: DateTime<Utc>,
+
     /// ID token claims stored for reference
     pub id_token_claims: Option<IdTokenClaims>,
 }
@@ -73,10 +77,22 @@ impl TokenEntry {
             refresh_token,
             grant,
             expiry,
+            refresh_expiry: expiry,
             id_token_claims,
         }
     }
 
+    /// Set the expiration time of the refresh token
+    pub fn with_refresh_expiry(mut self, refresh_expiry: DateTime<Utc>) -> Self {
+        self.refresh_expiry = refresh_expiry;
+        self
+    }
+
+    /// Check if the refresh token has expired
+    pub fn is_refresh_expired(&self) -> bool {
+        self.refresh_expiry < Utc::now()
+    }
+
     /// Check if the token has expired
     pub fn is_expired(&self) -> bool {
         self.expiry < Utc::now()
@@ -16,6 +16,7 @@ use std::sync::Arc;
 use url::Url;
 
 use super::claims::{IdTokenClaims, JwtClaims};
+use super::keys::JwkKeySet;
 use super::token_entry::TokenEntry;
 
 /// A custom JWT token issuer implementation
@@ -44,6 +45,21 @@ use super::token_entry::TokenEntry;
 ///
 /// This allows efficient lookup of tokens by either the access token or
 /// refresh token value.
+///
+/// ### Refresh Token Rotation
+///
+/// Every refresh consumes the presented refresh token and issues a new one.
+/// Consumed tokens are remembered in `rotated_refresh_tokens` until the end of
+/// their family's lifetime: presenting one again is treated as a replay (the
+/// token was probably stolen) and revokes the live token of the family, see
+/// [`JwtTokenMap::revoke_replayed_refresh_token`].
+///
+/// ### Key Rotation
+///
+/// RS256 tokens carry the key ID (`kid`) of their signing key in their
+/// header. [`JwtTokenMap::rotate_rs256_key`] replaces the signing key and
+/// keeps the previous public key in `previous_verification_keys`, so that the
+/// tokens it signed remain valid until they expire.
 pub struct JwtTokenMap {
     /// Map of access tokens
     ///
@@ -57,6 +73,17 @@ pub struct JwtTokenMap {
     /// Used during token refresh operations.
     pub refresh_tokens: HashMap<String, Arc<TokenEntry>>,
 
+    /// Refresh tokens consumed by a rotation
+    ///
+    /// Maps a consumed refresh token to the refresh token that replaced it and
+    /// to the expiry of the replacement, after which the entry is pruned.
+    pub rotated_refresh_tokens: HashMap<String, (Option<String>, DateTime<Utc>)>,
+
+    /// Refresh token lifetime of each client
+    ///
+    /// Clients without an entry get refresh tokens expiring with their access token.
+    pub refresh_token_lifetimes: HashMap<String, Duration>,
+
     /// JWT signing key
     ///
     /// The key used to sign JWT tokens. For symmetric algorithms like HS256,
@@ -71,6 +98,17 @@ pub struct JwtTokenMap {
     /// algorithms like RS256, this contains the public key.
     pub verification_key: DecodingKey,
 
+    /// Key ID (`kid`) of the signing key
+    ///
+    /// Set in the header of the issued tokens. Only RS256 keys have one: it is
+    /// the RFC 7638 thumbprint of the public key, as published in the JWKS.
+    pub signing_kid: Option<String>,
+
+    /// Verification keys of the previous signing keys, by key ID
+    ///
+    /// Tokens whose header names one of these keys are verified with it.
+    pub previous_verification_keys: HashMap<String, DecodingKey>,
+
     /// Random generator for refresh tokens
     ///
     /// Generates cryptographically secure random tokens for use as refresh tokens.
@@ -106,14 +144,25 @@ pub struct JwtTokenMap {
     pub claims: HashMap<String, Value>,
 }
 
+/// Key ID of an RS256 public key, `None` if the key cannot be parsed
+fn rs256_kid(public_key_pem: &[u8]) -> Option<String> {
+    JwkKeySet::kid_from_pem(public_key_pem)
+        .map_err(|e| log::warn!("RS256 tokens will be issued without key ID: {}", e))
+        .ok()
+}
+
 impl JwtTokenMap {
     /// Create a new JWT token issuer with the given secret key
     pub fn new(secret: &[u8]) -> Self {
         JwtTokenMap {
             access_tokens: HashMap::new(),
             refresh_tokens: HashMap::new(),
+            rotated_refresh_tokens: HashMap::new(),
+            refresh_token_lifetimes: HashMap::new(),
             signing_key: EncodingKey::from_secret(secret),
             verification_key: DecodingKey::from_secret(secret),
+            signing_kid: None,
+            previous_verification_keys: HashMap::new(),
             refresh_generator: RandomGenerator::new(16),
             token_duration: Some(Duration::hours(1)), // Default 1 hour
             issuer: "LaserSmartServer".to_string(),
@@ -132,8 +181,12 @@ impl JwtTokenMap {
         JwtTokenMap {
             access_tokens: HashMap::new(),
             refresh_tokens: HashMap::new(),
+            rotated_refresh_tokens: HashMap::new(),
+            refresh_token_lifetimes: HashMap::new(),
             signing_key: encoding_key,
             verification_key: decoding_key,
+            signing_kid: None,
+            previous_verification_keys: HashMap::new(),
             refresh_generator: RandomGenerator::new(16),
             token_duration: Some(Duration::hours(1)), // Default 1 hour
             issuer: "LaserSmartServer".to_string(),
@@ -159,12 +212,17 @@ impl JwtTokenMap {
     ) -> Result<Self, jsonwebtoken::errors::Error> {
         let encoding_key = EncodingKey::from_rsa_pem(private_key_pem)?;
         let decoding_key = DecodingKey::from_rsa_pem(public_key_pem)?;
+        let signing_kid = rs256_kid(public_key_pem);
 
         Ok(JwtTokenMap {
             access_tokens: HashMap::new(),
             refresh_tokens: HashMap::new(),
+            rotated_refresh_tokens: HashMap::new(),
+            refresh_token_lifetimes: HashMap::new(),
             signing_key: encoding_key,
             verification_key: decoding_key,
+            signing_kid,
+            previous_verification_keys: HashMap::new(),
             refresh_generator: RandomGenerator::new(16),
             token_duration: Some(Duration::hours(1)), // Default 1 hour
             issuer: "LaserSmartServer".to_string(),
@@ -174,6 +232,29 @@ impl JwtTokenMap {
         })
     }
 
+    /// Create a new JWT token issuer with EdDSA algorithm using PEM encoded Ed25519 keys
+    ///
+    /// ### Parameters
+    ///
+    /// * `private_key_pem` - PKCS#8 PEM encoded Ed25519 private key
+    /// * `public_key_pem` - PEM encoded Ed25519 public key
+    ///
+    /// ### Returns
+    ///
+    /// A new JwtTokenMap configured to use EdDSA algorithm with the provided keys
+    pub fn with_ed25519_pem(
+        private_key_pem: &[u8],
+        public_key_pem: &[u8],
+    ) -> Result<Self, jsonwebtoken::errors::Error> {
+        let encoding_key = EncodingKey::from_ed_pem(private_key_pem)?;
+        let decoding_key = DecodingKey::from_ed_pem(public_key_pem)?;
+        Ok(Self::with_keys(
+            Algorithm::EdDSA,
+            encoding_key,
+            decoding_key,
+        ))
+    }
+
     /// Sets the JWT signing algorithm
     pub fn with_algorithm(mut self, algorithm: Algorithm) -> Self {
         self.algorithm = algorithm;
@@ -192,6 +273,152 @@ impl JwtTokenMap {
         self
     }
 
+    /// Replace the RS256 signing key, keeping the current one for verification
+    ///
+    /// Tokens are signed with the new key from now on. The current public key
+    /// joins the previous verification keys, so that the tokens it signed
+    /// stay valid until they expire or [`JwtTokenMap::remove_verification_key`]
+    /// retires it. A current key without key ID (HS256) cannot be told apart
+    /// from the new one and is dropped.
+    ///
+    /// ### Parameters
+    ///
+    /// * `private_key_pem` - PEM encoded private key of the new signing key
+    /// * `public_key_pem` - PEM encoded public key of the new signing key
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if a key cannot be parsed, the current key is kept
+    pub fn rotate_rs256_key(
+        &mut self,
+        private_key_pem: &[u8],
+        public_key_pem: &[u8],
+    ) -> Result<(), jsonwebtoken::errors::Error> {
+        let encoding_key = EncodingKey::from_rsa_pem(private_key_pem)?;
+        let decoding_key = DecodingKey::from_rsa_pem(public_key_pem)?;
+
+        let previous_key = std::mem::replace(&mut self.verification_key, decoding_key);
+        if let Some(previous_kid) = self.signing_kid.take() {
+            self.previous_verification_keys
+                .insert(previous_kid, previous_key);
+        }
+        self.signing_key = encoding_key;
+        self.signing_kid = rs256_kid(public_key_pem);
+        self.algorithm = Algorithm::RS256;
+        if let Some(kid) = &self.signing_kid {
+            // The key may come back after having been rotated out
+            self.previous_verification_keys.remove(kid);
+        }
+        Ok(())
+    }
+
+    /// Accept the tokens signed with another RS256 key
+    ///
+    /// Used at startup for the keys rotated out before a restart.
+    ///
+    /// ### Parameters
+    ///
+    /// * `public_key_pem` - PEM encoded public key
+    ///
+    /// ### Returns
+    ///
+    /// The key ID of the key
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if the key cannot be parsed
+    pub fn add_verification_key(&mut self, public_key_pem: &[u8]) -> anyhow::Result<String> {
+        let decoding_key = DecodingKey::from_rsa_pem(public_key_pem)?;
+        let kid = JwkKeySet::kid_from_pem(public_key_pem)?;
+        if self.signing_kid.as_ref() != Some(&kid) {
+            self.previous_verification_keys
+                .insert(kid.clone(), decoding_key);
+        }
+        Ok(kid)
+    }
+
+    /// Stop accepting the tokens signed with a previous key
+    ///
+    /// ### Returns
+    ///
+    /// `true` if the key was one of the previous verification keys
+    pub fn remove_verification_key(&mut self, kid: &str) -> bool {
+        self.previous_verification_keys.remove(kid).is_some()
+    }
+
+    /// Select the key verifying a token from the key ID in its header
+    ///
+    /// Tokens without key ID, issued before key IDs were introduced or signed
+    /// with HS256, are verified with the current key.
+    ///
+    /// ### Returns
+    ///
+    /// The verification key, or `None` if the header cannot be decoded or
+    /// names an unknown key
+    pub fn verification_key_for(&self, token: &str) -> Option<&DecodingKey> {
+        let header = jsonwebtoken::decode_header(token).ok()?;
+        match header.kid {
+            Some(kid) if self.signing_kid.as_ref() != Some(&kid) => {
+                self.previous_verification_keys.get(&kid)
+            }
+            _ => Some(&self.verification_key),
+        }
+    }
+
+    /// Header of the issued tokens, naming the signing key
+    fn token_header(&self) -> Header {
+        let mut header = Header::new(self.algorithm);
+        header.kid = self.signing_kid.clone();
+        header
+    }
+
+    /// Set the refresh token lifetime of each client, replacing the previous ones
+    pub fn set_refresh_token_lifetimes(&mut self, lifetimes: HashMap<String, Duration>) {
+        self.refresh_token_lifetimes = lifetimes;
+    }
+
+    /// Compute the expiry of a refresh token issued now to `client_id`
+    fn refresh_expiry(
+        &self,
+        client_id: &str,
+        now: DateTime<Utc>,
+        access_expiry: DateTime<Utc>,
+    ) -> DateTime<Utc> {
+        self.refresh_token_lifetimes
+            .get(client_id)
+            .map(|lifetime| now + *lifetime)
+            .unwrap_or(access_expiry)
+    }
+
+    /// Revoke a refresh token family if `refresh` is an already rotated token
+    ///
+    /// A rotated refresh token must never be presented again. When it is, the
+    /// chain of rotations is followed up to the live refresh token, which is
+    /// revoked together with its access token so that neither the attacker nor
+    /// the legitimate client can keep refreshing. The client has to go through
+    /// the authorization flow again.
+    ///
+    /// ### Returns
+    ///
+    /// `true` if `refresh` was a replayed token and its family was revoked
+    pub fn revoke_replayed_refresh_token(&mut self, refresh: &str) -> bool {
+        let Some((mut successor, _)) = self.rotated_refresh_tokens.remove(refresh) else {
+            return false;
+        };
+
+        while let Some(token) = successor {
+            if let Some(entry) = self.refresh_tokens.remove(&token) {
+                self.access_tokens.remove(&entry.access_token);
+            }
+            successor = self
+                .rotated_refresh_tokens
+                .remove(&token)
+                .and_then(|(next, _)| next);
+        }
+
+        true
+    }
+
     /// Add user information to token claims that will be included in the next issued token
     pub fn add_user_claims(&mut self, username: &str, permissions: &[String]) -> &mut Self {
         // Clear previous user claims to avoid accumulation
@@ -405,7 +632,7 @@ impl Issuer for JwtTokenMap {
         let claims = self.create_access_token_claims(&grant, now, grant.until);
 
         // Create JWT token with specific algorithm
-        let header = Header::new(self.algorithm);
+        let header = self.token_header();
         let access_token = encode(&header, &claims, &self.signing_key).map_err(|_| ())?;
 
         // Generate a refresh token using random generator
@@ -416,21 +643,25 @@ impl Issuer for JwtTokenMap {
         let id_token_claims = self.create_id_token_claims(&grant, now, grant.until);
         let id_token = if let Some(claims) = &id_token_claims {
             // Create ID token with specific algorithm
-            let id_header = Header::new(self.algorithm);
+            let id_header = self.token_header();
             Some(encode(&id_header, claims, &self.signing_key).map_err(|_| ())?)
         } else {
             None // No ID token if 'openid' scope is not requested
         };
 
         // Store the token
-        let token_entry = Arc::new(TokenEntry::new(
-            access_token.clone(),
-            id_token.clone(),
-            refresh_token.clone(),
-            grant.clone(),
-            grant.until,
-            id_token_claims,
-        ));
+        let refresh_expiry = self.refresh_expiry(&grant.client_id, now, grant.until);
+        let token_entry = Arc::new(
+            TokenEntry::new(
+                access_token.clone(),
+                id_token.clone(),
+                refresh_token.clone(),
+                grant.clone(),
+                grant.until,
+                id_token_claims,
+            )
+            .with_refresh_expiry(refresh_expiry),
+        );
 
         // Add to maps
         self.access_tokens
@@ -460,9 +691,10 @@ impl Issuer for JwtTokenMap {
         let (old_access_token, old_refresh_token) = {
             let token_entry = self.refresh_tokens.get(refresh).ok_or(())?;
 
-            // Verify that the grant matches
+            // Verify that the grant matches and the refresh token is still valid
             if token_entry.grant.client_id != grant.client_id
                 || token_entry.grant.owner_id != grant.owner_id
+                || token_entry.is_refresh_expired()
             {
                 return Err(());
             }
@@ -484,7 +716,7 @@ impl Issuer for JwtTokenMap {
         let claims = self.create_access_token_claims(&grant, now, grant.until);
 
         // Create new JWT token with specific algorithm
-        let header = Header::new(self.algorithm);
+        let header = self.token_header();
         let new_access_token = encode(&header, &claims, &self.signing_key).map_err(|_| ())?;
 
         // Generate a new refresh token
@@ -497,15 +729,28 @@ impl Issuer for JwtTokenMap {
             self.refresh_tokens.remove(old_refresh);
         }
 
+        // Remember the consumed refresh token to detect replays, forgetting
+        // rotations whose family has expired
+        let refresh_expiry = self.refresh_expiry(&grant.client_id, now, grant.until);
+        self.rotated_refresh_tokens
+            .retain(|_, (_, until)| *until >= now);
+        if let Some(old_refresh) = old_refresh_token {
+            self.rotated_refresh_tokens
+                .insert(old_refresh, (new_refresh_token.clone(), refresh_expiry));
+        }
+
         // Create and store the new token
-        let new_token_entry = Arc::new(TokenEntry::new(
-            new_access_token.clone(),
-            None, // ID token not generated here
-            new_refresh_token.clone(),
-            grant.clone(),
-            grant.until,
-            None,
-        ));
+        let new_token_entry = Arc::new(
+            TokenEntry::new(
+                new_access_token.clone(),
+                None, // ID token not generated here
+                new_refresh_token.clone(),
+                grant.clone(),
+                grant.until,
+                None,
+            )
+            .with_refresh_expiry(refresh_expiry),
+        );
 
         // Add to maps
         self.access_tokens
@@ -547,7 +792,11 @@ impl Issuer for JwtTokenMap {
         // We should extract the audience from the token first and then validate it
         // This approach is needed because we may not know the audience in advance
 
-        let token_data = match decode::<JwtClaims>(token, &self.verification_key, &validation) {
+        let Some(verification_key) = self.verification_key_for(token) else {
+            log::debug!("JWT signed with an unknown key");
+            return Ok(None);
+        };
+        let token_data = match decode::<JwtClaims>(token, verification_key, &validation) {
             Ok(data) => data,
             Err(err) => {
                 log::debug!("JWT validation failed: {:?}", err);
@@ -602,7 +851,7 @@ impl Issuer for JwtTokenMap {
         match self.refresh_tokens.get(token) {
             Some(entry) => {
                 // Check if the token has expired
-                if entry.expiry < Utc::now() {
+                if entry.is_refresh_expired() {
                     Ok(None)
                 } else {
                     Ok(Some(entry.grant.clone()))
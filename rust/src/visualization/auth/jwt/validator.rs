@@ -49,11 +49,14 @@
 
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, TimeZone, Utc};
-use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Header, Validation};
 use log::debug;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 
+use super::keys::JwkKeySet;
+use super::remote_jwks::RemoteJwks;
 use crate::config::AccessConfig;
 
 /// Custom JWT claims structure matching the one in jwt.rs
@@ -131,7 +134,8 @@ pub struct JwtClaims {
 ///
 /// ### Features
 ///
-/// - Support for different JWT algorithms (HS256, RS256, etc.)
+/// - Support for different JWT algorithms (HS256, RS256, EdDSA)
+/// - Verification of tokens from an external identity provider through its JWKS
 /// - Validation of token expiration and activation time
 /// - Optional verification of issuer and audience claims
 /// - Extraction of user information from validated tokens
@@ -181,6 +185,12 @@ pub struct JwtValidator {
     hmac_key: Option<DecodingKey>,
     /// Optional RS256 public key
     rs256_key: Option<DecodingKey>,
+    /// RS256 public keys by key ID (`kid`), the current key and the previous ones
+    rs256_keys: HashMap<String, DecodingKey>,
+    /// Optional Ed25519 public key for EdDSA
+    ed25519_key: Option<DecodingKey>,
+    /// Optional JWKS of an external identity provider, for the RS256 key IDs not found locally
+    remote_jwks: Option<Arc<RemoteJwks>>,
     /// The expected issuer of the token, if any
     expected_issuer: Option<String>,
 
@@ -199,20 +209,117 @@ impl JwtValidator {
         access_config: AccessConfig,
     ) -> Result<Self, jsonwebtoken::errors::Error> {
         let hmac_key = hmac_secret.map(DecodingKey::from_secret);
+        let mut rs256_keys = HashMap::new();
         let rs256_key = if let Some(pem) = rs256_public_key_pem {
-            Some(DecodingKey::from_rsa_pem(pem)?)
+            let key = DecodingKey::from_rsa_pem(pem)?;
+            if let Ok(kid) = JwkKeySet::kid_from_pem(pem) {
+                rs256_keys.insert(kid, key.clone());
+            }
+            Some(key)
         } else {
             None
         };
         Ok(JwtValidator {
             hmac_key,
             rs256_key,
+            rs256_keys,
+            ed25519_key: None,
+            remote_jwks: None,
             expected_issuer: None,
             expected_audience: None,
             access_config: access_config,
         })
     }
 
+    /// Also accept the RS256 tokens signed with another key
+    ///
+    /// During a key rotation, the public keys of the previous signing keys are
+    /// added here, so that the tokens they signed stay valid until they
+    /// expire. Tokens are matched with their key through the key ID (`kid`)
+    /// of their header.
+    ///
+    /// ### Parameters
+    ///
+    /// * `public_key_pem` - PEM encoded RSA public key
+    ///
+    /// ### Returns
+    ///
+    /// Self with the additional key, allowing for method chaining
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if the key cannot be parsed
+    pub fn with_rs256_public_key(mut self, public_key_pem: &[u8]) -> Result<Self> {
+        let key = DecodingKey::from_rsa_pem(public_key_pem)
+            .map_err(|e| anyhow!("Invalid RS256 public key: {}", e))?;
+        let kid = JwkKeySet::kid_from_pem(public_key_pem)?;
+        self.rs256_keys.insert(kid, key);
+        Ok(self)
+    }
+
+    /// Also accept the EdDSA tokens signed with an Ed25519 key
+    ///
+    /// ### Parameters
+    ///
+    /// * `public_key_pem` - PEM encoded Ed25519 public key
+    ///
+    /// ### Returns
+    ///
+    /// Self with the Ed25519 key, allowing for method chaining
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if the key cannot be parsed
+    pub fn with_ed25519_public_key(mut self, public_key_pem: &[u8]) -> Result<Self> {
+        let key = DecodingKey::from_ed_pem(public_key_pem)
+            .map_err(|e| anyhow!("Invalid Ed25519 public key: {}", e))?;
+        self.ed25519_key = Some(key);
+        Ok(self)
+    }
+
+    /// Also accept the RS256 tokens signed with the keys of a remote JWKS
+    ///
+    /// Tokens issued by an external identity provider are verified with the
+    /// key of its JWKS named by their key ID (`kid`). Local keys take
+    /// precedence. The remote keys are fetched by
+    /// [`fetch_remote_key`](Self::fetch_remote_key), which must be awaited
+    /// before [`validate`](Self::validate).
+    ///
+    /// ### Parameters
+    ///
+    /// * `remote_jwks` - The remote JWKS, shared to keep its cache across validators
+    ///
+    /// ### Returns
+    ///
+    /// Self with the remote JWKS, allowing for method chaining
+    pub fn with_remote_jwks(mut self, remote_jwks: Arc<RemoteJwks>) -> Self {
+        self.remote_jwks = Some(remote_jwks);
+        self
+    }
+
+    /// Fetch the remote key a token is signed with, if needed
+    ///
+    /// Does nothing without remote JWKS, for tokens without key ID and for
+    /// tokens signed with a local key. Otherwise refreshes the remote JWKS
+    /// cache when it is stale or lacks the key (see [`RemoteJwks::ensure_key`]).
+    ///
+    /// ### Parameters
+    ///
+    /// * `token` - The JWT token string about to be validated
+    pub async fn fetch_remote_key(&self, token: &str) {
+        let Some(remote_jwks) = &self.remote_jwks else {
+            return;
+        };
+        let Ok(header) = jsonwebtoken::decode_header(token) else {
+            return;
+        };
+        if let Some(kid) = header.kid {
+            if !self.rs256_keys.contains_key(&kid) {
+                remote_jwks.ensure_key(&kid).await;
+            }
+        }
+    }
+
     /// Set the expected issuer name
     ///
     /// Configures the validator to verify that the token's "iss" claim
@@ -272,8 +379,12 @@ impl JwtValidator {
     /// Validates the JWT token by:
     /// - Verifying the signature using the configured key and algorithm
     /// - Checking that the token is not expired (exp claim)
-    /// - Verifying that the token is active (nbf claim)
-    /// - Comparing issuer and audience if configured
+    /// - Verifying that the token is active (nbf claim) and not issued in the future (iat claim)
+    /// - Comparing the issuer with the expected one, `iss` of the access configuration by default
+    ///   (`LaserSmartServer` when unset)
+    /// - Comparing the audience with the expected one, the configured clients by default
+    ///
+    /// The time claims tolerate the `clock_skew_seconds` of the access configuration.
     ///
     /// ### Parameters
     ///
@@ -288,10 +399,12 @@ impl JwtValidator {
     ///
     /// This function will return an error if:
     /// - The token's signature is invalid
-    /// - The token has expired (current time > exp claim)
-    /// - The token is not yet valid (current time < nbf claim)
-    /// - The token's issuer doesn't match the expected issuer (if configured)
-    /// - The token's audience doesn't match the expected audience (if configured)
+    /// - The token has expired (current time > exp claim + clock skew)
+    /// - The token is not yet valid (current time < nbf claim - clock skew)
+    /// - The token is issued in the future (current time < iat claim - clock skew)
+    /// - The token lacks the exp, iss or aud claim
+    /// - The token's issuer doesn't match the expected issuer
+    /// - The token's audience doesn't match the expected audience
     /// - The token contains invalid claim values (like malformed timestamps)
     ///
     /// ### Examples
@@ -307,33 +420,20 @@ impl JwtValidator {
         // Parse the header to determine the algorithm
         let header = jsonwebtoken::decode_header(token)
             .map_err(|e| anyhow!("Failed to decode JWT header: {}", e))?;
-        let alg = header.alg;
-        let (key, algorithm) = match alg {
-            Algorithm::HS256 => {
-                let key = self
-                    .hmac_key
-                    .as_ref()
-                    .ok_or_else(|| anyhow!("HS256 key not configured"))?;
-                debug!("Using HS256 key for validation");
-                (key, Algorithm::HS256)
-            }
-            Algorithm::RS256 => {
-                let key = self
-                    .rs256_key
-                    .as_ref()
-                    .ok_or_else(|| anyhow!("RS256 key not configured"))?;
-                debug!("Using RS256 key for validation");
-                (key, Algorithm::RS256)
-            }
-            _ => return Err(anyhow!("Unsupported JWT algorithm: {:?}", alg)),
-        };
+        let (key, algorithm, remote_issuer) = self.decoding_key(&header)?;
+        let clock_skew = self.access_config.clock_skew_seconds;
         let mut validation = Validation::new(algorithm);
         validation.validate_exp = true;
         validation.validate_nbf = true;
-        if let Some(ref issuer) = self.expected_issuer {
-            debug!("Validating issuer: {}", issuer);
-            validation.set_issuer(&[issuer]);
-        }
+        validation.leeway = clock_skew;
+        validation.set_required_spec_claims(&["exp", "iss", "aud"]);
+        // Without an explicit issuer, the tokens must come from the configured one
+        let issuer = remote_issuer
+            .or(self.expected_issuer.as_deref())
+            .or(self.access_config.iss.as_deref())
+            .unwrap_or("LaserSmartServer");
+        debug!("Validating issuer: {}", issuer);
+        validation.set_issuer(&[issuer]);
 
         // Audience validation: prefer the statically-configured expected_audience
         // (set at startup via `with_audience`) over the dynamic client list.
@@ -363,21 +463,121 @@ impl JwtValidator {
             }
         }
 
-        let token_data = decode::<JwtClaims>(token, key, &validation).map_err(|e| {
+        let token_data = decode::<JwtClaims>(token, &key, &validation).map_err(|e| {
             debug!("JWT validation error: {}", e);
             anyhow!("JWT validation failed: {}", e)
         })?;
         let now = Utc::now();
+        let clock_skew = chrono::Duration::seconds(clock_skew as i64);
         let exp_time = Utc
             .timestamp_opt(token_data.claims.exp, 0)
             .single()
             .ok_or_else(|| anyhow!("Invalid expiry time in token"))?;
-        if exp_time < now {
+        if exp_time + clock_skew < now {
             return Err(anyhow!("Token has expired"));
         }
+        let issued_at = Utc
+            .timestamp_opt(token_data.claims.iat, 0)
+            .single()
+            .ok_or_else(|| anyhow!("Invalid issued at time in token"))?;
+        if issued_at > now + clock_skew {
+            return Err(anyhow!("Token issued in the future"));
+        }
         Ok(token_data.claims)
     }
 
+    /// Select the key verifying the signature of a token
+    ///
+    /// ### Parameters
+    ///
+    /// * `header` - The decoded header of the token
+    ///
+    /// ### Returns
+    ///
+    /// The key, its algorithm and, when the key comes from the JWKS of an
+    /// external identity provider, the issuer of that provider
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if the algorithm is unsupported or no key matches
+    fn decoding_key(&self, header: &Header) -> Result<(DecodingKey, Algorithm, Option<&str>)> {
+        // Issuer of the remote identity provider, when the key comes from its JWKS
+        let mut remote_issuer = None;
+        let (key, algorithm) = match header.alg {
+            Algorithm::HS256 => {
+                let key = self
+                    .hmac_key
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("HS256 key not configured"))?;
+                debug!("Using HS256 key for validation");
+                (key.clone(), Algorithm::HS256)
+            }
+            Algorithm::RS256 => {
+                // Tokens without key ID predate key rotation: use the current key
+                let key = match header.kid.as_deref() {
+                    Some(kid) => match self.rs256_keys.get(kid) {
+                        Some(key) => key.clone(),
+                        None => {
+                            let remote_jwks = self
+                                .remote_jwks
+                                .as_ref()
+                                .ok_or_else(|| anyhow!("Unknown RS256 key ID: {}", kid))?;
+                            let key = remote_jwks
+                                .key(kid)
+                                .ok_or_else(|| anyhow!("Unknown RS256 key ID: {}", kid))?;
+                            remote_issuer = remote_jwks.issuer();
+                            key
+                        }
+                    },
+                    None => self
+                        .rs256_key
+                        .clone()
+                        .ok_or_else(|| anyhow!("RS256 key not configured"))?,
+                };
+                debug!("Using RS256 key {:?} for validation", header.kid);
+                (key, Algorithm::RS256)
+            }
+            Algorithm::EdDSA => {
+                let key = self
+                    .ed25519_key
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("Ed25519 key not configured"))?;
+                debug!("Using Ed25519 key for validation");
+                (key.clone(), Algorithm::EdDSA)
+            }
+            _ => return Err(anyhow!("Unsupported JWT algorithm: {:?}", header.alg)),
+        };
+        Ok((key, algorithm, remote_issuer))
+    }
+
+    /// Verify the signature of a token, ignoring its claims
+    ///
+    /// Unlike [`validate`](Self::validate), expired tokens and tokens of
+    /// another issuer or audience pass, so that tools can tell a forged token
+    /// from an outdated one.
+    ///
+    /// ### Parameters
+    ///
+    /// * `token` - The JWT token string to verify
+    ///
+    /// ### Errors
+    ///
+    /// Returns an error if the token is malformed, no key matches or the
+    /// signature is invalid
+    pub fn verify_signature(&self, token: &str) -> Result<()> {
+        let header = jsonwebtoken::decode_header(token)
+            .map_err(|e| anyhow!("Failed to decode JWT header: {}", e))?;
+        let (key, algorithm, _) = self.decoding_key(&header)?;
+        let mut validation = Validation::new(algorithm);
+        validation.validate_exp = false;
+        validation.validate_nbf = false;
+        validation.validate_aud = false;
+        validation.required_spec_claims.clear();
+        decode::<serde_json::Value>(token, &key, &validation)
+            .map_err(|e| anyhow!("JWT signature verification failed: {}", e))?;
+        Ok(())
+    }
+
     /// Extract user information from a validated token
     ///
     /// This method validates the token and converts the JWT claims into a more
@@ -433,7 +633,7 @@ impl JwtValidator {
             }
         }
 
-        let permissions = user.permissions.clone();
+        let permissions = access_config.effective_permissions(&user);
 
         Ok(UserSysInfo {
             user_id: claims.sub,
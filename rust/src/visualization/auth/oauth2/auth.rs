@@ -25,7 +25,7 @@ use crate::visualization::pwhash;
 /// 1. **User Lookup**: Searches for the username in the access configuration
 /// 2. **Hash Decoding**: Decodes the stored base64-encoded password hash
 /// 3. **Format Cleanup**: Removes trailing newlines/carriage returns from the hash
-/// 4. **Hash Verification**: Uses `pwhash::unix::verify` to check the password
+/// 4. **Hash Verification**: Uses [`pwhash::verify`] to check the password
 ///
 /// ### Supported Hash Formats
 ///
@@ -39,10 +39,9 @@ use crate::visualization::pwhash;
 ///
 /// ### Security Features
 ///
-/// - **Constant-time comparison**: Uses secure hash verification functions
+/// - **Constant-time comparison**: The computed and stored hashes are compared in constant time
 /// - **Salt protection**: Leverages salted hashes to prevent rainbow table attacks  
-/// - **Early termination**: Stops checking once the correct user is found
-/// - **No timing attacks**: Hash verification timing is consistent
+/// - **No user enumeration**: Unknown users cost a hash computation as well
 ///
 /// ### Parameters
 ///
@@ -83,38 +82,78 @@ use crate::visualization::pwhash;
 /// ### Related Functions
 ///
 /// - [`User::new`] - Creates new user objects
-/// - [`pwhash::unix::verify`] - The underlying password verification function
+/// - [`validate_user_with_rehash`] - Also upgrades weak password hashes
+/// - [`pwhash::verify`] - The underlying password verification function
 pub fn validate_user(username: &str, password: &str, access_config: &AccessConfig) -> Option<User> {
-    for user in &access_config.users {
-        if user.user == username {
-            // Decode the base64 password hash
-            if let Ok(hash_bytes) = base64::engine::general_purpose::STANDARD.decode(&user.pass) {
-                // If last byte is \n, remove it
-                let hash_bytes = if hash_bytes.last() == Some(&b'\n') {
-                    &hash_bytes[..hash_bytes.len() - 1]
-                } else {
-                    &hash_bytes
-                };
-                // if last byte is \r, remove it
-                let hash_bytes = if hash_bytes.last() == Some(&b'\r') {
-                    &hash_bytes[..hash_bytes.len() - 1]
-                } else {
-                    hash_bytes
-                };
-                if let Ok(stored_hash) = String::from_utf8(hash_bytes.to_vec()) {
-                    // Use pwhash to verify the password
-                    // The stored hash is in the format $algo$salt$hash
-                    debug!(
-                        "Verifying password for user: {} hash: {}",
-                        username, stored_hash
-                    );
-                    if pwhash::verify(password, &stored_hash) {
-                        return Some(user.clone());
-                    }
-                }
-            }
-            break; // Username matched but password didn't, don't check other users
-        }
+    validate_user_with_rehash(username, password, access_config).map(|(user, _)| user)
+}
+
+/// Validate user credentials and rehash the password if its hash is too weak
+///
+/// Same as [`validate_user`], but when the stored hash uses a weaker algorithm
+/// or fewer rounds than [`AccessConfig::password_hash`], the password is
+/// hashed again with the configured cost. The caller stores the new hash so
+/// that the next logins use it.
+///
+/// Unknown users and invalid stored hashes still cost a hash computation, so
+/// that the response time does not reveal whether a username exists.
+///
+/// ### Parameters
+///
+/// * `username` - The username to authenticate
+/// * `password` - The plaintext password to verify
+/// * `access_config` - The access configuration containing user credentials
+///
+/// ### Returns
+///
+/// * `Some((user, None))` - If authentication succeeds and the hash is up to date
+/// * `Some((user, Some(pass)))` - If authentication succeeds, with the new
+///   base64-encoded hash to store in [`User::pass`]
+/// * `None` - If authentication fails
+pub fn validate_user_with_rehash(
+    username: &str,
+    password: &str,
+    access_config: &AccessConfig,
+) -> Option<(User, Option<String>)> {
+    let stored = access_config
+        .users
+        .iter()
+        .find(|user| user.user == username)
+        .and_then(|user| Some((user, decode_password_hash(&user.pass)?)));
+    let Some((user, stored_hash)) = stored else {
+        pwhash::dummy_verify(password, &access_config.password_hash);
+        return None;
+    };
+
+    // The stored hash is in the format $algo$salt$hash
+    debug!("Verifying password for user: {}", username);
+    let verification =
+        pwhash::verify_and_upgrade(password, &stored_hash, &access_config.password_hash);
+    if !verification.valid {
+        return None;
     }
-    None
+    let upgraded_pass = verification
+        .upgraded_hash
+        .map(|hash| base64::engine::general_purpose::STANDARD.encode(hash));
+    Some((user.clone(), upgraded_pass))
+}
+
+/// Decode a base64-encoded password hash, without trailing line break
+fn decode_password_hash(pass: &str) -> Option<String> {
+    let hash_bytes = base64::engine::general_purpose::STANDARD
+        .decode(pass)
+        .ok()?;
+    // If last byte is \n, remove it
+    let hash_bytes = if hash_bytes.last() == Some(&b'\n') {
+        &hash_bytes[..hash_bytes.len() - 1]
+    } else {
+        &hash_bytes
+    };
+    // if last byte is \r, remove it
+    let hash_bytes = if hash_bytes.last() == Some(&b'\r') {
+        &hash_bytes[..hash_bytes.len() - 1]
+    } else {
+        hash_bytes
+    };
+    String::from_utf8(hash_bytes.to_vec()).ok()
 }
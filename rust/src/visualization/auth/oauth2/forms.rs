@@ -117,6 +117,7 @@ impl<'r> FromRequest<'r> for AuthenticatedUser {
 ///     user: "alice".to_string(),
 ///     pass: "".to_string(), // Password not included in session
 ///     permissions: vec!["read:api".to_string(), "write:api".to_string()],
+///     roles: vec![],
 ///     email: None,
 ///     name: None,
 /// };
@@ -224,6 +225,7 @@ pub fn decode_user_session(cookie_value: &str) -> Option<User> {
             user: username.to_string(),
             pass: String::new(), // Password is not stored in session
             permissions,
+            roles: vec![],
             email: None,
             name: None,
         })
@@ -8,12 +8,16 @@
 //! including authorization, token exchange, refresh, and user info.
 
 use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::Path;
 use std::sync::Arc;
 
-use log::debug;
-use oxide_auth::endpoint::{Solicitation, WebRequest};
+use log::{debug, info, warn};
+use oxide_auth::endpoint::{AccessTokenFlow, AuthorizationFlow, Solicitation, WebRequest};
 use oxide_auth::frontends::simple::endpoint::FnSolicitor;
+use oxide_auth::frontends::simple::extensions::{AddonList, Extended, Pkce};
 use oxide_auth_rocket::{OAuthFailure, OAuthRequest, OAuthResponse};
+use rocket::either::Either;
 use rocket::form::Form;
 use rocket::http::{Cookie, CookieJar, Status};
 use rocket::serde::json::Json;
@@ -23,9 +27,10 @@ use tokio::sync::RwLock;
 
 use super::consent::{consent_decision, consent_form};
 use super::forms::{encode_user_session, login_page_html, AuthForm, AuthenticatedUser};
+use super::lockout::{LockedOut, LoginLockout};
 use super::state::OxideState;
-use crate::config::Config;
-use crate::visualization::auth::oauth2::validate_user;
+use crate::config::{Config, ConfigFilePath, User};
+use crate::visualization::auth::oauth2::validate_user_with_rehash;
 use crate::visualization::auth::OAuthBearer;
 use crate::visualization::user_info_reponse::UserInfoResponse;
 
@@ -49,6 +54,12 @@ use crate::visualization::user_info_reponse::UserInfoResponse;
 /// - `scope`: Requested permission scopes
 /// - `state`: Optional state for CSRF protection
 ///
+/// PKCE (RFC 7636) parameters:
+/// - `code_challenge`: Challenge derived from the client's `code_verifier`
+/// - `code_challenge_method`: `S256`, the `plain` method is refused
+///
+/// Clients configured with `require_pkce` must send a challenge.
+///
 /// ### Returns
 ///
 /// - On initial access: A consent form HTML page
@@ -99,55 +110,59 @@ pub fn authorize(
             }
             Err(_) => None,
         };
-        return state
-            .endpoint()
-            .with_solicitor(FnSolicitor(consent_form))
-            .authorization_flow()
-            .execute(oauth)
-            .map_err(|err| {
-                debug!("OAuth authorization flow error occurred");
-                match err {
-                    oxide_auth::frontends::simple::endpoint::Error::OAuth(oauth_error) => {
-                        match oauth_error {
-                            oxide_auth::endpoint::OAuthError::BadRequest => {
-                                debug!("Bad request error in authorization flow");
-                                OAuthFailure::from(oxide_auth::endpoint::OAuthError::BadRequest)
-                            }
-                            oxide_auth::endpoint::OAuthError::DenySilently => {
-                                debug!("Deny silently error in authorization flow - For example, this response is given when an incorrect client has been provided in the authorization request in order to avoid potential indirect denial of service vulnerabilities.");
-                                if let Some((client_id, redirect_uri, scope, code_challenge, code_challenge_method)) = &debug_info {
-                                    debug!("Requested parameters:");
-                                    if let Some(cid) = client_id {
-                                        debug!("  client_id: {}", cid);
-                                    }
-                                    if let Some(ruri) = redirect_uri {
-                                        debug!("  redirect_uri: {}", ruri);
-                                    }
-                                    if let Some(s) = scope {
-                                        debug!("  scope: {}", s);
-                                    }
-                                    if let Some(cc) = code_challenge {
-                                        debug!("  code_challenge: {}", cc);
-                                    }
-                                    if let Some(ccm) = code_challenge_method {
-                                        debug!("  code_challenge_method: {}", ccm);
-                                    }
-
+        let pkce_required = debug_info
+            .as_ref()
+            .and_then(|(client_id, ..)| client_id.as_deref())
+            .is_some_and(|client_id| state.requires_pkce(client_id));
+        return AuthorizationFlow::prepare(with_pkce(
+            state.endpoint().with_solicitor(FnSolicitor(consent_form)),
+            pkce_required,
+        ))
+        .and_then(|mut flow| flow.execute(oauth))
+        .map_err(|err| {
+            debug!("OAuth authorization flow error occurred");
+            match err {
+                oxide_auth::frontends::simple::endpoint::Error::OAuth(oauth_error) => {
+                    match oauth_error {
+                        oxide_auth::endpoint::OAuthError::BadRequest => {
+                            debug!("Bad request error in authorization flow");
+                            OAuthFailure::from(oxide_auth::endpoint::OAuthError::BadRequest)
+                        }
+                        oxide_auth::endpoint::OAuthError::DenySilently => {
+                            debug!("Deny silently error in authorization flow - For example, this response is given when an incorrect client has been provided in the authorization request in order to avoid potential indirect denial of service vulnerabilities.");
+                            if let Some((client_id, redirect_uri, scope, code_challenge, code_challenge_method)) = &debug_info {
+                                debug!("Requested parameters:");
+                                if let Some(cid) = client_id {
+                                    debug!("  client_id: {}", cid);
                                 }
-                                OAuthFailure::from(oxide_auth::endpoint::OAuthError::DenySilently)
-                            }
-                            oxide_auth::endpoint::OAuthError::PrimitiveError => {
-                                debug!("Primitive error in authorization flow - server component failed");
-                                OAuthFailure::from(oxide_auth::endpoint::OAuthError::PrimitiveError)
+                                if let Some(ruri) = redirect_uri {
+                                    debug!("  redirect_uri: {}", ruri);
+                                }
+                                if let Some(s) = scope {
+                                    debug!("  scope: {}", s);
+                                }
+                                if let Some(cc) = code_challenge {
+                                    debug!("  code_challenge: {}", cc);
+                                }
+                                if let Some(ccm) = code_challenge_method {
+                                    debug!("  code_challenge_method: {}", ccm);
+                                }
+
                             }
+                            OAuthFailure::from(oxide_auth::endpoint::OAuthError::DenySilently)
+                        }
+                        oxide_auth::endpoint::OAuthError::PrimitiveError => {
+                            debug!("Primitive error in authorization flow - server component failed");
+                            OAuthFailure::from(oxide_auth::endpoint::OAuthError::PrimitiveError)
                         }
-                    }
-                    _ => {
-                        debug!("Other authorization flow error");
-                        OAuthFailure::from(oxide_auth::endpoint::OAuthError::PrimitiveError)
                     }
                 }
-            });
+                _ => {
+                    debug!("Other authorization flow error");
+                    OAuthFailure::from(oxide_auth::endpoint::OAuthError::PrimitiveError)
+                }
+            }
+        });
     }
 
     // Otherwise show login form
@@ -194,21 +209,78 @@ pub fn authorize(
 /// The access configuration (users and credentials) is read live from the shared
 /// `Arc<RwLock<Config>>` so that credential changes take effect immediately without
 /// restarting the server.
+///
+/// When [`LoginLockout`] state is managed, repeated failures of a user from the
+/// same address lock further logins out: they are refused with
+/// `429 Too Many Requests` and a `Retry-After` header, even with correct
+/// credentials, until the lockout ends.
+///
+/// A password hash weaker than `access.password_hash` is rehashed on login.
+/// The new hash replaces the old one in the running configuration, and in the
+/// configuration file when [`ConfigFilePath`] is managed, so the upgrade is
+/// kept across restarts. Without a configuration file it only lasts until the
+/// next restart.
 #[post("/login", data = "<form>")]
 pub async fn login(
     form: Form<AuthForm>,
     state: &State<OxideState>,
     config: &State<Arc<RwLock<Config>>>,
+    config_path: Option<&State<ConfigFilePath>>,
+    lockout: Option<&State<LoginLockout>>,
+    client_ip: Option<IpAddr>,
     cookies: &CookieJar<'_>,
-) -> Result<OAuthResponse, OAuthFailure> {
+) -> Result<Either<OAuthResponse, LockedOut>, OAuthFailure> {
     debug!("Login form data: {:?}", form);
     // Read live access config from the shared config state
     let access_config = config.read().await.access.clone();
+    let lockout = lockout.filter(|_| access_config.login_lockout.enabled);
+
+    // Refuse any attempt while the user is locked out from this address
+    if let Some(Err(remaining)) = lockout.map(|lockout| lockout.check(&form.username, client_ip)) {
+        return Ok(Either::Right(locked_out(&form, remaining)));
+    }
 
     // Validate user credentials
-    if let Some(user) = validate_user(&form.username, &form.password, &access_config) {
-        // Set authenticated session cookie
-        let mut cookie = Cookie::new("user_session", encode_user_session(user.clone()));
+    if let Some((user, upgraded_pass)) =
+        validate_user_with_rehash(&form.username, &form.password, &access_config)
+    {
+        // Store the password hash rehashed with the configured cost
+        if let Some(pass) = upgraded_pass {
+            if let Some(config_path) = config_path {
+                match persist_password_hash(&config_path.0, &user.pass, &pass).await {
+                    Ok(()) => info!(
+                        "Upgraded the password hash of user {} in {}",
+                        user.user,
+                        config_path.0.display()
+                    ),
+                    Err(e) => warn!(
+                        "Upgraded password hash of user {} not saved to {}: {}",
+                        user.user,
+                        config_path.0.display(),
+                        e
+                    ),
+                }
+            }
+            let mut config = config.write().await;
+            if let Some(stored) = config
+                .access
+                .users
+                .iter_mut()
+                .find(|stored| stored.user == user.user)
+            {
+                stored.pass = pass;
+                info!("Upgraded the password hash of user {}", user.user);
+            }
+        }
+        if let Some(lockout) = lockout {
+            lockout.record_success(&form.username, client_ip);
+        }
+        // Set authenticated session cookie, with role permissions expanded
+        let session_user = User {
+            permissions: access_config.effective_permissions(&user),
+            ..user
+        };
+        let mut cookie = Cookie::new("user_session", encode_user_session(session_user));
         cookie.set_http_only(true);
         cookie.set_path("/");
         cookie.set_max_age(Duration::hours(1));
@@ -241,11 +313,23 @@ pub async fn login(
             serde_urlencoded::to_string(&query_params).unwrap_or_else(|_| String::new());
         let redirect_url = format!("/authorize?{}", query_string);
 
-        Ok(OAuthResponse::new()
-            .set_status(Status::Found)
-            .set_location(Some(&redirect_url))
-            .clone())
+        Ok(Either::Left(
+            OAuthResponse::new()
+                .set_status(Status::Found)
+                .set_location(Some(&redirect_url))
+                .clone(),
+        ))
     } else {
+        if let Some(locked_for) = lockout.and_then(|lockout| {
+            lockout.record_failure(&form.username, client_ip, &access_config.login_lockout)
+        }) {
+            warn!(
+                "Locked out user {} from {:?} after repeated failed logins",
+                form.username, client_ip
+            );
+            return Ok(Either::Right(locked_out(&form, locked_for)));
+        }
+
         // Invalid credentials, show login form with error
         let output = login_page_html(
             form.response_type.clone(),
@@ -258,10 +342,35 @@ pub async fn login(
             Some("Invalid username or password."),
         );
 
-        Ok(OAuthResponse::new()
-            .body_html(&output)
-            .set_status(Status::Unauthorized)
-            .clone())
+        Ok(Either::Left(
+            OAuthResponse::new()
+                .body_html(&output)
+                .set_status(Status::Unauthorized)
+                .clone(),
+        ))
+    }
+}
+
+/// Login form explaining that the user is locked out for `remaining`
+fn locked_out(form: &AuthForm, remaining: std::time::Duration) -> LockedOut {
+    // Round up so that clients never retry before the lockout ends
+    let retry_after = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+    let message = format!(
+        "Too many failed login attempts. Try again in {} seconds.",
+        retry_after
+    );
+    LockedOut {
+        retry_after,
+        html: login_page_html(
+            form.response_type.clone(),
+            form.client_id.clone(),
+            form.redirect_uri.clone(),
+            form.state.clone(),
+            form.scope.clone(),
+            form.code_challenge.clone(),
+            form.code_challenge_method.clone(),
+            Some(&message),
+        ),
     }
 }
 
@@ -287,7 +396,7 @@ pub async fn login(
 /// - On error: An OAuth error response
 #[post("/authorize?<allow>")]
 pub fn authorize_consent(
-    oauth: OAuthRequest<'_>,
+    mut oauth: OAuthRequest<'_>,
     allow: Option<bool>,
     authenticated_user: Option<AuthenticatedUser>,
     state: &State<OxideState>,
@@ -302,15 +411,48 @@ pub fn authorize_consent(
     }
 
     let user = authenticated_user.unwrap();
+    let pkce_required = oauth
+        .query()
+        .ok()
+        .and_then(|query| query.unique_value("client_id").map(|v| v.into_owned()))
+        .is_some_and(|client_id| state.requires_pkce(&client_id));
 
-    state
-        .endpoint()
-        .with_solicitor(FnSolicitor(move |_: &mut _, grant: Solicitation<'_>| {
-            consent_decision(allowed, grant, user.0.username.clone())
-        }))
-        .authorization_flow()
-        .execute(oauth)
-        .map_err(|err| err.pack::<OAuthFailure>())
+    AuthorizationFlow::prepare(with_pkce(
+        state
+            .endpoint()
+            .with_solicitor(FnSolicitor(move |_: &mut _, grant: Solicitation<'_>| {
+                consent_decision(allowed, grant, user.0.username.clone())
+            })),
+        pkce_required,
+    ))
+    .and_then(|mut flow| flow.execute(oauth))
+    .map_err(|err| err.pack::<OAuthFailure>())
+}
+
+/// Wrap an endpoint with PKCE (RFC 7636) verification
+///
+/// The challenge sent to the authorization endpoint is stored with the grant
+/// and the token endpoint only issues tokens when the presented
+/// `code_verifier` matches it. Only the `S256` method is accepted: a `plain`
+/// challenge is the verifier itself and protects nothing once intercepted.
+///
+/// ### Parameters
+///
+/// * `endpoint` - The endpoint running the authorization or access token flow
+/// * `required` - Reject requests without a challenge
+///
+/// ### Returns
+///
+/// The endpoint extended with the PKCE addon
+fn with_pkce<E>(endpoint: E, required: bool) -> Extended<E, AddonList> {
+    let pkce = if required {
+        Pkce::required()
+    } else {
+        Pkce::optional()
+    };
+    let mut addons = AddonList::new();
+    addons.push_code(pkce);
+    Extended::extend_with(endpoint, addons)
 }
 
 /// OIDC end-session (logout) endpoint
@@ -372,10 +514,17 @@ pub fn logout(cookies: &CookieJar<'_>, post_logout_redirect_uri: Option<String>)
 /// ### Request Body
 ///
 /// Form-encoded with standard OAuth 2.0 parameters:
-/// - `grant_type`: Must be "authorization_code"
+/// - `grant_type`: "authorization_code" or "refresh_token"
 /// - `code`: The authorization code from the authorize endpoint
 /// - `redirect_uri`: Must match the original authorization request
 /// - `client_id`: The client identifier
+/// - `refresh_token`: The refresh token to exchange (refresh_token grant)
+/// - `code_verifier`: The PKCE verifier matching the authorization request's
+///   `code_challenge` (authorization_code grant)
+///
+/// Refresh tokens are single use: each refresh returns a new refresh token and
+/// invalidates the presented one. Presenting an already used refresh token
+/// revokes the whole refresh token family.
 ///
 /// ### Returns
 ///
@@ -389,11 +538,12 @@ pub async fn token<'r>(
 ) -> Result<OAuthResponse, OAuthFailure> {
     // Extract all values from body as owned Strings before any `.await`.
     // `Cow<dyn QueryParameter>` is `!Sync` and cannot be held across await points.
-    let (grant_type, refresh_token_for_claims) = {
+    let (grant_type, refresh_token_for_claims, client_id) = {
         let body = oauth.urlbody()?;
         let gt = body.unique_value("grant_type").map(|v| v.into_owned());
         let rt = body.unique_value("refresh_token").map(|v| v.into_owned());
-        (gt, rt)
+        let cid = body.unique_value("client_id").map(|v| v.into_owned());
+        (gt, rt, cid)
     };
     debug!("grant_type: {:?}", grant_type);
 
@@ -410,6 +560,8 @@ pub async fn token<'r>(
         // from the live AccessConfig so that any changes to config.yaml (e.g. removing
         // write:api) are reflected immediately in the newly issued token.
         if let Some(refresh_token_value) = refresh_token_for_claims {
+            revoke_if_replayed(state, &refresh_token_value);
+
             let maybe_owner_id = state
                 .issuer
                 .lock()
@@ -423,7 +575,7 @@ pub async fn token<'r>(
                         .users
                         .iter()
                         .find(|u| u.user == owner_id)
-                        .map(|u| u.permissions.clone())
+                        .map(|u| access.effective_permissions(u))
                         .unwrap_or_default()
                 };
                 if let Ok(mut issuer) = state.issuer.lock() {
@@ -442,14 +594,29 @@ pub async fn token<'r>(
             .execute(oauth)
             .map_err(|err| err.pack::<OAuthFailure>())
     } else {
-        // Handle authorization code flow
-        let mut endpoint = state.endpoint().access_token_flow();
-        endpoint
-            .execute(oauth)
+        // Handle authorization code flow, checking the PKCE code_verifier
+        let pkce_required = client_id.is_some_and(|client_id| state.requires_pkce(&client_id));
+        AccessTokenFlow::prepare(with_pkce(state.endpoint(), pkce_required))
+            .and_then(|mut flow| flow.execute(oauth))
             .map_err(|err| err.pack::<OAuthFailure>())
     }
 }
 
+/// Revoke the refresh token family when an already rotated refresh token is presented
+///
+/// The refresh flow then rejects the request with `invalid_grant`, as the
+/// presented token is no longer known.
+fn revoke_if_replayed(state: &OxideState, refresh_token: &str) {
+    let revoked = state
+        .issuer
+        .lock()
+        .map(|issuer| issuer.revoke_replayed_refresh_token(refresh_token))
+        .unwrap_or(false);
+    if revoked {
+        warn!("Replayed refresh token detected, refresh token family revoked");
+    }
+}
+
 /// OAuth 2.0 token refresh endpoint
 ///
 /// This Rocket handler implements the OAuth 2.0 token refresh flow,
@@ -485,6 +652,8 @@ pub async fn refresh<'r>(
     // Inject current permissions from live AccessConfig before reissuing the token,
     // so that config.yaml permission changes take effect immediately on refresh.
     if let Some(refresh_token_value) = refresh_token_for_claims {
+        revoke_if_replayed(state, &refresh_token_value);
+
         let maybe_owner_id = state
             .issuer
             .lock()
@@ -498,7 +667,7 @@ pub async fn refresh<'r>(
                     .users
                     .iter()
                     .find(|u| u.user == owner_id)
-                    .map(|u| u.permissions.clone())
+                    .map(|u| access.effective_permissions(u))
                     .unwrap_or_default()
             };
             if let Ok(mut issuer) = state.issuer.lock() {
@@ -557,3 +726,28 @@ pub async fn userinfo(
         }, // Permissions from the OAuthBearer
     ))
 }
+
+/// Replace a password hash in the configuration file
+///
+/// Only the encoded hash is replaced, keeping the comments and layout of the
+/// file. The file is written to a temporary file first, then renamed.
+///
+/// ### Errors
+///
+/// Fails if the file cannot be read or written, or if `old_pass` does not
+/// appear exactly once in the file.
+async fn persist_password_hash(path: &Path, old_pass: &str, new_pass: &str) -> anyhow::Result<()> {
+    let content = tokio::fs::read_to_string(path).await?;
+    let occurrences = content.matches(old_pass).count();
+    anyhow::ensure!(
+        occurrences == 1,
+        "the stored hash appears {} times in the file",
+        occurrences
+    );
+
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    tokio::fs::write(&temporary, content.replacen(old_pass, new_pass, 1)).await?;
+    tokio::fs::rename(&temporary, path).await?;
+    Ok(())
+}
@@ -0,0 +1,156 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Lockout of users after repeated failed logins
+//!
+//! [`LoginLockout`] counts the failed logins of each user from each source IP
+//! address. When [`LoginLockoutConfig::max_failures`] failures happen within
+//! the configured window, further logins are refused with
+//! `429 Too Many Requests` and a `Retry-After` header until the lockout ends,
+//! whatever the credentials. A successful login resets the count.
+//!
+//! Attempts are tracked in memory and forgotten on restart.
+
+use crate::config::LoginLockoutConfig;
+use rocket::http::Status;
+use rocket::response::content::RawHtml;
+use rocket::response::{self, Responder, Response};
+use rocket::Request;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Number of tracked users above which stale entries are pruned
+const MAX_TRACKED_LOGINS: usize = 10_000;
+
+/// Failed logins of a user from an address
+#[derive(Debug, Clone)]
+struct LoginFailures {
+    /// Times of the failures within the counting window
+    failures: Vec<Instant>,
+    /// End of the current lockout, if any
+    locked_until: Option<Instant>,
+}
+
+/// Failed login tracker, managed as Rocket state
+///
+/// The server builder manages one instance; without it logins are never
+/// locked out.
+#[derive(Debug, Default)]
+pub struct LoginLockout {
+    logins: Mutex<HashMap<String, LoginFailures>>,
+}
+
+impl LoginLockout {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Key of the failures of `username` from `ip`
+    fn key(username: &str, ip: Option<IpAddr>) -> String {
+        match ip {
+            Some(ip) => format!("{}|{}", username, ip),
+            None => format!("{}|unknown", username),
+        }
+    }
+
+    /// Check whether the logins of a user from an address are locked out
+    ///
+    /// ### Parameters
+    ///
+    /// * `username` - The username of the login attempt
+    /// * `ip` - The source address of the attempt
+    ///
+    /// ### Returns
+    ///
+    /// `Ok(())` when the login may be attempted, otherwise `Err` with the
+    /// time remaining until the lockout ends
+    pub fn check(&self, username: &str, ip: Option<IpAddr>) -> Result<(), Duration> {
+        let now = Instant::now();
+        let logins = self.logins.lock().unwrap();
+        match logins
+            .get(&Self::key(username, ip))
+            .and_then(|login| login.locked_until)
+        {
+            Some(locked_until) if locked_until > now => Err(locked_until - now),
+            _ => Ok(()),
+        }
+    }
+
+    /// Record a failed login
+    ///
+    /// ### Parameters
+    ///
+    /// * `username` - The username of the failed attempt
+    /// * `ip` - The source address of the attempt
+    /// * `config` - The lockout settings
+    ///
+    /// ### Returns
+    ///
+    /// The duration of the lockout when this failure triggers one
+    pub fn record_failure(
+        &self,
+        username: &str,
+        ip: Option<IpAddr>,
+        config: &LoginLockoutConfig,
+    ) -> Option<Duration> {
+        let now = Instant::now();
+        let window = Duration::from_secs(config.window_secs);
+        let mut logins = self.logins.lock().unwrap();
+
+        if logins.len() > MAX_TRACKED_LOGINS {
+            logins.retain(|_, login| {
+                login.locked_until.is_some_and(|until| until > now)
+                    || login
+                        .failures
+                        .last()
+                        .is_some_and(|last| now.saturating_duration_since(*last) < window)
+            });
+        }
+
+        let login = logins
+            .entry(Self::key(username, ip))
+            .or_insert_with(|| LoginFailures {
+                failures: Vec::new(),
+                locked_until: None,
+            });
+        login
+            .failures
+            .retain(|failure| now.saturating_duration_since(*failure) < window);
+        login.failures.push(now);
+
+        if login.failures.len() >= config.max_failures.max(1) as usize {
+            let lockout = Duration::from_secs(config.lockout_secs);
+            login.failures.clear();
+            login.locked_until = Some(now + lockout);
+            return Some(lockout);
+        }
+        None
+    }
+
+    /// Forget the failed logins of a user from an address after a successful login
+    pub fn record_success(&self, username: &str, ip: Option<IpAddr>) {
+        self.logins.lock().unwrap().remove(&Self::key(username, ip));
+    }
+}
+
+/// `429 Too Many Requests` login page sent while a user is locked out
+#[derive(Debug, Clone)]
+pub struct LockedOut {
+    /// Seconds until the lockout ends
+    pub retry_after: u64,
+    /// The login page explaining the lockout
+    pub html: String,
+}
+
+impl<'r> Responder<'r, 'static> for LockedOut {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        Response::build_from(RawHtml(self.html).respond_to(request)?)
+            .status(Status::TooManyRequests)
+            .raw_header("Retry-After", self.retry_after.to_string())
+            .ok()
+    }
+}
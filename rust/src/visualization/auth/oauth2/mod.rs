@@ -7,13 +7,15 @@ pub mod auth;
 pub mod consent;
 pub mod forms;
 pub mod handlers;
+pub mod lockout;
 pub mod state;
 
 // Re-export main items
-pub use auth::validate_user;
+pub use auth::{validate_user, validate_user_with_rehash};
 pub use consent::{consent_decision, consent_form, consent_page_html};
 pub use forms::{
     decode_user_session, encode_user_session, AuthForm, AuthenticatedUser, UserSession,
 };
 pub use handlers::{authorize, authorize_consent, login, logout, refresh, token, userinfo};
+pub use lockout::{LockedOut, LoginLockout};
 pub use state::OxideState;
@@ -8,17 +8,21 @@
 //! which manages the OAuth 2.0 server state including client registrations,
 //! authorization storage, and token issuance.
 
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use tokio::sync::RwLock;
 
-use log::debug;
+use base64::Engine;
+use log::{debug, warn};
 use oxide_auth::frontends::simple::endpoint::{Generic, Vacant};
 use oxide_auth::primitives::prelude::*;
 use oxide_auth::primitives::registrar::RegisteredUrl;
 use rocket::figment::Figment;
 use url::Url;
 
+use crate::config::access::ClientType;
 use crate::config::{AccessConfig, GenerixConfig};
+use crate::visualization::auth::jwt::RemoteJwks;
 use crate::visualization::jwt::JwtIssuer;
 
 /// Main state container for the OAuth 2.0 server implementation
@@ -35,6 +39,7 @@ use crate::visualization::jwt::JwtIssuer;
 /// * `registrar` - Stores registered OAuth clients
 /// * `authorizer` - Manages authorization grants and codes
 /// * `issuer` - JWT token issuer for generating access tokens
+/// * `pkce_required_clients` - Clients whose authorization requests must use PKCE
 /// * `hmac_secret` - Shared secret for JWT token validation
 ///
 /// ### Thread Safety
@@ -63,6 +68,11 @@ pub struct OxideState {
     /// This is wrapped in Arc<Mutex<>> to allow shared mutable access.
     pub issuer: Arc<Mutex<JwtIssuer>>,
 
+    /// Identifiers of the clients configured with `require_pkce`
+    ///
+    /// Rebuilt together with the registrar when the access configuration changes.
+    pkce_required_clients: Arc<Mutex<HashSet<String>>>,
+
     /// HMAC secret for JWT validation
     ///
     /// The secret key used for signing and validating JWT tokens.
@@ -79,6 +89,21 @@ pub struct OxideState {
     /// Used for verifying JWT tokens signed with the RS256 algorithm.
     pub rs256_public_key: String,
 
+    /// Public keys of the previous RS256 signing keys in base64 encoded PEM format
+    ///
+    /// Tokens signed with these keys remain valid during a key rotation.
+    pub rs256_previous_public_keys: Vec<String>,
+
+    /// Ed25519 public key in base64 encoded PEM format, empty when not configured
+    ///
+    /// Used for verifying JWT tokens signed with the EdDSA algorithm.
+    pub ed25519_public_key: String,
+
+    /// JWKS of an external identity provider whose tokens are accepted
+    ///
+    /// Shared by all the validators so that the fetched keys are cached once.
+    pub remote_jwks: Option<Arc<RemoteJwks>>,
+
     /// User access configuration
     ///
     /// Contains the list of users and their permissions used for authentication
@@ -102,9 +127,13 @@ impl Clone for OxideState {
             registrar: Arc::clone(&self.registrar),
             authorizer: Arc::clone(&self.authorizer),
             issuer: Arc::clone(&self.issuer),
+            pkce_required_clients: Arc::clone(&self.pkce_required_clients),
             hmac_secret: self.hmac_secret.clone(),
             rs256_private_key: self.rs256_private_key.clone(),
             rs256_public_key: self.rs256_public_key.clone(),
+            rs256_previous_public_keys: self.rs256_previous_public_keys.clone(),
+            ed25519_public_key: self.ed25519_public_key.clone(),
+            remote_jwks: self.remote_jwks.clone(),
             access_config: Arc::clone(&self.access_config),
             generix_config: self.generix_config.clone(),
         }
@@ -166,12 +195,14 @@ impl OxideState {
                     .iss
                     .unwrap_or("LaserSmartServer".to_string()),
             ) // Set the issuer name
-            .valid_for(chrono::Duration::hours(1)); // Tokens valid for 1 hour
+            .valid_for(chrono::Duration::hours(1)) // Tokens valid for 1 hour
+            .with_refresh_token_lifetimes(refresh_token_lifetimes(&access_config));
+        let pkce_required_clients = pkce_required_clients(&access_config);
 
         for client in access_config.clients {
             debug!("Adding client to oxide-auth: {:?}", client.client_id);
-            let mut oauth_client = Client::public(
-                client.client_id.as_str(),
+            let mut oauth_client = oauth_client(
+                &client,
                 RegisteredUrl::Semantic(client.allowed_callbacks[0].parse::<Url>().unwrap()),
                 client.default_scope.parse::<Scope>().unwrap(),
             );
@@ -197,11 +228,15 @@ impl OxideState {
             // These tokens can be verified independently by the resource server
             // and contain user information embedded within them
             issuer: Arc::new(Mutex::new(jwt_issuer)),
+            pkce_required_clients: Arc::new(Mutex::new(pkce_required_clients)),
             // Store the HMAC secret for validation elsewhere
             hmac_secret: hmac_secret.to_string(),
             // Add RS256 keys (to be set later)
             rs256_private_key: String::new(),
             rs256_public_key: String::new(),
+            rs256_previous_public_keys: Vec::new(),
+            ed25519_public_key: String::new(),
+            remote_jwks: None,
             // Initialize access config with default values — wrapped for live updates
             access_config: Arc::new(RwLock::new(AccessConfig::default())),
             // Initialize the generix configuration
@@ -259,12 +294,14 @@ impl OxideState {
                     .clone()
                     .unwrap_or("LaserSmartServer".to_string()),
             ) // Set the issuer name
-            .valid_for(chrono::Duration::hours(1)); // Tokens valid for 1 hour
+            .valid_for(access_config.access_token_lifetime())
+            .with_refresh_token_lifetimes(refresh_token_lifetimes(&access_config));
+        let pkce_required_clients = pkce_required_clients(&access_config);
 
         for client in &access_config.clients {
             debug!("Adding client to oxide-auth: {:?}", client.client_id);
-            let mut oauth_client = Client::public(
-                client.client_id.as_str(),
+            let mut oauth_client = oauth_client(
+                client,
                 RegisteredUrl::Semantic(client.allowed_callbacks[0].parse::<Url>().unwrap()),
                 client.default_scope.parse::<Scope>().unwrap(),
             );
@@ -284,6 +321,27 @@ impl OxideState {
 
         let rs256_private_key = config_read.visualization.rs256_private_key.clone();
         let rs256_public_key = config_read.visualization.rs256_public_key.clone();
+        let rs256_previous_public_keys =
+            config_read.visualization.rs256_previous_public_keys.clone();
+        let ed25519_public_key = config_read
+            .visualization
+            .ed25519_public_key
+            .clone()
+            .unwrap_or_default();
+        let remote_jwks = config_read
+            .visualization
+            .remote_jwks
+            .as_ref()
+            .map(|remote| {
+                let mut remote_jwks = RemoteJwks::new(
+                    remote.url.clone(),
+                    std::time::Duration::from_secs(remote.refresh_interval_secs),
+                );
+                if let Some(issuer) = &remote.issuer {
+                    remote_jwks = remote_jwks.with_issuer(issuer.clone());
+                }
+                Arc::new(remote_jwks)
+            });
         let generix_config = config_read.generix.clone();
         drop(config_read);
 
@@ -295,11 +353,15 @@ impl OxideState {
             // These tokens can be verified independently by the resource server
             // and contain user information embedded within them
             issuer: Arc::new(Mutex::new(jwt_issuer)),
+            pkce_required_clients: Arc::new(Mutex::new(pkce_required_clients)),
             // Store the HMAC secret for validation elsewhere
             hmac_secret,
             // Set RS256 keys from config
             rs256_private_key,
             rs256_public_key,
+            rs256_previous_public_keys,
+            ed25519_public_key,
+            remote_jwks,
             // Wrap the access config in Arc<RwLock<>> for live updates
             access_config: Arc::new(RwLock::new(access_config)),
             // Use the generix configuration from config
@@ -349,12 +411,64 @@ impl OxideState {
         }
     }
 
+    /// Check whether a client must protect its authorization codes with PKCE
+    ///
+    /// ### Parameters
+    ///
+    /// * `client_id` - The identifier of the requesting client
+    ///
+    /// ### Returns
+    ///
+    /// `true` if the client is configured with `require_pkce`
+    pub fn requires_pkce(&self, client_id: &str) -> bool {
+        self.pkce_required_clients
+            .lock()
+            .unwrap()
+            .contains(client_id)
+    }
+
+    /// Decode the active RS256 public keys
+    ///
+    /// ### Returns
+    ///
+    /// The PEM encoded public keys verifying tokens, the current signing key
+    /// first, then the previous ones. Empty or invalid base64 keys are skipped.
+    pub fn rs256_public_keys_pem(&self) -> Vec<Vec<u8>> {
+        std::iter::once(&self.rs256_public_key)
+            .chain(&self.rs256_previous_public_keys)
+            .filter_map(|key| decode_public_key(key))
+            .collect()
+    }
+
+    /// Decode the public keys of the previous RS256 signing keys
+    ///
+    /// ### Returns
+    ///
+    /// The PEM encoded public keys still accepted after a key rotation.
+    /// Empty or invalid base64 keys are skipped.
+    pub fn rs256_previous_public_keys_pem(&self) -> Vec<Vec<u8>> {
+        self.rs256_previous_public_keys
+            .iter()
+            .filter_map(|key| decode_public_key(key))
+            .collect()
+    }
+
+    /// Decode the Ed25519 public key
+    ///
+    /// ### Returns
+    ///
+    /// The PEM encoded public key verifying EdDSA tokens, `None` when it is
+    /// not configured or not valid base64.
+    pub fn ed25519_public_key_pem(&self) -> Option<Vec<u8>> {
+        decode_public_key(&self.ed25519_public_key)
+    }
+
     /// Update the access configuration at runtime (hot-reload)
     ///
     /// This method atomically updates all components that depend on `AccessConfig`:
     /// - The stored `access_config` (users, iss, duration)
-    /// - The OAuth2 client `registrar` (client list + allowed callbacks)
-    /// - The JWT `issuer` (issuer name + token duration)
+    /// - The OAuth2 client `registrar` (client list + allowed callbacks + PKCE requirement)
+    /// - The JWT `issuer` (issuer name, token duration, refresh token lifetimes)
     ///
     /// Call this whenever `Config.access` changes (e.g. after a config file reload
     /// or a `POST /api/config` update) to keep the OAuth state in sync.
@@ -391,8 +505,8 @@ impl OxideState {
             if client.allowed_callbacks.is_empty() {
                 continue;
             }
-            let mut oauth_client = Client::public(
-                client.client_id.as_str(),
+            let mut oauth_client = oauth_client(
+                client,
                 RegisteredUrl::Semantic(
                     client.allowed_callbacks[0]
                         .parse::<url::Url>()
@@ -422,9 +536,11 @@ impl OxideState {
             client_map.push(oauth_client);
         }
         *self.registrar.lock().unwrap() = client_map.into_iter().collect::<ClientMap>();
+        *self.pkce_required_clients.lock().unwrap() = pkce_required_clients(access_config);
     }
 
-    /// Update the JWT issuer name and token duration from a new `AccessConfig`.
+    /// Update the JWT issuer name, token duration and refresh token lifetimes
+    /// from a new `AccessConfig`.
     ///
     /// Called by [`update_access_config`].
     fn update_jwt_issuer(&self, access_config: &AccessConfig) {
@@ -435,8 +551,71 @@ impl OxideState {
                 .clone()
                 .unwrap_or_else(|| "LaserSmartServer".to_string()),
         );
-        issuer.valid_for(chrono::Duration::seconds(
-            access_config.duration.unwrap_or(86400),
-        ));
+        issuer.valid_for(access_config.access_token_lifetime());
+        issuer.with_refresh_token_lifetimes(refresh_token_lifetimes(access_config));
+    }
+}
+
+/// Collect the refresh token lifetime of every client that defines one
+fn refresh_token_lifetimes(access_config: &AccessConfig) -> HashMap<String, chrono::Duration> {
+    access_config
+        .clients
+        .iter()
+        .filter_map(|client| {
+            client
+                .refresh_token_lifetime
+                .map(|seconds| (client.client_id.clone(), chrono::Duration::seconds(seconds)))
+        })
+        .collect()
+}
+
+/// Collect the identifiers of the clients that must use PKCE
+fn pkce_required_clients(access_config: &AccessConfig) -> HashSet<String> {
+    access_config
+        .clients
+        .iter()
+        .filter(|client| client.require_pkce)
+        .map(|client| client.client_id.clone())
+        .collect()
+}
+
+/// Create the oxide-auth client registration matching the configured client type
+///
+/// Confidential clients without a `client_secret` are registered as public
+/// clients, since they have no credentials to authenticate with.
+fn oauth_client(
+    client: &crate::config::access::Client,
+    redirect_uri: RegisteredUrl,
+    default_scope: Scope,
+) -> Client {
+    match (client.client_type, &client.client_secret) {
+        (ClientType::Confidential, Some(secret)) => Client::confidential(
+            client.client_id.as_str(),
+            redirect_uri,
+            default_scope,
+            secret.as_bytes(),
+        ),
+        (ClientType::Confidential, None) => {
+            warn!(
+                "Confidential client {} has no client_secret, registering it as public",
+                client.client_id
+            );
+            Client::public(client.client_id.as_str(), redirect_uri, default_scope)
+        }
+        (ClientType::Public, _) => {
+            Client::public(client.client_id.as_str(), redirect_uri, default_scope)
+        }
+    }
+}
+
+/// Decode a base64 encoded PEM public key, `None` if it is empty or invalid
+fn decode_public_key(key: &str) -> Option<Vec<u8>> {
+    match base64::engine::general_purpose::STANDARD.decode(key) {
+        Ok(pem) if !pem.is_empty() => Some(pem),
+        Ok(_) => None,
+        Err(e) => {
+            warn!("Ignoring public key that is not valid base64: {}", e);
+            None
+        }
     }
 }
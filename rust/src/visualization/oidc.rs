@@ -9,7 +9,8 @@
 //! It includes:
 //!
 //! - `.well-known/openid-configuration` discovery endpoint
-//! - JWKS (JSON Web Key Set) endpoint for public key exposure
+//! - JWKS (JSON Web Key Set) endpoints (`.well-known/jwks.json` and `oauth/jwks.json`)
+//!   for public key exposure, including the previous keys during a key rotation
 //! - Helper functions for OIDC metadata generation
 //!
 //! These endpoints allow OAuth/OIDC clients to automatically discover the server's
@@ -17,7 +18,6 @@
 
 use std::sync::Arc;
 
-use base64::Engine;
 use log::debug;
 use rocket::serde::json::{json, Json, Value};
 use rocket::{get, State};
@@ -71,6 +71,9 @@ pub struct OpenIdConfiguration {
     /// JSON array containing the scopes that this server supports
     pub scopes_supported: Vec<String>,
 
+    /// JSON array containing the PKCE code challenge methods supported by this server (RFC 8414)
+    pub code_challenge_methods_supported: Vec<String>,
+
     /// URL of the OP's End-Session (logout) Endpoint
     ///
     /// When present, OIDC clients (e.g. `oidc-client-ts`) will redirect the
@@ -151,6 +154,7 @@ fn generate_openid_configuration(
             "read:api".to_string(),
             "write:api".to_string(),
         ],
+        code_challenge_methods_supported: vec!["S256".to_string()],
         claims_supported: vec![
             "sub".to_string(),
             "iss".to_string(),
@@ -203,10 +207,11 @@ pub async fn openid_configuration(
 /// Clients can use these keys to verify the signatures of tokens
 /// issued by this server.
 ///
-/// ### URL
-///
-///
+/// The set holds the current signing key and the previous keys still
+/// accepted during a key rotation; tokens name their key with the `kid`
+/// of their header.
 ///
+/// ### URL
 ///
 /// `GET /.well-known/jwks.json`
 ///
@@ -215,23 +220,31 @@ pub async fn openid_configuration(
 /// JSON object containing the JWKS with public keys
 #[get("/.well-known/jwks.json")]
 pub async fn jwks(state: &State<OxideState>) -> Json<Value> {
-    // Create a key set for our public keys
-    let mut keys = vec![];
-
-    // If we have an RS256 public key, add it to the key set
-    if let Ok(rs256_pub_key) =
-        base64::engine::general_purpose::STANDARD.decode(&state.rs256_public_key)
-    {
-        if !rs256_pub_key.is_empty() {
-            // Parse the PEM encoded public key
-            if let Ok(jwk) = JwkKeySet::create_jwk_from_pem(&rs256_pub_key) {
-                keys.push(jwk);
-            }
-        }
-    }
+    Json(jwks_document(state))
+}
+
+/// JSON Web Key Set (JWKS) endpoint of the OAuth server
+///
+/// Same key set as [`jwks`], at the location expected by OAuth clients
+/// that do not use OpenID Connect discovery.
+///
+/// ### URL
+///
+/// `GET /oauth/jwks.json`
+///
+/// ### Returns
+///
+/// JSON object containing the JWKS with public keys
+#[get("/oauth/jwks.json")]
+pub async fn oauth_jwks(state: &State<OxideState>) -> Json<Value> {
+    Json(jwks_document(state))
+}
 
-    // Return the key set
-    Json(json!({
-        "keys": keys
-    }))
+/// Build the JWKS of the active RS256 public keys
+fn jwks_document(state: &OxideState) -> Value {
+    let pems = state.rs256_public_keys_pem();
+    let key_set = JwkKeySet::from_public_pems(pems.iter().map(Vec::as_slice));
+    json!({
+        "keys": key_set.keys
+    })
 }
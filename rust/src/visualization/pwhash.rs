@@ -7,11 +7,19 @@
 //! This module provides utilities for verifying password hashes
 //! against the formats generated by `openssl passwd`.
 //!
-//! It supports verification of MD5, SHA-256, and SHA-512 crypt hashes.
+//! It supports verification of MD5, SHA-256, and SHA-512 crypt hashes, and
+//! hashing with the algorithm and number of rounds of a [`PasswordHashConfig`].
+//! The computed and stored hashes are compared in constant time, and
+//! [`verify_and_upgrade`] rehashes passwords whose hash is weaker than the
+//! configured one.
 
+use crate::config::{PasswordHashAlgorithm, PasswordHashConfig};
 /// Unix-style password hash verification
 use std::fmt;
 
+/// Number of rounds of the SHA crypt hashes without `rounds=` parameter
+const DEFAULT_SHA_CRYPT_ROUNDS: u32 = 5000;
+
 #[derive(Debug)]
 pub enum Error {
     InvalidFormat,
@@ -35,8 +43,11 @@ impl std::error::Error for Error {}
 ///
 /// Supported formats:
 /// - MD5 crypt ($1$)
-/// - SHA-256 crypt ($5$)
-/// - SHA-512 crypt ($6$)
+/// - SHA-256 crypt ($5$), with optional `rounds=N`
+/// - SHA-512 crypt ($6$), with optional `rounds=N`
+///
+/// The password is hashed with the salt and rounds of the stored hash, then
+/// both hashes are compared in constant time.
 ///
 /// ### Arguments
 ///
@@ -63,17 +74,161 @@ impl std::error::Error for Error {}
 /// }
 /// ```
 pub fn verify(password: &str, hash: &str) -> bool {
-    match parse_hash(hash) {
-        Ok((algorithm, salt, hash_value)) => match algorithm.as_str() {
-            "$1$" => verify_md5crypt(password, salt.as_str(), hash_value.as_str()),
-            "$5$" => verify_sha256crypt(password, salt.as_str(), hash_value.as_str()),
-            "$6$" => verify_sha512crypt(password, salt.as_str(), hash_value.as_str()),
-            _ => false,
+    let computed = match parse_hash(hash) {
+        Ok((algorithm, _, _)) => match algorithm.as_str() {
+            "$1$" => pwhash::md5_crypt::hash_with(hash, password),
+            "$5$" => pwhash::sha256_crypt::hash_with(hash, password),
+            "$6$" => pwhash::sha512_crypt::hash_with(hash, password),
+            _ => return false,
+        },
+        Err(_) => return false,
+    };
+    computed.is_ok_and(|computed| constant_time_eq(computed.as_bytes(), hash.as_bytes()))
+}
+
+/// Hash a password with the configured algorithm and number of rounds
+///
+/// A random salt is generated for each hash.
+///
+/// ### Arguments
+///
+/// * `password` - The plaintext password to hash
+/// * `config` - The algorithm and number of rounds
+///
+/// ### Returns
+///
+/// The hash in OpenSSL passwd format, e.g. `$5$rounds=10000$salt$hash`
+///
+/// ### Errors
+///
+/// Returns [`Error::InvalidHash`] if the hash cannot be computed.
+pub fn hash(password: &str, config: &PasswordHashConfig) -> Result<String, Error> {
+    let setup = pwhash::HashSetup {
+        salt: None,
+        rounds: Some(config.rounds),
+    };
+    match config.algorithm {
+        PasswordHashAlgorithm::Sha256 => pwhash::sha256_crypt::hash_with(setup, password),
+        PasswordHashAlgorithm::Sha512 => pwhash::sha512_crypt::hash_with(setup, password),
+    }
+    .map_err(|_| Error::InvalidHash)
+}
+
+/// Check whether a hash is weaker than the configured algorithm and rounds
+///
+/// Algorithms rank MD5 < SHA-256 < SHA-512. MD5 hashes, hashes of a weaker
+/// algorithm and hashes of the configured algorithm with fewer rounds need a
+/// rehash. Hashes of a stronger algorithm or with more rounds are kept, so
+/// that a rehash never downgrades a hash.
+///
+/// ### Arguments
+///
+/// * `hash` - The stored hash in OpenSSL passwd format
+/// * `config` - The configured algorithm and number of rounds
+pub fn needs_rehash(hash: &str, config: &PasswordHashConfig) -> bool {
+    let configured_strength = match config.algorithm {
+        PasswordHashAlgorithm::Sha256 => 1,
+        PasswordHashAlgorithm::Sha512 => 2,
+    };
+    let strength = match parse_hash(hash) {
+        Ok((algorithm, _, _)) => match algorithm.as_str() {
+            "$5$" => 1,
+            "$6$" => 2,
+            _ => 0,
         },
-        Err(_) => false,
+        Err(_) => return true,
+    };
+    strength < configured_strength
+        || (strength == configured_strength
+            && rounds(hash).is_none_or(|rounds| rounds < config.rounds))
+}
+
+/// Number of rounds of a SHA crypt hash
+///
+/// ### Returns
+///
+/// The `rounds=N` parameter, 5000 when absent, or `None` for MD5 and invalid hashes
+pub fn rounds(hash: &str) -> Option<u32> {
+    let (algorithm, _, _) = parse_hash(hash).ok()?;
+    if algorithm == "$1$" {
+        return None;
+    }
+    match hash.split('$').nth(2)?.strip_prefix("rounds=") {
+        Some(rounds) => rounds.parse().ok(),
+        None => Some(DEFAULT_SHA_CRYPT_ROUNDS),
+    }
+}
+
+/// Result of [`verify_and_upgrade`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verification {
+    /// Whether the password matches the stored hash
+    pub valid: bool,
+    /// New hash of the password with the configured cost, when the password
+    /// is valid and the stored hash is weaker
+    pub upgraded_hash: Option<String>,
+}
+
+/// Verify a password and rehash it when its stored hash is weaker than configured
+///
+/// ### Arguments
+///
+/// * `password` - The plaintext password to verify
+/// * `hash` - The stored hash in OpenSSL passwd format
+/// * `config` - The configured algorithm and number of rounds
+///
+/// ### Returns
+///
+/// The verification result, with the hash to store instead of `hash` if it
+/// must be upgraded
+///
+/// ### Example
+///
+/// ```
+/// use rust_photoacoustic::config::PasswordHashConfig;
+/// use rust_photoacoustic::visualization::pwhash;
+///
+/// let weak = PasswordHashConfig { rounds: 1000, ..Default::default() };
+/// let stored = pwhash::hash("secret", &weak).unwrap();
+///
+/// let verification = pwhash::verify_and_upgrade("secret", &stored, &PasswordHashConfig::default());
+/// assert!(verification.valid);
+/// assert!(verification.upgraded_hash.is_some());
+/// ```
+pub fn verify_and_upgrade(password: &str, hash: &str, config: &PasswordHashConfig) -> Verification {
+    let valid = verify(password, hash);
+    let upgraded_hash = if valid && needs_rehash(hash, config) {
+        self::hash(password, config).ok()
+    } else {
+        None
+    };
+    Verification {
+        valid,
+        upgraded_hash,
     }
 }
 
+/// Spend the time of a verification when there is no hash to verify against
+///
+/// Hashes the password with the configured cost. Called for unknown users,
+/// so that the response time does not reveal whether a username exists.
+///
+/// ### Arguments
+///
+/// * `password` - The plaintext password received
+/// * `config` - The configured algorithm and number of rounds
+pub fn dummy_verify(password: &str, config: &PasswordHashConfig) {
+    let _ = hash(password, config);
+}
+
+/// Compare two byte strings in constant time
+///
+/// The time taken only depends on the lengths of the inputs, never on the
+/// position of the first difference.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
 fn parse_hash(hash: &str) -> Result<(String, String, String), Error> {
     let parts: Vec<&str> = hash.split('$').collect();
 
@@ -85,26 +240,16 @@ fn parse_hash(hash: &str) -> Result<(String, String, String), Error> {
 
     match algorithm.as_str() {
         "$1$" | "$5$" | "$6$" => {
-            let salt = parts[2].to_string();
-            let hash_value = parts[3].to_string();
+            // SHA crypt hashes may carry a rounds parameter before the salt
+            let offset = usize::from(parts[2].starts_with("rounds=") && algorithm != "$1$");
+            if parts.len() < 4 + offset {
+                return Err(Error::InvalidFormat);
+            }
+            let salt = parts[2 + offset].to_string();
+            let hash_value = parts[3 + offset].to_string();
 
             Ok((algorithm, salt, hash_value))
         }
         _ => Err(Error::UnsupportedAlgorithm(algorithm)),
     }
 }
-
-fn verify_md5crypt(password: &str, salt: &str, hash_value: &str) -> bool {
-    use pwhash::md5_crypt;
-    md5_crypt::verify(password, &format!("$1${}${}", salt, hash_value))
-}
-
-fn verify_sha256crypt(password: &str, salt: &str, hash_value: &str) -> bool {
-    use pwhash::sha256_crypt;
-    sha256_crypt::verify(password, &format!("$5${}${}", salt, hash_value))
-}
-
-fn verify_sha512crypt(password: &str, salt: &str, hash_value: &str) -> bool {
-    use pwhash::sha512_crypt;
-    sha512_crypt::verify(password, &format!("$6${}${}", salt, hash_value))
-}
@@ -2,8 +2,9 @@
 // This file is part of the rust-photoacoustic project and is licensed under the
 // SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
 
+use chrono::{DateTime, NaiveDateTime, Utc};
 use rocket::http::uri::{Host, Origin};
-use rocket::http::{ContentType, Header, HeaderMap};
+use rocket::http::{ContentType, Header, HeaderMap, Status};
 use rocket::request::FromRequest;
 use rocket::response::Responder;
 
@@ -70,6 +71,111 @@ impl<'r> Responder<'r, 'r> for StaticFileResponse {
     }
 }
 
+/// `Cache-Control` of files whose name contains a content hash
+const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// `Cache-Control` of files that must be revalidated with their `ETag`
+const REVALIDATE_CACHE_CONTROL: &str = "no-cache";
+
+/// Format of HTTP dates (`Last-Modified`, `If-Modified-Since`)
+const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// Response type for static files supporting conditional requests
+///
+/// Unlike [`StaticFileResponse`], this response carries validators: an `ETag`
+/// derived from the file content and an optional `Last-Modified` date. A request
+/// whose `If-None-Match` matches the `ETag`, or, without `If-None-Match`, whose
+/// `If-Modified-Since` is not older than `Last-Modified`, gets an empty
+/// `304 Not Modified` response.
+///
+/// Files with a content hash in their name never change and are cached for a
+/// year as `immutable`; other files must be revalidated on each use.
+pub struct CachedFileResponse {
+    /// The binary content of the file
+    pub content: &'static [u8],
+    /// The content type of the file
+    pub content_type: ContentType,
+    /// Quoted entity tag of the content
+    pub etag: String,
+    /// Modification date of the content
+    pub last_modified: Option<DateTime<Utc>>,
+    /// Whether the file name contains a content hash
+    pub immutable: bool,
+}
+
+impl CachedFileResponse {
+    /// Check the conditional headers of `request` against this file
+    ///
+    /// ### Returns
+    ///
+    /// `true` if the client copy is current and a `304 Not Modified` can be sent
+    pub fn is_not_modified(&self, request: &Request<'_>) -> bool {
+        if let Some(if_none_match) = request.headers().get_one("If-None-Match") {
+            // Weak comparison, as required for If-None-Match (RFC 9110 13.1.2)
+            let etag = self.etag.trim_start_matches("W/");
+            return if_none_match
+                .split(',')
+                .map(str::trim)
+                .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag);
+        }
+
+        match (
+            request.headers().get_one("If-Modified-Since"),
+            self.last_modified,
+        ) {
+            (Some(since), Some(last_modified)) => {
+                NaiveDateTime::parse_from_str(since.trim(), HTTP_DATE_FORMAT)
+                    .map(|since| last_modified.timestamp() <= since.and_utc().timestamp())
+                    .unwrap_or(false)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Debug for CachedFileResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachedFileResponse")
+            .field("content_length", &self.content.len())
+            .field("content_type", &self.content_type)
+            .field("etag", &self.etag)
+            .field("last_modified", &self.last_modified)
+            .field("immutable", &self.immutable)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<'r> Responder<'r, 'static> for CachedFileResponse {
+    fn respond_to(self, request: &'r Request<'_>) -> rocket::response::Result<'static> {
+        let cache_control = if self.immutable {
+            IMMUTABLE_CACHE_CONTROL
+        } else {
+            REVALIDATE_CACHE_CONTROL
+        };
+
+        let mut response = Response::build();
+        response
+            .raw_header("ETag", self.etag.clone())
+            .raw_header("Cache-Control", cache_control);
+        if let Some(last_modified) = self.last_modified {
+            response.raw_header(
+                "Last-Modified",
+                last_modified.format(HTTP_DATE_FORMAT).to_string(),
+            );
+        }
+
+        if self.is_not_modified(request) {
+            response.status(Status::NotModified);
+        } else {
+            response
+                .header(self.content_type)
+                .sized_body(self.content.len(), Cursor::new(self.content));
+        }
+        response.ok()
+    }
+}
+
 /// Request guard for accessing HTTP headers in a route
 ///
 /// This struct acts as a wrapper around Rocket's `HeaderMap`, providing a
@@ -10,19 +10,22 @@
 use super::cors::CORS;
 use super::handlers::*;
 use crate::acquisition::SharedAudioStream;
-use crate::config::{Config, GenerixConfig};
+use crate::config::{Config, GenerixConfig, MutualTlsConfig};
 use crate::include_png_as_base64;
 use crate::processing::computing_nodes::SharedComputingState;
 use crate::processing::nodes::streaming_registry::StreamingNodeRegistry;
 use crate::thermal_regulation::SharedThermalState;
 use crate::visualization::api::action::get_action_routes;
 use crate::visualization::api::graph::graph::*;
+use crate::visualization::api::openapi::openapi_to_yaml;
 use crate::visualization::api::*;
+use crate::visualization::auth::guards::{too_many_requests, RateLimiter};
+use crate::visualization::auth::oauth2::LoginLockout;
 use crate::visualization::auth::{
     authorize, oauth2::authorize_consent, oauth2::login, oauth2::logout, oauth2::userinfo, refresh,
     token, OxideState,
 };
-use crate::visualization::oidc::{jwks, openid_configuration};
+use crate::visualization::oidc::{jwks, oauth_jwks, openid_configuration};
 use crate::visualization::shared_state::SharedVisualizationState;
 use crate::visualization::streaming::AudioStreamState;
 use crate::visualization::streaming::*;
@@ -31,7 +34,7 @@ use base64::Engine;
 use log::{debug, info, warn};
 use rocket::figment::Figment;
 use rocket::http::ContentType;
-use rocket::{routes, Route};
+use rocket::{catchers, routes, Route};
 use rocket::{Build, Rocket};
 use rocket_async_compression::CachedCompression;
 use rocket_okapi::handlers::{ContentHandler, RedirectHandler};
@@ -102,6 +105,7 @@ pub async fn build_openapi_spec(
     // Initialize OpenAPI specification accumulator with proper version
     let mut openapi_spec = OpenApi::default();
     openapi_spec.openapi = "3.0.0".to_string();
+    stamp_openapi_info(&mut openapi_spec);
 
     // Add config routes
     let (_, openapi_spec_config) = get_config_routes();
@@ -115,6 +119,16 @@ pub async fn build_openapi_spec(
         warn!("Failed to merge config OpenAPI spec: {}", e);
     }
 
+    // Add Modbus routes
+    let (_, openapi_spec_modbus) = get_modbus_routes();
+    if let Err(e) = rocket_okapi::okapi::merge::merge_specs(
+        &mut openapi_spec,
+        &"/".to_string(),
+        &openapi_spec_modbus,
+    ) {
+        warn!("Failed to merge Modbus OpenAPI spec: {}", e);
+    }
+
     // Add visualization routes if requested
     if include_visualization_state {
         // Get graph and system routes
@@ -262,6 +276,42 @@ pub async fn generate_openapi_json(
     serde_json::to_string_pretty(&spec)
 }
 
+/// Generate OpenAPI specification as a YAML string
+///
+/// YAML counterpart of [`generate_openapi_json`], used by `--dump-openapi`
+/// when the output file has a `.yaml` or `.yml` extension.
+///
+/// ### Parameters
+///
+/// * `config` - The application configuration containing HMAC secret and other settings
+/// * `include_visualization_state` - If `true`, includes routes that depend on SharedVisualizationState
+/// * `include_thermal_state` - If `true`, includes thermal regulation routes
+/// * `include_computing_state` - If `true`, includes computing node routes
+/// * `include_audio_stream` - If `true`, includes audio streaming routes
+///
+/// ### Returns
+///
+/// * `anyhow::Result<String>` - The OpenAPI specification as YAML,
+///   or an error if serialization fails
+pub async fn generate_openapi_yaml(
+    config: &Arc<RwLock<Config>>,
+    include_visualization_state: bool,
+    include_thermal_state: bool,
+    include_computing_state: bool,
+    include_audio_stream: bool,
+) -> anyhow::Result<String> {
+    let spec = build_openapi_spec(
+        config,
+        include_visualization_state,
+        include_thermal_state,
+        include_computing_state,
+        include_audio_stream,
+    )
+    .await;
+
+    openapi_to_yaml(&spec)
+}
+
 /// Build a configured Rocket server instance
 ///
 /// This function creates and configures a Rocket server instance with all
@@ -401,9 +451,21 @@ async fn build_rocket_inner(
                     &decoded_private,
                     &decoded_public,
                 ) {
-                    let duration: chrono::TimeDelta =
-                        chrono::TimeDelta::seconds(access_config.duration.or(Some(86400)).unwrap());
-                    jwt_issuer.valid_for(duration);
+                    // The validators require the configured issuer
+                    jwt_issuer
+                        .with_issuer(
+                            access_config
+                                .iss
+                                .clone()
+                                .unwrap_or_else(|| "LaserSmartServer".to_string()),
+                        )
+                        .valid_for(access_config.access_token_lifetime());
+                    // Keep accepting the tokens signed before the last key rotation
+                    for previous_key in oxide_state.rs256_previous_public_keys_pem() {
+                        if let Err(e) = jwt_issuer.add_verification_key(&previous_key) {
+                            eprintln!("Warning: Ignoring previous RS256 public key: {}", e);
+                        }
+                    }
                     oxide_state.issuer = std::sync::Arc::new(std::sync::Mutex::new(jwt_issuer));
                 }
             }
@@ -433,8 +495,21 @@ async fn build_rocket_inner(
     let jwt_validator = match crate::visualization::api_auth::init_jwt_validator(
         hmac_secret.clone().as_str(),
         rs256_public_key_bytes.as_deref(),
+        oxide_state.ed25519_public_key_pem().as_deref(),
         access_config,
-    ) {
+    )
+    .and_then(|validator| {
+        oxide_state
+            .rs256_previous_public_keys_pem()
+            .iter()
+            .try_fold(validator, |validator, previous_key| {
+                validator.with_rs256_public_key(previous_key)
+            })
+    })
+    .map(|validator| match &oxide_state.remote_jwks {
+        Some(remote_jwks) => validator.with_remote_jwks(remote_jwks.clone()),
+        None => validator,
+    }) {
         Ok(validator) => std::sync::Arc::new(validator),
         Err(e) => {
             eprintln!("Failed to initialize JWT validator: {}", e);
@@ -448,10 +523,8 @@ async fn build_rocket_inner(
     let mut openapi_spec = OpenApi::default();
     openapi_spec.openapi = "3.0.0".to_string(); // Set the version to match other specs
 
-    // Set API information
-    openapi_spec.info.title = "SCTG rust-photoacoustic API".to_string();
-    openapi_spec.info.description =
-        Some("Flexible Gas Analyzer using Laser Photoacoustic Spectroscopy".to_string());
+    // Set API information, versioned with the crate version
+    stamp_openapi_info(&mut openapi_spec);
 
     // Add config routes
     let (openapi_routes_config, openapi_spec_config) = get_config_routes();
@@ -467,6 +540,20 @@ async fn build_rocket_inner(
 
     let rocket_builder = rocket_builder.mount("/", openapi_routes_config);
 
+    // Add Modbus routes
+    let (openapi_routes_modbus, openapi_spec_modbus) = get_modbus_routes();
+
+    // Merge Modbus OpenAPI spec
+    if let Err(e) = rocket_okapi::okapi::merge::merge_specs(
+        &mut openapi_spec,
+        &"/".to_string(),
+        &openapi_spec_modbus,
+    ) {
+        warn!("Failed to merge Modbus OpenAPI spec: {}", e);
+    }
+
+    let rocket_builder = rocket_builder.mount("/", openapi_routes_modbus);
+
     // Add visualization, system, and action routes if visualization state is available
     // All these routes depend on SharedVisualizationState
     let rocket_builder = add_visualization_state_dependent_routes(
@@ -507,15 +594,20 @@ async fn build_rocket_inner(
                 crate::visualization::introspection::introspect,
                 openid_configuration,
                 jwks,
+                oauth_jwks,
                 get_generix_config,
                 crate::visualization::api_auth::get_profile,
                 crate::visualization::api_auth::get_data,
             ],
         )
         .mount("/", vite_dev_proxy::get_vite_dev_routes())
+        .mount("/", get_health_routes())
         .manage(oxide_state) // Rocket owns OxideState; State<OxideState> works in handlers
         .manage(jwt_validator)
-        .manage(config.clone()); // Add config as managed state for future dynamic configuration
+        .manage(config.clone()) // Add config as managed state for future dynamic configuration
+        .manage(RateLimiter::new())
+        .manage(LoginLockout::new())
+        .register("/", catchers![too_many_requests]);
 
     // Add computing routes and state if available
     let rocket_builder =
@@ -540,6 +632,46 @@ async fn build_rocket_inner(
     (rocket, oxide_state_for_caller)
 }
 
+/// Add the client certificate verification settings to a Rocket figment
+///
+/// When mutual TLS is configured, Rocket verifies the client certificates
+/// against its CA bundle during the TLS handshake, and rejects connections
+/// without certificate if it is mandatory. The figment must already enable
+/// TLS with `tls.certs` and `tls.key`.
+///
+/// ### Parameters
+///
+/// * `figment` - The Rocket figment to extend
+/// * `mutual_tls` - The `visualization.mutual_tls` settings, if any
+///
+/// ### Returns
+///
+/// The figment with the `tls.mutual` settings, unchanged without mutual TLS
+///
+/// ### Errors
+///
+/// Returns an error if the CA bundle is not valid Base64.
+pub fn configure_mutual_tls(
+    figment: Figment,
+    mutual_tls: Option<&MutualTlsConfig>,
+) -> anyhow::Result<Figment> {
+    let Some(mutual_tls) = mutual_tls else {
+        return Ok(figment);
+    };
+    let ca_certs = base64::engine::general_purpose::STANDARD.decode(&mutual_tls.ca_certs)?;
+    info!(
+        "Client certificate authentication enabled ({})",
+        if mutual_tls.mandatory {
+            "mandatory"
+        } else {
+            "optional"
+        }
+    );
+    Ok(figment
+        .merge(("tls.mutual.ca_certs", ca_certs))
+        .merge(("tls.mutual.mandatory", mutual_tls.mandatory)))
+}
+
 use rocket::{get, http::Status, serde::json::Json, State};
 
 #[get("/client/generix.json", rank = 1)]
@@ -680,6 +812,7 @@ fn add_audio_routes(
 
         rocket_builder
             .mount("/", openapi_routes_audio)
+            .mount("/", get_websocket_routes())
             .manage(audio_state)
     } else {
         debug!("No audio stream provided, skipping audio routes");
@@ -898,11 +1031,22 @@ fn make_rapidoc_with_vite_assets(config: &RapiDocConfig) -> Vec<Route> {
 }
 
 /// Adds OpenAPI documentation routes to the Rocket instance.
-/// This function mounts the openapi.json endpoint and RapiDoc interface.
+/// This function mounts the openapi.json endpoint, the `/api/openapi.{json,yaml}`
+/// downloads and the RapiDoc interface.
 fn add_openapi_documentation(
     rocket_builder: Rocket<Build>,
     openapi_spec: OpenApi,
 ) -> Rocket<Build> {
+    let rocket_builder = match OpenApiDocument::new(&openapi_spec) {
+        Ok(document) => rocket_builder
+            .manage(document)
+            .mount("/", get_openapi_export_routes()),
+        Err(e) => {
+            warn!("OpenAPI specification export disabled: {:#}", e);
+            rocket_builder
+        }
+    };
+
     let openapi_settings = OpenApiSettings::default();
     let rocket_builder = rocket_builder.mount(
         "/",
@@ -1015,6 +1159,7 @@ pub fn build_rocket_test_instance() -> Rocket<Build> {
     let jwt_validator = match crate::visualization::api_auth::init_jwt_validator(
         test_hmac_secret,
         None,
+        None,
         access_config,
     ) {
         Ok(validator) => std::sync::Arc::new(validator),
@@ -1042,6 +1187,7 @@ pub fn build_rocket_test_instance() -> Rocket<Build> {
                 refresh,
                 openid_configuration, // Add OIDC configuration endpoint
                 jwks,                 // Add JWKS endpoint
+                oauth_jwks,           // Add OAuth JWKS endpoint
                 introspect,           //Add introspection endpoint once fixed
                 get_generix_config,   // Add generix.json endpoint
             ],
@@ -1056,4 +1202,7 @@ pub fn build_rocket_test_instance() -> Rocket<Build> {
         .manage(oxide_state)
         .manage(jwt_validator)
         .manage(app_config) // Add config as managed state
+        .manage(RateLimiter::new())
+        .manage(LoginLockout::new())
+        .register("/", catchers![too_many_requests])
 }
@@ -5,24 +5,31 @@
 //! Cross-Origin Resource Sharing (CORS) support
 //!
 //! This module provides CORS fairing implementation for Rocket to enable
-//! cross-origin requests from web clients.
+//! cross-origin requests from the web clients allowed by the configuration.
 
+use crate::config::{Config, CorsConfig};
 use rocket::fairing::{Fairing, Info, Kind};
-use rocket::http::Header;
+use rocket::http::{Header, Method, Status};
 use rocket::{Request, Response};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Headers allowed in preflight responses when the request does not list any
+const DEFAULT_ALLOWED_HEADERS: &str = "Authorization, Content-Type";
 
 /// Cross-Origin Resource Sharing (CORS) fairing for Rocket
 ///
-/// This fairing adds CORS headers to all responses from the server,
-/// enabling cross-origin requests from web clients. This is particularly
-/// important for APIs that are accessed from web applications hosted
-/// on different domains.
+/// This fairing adds CORS headers to the responses of cross-origin requests
+/// whose `Origin` is allowed by [`CorsConfig`]. The policy is read from the
+/// managed configuration on each request, so hot-reloads apply immediately.
 ///
-/// ### Security Note
+/// - Requests without `Origin` or from the server's own origin are left untouched.
+/// - Allowed origins are echoed in `Access-Control-Allow-Origin` (or `*` when any
+///   origin is allowed without credentials), with `Vary: Origin`.
+/// - Other origins get no CORS headers, and their preflight requests are
+///   answered with `403 Forbidden`.
 ///
-/// The current implementation uses very permissive settings (`*` for origins
-/// and headers). For production environments, consider restricting these to
-/// specific origins and headers needed by your application.
+/// Without configuration, only same-origin requests are possible.
 pub struct CORS;
 
 /// Implementation of the Rocket Fairing trait for CORS
@@ -46,15 +53,45 @@ impl Fairing for CORS {
     /// Modifies responses to include CORS headers
     ///
     /// This method is called for every response and adds the appropriate
-    /// CORS headers to enable cross-origin requests.
+    /// CORS headers when the request comes from an allowed origin.
     ///
     /// ### Parameters
     ///
-    /// * `_request` - The request that generated this response (unused)
+    /// * `request` - The request that generated this response
     /// * `response` - The response to modify with CORS headers
-    async fn on_response<'r>(&self, _request: &'r Request<'_>, response: &mut Response<'r>) {
-        // Allow requests from any origin
-        response.set_header(Header::new("Access-Control-Allow-Origin", "*"));
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let Some(origin) = request.headers().get_one("Origin") else {
+            return;
+        };
+        if is_same_origin(request, origin) {
+            return;
+        }
+
+        let cors = match request.rocket().state::<Arc<RwLock<Config>>>() {
+            Some(config) => config.read().await.visualization.cors.clone(),
+            None => CorsConfig::default(),
+        };
+        let preflight = request.method() == Method::Options
+            && request.headers().contains("Access-Control-Request-Method");
+
+        if !cors.is_origin_allowed(origin) {
+            log::debug!("Rejecting cross-origin request from {}", origin);
+            if preflight {
+                response.set_status(Status::Forbidden);
+            }
+            return;
+        }
+
+        let allowed_origin = if cors.allow_any_origin && !cors.allow_credentials {
+            "*"
+        } else {
+            origin
+        };
+        response.set_header(Header::new(
+            "Access-Control-Allow-Origin",
+            allowed_origin.to_string(),
+        ));
+        response.adjoin_header(Header::new("Vary", "Origin"));
 
         // Allow common HTTP methods
         response.set_header(Header::new(
@@ -62,10 +99,30 @@ impl Fairing for CORS {
             "POST, GET, PUT, DELETE, OPTIONS",
         ));
 
-        // Allow all headers
-        response.set_header(Header::new("Access-Control-Allow-Headers", "*"));
+        // Allow the headers requested by the preflight
+        let allowed_headers = request
+            .headers()
+            .get_one("Access-Control-Request-Headers")
+            .unwrap_or(DEFAULT_ALLOWED_HEADERS)
+            .to_string();
+        response.set_header(Header::new("Access-Control-Allow-Headers", allowed_headers));
 
-        // Allow credentials (cookies, etc.)
-        response.set_header(Header::new("Access-Control-Allow-Credentials", "true"));
+        if cors.allow_credentials {
+            response.set_header(Header::new("Access-Control-Allow-Credentials", "true"));
+        }
     }
 }
+
+/// Check whether `origin` is the origin the request was sent to
+///
+/// Browsers send `Origin` on some same-origin requests (e.g. `POST`); these must
+/// not be treated as cross-origin.
+fn is_same_origin(request: &Request<'_>, origin: &str) -> bool {
+    let authority = origin
+        .split_once("://")
+        .map_or(origin, |(_, authority)| authority)
+        .trim_end_matches('/');
+    request
+        .host()
+        .is_some_and(|host| host.to_string().eq_ignore_ascii_case(authority))
+}
@@ -7,22 +7,31 @@
 //! This module contains the route handlers for serving static files,
 //! web client assets, and development proxy routes.
 
-use include_dir::{include_dir, Dir};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use include_dir::{include_dir, Dir, File};
 use rocket::http::ContentType;
 use rocket::response::Redirect;
-use rocket::{get, options, uri};
+use rocket::{get, options, uri, Either};
 use rocket_okapi::openapi;
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
 
-use crate::visualization::request_guard::{RawQueryString, StaticFileResponse};
+use crate::build_info::BuildInfo;
+use crate::visualization::request_guard::{CachedFileResponse, RawQueryString, StaticFileResponse};
 use crate::visualization::vite_dev_proxy;
 
 /// Static directory containing the web client files
 ///
-/// This constant includes the compiled web client files at compile time.
+/// This static includes the compiled web client files at compile time.
 /// The files are embedded in the binary, eliminating the need for external
 /// file dependencies when deploying the server.
-const STATIC_DIR: Dir = include_dir!("../web/dist");
+static STATIC_DIR: Dir = include_dir!("../web/dist");
+
+/// Directory of the Vite build output whose file names contain a content hash
+const HASHED_ASSETS_DIR: &str = "assets";
 
 /// Handler for HTTP OPTIONS requests required for CORS preflight
 ///
@@ -56,13 +65,17 @@ pub async fn options(_path: PathBuf) -> Result<(), std::io::Error> {
 /// If the requested file is not found, it falls back to serving index.html,
 /// enabling client-side routing.
 ///
+/// Embedded files carry an `ETag` and a `Last-Modified` header and conditional
+/// requests get `304 Not Modified`, see [`embedded_file_response`].
+///
 /// ### Parameters
 ///
 /// * `path` - The path to the requested file relative to the web/dist directory
 ///
 /// ### Returns
 ///
-/// * `Some(StaticFileResponse)` - The requested file content with appropriate headers
+/// * `Some(Either::Left(StaticFileResponse))` - Content proxied from the Vite server
+/// * `Some(Either::Right(CachedFileResponse))` - The embedded file with caching headers
 /// * `None` - If the file cannot be found or served
 ///
 /// ### Development Mode
@@ -71,39 +84,21 @@ pub async fn options(_path: PathBuf) -> Result<(), std::io::Error> {
 /// to the URL specified in that variable (defaulting to `http://localhost:5173`).
 /// This allows for hot-reloading and other development features.
 #[get("/client/<path..>", rank = 2)]
-pub async fn webclient(path: PathBuf, raw_query: RawQueryString) -> Option<StaticFileResponse> {
+pub async fn webclient(
+    path: PathBuf,
+    raw_query: RawQueryString,
+) -> Option<Either<StaticFileResponse, CachedFileResponse>> {
     if vite_dev_proxy::is_vite_development_enabled() {
-        return vite_dev_proxy::proxy_to_vite_dev_server(path, raw_query).await;
+        return vite_dev_proxy::proxy_to_vite_dev_server(path, raw_query)
+            .await
+            .map(Either::Left);
     }
 
     let path = path.to_str().unwrap_or("");
-    let file = STATIC_DIR.get_file(path).map(|file| {
-        let content_type = ContentType::from_extension(
-            file.path()
-                .extension()
-                .unwrap_or_default()
-                .to_str()
-                .unwrap(),
-        )
-        .unwrap_or(ContentType::Binary);
-        StaticFileResponse(file.contents().to_vec(), content_type)
-    });
-    if file.is_some() {
-        file
-    } else {
-        let file = STATIC_DIR.get_file("index.html").map(|file| {
-            let content_type = ContentType::from_extension(
-                file.path()
-                    .extension()
-                    .unwrap_or_default()
-                    .to_str()
-                    .unwrap(),
-            )
-            .unwrap_or(ContentType::Binary);
-            StaticFileResponse(file.contents().to_vec(), content_type)
-        });
-        file
-    }
+    STATIC_DIR
+        .get_file(path)
+        .or_else(|| STATIC_DIR.get_file("index.html"))
+        .map(|file| Either::Right(embedded_file_response(file)))
 }
 
 /// Redirect `/index.html` to the web client
@@ -156,20 +151,57 @@ pub async fn webclient_index() -> Redirect {
 ///
 /// ### Returns
 ///
-/// * `Some(StaticFileResponse)` - The favicon file content with appropriate headers
+/// * `Some(CachedFileResponse)` - The favicon file content with appropriate headers
 /// * `None` - If the favicon file cannot be found
 #[get("/favicon.ico")]
-pub async fn favicon() -> Option<StaticFileResponse> {
-    let file = STATIC_DIR.get_file("favicon.ico").map(|file| {
-        let content_type = ContentType::from_extension(
-            file.path()
-                .extension()
-                .unwrap_or_default()
-                .to_str()
-                .unwrap(),
-        )
-        .unwrap_or(ContentType::Binary);
-        StaticFileResponse(file.contents().to_vec(), content_type)
-    });
-    file
+pub async fn favicon() -> Option<CachedFileResponse> {
+    STATIC_DIR
+        .get_file("favicon.ico")
+        .map(embedded_file_response)
+}
+
+/// Build the cacheable response of an embedded web client file
+///
+/// The `ETag` is derived from the SHA-256 of the content and computed once per
+/// file. Embedded files cannot change without rebuilding the binary, so the build
+/// time is used as `Last-Modified`. Files of the Vite `assets/` directory have a
+/// content hash in their name and are served as immutable.
+///
+/// ### Parameters
+///
+/// * `file` - The embedded file to serve
+///
+/// ### Returns
+///
+/// The file content with its caching headers
+pub fn embedded_file_response(file: &'static File<'static>) -> CachedFileResponse {
+    static ETAGS: OnceLock<Mutex<HashMap<&'static Path, String>>> = OnceLock::new();
+
+    let content_type = ContentType::from_extension(
+        file.path()
+            .extension()
+            .unwrap_or_default()
+            .to_str()
+            .unwrap(),
+    )
+    .unwrap_or(ContentType::Binary);
+
+    let etag = ETAGS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap()
+        .entry(file.path())
+        .or_insert_with(|| {
+            let digest = Sha256::digest(file.contents());
+            format!("\"{}\"", URL_SAFE_NO_PAD.encode(&digest[..16]))
+        })
+        .clone();
+
+    CachedFileResponse {
+        content: file.contents(),
+        content_type,
+        etag,
+        last_modified: BuildInfo::get().build_datetime().ok(),
+        immutable: file.path().starts_with(HASHED_ASSETS_DIR),
+    }
 }
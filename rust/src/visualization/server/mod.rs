@@ -61,8 +61,8 @@ pub mod proxy;
 
 // Re-export main functions from builder
 pub use self::builder::{
-    build_openapi_spec, build_rocket, build_rocket_for_daemon, generate_openapi_json,
-    get_generix_config,
+    build_openapi_spec, build_rocket, build_rocket_for_daemon, configure_mutual_tls,
+    generate_openapi_json, generate_openapi_yaml, get_generix_config,
 };
 
 #[cfg(test)]
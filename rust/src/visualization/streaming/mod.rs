@@ -1,5 +1,9 @@
 mod audio;
+mod websocket;
 pub use audio::{
     create_audio_stream, create_node_audio_stream, get_audio_streaming_routes,
     AudioFastFrameResponse, AudioFrameResponse, AudioStreamState, SpectralDataResponse,
 };
+pub use websocket::{
+    get_websocket_routes, StreamChannel, WebSocketAudioFrame, WebSocketBearer, SEND_TIMEOUT,
+};
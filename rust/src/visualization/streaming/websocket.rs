@@ -0,0 +1,282 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! WebSocket streaming of live audio frames
+//!
+//! `GET /ws/stream` upgrades to a WebSocket which pushes every frame published on
+//! the realtime [`SharedAudioStream`](crate::acquisition::SharedAudioStream) as a
+//! JSON text message, without the per-request overhead of polling.
+//!
+//! ### Authentication
+//!
+//! The upgrade request needs a JWT with the `read:api` permission. Browsers
+//! cannot set headers on WebSocket requests, so besides the usual
+//! `Authorization: Bearer <token>` header the token is accepted in the
+//! `access_token` query parameter.
+//!
+//! ### Channel Selection
+//!
+//! Clients pick the channel with the `channel` query parameter (`a`, `b` or
+//! `differential`, default `a`) and can switch later by sending a text message:
+//!
+//! ```json
+//! {"channel": "differential"}
+//! ```
+//!
+//! ### Backpressure
+//!
+//! Frames are read from a bounded broadcast channel. A client that does not keep
+//! up skips the oldest frames instead of slowing down the acquisition; the number
+//! of skipped frames is reported in the next message's `dropped_frames`. A client
+//! that does not accept a message within [`SEND_TIMEOUT`] is disconnected.
+
+use super::AudioStreamState;
+use crate::acquisition::AudioFrame;
+use crate::config::Config;
+use crate::visualization::auth::guards::{OAuthBearer, RateLimit};
+use rocket::futures::{SinkExt, StreamExt};
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::response::status::Forbidden;
+use rocket::{get, routes, Either, FromFormField, State};
+use rocket_ws::{Channel, Message, WebSocket};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::RwLock;
+use tokio::time::timeout;
+
+/// Maximum time a client may take to accept a message before being disconnected
+pub const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Interval without frames after which a ping keeps the connection alive
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Audio channel streamed to a WebSocket client
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, FromFormField)]
+#[serde(rename_all = "lowercase")]
+pub enum StreamChannel {
+    /// Channel A samples
+    #[default]
+    A,
+    /// Channel B samples
+    B,
+    /// Channel A minus channel B, sample by sample
+    Differential,
+}
+
+impl StreamChannel {
+    /// Extract the samples of this channel from a frame
+    pub fn samples(&self, frame: &AudioFrame) -> Vec<f32> {
+        match self {
+            StreamChannel::A => frame.channel_a.clone(),
+            StreamChannel::B => frame.channel_b.clone(),
+            StreamChannel::Differential => frame
+                .channel_a
+                .iter()
+                .zip(&frame.channel_b)
+                .map(|(a, b)| a - b)
+                .collect(),
+        }
+    }
+}
+
+/// Message pushed to WebSocket clients for each audio frame
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebSocketAudioFrame {
+    /// Channel the samples belong to
+    pub channel: StreamChannel,
+    /// Samples of the selected channel
+    pub samples: Vec<f32>,
+    /// Sample rate of the audio data
+    pub sample_rate: u32,
+    /// Timestamp when the frame was captured
+    pub timestamp: u64,
+    /// Sequential frame number
+    pub frame_number: u64,
+    /// Frames skipped since the previous message because the client fell behind
+    pub dropped_frames: u64,
+}
+
+impl WebSocketAudioFrame {
+    /// Build the message for `frame` restricted to `channel`
+    pub fn new(frame: &AudioFrame, channel: StreamChannel, dropped_frames: u64) -> Self {
+        Self {
+            channel,
+            samples: channel.samples(frame),
+            sample_rate: frame.sample_rate,
+            timestamp: frame.timestamp,
+            frame_number: frame.frame_number,
+            dropped_frames,
+        }
+    }
+}
+
+/// Text message sent by clients to change their channel subscription
+#[derive(Debug, Clone, Deserialize)]
+struct SubscribeMessage {
+    channel: StreamChannel,
+}
+
+/// Bearer guard for WebSocket upgrade requests
+///
+/// Uses the `Authorization` header when present, otherwise the `access_token`
+/// query parameter. Without either, it behaves like [`OAuthBearer`] (including
+/// the local loopback bypass).
+pub struct WebSocketBearer(pub OAuthBearer);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for WebSocketBearer {
+    type Error = (Status, &'static str);
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let query_token = match request.query_value::<&str>("access_token") {
+            Some(Ok(token)) if request.headers().get_one("Authorization").is_none() => token,
+            _ => return request.guard::<OAuthBearer>().await.map(WebSocketBearer),
+        };
+
+        let access_config = match request.guard::<&State<Arc<RwLock<Config>>>>().await {
+            Outcome::Success(config) => config.read().await.access.clone(),
+            _ => {
+                return Outcome::Error((
+                    Status::InternalServerError,
+                    (Status::InternalServerError, "Missing config state"),
+                ))
+            }
+        };
+        OAuthBearer::from_token(request, query_token, &access_config)
+            .await
+            .map(WebSocketBearer)
+    }
+}
+
+/// Stream realtime audio frames over a WebSocket
+///
+/// ### Authentication
+/// Requires a valid JWT token with `read:api` permission, given in the
+/// `Authorization` header or the `access_token` query parameter.
+///
+/// ### Parameters
+/// - `channel`: `a`, `b` or `differential` (default `a`)
+///
+/// ### Message Format
+/// Each frame is sent as a JSON text message:
+/// ```json
+/// {"channel": "a", "samples": [...], "sample_rate": 48000, "timestamp": 1700000000000, "frame_number": 42, "dropped_frames": 0}
+/// ```
+#[get("/ws/stream?<channel>")]
+pub fn stream_audio_websocket(
+    ws: WebSocket,
+    bearer: WebSocketBearer,
+    _rate_limit: RateLimit,
+    channel: Option<StreamChannel>,
+    stream_state: &State<AudioStreamState>,
+) -> Either<Forbidden<&'static str>, Channel<'static>> {
+    if !bearer.0.has_permission("read:api") {
+        return Either::Left(Forbidden("Permission denied"));
+    }
+
+    let mut receiver = stream_state.stream.subscribe();
+    let mut selected = channel.unwrap_or_default();
+
+    Either::Right(ws.channel(move |mut socket| {
+        Box::pin(async move {
+            let mut dropped_frames = 0u64;
+
+            loop {
+                tokio::select! {
+                    received = receiver.recv() => {
+                        let frame = match received {
+                            Ok(frame) => frame,
+                            Err(RecvError::Lagged(skipped)) => {
+                                log::debug!("WebSocket client lagged behind, skipped {} frames", skipped);
+                                dropped_frames += skipped;
+                                continue;
+                            }
+                            Err(RecvError::Closed) => {
+                                log::info!("Audio stream closed, ending WebSocket stream");
+                                break;
+                            }
+                        };
+
+                        let message = WebSocketAudioFrame::new(&frame, selected, dropped_frames);
+                        let text = match serde_json::to_string(&message) {
+                            Ok(text) => text,
+                            Err(e) => {
+                                log::error!("Failed to serialize WebSocket audio frame: {}", e);
+                                continue;
+                            }
+                        };
+                        match timeout(SEND_TIMEOUT, socket.send(Message::Text(text))).await {
+                            Ok(Ok(())) => dropped_frames = 0,
+                            Ok(Err(e)) => {
+                                log::debug!("WebSocket client disconnected: {}", e);
+                                break;
+                            }
+                            Err(_) => {
+                                log::warn!("WebSocket client too slow, closing the connection");
+                                break;
+                            }
+                        }
+                    }
+                    incoming = socket.next() => match incoming {
+                        Some(Ok(Message::Text(text))) => {
+                            match serde_json::from_str::<SubscribeMessage>(&text) {
+                                Ok(subscribe) => selected = subscribe.channel,
+                                Err(e) => log::debug!("Ignoring WebSocket message {:?}: {}", text, e),
+                            }
+                        }
+                        Some(Ok(Message::Close(_))) | None => break,
+                        Some(Ok(_)) => {}
+                        Some(Err(e)) => {
+                            log::debug!("WebSocket receive error: {}", e);
+                            break;
+                        }
+                    },
+                    _ = tokio::time::sleep(HEARTBEAT_INTERVAL) => {
+                        if socket.send(Message::Ping(Vec::new())).await.is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+
+            Ok(())
+        })
+    }))
+}
+
+/// Get the WebSocket streaming routes
+///
+/// These routes are not part of the OpenAPI specification, which cannot
+/// describe WebSocket upgrades.
+pub fn get_websocket_routes() -> Vec<rocket::Route> {
+    routes![stream_audio_websocket]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_samples() {
+        let frame = AudioFrame::new(vec![1.0, 2.0, 3.0], vec![0.5, 0.5, 4.0], 48000, 1);
+
+        assert_eq!(StreamChannel::A.samples(&frame), vec![1.0, 2.0, 3.0]);
+        assert_eq!(StreamChannel::B.samples(&frame), vec![0.5, 0.5, 4.0]);
+        assert_eq!(
+            StreamChannel::Differential.samples(&frame),
+            vec![0.5, 1.5, -1.0]
+        );
+    }
+
+    #[test]
+    fn test_subscribe_message() {
+        let message: SubscribeMessage =
+            serde_json::from_str(r#"{"channel": "differential"}"#).unwrap();
+        assert_eq!(message.channel, StreamChannel::Differential);
+        assert!(serde_json::from_str::<SubscribeMessage>(r#"{"channel": "c"}"#).is_err());
+    }
+}
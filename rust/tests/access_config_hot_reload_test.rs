@@ -107,7 +107,8 @@ fn issue_test_token(username: &str) -> String {
 
 /// Create a `JwtValidator` configured for the test HMAC secret.
 fn test_jwt_validator(access: AccessConfig) -> JwtValidator {
-    init_jwt_validator(TEST_HMAC_SECRET, None, access).expect("validator creation must not fail")
+    init_jwt_validator(TEST_HMAC_SECRET, None, None, access)
+        .expect("validator creation must not fail")
 }
 
 /// Build a full Rocket instance around a shared `Arc<RwLock<Config>>`.
@@ -134,6 +135,7 @@ fn make_user(username: &str, permissions: &[&str]) -> User {
         user: username.to_string(),
         pass: ADMIN123_HASH.to_string(),
         permissions: permissions.iter().map(|s| s.to_string()).collect(),
+        roles: vec![],
         email: Some(format!("{}@example.com", username)),
         name: Some(username.to_string()),
     }
@@ -910,6 +912,7 @@ fn test_validator_expected_audience_wins_over_clients() {
         client_id: "WrongClient".to_string(),
         default_scope: "read:api".to_string(),
         allowed_callbacks: vec![],
+        ..Client::default()
     }];
 
     // Create a validator WITH expected_audience — mirrors what init_jwt_validator does.
@@ -959,6 +962,7 @@ fn test_validator_falls_back_to_clients_without_expected_audience() {
         client_id: "AnotherApp".to_string(),
         default_scope: "read:api".to_string(),
         allowed_callbacks: vec![],
+        ..Client::default()
     }];
     let validator_no_match =
         JwtValidator::new(Some(TEST_HMAC_SECRET.as_bytes()), None, access_no_match)
@@ -1015,6 +1019,7 @@ async fn test_build_rocket_for_daemon_returns_shared_oxide_state() {
         user: "phase5_user".to_string(),
         pass: ADMIN123_HASH.to_string(),
         permissions: vec!["read:api".to_string()],
+        roles: vec![],
         email: None,
         name: None,
     });
@@ -1077,6 +1082,7 @@ async fn test_build_rocket_for_daemon_update_access_config_updates_clients() {
         client_id: "HotReloadedClient".to_string(),
         default_scope: "read:api".to_string(),
         allowed_callbacks: vec!["https://localhost/callback2".to_string()],
+        ..Client::default()
     });
     oxide_state_clone.update_access_config(new_access).await;
 
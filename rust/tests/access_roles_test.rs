@@ -0,0 +1,131 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Tests for role based permissions
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_effective_permissions_expand_roles`] | Role permissions are merged with the user's own permissions |
+//! | [`test_effective_permissions_ignore_unknown_roles`] | Unknown role names do not grant anything |
+//! | [`test_user_info_carries_role_permissions`] | Validated tokens expose the effective permissions |
+//! | [`test_has_any_permission`] | `OAuthBearer::has_any_permission` matches on one permission |
+//! | [`test_has_all_permissions`] | `OAuthBearer::has_all_permissions` requires every permission |
+//! | [`test_unknown_role_fails_validation`] | Users referencing undefined roles are rejected |
+
+use oxide_auth::primitives::grant::{Extensions, Grant};
+use oxide_auth::primitives::issuer::Issuer;
+use rust_photoacoustic::config::utils::validate_specific_rules;
+use rust_photoacoustic::config::{AccessConfig, Config, Role, User};
+use rust_photoacoustic::visualization::api_auth::init_jwt_validator;
+use rust_photoacoustic::visualization::auth::jwt::JwtIssuer;
+use rust_photoacoustic::visualization::auth::OAuthBearer;
+
+mod common;
+use common::TEST_HMAC_SECRET;
+
+/// Access configuration with an `operator` and an `auditor` role
+fn access_with_roles(user_permissions: &[&str], user_roles: &[&str]) -> AccessConfig {
+    let mut access = AccessConfig::default();
+    access.roles = vec![
+        Role {
+            name: "operator".to_string(),
+            permissions: vec!["read:api".to_string(), "write:api".to_string()],
+        },
+        Role {
+            name: "auditor".to_string(),
+            permissions: vec!["read:api".to_string(), "read:logs".to_string()],
+        },
+    ];
+    access.users = vec![User {
+        user: "alice".to_string(),
+        permissions: user_permissions.iter().map(|s| s.to_string()).collect(),
+        roles: user_roles.iter().map(|s| s.to_string()).collect(),
+        ..User::default()
+    }];
+    access
+}
+
+/// Build a bearer for `alice` from a token validated against `access`
+fn bearer_for(access: AccessConfig) -> OAuthBearer {
+    let mut issuer = JwtIssuer::new(TEST_HMAC_SECRET.as_bytes());
+    let grant = Grant {
+        owner_id: "alice".to_string(),
+        client_id: "LaserSmartClient".to_string(),
+        scope: "read:api".parse().unwrap(),
+        redirect_uri: "https://localhost/callback".parse().unwrap(),
+        until: chrono::Utc::now() + chrono::Duration::hours(1),
+        extensions: Extensions::new(),
+    };
+    let token = issuer.issue(grant).expect("token issued").token;
+
+    let validator = init_jwt_validator(TEST_HMAC_SECRET, None, None, access.clone())
+        .expect("validator creation must not fail");
+    let user_info = validator
+        .get_user_info(&token, access)
+        .expect("alice is a known user");
+    OAuthBearer {
+        permissions: user_info.permissions.clone(),
+        user_info,
+        token,
+    }
+}
+
+#[test]
+fn test_effective_permissions_expand_roles() {
+    let access = access_with_roles(&["admin:api", "read:api"], &["operator", "auditor"]);
+
+    assert_eq!(
+        access.effective_permissions(&access.users[0]),
+        vec!["admin:api", "read:api", "write:api", "read:logs"]
+    );
+}
+
+#[test]
+fn test_effective_permissions_ignore_unknown_roles() {
+    let access = access_with_roles(&["read:api"], &["superuser"]);
+
+    assert_eq!(
+        access.effective_permissions(&access.users[0]),
+        vec!["read:api"]
+    );
+}
+
+#[test]
+fn test_user_info_carries_role_permissions() {
+    let bearer = bearer_for(access_with_roles(&[], &["operator"]));
+
+    assert_eq!(
+        bearer.user_info.permissions,
+        Some(vec!["read:api".to_string(), "write:api".to_string()])
+    );
+}
+
+#[test]
+fn test_has_any_permission() {
+    let bearer = bearer_for(access_with_roles(&[], &["auditor"]));
+
+    assert!(bearer.has_any_permission(&["write:api", "read:logs"]));
+    assert!(!bearer.has_any_permission(&["write:api", "admin:api"]));
+    assert!(!bearer.has_any_permission(&[]));
+}
+
+#[test]
+fn test_has_all_permissions() {
+    let bearer = bearer_for(access_with_roles(&["admin:api"], &["operator"]));
+
+    assert!(bearer.has_all_permissions(&["read:api", "write:api", "admin:api"]));
+    assert!(!bearer.has_all_permissions(&["read:api", "read:logs"]));
+    assert!(bearer.has_all_permissions(&[]));
+}
+
+#[test]
+fn test_unknown_role_fails_validation() {
+    let mut config = Config::default();
+    config.access = access_with_roles(&[], &["operator"]);
+    assert!(validate_specific_rules(&config).is_ok());
+
+    config.access.users[0].roles.push("superuser".to_string());
+    let err = validate_specific_rules(&config).unwrap_err();
+    assert!(err.to_string().contains("unknown role"));
+}
@@ -0,0 +1,253 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the dead-letter queue of the action nodes
+//!
+//! The driver under test fails while its `failing` flag is set, and records
+//! the concentrations it delivers otherwise.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_failed_measurements_are_queued_and_redelivered`] | Undelivered measurements land in the bounded queue file and are re-delivered in order once the driver succeeds |
+//! | [`test_queue_survives_restart`] | Measurements queued before a shutdown are re-delivered by the next node using the same directory |
+//! | [`test_queue_from_graph_config`] | The `dead_letter` parameter creates the queue, its directory is required |
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use rust_photoacoustic::config::processing::{NodeConfig, ProcessingGraphConfig};
+use rust_photoacoustic::processing::computing_nodes::{
+    ActionNode, ComputingSharedData, ConcentrationResult,
+};
+use rust_photoacoustic::processing::{
+    ActionDriver, AlertData, DeadLetterQueue, DeadLetterStats, MeasurementData, ProcessingGraph,
+    ProcessingNode, UniversalActionNode,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tempfile::tempdir;
+
+const RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Action driver failing on demand and recording the delivered concentrations
+#[derive(Debug)]
+struct FlakyDriver {
+    failing: Arc<AtomicBool>,
+    delivered: Arc<Mutex<Vec<f64>>>,
+}
+
+#[async_trait]
+impl ActionDriver for FlakyDriver {
+    async fn initialize(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn update_action(&mut self, data: &MeasurementData) -> Result<()> {
+        if self.failing.load(Ordering::SeqCst) {
+            return Err(anyhow!("endpoint unreachable"));
+        }
+        self.delivered.lock().unwrap().push(data.concentration_ppm);
+        Ok(())
+    }
+
+    async fn show_alert(&mut self, _alert: &AlertData) -> Result<()> {
+        Ok(())
+    }
+
+    async fn clear_action(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_status(&self) -> Result<Value> {
+        Ok(json!({ "driver_type": self.driver_type() }))
+    }
+
+    fn driver_type(&self) -> &str {
+        "flaky"
+    }
+}
+
+/// Action node sending every update to a `FlakyDriver`, with a dead-letter queue in `directory`
+fn flaky_node(
+    directory: &Path,
+    capacity: usize,
+    failing: &Arc<AtomicBool>,
+    delivered: &Arc<Mutex<Vec<f64>>>,
+) -> Result<UniversalActionNode> {
+    let queue = DeadLetterQueue::open(directory, "action", capacity, RETRY_INTERVAL)?;
+    Ok(UniversalActionNode::new("action".to_string())
+        .with_history_buffer_capacity(10)
+        .with_update_interval(0)
+        .with_dead_letter_queue(queue)
+        .with_driver(Box::new(FlakyDriver {
+            failing: Arc::clone(failing),
+            delivered: Arc::clone(delivered),
+        })))
+}
+
+/// Have the node send a measurement of `concentration_ppm` to its driver
+fn send_measurement(node: &mut UniversalActionNode, concentration_ppm: f64) -> Result<()> {
+    let mut computing_data = ComputingSharedData::default();
+    computing_data.update_concentration_result(
+        "concentration".to_string(),
+        ConcentrationResult {
+            concentration_ppm,
+            concentration_variance: None,
+            source_peak_finder_id: "peak_finder".to_string(),
+            spectral_line_id: None,
+            polynomial_coefficients: [0.0; 5],
+            source_amplitude: 0.1,
+            source_frequency: 2000.0,
+            temperature_compensated: false,
+            timestamp: SystemTime::now(),
+            processing_metadata: HashMap::new(),
+        },
+    );
+    node.update_from_computing_data(&computing_data)
+}
+
+/// Wait until `condition` holds, failing after 5 seconds
+fn wait_until(description: &str, condition: impl Fn() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !condition() {
+        assert!(
+            Instant::now() < deadline,
+            "timed out waiting for {}",
+            description
+        );
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Concentrations stored in a queue file
+fn queued_concentrations(path: &Path) -> Result<Vec<f64>> {
+    std::fs::read_to_string(path)?
+        .lines()
+        .map(|line| Ok(serde_json::from_str::<MeasurementData>(line)?.concentration_ppm))
+        .collect()
+}
+
+#[test]
+fn test_failed_measurements_are_queued_and_redelivered() -> Result<()> {
+    let dir = tempdir()?;
+    let failing = Arc::new(AtomicBool::new(true));
+    let delivered = Arc::new(Mutex::new(Vec::new()));
+    let mut node = flaky_node(dir.path(), 3, &failing, &delivered)?;
+
+    for concentration in [10.0, 20.0, 30.0, 40.0, 50.0] {
+        send_measurement(&mut node, concentration)?;
+    }
+    wait_until("the failed measurements to be queued", || {
+        node.dead_letter_stats().unwrap().dead_lettered == 5
+    });
+
+    // The two oldest measurements were dropped to respect the capacity
+    assert_eq!(
+        node.dead_letter_stats(),
+        Some(DeadLetterStats {
+            queued: 3,
+            dead_lettered: 5,
+            redelivered: 0,
+            dropped: 2,
+        })
+    );
+    let queue_file = dir.path().join("action.jsonl");
+    assert_eq!(queued_concentrations(&queue_file)?, vec![30.0, 40.0, 50.0]);
+    assert_eq!(node.get_history_statistics()["dead_letter"]["queued"], 3);
+    assert!(delivered.lock().unwrap().is_empty());
+
+    // Without new measurements, the periodic attempts deliver the backlog
+    failing.store(false, Ordering::SeqCst);
+    wait_until("the queue to be delivered", || {
+        node.dead_letter_stats().unwrap().queued == 0
+    });
+    assert_eq!(*delivered.lock().unwrap(), vec![30.0, 40.0, 50.0]);
+    assert_eq!(node.dead_letter_stats().unwrap().redelivered, 3);
+    assert!(queued_concentrations(&queue_file)?.is_empty());
+
+    // Delivered measurements do not go through the queue
+    send_measurement(&mut node, 60.0)?;
+    wait_until("the measurement to be delivered", || {
+        delivered.lock().unwrap().len() == 4
+    });
+    assert_eq!(node.dead_letter_stats().unwrap().dead_lettered, 5);
+
+    node.shutdown();
+    Ok(())
+}
+
+#[test]
+fn test_queue_survives_restart() -> Result<()> {
+    let dir = tempdir()?;
+    let failing = Arc::new(AtomicBool::new(true));
+    let delivered = Arc::new(Mutex::new(Vec::new()));
+
+    let mut node = flaky_node(dir.path(), 10, &failing, &delivered)?;
+    send_measurement(&mut node, 11.0)?;
+    send_measurement(&mut node, 12.0)?;
+    wait_until("the failed measurements to be queued", || {
+        node.dead_letter_stats().unwrap().queued == 2
+    });
+    node.shutdown();
+
+    let queue = DeadLetterQueue::open(dir.path(), "action", 10, RETRY_INTERVAL)?;
+    assert_eq!(queue.len(), 2);
+    assert_eq!(queue.front().unwrap().concentration_ppm, 11.0);
+    drop(queue);
+
+    // The next run delivers the backlog as soon as its driver is initialized
+    failing.store(false, Ordering::SeqCst);
+    let mut restarted = flaky_node(dir.path(), 10, &failing, &delivered)?;
+    wait_until("the backlog to be delivered", || {
+        delivered.lock().unwrap().len() == 2
+    });
+    assert_eq!(*delivered.lock().unwrap(), vec![11.0, 12.0]);
+    assert_eq!(restarted.dead_letter_stats().unwrap().queued, 0);
+
+    restarted.shutdown();
+    Ok(())
+}
+
+#[test]
+fn test_queue_from_graph_config() -> Result<()> {
+    let dir = tempdir()?;
+    let directory = dir.path().join("dead_letter");
+    let graph_config = |dead_letter: Value| ProcessingGraphConfig {
+        id: "dead_letter".to_string(),
+        nodes: vec![
+            NodeConfig {
+                id: "input".to_string(),
+                node_type: "input".to_string(),
+                parameters: Value::Null,
+            },
+            NodeConfig {
+                id: "dashboard".to_string(),
+                node_type: "action_universal".to_string(),
+                parameters: json!({ "buffer_capacity": 10, "dead_letter": dead_letter }),
+            },
+        ],
+        connections: Vec::new(),
+        output_node: None,
+    };
+
+    let graph = ProcessingGraph::from_config(&graph_config(json!({
+        "directory": directory,
+        "capacity": 5,
+        "retry_interval_ms": 100
+    })))?;
+    let node = graph.get_universal_action_node("dashboard").unwrap();
+    assert_eq!(node.dead_letter_stats(), Some(DeadLetterStats::default()));
+    assert!(directory.is_dir());
+
+    assert!(ProcessingGraph::from_config(&graph_config(json!({ "capacity": 5 }))).is_err());
+    assert!(ProcessingGraph::from_config(&graph_config(json!({
+        "directory": directory,
+        "capacity": 0
+    })))
+    .is_err());
+    Ok(())
+}
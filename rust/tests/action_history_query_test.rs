@@ -0,0 +1,606 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the time range and cursor parameters of the action history API
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_time_range_filter`] | `since` / `until` keep the entries of an inclusive time window |
+//! | [`test_limit_keeps_newest_entries`] | `limit` alone still returns the newest entries |
+//! | [`test_backward_pagination`] | Following `before` cursors walks the whole history once, newest first |
+//! | [`test_forward_pagination`] | Following `after` cursors walks towards newer entries |
+//! | [`test_pagination_with_duplicate_timestamps`] | Page boundaries inside a group of entries sharing a timestamp skip and repeat nothing |
+//! | [`test_history_endpoint_pagination`] | The endpoint applies the parameters and returns `next_cursor` in the body and `X-Next-Cursor` |
+//! | [`test_history_to_csv`] | CSV export has a header row and flattens metadata into columns |
+//! | [`test_history_endpoint_formats`] | JSON by default, CSV with `Accept: text/csv` or `format=csv` |
+//! | [`test_combined_history`] | `/api/action/history?nodes=...` maps each node to its history or an error |
+//! | [`test_clear_history`] | `DELETE` empties the history buffer and resets the node counters |
+
+use rocket::http::{Accept, ContentType, Header, Status};
+use rocket::local::asynchronous::{Client, LocalResponse};
+use rust_photoacoustic::config::Config;
+use rust_photoacoustic::processing::computing_nodes::action_drivers::MeasurementData;
+use rust_photoacoustic::processing::computing_nodes::action_trait::ActionNode;
+use rust_photoacoustic::processing::computing_nodes::{ComputingSharedData, ConcentrationResult};
+use rust_photoacoustic::processing::{
+    InputNode, ProcessingGraph, ProcessingNode, UniversalActionNode,
+};
+use rust_photoacoustic::visualization::api::action::{
+    history_cursors, history_to_csv, paginate_history, HistoryCursor, HistoryQuery,
+};
+use rust_photoacoustic::visualization::shared_state::SharedVisualizationState;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
+
+mod common;
+use common::{access_token, test_figment, TEST_HMAC_SECRET};
+
+/// Unix time of the oldest generated entry
+const BASE_SECONDS: u64 = 1_700_000_000;
+
+/// Ten entries one minute apart, newest first, with `concentration_ppm` set to their index
+fn minute_history() -> Vec<MeasurementData> {
+    (0..10u64)
+        .rev()
+        .map(|index| MeasurementData {
+            concentration_ppm: index as f64,
+            source_node_id: "concentration".to_string(),
+            peak_amplitude: 0.5,
+            peak_frequency: 2000.0,
+            timestamp: UNIX_EPOCH + Duration::from_secs(BASE_SECONDS + index * 60),
+            metadata: HashMap::new(),
+        })
+        .collect()
+}
+
+fn indices(entries: &[MeasurementData]) -> Vec<u64> {
+    entries
+        .iter()
+        .map(|entry| entry.concentration_ppm as u64)
+        .collect()
+}
+
+#[test]
+fn test_time_range_filter() {
+    let page = paginate_history(
+        minute_history(),
+        &HistoryQuery {
+            since: Some(BASE_SECONDS + 120),
+            until: Some(BASE_SECONDS + 300),
+            ..HistoryQuery::default()
+        },
+    );
+    assert_eq!(indices(&page.items), vec![5, 4, 3, 2]);
+    assert_eq!(page.next_cursor, None);
+
+    // Open-ended ranges
+    let page = paginate_history(
+        minute_history(),
+        &HistoryQuery {
+            since: Some(BASE_SECONDS + 480),
+            ..HistoryQuery::default()
+        },
+    );
+    assert_eq!(indices(&page.items), vec![9, 8]);
+
+    let page = paginate_history(
+        minute_history(),
+        &HistoryQuery {
+            until: Some(BASE_SECONDS + 59),
+            ..HistoryQuery::default()
+        },
+    );
+    assert_eq!(indices(&page.items), vec![0]);
+}
+
+#[test]
+fn test_limit_keeps_newest_entries() {
+    let page = paginate_history(
+        minute_history(),
+        &HistoryQuery {
+            limit: Some(3),
+            ..HistoryQuery::default()
+        },
+    );
+    assert_eq!(indices(&page.items), vec![9, 8, 7]);
+    assert_eq!(
+        page.next_cursor,
+        Some(history_cursors(&minute_history())[2])
+    );
+
+    // Combined with a time range
+    let page = paginate_history(
+        minute_history(),
+        &HistoryQuery {
+            limit: Some(2),
+            until: Some(BASE_SECONDS + 300),
+            ..HistoryQuery::default()
+        },
+    );
+    assert_eq!(indices(&page.items), vec![5, 4]);
+}
+
+#[test]
+fn test_backward_pagination() {
+    let mut pages = Vec::new();
+    let mut before = None;
+    loop {
+        let page = paginate_history(
+            minute_history(),
+            &HistoryQuery {
+                limit: Some(4),
+                before,
+                ..HistoryQuery::default()
+            },
+        );
+        pages.push(indices(&page.items));
+        match page.next_cursor {
+            Some(cursor) => before = Some(cursor),
+            None => break,
+        }
+    }
+    assert_eq!(pages, vec![vec![9, 8, 7, 6], vec![5, 4, 3, 2], vec![1, 0]]);
+}
+
+#[test]
+fn test_forward_pagination() {
+    let cursors = history_cursors(&minute_history());
+    let mut after = Some(cursors[7]); // entry 2
+
+    let mut pages = Vec::new();
+    while let Some(cursor) = after {
+        let page = paginate_history(
+            minute_history(),
+            &HistoryQuery {
+                limit: Some(3),
+                after: Some(cursor),
+                ..HistoryQuery::default()
+            },
+        );
+        pages.push(indices(&page.items));
+        after = page.next_cursor;
+    }
+    assert_eq!(pages, vec![vec![5, 4, 3], vec![8, 7, 6], vec![9]]);
+
+    // `before` and `after` together select the entries strictly between them
+    let page = paginate_history(
+        minute_history(),
+        &HistoryQuery {
+            after: Some(cursors[7]),
+            before: Some(cursors[2]),
+            ..HistoryQuery::default()
+        },
+    );
+    assert_eq!(indices(&page.items), vec![6, 5, 4, 3]);
+}
+
+/// Walk the whole history with pages of `limit` entries, backward or forward
+fn walk_history(history: &[MeasurementData], limit: usize, forward: bool) -> Vec<Vec<u64>> {
+    let mut pages = Vec::new();
+    let mut cursor = forward.then(|| HistoryCursor {
+        timestamp_ns: 0,
+        sequence: 0,
+    });
+    loop {
+        let query = HistoryQuery {
+            limit: Some(limit),
+            before: cursor.filter(|_| !forward),
+            after: cursor.filter(|_| forward),
+            ..HistoryQuery::default()
+        };
+        let page = paginate_history(history.to_vec(), &query);
+        pages.push(indices(&page.items));
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    pages
+}
+
+#[test]
+fn test_pagination_with_duplicate_timestamps() {
+    // Entries 2 to 6 share the timestamp of entry 2
+    let mut history = minute_history();
+    let shared = history[7].timestamp;
+    for entry in &mut history[3..=7] {
+        entry.timestamp = shared;
+    }
+
+    let cursors = history_cursors(&history);
+    assert_eq!(cursors[7].sequence, 0);
+    assert_eq!(cursors[3].sequence, 4);
+    assert_eq!(cursors[2].sequence, 0);
+    assert!(cursors.windows(2).all(|pair| pair[0] > pair[1]));
+    assert_eq!(
+        cursors[3].to_string().parse::<HistoryCursor>().unwrap(),
+        cursors[3]
+    );
+
+    assert_eq!(
+        walk_history(&history, 3, false),
+        vec![vec![9, 8, 7], vec![6, 5, 4], vec![3, 2, 1], vec![0]]
+    );
+    assert_eq!(
+        walk_history(&history, 4, true),
+        vec![vec![3, 2, 1, 0], vec![7, 6, 5, 4], vec![9, 8]]
+    );
+}
+
+/// Action node `node_id` with `entries` history entries recorded from `concentration`
+fn action_node_with_history(node_id: &str, entries: usize) -> UniversalActionNode {
+    let mut node = UniversalActionNode::new(node_id.to_string())
+        .with_history_buffer_capacity(entries)
+        .with_monitored_node("concentration".to_string());
+
+    for index in 0..entries {
+        let mut computing_data = ComputingSharedData::default();
+        computing_data.update_concentration_result(
+            "concentration".to_string(),
+            ConcentrationResult {
+                concentration_ppm: index as f64,
+                concentration_variance: None,
+                source_peak_finder_id: "peak_finder".to_string(),
+                spectral_line_id: None,
+                polynomial_coefficients: [0.0, 1.0, 0.0, 0.0, 0.0],
+                source_amplitude: 0.5,
+                source_frequency: 2000.0,
+                temperature_compensated: false,
+                timestamp: SystemTime::now(),
+                processing_metadata: HashMap::new(),
+            },
+        );
+        node.update_from_computing_data(&computing_data).unwrap();
+        // Distinct timestamps so that cursors identify a single entry
+        std::thread::sleep(Duration::from_millis(2));
+    }
+    node
+}
+
+async fn build_test_client(nodes: Vec<UniversalActionNode>) -> Client {
+    let mut graph = ProcessingGraph::new();
+    graph
+        .add_node(Box::new(InputNode::new("input".to_string())))
+        .unwrap();
+    for node in nodes {
+        let node_id = node.node_id().to_string();
+        graph.add_node(Box::new(node)).unwrap();
+        graph.connect("input", &node_id).unwrap();
+    }
+
+    let visualization_state = SharedVisualizationState::new();
+    visualization_state
+        .set_live_processing_graph(Arc::new(RwLock::new(graph)))
+        .await;
+
+    let mut config = Config::default();
+    config.visualization.hmac_secret = TEST_HMAC_SECRET.to_string();
+
+    let rocket = rust_photoacoustic::visualization::server::build_rocket(
+        test_figment(),
+        Arc::new(RwLock::new(config)),
+        None,
+        Some(Arc::new(visualization_state)),
+        None,
+        None,
+        None,
+    )
+    .await;
+    Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance")
+}
+
+async fn get_history<'c>(client: &'c Client, token: &str, query: &str) -> LocalResponse<'c> {
+    client
+        .get(format!("/api/action/action/history{}", query))
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .dispatch()
+        .await
+}
+
+/// Concentrations of a JSON history page, newest first
+async fn concentrations(response: LocalResponse<'_>) -> Vec<u64> {
+    let body: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    body["items"]
+        .as_array()
+        .expect("history array")
+        .iter()
+        .map(|entry| entry["concentration_ppm"].as_f64().unwrap() as u64)
+        .collect()
+}
+
+#[rocket::async_test]
+async fn test_history_endpoint_pagination() {
+    let client = build_test_client(vec![action_node_with_history("action", 5)]).await;
+    let token = access_token(&client, "admin", "read:api write:api");
+
+    // Without parameters the whole history is returned
+    let response = get_history(&client, &token, "").await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.headers().get_one("X-Next-Cursor"), None);
+    assert_eq!(concentrations(response).await, vec![4, 3, 2, 1, 0]);
+
+    // First page and the cursor of the next one, in the body and the header
+    let response = get_history(&client, &token, "?limit=3").await;
+    assert_eq!(response.status(), Status::Ok);
+    let header = response
+        .headers()
+        .get_one("X-Next-Cursor")
+        .expect("next cursor")
+        .to_string();
+    let body: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    let cursor = body["next_cursor"]
+        .as_str()
+        .expect("next cursor")
+        .to_string();
+    assert_eq!(cursor, header);
+    assert_eq!(body["items"].as_array().unwrap().len(), 3);
+    assert_eq!(body["items"][0]["concentration_ppm"], 4.0);
+
+    let response = get_history(&client, &token, &format!("?limit=3&before={}", cursor)).await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.headers().get_one("X-Next-Cursor"), None);
+    let body: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    assert!(body.get("next_cursor").is_none());
+    assert_eq!(body["items"].as_array().unwrap().len(), 2);
+
+    // Malformed cursors are rejected
+    let response = get_history(&client, &token, "?before=12345").await;
+    assert_eq!(response.status(), Status::BadRequest);
+
+    // Time range covering the current time, and one entirely in the future
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let response = get_history(
+        &client,
+        &token,
+        &format!("?since={}&until={}", now - 60, now + 60),
+    )
+    .await;
+    assert_eq!(concentrations(response).await.len(), 5);
+
+    let response = get_history(&client, &token, &format!("?since={}", now + 60)).await;
+    assert_eq!(response.status(), Status::Ok);
+    assert!(concentrations(response).await.is_empty());
+
+    // An inverted range is rejected
+    let response = get_history(
+        &client,
+        &token,
+        &format!("?since={}&until={}", now + 60, now - 60),
+    )
+    .await;
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+/// Parse a CSV document into its header and rows
+fn parse_csv(document: &str) -> (Vec<String>, Vec<Vec<String>>) {
+    let mut reader = csv::Reader::from_reader(document.as_bytes());
+    let header = reader
+        .headers()
+        .expect("CSV header")
+        .iter()
+        .map(str::to_string)
+        .collect();
+    let rows = reader
+        .records()
+        .map(|record| {
+            record
+                .expect("CSV record")
+                .iter()
+                .map(str::to_string)
+                .collect()
+        })
+        .collect();
+    (header, rows)
+}
+
+#[test]
+fn test_history_to_csv() {
+    let mut history = minute_history();
+    history.truncate(3);
+    history[0]
+        .metadata
+        .insert("trigger_type".to_string(), "concentration_threshold".into());
+    history[1]
+        .metadata
+        .insert("alert_message".to_string(), "High, \"quoted\" value".into());
+    history[1]
+        .metadata
+        .insert("threshold".to_string(), serde_json::json!(1000.5));
+
+    let (header, rows) = parse_csv(&history_to_csv(&history).unwrap());
+    assert_eq!(
+        header,
+        vec![
+            "timestamp",
+            "source_node_id",
+            "concentration_ppm",
+            "peak_amplitude",
+            "peak_frequency",
+            "metadata.alert_message",
+            "metadata.threshold",
+            "metadata.trigger_type",
+        ]
+    );
+    assert_eq!(rows.len(), 3);
+
+    // Rows keep the order of the history, newest first
+    assert_eq!(rows[0][0], "2023-11-14T22:22:20.000Z");
+    assert_eq!(rows[0][1], "concentration");
+    assert_eq!(rows[0][2], "9");
+    assert_eq!(rows[0][4], "2000");
+    assert_eq!(rows[0][5..], ["", "", "concentration_threshold"]);
+    assert_eq!(rows[1][5..], ["High, \"quoted\" value", "1000.5", ""]);
+    assert_eq!(rows[2][5..], ["", "", ""]);
+
+    // An empty history still has the fixed columns
+    let (header, rows) = parse_csv(&history_to_csv(&[]).unwrap());
+    assert_eq!(header.len(), 5);
+    assert!(rows.is_empty());
+}
+
+#[rocket::async_test]
+async fn test_history_endpoint_formats() {
+    let client = build_test_client(vec![action_node_with_history("action", 4)]).await;
+    let token = access_token(&client, "admin", "read:api write:api");
+
+    // JSON by default
+    let response = get_history(&client, &token, "").await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::JSON));
+    assert_eq!(concentrations(response).await, vec![3, 2, 1, 0]);
+
+    // CSV through content negotiation
+    let response = client
+        .get("/api/action/action/history")
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .header(Accept::CSV)
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::CSV));
+    let (header, rows) = parse_csv(&response.into_string().await.unwrap());
+    assert_eq!(
+        header,
+        vec![
+            "timestamp",
+            "source_node_id",
+            "concentration_ppm",
+            "peak_amplitude",
+            "peak_frequency",
+        ]
+    );
+    assert_eq!(rows.len(), 4);
+    assert_eq!(
+        rows.iter().map(|row| row[2].as_str()).collect::<Vec<_>>(),
+        vec!["3", "2", "1", "0"]
+    );
+
+    // CSV through the query parameter, combined with pagination
+    let response = get_history(&client, &token, "?format=csv&limit=2").await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::CSV));
+    assert!(response.headers().get_one("X-Next-Cursor").is_some());
+    let (_, rows) = parse_csv(&response.into_string().await.unwrap());
+    assert_eq!(rows.len(), 2);
+
+    // The query parameter takes precedence over the Accept header
+    let response = client
+        .get("/api/action/action/history?format=json")
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .header(Accept::CSV)
+        .dispatch()
+        .await;
+    assert_eq!(response.content_type(), Some(ContentType::JSON));
+
+    let response = get_history(&client, &token, "?format=xml").await;
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[rocket::async_test]
+async fn test_combined_history() {
+    let client = build_test_client(vec![
+        action_node_with_history("co2_action", 3),
+        action_node_with_history("ch4_action", 5),
+    ])
+    .await;
+    let token = access_token(&client, "admin", "read:api write:api");
+
+    let response = client
+        .get("/api/action/history?nodes=co2_action,%20ch4_action,missing_action&limit=4")
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    let body: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    let nodes = body.as_object().expect("map of nodes");
+    assert_eq!(nodes.len(), 3);
+
+    let co2 = body["co2_action"]["history"].as_array().unwrap();
+    assert_eq!(co2.len(), 3);
+    assert!(body["co2_action"].get("error").is_none());
+
+    // The limit applies to each node
+    let ch4 = body["ch4_action"]["history"].as_array().unwrap();
+    assert_eq!(
+        ch4.iter()
+            .map(|entry| entry["concentration_ppm"].as_f64().unwrap() as u64)
+            .collect::<Vec<_>>(),
+        vec![4, 3, 2, 1]
+    );
+
+    // Unknown ids get an error entry instead of failing the request
+    assert!(body["missing_action"].get("history").is_none());
+    assert!(body["missing_action"]["error"]
+        .as_str()
+        .unwrap()
+        .contains("missing_action"));
+
+    // The single node endpoint still fails for unknown nodes
+    let response = get_history(&client, &token, "").await;
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[rocket::async_test]
+async fn test_clear_history() {
+    let client = build_test_client(vec![action_node_with_history("action", 5)]).await;
+    let token = access_token(&client, "admin", "read:api write:api");
+    let authorization = Header::new("Authorization", format!("Bearer {}", token));
+
+    let response = get_history(&client, &token, "").await;
+    assert_eq!(concentrations(response).await.len(), 5);
+
+    let response = client
+        .delete("/api/action/action/history")
+        .header(authorization.clone())
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    let body: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    assert_eq!(body["node_id"], "action");
+    assert_eq!(body["removed_entries"], 5);
+
+    let response = get_history(&client, &token, "").await;
+    assert_eq!(response.status(), Status::Ok);
+    assert!(concentrations(response).await.is_empty());
+
+    let response = client
+        .get("/api/action/action/history/stats")
+        .header(authorization.clone())
+        .dispatch()
+        .await;
+    let stats: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    assert_eq!(stats["history_buffer"]["current_size"], 0);
+    assert_eq!(stats["history_buffer"]["capacity"], 5);
+    assert_eq!(stats["performance"]["processing_count"], 0);
+    assert_eq!(stats["performance"]["actions_triggered"], 0);
+    assert!(stats["performance"]["last_update_time"].is_null());
+
+    // Clearing again removes nothing
+    let response = client
+        .delete("/api/action/action/history")
+        .header(authorization.clone())
+        .dispatch()
+        .await;
+    let body: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    assert_eq!(body["removed_entries"], 0);
+
+    let response = client
+        .delete("/api/action/missing_action/history")
+        .header(authorization)
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::NotFound);
+
+    // Clearing requires authentication
+    let response = client.delete("/api/action/action/history").dispatch().await;
+    assert_eq!(response.status(), Status::Unauthorized);
+}
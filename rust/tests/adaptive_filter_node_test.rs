@@ -0,0 +1,219 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the adaptive noise-cancellation node
+//!
+//! Channel B is Gaussian noise. Channel A carries a 1 kHz tone, the same noise
+//! through the acoustic path `0.8·n[k] + 0.3·n[k−2] − 0.1·n[k−5]` and a little
+//! independent sensor noise. Subtracting channel B leaves most of the noise;
+//! the adaptive filter learns the path. The residual is the output minus the
+//! tone over the second of the two seconds streamed.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_cancels_correlated_noise`] | The residual noise is far below simple subtraction, the tone is kept and the weights match the path |
+//! | [`test_node_from_graph_config`] | An `adaptive_filter` graph node turns audio frames into the denoised channel A |
+//! | [`test_parameter_validation`] | Out-of-range filter lengths and step sizes are rejected, and a new length restarts adaptation |
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::AudioFrame;
+use rust_photoacoustic::config::processing::{ConnectionConfig, NodeConfig, ProcessingGraphConfig};
+use rust_photoacoustic::processing::nodes::AdaptiveFilterNode;
+use rust_photoacoustic::processing::{ProcessingData, ProcessingGraph, ProcessingNode};
+use rust_photoacoustic::utility::noise_generator::NoiseGenerator;
+use std::f64::consts::TAU;
+
+const SAMPLE_RATE: u32 = 48000;
+/// 100 ms frames
+const FRAME_SIZE: usize = 4800;
+const TONE_HZ: f64 = 1000.0;
+const TONE_AMPLITUDE: f64 = 0.05;
+/// Acoustic path from the reference to channel A, as (delay, gain)
+const NOISE_PATH: [(usize, f32); 3] = [(0, 0.8), (2, 0.3), (5, -0.1)];
+
+fn tone(n: usize) -> f32 {
+    (TONE_AMPLITUDE * (TAU * TONE_HZ * n as f64 / SAMPLE_RATE as f64).sin()) as f32
+}
+
+/// Two seconds of channels A and B
+fn channels() -> (Vec<f32>, Vec<f32>) {
+    let length = 2 * SAMPLE_RATE as usize;
+    let mut noise = NoiseGenerator::new(42);
+    let mut sensor = NoiseGenerator::new(7);
+    let reference: Vec<f32> = (0..length).map(|_| 0.2 * noise.random_gaussian()).collect();
+    let signal = (0..length)
+        .map(|n| {
+            let correlated: f32 = NOISE_PATH
+                .iter()
+                .filter(|(delay, _)| n >= *delay)
+                .map(|(delay, gain)| gain * reference[n - delay])
+                .sum();
+            tone(n) + correlated + 0.005 * sensor.random_gaussian()
+        })
+        .collect();
+    (signal, reference)
+}
+
+/// Residual power once the tone is removed, over the second second
+fn residual_power(output: &[f32]) -> f64 {
+    let start = SAMPLE_RATE as usize;
+    output[start..]
+        .iter()
+        .enumerate()
+        .map(|(i, sample)| ((sample - tone(start + i)) as f64).powi(2))
+        .sum::<f64>()
+        / (output.len() - start) as f64
+}
+
+/// Amplitude of the tone over the second second, which holds a whole number of periods
+fn tone_amplitude(output: &[f32]) -> f64 {
+    let start = SAMPLE_RATE as usize;
+    let (re, im) = output[start..]
+        .iter()
+        .enumerate()
+        .fold((0.0, 0.0), |(re, im), (i, sample)| {
+            let phase = TAU * TONE_HZ * i as f64 / SAMPLE_RATE as f64;
+            (
+                re + *sample as f64 * phase.cos(),
+                im + *sample as f64 * phase.sin(),
+            )
+        });
+    2.0 * re.hypot(im) / (output.len() - start) as f64
+}
+
+/// Compare `output` with the simple subtraction of the channels
+fn assert_cancelled(output: &[f32], signal: &[f32], reference: &[f32]) {
+    let subtraction: Vec<f32> = signal.iter().zip(reference).map(|(a, b)| a - b).collect();
+    let improvement_db = 10.0 * (residual_power(&subtraction) / residual_power(output)).log10();
+    assert!(
+        improvement_db > 12.0,
+        "residual noise only {} dB below subtraction",
+        improvement_db
+    );
+
+    let amplitude = tone_amplitude(output);
+    assert!(
+        (amplitude - TONE_AMPLITUDE).abs() / TONE_AMPLITUDE < 0.02,
+        "tone amplitude {}",
+        amplitude
+    );
+}
+
+#[test]
+fn test_cancels_correlated_noise() -> Result<()> {
+    let (signal, reference) = channels();
+    let mut node = AdaptiveFilterNode::new("noise_canceller".to_string(), 16, 0.1)?;
+
+    let mut output = Vec::new();
+    for (frame_number, (channel_a, channel_b)) in signal
+        .chunks(FRAME_SIZE)
+        .zip(reference.chunks(FRAME_SIZE))
+        .enumerate()
+    {
+        match node.process(ProcessingData::DualChannel {
+            channel_a: channel_a.to_vec(),
+            channel_b: channel_b.to_vec(),
+            sample_rate: SAMPLE_RATE,
+            timestamp: 0,
+            frame_number: frame_number as u64,
+        })? {
+            ProcessingData::SingleChannel { samples, .. } => output.extend(samples),
+            _ => panic!("unexpected output type"),
+        }
+    }
+    assert_cancelled(&output, &signal, &reference);
+
+    // The weights converge to the acoustic path
+    for (tap, weight) in node.weights().iter().enumerate() {
+        let expected = NOISE_PATH
+            .iter()
+            .find(|(delay, _)| *delay == tap)
+            .map_or(0.0, |(_, gain)| *gain);
+        assert!(
+            (weight - expected).abs() < 0.03,
+            "tap {}: {} instead of {}",
+            tap,
+            weight,
+            expected
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_node_from_graph_config() -> Result<()> {
+    let config = ProcessingGraphConfig {
+        id: "noise_cancelling_graph".to_string(),
+        nodes: vec![
+            NodeConfig {
+                id: "input".to_string(),
+                node_type: "input".to_string(),
+                parameters: serde_json::Value::Null,
+            },
+            NodeConfig {
+                id: "noise_canceller".to_string(),
+                node_type: "adaptive_filter".to_string(),
+                parameters: serde_json::json!({
+                    "filter_length": 8,
+                    "step_size": 0.1
+                }),
+            },
+        ],
+        connections: vec![ConnectionConfig {
+            from: "input".to_string(),
+            to: "noise_canceller".to_string(),
+        }],
+        output_node: Some("noise_canceller".to_string()),
+    };
+    let mut graph = ProcessingGraph::from_config(&config)?;
+
+    let (signal, reference) = channels();
+    let mut output = Vec::new();
+    for (frame_number, (channel_a, channel_b)) in signal
+        .chunks(FRAME_SIZE)
+        .zip(reference.chunks(FRAME_SIZE))
+        .enumerate()
+    {
+        let outputs = graph.execute(ProcessingData::AudioFrame(AudioFrame::new(
+            channel_a.to_vec(),
+            channel_b.to_vec(),
+            SAMPLE_RATE,
+            frame_number as u64,
+        )))?;
+        match &outputs[..] {
+            [ProcessingData::SingleChannel { samples, .. }] => output.extend_from_slice(samples),
+            _ => panic!("unexpected output"),
+        }
+    }
+    assert_cancelled(&output, &signal, &reference);
+    Ok(())
+}
+
+#[test]
+fn test_parameter_validation() -> Result<()> {
+    assert!(AdaptiveFilterNode::new("lms".to_string(), 0, 0.1).is_err());
+    assert!(AdaptiveFilterNode::new("lms".to_string(), 16, 0.0).is_err());
+    assert!(AdaptiveFilterNode::new("lms".to_string(), 16, 2.0).is_err());
+
+    let mut node = AdaptiveFilterNode::new("lms".to_string(), 16, 0.5)?;
+    assert!(node
+        .update_config(&serde_json::json!({"step_size": -0.1}))
+        .is_err());
+    assert_eq!(node.get_step_size(), 0.5);
+
+    node.process(ProcessingData::DualChannel {
+        channel_a: vec![0.5, -0.2, 0.3, 0.1],
+        channel_b: vec![0.4, -0.1, 0.2, 0.3],
+        sample_rate: SAMPLE_RATE,
+        timestamp: 0,
+        frame_number: 1,
+    })?;
+    assert!(node.weights().iter().any(|weight| *weight != 0.0));
+
+    assert!(node.update_config(&serde_json::json!({"filter_length": 4, "step_size": 0.2}))?);
+    assert_eq!(node.get_filter_length(), 4);
+    assert_eq!(node.get_step_size(), 0.2);
+    assert!(node.weights().iter().all(|weight| *weight == 0.0));
+    Ok(())
+}
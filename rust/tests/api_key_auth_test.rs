@@ -0,0 +1,141 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the API key authentication of machine clients
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_valid_api_key_grants_access`] | A configured key in `X-API-Key` reaches a protected route with the key's permissions |
+//! | [`test_unknown_api_key_rejected`] | An unknown key is rejected with 401, the bearer token still takes precedence |
+//! | [`test_api_key_without_permission_forbidden`] | A valid key lacking the route's permission gets 403 |
+//! | [`test_api_key_hash_matching`] | Keys are matched against their hex SHA-256 hash |
+
+use auth_macros::protect_get;
+use rocket::http::{Header, Status};
+use rocket::local::blocking::Client;
+use rocket::routes;
+use rocket::serde::json::Json;
+use rust_photoacoustic::config::{ApiKey, Config};
+use rust_photoacoustic::visualization::auth::guards::ApiKeyAuth;
+use rust_photoacoustic::visualization::auth::oauth2::OxideState;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+const MONITORING_KEY: &str = "monitoring-secret-key";
+
+#[protect_get("/api/read", "read:api")]
+fn read_route(
+    bearer: rust_photoacoustic::visualization::auth::guards::bearer::OAuthBearer,
+) -> Json<Vec<String>> {
+    Json(vec![
+        bearer.user_info.user_id.clone(),
+        bearer.permissions.clone().unwrap_or_default().join(" "),
+    ])
+}
+
+#[protect_get("/api/admin", "admin:api")]
+fn admin_route(
+    bearer: rust_photoacoustic::visualization::auth::guards::bearer::OAuthBearer,
+) -> String {
+    bearer.user_info.user_id.clone()
+}
+
+#[rocket::get("/api/probe")]
+fn probe_route(api_key: ApiKeyAuth) -> String {
+    api_key.name
+}
+
+/// Build a client whose configuration holds a `monitoring` key granting `read:api`
+fn client() -> Client {
+    let mut config = Config::default();
+    config.visualization.enable_local_visualization = false;
+    config.access.api_keys = vec![ApiKey {
+        name: "monitoring".to_string(),
+        key_hash: ApiKey::hash_key(MONITORING_KEY),
+        permissions: vec!["read:api".to_string()],
+    }];
+
+    let rocket = rocket::build()
+        .manage(Arc::new(RwLock::new(config)))
+        .manage(OxideState::preconfigured(
+            rocket::Config::figment().merge(("hmac_secret", "test-api-key".to_string())),
+        ))
+        .mount("/", routes![read_route, admin_route, probe_route]);
+    Client::tracked(rocket).expect("valid rocket instance")
+}
+
+#[test]
+fn test_valid_api_key_grants_access() {
+    let client = client();
+
+    let response = client
+        .get("/api/read")
+        .header(Header::new("X-API-Key", MONITORING_KEY))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    let body: Vec<String> = response.into_json().expect("JSON body");
+    assert_eq!(body, vec!["api-key:monitoring", "read:api"]);
+
+    // The dedicated guard accepts the key as well
+    let response = client
+        .get("/api/probe")
+        .header(Header::new("X-API-Key", MONITORING_KEY))
+        .dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.into_string().unwrap(), "monitoring");
+}
+
+#[test]
+fn test_unknown_api_key_rejected() {
+    let client = client();
+
+    for path in ["/api/read", "/api/probe"] {
+        let response = client
+            .get(path)
+            .header(Header::new("X-API-Key", "not-a-configured-key"))
+            .dispatch();
+        assert_eq!(response.status(), Status::Unauthorized, "{}", path);
+    }
+
+    // Without any credential the request is rejected too
+    let response = client.get("/api/read").dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    // An Authorization header takes precedence over the API key
+    let response = client
+        .get("/api/read")
+        .header(Header::new("Authorization", "Bearer invalid-token"))
+        .header(Header::new("X-API-Key", MONITORING_KEY))
+        .dispatch();
+    assert_eq!(response.status(), Status::Unauthorized);
+}
+
+#[test]
+fn test_api_key_without_permission_forbidden() {
+    let client = client();
+
+    let response = client
+        .get("/api/admin")
+        .header(Header::new("X-API-Key", MONITORING_KEY))
+        .dispatch();
+    assert_eq!(response.status(), Status::Forbidden);
+}
+
+#[test]
+fn test_api_key_hash_matching() {
+    // printf '%s' test | sha256sum
+    assert_eq!(
+        ApiKey::hash_key("test"),
+        "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+    );
+
+    let api_key = ApiKey {
+        name: "probe".to_string(),
+        key_hash: "9F86D081884C7D659A2FEAA0C55AD015A3BF4F1B2B0B822CD15D6C15B0F00A08".to_string(),
+        permissions: vec![],
+    };
+    assert!(api_key.matches("test"));
+    assert!(!api_key.matches("Test"));
+    assert!(!api_key.matches(""));
+}
@@ -0,0 +1,289 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the reuse of sample buffers across frames
+//!
+//! The fan-out graph reads the output of `select_a` from two branches, so
+//! that the pooled path both moves and copies node outputs:
+//!
+//! ```text
+//! input ─┬─ select_a ─┬─ bandpass_a ── gain_a ── output_a
+//!        │            └─ highpass_a
+//!        └─ select_b ──── lowpass_b
+//! ```
+//!
+//! `output_a`, `highpass_a` and `lowpass_b` are output nodes. The processing
+//! latency of the photoacoustic result is a wall-clock measurement, so it is
+//! ignored when comparing outputs.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_pooled_matches_allocating`] | Pooled execution, serial and parallel, returns the outputs of the allocating path over frames of varying length |
+//! | [`test_held_outputs_stay_intact`] | Outputs the caller keeps are not overwritten by later frames |
+//! | [`test_pool_reaches_steady_state`] | Once warmed up, frames of the same size are served from the pool without allocating |
+//! | [`test_buffer_reuse_toggle`] | Buffer reuse is disabled by default and can be switched off again |
+//! | [`test_frame_buffer_pool`] | Taken buffers are empty, the pool keeps at most its capacity and copies are equal |
+//! | [`test_buffer_reuse_from_config`] | `processing.performance.reuse_buffers` defaults to false and is read from YAML |
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::AudioFrame;
+use rust_photoacoustic::config::processing::{
+    ConnectionConfig, NodeConfig, ProcessingConfig, ProcessingGraphConfig,
+};
+use rust_photoacoustic::processing::{FrameBufferPool, ProcessingData, ProcessingGraph};
+use rust_photoacoustic::utility::noise_generator::NoiseGenerator;
+use std::f32::consts::TAU;
+
+const SAMPLE_RATE: u32 = 48000;
+/// Frame lengths cycled through, growing and shrinking the pooled buffers
+const FRAME_SIZES: [usize; 6] = [2048, 1000, 4096, 2048, 17, 3000];
+
+fn node(id: &str, node_type: &str, parameters: serde_json::Value) -> NodeConfig {
+    NodeConfig {
+        id: id.to_string(),
+        node_type: node_type.to_string(),
+        parameters,
+    }
+}
+
+fn connection(from: &str, to: &str) -> ConnectionConfig {
+    ConnectionConfig {
+        from: from.to_string(),
+        to: to.to_string(),
+    }
+}
+
+fn fan_out_config() -> ProcessingGraphConfig {
+    ProcessingGraphConfig {
+        id: "fan_out".to_string(),
+        nodes: vec![
+            node("input", "input", serde_json::Value::Null),
+            node(
+                "select_a",
+                "channel_selector",
+                serde_json::json!({"target_channel": "ChannelA"}),
+            ),
+            node(
+                "select_b",
+                "channel_selector",
+                serde_json::json!({"target_channel": "ChannelB"}),
+            ),
+            node(
+                "bandpass_a",
+                "filter",
+                serde_json::json!({
+                    "type": "bandpass",
+                    "center_frequency": 2000.0,
+                    "bandwidth": 200.0,
+                    "order": 4
+                }),
+            ),
+            node("gain_a", "gain", serde_json::json!({"gain_db": 6.0})),
+            node(
+                "output_a",
+                "photoacoustic_output",
+                serde_json::json!({"detection_threshold": 0.05}),
+            ),
+            node(
+                "highpass_a",
+                "filter",
+                serde_json::json!({"type": "highpass", "cutoff_frequency": 500.0, "order": 2}),
+            ),
+            node(
+                "lowpass_b",
+                "filter",
+                serde_json::json!({"type": "lowpass", "cutoff_frequency": 1000.0, "order": 4}),
+            ),
+        ],
+        connections: vec![
+            connection("input", "select_a"),
+            connection("input", "select_b"),
+            connection("select_a", "bandpass_a"),
+            connection("bandpass_a", "gain_a"),
+            connection("gain_a", "output_a"),
+            connection("select_a", "highpass_a"),
+            connection("select_b", "lowpass_b"),
+        ],
+        output_node: Some("output_a".to_string()),
+    }
+}
+
+fn fan_out_graph(parallel: bool, reuse_buffers: bool) -> Result<ProcessingGraph> {
+    let mut graph = ProcessingGraph::from_config(&fan_out_config())?;
+    graph.set_output_node("highpass_a")?;
+    graph.set_output_node("lowpass_b")?;
+    graph.set_parallel_execution(parallel);
+    graph.set_buffer_reuse(reuse_buffers);
+    Ok(graph)
+}
+
+fn frame(noise: &mut NoiseGenerator, frame_number: u64, len: usize) -> ProcessingData {
+    let (channel_a, channel_b) = (0..len)
+        .map(|n| {
+            let t = n as f32 / SAMPLE_RATE as f32;
+            (
+                0.3 * (TAU * 2000.0 * t).sin() + 0.05 * noise.random_gaussian(),
+                0.2 * (TAU * 700.0 * t).sin() + 0.05 * noise.random_gaussian(),
+            )
+        })
+        .unzip();
+    ProcessingData::AudioFrame(AudioFrame {
+        channel_a,
+        channel_b,
+        sample_rate: SAMPLE_RATE,
+        timestamp: 1000 + frame_number * 43,
+        frame_number,
+    })
+}
+
+/// Drop the wall-clock latency of photoacoustic results
+fn without_latency(mut outputs: Vec<ProcessingData>) -> Vec<ProcessingData> {
+    for output in &mut outputs {
+        if let ProcessingData::PhotoacousticResult { metadata, .. } = output {
+            metadata.processing_latency_us = 0;
+        }
+    }
+    outputs
+}
+
+#[test]
+fn test_pooled_matches_allocating() -> Result<()> {
+    let mut allocating = fan_out_graph(false, false)?;
+    let mut pooled = fan_out_graph(false, true)?;
+    let mut parallel_pooled = fan_out_graph(true, true)?;
+
+    let mut noise = NoiseGenerator::new(23);
+    for frame_number in 0..30 {
+        let len = FRAME_SIZES[frame_number as usize % FRAME_SIZES.len()];
+        let input = frame(&mut noise, frame_number, len);
+        let expected = without_latency(allocating.execute(input.clone())?);
+        assert_eq!(expected.len(), 3);
+
+        for graph in [&mut pooled, &mut parallel_pooled] {
+            let outputs = without_latency(graph.execute(input.clone())?);
+            assert_eq!(
+                outputs, expected,
+                "frame {} of {} samples",
+                frame_number, len
+            );
+            graph.recycle(outputs);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_held_outputs_stay_intact() -> Result<()> {
+    let mut graph = fan_out_graph(false, true)?;
+    let mut noise = NoiseGenerator::new(29);
+
+    let held = graph.execute(frame(&mut noise, 0, 2048))?;
+    let snapshot = held.clone();
+    for frame_number in 1..20 {
+        let outputs = graph.execute(frame(&mut noise, frame_number, 2048))?;
+        assert_ne!(
+            without_latency(outputs.clone()),
+            without_latency(held.clone())
+        );
+        graph.recycle(outputs);
+    }
+    assert_eq!(held, snapshot);
+    Ok(())
+}
+
+#[test]
+fn test_pool_reaches_steady_state() -> Result<()> {
+    let mut graph = fan_out_graph(false, true)?;
+    let mut noise = NoiseGenerator::new(31);
+    for frame_number in 0..3 {
+        let outputs = graph.execute(frame(&mut noise, frame_number, 4096))?;
+        graph.recycle(outputs);
+    }
+
+    let warm = graph.buffer_pool_statistics().unwrap();
+    assert!(warm.allocations > 0);
+    for frame_number in 3..23 {
+        let outputs = graph.execute(frame(&mut noise, frame_number, 4096))?;
+        graph.recycle(outputs);
+    }
+    let statistics = graph.buffer_pool_statistics().unwrap();
+    assert_eq!(statistics.allocations, warm.allocations);
+    assert!(statistics.reuses >= warm.reuses + 20 * 4);
+    assert!(statistics.pooled_buffers > 0);
+    Ok(())
+}
+
+#[test]
+fn test_buffer_reuse_toggle() -> Result<()> {
+    let mut graph = ProcessingGraph::from_config(&fan_out_config())?;
+    assert!(!graph.buffer_reuse());
+    assert_eq!(graph.buffer_pool_statistics(), None);
+
+    graph.set_buffer_reuse(true);
+    assert!(graph.buffer_reuse());
+    let mut noise = NoiseGenerator::new(37);
+    let outputs = graph.execute(frame(&mut noise, 0, 512))?;
+    graph.recycle(outputs);
+    let statistics = graph.buffer_pool_statistics().unwrap();
+
+    // Enabling again keeps the pool and its buffers
+    graph.set_buffer_reuse(true);
+    assert_eq!(graph.buffer_pool_statistics(), Some(statistics));
+
+    graph.set_buffer_reuse(false);
+    assert!(!graph.buffer_reuse());
+    assert_eq!(graph.buffer_pool_statistics(), None);
+    assert_eq!(graph.execute(frame(&mut noise, 1, 512))?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_frame_buffer_pool() {
+    let pool = FrameBufferPool::new(2);
+
+    // A reused buffer holds none of its previous samples
+    let mut buffer = pool.take(8);
+    buffer.extend_from_slice(&[1.0; 8]);
+    pool.give(buffer);
+    let buffer = pool.take(4);
+    assert!(buffer.is_empty());
+    assert!(buffer.capacity() >= 8);
+
+    // Growing a pooled buffer counts as an allocation
+    pool.give(buffer);
+    let grown = pool.take(64);
+    assert!(grown.capacity() >= 64);
+    let statistics = pool.statistics();
+    assert_eq!((statistics.allocations, statistics.reuses), (2, 1));
+
+    // The pool keeps at most its capacity and ignores empty buffers
+    pool.give(grown);
+    pool.give(vec![0.0; 16]);
+    pool.give(vec![0.0; 16]);
+    pool.give(Vec::new());
+    assert_eq!(pool.statistics().pooled_buffers, 2);
+
+    let data = ProcessingData::DualChannel {
+        channel_a: vec![0.25; 100],
+        channel_b: vec![-0.5; 100],
+        sample_rate: SAMPLE_RATE,
+        timestamp: 7,
+        frame_number: 3,
+    };
+    let copy = pool.clone_data(&data);
+    assert_eq!(copy, data);
+    assert_eq!(pool.statistics().pooled_buffers, 0);
+    pool.recycle(copy);
+    assert_eq!(pool.statistics().pooled_buffers, 2);
+}
+
+#[test]
+fn test_buffer_reuse_from_config() -> Result<()> {
+    let config: ProcessingConfig = serde_yml::from_str("enabled: true\n")?;
+    assert!(!config.performance.reuse_buffers);
+
+    let config: ProcessingConfig = serde_yml::from_str("performance:\n  reuse_buffers: true\n")?;
+    assert!(config.performance.reuse_buffers);
+    Ok(())
+}
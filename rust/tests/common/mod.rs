@@ -0,0 +1,138 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Fixtures shared by the API integration tests
+//!
+//! Each test file includes this module with `mod common;` and builds its Rocket
+//! instance from [`test_figment`], then authenticates with [`access_token`].
+//! Thermal regulation tests build their mock driver from [`BUS_YAML`] and
+//! [`REGULATOR_YAML`].
+
+// Every test crate uses a different subset of the fixtures
+#![allow(dead_code)]
+
+use chrono::{Duration, Utc};
+use oxide_auth::primitives::grant::{Extensions, Grant};
+use oxide_auth::primitives::issuer::Issuer;
+use rocket::config::LogLevel;
+use rocket::local::asynchronous::Client;
+use rocket::{Phase, Rocket};
+use rust_photoacoustic::config::{AccessConfig, VisualizationConfig};
+use rust_photoacoustic::visualization::auth::OxideState;
+
+/// HMAC secret of the test configurations, set in `config.visualization.hmac_secret`
+pub const TEST_HMAC_SECRET: &str = "test-hmac-secret-key-for-testing";
+
+/// Rocket configuration of the test servers, on a random port with logs disabled
+pub fn test_figment() -> rocket::figment::Figment {
+    rocket::Config::figment()
+        .merge(("port", 0))
+        .merge(("address", "127.0.0.1"))
+        .merge(("log_level", LogLevel::Off))
+        .merge(("hmac_secret", TEST_HMAC_SECRET.to_string()))
+        .merge(("secret_key", "/qCJ7RyQIugza05wgFNN6R+c2/afrKlG5jJfZ0oQPis="))
+        .merge(("access_config", AccessConfig::default()))
+        .merge(("visualization_config", VisualizationConfig::default()))
+}
+
+/// Issue a bearer token valid for five minutes from the server's own issuer
+///
+/// # Arguments
+/// * `client` - Client of the server under test
+/// * `owner_id` - User the token is issued to
+/// * `scope` - Space separated permissions, e.g. `"read:api write:api"`
+pub fn access_token(client: &Client, owner_id: &str, scope: &str) -> String {
+    rocket_access_token(client.rocket(), owner_id, scope)
+}
+
+/// Same as [`access_token`] for a server launched without a local client
+pub fn rocket_access_token<P: Phase>(rocket: &Rocket<P>, owner_id: &str, scope: &str) -> String {
+    let grant = Grant {
+        owner_id: owner_id.to_string(),
+        client_id: "LaserSmartClient".to_string(),
+        scope: scope.parse().unwrap(),
+        redirect_uri: "https://localhost:8080/client/".parse().unwrap(),
+        until: Utc::now() + Duration::minutes(5),
+        extensions: Extensions::new(),
+    };
+    rocket
+        .state::<OxideState>()
+        .expect("OxideState is managed")
+        .issuer
+        .lock()
+        .unwrap()
+        .issue(grant)
+        .expect("token issued")
+        .token
+}
+
+/// Mock I2C bus carrying the controllers used by [`REGULATOR_YAML`]
+pub const BUS_YAML: &str = r#"
+type: mock
+device: mock
+pwm_controllers:
+  - address: 0x40
+adc_controllers:
+  - address: 0x48
+gpio_controllers:
+  - address: 0x20
+"#;
+
+/// Regulator of a mock cell driven through an H-Bridge on [`BUS_YAML`]
+pub const REGULATOR_YAML: &str = r#"
+id: mock_cell
+name: Mock cell
+i2c_bus: mock_thermal
+temperature_sensor:
+  adc_address: 0x48
+  adc_channel: 0
+actuators:
+  thermal_control:
+    pwm_controller:
+      address: 0x40
+      channel: 0
+    direction_controller:
+      address: 0x20
+      gpio_pins:
+        h_bridge_in1: 0
+        h_bridge_in2: 1
+        h_bridge_enable: 2
+    thermal_modes:
+      heating_tec:
+        description: Peltier heating
+        h_bridge_direction: forward
+        power_range: 0-100%
+        max_power_percent: 100.0
+      cooling_tec:
+        description: Peltier cooling
+        h_bridge_direction: reverse
+        power_range: 0-100%
+        max_power_percent: 100.0
+      heating_resistive:
+        description: Resistive heating
+        h_bridge_direction: forward
+        power_range: 0-100%
+        max_power_percent: 100.0
+temperature_conversion:
+  formula: NTC_10K_3977
+  adc_resolution: 16
+  voltage_reference: 5.0
+  conversion_type: ntc_thermistor
+pid_parameters:
+  kp: 1.0
+  ki: 0.05
+  kd: 0.02
+  setpoint: 303.15
+  output_min: -100.0
+  output_max: 100.0
+  integral_max: 30.0
+control_parameters:
+  sampling_frequency_hz: 20.0
+  pwm_frequency_hz: 1000.0
+safety_limits:
+  min_temperature_k: 273.15
+  max_temperature_k: 353.15
+  max_heating_duty: 80.0
+  max_cooling_duty: 80.0
+"#;
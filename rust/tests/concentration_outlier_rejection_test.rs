@@ -0,0 +1,270 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the outlier rejection of the concentration node
+//!
+//! A peak finder publishes one reading per second. With a linear calibration the
+//! concentration equals the amplitude: 400 ppm with a slow 10 ppm oscillation and
+//! 2 ppm of Gaussian noise, four isolated spikes of several hundred ppm, and a
+//! sustained 150 ppm step from reading 200.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_rejects_isolated_spikes`] | Spikes are never published and counted, while every other reading and the step are published |
+//! | [`test_node_from_graph_config`] | The `outlier_rejection` parameters of a `computing_concentration` graph node enable the filter |
+//! | [`test_flat_signal_accepts_changes`] | A window of identical readings rejects nothing without a deviation floor, and honours the floor when set |
+//! | [`test_outlier_rejection_configuration`] | Invalid filter parameters are rejected and the filter can be disabled at runtime |
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::AudioFrame;
+use rust_photoacoustic::config::processing::{ConnectionConfig, NodeConfig, ProcessingGraphConfig};
+use rust_photoacoustic::processing::computing_nodes::{
+    ComputingSharedData, ConcentrationNode, HampelFilter, PeakResult, SharedComputingState,
+};
+use rust_photoacoustic::processing::{ProcessingData, ProcessingGraph, ProcessingNode};
+use rust_photoacoustic::utility::noise_generator::NoiseGenerator;
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::RwLock;
+
+const PEAK_FINDER_ID: &str = "peak_finder";
+const NODE_ID: &str = "concentration";
+const READINGS: usize = 300;
+const SPIKES: [usize; 4] = [60, 97, 130, 171];
+const STEP_AT: usize = 200;
+const WINDOW_SIZE: usize = 11;
+
+/// Concentration readings in ppm
+fn readings() -> Vec<f32> {
+    let mut noise = NoiseGenerator::new(42);
+    (0..READINGS)
+        .map(|k| {
+            let mut reading = 400.0 + 10.0 * (TAU * k as f64 / 50.0).sin() as f32;
+            reading += 2.0 * noise.random_gaussian();
+            if k >= STEP_AT {
+                reading += 150.0;
+            }
+            if SPIKES.contains(&k) {
+                reading += if k % 2 == 1 { 300.0 } else { -250.0 };
+            }
+            reading
+        })
+        .collect()
+}
+
+/// Publish reading `k` as the peak amplitude, one second after the previous one
+fn publish(state: &SharedComputingState, k: usize, amplitude: f32) {
+    let result = PeakResult {
+        frequency: 2000.0,
+        amplitude,
+        concentration_ppm: None,
+        timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(k as u64 + 1),
+        coherence_score: 1.0,
+        processing_metadata: HashMap::new(),
+    };
+    state
+        .try_write()
+        .expect("shared state available")
+        .update_peak_result(PEAK_FINDER_ID.to_string(), result);
+}
+
+/// Concentration published by the node
+fn published(state: &SharedComputingState) -> f64 {
+    state
+        .try_read()
+        .expect("shared state available")
+        .get_concentration_result(NODE_ID)
+        .expect("concentration published")
+        .concentration_ppm
+}
+
+/// Indices of the readings that were not published
+fn rejected_readings(published: &[f64], readings: &[f32]) -> Vec<usize> {
+    published
+        .iter()
+        .zip(readings)
+        .enumerate()
+        .filter(|(_, (published, reading))| **published != **reading as f64)
+        .map(|(k, _)| k)
+        .collect()
+}
+
+/// Check that only the spikes and the start of the step were held back
+fn assert_spikes_rejected(published: &[f64], readings: &[f32]) {
+    let rejected = rejected_readings(published, readings);
+    let (before_step, after_step): (Vec<usize>, Vec<usize>) =
+        rejected.iter().partition(|k| **k < STEP_AT);
+    assert_eq!(before_step, SPIKES.to_vec());
+
+    // The step is held back until it fills more than half of the window
+    assert!(after_step.len() <= WINDOW_SIZE / 2 + 1, "{:?}", after_step);
+    for (i, k) in after_step.iter().enumerate() {
+        assert_eq!(*k, STEP_AT + i);
+    }
+
+    // Rejected readings leave the previous concentration in the shared state
+    for k in rejected.iter().filter(|k| **k > 0) {
+        assert_eq!(published[*k], published[k - 1]);
+    }
+}
+
+fn frame(frame_number: u64) -> ProcessingData {
+    ProcessingData::SingleChannel {
+        samples: vec![0.0; 16],
+        sample_rate: 48000,
+        timestamp: 0,
+        frame_number,
+    }
+}
+
+#[test]
+fn test_rejects_isolated_spikes() -> Result<()> {
+    let state: SharedComputingState = Arc::new(RwLock::new(ComputingSharedData::default()));
+    let mut node =
+        ConcentrationNode::new_with_shared_state(NODE_ID.to_string(), Some(state.clone()))
+            .with_peak_finder_source(PEAK_FINDER_ID.to_string())
+            .with_outlier_rejection(HampelFilter::new(WINDOW_SIZE, 3.0)?.with_min_deviation(20.0));
+
+    let readings = readings();
+    let mut concentrations = Vec::new();
+    for (k, reading) in readings.iter().enumerate() {
+        publish(&state, k, *reading);
+        // Several frames see the same reading, which is checked only once
+        for frame_number in 0..3 {
+            node.process(frame(frame_number))?;
+        }
+        concentrations.push(published(&state));
+    }
+
+    assert_spikes_rejected(&concentrations, &readings);
+    let rejected = rejected_readings(&concentrations, &readings).len() as u64;
+    assert_eq!(node.get_rejected_count(), rejected);
+    assert_eq!(
+        *concentrations.last().unwrap(),
+        *readings.last().unwrap() as f64
+    );
+
+    // The counter is published with the concentration
+    let state = state.try_read().expect("shared state available");
+    let result = state.get_concentration_result(NODE_ID).unwrap();
+    assert_eq!(
+        result.processing_metadata.get("outliers_rejected"),
+        Some(&rejected.to_string())
+    );
+    Ok(())
+}
+
+#[test]
+fn test_node_from_graph_config() -> Result<()> {
+    let config = ProcessingGraphConfig {
+        id: "outlier_graph".to_string(),
+        nodes: vec![
+            NodeConfig {
+                id: "input".to_string(),
+                node_type: "input".to_string(),
+                parameters: serde_json::Value::Null,
+            },
+            NodeConfig {
+                id: NODE_ID.to_string(),
+                node_type: "computing_concentration".to_string(),
+                parameters: serde_json::json!({
+                    "computing_peak_finder_id": PEAK_FINDER_ID,
+                    "polynomial_coefficients": [0.0, 1.0, 0.0, 0.0, 0.0],
+                    "outlier_rejection": {
+                        "window_size": WINDOW_SIZE,
+                        "threshold_mads": 3.0,
+                        "min_deviation_ppm": 20.0
+                    }
+                }),
+            },
+        ],
+        connections: vec![ConnectionConfig {
+            from: "input".to_string(),
+            to: NODE_ID.to_string(),
+        }],
+        output_node: Some(NODE_ID.to_string()),
+    };
+    let state: SharedComputingState = Arc::new(RwLock::new(ComputingSharedData::default()));
+    let mut graph =
+        ProcessingGraph::from_config_with_computing_state(&config, Some(state.clone()))?;
+
+    let readings = readings();
+    let mut concentrations = Vec::new();
+    for (k, reading) in readings.iter().enumerate() {
+        publish(&state, k, *reading);
+        graph.execute(ProcessingData::AudioFrame(AudioFrame::new(
+            vec![0.0; 16],
+            vec![0.0; 16],
+            48000,
+            k as u64,
+        )))?;
+        concentrations.push(published(&state));
+    }
+    assert_spikes_rejected(&concentrations, &readings);
+
+    // Invalid filter parameters are reported when the graph is built
+    let mut invalid = config.clone();
+    invalid.nodes[1].parameters["outlier_rejection"]["window_size"] = serde_json::json!(2);
+    assert!(ProcessingGraph::from_config(&invalid).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_flat_signal_accepts_changes() -> Result<()> {
+    let mut filter = HampelFilter::new(5, 3.0)?;
+    for _ in 0..5 {
+        assert!(filter.accept(400.0));
+    }
+    // The MAD of the window is zero: small changes and a step are accepted
+    assert!(filter.accept(400.1));
+    assert!(filter.accept(450.0));
+    assert_eq!(filter.rejected_count(), 0);
+
+    // An explicit floor still applies to a flat window
+    let mut filter = HampelFilter::new(5, 3.0)?.with_min_deviation(5.0);
+    for _ in 0..5 {
+        assert!(filter.accept(400.0));
+    }
+    assert!(filter.accept(404.0));
+    assert!(!filter.accept(450.0));
+    assert_eq!(filter.rejected_count(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_outlier_rejection_configuration() -> Result<()> {
+    assert!(HampelFilter::new(2, 3.0).is_err());
+    assert!(HampelFilter::new(11, 0.0).is_err());
+    assert!(HampelFilter::from_config(&serde_json::json!({"min_deviation_ppm": -1.0})).is_err());
+    let filter = HampelFilter::from_config(&serde_json::json!({}))?;
+    assert_eq!(filter.window_size(), HampelFilter::DEFAULT_WINDOW_SIZE);
+    assert_eq!(filter.threshold(), HampelFilter::DEFAULT_THRESHOLD);
+    assert_eq!(filter.min_deviation(), 0.0);
+
+    let state: SharedComputingState = Arc::new(RwLock::new(ComputingSharedData::default()));
+    let mut node =
+        ConcentrationNode::new_with_shared_state(NODE_ID.to_string(), Some(state.clone()))
+            .with_peak_finder_source(PEAK_FINDER_ID.to_string());
+    assert!(node
+        .update_config(&serde_json::json!({"outlier_rejection": {"threshold_mads": -1.0}}))
+        .is_err());
+    assert!(node.update_config(&serde_json::json!({"outlier_rejection": {"window_size": 3}}))?);
+
+    for (k, reading) in [400.0, 401.0, 399.0, 1000.0].into_iter().enumerate() {
+        publish(&state, k, reading);
+        node.process(frame(k as u64))?;
+    }
+    assert_eq!(node.get_rejected_count(), 1);
+    assert_eq!(published(&state), 399.0);
+
+    // Without the filter every reading is published
+    assert!(node.update_config(&serde_json::json!({"outlier_rejection": null}))?);
+    assert_eq!(node.get_rejected_count(), 0);
+    publish(&state, 4, 1000.0);
+    node.process(frame(4))?;
+    assert_eq!(published(&state), 1000.0);
+    Ok(())
+}
@@ -0,0 +1,124 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the `include:` configuration directive
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_include_overrides_scalars_and_merges_maps`] | The including file overrides base scalars while keeping the other keys of merged maps |
+//! | [`test_later_includes_win`] | Later entries of the directive override earlier ones |
+//! | [`test_include_cycle_is_rejected`] | Direct and indirect cyclic includes are rejected |
+
+use anyhow::Result;
+use rust_photoacoustic::config::reload::load_config_file;
+use rust_photoacoustic::config::Config;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+/// Write a complete base configuration named `name` into `dir`
+fn write_base(dir: &Path, name: &str) -> Result<PathBuf> {
+    let path = dir.join(name);
+    let mut base = Config::default();
+    base.visualization.name = "BaseServer".to_string();
+    base.modbus.enabled = true;
+    base.save_to_file(&path)?;
+    Ok(path)
+}
+
+#[test]
+fn test_include_overrides_scalars_and_merges_maps() -> Result<()> {
+    let dir = tempdir()?;
+    write_base(dir.path(), "base.yaml")?;
+    let site_path = dir.path().join("site.yaml");
+    fs::write(
+        &site_path,
+        r#"
+include: base.yaml
+visualization:
+  name: "SiteServer"
+modbus:
+  port: 5020
+"#,
+    )?;
+
+    let defaults = Config::default();
+    let config = Config::from_file(&site_path)?;
+
+    // Scalars of the including file win
+    assert_eq!(config.visualization.name, "SiteServer");
+    assert_eq!(config.modbus.port, 5020);
+    // The other keys of the merged maps come from the base file
+    assert_eq!(config.visualization.address, defaults.visualization.address);
+    assert_eq!(config.visualization.port, defaults.visualization.port);
+    assert!(config.modbus.enabled);
+    assert_eq!(config.modbus.address, defaults.modbus.address);
+    // Sections absent from the including file come from the base file
+    assert_eq!(config.access.users.len(), defaults.access.users.len());
+
+    // The reload path resolves includes the same way
+    let reloaded = load_config_file(&site_path).expect("layered config is valid");
+    assert_eq!(reloaded.visualization.name, "SiteServer");
+    assert!(reloaded.modbus.enabled);
+    Ok(())
+}
+
+#[test]
+fn test_later_includes_win() -> Result<()> {
+    let dir = tempdir()?;
+    write_base(dir.path(), "base.yaml")?;
+    fs::write(
+        dir.path().join("hardware.yaml"),
+        r#"
+visualization:
+  name: "HardwareServer"
+modbus:
+  enabled: false
+"#,
+    )?;
+    let site_path = dir.path().join("site.yaml");
+    fs::write(
+        &site_path,
+        r#"
+include:
+  - base.yaml
+  - hardware.yaml
+modbus:
+  port: 5021
+"#,
+    )?;
+
+    let config = Config::from_file(&site_path)?;
+    assert_eq!(config.visualization.name, "HardwareServer");
+    assert!(!config.modbus.enabled);
+    assert_eq!(config.modbus.port, 5021);
+    Ok(())
+}
+
+#[test]
+fn test_include_cycle_is_rejected() -> Result<()> {
+    let dir = tempdir()?;
+
+    // a.yaml -> b.yaml -> a.yaml
+    let a_path = dir.path().join("a.yaml");
+    fs::write(&a_path, "include: b.yaml\n")?;
+    fs::write(dir.path().join("b.yaml"), "include: a.yaml\n")?;
+
+    let error = Config::from_file(&a_path).expect_err("cyclic include must be rejected");
+    assert!(
+        format!("{:#}", error).contains("include cycle"),
+        "unexpected error: {:#}",
+        error
+    );
+
+    // A file including itself
+    let self_path = dir.path().join("self.yaml");
+    fs::write(&self_path, "include: self.yaml\n")?;
+    let error = Config::from_file(&self_path).expect_err("self include must be rejected");
+    assert!(format!("{:#}", error).contains("include cycle"));
+
+    let errors = load_config_file(&a_path).expect_err("cyclic include must be rejected");
+    assert!(errors[0].contains("include cycle"));
+    Ok(())
+}
@@ -0,0 +1,113 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the configuration schema migrations
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_v1_fixture_migrates_to_current_version`] | The v1 fixture loads, validates and keeps its values |
+//! | [`test_migration_steps_are_reported`] | `migrate` rewrites the document and lists the applied steps |
+//! | [`test_current_version_is_not_migrated`] | A current document is left unchanged |
+//! | [`test_future_version_is_rejected`] | A version newer than the current one fails with a clear error |
+
+use anyhow::Result;
+use rust_photoacoustic::config::migration::{migrate, CURRENT_SCHEMA_VERSION, MIGRATIONS};
+use rust_photoacoustic::config::reload::load_config_file;
+use rust_photoacoustic::config::{Config, PhotoacousticConfig};
+use std::fs;
+use tempfile::tempdir;
+
+const V1_FIXTURE: &str = "tests/data/config_v1.yaml";
+
+#[test]
+fn test_v1_fixture_migrates_to_current_version() -> Result<()> {
+    // Load a copy, so that a failed load never writes next to the fixture
+    let dir = tempdir()?;
+    let path = dir.path().join("config.yaml");
+    fs::copy(V1_FIXTURE, &path)?;
+
+    let config = Config::from_file(&path)?;
+    assert_eq!(config.schema_version, CURRENT_SCHEMA_VERSION);
+    // Renamed field keeps its value
+    assert_eq!(config.photoacoustic.frame_size, 2048);
+    // Existing values are preserved
+    assert_eq!(config.photoacoustic.frequency, 2120.0);
+    assert_eq!(config.photoacoustic.bandwidth, 100.0);
+    assert_eq!(
+        config.photoacoustic.input_file.as_deref(),
+        Some("data/sample.wav")
+    );
+    assert_eq!(config.visualization.name, "LegacyServer");
+    assert!(config.modbus.enabled);
+    assert_eq!(config.modbus.port, 5020);
+    // Missing required field is filled with its default
+    assert_eq!(
+        config.photoacoustic.averages,
+        PhotoacousticConfig::default().averages
+    );
+
+    // The reload path migrates the same way
+    let reloaded = load_config_file(&path).expect("migrated config is valid");
+    assert_eq!(reloaded.photoacoustic.frame_size, 2048);
+    Ok(())
+}
+
+#[test]
+fn test_migration_steps_are_reported() -> Result<()> {
+    let mut document: serde_yml::Value = serde_yml::from_str(&fs::read_to_string(V1_FIXTURE)?)?;
+
+    let applied = migrate(&mut document)?;
+    assert_eq!(applied.len(), MIGRATIONS.len());
+    assert_eq!(applied[0], MIGRATIONS[0].description);
+
+    assert_eq!(
+        document["schema_version"].as_u64(),
+        Some(CURRENT_SCHEMA_VERSION as u64)
+    );
+    assert!(document["photoacoustic"].get("window_size").is_none());
+    assert_eq!(document["photoacoustic"]["frame_size"].as_u64(), Some(2048));
+    Ok(())
+}
+
+#[test]
+fn test_current_version_is_not_migrated() -> Result<()> {
+    let mut config = Config::default();
+    config.photoacoustic.frame_size = 1024;
+    let mut document = serde_yml::to_value(&config)?;
+    let original = document.clone();
+
+    let applied = migrate(&mut document)?;
+    assert!(applied.is_empty());
+    assert_eq!(document, original);
+    Ok(())
+}
+
+#[test]
+fn test_future_version_is_rejected() -> Result<()> {
+    let dir = tempdir()?;
+    let path = dir.path().join("config.yaml");
+    let future = fs::read_to_string(V1_FIXTURE)?.replace(
+        "visualization:",
+        &format!(
+            "schema_version: {}\nvisualization:",
+            CURRENT_SCHEMA_VERSION + 1
+        ),
+    );
+    fs::write(&path, future)?;
+
+    let error = Config::from_file(&path).expect_err("future schema version must be rejected");
+    let message = format!("{:#}", error);
+    assert!(
+        message.contains(&format!(
+            "schema version {} is newer",
+            CURRENT_SCHEMA_VERSION + 1
+        )),
+        "unexpected error: {}",
+        message
+    );
+
+    let mut document: serde_yml::Value = serde_yml::from_str("schema_version: 0\n")?;
+    assert!(migrate(&mut document).is_err());
+    Ok(())
+}
@@ -0,0 +1,169 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the `POST /api/config/preview` endpoint
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_preview_lists_changed_fields`] | A JSON candidate is diffed field by field with restart classification, without being applied |
+//! | [`test_preview_accepts_yaml`] | A YAML candidate gives the same diff, and an identical candidate gives none |
+//! | [`test_preview_rejects_invalid_config`] | An invalid candidate is answered with its validation errors |
+
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::asynchronous::Client;
+use rust_photoacoustic::config::reload::parse_config;
+use rust_photoacoustic::config::{Config, ConfigFieldChange, ConfigPreview};
+use serde_json::json;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+mod common;
+use common::{access_token, test_figment, TEST_HMAC_SECRET};
+
+/// Running configuration, normalized through the same parser as the candidates
+fn running_config() -> Config {
+    let mut config = Config::default();
+    config.visualization.hmac_secret = TEST_HMAC_SECRET.to_string();
+    parse_config(&serde_yml::to_string(&config).unwrap()).expect("running config is valid")
+}
+
+/// Candidate changing one restart-only field and one hot-applied field
+fn modified_config(running: &Config) -> Config {
+    let mut candidate = running.clone();
+    candidate.modbus.port = running.modbus.port + 1;
+    candidate.processing.default_graph.nodes[1].parameters =
+        json!({ "target_channel": "ChannelB" });
+    candidate
+}
+
+fn expected_changes(running: &Config) -> Vec<ConfigFieldChange> {
+    vec![
+        ConfigFieldChange {
+            path: "modbus.port".to_string(),
+            current: Some(json!(running.modbus.port)),
+            candidate: Some(json!(running.modbus.port + 1)),
+            requires_restart: true,
+        },
+        ConfigFieldChange {
+            path: "processing.default_graph.nodes[1].parameters.target_channel".to_string(),
+            current: Some(json!("ChannelA")),
+            candidate: Some(json!("ChannelB")),
+            requires_restart: false,
+        },
+    ]
+}
+
+async fn build_test_client(config: Arc<RwLock<Config>>) -> Client {
+    let rocket = rust_photoacoustic::visualization::server::build_rocket(
+        test_figment(),
+        config,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+    Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance")
+}
+
+async fn submit_preview(
+    client: &Client,
+    token: &str,
+    content_type: ContentType,
+    body: String,
+) -> (Status, ConfigPreview) {
+    let response = client
+        .post("/api/config/preview")
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .header(content_type)
+        .body(body)
+        .dispatch()
+        .await;
+    let status = response.status();
+    let preview = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    (status, preview)
+}
+
+#[rocket::async_test]
+async fn test_preview_lists_changed_fields() {
+    let running = running_config();
+    let config = Arc::new(RwLock::new(running.clone()));
+    let client = build_test_client(config.clone()).await;
+    let token = access_token(&client, "admin", "read:api write:api admin:api");
+
+    let candidate = serde_json::to_string(&modified_config(&running)).unwrap();
+    let (status, preview) = submit_preview(&client, &token, ContentType::JSON, candidate).await;
+    assert_eq!(status, Status::Ok);
+    assert!(preview.valid);
+    assert!(preview.requires_restart);
+    assert!(preview.errors.is_empty());
+    assert_eq!(preview.changes, expected_changes(&running));
+
+    // The preview does not apply anything
+    let current = config.read().await;
+    assert_eq!(current.modbus.port, running.modbus.port);
+    assert_eq!(
+        current.processing.default_graph.nodes[1].parameters["target_channel"],
+        "ChannelA"
+    );
+}
+
+#[rocket::async_test]
+async fn test_preview_accepts_yaml() {
+    let running = running_config();
+    let client = build_test_client(Arc::new(RwLock::new(running.clone()))).await;
+    let token = access_token(&client, "admin", "read:api write:api admin:api");
+    let yaml = ContentType::new("application", "yaml");
+
+    let candidate = serde_yml::to_string(&modified_config(&running)).unwrap();
+    let (status, preview) = submit_preview(&client, &token, yaml.clone(), candidate).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(preview.changes, expected_changes(&running));
+
+    // A hot-applied change alone does not require a restart
+    let mut candidate = running.clone();
+    candidate.processing.default_graph.nodes[1].parameters =
+        json!({ "target_channel": "ChannelB" });
+    let candidate = serde_yml::to_string(&candidate).unwrap();
+    let (status, preview) = submit_preview(&client, &token, yaml.clone(), candidate).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(preview.changes.len(), 1);
+    assert!(!preview.requires_restart);
+
+    let candidate = serde_yml::to_string(&running).unwrap();
+    let (status, preview) = submit_preview(&client, &token, yaml, candidate).await;
+    assert_eq!(status, Status::Ok);
+    assert!(preview.valid);
+    assert!(preview.changes.is_empty());
+    assert!(!preview.requires_restart);
+}
+
+#[rocket::async_test]
+async fn test_preview_rejects_invalid_config() {
+    let running = running_config();
+    let client = build_test_client(Arc::new(RwLock::new(running.clone()))).await;
+    let token = access_token(&client, "admin", "read:api write:api admin:api");
+
+    let mut candidate = serde_json::to_value(&running).unwrap();
+    candidate["visualization"]["port"] = json!(70000);
+    let (status, preview) =
+        submit_preview(&client, &token, ContentType::JSON, candidate.to_string()).await;
+    assert_eq!(status, Status::UnprocessableEntity);
+    assert!(!preview.valid);
+    assert!(!preview.errors.is_empty());
+    assert!(preview.changes.is_empty());
+
+    let (status, preview) = submit_preview(
+        &client,
+        &token,
+        ContentType::JSON,
+        "{ not a configuration".to_string(),
+    )
+    .await;
+    assert_eq!(status, Status::UnprocessableEntity);
+    assert!(!preview.errors.is_empty());
+}
@@ -0,0 +1,160 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the `POST /api/config/reload` endpoint
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_reload_valid_config`] | A valid file replaces the shared config and reports hot-applied and pending-restart sections |
+//! | [`test_reload_invalid_config`] | An invalid file is rejected with its validation errors and nothing is applied |
+//! | [`test_reload_requires_config_file_and_token`] | The reload needs authentication and a configuration file |
+
+use rocket::http::{Header, Status};
+use rocket::local::asynchronous::Client;
+use rust_photoacoustic::config::reload::load_config_file;
+use rust_photoacoustic::config::{Config, ConfigFilePath, ConfigReloadReport};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+mod common;
+use common::{access_token, test_figment, TEST_HMAC_SECRET};
+
+/// Write the initial configuration file and load it back as the running config
+fn write_initial_config(dir: &TempDir) -> (PathBuf, Config) {
+    let path = dir.path().join("config.yaml");
+    let mut config = Config::default();
+    config.visualization.hmac_secret = TEST_HMAC_SECRET.to_string();
+    config.save_to_file(&path).unwrap();
+    let config = load_config_file(&path).expect("initial config is valid");
+    (path, config)
+}
+
+/// Build a client serving `config`, reloadable from `config_path` if any
+async fn build_test_client(config: Arc<RwLock<Config>>, config_path: Option<&Path>) -> Client {
+    let mut rocket = rust_photoacoustic::visualization::server::build_rocket(
+        test_figment(),
+        config,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+    if let Some(config_path) = config_path {
+        rocket = rocket.manage(ConfigFilePath(config_path.to_path_buf()));
+    }
+    Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance")
+}
+
+async fn reload(client: &Client, token: &str) -> (Status, Option<ConfigReloadReport>) {
+    let response = client
+        .post("/api/config/reload")
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .dispatch()
+        .await;
+    let status = response.status();
+    let report = response
+        .into_string()
+        .await
+        .and_then(|body| serde_json::from_str(&body).ok());
+    (status, report)
+}
+
+#[rocket::async_test]
+async fn test_reload_valid_config() {
+    let dir = TempDir::new().unwrap();
+    let (path, initial) = write_initial_config(&dir);
+    let config = Arc::new(RwLock::new(initial.clone()));
+    let client = build_test_client(config.clone(), Some(&path)).await;
+    let token = access_token(&client, "admin", "read:api write:api admin:api");
+
+    // Reloading an unchanged file applies nothing new
+    let (status, report) = reload(&client, &token).await;
+    assert_eq!(status, Status::Ok);
+    let report = report.expect("reload report");
+    assert!(report.applied);
+    assert!(report.hot_applied.is_empty());
+    assert!(report.pending_restart.is_empty());
+
+    // A filter parameter change is hot-applied, a Modbus change needs a restart
+    let mut updated = initial.clone();
+    updated.processing.default_graph.nodes[1].parameters =
+        serde_json::json!({ "target_channel": "ChannelB" });
+    updated.modbus.port = initial.modbus.port + 1;
+    updated.save_to_file(&path).unwrap();
+
+    let (status, report) = reload(&client, &token).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(
+        report.expect("reload report"),
+        ConfigReloadReport {
+            applied: true,
+            hot_applied: vec!["processing".to_string()],
+            pending_restart: vec!["modbus".to_string()],
+            errors: Vec::new(),
+        }
+    );
+
+    let running = config.read().await;
+    assert_eq!(
+        running.processing.default_graph.nodes[1].parameters["target_channel"],
+        "ChannelB"
+    );
+    assert_eq!(running.modbus.port, initial.modbus.port + 1);
+}
+
+#[rocket::async_test]
+async fn test_reload_invalid_config() {
+    let dir = TempDir::new().unwrap();
+    let (path, initial) = write_initial_config(&dir);
+    let config = Arc::new(RwLock::new(initial.clone()));
+    let client = build_test_client(config.clone(), Some(&path)).await;
+    let token = access_token(&client, "admin", "read:api write:api admin:api");
+
+    // Valid processing change next to an out-of-range port
+    let mut updated = initial.clone();
+    updated.processing.result_buffer_size += 1;
+    let mut yaml: serde_json::Value =
+        serde_yml::from_str(&serde_yml::to_string(&updated).unwrap()).unwrap();
+    yaml["visualization"]["port"] = serde_json::json!(70000);
+    std::fs::write(&path, serde_yml::to_string(&yaml).unwrap()).unwrap();
+
+    let (status, report) = reload(&client, &token).await;
+    assert_eq!(status, Status::UnprocessableEntity);
+    let report = report.expect("reload report");
+    assert!(!report.applied);
+    assert!(!report.errors.is_empty());
+    assert!(report.hot_applied.is_empty());
+    assert!(report.pending_restart.is_empty());
+
+    // Nothing from the rejected file is applied and no sample file is written
+    let running = config.read().await;
+    assert_eq!(
+        running.processing.result_buffer_size,
+        initial.processing.result_buffer_size
+    );
+    assert_eq!(running.visualization.port, initial.visualization.port);
+    assert!(!path.with_extension("sample.yaml").exists());
+}
+
+#[rocket::async_test]
+async fn test_reload_requires_config_file_and_token() {
+    let dir = TempDir::new().unwrap();
+    let (path, initial) = write_initial_config(&dir);
+
+    let client = build_test_client(Arc::new(RwLock::new(initial.clone())), Some(&path)).await;
+    let response = client.post("/api/config/reload").dispatch().await;
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let client = build_test_client(Arc::new(RwLock::new(initial)), None).await;
+    let token = access_token(&client, "admin", "read:api write:api admin:api");
+    let (status, report) = reload(&client, &token).await;
+    assert_eq!(status, Status::NotFound);
+    assert!(!report.expect("reload report").applied);
+}
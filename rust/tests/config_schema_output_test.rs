@@ -4,6 +4,7 @@
 
 use anyhow::Result;
 use rust_photoacoustic::config;
+use serde_json::json;
 
 #[test]
 fn test_config_schema_output() -> Result<()> {
@@ -17,3 +18,58 @@ fn test_config_schema_output() -> Result<()> {
     // If we got here without errors, the test passes
     Ok(())
 }
+
+/// Parameter schema of a node type in the editor schema
+fn node_parameters<'a>(schema: &'a serde_json::Value, node_type: &str) -> &'a serde_json::Value {
+    schema["properties"]["processing"]["properties"]["default_graph"]["properties"]["nodes"]
+        ["items"]["allOf"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|branch| branch["if"]["properties"]["node_type"]["const"] == node_type)
+        .map(|branch| &branch["then"]["properties"]["parameters"])
+        .unwrap()
+}
+
+#[test]
+fn test_editor_schema_annotations() -> Result<()> {
+    let schema = config::editor_config_schema()?;
+
+    // Annotations of the embedded schema are kept
+    let filter_type = &node_parameters(&schema, "filter")["properties"]["type"];
+    assert_eq!(filter_type["description"], "Filter type");
+    let filter_types = filter_type["enum"].as_array().unwrap();
+    assert!(filter_types.contains(&json!("lowpass")));
+    assert!(filter_types.contains(&json!("butter_bandpass")));
+
+    // Missing ones are taken from the doc comments and defaults of the Rust types
+    let port = &schema["properties"]["visualization"]["properties"]["port"];
+    assert!(port["description"]
+        .as_str()
+        .unwrap()
+        .contains("The TCP port the visualization server will listen on."));
+    assert_eq!(port["default"], 8080);
+    assert!(schema["properties"]["photoacoustic"]["description"]
+        .as_str()
+        .is_some());
+    Ok(())
+}
+
+#[test]
+fn test_editor_schema_validates_example_config() -> Result<()> {
+    let schema = config::editor_config_schema()?;
+    let validator = jsonschema::draft202012::new(&schema)?;
+
+    let example: serde_json::Value =
+        serde_yml::from_str(&std::fs::read_to_string("config.example.yaml")?)?;
+    assert!(validator.is_valid(&example));
+    Ok(())
+}
+
+#[test]
+fn test_yaml_language_server_header() {
+    assert_eq!(
+        config::yaml_language_server_header("config_schema.json"),
+        "# yaml-language-server: $schema=config_schema.json"
+    );
+}
@@ -27,25 +27,38 @@ fn test_config_load_and_save() -> Result<()> {
                 .encode((include_str!("../resources/private.key")).as_bytes()),
             rs256_public_key: base64::engine::general_purpose::STANDARD
                 .encode((include_str!("../resources/pub.key")).as_bytes()),
+            rs256_previous_public_keys: vec![],
+            ed25519_private_key: None,
+            ed25519_public_key: None,
+            remote_jwks: None,
+            mutual_tls: None,
             session_secret: "session-secret".to_string(),
             enable_compression: true,
             enable_local_visualization: false,
+            rate_limit: Default::default(),
+            cors: Default::default(),
+            measurement_stream_interval_ms: 250,
+            thermal_stream_interval_ms: 100,
             output: vec![],
         },
         acquisition: AcquisitionConfig {
             enabled: false,
             interval_ms: 1000,
+            stream_buffer_frames: None,
         },
         modbus: ModbusConfig {
             enabled: false,
             port: 502,
             address: "127.0.0.1".to_string(),
+            ..ModbusConfig::default()
         },
         photoacoustic: PhotoacousticConfig::default(),
         access: AccessConfig::default(),
         generix: GenerixConfig::default(),
         processing: rust_photoacoustic::config::ProcessingConfig::default(),
         thermal_regulation: rust_photoacoustic::config::ThermalRegulationConfig::default(),
+        daemon: rust_photoacoustic::config::DaemonConfig::default(),
+        schema_version: rust_photoacoustic::config::migration::CURRENT_SCHEMA_VERSION,
     };
 
     // Save config to file
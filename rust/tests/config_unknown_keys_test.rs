@@ -0,0 +1,198 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the detection of unknown configuration keys
+//!
+//! The file-based tests start from the version 1 fixture, which loads
+//! without error, and add keys to it.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_typo_key_is_reported`] | A misspelled key is listed and logged with its exact path, while the file still loads with the default value |
+//! | [`test_shipped_configs_have_no_unknown_keys`] | The example configuration and the serialized default configuration only use known keys |
+//! | [`test_node_parameters_follow_node_type`] | Node parameters are checked against their node type, free-form sections are not reported |
+//! | [`test_pattern_properties`] | I2C bus names match the bus name pattern and the keys of a bus are checked |
+//! | [`test_former_key_names_are_reported`] | `sampling_rate` and `compression` are reported instead of setting `sample_rate` and `enable_compression` |
+
+use anyhow::Result;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use rust_photoacoustic::config::unknown_keys::find_unknown_keys;
+use rust_photoacoustic::config::Config;
+use serde_json::json;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tempfile::TempDir;
+
+const V1_FIXTURE: &str = "tests/data/config_v1.yaml";
+
+/// Warnings logged by the tests of this file
+static WARNINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct WarningCapture;
+
+impl Log for WarningCapture {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Warn
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            WARNINGS.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: WarningCapture = WarningCapture;
+
+fn schema() -> serde_json::Value {
+    serde_json::from_str(include_str!("../resources/config.schema.json")).unwrap()
+}
+
+/// Copy of the v1 fixture with `extra` appended, in its own directory
+fn fixture_with(extra: &str) -> Result<(TempDir, PathBuf)> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("config.yaml");
+    fs::write(&path, fs::read_to_string(V1_FIXTURE)? + extra)?;
+    Ok((dir, path))
+}
+
+#[test]
+fn test_typo_key_is_reported() -> Result<()> {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(LevelFilter::Warn);
+    }
+    let (_dir, path) = fixture_with("processing:\n  performance:\n    reuse_bufers: true\n")?;
+
+    assert_eq!(
+        Config::unknown_keys_in_file(&path)?,
+        vec!["processing.performance.reuse_bufers"]
+    );
+
+    // The key is ignored: the file loads with the default value
+    let config = Config::from_file(&path)?;
+    assert!(!config.processing.performance.reuse_buffers);
+
+    let file = path.display().to_string();
+    let warnings: Vec<String> = WARNINGS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|warning| warning.contains(&file))
+        .cloned()
+        .collect();
+    assert_eq!(warnings.len(), 1, "{:?}", warnings);
+    assert!(
+        warnings[0].contains("'processing.performance.reuse_bufers'"),
+        "{}",
+        warnings[0]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_shipped_configs_have_no_unknown_keys() -> Result<()> {
+    assert_eq!(
+        Config::unknown_keys_in_file("config.example.yaml")?,
+        Vec::<String>::new()
+    );
+    assert_eq!(
+        Config::unknown_keys_in_file(V1_FIXTURE)?,
+        Vec::<String>::new()
+    );
+
+    let default_config = serde_json::to_value(Config::default())?;
+    assert_eq!(
+        find_unknown_keys(&default_config, &schema()),
+        Vec::<String>::new()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_node_parameters_follow_node_type() {
+    let document = json!({
+        "processing": {
+            "default_graph": {
+                "id": "typos",
+                "nodes": [
+                    {"id": "input", "node_type": "input", "parameters": null},
+                    {
+                        "id": "lowpass",
+                        "node_type": "filter",
+                        "parameters": {"type": "lowpass", "cutof_frequency": 500.0, "order": 4}
+                    },
+                    {"id": "gain", "node_type": "gain", "parameters": {"gain_db": 6.0}},
+                    {
+                        "id": "display",
+                        "node_type": "action_universal",
+                        "parameters": {
+                            "monitored_node": ["peak"],
+                            "driver": {
+                                "type": "https_callback",
+                                "config": {"callback_url": "http://localhost", "any_key": 1}
+                            }
+                        }
+                    },
+                    {
+                        "id": "script",
+                        "node_type": "python",
+                        "parameters": {"script_path": "node.py", "anything": true}
+                    }
+                ],
+                "conections": []
+            }
+        }
+    });
+
+    assert_eq!(
+        find_unknown_keys(&document, &schema()),
+        vec![
+            "processing.default_graph.nodes[1].parameters.cutof_frequency",
+            "processing.default_graph.nodes[3].parameters.monitored_node",
+            "processing.default_graph.conections",
+        ]
+    );
+}
+
+#[test]
+fn test_pattern_properties() {
+    let document = json!({
+        "thermal_regulation": {
+            "enabled": true,
+            "i2c_buses": {
+                "main_bus": {"type": "mock", "device": "mock", "devise": "/dev/i2c-1"}
+            },
+            "regulators": []
+        }
+    });
+
+    assert_eq!(
+        find_unknown_keys(&document, &schema()),
+        vec!["thermal_regulation.i2c_buses.main_bus.devise"]
+    );
+}
+
+#[test]
+fn test_former_key_names_are_reported() -> Result<()> {
+    let (_dir, path) = fixture_with("  sampling_rate: 48000\n")?;
+    let contents = fs::read_to_string(&path)?.replace(
+        "  enabled: true\n  session_secret",
+        "  compression: false\n  session_secret",
+    );
+    fs::write(&path, contents)?;
+
+    let mut unknown_keys = Config::unknown_keys_in_file(&path)?;
+    unknown_keys.sort();
+    assert_eq!(
+        unknown_keys,
+        vec!["photoacoustic.sampling_rate", "visualization.compression"]
+    );
+    let config = Config::from_file(&path)?;
+    assert_eq!(config.photoacoustic.sample_rate, 44100);
+    assert!(config.visualization.enable_compression);
+    Ok(())
+}
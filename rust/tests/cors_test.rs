@@ -0,0 +1,191 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the configurable CORS policy
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_same_origin_only_by_default`] | Without configuration no origin is allowed and preflights are refused |
+//! | [`test_allowed_origin_is_echoed`] | Configured origins are echoed, other origins get no CORS headers |
+//! | [`test_preflight_from_allowed_origin`] | Preflights from allowed origins get the allowed methods and headers |
+//! | [`test_any_origin`] | `allow_any_origin` answers `*`, or echoes the origin with credentials |
+//! | [`test_same_origin_request_untouched`] | Requests whose `Origin` matches `Host` are not treated as cross-origin |
+
+use rocket::http::{Header, Status};
+use rocket::local::asynchronous::{Client, LocalResponse};
+use rust_photoacoustic::config::{Config, CorsConfig};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+mod common;
+use common::test_figment;
+
+const ALLOWED_ORIGIN: &str = "https://dashboard.example.com";
+const OTHER_ORIGIN: &str = "https://evil.example.com";
+
+async fn build_test_client(cors: CorsConfig) -> Client {
+    let mut config = Config::default();
+    config.visualization.cors = cors;
+
+    let rocket = rust_photoacoustic::visualization::server::build_rocket(
+        test_figment(),
+        Arc::new(RwLock::new(config)),
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+    Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance")
+}
+
+/// `GET` a public endpoint with an `Origin` header
+async fn get_from<'c>(client: &'c Client, origin: &str) -> LocalResponse<'c> {
+    client
+        .get("/.well-known/openid-configuration")
+        .header(Header::new("Origin", origin.to_string()))
+        .dispatch()
+        .await
+}
+
+/// Send a CORS preflight request from `origin`
+async fn preflight_from<'c>(client: &'c Client, origin: &str) -> LocalResponse<'c> {
+    client
+        .options("/api/computing")
+        .header(Header::new("Origin", origin.to_string()))
+        .header(Header::new("Access-Control-Request-Method", "GET"))
+        .header(Header::new(
+            "Access-Control-Request-Headers",
+            "authorization",
+        ))
+        .dispatch()
+        .await
+}
+
+fn allow_origin<'a>(response: &'a LocalResponse<'_>) -> Option<&'a str> {
+    response.headers().get_one("Access-Control-Allow-Origin")
+}
+
+#[rocket::async_test]
+async fn test_same_origin_only_by_default() {
+    let client = build_test_client(CorsConfig::default()).await;
+
+    let response = get_from(&client, ALLOWED_ORIGIN).await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(allow_origin(&response), None);
+    assert_eq!(
+        response
+            .headers()
+            .get_one("Access-Control-Allow-Credentials"),
+        None
+    );
+
+    let response = preflight_from(&client, ALLOWED_ORIGIN).await;
+    assert_eq!(response.status(), Status::Forbidden);
+    assert_eq!(allow_origin(&response), None);
+}
+
+#[rocket::async_test]
+async fn test_allowed_origin_is_echoed() {
+    let client = build_test_client(CorsConfig {
+        allowed_origins: vec![ALLOWED_ORIGIN.to_string()],
+        ..CorsConfig::default()
+    })
+    .await;
+
+    let response = get_from(&client, ALLOWED_ORIGIN).await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(allow_origin(&response), Some(ALLOWED_ORIGIN));
+    assert!(response
+        .headers()
+        .get("Vary")
+        .any(|value| value.contains("Origin")));
+    assert_eq!(
+        response
+            .headers()
+            .get_one("Access-Control-Allow-Credentials"),
+        None
+    );
+
+    let response = get_from(&client, OTHER_ORIGIN).await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(allow_origin(&response), None);
+
+    let response = preflight_from(&client, OTHER_ORIGIN).await;
+    assert_eq!(response.status(), Status::Forbidden);
+}
+
+#[rocket::async_test]
+async fn test_preflight_from_allowed_origin() {
+    let client = build_test_client(CorsConfig {
+        allowed_origins: vec![ALLOWED_ORIGIN.to_string()],
+        allow_credentials: true,
+        ..CorsConfig::default()
+    })
+    .await;
+
+    let response = preflight_from(&client, ALLOWED_ORIGIN).await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(allow_origin(&response), Some(ALLOWED_ORIGIN));
+    assert!(response
+        .headers()
+        .get_one("Access-Control-Allow-Methods")
+        .unwrap()
+        .contains("GET"));
+    assert_eq!(
+        response.headers().get_one("Access-Control-Allow-Headers"),
+        Some("authorization")
+    );
+    assert_eq!(
+        response
+            .headers()
+            .get_one("Access-Control-Allow-Credentials"),
+        Some("true")
+    );
+}
+
+#[rocket::async_test]
+async fn test_any_origin() {
+    let client = build_test_client(CorsConfig {
+        allow_any_origin: true,
+        ..CorsConfig::default()
+    })
+    .await;
+    let response = get_from(&client, OTHER_ORIGIN).await;
+    assert_eq!(allow_origin(&response), Some("*"));
+
+    // `*` is not valid with credentials, the origin is echoed instead
+    let client = build_test_client(CorsConfig {
+        allow_any_origin: true,
+        allow_credentials: true,
+        ..CorsConfig::default()
+    })
+    .await;
+    let response = get_from(&client, OTHER_ORIGIN).await;
+    assert_eq!(allow_origin(&response), Some(OTHER_ORIGIN));
+    assert_eq!(
+        response
+            .headers()
+            .get_one("Access-Control-Allow-Credentials"),
+        Some("true")
+    );
+}
+
+#[rocket::async_test]
+async fn test_same_origin_request_untouched() {
+    let client = build_test_client(CorsConfig::default()).await;
+
+    let response = client
+        .options("/api/computing")
+        .header(Header::new("Host", "photoacoustic.local:8080"))
+        .header(Header::new("Origin", "https://photoacoustic.local:8080"))
+        .header(Header::new("Access-Control-Request-Method", "GET"))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(allow_origin(&response), None);
+}
@@ -0,0 +1,131 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Tests for the batch token creation of the `create_token` binary
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_batch_mixed_rows`] | Valid rows get a token, invalid rows get an error without aborting the batch |
+//! | [`test_batch_results_csv`] | The results serialize to CSV with one row per input row and the error column filled |
+//! | [`test_batch_without_header`] | A header row alone is an empty batch, an unreadable header row fails the whole batch |
+
+use rust_photoacoustic::config::Config;
+use rust_photoacoustic::utility::jwt_token::{
+    batch_results_to_csv, BatchTokenResult, ConfigLoader, JwtAlgorithm, TokenCreationError,
+    TokenCreator, TokenInspector, BATCH_RESULT_COLUMNS,
+};
+
+/// Three valid rows and four invalid ones, the header being line 1
+const BATCH_CSV: &str = "\
+user,client,algorithm,duration
+admin,LaserSmartClient,HS256,600
+admin, LaserSmartClient ,,
+nobody,LaserSmartClient,HS256,600
+admin,UnknownClient,HS256,600
+admin,LaserSmartClient,ES512,600
+admin,LaserSmartClient,HS256,soon
+admin,LaserSmartClient,RS256,60
+";
+
+fn config_loader() -> ConfigLoader {
+    let mut config = Config::default();
+    config.visualization.hmac_secret = "test-hmac-secret-for-batch".to_string();
+    ConfigLoader::from_config(&config).unwrap()
+}
+
+fn run_batch(config_loader: &ConfigLoader) -> Vec<BatchTokenResult> {
+    TokenCreator::new(config_loader)
+        .unwrap()
+        .create_batch(BATCH_CSV.as_bytes(), &JwtAlgorithm::HS256, 3600)
+        .expect("batch processed")
+}
+
+#[test]
+fn test_batch_mixed_rows() {
+    let config_loader = config_loader();
+    let results = run_batch(&config_loader);
+    assert_eq!(results.len(), 7);
+    assert_eq!(
+        results.iter().map(|result| result.line).collect::<Vec<_>>(),
+        vec![2, 3, 4, 5, 6, 7, 8]
+    );
+
+    // Valid rows, the empty algorithm and duration taking the defaults
+    let inspector = TokenInspector::new(&config_loader).unwrap();
+    for (index, algorithm, duration) in [(0, "HS256", 600), (1, "HS256", 3600), (6, "RS256", 60)] {
+        let result = &results[index];
+        assert_eq!(result.error, None, "line {}", result.line);
+        assert_eq!(result.algorithm, algorithm);
+        assert_eq!(result.duration, Some(duration));
+        assert_eq!(result.client, "LaserSmartClient");
+        let token = result.token.as_ref().expect("token created");
+        assert!(inspector.inspect(token).unwrap().status.is_valid());
+    }
+
+    // Invalid rows
+    let error = |index: usize| {
+        let result: &BatchTokenResult = &results[index];
+        assert_eq!(result.token, None, "line {}", result.line);
+        result.error.clone().expect("error reported")
+    };
+    assert!(error(2).contains("User 'nobody' not found"), "{}", error(2));
+    assert!(
+        error(3).contains("Client 'UnknownClient' not found"),
+        "{}",
+        error(3)
+    );
+    assert!(
+        error(4).contains("Unsupported algorithm: ES512"),
+        "{}",
+        error(4)
+    );
+    assert!(!error(5).is_empty());
+    assert_eq!(results[5].user, "admin");
+}
+
+#[test]
+fn test_batch_results_csv() {
+    let results = run_batch(&config_loader());
+    let csv = batch_results_to_csv(&results).unwrap();
+
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    assert_eq!(
+        reader.headers().unwrap().iter().collect::<Vec<_>>(),
+        BATCH_RESULT_COLUMNS.to_vec()
+    );
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(rows.len(), results.len());
+
+    assert_eq!(&rows[0][0], "2");
+    assert_eq!(&rows[0][1], "admin");
+    assert_eq!(&rows[0][5], results[0].token.as_deref().unwrap());
+    assert_eq!(&rows[0][6], "");
+
+    assert_eq!(&rows[2][0], "4");
+    assert_eq!(&rows[2][1], "nobody");
+    assert_eq!(&rows[2][5], "");
+    assert!(rows[2][6].contains("not found"));
+}
+
+#[test]
+fn test_batch_without_header() {
+    let token_creator = TokenCreator::new(&config_loader()).unwrap();
+
+    // A header without rows is an empty batch
+    let results = token_creator
+        .create_batch(
+            "user,client,algorithm,duration\n".as_bytes(),
+            &JwtAlgorithm::HS256,
+            60,
+        )
+        .unwrap();
+    assert!(results.is_empty());
+
+    // Invalid UTF-8 in the header row fails the whole batch
+    let result = token_creator.create_batch(&b"user,\xff\n"[..], &JwtAlgorithm::HS256, 60);
+    assert!(matches!(
+        result,
+        Err(TokenCreationError::BatchFileError { .. })
+    ));
+}
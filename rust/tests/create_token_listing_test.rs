@@ -0,0 +1,112 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Tests for the listings of the `create_token` binary
+//!
+//! A sample configuration is written to a temporary file and loaded the way
+//! `create_token` loads it before listing its users, clients and permissions.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_list_users`] | Every user is listed with its permissions, role permissions expanded |
+//! | [`test_list_clients`] | Every client is listed with its default scopes |
+//! | [`test_list_permissions`] | Every granted permission is listed once, sorted, with the users holding it |
+
+use rust_photoacoustic::config::{Config, Role, User};
+use rust_photoacoustic::utility::jwt_token::{ConfigLoader, EntityListing};
+use tempfile::tempdir;
+
+/// Load a sample configuration with an `operator` role from a temporary file
+fn sample_config_loader() -> ConfigLoader {
+    let mut config = Config::default();
+    config.access.roles = vec![Role {
+        name: "operator".to_string(),
+        permissions: vec!["read:api".to_string(), "write:api".to_string()],
+    }];
+    config.access.users.push(User {
+        user: "operator1".to_string(),
+        permissions: vec!["read:logs".to_string()],
+        roles: vec!["operator".to_string()],
+        ..User::default()
+    });
+    let mut device_client = config.access.clients[0].clone();
+    device_client.client_id = "DeviceClient".to_string();
+    device_client.default_scope = "read:api".to_string();
+    config.access.clients.push(device_client);
+
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("config.yaml");
+    config.save_to_file(&path).expect("sample config written");
+    let config = Config::from_file(&path).expect("sample config loaded");
+    ConfigLoader::from_config(&config).unwrap()
+}
+
+#[test]
+fn test_list_users() {
+    let users = sample_config_loader().list_users();
+
+    assert_eq!(
+        users
+            .iter()
+            .map(|user| user.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["admin", "operator1"]
+    );
+    assert_eq!(
+        users[1],
+        EntityListing {
+            name: "operator1".to_string(),
+            permissions: vec![
+                "read:logs".to_string(),
+                "read:api".to_string(),
+                "write:api".to_string()
+            ],
+        }
+    );
+    assert!(users[0].permissions.contains(&"admin:api".to_string()));
+}
+
+#[test]
+fn test_list_clients() {
+    let clients = sample_config_loader().list_clients();
+
+    let laser = clients
+        .iter()
+        .find(|client| client.name == "LaserSmartClient")
+        .expect("default client listed");
+    assert!(laser.permissions.contains(&"openid".to_string()));
+    assert!(laser.permissions.contains(&"read:api".to_string()));
+
+    let device = clients
+        .iter()
+        .find(|client| client.name == "DeviceClient")
+        .expect("added client listed");
+    assert_eq!(device.permissions, vec!["read:api"]);
+}
+
+#[test]
+fn test_list_permissions() {
+    let permissions = sample_config_loader().list_permissions();
+
+    let names: Vec<&str> = permissions
+        .iter()
+        .map(|listing| listing.permission.as_str())
+        .collect();
+    let mut sorted = names.clone();
+    sorted.sort();
+    sorted.dedup();
+    assert_eq!(names, sorted, "permissions listed once, sorted");
+
+    let holders = |permission: &str| {
+        permissions
+            .iter()
+            .find(|listing| listing.permission == permission)
+            .map(|listing| listing.users.clone())
+            .unwrap_or_default()
+    };
+    assert_eq!(holders("read:api"), vec!["admin", "operator1"]);
+    assert_eq!(holders("read:logs"), vec!["operator1"]);
+    assert_eq!(holders("admin:api"), vec!["admin"]);
+    assert!(holders("not:granted").is_empty());
+}
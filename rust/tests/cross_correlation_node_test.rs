@@ -0,0 +1,217 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the cross-correlation node
+//!
+//! Channel B is channel A evaluated `delay` samples earlier, so that fractional
+//! delays are exact: the test signal is a sum of sinusoids computed analytically
+//! at any time.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_integer_delays`] | Positive, negative and zero delays are estimated to the sample, in samples and seconds |
+//! | [`test_fractional_delays`] | Parabolic interpolation recovers sub-sample delays, which are rounded without it |
+//! | [`test_window_accumulation`] | The lag is only estimated once a full window is buffered, across frames |
+//! | [`test_node_from_graph_config`] | A `computing_cross_correlation` node writes its result to the graph computing state |
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::AudioFrame;
+use rust_photoacoustic::config::processing::{ConnectionConfig, NodeConfig, ProcessingGraphConfig};
+use rust_photoacoustic::processing::computing_nodes::{
+    ComputingSharedData, CrossCorrelationNode, SharedComputingState, TimeDelayResult,
+};
+use rust_photoacoustic::processing::{ProcessingData, ProcessingGraph, ProcessingNode};
+use std::f64::consts::TAU;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+const SAMPLE_RATE: u32 = 48000;
+const WINDOW_SIZE: usize = 4096;
+
+/// Broadband sum of sinusoids at time `t` in seconds
+fn signal(t: f64) -> f32 {
+    [
+        (310.0, 0.4),
+        (770.0, 1.3),
+        (1130.0, 2.9),
+        (1790.0, 5.1),
+        (2450.0, 0.7),
+    ]
+    .iter()
+    .map(|(frequency, phase)| 0.2 * (TAU * frequency * t + phase).sin())
+    .sum::<f64>() as f32
+}
+
+/// Channels A and B of `length` samples from `first_sample`, B lagging `delay` samples behind A
+fn delayed_channels(first_sample: usize, length: usize, delay: f64) -> (Vec<f32>, Vec<f32>) {
+    (first_sample..first_sample + length)
+        .map(|n| {
+            (
+                signal(n as f64 / SAMPLE_RATE as f64),
+                signal((n as f64 - delay) / SAMPLE_RATE as f64),
+            )
+        })
+        .unzip()
+}
+
+fn dual_channel(channel_a: Vec<f32>, channel_b: Vec<f32>, frame_number: u64) -> ProcessingData {
+    ProcessingData::DualChannel {
+        channel_a,
+        channel_b,
+        sample_rate: SAMPLE_RATE,
+        timestamp: 0,
+        frame_number,
+    }
+}
+
+/// Estimated lag of a single window delayed by `delay` samples
+fn estimate(delay: f64, interpolation: bool) -> Result<TimeDelayResult> {
+    let mut node = CrossCorrelationNode::new("time_delay".to_string())
+        .with_window_size(WINDOW_SIZE)
+        .with_max_lag(32)
+        .with_interpolation(interpolation);
+    let (channel_a, channel_b) = delayed_channels(0, WINDOW_SIZE, delay);
+    let output = node.process(dual_channel(channel_a.clone(), channel_b, 1))?;
+
+    // Pass-through
+    match output {
+        ProcessingData::DualChannel {
+            channel_a: output_a,
+            ..
+        } => assert_eq!(output_a, channel_a),
+        _ => panic!("unexpected output type"),
+    }
+
+    let state = node.get_shared_state();
+    let result = state
+        .try_read()?
+        .get_time_delay_result("time_delay")
+        .cloned()
+        .expect("time delay estimated");
+    Ok(result)
+}
+
+#[test]
+fn test_integer_delays() -> Result<()> {
+    for delay in [0.0, 5.0, -7.0, 20.0] {
+        let result = estimate(delay, true)?;
+        assert!(
+            (result.lag_samples - delay).abs() < 0.01,
+            "delay {}: estimated {}",
+            delay,
+            result.lag_samples
+        );
+        assert!((result.lag_seconds - delay / SAMPLE_RATE as f64).abs() < 1e-6);
+        assert!(
+            result.peak_correlation > 0.95,
+            "{}",
+            result.peak_correlation
+        );
+        assert_eq!(result.window_size, WINDOW_SIZE);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_fractional_delays() -> Result<()> {
+    for delay in [3.4, -2.25, 12.75] {
+        let result = estimate(delay, true)?;
+        assert!(
+            (result.lag_samples - delay).abs() < 0.02,
+            "delay {}: estimated {}",
+            delay,
+            result.lag_samples
+        );
+
+        let rounded = estimate(delay, false)?;
+        assert_eq!(rounded.lag_samples, delay.round());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_window_accumulation() -> Result<()> {
+    let mut node = CrossCorrelationNode::new("time_delay".to_string())
+        .with_window_size(WINDOW_SIZE)
+        .with_max_lag(32);
+    let state = node.get_shared_state();
+
+    let frame_size = WINDOW_SIZE / 4;
+    for frame in 0..6 {
+        let (channel_a, channel_b) = delayed_channels(frame * frame_size, frame_size, 9.0);
+        node.process(dual_channel(channel_a, channel_b, frame as u64 + 1))?;
+
+        let result = state
+            .try_read()?
+            .get_time_delay_result("time_delay")
+            .cloned();
+        if frame < 3 {
+            assert!(result.is_none(), "estimated before a full window");
+        } else {
+            let lag = result.expect("time delay estimated").lag_samples;
+            assert!(
+                (lag - 9.0).abs() < 0.01,
+                "frame {}: estimated {}",
+                frame,
+                lag
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_node_from_graph_config() -> Result<()> {
+    let config = ProcessingGraphConfig {
+        id: "time_delay_graph".to_string(),
+        nodes: vec![
+            NodeConfig {
+                id: "input".to_string(),
+                node_type: "input".to_string(),
+                parameters: serde_json::Value::Null,
+            },
+            NodeConfig {
+                id: "time_delay".to_string(),
+                node_type: "computing_cross_correlation".to_string(),
+                parameters: serde_json::json!({
+                    "window_size": WINDOW_SIZE,
+                    "max_lag_samples": 16,
+                    "interpolation": true
+                }),
+            },
+        ],
+        connections: vec![ConnectionConfig {
+            from: "input".to_string(),
+            to: "time_delay".to_string(),
+        }],
+        output_node: Some("time_delay".to_string()),
+    };
+    let computing_state: SharedComputingState =
+        Arc::new(RwLock::new(ComputingSharedData::default()));
+    let mut graph =
+        ProcessingGraph::from_config_with_computing_state(&config, Some(computing_state.clone()))?;
+
+    let (channel_a, channel_b) = delayed_channels(0, WINDOW_SIZE, -4.5);
+    graph.execute(ProcessingData::AudioFrame(AudioFrame::new(
+        channel_a,
+        channel_b,
+        SAMPLE_RATE,
+        1,
+    )))?;
+
+    let state = computing_state.try_read()?;
+    let result = state
+        .get_time_delay_result("time_delay")
+        .expect("time delay estimated");
+    assert!(
+        (result.lag_samples + 4.5).abs() < 0.02,
+        "{}",
+        result.lag_samples
+    );
+    assert_eq!(
+        state.script_context()["time_delay_results"]["time_delay"]["window_size"],
+        WINDOW_SIZE
+    );
+    Ok(())
+}
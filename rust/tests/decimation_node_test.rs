@@ -0,0 +1,277 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the decimation node
+//!
+//! One second at 48 kHz is decimated by 4 to 12 kHz. The band-limited signal
+//! is a 1 kHz and a 2.5 kHz tone, below the 4.8 kHz cutoff. Tones at 10 kHz and
+//! 15 kHz, above the output Nyquist frequency, would alias to 2 kHz and 3 kHz.
+//! Amplitudes are measured over the second half of the output, which holds a
+//! whole number of periods of every tone.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_decimates_without_aliasing`] | The output rate is 12 kHz, the tones are kept and the out-of-band tones do not alias |
+//! | [`test_frame_bookkeeping`] | Frames of any size give the same samples as one frame, with their frame numbers and timestamps |
+//! | [`test_node_from_graph_config`] | A `decimation` graph node decimates both channels |
+//! | [`test_parameter_validation`] | Invalid factors, cutoffs, filter lengths and sample rates are rejected |
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::AudioFrame;
+use rust_photoacoustic::config::processing::{ConnectionConfig, NodeConfig, ProcessingGraphConfig};
+use rust_photoacoustic::processing::nodes::DecimationNode;
+use rust_photoacoustic::processing::{ProcessingData, ProcessingGraph, ProcessingNode};
+use rust_photoacoustic::utility::noise_generator::NoiseGenerator;
+use std::f64::consts::TAU;
+
+const SAMPLE_RATE: u32 = 48000;
+const FACTOR: usize = 4;
+const OUTPUT_RATE: u32 = SAMPLE_RATE / FACTOR as u32;
+/// 100 ms frames
+const FRAME_SIZE: usize = 4800;
+/// Tones kept by the decimation, as (frequency, amplitude)
+const IN_BAND: [(f64, f64); 2] = [(1000.0, 0.2), (2500.0, 0.1)];
+/// Tones above the output Nyquist frequency, as (frequency, amplitude)
+const OUT_OF_BAND: [(f64, f64); 2] = [(10000.0, 0.5), (15000.0, 0.3)];
+/// Frequencies the out-of-band tones alias to at 12 kHz
+const ALIASES: [f64; 2] = [2000.0, 3000.0];
+
+/// One second of the in-band and out-of-band tones
+fn signal() -> Vec<f32> {
+    (0..SAMPLE_RATE as usize)
+        .map(|n| {
+            let t = n as f64 / SAMPLE_RATE as f64;
+            IN_BAND
+                .iter()
+                .chain(&OUT_OF_BAND)
+                .map(|(frequency, amplitude)| amplitude * (TAU * frequency * t).sin())
+                .sum::<f64>() as f32
+        })
+        .collect()
+}
+
+/// Amplitude at `frequency` over the second half of a signal sampled at `sample_rate`
+fn amplitude(samples: &[f32], frequency: f64, sample_rate: u32) -> f64 {
+    let half = &samples[samples.len() / 2..];
+    let (re, im) = half
+        .iter()
+        .enumerate()
+        .fold((0.0, 0.0), |(re, im), (i, sample)| {
+            let phase = TAU * frequency * i as f64 / sample_rate as f64;
+            (
+                re + *sample as f64 * phase.cos(),
+                im + *sample as f64 * phase.sin(),
+            )
+        });
+    2.0 * re.hypot(im) / half.len() as f64
+}
+
+/// Check the tones of one second decimated to 12 kHz
+fn assert_decimated(output: &[f32]) {
+    assert_eq!(output.len(), OUTPUT_RATE as usize);
+    for (frequency, expected) in IN_BAND {
+        let measured = amplitude(output, frequency, OUTPUT_RATE);
+        assert!(
+            (measured - expected).abs() / expected < 0.001,
+            "{} Hz: amplitude {} instead of {}",
+            frequency,
+            measured,
+            expected
+        );
+    }
+    for frequency in ALIASES {
+        let measured = amplitude(output, frequency, OUTPUT_RATE);
+        assert!(
+            measured < 1e-4,
+            "{} Hz: alias of amplitude {}",
+            frequency,
+            measured
+        );
+    }
+}
+
+fn decimate(node: &mut DecimationNode, frame: &[f32], frame_number: u64) -> Result<Vec<f32>> {
+    match node.process(ProcessingData::SingleChannel {
+        samples: frame.to_vec(),
+        sample_rate: SAMPLE_RATE,
+        timestamp: 1000 + frame_number * 100,
+        frame_number,
+    })? {
+        ProcessingData::SingleChannel {
+            samples,
+            sample_rate,
+            timestamp,
+            frame_number: output_frame_number,
+        } => {
+            assert_eq!(sample_rate, OUTPUT_RATE);
+            assert_eq!(timestamp, 1000 + frame_number * 100);
+            assert_eq!(output_frame_number, frame_number);
+            Ok(samples)
+        }
+        _ => panic!("unexpected output type"),
+    }
+}
+
+#[test]
+fn test_decimates_without_aliasing() -> Result<()> {
+    let signal = signal();
+    let mut node = DecimationNode::new("decimate".to_string(), FACTOR)?;
+    assert_eq!(node.get_filter_length(), 16 * FACTOR + 1);
+    assert_eq!(node.latency_samples(), 8 * FACTOR);
+
+    let mut output = Vec::new();
+    for (frame_number, frame) in signal.chunks(FRAME_SIZE).enumerate() {
+        let samples = decimate(&mut node, frame, frame_number as u64)?;
+        assert_eq!(samples.len(), FRAME_SIZE / FACTOR);
+        output.extend(samples);
+    }
+    assert_decimated(&output);
+
+    // Keeping every 4th sample without filtering aliases the out-of-band tones
+    let naive: Vec<f32> = signal.iter().step_by(FACTOR).copied().collect();
+    for ((_, amplitude_in), frequency) in OUT_OF_BAND.iter().zip(ALIASES) {
+        assert!(amplitude(&naive, frequency, OUTPUT_RATE) > 0.9 * amplitude_in);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_frame_bookkeeping() -> Result<()> {
+    let mut noise = NoiseGenerator::new(42);
+    let signal: Vec<f32> = (0..10000).map(|_| noise.random_gaussian()).collect();
+
+    let mut reference = DecimationNode::new("decimate".to_string(), 3)?;
+    let mut expected = decimate(&mut reference, &signal, 0)?;
+    assert_eq!(expected.len(), signal.len().div_ceil(3));
+
+    // Frames shorter than the factor, and lengths that are not multiples of it
+    let mut node = DecimationNode::new("decimate".to_string(), 3)?;
+    let mut output = Vec::new();
+    let mut start = 0;
+    for (frame_number, size) in [1000, 1, 2, 4, 333, 7, 4800, 1]
+        .into_iter()
+        .cycle()
+        .enumerate()
+    {
+        let end = (start + size).min(signal.len());
+        output.extend(decimate(
+            &mut node,
+            &signal[start..end],
+            frame_number as u64,
+        )?);
+        start = end;
+        if start == signal.len() {
+            break;
+        }
+    }
+    assert_eq!(output, expected);
+
+    // After a reset, the stream starts again from silence
+    node.reset();
+    expected.truncate(100);
+    assert_eq!(decimate(&mut node, &signal[..300], 0)?, expected);
+    Ok(())
+}
+
+#[test]
+fn test_node_from_graph_config() -> Result<()> {
+    let config = ProcessingGraphConfig {
+        id: "decimation_graph".to_string(),
+        nodes: vec![
+            NodeConfig {
+                id: "input".to_string(),
+                node_type: "input".to_string(),
+                parameters: serde_json::Value::Null,
+            },
+            NodeConfig {
+                id: "decimate".to_string(),
+                node_type: "decimation".to_string(),
+                parameters: serde_json::json!({
+                    "factor": FACTOR,
+                    "cutoff_ratio": 0.8
+                }),
+            },
+        ],
+        connections: vec![ConnectionConfig {
+            from: "input".to_string(),
+            to: "decimate".to_string(),
+        }],
+        output_node: Some("decimate".to_string()),
+    };
+    let mut graph = ProcessingGraph::from_config(&config)?;
+
+    let signal = signal();
+    let mut channel_a = Vec::new();
+    let mut channel_b = Vec::new();
+    for (frame_number, frame) in signal.chunks(FRAME_SIZE).enumerate() {
+        let inverted: Vec<f32> = frame.iter().map(|sample| -sample).collect();
+        let outputs = graph.execute(ProcessingData::AudioFrame(AudioFrame::new(
+            frame.to_vec(),
+            inverted,
+            SAMPLE_RATE,
+            frame_number as u64,
+        )))?;
+        match &outputs[..] {
+            [ProcessingData::DualChannel {
+                channel_a: a,
+                channel_b: b,
+                sample_rate,
+                ..
+            }] => {
+                assert_eq!(*sample_rate, OUTPUT_RATE);
+                channel_a.extend_from_slice(a);
+                channel_b.extend_from_slice(b);
+            }
+            _ => panic!("unexpected output"),
+        }
+    }
+    assert_decimated(&channel_a);
+    assert_decimated(&channel_b);
+    for (a, b) in channel_a.iter().zip(&channel_b) {
+        assert_eq!(*a, -*b);
+    }
+
+    // The factor is required
+    let mut invalid = config.clone();
+    invalid.nodes[1].parameters = serde_json::json!({"cutoff_ratio": 0.8});
+    assert!(ProcessingGraph::from_config(&invalid).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_parameter_validation() -> Result<()> {
+    assert!(DecimationNode::new("decimate".to_string(), 1).is_err());
+    let node = DecimationNode::new("decimate".to_string(), FACTOR)?;
+    assert!(node.clone().with_cutoff_ratio(0.0).is_err());
+    assert!(node.clone().with_cutoff_ratio(1.5).is_err());
+    assert!(node.clone().with_filter_length(64).is_err());
+    assert!(node.clone().with_filter_length(1).is_err());
+
+    // The input sample rate must be a multiple of the factor
+    let mut node = node.with_filter_length(33)?;
+    assert_eq!(node.latency_samples(), 16);
+    assert!(node
+        .process(ProcessingData::SingleChannel {
+            samples: vec![0.0; 441],
+            sample_rate: 44100,
+            timestamp: 0,
+            frame_number: 0,
+        })
+        .is_err());
+
+    // An invalid update leaves the node unchanged
+    assert!(node
+        .update_config(&serde_json::json!({"factor": 8, "filter_length": 20}))
+        .is_err());
+    assert_eq!(node.get_factor(), FACTOR);
+    assert!(!node.update_config(&serde_json::json!({"factor": FACTOR}))?);
+
+    // A new factor without a length uses the default length for that factor
+    assert!(node.update_config(&serde_json::json!({"factor": 8}))?);
+    assert_eq!(node.get_factor(), 8);
+    assert_eq!(node.get_filter_length(), 16 * 8 + 1);
+    assert!(node.update_config(&serde_json::json!({"cutoff_ratio": 0.5}))?);
+    assert_eq!(node.get_cutoff_ratio(), 0.5);
+    Ok(())
+}
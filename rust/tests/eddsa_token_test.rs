@@ -0,0 +1,160 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Tests for the EdDSA (Ed25519) JWT signing algorithm
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_eddsa_token_round_trip`] | A token minted with EdDSA carries the `EdDSA` header and is accepted by the validator and the inspector |
+//! | [`test_eddsa_token_other_key_rejected`] | A token signed with another Ed25519 key is rejected |
+//! | [`test_eddsa_without_keys`] | Minting with EdDSA fails when no Ed25519 key is configured |
+//! | [`test_eddsa_token_on_protected_route`] | A protected HTTP route accepts an EdDSA token and rejects one signed with another key |
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use rocket::http::{Header, Status};
+use rocket::local::asynchronous::Client;
+use rust_photoacoustic::config::{AccessConfig, Config};
+use rust_photoacoustic::utility::jwt_token::{
+    ConfigLoader, JwtAlgorithm, TokenCreationError, TokenCreationParams, TokenCreator,
+    TokenInspector, TokenStatus,
+};
+use rust_photoacoustic::visualization::auth::jwt::JwtValidator;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+mod common;
+use common::{test_figment, TEST_HMAC_SECRET};
+
+/// Generate an Ed25519 key pair as (private, public) PEM
+fn ed25519_keys() -> (String, String) {
+    let key_pair = rcgen::KeyPair::generate_for(&rcgen::PKCS_ED25519).unwrap();
+    (key_pair.serialize_pem(), key_pair.public_key_pem())
+}
+
+/// Configuration holding the given Ed25519 keys
+fn eddsa_config(keys: Option<&(String, String)>) -> Config {
+    let mut config = Config::default();
+    config.visualization.hmac_secret = TEST_HMAC_SECRET.to_string();
+    if let Some((private_pem, public_pem)) = keys {
+        config.visualization.ed25519_private_key = Some(BASE64_STANDARD.encode(private_pem));
+        config.visualization.ed25519_public_key = Some(BASE64_STANDARD.encode(public_pem));
+    }
+    config
+}
+
+/// Load a configuration holding the given Ed25519 keys
+fn config_loader(keys: Option<&(String, String)>) -> ConfigLoader {
+    ConfigLoader::from_config(&eddsa_config(keys)).unwrap()
+}
+
+fn mint(config_loader: &ConfigLoader) -> Result<String, TokenCreationError> {
+    TokenCreator::new(config_loader)?
+        .create_token(&TokenCreationParams {
+            user_id: "admin".to_string(),
+            client_id: "LaserSmartClient".to_string(),
+            algorithm: JwtAlgorithm::from_str("EdDSA")?,
+            duration_seconds: 3600,
+        })
+        .map(|result| result.token)
+}
+
+#[test]
+fn test_eddsa_token_round_trip() {
+    let keys = ed25519_keys();
+    let config_loader = config_loader(Some(&keys));
+    let token = mint(&config_loader).expect("EdDSA token minted");
+
+    let header = jsonwebtoken::decode_header(&token).unwrap();
+    assert_eq!(header.alg, jsonwebtoken::Algorithm::EdDSA);
+
+    let validator = JwtValidator::new(None, None, AccessConfig::default())
+        .unwrap()
+        .with_ed25519_public_key(keys.1.as_bytes())
+        .unwrap()
+        .with_issuer("LaserSmartServer")
+        .with_audience("LaserSmartClient");
+    let claims = validator.validate(&token).expect("EdDSA token accepted");
+    assert_eq!(claims.sub, "admin");
+
+    let inspection = TokenInspector::new(&config_loader)
+        .unwrap()
+        .inspect(&token)
+        .unwrap();
+    assert_eq!(inspection.status, TokenStatus::Valid);
+    assert_eq!(inspection.header["alg"], "EdDSA");
+}
+
+#[test]
+fn test_eddsa_token_other_key_rejected() {
+    let token = mint(&config_loader(Some(&ed25519_keys()))).unwrap();
+    let other_keys = ed25519_keys();
+
+    let validator = JwtValidator::new(None, None, AccessConfig::default())
+        .unwrap()
+        .with_ed25519_public_key(other_keys.1.as_bytes())
+        .unwrap();
+    assert!(validator.validate(&token).is_err());
+
+    let inspection = TokenInspector::new(&config_loader(Some(&other_keys)))
+        .unwrap()
+        .inspect(&token)
+        .unwrap();
+    assert!(
+        matches!(inspection.status, TokenStatus::InvalidSignature { .. }),
+        "{:?}",
+        inspection.status
+    );
+
+    // A validator without Ed25519 key refuses EdDSA tokens
+    let validator = JwtValidator::new(None, None, AccessConfig::default()).unwrap();
+    assert!(validator.validate(&token).is_err());
+}
+
+#[test]
+fn test_eddsa_without_keys() {
+    assert!(matches!(
+        mint(&config_loader(None)),
+        Err(TokenCreationError::KeyDecodingError { .. })
+    ));
+}
+
+#[rocket::async_test]
+async fn test_eddsa_token_on_protected_route() {
+    let keys = ed25519_keys();
+    let token = mint(&config_loader(Some(&keys))).unwrap();
+    let other_token = mint(&config_loader(Some(&ed25519_keys()))).unwrap();
+
+    let rocket = rust_photoacoustic::visualization::server::build_rocket(
+        test_figment(),
+        Arc::new(RwLock::new(eddsa_config(Some(&keys)))),
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+    let client = Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance");
+
+    let response = client
+        .get("/api/test/ping")
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+
+    let response = client
+        .get("/api/test/ping")
+        .header(Header::new(
+            "Authorization",
+            format!("Bearer {}", other_token),
+        ))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Unauthorized);
+}
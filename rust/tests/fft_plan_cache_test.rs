@@ -0,0 +1,203 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the cached FFT plan and buffers of `FFTAnalyzer`
+//!
+//! A freshly created analyzer builds its plan, window and buffers on its first
+//! call: it serves as the uncached reference. A counting global allocator
+//! measures the allocations of the calling thread.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_cached_analysis_matches_fresh_analyzer`] | A long-lived analyzer returns bit-identical spectra to a fresh analyzer, with and without averaging |
+//! | [`test_allocations_after_warm_up`] | After warm-up, an analysis only allocates the three vectors of the returned spectrum |
+//! | [`test_reconfiguration_rebuilds_cache`] | Changing the window size or window function rebuilds the cache and matches a fresh analyzer |
+//! | [`test_analyzer_moves_across_threads`] | A warmed-up analyzer keeps working as a `Box<dyn SpectralAnalyzer>` moved to another thread |
+
+use rust_photoacoustic::spectral::fft::{
+    FFTAnalyzer, SpectralAnalyzer, SpectrumData, WindowFunction,
+};
+use rust_photoacoustic::utility::noise_generator::NoiseGenerator;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::f32::consts::TAU;
+
+/// Global allocator counting the allocations of each thread
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn count_allocation() {
+    let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count_allocation();
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count_allocation();
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count_allocation();
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Number of allocations made by the current thread while running `f`
+fn allocations<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+const SAMPLE_RATE: u32 = 48000;
+
+/// Frame `index` of a 2 kHz tone in noise whose amplitude changes every frame
+fn frame(len: usize, index: u32) -> Vec<f32> {
+    let mut noise = NoiseGenerator::new(index + 1);
+    let amplitude = 0.2 + 0.05 * index as f32;
+    (0..len)
+        .map(|n| {
+            let t = n as f32 / SAMPLE_RATE as f32;
+            amplitude * (TAU * 2000.0 * t).sin() + 0.01 * noise.random_gaussian()
+        })
+        .collect()
+}
+
+fn assert_identical(what: &str, cached: &SpectrumData, fresh: &SpectrumData) {
+    assert_eq!(
+        cached.frequencies, fresh.frequencies,
+        "{}: frequencies",
+        what
+    );
+    assert_eq!(cached.amplitudes, fresh.amplitudes, "{}: amplitudes", what);
+    assert_eq!(cached.phases, fresh.phases, "{}: phases", what);
+}
+
+#[test]
+fn test_cached_analysis_matches_fresh_analyzer() {
+    for averages in [1, 3] {
+        let mut cached = FFTAnalyzer::new(2048, averages);
+        for index in 0..8 {
+            let spectrum = cached
+                .analyze(&frame(2048, index), SAMPLE_RATE)
+                .expect("spectrum analyzed");
+
+            // A fresh analyzer fed the same averaging history
+            let mut fresh = FFTAnalyzer::new(2048, averages);
+            let first = (index + 1).saturating_sub(averages as u32);
+            let mut expected = None;
+            for history in first..=index {
+                expected = Some(
+                    fresh
+                        .analyze(&frame(2048, history), SAMPLE_RATE)
+                        .expect("spectrum analyzed"),
+                );
+            }
+            assert_identical(
+                &format!("{} averages, frame {}", averages, index),
+                &spectrum,
+                &expected.unwrap(),
+            );
+        }
+    }
+}
+
+#[test]
+fn test_allocations_after_warm_up() {
+    let signal = frame(4096, 0);
+    let mut analyzer = FFTAnalyzer::new(4096, 4);
+
+    let (_, first_call) = allocations(|| analyzer.analyze(&signal, SAMPLE_RATE).unwrap());
+    assert!(first_call > 3, "first call: {} allocations", first_call);
+
+    // Fill the averaging history
+    for _ in 0..4 {
+        analyzer.analyze(&signal, SAMPLE_RATE).unwrap();
+    }
+
+    for call in 0..10 {
+        let (spectrum, count) = allocations(|| analyzer.analyze(&signal, SAMPLE_RATE).unwrap());
+        assert_eq!(spectrum.amplitudes.len(), 2048);
+        assert!(
+            count <= 3,
+            "call {} after warm-up: {} allocations",
+            call,
+            count
+        );
+    }
+}
+
+#[test]
+fn test_reconfiguration_rebuilds_cache() {
+    let mut analyzer = FFTAnalyzer::new(1024, 2);
+    for index in 0..3 {
+        analyzer
+            .analyze(&frame(1024, index), SAMPLE_RATE)
+            .expect("spectrum analyzed");
+    }
+
+    // A new window size clears the averaging history
+    analyzer.set_frame_size(2048);
+    assert_eq!(analyzer.frame_size(), 2048);
+    let signal = frame(2048, 5);
+    let spectrum = analyzer.analyze(&signal, SAMPLE_RATE).unwrap();
+    assert_eq!(spectrum.frequencies.len(), 1024);
+    let expected = FFTAnalyzer::new(2048, 2)
+        .analyze(&signal, SAMPLE_RATE)
+        .unwrap();
+    assert_identical("window size 2048", &spectrum, &expected);
+    assert!(analyzer.analyze(&frame(1024, 6), SAMPLE_RATE).is_err());
+
+    // A new window function rebuilds the coefficients
+    let mut analyzer = FFTAnalyzer::new(1024, 1);
+    analyzer.analyze(&frame(1024, 0), SAMPLE_RATE).unwrap();
+    let mut analyzer = analyzer.with_window_function(WindowFunction::Blackman);
+    let signal = frame(1024, 1);
+    let spectrum = analyzer.analyze(&signal, SAMPLE_RATE).unwrap();
+    let expected = FFTAnalyzer::new(1024, 1)
+        .with_window_function(WindowFunction::Blackman)
+        .analyze(&signal, SAMPLE_RATE)
+        .unwrap();
+    assert_identical("Blackman window", &spectrum, &expected);
+    let hann = FFTAnalyzer::new(1024, 1)
+        .analyze(&signal, SAMPLE_RATE)
+        .unwrap();
+    assert_ne!(spectrum.amplitudes, hann.amplitudes);
+}
+
+#[test]
+fn test_analyzer_moves_across_threads() {
+    let mut analyzer: Box<dyn SpectralAnalyzer> = Box::new(FFTAnalyzer::new(1024, 2));
+    analyzer.analyze(&frame(1024, 0), SAMPLE_RATE).unwrap();
+
+    let handle = std::thread::spawn(move || {
+        let spectrum = analyzer.analyze(&frame(1024, 1), SAMPLE_RATE).unwrap();
+        (analyzer, spectrum)
+    });
+    let (analyzer, spectrum) = handle.join().expect("analysis thread");
+
+    let mut expected = FFTAnalyzer::new(1024, 2);
+    expected.analyze(&frame(1024, 0), SAMPLE_RATE).unwrap();
+    let expected = expected.analyze(&frame(1024, 1), SAMPLE_RATE).unwrap();
+    assert_identical("moved analyzer", &spectrum, &expected);
+    assert_eq!(
+        analyzer.get_amplitude_at(2000.0).unwrap(),
+        expected.amplitudes[(2000.0_f32 / (SAMPLE_RATE as f32 / 1024.0)).round() as usize]
+    );
+}
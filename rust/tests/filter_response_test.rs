@@ -0,0 +1,180 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the impulse and step test signals
+//!
+//! The cascaded first-order filters of `standard_filters` have closed-form
+//! responses. With `α = ω/(ω + 1)` and `ω = 2π·fc/fs`, a lowpass stage is
+//! `y[n] = α·x[n] + (1 − α)·y[n−1]`, whose impulse response is
+//! `α·(1 − α)ⁿ`, and two stages give `α²·(n + 1)·(1 − α)ⁿ`. With the pole
+//! `p = e^(−ω)`, a highpass stage is `y[n] = p·y[n−1] + x[n] − x[n−1]`,
+//! whose step response is `pⁿ`.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_test_signals`] | The impulse and step helpers place their transition at the requested sample |
+//! | [`test_lowpass_impulse_response`] | A first-order lowpass fed with a mock source impulse matches `α·(1 − α)ⁿ` |
+//! | [`test_second_order_lowpass_impulse_response`] | Two cascaded lowpass stages match `α²·(n + 1)·(1 − α)ⁿ` |
+//! | [`test_highpass_step_response`] | A first-order highpass fed with a mock source step matches `pⁿ` |
+//! | [`test_test_signal_validation`] | Negative step times, out-of-range amplitudes and combined test signals are rejected |
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::{AudioSource, MockSource};
+use rust_photoacoustic::config::utils::validate_specific_rules;
+use rust_photoacoustic::config::{
+    Config, MultiToneConfig, PhotoacousticConfig, SimulatedSourceConfig, TestSignalConfig,
+};
+use rust_photoacoustic::preprocessing::filter::{Filter, HighpassFilter, LowpassFilter};
+use rust_photoacoustic::utility::test_signal::{impulse, step};
+use std::f64::consts::PI;
+
+const SAMPLE_RATE: u16 = 48000;
+const FRAME_SIZE: u16 = 480;
+
+/// Channel A of `frames` frames of the mock source streaming `test_signal`
+fn generate(test_signal: TestSignalConfig, frames: usize) -> Result<Vec<f32>> {
+    let mut source = MockSource::new(PhotoacousticConfig {
+        sample_rate: SAMPLE_RATE,
+        frame_size: FRAME_SIZE,
+        simulated_source: Some(SimulatedSourceConfig {
+            source_type: "mock".to_string(),
+            test_signal: Some(test_signal),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })?;
+    source.set_real_time_mode(false);
+
+    let mut channel_a = Vec::new();
+    for _ in 0..frames {
+        let (frame_a, frame_b) = source.read_frame()?;
+        assert_eq!(frame_a, frame_b);
+        channel_a.extend(frame_a);
+    }
+    Ok(channel_a)
+}
+
+fn lowpass_alpha(cutoff: f64) -> f64 {
+    let omega = 2.0 * PI * cutoff / SAMPLE_RATE as f64;
+    omega / (omega + 1.0)
+}
+
+/// Compare `response` from `start` on with `expected(n)`, and silence before `start`
+fn assert_response(response: &[f32], start: usize, expected: impl Fn(usize) -> f64) {
+    assert!(response[..start].iter().all(|sample| *sample == 0.0));
+    for (n, sample) in response[start..].iter().enumerate() {
+        let expected = expected(n);
+        assert!(
+            (*sample as f64 - expected).abs() < 1e-5,
+            "sample {} after the transition: {} instead of {}",
+            n,
+            sample,
+            expected
+        );
+    }
+}
+
+#[test]
+fn test_test_signals() {
+    let signal = impulse(8, 3);
+    assert_eq!(signal, [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0]);
+    assert_eq!(impulse(4, 10), [0.0; 4]);
+    assert_eq!(step(6, 2), [0.0, 0.0, 1.0, 1.0, 1.0, 1.0]);
+
+    let step_config = TestSignalConfig::Step {
+        time_seconds: 0.01,
+        amplitude: 0.5,
+    };
+    assert_eq!(step_config.start_sample(48000), 480);
+    assert_eq!(step_config.value_at(479, 48000), 0.0);
+    assert_eq!(step_config.value_at(1000, 48000), 0.5);
+}
+
+#[test]
+fn test_lowpass_impulse_response() -> Result<()> {
+    // The impulse falls in the middle of the second frame
+    let start = 700;
+    let signal = generate(
+        TestSignalConfig::Impulse {
+            sample: start as u64,
+            amplitude: 1.0,
+        },
+        4,
+    )?;
+    assert_eq!(signal, impulse(4 * FRAME_SIZE as usize, start));
+
+    let filter = LowpassFilter::new(1000.0).with_sample_rate(SAMPLE_RATE as u32);
+    let alpha = lowpass_alpha(1000.0);
+    assert_response(&filter.apply(&signal), start, |n| {
+        alpha * (1.0 - alpha).powi(n as i32)
+    });
+    Ok(())
+}
+
+#[test]
+fn test_second_order_lowpass_impulse_response() {
+    let start = 100;
+    let signal = impulse(2000, start);
+
+    let filter = LowpassFilter::new(500.0)
+        .with_sample_rate(SAMPLE_RATE as u32)
+        .with_order(2);
+    let alpha = lowpass_alpha(500.0);
+    assert_response(&filter.apply(&signal), start, |n| {
+        alpha * alpha * (n + 1) as f64 * (1.0 - alpha).powi(n as i32)
+    });
+}
+
+#[test]
+fn test_highpass_step_response() -> Result<()> {
+    // 0.01 s is sample 480, the first sample of the second frame
+    let signal = generate(
+        TestSignalConfig::Step {
+            time_seconds: 0.01,
+            amplitude: 1.0,
+        },
+        4,
+    )?;
+    let start = 480;
+    assert_eq!(signal, step(4 * FRAME_SIZE as usize, start));
+
+    let filter = HighpassFilter::new(200.0).with_sample_rate(SAMPLE_RATE as u32);
+    let pole = (-2.0 * PI * 200.0 / SAMPLE_RATE as f64).exp();
+    assert_response(&filter.apply(&signal), start, |n| pole.powi(n as i32));
+    Ok(())
+}
+
+#[test]
+fn test_test_signal_validation() {
+    let validate = |simulated_source: SimulatedSourceConfig| {
+        let mut config = Config::default();
+        config.photoacoustic.simulated_source = Some(simulated_source);
+        validate_specific_rules(&config)
+    };
+    let with_test_signal = |test_signal| SimulatedSourceConfig {
+        source_type: "mock".to_string(),
+        test_signal: Some(test_signal),
+        ..Default::default()
+    };
+
+    let step_signal = TestSignalConfig::Step {
+        time_seconds: 0.5,
+        amplitude: 1.0,
+    };
+    assert!(validate(with_test_signal(step_signal)).is_ok());
+    assert!(validate(with_test_signal(TestSignalConfig::Step {
+        time_seconds: -0.1,
+        amplitude: 1.0,
+    }))
+    .is_err());
+    assert!(validate(with_test_signal(TestSignalConfig::Impulse {
+        sample: 0,
+        amplitude: 1.5,
+    }))
+    .is_err());
+
+    let mut with_tones = with_test_signal(step_signal);
+    with_tones.tones = Some(MultiToneConfig::default());
+    assert!(validate(with_tones).is_err());
+}
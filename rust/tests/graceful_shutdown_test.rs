@@ -0,0 +1,202 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the ordered graceful shutdown
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_drain_processes_queued_frames_and_flushes_nodes`] | Queued frames are processed, the recording is finalized and the driver's `shutdown` is called |
+//! | [`test_graph_shutdown_flushes_nodes`] | `ProcessingGraph::shutdown` finalizes record nodes and shuts action drivers down |
+//! | [`test_daemon_graceful_shutdown_completes_within_deadline`] | An idle daemon stops well before its long-period tasks would wake up |
+
+use anyhow::Result;
+use async_trait::async_trait;
+use rust_photoacoustic::acquisition::{AudioFrame, SharedAudioStream};
+use rust_photoacoustic::config::Config;
+use rust_photoacoustic::daemon::launch_daemon::Daemon;
+use rust_photoacoustic::processing::{
+    ActionDriver, AlertData, InputNode, MeasurementData, ProcessingConsumer, ProcessingData,
+    ProcessingGraph, RecordNode, UniversalActionNode,
+};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::tempdir;
+use tokio::sync::RwLock;
+
+const FRAME_SAMPLES: usize = 480;
+
+/// Action driver recording whether its `shutdown` was called
+#[derive(Debug)]
+struct ShutdownTrackingDriver {
+    shut_down: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl ActionDriver for ShutdownTrackingDriver {
+    async fn initialize(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn update_action(&mut self, _data: &MeasurementData) -> Result<()> {
+        Ok(())
+    }
+
+    async fn show_alert(&mut self, _alert: &AlertData) -> Result<()> {
+        Ok(())
+    }
+
+    async fn clear_action(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_status(&self) -> Result<Value> {
+        Ok(json!({ "driver_type": self.driver_type() }))
+    }
+
+    fn driver_type(&self) -> &str {
+        "shutdown_tracking"
+    }
+
+    async fn shutdown(&mut self) -> Result<()> {
+        self.shut_down.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// Graph recording its input to `record_path` and feeding an action node
+fn recording_graph(record_path: &Path, shut_down: Arc<AtomicBool>) -> ProcessingGraph {
+    let mut graph = ProcessingGraph::new();
+    graph
+        .add_node(Box::new(InputNode::new("input".to_string())))
+        .unwrap();
+    graph
+        .add_node(Box::new(RecordNode::new(
+            "record".to_string(),
+            record_path.to_path_buf(),
+            1024,
+            false,
+            None,
+        )))
+        .unwrap();
+    graph
+        .add_node(Box::new(
+            UniversalActionNode::new("action".to_string())
+                .with_history_buffer_capacity(10)
+                .with_driver(Box::new(ShutdownTrackingDriver { shut_down })),
+        ))
+        .unwrap();
+    graph.connect("input", "record").unwrap();
+    graph.connect("record", "action").unwrap();
+    graph.set_output_node("action").unwrap();
+    graph
+}
+
+fn test_frame(frame_number: u64) -> AudioFrame {
+    AudioFrame::new(
+        vec![0.1; FRAME_SAMPLES],
+        vec![-0.1; FRAME_SAMPLES],
+        48000,
+        frame_number,
+    )
+}
+
+#[tokio::test]
+async fn test_drain_processes_queued_frames_and_flushes_nodes() -> Result<()> {
+    let dir = tempdir()?;
+    let record_path = dir.path().join("recording.wav");
+    let shut_down = Arc::new(AtomicBool::new(false));
+
+    let stream = Arc::new(SharedAudioStream::new(64));
+    let mut consumer = ProcessingConsumer::new(
+        Arc::clone(&stream),
+        recording_graph(&record_path, Arc::clone(&shut_down)),
+    );
+    let handle = consumer.handle();
+    let consumer_task = tokio::spawn(async move { consumer.start().await });
+
+    // Wait for the consumer to subscribe to the stream
+    for _ in 0..100 {
+        if stream.subscriber_count() > 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(stream.subscriber_count(), 1, "consumer did not subscribe");
+
+    // Hold the graph so that the published frames stay queued in the stream
+    let graph = handle.processing_graph();
+    let frame_count = 20;
+    {
+        let _paused = graph.write().await;
+        for frame_number in 1..=frame_count {
+            stream.publish(test_frame(frame_number)).await?;
+        }
+        assert!(!shut_down.load(Ordering::SeqCst));
+    }
+
+    tokio::time::timeout(Duration::from_secs(10), handle.drain_and_shutdown())
+        .await
+        .expect("drain did not complete");
+    assert!(!handle.is_running());
+    consumer_task.await??;
+
+    // Every queued frame reached the recording, and the file was finalized
+    // while the graph is still alive
+    let reader = hound::WavReader::open(&record_path)?;
+    assert_eq!(reader.spec().channels, 2);
+    assert_eq!(reader.duration(), frame_count as u32 * FRAME_SAMPLES as u32);
+    assert!(shut_down.load(Ordering::SeqCst));
+    drop(graph);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_graph_shutdown_flushes_nodes() -> Result<()> {
+    let dir = tempdir()?;
+    let record_path = dir.path().join("recording.wav");
+    let shut_down = Arc::new(AtomicBool::new(false));
+
+    let mut graph = recording_graph(&record_path, Arc::clone(&shut_down));
+    graph.execute(ProcessingData::AudioFrame(test_frame(1)))?;
+
+    tokio::task::spawn_blocking(move || {
+        graph.shutdown();
+        // The graph is still alive: the file was not finalized by `Drop`
+        let reader = hound::WavReader::open(&record_path).expect("finalized recording");
+        assert_eq!(reader.duration(), FRAME_SAMPLES as u32);
+        drop(graph);
+    })
+    .await?;
+
+    assert!(shut_down.load(Ordering::SeqCst));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_daemon_graceful_shutdown_completes_within_deadline() -> Result<()> {
+    let mut config = Config::default();
+    config.visualization.enabled = false;
+    config.acquisition.enabled = false;
+    config.processing.enabled = false;
+    config.modbus.enabled = false;
+    config.thermal_regulation.enabled = false;
+    config.photoacoustic.record_consumer = false;
+    config.daemon.shutdown_timeout_ms = 30_000;
+
+    let mut daemon = Daemon::new();
+    daemon.launch(Arc::new(RwLock::new(config))).await?;
+
+    // The heartbeat sleeps for a minute between beats; it must not hold the shutdown
+    let started = std::time::Instant::now();
+    daemon.graceful_shutdown().await?;
+    assert!(
+        started.elapsed() < Duration::from_secs(5),
+        "graceful shutdown took {:?}",
+        started.elapsed()
+    );
+    Ok(())
+}
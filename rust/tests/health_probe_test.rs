@@ -0,0 +1,284 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the `/healthz` and `/readyz` orchestration probes
+//!
+//! Each test builds the server with an audio stream and a live processing graph
+//! whose subsystems are put in a healthy or failing state:
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_liveness_probe`] | `/healthz` answers 200 without authentication |
+//! | [`test_ready_when_all_subsystems_healthy`] | `/readyz` answers 200 when frames flow, the graph is valid and drivers are connected |
+//! | [`test_not_ready_without_audio_frames`] | Missing or stale audio frames make `/readyz` answer 503 |
+//! | [`test_not_ready_with_invalid_graph`] | A graph failing validation makes `/readyz` answer 503 |
+//! | [`test_not_ready_with_disconnected_driver`] | A driver that failed to initialize makes `/readyz` answer 503 |
+//! | [`test_not_ready_when_driver_loses_connection`] | A driver reporting itself not ready after its initialization makes `/readyz` answer 503 |
+//! | [`test_disabled_subsystems_do_not_block_readiness`] | Subsystems disabled in the configuration are skipped |
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use rocket::http::Status;
+use rocket::local::asynchronous::Client;
+use rust_photoacoustic::acquisition::{AudioFrame, SharedAudioStream};
+use rust_photoacoustic::config::Config;
+use rust_photoacoustic::processing::{
+    ActionDriver, AlertData, InputNode, MeasurementData, ProcessingGraph, UniversalActionNode,
+};
+use rust_photoacoustic::visualization::shared_state::SharedVisualizationState;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+mod common;
+use common::test_figment;
+
+/// Action driver whose initialization and connection succeed or fail on demand
+#[derive(Debug)]
+struct TestDriver {
+    fail_initialization: bool,
+    /// Reported by [`ActionDriver::is_ready`], shared with the test
+    connected: Arc<AtomicBool>,
+}
+
+impl TestDriver {
+    fn new(fail_initialization: bool) -> Self {
+        Self {
+            fail_initialization,
+            connected: Arc::new(AtomicBool::new(true)),
+        }
+    }
+}
+
+#[async_trait]
+impl ActionDriver for TestDriver {
+    async fn initialize(&mut self) -> Result<()> {
+        if self.fail_initialization {
+            Err(anyhow!("connection refused"))
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn update_action(&mut self, _data: &MeasurementData) -> Result<()> {
+        Ok(())
+    }
+
+    async fn show_alert(&mut self, _alert: &AlertData) -> Result<()> {
+        Ok(())
+    }
+
+    async fn clear_action(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_status(&self) -> Result<Value> {
+        Ok(json!({ "driver_type": self.driver_type() }))
+    }
+
+    async fn is_ready(&self) -> bool {
+        self.connected.load(Ordering::SeqCst)
+    }
+
+    fn driver_type(&self) -> &str {
+        "test"
+    }
+}
+
+/// Valid graph made of an input node and an action node using `driver`
+///
+/// The readiness of the driver is asked through its thread after the
+/// initialization, so the graph can be used right away.
+fn graph_with_driver(driver: Option<TestDriver>) -> ProcessingGraph {
+    let mut graph = ProcessingGraph::new();
+    graph
+        .add_node(Box::new(InputNode::new("input".to_string())))
+        .unwrap();
+
+    if let Some(driver) = driver {
+        let node = UniversalActionNode::new("action".to_string())
+            .with_history_buffer_capacity(10)
+            .with_driver(Box::new(driver));
+        graph.add_node(Box::new(node)).unwrap();
+        graph.connect("input", "action").unwrap();
+    }
+    graph
+}
+
+/// Build a client with an audio stream and a live processing graph
+async fn build_test_client(
+    config: Config,
+    stream: Arc<SharedAudioStream>,
+    graph: ProcessingGraph,
+) -> Client {
+    let visualization_state = SharedVisualizationState::new();
+    visualization_state
+        .set_live_processing_graph(Arc::new(RwLock::new(graph)))
+        .await;
+
+    let rocket = rust_photoacoustic::visualization::server::build_rocket(
+        test_figment(),
+        Arc::new(RwLock::new(config)),
+        Some(stream),
+        Some(Arc::new(visualization_state)),
+        None,
+        None,
+        None,
+    )
+    .await;
+    Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance")
+}
+
+/// Audio stream with one frame captured just now
+async fn streaming_audio() -> Arc<SharedAudioStream> {
+    let stream = Arc::new(SharedAudioStream::new(16));
+    stream
+        .publish(AudioFrame::new(vec![0.1; 4], vec![0.2; 4], 48000, 1))
+        .await
+        .unwrap();
+    stream
+}
+
+/// `GET /readyz` returning the status and JSON body
+async fn readyz(client: &Client) -> (Status, Value) {
+    let response = client.get("/readyz").dispatch().await;
+    let status = response.status();
+    let body = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    (status, body)
+}
+
+#[rocket::async_test]
+async fn test_liveness_probe() {
+    let client = build_test_client(
+        Config::default(),
+        Arc::new(SharedAudioStream::new(16)),
+        ProcessingGraph::new(),
+    )
+    .await;
+
+    let response = client.get("/healthz").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    let body: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    assert_eq!(body["status"], "ok");
+}
+
+#[rocket::async_test]
+async fn test_ready_when_all_subsystems_healthy() {
+    let graph = graph_with_driver(Some(TestDriver::new(false)));
+    let client = build_test_client(Config::default(), streaming_audio().await, graph).await;
+
+    let (status, body) = readyz(&client).await;
+    assert_eq!(status, Status::Ok, "unexpected report: {}", body);
+    assert_eq!(body["status"], "ok");
+    assert_eq!(body["subsystems"]["acquisition"]["status"], "ok");
+    assert_eq!(body["subsystems"]["processing"]["status"], "ok");
+    assert_eq!(body["subsystems"]["action_drivers"]["status"], "ok");
+}
+
+#[rocket::async_test]
+async fn test_not_ready_without_audio_frames() {
+    let client = build_test_client(
+        Config::default(),
+        Arc::new(SharedAudioStream::new(16)),
+        graph_with_driver(None),
+    )
+    .await;
+
+    let (status, body) = readyz(&client).await;
+    assert_eq!(status, Status::ServiceUnavailable);
+    assert_eq!(body["status"], "unavailable");
+    assert_eq!(body["subsystems"]["acquisition"]["status"], "unavailable");
+    assert_eq!(body["subsystems"]["processing"]["status"], "ok");
+
+    // A frame captured long ago does not count as streaming
+    let stream = Arc::new(SharedAudioStream::new(16));
+    let mut frame = AudioFrame::new(vec![0.1; 4], vec![0.2; 4], 48000, 1);
+    frame.timestamp -= 60_000;
+    stream.publish(frame).await.unwrap();
+    let client = build_test_client(Config::default(), stream, graph_with_driver(None)).await;
+
+    let (status, body) = readyz(&client).await;
+    assert_eq!(status, Status::ServiceUnavailable);
+    assert_eq!(body["subsystems"]["acquisition"]["status"], "unavailable");
+}
+
+#[rocket::async_test]
+async fn test_not_ready_with_invalid_graph() {
+    // A graph without input node fails validation
+    let client = build_test_client(
+        Config::default(),
+        streaming_audio().await,
+        ProcessingGraph::new(),
+    )
+    .await;
+
+    let (status, body) = readyz(&client).await;
+    assert_eq!(status, Status::ServiceUnavailable);
+    assert_eq!(body["subsystems"]["acquisition"]["status"], "ok");
+    assert_eq!(body["subsystems"]["processing"]["status"], "unavailable");
+}
+
+#[rocket::async_test]
+async fn test_not_ready_with_disconnected_driver() {
+    let graph = graph_with_driver(Some(TestDriver::new(true)));
+    let client = build_test_client(Config::default(), streaming_audio().await, graph).await;
+
+    let (status, body) = readyz(&client).await;
+    assert_eq!(status, Status::ServiceUnavailable);
+    assert_eq!(body["subsystems"]["processing"]["status"], "ok");
+    assert_eq!(
+        body["subsystems"]["action_drivers"]["status"],
+        "unavailable"
+    );
+    assert!(body["subsystems"]["action_drivers"]["detail"]
+        .as_str()
+        .unwrap()
+        .contains("action"));
+}
+
+#[rocket::async_test]
+async fn test_not_ready_when_driver_loses_connection() {
+    let driver = TestDriver::new(false);
+    let connected = driver.connected.clone();
+    let graph = graph_with_driver(Some(driver));
+    let client = build_test_client(Config::default(), streaming_audio().await, graph).await;
+
+    let (status, _) = readyz(&client).await;
+    assert_eq!(status, Status::Ok);
+
+    connected.store(false, Ordering::SeqCst);
+    let (status, body) = readyz(&client).await;
+    assert_eq!(status, Status::ServiceUnavailable);
+    assert_eq!(
+        body["subsystems"]["action_drivers"]["status"],
+        "unavailable"
+    );
+
+    connected.store(true, Ordering::SeqCst);
+    let (status, _) = readyz(&client).await;
+    assert_eq!(status, Status::Ok);
+}
+
+#[rocket::async_test]
+async fn test_disabled_subsystems_do_not_block_readiness() {
+    let mut config = Config::default();
+    config.acquisition.enabled = false;
+    config.processing.enabled = false;
+    let client = build_test_client(
+        config,
+        Arc::new(SharedAudioStream::new(16)),
+        ProcessingGraph::new(),
+    )
+    .await;
+
+    let (status, body) = readyz(&client).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body["subsystems"]["acquisition"]["status"], "disabled");
+    assert_eq!(body["subsystems"]["processing"]["status"], "disabled");
+    // No task supervisor is managed by this server
+    assert_eq!(body["subsystems"]["tasks"]["status"], "disabled");
+}
@@ -0,0 +1,196 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the HMAC signing of the HTTPS callback driver
+//!
+//! The endpoint is a mock HTTP server; the signatures of the requests it
+//! received are recomputed here with the shared secret.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_signature_matches_hmac_of_body`] | Each attempt carries a timestamp and the HMAC-SHA256 of `<timestamp>.<body>`, retries included |
+//! | [`test_signature_headers_from_config`] | `hmac_secret` and the header names are read from the driver config, the secret is not reported in the status |
+//! | [`test_invalid_signing_rejected_at_initialize`] | Empty secrets and invalid or identical header names fail `initialize` |
+
+use anyhow::Result;
+use hmac::{Hmac, KeyInit, Mac};
+use rust_photoacoustic::processing::computing_nodes::action_drivers::{
+    create_action_driver, ActionDriver, AlertData, HttpsCallbackActionDriver, MeasurementData,
+};
+use serde_json::json;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+const SECRET: &str = "callback-secret";
+
+/// Expected signature header value, computed independently of the driver
+fn expected_signature(timestamp: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+    mac.update(format!("{}.", timestamp).as_bytes());
+    mac.update(body);
+    let digest: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("sha256={}", digest)
+}
+
+/// Check the signature and the timestamp of a received request
+fn assert_signed(request: &Request, signature_header: &str, timestamp_header: &str) {
+    let header = |name: &str| {
+        request
+            .headers
+            .get(name)
+            .unwrap_or_else(|| panic!("missing {} header", name))
+            .to_str()
+            .unwrap()
+            .to_string()
+    };
+    let timestamp = header(timestamp_header);
+    assert_eq!(
+        header(signature_header),
+        expected_signature(&timestamp, &request.body)
+    );
+
+    let timestamp: u64 = timestamp.parse().unwrap();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    assert!(
+        now.abs_diff(timestamp) <= 60,
+        "stale timestamp {}",
+        timestamp
+    );
+}
+
+fn measurement() -> MeasurementData {
+    MeasurementData {
+        concentration_ppm: 12.5,
+        source_node_id: "concentration".to_string(),
+        peak_amplitude: 0.25,
+        peak_frequency: 2000.5,
+        timestamp: SystemTime::now(),
+        metadata: HashMap::new(),
+    }
+}
+
+#[tokio::test]
+async fn test_signature_matches_hmac_of_body() -> Result<()> {
+    // The first attempt fails, so the retry is signed too
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/callback"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/callback"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let mut driver = HttpsCallbackActionDriver::new(format!("{}/callback", server.uri()))
+        .with_retry_count(1)
+        .with_hmac_secret(SECRET);
+    driver.update_action(&measurement()).await?;
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2);
+    for request in &requests {
+        assert_signed(request, "X-Signature", "X-Signature-Timestamp");
+        assert_eq!(
+            request.headers.get("content-type").unwrap(),
+            "application/json"
+        );
+    }
+
+    // The signed bodies are the payloads, the retry being annotated
+    let first: serde_json::Value = serde_json::from_slice(&requests[0].body)?;
+    let retry: serde_json::Value = serde_json::from_slice(&requests[1].body)?;
+    assert_eq!(first["concentration_ppm"], 12.5);
+    assert_eq!(retry["retry_attempt"], 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_signature_headers_from_config() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let config = json!({
+        "callback_url": server.uri(),
+        "hmac_secret": SECRET,
+        "signature_header": "X-Hub-Signature-256",
+        "signature_timestamp_header": "X-Hub-Timestamp"
+    });
+    let mut driver = create_action_driver("https_callback", config.as_object().unwrap())?;
+    driver.initialize().await?;
+    driver
+        .show_alert(&AlertData {
+            alert_type: "concentration_threshold".to_string(),
+            severity: "warning".to_string(),
+            message: "Concentration above threshold".to_string(),
+            data: HashMap::new(),
+            timestamp: SystemTime::now(),
+        })
+        .await?;
+
+    let requests = server.received_requests().await.unwrap();
+    let alert = requests
+        .iter()
+        .find(|request| request.method.as_str() == "POST")
+        .expect("the alert was not posted");
+    assert_signed(alert, "X-Hub-Signature-256", "X-Hub-Timestamp");
+    assert!(alert.headers.get("X-Signature").is_none());
+
+    let status = driver.get_status().await?;
+    assert_eq!(
+        status["hmac_signing"],
+        json!({
+            "signature_header": "X-Hub-Signature-256",
+            "timestamp_header": "X-Hub-Timestamp"
+        })
+    );
+    assert!(!status.to_string().contains(SECRET));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_signing_rejected_at_initialize() -> Result<()> {
+    // The URL is never contacted: the signing is checked first
+    let driver = || HttpsCallbackActionDriver::new("http://127.0.0.1:9/callback");
+    for (mut driver, expected) in [
+        (driver().with_hmac_secret(""), "must not be empty"),
+        (
+            driver().with_signature_headers("X-Signature", "X-Signature-Timestamp"),
+            "must not be empty",
+        ),
+        (
+            driver()
+                .with_hmac_secret(SECRET)
+                .with_signature_headers("X Signature", "X-Signature-Timestamp"),
+            "Invalid signature header",
+        ),
+        (
+            driver()
+                .with_hmac_secret(SECRET)
+                .with_signature_headers("X-Signature", "x-signature"),
+            "must differ",
+        ),
+    ] {
+        let error = driver.initialize().await.unwrap_err();
+        assert!(error.to_string().contains(expected), "{}", error);
+    }
+    Ok(())
+}
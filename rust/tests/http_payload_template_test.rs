@@ -0,0 +1,137 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the payload templates of the HTTPS callback driver
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_template_renders_custom_shape`] | Placeholders are replaced by the measurement fields, keeping their JSON type when they are the whole string |
+//! | [`test_driver_posts_templated_payload`] | A driver configured with `payload_template` posts the rendered payload to the callback URL |
+//! | [`test_invalid_templates_rejected_at_initialize`] | Unknown or unclosed placeholders and non-object templates fail `initialize` |
+
+use anyhow::Result;
+use rust_photoacoustic::processing::computing_nodes::action_drivers::{
+    create_action_driver, ActionDriver, HttpsCallbackActionDriver, MeasurementData, PayloadTemplate,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::{Duration, UNIX_EPOCH};
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn measurement() -> MeasurementData {
+    let mut metadata = HashMap::new();
+    metadata.insert("unit".to_string(), json!("ppm"));
+    metadata.insert("calibration".to_string(), json!({"version": 3}));
+    MeasurementData {
+        concentration_ppm: 12.5,
+        source_node_id: "concentration".to_string(),
+        peak_amplitude: 0.25,
+        peak_frequency: 2000.5,
+        timestamp: UNIX_EPOCH + Duration::from_millis(1_700_000_000_123),
+        metadata,
+    }
+}
+
+/// Template of a hypothetical telemetry API
+fn telemetry_template() -> serde_json::Value {
+    json!({
+        "device": "{{source_node_id}}",
+        "readings": [
+            {"name": "co2", "value": "{{concentration_ppm}}", "unit": "{{metadata.unit}}"},
+            {"name": "peak", "value": "{{ peak_amplitude }}", "frequency": "{{peak_frequency}}"}
+        ],
+        "time": "{{timestamp_rfc3339}}",
+        "epoch_ms": "{{timestamp_ms}}",
+        "summary": "{{concentration_ppm}} {{metadata.unit}} at {{timestamp}}",
+        "calibration": "{{metadata.calibration}}",
+        "site": "{{metadata.site}}",
+        "static": true
+    })
+}
+
+#[test]
+fn test_template_renders_custom_shape() -> Result<()> {
+    let template = PayloadTemplate::new(telemetry_template());
+    template.validate()?;
+
+    assert_eq!(
+        template.render(&measurement())?,
+        json!({
+            "device": "concentration",
+            "readings": [
+                {"name": "co2", "value": 12.5, "unit": "ppm"},
+                {"name": "peak", "value": 0.25, "frequency": 2000.5}
+            ],
+            "time": "2023-11-14T22:13:20.123Z",
+            "epoch_ms": 1_700_000_000_123u64,
+            "summary": "12.5 ppm at 1700000000",
+            "calibration": {"version": 3},
+            "site": null,
+            "static": true
+        })
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_driver_posts_templated_payload() -> Result<()> {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/ingest"))
+        .and(body_json(json!({
+            "sensor": "concentration",
+            "ppm": 12.5,
+            "label": "concentration: 12.5 ppm"
+        })))
+        .respond_with(ResponseTemplate::new(202))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let config = json!({
+        "callback_url": format!("{}/ingest", server.uri()),
+        "retry_count": 0,
+        "payload_template": {
+            "sensor": "{{source_node_id}}",
+            "ppm": "{{concentration_ppm}}",
+            "label": "{{source_node_id}}: {{concentration_ppm}} {{metadata.unit}}"
+        }
+    });
+    let mut driver = create_action_driver("https_callback", config.as_object().unwrap())?;
+    driver.initialize().await?;
+    driver.update_action(&measurement()).await?;
+
+    let status = driver.get_status().await?;
+    assert_eq!(
+        status["payload_template"]["ppm"],
+        json!("{{concentration_ppm}}")
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_templates_rejected_at_initialize() -> Result<()> {
+    for (template, expected) in [
+        (json!({"value": "{{concentration}}"}), "Unknown placeholder"),
+        (json!({"values": ["{{metadata.}}"]}), "$.values[0]"),
+        (
+            json!({"value": "{{concentration_ppm"}),
+            "Unclosed placeholder",
+        ),
+        (json!(["{{concentration_ppm}}"]), "must be a JSON object"),
+    ] {
+        // The URL is never contacted: the template is checked first
+        let mut driver = HttpsCallbackActionDriver::new("http://127.0.0.1:9/ingest")
+            .with_payload_template(template.clone());
+        let error = driver.initialize().await.unwrap_err();
+        assert!(
+            error.to_string().contains(expected),
+            "{}: {}",
+            template,
+            error
+        );
+    }
+    Ok(())
+}
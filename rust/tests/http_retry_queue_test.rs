@@ -0,0 +1,181 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the durable retry queue of the HTTPS callback driver
+//!
+//! The endpoint is a mock HTTP server, and a restart of the daemon is
+//! simulated by building a fresh driver over the same queue file.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_pending_callbacks_delivered_after_restart`] | Callbacks queued during an outage are delivered in order by a fresh driver, despite a record cut short by a crash |
+//! | [`test_queue_log_is_compacted`] | The log of delivered callbacks is compacted instead of growing without bound |
+//! | [`test_refused_callback_does_not_block_queue`] | A callback refused with a client error is discarded and the next ones are delivered |
+
+use anyhow::Result;
+use rust_photoacoustic::processing::computing_nodes::action_drivers::{
+    create_action_driver, ActionDriver, HttpsCallbackActionDriver, MeasurementData,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::time::SystemTime;
+use wiremock::matchers::{body_partial_json, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn measurement(concentration_ppm: f64) -> MeasurementData {
+    MeasurementData {
+        concentration_ppm,
+        source_node_id: "concentration".to_string(),
+        peak_amplitude: 0.25,
+        peak_frequency: 2000.0,
+        timestamp: SystemTime::now(),
+        metadata: HashMap::new(),
+    }
+}
+
+/// Concentrations of the measurements posted to the endpoint, in order
+async fn posted_concentrations(server: &MockServer) -> Vec<f64> {
+    server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .filter(|request| request.method.as_str() == "POST")
+        .map(|request| {
+            let payload: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            payload["concentration_ppm"].as_f64().unwrap()
+        })
+        .collect()
+}
+
+/// Driver configured with a retry queue, as in the configuration file
+fn driver_from_config(server: &MockServer, queue_path: &Path) -> Result<Box<dyn ActionDriver>> {
+    let config = json!({
+        "callback_url": server.uri(),
+        "retry_count": 0,
+        "retry_queue_path": queue_path,
+        "retry_queue_capacity": 100
+    });
+    create_action_driver("https_callback", config.as_object().unwrap())
+}
+
+#[tokio::test]
+async fn test_pending_callbacks_delivered_after_restart() -> Result<()> {
+    let directory = tempfile::tempdir()?;
+    let queue_path = directory.path().join("queue").join("callbacks.jsonl");
+
+    // The endpoint is down: the callbacks are accepted but stay queued
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&server)
+        .await;
+    let mut driver = driver_from_config(&server, &queue_path)?;
+    driver.initialize().await?;
+    for concentration_ppm in [1.0, 2.0, 3.0] {
+        driver
+            .update_action(&measurement(concentration_ppm))
+            .await?;
+    }
+    let status = driver.get_status().await?;
+    assert_eq!(status["retry_queue"]["stats"]["pending"], 3);
+    assert_eq!(status["retry_queue"]["stats"]["delivered"], 0);
+
+    // The daemon crashes while writing a record
+    drop(driver);
+    OpenOptions::new()
+        .append(true)
+        .open(&queue_path)?
+        .write_all(br#"{"op":"enqueue","id":3,"annot"#)?;
+
+    // After the restart, the endpoint is back
+    server.reset().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    let mut driver = driver_from_config(&server, &queue_path)?;
+    driver.initialize().await?;
+    assert_eq!(posted_concentrations(&server).await, vec![1.0, 2.0, 3.0]);
+    let status = driver.get_status().await?;
+    assert_eq!(status["retry_queue"]["stats"]["pending"], 0);
+    assert_eq!(status["retry_queue"]["stats"]["delivered"], 3);
+
+    // New callbacks are sent right away
+    driver.update_action(&measurement(4.0)).await?;
+    assert_eq!(
+        posted_concentrations(&server).await,
+        vec![1.0, 2.0, 3.0, 4.0]
+    );
+
+    // Nothing is left for the next run
+    drop(driver);
+    server.reset().await;
+    let mut driver = driver_from_config(&server, &queue_path)?;
+    driver.initialize().await?;
+    assert!(posted_concentrations(&server).await.is_empty());
+    assert_eq!(std::fs::read_to_string(&queue_path)?, "");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_queue_log_is_compacted() -> Result<()> {
+    let directory = tempfile::tempdir()?;
+    let queue_path = directory.path().join("callbacks.jsonl");
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let mut driver = HttpsCallbackActionDriver::new(server.uri()).with_retry_queue(&queue_path, 10);
+    for index in 0..200 {
+        driver.update_action(&measurement(index as f64)).await?;
+        let records = std::fs::read_to_string(&queue_path)?.lines().count();
+        assert!(records < 130, "{} records in the log", records);
+    }
+
+    let status = driver.get_status().await?;
+    assert_eq!(status["retry_queue"]["stats"]["delivered"], 200);
+    assert!(
+        status["retry_queue"]["stats"]["compactions"]
+            .as_u64()
+            .unwrap()
+            >= 3
+    );
+    assert_eq!(posted_concentrations(&server).await.len(), 200);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_refused_callback_does_not_block_queue() -> Result<()> {
+    let directory = tempfile::tempdir()?;
+    let queue_path = directory.path().join("callbacks.jsonl");
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(json!({ "concentration_ppm": 1.0 })))
+        .respond_with(ResponseTemplate::new(422))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    let mut driver = HttpsCallbackActionDriver::new(server.uri())
+        .with_retry_count(0)
+        .with_retry_queue(&queue_path, 10);
+    driver.update_action(&measurement(1.0)).await?;
+    driver.update_action(&measurement(2.0)).await?;
+
+    assert_eq!(posted_concentrations(&server).await, vec![1.0, 2.0]);
+    let stats = driver.get_status().await?["retry_queue"]["stats"].clone();
+    assert_eq!(stats["pending"], 0);
+    assert_eq!(stats["rejected"], 1);
+    assert_eq!(stats["delivered"], 1);
+    Ok(())
+}
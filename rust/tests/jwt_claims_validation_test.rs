@@ -0,0 +1,181 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Tests for the time, issuer and audience checks of `JwtValidator`
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_token_within_clock_skew_accepted`] | `exp`, `nbf` and `iat` slightly off by less than `clock_skew_seconds` are tolerated |
+//! | [`test_token_beyond_clock_skew_rejected`] | `exp`, `nbf` and `iat` off by more than `clock_skew_seconds` are rejected |
+//! | [`test_issuer_mismatch_rejected`] | Tokens must carry the configured issuer, even without an explicit expected issuer |
+//! | [`test_audience_mismatch_rejected`] | Tokens must be intended for a configured client or the expected audience |
+//! | [`test_access_token_ttl_configures_issuer`] | `access_token_ttl` (or its former name `duration`) sets the lifetime of issued tokens |
+
+use jsonwebtoken::{EncodingKey, Header};
+use oxide_auth::primitives::grant::{Extensions, Grant};
+use oxide_auth::primitives::issuer::Issuer;
+use rust_photoacoustic::config::{AccessConfig, Config};
+use rust_photoacoustic::visualization::auth::jwt::JwtValidator;
+use rust_photoacoustic::visualization::auth::OxideState;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+mod common;
+use common::TEST_HMAC_SECRET;
+
+/// Claims of a valid token for the default administrator, valid for an hour
+fn claims() -> Value {
+    let now = chrono::Utc::now().timestamp();
+    json!({
+        "sub": "admin",
+        "iat": now,
+        "exp": now + 3600,
+        "nbf": now,
+        "jti": "test-token",
+        "aud": "LaserSmartClient",
+        "iss": "LaserSmartServer",
+        "scope": "read:api",
+    })
+}
+
+/// Claims with `offsets` (in seconds from now) applied to the given time claims
+fn claims_with(offsets: &[(&str, i64)]) -> Value {
+    let now = chrono::Utc::now().timestamp();
+    let mut claims = claims();
+    for (claim, offset) in offsets {
+        claims[*claim] = json!(now + offset);
+    }
+    claims
+}
+
+fn sign(claims: &Value) -> String {
+    jsonwebtoken::encode(
+        &Header::default(),
+        claims,
+        &EncodingKey::from_secret(TEST_HMAC_SECRET.as_bytes()),
+    )
+    .expect("Failed to sign token")
+}
+
+fn validator(access: AccessConfig) -> JwtValidator {
+    JwtValidator::new(Some(TEST_HMAC_SECRET.as_bytes()), None, access)
+        .expect("Failed to create JWT validator")
+}
+
+fn validate(access: AccessConfig, claims: &Value) -> anyhow::Result<()> {
+    validator(access).validate(&sign(claims)).map(|_| ())
+}
+
+#[test]
+fn test_token_within_clock_skew_accepted() {
+    let access = AccessConfig {
+        clock_skew_seconds: 60,
+        ..AccessConfig::default()
+    };
+    assert!(validate(access.clone(), &claims()).is_ok());
+
+    // Expired 30 seconds ago
+    assert!(validate(access.clone(), &claims_with(&[("exp", -30)])).is_ok());
+    // Valid from 30 seconds in the future
+    assert!(validate(access.clone(), &claims_with(&[("nbf", 30)])).is_ok());
+    // Issued 30 seconds in the future
+    assert!(validate(access, &claims_with(&[("iat", 30)])).is_ok());
+}
+
+#[test]
+fn test_token_beyond_clock_skew_rejected() {
+    let access = AccessConfig {
+        clock_skew_seconds: 60,
+        ..AccessConfig::default()
+    };
+    let error = validate(access.clone(), &claims_with(&[("exp", -120)])).unwrap_err();
+    assert!(error.to_string().contains("xpired"), "{}", error);
+    assert!(validate(access.clone(), &claims_with(&[("nbf", 120)])).is_err());
+    let error = validate(access, &claims_with(&[("iat", 120)])).unwrap_err();
+    assert!(error.to_string().contains("future"), "{}", error);
+
+    // Without tolerance, a token expired 30 seconds ago is rejected
+    let strict = AccessConfig {
+        clock_skew_seconds: 0,
+        ..AccessConfig::default()
+    };
+    assert!(validate(strict, &claims_with(&[("exp", -30)])).is_err());
+}
+
+#[test]
+fn test_issuer_mismatch_rejected() {
+    let mut other_issuer = claims();
+    other_issuer["iss"] = json!("OtherServer");
+    assert!(validate(AccessConfig::default(), &other_issuer).is_err());
+
+    // The configured issuer is required
+    let access = AccessConfig {
+        iss: Some("OtherServer".to_string()),
+        ..AccessConfig::default()
+    };
+    assert!(validate(access.clone(), &other_issuer).is_ok());
+    assert!(validate(access.clone(), &claims()).is_err());
+
+    // An explicit expected issuer takes precedence
+    let token = sign(&claims());
+    assert!(validator(access)
+        .with_issuer("LaserSmartServer")
+        .validate(&token)
+        .is_ok());
+
+    // A token without issuer is rejected
+    let mut no_issuer = claims();
+    no_issuer.as_object_mut().unwrap().remove("iss");
+    assert!(validate(AccessConfig::default(), &no_issuer).is_err());
+}
+
+#[test]
+fn test_audience_mismatch_rejected() {
+    let mut claims = claims();
+    claims["aud"] = json!("UnknownClient");
+    let token = sign(&claims);
+
+    // Audiences default to the configured clients
+    assert!(validator(AccessConfig::default()).validate(&token).is_err());
+    assert!(validator(AccessConfig::default())
+        .with_audience("LaserSmartClient")
+        .validate(&token)
+        .is_err());
+    assert!(validator(AccessConfig::default())
+        .with_audience("UnknownClient")
+        .validate(&token)
+        .is_ok());
+}
+
+#[tokio::test]
+async fn test_access_token_ttl_configures_issuer() {
+    let access: AccessConfig = serde_yml::from_str(
+        "users: []\nclients: []\naccess_token_ttl: 7200\nclock_skew_seconds: 5\n",
+    )
+    .unwrap();
+    assert_eq!(access.access_token_ttl, Some(7200));
+    assert_eq!(access.clock_skew_seconds, 5);
+    let legacy: AccessConfig =
+        serde_yml::from_str("users: []\nclients: []\nduration: 5400\n").unwrap();
+    assert_eq!(legacy.access_token_ttl, Some(5400));
+    assert_eq!(legacy.clock_skew_seconds, 60);
+
+    let mut config = Config::default();
+    config.visualization.hmac_secret = TEST_HMAC_SECRET.to_string();
+    config.access.access_token_ttl = Some(7200);
+    let state = OxideState::from_config(&Arc::new(RwLock::new(config))).await;
+    let grant = Grant {
+        owner_id: "admin".to_string(),
+        client_id: "LaserSmartClient".to_string(),
+        scope: "read:api".parse().unwrap(),
+        redirect_uri: "http://localhost:8080/client/".parse().unwrap(),
+        until: chrono::Utc::now() + chrono::Duration::hours(1),
+        extensions: Extensions::new(),
+    };
+    let token = state.issuer.lock().unwrap().issue(grant).unwrap().token;
+
+    let claims = validator(AccessConfig::default()).validate(&token).unwrap();
+    assert_eq!(claims.exp - claims.iat, 7200);
+}
@@ -0,0 +1,226 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the rotation of the RS256 signing key
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_rotation_keeps_previous_tokens_valid`] | Tokens name their key with `kid`, tokens signed before a rotation are still verified |
+//! | [`test_unknown_or_removed_key_rejected`] | Tokens signed with a key outside the active set are rejected |
+//! | [`test_token_without_kid_uses_current_key`] | Tokens issued before key IDs are verified with the current key |
+//! | [`test_jwks_endpoints_publish_active_keys`] | `/oauth/jwks.json` and `/.well-known/jwks.json` list the current and previous keys |
+
+use base64::Engine;
+use jsonwebtoken::{Algorithm, Validation};
+use oxide_auth::primitives::grant::{Extensions, Grant};
+use oxide_auth::primitives::issuer::Issuer;
+use rocket::http::Status;
+use rocket::local::asynchronous::Client;
+use rsa::pkcs1::{EncodeRsaPrivateKey, EncodeRsaPublicKey};
+use rust_photoacoustic::config::{AccessConfig, Config};
+use rust_photoacoustic::visualization::auth::jwt::{JwkKeySet, JwtIssuer, JwtValidator};
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// RS256 key pair in PEM format
+struct KeyPair {
+    private_pem: Vec<u8>,
+    public_pem: Vec<u8>,
+}
+
+impl KeyPair {
+    fn generate() -> Self {
+        let mut rng = rsa::rand_core::OsRng;
+        let private_key =
+            rsa::RsaPrivateKey::new(&mut rng, 2048).expect("Failed to generate RSA private key");
+        let public_key = rsa::RsaPublicKey::from(&private_key);
+        Self {
+            private_pem: private_key
+                .to_pkcs1_pem(rsa::pkcs1::LineEnding::LF)
+                .expect("Failed to encode private key")
+                .as_bytes()
+                .to_vec(),
+            public_pem: public_key
+                .to_pkcs1_pem(rsa::pkcs1::LineEnding::LF)
+                .expect("Failed to encode public key")
+                .into_bytes(),
+        }
+    }
+
+    fn kid(&self) -> String {
+        JwkKeySet::kid_from_pem(&self.public_pem).expect("Failed to compute key ID")
+    }
+
+    fn issuer(&self) -> JwtIssuer {
+        JwtIssuer::with_rs256_pem(&self.private_pem, &self.public_pem)
+            .expect("Failed to create JWT issuer")
+    }
+
+    fn validator(&self) -> JwtValidator {
+        JwtValidator::new(None, Some(&self.public_pem), AccessConfig::default())
+            .expect("Failed to create JWT validator")
+            .with_issuer("LaserSmartServer")
+            .with_audience("LaserSmartClient")
+    }
+}
+
+fn issue_token(issuer: &mut JwtIssuer) -> String {
+    let grant = Grant {
+        owner_id: "administrator".to_string(),
+        client_id: "LaserSmartClient".to_string(),
+        redirect_uri: "http://localhost:8080/client/".parse().unwrap(),
+        scope: "read:api".parse().unwrap(),
+        until: chrono::Utc::now() + chrono::Duration::hours(1),
+        extensions: Extensions::new(),
+    };
+    issuer.issue(grant).expect("Failed to issue token").token
+}
+
+/// Whether the issuer selects a key verifying the signature of `token`
+fn issuer_verifies(issuer: &JwtIssuer, token: &str) -> bool {
+    let map = issuer.0.lock().unwrap();
+    let Some(key) = map.verification_key_for(token) else {
+        return false;
+    };
+    let mut validation = Validation::new(Algorithm::RS256);
+    validation.set_audience(&["LaserSmartClient"]);
+    jsonwebtoken::decode::<Value>(token, key, &validation).is_ok()
+}
+
+fn token_kid(token: &str) -> Option<String> {
+    jsonwebtoken::decode_header(token)
+        .expect("Invalid token header")
+        .kid
+}
+
+#[test]
+fn test_rotation_keeps_previous_tokens_valid() {
+    let old_key = KeyPair::generate();
+    let new_key = KeyPair::generate();
+
+    let mut issuer = old_key.issuer();
+    assert_eq!(issuer.signing_kid(), Some(old_key.kid()));
+    let old_token = issue_token(&mut issuer);
+    assert_eq!(token_kid(&old_token), Some(old_key.kid()));
+
+    // Tokens are signed with the new key from now on
+    issuer
+        .rotate_rs256_key(&new_key.private_pem, &new_key.public_pem)
+        .expect("Failed to rotate key");
+    assert_eq!(issuer.signing_kid(), Some(new_key.kid()));
+    let new_token = issue_token(&mut issuer);
+    assert_eq!(token_kid(&new_token), Some(new_key.kid()));
+
+    // The issuer verifies both signatures
+    assert!(issuer_verifies(&issuer, &old_token));
+    assert!(issuer_verifies(&issuer, &new_token));
+
+    // A validator knowing the old key as a previous one accepts both tokens
+    let validator = new_key
+        .validator()
+        .with_rs256_public_key(&old_key.public_pem)
+        .expect("Failed to add previous key");
+    assert_eq!(validator.validate(&old_token).unwrap().sub, "administrator");
+    assert_eq!(validator.validate(&new_token).unwrap().sub, "administrator");
+
+    // After a restart, the previous key is registered again
+    let restarted = new_key.issuer();
+    assert!(!issuer_verifies(&restarted, &old_token));
+    assert_eq!(
+        restarted.add_verification_key(&old_key.public_pem).unwrap(),
+        old_key.kid()
+    );
+    assert!(issuer_verifies(&restarted, &old_token));
+    assert!(issuer_verifies(&restarted, &new_token));
+}
+
+#[test]
+fn test_unknown_or_removed_key_rejected() {
+    let old_key = KeyPair::generate();
+    let new_key = KeyPair::generate();
+
+    let mut issuer = old_key.issuer();
+    let old_token = issue_token(&mut issuer);
+    issuer
+        .rotate_rs256_key(&new_key.private_pem, &new_key.public_pem)
+        .expect("Failed to rotate key");
+
+    // A validator without the old key rejects its tokens
+    let error = new_key.validator().validate(&old_token).unwrap_err();
+    assert!(
+        error.to_string().contains("Unknown RS256 key ID"),
+        "{}",
+        error
+    );
+
+    // Retiring the old key ends the overlap
+    assert!(issuer.remove_verification_key(&old_key.kid()));
+    assert!(!issuer.remove_verification_key(&old_key.kid()));
+    assert!(!issuer_verifies(&issuer, &old_token));
+    let new_token = issue_token(&mut issuer);
+    assert!(issuer_verifies(&issuer, &new_token));
+}
+
+#[test]
+fn test_token_without_kid_uses_current_key() {
+    let key = KeyPair::generate();
+    let mut issuer = key.issuer();
+    issuer.0.lock().unwrap().signing_kid = None;
+    let legacy_token = issue_token(&mut issuer);
+    assert_eq!(token_kid(&legacy_token), None);
+
+    assert!(key.validator().validate(&legacy_token).is_ok());
+    let other = KeyPair::generate();
+    assert!(other
+        .validator()
+        .with_rs256_public_key(&key.public_pem)
+        .unwrap()
+        .validate(&legacy_token)
+        .is_err());
+}
+
+#[rocket::async_test]
+async fn test_jwks_endpoints_publish_active_keys() {
+    let old_key = KeyPair::generate();
+    let new_key = KeyPair::generate();
+    let encode = |pem: &[u8]| base64::engine::general_purpose::STANDARD.encode(pem);
+
+    let mut config = Config::default();
+    config.visualization.rs256_private_key = encode(&new_key.private_pem);
+    config.visualization.rs256_public_key = encode(&new_key.public_pem);
+    config.visualization.rs256_previous_public_keys = vec![encode(&old_key.public_pem)];
+
+    let figment = rocket::Config::figment()
+        .merge(("port", 0))
+        .merge(("address", "127.0.0.1"))
+        .merge(("hmac_secret", "test-hmac-secret-key-for-testing"));
+    let rocket = rust_photoacoustic::visualization::server::build_rocket(
+        figment,
+        Arc::new(RwLock::new(config)),
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+    let client = Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance");
+
+    for path in ["/oauth/jwks.json", "/.well-known/jwks.json"] {
+        let response = client.get(path).dispatch().await;
+        assert_eq!(response.status(), Status::Ok, "{}", path);
+        let jwks: Value =
+            serde_json::from_str(&response.into_string().await.expect("JWKS body")).unwrap();
+        let kids: Vec<&str> = jwks["keys"]
+            .as_array()
+            .expect("keys should be an array")
+            .iter()
+            .map(|key| key["kid"].as_str().expect("key should have a kid"))
+            .collect();
+        assert_eq!(kids, vec![new_key.kid(), old_key.kid()], "{}", path);
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the Avro serialization of the Kafka action driver
+//!
+//! The schema registry is served by a mock HTTP server and a mock producer
+//! records the produced messages, so no Kafka broker is needed.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_avro_records_carry_schema_id`] | Measurements are produced as magic byte + schema id + Avro datum, decoding back to the original measurement with the Avro schema |
+//! | [`test_json_remains_default`] | Without Avro settings, measurements are produced as JSON |
+//! | [`test_avro_from_config`] | `serialization: avro` requires `schema_registry_url`, unknown serializations are refused |
+
+use anyhow::Result;
+use apache_avro::types::Value as AvroValue;
+use async_trait::async_trait;
+use rdkafka::error::KafkaError;
+use rdkafka::message::OwnedMessage;
+use rust_photoacoustic::processing::computing_nodes::action_drivers::{
+    create_action_driver, decode_measurement, measurement_schema, ActionDriver, KafkaActionDriver,
+    MeasurementData, ProducerLike, AVRO_MAGIC_BYTE, MEASUREMENT_AVRO_SCHEMA,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, UNIX_EPOCH};
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const SCHEMA_ID: u32 = 42;
+
+/// Producer recording the (topic, key, payload) of the produced messages
+#[derive(Default)]
+struct RecordingProducer {
+    messages: Mutex<Vec<(String, String, Vec<u8>)>>,
+}
+
+#[async_trait]
+impl ProducerLike for RecordingProducer {
+    async fn send(
+        &self,
+        topic: &str,
+        key: &str,
+        payload: &[u8],
+        _timeout_ms: u64,
+    ) -> Result<(), (KafkaError, OwnedMessage)> {
+        self.messages
+            .lock()
+            .unwrap()
+            .push((topic.to_string(), key.to_string(), payload.to_vec()));
+        Ok(())
+    }
+}
+
+fn measurement(concentration_ppm: f64) -> MeasurementData {
+    let mut metadata = HashMap::new();
+    metadata.insert("unit".to_string(), json!("ppm"));
+    metadata.insert("calibration".to_string(), json!({"version": 3}));
+    MeasurementData {
+        concentration_ppm,
+        source_node_id: "concentration".to_string(),
+        peak_amplitude: 0.25,
+        peak_frequency: 2000.5,
+        timestamp: UNIX_EPOCH + Duration::from_millis(1_700_000_000_123),
+        metadata,
+    }
+}
+
+#[tokio::test]
+async fn test_avro_records_carry_schema_id() -> Result<()> {
+    // The schema is registered once, under the subject of the display topic
+    let registry = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/subjects/displays-value/versions"))
+        .and(body_json(json!({ "schema": MEASUREMENT_AVRO_SCHEMA })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": SCHEMA_ID })))
+        .expect(1)
+        .mount(&registry)
+        .await;
+
+    let producer = Arc::new(RecordingProducer::default());
+    let mut driver = KafkaActionDriver::new("localhost:9092", "displays", "alerts")
+        .with_avro(registry.uri(), None);
+    driver.set_producer_for_test(producer.clone());
+
+    driver.update_action(&measurement(12.5)).await?;
+    driver.update_action(&measurement(13.5)).await?;
+    driver.clear_action().await?;
+
+    let messages = producer.messages.lock().unwrap().clone();
+    assert_eq!(messages.len(), 2);
+    let (topic, key, payload) = &messages[0];
+    assert_eq!(topic, "displays");
+    assert_eq!(key, "concentration");
+    assert_eq!(payload[0], AVRO_MAGIC_BYTE);
+    assert_eq!(payload[1..5], SCHEMA_ID.to_be_bytes());
+
+    // The datum decodes with the Avro schema alone
+    let schema = measurement_schema()?;
+    let AvroValue::Record(fields) =
+        apache_avro::from_avro_datum(&schema, &mut &payload[5..], None)?
+    else {
+        panic!("the measurement is not an Avro record");
+    };
+    let fields: HashMap<String, AvroValue> = fields.into_iter().collect();
+    assert_eq!(fields["concentration_ppm"], AvroValue::Double(12.5));
+    assert_eq!(
+        fields["source_node_id"],
+        AvroValue::String("concentration".to_string())
+    );
+    assert_eq!(
+        fields["timestamp"],
+        AvroValue::TimestampMillis(1_700_000_000_123)
+    );
+
+    // And back to the original measurement
+    let (schema_id, decoded) = decode_measurement(&schema, payload)?;
+    assert_eq!(schema_id, SCHEMA_ID);
+    let original = measurement(12.5);
+    assert_eq!(decoded.concentration_ppm, original.concentration_ppm);
+    assert_eq!(decoded.source_node_id, original.source_node_id);
+    assert_eq!(decoded.peak_amplitude, original.peak_amplitude);
+    assert_eq!(decoded.peak_frequency, original.peak_frequency);
+    assert_eq!(decoded.timestamp, original.timestamp);
+    assert_eq!(decoded.metadata, original.metadata);
+    assert_eq!(
+        decode_measurement(&schema, &messages[1].2)?
+            .1
+            .concentration_ppm,
+        13.5
+    );
+
+    let status = driver.get_status().await?;
+    assert_eq!(status["serialization"], "avro");
+    assert_eq!(status["avro"]["subject"], "displays-value");
+    assert_eq!(status["avro"]["schema_id"], SCHEMA_ID);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_json_remains_default() -> Result<()> {
+    let producer = Arc::new(RecordingProducer::default());
+    let mut driver = KafkaActionDriver::new("localhost:9092", "displays", "alerts");
+    driver.set_producer_for_test(producer.clone());
+
+    driver.update_action(&measurement(12.5)).await?;
+
+    let messages = producer.messages.lock().unwrap().clone();
+    let payload: serde_json::Value = serde_json::from_slice(&messages[0].2)?;
+    assert_eq!(payload["type"], "display_update");
+    assert_eq!(payload["concentration_ppm"], 12.5);
+    assert_eq!(driver.get_status().await?["serialization"], "json");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_avro_from_config() -> Result<()> {
+    let config = |extra: serde_json::Value| {
+        let mut config = json!({
+            "bootstrap_servers": "localhost:9092",
+            "topic": "displays"
+        });
+        config
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        config
+    };
+
+    let driver = create_action_driver(
+        "kafka",
+        config(json!({
+            "serialization": "avro",
+            "schema_registry_url": "http://registry:8081/",
+            "schema_subject": "photoacoustic-measurement"
+        }))
+        .as_object()
+        .unwrap(),
+    )?;
+    let status = driver.get_status().await?;
+    assert_eq!(status["avro"]["registry_url"], "http://registry:8081");
+    assert_eq!(status["avro"]["subject"], "photoacoustic-measurement");
+    assert_eq!(status["avro"]["schema_id"], json!(null));
+
+    let error = create_action_driver(
+        "kafka",
+        config(json!({ "serialization": "avro" }))
+            .as_object()
+            .unwrap(),
+    )
+    .unwrap_err();
+    assert!(
+        error.to_string().contains("schema_registry_url"),
+        "{}",
+        error
+    );
+    assert!(create_action_driver(
+        "kafka",
+        config(json!({ "serialization": "protobuf" }))
+            .as_object()
+            .unwrap()
+    )
+    .is_err());
+    Ok(())
+}
@@ -0,0 +1,175 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the command consumer of the Kafka action driver
+//!
+//! A mock consumer delivers the command messages and records the committed
+//! offsets, so no Kafka broker is needed.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_update_config_command_changes_threshold`] | An `update_config` command changes the threshold of the action node, and its offset is committed |
+//! | [`test_acknowledge_and_invalid_commands`] | Acknowledgements are recorded, invalid messages are rejected and committed |
+
+use anyhow::Result;
+use async_trait::async_trait;
+use rust_photoacoustic::processing::computing_nodes::action_drivers::{
+    ConsumerLike, KafkaActionDriver, KafkaCommandMessage,
+};
+use rust_photoacoustic::processing::computing_nodes::{
+    ActionNode, ComputingSharedData, ConcentrationResult,
+};
+use rust_photoacoustic::processing::{ActionDriver, UniversalActionNode};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc;
+
+/// Consumer delivering the messages sent on its channel and recording the commits
+struct MockConsumer {
+    messages: tokio::sync::Mutex<mpsc::UnboundedReceiver<KafkaCommandMessage>>,
+    committed: Arc<Mutex<Vec<i64>>>,
+}
+
+#[async_trait]
+impl ConsumerLike for MockConsumer {
+    async fn recv(&self) -> Result<KafkaCommandMessage> {
+        match self.messages.lock().await.recv().await {
+            Some(message) => Ok(message),
+            // No more messages: wait until the driver shuts down
+            None => std::future::pending().await,
+        }
+    }
+
+    fn commit(&self, message: &KafkaCommandMessage) -> Result<()> {
+        self.committed.lock().unwrap().push(message.offset);
+        Ok(())
+    }
+}
+
+/// Kafka driver consuming the given command payloads, with the recorded commits
+fn driver_with_commands(payloads: &[&str]) -> (KafkaActionDriver, Arc<Mutex<Vec<i64>>>) {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    for (offset, payload) in payloads.iter().enumerate() {
+        sender
+            .send(KafkaCommandMessage {
+                topic: "photoacoustic.commands".to_string(),
+                partition: 0,
+                offset: offset as i64,
+                payload: Some(payload.to_string()),
+            })
+            .unwrap();
+    }
+    let committed = Arc::new(Mutex::new(Vec::new()));
+
+    let mut driver = KafkaActionDriver::new("localhost:9092", "displays", "alerts")
+        .with_command_topic("photoacoustic.commands", "test-group");
+    driver.set_consumer_for_test(Arc::new(MockConsumer {
+        messages: tokio::sync::Mutex::new(receiver),
+        committed: Arc::clone(&committed),
+    }));
+    (driver, committed)
+}
+
+/// Wait until `condition` holds, failing after 5 seconds
+fn wait_until(description: &str, condition: impl Fn() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !condition() {
+        assert!(
+            Instant::now() < deadline,
+            "timed out waiting for {}",
+            description
+        );
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Computing data with a concentration result
+fn computing_data(concentration_ppm: f64) -> ComputingSharedData {
+    let mut computing_data = ComputingSharedData::default();
+    computing_data.update_concentration_result(
+        "concentration".to_string(),
+        ConcentrationResult {
+            concentration_ppm,
+            concentration_variance: None,
+            source_peak_finder_id: "peak_finder".to_string(),
+            spectral_line_id: None,
+            polynomial_coefficients: [0.0; 5],
+            source_amplitude: 0.1,
+            source_frequency: 2000.0,
+            temperature_compensated: false,
+            timestamp: SystemTime::now(),
+            processing_metadata: HashMap::new(),
+        },
+    );
+    computing_data
+}
+
+#[test]
+fn test_update_config_command_changes_threshold() -> Result<()> {
+    let (driver, committed) = driver_with_commands(&[
+        r#"{"command": "update_config", "parameters": {"concentration_threshold": 500.0}}"#,
+    ]);
+    let mut node = UniversalActionNode::new("action".to_string())
+        .with_history_buffer_capacity(10)
+        .with_concentration_threshold(1000.0)
+        .with_driver(Box::new(driver));
+
+    wait_until("the command to be committed", || {
+        *committed.lock().unwrap() == vec![0]
+    });
+
+    // The node applies the update before handling the next computing data
+    assert_eq!(
+        node.get_history_statistics()["thresholds"]["concentration_threshold"],
+        1000.0
+    );
+    node.update_from_computing_data(&computing_data(100.0))?;
+    assert_eq!(
+        node.get_history_statistics()["thresholds"]["concentration_threshold"],
+        500.0
+    );
+
+    node.shutdown();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_acknowledge_and_invalid_commands() -> Result<()> {
+    let (mut driver, committed) = driver_with_commands(&[
+        r#"{"command": "acknowledge", "alert_type": "concentration_threshold"}"#,
+        r#"{"command": "reboot"}"#,
+        r#"{"command": "update_config", "parameters": 42}"#,
+        "not json",
+    ]);
+    driver.initialize().await?;
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while committed.lock().unwrap().len() < 4 {
+        assert!(
+            Instant::now() < deadline,
+            "timed out waiting for the commits"
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(*committed.lock().unwrap(), vec![0, 1, 2, 3]);
+
+    let commands = driver.get_status().await?["commands"].clone();
+    assert_eq!(commands["topic"], "photoacoustic.commands");
+    assert_eq!(commands["group_id"], "test-group");
+    assert_eq!(
+        commands["stats"],
+        json!({
+            "received": 4,
+            "applied": 1,
+            "rejected": 3,
+            "committed": 4,
+            "last_acknowledged_alert": "concentration_threshold"
+        })
+    );
+
+    driver.shutdown().await?;
+    Ok(())
+}
@@ -0,0 +1,265 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the Kalman concentration node
+//!
+//! A concentration source publishes one measurement per second: 100 ppm for
+//! 50 s, then 200 ppm for 100 s, with Gaussian noise of 5 ppm standard
+//! deviation. The node filters each new measurement with a measurement noise
+//! matching the source (25 ppm²) and a process noise of 1 ppm²/s.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_filters_noisy_step`] | The variance decreases, the estimate converges to the step and is less noisy than the measurements |
+//! | [`test_node_from_graph_config`] | A `computing_kalman_concentration` graph node filters the concentration in the shared state |
+//! | [`test_parameter_validation`] | Invalid noise parameters are rejected and a new source restarts the filter |
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::AudioFrame;
+use rust_photoacoustic::config::processing::{ConnectionConfig, NodeConfig, ProcessingGraphConfig};
+use rust_photoacoustic::processing::computing_nodes::{
+    ComputingSharedData, ConcentrationResult, KalmanConcentrationNode, SharedComputingState,
+};
+use rust_photoacoustic::processing::{ProcessingData, ProcessingGraph, ProcessingNode};
+use rust_photoacoustic::utility::noise_generator::NoiseGenerator;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::RwLock;
+
+const SOURCE_ID: &str = "concentration";
+const NODE_ID: &str = "smoothed_concentration";
+const MEASUREMENT_NOISE_PPM: f32 = 5.0;
+const STEP_AT: usize = 50;
+const MEASUREMENTS: usize = 150;
+
+/// True concentration at measurement `k`
+fn true_concentration(k: usize) -> f64 {
+    if k < STEP_AT {
+        100.0
+    } else {
+        200.0
+    }
+}
+
+/// Noisy measurements of the step
+fn measurements() -> Vec<f64> {
+    let mut noise = NoiseGenerator::new(42);
+    (0..MEASUREMENTS)
+        .map(|k| true_concentration(k) + (MEASUREMENT_NOISE_PPM * noise.random_gaussian()) as f64)
+        .collect()
+}
+
+/// Publish measurement `k` as the source concentration, one second after the previous one
+fn publish(state: &SharedComputingState, k: usize, concentration_ppm: f64) {
+    let result = ConcentrationResult {
+        concentration_ppm,
+        concentration_variance: None,
+        source_peak_finder_id: "peak_finder".to_string(),
+        spectral_line_id: None,
+        polynomial_coefficients: [0.0, 1.0, 0.0, 0.0, 0.0],
+        source_amplitude: 0.5,
+        source_frequency: 2000.0,
+        temperature_compensated: false,
+        timestamp: SystemTime::UNIX_EPOCH + Duration::from_secs(k as u64 + 1),
+        processing_metadata: HashMap::new(),
+    };
+    state
+        .try_write()
+        .expect("shared state available")
+        .update_concentration_result(SOURCE_ID.to_string(), result);
+}
+
+fn filtered(state: &SharedComputingState) -> ConcentrationResult {
+    state
+        .try_read()
+        .expect("shared state available")
+        .get_concentration_result(NODE_ID)
+        .cloned()
+        .expect("filtered concentration published")
+}
+
+fn frame(frame_number: u64) -> ProcessingData {
+    ProcessingData::SingleChannel {
+        samples: vec![0.0; 16],
+        sample_rate: 48000,
+        timestamp: 0,
+        frame_number,
+    }
+}
+
+/// Standard deviation of `values` around the true step over the last 50 measurements
+fn residual_std(values: &[f64]) -> f64 {
+    let start = MEASUREMENTS - 50;
+    let power = values[start..]
+        .iter()
+        .enumerate()
+        .map(|(i, value)| (value - true_concentration(start + i)).powi(2))
+        .sum::<f64>()
+        / (MEASUREMENTS - start) as f64;
+    power.sqrt()
+}
+
+/// Check the filtered concentrations and variances of the noisy step
+fn assert_filtered(measured: &[f64], concentrations: &[f64], variances: &[f64]) {
+    let measurement_noise = (MEASUREMENT_NOISE_PPM as f64).powi(2);
+    assert_eq!(variances[0], measurement_noise);
+    for pair in variances.windows(2) {
+        assert!(pair[1] <= pair[0], "variance increased: {:?}", pair);
+    }
+    let final_variance = variances[MEASUREMENTS - 1];
+    assert!(
+        final_variance < measurement_noise / 4.0,
+        "final variance {} ppm²",
+        final_variance
+    );
+
+    let final_mean = concentrations[MEASUREMENTS - 50..].iter().sum::<f64>() / 50.0;
+    assert!(
+        (final_mean - 200.0).abs() < 3.0,
+        "filtered concentration {} ppm",
+        final_mean
+    );
+    assert!(
+        residual_std(concentrations) < 0.6 * residual_std(measured),
+        "filtered noise {} ppm, measured noise {} ppm",
+        residual_std(concentrations),
+        residual_std(measured)
+    );
+}
+
+#[test]
+fn test_filters_noisy_step() -> Result<()> {
+    let state: SharedComputingState = Arc::new(RwLock::new(ComputingSharedData::default()));
+    let mut node =
+        KalmanConcentrationNode::new_with_shared_state(NODE_ID.to_string(), Some(state.clone()))
+            .with_concentration_source(SOURCE_ID.to_string())
+            .with_process_noise(1.0)
+            .with_measurement_noise((MEASUREMENT_NOISE_PPM as f64).powi(2));
+
+    // Nothing is published before the source has a concentration
+    node.process(frame(0))?;
+    assert!(node.get_estimate().is_none());
+
+    let measured = measurements();
+    let mut concentrations = Vec::new();
+    let mut variances = Vec::new();
+    for (k, measurement) in measured.iter().enumerate() {
+        publish(&state, k, *measurement);
+        // Frames between two measurements do not update the estimate
+        for frame_number in 0..3 {
+            assert!(matches!(
+                node.process(frame(frame_number))?,
+                ProcessingData::SingleChannel { .. }
+            ));
+        }
+
+        let result = filtered(&state);
+        concentrations.push(result.concentration_ppm);
+        variances.push(result.concentration_variance.expect("variance published"));
+        assert_eq!(
+            result.processing_metadata.get("source_concentration_id"),
+            Some(&SOURCE_ID.to_string())
+        );
+    }
+    assert_eq!(
+        node.get_statistics(),
+        (3 * MEASUREMENTS as u64 + 1, MEASUREMENTS as u64)
+    );
+    assert_filtered(&measured, &concentrations, &variances);
+
+    // The variance is exposed to scripts, the source has none
+    let context = state
+        .try_read()
+        .expect("shared state available")
+        .script_context();
+    assert_eq!(
+        context["concentration_results"][NODE_ID]["concentration_variance"],
+        serde_json::json!(variances[MEASUREMENTS - 1])
+    );
+    assert!(context["concentration_results"][SOURCE_ID]["concentration_variance"].is_null());
+    Ok(())
+}
+
+#[test]
+fn test_node_from_graph_config() -> Result<()> {
+    let config = ProcessingGraphConfig {
+        id: "kalman_graph".to_string(),
+        nodes: vec![
+            NodeConfig {
+                id: "input".to_string(),
+                node_type: "input".to_string(),
+                parameters: serde_json::Value::Null,
+            },
+            NodeConfig {
+                id: NODE_ID.to_string(),
+                node_type: "computing_kalman_concentration".to_string(),
+                parameters: serde_json::json!({
+                    "computing_concentration_id": SOURCE_ID,
+                    "process_noise": 1.0,
+                    "measurement_noise": 25.0
+                }),
+            },
+        ],
+        connections: vec![ConnectionConfig {
+            from: "input".to_string(),
+            to: NODE_ID.to_string(),
+        }],
+        output_node: Some(NODE_ID.to_string()),
+    };
+    let state: SharedComputingState = Arc::new(RwLock::new(ComputingSharedData::default()));
+    let mut graph =
+        ProcessingGraph::from_config_with_computing_state(&config, Some(state.clone()))?;
+
+    let measured = measurements();
+    let mut concentrations = Vec::new();
+    let mut variances = Vec::new();
+    for (k, measurement) in measured.iter().enumerate() {
+        publish(&state, k, *measurement);
+        graph.execute(ProcessingData::AudioFrame(AudioFrame::new(
+            vec![0.0; 16],
+            vec![0.0; 16],
+            48000,
+            k as u64,
+        )))?;
+
+        let result = filtered(&state);
+        concentrations.push(result.concentration_ppm);
+        variances.push(result.concentration_variance.expect("variance published"));
+    }
+    assert_filtered(&measured, &concentrations, &variances);
+
+    // The source concentration ID is required
+    let mut invalid = config.clone();
+    invalid.nodes[1].parameters = serde_json::json!({"process_noise": 1.0});
+    assert!(ProcessingGraph::from_config(&invalid).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_parameter_validation() -> Result<()> {
+    let state: SharedComputingState = Arc::new(RwLock::new(ComputingSharedData::default()));
+    let mut node =
+        KalmanConcentrationNode::new_with_shared_state(NODE_ID.to_string(), Some(state.clone()))
+            .with_concentration_source(SOURCE_ID.to_string());
+
+    assert!(node
+        .update_config(&serde_json::json!({"process_noise": -1.0}))
+        .is_err());
+    assert!(node
+        .update_config(&serde_json::json!({"measurement_noise": 0.0}))
+        .is_err());
+    assert!(node.update_config(&serde_json::json!({"measurement_noise": 25.0}))?);
+
+    publish(&state, 0, 120.0);
+    node.process(frame(0))?;
+    assert_eq!(node.get_estimate(), Some((120.0, 25.0)));
+
+    // The same source keeps the estimate, a new one restarts the filter
+    assert!(!node.update_config(&serde_json::json!({"computing_concentration_id": SOURCE_ID}))?);
+    assert!(node.get_estimate().is_some());
+    assert!(node.update_config(&serde_json::json!({"computing_concentration_id": "other"}))?);
+    assert!(node.get_estimate().is_none());
+    Ok(())
+}
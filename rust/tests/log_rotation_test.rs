@@ -0,0 +1,184 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the rotated daemon log file
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_rotation_keeps_configured_number_of_files`] | Enough log volume rotates the file and only `log_file_max_files` rotated files are kept |
+//! | [`test_concurrent_logging_loses_no_lines`] | Lines logged from several threads across rotations are all written, whole and in bounded files |
+//! | [`test_configure_from_daemon_config`] | `DaemonLogger::configure` opens the configured file and can disable the console |
+
+use anyhow::Result;
+use log::{Level, LevelFilter, Log, Record};
+use rust_photoacoustic::config::DaemonConfig;
+use rust_photoacoustic::utility::log_file::{DaemonLogger, RollingFileWriter};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::tempdir;
+
+/// Logger writing to `writer` only
+fn file_logger(writer: RollingFileWriter) -> DaemonLogger {
+    let console = env_logger::Builder::new()
+        .filter_level(LevelFilter::Info)
+        .build();
+    let logger = DaemonLogger::new(console);
+    logger.set_console_enabled(false);
+    logger.set_file(Some(writer));
+    logger
+}
+
+fn log_line(logger: &DaemonLogger, message: &str) {
+    logger.log(
+        &Record::builder()
+            .level(Level::Info)
+            .target("log_rotation_test")
+            .args(format_args!("{}", message))
+            .build(),
+    );
+}
+
+/// Current file and rotated files `<path>.1`, `<path>.2`... that exist
+fn log_files(path: &Path) -> Vec<std::path::PathBuf> {
+    let mut files = vec![path.to_path_buf()];
+    for index in 1.. {
+        let rotated = path.with_file_name(format!(
+            "{}.{}",
+            path.file_name().unwrap().to_string_lossy(),
+            index
+        ));
+        if !rotated.exists() {
+            break;
+        }
+        files.push(rotated);
+    }
+    files
+}
+
+#[test]
+fn test_rotation_keeps_configured_number_of_files() -> Result<()> {
+    let dir = tempdir()?;
+    let path = dir.path().join("logs").join("daemon.log");
+    let writer = RollingFileWriter::open(&path, 1024, 3)?;
+    assert_eq!(writer.path(), path);
+    let logger = file_logger(writer);
+
+    // About 20 KiB of logs: many more rotations than retained files
+    for index in 0..400 {
+        log_line(&logger, &format!("measurement {:04} recorded", index));
+    }
+    logger.flush();
+
+    assert!(path.exists());
+    for index in 1..=3 {
+        assert!(dir
+            .path()
+            .join("logs")
+            .join(format!("daemon.log.{}", index))
+            .exists());
+    }
+    assert!(!dir.path().join("logs").join("daemon.log.4").exists());
+    assert_eq!(log_files(&path).len(), 4);
+
+    // The most recent line is in the current file
+    assert!(fs::read_to_string(&path)?.contains("measurement 0399 recorded"));
+    Ok(())
+}
+
+#[test]
+fn test_concurrent_logging_loses_no_lines() -> Result<()> {
+    let dir = tempdir()?;
+    let path = dir.path().join("daemon.log");
+    let max_size = 4096;
+    // Retain every rotated file so that each line can be found again
+    let logger = Arc::new(file_logger(RollingFileWriter::open(
+        &path, max_size, 10_000,
+    )?));
+
+    let threads = 8;
+    let lines_per_thread = 500;
+    let handles: Vec<_> = (0..threads)
+        .map(|thread| {
+            let logger = Arc::clone(&logger);
+            std::thread::spawn(move || {
+                for line in 0..lines_per_thread {
+                    log_line(&logger, &format!("thread={} line={}", thread, line));
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    logger.flush();
+
+    let files = log_files(&path);
+    assert!(files.len() > 2, "no rotation happened");
+
+    let mut seen = HashSet::new();
+    for file in &files {
+        let content = fs::read_to_string(file)?;
+        assert!(content.len() as u64 <= max_size);
+        for line in content.lines() {
+            // Every line is whole: header and message are never split
+            assert!(line.starts_with('['), "truncated line: {:?}", line);
+            let message = line
+                .split("] ")
+                .nth(1)
+                .unwrap_or_else(|| panic!("malformed line: {:?}", line));
+            assert!(seen.insert(message.to_string()), "duplicate: {}", message);
+        }
+    }
+    assert_eq!(seen.len(), threads * lines_per_thread);
+    Ok(())
+}
+
+#[test]
+fn test_configure_from_daemon_config() -> Result<()> {
+    let dir = tempdir()?;
+    let path = dir.path().join("daemon.log");
+    let console = env_logger::Builder::new()
+        .filter_level(LevelFilter::Info)
+        .build();
+    let logger = DaemonLogger::new(console);
+
+    let config = DaemonConfig {
+        log_file: Some(path.to_string_lossy().into_owned()),
+        log_file_max_size: 1024,
+        log_file_max_files: 2,
+        log_to_console: false,
+        ..DaemonConfig::default()
+    };
+    logger.configure(&config)?;
+    log_line(&logger, "written to the configured file");
+    // Below the configured level
+    logger.log(
+        &Record::builder()
+            .level(Level::Debug)
+            .target("log_rotation_test")
+            .args(format_args!("filtered out"))
+            .build(),
+    );
+    logger.flush();
+
+    let content = fs::read_to_string(&path)?;
+    assert!(content.contains("INFO  log_rotation_test] written to the configured file"));
+    assert!(!content.contains("filtered out"));
+
+    // A file that cannot be created is reported
+    let blocked = DaemonConfig {
+        log_file: Some(
+            dir.path()
+                .join("daemon.log")
+                .join("nested.log")
+                .to_string_lossy()
+                .into_owned(),
+        ),
+        ..DaemonConfig::default()
+    };
+    assert!(logger.configure(&blocked).is_err());
+    Ok(())
+}
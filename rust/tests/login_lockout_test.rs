@@ -0,0 +1,155 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the lockout of users after repeated failed logins
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_repeated_failures_lock_out`] | The configured number of failures answers 429 with `Retry-After`, other users and addresses are unaffected |
+//! | [`test_correct_password_refused_until_cooldown`] | The correct password is refused during the lockout and accepted once it ends |
+//! | [`test_success_resets_failures`] | A successful login forgets the previous failures |
+//! | [`test_lockout_disabled`] | Without lockout, failures never block a correct login |
+
+use rocket::http::{ContentType, Status};
+use rocket::local::blocking::{Client, LocalResponse};
+use rocket::routes;
+use rust_photoacoustic::config::{Config, LoginLockoutConfig};
+use rust_photoacoustic::visualization::auth::oauth2::{login, LoginLockout, OxideState};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+const ATTACKER: &str = "192.0.2.10:40000";
+const OTHER_ADDRESS: &str = "192.0.2.20:40000";
+
+/// Build a client serving the login handler with the given lockout settings
+fn client(login_lockout: LoginLockoutConfig) -> Client {
+    let mut config = Config::default();
+    config.access.login_lockout = login_lockout;
+
+    let rocket = rocket::build()
+        .manage(Arc::new(RwLock::new(config)))
+        .manage(OxideState::preconfigured(
+            rocket::Config::figment().merge(("hmac_secret", "test-login-lockout".to_string())),
+        ))
+        .manage(LoginLockout::new())
+        .mount("/", routes![login]);
+    Client::tracked(rocket).expect("valid rocket instance")
+}
+
+fn lockout(lockout_secs: u64) -> LoginLockoutConfig {
+    LoginLockoutConfig {
+        enabled: true,
+        max_failures: 3,
+        window_secs: 60,
+        lockout_secs,
+    }
+}
+
+/// Submit the login form of `username` from `address`
+fn login_as<'c>(
+    client: &'c Client,
+    username: &str,
+    password: &str,
+    address: &str,
+) -> LocalResponse<'c> {
+    client
+        .post("/login")
+        .header(ContentType::Form)
+        .remote(address.parse::<SocketAddr>().unwrap())
+        .body(format!(
+            "username={}&password={}&response_type=code&client_id=LaserSmartClient&redirect_uri=https%3A%2F%2Flocalhost%2Fcallback",
+            username, password
+        ))
+        .dispatch()
+}
+
+#[test]
+fn test_repeated_failures_lock_out() {
+    let client = client(lockout(900));
+
+    for _ in 0..2 {
+        let response = login_as(&client, "admin", "wrong", ATTACKER);
+        assert_eq!(response.status(), Status::Unauthorized);
+    }
+    let response = login_as(&client, "admin", "wrong", ATTACKER);
+    assert_eq!(response.status(), Status::TooManyRequests);
+    assert_eq!(response.headers().get_one("Retry-After"), Some("900"));
+    assert!(response
+        .into_string()
+        .unwrap()
+        .contains("Too many failed login attempts"));
+
+    // Further attempts stay locked out
+    let response = login_as(&client, "admin", "wrong", ATTACKER);
+    assert_eq!(response.status(), Status::TooManyRequests);
+    let retry_after: u64 = response
+        .headers()
+        .get_one("Retry-After")
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(retry_after > 0 && retry_after <= 900);
+
+    // The same user from another address and other users are not locked out
+    let response = login_as(&client, "admin", "admin123", OTHER_ADDRESS);
+    assert_eq!(response.status(), Status::Found);
+    let response = login_as(&client, "nobody", "wrong", ATTACKER);
+    assert_eq!(response.status(), Status::Unauthorized);
+}
+
+#[test]
+fn test_correct_password_refused_until_cooldown() {
+    let client = client(lockout(1));
+
+    for _ in 0..3 {
+        login_as(&client, "admin", "wrong", ATTACKER);
+    }
+    let response = login_as(&client, "admin", "admin123", ATTACKER);
+    assert_eq!(response.status(), Status::TooManyRequests);
+    assert_eq!(response.headers().get_one("Retry-After"), Some("1"));
+
+    std::thread::sleep(Duration::from_millis(1100));
+    let response = login_as(&client, "admin", "admin123", ATTACKER);
+    assert_eq!(response.status(), Status::Found);
+    assert!(response
+        .headers()
+        .get_one("Location")
+        .is_some_and(|location| location.starts_with("/authorize?")));
+}
+
+#[test]
+fn test_success_resets_failures() {
+    let client = client(lockout(900));
+
+    for _ in 0..2 {
+        login_as(&client, "admin", "wrong", ATTACKER);
+    }
+    let response = login_as(&client, "admin", "admin123", ATTACKER);
+    assert_eq!(response.status(), Status::Found);
+
+    // Two more failures stay below the threshold
+    for _ in 0..2 {
+        let response = login_as(&client, "admin", "wrong", ATTACKER);
+        assert_eq!(response.status(), Status::Unauthorized);
+    }
+    let response = login_as(&client, "admin", "admin123", ATTACKER);
+    assert_eq!(response.status(), Status::Found);
+}
+
+#[test]
+fn test_lockout_disabled() {
+    let client = client(LoginLockoutConfig {
+        enabled: false,
+        ..lockout(900)
+    });
+
+    for _ in 0..5 {
+        let response = login_as(&client, "admin", "wrong", ATTACKER);
+        assert_eq!(response.status(), Status::Unauthorized);
+    }
+    let response = login_as(&client, "admin", "admin123", ATTACKER);
+    assert_eq!(response.status(), Status::Found);
+}
@@ -0,0 +1,331 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the Lua processing node
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_lua_script_scales_signal`] | A gain script scales every buffer of each data type and keeps the other fields |
+//! | [`test_lua_reads_computing_state`] | The script reads the shared computing state, `nil` without state, the last snapshot while the state is written |
+//! | [`test_lua_globals_persist_and_reload`] | Globals persist between frames, `script_path` swaps the script and an invalid one is rejected |
+//! | [`test_lua_timeout_interrupts_call`] | A script stuck in a loop is interrupted and the next frame is processed |
+//! | [`test_lua_node_from_config`] | The node is created from its graph parameters and requires `script_path` |
+
+#![cfg(feature = "lua-node")]
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::AudioFrame;
+use rust_photoacoustic::processing::computing_nodes::ComputingSharedData;
+use rust_photoacoustic::processing::nodes::{
+    LuaNode, LuaNodeConfig, ProcessingData, ProcessingMetadata, ProcessingNode,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+fn gain_script(gain: f32) -> String {
+    format!(
+        r#"
+local gain = {:?}
+
+function process_data(data)
+    for _, key in ipairs({{"channel_a", "channel_b", "samples", "signal"}}) do
+        local buffer = data[key]
+        if buffer then
+            for i = 1, #buffer do
+                buffer[i] = buffer[i] * gain
+            end
+        end
+    end
+    return data
+end
+"#,
+        gain
+    )
+}
+
+fn write_script(dir: &TempDir, name: &str, content: &str) -> PathBuf {
+    let script_path = dir.path().join(name);
+    std::fs::write(&script_path, content).expect("Failed to write test script");
+    script_path
+}
+
+fn lua_node(script_path: PathBuf) -> LuaNode {
+    LuaNode::new(
+        "lua".to_string(),
+        LuaNodeConfig {
+            script_path,
+            ..Default::default()
+        },
+    )
+}
+
+fn single_channel(samples: Vec<f32>, frame_number: u64) -> ProcessingData {
+    ProcessingData::SingleChannel {
+        samples,
+        sample_rate: 48000,
+        timestamp: 1000,
+        frame_number,
+    }
+}
+
+fn samples(output: ProcessingData) -> Vec<f32> {
+    match output {
+        ProcessingData::SingleChannel { samples, .. } => samples,
+        other => panic!("Unexpected output {:?}", other),
+    }
+}
+
+#[test]
+fn test_lua_script_scales_signal() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut node = lua_node(write_script(&dir, "gain.lua", &gain_script(2.0)));
+    assert_eq!(node.node_type(), "lua");
+
+    let metadata = ProcessingMetadata {
+        original_frame_number: 7,
+        original_timestamp: 4000,
+        sample_rate: 48000,
+        processing_steps: vec!["bandpass".to_string()],
+        processing_latency_us: 120,
+        snr_db: None,
+    };
+    let cases = vec![
+        (
+            ProcessingData::AudioFrame(AudioFrame {
+                channel_a: vec![0.5, -0.25],
+                channel_b: vec![1.0, 0.0],
+                sample_rate: 48000,
+                timestamp: 1000,
+                frame_number: 1,
+            }),
+            ProcessingData::AudioFrame(AudioFrame {
+                channel_a: vec![1.0, -0.5],
+                channel_b: vec![2.0, 0.0],
+                sample_rate: 48000,
+                timestamp: 1000,
+                frame_number: 1,
+            }),
+        ),
+        (
+            single_channel(vec![0.1, 0.2, 0.3], 2),
+            single_channel(vec![0.2, 0.4, 0.6], 2),
+        ),
+        (
+            ProcessingData::DualChannel {
+                channel_a: vec![0.25],
+                channel_b: vec![-0.75],
+                sample_rate: 44100,
+                timestamp: 3000,
+                frame_number: 3,
+            },
+            ProcessingData::DualChannel {
+                channel_a: vec![0.5],
+                channel_b: vec![-1.5],
+                sample_rate: 44100,
+                timestamp: 3000,
+                frame_number: 3,
+            },
+        ),
+        (
+            ProcessingData::PhotoacousticResult {
+                signal: vec![0.125, 0.5],
+                metadata: metadata.clone(),
+            },
+            ProcessingData::PhotoacousticResult {
+                signal: vec![0.25, 1.0],
+                metadata,
+            },
+        ),
+    ];
+
+    for (input, expected) in cases {
+        assert_eq!(node.process(input)?, expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_lua_reads_computing_state() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut node = lua_node(write_script(
+        &dir,
+        "state.lua",
+        r#"
+function process_data(data)
+    if computing_state == nil then
+        data.samples = {-1.0}
+    elseif computing_state.concentration_ppm == nil then
+        data.samples = {0.0}
+    else
+        data.samples = {computing_state.concentration_ppm}
+    end
+    return data
+end
+"#,
+    ));
+
+    assert_eq!(
+        samples(node.process(single_channel(vec![], 1))?),
+        vec![-1.0]
+    );
+
+    let state = Arc::new(RwLock::new(ComputingSharedData::default()));
+    node.set_shared_computing_state(Some(Arc::clone(&state)));
+    assert_eq!(samples(node.process(single_channel(vec![], 2))?), vec![0.0]);
+
+    state.try_write().unwrap().concentration_ppm = Some(412.5);
+    assert_eq!(
+        samples(node.process(single_channel(vec![], 3))?),
+        vec![412.5]
+    );
+
+    // While a computing node writes the state, the previous snapshot is used
+    let mut writer = state.try_write().unwrap();
+    writer.concentration_ppm = Some(900.0);
+    assert_eq!(
+        samples(node.process(single_channel(vec![], 4))?),
+        vec![412.5]
+    );
+    drop(writer);
+    assert_eq!(
+        samples(node.process(single_channel(vec![], 5))?),
+        vec![900.0]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_lua_globals_persist_and_reload() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut node = lua_node(write_script(
+        &dir,
+        "counter.lua",
+        r#"
+local frames = 0
+
+function initialize()
+    frames = 100
+end
+
+function process_data(data)
+    frames = frames + 1
+    data.samples = {frames}
+    return data
+end
+"#,
+    ));
+    assert!(node.supports_hot_reload());
+    assert_eq!(
+        samples(node.process(single_channel(vec![], 1))?),
+        vec![101.0]
+    );
+    assert_eq!(
+        samples(node.process(single_channel(vec![], 2))?),
+        vec![102.0]
+    );
+
+    // A new script replaces the interpreter
+    let gain = write_script(&dir, "gain.lua", &gain_script(3.0));
+    assert!(node.update_config(&json!({ "script_path": gain }))?);
+    assert_eq!(
+        samples(node.process(single_channel(vec![1.0], 3))?),
+        vec![3.0]
+    );
+
+    // Scripts that do not load or lack the processing function are rejected
+    for (name, broken) in [
+        ("syntax.lua", "function process_data(data"),
+        ("missing.lua", "function other(data) return data end"),
+        (
+            "init.lua",
+            "function initialize() error('boom') end\nfunction process_data(d) return d end",
+        ),
+    ] {
+        let broken = write_script(&dir, name, broken);
+        assert!(node
+            .update_config(&json!({ "script_path": broken }))
+            .is_err());
+        assert_eq!(node.config().script_path, gain);
+        assert_eq!(
+            samples(node.process(single_channel(vec![1.0], 4))?),
+            vec![3.0]
+        );
+    }
+
+    // A reset loads the script again with fresh globals
+    let counter = dir.path().join("counter.lua");
+    assert!(node.update_config(&json!({ "script_path": counter }))?);
+    assert_eq!(
+        samples(node.process(single_channel(vec![], 5))?),
+        vec![101.0]
+    );
+    node.reset();
+    assert_eq!(
+        samples(node.process(single_channel(vec![], 6))?),
+        vec![101.0]
+    );
+
+    // Other parameters require the node to be rebuilt
+    assert!(!node.update_config(&json!({ "process_function": "other" }))?);
+    Ok(())
+}
+
+#[test]
+fn test_lua_timeout_interrupts_call() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut node = LuaNode::new(
+        "lua".to_string(),
+        LuaNodeConfig {
+            script_path: write_script(
+                &dir,
+                "stuck.lua",
+                r#"
+function process_data(data)
+    if data.frame_number == 1 then
+        while true do end
+    end
+    return data
+end
+"#,
+            ),
+            timeout_seconds: 1,
+            ..Default::default()
+        },
+    );
+
+    let start = Instant::now();
+    assert!(node.process(single_channel(vec![0.5], 1)).is_err());
+    assert!(start.elapsed() < Duration::from_secs(10));
+    assert_eq!(node.timeout_count(), 1);
+
+    assert_eq!(
+        samples(node.process(single_channel(vec![0.5], 2))?),
+        vec![0.5]
+    );
+    assert_eq!(ProcessingNode::timeout_count(&node), 1);
+    Ok(())
+}
+
+#[test]
+fn test_lua_node_from_config() -> Result<()> {
+    let config: HashMap<String, serde_json::Value> = serde_json::from_value(json!({
+        "script_path": "gain.lua",
+        "process_function": "scale",
+        "timeout_seconds": 5
+    }))?;
+    let node = LuaNode::from_config("lua_gain".to_string(), config)?;
+    assert_eq!(node.node_id(), "lua_gain");
+    assert_eq!(node.config().script_path, PathBuf::from("gain.lua"));
+    assert_eq!(node.config().process_function, "scale");
+    assert_eq!(node.config().init_function, "initialize");
+    assert_eq!(node.config().timeout_seconds, 5);
+
+    assert!(LuaNode::from_config("lua".to_string(), HashMap::new()).is_err());
+    Ok(())
+}
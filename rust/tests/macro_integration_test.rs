@@ -28,6 +28,65 @@ fn test_protected_route(
     })
 }
 
+/// Test route accessible with any one of several permissions
+#[protect_get("/api/test/any", any = ["write:api", "admin:api"])]
+fn test_any_permission_route(
+    bearer: rust_photoacoustic::visualization::auth::guards::bearer::OAuthBearer,
+) -> Json<ApiResponse> {
+    Json(ApiResponse {
+        message: granted_permissions.join(" "),
+        user_id: bearer.user_info.user_id.clone(),
+    })
+}
+
+/// Test route whose permissions are all missing from the local bearer
+#[protect_get("/api/test/any_missing", any = ["write:api", "delete:api"])]
+fn test_any_permission_missing_route(
+    bearer: rust_photoacoustic::visualization::auth::guards::bearer::OAuthBearer,
+) -> Json<ApiResponse> {
+    Json(ApiResponse {
+        message: "Should not be reached".to_string(),
+        user_id: bearer.user_info.user_id.clone(),
+    })
+}
+
+/// Test route requiring every listed permission, reporting the granted ones
+#[protect_get("/api/test/all", all = ["read:api", "admin:api"])]
+fn test_all_permissions_route(
+    bearer: rust_photoacoustic::visualization::auth::guards::bearer::OAuthBearer,
+) -> Json<ApiResponse> {
+    Json(ApiResponse {
+        message: granted_permissions.join(" "),
+        user_id: bearer.user_info.user_id.clone(),
+    })
+}
+
+/// Test route requiring a permission the local bearer lacks
+#[protect_get("/api/test/all_missing", all = ["read:api", "write:api"])]
+fn test_all_permissions_missing_route(
+    bearer: rust_photoacoustic::visualization::auth::guards::bearer::OAuthBearer,
+) -> Json<ApiResponse> {
+    Json(ApiResponse {
+        message: "Should not be reached".to_string(),
+        user_id: bearer.user_info.user_id.clone(),
+    })
+}
+
+/// Build a client whose loopback requests get the local `read:api admin:api` bearer
+fn local_visualization_client(routes: Vec<rocket::Route>) -> Client {
+    let mut cfg = Config::default();
+    cfg.visualization.enable_local_visualization = true;
+
+    let rocket = rocket::build()
+        .manage(Arc::new(RwLock::new(cfg)))
+        .manage(OxideState::preconfigured(
+            rocket::Config::figment().merge(("hmac_secret", "test-local".to_string())),
+        ))
+        .mount("/", routes);
+
+    Client::tracked(rocket).expect("valid rocket instance")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,4 +173,63 @@ mod tests {
 
         assert_eq!(response.status(), Status::Ok);
     }
+
+    #[test]
+    fn test_any_permission_macro_grants_with_one_permission() {
+        let client = local_visualization_client(routes![test_any_permission_route]);
+        let response = client
+            .get("/api/test/any")
+            .remote("127.0.0.1:8000".parse().unwrap())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn test_any_permission_macro_forbids_without_any_permission() {
+        let client = local_visualization_client(routes![test_any_permission_missing_route]);
+        let response = client
+            .get("/api/test/any_missing")
+            .remote("127.0.0.1:8000".parse().unwrap())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Forbidden);
+    }
+
+    #[test]
+    fn test_any_permission_macro_exposes_matched_permission() {
+        let client = local_visualization_client(routes![test_any_permission_route]);
+        let response = client
+            .get("/api/test/any")
+            .remote("127.0.0.1:8000".parse().unwrap())
+            .dispatch();
+
+        let body: serde_json::Value = response.into_json().expect("JSON body");
+        assert_eq!(body["message"], "admin:api");
+    }
+
+    #[test]
+    fn test_all_permissions_macro_grants_with_every_permission() {
+        let client = local_visualization_client(routes![test_all_permissions_route]);
+        let response = client
+            .get("/api/test/all")
+            .remote("127.0.0.1:8000".parse().unwrap())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let body: serde_json::Value = response.into_json().expect("JSON body");
+        assert_eq!(body["message"], "read:api admin:api");
+    }
+
+    #[test]
+    fn test_all_permissions_macro_forbids_when_one_is_missing() {
+        let client = local_visualization_client(routes![test_all_permissions_missing_route]);
+        let response = client
+            .get("/api/test/all_missing")
+            .remote("127.0.0.1:8000".parse().unwrap())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Forbidden);
+        assert_eq!(response.into_string().as_deref(), Some("Permission denied"));
+    }
 }
@@ -0,0 +1,225 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the `/api/stream/measurements` Server-Sent Events endpoint
+//!
+//! The server is built with a [`SharedComputingState`] which the tests update
+//! while reading the event stream through the local client:
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_measurement_stream_emits_updates`] | Peak and concentration updates are pushed as `peak` / `concentration` events |
+//! | [`test_measurement_stream_coalesces_updates`] | Updates faster than the minimum interval only emit the latest value |
+//! | [`test_measurement_stream_requires_token`] | The stream is refused without a valid token |
+
+use rocket::http::{Header, Status};
+use rocket::local::asynchronous::{Client, LocalResponse};
+use rust_photoacoustic::config::Config;
+use rust_photoacoustic::processing::computing_nodes::{
+    ComputingSharedData, ConcentrationResult, PeakResult, SharedComputingState,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::io::AsyncReadExt;
+use tokio::sync::RwLock;
+use tokio::time::timeout;
+
+mod common;
+use common::{access_token, test_figment, TEST_HMAC_SECRET};
+
+async fn build_test_client(computing_state: SharedComputingState, interval_ms: u64) -> Client {
+    let mut config = Config::default();
+    config.visualization.hmac_secret = TEST_HMAC_SECRET.to_string();
+    config.visualization.measurement_stream_interval_ms = interval_ms;
+
+    let rocket = rust_photoacoustic::visualization::server::build_rocket(
+        test_figment(),
+        Arc::new(RwLock::new(config)),
+        None,
+        None,
+        None,
+        None,
+        Some(computing_state),
+    )
+    .await;
+    Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance")
+}
+
+fn peak_result(frequency: f32, amplitude: f32) -> PeakResult {
+    PeakResult {
+        frequency,
+        amplitude,
+        concentration_ppm: None,
+        timestamp: SystemTime::now(),
+        coherence_score: 0.9,
+        processing_metadata: HashMap::new(),
+    }
+}
+
+fn concentration_result(concentration_ppm: f64) -> ConcentrationResult {
+    ConcentrationResult {
+        concentration_ppm,
+        concentration_variance: None,
+        source_peak_finder_id: "peak_finder".to_string(),
+        spectral_line_id: Some("CO2_line".to_string()),
+        polynomial_coefficients: [0.0, 1.0, 0.0, 0.0, 0.0],
+        source_amplitude: 0.5,
+        source_frequency: 2000.0,
+        temperature_compensated: false,
+        timestamp: SystemTime::now(),
+        processing_metadata: HashMap::new(),
+    }
+}
+
+/// Incremental reader of the SSE events of a streamed response
+struct SseReader<'c> {
+    response: LocalResponse<'c>,
+    buffer: String,
+}
+
+impl<'c> SseReader<'c> {
+    fn new(response: LocalResponse<'c>) -> Self {
+        Self {
+            response,
+            buffer: String::new(),
+        }
+    }
+
+    /// Wait for the next named event and return its name and JSON data
+    async fn next_event(&mut self) -> (String, Value) {
+        loop {
+            while let Some(end) = self.buffer.find("\n\n") {
+                let block: String = self.buffer.drain(..end + 2).collect();
+                let mut name = None;
+                let mut data = String::new();
+                for line in block.lines() {
+                    if let Some(value) = line.strip_prefix("event:") {
+                        name = Some(value.trim().to_string());
+                    } else if let Some(value) = line.strip_prefix("data:") {
+                        data.push_str(value.trim());
+                    }
+                }
+                // Comments (heartbeats) have neither a name nor data
+                if let Some(name) = name {
+                    return (name, serde_json::from_str(&data).expect("JSON event data"));
+                }
+            }
+
+            let mut chunk = [0u8; 4096];
+            let read = timeout(
+                std::time::Duration::from_secs(5),
+                self.response.read(&mut chunk),
+            )
+            .await
+            .expect("event received in time")
+            .expect("readable stream");
+            assert!(read > 0, "event stream ended unexpectedly");
+            self.buffer
+                .push_str(std::str::from_utf8(&chunk[..read]).expect("UTF-8 stream"));
+        }
+    }
+}
+
+#[rocket::async_test]
+async fn test_measurement_stream_emits_updates() {
+    let computing_state: SharedComputingState =
+        Arc::new(RwLock::new(ComputingSharedData::default()));
+    let client = build_test_client(computing_state.clone(), 20).await;
+    let token = access_token(&client, "admin", "read:api");
+
+    let response = client
+        .get("/api/stream/measurements")
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    let mut events = SseReader::new(response);
+
+    computing_state
+        .write()
+        .await
+        .update_peak_result("peak_finder".to_string(), peak_result(2000.0, 0.5));
+    let (name, data) = events.next_event().await;
+    assert_eq!(name, "peak");
+    assert_eq!(data["node_id"], "peak_finder");
+    assert_eq!(data["frequency"], 2000.0);
+    assert_eq!(data["amplitude"], 0.5);
+
+    computing_state
+        .write()
+        .await
+        .update_concentration_result("concentration".to_string(), concentration_result(412.5));
+    let (name, data) = events.next_event().await;
+    assert_eq!(name, "concentration");
+    assert_eq!(data["node_id"], "concentration");
+    assert_eq!(data["concentration_ppm"], 412.5);
+    assert_eq!(data["spectral_line_id"], "CO2_line");
+
+    computing_state
+        .write()
+        .await
+        .update_peak_result("peak_finder".to_string(), peak_result(2010.0, 0.75));
+    let (name, data) = events.next_event().await;
+    assert_eq!(name, "peak");
+    assert_eq!(data["frequency"], 2010.0);
+    assert_eq!(data["amplitude"], 0.75);
+}
+
+#[rocket::async_test]
+async fn test_measurement_stream_coalesces_updates() {
+    let computing_state: SharedComputingState =
+        Arc::new(RwLock::new(ComputingSharedData::default()));
+    computing_state
+        .write()
+        .await
+        .update_peak_result("peak_finder".to_string(), peak_result(1000.0, 0.1));
+    let client = build_test_client(computing_state.clone(), 500).await;
+    let token = access_token(&client, "admin", "read:api");
+
+    let response = client
+        .get("/api/stream/measurements")
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    let mut events = SseReader::new(response);
+
+    // The current result is sent on connection
+    let (name, data) = events.next_event().await;
+    assert_eq!(name, "peak");
+    assert_eq!(data["frequency"], 1000.0);
+
+    // Several updates within one interval only emit the last one
+    for step in 1..=5 {
+        computing_state.write().await.update_peak_result(
+            "peak_finder".to_string(),
+            peak_result(1000.0 + step as f32, 0.1),
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    let (name, data) = events.next_event().await;
+    assert_eq!(name, "peak");
+    assert_eq!(data["frequency"], 1005.0);
+}
+
+#[rocket::async_test]
+async fn test_measurement_stream_requires_token() {
+    let computing_state: SharedComputingState =
+        Arc::new(RwLock::new(ComputingSharedData::default()));
+    let client = build_test_client(computing_state, 20).await;
+
+    let response = client.get("/api/stream/measurements").dispatch().await;
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let response = client
+        .get("/api/stream/measurements")
+        .header(Header::new("Authorization", "Bearer invalid"))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Unauthorized);
+}
@@ -0,0 +1,193 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the frequency sweep of the mock source
+//!
+//! The instantaneous frequency of the generated signal is measured from the
+//! period between two consecutive rising zero crossings, which for a sweep is
+//! the frequency at the middle of that period.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_linear_sweep_frequency`] | A linear sweep follows the expected frequency at several time points |
+//! | [`test_logarithmic_sweep_frequency`] | A logarithmic sweep follows the expected frequency at several time points |
+//! | [`test_repeated_sweep`] | A repeated sweep restarts from the start frequency |
+//! | [`test_single_sweep_then_silence`] | A single sweep is followed by silence and both channels are identical |
+//! | [`test_sweep_continues_after_streaming`] | Frames read after streaming continue the sweep instead of restarting it |
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::{
+    AudioSource, MockSource, RealTimeAudioSource, SharedAudioStream,
+};
+use rust_photoacoustic::config::{
+    ChirpConfig, ChirpSweep, PhotoacousticConfig, SimulatedSourceConfig,
+};
+use std::sync::Arc;
+
+const SAMPLE_RATE: u16 = 48000;
+/// 100 ms frames
+const FRAME_SIZE: u16 = 4800;
+
+fn chirp(
+    sweep: ChirpSweep,
+    start_frequency_hz: f64,
+    stop_frequency_hz: f64,
+    duration_seconds: f64,
+    repeat: bool,
+) -> ChirpConfig {
+    ChirpConfig {
+        start_frequency_hz,
+        stop_frequency_hz,
+        duration_seconds,
+        sweep,
+        repeat,
+        amplitude: 0.5,
+    }
+}
+
+/// Mock source generating `chirp`
+fn chirp_source(chirp: ChirpConfig) -> Result<MockSource> {
+    MockSource::new(PhotoacousticConfig {
+        sample_rate: SAMPLE_RATE,
+        frame_size: FRAME_SIZE,
+        simulated_source: Some(SimulatedSourceConfig {
+            source_type: "mock".to_string(),
+            chirp: Some(chirp),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+}
+
+/// Channels A and B of `seconds` of the sweep
+fn generate(chirp: ChirpConfig, seconds: f64) -> Result<(Vec<f32>, Vec<f32>)> {
+    let mut source = chirp_source(chirp)?;
+    source.set_real_time_mode(false);
+
+    let frames = (seconds * SAMPLE_RATE as f64 / FRAME_SIZE as f64).ceil() as usize;
+    let (mut channel_a, mut channel_b) = (Vec::new(), Vec::new());
+    for _ in 0..frames {
+        let (frame_a, frame_b) = source.read_frame()?;
+        channel_a.extend(frame_a);
+        channel_b.extend(frame_b);
+    }
+    Ok((channel_a, channel_b))
+}
+
+/// Times of the rising zero crossings, interpolated between samples
+fn rising_zero_crossings(signal: &[f32]) -> Vec<f64> {
+    signal
+        .windows(2)
+        .enumerate()
+        .filter(|(_, pair)| pair[0] < 0.0 && pair[1] >= 0.0)
+        .map(|(index, pair)| {
+            let fraction = pair[0] as f64 / (pair[0] as f64 - pair[1] as f64);
+            (index as f64 + fraction) / SAMPLE_RATE as f64
+        })
+        .collect()
+}
+
+/// Measured frequency of the period closest to `time`, and the middle of that period
+fn measured_frequency(crossings: &[f64], time: f64) -> (f64, f64) {
+    crossings
+        .windows(2)
+        .map(|pair| (1.0 / (pair[1] - pair[0]), (pair[0] + pair[1]) / 2.0))
+        .min_by(|a, b| (a.1 - time).abs().total_cmp(&(b.1 - time).abs()))
+        .expect("at least two zero crossings")
+}
+
+fn assert_follows_sweep(chirp: &ChirpConfig, signal: &[f32], times: &[f64]) {
+    let crossings = rising_zero_crossings(signal);
+    for &time in times {
+        let (frequency, middle) = measured_frequency(&crossings, time);
+        let expected = chirp.frequency_at(middle).expect("sweep running");
+        assert!(
+            ((frequency - expected) / expected).abs() < 0.005,
+            "at {:.3} s: measured {:.2} Hz, expected {:.2} Hz",
+            middle,
+            frequency,
+            expected
+        );
+    }
+}
+
+#[test]
+fn test_linear_sweep_frequency() -> Result<()> {
+    let chirp = chirp(ChirpSweep::Linear, 100.0, 2000.0, 1.0, false);
+    let (channel_a, _) = generate(chirp, 1.0)?;
+
+    assert_eq!(chirp.frequency_at(0.5), Some(1050.0));
+    assert_follows_sweep(&chirp, &channel_a, &[0.05, 0.25, 0.5, 0.75, 0.95]);
+    Ok(())
+}
+
+#[test]
+fn test_logarithmic_sweep_frequency() -> Result<()> {
+    let chirp = chirp(ChirpSweep::Logarithmic, 100.0, 3200.0, 2.0, false);
+    let (channel_a, _) = generate(chirp, 2.0)?;
+
+    // Five octaves in 2 s: one octave every 0.4 s
+    for (time, expected) in [(0.4, 200.0), (0.8, 400.0), (1.2, 800.0), (1.6, 1600.0)] {
+        let frequency = chirp.frequency_at(time).unwrap();
+        assert!((frequency - expected).abs() < 1e-9, "{} Hz", frequency);
+    }
+    assert_follows_sweep(&chirp, &channel_a, &[0.1, 0.5, 1.0, 1.5, 1.9]);
+    Ok(())
+}
+
+#[test]
+fn test_repeated_sweep() -> Result<()> {
+    let chirp = chirp(ChirpSweep::Linear, 200.0, 1000.0, 0.5, true);
+    let (channel_a, _) = generate(chirp, 1.5)?;
+
+    assert_eq!(chirp.frequency_at(0.6), chirp.frequency_at(0.1));
+    assert_follows_sweep(&chirp, &channel_a, &[0.1, 0.4, 0.6, 0.9, 1.2, 1.45]);
+    Ok(())
+}
+
+#[test]
+fn test_single_sweep_then_silence() -> Result<()> {
+    let chirp = chirp(ChirpSweep::Logarithmic, 500.0, 5000.0, 0.5, false);
+    let (channel_a, channel_b) = generate(chirp, 1.0)?;
+
+    assert_eq!(channel_a, channel_b);
+    assert_eq!(chirp.frequency_at(0.5), None);
+
+    let end = (0.5 * SAMPLE_RATE as f64) as usize;
+    let peak = channel_a[..end]
+        .iter()
+        .fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+    assert!((peak - 0.5).abs() < 0.01, "peak {}", peak);
+    assert!(channel_a[end..].iter().all(|sample| *sample == 0.0));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sweep_continues_after_streaming() -> Result<()> {
+    let chirp = chirp(ChirpSweep::Linear, 500.0, 5000.0, 2.0, false);
+    let mut source = chirp_source(chirp)?;
+    let stream = Arc::new(SharedAudioStream::new(16));
+    let mut frames = stream.subscribe();
+
+    source.start_streaming(stream.clone()).await?;
+    for _ in 0..3 {
+        frames.recv().await?;
+    }
+    source.stop_streaming().await?;
+
+    // The streaming task may have generated a frame it did not publish
+    let mut reference = chirp_source(chirp)?;
+    reference.set_real_time_mode(false);
+    let reference_frames = (0..8)
+        .map(|_| reference.read_frame())
+        .collect::<Result<Vec<_>>>()?;
+    source.set_real_time_mode(false);
+    let next_frame = source.read_frame()?;
+    let position = reference_frames
+        .iter()
+        .position(|frame| *frame == next_frame)
+        .expect("the frame continues the sweep");
+    assert!(position >= 3, "the sweep restarted at frame {}", position);
+    Ok(())
+}
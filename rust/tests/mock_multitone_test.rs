@@ -0,0 +1,162 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the multi-tone generation of the mock source
+//!
+//! One second of signal is analysed with 1 Hz bins, so that every tone falls
+//! exactly on a bin and nothing leaks into the neighbouring bins.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_two_tones_in_spectrum`] | Both tones appear at their configured amplitudes with no other component |
+//! | [`test_independent_channels`] | Each channel carries its own components, with the configured phase |
+//! | [`test_tones_validation`] | Clipping amplitude sums and tones combined with a chirp are rejected |
+
+use anyhow::Result;
+use realfft::RealFftPlanner;
+use rust_photoacoustic::acquisition::{AudioSource, MockSource};
+use rust_photoacoustic::config::utils::validate_specific_rules;
+use rust_photoacoustic::config::{
+    ChirpConfig, ChirpSweep, Config, MultiToneConfig, PhotoacousticConfig, SimulatedSourceConfig,
+    ToneComponent,
+};
+
+const SAMPLE_RATE: u16 = 48000;
+/// 100 ms frames
+const FRAME_SIZE: u16 = 4800;
+
+fn tone(frequency_hz: f64, amplitude: f32, phase_degrees: f64) -> ToneComponent {
+    ToneComponent {
+        frequency_hz,
+        amplitude,
+        phase_degrees,
+    }
+}
+
+fn simulated_source(tones: MultiToneConfig) -> SimulatedSourceConfig {
+    SimulatedSourceConfig {
+        source_type: "mock".to_string(),
+        tones: Some(tones),
+        ..Default::default()
+    }
+}
+
+/// Channels A and B of one second of the tones
+fn generate(tones: MultiToneConfig) -> Result<(Vec<f32>, Vec<f32>)> {
+    let mut source = MockSource::new(PhotoacousticConfig {
+        sample_rate: SAMPLE_RATE,
+        frame_size: FRAME_SIZE,
+        simulated_source: Some(simulated_source(tones)),
+        ..Default::default()
+    })?;
+    source.set_real_time_mode(false);
+
+    let (mut channel_a, mut channel_b) = (Vec::new(), Vec::new());
+    for _ in 0..SAMPLE_RATE / FRAME_SIZE {
+        let (frame_a, frame_b) = source.read_frame()?;
+        channel_a.extend(frame_a);
+        channel_b.extend(frame_b);
+    }
+    Ok((channel_a, channel_b))
+}
+
+/// Peak amplitude of the sinusoid in each bin
+fn amplitude_spectrum(samples: &[f32]) -> Vec<f32> {
+    let mut planner = RealFftPlanner::<f32>::new();
+    let fft = planner.plan_fft_forward(samples.len());
+    let mut input = samples.to_vec();
+    let mut output = fft.make_output_vec();
+    fft.process(&mut input, &mut output).unwrap();
+    let n = samples.len() as f32;
+    output.iter().map(|bin| 2.0 * bin.norm() / n).collect()
+}
+
+/// Check the tones of `expected` (bin, amplitude) and that every other bin is empty
+fn assert_tones(samples: &[f32], expected: &[(usize, f32)]) {
+    let spectrum = amplitude_spectrum(samples);
+    for &(bin, amplitude) in expected {
+        assert!(
+            (spectrum[bin] - amplitude).abs() < 1e-3,
+            "{} Hz: amplitude {} instead of {}",
+            bin,
+            spectrum[bin],
+            amplitude
+        );
+    }
+    let (spur_bin, spur) = spectrum
+        .iter()
+        .enumerate()
+        .filter(|(bin, _)| !expected.iter().any(|(tone_bin, _)| tone_bin == bin))
+        .fold((0, 0.0f32), |strongest, (bin, amplitude)| {
+            if *amplitude > strongest.1 {
+                (bin, *amplitude)
+            } else {
+                strongest
+            }
+        });
+    assert!(spur < 1e-4, "spur of {} at {} Hz", spur, spur_bin);
+}
+
+#[test]
+fn test_two_tones_in_spectrum() -> Result<()> {
+    let (channel_a, channel_b) = generate(MultiToneConfig {
+        channel_a: vec![tone(1000.0, 0.5, 0.0), tone(1200.0, 0.25, 30.0)],
+        channel_b: Vec::new(),
+    })?;
+
+    assert_tones(&channel_a, &[(1000, 0.5), (1200, 0.25)]);
+    assert!(channel_b.iter().all(|sample| *sample == 0.0));
+    Ok(())
+}
+
+#[test]
+fn test_independent_channels() -> Result<()> {
+    let (channel_a, channel_b) = generate(MultiToneConfig {
+        channel_a: vec![tone(440.0, 0.3, 0.0), tone(5000.0, 0.3, 0.0)],
+        channel_b: vec![tone(3000.0, 0.6, 90.0)],
+    })?;
+
+    assert_tones(&channel_a, &[(440, 0.3), (5000, 0.3)]);
+    assert_tones(&channel_b, &[(3000, 0.6)]);
+
+    // A sine starting at zero, and a cosine at its peak
+    assert_eq!(channel_a[0], 0.0);
+    assert!((channel_b[0] - 0.6).abs() < 1e-6, "{}", channel_b[0]);
+    Ok(())
+}
+
+#[test]
+fn test_tones_validation() {
+    let validate = |simulated_source: SimulatedSourceConfig| {
+        let mut config = Config::default();
+        config.photoacoustic.sample_rate = SAMPLE_RATE;
+        config.photoacoustic.simulated_source = Some(simulated_source);
+        validate_specific_rules(&config)
+    };
+
+    let tones = MultiToneConfig {
+        channel_a: vec![tone(1000.0, 0.5, 0.0), tone(2000.0, 0.5, 0.0)],
+        channel_b: vec![tone(1000.0, 0.9, 45.0)],
+    };
+    assert!(validate(simulated_source(tones.clone())).is_ok());
+
+    let mut clipping = tones.clone();
+    clipping.channel_b.push(tone(2000.0, 0.2, 0.0));
+    assert!(validate(simulated_source(clipping)).is_err());
+
+    let mut above_nyquist = tones.clone();
+    above_nyquist.channel_a[1].frequency_hz = SAMPLE_RATE as f64;
+    assert!(validate(simulated_source(above_nyquist)).is_err());
+
+    let mut with_chirp = simulated_source(tones);
+    with_chirp.chirp = Some(ChirpConfig {
+        start_frequency_hz: 100.0,
+        stop_frequency_hz: 1000.0,
+        duration_seconds: 1.0,
+        sweep: ChirpSweep::Linear,
+        repeat: false,
+        amplitude: 0.5,
+    });
+    assert!(validate(with_chirp).is_err());
+}
@@ -0,0 +1,191 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Tests for the `GET /api/modbus/map` endpoint
+//!
+//! These tests check that the published register map matches the constants
+//! used by the Modbus server, that the thermal blocks follow the shared
+//! thermal state the server reads, and that the endpoint is protected.
+
+use rocket::http::{Header, Status};
+use rust_photoacoustic::config::modbus::{ModbusValueEncoding, ModbusWordOrder};
+use rust_photoacoustic::config::Config;
+use rust_photoacoustic::modbus::register_map::{
+    exposed_regulator_ids, thermal_block_base, CONCENTRATION_REGISTER, CONCENTRATION_SCALE,
+    FLOAT32_CONCENTRATION_REGISTER, FREQUENCY_REGISTER, FREQUENCY_SCALE,
+};
+use rust_photoacoustic::thermal_regulation::shared_state::CurrentPidParams;
+use rust_photoacoustic::thermal_regulation::{create_shared_thermal_state, SharedThermalState};
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+mod common;
+use common::{access_token, test_figment, TEST_HMAC_SECRET};
+
+fn get_test_config() -> Config {
+    let mut config = Config::default();
+    config.visualization.hmac_secret = TEST_HMAC_SECRET.to_string();
+    config
+}
+
+async fn create_client(config: Config) -> rocket::local::asynchronous::Client {
+    create_client_with_thermal_state(config, None).await
+}
+
+async fn create_client_with_thermal_state(
+    config: Config,
+    thermal_state: Option<SharedThermalState>,
+) -> rocket::local::asynchronous::Client {
+    let rocket = rust_photoacoustic::visualization::server::build_rocket(
+        test_figment(),
+        Arc::new(RwLock::new(config)),
+        None,
+        None,
+        None,
+        thermal_state,
+        None,
+    )
+    .await;
+    rocket::local::asynchronous::Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance")
+}
+
+/// Find the descriptor of a register in the map
+fn find_register<'a>(registers: &'a [Value], register_type: &str, address: u16) -> &'a Value {
+    registers
+        .iter()
+        .find(|register| {
+            register["register_type"] == register_type && register["address"] == address
+        })
+        .unwrap_or_else(|| panic!("{} register {} not in map", register_type, address))
+}
+
+#[rocket::async_test]
+async fn test_modbus_map_contains_measurement_registers() {
+    let config = get_test_config();
+    let client = create_client(config).await;
+    let token = access_token(&client, "admin", "read:api");
+
+    let response = client
+        .get("/api/modbus/map")
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+
+    let body: Value = serde_json::from_str(&response.into_string().await.expect("body"))
+        .expect("valid JSON response");
+    let registers = body["registers"].as_array().expect("registers array");
+
+    let frequency = find_register(registers, "input", FREQUENCY_REGISTER);
+    assert_eq!(frequency["name"], "resonance_frequency");
+    assert_eq!(frequency["scale_factor"], FREQUENCY_SCALE);
+    assert_eq!(frequency["scale_factor"], 10.0);
+    assert_eq!(frequency["unit"], "Hz");
+    assert_eq!(frequency["access"], "read");
+
+    let concentration = find_register(registers, "input", CONCENTRATION_REGISTER);
+    assert_eq!(concentration["name"], "concentration");
+    assert_eq!(concentration["scale_factor"], CONCENTRATION_SCALE);
+    assert_eq!(concentration["scale_factor"], 10.0);
+    assert_eq!(concentration["unit"], "ppm");
+
+    assert_eq!(frequency["data_type"], "uint16");
+    assert_eq!(frequency["register_count"], 1);
+
+    let interval = find_register(registers, "holding", 0);
+    assert_eq!(interval["access"], "read_write");
+    assert_eq!(interval["default_value"], 10);
+
+    // Thermal regulation is disabled in the default configuration
+    assert!(body["thermal_regulators"]
+        .as_array()
+        .expect("thermal_regulators array")
+        .is_empty());
+}
+
+#[rocket::async_test]
+async fn test_modbus_map_float32_encoding() {
+    let mut config = get_test_config();
+    config.modbus.value_encoding = ModbusValueEncoding::Float32;
+    config.modbus.word_order = ModbusWordOrder::LittleEndian;
+    let client = create_client(config).await;
+    let token = access_token(&client, "admin", "read:api");
+
+    let response = client
+        .get("/api/modbus/map")
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+
+    let body: Value = serde_json::from_str(&response.into_string().await.expect("body"))
+        .expect("valid JSON response");
+    let registers = body["registers"].as_array().expect("registers array");
+
+    let concentration = find_register(registers, "input", FLOAT32_CONCENTRATION_REGISTER);
+    assert_eq!(concentration["name"], "concentration");
+    assert_eq!(concentration["data_type"], "float32");
+    assert_eq!(concentration["register_count"], 2);
+    assert_eq!(concentration["word_order"], "little_endian");
+    assert_eq!(concentration["scale_factor"], 1.0);
+}
+
+#[rocket::async_test]
+async fn test_modbus_map_requires_authentication() {
+    let client = create_client(get_test_config()).await;
+
+    let response = client.get("/api/modbus/map").dispatch().await;
+    assert_eq!(response.status(), Status::Unauthorized);
+}
+
+#[rocket::async_test]
+async fn test_modbus_map_follows_shared_thermal_state() {
+    // The configuration declares no regulator: the blocks come from the
+    // running regulators, as in the Modbus server
+    let config = get_test_config();
+    let thermal_state = create_shared_thermal_state();
+    {
+        let mut state = thermal_state.write().await;
+        for id in ["cell_b", "cell_a"] {
+            state
+                .initialize_regulator(
+                    id.to_string(),
+                    format!("Regulator {}", id),
+                    CurrentPidParams {
+                        kp: 1.0,
+                        ki: 0.1,
+                        kd: 0.01,
+                        setpoint_celsius: 25.0,
+                        output_min: -100.0,
+                        output_max: 100.0,
+                    },
+                )
+                .unwrap();
+        }
+    }
+    let expected = exposed_regulator_ids(&*thermal_state.read().await);
+    let client = create_client_with_thermal_state(config, Some(thermal_state)).await;
+    let token = access_token(&client, "admin", "read:api");
+
+    let response = client
+        .get("/api/modbus/map")
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+
+    let body: Value = serde_json::from_str(&response.into_string().await.expect("body"))
+        .expect("valid JSON response");
+    assert_eq!(expected, vec!["cell_a", "cell_b"]);
+    assert_eq!(body["thermal_regulators"], serde_json::json!(expected));
+
+    let registers = body["registers"].as_array().expect("registers array");
+    for (index, id) in expected.iter().enumerate() {
+        let temperature = find_register(registers, "input", thermal_block_base(index));
+        assert_eq!(temperature["name"], format!("thermal.{}.temperature", id));
+    }
+}
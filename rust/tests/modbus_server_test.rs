@@ -7,7 +7,8 @@
 //! These tests validate the Modbus server functionality by starting a server
 //! instance and connecting to it via a Modbus client. Various Modbus operations
 //! are tested including reading input registers, reading holding registers,
-//! writing to holding registers, and testing error conditions.
+//! writing to holding registers, and testing error conditions. The RTU
+//! transport is exercised over an in-memory serial line.
 
 use std::net::SocketAddr;
 use std::str::FromStr;
@@ -19,7 +20,22 @@ use tokio_modbus::{
     server::tcp::{accept_tcp_connection, Server},
 };
 
-use rust_photoacoustic::modbus::PhotoacousticModbusServer;
+use rust_photoacoustic::config::modbus::{ModbusValueEncoding, ModbusWordOrder};
+use rust_photoacoustic::modbus::register_map::encode_thermal_value;
+use rust_photoacoustic::modbus::register_map::{
+    decode_f32, decode_u32, encode_f32, encode_u32, MeasurementEncoding,
+    FLOAT32_CONCENTRATION_REGISTER, FLOAT32_STATUS_REGISTER,
+};
+use rust_photoacoustic::modbus::register_map::{
+    decode_thermal_value, THERMAL_REGULATOR_BLOCK_BASE, THERMAL_REGULATOR_BLOCK_SIZE,
+    THERMAL_REGULATOR_COUNT_REGISTER,
+};
+use rust_photoacoustic::modbus::rtu::serve_rtu;
+use rust_photoacoustic::modbus::{PhotoacousticModbusServer, RtuSlaveServer};
+use rust_photoacoustic::thermal_regulation::shared_state::{
+    CurrentPidParams, PidComponents, RegulatorStatus, SetpointLimits,
+};
+use rust_photoacoustic::thermal_regulation::{create_shared_thermal_state, SharedThermalState};
 
 // This allows us to use #[tokio::test]
 extern crate tokio;
@@ -27,6 +43,16 @@ extern crate tokio;
 /// Test utility function to start a Modbus server in the background
 async fn start_test_server(
 ) -> Result<(SocketAddr, tokio::task::JoinHandle<()>), Box<dyn std::error::Error>> {
+    start_test_server_with(PhotoacousticModbusServer::new).await
+}
+
+/// Test utility function to start a Modbus server built by `make_service`
+async fn start_test_server_with<F>(
+    make_service: F,
+) -> Result<(SocketAddr, tokio::task::JoinHandle<()>), Box<dyn std::error::Error>>
+where
+    F: Fn() -> PhotoacousticModbusServer + Clone + Send + Sync + 'static,
+{
     // Use port 0 to let the OS assign an available port
     let socket_addr = SocketAddr::from_str("127.0.0.1:0").unwrap();
     let listener = TcpListener::bind(socket_addr).await?;
@@ -36,10 +62,13 @@ async fn start_test_server(
     println!("Test server started on: {}", socket_addr);
 
     let server = Server::new(listener);
-    let photoacoustic_modbus_service = |_socket_addr| Ok(Some(PhotoacousticModbusServer::new()));
-
-    let on_connected = move |stream, socket_addr| async move {
-        accept_tcp_connection(stream, socket_addr, photoacoustic_modbus_service)
+    let on_connected = move |stream, socket_addr| {
+        let make_service = make_service.clone();
+        async move {
+            accept_tcp_connection(stream, socket_addr, move |_socket_addr| {
+                Ok(Some(make_service()))
+            })
+        }
     };
 
     let on_process_error = |err| {
@@ -234,3 +263,335 @@ async fn test_real_world_scenario() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Build a thermal state with two regulators, one running and one in fault
+async fn create_test_thermal_state() -> SharedThermalState {
+    let thermal_state = create_shared_thermal_state();
+    let mut state = thermal_state.write().await;
+
+    for (id, setpoint) in [("cell_a", 45.0), ("cell_b", 12.5)] {
+        state
+            .initialize_regulator(
+                id.to_string(),
+                format!("Regulator {}", id),
+                CurrentPidParams {
+                    kp: 1.0,
+                    ki: 0.1,
+                    kd: 0.01,
+                    setpoint_celsius: setpoint,
+                    output_min: -100.0,
+                    output_max: 100.0,
+                },
+            )
+            .unwrap();
+    }
+
+    let pid_components = PidComponents {
+        proportional: 1.0,
+        integral: 0.5,
+        derivative: 0.1,
+        feedforward: 0.0,
+        error: 1.5,
+    };
+    state
+        .update_regulator_data("cell_a", 43.5, 62.25, 45.0, pid_components.clone())
+        .unwrap();
+    state
+        .update_regulator_data("cell_b", 14.75, -35.5, 12.5, pid_components)
+        .unwrap();
+    for id in ["cell_a", "cell_b"] {
+        state
+            .set_setpoint_limits(
+                id,
+                SetpointLimits {
+                    min_celsius: 5.0,
+                    max_celsius: 60.0,
+                },
+            )
+            .unwrap();
+    }
+    state
+        .update_regulator_status(
+            "cell_b",
+            RegulatorStatus::Error {
+                message: "sensor disconnected".to_string(),
+            },
+        )
+        .unwrap();
+
+    drop(state);
+    thermal_state
+}
+
+#[tokio::test]
+async fn test_read_thermal_input_registers() -> Result<(), Box<dyn std::error::Error>> {
+    let thermal_state = create_test_thermal_state().await;
+    let (socket_addr, _server_handle) = start_test_server_with(move || {
+        PhotoacousticModbusServer::new().with_thermal_state(&thermal_state)
+    })
+    .await?;
+
+    let mut ctx = tcp::connect(socket_addr).await?;
+
+    // Regulator count
+    let count = ctx
+        .read_input_registers(THERMAL_REGULATOR_COUNT_REGISTER, 1)
+        .await??;
+    assert_eq!(count[0], 2);
+
+    // Regulators are ordered by identifier: cell_a first, then cell_b
+    let cell_a = ctx
+        .read_input_registers(THERMAL_REGULATOR_BLOCK_BASE, 4)
+        .await??;
+    assert_eq!(decode_thermal_value(cell_a[0]), Some(43.5));
+    assert_eq!(decode_thermal_value(cell_a[1]), Some(45.0));
+    assert_eq!(decode_thermal_value(cell_a[2]), Some(62.25));
+    assert_eq!(cell_a[3], 0);
+
+    let cell_b = ctx
+        .read_input_registers(
+            THERMAL_REGULATOR_BLOCK_BASE + THERMAL_REGULATOR_BLOCK_SIZE,
+            4,
+        )
+        .await??;
+    assert_eq!(decode_thermal_value(cell_b[0]), Some(14.75));
+    assert_eq!(decode_thermal_value(cell_b[1]), Some(12.5));
+    assert_eq!(decode_thermal_value(cell_b[2]), Some(-35.5));
+    assert_eq!(cell_b[3], 1); // Fault bit set for the regulator in error
+
+    // No third regulator block
+    let result = ctx
+        .read_input_registers(
+            THERMAL_REGULATOR_BLOCK_BASE + 2 * THERMAL_REGULATOR_BLOCK_SIZE,
+            1,
+        )
+        .await?;
+    assert!(result.is_err());
+
+    ctx.disconnect().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_write_thermal_setpoint() -> Result<(), Box<dyn std::error::Error>> {
+    let thermal_state = create_test_thermal_state().await;
+    let server_state = thermal_state.clone();
+    let (socket_addr, _server_handle) = start_test_server_with(move || {
+        PhotoacousticModbusServer::new().with_thermal_state(&server_state)
+    })
+    .await?;
+
+    let mut ctx = tcp::connect(socket_addr).await?;
+    let setpoint_register = THERMAL_REGULATOR_BLOCK_BASE + 1;
+
+    // The holding register mirrors the current target of cell_a
+    let data = ctx.read_holding_registers(setpoint_register, 1).await??;
+    assert_eq!(decode_thermal_value(data[0]), Some(45.0));
+
+    // Command a new setpoint
+    ctx.write_single_register(setpoint_register, encode_thermal_value(38.25))
+        .await??;
+
+    {
+        let mut state = thermal_state.write().await;
+        let regulator = state.get_regulator_history("cell_a").unwrap();
+        assert_eq!(regulator.current_pid_params.setpoint_celsius, 38.25);
+        assert_eq!(state.take_pending_setpoint("cell_a"), Some(38.25));
+    }
+
+    // The change is reflected in the monitoring input registers
+    let data = ctx.read_input_registers(setpoint_register, 1).await??;
+    assert_eq!(decode_thermal_value(data[0]), Some(38.25));
+
+    ctx.disconnect().await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_write_thermal_setpoint_out_of_range() -> Result<(), Box<dyn std::error::Error>> {
+    let thermal_state = create_test_thermal_state().await;
+    let server_state = thermal_state.clone();
+    let (socket_addr, _server_handle) = start_test_server_with(move || {
+        PhotoacousticModbusServer::new().with_thermal_state(&server_state)
+    })
+    .await?;
+
+    let mut ctx = tcp::connect(socket_addr).await?;
+    let cell_a_setpoint = THERMAL_REGULATOR_BLOCK_BASE + 1;
+    let cell_b_setpoint = cell_a_setpoint + THERMAL_REGULATOR_BLOCK_SIZE;
+
+    // Above the 60 °C safety limit
+    let result = ctx
+        .write_single_register(cell_a_setpoint, encode_thermal_value(95.0))
+        .await?;
+    assert!(result.is_err());
+    if let Err(error) = result {
+        assert_eq!(error.to_string(), "Illegal data value");
+    }
+
+    // A multi-register write with one invalid setpoint is refused as a whole
+    let values = [
+        encode_thermal_value(20.0),
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        encode_thermal_value(-10.0),
+    ];
+    let result = ctx
+        .write_multiple_registers(cell_a_setpoint, &values)
+        .await?;
+    assert!(result.is_err());
+
+    {
+        let mut state = thermal_state.write().await;
+        let cell_a = state.get_regulator_history("cell_a").unwrap();
+        assert_eq!(cell_a.current_pid_params.setpoint_celsius, 45.0);
+        let cell_b = state.get_regulator_history("cell_b").unwrap();
+        assert_eq!(cell_b.current_pid_params.setpoint_celsius, 12.5);
+        assert_eq!(state.take_pending_setpoint("cell_a"), None);
+        assert_eq!(state.take_pending_setpoint("cell_b"), None);
+    }
+
+    let data = ctx.read_holding_registers(cell_b_setpoint, 1).await??;
+    assert_eq!(decode_thermal_value(data[0]), Some(12.5));
+
+    ctx.disconnect().await?;
+
+    Ok(())
+}
+
+/// Test utility function to serve `server` as RTU slave `slave_id` over an in-memory serial line
+///
+/// Returns the master side of the line.
+fn start_test_rtu_server(
+    slave_id: u8,
+    server: PhotoacousticModbusServer,
+) -> tokio::io::DuplexStream {
+    let (master_side, slave_side) = tokio::io::duplex(256);
+    tokio::spawn(serve_rtu(slave_side, RtuSlaveServer::new(slave_id, server)));
+    master_side
+}
+
+#[tokio::test]
+async fn test_rtu_read_registers() -> Result<(), Box<dyn std::error::Error>> {
+    let line = start_test_rtu_server(17, PhotoacousticModbusServer::new());
+    let mut ctx = rtu::attach_slave(line, Slave(17));
+
+    // Same register map as the TCP transport
+    let data = ctx.read_input_registers(0, 6).await??;
+    assert_eq!(data[0], 1234 * 10);
+    assert_eq!(data[1], 5678);
+    assert_eq!(data[2], 1000 * 10);
+
+    ctx.write_single_register(2, 55).await??;
+    let data = ctx.read_holding_registers(0, 4).await??;
+    assert_eq!(data, vec![10, 20, 55, 40]);
+
+    // Invalid addresses raise the same exceptions
+    let result = ctx.read_input_registers(50, 1).await?;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rtu_write_thermal_setpoint() -> Result<(), Box<dyn std::error::Error>> {
+    let thermal_state = create_test_thermal_state().await;
+    let line = start_test_rtu_server(
+        3,
+        PhotoacousticModbusServer::new().with_thermal_state(&thermal_state),
+    );
+    let mut ctx = rtu::attach_slave(line, Slave(3));
+
+    let cell_a_setpoint = THERMAL_REGULATOR_BLOCK_BASE + 1;
+    ctx.write_single_register(cell_a_setpoint, encode_thermal_value(30.0))
+        .await??;
+
+    let mut state = thermal_state.write().await;
+    assert_eq!(state.take_pending_setpoint("cell_a"), Some(30.0));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_rtu_ignores_other_slaves() -> Result<(), Box<dyn std::error::Error>> {
+    let line = start_test_rtu_server(17, PhotoacousticModbusServer::new());
+    let mut ctx = rtu::attach_slave(line, Slave(18));
+
+    // Requests for another unit on the bus must stay unanswered
+    let result = time::timeout(Duration::from_millis(300), ctx.read_input_registers(0, 1)).await;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_float32_word_order_encoding() {
+    // 1.0 = 0x3F800000, 123.456 = 0x42F6E979, -2.5 = 0xC0200000
+    let known = [
+        (1.0f32, [0x3F80, 0x0000]),
+        (123.456f32, [0x42F6, 0xE979]),
+        (-2.5f32, [0xC020, 0x0000]),
+    ];
+
+    for (value, big_endian) in known {
+        let encoded = encode_f32(value, ModbusWordOrder::BigEndian);
+        assert_eq!(encoded, big_endian);
+        assert_eq!(decode_f32(encoded, ModbusWordOrder::BigEndian), value);
+
+        let encoded = encode_f32(value, ModbusWordOrder::LittleEndian);
+        assert_eq!(encoded, [big_endian[1], big_endian[0]]);
+        assert_eq!(decode_f32(encoded, ModbusWordOrder::LittleEndian), value);
+    }
+
+    let timestamp = 1_760_000_000u32;
+    for word_order in [ModbusWordOrder::BigEndian, ModbusWordOrder::LittleEndian] {
+        assert_eq!(
+            decode_u32(encode_u32(timestamp, word_order), word_order),
+            timestamp
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_read_float32_input_registers() -> Result<(), Box<dyn std::error::Error>> {
+    for word_order in [ModbusWordOrder::BigEndian, ModbusWordOrder::LittleEndian] {
+        let encoding = MeasurementEncoding {
+            value_encoding: ModbusValueEncoding::Float32,
+            word_order,
+        };
+        let (socket_addr, _server_handle) = start_test_server_with(move || {
+            let server = PhotoacousticModbusServer::new().with_measurement_encoding(encoding);
+            server.update_measurement_data(2012.5, 0.0421, 815.25);
+            server
+        })
+        .await?;
+
+        let mut ctx = tcp::connect(socket_addr).await?;
+        let data = ctx
+            .read_input_registers(0, FLOAT32_STATUS_REGISTER + 1)
+            .await??;
+
+        assert_eq!(decode_f32([data[0], data[1]], word_order), 2012.5);
+        assert_eq!(decode_f32([data[2], data[3]], word_order), 0.0421);
+        let concentration = FLOAT32_CONCENTRATION_REGISTER as usize;
+        assert_eq!(
+            decode_f32([data[concentration], data[concentration + 1]], word_order),
+            815.25
+        );
+        assert!(decode_u32([data[6], data[7]], word_order) > 0);
+        assert_eq!(data[FLOAT32_STATUS_REGISTER as usize], 0);
+
+        ctx.disconnect().await?;
+    }
+
+    Ok(())
+}
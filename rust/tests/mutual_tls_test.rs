@@ -0,0 +1,182 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the client certificate authentication (mutual TLS)
+//!
+//! A CA, a server certificate and client certificates are generated for each
+//! test, and the server is launched with TLS on a local port.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_mandatory_client_certificate`] | A valid client certificate reaches a protected route, a connection without certificate is refused |
+//! | [`test_optional_client_certificate`] | Without certificate the request is 401, an unknown subject is 401, a certificate of another CA is refused |
+
+use auth_macros::protect_get;
+use base64::Engine;
+use rcgen::{BasicConstraints, CertificateParams, DnType, IsCa, Issuer, KeyPair, KeyUsagePurpose};
+use rocket::config::LogLevel;
+use rocket::http::Status;
+use rocket::routes;
+use rust_photoacoustic::config::{ClientCertificate, Config, MutualTlsConfig};
+use rust_photoacoustic::visualization::auth::oauth2::OxideState;
+use rust_photoacoustic::visualization::server::configure_mutual_tls;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// Each test launches its own server so they can run in parallel
+const TEST_PORT: u16 = 8100;
+
+#[protect_get("/api/device", "read:api")]
+fn device_route(
+    bearer: rust_photoacoustic::visualization::auth::guards::bearer::OAuthBearer,
+) -> String {
+    bearer.user_info.user_id.clone()
+}
+
+/// Certificate authority issuing server and client certificates
+struct TestCa {
+    cert_pem: String,
+    issuer: Issuer<'static, KeyPair>,
+}
+
+impl TestCa {
+    fn generate(name: &str) -> Self {
+        let key = KeyPair::generate().expect("CA key generated");
+        let mut params = CertificateParams::new(Vec::<String>::new()).unwrap();
+        params.distinguished_name.push(DnType::CommonName, name);
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign];
+        let cert = params.self_signed(&key).expect("CA certificate generated");
+        Self {
+            cert_pem: cert.pem(),
+            issuer: Issuer::new(params, key),
+        }
+    }
+
+    /// Issue a certificate, returning its PEM and the PEM of its private key
+    fn issue(&self, common_name: &str, subject_alt_names: &[&str]) -> (String, String) {
+        let key = KeyPair::generate().expect("key generated");
+        let mut params = CertificateParams::new(
+            subject_alt_names
+                .iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+        params
+            .distinguished_name
+            .push(DnType::CommonName, common_name);
+        let cert = params
+            .signed_by(&key, &self.issuer)
+            .expect("certificate signed");
+        (cert.pem(), key.serialize_pem())
+    }
+
+    /// HTTPS client trusting this CA, presenting `identity` if given
+    fn client(&self, identity: Option<&(String, String)>) -> reqwest::Client {
+        let mut builder = reqwest::Client::builder()
+            .add_root_certificate(
+                reqwest::Certificate::from_pem(self.cert_pem.as_bytes()).expect("CA certificate"),
+            )
+            .timeout(Duration::from_secs(5));
+        if let Some((cert, key)) = identity {
+            builder = builder.identity(
+                reqwest::Identity::from_pem(format!("{}{}", cert, key).as_bytes())
+                    .expect("client identity"),
+            );
+        }
+        builder.build().expect("HTTPS client")
+    }
+}
+
+/// Launch a TLS server on `port` verifying client certificates against `ca`
+///
+/// The `device-01` certificate subject is granted `read:api`.
+async fn launch_server(ca: &TestCa, port: u16, mandatory: bool) {
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    let (server_cert, server_key) = ca.issue("localhost", &["localhost", "127.0.0.1"]);
+    let mutual_tls = MutualTlsConfig {
+        ca_certs: base64::engine::general_purpose::STANDARD.encode(&ca.cert_pem),
+        mandatory,
+    };
+
+    let mut config = Config::default();
+    config.visualization.enable_local_visualization = false;
+    config.visualization.mutual_tls = Some(mutual_tls.clone());
+    config.access.client_certificates = vec![ClientCertificate {
+        common_name: "device-01".to_string(),
+        permissions: vec!["read:api".to_string()],
+    }];
+
+    let figment = rocket::Config::figment()
+        .merge(("address", "127.0.0.1"))
+        .merge(("port", port))
+        .merge(("log_level", LogLevel::Off))
+        .merge(("hmac_secret", "test-mutual-tls".to_string()))
+        .merge(("tls.certs", server_cert.into_bytes()))
+        .merge(("tls.key", server_key.into_bytes()));
+    let figment = configure_mutual_tls(figment, Some(&mutual_tls)).expect("mutual TLS figment");
+
+    let rocket = rocket::custom(figment.clone())
+        .manage(Arc::new(RwLock::new(config)))
+        .manage(OxideState::preconfigured(figment))
+        .mount("/", routes![device_route]);
+    tokio::spawn(async move {
+        let _ = rocket.launch().await;
+    });
+    // Give the server time to start up
+    tokio::time::sleep(Duration::from_millis(500)).await;
+}
+
+#[tokio::test]
+async fn test_mandatory_client_certificate() {
+    let ca = TestCa::generate("Test CA");
+    launch_server(&ca, TEST_PORT, true).await;
+    let url = format!("https://127.0.0.1:{}/api/device", TEST_PORT);
+
+    let device = ca.issue("device-01", &[]);
+    let response = ca
+        .client(Some(&device))
+        .get(&url)
+        .send()
+        .await
+        .expect("request with client certificate");
+    assert_eq!(response.status().as_u16(), Status::Ok.code);
+    assert_eq!(response.text().await.unwrap(), "cert:device-01");
+
+    // The TLS handshake fails without client certificate
+    assert!(ca.client(None).get(&url).send().await.is_err());
+}
+
+#[tokio::test]
+async fn test_optional_client_certificate() {
+    let ca = TestCa::generate("Test CA");
+    launch_server(&ca, TEST_PORT + 1, false).await;
+    let url = format!("https://127.0.0.1:{}/api/device", TEST_PORT + 1);
+
+    // Without certificate, the request needs another credential
+    let response = ca
+        .client(None)
+        .get(&url)
+        .send()
+        .await
+        .expect("request without client certificate");
+    assert_eq!(response.status().as_u16(), Status::Unauthorized.code);
+
+    // A verified certificate whose subject is not configured is not authenticated
+    let unknown = ca.issue("device-99", &[]);
+    let response = ca
+        .client(Some(&unknown))
+        .get(&url)
+        .send()
+        .await
+        .expect("request with unknown subject");
+    assert_eq!(response.status().as_u16(), Status::Unauthorized.code);
+
+    // A certificate of another CA fails the TLS handshake
+    let other_ca = TestCa::generate("Other CA");
+    let forged = other_ca.issue("device-01", &[]);
+    assert!(ca.client(Some(&forged)).get(&url).send().await.is_err());
+}
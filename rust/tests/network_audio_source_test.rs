@@ -0,0 +1,275 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the network audio source
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_rtp_in_order`] | RTP L16 packets are decoded into frames across a sequence number wrap-around |
+//! | [`test_reordered_packets`] | Packets received out of order are published in sequence order |
+//! | [`test_lost_packet_concealed`] | A missing packet is replaced by silence once the reorder window is full, and a late copy is dropped |
+//! | [`test_straggler_dropped_without_resync`] | A packet far behind the stream is counted as late and does not flush the pending packets |
+//! | [`test_invalid_packets_dropped`] | Truncated packets are counted and do not disturb the stream |
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::{
+    AudioStreamConsumer, NetworkAudioSource, NetworkStats, RealTimeAudioSource, SharedAudioStream,
+};
+use rust_photoacoustic::config::photoacoustic::{
+    NetworkProtocol, NetworkSampleFormat, NetworkSourceConfig,
+};
+use rust_photoacoustic::config::PhotoacousticConfig;
+use std::net::UdpSocket;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Stereo samples per packet
+const PACKET_SAMPLES: usize = 32;
+/// Samples per published frame
+const FRAME_SIZE: usize = 64;
+
+fn network_source(
+    protocol: NetworkProtocol,
+    sample_format: NetworkSampleFormat,
+    reorder_window: usize,
+) -> Result<NetworkAudioSource> {
+    NetworkAudioSource::new(PhotoacousticConfig {
+        sample_rate: 48000,
+        frame_size: FRAME_SIZE as u16,
+        network_source: Some(NetworkSourceConfig {
+            bind_address: "127.0.0.1:0".to_string(),
+            protocol,
+            sample_format,
+            reorder_window,
+        }),
+        ..Default::default()
+    })
+}
+
+/// 16-bit samples of channel A and channel B of a packet
+fn packet_samples(index: usize) -> Vec<(i16, i16)> {
+    (0..PACKET_SAMPLES)
+        .map(|i| {
+            let value = (index * PACKET_SAMPLES + i) as i16 * 100;
+            (value, -value)
+        })
+        .collect()
+}
+
+fn rtp_packet(sequence: u16, samples: &[(i16, i16)]) -> Vec<u8> {
+    let timestamp = sequence as u32 * PACKET_SAMPLES as u32;
+    let mut packet = vec![0x80, 96];
+    packet.extend(sequence.to_be_bytes());
+    packet.extend(timestamp.to_be_bytes());
+    packet.extend(0x1234_5678u32.to_be_bytes());
+    for (sample_a, sample_b) in samples {
+        packet.extend(sample_a.to_be_bytes());
+        packet.extend(sample_b.to_be_bytes());
+    }
+    packet
+}
+
+fn raw_packet(sequence: u32, samples: &[(i16, i16)]) -> Vec<u8> {
+    let mut packet = sequence.to_be_bytes().to_vec();
+    for (sample_a, sample_b) in samples {
+        packet.extend((*sample_a as f32 / 32768.0).to_le_bytes());
+        packet.extend((*sample_b as f32 / 32768.0).to_le_bytes());
+    }
+    packet
+}
+
+fn i16_to_f32(sample: i16) -> f32 {
+    if sample >= 0 {
+        sample as f32 / i16::MAX as f32
+    } else {
+        sample as f32 / -(i16::MIN as f32)
+    }
+}
+
+fn send(source: &NetworkAudioSource, packets: &[Vec<u8>]) -> Result<()> {
+    let sender = UdpSocket::bind("127.0.0.1:0")?;
+    for packet in packets {
+        sender.send_to(packet, source.local_addr()?)?;
+    }
+    Ok(())
+}
+
+/// Stream the source and collect the channel samples of `frames` frames
+async fn receive(
+    source: &mut NetworkAudioSource,
+    packets: &[Vec<u8>],
+    frames: usize,
+) -> Result<(Vec<f32>, Vec<f32>)> {
+    let stream = Arc::new(SharedAudioStream::new(64));
+    let mut consumer = AudioStreamConsumer::new(&stream);
+    source.start_streaming(Arc::clone(&stream)).await?;
+    send(source, packets)?;
+
+    let (mut channel_a, mut channel_b) = (Vec::new(), Vec::new());
+    for frame_number in 1..=frames as u64 {
+        let frame = tokio::time::timeout(Duration::from_secs(5), consumer.next_frame())
+            .await?
+            .expect("frame published");
+        assert_eq!(frame.frame_number, frame_number);
+        assert_eq!(frame.sample_rate, 48000);
+        assert_eq!(frame.channel_a.len(), FRAME_SIZE);
+        channel_a.extend(frame.channel_a);
+        channel_b.extend(frame.channel_b);
+    }
+    Ok((channel_a, channel_b))
+}
+
+/// Wait until the statistics of the source satisfy `condition`
+async fn wait_for_stats(source: &NetworkAudioSource, condition: impl Fn(&NetworkStats) -> bool) {
+    for _ in 0..100 {
+        if condition(&source.stats()) {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("unexpected statistics {:?}", source.stats());
+}
+
+#[tokio::test]
+async fn test_rtp_in_order() -> Result<()> {
+    let mut source = network_source(NetworkProtocol::Rtp, NetworkSampleFormat::S16be, 4)?;
+    let packets = (0..8)
+        .map(|index| rtp_packet(65532u16.wrapping_add(index as u16), &packet_samples(index)))
+        .collect::<Vec<_>>();
+
+    let (channel_a, channel_b) = receive(&mut source, &packets, 4).await?;
+    source.stop_streaming().await?;
+    assert!(!source.is_streaming());
+
+    let expected = (0..8).flat_map(packet_samples).collect::<Vec<_>>();
+    let expected_a = expected
+        .iter()
+        .map(|(a, _)| i16_to_f32(*a))
+        .collect::<Vec<_>>();
+    let expected_b = expected
+        .iter()
+        .map(|(_, b)| i16_to_f32(*b))
+        .collect::<Vec<_>>();
+    assert_eq!(channel_a, expected_a);
+    assert_eq!(channel_b, expected_b);
+
+    let stats = source.stats();
+    assert_eq!(stats.packets_received, 8);
+    assert_eq!(stats.packets_lost, 0);
+    assert_eq!(stats.packets_reordered, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reordered_packets() -> Result<()> {
+    let mut source = network_source(NetworkProtocol::Rtp, NetworkSampleFormat::S16be, 4)?;
+    let packets = [0, 2, 1, 3, 5, 4, 6, 7]
+        .map(|index| rtp_packet(100 + index as u16, &packet_samples(index)));
+
+    let (channel_a, _) = receive(&mut source, &packets, 4).await?;
+    source.stop_streaming().await?;
+
+    let expected_a = (0..8)
+        .flat_map(packet_samples)
+        .map(|(a, _)| i16_to_f32(a))
+        .collect::<Vec<_>>();
+    assert_eq!(channel_a, expected_a);
+
+    let stats = source.stats();
+    assert_eq!(stats.packets_reordered, 2);
+    assert_eq!(stats.packets_lost, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_lost_packet_concealed() -> Result<()> {
+    let mut source = network_source(NetworkProtocol::Raw, NetworkSampleFormat::F32le, 2)?;
+    // Packet 2 is missing until packet 5 overflows the reorder window
+    let packets =
+        [0, 1, 3, 4, 5, 6, 7, 8].map(|index| raw_packet(index as u32, &packet_samples(index)));
+
+    let (channel_a, channel_b) = receive(&mut source, &packets, 4).await?;
+
+    let mut expected = Vec::new();
+    for index in 0..8 {
+        if index == 2 {
+            expected.extend([(0.0, 0.0); PACKET_SAMPLES]);
+        } else {
+            expected.extend(
+                packet_samples(index)
+                    .iter()
+                    .map(|(a, b)| (*a as f32 / 32768.0, *b as f32 / 32768.0)),
+            );
+        }
+    }
+    assert_eq!(
+        channel_a,
+        expected.iter().map(|(a, _)| *a).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        channel_b,
+        expected.iter().map(|(_, b)| *b).collect::<Vec<_>>()
+    );
+    assert_eq!(source.stats().packets_lost, 1);
+
+    // The lost packet arriving afterwards is dropped
+    send(&source, &[raw_packet(2, &packet_samples(2))])?;
+    wait_for_stats(&source, |stats| stats.packets_late == 1).await;
+    source.stop_streaming().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_straggler_dropped_without_resync() -> Result<()> {
+    let mut source = network_source(NetworkProtocol::Raw, NetworkSampleFormat::F32le, 4)?;
+    // Packet 1002 is pending when a packet from long ago arrives
+    let mut packets = [0, 1, 3]
+        .map(|index| raw_packet(1000 + index as u32, &packet_samples(index)))
+        .to_vec();
+    packets.push(raw_packet(10, &packet_samples(0)));
+    packets.extend(
+        [2, 4, 5, 6, 7].map(|index| raw_packet(1000 + index as u32, &packet_samples(index))),
+    );
+
+    let (channel_a, _) = receive(&mut source, &packets, 4).await?;
+    wait_for_stats(&source, |stats| stats.packets_received == 9).await;
+    source.stop_streaming().await?;
+
+    let expected_a = (0..8)
+        .flat_map(packet_samples)
+        .map(|(a, _)| a as f32 / 32768.0)
+        .collect::<Vec<_>>();
+    assert_eq!(channel_a, expected_a);
+
+    let stats = source.stats();
+    assert_eq!(stats.packets_late, 1);
+    assert_eq!(stats.packets_lost, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_packets_dropped() -> Result<()> {
+    let mut source = network_source(NetworkProtocol::Rtp, NetworkSampleFormat::S16be, 4)?;
+    let mut packets = Vec::new();
+    for index in 0..4 {
+        packets.push(rtp_packet(index as u16, &packet_samples(index)));
+        // Shorter than an RTP header, then an odd payload length
+        packets.push(vec![0x80; 6]);
+        let mut truncated = rtp_packet(50, &packet_samples(index));
+        truncated.pop();
+        packets.push(truncated);
+    }
+
+    let (channel_a, _) = receive(&mut source, &packets, 2).await?;
+    wait_for_stats(&source, |stats| stats.packets_invalid == 8).await;
+    source.stop_streaming().await?;
+
+    let expected_a = (0..4)
+        .flat_map(packet_samples)
+        .map(|(a, _)| i16_to_f32(a))
+        .collect::<Vec<_>>();
+    assert_eq!(channel_a, expected_a);
+    assert_eq!(source.stats().packets_received, 4);
+    Ok(())
+}
@@ -0,0 +1,98 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Tests for the noise characterization utility
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_tone_plus_noise_snr`] | The SNR and noise RMS of a tone plus Gaussian noise match their expected values |
+//! | [`test_off_bin_tone_snr`] | A tone between two FFT bins gives the same SNR |
+//! | [`test_spectral_flatness`] | White noise is flat, a pure tone is not |
+//! | [`test_degenerate_signals`] | Short and silent signals return all-zero statistics |
+
+use rust_photoacoustic::utility::noise_generator::NoiseGenerator;
+use rust_photoacoustic::utility::noise_stats::{noise_stats, NoiseStats};
+
+const SAMPLE_RATE: u32 = 48000;
+
+/// One second of a sine tone plus Gaussian noise of the given RMS level
+fn tone_plus_noise(frequency: f32, amplitude: f32, noise_rms: f32, seed: u32) -> Vec<f32> {
+    let mut generator = NoiseGenerator::new(seed);
+    (0..SAMPLE_RATE)
+        .map(|i| {
+            // Phase computed in f64 to keep its rounding out of the noise floor
+            let t = i as f64 / SAMPLE_RATE as f64;
+            let tone = (2.0 * std::f64::consts::PI * frequency as f64 * t).sin() as f32;
+            amplitude * tone + noise_rms * generator.random_gaussian()
+        })
+        .collect()
+}
+
+/// SNR in dB of a sine of the given amplitude in noise of the given RMS level
+fn expected_snr_db(amplitude: f32, noise_rms: f32) -> f32 {
+    10.0 * ((amplitude * amplitude / 2.0) / (noise_rms * noise_rms)).log10()
+}
+
+#[test]
+fn test_tone_plus_noise_snr() {
+    for (amplitude, noise_rms) in [(0.5, 0.05), (0.5, 0.2), (0.1, 0.001)] {
+        let samples = tone_plus_noise(2000.0, amplitude, noise_rms, 12345);
+        let stats = noise_stats(&samples, SAMPLE_RATE);
+        let expected = expected_snr_db(amplitude, noise_rms);
+
+        assert!(
+            (stats.snr_db - expected).abs() < 0.5,
+            "SNR {} dB, expected {} dB",
+            stats.snr_db,
+            expected
+        );
+        assert!(
+            (stats.noise_rms - noise_rms).abs() < noise_rms * 0.05,
+            "noise RMS {}, expected {}",
+            stats.noise_rms,
+            noise_rms
+        );
+        assert!((stats.dominant_frequency - 2000.0).abs() <= 1.0);
+        let signal_rms = (amplitude * amplitude / 2.0 + noise_rms * noise_rms).sqrt();
+        assert!((stats.signal_rms - signal_rms).abs() < signal_rms * 0.02);
+    }
+}
+
+#[test]
+fn test_off_bin_tone_snr() {
+    let samples = tone_plus_noise(1234.5, 0.5, 0.05, 777);
+    let stats = noise_stats(&samples, SAMPLE_RATE);
+
+    assert!((stats.snr_db - expected_snr_db(0.5, 0.05)).abs() < 0.5);
+    assert!((stats.dominant_frequency - 1234.5).abs() <= 1.0);
+}
+
+#[test]
+fn test_spectral_flatness() {
+    let noise = tone_plus_noise(1000.0, 0.0, 0.1, 42);
+    let flatness = noise_stats(&noise, SAMPLE_RATE).spectral_flatness;
+    // The power of Gaussian noise bins is exponentially distributed,
+    // whose geometric over arithmetic mean ratio is exp(-γ) ≈ 0.56
+    assert!(flatness > 0.45 && flatness < 0.65, "flatness {}", flatness);
+
+    let tone = tone_plus_noise(1000.0, 0.5, 0.0, 42);
+    let stats = noise_stats(&tone, SAMPLE_RATE);
+    assert!(
+        stats.spectral_flatness < 0.01,
+        "{}",
+        stats.spectral_flatness
+    );
+    assert!(stats.snr_db > 60.0, "{}", stats.snr_db);
+}
+
+#[test]
+fn test_degenerate_signals() {
+    assert_eq!(noise_stats(&[], SAMPLE_RATE), NoiseStats::default());
+    assert_eq!(noise_stats(&[0.5; 8], SAMPLE_RATE), NoiseStats::default());
+    // A constant signal has no power once its DC level is removed
+    assert_eq!(
+        noise_stats(&[0.5; 1024], SAMPLE_RATE),
+        NoiseStats::default()
+    );
+}
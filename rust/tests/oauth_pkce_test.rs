@@ -0,0 +1,236 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for PKCE (RFC 7636) in the authorization code flow
+//!
+//! Each test logs in through `POST /login`, grants consent through
+//! `POST /authorize?allow=true` and exchanges the resulting code at `POST /token`:
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_pkce_s256_round_trip`] | A code obtained with an S256 challenge is exchanged with the matching verifier |
+//! | [`test_pkce_mismatched_verifier_rejected`] | A fresh code is not exchanged with a wrong verifier |
+//! | [`test_pkce_missing_verifier_rejected`] | A code bound to a challenge requires a verifier |
+//! | [`test_pkce_plain_method_rejected`] | A `plain` challenge is refused even for clients without `require_pkce` |
+//! | [`test_pkce_required_client_without_challenge`] | `require_pkce` clients cannot obtain a code without a challenge |
+//! | [`test_pkce_advertised_in_discovery`] | The discovery document only lists the `S256` challenge method |
+
+use base64::Engine;
+use reqwest::Url;
+use rocket::http::{ContentType, Status};
+use rust_photoacoustic::config::access::Client;
+use rust_photoacoustic::config::Config;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+mod common;
+use common::{test_figment, TEST_HMAC_SECRET};
+
+const REDIRECT_URI: &str = "http://localhost:8080/client/";
+
+async fn build_test_client(config: Config) -> rocket::local::asynchronous::Client {
+    let rocket = rust_photoacoustic::visualization::server::build_rocket(
+        test_figment(),
+        Arc::new(RwLock::new(config)),
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+    rocket::local::asynchronous::Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance")
+}
+
+fn test_config() -> Config {
+    let mut config = Config::default();
+    config.visualization.hmac_secret = TEST_HMAC_SECRET.to_string();
+    config
+}
+
+/// Generate a PKCE verifier and its S256 challenge
+fn s256_pair() -> (String, String) {
+    let verifier: String = rand::random::<[u8; 32]>()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let challenge = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .encode(Sha256::digest(verifier.as_bytes()));
+    (verifier, challenge)
+}
+
+/// Log in as the default admin user and grant consent for `LaserSmartClient`
+///
+/// Returns the authorization code, or `None` when the server did not redirect
+/// back to the client with a code.
+async fn authorization_code(
+    client: &rocket::local::asynchronous::Client,
+    code_challenge: Option<&str>,
+) -> Option<String> {
+    authorization_code_with_method(client, code_challenge, "S256").await
+}
+
+/// Same as [`authorization_code`] with the given `code_challenge_method`
+async fn authorization_code_with_method(
+    client: &rocket::local::asynchronous::Client,
+    code_challenge: Option<&str>,
+    code_challenge_method: &str,
+) -> Option<String> {
+    let mut params = vec![
+        ("response_type", "code"),
+        ("client_id", "LaserSmartClient"),
+        ("redirect_uri", REDIRECT_URI),
+        ("scope", "openid read:api"),
+    ];
+    if let Some(challenge) = code_challenge {
+        params.push(("code_challenge", challenge));
+        params.push(("code_challenge_method", code_challenge_method));
+    }
+
+    let mut login_form = params.clone();
+    login_form.push(("username", "admin"));
+    login_form.push(("password", "admin123"));
+    let login = client
+        .post("/login")
+        .header(ContentType::Form)
+        .body(serde_urlencoded::to_string(&login_form).unwrap())
+        .dispatch()
+        .await;
+    assert_eq!(login.status(), Status::Found, "login succeeds");
+
+    let consent = client
+        .post(format!(
+            "/authorize?{}&allow=true",
+            serde_urlencoded::to_string(&params).unwrap()
+        ))
+        .dispatch()
+        .await;
+    let location = consent.headers().get_one("Location")?;
+    Url::parse(location)
+        .ok()?
+        .query_pairs()
+        .find(|(key, _)| key == "code")
+        .map(|(_, value)| value.into_owned())
+}
+
+/// Exchange an authorization code at the token endpoint
+async fn exchange_code(
+    client: &rocket::local::asynchronous::Client,
+    code: &str,
+    code_verifier: Option<&str>,
+) -> Status {
+    let mut form = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", REDIRECT_URI),
+        ("client_id", "LaserSmartClient"),
+    ];
+    if let Some(verifier) = code_verifier {
+        form.push(("code_verifier", verifier));
+    }
+    client
+        .post("/token")
+        .header(ContentType::Form)
+        .body(serde_urlencoded::to_string(&form).unwrap())
+        .dispatch()
+        .await
+        .status()
+}
+
+#[rocket::async_test]
+async fn test_pkce_s256_round_trip() {
+    let client = build_test_client(test_config()).await;
+    let (verifier, challenge) = s256_pair();
+
+    let code = authorization_code(&client, Some(&challenge))
+        .await
+        .expect("authorization code issued");
+    assert_eq!(
+        exchange_code(&client, &code, Some(&verifier)).await,
+        Status::Ok
+    );
+}
+
+#[rocket::async_test]
+async fn test_pkce_mismatched_verifier_rejected() {
+    let client = build_test_client(test_config()).await;
+    let (_, challenge) = s256_pair();
+    let (other_verifier, _) = s256_pair();
+
+    let code = authorization_code(&client, Some(&challenge))
+        .await
+        .expect("authorization code issued");
+    assert_eq!(
+        exchange_code(&client, &code, Some(&other_verifier)).await,
+        Status::BadRequest
+    );
+}
+
+#[rocket::async_test]
+async fn test_pkce_missing_verifier_rejected() {
+    let client = build_test_client(test_config()).await;
+    let (_, challenge) = s256_pair();
+
+    let code = authorization_code(&client, Some(&challenge))
+        .await
+        .expect("authorization code issued");
+    assert_eq!(
+        exchange_code(&client, &code, None).await,
+        Status::BadRequest
+    );
+}
+
+#[rocket::async_test]
+async fn test_pkce_plain_method_rejected() {
+    let client = build_test_client(test_config()).await;
+    let (verifier, _) = s256_pair();
+
+    assert!(
+        authorization_code_with_method(&client, Some(&verifier), "plain")
+            .await
+            .is_none()
+    );
+}
+
+#[rocket::async_test]
+async fn test_pkce_required_client_without_challenge() {
+    let mut config = test_config();
+    config.access.clients = vec![Client {
+        require_pkce: true,
+        ..Client::default()
+    }];
+    let client = build_test_client(config).await;
+
+    assert!(authorization_code(&client, None).await.is_none());
+
+    // The same client still completes the flow with a challenge
+    let (verifier, challenge) = s256_pair();
+    let code = authorization_code(&client, Some(&challenge))
+        .await
+        .expect("authorization code issued");
+    assert_eq!(
+        exchange_code(&client, &code, Some(&verifier)).await,
+        Status::Ok
+    );
+}
+
+#[rocket::async_test]
+async fn test_pkce_advertised_in_discovery() {
+    let client = build_test_client(test_config()).await;
+    let response = client
+        .get("/.well-known/openid-configuration")
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+
+    let body: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    let methods = body["code_challenge_methods_supported"]
+        .as_array()
+        .expect("code_challenge_methods_supported is advertised");
+    assert_eq!(methods, &vec![Value::from("S256")]);
+}
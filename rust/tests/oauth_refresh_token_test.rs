@@ -0,0 +1,230 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the OAuth 2.0 `refresh_token` grant
+//!
+//! Tokens are issued directly through the managed [`OxideState`] issuer so that
+//! the tests focus on `POST /token` with `grant_type=refresh_token`:
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_refresh_token_grant_issues_new_tokens`] | A valid refresh token yields a new access and refresh token |
+//! | [`test_refresh_token_is_rotated`] | A used refresh token cannot be used again, its successor can |
+//! | [`test_replayed_refresh_token_revokes_family`] | Replaying a rotated token also revokes its successor |
+//! | [`test_refresh_token_lifetime_from_access_config`] | Per-client refresh lifetimes are honoured |
+//! | [`test_declared_client_gets_default_refresh_lifetime`] | A client declared without `refresh_token_lifetime` gets the same lifetime as the built-in client |
+
+use chrono::{Duration, Utc};
+use oxide_auth::primitives::grant::{Extensions, Grant};
+use oxide_auth::primitives::issuer::Issuer;
+use rocket::http::{ContentType, Status};
+use rust_photoacoustic::config::access::Client;
+use rust_photoacoustic::config::Config;
+use rust_photoacoustic::visualization::auth::OxideState;
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+mod common;
+use common::{test_figment, TEST_HMAC_SECRET};
+
+async fn build_test_client(config: Config) -> rocket::local::asynchronous::Client {
+    let rocket = rust_photoacoustic::visualization::server::build_rocket(
+        test_figment(),
+        Arc::new(RwLock::new(config)),
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+    rocket::local::asynchronous::Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance")
+}
+
+fn test_config() -> Config {
+    let mut config = Config::default();
+    config.visualization.hmac_secret = TEST_HMAC_SECRET.to_string();
+    config
+}
+
+/// Issue an access/refresh token pair for `client_id` through the server's issuer
+fn issue_tokens(client: &rocket::local::asynchronous::Client, client_id: &str) -> (String, String) {
+    let state = client
+        .rocket()
+        .state::<OxideState>()
+        .expect("OxideState is managed");
+    let grant = Grant {
+        owner_id: "admin".to_string(),
+        client_id: client_id.to_string(),
+        scope: "read:api".parse().unwrap(),
+        redirect_uri: "https://localhost:8080/client/".parse().unwrap(),
+        until: Utc::now() + Duration::minutes(5),
+        extensions: Extensions::new(),
+    };
+    let issued = state
+        .issuer
+        .lock()
+        .unwrap()
+        .issue(grant)
+        .expect("token issued");
+    (issued.token, issued.refresh.expect("refresh token issued"))
+}
+
+/// Exchange a refresh token at the token endpoint
+async fn refresh(
+    client: &rocket::local::asynchronous::Client,
+    refresh_token: &str,
+) -> (Status, Value) {
+    let body = serde_urlencoded::to_string([
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token),
+        ("client_id", "LaserSmartClient"),
+    ])
+    .unwrap();
+    let response = client
+        .post("/token")
+        .header(ContentType::Form)
+        .body(body)
+        .dispatch()
+        .await;
+    let status = response.status();
+    let body = response.into_string().await.unwrap_or_default();
+    (status, serde_json::from_str(&body).unwrap_or(Value::Null))
+}
+
+#[rocket::async_test]
+async fn test_refresh_token_grant_issues_new_tokens() {
+    let client = build_test_client(test_config()).await;
+    let (access_token, refresh_token) = issue_tokens(&client, "LaserSmartClient");
+
+    let (status, body) = refresh(&client, &refresh_token).await;
+    assert_eq!(status, Status::Ok);
+
+    let new_access_token = body["access_token"].as_str().expect("access_token");
+    let new_refresh_token = body["refresh_token"].as_str().expect("refresh_token");
+    assert_eq!(
+        new_access_token.split('.').count(),
+        3,
+        "access token is a JWT"
+    );
+    assert_ne!(new_access_token, access_token);
+    assert_ne!(new_refresh_token, refresh_token);
+}
+
+#[rocket::async_test]
+async fn test_refresh_token_is_rotated() {
+    let client = build_test_client(test_config()).await;
+    let (_, first_refresh_token) = issue_tokens(&client, "LaserSmartClient");
+
+    let (status, body) = refresh(&client, &first_refresh_token).await;
+    assert_eq!(status, Status::Ok);
+    let second_refresh_token = body["refresh_token"].as_str().unwrap().to_string();
+
+    // The successor keeps working and is rotated in turn
+    let (status, body) = refresh(&client, &second_refresh_token).await;
+    assert_eq!(status, Status::Ok);
+    assert_ne!(
+        body["refresh_token"].as_str().unwrap(),
+        second_refresh_token
+    );
+
+    // Rotated tokens are single use
+    let (status, _) = refresh(&client, &second_refresh_token).await;
+    assert_eq!(status, Status::BadRequest);
+}
+
+#[rocket::async_test]
+async fn test_replayed_refresh_token_revokes_family() {
+    let client = build_test_client(test_config()).await;
+    let (_, stolen_refresh_token) = issue_tokens(&client, "LaserSmartClient");
+
+    let (status, body) = refresh(&client, &stolen_refresh_token).await;
+    assert_eq!(status, Status::Ok);
+    let live_refresh_token = body["refresh_token"].as_str().unwrap().to_string();
+
+    // Replaying the consumed token is rejected...
+    let (status, _) = refresh(&client, &stolen_refresh_token).await;
+    assert_eq!(status, Status::BadRequest);
+
+    // ...and revokes the token that replaced it
+    let (status, _) = refresh(&client, &live_refresh_token).await;
+    assert_eq!(status, Status::BadRequest);
+
+    // Other token families are not affected
+    let (_, other_refresh_token) = issue_tokens(&client, "LaserSmartClient");
+    let (status, _) = refresh(&client, &other_refresh_token).await;
+    assert_eq!(status, Status::Ok);
+}
+
+#[rocket::async_test]
+async fn test_refresh_token_lifetime_from_access_config() {
+    let mut config = test_config();
+    config.access.clients = vec![
+        Client {
+            refresh_token_lifetime: Some(3 * 24 * 3600),
+            ..Client::default()
+        },
+        Client {
+            client_id: "ShortLivedClient".to_string(),
+            refresh_token_lifetime: None,
+            ..Client::default()
+        },
+    ];
+    let client = build_test_client(config).await;
+
+    let (_, long_lived) = issue_tokens(&client, "LaserSmartClient");
+    let (_, short_lived) = issue_tokens(&client, "ShortLivedClient");
+
+    let state = client.rocket().state::<OxideState>().unwrap();
+    let issuer = state.issuer.lock().unwrap();
+    let map = issuer.0.lock().unwrap();
+
+    let entry = map
+        .refresh_tokens
+        .get(&long_lived)
+        .expect("refresh token stored");
+    let lifetime = entry.refresh_expiry - Utc::now();
+    assert!(lifetime > Duration::days(2) && lifetime <= Duration::days(3));
+    assert!(entry.refresh_expiry > entry.expiry);
+
+    // Without a configured lifetime the refresh token expires with the access token
+    let entry = map
+        .refresh_tokens
+        .get(&short_lived)
+        .expect("refresh token stored");
+    assert_eq!(entry.refresh_expiry, entry.expiry);
+}
+
+#[test]
+fn test_declared_client_gets_default_refresh_lifetime() {
+    let declared: Client = serde_yml::from_str(
+        r#"
+client_id: LaserSmartClient
+default_scope: "openid profile email offline_access read:api write:api"
+allowed_callbacks:
+  - "http://localhost:8080/client/"
+"#,
+    )
+    .expect("valid client");
+    assert_eq!(
+        declared.refresh_token_lifetime,
+        Client::default().refresh_token_lifetime
+    );
+    assert_eq!(declared.refresh_token_lifetime, Some(604800));
+
+    // An explicit null ties the refresh tokens to their access token
+    let tied: Client = serde_yml::from_str(
+        r#"
+client_id: LaserSmartClient
+default_scope: "openid"
+allowed_callbacks: []
+refresh_token_lifetime: null
+"#,
+    )
+    .expect("valid client");
+    assert_eq!(tied.refresh_token_lifetime, None);
+}
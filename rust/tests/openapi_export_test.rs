@@ -0,0 +1,106 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the downloadable OpenAPI specification
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_openapi_json_export`] | `/api/openapi.json` serves a JSON spec versioned with the build version |
+//! | [`test_openapi_yaml_export`] | `/api/openapi.yaml` serves the same spec as YAML |
+//! | [`test_generated_spec_is_versioned`] | The CLI generators stamp the build version as well |
+
+use rocket::http::{ContentType, Status};
+use rocket::local::asynchronous::Client;
+use rust_photoacoustic::build_info::BuildInfo;
+use rust_photoacoustic::config::Config;
+use rust_photoacoustic::visualization::server::{generate_openapi_json, generate_openapi_yaml};
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+mod common;
+use common::test_figment;
+
+async fn build_test_client() -> Client {
+    let rocket = rust_photoacoustic::visualization::server::build_rocket(
+        test_figment(),
+        Arc::new(RwLock::new(Config::default())),
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+    Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance")
+}
+
+#[rocket::async_test]
+async fn test_openapi_json_export() {
+    let client = build_test_client().await;
+
+    let response = client.get("/api/openapi.json").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::JSON));
+    let spec: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    assert_eq!(spec["info"]["version"], BuildInfo::get().version);
+    assert_eq!(spec["openapi"], "3.0.0");
+    assert!(spec["paths"]
+        .as_object()
+        .is_some_and(|paths| !paths.is_empty()));
+
+    // The RapiDoc specification carries the same version
+    let response = client.get("/openapi.json").dispatch().await;
+    let spec: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    assert_eq!(spec["info"]["version"], BuildInfo::get().version);
+}
+
+#[rocket::async_test]
+async fn test_openapi_yaml_export() {
+    let client = build_test_client().await;
+
+    let json_spec: Value = serde_json::from_str(
+        &client
+            .get("/api/openapi.json")
+            .dispatch()
+            .await
+            .into_string()
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+
+    let response = client.get("/api/openapi.yaml").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(
+        response.content_type(),
+        Some(ContentType::new("application", "yaml"))
+    );
+    let yaml_spec: Value = serde_yml::from_str(&response.into_string().await.unwrap()).unwrap();
+    assert_eq!(yaml_spec["info"]["version"], BuildInfo::get().version);
+    assert_eq!(yaml_spec, json_spec);
+}
+
+#[rocket::async_test]
+async fn test_generated_spec_is_versioned() {
+    let config = Arc::new(RwLock::new(Config::default()));
+
+    let json_spec: Value = serde_json::from_str(
+        &generate_openapi_json(&config, true, true, true, true)
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(json_spec["info"]["version"], BuildInfo::get().version);
+
+    let yaml_spec: Value = serde_yml::from_str(
+        &generate_openapi_yaml(&config, true, true, true, true)
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(yaml_spec, json_spec);
+}
@@ -0,0 +1,383 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the detection state machine of the photoacoustic output node
+//!
+//! Frames of 100 ms carry a sine whose amplitude is the level seen by the node.
+//! A detection starts above 0.05 after a 500 ms dwell and ends at or below 0.04
+//! after a 1 s dwell. The background level is 0.02.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_brief_crossings_do_not_fire`] | A crossing shorter than the dwell time, and a level flickering around the threshold, emit no event |
+//! | [`test_sustained_crossing_fires_after_dwell`] | A sustained crossing fires one onset after the dwell, and one release after the release dwell, a short rise restarting it |
+//! | [`test_events_reach_action_nodes`] | A graph output node publishes its events in the shared state and an action node handles each of them once |
+//! | [`test_events_published_after_lock_released`] | Events occurring while the shared state is locked are published at the next frame |
+//! | [`test_detection_configuration`] | Invalid thresholds are rejected, a configuration update keeps the event numbering, and defaults follow the per-frame threshold |
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::AudioFrame;
+use rust_photoacoustic::config::processing::{ConnectionConfig, NodeConfig, ProcessingGraphConfig};
+use rust_photoacoustic::processing::computing_nodes::{
+    ActionNode, ComputingSharedData, SharedComputingState, UniversalActionNode,
+};
+use rust_photoacoustic::processing::nodes::{
+    DetectionEventKind, DetectionState, DetectionStateMachine, PhotoacousticOutputNode,
+};
+use rust_photoacoustic::processing::{ProcessingData, ProcessingGraph, ProcessingNode};
+use std::f32::consts::TAU;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+const SAMPLE_RATE: u32 = 48000;
+/// Samples of a 100 ms frame
+const FRAME_SIZE: usize = 4800;
+const FRAME_MS: u64 = 100;
+const BACKGROUND: f32 = 0.02;
+const SIGNAL: f32 = 0.08;
+
+/// 100 ms of a 1 kHz sine whose peak amplitude is `level`
+fn samples(level: f32) -> Vec<f32> {
+    (0..FRAME_SIZE)
+        .map(|n| level * (TAU * 1000.0 * n as f32 / SAMPLE_RATE as f32 + 0.25 * TAU).sin())
+        .collect()
+}
+
+fn detector() -> Result<DetectionStateMachine> {
+    DetectionStateMachine::new(0.05, 0.04, 500, 1000)
+}
+
+/// Process frame `frame_number` at `level` and return its processing steps
+fn analyze(node: &mut PhotoacousticOutputNode, frame_number: u64, level: f32) -> Vec<String> {
+    let output = node
+        .process(ProcessingData::SingleChannel {
+            samples: samples(level),
+            sample_rate: SAMPLE_RATE,
+            timestamp: 1000 + frame_number * FRAME_MS,
+            frame_number,
+        })
+        .expect("frame processed");
+    match output {
+        ProcessingData::PhotoacousticResult { metadata, .. } => metadata.processing_steps,
+        _ => panic!("unexpected output type"),
+    }
+}
+
+fn has_step(steps: &[String], step: &str) -> bool {
+    steps.iter().any(|s| s == step)
+}
+
+#[test]
+fn test_brief_crossings_do_not_fire() -> Result<()> {
+    let mut node = PhotoacousticOutputNode::new("output".to_string()).with_detection(detector()?);
+    let mut frame_number = 0;
+    let mut feed = |node: &mut PhotoacousticOutputNode, level: f32| {
+        let steps = analyze(node, frame_number, level);
+        frame_number += 1;
+        assert!(!has_step(&steps, "detection_confirmed"), "{:?}", steps);
+        assert!(!has_step(&steps, "detection_onset"), "{:?}", steps);
+    };
+
+    for _ in 0..5 {
+        feed(&mut node, BACKGROUND);
+    }
+    // 400 ms above the threshold, shorter than the 500 ms dwell
+    for _ in 0..4 {
+        feed(&mut node, SIGNAL);
+    }
+    assert_eq!(node.get_detection().state(), DetectionState::Armed);
+    feed(&mut node, BACKGROUND);
+    assert_eq!(node.get_detection().state(), DetectionState::Idle);
+
+    // A level flickering around the threshold never stays above it long enough
+    for k in 0..50 {
+        feed(&mut node, if k % 3 == 2 { 0.045 } else { 0.055 });
+    }
+    assert!(node.get_recent_events().is_empty());
+
+    // Without debouncing, the same levels give a detection at every crossing
+    let mut plain =
+        PhotoacousticOutputNode::new("plain".to_string()).with_detection_threshold(0.05);
+    let onsets = (0..50)
+        .filter(|k| {
+            let level = if k % 3 == 2 { 0.045 } else { 0.055 };
+            has_step(&analyze(&mut plain, *k, level), "detection_onset")
+        })
+        .count();
+    assert_eq!(onsets, 17);
+    Ok(())
+}
+
+#[test]
+fn test_sustained_crossing_fires_after_dwell() -> Result<()> {
+    let mut node = PhotoacousticOutputNode::new("output".to_string()).with_detection(detector()?);
+    let level = |k: u64| match k {
+        10..=39 => SIGNAL,
+        // Above the release threshold, restarting the release dwell
+        43 => 0.045,
+        _ => BACKGROUND,
+    };
+
+    for k in 0..70 {
+        let steps = analyze(&mut node, k, level(k));
+        // Onset 500 ms after the crossing at frame 10, release 1 s after the last
+        // frame above the release threshold
+        assert_eq!(has_step(&steps, "detection_onset"), k == 15, "frame {}", k);
+        assert_eq!(
+            has_step(&steps, "detection_release"),
+            k == 54,
+            "frame {}",
+            k
+        );
+        assert_eq!(
+            has_step(&steps, "detection_confirmed"),
+            (15..54).contains(&k),
+            "frame {}",
+            k
+        );
+        let expected_state = match k {
+            10..=14 => DetectionState::Armed,
+            15..=39 | 43 => DetectionState::Detected,
+            40..=42 | 44..=53 => DetectionState::Holding,
+            _ => DetectionState::Idle,
+        };
+        assert_eq!(node.get_detection().state(), expected_state, "frame {}", k);
+    }
+
+    let events = node.get_recent_events();
+    assert_eq!(events.len(), 2);
+    let (onset, release) = (&events[0], &events[1]);
+    assert_eq!(onset.kind, DetectionEventKind::Onset);
+    assert_eq!(onset.sequence, 1);
+    assert_eq!(onset.frame_number, 15);
+    assert_eq!(onset.frame_timestamp, 1000 + 15 * FRAME_MS);
+    assert_eq!(onset.duration_ms, 500);
+    assert!((onset.level - SIGNAL).abs() < 1e-3);
+    assert_eq!(release.kind, DetectionEventKind::Release);
+    assert_eq!(release.sequence, 2);
+    assert_eq!(release.frame_number, 54);
+    assert_eq!(release.duration_ms, (54 - 15) * FRAME_MS);
+
+    // A reset returns to idle and keeps numbering the events
+    for k in 70..76 {
+        analyze(&mut node, k, SIGNAL);
+    }
+    assert_eq!(node.get_detection().state(), DetectionState::Detected);
+    node.reset();
+    assert_eq!(node.get_detection().state(), DetectionState::Idle);
+    assert!(node.get_recent_events().is_empty());
+    for k in 100..106 {
+        analyze(&mut node, k, SIGNAL);
+    }
+    assert_eq!(node.get_recent_events()[0].sequence, 4);
+    Ok(())
+}
+
+#[test]
+fn test_events_reach_action_nodes() -> Result<()> {
+    let config = ProcessingGraphConfig {
+        id: "detection_graph".to_string(),
+        nodes: vec![
+            NodeConfig {
+                id: "input".to_string(),
+                node_type: "input".to_string(),
+                parameters: serde_json::Value::Null,
+            },
+            NodeConfig {
+                id: "select".to_string(),
+                node_type: "channel_selector".to_string(),
+                parameters: serde_json::json!({"target_channel": "ChannelA"}),
+            },
+            NodeConfig {
+                id: "photoacoustic_output".to_string(),
+                node_type: "photoacoustic_output".to_string(),
+                parameters: serde_json::json!({
+                    "detection_threshold": 0.05,
+                    "release_threshold": 0.04,
+                    "detection_dwell_ms": 500,
+                    "release_dwell_ms": 1000
+                }),
+            },
+        ],
+        connections: vec![
+            ConnectionConfig {
+                from: "input".to_string(),
+                to: "select".to_string(),
+            },
+            ConnectionConfig {
+                from: "select".to_string(),
+                to: "photoacoustic_output".to_string(),
+            },
+        ],
+        output_node: Some("photoacoustic_output".to_string()),
+    };
+    let state: SharedComputingState = Arc::new(RwLock::new(ComputingSharedData::default()));
+    let mut graph =
+        ProcessingGraph::from_config_with_computing_state(&config, Some(state.clone()))?;
+
+    let mut action =
+        UniversalActionNode::new_with_shared_state("alerts".to_string(), Some(state.clone()))
+            .with_history_buffer_capacity(10)
+            .with_monitored_node("photoacoustic_output".to_string());
+    let actions_triggered = |action: &UniversalActionNode| -> Result<u64> {
+        Ok(action.get_status()?["performance"]["actions_triggered"]
+            .as_u64()
+            .unwrap())
+    };
+
+    let mut execute = |frame_number: u64, level: f32| -> Result<()> {
+        graph.execute(ProcessingData::AudioFrame(AudioFrame {
+            channel_a: samples(level),
+            channel_b: samples(BACKGROUND),
+            sample_rate: SAMPLE_RATE,
+            timestamp: 1000 + frame_number * FRAME_MS,
+            frame_number,
+        }))?;
+        Ok(())
+    };
+
+    // A brief crossing publishes the armed state, but no event
+    for k in 0..3 {
+        execute(k, SIGNAL)?;
+    }
+    execute(3, BACKGROUND)?;
+    action.process(ProcessingData::SingleChannel {
+        samples: vec![0.0; 16],
+        sample_rate: SAMPLE_RATE,
+        timestamp: 0,
+        frame_number: 0,
+    })?;
+    assert_eq!(actions_triggered(&action)?, 0);
+
+    // A sustained crossing fires an onset, then a release
+    for k in 4..20 {
+        execute(k, SIGNAL)?;
+    }
+    for k in 20..40 {
+        execute(k, BACKGROUND)?;
+    }
+    {
+        let shared = state.try_read().expect("shared state available");
+        let result = shared
+            .get_detection_result("photoacoustic_output")
+            .expect("detection published");
+        assert_eq!(result.state, DetectionState::Idle);
+        let kinds: Vec<DetectionEventKind> = result.events.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![DetectionEventKind::Onset, DetectionEventKind::Release]
+        );
+        assert_eq!(result.events[0].frame_number, 9);
+
+        let context = shared.script_context();
+        let published = &context["detection_results"]["photoacoustic_output"];
+        assert_eq!(published["state"], "idle");
+        assert_eq!(published["detected"], false);
+        assert_eq!(published["events"][0]["kind"], "onset");
+        assert_eq!(published["events"][1]["sequence"], 2);
+    }
+
+    // The action node handles each event once
+    for frame_number in 0..3 {
+        action.process(ProcessingData::SingleChannel {
+            samples: vec![0.0; 16],
+            sample_rate: SAMPLE_RATE,
+            timestamp: 0,
+            frame_number,
+        })?;
+        assert_eq!(actions_triggered(&action)?, 2);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_events_published_after_lock_released() -> Result<()> {
+    let state: SharedComputingState = Arc::new(RwLock::new(ComputingSharedData::default()));
+    let mut node =
+        PhotoacousticOutputNode::new_with_shared_state("output".to_string(), Some(state.clone()))
+            .with_detection(DetectionStateMachine::new(0.05, 0.04, 0, 0)?);
+
+    // Onset and release while a reader holds the shared state
+    let reader = state.try_read().expect("shared state available");
+    assert!(has_step(&analyze(&mut node, 0, SIGNAL), "detection_onset"));
+    assert!(has_step(
+        &analyze(&mut node, 1, BACKGROUND),
+        "detection_release"
+    ));
+    assert!(reader.get_detection_result("output").is_none());
+    drop(reader);
+
+    // The next frame publishes both events, without any state change
+    analyze(&mut node, 2, BACKGROUND);
+    let shared = state.try_read().expect("shared state available");
+    let result = shared
+        .get_detection_result("output")
+        .expect("detection published");
+    assert_eq!(result.state, DetectionState::Idle);
+    let kinds: Vec<DetectionEventKind> = result.events.iter().map(|e| e.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![DetectionEventKind::Onset, DetectionEventKind::Release]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_detection_configuration() -> Result<()> {
+    assert!(DetectionStateMachine::new(0.05, 0.06, 0, 0).is_err());
+    assert!(DetectionStateMachine::new(f32::NAN, 0.0, 0, 0).is_err());
+
+    // The release threshold cannot be above the detection threshold in a graph either
+    let config = ProcessingGraphConfig {
+        id: "invalid".to_string(),
+        nodes: vec![NodeConfig {
+            id: "photoacoustic_output".to_string(),
+            node_type: "photoacoustic_output".to_string(),
+            parameters: serde_json::json!({
+                "detection_threshold": 0.05,
+                "release_threshold": 0.1
+            }),
+        }],
+        connections: vec![],
+        output_node: None,
+    };
+    assert!(ProcessingGraph::from_config(&config).is_err());
+
+    // Default dwell times detect and release on the frames crossing the threshold
+    let mut node =
+        PhotoacousticOutputNode::new("output".to_string()).with_detection_threshold(0.05);
+    assert!(has_step(&analyze(&mut node, 0, SIGNAL), "detection_onset"));
+    assert!(has_step(
+        &analyze(&mut node, 1, SIGNAL),
+        "detection_confirmed"
+    ));
+    assert!(has_step(
+        &analyze(&mut node, 2, BACKGROUND),
+        "detection_release"
+    ));
+
+    // An invalid update leaves the node unchanged
+    assert!(node
+        .update_config(&serde_json::json!({"release_threshold": 0.2}))
+        .is_err());
+    assert_eq!(node.get_detection().release_threshold(), 0.05);
+    assert!(!node.update_config(&serde_json::json!({"detection_threshold": 0.05}))?);
+
+    // A new configuration restarts from idle and keeps numbering the events
+    assert!(node.update_config(&serde_json::json!({
+        "release_threshold": 0.04,
+        "detection_dwell_ms": 200
+    }))?);
+    let detection = node.get_detection();
+    assert_eq!(detection.detection_threshold(), 0.05);
+    assert_eq!(detection.release_threshold(), 0.04);
+    assert_eq!(detection.detection_dwell_ms(), 200);
+    assert_eq!(detection.release_dwell_ms(), 0);
+    for k in 3..5 {
+        assert!(!has_step(&analyze(&mut node, k, SIGNAL), "detection_onset"));
+    }
+    assert!(has_step(&analyze(&mut node, 5, SIGNAL), "detection_onset"));
+    assert_eq!(node.get_recent_events().last().unwrap().sequence, 3);
+    Ok(())
+}
@@ -0,0 +1,251 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the SNR gate of the photoacoustic output node
+//!
+//! Frames of 100 ms carry either a 2 kHz tone of amplitude 0.1 in light noise
+//! (0.005 RMS), or a broadband noise burst of 0.3 RMS whose peaks reach well
+//! above the tone. Both cross the 0.05 detection threshold; only the tone stands
+//! 20 dB above the noise floor of its spectrum.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_high_snr_tone_is_detected`] | A tone in light noise measures more than 20 dB and is detected |
+//! | [`test_noise_burst_is_gated`] | A loud noise burst measures less than 20 dB and is gated, while it is detected without the gate |
+//! | [`test_snr_gate_from_graph_config`] | A graph node reads `snr_threshold_db`, rejects invalid values and can disable the gate on update |
+//! | [`test_peak_to_noise_floor`] | White noise measures the same ratio at any level, silent and short signals measure 0 |
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::AudioFrame;
+use rust_photoacoustic::config::processing::{ConnectionConfig, NodeConfig, ProcessingGraphConfig};
+use rust_photoacoustic::processing::nodes::{PhotoacousticOutputNode, ProcessingMetadata};
+use rust_photoacoustic::processing::{ProcessingData, ProcessingGraph, ProcessingNode};
+use rust_photoacoustic::utility::noise_generator::NoiseGenerator;
+use rust_photoacoustic::utility::noise_stats::peak_to_noise_floor_db;
+use std::f64::consts::TAU;
+
+const SAMPLE_RATE: u32 = 48000;
+/// Samples of a 100 ms frame
+const FRAME_SIZE: usize = 4800;
+const FRAME_MS: u64 = 100;
+const DETECTION_THRESHOLD: f32 = 0.05;
+const SNR_THRESHOLD_DB: f32 = 20.0;
+
+/// Frame `frame_number` of a 2 kHz tone of amplitude 0.1 in 0.005 RMS noise
+fn tone(noise: &mut NoiseGenerator, frame_number: u64) -> Vec<f32> {
+    (0..FRAME_SIZE)
+        .map(|n| {
+            let t = (frame_number as usize * FRAME_SIZE + n) as f64 / SAMPLE_RATE as f64;
+            0.1 * (TAU * 2000.0 * t).sin() as f32 + 0.005 * noise.random_gaussian()
+        })
+        .collect()
+}
+
+/// A frame of 0.3 RMS Gaussian noise
+fn burst(noise: &mut NoiseGenerator) -> Vec<f32> {
+    (0..FRAME_SIZE)
+        .map(|_| 0.3 * noise.random_gaussian())
+        .collect()
+}
+
+fn gated_node() -> PhotoacousticOutputNode {
+    PhotoacousticOutputNode::new("output".to_string())
+        .with_detection_threshold(DETECTION_THRESHOLD)
+        .with_snr_threshold_db(Some(SNR_THRESHOLD_DB))
+}
+
+/// Process frame `frame_number` and return its metadata
+fn analyze(
+    node: &mut PhotoacousticOutputNode,
+    frame_number: u64,
+    samples: Vec<f32>,
+) -> ProcessingMetadata {
+    let output = node
+        .process(ProcessingData::SingleChannel {
+            samples,
+            sample_rate: SAMPLE_RATE,
+            timestamp: 1000 + frame_number * FRAME_MS,
+            frame_number,
+        })
+        .expect("frame processed");
+    match output {
+        ProcessingData::PhotoacousticResult { metadata, .. } => metadata,
+        _ => panic!("unexpected output type"),
+    }
+}
+
+fn has_step(metadata: &ProcessingMetadata, step: &str) -> bool {
+    metadata.processing_steps.iter().any(|s| s == step)
+}
+
+#[test]
+fn test_high_snr_tone_is_detected() -> Result<()> {
+    let mut noise = NoiseGenerator::new(7);
+    let mut node = gated_node();
+    assert_eq!(node.get_snr_threshold_db(), Some(SNR_THRESHOLD_DB));
+
+    for frame_number in 0..10 {
+        let metadata = analyze(&mut node, frame_number, tone(&mut noise, frame_number));
+        let snr_db = metadata.snr_db.expect("SNR measured");
+        assert!(snr_db > 40.0, "frame {}: SNR {} dB", frame_number, snr_db);
+        assert!(!has_step(&metadata, "snr_gated"));
+        assert!(has_step(&metadata, "detection_confirmed"));
+        assert_eq!(
+            has_step(&metadata, "detection_onset"),
+            frame_number == 0,
+            "frame {}",
+            frame_number
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_noise_burst_is_gated() -> Result<()> {
+    let mut noise = NoiseGenerator::new(11);
+    let frames: Vec<Vec<f32>> = (0..20).map(|_| burst(&mut noise)).collect();
+
+    let mut node = gated_node();
+    for (frame_number, frame) in frames.iter().enumerate() {
+        let metadata = analyze(&mut node, frame_number as u64, frame.clone());
+        let snr_db = metadata.snr_db.expect("SNR measured");
+        assert!(
+            (0.0..SNR_THRESHOLD_DB).contains(&snr_db),
+            "frame {}: SNR {} dB",
+            frame_number,
+            snr_db
+        );
+        assert!(has_step(&metadata, "snr_gated"));
+        assert!(!has_step(&metadata, "detection_onset"));
+        assert!(!has_step(&metadata, "detection_confirmed"));
+    }
+    assert!(node.get_recent_events().is_empty());
+
+    // The tone following the burst is detected
+    let metadata = analyze(&mut node, 20, tone(&mut noise, 20));
+    assert!(has_step(&metadata, "detection_onset"));
+
+    // Without the gate, the amplitude of the burst alone fires a detection
+    let mut ungated =
+        PhotoacousticOutputNode::new("output".to_string()).with_detection_threshold(0.05);
+    let metadata = analyze(&mut ungated, 0, frames[0].clone());
+    assert!(has_step(&metadata, "detection_onset"));
+    assert_eq!(metadata.snr_db, None);
+    Ok(())
+}
+
+#[test]
+fn test_snr_gate_from_graph_config() -> Result<()> {
+    let config = ProcessingGraphConfig {
+        id: "snr_gate_graph".to_string(),
+        nodes: vec![
+            NodeConfig {
+                id: "input".to_string(),
+                node_type: "input".to_string(),
+                parameters: serde_json::Value::Null,
+            },
+            NodeConfig {
+                id: "select".to_string(),
+                node_type: "channel_selector".to_string(),
+                parameters: serde_json::json!({"target_channel": "ChannelA"}),
+            },
+            NodeConfig {
+                id: "photoacoustic_output".to_string(),
+                node_type: "photoacoustic_output".to_string(),
+                parameters: serde_json::json!({
+                    "detection_threshold": DETECTION_THRESHOLD,
+                    "snr_threshold_db": SNR_THRESHOLD_DB,
+                    "analysis_window_size": 2048
+                }),
+            },
+        ],
+        connections: vec![
+            ConnectionConfig {
+                from: "input".to_string(),
+                to: "select".to_string(),
+            },
+            ConnectionConfig {
+                from: "select".to_string(),
+                to: "photoacoustic_output".to_string(),
+            },
+        ],
+        output_node: Some("photoacoustic_output".to_string()),
+    };
+    let mut graph = ProcessingGraph::from_config(&config)?;
+
+    let mut noise = NoiseGenerator::new(23);
+    let execute = |graph: &mut ProcessingGraph, frame_number: u64, channel_a: Vec<f32>| {
+        let outputs = graph
+            .execute(ProcessingData::AudioFrame(AudioFrame {
+                channel_a,
+                channel_b: vec![0.0; FRAME_SIZE],
+                sample_rate: SAMPLE_RATE,
+                timestamp: 1000 + frame_number * FRAME_MS,
+                frame_number,
+            }))
+            .expect("graph executed");
+        match &outputs[..] {
+            [ProcessingData::PhotoacousticResult { metadata, .. }] => metadata.clone(),
+            _ => panic!("unexpected output"),
+        }
+    };
+
+    let frame = burst(&mut noise);
+    let metadata = execute(&mut graph, 0, frame.clone());
+    assert!(has_step(&metadata, "snr_gated"));
+    assert!(metadata.snr_db.unwrap() < SNR_THRESHOLD_DB);
+    let frame = tone(&mut noise, 1);
+    let metadata = execute(&mut graph, 1, frame);
+    assert!(has_step(&metadata, "detection_onset"));
+    assert!(metadata.snr_db.unwrap() > SNR_THRESHOLD_DB);
+
+    // A null threshold disables the gate
+    assert!(graph.update_node_config(
+        "photoacoustic_output",
+        &serde_json::json!({"snr_threshold_db": null})
+    )?);
+    let metadata = execute(&mut graph, 2, burst(&mut noise));
+    assert!(has_step(&metadata, "detection_confirmed"));
+    assert_eq!(metadata.snr_db, None);
+
+    // The threshold must be a number
+    let mut invalid = config.clone();
+    invalid.nodes[2].parameters = serde_json::json!({"snr_threshold_db": "high"});
+    assert!(ProcessingGraph::from_config(&invalid).is_err());
+    let mut node = gated_node();
+    assert!(node
+        .update_config(&serde_json::json!({"snr_threshold_db": "high", "detection_threshold": 0.1}))
+        .is_err());
+    assert_eq!(node.get_snr_threshold_db(), Some(SNR_THRESHOLD_DB));
+    assert_eq!(
+        node.get_detection().detection_threshold(),
+        DETECTION_THRESHOLD
+    );
+    Ok(())
+}
+
+#[test]
+fn test_peak_to_noise_floor() {
+    let mut noise = NoiseGenerator::new(5);
+    let white: Vec<f32> = (0..4096).map(|_| noise.random_gaussian()).collect();
+    let quiet: Vec<f32> = white.iter().map(|s| 0.001 * s).collect();
+    let loud: Vec<f32> = white.iter().map(|s| 0.5 * s).collect();
+    let quiet_db = peak_to_noise_floor_db(&quiet);
+    let loud_db = peak_to_noise_floor_db(&loud);
+    assert!(
+        (5.0..15.0).contains(&loud_db),
+        "white noise: {} dB",
+        loud_db
+    );
+    assert!((quiet_db - loud_db).abs() < 0.01);
+
+    // A noiseless tone stands far above the leakage of the window
+    let pure: Vec<f32> = (0..4096)
+        .map(|n| (TAU * 1000.0 * n as f64 / SAMPLE_RATE as f64).sin() as f32)
+        .collect();
+    assert!(peak_to_noise_floor_db(&pure) > 60.0);
+
+    assert_eq!(peak_to_noise_floor_db(&[0.0; 1024]), 0.0);
+    assert_eq!(peak_to_noise_floor_db(&[0.5; 8]), 0.0);
+}
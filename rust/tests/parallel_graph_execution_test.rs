@@ -0,0 +1,380 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the parallel execution of independent graph branches
+//!
+//! The fan-out graph splits the input into three branches of different depth:
+//!
+//! ```text
+//! input ─┬─ select_a ─┬─ bandpass_a ── gain_a ── output_a
+//!        │            └─ highpass_a
+//!        └─ select_b ──── lowpass_b
+//! ```
+//!
+//! `output_a`, `highpass_a` and `lowpass_b` are output nodes. The processing
+//! latency of the photoacoustic result is a wall-clock measurement, so it is
+//! ignored when comparing outputs.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_parallel_matches_serial_on_fan_out`] | Parallel and serial execution return identical outputs, in output node order, over 20 frames |
+//! | [`test_parallel_statistics`] | Every node records one execution per frame in both modes |
+//! | [`test_parallel_error_is_deterministic`] | With two failing nodes in the same level, both modes report the same node |
+//! | [`test_parallel_levels_run_on_graph_pool`] | With a thread pool set, the nodes of a level run on its threads |
+//! | [`test_node_panic_is_reported`] | A panicking node fails the frame with `NodePanicked` in both modes, and the graph keeps running |
+//! | [`test_parallel_execution_from_config`] | `processing.performance.parallel_execution` defaults to false and is read from YAML |
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::AudioFrame;
+use rust_photoacoustic::config::processing::{
+    ConnectionConfig, NodeConfig, ProcessingConfig, ProcessingGraphConfig,
+};
+use rust_photoacoustic::processing::{
+    ProcessingData, ProcessingGraph, ProcessingGraphError, ProcessingNode,
+};
+use rust_photoacoustic::utility::noise_generator::NoiseGenerator;
+use std::f32::consts::TAU;
+use std::sync::{Arc, Mutex};
+
+const SAMPLE_RATE: u32 = 48000;
+const FRAME_SIZE: usize = 2048;
+
+fn node(id: &str, node_type: &str, parameters: serde_json::Value) -> NodeConfig {
+    NodeConfig {
+        id: id.to_string(),
+        node_type: node_type.to_string(),
+        parameters,
+    }
+}
+
+fn connection(from: &str, to: &str) -> ConnectionConfig {
+    ConnectionConfig {
+        from: from.to_string(),
+        to: to.to_string(),
+    }
+}
+
+fn fan_out_config() -> ProcessingGraphConfig {
+    ProcessingGraphConfig {
+        id: "fan_out".to_string(),
+        nodes: vec![
+            node("input", "input", serde_json::Value::Null),
+            node(
+                "select_a",
+                "channel_selector",
+                serde_json::json!({"target_channel": "ChannelA"}),
+            ),
+            node(
+                "select_b",
+                "channel_selector",
+                serde_json::json!({"target_channel": "ChannelB"}),
+            ),
+            node(
+                "bandpass_a",
+                "filter",
+                serde_json::json!({
+                    "type": "bandpass",
+                    "center_frequency": 2000.0,
+                    "bandwidth": 200.0,
+                    "order": 8
+                }),
+            ),
+            node("gain_a", "gain", serde_json::json!({"gain_db": 6.0})),
+            node(
+                "output_a",
+                "photoacoustic_output",
+                serde_json::json!({"detection_threshold": 0.05}),
+            ),
+            node(
+                "highpass_a",
+                "filter",
+                serde_json::json!({"type": "highpass", "cutoff_frequency": 500.0, "order": 2}),
+            ),
+            node(
+                "lowpass_b",
+                "filter",
+                serde_json::json!({"type": "lowpass", "cutoff_frequency": 1000.0, "order": 4}),
+            ),
+        ],
+        connections: vec![
+            connection("input", "select_a"),
+            connection("input", "select_b"),
+            connection("select_a", "bandpass_a"),
+            connection("bandpass_a", "gain_a"),
+            connection("gain_a", "output_a"),
+            connection("select_a", "highpass_a"),
+            connection("select_b", "lowpass_b"),
+        ],
+        output_node: Some("output_a".to_string()),
+    }
+}
+
+fn fan_out_graph(parallel: bool) -> Result<ProcessingGraph> {
+    let mut graph = ProcessingGraph::from_config(&fan_out_config())?;
+    graph.set_output_node("highpass_a")?;
+    graph.set_output_node("lowpass_b")?;
+    graph.set_parallel_execution(parallel);
+    Ok(graph)
+}
+
+fn frame(noise: &mut NoiseGenerator, frame_number: u64) -> ProcessingData {
+    let start = frame_number as usize * FRAME_SIZE;
+    let (channel_a, channel_b) = (0..FRAME_SIZE)
+        .map(|n| {
+            let t = (start + n) as f32 / SAMPLE_RATE as f32;
+            (
+                0.3 * (TAU * 2000.0 * t).sin() + 0.05 * noise.random_gaussian(),
+                0.2 * (TAU * 700.0 * t).sin() + 0.05 * noise.random_gaussian(),
+            )
+        })
+        .unzip();
+    ProcessingData::AudioFrame(AudioFrame {
+        channel_a,
+        channel_b,
+        sample_rate: SAMPLE_RATE,
+        timestamp: 1000 + frame_number * 43,
+        frame_number,
+    })
+}
+
+/// Node panicking on every frame
+struct PanickingNode {
+    id: String,
+}
+
+impl ProcessingNode for PanickingNode {
+    fn process(&mut self, _input: ProcessingData) -> Result<ProcessingData> {
+        panic!("injected panic");
+    }
+
+    fn node_id(&self) -> &str {
+        &self.id
+    }
+
+    fn node_type(&self) -> &str {
+        "panicking"
+    }
+
+    fn accepts_input(&self, _input: &ProcessingData) -> bool {
+        true
+    }
+
+    fn output_type(&self, _input: &ProcessingData) -> Option<String> {
+        None
+    }
+
+    fn reset(&mut self) {}
+
+    fn clone_node(&self) -> Box<dyn ProcessingNode> {
+        Box::new(PanickingNode {
+            id: self.id.clone(),
+        })
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Pass-through node recording the name of the threads it runs on
+struct ThreadRecordingNode {
+    id: String,
+    threads: Arc<Mutex<Vec<String>>>,
+}
+
+impl ProcessingNode for ThreadRecordingNode {
+    fn process(&mut self, input: ProcessingData) -> Result<ProcessingData> {
+        let name = std::thread::current()
+            .name()
+            .unwrap_or_default()
+            .to_string();
+        self.threads.lock().unwrap().push(name);
+        Ok(input)
+    }
+
+    fn node_id(&self) -> &str {
+        &self.id
+    }
+
+    fn node_type(&self) -> &str {
+        "thread_recording"
+    }
+
+    fn accepts_input(&self, _input: &ProcessingData) -> bool {
+        true
+    }
+
+    fn output_type(&self, _input: &ProcessingData) -> Option<String> {
+        None
+    }
+
+    fn reset(&mut self) {}
+
+    fn clone_node(&self) -> Box<dyn ProcessingNode> {
+        Box::new(ThreadRecordingNode {
+            id: self.id.clone(),
+            threads: self.threads.clone(),
+        })
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Drop the wall-clock latency of photoacoustic results
+fn without_latency(mut outputs: Vec<ProcessingData>) -> Vec<ProcessingData> {
+    for output in &mut outputs {
+        if let ProcessingData::PhotoacousticResult { metadata, .. } = output {
+            metadata.processing_latency_us = 0;
+        }
+    }
+    outputs
+}
+
+#[test]
+fn test_parallel_matches_serial_on_fan_out() -> Result<()> {
+    let mut serial = fan_out_graph(false)?;
+    let mut parallel = fan_out_graph(true)?;
+    assert!(!serial.parallel_execution());
+    assert!(parallel.parallel_execution());
+
+    let mut noise = NoiseGenerator::new(17);
+    for frame_number in 0..20 {
+        let input = frame(&mut noise, frame_number);
+        let expected = without_latency(serial.execute(input.clone())?);
+        let outputs = without_latency(parallel.execute(input)?);
+        assert_eq!(outputs.len(), 3);
+        assert!(matches!(
+            outputs[0],
+            ProcessingData::PhotoacousticResult { .. }
+        ));
+        assert_eq!(outputs, expected, "frame {}", frame_number);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_parallel_statistics() -> Result<()> {
+    let mut serial = fan_out_graph(false)?;
+    let mut parallel = fan_out_graph(true)?;
+    let mut noise = NoiseGenerator::new(3);
+    for frame_number in 0..5 {
+        let input = frame(&mut noise, frame_number);
+        serial.execute(input.clone())?;
+        parallel.execute(input)?;
+    }
+
+    for graph in [&serial, &parallel] {
+        let statistics = graph.get_statistics();
+        assert_eq!(statistics.total_executions, 5);
+        assert_eq!(statistics.node_statistics.len(), 8);
+        for (node_id, node_statistics) in &statistics.node_statistics {
+            assert_eq!(node_statistics.frames_processed, 5, "node {}", node_id);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_parallel_error_is_deterministic() -> Result<()> {
+    // Differential nodes need dual channel data and fail on a selected channel
+    let mut config = fan_out_config();
+    config
+        .nodes
+        .push(node("diff_a", "differential", serde_json::Value::Null));
+    config
+        .nodes
+        .push(node("diff_b", "differential", serde_json::Value::Null));
+    config.connections.push(connection("select_a", "diff_a"));
+    config.connections.push(connection("select_b", "diff_b"));
+    let mut graph = ProcessingGraph::from_config(&config)?;
+
+    let mut noise = NoiseGenerator::new(5);
+    let input = frame(&mut noise, 0);
+    let serial_error = graph.execute(input.clone()).unwrap_err().to_string();
+    assert!(
+        serial_error.contains("Node 'diff_a' failed")
+            || serial_error.contains("Node 'diff_b' failed"),
+        "{}",
+        serial_error
+    );
+
+    graph.set_parallel_execution(true);
+    for _ in 0..5 {
+        let parallel_error = graph.execute(input.clone()).unwrap_err().to_string();
+        assert_eq!(parallel_error, serial_error);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_parallel_levels_run_on_graph_pool() -> Result<()> {
+    let threads = Arc::new(Mutex::new(Vec::new()));
+    let mut graph = fan_out_graph(true)?;
+    for (id, source) in [("record_a", "select_a"), ("record_b", "select_b")] {
+        graph.add_node(Box::new(ThreadRecordingNode {
+            id: id.to_string(),
+            threads: threads.clone(),
+        }))?;
+        graph.connect(source, id)?;
+    }
+    let thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(2)
+        .thread_name(|index| format!("graph-pool-{}", index))
+        .build()?;
+    graph.set_thread_pool(Some(Arc::new(thread_pool)));
+
+    let mut noise = NoiseGenerator::new(11);
+    for frame_number in 0..5 {
+        graph.execute(frame(&mut noise, frame_number))?;
+    }
+    let threads = threads.lock().unwrap();
+    assert_eq!(threads.len(), 10);
+    assert!(
+        threads.iter().all(|name| name.starts_with("graph-pool-")),
+        "{:?}",
+        threads
+    );
+    Ok(())
+}
+
+#[test]
+fn test_node_panic_is_reported() -> Result<()> {
+    // `panic_b` runs in the same level as `bandpass_a`, `highpass_a` and `lowpass_b`
+    let mut graph = fan_out_graph(false)?;
+    graph.add_node(Box::new(PanickingNode {
+        id: "panic_b".to_string(),
+    }))?;
+    graph.connect("select_b", "panic_b")?;
+
+    let mut noise = NoiseGenerator::new(7);
+    for parallel in [false, true, true] {
+        graph.set_parallel_execution(parallel);
+        let error = graph.execute(frame(&mut noise, 0)).unwrap_err();
+        match error.downcast_ref::<ProcessingGraphError>() {
+            Some(ProcessingGraphError::NodePanicked(node_id, message)) => {
+                assert_eq!(node_id, "panic_b");
+                assert_eq!(message, "injected panic");
+            }
+            other => panic!("expected NodePanicked, got {:?}", other),
+        }
+    }
+
+    // The graph keeps running once the node is removed
+    graph.remove_node("panic_b")?;
+    assert_eq!(graph.execute(frame(&mut noise, 1))?.len(), 3);
+    Ok(())
+}
+
+#[test]
+fn test_parallel_execution_from_config() -> Result<()> {
+    let config: ProcessingConfig = serde_yml::from_str("enabled: true\n")?;
+    assert!(!config.performance.parallel_execution);
+
+    let config: ProcessingConfig =
+        serde_yml::from_str("performance:\n  parallel_execution: true\n")?;
+    assert!(config.performance.parallel_execution);
+    Ok(())
+}
@@ -0,0 +1,197 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Tests for password hashing, verification and rehash-on-verify
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_correct_and_incorrect_password`] | Hashes of every algorithm verify their password only, `rounds=N` hashes included |
+//! | [`test_verification_does_not_short_circuit`] | Unknown users cost a hash computation, hashes are compared in constant time |
+//! | [`test_weak_hash_upgraded_on_login`] | A hash with fewer rounds or a weaker algorithm is rehashed with the configured cost, a stronger one is never downgraded |
+//! | [`test_upgraded_hash_saved_to_config_file`] | The login handler replaces only the upgraded hash in the configuration file |
+
+use base64::Engine;
+use rocket::http::{ContentType, Status};
+use rocket::local::blocking::Client;
+use rocket::routes;
+use rust_photoacoustic::config::{
+    AccessConfig, Config, ConfigFilePath, PasswordHashAlgorithm, PasswordHashConfig, User,
+};
+use rust_photoacoustic::visualization::auth::oauth2::{
+    login, validate_user, validate_user_with_rehash, OxideState,
+};
+use rust_photoacoustic::visualization::pwhash;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+fn cost(algorithm: PasswordHashAlgorithm, rounds: u32) -> PasswordHashConfig {
+    PasswordHashConfig { algorithm, rounds }
+}
+
+/// Access configuration with a single `alice` user whose password hash is `hash`
+fn access_with_hash(hash: &str, password_hash: PasswordHashConfig) -> AccessConfig {
+    AccessConfig {
+        users: vec![User {
+            user: "alice".to_string(),
+            pass: base64::engine::general_purpose::STANDARD.encode(format!("{}\n", hash)),
+            ..User::default()
+        }],
+        password_hash,
+        ..AccessConfig::default()
+    }
+}
+
+fn elapsed(f: impl Fn()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..3 {
+        f();
+    }
+    start.elapsed()
+}
+
+#[test]
+fn test_correct_and_incorrect_password() {
+    for algorithm in [PasswordHashAlgorithm::Sha256, PasswordHashAlgorithm::Sha512] {
+        let hash = pwhash::hash("correct horse", &cost(algorithm, 2000)).unwrap();
+        assert!(hash.contains("rounds=2000"), "{}", hash);
+        assert_eq!(pwhash::rounds(&hash), Some(2000));
+        assert!(pwhash::verify("correct horse", &hash));
+        assert!(!pwhash::verify("correct horsE", &hash));
+        assert!(!pwhash::verify("", &hash));
+    }
+
+    // Hashes of `openssl passwd` without rounds parameter
+    let default_admin = User::default();
+    let access = AccessConfig::default();
+    assert_eq!(
+        validate_user("admin", "admin123", &access).map(|user| user.user),
+        Some(default_admin.user)
+    );
+    assert!(validate_user("admin", "admin124", &access).is_none());
+    assert!(validate_user("nobody", "admin123", &access).is_none());
+
+    // Malformed hashes never verify
+    assert!(!pwhash::verify("admin123", "$5$rounds=2000$"));
+    assert!(!pwhash::verify("admin123", "not a hash"));
+}
+
+#[test]
+fn test_verification_does_not_short_circuit() {
+    let expensive = cost(PasswordHashAlgorithm::Sha512, 50_000);
+    let hash = pwhash::hash("secret", &expensive).unwrap();
+    let access = access_with_hash(&hash, expensive);
+
+    // An unknown user takes about as long as a wrong password
+    let wrong_password = elapsed(|| assert!(validate_user("alice", "wrong", &access).is_none()));
+    let unknown_user = elapsed(|| assert!(validate_user("mallory", "wrong", &access).is_none()));
+    assert!(
+        unknown_user * 3 >= wrong_password,
+        "unknown user {:?}, wrong password {:?}",
+        unknown_user,
+        wrong_password
+    );
+
+    // The comparison inspects every byte
+    assert!(pwhash::constant_time_eq(b"abcdef", b"abcdef"));
+    assert!(!pwhash::constant_time_eq(b"abcdef", b"xbcdef"));
+    assert!(!pwhash::constant_time_eq(b"abcdef", b"abcdex"));
+    assert!(!pwhash::constant_time_eq(b"abcdef", b"abcde"));
+}
+
+#[test]
+fn test_weak_hash_upgraded_on_login() {
+    let configured = cost(PasswordHashAlgorithm::Sha512, 6000);
+    let weak = pwhash::hash("secret", &cost(PasswordHashAlgorithm::Sha512, 1000)).unwrap();
+    assert!(pwhash::needs_rehash(&weak, &configured));
+
+    let access = access_with_hash(&weak, configured.clone());
+    let (user, upgraded_pass) =
+        validate_user_with_rehash("alice", "secret", &access).expect("valid credentials");
+    assert_eq!(user.user, "alice");
+    let upgraded = String::from_utf8(
+        base64::engine::general_purpose::STANDARD
+            .decode(upgraded_pass.expect("hash upgraded"))
+            .unwrap(),
+    )
+    .unwrap();
+    assert!(upgraded.starts_with("$6$rounds=6000$"), "{}", upgraded);
+    assert!(pwhash::verify("secret", &upgraded));
+    assert!(!pwhash::needs_rehash(&upgraded, &configured));
+
+    // An up-to-date hash is kept, a wrong password is never rehashed
+    let access = access_with_hash(&upgraded, configured.clone());
+    let (_, upgraded_pass) = validate_user_with_rehash("alice", "secret", &access).unwrap();
+    assert!(upgraded_pass.is_none());
+    let access = access_with_hash(&weak, configured.clone());
+    assert!(validate_user_with_rehash("alice", "wrong", &access).is_none());
+
+    // A weaker algorithm is upgraded even with more rounds
+    let sha256 = pwhash::hash("secret", &cost(PasswordHashAlgorithm::Sha256, 10_000)).unwrap();
+    let verification = pwhash::verify_and_upgrade("secret", &sha256, &configured);
+    assert!(verification.valid);
+    assert!(verification
+        .upgraded_hash
+        .is_some_and(|hash| hash.starts_with("$6$")));
+
+    // Stronger hashes are not downgraded
+    let strong = pwhash::hash("secret", &cost(PasswordHashAlgorithm::Sha512, 8000)).unwrap();
+    assert!(!pwhash::needs_rehash(&strong, &configured));
+    let sha256_configured = cost(PasswordHashAlgorithm::Sha256, 5000);
+    assert!(!pwhash::needs_rehash(&weak, &sha256_configured));
+    assert!(pwhash::needs_rehash(
+        &sha256,
+        &cost(PasswordHashAlgorithm::Sha256, 20_000)
+    ));
+
+    // MD5 hashes are always upgraded
+    let md5 = ::pwhash::md5_crypt::hash("secret").unwrap();
+    assert!(pwhash::needs_rehash(&md5, &sha256_configured));
+}
+
+#[test]
+fn test_upgraded_hash_saved_to_config_file() {
+    let configured = cost(PasswordHashAlgorithm::Sha512, 6000);
+    let weak = pwhash::hash("secret", &cost(PasswordHashAlgorithm::Sha512, 1000)).unwrap();
+    let access = access_with_hash(&weak, configured);
+    let weak_pass = access.users[0].pass.clone();
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.yaml");
+    let original = format!(
+        "# Users of the web interface\naccess:\n  users:\n    - user: alice\n      pass: {} # keep me\n",
+        weak_pass
+    );
+    std::fs::write(&path, &original).unwrap();
+
+    let mut config = Config::default();
+    config.access = access;
+    let config = Arc::new(RwLock::new(config));
+    let rocket = rocket::build()
+        .manage(config.clone())
+        .manage(ConfigFilePath(path.clone()))
+        .manage(OxideState::preconfigured(
+            rocket::Config::figment().merge(("hmac_secret", "test-password-hash".to_string())),
+        ))
+        .mount("/", routes![login]);
+    let client = Client::tracked(rocket).expect("valid rocket instance");
+    let login_alice = || {
+        client
+            .post("/login")
+            .header(ContentType::Form)
+            .body("username=alice&password=secret&response_type=code&client_id=LaserSmartClient&redirect_uri=https%3A%2F%2Flocalhost%2Fcallback")
+            .dispatch()
+            .status()
+    };
+
+    assert_eq!(login_alice(), Status::Found);
+    let upgraded_pass = config.blocking_read().access.users[0].pass.clone();
+    assert_ne!(upgraded_pass, weak_pass);
+    let saved = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(saved, original.replace(&weak_pass, &upgraded_pass));
+
+    // The upgraded hash is up to date, the file is left as is
+    assert_eq!(login_alice(), Status::Found);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), saved);
+}
@@ -0,0 +1,315 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the native plugin node
+//!
+//! The tests build a small C plugin with the system C compiler (`cc`).
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_plugin_processes_frame`] | A gain plugin is applied to single and dual channel frames |
+//! | [`test_plugin_update_config`] | `config` is applied in place or requests a rebuild, errors are reported, `library_path` swaps the library |
+//! | [`test_plugin_instances_destroyed`] | Clones create their own instance, reset and drop release them |
+//! | [`test_plugin_load_errors`] | Missing libraries or symbols, ABI mismatches and `create()` failures are clear errors |
+//! | [`test_plugin_node_from_config`] | The node is created from its graph parameters and requires `library_path` |
+
+#![cfg(all(feature = "plugin-node", unix))]
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::AudioFrame;
+use rust_photoacoustic::processing::nodes::{
+    PluginNode, PluginNodeConfig, ProcessingData, ProcessingNode, PLUGIN_ABI_VERSION,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+
+/// Multiplies channel A by the `gain` of its configuration
+const GAIN_PLUGIN: &str = r#"
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+#include <string.h>
+
+typedef struct {
+    float gain;
+} gain_plugin;
+
+static int32_t live = 0;
+
+static float parse_gain(const char *config_json) {
+    const char *gain = strstr(config_json, "\"gain\":");
+    return gain ? strtof(gain + 7, NULL) : 1.0f;
+}
+
+uint32_t abi_version(void) { return ABI_VERSION; }
+
+int32_t live_instances(void) { return live; }
+
+void *create(const char *config_json) {
+    float gain = parse_gain(config_json);
+    if (gain < 0.0f) {
+        return NULL;
+    }
+    gain_plugin *plugin = malloc(sizeof *plugin);
+    if (plugin != NULL) {
+        plugin->gain = gain;
+        live++;
+    }
+    return plugin;
+}
+
+int32_t process(void *instance, const float *channel_a, const float *channel_b,
+                size_t len, float *out, size_t *out_len) {
+    gain_plugin *plugin = instance;
+    (void)channel_b;
+    for (size_t i = 0; i < len; i++) {
+        out[i] = channel_a[i] * plugin->gain;
+    }
+    *out_len = len;
+    return 0;
+}
+
+int32_t update_config(void *instance, const char *config_json) {
+    if (strstr(config_json, "\"mode\"") != NULL) {
+        return 1;
+    }
+    float gain = parse_gain(config_json);
+    if (gain < 0.0f) {
+        return -22;
+    }
+    ((gain_plugin *)instance)->gain = gain;
+    return 0;
+}
+
+#ifndef NO_DESTROY
+void destroy(void *instance) {
+    free(instance);
+    live--;
+}
+#endif
+"#;
+
+/// Compile the gain plugin with the given preprocessor definitions
+fn build_plugin(dir: &TempDir, name: &str, defines: &[&str]) -> PathBuf {
+    let source = dir.path().join("gain_plugin.c");
+    std::fs::write(&source, GAIN_PLUGIN).expect("Failed to write plugin source");
+    let library_path = dir
+        .path()
+        .join(format!("lib{}.{}", name, std::env::consts::DLL_EXTENSION));
+
+    let mut command = Command::new("cc");
+    command.args(["-shared", "-fPIC", "-o"]).arg(&library_path);
+    if !defines
+        .iter()
+        .any(|define| define.starts_with("ABI_VERSION"))
+    {
+        command.arg(format!("-DABI_VERSION={}", PLUGIN_ABI_VERSION));
+    }
+    for define in defines {
+        command.arg(format!("-D{}", define));
+    }
+    let status = command
+        .arg(&source)
+        .status()
+        .expect("A C compiler (cc) is required to build the test plugin");
+    assert!(status.success(), "Failed to build the test plugin");
+    library_path
+}
+
+fn plugin_node(library_path: PathBuf, gain: f32) -> Result<PluginNode> {
+    PluginNode::new(
+        "plugin".to_string(),
+        PluginNodeConfig {
+            library_path,
+            config: json!({ "gain": gain }),
+        },
+    )
+}
+
+fn single_channel(samples: Vec<f32>, frame_number: u64) -> ProcessingData {
+    ProcessingData::SingleChannel {
+        samples,
+        sample_rate: 48000,
+        timestamp: 1000,
+        frame_number,
+    }
+}
+
+/// Number of plugin instances alive in the library
+fn live_instances(library_path: &Path) -> i32 {
+    // Loading the library again returns the one already loaded by the node
+    unsafe {
+        let library = libloading::Library::new(library_path).unwrap();
+        let live_instances = library
+            .get::<unsafe extern "C" fn() -> i32>(b"live_instances\0")
+            .unwrap();
+        live_instances()
+    }
+}
+
+#[test]
+fn test_plugin_processes_frame() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut node = plugin_node(build_plugin(&dir, "gain", &[]), 2.0)?;
+    assert_eq!(node.node_type(), "plugin");
+
+    assert_eq!(
+        node.process(single_channel(vec![0.5, -0.25, 1.5], 1))?,
+        single_channel(vec![1.0, -0.5, 3.0], 1)
+    );
+
+    let frame = ProcessingData::AudioFrame(AudioFrame {
+        channel_a: vec![0.25, 0.5],
+        channel_b: vec![9.0, 9.0],
+        sample_rate: 44100,
+        timestamp: 2000,
+        frame_number: 2,
+    });
+    assert_eq!(node.output_type(&frame), Some("SingleChannel".to_string()));
+    assert_eq!(
+        node.process(frame)?,
+        ProcessingData::SingleChannel {
+            samples: vec![0.5, 1.0],
+            sample_rate: 44100,
+            timestamp: 2000,
+            frame_number: 2,
+        }
+    );
+
+    assert_eq!(
+        node.process(single_channel(vec![], 3))?,
+        single_channel(vec![], 3)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_plugin_update_config() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut node = plugin_node(build_plugin(&dir, "gain", &[]), 2.0)?;
+    assert!(node.supports_hot_reload());
+
+    assert!(node.update_config(&json!({ "config": { "gain": 3.0 } }))?);
+    assert_eq!(node.config().config, json!({ "gain": 3.0 }));
+    assert_eq!(
+        node.process(single_channel(vec![1.0], 1))?,
+        single_channel(vec![3.0], 1)
+    );
+
+    // The plugin asks for a new instance, or rejects the configuration
+    assert!(!node.update_config(&json!({ "config": { "mode": "fast" } }))?);
+    let error = node
+        .update_config(&json!({ "config": { "gain": -1.0 } }))
+        .expect_err("the plugin rejects a negative gain");
+    assert!(error.to_string().contains("-22"), "{}", error);
+    assert_eq!(
+        node.process(single_channel(vec![1.0], 2))?,
+        single_channel(vec![3.0], 2)
+    );
+
+    // A new library replaces the instance, an invalid one keeps the current
+    let other = build_plugin(&dir, "gain_copy", &[]);
+    assert!(node.update_config(&json!({ "library_path": other, "config": { "gain": 5.0 } }))?);
+    assert_eq!(node.config().library_path, other);
+    assert_eq!(
+        node.process(single_channel(vec![1.0], 3))?,
+        single_channel(vec![5.0], 3)
+    );
+    assert!(node
+        .update_config(&json!({ "library_path": dir.path().join("missing.so") }))
+        .is_err());
+    assert_eq!(node.config().library_path, other);
+    assert_eq!(
+        node.process(single_channel(vec![1.0], 4))?,
+        single_channel(vec![5.0], 4)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_plugin_instances_destroyed() -> Result<()> {
+    let dir = TempDir::new()?;
+    let library_path = build_plugin(&dir, "gain_instances", &[]);
+    let mut node = plugin_node(library_path.clone(), 2.0)?;
+    assert_eq!(live_instances(&library_path), 1);
+
+    let mut clone = node.clone_node();
+    assert_eq!(live_instances(&library_path), 1);
+    assert_eq!(
+        clone.process(single_channel(vec![1.0], 1))?,
+        single_channel(vec![2.0], 1)
+    );
+    assert_eq!(live_instances(&library_path), 2);
+
+    node.reset();
+    assert_eq!(live_instances(&library_path), 1);
+    node.process(single_channel(vec![1.0], 2))?;
+    assert_eq!(live_instances(&library_path), 2);
+
+    drop(clone);
+    drop(node);
+    assert_eq!(live_instances(&library_path), 0);
+    Ok(())
+}
+
+#[test]
+fn test_plugin_load_errors() -> Result<()> {
+    let dir = TempDir::new()?;
+
+    let error = plugin_node(dir.path().join("missing.so"), 1.0).unwrap_err();
+    assert!(
+        error.to_string().contains("Failed to load plugin library"),
+        "{}",
+        error
+    );
+
+    let error = plugin_node(build_plugin(&dir, "no_destroy", &["NO_DESTROY"]), 1.0).unwrap_err();
+    assert!(
+        error.to_string().contains("does not export 'destroy'"),
+        "{}",
+        error
+    );
+
+    let error = plugin_node(build_plugin(&dir, "abi_next", &["ABI_VERSION=2"]), 1.0).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("implements ABI version 2, expected version 1"),
+        "{}",
+        error
+    );
+
+    let error = plugin_node(build_plugin(&dir, "gain", &[]), -1.0).unwrap_err();
+    assert!(error.to_string().contains("create() failed"), "{}", error);
+    Ok(())
+}
+
+#[test]
+fn test_plugin_node_from_config() -> Result<()> {
+    let dir = TempDir::new()?;
+    let library_path = build_plugin(&dir, "gain", &[]);
+    let config: HashMap<String, serde_json::Value> = serde_json::from_value(json!({
+        "library_path": library_path,
+        "config": { "gain": 4.0 }
+    }))?;
+    let mut node = PluginNode::from_config("vendor_dsp".to_string(), config)?;
+    assert_eq!(node.node_id(), "vendor_dsp");
+    assert_eq!(node.config().library_path, library_path);
+    assert_eq!(
+        node.process(single_channel(vec![0.5], 1))?,
+        single_channel(vec![2.0], 1)
+    );
+
+    // Without configuration the plugin receives an empty object
+    let config: HashMap<String, serde_json::Value> =
+        serde_json::from_value(json!({ "library_path": library_path }))?;
+    let node = PluginNode::from_config("plugin".to_string(), config)?;
+    assert_eq!(node.config().config, json!({}));
+
+    assert!(PluginNode::from_config("plugin".to_string(), HashMap::new()).is_err());
+    Ok(())
+}
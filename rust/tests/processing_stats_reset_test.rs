@@ -0,0 +1,154 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the `POST /api/processing/stats/reset` endpoint
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_reset_returns_previous_summary`] | The reset answers the summary of the activity before the reset |
+//! | [`test_counters_only_cover_post_reset_activity`] | Executions after the reset are counted from zero |
+//! | [`test_reset_requires_live_graph_and_token`] | The reset needs authentication and a running graph |
+
+use rocket::http::{Header, Status};
+use rocket::local::asynchronous::Client;
+use rust_photoacoustic::acquisition::AudioFrame;
+use rust_photoacoustic::config::Config;
+use rust_photoacoustic::processing::{InputNode, ProcessingData, ProcessingGraph};
+use rust_photoacoustic::visualization::shared_state::SharedVisualizationState;
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+mod common;
+use common::{access_token, test_figment, TEST_HMAC_SECRET};
+
+/// Build a client whose live processing graph is `graph`, if any
+async fn build_test_client(graph: Option<Arc<RwLock<ProcessingGraph>>>) -> Client {
+    let visualization_state = SharedVisualizationState::new();
+    if let Some(graph) = graph {
+        visualization_state.set_live_processing_graph(graph).await;
+    }
+
+    let mut config = Config::default();
+    config.visualization.hmac_secret = TEST_HMAC_SECRET.to_string();
+
+    let rocket = rust_photoacoustic::visualization::server::build_rocket(
+        test_figment(),
+        Arc::new(RwLock::new(config)),
+        None,
+        Some(Arc::new(visualization_state)),
+        None,
+        None,
+        None,
+    )
+    .await;
+    Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance")
+}
+
+/// Live graph made of a single input node
+fn live_graph() -> Arc<RwLock<ProcessingGraph>> {
+    let mut graph = ProcessingGraph::new();
+    graph
+        .add_node(Box::new(InputNode::new("input".to_string())))
+        .unwrap();
+    Arc::new(RwLock::new(graph))
+}
+
+/// Run `count` frames through the graph
+async fn run_executions(graph: &Arc<RwLock<ProcessingGraph>>, count: u64) {
+    let mut graph = graph.write().await;
+    for frame_number in 0..count {
+        let frame = AudioFrame::new(vec![0.1; 64], vec![0.2; 64], 48000, frame_number);
+        graph.execute(ProcessingData::AudioFrame(frame)).unwrap();
+    }
+}
+
+async fn reset(client: &Client, token: &str) -> (Status, Option<Value>) {
+    let response = client
+        .post("/api/processing/stats/reset")
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .dispatch()
+        .await;
+    let status = response.status();
+    let body = response
+        .into_string()
+        .await
+        .and_then(|body| serde_json::from_str(&body).ok());
+    (status, body)
+}
+
+#[rocket::async_test]
+async fn test_reset_returns_previous_summary() {
+    let graph = live_graph();
+    let client = build_test_client(Some(graph.clone())).await;
+    let token = access_token(&client, "admin", "read:api write:api");
+
+    run_executions(&graph, 3).await;
+
+    let (status, summary) = reset(&client, &token).await;
+    assert_eq!(status, Status::Ok);
+    let summary = summary.expect("performance summary");
+    assert_eq!(summary["total_executions"], 3);
+    assert_eq!(summary["total_nodes"], 1);
+    let nodes = summary["nodes_by_performance"].as_array().unwrap();
+    assert_eq!(nodes.len(), 1);
+    assert_eq!(nodes[0]["node_id"], "input");
+    assert_eq!(nodes[0]["frames_processed"], 3);
+
+    // The published statistics are reset as well
+    let response = client
+        .get("/api/graph-statistics")
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    let statistics: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    assert_eq!(statistics["total_executions"], 0);
+    assert_eq!(
+        statistics["node_statistics"]["input"]["frames_processed"],
+        0
+    );
+}
+
+#[rocket::async_test]
+async fn test_counters_only_cover_post_reset_activity() {
+    let graph = live_graph();
+    let client = build_test_client(Some(graph.clone())).await;
+    let token = access_token(&client, "admin", "read:api write:api");
+
+    run_executions(&graph, 5).await;
+    let (status, _) = reset(&client, &token).await;
+    assert_eq!(status, Status::Ok);
+    run_executions(&graph, 2).await;
+
+    {
+        let graph = graph.read().await;
+        let statistics = graph.get_statistics();
+        assert_eq!(statistics.total_executions, 2);
+        assert_eq!(
+            graph.get_node_statistics("input").unwrap().frames_processed,
+            2
+        );
+    }
+
+    // A second reset reports only the activity since the first one
+    let (status, summary) = reset(&client, &token).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(summary.unwrap()["total_executions"], 2);
+    assert_eq!(graph.read().await.get_statistics().total_executions, 0);
+}
+
+#[rocket::async_test]
+async fn test_reset_requires_live_graph_and_token() {
+    let client = build_test_client(Some(live_graph())).await;
+    let response = client.post("/api/processing/stats/reset").dispatch().await;
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let client = build_test_client(None).await;
+    let token = access_token(&client, "admin", "read:api write:api");
+    let (status, _) = reset(&client, &token).await;
+    assert_eq!(status, Status::NotFound);
+}
@@ -0,0 +1,185 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the `computing_state` global exposed to Python scripts
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_script_context_schema`] | The snapshot holds the latest peak, concentration and polynomial coefficients |
+//! | [`test_python_node_reads_computing_state`] | A `PythonNode` script reads the concentration computed by other nodes, `None` without state, the last snapshot while the state is written |
+//! | [`test_python_driver_reads_computing_state`] | A `PythonActionDriver` script reads the state given through its action node |
+
+#![cfg(feature = "python-driver")]
+
+use anyhow::Result;
+use rust_photoacoustic::processing::computing_nodes::action_drivers::{
+    ActionDriver, PythonActionDriver, PythonDriverConfig,
+};
+use rust_photoacoustic::processing::computing_nodes::{
+    ComputingSharedData, ConcentrationResult, PeakResult, SharedComputingState,
+};
+use rust_photoacoustic::processing::nodes::{
+    ProcessingData, ProcessingNode, PythonNode, PythonNodeConfig,
+};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+use tokio::sync::RwLock;
+
+const COEFFICIENTS: [f64; 5] = [0.5, 1500.0, -2.0, 0.0, 0.0];
+
+/// Shared state holding one peak and one concentration result
+fn computing_state() -> SharedComputingState {
+    let timestamp = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+    let mut data = ComputingSharedData::default();
+    data.update_peak_result(
+        "peak_finder".to_string(),
+        PeakResult {
+            frequency: 2000.0,
+            amplitude: 0.25,
+            concentration_ppm: None,
+            timestamp,
+            coherence_score: 0.9,
+            processing_metadata: HashMap::new(),
+        },
+    );
+    data.update_concentration_result(
+        "concentration".to_string(),
+        ConcentrationResult {
+            concentration_ppm: 412.5,
+            concentration_variance: None,
+            source_peak_finder_id: "peak_finder".to_string(),
+            spectral_line_id: Some("CO2_line".to_string()),
+            polynomial_coefficients: COEFFICIENTS,
+            source_amplitude: 0.25,
+            source_frequency: 2000.0,
+            temperature_compensated: false,
+            timestamp,
+            processing_metadata: HashMap::new(),
+        },
+    );
+    Arc::new(RwLock::new(data))
+}
+
+fn write_script(dir: &TempDir, content: &str) -> PathBuf {
+    let script_path = dir.path().join("script.py");
+    std::fs::write(&script_path, content).expect("Failed to write test script");
+    script_path
+}
+
+#[tokio::test]
+async fn test_script_context_schema() {
+    let state = computing_state();
+    let context = state.read().await.script_context();
+
+    assert_eq!(context["peak_frequency"], 2000.0);
+    assert_eq!(context["peak_amplitude"], 0.25);
+    assert_eq!(context["concentration_ppm"], 412.5);
+    assert_eq!(context["polynomial_coefficients"][1], 1500.0);
+    assert_eq!(context["last_update"], 1_700_000_000.0);
+    assert_eq!(
+        context["peak_results"]["peak_finder"]["coherence_score"],
+        0.9f32 as f64
+    );
+    let concentration = &context["concentration_results"]["concentration"];
+    assert_eq!(concentration["concentration_ppm"], 412.5);
+    assert_eq!(concentration["spectral_line_id"], "CO2_line");
+    assert_eq!(concentration["source_peak_finder_id"], "peak_finder");
+    assert_eq!(concentration["timestamp"], 1_700_000_000.0);
+
+    // Without results the values are null
+    let empty = ComputingSharedData::default().script_context();
+    assert!(empty["concentration_ppm"].is_null());
+    assert!(empty["peak_results"].as_object().unwrap().is_empty());
+}
+
+#[test]
+fn test_python_node_reads_computing_state() -> Result<()> {
+    let dir = TempDir::new()?;
+    let script_path = write_script(
+        &dir,
+        r#"
+def process_data(data):
+    if computing_state is None:
+        data["samples"] = [-1.0]
+    else:
+        data["samples"] = [
+            computing_state["concentration_ppm"],
+            computing_state["concentration_results"]["concentration"]["concentration_ppm"],
+            computing_state["peak_results"]["peak_finder"]["frequency"],
+            computing_state["polynomial_coefficients"][1],
+        ]
+    return data
+"#,
+    );
+    let mut node = PythonNode::new(
+        "python_alert".to_string(),
+        PythonNodeConfig {
+            script_path,
+            ..Default::default()
+        },
+    );
+    let input = ProcessingData::SingleChannel {
+        samples: vec![0.0],
+        sample_rate: 48000,
+        timestamp: 0,
+        frame_number: 1,
+    };
+
+    let samples = |output: ProcessingData| match output {
+        ProcessingData::SingleChannel { samples, .. } => samples,
+        other => panic!("Unexpected output {:?}", other),
+    };
+
+    // Not attached to a graph
+    assert_eq!(samples(node.process(input.clone())?), vec![-1.0]);
+
+    let state = computing_state();
+    node.set_shared_computing_state(Some(Arc::clone(&state)));
+    assert_eq!(
+        samples(node.process(input.clone())?),
+        vec![412.5, 412.5, 2000.0, 1500.0]
+    );
+
+    // The script sees the values current at each call
+    state.try_write().unwrap().concentration_ppm = Some(800.0);
+    assert_eq!(samples(node.process(input.clone())?)[0], 800.0);
+
+    // While a computing node writes the state, the previous snapshot is used
+    let mut writer = state.try_write().unwrap();
+    writer.concentration_ppm = Some(900.0);
+    assert_eq!(samples(node.process(input.clone())?)[0], 800.0);
+    drop(writer);
+    assert_eq!(samples(node.process(input)?)[0], 900.0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_python_driver_reads_computing_state() -> Result<()> {
+    let dir = TempDir::new()?;
+    let script_path = write_script(
+        &dir,
+        r#"
+def initialize():
+    return {"status": "initialized"}
+
+def get_status():
+    if computing_state is None:
+        return -1.0
+    return computing_state["concentration_results"]["concentration"]["concentration_ppm"]
+"#,
+    );
+    let mut driver = PythonActionDriver::new(PythonDriverConfig {
+        script_path,
+        ..Default::default()
+    });
+    driver.initialize().await?;
+    assert_eq!(driver.get_status().await?["python_status"], -1.0);
+
+    driver.set_shared_computing_state(Some(computing_state()));
+    assert_eq!(driver.get_status().await?["python_status"], 412.5);
+    Ok(())
+}
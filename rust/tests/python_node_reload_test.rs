@@ -0,0 +1,173 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for reloading the script of a `PythonNode` in place
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_reload_picks_up_new_script`] | `reload()` runs the modified script and keeps the node id |
+//! | [`test_broken_script_keeps_previous`] | A script that does not import or lacks `process_data` is rejected |
+//! | [`test_auto_reload_on_change`] | With `auto_reload`, a modified file is used by the next frame, a broken one is skipped |
+//! | [`test_reload_through_update_config`] | Posting `script_path` swaps the script, an invalid one restores the previous path |
+
+#![cfg(feature = "python-driver")]
+
+use anyhow::Result;
+use rust_photoacoustic::processing::nodes::{
+    ProcessingData, ProcessingNode, PythonNode, PythonNodeConfig,
+};
+use serde_json::json;
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+
+/// Script multiplying single channel samples by `gain`
+fn gain_script(gain: f32) -> String {
+    format!(
+        r#"
+def initialize():
+    return {{"status": "initialized"}}
+
+def process_data(data):
+    data["samples"] = [s * {} for s in data["samples"]]
+    return data
+
+def shutdown():
+    return {{"status": "shutdown"}}
+"#,
+        gain
+    )
+}
+
+const SYNTAX_ERROR_SCRIPT: &str = "def process_data(data)\n    return data\n";
+const MISSING_FUNCTION_SCRIPT: &str = "def initialize():\n    return {}\n";
+const IMPORT_ERROR_SCRIPT: &str =
+    "import module_that_does_not_exist\n\ndef process_data(data):\n    return data\n";
+
+/// Write `content` with a modification time `age_seconds` after a fixed origin,
+/// so that successive writes are seen as changes whatever the file system resolution
+fn write_script(path: &Path, content: &str, age_seconds: u64) -> Result<()> {
+    fs::write(path, content)?;
+    File::options()
+        .write(true)
+        .open(path)?
+        .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000 + age_seconds))?;
+    Ok(())
+}
+
+fn process_sample(node: &mut PythonNode) -> Result<f32> {
+    let input = ProcessingData::SingleChannel {
+        samples: vec![1.0],
+        sample_rate: 48000,
+        timestamp: 0,
+        frame_number: 1,
+    };
+    match node.process(input)? {
+        ProcessingData::SingleChannel { samples, .. } => Ok(samples[0]),
+        other => panic!("Unexpected output {:?}", other),
+    }
+}
+
+fn gain_node(dir: &TempDir, auto_reload: bool) -> Result<PythonNode> {
+    let script_path = dir.path().join("gain.py");
+    write_script(&script_path, &gain_script(2.0), 0)?;
+    Ok(PythonNode::new(
+        "python_gain".to_string(),
+        PythonNodeConfig {
+            script_path,
+            auto_reload,
+            ..Default::default()
+        },
+    ))
+}
+
+#[test]
+fn test_reload_picks_up_new_script() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut node = gain_node(&dir, false)?;
+    assert_eq!(process_sample(&mut node)?, 2.0);
+
+    // Without auto_reload the change is ignored until an explicit reload
+    write_script(&node.config().script_path, &gain_script(3.0), 10)?;
+    assert_eq!(process_sample(&mut node)?, 2.0);
+
+    node.reload()?;
+    assert_eq!(process_sample(&mut node)?, 3.0);
+    assert_eq!(node.node_id(), "python_gain");
+    Ok(())
+}
+
+#[test]
+fn test_broken_script_keeps_previous() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut node = gain_node(&dir, false)?;
+    assert_eq!(process_sample(&mut node)?, 2.0);
+
+    for (age, broken) in [
+        SYNTAX_ERROR_SCRIPT,
+        MISSING_FUNCTION_SCRIPT,
+        IMPORT_ERROR_SCRIPT,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        write_script(&node.config().script_path, broken, 10 + age as u64)?;
+        assert!(node.reload().is_err(), "accepted {:?}", broken);
+        assert_eq!(process_sample(&mut node)?, 2.0);
+    }
+
+    // A missing file is rejected too
+    fs::remove_file(&node.config().script_path)?;
+    assert!(node.reload().is_err());
+    assert_eq!(process_sample(&mut node)?, 2.0);
+    Ok(())
+}
+
+#[test]
+fn test_auto_reload_on_change() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut node = gain_node(&dir, true)?;
+    let script_path = node.config().script_path.clone();
+    assert_eq!(process_sample(&mut node)?, 2.0);
+
+    write_script(&script_path, &gain_script(4.0), 10)?;
+    assert_eq!(process_sample(&mut node)?, 4.0);
+
+    // A broken edit does not interrupt the processing
+    write_script(&script_path, SYNTAX_ERROR_SCRIPT, 20)?;
+    assert_eq!(process_sample(&mut node)?, 4.0);
+    assert_eq!(process_sample(&mut node)?, 4.0);
+
+    // The fixed script is picked up
+    write_script(&script_path, &gain_script(5.0), 30)?;
+    assert_eq!(process_sample(&mut node)?, 5.0);
+    Ok(())
+}
+
+#[test]
+fn test_reload_through_update_config() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut node = gain_node(&dir, false)?;
+    assert!(node.supports_hot_reload());
+    assert_eq!(process_sample(&mut node)?, 2.0);
+
+    let other_script = dir.path().join("other.py");
+    write_script(&other_script, &gain_script(6.0), 0)?;
+    assert!(node.update_config(&json!({ "script_path": other_script }))?);
+    assert_eq!(node.config().script_path, other_script);
+    assert_eq!(process_sample(&mut node)?, 6.0);
+
+    let broken_script = dir.path().join("broken.py");
+    write_script(&broken_script, MISSING_FUNCTION_SCRIPT, 0)?;
+    assert!(node
+        .update_config(&json!({ "script_path": broken_script }))
+        .is_err());
+    assert_eq!(node.config().script_path, other_script);
+    assert_eq!(process_sample(&mut node)?, 6.0);
+
+    // Other parameters require the node to be rebuilt
+    assert!(!node.update_config(&json!({ "process_function": "other" }))?);
+    Ok(())
+}
@@ -0,0 +1,177 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the NumPy array path of the Python node
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_numpy_path_matches_list_path`] | A vectorized NumPy script gives the same frames as the equivalent list script |
+//! | [`test_numpy_path_accepts_returned_lists`] | Buffers returned as lists or float64 arrays are accepted on the NumPy path |
+//! | [`test_numpy_arrays_from_config`] | `numpy_arrays` is read from the node parameters and off by default |
+
+#![cfg(feature = "python-driver")]
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::AudioFrame;
+use rust_photoacoustic::processing::nodes::{
+    ProcessingData, ProcessingMetadata, ProcessingNode, PythonNode, PythonNodeConfig,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use tempfile::TempDir;
+
+/// `2x - 0.5` on every buffer, in place on the NumPy arrays
+const NUMPY_SCRIPT: &str = r#"
+import numpy as np
+
+def process_data(data):
+    for key in ("samples", "channel_a", "channel_b", "signal"):
+        if key in data:
+            assert isinstance(data[key], np.ndarray), key
+            assert data[key].dtype == np.float32, key
+            data[key] *= 2.0
+            data[key] -= 0.5
+    return data
+"#;
+
+/// `2x - 0.5` on every buffer, with lists
+const LIST_SCRIPT: &str = r#"
+def process_data(data):
+    for key in ("samples", "channel_a", "channel_b", "signal"):
+        if key in data:
+            assert isinstance(data[key], list), key
+            data[key] = [x * 2.0 - 0.5 for x in data[key]]
+    return data
+"#;
+
+fn python_node(dir: &TempDir, script: &str, numpy_arrays: bool) -> Result<PythonNode> {
+    let script_path = dir.path().join(format!("script_{}.py", numpy_arrays));
+    std::fs::write(&script_path, script)?;
+    Ok(PythonNode::new(
+        "python".to_string(),
+        PythonNodeConfig {
+            script_path,
+            numpy_arrays,
+            ..Default::default()
+        },
+    ))
+}
+
+fn samples(len: usize, phase: f32) -> Vec<f32> {
+    (0..len).map(|i| (i as f32 * 0.013 + phase).sin()).collect()
+}
+
+fn inputs() -> Vec<ProcessingData> {
+    vec![
+        ProcessingData::AudioFrame(AudioFrame {
+            channel_a: samples(4096, 0.0),
+            channel_b: samples(4096, 1.0),
+            sample_rate: 48000,
+            timestamp: 1000,
+            frame_number: 1,
+        }),
+        ProcessingData::SingleChannel {
+            samples: samples(4096, 2.0),
+            sample_rate: 48000,
+            timestamp: 2000,
+            frame_number: 2,
+        },
+        ProcessingData::DualChannel {
+            channel_a: samples(4096, 3.0),
+            channel_b: samples(4096, 4.0),
+            sample_rate: 48000,
+            timestamp: 3000,
+            frame_number: 3,
+        },
+        ProcessingData::PhotoacousticResult {
+            signal: samples(512, 5.0),
+            metadata: ProcessingMetadata {
+                original_frame_number: 4,
+                original_timestamp: 4000,
+                sample_rate: 48000,
+                processing_steps: vec!["bandpass".to_string()],
+                processing_latency_us: 120,
+                snr_db: None,
+            },
+        },
+    ]
+}
+
+#[test]
+fn test_numpy_path_matches_list_path() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut numpy_node = python_node(&dir, NUMPY_SCRIPT, true)?;
+    let mut list_node = python_node(&dir, LIST_SCRIPT, false)?;
+
+    for input in inputs() {
+        let numpy_output = numpy_node.process(input.clone())?;
+        let list_output = list_node.process(input.clone())?;
+        assert_eq!(numpy_output, list_output);
+        assert_ne!(numpy_output, input);
+    }
+
+    // The expected values, computed in Rust
+    if let ProcessingData::SingleChannel {
+        samples: output, ..
+    } = numpy_node.process(inputs()[1].clone())?
+    {
+        let expected: Vec<f32> = samples(4096, 2.0).iter().map(|x| x * 2.0 - 0.5).collect();
+        assert_eq!(output, expected);
+    } else {
+        panic!("Expected SingleChannel output");
+    }
+    Ok(())
+}
+
+#[test]
+fn test_numpy_path_accepts_returned_lists() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut node = python_node(
+        &dir,
+        r#"
+import numpy as np
+
+def process_data(data):
+    data["channel_a"] = [float(x) + 1.0 for x in data["channel_a"]]
+    data["channel_b"] = data["channel_b"].astype(np.float64) - 1.0
+    return data
+"#,
+        true,
+    )?;
+
+    let output = node.process(ProcessingData::DualChannel {
+        channel_a: vec![0.0, 0.5, 1.0],
+        channel_b: vec![0.25, 0.5, 0.75],
+        sample_rate: 48000,
+        timestamp: 0,
+        frame_number: 1,
+    })?;
+    match output {
+        ProcessingData::DualChannel {
+            channel_a,
+            channel_b,
+            frame_number,
+            ..
+        } => {
+            assert_eq!(channel_a, vec![1.0, 1.5, 2.0]);
+            assert_eq!(channel_b, vec![-0.75, -0.5, -0.25]);
+            assert_eq!(frame_number, 1);
+        }
+        other => panic!("Unexpected output {:?}", other),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_numpy_arrays_from_config() -> Result<()> {
+    assert!(!PythonNodeConfig::default().numpy_arrays);
+
+    let config: HashMap<String, serde_json::Value> = serde_json::from_value(json!({
+        "script_path": "processor.py",
+        "numpy_arrays": true
+    }))?;
+    let node = PythonNode::from_config("python".to_string(), config)?;
+    assert!(node.config().numpy_arrays);
+    Ok(())
+}
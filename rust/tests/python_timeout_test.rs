@@ -0,0 +1,252 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the execution timeout of the Python node and driver
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_drop_policy_interrupts_and_fails_frame`] | A script stuck in a loop is interrupted, the frame fails and the next one is processed |
+//! | [`test_pass_through_policy_returns_input`] | With `pass_through` the interrupted frame is returned unmodified |
+//! | [`test_graph_counts_timeouts`] | The graph drops the frame, keeps executing and reports `timeouts` in the node statistics |
+//! | [`test_timeout_policy_from_config`] | `timeout_policy` is read from the node parameters, unknown values are rejected |
+//! | [`test_driver_interrupts_script`] | A `PythonActionDriver` call stuck in a loop is interrupted and counted in its status |
+
+#![cfg(feature = "python-driver")]
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::AudioFrame;
+use rust_photoacoustic::processing::computing_nodes::action_drivers::{
+    ActionDriver, MeasurementData, PythonActionDriver, PythonDriverConfig,
+};
+use rust_photoacoustic::processing::nodes::{
+    InputNode, ProcessingData, ProcessingNode, PythonCallTimeout, PythonNode, PythonNodeConfig,
+    PythonTimeoutPolicy,
+};
+use rust_photoacoustic::processing::ProcessingGraph;
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+use tempfile::TempDir;
+
+/// Loops forever on frame 1, doubles the samples of the other frames
+const STUCK_ON_FIRST_FRAME: &str = r#"
+import time
+
+def process_data(data):
+    if data["frame_number"] == 1:
+        while True:
+            time.sleep(0.01)
+    for key in ("samples", "channel_a", "channel_b"):
+        if key in data:
+            data[key] = [x * 2.0 for x in data[key]]
+    return data
+"#;
+
+/// Upper bound for a one second timeout to fire, generous for loaded machines
+const TIMEOUT_BOUND: Duration = Duration::from_secs(10);
+
+fn write_script(dir: &TempDir, content: &str) -> PathBuf {
+    let script_path = dir.path().join("script.py");
+    std::fs::write(&script_path, content).expect("Failed to write test script");
+    script_path
+}
+
+fn stuck_node(dir: &TempDir, timeout_policy: PythonTimeoutPolicy) -> PythonNode {
+    PythonNode::new(
+        "python_stuck".to_string(),
+        PythonNodeConfig {
+            script_path: write_script(dir, STUCK_ON_FIRST_FRAME),
+            timeout_seconds: 1,
+            timeout_policy,
+            ..Default::default()
+        },
+    )
+}
+
+fn frame(frame_number: u64) -> ProcessingData {
+    ProcessingData::SingleChannel {
+        samples: vec![0.25, 0.5],
+        sample_rate: 48000,
+        timestamp: 0,
+        frame_number,
+    }
+}
+
+fn samples(output: ProcessingData) -> Vec<f32> {
+    match output {
+        ProcessingData::SingleChannel { samples, .. } => samples,
+        other => panic!("Unexpected output {:?}", other),
+    }
+}
+
+#[test]
+fn test_drop_policy_interrupts_and_fails_frame() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut node = stuck_node(&dir, PythonTimeoutPolicy::Drop);
+    assert_eq!(samples(node.process(frame(0))?), vec![0.5, 1.0]);
+
+    let start = Instant::now();
+    let error = node
+        .process(frame(1))
+        .expect_err("the stuck frame must fail");
+    assert!(
+        start.elapsed() < TIMEOUT_BOUND,
+        "took {:?}",
+        start.elapsed()
+    );
+    let timeout = error
+        .downcast_ref::<PythonCallTimeout>()
+        .expect("a timeout error");
+    assert_eq!(timeout.function, "process_data");
+    assert_eq!(timeout.timeout, Duration::from_secs(1));
+    assert_eq!(node.timeout_count(), 1);
+
+    // The following frames are processed normally
+    assert_eq!(samples(node.process(frame(2))?), vec![0.5, 1.0]);
+    assert_eq!(samples(node.process(frame(3))?), vec![0.5, 1.0]);
+    assert_eq!(node.timeout_count(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_pass_through_policy_returns_input() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut node = stuck_node(&dir, PythonTimeoutPolicy::PassThrough);
+
+    let start = Instant::now();
+    assert_eq!(node.process(frame(1))?, frame(1));
+    assert!(
+        start.elapsed() < TIMEOUT_BOUND,
+        "took {:?}",
+        start.elapsed()
+    );
+    assert_eq!(ProcessingNode::timeout_count(&node), 1);
+
+    assert_eq!(samples(node.process(frame(2))?), vec![0.5, 1.0]);
+    Ok(())
+}
+
+#[test]
+fn test_graph_counts_timeouts() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut graph = ProcessingGraph::new();
+    graph.add_node(Box::new(InputNode::new("input".to_string())))?;
+    graph.add_node(Box::new(stuck_node(&dir, PythonTimeoutPolicy::Drop)))?;
+    graph.connect("input", "python_stuck")?;
+    graph.set_output_node("python_stuck")?;
+
+    let audio_frame = |frame_number| {
+        ProcessingData::AudioFrame(AudioFrame {
+            channel_a: vec![0.25, 0.5],
+            channel_b: vec![0.5, 0.25],
+            sample_rate: 48000,
+            timestamp: 0,
+            frame_number,
+        })
+    };
+
+    assert!(graph.execute(audio_frame(1)).is_err());
+    let outputs = graph.execute(audio_frame(2))?;
+    assert_eq!(outputs.len(), 1);
+
+    let stats = &graph.get_statistics().node_statistics["python_stuck"];
+    assert_eq!(stats.timeouts, 1);
+    assert_eq!(stats.frames_processed, 1);
+    assert!(stats.to_string().contains("1 timeouts"));
+
+    // A reset restarts the count without losing later timeouts
+    graph.get_statistics_mut().reset_all_statistics();
+    assert!(graph.execute(audio_frame(1)).is_err());
+    assert_eq!(
+        graph.get_statistics().node_statistics["python_stuck"].timeouts,
+        1
+    );
+    Ok(())
+}
+
+#[test]
+fn test_timeout_policy_from_config() -> Result<()> {
+    let parse = |policy: serde_json::Value| {
+        let config: HashMap<String, serde_json::Value> = serde_json::from_value(json!({
+            "script_path": "processor.py",
+            "timeout_policy": policy
+        }))
+        .unwrap();
+        PythonNode::from_config("python".to_string(), config)
+    };
+
+    assert_eq!(
+        PythonNodeConfig::default().timeout_policy,
+        PythonTimeoutPolicy::Drop
+    );
+    assert_eq!(
+        parse(json!("pass_through"))?.config().timeout_policy,
+        PythonTimeoutPolicy::PassThrough
+    );
+    assert_eq!(
+        parse(json!("drop"))?.config().timeout_policy,
+        PythonTimeoutPolicy::Drop
+    );
+    assert!(parse(json!("retry")).is_err());
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_driver_interrupts_script() -> Result<()> {
+    let dir = TempDir::new()?;
+    let script_path = write_script(
+        &dir,
+        r#"
+import time
+
+def initialize():
+    return {"status": "initialized"}
+
+def on_measurement(data):
+    if data["concentration_ppm"] > 1000.0:
+        while True:
+            time.sleep(0.01)
+    return {"status": "ok"}
+
+def get_status():
+    return "running"
+"#,
+    );
+    let mut driver = PythonActionDriver::new(PythonDriverConfig {
+        script_path,
+        timeout_seconds: 1,
+        ..Default::default()
+    });
+    driver.initialize().await?;
+
+    let measurement = |concentration_ppm| MeasurementData {
+        concentration_ppm,
+        source_node_id: "concentration".to_string(),
+        peak_amplitude: 0.5,
+        peak_frequency: 2000.0,
+        timestamp: SystemTime::now(),
+        metadata: HashMap::new(),
+    };
+
+    let start = Instant::now();
+    let error = driver
+        .update_action(&measurement(2000.0))
+        .await
+        .expect_err("the stuck call must fail");
+    assert!(
+        start.elapsed() < TIMEOUT_BOUND,
+        "took {:?}",
+        start.elapsed()
+    );
+    assert!(error.is::<PythonCallTimeout>(), "{}", error);
+    assert_eq!(driver.timeout_count(), 1);
+
+    // The driver keeps working
+    driver.update_action(&measurement(400.0)).await?;
+    let status = driver.get_status().await?;
+    assert_eq!(status["python_status"], "running");
+    assert_eq!(status["timeouts"], 1);
+    Ok(())
+}
@@ -0,0 +1,184 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the rate limiting of protected routes
+//!
+//! Requests go to the protected test routes (`/api/test/<path..>` and
+//! `/api/test_rate_limit`) with bearer tokens issued by the server's own
+//! issuer, see [`common::access_token`]:
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_global_limit_returns_429_with_retry_after`] | Requests past the limit get 429 and a `Retry-After` header |
+//! | [`test_traffic_resumes_after_window`] | Requests are accepted again once the bucket refilled |
+//! | [`test_limit_per_token_subject`] | A token is limited across client IPs |
+//! | [`test_limit_per_client_ip`] | An IP is limited across token subjects |
+//! | [`test_route_override_without_global_limit`] | `rate_limit = N` routes are limited while the global limit is disabled |
+
+use rocket::http::{Header, Status};
+use rocket::local::asynchronous::{Client, LocalResponse};
+use rust_photoacoustic::config::{Config, RateLimitConfig, User};
+use serde_json::Value;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+mod common;
+use common::{access_token, test_figment, TEST_HMAC_SECRET};
+
+async fn build_test_client(config: Config) -> Client {
+    let rocket = rust_photoacoustic::visualization::server::build_rocket(
+        test_figment(),
+        Arc::new(RwLock::new(config)),
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+    Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance")
+}
+
+/// Configuration with an `operator` user next to the default `admin`
+fn test_config(rate_limit: RateLimitConfig) -> Config {
+    let mut config = Config::default();
+    config.visualization.hmac_secret = TEST_HMAC_SECRET.to_string();
+    config.visualization.rate_limit = rate_limit;
+    config.access.users.push(User {
+        user: "operator".to_string(),
+        permissions: vec!["read:api".to_string()],
+        ..User::default()
+    });
+    config
+}
+
+/// Three requests per one second window
+fn three_per_second() -> RateLimitConfig {
+    RateLimitConfig {
+        enabled: true,
+        requests: 3,
+        window_secs: 1,
+    }
+}
+
+/// `GET path` with a bearer token from `remote`
+async fn get<'c>(client: &'c Client, path: &str, token: &str, remote: &str) -> LocalResponse<'c> {
+    client
+        .get(path.to_string())
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .remote(remote.parse::<SocketAddr>().unwrap())
+        .dispatch()
+        .await
+}
+
+/// Parse the `Retry-After` header of a 429 response
+fn retry_after(response: &LocalResponse<'_>) -> u64 {
+    response
+        .headers()
+        .get_one("Retry-After")
+        .expect("Retry-After header present")
+        .parse()
+        .expect("Retry-After is a number of seconds")
+}
+
+#[rocket::async_test]
+async fn test_global_limit_returns_429_with_retry_after() {
+    let client = build_test_client(test_config(three_per_second())).await;
+    let token = access_token(&client, "admin", "read:api");
+
+    for _ in 0..3 {
+        let response = get(&client, "/api/test/ping", &token, "10.0.0.1:8000").await;
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    let response = get(&client, "/api/test/ping", &token, "10.0.0.1:8000").await;
+    assert_eq!(response.status(), Status::TooManyRequests);
+    assert!(retry_after(&response) >= 1);
+    let body: Value = serde_json::from_str(&response.into_string().await.unwrap()).unwrap();
+    assert_eq!(body["error"], "Too many requests");
+}
+
+#[rocket::async_test]
+async fn test_traffic_resumes_after_window() {
+    let client = build_test_client(test_config(three_per_second())).await;
+    let token = access_token(&client, "admin", "read:api");
+
+    for _ in 0..3 {
+        get(&client, "/api/test/ping", &token, "10.0.0.1:8000").await;
+    }
+    let response = get(&client, "/api/test/ping", &token, "10.0.0.1:8000").await;
+    assert_eq!(response.status(), Status::TooManyRequests);
+    let wait = retry_after(&response);
+    assert_eq!(wait, 1, "one token refills within the one second window");
+
+    tokio::time::sleep(std::time::Duration::from_millis(wait * 1000 + 100)).await;
+
+    let response = get(&client, "/api/test/ping", &token, "10.0.0.1:8000").await;
+    assert_eq!(response.status(), Status::Ok);
+}
+
+#[rocket::async_test]
+async fn test_limit_per_token_subject() {
+    let client = build_test_client(test_config(three_per_second())).await;
+    let token = access_token(&client, "admin", "read:api");
+
+    for remote in ["10.0.0.1:8000", "10.0.0.2:8000", "10.0.0.3:8000"] {
+        let response = get(&client, "/api/test/ping", &token, remote).await;
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    // A fresh IP does not help once the token subject is exhausted
+    let response = get(&client, "/api/test/ping", &token, "10.0.0.4:8000").await;
+    assert_eq!(response.status(), Status::TooManyRequests);
+
+    // Other subjects are not affected
+    let other_token = access_token(&client, "operator", "read:api");
+    let response = get(&client, "/api/test/ping", &other_token, "10.0.0.5:8000").await;
+    assert_eq!(response.status(), Status::Ok);
+}
+
+#[rocket::async_test]
+async fn test_limit_per_client_ip() {
+    let client = build_test_client(test_config(three_per_second())).await;
+    let admin_token = access_token(&client, "admin", "read:api");
+    let operator_token = access_token(&client, "operator", "read:api");
+
+    for token in [&admin_token, &operator_token, &admin_token] {
+        let response = get(&client, "/api/test/ping", token, "10.0.0.1:8000").await;
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    // The operator still has tokens left, but its IP does not
+    let response = get(&client, "/api/test/ping", &operator_token, "10.0.0.1:8000").await;
+    assert_eq!(response.status(), Status::TooManyRequests);
+}
+
+#[rocket::async_test]
+async fn test_route_override_without_global_limit() {
+    let client = build_test_client(test_config(RateLimitConfig {
+        enabled: false,
+        requests: 3,
+        window_secs: 1,
+    }))
+    .await;
+    let token = access_token(&client, "admin", "read:api");
+
+    // Routes without an override are not limited
+    for _ in 0..10 {
+        let response = get(&client, "/api/test/ping", &token, "10.0.0.1:8000").await;
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    // `/api/test_rate_limit` allows two requests per window
+    for _ in 0..2 {
+        let response = get(&client, "/api/test_rate_limit", &token, "10.0.0.1:8000").await;
+        assert_eq!(response.status(), Status::Ok);
+    }
+    let response = get(&client, "/api/test_rate_limit", &token, "10.0.0.1:8000").await;
+    assert_eq!(response.status(), Status::TooManyRequests);
+    assert!(retry_after(&response) >= 1);
+}
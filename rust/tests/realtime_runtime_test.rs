@@ -0,0 +1,120 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the dedicated runtime of the real-time tasks
+//!
+//! CPU affinity is only checked where it is supported (Linux). The tests pin
+//! threads to a CPU the test process may already run on, so they pass inside
+//! containers restricted to a subset of the host CPUs.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_thread_count_is_respected`] | The runtime and its thread pool have the configured number of named threads |
+//! | [`test_affinity_applied_or_ignored`] | Worker, blocking and thread pool threads run on the configured CPU, or affinity is ignored where unsupported |
+//! | [`test_invalid_settings`] | A zero thread count, an empty CPU list and an unavailable CPU are rejected |
+//! | [`test_from_config`] | The runtime is only created when configured, with the thread count defaulting to the CPU count |
+
+use anyhow::Result;
+use rust_photoacoustic::config::utils::validate_specific_rules;
+use rust_photoacoustic::config::{Config, DaemonConfig};
+use rust_photoacoustic::daemon::realtime_runtime::{
+    affinity_supported, current_thread_affinity, RealtimeRuntime, REALTIME_POOL_THREAD_NAME,
+    REALTIME_THREAD_NAME,
+};
+
+/// A CPU the test process may run on, where affinity is supported
+fn available_cpu() -> Option<usize> {
+    current_thread_affinity()
+        .ok()
+        .and_then(|cpus| cpus.last().copied())
+}
+
+#[test]
+fn test_thread_count_is_respected() -> Result<()> {
+    let runtime = RealtimeRuntime::new(3, None)?;
+    assert_eq!(runtime.worker_threads(), 3);
+    assert_eq!(runtime.handle().metrics().num_workers(), 3);
+    assert_eq!(runtime.cpu_affinity(), None);
+
+    let thread_name = runtime.handle().block_on(async {
+        tokio::spawn(async { std::thread::current().name().map(str::to_string) }).await
+    })?;
+    assert_eq!(thread_name.as_deref(), Some(REALTIME_THREAD_NAME));
+
+    let thread_pool = runtime.thread_pool();
+    assert_eq!(thread_pool.current_num_threads(), 3);
+    let pool_thread_name =
+        thread_pool.install(|| std::thread::current().name().map(str::to_string));
+    assert!(pool_thread_name
+        .unwrap()
+        .starts_with(REALTIME_POOL_THREAD_NAME));
+    Ok(())
+}
+
+#[test]
+fn test_affinity_applied_or_ignored() -> Result<()> {
+    let Some(cpu) = available_cpu() else {
+        assert!(!affinity_supported());
+        assert_eq!(
+            current_thread_affinity().unwrap_err().kind(),
+            std::io::ErrorKind::Unsupported
+        );
+
+        // The runtime still starts, with its threads left unpinned
+        let runtime = RealtimeRuntime::new(2, Some(vec![0]))?;
+        assert_eq!(runtime.cpu_affinity(), None);
+        assert_eq!(runtime.handle().block_on(async { 1 + 1 }), 2);
+        return Ok(());
+    };
+
+    let runtime = RealtimeRuntime::new(2, Some(vec![cpu]))?;
+    assert_eq!(runtime.cpu_affinity(), Some(&[cpu][..]));
+    let (worker, blocking) = runtime.handle().block_on(async {
+        let worker = tokio::spawn(async { current_thread_affinity() }).await;
+        let blocking = tokio::task::spawn_blocking(current_thread_affinity).await;
+        (worker, blocking)
+    });
+    assert_eq!(worker??, vec![cpu]);
+    assert_eq!(blocking??, vec![cpu]);
+    assert_eq!(
+        runtime.thread_pool().install(current_thread_affinity)?,
+        vec![cpu]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_invalid_settings() {
+    assert!(RealtimeRuntime::new(0, None).is_err());
+    assert!(RealtimeRuntime::new(2, Some(Vec::new())).is_err());
+    if affinity_supported() {
+        assert!(RealtimeRuntime::new(2, Some(vec![1 << 20])).is_err());
+    }
+
+    let mut config = Config::default();
+    assert!(validate_specific_rules(&config).is_ok());
+    config.daemon.realtime_threads = Some(0);
+    assert!(validate_specific_rules(&config).is_err());
+    config.daemon.realtime_threads = Some(2);
+    config.daemon.realtime_cpu_affinity = Some(Vec::new());
+    assert!(validate_specific_rules(&config).is_err());
+}
+
+#[test]
+fn test_from_config() -> Result<()> {
+    assert!(RealtimeRuntime::from_config(&DaemonConfig::default())?.is_none());
+
+    let config: DaemonConfig = serde_yml::from_str("realtime_threads: 2\n")?;
+    let runtime = RealtimeRuntime::from_config(&config)?.unwrap();
+    assert_eq!(runtime.worker_threads(), 2);
+    assert_eq!(runtime.cpu_affinity(), None);
+
+    let cpu = available_cpu().unwrap_or(0);
+    let config: DaemonConfig =
+        serde_yml::from_str(&format!("realtime_cpu_affinity: [{}, {}]\n", cpu, cpu))?;
+    let runtime = RealtimeRuntime::from_config(&config)?.unwrap();
+    assert_eq!(runtime.worker_threads(), 2);
+    assert_eq!(runtime.handle().metrics().num_workers(), 2);
+    Ok(())
+}
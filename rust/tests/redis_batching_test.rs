@@ -0,0 +1,253 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the batched delivery of the Redis action driver
+//!
+//! The driver talks to a minimal in-process server speaking the Redis
+//! protocol, which records every command it receives, so no Redis server is
+//! needed.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_batches_of_configured_size`] | Measurements are sent in order, in pipelines of the configured size, and the partial batch is flushed on shutdown |
+//! | [`test_partial_batch_flushed_after_max_latency`] | A batch that does not fill is sent once its oldest measurement waited the maximum latency |
+//! | [`test_key_value_batching_from_config`] | `batch_size` and `batch_max_latency_ms` configure the driver, key-value batches write the measurement and latest keys |
+//! | [`test_zero_max_latency_rejected`] | A batching configuration with `batch_max_latency_ms: 0` is refused |
+
+use anyhow::Result;
+use rust_photoacoustic::processing::computing_nodes::action_drivers::{
+    create_action_driver, ActionDriver, MeasurementData, RedisActionDriver,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Commands received by the fake server, as lists of arguments
+type Commands = Arc<Mutex<Vec<Vec<String>>>>;
+
+/// Start a fake Redis server on a free local port
+///
+/// Returns its URL and the commands it receives.
+async fn start_fake_redis() -> Result<(String, Commands)> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("redis://{}", listener.local_addr()?);
+    let commands: Commands = Arc::new(Mutex::new(Vec::new()));
+
+    let received = Arc::clone(&commands);
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(serve_connection(stream, Arc::clone(&received)));
+        }
+    });
+    Ok((url, commands))
+}
+
+/// Answer the commands of one client connection until it closes
+async fn serve_connection(stream: TcpStream, commands: Commands) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await? == 0 {
+            return Ok(());
+        }
+        let count: usize = header.trim_end().trim_start_matches('*').parse()?;
+
+        let mut args = Vec::with_capacity(count);
+        for _ in 0..count {
+            let mut length = String::new();
+            reader.read_line(&mut length).await?;
+            let length: usize = length.trim_end().trim_start_matches('$').parse()?;
+            let mut arg = vec![0; length + 2];
+            reader.read_exact(&mut arg).await?;
+            arg.truncate(length);
+            args.push(String::from_utf8(arg)?);
+        }
+
+        let reply = match args[0].to_uppercase().as_str() {
+            "PING" => "+PONG\r\n".to_string(),
+            "ECHO" => format!("${}\r\n{}\r\n", args[1].len(), args[1]),
+            "PUBLISH" => ":0\r\n".to_string(),
+            _ => "+OK\r\n".to_string(),
+        };
+        commands.lock().unwrap().push(args);
+        writer.write_all(reply.as_bytes()).await?;
+    }
+}
+
+/// Received commands named `name`
+fn commands_named(commands: &Commands, name: &str) -> Vec<Vec<String>> {
+    commands
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|args| args[0].eq_ignore_ascii_case(name))
+        .cloned()
+        .collect()
+}
+
+/// Concentrations of the published measurements, in the order received
+fn published_concentrations(commands: &Commands) -> Vec<f64> {
+    commands_named(commands, "PUBLISH")
+        .iter()
+        .map(|args| {
+            let payload: Value = serde_json::from_str(&args[2]).unwrap();
+            payload["concentration_ppm"].as_f64().unwrap()
+        })
+        .collect()
+}
+
+fn measurement(concentration_ppm: f64) -> MeasurementData {
+    MeasurementData {
+        concentration_ppm,
+        source_node_id: "concentration".to_string(),
+        peak_amplitude: 0.1,
+        peak_frequency: 2000.0,
+        timestamp: UNIX_EPOCH + Duration::from_secs(1_700_000_000 + concentration_ppm as u64),
+        metadata: HashMap::new(),
+    }
+}
+
+#[tokio::test]
+async fn test_batches_of_configured_size() -> Result<()> {
+    let (url, commands) = start_fake_redis().await?;
+    let mut driver = RedisActionDriver::new_pubsub(&url, "measurements")
+        .with_batching(10, Duration::from_secs(60));
+    driver.initialize().await?;
+
+    for concentration in 0..25 {
+        driver
+            .update_action(&measurement(concentration as f64))
+            .await?;
+    }
+
+    // Two full batches were sent, five measurements wait for the third one
+    let expected: Vec<f64> = (0..25).map(f64::from).collect();
+    assert_eq!(published_concentrations(&commands), &expected[..20]);
+    let batching = driver.get_status().await?["batching"].clone();
+    assert_eq!(batching["batch_size"], 10);
+    assert_eq!(batching["batches_sent"], 2);
+    assert_eq!(batching["last_batch_size"], 10);
+    assert_eq!(batching["pending"], 5);
+
+    // Shutdown sends the partial batch
+    driver.shutdown().await?;
+    assert_eq!(published_concentrations(&commands), expected);
+    assert!(commands_named(&commands, "PUBLISH")
+        .iter()
+        .all(|args| args[1] == "measurements"));
+    let batching = driver.get_status().await?["batching"].clone();
+    assert_eq!(batching["batches_sent"], 3);
+    assert_eq!(batching["measurements_sent"], 25);
+    assert_eq!(batching["last_batch_size"], 5);
+    assert_eq!(batching["pending"], 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_partial_batch_flushed_after_max_latency() -> Result<()> {
+    let (url, commands) = start_fake_redis().await?;
+    let mut driver = RedisActionDriver::new_pubsub(&url, "measurements")
+        .with_batching(100, Duration::from_millis(100));
+    driver.initialize().await?;
+
+    let queued_at = Instant::now();
+    for concentration in [1.0, 2.0, 3.0] {
+        driver.update_action(&measurement(concentration)).await?;
+    }
+    assert!(published_concentrations(&commands).is_empty());
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while published_concentrations(&commands).len() < 3 {
+        assert!(Instant::now() < deadline, "timed out waiting for the flush");
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    assert!(queued_at.elapsed() >= Duration::from_millis(100));
+    assert_eq!(published_concentrations(&commands), vec![1.0, 2.0, 3.0]);
+
+    let batching = driver.get_status().await?["batching"].clone();
+    assert_eq!(batching["batches_sent"], 1);
+    assert_eq!(batching["last_batch_size"], 3);
+    assert_eq!(batching["pending"], 0);
+
+    driver.shutdown().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_key_value_batching_from_config() -> Result<()> {
+    let (url, commands) = start_fake_redis().await?;
+    let config = json!({
+        "connection_string": url,
+        "mode": "key_value",
+        "channel_or_prefix": "photoacoustic",
+        "expiration_seconds": 3600,
+        "batch_size": 2,
+        "batch_max_latency_ms": 250
+    });
+    let mut driver = create_action_driver("redis", config.as_object().unwrap())?;
+    driver.initialize().await?;
+
+    let status = driver.get_status().await?;
+    assert_eq!(status["batching"]["batch_size"], 2);
+    assert_eq!(status["batching"]["max_latency_ms"], 250);
+
+    driver.update_action(&measurement(1.0)).await?;
+    assert!(commands_named(&commands, "SETEX").is_empty());
+    driver.update_action(&measurement(2.0)).await?;
+
+    let keys: Vec<(String, String)> = commands_named(&commands, "SETEX")
+        .into_iter()
+        .map(|args| (args[1].clone(), args[2].clone()))
+        .collect();
+    assert_eq!(
+        keys,
+        vec![
+            (
+                "photoacoustic:display:concentration:1700000001".to_string(),
+                "3600".to_string()
+            ),
+            (
+                "photoacoustic:latest:concentration".to_string(),
+                "3600".to_string()
+            ),
+            (
+                "photoacoustic:display:concentration:1700000002".to_string(),
+                "3600".to_string()
+            ),
+            (
+                "photoacoustic:latest:concentration".to_string(),
+                "3600".to_string()
+            ),
+        ]
+    );
+
+    driver.shutdown().await?;
+    Ok(())
+}
+
+#[test]
+fn test_zero_max_latency_rejected() {
+    let config = json!({
+        "connection_string": "redis://127.0.0.1:6379",
+        "mode": "pub_sub",
+        "batch_size": 10,
+        "batch_max_latency_ms": 0
+    });
+    let Err(error) = create_action_driver("redis", config.as_object().unwrap()) else {
+        panic!("a zero batch latency was accepted");
+    };
+    assert!(error.to_string().contains("batch_max_latency_ms"));
+
+    // Without batching the latency is not used
+    let config = json!({
+        "connection_string": "redis://127.0.0.1:6379",
+        "batch_size": 1,
+        "batch_max_latency_ms": 0
+    });
+    assert!(create_action_driver("redis", config.as_object().unwrap()).is_ok());
+}
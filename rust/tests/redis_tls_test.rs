@@ -0,0 +1,163 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Tests of the TLS client certificate setup of the Redis action driver
+//!
+//! The certificates are generated for each test. No Redis server is needed:
+//! the TLS files are checked before connecting.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_tls_files_are_loaded`] | The client certificate, key and CA bundle are read into the Redis TLS certificates |
+//! | [`test_mismatched_key_pair_is_rejected`] | A certificate with the key of another certificate fails with an error naming both files |
+//! | [`test_invalid_tls_configuration`] | Missing files, a certificate without key and an empty CA bundle are rejected |
+//! | [`test_initialize_checks_tls_files`] | The driver configured with TLS options checks them in `initialize` and requires a `rediss://` URL |
+
+use anyhow::Result;
+use rcgen::{CertificateParams, DnType, KeyPair};
+use rust_photoacoustic::processing::computing_nodes::action_drivers::{
+    create_action_driver, ActionDriver, RedisTlsFiles,
+};
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+/// Generate a self-signed certificate, returning its PEM and the PEM of its key
+fn self_signed(common_name: &str) -> (String, String) {
+    let key = KeyPair::generate().expect("key generated");
+    let mut params = CertificateParams::new(vec![common_name.to_string()]).unwrap();
+    params
+        .distinguished_name
+        .push(DnType::CommonName, common_name);
+    let cert = params.self_signed(&key).expect("certificate generated");
+    (cert.pem(), key.serialize_pem())
+}
+
+/// Write `contents` to `name` in `dir`
+fn write_file(dir: &Path, name: &str, contents: &str) -> Result<PathBuf> {
+    let path = dir.join(name);
+    fs::write(&path, contents)?;
+    Ok(path)
+}
+
+#[test]
+fn test_tls_files_are_loaded() -> Result<()> {
+    let dir = tempdir()?;
+    let (ca_cert, _) = self_signed("redis-ca");
+    let (client_cert, client_key) = self_signed("photoacoustic");
+
+    let files = RedisTlsFiles {
+        client_cert: Some(write_file(dir.path(), "client.pem", &client_cert)?),
+        client_key: Some(write_file(dir.path(), "client.key", &client_key)?),
+        ca_cert: Some(write_file(dir.path(), "ca.pem", &ca_cert)?),
+    };
+    let certificates = files.load()?;
+
+    let client_tls = certificates.client_tls.expect("client certificate loaded");
+    assert_eq!(client_tls.client_cert, client_cert.as_bytes());
+    assert_eq!(client_tls.client_key, client_key.as_bytes());
+    assert_eq!(certificates.root_cert.unwrap(), ca_cert.as_bytes());
+
+    // A CA bundle alone only verifies the server
+    let server_only = RedisTlsFiles {
+        ca_cert: files.ca_cert.clone(),
+        ..Default::default()
+    }
+    .load()?;
+    assert!(server_only.client_tls.is_none());
+    assert!(server_only.root_cert.is_some());
+    Ok(())
+}
+
+#[test]
+fn test_mismatched_key_pair_is_rejected() -> Result<()> {
+    let dir = tempdir()?;
+    let (client_cert, _) = self_signed("photoacoustic");
+    let (_, other_key) = self_signed("other");
+
+    let files = RedisTlsFiles {
+        client_cert: Some(write_file(dir.path(), "client.pem", &client_cert)?),
+        client_key: Some(write_file(dir.path(), "other.key", &other_key)?),
+        ca_cert: None,
+    };
+    let error = format!("{:#}", files.load().unwrap_err());
+    assert!(error.contains("client.pem"), "{}", error);
+    assert!(error.contains("other.key"), "{}", error);
+    assert!(
+        error.contains("Client certificate does not match the private key"),
+        "{}",
+        error
+    );
+    Ok(())
+}
+
+#[test]
+fn test_invalid_tls_configuration() -> Result<()> {
+    let dir = tempdir()?;
+    let (client_cert, client_key) = self_signed("photoacoustic");
+    let cert_path = write_file(dir.path(), "client.pem", &client_cert)?;
+    let key_path = write_file(dir.path(), "client.key", &client_key)?;
+
+    let missing_key = RedisTlsFiles {
+        client_cert: Some(cert_path.clone()),
+        client_key: Some(dir.path().join("missing.key")),
+        ca_cert: None,
+    };
+    let error = format!("{:#}", missing_key.load().unwrap_err());
+    assert!(error.contains("missing.key"), "{}", error);
+
+    let cert_without_key = RedisTlsFiles {
+        client_cert: Some(cert_path),
+        ..Default::default()
+    };
+    let error = cert_without_key.load().unwrap_err().to_string();
+    assert!(error.contains("configured together"), "{}", error);
+
+    // A key is not a CA bundle
+    let empty_bundle = RedisTlsFiles {
+        ca_cert: Some(key_path),
+        ..Default::default()
+    };
+    let error = empty_bundle.load().unwrap_err().to_string();
+    assert!(error.contains("contains no certificate"), "{}", error);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_initialize_checks_tls_files() -> Result<()> {
+    let dir = tempdir()?;
+    let (client_cert, _) = self_signed("photoacoustic");
+    let (_, other_key) = self_signed("other");
+    let cert_path = write_file(dir.path(), "client.pem", &client_cert)?;
+    let key_path = write_file(dir.path(), "other.key", &other_key)?;
+
+    let driver_config = |url: &str| {
+        json!({
+            "connection_string": url,
+            "mode": "pub_sub",
+            "channel_or_prefix": "photoacoustic",
+            "tls_client_cert": cert_path,
+            "tls_client_key": key_path
+        })
+    };
+
+    // The pair is checked before any connection attempt
+    let config = driver_config("rediss://127.0.0.1:1");
+    let mut driver = create_action_driver("redis", config.as_object().unwrap())?;
+    let status = driver.get_status().await?;
+    assert_eq!(status["tls"]["client_cert"], json!(cert_path));
+    let error = format!("{:#}", driver.initialize().await.unwrap_err());
+    assert!(
+        error.contains("Client certificate does not match the private key"),
+        "{}",
+        error
+    );
+
+    let config = driver_config("redis://127.0.0.1:1");
+    let mut driver = create_action_driver("redis", config.as_object().unwrap())?;
+    let error = driver.initialize().await.unwrap_err().to_string();
+    assert!(error.contains("require a rediss:// URL"), "{}", error);
+    Ok(())
+}
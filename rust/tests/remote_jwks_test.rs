@@ -0,0 +1,206 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the validation of tokens signed with the keys of a remote JWKS
+//!
+//! The JWKS of the external identity provider is served by a mock HTTP server.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_remote_key_validates_external_token`] | A token is validated with the key fetched from the remote JWKS, selected by `kid` |
+//! | [`test_remote_issuer_checked`] | Tokens verified with a remote key must carry the identity provider's issuer |
+//! | [`test_remote_jwks_cached`] | The JWKS is fetched once, unknown key IDs do not refetch it at every validation |
+//! | [`test_remote_jwks_refresh_failure_keeps_keys`] | A failed refresh keeps the cached keys, a missing JWKS rejects the token |
+
+use jsonwebtoken::{EncodingKey, Header};
+use rsa::pkcs1::{EncodeRsaPrivateKey, EncodeRsaPublicKey};
+use rust_photoacoustic::config::AccessConfig;
+use rust_photoacoustic::visualization::auth::jwt::{JwkKeySet, JwtValidator, RemoteJwks};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const IDP_ISSUER: &str = "https://idp.example.com/";
+
+/// RS256 key pair of the external identity provider
+struct IdpKey {
+    private_pem: Vec<u8>,
+    public_pem: Vec<u8>,
+}
+
+impl IdpKey {
+    fn generate() -> Self {
+        let mut rng = rsa::rand_core::OsRng;
+        let private_key =
+            rsa::RsaPrivateKey::new(&mut rng, 2048).expect("Failed to generate RSA private key");
+        let public_key = rsa::RsaPublicKey::from(&private_key);
+        Self {
+            private_pem: private_key
+                .to_pkcs1_pem(rsa::pkcs1::LineEnding::LF)
+                .expect("Failed to encode private key")
+                .as_bytes()
+                .to_vec(),
+            public_pem: public_key
+                .to_pkcs1_pem(rsa::pkcs1::LineEnding::LF)
+                .expect("Failed to encode public key")
+                .into_bytes(),
+        }
+    }
+
+    fn kid(&self) -> String {
+        JwkKeySet::kid_from_pem(&self.public_pem).expect("Failed to compute key ID")
+    }
+
+    /// Sign a token for `administrator` with the given issuer
+    fn sign(&self, issuer: &str) -> String {
+        let now = chrono::Utc::now().timestamp();
+        let claims = json!({
+            "sub": "administrator",
+            "iat": now,
+            "exp": now + 3600,
+            "nbf": now,
+            "jti": "external-token",
+            "aud": "LaserSmartClient",
+            "iss": issuer,
+            "scope": "openid read:api",
+        });
+        let mut header = Header::new(jsonwebtoken::Algorithm::RS256);
+        header.kid = Some(self.kid());
+        let key = EncodingKey::from_rsa_pem(&self.private_pem).expect("Invalid private key");
+        jsonwebtoken::encode(&header, &claims, &key).expect("Failed to sign token")
+    }
+}
+
+/// Serve the JWKS of `keys` at `/jwks.json`, expecting `fetches` requests
+async fn serve_jwks(keys: &[&IdpKey], fetches: u64) -> MockServer {
+    let server = MockServer::start().await;
+    let jwks = JwkKeySet::from_public_pems(keys.iter().map(|key| key.public_pem.as_slice()));
+    Mock::given(method("GET"))
+        .and(path("/jwks.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&jwks))
+        .expect(fetches)
+        .mount(&server)
+        .await;
+    server
+}
+
+fn validator(remote_jwks: Arc<RemoteJwks>) -> JwtValidator {
+    JwtValidator::new(Some(b"local-secret"), None, AccessConfig::default())
+        .expect("Failed to create JWT validator")
+        .with_issuer("LaserSmartServer")
+        .with_audience("LaserSmartClient")
+        .with_remote_jwks(remote_jwks)
+}
+
+#[tokio::test]
+async fn test_remote_key_validates_external_token() {
+    let idp_key = IdpKey::generate();
+    let other_key = IdpKey::generate();
+    let server = serve_jwks(&[&other_key, &idp_key], 1).await;
+    let remote_jwks = Arc::new(RemoteJwks::new(
+        format!("{}/jwks.json", server.uri()),
+        Duration::from_secs(300),
+    ));
+    let validator = validator(remote_jwks.clone());
+    let token = idp_key.sign("LaserSmartServer");
+
+    // Nothing is fetched until the key is needed
+    let error = validator.validate(&token).unwrap_err();
+    assert!(
+        error.to_string().contains("Unknown RS256 key ID"),
+        "{}",
+        error
+    );
+
+    validator.fetch_remote_key(&token).await;
+    let mut kids = vec![idp_key.kid(), other_key.kid()];
+    kids.sort();
+    assert_eq!(remote_jwks.key_ids(), kids);
+    assert!(!remote_jwks.is_stale());
+
+    let claims = validator.validate(&token).expect("Token should be valid");
+    assert_eq!(claims.sub, "administrator");
+    assert_eq!(claims.scope, "openid read:api");
+}
+
+#[tokio::test]
+async fn test_remote_issuer_checked() {
+    let idp_key = IdpKey::generate();
+    let server = serve_jwks(&[&idp_key], 1).await;
+    let remote_jwks = Arc::new(
+        RemoteJwks::new(
+            format!("{}/jwks.json", server.uri()),
+            Duration::from_secs(300),
+        )
+        .with_issuer(IDP_ISSUER),
+    );
+    let validator = validator(remote_jwks);
+
+    let token = idp_key.sign(IDP_ISSUER);
+    validator.fetch_remote_key(&token).await;
+    assert!(validator.validate(&token).is_ok());
+
+    // The local issuer is not accepted for the identity provider's keys
+    let local_issuer_token = idp_key.sign("LaserSmartServer");
+    assert!(validator.validate(&local_issuer_token).is_err());
+}
+
+#[tokio::test]
+async fn test_remote_jwks_cached() {
+    let idp_key = IdpKey::generate();
+    let unknown_key = IdpKey::generate();
+    let server = serve_jwks(&[&idp_key], 1).await;
+    let remote_jwks = Arc::new(RemoteJwks::new(
+        format!("{}/jwks.json", server.uri()),
+        Duration::from_secs(300),
+    ));
+    let validator = validator(remote_jwks);
+
+    let token = idp_key.sign("LaserSmartServer");
+    for _ in 0..3 {
+        validator.fetch_remote_key(&token).await;
+        assert!(validator.validate(&token).is_ok());
+    }
+
+    // A key missing from the JWKS does not trigger a fetch per validation
+    let unknown_token = unknown_key.sign("LaserSmartServer");
+    for _ in 0..3 {
+        validator.fetch_remote_key(&unknown_token).await;
+        assert!(validator.validate(&unknown_token).is_err());
+    }
+
+    // Dropping the server checks that the JWKS was fetched exactly once
+    drop(server);
+}
+
+#[tokio::test]
+async fn test_remote_jwks_refresh_failure_keeps_keys() {
+    let idp_key = IdpKey::generate();
+    let server = serve_jwks(&[&idp_key], 1).await;
+    let remote_jwks = Arc::new(RemoteJwks::new(
+        format!("{}/jwks.json", server.uri()),
+        Duration::from_secs(300),
+    ));
+    assert!(remote_jwks.is_stale());
+    assert_eq!(remote_jwks.refresh().await.unwrap(), 1);
+
+    // The JWKS is no longer served
+    server.reset().await;
+    assert!(remote_jwks.refresh().await.is_err());
+    assert_eq!(remote_jwks.key_ids(), vec![idp_key.kid()]);
+    let token = idp_key.sign("LaserSmartServer");
+    assert!(validator(remote_jwks).validate(&token).is_ok());
+
+    // Without any key fetched, the token is rejected
+    let unreachable = Arc::new(RemoteJwks::new(
+        format!("{}/jwks.json", server.uri()),
+        Duration::from_secs(300),
+    ));
+    let rejecting = validator(unreachable.clone());
+    rejecting.fetch_remote_key(&token).await;
+    assert!(unreachable.is_stale());
+    assert!(rejecting.validate(&token).is_err());
+}
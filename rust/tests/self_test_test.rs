@@ -0,0 +1,352 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the `--self-test` mode
+//!
+//! The mock configuration runs a 2 kHz synthetic tone through a bandpass
+//! filter and a peak finder, reports to an HTTP callback served by the test
+//! and regulates a cell on the mock I2C bus.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_mock_config_passes`] | Every check of the mock configuration passes and the report says so |
+//! | [`test_peak_outside_tolerance_fails`] | A peak finder looking away from the modulation frequency fails the signal chain check |
+//! | [`test_missing_peak_finder_fails`] | The default graph, without peak finder, fails while the other checks are skipped |
+//! | [`test_unreachable_driver_fails`] | An unreachable callback and an unknown driver type fail their checks |
+//! | [`test_thermal_failures`] | A regulator on an unknown I2C bus fails, disabled regulators are skipped |
+//! | [`test_disabled_checks_are_skipped`] | With processing and thermal regulation disabled, every check is skipped and the self-test passes |
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+
+use rust_photoacoustic::config::processing::{ConnectionConfig, NodeConfig, ProcessingGraphConfig};
+use rust_photoacoustic::config::{Config, ThermalRegulationConfig};
+use rust_photoacoustic::daemon::self_test::{
+    check_action_drivers, check_signal_chain, check_thermal_sensors, run_self_test, CheckKind,
+    CheckStatus, SelfTestReport,
+};
+
+const FREQUENCY: f32 = 2000.0;
+
+const THERMAL_YAML: &str = r#"
+enabled: true
+i2c_buses:
+  mock_thermal:
+    type: mock
+    device: mock
+    pwm_controllers:
+      - address: 0x40
+    adc_controllers:
+      - address: 0x48
+    gpio_controllers:
+      - address: 0x20
+regulators:
+  - id: mock_cell
+    name: Mock cell
+    i2c_bus: mock_thermal
+    temperature_sensor:
+      adc_address: 0x48
+      adc_channel: 0
+    actuators:
+      thermal_control:
+        pwm_controller:
+          address: 0x40
+          channel: 0
+        direction_controller:
+          address: 0x20
+          gpio_pins:
+            h_bridge_in1: 0
+            h_bridge_in2: 1
+            h_bridge_enable: 2
+        thermal_modes:
+          heating_tec:
+            description: Peltier heating
+            h_bridge_direction: forward
+            power_range: 0-100%
+            max_power_percent: 100.0
+          cooling_tec:
+            description: Peltier cooling
+            h_bridge_direction: reverse
+            power_range: 0-100%
+            max_power_percent: 100.0
+          heating_resistive:
+            description: Resistive heating
+            h_bridge_direction: forward
+            power_range: 0-100%
+            max_power_percent: 100.0
+    temperature_conversion:
+      formula: NTC_10K_3977
+      adc_resolution: 16
+      voltage_reference: 5.0
+      conversion_type: ntc_thermistor
+    pid_parameters:
+      kp: 1.0
+      ki: 0.05
+      kd: 0.02
+      setpoint: 303.15
+      output_min: -100.0
+      output_max: 100.0
+      integral_max: 30.0
+    control_parameters:
+      sampling_frequency_hz: 5.0
+      pwm_frequency_hz: 1000.0
+    safety_limits:
+      min_temperature_k: 273.15
+      max_temperature_k: 353.15
+      max_heating_duty: 80.0
+      max_cooling_duty: 80.0
+"#;
+
+fn node(id: &str, node_type: &str, parameters: serde_json::Value) -> NodeConfig {
+    NodeConfig {
+        id: id.to_string(),
+        node_type: node_type.to_string(),
+        parameters,
+    }
+}
+
+fn connection(from: &str, to: &str) -> ConnectionConfig {
+    ConnectionConfig {
+        from: from.to_string(),
+        to: to.to_string(),
+    }
+}
+
+/// The HTTP client of the callback driver needs a rustls provider, as in `main`
+fn install_crypto_provider() {
+    let _ = rustls::crypto::ring::default_provider().install_default();
+}
+
+/// Serve an empty 200 response to every request, returning the callback URL
+fn http_callback_server() -> String {
+    install_crypto_provider();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/callback", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut request = [0u8; 4096];
+            let _ = stream.read(&mut request);
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+        }
+    });
+    url
+}
+
+/// URL of a port nobody listens on
+fn unreachable_url() -> String {
+    install_crypto_provider();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    format!("http://{}/callback", listener.local_addr().unwrap())
+}
+
+fn action_node(id: &str, driver_type: &str, driver_config: serde_json::Value) -> NodeConfig {
+    node(
+        id,
+        "action_universal",
+        serde_json::json!({
+            "monitored_nodes": ["peak_finder"],
+            "driver": {"type": driver_type, "config": driver_config}
+        }),
+    )
+}
+
+fn mock_config(peak_finder_range: (f32, f32), callback_url: &str) -> Config {
+    let mut config = Config::default();
+    config.photoacoustic.frequency = FREQUENCY;
+    config.photoacoustic.sample_rate = 48000;
+    config.photoacoustic.frame_size = 4096;
+    config.processing.default_graph = ProcessingGraphConfig {
+        id: "self_test".to_string(),
+        nodes: vec![
+            node("input", "input", serde_json::Value::Null),
+            node(
+                "select_a",
+                "channel_selector",
+                serde_json::json!({"target_channel": "ChannelA"}),
+            ),
+            node(
+                "bandpass",
+                "filter",
+                serde_json::json!({
+                    "type": "bandpass",
+                    "center_frequency": FREQUENCY,
+                    "bandwidth": 200.0,
+                    "order": 4
+                }),
+            ),
+            node(
+                "peak_finder",
+                "computing_peak_finder",
+                serde_json::json!({
+                    "detection_threshold": 0.1,
+                    "frequency_min": peak_finder_range.0,
+                    "frequency_max": peak_finder_range.1
+                }),
+            ),
+            action_node(
+                "display",
+                "https_callback",
+                serde_json::json!({"callback_url": callback_url, "timeout_ms": 2000}),
+            ),
+        ],
+        connections: vec![
+            connection("input", "select_a"),
+            connection("select_a", "bandpass"),
+            connection("bandpass", "peak_finder"),
+            connection("peak_finder", "display"),
+        ],
+        output_node: Some("peak_finder".to_string()),
+    };
+    config.thermal_regulation = serde_yml::from_str(THERMAL_YAML).unwrap();
+    config
+}
+
+fn statuses(report: &SelfTestReport, kind: CheckKind) -> Vec<CheckStatus> {
+    report.checks_of(kind).map(|check| check.status).collect()
+}
+
+#[tokio::test]
+async fn test_mock_config_passes() {
+    let config = mock_config((1000.0, 4000.0), &http_callback_server());
+    let report = run_self_test(&config).await;
+
+    assert!(report.passed(), "{}", report);
+    assert_eq!(
+        statuses(&report, CheckKind::SignalChain),
+        vec![CheckStatus::Pass]
+    );
+    assert_eq!(
+        statuses(&report, CheckKind::ActionDriver),
+        vec![CheckStatus::Pass]
+    );
+    assert_eq!(
+        statuses(&report, CheckKind::ThermalSensor),
+        vec![CheckStatus::Pass]
+    );
+    assert_eq!(report.checks[0].name, "peak_finder");
+    assert_eq!(report.checks[1].name, "display (https_callback)");
+    assert_eq!(report.checks[2].name, "mock_cell");
+
+    let text = report.to_string();
+    assert!(
+        text.contains("[PASS] signal chain 'peak_finder'"),
+        "{}",
+        text
+    );
+    assert!(text.ends_with("Result: PASSED (3 checks)"), "{}", text);
+}
+
+#[tokio::test]
+async fn test_peak_outside_tolerance_fails() {
+    let config = mock_config((5000.0, 8000.0), &http_callback_server());
+    let checks = check_signal_chain(&config).await;
+
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0].status, CheckStatus::Fail);
+    assert!(
+        checks[0].detail.contains("expected 2000.0 Hz"),
+        "{:?}",
+        checks
+    );
+
+    let report = run_self_test(&config).await;
+    assert!(!report.passed());
+    assert_eq!(report.failures().count(), 1);
+    assert!(report
+        .to_string()
+        .ends_with("Result: FAILED (1 of 3 checks failed)"));
+}
+
+#[tokio::test]
+async fn test_missing_peak_finder_fails() {
+    let report = run_self_test(&Config::default()).await;
+
+    assert!(!report.passed());
+    assert_eq!(
+        statuses(&report, CheckKind::SignalChain),
+        vec![CheckStatus::Fail]
+    );
+    assert_eq!(
+        statuses(&report, CheckKind::ActionDriver),
+        vec![CheckStatus::Skip]
+    );
+    assert_eq!(
+        statuses(&report, CheckKind::ThermalSensor),
+        vec![CheckStatus::Skip]
+    );
+}
+
+#[tokio::test]
+async fn test_unreachable_driver_fails() {
+    let mut config = mock_config((1000.0, 4000.0), &unreachable_url());
+    config.processing.default_graph.nodes.push(action_node(
+        "pager",
+        "carrier_pigeon",
+        serde_json::json!({}),
+    ));
+
+    let checks = check_action_drivers(&config).await;
+    assert_eq!(checks.len(), 2);
+    assert!(checks.iter().all(|check| check.status == CheckStatus::Fail));
+    assert_eq!(checks[1].name, "pager (carrier_pigeon)");
+    assert!(checks[1].detail.contains("Unsupported driver type"));
+
+    // The synthetic signal never reaches the driver, the signal chain still passes
+    let report = run_self_test(&config).await;
+    assert_eq!(
+        statuses(&report, CheckKind::SignalChain),
+        vec![CheckStatus::Pass]
+    );
+    assert_eq!(report.failures().count(), 2);
+}
+
+#[tokio::test]
+async fn test_thermal_failures() {
+    let mut thermal: ThermalRegulationConfig = serde_yml::from_str(THERMAL_YAML).unwrap();
+    let mut orphan = thermal.regulators[0].clone();
+    orphan.id = "orphan_cell".to_string();
+    orphan.i2c_bus = "missing_bus".to_string();
+    let mut disabled = orphan.clone();
+    disabled.id = "disabled_cell".to_string();
+    disabled.enabled = false;
+    thermal.regulators.push(orphan);
+    thermal.regulators.push(disabled);
+
+    let mut config = Config::default();
+    config.thermal_regulation = thermal;
+    let checks = check_thermal_sensors(&config).await;
+    assert_eq!(checks.len(), 2);
+    assert_eq!(
+        (checks[0].name.as_str(), checks[0].status),
+        ("mock_cell", CheckStatus::Pass)
+    );
+    assert_eq!(
+        (checks[1].name.as_str(), checks[1].status),
+        ("orphan_cell", CheckStatus::Fail)
+    );
+    assert!(checks[1].detail.contains("missing_bus"));
+
+    // No enabled regulator left to read
+    for regulator in &mut config.thermal_regulation.regulators {
+        regulator.enabled = false;
+    }
+    let checks = check_thermal_sensors(&config).await;
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0].status, CheckStatus::Skip);
+}
+
+#[tokio::test]
+async fn test_disabled_checks_are_skipped() {
+    let mut config = Config::default();
+    config.processing.enabled = false;
+    let report = run_self_test(&config).await;
+
+    assert!(report.passed());
+    assert_eq!(report.checks.len(), 3);
+    assert!(report
+        .checks
+        .iter()
+        .all(|check| check.status == CheckStatus::Skip));
+}
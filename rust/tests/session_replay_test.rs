@@ -0,0 +1,363 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the session recording and replay
+//!
+//! The session is 30 frames of 20 ms at 48 kHz: noise on both channels, and a
+//! 2 kHz tone on channel A from the tenth frame. The graph filters both
+//! channels, subtracts them and detects the tone after a 100 ms dwell, so that
+//! the results depend on the filter state, on the frame timestamps and on the
+//! detection state carried from frame to frame.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_replay_reproduces_live_run`] | A session recorded by a running consumer replays the exact frames and configuration, and the replayed run gives the live results bit for bit |
+//! | [`test_streamed_replay_matches_offline_run`] | A replay streamed as fast as possible through a small stream loses no frame and gives the offline results |
+//! | [`test_replay_timing`] | An original-timing replay follows the recorded timestamps, an as-fast-as-possible replay does not wait |
+//! | [`test_invalid_sessions`] | Files that are not sessions are rejected, and a graph without configuration cannot be recorded |
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::{
+    AudioFrame, AudioSource, AudioStreamConsumer, RealTimeAudioSource, ReplaySource, ReplayTiming,
+    SessionRecorder, SharedAudioStream,
+};
+use rust_photoacoustic::config::processing::{ConnectionConfig, NodeConfig, ProcessingGraphConfig};
+use rust_photoacoustic::processing::{
+    InputNode, ProcessingConsumer, ProcessingData, ProcessingGraph, ProcessingResult,
+};
+use rust_photoacoustic::utility::noise_generator::NoiseGenerator;
+use std::f64::consts::TAU;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+use tokio::sync::broadcast;
+
+const SAMPLE_RATE: u32 = 48000;
+/// Samples of a 20 ms frame
+const FRAME_SIZE: usize = 960;
+const FRAME_MS: u64 = 20;
+const FRAME_COUNT: usize = 30;
+
+fn graph_config() -> ProcessingGraphConfig {
+    let node = |id: &str, node_type: &str, parameters: serde_json::Value| NodeConfig {
+        id: id.to_string(),
+        node_type: node_type.to_string(),
+        parameters,
+    };
+    let connection = |from: &str, to: &str| ConnectionConfig {
+        from: from.to_string(),
+        to: to.to_string(),
+    };
+    ProcessingGraphConfig {
+        id: "session_graph".to_string(),
+        nodes: vec![
+            node("input", "input", serde_json::Value::Null),
+            node(
+                "bandpass",
+                "filter",
+                serde_json::json!({
+                    "type": "bandpass",
+                    "center_frequency": 2000.0,
+                    "bandwidth": 400.0
+                }),
+            ),
+            node("differential", "differential", serde_json::Value::Null),
+            node(
+                "photoacoustic_output",
+                "photoacoustic_output",
+                serde_json::json!({
+                    "detection_threshold": 0.05,
+                    "detection_dwell_ms": 100
+                }),
+            ),
+        ],
+        connections: vec![
+            connection("input", "bandpass"),
+            connection("bandpass", "differential"),
+            connection("differential", "photoacoustic_output"),
+        ],
+        output_node: Some("photoacoustic_output".to_string()),
+    }
+}
+
+/// The frames of the session
+fn frames() -> Vec<AudioFrame> {
+    let mut noise = NoiseGenerator::new(2024);
+    (0..FRAME_COUNT)
+        .map(|k| {
+            let tone = if k >= 10 { 0.2 } else { 0.0 };
+            let channel_a = (0..FRAME_SIZE)
+                .map(|n| {
+                    let t = (k * FRAME_SIZE + n) as f64 / SAMPLE_RATE as f64;
+                    (tone * (TAU * 2000.0 * t).sin()) as f32 + 0.01 * noise.random_gaussian()
+                })
+                .collect();
+            let channel_b = (0..FRAME_SIZE)
+                .map(|_| 0.01 * noise.random_gaussian())
+                .collect();
+            AudioFrame {
+                channel_a,
+                channel_b,
+                sample_rate: SAMPLE_RATE,
+                timestamp: 1_000 + k as u64 * FRAME_MS,
+                frame_number: k as u64 + 1,
+            }
+        })
+        .collect()
+}
+
+/// Signal and processing steps of a frame result
+type FrameOutput = (Vec<f32>, Vec<String>);
+
+/// Execute the frames through a graph built from `config`
+fn run_offline(config: &ProcessingGraphConfig, frames: &[AudioFrame]) -> Result<Vec<FrameOutput>> {
+    let mut graph = ProcessingGraph::from_config(config)?;
+    frames
+        .iter()
+        .map(
+            |frame| match &graph.execute(ProcessingData::AudioFrame(frame.clone()))?[..] {
+                [ProcessingData::PhotoacousticResult { signal, metadata }] => {
+                    Ok((signal.clone(), metadata.processing_steps.clone()))
+                }
+                _ => panic!("unexpected output"),
+            },
+        )
+        .collect()
+}
+
+fn consumer_output(result: &ProcessingResult) -> FrameOutput {
+    (
+        result.analysis.signal.clone(),
+        result
+            .metadata
+            .processing_chain
+            .iter()
+            .map(|step| step.node_type.clone())
+            .collect(),
+    )
+}
+
+/// Wait for a consumer to subscribe to the stream
+async fn wait_for_subscriber(stream: &SharedAudioStream) {
+    for _ in 0..100 {
+        if stream.subscriber_count() > 0 {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("consumer did not subscribe");
+}
+
+/// Receive the results of `count` frames
+async fn receive_results(
+    receiver: &mut broadcast::Receiver<ProcessingResult>,
+    count: usize,
+) -> Result<Vec<ProcessingResult>> {
+    let mut results = Vec::new();
+    while results.len() < count {
+        results.push(tokio::time::timeout(Duration::from_secs(10), receiver.recv()).await??);
+    }
+    Ok(results)
+}
+
+fn write_session(path: &Path, frames: &[AudioFrame]) -> Result<()> {
+    let mut recorder = SessionRecorder::create(path, &graph_config())?;
+    for frame in frames {
+        recorder.record(frame)?;
+    }
+    assert_eq!(recorder.finish()?, frames.len() as u64);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replay_reproduces_live_run() -> Result<()> {
+    let dir = tempdir()?;
+    let session_path = dir.path().join("session.jsonl");
+    let frames = frames();
+
+    // Live run, recorded by the consumer
+    let stream = Arc::new(SharedAudioStream::new(64));
+    let (mut consumer, mut receiver) = ProcessingConsumer::new_with_broadcast(
+        Arc::clone(&stream),
+        ProcessingGraph::from_config(&graph_config())?,
+        64,
+    );
+    consumer.record_session(&session_path).await?;
+    let handle = consumer.handle();
+    let consumer_task = tokio::spawn(async move { consumer.start().await });
+    wait_for_subscriber(&stream).await;
+
+    for frame in &frames {
+        stream.publish(frame.clone()).await?;
+    }
+    let live = receive_results(&mut receiver, FRAME_COUNT).await?;
+    handle.drain_and_shutdown().await;
+    consumer_task.await??;
+    assert_eq!(handle.stop_session_recording()?, Some(FRAME_COUNT as u64));
+    assert_eq!(handle.stop_session_recording()?, None);
+
+    // The tone was detected after the dwell time
+    let live: Vec<FrameOutput> = live.iter().map(consumer_output).collect();
+    let onset = live
+        .iter()
+        .position(|(_, steps)| steps.iter().any(|s| s == "detection_onset"))
+        .expect("tone detected");
+    assert!(onset > 10, "onset at frame {}", onset);
+
+    // The session holds the configuration and the exact frames
+    let mut replay = ReplaySource::open(&session_path, ReplayTiming::AsFastAsPossible)?;
+    assert_eq!(
+        serde_json::to_value(replay.graph_config())?,
+        serde_json::to_value(graph_config())?
+    );
+    assert_eq!(AudioSource::sample_rate(&replay), SAMPLE_RATE);
+    let mut replayed = Vec::new();
+    while let Some(frame) = replay.next_frame()? {
+        replayed.push(frame);
+    }
+    assert_eq!(replayed, frames);
+    assert!(replay.read_frame().is_err());
+
+    // The replayed run gives the live results, bit for bit
+    let offline = run_offline(replay.graph_config(), &replayed)?;
+    assert_eq!(offline.len(), live.len());
+    for (k, (replayed, live)) in offline.iter().zip(&live).enumerate() {
+        assert_eq!(
+            replayed.0.iter().map(|s| s.to_bits()).collect::<Vec<_>>(),
+            live.0.iter().map(|s| s.to_bits()).collect::<Vec<_>>(),
+            "frame {}: signal differs",
+            k
+        );
+        assert_eq!(replayed.1, live.1, "frame {}: steps differ", k);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_streamed_replay_matches_offline_run() -> Result<()> {
+    let dir = tempdir()?;
+    let session_path = dir.path().join("session.jsonl");
+    let frames = frames();
+    write_session(&session_path, &frames)?;
+    let expected = run_offline(&graph_config(), &frames)?;
+
+    // A stream of 4 frames, much shorter than the session
+    let mut replay = ReplaySource::open(&session_path, ReplayTiming::AsFastAsPossible)?;
+    let stream = Arc::new(SharedAudioStream::new(4));
+    let (mut consumer, mut receiver) = ProcessingConsumer::new_with_broadcast(
+        Arc::clone(&stream),
+        ProcessingGraph::from_config(replay.graph_config())?,
+        64,
+    );
+    let handle = consumer.handle();
+    let consumer_task = tokio::spawn(async move { consumer.start().await });
+    wait_for_subscriber(&stream).await;
+
+    replay.start_streaming(Arc::clone(&stream)).await?;
+    let results = receive_results(&mut receiver, FRAME_COUNT).await?;
+    handle.drain_and_shutdown().await;
+    consumer_task.await??;
+    assert!(!replay.is_streaming());
+    assert_eq!(stream.get_stats().await.total_frames, FRAME_COUNT as u64);
+
+    for (k, (result, expected)) in results.iter().zip(&expected).enumerate() {
+        assert_eq!(result.frame_info.frame_number, frames[k].frame_number);
+        assert_eq!(result.frame_info.timestamp, frames[k].timestamp);
+        assert_eq!(&consumer_output(result), expected, "frame {}", k);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replay_timing() -> Result<()> {
+    let dir = tempdir()?;
+    let session_path = dir.path().join("session.jsonl");
+    // Six frames recorded 50 ms apart
+    let frames: Vec<AudioFrame> = frames()
+        .into_iter()
+        .take(6)
+        .enumerate()
+        .map(|(k, frame)| AudioFrame {
+            timestamp: 1_000 + k as u64 * 50,
+            ..frame
+        })
+        .collect();
+    write_session(&session_path, &frames)?;
+
+    for (timing, min_ms, max_ms) in [
+        (ReplayTiming::Original, 240, 1000),
+        (ReplayTiming::AsFastAsPossible, 0, 150),
+    ] {
+        let stream = Arc::new(SharedAudioStream::new(16));
+        let mut consumer = AudioStreamConsumer::new(&stream);
+        let mut replay = ReplaySource::open(&session_path, timing)?;
+        assert_eq!(replay.timing(), timing);
+
+        let started = Instant::now();
+        replay.start_streaming(Arc::clone(&stream)).await?;
+        let mut received = Vec::new();
+        while received.len() < frames.len() {
+            let frame = tokio::time::timeout(Duration::from_secs(5), consumer.next_frame())
+                .await?
+                .expect("replayed frame");
+            received.push(frame);
+        }
+        let elapsed = started.elapsed().as_millis();
+        assert!(
+            (min_ms..max_ms).contains(&elapsed),
+            "{:?} replay took {} ms",
+            timing,
+            elapsed
+        );
+        assert_eq!(received, frames);
+        replay.stop_streaming().await?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_sessions() -> Result<()> {
+    let dir = tempdir()?;
+
+    let missing = dir.path().join("missing.jsonl");
+    assert!(ReplaySource::open(&missing, ReplayTiming::Original).is_err());
+
+    let other = dir.path().join("other.json");
+    std::fs::write(&other, "{\"format\":\"other\",\"version\":1}\n")?;
+    assert!(ReplaySource::open(&other, ReplayTiming::Original).is_err());
+
+    // A newer format version is not read
+    let session_path = dir.path().join("session.jsonl");
+    write_session(&session_path, &frames()[..2])?;
+    let content = std::fs::read_to_string(&session_path)?;
+    let newer = dir.path().join("newer.jsonl");
+    std::fs::write(
+        &newer,
+        content.replacen("\"version\":1", "\"version\":99", 1),
+    )?;
+    assert!(ReplaySource::open(&newer, ReplayTiming::Original).is_err());
+
+    // A truncated frame is reported when it is read
+    let truncated = dir.path().join("truncated.jsonl");
+    let mut lines: Vec<&str> = content.lines().collect();
+    let last = lines.pop().unwrap();
+    let cut = last.replacen("\"channel_a\":\"", "\"channel_a\":\"AAA", 1);
+    lines.push(&cut);
+    std::fs::write(&truncated, lines.join("\n"))?;
+    let mut replay = ReplaySource::open(&truncated, ReplayTiming::Original)?;
+    assert!(replay.next_frame()?.is_some());
+    assert!(replay.next_frame().is_err());
+
+    // A graph assembled in code has no configuration to record
+    let mut graph = ProcessingGraph::new();
+    graph.add_node(Box::new(InputNode::new("input".to_string())))?;
+    let consumer = ProcessingConsumer::new(Arc::new(SharedAudioStream::new(4)), graph);
+    assert!(consumer
+        .record_session(dir.path().join("manual.jsonl"))
+        .await
+        .is_err());
+    assert_eq!(consumer.stop_session_recording()?, None);
+    Ok(())
+}
@@ -0,0 +1,210 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the gas event schedule of the simulated source
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_concentration_profile_follows_events`] | Ramps, steps, interrupted ramps and unordered events produce the expected concentration |
+//! | [`test_frame_amplitude_tracks_schedule`] | The amplitude of the generated frames follows the scheduled concentration |
+//! | [`test_streamed_frames_track_schedule`] | Frames published by the streaming task follow the same profile |
+//! | [`test_gas_events_from_yaml`] | `gas_events` deserialize from YAML and invalid events are rejected |
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::{
+    RealTimeAudioSource, SharedAudioStream, SimulatedPhotoacousticRealtimeAudioSource,
+};
+use rust_photoacoustic::config::utils::validate_specific_rules;
+use rust_photoacoustic::config::{Config, GasEvent, PhotoacousticConfig, SimulatedSourceConfig};
+use std::sync::Arc;
+use std::time::Duration;
+
+const SAMPLE_RATE: u16 = 48000;
+/// 100 ms frames
+const FRAME_SIZE: u16 = 4800;
+
+/// Noise-free universal source rising from 1000 to 2000 ppm between 1 s and
+/// 2 s, then stepping down to 500 ppm at 3 s
+fn scheduled_config() -> SimulatedSourceConfig {
+    SimulatedSourceConfig {
+        source_type: "universal".to_string(),
+        background_noise_amplitude: 0.0,
+        gas_flow_noise_factor: 0.0,
+        temperature_drift_factor: 0.0,
+        laser_modulation_depth: 0.8,
+        signal_amplitude: 0.2,
+        reference_concentration_ppm: 1000.0,
+        gas_events: vec![
+            GasEvent {
+                time_offset_seconds: 3.0,
+                target_concentration_ppm: 500.0,
+                ramp_seconds: 0.0,
+            },
+            GasEvent {
+                time_offset_seconds: 1.0,
+                target_concentration_ppm: 2000.0,
+                ramp_seconds: 1.0,
+            },
+        ],
+        ..Default::default()
+    }
+}
+
+fn photoacoustic_config(simulated: &SimulatedSourceConfig) -> PhotoacousticConfig {
+    PhotoacousticConfig {
+        sample_rate: SAMPLE_RATE,
+        frame_size: FRAME_SIZE,
+        simulated_source: Some(simulated.clone()),
+        ..Default::default()
+    }
+}
+
+/// Peak amplitude expected on channel A for a noise-free frame starting at `elapsed_seconds`
+fn expected_peak(config: &SimulatedSourceConfig, elapsed_seconds: f64) -> f32 {
+    // The laser modulation peaks at sin(depth), then the output is soft clipped
+    (config.signal_amplitude_at(elapsed_seconds) * config.laser_modulation_depth.sin()).tanh()
+}
+
+fn peak(samples: &[f32]) -> f32 {
+    samples
+        .iter()
+        .fold(0.0f32, |peak, sample| peak.max(sample.abs()))
+}
+
+fn assert_tracks_schedule(config: &SimulatedSourceConfig, frame_number: u64, channel_a: &[f32]) {
+    // Simulated start time of the frame, computed as the source does
+    let elapsed_seconds = (frame_number - 1) as f64 * FRAME_SIZE as f64 / SAMPLE_RATE as f64;
+    let expected = expected_peak(config, elapsed_seconds);
+    let actual = peak(channel_a);
+    // The bounded concentration random walk of the generator stays within a few percent
+    assert!(
+        (actual - expected).abs() <= expected * 0.03,
+        "frame {} at {:.1} s: peak {} instead of {}",
+        frame_number,
+        elapsed_seconds,
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn test_concentration_profile_follows_events() {
+    let config = scheduled_config();
+    // Reference concentration before the first event
+    assert_eq!(config.concentration_at(0.0), 1000.0);
+    assert_eq!(config.concentration_at(0.99), 1000.0);
+    // Linear ramp
+    assert_eq!(config.concentration_at(1.0), 1000.0);
+    assert!((config.concentration_at(1.5) - 1500.0).abs() < 1e-3);
+    assert_eq!(config.concentration_at(2.0), 2000.0);
+    assert_eq!(config.concentration_at(2.5), 2000.0);
+    // Step change
+    assert_eq!(config.concentration_at(3.0), 500.0);
+    assert_eq!(config.concentration_at(100.0), 500.0);
+    assert!((config.signal_amplitude_at(1.5) - 0.3).abs() < 1e-6);
+    assert!((config.signal_amplitude_at(10.0) - 0.1).abs() < 1e-6);
+
+    // An event starting during a ramp ramps from the concentration reached so far
+    let interrupted = SimulatedSourceConfig {
+        gas_events: vec![
+            GasEvent {
+                time_offset_seconds: 0.0,
+                target_concentration_ppm: 3000.0,
+                ramp_seconds: 2.0,
+            },
+            GasEvent {
+                time_offset_seconds: 1.0,
+                target_concentration_ppm: 0.0,
+                ramp_seconds: 4.0,
+            },
+        ],
+        ..scheduled_config()
+    };
+    assert_eq!(interrupted.concentration_at(1.0), 2000.0);
+    assert!((interrupted.concentration_at(3.0) - 1000.0).abs() < 1e-3);
+    assert_eq!(interrupted.concentration_at(5.0), 0.0);
+
+    // Without events the configured amplitude is used as is
+    let static_config = SimulatedSourceConfig::default();
+    assert_eq!(
+        static_config.signal_amplitude_at(42.0),
+        static_config.signal_amplitude
+    );
+}
+
+#[test]
+fn test_frame_amplitude_tracks_schedule() -> Result<()> {
+    let config = scheduled_config();
+    let mut source = SimulatedPhotoacousticRealtimeAudioSource::new(
+        photoacoustic_config(&config),
+        config.clone(),
+    )?;
+
+    // 4 seconds of signal: static, ramp up, plateau, step down
+    for expected_number in 1..=40 {
+        let frame = source.next_frame();
+        assert_eq!(frame.frame_number, expected_number);
+        assert_eq!(frame.channel_a.len(), FRAME_SIZE as usize);
+        assert_tracks_schedule(&config, frame.frame_number, &frame.channel_a);
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_streamed_frames_track_schedule() -> Result<()> {
+    let config = scheduled_config();
+    let mut source = SimulatedPhotoacousticRealtimeAudioSource::new(
+        photoacoustic_config(&config),
+        config.clone(),
+    )?;
+    source.set_real_time_mode(false);
+
+    let stream = Arc::new(SharedAudioStream::new(1024));
+    let mut receiver = stream.subscribe();
+    source.start_streaming(Arc::clone(&stream)).await?;
+
+    let mut checked = 0;
+    while checked < 40 {
+        let frame = match tokio::time::timeout(Duration::from_secs(10), receiver.recv()).await? {
+            Ok(frame) => frame,
+            // Frames skipped by a lagging receiver are simply not checked
+            Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(e) => return Err(e.into()),
+        };
+        assert_tracks_schedule(&config, frame.frame_number, &frame.channel_a);
+        checked += 1;
+    }
+
+    source.stop_streaming().await?;
+    Ok(())
+}
+
+#[test]
+fn test_gas_events_from_yaml() -> Result<()> {
+    let yaml = r#"
+source_type: universal
+reference_concentration_ppm: 400.0
+gas_events:
+  - time_offset_seconds: 10.0
+    target_concentration_ppm: 800.0
+    ramp_seconds: 5.0
+  - time_offset_seconds: 30.0
+    target_concentration_ppm: 400.0
+"#;
+    let config: SimulatedSourceConfig = serde_yml::from_str(yaml)?;
+    assert_eq!(config.gas_events.len(), 2);
+    assert_eq!(config.gas_events[1].ramp_seconds, 0.0);
+    assert_eq!(config.concentration_at(12.5), 600.0);
+    assert_eq!(config.concentration_at(30.0), 400.0);
+
+    let mut full_config = Config::default();
+    full_config.photoacoustic.simulated_source = Some(config.clone());
+    assert!(validate_specific_rules(&full_config).is_ok());
+
+    let mut invalid = config;
+    invalid.gas_events[0].ramp_seconds = -1.0;
+    full_config.photoacoustic.simulated_source = Some(invalid);
+    assert!(validate_specific_rules(&full_config).is_err());
+    Ok(())
+}
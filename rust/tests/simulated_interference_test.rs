@@ -0,0 +1,245 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the noise floor and interference of the simulated source
+//!
+//! The photoacoustic signal is disabled so that the output only contains the
+//! injected noise and tones, measured on the spectrum of one second of signal
+//! (1 Hz bins):
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_defaults_add_nothing`] | Without noise or tones configured the output is unchanged |
+//! | [`test_interferer_and_white_noise_levels`] | The interferer, the mains hum and the white noise floor appear at their configured levels |
+//! | [`test_pink_noise_level_and_slope`] | Pink noise has the configured RMS level and a 1/f spectrum |
+//! | [`test_interference_config_validation`] | Out of range levels and hum frequencies are rejected |
+
+use anyhow::Result;
+use realfft::RealFftPlanner;
+use rust_photoacoustic::acquisition::SimulatedPhotoacousticRealtimeAudioSource;
+use rust_photoacoustic::config::utils::validate_specific_rules;
+use rust_photoacoustic::config::{
+    Config, PhotoacousticConfig, SimulatedSourceConfig, ToneInterference,
+};
+
+const SAMPLE_RATE: u16 = 48000;
+const FRAME_SIZE: u16 = 4800;
+
+/// Universal source producing no photoacoustic signal nor internal noise
+fn silent_config() -> SimulatedSourceConfig {
+    SimulatedSourceConfig {
+        source_type: "universal".to_string(),
+        background_noise_amplitude: 0.0,
+        gas_flow_noise_factor: 0.0,
+        temperature_drift_factor: 0.0,
+        signal_amplitude: 0.0,
+        ..Default::default()
+    }
+}
+
+/// Generate `seconds` of both channels
+fn generate(config: &SimulatedSourceConfig, seconds: usize) -> Result<(Vec<f32>, Vec<f32>)> {
+    let photoacoustic = PhotoacousticConfig {
+        sample_rate: SAMPLE_RATE,
+        frame_size: FRAME_SIZE,
+        simulated_source: Some(config.clone()),
+        ..Default::default()
+    };
+    let mut source = SimulatedPhotoacousticRealtimeAudioSource::new(photoacoustic, config.clone())?;
+
+    let frames = seconds * SAMPLE_RATE as usize / FRAME_SIZE as usize;
+    let mut channel_a = Vec::new();
+    let mut channel_b = Vec::new();
+    for _ in 0..frames {
+        let frame = source.next_frame();
+        channel_a.extend(frame.channel_a);
+        channel_b.extend(frame.channel_b);
+    }
+    Ok((channel_a, channel_b))
+}
+
+/// Power spectrum `|X_k|² / N` of `samples`, one bin per Hz for one second of signal
+fn power_spectrum(samples: &[f32]) -> Vec<f32> {
+    let mut planner = RealFftPlanner::<f32>::new();
+    let fft = planner.plan_fft_forward(samples.len());
+    let mut input = samples.to_vec();
+    let mut output = fft.make_output_vec();
+    fft.process(&mut input, &mut output).unwrap();
+    let n = samples.len() as f32;
+    output.iter().map(|bin| bin.norm_sqr() / n).collect()
+}
+
+/// Peak amplitude of the sinusoid in bin `k` of a power spectrum of `n` samples
+fn tone_amplitude(spectrum: &[f32], k: usize, n: usize) -> f32 {
+    2.0 * (spectrum[k] * n as f32).sqrt() / n as f32
+}
+
+/// Mean power of the bins in `[low, high)`
+fn band_power(spectrum: &[f32], low: usize, high: usize) -> f32 {
+    spectrum[low..high].iter().sum::<f32>() / (high - low) as f32
+}
+
+fn rms(samples: &[f32]) -> f32 {
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+fn assert_close(actual: f32, expected: f32, tolerance: f32, what: &str) {
+    assert!(
+        (actual - expected).abs() <= expected * tolerance,
+        "{}: {} instead of {}",
+        what,
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn test_defaults_add_nothing() -> Result<()> {
+    let defaults = SimulatedSourceConfig::default();
+    assert_eq!(defaults.white_noise_level, 0.0);
+    assert_eq!(defaults.pink_noise_level, 0.0);
+    assert!(defaults.interferer.is_none());
+    assert!(defaults.mains_hum.is_none());
+
+    // Existing configurations deserialize to the same defaults
+    let config: SimulatedSourceConfig = serde_yml::from_str("source_type: universal\n")?;
+    assert_eq!(config.white_noise_level, 0.0);
+    assert!(config.interferer.is_none());
+
+    let (channel_a, channel_b) = generate(&silent_config(), 1)?;
+    assert!(channel_a.iter().chain(&channel_b).all(|&s| s == 0.0));
+    Ok(())
+}
+
+#[test]
+fn test_interferer_and_white_noise_levels() -> Result<()> {
+    let config = SimulatedSourceConfig {
+        white_noise_level: 0.01,
+        interferer: Some(ToneInterference {
+            frequency_hz: 1000.0,
+            amplitude: 0.05,
+        }),
+        mains_hum: Some(ToneInterference {
+            frequency_hz: 50.0,
+            amplitude: 0.02,
+        }),
+        ..silent_config()
+    };
+    let (channel_a, channel_b) = generate(&config, 1)?;
+    let n = channel_a.len();
+    assert_eq!(n, SAMPLE_RATE as usize);
+
+    for channel in [&channel_a, &channel_b] {
+        let spectrum = power_spectrum(channel);
+        assert_close(tone_amplitude(&spectrum, 1000, n), 0.05, 0.05, "interferer");
+        assert_close(tone_amplitude(&spectrum, 50, n), 0.02, 0.1, "mains hum");
+
+        // White noise of RMS level σ has a flat power spectrum of σ²
+        let floor = band_power(&spectrum, 2000, 20000);
+        assert_close(floor, 0.01 * 0.01, 0.1, "white noise floor");
+        // Nothing else stands out of the floor
+        let strongest_other = spectrum
+            .iter()
+            .enumerate()
+            .filter(|(k, _)| *k != 50 && *k != 1000)
+            .map(|(_, power)| *power)
+            .fold(0.0f32, f32::max);
+        assert!(strongest_other < floor * 20.0);
+    }
+
+    // The tones are common to both channels while the noise is independent:
+    // the cross power only contains the power of the tones
+    let cross_power = channel_a
+        .iter()
+        .zip(&channel_b)
+        .map(|(a, b)| a * b)
+        .sum::<f32>()
+        / n as f32;
+    let tones_power = 0.05f32 * 0.05 / 2.0 + 0.02 * 0.02 / 2.0;
+    assert!((cross_power - tones_power).abs() < 0.01 * 0.01 * 0.2);
+    Ok(())
+}
+
+#[test]
+fn test_pink_noise_level_and_slope() -> Result<()> {
+    let config = SimulatedSourceConfig {
+        pink_noise_level: 0.05,
+        ..silent_config()
+    };
+    // Long enough for the low frequency content to average out
+    let seconds = 20;
+    let (channel_a, _) = generate(&config, seconds)?;
+    assert_close(rms(&channel_a), 0.05, 0.15, "pink noise RMS");
+
+    // Average the spectra of one second segments
+    let mut spectrum = vec![0.0f32; SAMPLE_RATE as usize / 2 + 1];
+    for segment in channel_a.chunks_exact(SAMPLE_RATE as usize) {
+        for (total, power) in spectrum.iter_mut().zip(power_spectrum(segment)) {
+            *total += power / seconds as f32;
+        }
+    }
+
+    // 1/f: the power density drops 16 times over four octaves
+    let low = band_power(&spectrum, 100, 200);
+    let high = band_power(&spectrum, 1600, 3200);
+    let ratio = low / high;
+    assert!(
+        (10.0..25.0).contains(&ratio),
+        "power ratio between 100 Hz and 1600 Hz octaves: {}",
+        ratio
+    );
+    Ok(())
+}
+
+#[test]
+fn test_interference_config_validation() {
+    let mut config = Config::default();
+    config.photoacoustic.simulated_source = Some(SimulatedSourceConfig {
+        white_noise_level: 0.01,
+        pink_noise_level: 0.02,
+        interferer: Some(ToneInterference {
+            frequency_hz: 1000.0,
+            amplitude: 0.1,
+        }),
+        mains_hum: Some(ToneInterference {
+            frequency_hz: 60.0,
+            amplitude: 0.01,
+        }),
+        ..silent_config()
+    });
+    assert!(validate_specific_rules(&config).is_ok());
+
+    let invalid = [
+        SimulatedSourceConfig {
+            white_noise_level: 1.5,
+            ..silent_config()
+        },
+        SimulatedSourceConfig {
+            pink_noise_level: -0.1,
+            ..silent_config()
+        },
+        SimulatedSourceConfig {
+            interferer: Some(ToneInterference {
+                frequency_hz: 30000.0,
+                amplitude: 0.1,
+            }),
+            ..silent_config()
+        },
+        SimulatedSourceConfig {
+            mains_hum: Some(ToneInterference {
+                frequency_hz: 55.0,
+                amplitude: 0.01,
+            }),
+            ..silent_config()
+        },
+    ];
+    for simulated_source in invalid {
+        config.photoacoustic.simulated_source = Some(simulated_source.clone());
+        assert!(
+            validate_specific_rules(&config).is_err(),
+            "accepted {:?}",
+            simulated_source
+        );
+    }
+}
@@ -0,0 +1,248 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the sliding DFT analyzer
+//!
+//! The stream is a 2 kHz tone plus a weaker 5 kHz tone in noise, fed in
+//! blocks of irregular sizes. After each block, the sliding DFT spectrum is
+//! compared with a batch transform of the last `frame_size` samples of the
+//! stream: `FFTAnalyzer` for the rectangular window, and an FFT of the
+//! periodically windowed samples for the Hann and Blackman windows.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_rectangular_matches_fft_analyzer`] | Amplitudes and phases match `FFTAnalyzer` after every block, including before the window is full |
+//! | [`test_windowed_matches_batch_fft`] | Hann and Blackman spectra match a batch FFT of the periodically windowed samples |
+//! | [`test_frequency_range`] | Only the bins of the frequency range are reported, with the values of the full analysis |
+//! | [`test_long_stream_stays_accurate`] | The spectrum still matches a batch FFT once the bins have been recomputed from the stored samples |
+//! | [`test_factory_modes`] | `create_spectral_analyzer` builds both modes, which find the same peak on a steady tone |
+
+use rust_photoacoustic::spectral::fft::{
+    FFTAnalyzer, SpectralAnalyzer, SpectrumData, WindowFunction,
+};
+use rust_photoacoustic::spectral::sliding_dft::{SlidingDftAnalyzer, RESYNC_INTERVAL};
+use rust_photoacoustic::spectral::{create_spectral_analyzer, SpectralMode};
+use rust_photoacoustic::utility::noise_generator::NoiseGenerator;
+use rustfft::num_complex::Complex64;
+use rustfft::FftPlanner;
+use std::f64::consts::TAU;
+
+const SAMPLE_RATE: u32 = 48000;
+/// Irregular block sizes, shorter and longer than the windows under test
+const BLOCKS: [usize; 8] = [100, 1000, 37, 4096, 2500, 1, 7000, 512];
+
+/// `len` samples of the stream starting at sample `start`
+fn stream(start: usize, len: usize) -> Vec<f32> {
+    let mut noise = NoiseGenerator::new(start as u32 + 1);
+    (start..start + len)
+        .map(|n| {
+            let t = n as f64 / SAMPLE_RATE as f64;
+            (0.5 * (TAU * 2000.0 * t).sin() + 0.1 * (TAU * 5000.0 * t).cos()) as f32
+                + 0.02 * noise.random_gaussian()
+        })
+        .collect()
+}
+
+/// Last `frame_size` samples of `history`, zero-padded at the start
+fn last_frame(history: &[f32], frame_size: usize) -> Vec<f32> {
+    let mut frame = vec![0.0; frame_size.saturating_sub(history.len())];
+    frame.extend_from_slice(&history[history.len().saturating_sub(frame_size)..]);
+    frame
+}
+
+/// Spectrum of `frame` with a periodic window, normalized like `FFTAnalyzer`
+fn batch_spectrum(frame: &[f32], window_function: WindowFunction) -> SpectrumData {
+    let n = frame.len();
+    let mut buffer: Vec<Complex64> = frame
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| {
+            let phase = TAU * i as f64 / n as f64;
+            let w = match window_function {
+                WindowFunction::Rectangular => 1.0,
+                WindowFunction::Hann => 0.5 - 0.5 * phase.cos(),
+                WindowFunction::Blackman => 0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos(),
+            };
+            Complex64::new(x as f64 * w, 0.0)
+        })
+        .collect();
+    FftPlanner::new().plan_fft_forward(n).process(&mut buffer);
+
+    let df = SAMPLE_RATE as f32 / n as f32;
+    SpectrumData {
+        frequencies: (0..n / 2).map(|k| k as f32 * df).collect(),
+        amplitudes: buffer[..n / 2]
+            .iter()
+            .map(|c| (c.norm() / n as f64 * 2.0) as f32)
+            .collect(),
+        phases: buffer[..n / 2].iter().map(|c| c.arg() as f32).collect(),
+        sample_rate: SAMPLE_RATE,
+    }
+}
+
+fn assert_close(what: &str, sliding: &SpectrumData, batch: &SpectrumData) {
+    assert_eq!(
+        sliding.frequencies, batch.frequencies,
+        "{}: frequencies",
+        what
+    );
+    for (k, (a, b)) in sliding.amplitudes.iter().zip(&batch.amplitudes).enumerate() {
+        assert!(
+            (a - b).abs() < 1e-5,
+            "{}: bin {} amplitude sliding {} batch {}",
+            what,
+            k,
+            a,
+            b
+        );
+        // The phase is only meaningful for bins well above the noise
+        if *b > 1e-2 {
+            let phase_error = (sliding.phases[k] - batch.phases[k] + std::f32::consts::PI)
+                .rem_euclid(std::f32::consts::TAU)
+                - std::f32::consts::PI;
+            assert!(
+                phase_error.abs() < 1e-3,
+                "{}: bin {} phase sliding {} batch {}",
+                what,
+                k,
+                sliding.phases[k],
+                batch.phases[k]
+            );
+        }
+    }
+}
+
+#[test]
+fn test_rectangular_matches_fft_analyzer() {
+    for frame_size in [256, 2048] {
+        let mut sliding = SlidingDftAnalyzer::new(frame_size, 1)
+            .with_window_function(WindowFunction::Rectangular);
+        let mut history = Vec::new();
+        for &len in &BLOCKS {
+            let block = stream(history.len(), len);
+            history.extend_from_slice(&block);
+            let spectrum = sliding.analyze(&block, SAMPLE_RATE).unwrap();
+
+            let expected = FFTAnalyzer::new(frame_size, 1)
+                .with_window_function(WindowFunction::Rectangular)
+                .analyze(&last_frame(&history, frame_size), SAMPLE_RATE)
+                .unwrap();
+            assert_close(
+                &format!("window {} after {} samples", frame_size, history.len()),
+                &spectrum,
+                &expected,
+            );
+        }
+    }
+}
+
+#[test]
+fn test_windowed_matches_batch_fft() {
+    for window_function in [WindowFunction::Hann, WindowFunction::Blackman] {
+        let frame_size = 1000;
+        let mut sliding =
+            SlidingDftAnalyzer::new(frame_size, 1).with_window_function(window_function);
+        let mut history = Vec::new();
+        for &len in &BLOCKS {
+            let block = stream(history.len(), len);
+            history.extend_from_slice(&block);
+            let spectrum = sliding.analyze(&block, SAMPLE_RATE).unwrap();
+            assert_close(
+                &format!("{:?} after {} samples", window_function, history.len()),
+                &spectrum,
+                &batch_spectrum(&last_frame(&history, frame_size), window_function),
+            );
+        }
+    }
+}
+
+#[test]
+fn test_frequency_range() {
+    let frame_size = 2048;
+    let mut full = SlidingDftAnalyzer::new(frame_size, 1);
+    let mut band = SlidingDftAnalyzer::new(frame_size, 1).with_frequency_range(1800.0, 2200.0);
+    let df = SAMPLE_RATE as f32 / frame_size as f32;
+
+    let mut position = 0;
+    for &len in &BLOCKS {
+        let block = stream(position, len);
+        position += len;
+        let expected = full.analyze(&block, SAMPLE_RATE).unwrap();
+        let spectrum = band.analyze(&block, SAMPLE_RATE).unwrap();
+
+        for (k, &frequency) in spectrum.frequencies.iter().enumerate() {
+            let in_band = frequency >= 1800.0 - df && frequency <= 2200.0 + df;
+            if in_band {
+                assert!(
+                    (spectrum.amplitudes[k] - expected.amplitudes[k]).abs() < 1e-6,
+                    "bin {} inside the range",
+                    k
+                );
+            } else if frequency < 1800.0 - 2.0 * df || frequency > 2200.0 + 2.0 * df {
+                assert_eq!(spectrum.amplitudes[k], 0.0, "bin {} outside the range", k);
+            }
+        }
+    }
+
+    // The tracked tone stands out, the 5 kHz tone is not tracked
+    assert!(band.get_amplitude_at(2000.0).unwrap() > 0.1);
+    assert_eq!(band.get_amplitude_at(5000.0).unwrap(), 0.0);
+}
+
+#[test]
+fn test_long_stream_stays_accurate() {
+    let frame_size = 1024;
+    let mut sliding = SlidingDftAnalyzer::new(frame_size, 1).with_frequency_range(1500.0, 2500.0);
+
+    // Cross the resynchronization point with blocks that do not divide it
+    let total = RESYNC_INTERVAL + RESYNC_INTERVAL / 2;
+    let block_size = 4801;
+    let mut position = 0;
+    let mut last_block = Vec::new();
+    while position < total {
+        last_block = stream(position, block_size);
+        sliding.analyze(&last_block, SAMPLE_RATE).unwrap();
+        position += block_size;
+    }
+
+    let spectrum = sliding.get_amplitude_at(2000.0).unwrap();
+    let expected = batch_spectrum(&last_frame(&last_block, frame_size), WindowFunction::Hann);
+    let bin = (2000.0 / (SAMPLE_RATE as f32 / frame_size as f32)).round() as usize;
+    assert!(
+        (spectrum - expected.amplitudes[bin]).abs() < 1e-5,
+        "after {} samples: sliding {} batch {}",
+        position,
+        spectrum,
+        expected.amplitudes[bin]
+    );
+}
+
+#[test]
+fn test_factory_modes() {
+    // 2 kHz falls on bin 96 of a 2304-point window at 48 kHz
+    let frame_size = 2304;
+    let mut fft = create_spectral_analyzer(frame_size, 3, SpectralMode::Fft);
+    let mut sliding = create_spectral_analyzer(frame_size, 3, SpectralMode::SlidingDft);
+
+    let mut position = 0;
+    for _ in 0..4 {
+        let block = stream(position, frame_size);
+        position += frame_size;
+        let fft_spectrum = fft.analyze(&block, SAMPLE_RATE).unwrap();
+        let sliding_spectrum = sliding.analyze(&block, SAMPLE_RATE).unwrap();
+        assert_eq!(fft_spectrum.frequencies, sliding_spectrum.frequencies);
+    }
+
+    // Both use a Hann window, in its symmetric and periodic forms
+    let fft_amplitude = fft.get_amplitude_at(2000.0).unwrap();
+    let sliding_amplitude = sliding.get_amplitude_at(2000.0).unwrap();
+    assert!((fft_amplitude - 0.25).abs() < 5e-3, "FFT {}", fft_amplitude);
+    assert!(
+        (sliding_amplitude - fft_amplitude).abs() < 5e-3,
+        "sliding DFT {} FFT {}",
+        sliding_amplitude,
+        fft_amplitude
+    );
+    assert!(sliding.analyze(&[0.0; 16], 0).is_err());
+}
@@ -0,0 +1,262 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the spectral subtraction node
+//!
+//! The node learns the noise on 1 s of Gaussian white noise, then processes 2 s
+//! of a 1 kHz tone in the same noise. The last second of the input and of the
+//! output are analysed with 1 Hz bins: the tone falls exactly on a bin, and the
+//! noise floor is the mean power of the bins more than 100 Hz away from it.
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_passthrough_while_learning`] | Learnt frames come out unchanged, delayed by `fft_size` samples, and learning can be restarted |
+//! | [`test_noise_floor_reduced`] | The default parameters lower the noise floor while preserving the tone amplitude |
+//! | [`test_over_subtraction`] | A larger over-subtraction and a lower floor remove more noise |
+//! | [`test_node_from_graph_config`] | A `spectral_subtraction` graph node denoises both channels of audio frames |
+
+use anyhow::Result;
+use realfft::RealFftPlanner;
+use rust_photoacoustic::acquisition::AudioFrame;
+use rust_photoacoustic::config::processing::{ConnectionConfig, NodeConfig, ProcessingGraphConfig};
+use rust_photoacoustic::processing::nodes::SpectralSubtractionNode;
+use rust_photoacoustic::processing::{ProcessingData, ProcessingGraph, ProcessingNode};
+use rust_photoacoustic::utility::noise_generator::NoiseGenerator;
+use std::f64::consts::TAU;
+
+const SAMPLE_RATE: u32 = 48000;
+/// 100 ms frames
+const FRAME_SIZE: usize = 4800;
+const LEARN_FRAMES: usize = 10;
+const SIGNAL_FRAMES: usize = 20;
+const TONE_HZ: usize = 1000;
+const TONE_AMPLITUDE: f32 = 0.1;
+const NOISE_LEVEL: f32 = 0.05;
+
+/// Next frame of noise, with the tone when `tone` is set
+fn frame(noise: &mut NoiseGenerator, frame_number: usize, tone: bool) -> Vec<f32> {
+    (frame_number * FRAME_SIZE..(frame_number + 1) * FRAME_SIZE)
+        .map(|n| {
+            let sine = if tone {
+                TONE_AMPLITUDE * (TAU * TONE_HZ as f64 * n as f64 / SAMPLE_RATE as f64).sin() as f32
+            } else {
+                0.0
+            };
+            NOISE_LEVEL * noise.random_gaussian() + sine
+        })
+        .collect()
+}
+
+fn single_channel(samples: Vec<f32>, frame_number: usize) -> ProcessingData {
+    ProcessingData::SingleChannel {
+        samples,
+        sample_rate: SAMPLE_RATE,
+        timestamp: 0,
+        frame_number: frame_number as u64,
+    }
+}
+
+/// Input and output of the learning then signal frames
+fn stream(node: &mut SpectralSubtractionNode) -> Result<(Vec<f32>, Vec<f32>)> {
+    let mut noise = NoiseGenerator::new(12345);
+    let (mut input, mut output) = (Vec::new(), Vec::new());
+    for frame_number in 0..LEARN_FRAMES + SIGNAL_FRAMES {
+        let samples = frame(&mut noise, frame_number, frame_number >= LEARN_FRAMES);
+        input.extend_from_slice(&samples);
+        match node.process(single_channel(samples, frame_number))? {
+            ProcessingData::SingleChannel { samples, .. } => {
+                assert_eq!(samples.len(), FRAME_SIZE);
+                output.extend(samples);
+            }
+            _ => panic!("unexpected output type"),
+        }
+    }
+    Ok((input, output))
+}
+
+/// Peak amplitude of the sinusoid in each bin
+fn amplitude_spectrum(samples: &[f32]) -> Vec<f32> {
+    let mut planner = RealFftPlanner::<f32>::new();
+    let fft = planner.plan_fft_forward(samples.len());
+    let mut input = samples.to_vec();
+    let mut output = fft.make_output_vec();
+    fft.process(&mut input, &mut output).unwrap();
+    let n = samples.len() as f32;
+    output.iter().map(|bin| 2.0 * bin.norm() / n).collect()
+}
+
+/// Tone amplitude and noise floor power of the last second of `samples`
+fn analyse(samples: &[f32]) -> (f32, f32) {
+    let spectrum = amplitude_spectrum(&samples[samples.len() - SAMPLE_RATE as usize..]);
+    let floor: Vec<f32> = spectrum
+        .iter()
+        .enumerate()
+        .filter(|(bin, _)| *bin > 0 && bin.abs_diff(TONE_HZ) > 100)
+        .map(|(_, amplitude)| amplitude * amplitude)
+        .collect();
+    (
+        spectrum[TONE_HZ],
+        floor.iter().sum::<f32>() / floor.len() as f32,
+    )
+}
+
+/// Noise floor reduction in dB and relative tone amplitude error
+fn denoise(node: &mut SpectralSubtractionNode) -> Result<(f32, f32)> {
+    let (input, output) = stream(node)?;
+    let (input_tone, input_floor) = analyse(&input);
+    let (output_tone, output_floor) = analyse(&output);
+    assert!(
+        (input_tone - TONE_AMPLITUDE).abs() < 0.005,
+        "{}",
+        input_tone
+    );
+    Ok((
+        10.0 * (input_floor / output_floor).log10(),
+        (output_tone - input_tone).abs() / input_tone,
+    ))
+}
+
+#[test]
+fn test_passthrough_while_learning() -> Result<()> {
+    let mut node = SpectralSubtractionNode::new("denoise".to_string()).with_learn_frames(30);
+    let latency = node.latency_samples();
+    assert_eq!(latency, 1024);
+
+    let (input, output) = stream(&mut node)?;
+    assert!(!node.is_learning());
+    assert!(output[..latency].iter().all(|sample| sample.abs() < 1e-5));
+    for (n, (sample, expected)) in output[latency..].iter().zip(&input).enumerate() {
+        assert!(
+            (sample - expected).abs() < 1e-5,
+            "sample {}: {} instead of {}",
+            n,
+            sample,
+            expected
+        );
+    }
+
+    node.learn_noise(3);
+    let mut noise = NoiseGenerator::new(1);
+    for frame_number in 0..3 {
+        assert!(node.is_learning());
+        node.process(single_channel(
+            frame(&mut noise, frame_number, false),
+            frame_number,
+        ))?;
+    }
+    assert!(!node.is_learning());
+    Ok(())
+}
+
+#[test]
+fn test_noise_floor_reduced() -> Result<()> {
+    let mut node =
+        SpectralSubtractionNode::new("denoise".to_string()).with_learn_frames(LEARN_FRAMES);
+    let (reduction_db, tone_error) = denoise(&mut node)?;
+    assert!(
+        reduction_db > 6.0,
+        "noise floor lowered by {} dB",
+        reduction_db
+    );
+    assert!(
+        tone_error < 0.02,
+        "tone amplitude changed by {}",
+        tone_error
+    );
+    Ok(())
+}
+
+#[test]
+fn test_over_subtraction() -> Result<()> {
+    let mut default_node =
+        SpectralSubtractionNode::new("denoise".to_string()).with_learn_frames(LEARN_FRAMES);
+    let (default_reduction_db, _) = denoise(&mut default_node)?;
+
+    let mut node = SpectralSubtractionNode::new("denoise".to_string())
+        .with_learn_frames(LEARN_FRAMES)
+        .with_over_subtraction(4.0)
+        .with_spectral_floor(0.01);
+    let (reduction_db, tone_error) = denoise(&mut node)?;
+    assert!(
+        reduction_db > 12.0,
+        "noise floor lowered by {} dB",
+        reduction_db
+    );
+    assert!(reduction_db > default_reduction_db + 3.0);
+    assert!(
+        tone_error < 0.03,
+        "tone amplitude changed by {}",
+        tone_error
+    );
+    Ok(())
+}
+
+#[test]
+fn test_node_from_graph_config() -> Result<()> {
+    let config = ProcessingGraphConfig {
+        id: "denoise_graph".to_string(),
+        nodes: vec![
+            NodeConfig {
+                id: "input".to_string(),
+                node_type: "input".to_string(),
+                parameters: serde_json::Value::Null,
+            },
+            NodeConfig {
+                id: "denoise".to_string(),
+                node_type: "spectral_subtraction".to_string(),
+                parameters: serde_json::json!({
+                    "fft_size": 2048,
+                    "over_subtraction": 2.0,
+                    "spectral_floor": 0.02,
+                    "learn_frames": LEARN_FRAMES
+                }),
+            },
+        ],
+        connections: vec![ConnectionConfig {
+            from: "input".to_string(),
+            to: "denoise".to_string(),
+        }],
+        output_node: Some("denoise".to_string()),
+    };
+    let mut graph = ProcessingGraph::from_config(&config)?;
+
+    // Channel A carries the tone, channel B only noise
+    let (mut noise_a, mut noise_b) = (NoiseGenerator::new(7), NoiseGenerator::new(11));
+    let (mut input_a, mut input_b) = (Vec::new(), Vec::new());
+    let (mut output_a, mut output_b) = (Vec::new(), Vec::new());
+    for frame_number in 0..LEARN_FRAMES + SIGNAL_FRAMES {
+        let channel_a = frame(&mut noise_a, frame_number, frame_number >= LEARN_FRAMES);
+        let channel_b = frame(&mut noise_b, frame_number, false);
+        input_a.extend_from_slice(&channel_a);
+        input_b.extend_from_slice(&channel_b);
+
+        let outputs = graph.execute(ProcessingData::AudioFrame(AudioFrame::new(
+            channel_a,
+            channel_b,
+            SAMPLE_RATE,
+            frame_number as u64,
+        )))?;
+        match &outputs[..] {
+            [ProcessingData::DualChannel {
+                channel_a,
+                channel_b,
+                ..
+            }] => {
+                output_a.extend_from_slice(channel_a);
+                output_b.extend_from_slice(channel_b);
+            }
+            _ => panic!("unexpected output"),
+        }
+    }
+
+    let (input_tone, input_floor) = analyse(&input_a);
+    let (output_tone, output_floor) = analyse(&output_a);
+    assert!(input_floor / output_floor > 4.0);
+    assert!((output_tone - input_tone).abs() / input_tone < 0.02);
+
+    let (_, input_floor) = analyse(&input_b);
+    let (_, output_floor) = analyse(&output_b);
+    assert!(input_floor / output_floor > 4.0);
+    Ok(())
+}
@@ -0,0 +1,180 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the caching headers of the embedded web client files
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_static_file_has_validators`] | Embedded files carry `ETag`, `Last-Modified` and `Cache-Control` |
+//! | [`test_if_none_match_returns_304`] | A second request with the `ETag` gets an empty 304 |
+//! | [`test_if_modified_since_returns_304`] | A second request with the `Last-Modified` date gets a 304 |
+//! | [`test_changed_validators_return_content`] | Stale validators get the full content |
+//! | [`test_spa_fallback_is_revalidated`] | The `index.html` fallback is not cached as immutable |
+
+use rocket::http::{Header, Status};
+use rocket::local::asynchronous::Client;
+use rust_photoacoustic::config::Config;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+
+mod common;
+use common::test_figment;
+
+/// Serializes the tests, which change the process environment
+static ENV_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Build a client serving the embedded files
+///
+/// Must be called while holding [`ENV_LOCK`], and the lock kept for the
+/// whole test, so that no other test reads the environment meanwhile.
+async fn build_test_client() -> Client {
+    // Serve the embedded files rather than proxying to a Vite dev server
+    std::env::remove_var("EXTERNAL_WEB_CLIENT");
+
+    let rocket = rust_photoacoustic::visualization::server::build_rocket(
+        test_figment(),
+        Arc::new(RwLock::new(Config::default())),
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+    Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance")
+}
+
+/// Fetch `/client/index.html` and return its `ETag` and `Last-Modified` headers
+async fn validators(client: &Client) -> (String, String) {
+    let response = client.get("/client/index.html").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    let etag = response
+        .headers()
+        .get_one("ETag")
+        .expect("ETag header present")
+        .to_string();
+    let last_modified = response
+        .headers()
+        .get_one("Last-Modified")
+        .expect("Last-Modified header present")
+        .to_string();
+    (etag, last_modified)
+}
+
+#[rocket::async_test]
+async fn test_static_file_has_validators() {
+    let _env = ENV_LOCK.lock().await;
+    let client = build_test_client().await;
+
+    let response = client.get("/client/index.html").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    let etag = response.headers().get_one("ETag").unwrap().to_string();
+    assert!(etag.starts_with('"') && etag.ends_with('"'));
+    assert!(response
+        .headers()
+        .get_one("Last-Modified")
+        .unwrap()
+        .ends_with("GMT"));
+    assert_eq!(
+        response.headers().get_one("Cache-Control"),
+        Some("no-cache")
+    );
+    assert!(!response.into_bytes().await.unwrap().is_empty());
+
+    // The ETag is stable across requests
+    let (second_etag, _) = validators(&client).await;
+    assert_eq!(etag, second_etag);
+}
+
+#[rocket::async_test]
+async fn test_if_none_match_returns_304() {
+    let _env = ENV_LOCK.lock().await;
+    let client = build_test_client().await;
+    let (etag, _) = validators(&client).await;
+
+    let response = client
+        .get("/client/index.html")
+        .header(Header::new("If-None-Match", etag.clone()))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::NotModified);
+    assert_eq!(response.headers().get_one("ETag"), Some(etag.as_str()));
+    assert!(response.into_bytes().await.unwrap_or_default().is_empty());
+
+    // Lists and weak tags match as well
+    let response = client
+        .get("/client/index.html")
+        .header(Header::new(
+            "If-None-Match",
+            format!("\"other\", W/{}", etag),
+        ))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::NotModified);
+}
+
+#[rocket::async_test]
+async fn test_if_modified_since_returns_304() {
+    let _env = ENV_LOCK.lock().await;
+    let client = build_test_client().await;
+    let (_, last_modified) = validators(&client).await;
+
+    let response = client
+        .get("/client/index.html")
+        .header(Header::new("If-Modified-Since", last_modified))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::NotModified);
+}
+
+#[rocket::async_test]
+async fn test_changed_validators_return_content() {
+    let _env = ENV_LOCK.lock().await;
+    let client = build_test_client().await;
+    let (_, last_modified) = validators(&client).await;
+
+    let response = client
+        .get("/client/index.html")
+        .header(Header::new("If-None-Match", "\"outdated\""))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+
+    // If-None-Match takes precedence over If-Modified-Since
+    let response = client
+        .get("/client/index.html")
+        .header(Header::new("If-None-Match", "\"outdated\""))
+        .header(Header::new("If-Modified-Since", last_modified))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+
+    let response = client
+        .get("/client/index.html")
+        .header(Header::new(
+            "If-Modified-Since",
+            "Thu, 01 Jan 1970 00:00:00 GMT",
+        ))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+}
+
+#[rocket::async_test]
+async fn test_spa_fallback_is_revalidated() {
+    let _env = ENV_LOCK.lock().await;
+    let client = build_test_client().await;
+    let (etag, _) = validators(&client).await;
+
+    // Unknown client routes fall back to index.html, including under assets/
+    let response = client.get("/client/assets/missing-route").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.headers().get_one("ETag"), Some(etag.as_str()));
+    assert_eq!(
+        response.headers().get_one("Cache-Control"),
+        Some("no-cache")
+    );
+}
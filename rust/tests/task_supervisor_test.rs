@@ -0,0 +1,208 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the supervision of the daemon tasks
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_failing_task_is_restarted_until_healthy`] | A task failing with an error then a panic is restarted twice and ends up running |
+//! | [`test_task_is_marked_failed_after_max_restarts`] | A task failing every time is given up and makes `/readyz` answer 503 |
+//! | [`test_task_stopping_on_purpose_is_not_restarted`] | `Ok(())` and failures after the running flag is cleared stop the task |
+//! | [`test_backoff_doubles_up_to_the_maximum`] | The restart delay doubles after each failure and is capped |
+
+use anyhow::{anyhow, Result};
+use rocket::config::LogLevel;
+use rocket::http::Status;
+use rocket::local::asynchronous::Client;
+use rust_photoacoustic::config::{AccessConfig, Config, DaemonConfig, VisualizationConfig};
+use rust_photoacoustic::daemon::supervisor::{RestartPolicy, TaskState, TaskSupervisor};
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// Policy with short delays so that the tests restart tasks quickly
+fn fast_policy(max_restarts: u32) -> RestartPolicy {
+    RestartPolicy {
+        max_restarts,
+        initial_backoff: Duration::from_millis(10),
+        max_backoff: Duration::from_millis(50),
+    }
+}
+
+/// Wait until the task `name` reaches `state`
+async fn wait_for_state(supervisor: &TaskSupervisor, name: &str, state: TaskState) {
+    for _ in 0..200 {
+        if supervisor.status(name).map(|status| status.state) == Some(state) {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!(
+        "task '{}' did not reach {:?}: {:?}",
+        name,
+        state,
+        supervisor.status(name)
+    );
+}
+
+#[tokio::test]
+async fn test_failing_task_is_restarted_until_healthy() -> Result<()> {
+    let supervisor = TaskSupervisor::new(fast_policy(5));
+    let running = Arc::new(AtomicBool::new(true));
+    let attempts = Arc::new(AtomicU32::new(0));
+
+    let task_running = Arc::clone(&running);
+    let task_attempts = Arc::clone(&attempts);
+    let handle = supervisor.spawn("flaky", Arc::clone(&running), move || {
+        let running = Arc::clone(&task_running);
+        let attempt = task_attempts.fetch_add(1, Ordering::SeqCst) + 1;
+        async move {
+            match attempt {
+                1 => Err(anyhow!("device not found")),
+                2 => panic!("driver crashed"),
+                _ => {
+                    while running.load(Ordering::SeqCst) {
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                    }
+                    Ok(())
+                }
+            }
+        }
+    });
+
+    // Wait for the third attempt, which keeps running
+    for _ in 0..200 {
+        if attempts.load(Ordering::SeqCst) >= 3 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let status = supervisor.status("flaky").expect("task is registered");
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    assert_eq!(status.state, TaskState::Running);
+    assert_eq!(status.restarts, 2);
+    assert!(status
+        .last_error
+        .as_deref()
+        .is_some_and(|error| error.contains("driver crashed")));
+
+    running.store(false, Ordering::SeqCst);
+    handle.await??;
+    assert_eq!(
+        supervisor.status("flaky").map(|status| status.state),
+        Some(TaskState::Stopped)
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_task_is_marked_failed_after_max_restarts() -> Result<()> {
+    let supervisor = TaskSupervisor::new(fast_policy(2));
+    let running = Arc::new(AtomicBool::new(true));
+    let attempts = Arc::new(AtomicU32::new(0));
+
+    let task_attempts = Arc::clone(&attempts);
+    let handle = supervisor.spawn("broken", Arc::clone(&running), move || {
+        task_attempts.fetch_add(1, Ordering::SeqCst);
+        async { Err(anyhow!("port already in use")) }
+    });
+
+    let result = tokio::time::timeout(Duration::from_secs(5), handle).await??;
+    assert!(result.is_err());
+    wait_for_state(&supervisor, "broken", TaskState::Failed).await;
+    // The first attempt and its two restarts
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    assert_eq!(supervisor.status("broken").unwrap().restarts, 2);
+
+    // The failed task is reported by the readiness probe
+    let mut config = Config::default();
+    config.acquisition.enabled = false;
+    config.processing.enabled = false;
+    let figment = rocket::Config::figment()
+        .merge(("port", 0))
+        .merge(("address", "127.0.0.1"))
+        .merge(("log_level", LogLevel::Off))
+        .merge(("secret_key", "/qCJ7RyQIugza05wgFNN6R+c2/afrKlG5jJfZ0oQPis="))
+        .merge(("access_config", AccessConfig::default()))
+        .merge(("visualization_config", VisualizationConfig::default()));
+    let rocket = rust_photoacoustic::visualization::server::build_rocket(
+        figment,
+        Arc::new(RwLock::new(config)),
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .await
+    .manage(supervisor.clone());
+    let client = Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance");
+
+    let response = client.get("/readyz").dispatch().await;
+    assert_eq!(response.status(), Status::ServiceUnavailable);
+    let body: Value = serde_json::from_str(&response.into_string().await.unwrap())?;
+    assert_eq!(body["subsystems"]["tasks"]["status"], "unavailable");
+    let detail = body["subsystems"]["tasks"]["detail"].as_str().unwrap();
+    assert!(
+        detail.contains("broken failed"),
+        "unexpected detail: {}",
+        detail
+    );
+    assert!(detail.contains("port already in use"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_task_stopping_on_purpose_is_not_restarted() -> Result<()> {
+    let supervisor = TaskSupervisor::new(fast_policy(5));
+    let running = Arc::new(AtomicBool::new(true));
+    let attempts = Arc::new(AtomicU32::new(0));
+
+    let task_attempts = Arc::clone(&attempts);
+    supervisor
+        .spawn("oneshot", Arc::clone(&running), move || {
+            task_attempts.fetch_add(1, Ordering::SeqCst);
+            async { Ok(()) }
+        })
+        .await??;
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        supervisor.status("oneshot").unwrap().state,
+        TaskState::Stopped
+    );
+
+    // A failure while the daemon stops is not restarted either
+    running.store(false, Ordering::SeqCst);
+    supervisor
+        .spawn("stopping", Arc::clone(&running), || async {
+            Err(anyhow!("connection closed"))
+        })
+        .await??;
+    let status = supervisor.status("stopping").unwrap();
+    assert_eq!(status.state, TaskState::Stopped);
+    assert_eq!(status.restarts, 0);
+    Ok(())
+}
+
+#[test]
+fn test_backoff_doubles_up_to_the_maximum() {
+    let policy = RestartPolicy {
+        max_restarts: 10,
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_millis(1000),
+    };
+    assert_eq!(policy.backoff(1), Duration::from_millis(100));
+    assert_eq!(policy.backoff(2), Duration::from_millis(200));
+    assert_eq!(policy.backoff(4), Duration::from_millis(800));
+    assert_eq!(policy.backoff(5), Duration::from_millis(1000));
+    assert_eq!(policy.backoff(64), Duration::from_millis(1000));
+
+    let defaults = RestartPolicy::from(&DaemonConfig::default());
+    assert_eq!(defaults, RestartPolicy::default());
+    assert_eq!(defaults.max_restarts, 5);
+}
@@ -0,0 +1,177 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the tee audio source
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_tee_records_read_frames`] | Frames read through the tee are unchanged and recorded to a float WAV file |
+//! | [`test_tee_records_int16`] | The 16-bit PCM recording matches the frames to the 16-bit quantization |
+//! | [`test_tee_records_streamed_frames`] | A streaming tee publishes every frame and the WAV file holds exactly the published frames |
+//! | [`test_tee_to_second_stream`] | The stream sink publishes a copy of every frame read |
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::{
+    AudioSource, AudioStreamConsumer, MockSource, RealTimeAudioSource, SharedAudioStream, TeeSink,
+    TeeSource,
+};
+use rust_photoacoustic::config::photoacoustic::{InputRecordingConfig, RecordingSampleFormat};
+use rust_photoacoustic::config::{PhotoacousticConfig, SimulatedSourceConfig};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+const FRAMES: usize = 5;
+const SAMPLE_RATE: u16 = 48000;
+
+fn mock_source() -> Result<MockSource> {
+    let config = PhotoacousticConfig {
+        sample_rate: SAMPLE_RATE,
+        frame_size: 1024,
+        simulated_source: Some(SimulatedSourceConfig {
+            source_type: "mock".to_string(),
+            rng_seed: Some(42),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let mut source = MockSource::new(config)?;
+    source.set_real_time_mode(false);
+    Ok(source)
+}
+
+fn wav_sink(path: &Path, format: RecordingSampleFormat) -> TeeSink {
+    TeeSink::wav(&InputRecordingConfig {
+        path: path.to_string_lossy().into_owned(),
+        format,
+    })
+}
+
+/// Samples of both channels interleaved as in the WAV file
+fn interleave(frames: &[(Vec<f32>, Vec<f32>)]) -> Vec<f32> {
+    frames
+        .iter()
+        .flat_map(|(channel_a, channel_b)| {
+            channel_a
+                .iter()
+                .zip(channel_b)
+                .flat_map(|(sample_a, sample_b)| [*sample_a, *sample_b])
+        })
+        .collect()
+}
+
+#[test]
+fn test_tee_records_read_frames() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let path = temp_dir.path().join("input.wav");
+
+    let mut reference = mock_source()?;
+    let mut tee = TeeSource::new(
+        mock_source()?,
+        wav_sink(&path, RecordingSampleFormat::Float32),
+    );
+
+    let mut frames = Vec::new();
+    for _ in 0..FRAMES {
+        let frame = tee.read_frame()?;
+        assert_eq!(frame, reference.read_frame()?);
+        frames.push(frame);
+    }
+    tee.finish()?;
+
+    let mut reader = hound::WavReader::open(&path)?;
+    let spec = reader.spec();
+    assert_eq!(spec.channels, 2);
+    assert_eq!(spec.sample_rate, SAMPLE_RATE as u32);
+    assert_eq!(spec.bits_per_sample, 32);
+    assert_eq!(spec.sample_format, hound::SampleFormat::Float);
+    let samples = reader.samples::<f32>().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(samples, interleave(&frames));
+    Ok(())
+}
+
+#[test]
+fn test_tee_records_int16() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let path = temp_dir.path().join("input.wav");
+
+    let mut tee = TeeSource::new(
+        mock_source()?,
+        wav_sink(&path, RecordingSampleFormat::Int16),
+    );
+    let frames = (0..FRAMES)
+        .map(|_| tee.read_frame())
+        .collect::<Result<Vec<_>>>()?;
+    tee.finish()?;
+
+    let mut reader = hound::WavReader::open(&path)?;
+    assert_eq!(reader.spec().bits_per_sample, 16);
+    assert_eq!(reader.spec().sample_format, hound::SampleFormat::Int);
+    let samples = reader.samples::<i16>().collect::<Result<Vec<_>, _>>()?;
+
+    let expected = interleave(&frames);
+    assert_eq!(samples.len(), expected.len());
+    for (sample, expected) in samples.iter().zip(&expected) {
+        let sample = *sample as f32 / i16::MAX as f32;
+        assert!(
+            (sample - expected).abs() <= 2.0 / i16::MAX as f32,
+            "{} != {}",
+            sample,
+            expected
+        );
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_tee_records_streamed_frames() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let path = temp_dir.path().join("input.wav");
+
+    let stream = Arc::new(SharedAudioStream::new(1024));
+    let mut consumer = AudioStreamConsumer::new(&stream);
+    let mut tee = TeeSource::new(
+        mock_source()?,
+        wav_sink(&path, RecordingSampleFormat::Float32),
+    );
+
+    tee.start_streaming(Arc::clone(&stream)).await?;
+    assert!(tee.is_streaming());
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    tee.stop_streaming().await?;
+    assert!(!tee.is_streaming());
+
+    let mut frames = Vec::new();
+    while let Some(frame) = consumer.try_next_frame() {
+        assert_eq!(frame.frame_number, frames.len() as u64 + 1);
+        frames.push((frame.channel_a, frame.channel_b));
+    }
+    assert!(frames.len() >= 3, "{} frames", frames.len());
+
+    let samples = hound::WavReader::open(&path)?
+        .samples::<f32>()
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(samples, interleave(&frames));
+    Ok(())
+}
+
+#[test]
+fn test_tee_to_second_stream() -> Result<()> {
+    let second_stream = Arc::new(SharedAudioStream::new(16));
+    let mut consumer = AudioStreamConsumer::new(&second_stream);
+    let mut tee = TeeSource::new(mock_source()?, TeeSink::Stream(Arc::clone(&second_stream)));
+
+    for frame_number in 1..=FRAMES as u64 {
+        let (channel_a, channel_b) = tee.read_frame()?;
+        let copy = consumer
+            .try_next_frame()
+            .expect("frame copied to the stream");
+        assert_eq!(copy.frame_number, frame_number);
+        assert_eq!(copy.sample_rate, SAMPLE_RATE as u32);
+        assert_eq!(copy.channel_a, channel_a);
+        assert_eq!(copy.channel_b, channel_b);
+    }
+    assert!(consumer.try_next_frame().is_none());
+    Ok(())
+}
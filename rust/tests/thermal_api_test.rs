@@ -0,0 +1,216 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the thermal regulation REST API
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_thermal_status_lists_regulators`] | `GET /api/thermal/status` lists the running regulators with their telemetry |
+//! | [`test_thermal_regulator_by_id`] | `GET /api/thermal/<id>` returns one regulator and 404 for an unknown one |
+//! | [`test_setpoint_change_takes_effect`] | A valid `PUT /api/thermal/<id>/setpoint` reaches the regulation loop |
+//! | [`test_setpoint_validation_and_auth`] | Out-of-range setpoints, unknown regulators and missing scopes are rejected |
+
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::asynchronous::Client;
+use rust_photoacoustic::config::thermal_regulation::{I2CBusConfig, ThermalRegulatorConfig};
+use rust_photoacoustic::config::Config;
+use rust_photoacoustic::thermal_regulation::create_shared_thermal_state;
+use rust_photoacoustic::thermal_regulation::daemon::ThermalRegulatorDaemon;
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+
+mod common;
+use common::{access_token, test_figment, BUS_YAML, REGULATOR_YAML, TEST_HMAC_SECRET};
+
+/// Start a mock regulator and a client sharing its thermal state
+///
+/// The daemon is returned so that the test can stop it.
+async fn start_mock_system() -> (Client, ThermalRegulatorDaemon) {
+    let thermal_state = create_shared_thermal_state();
+    let bus_config: I2CBusConfig = serde_yml::from_str(BUS_YAML).unwrap();
+    let regulator_config: ThermalRegulatorConfig = serde_yml::from_str(REGULATOR_YAML).unwrap();
+    let mut daemon = ThermalRegulatorDaemon::new(
+        regulator_config,
+        bus_config,
+        thermal_state.clone(),
+        Arc::new(AtomicBool::new(true)),
+    )
+    .await
+    .unwrap();
+    daemon.start().unwrap();
+
+    let mut config = Config::default();
+    config.visualization.hmac_secret = TEST_HMAC_SECRET.to_string();
+    let rocket = rust_photoacoustic::visualization::server::build_rocket(
+        test_figment(),
+        Arc::new(RwLock::new(config)),
+        None,
+        None,
+        None,
+        Some(thermal_state),
+        None,
+    )
+    .await;
+    let client = Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance");
+
+    // Let the loop run a few cycles
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    (client, daemon)
+}
+
+fn bearer(token: &str) -> Header<'static> {
+    Header::new("Authorization", format!("Bearer {}", token))
+}
+
+async fn get_json(client: &Client, token: &str, uri: &str) -> (Status, Option<Value>) {
+    let response = client.get(uri).header(bearer(token)).dispatch().await;
+    let status = response.status();
+    let body = response
+        .into_string()
+        .await
+        .and_then(|body| serde_json::from_str(&body).ok());
+    (status, body)
+}
+
+async fn put_setpoint(
+    client: &Client,
+    token: &str,
+    id: &str,
+    setpoint_celsius: f64,
+) -> (Status, Option<Value>) {
+    let response = client
+        .put(format!("/api/thermal/{}/setpoint", id))
+        .header(bearer(token))
+        .header(ContentType::JSON)
+        .body(json!({ "setpoint_celsius": setpoint_celsius }).to_string())
+        .dispatch()
+        .await;
+    let status = response.status();
+    let body = response
+        .into_string()
+        .await
+        .and_then(|body| serde_json::from_str(&body).ok());
+    (status, body)
+}
+
+#[rocket::async_test]
+async fn test_thermal_status_lists_regulators() {
+    let (client, mut daemon) = start_mock_system().await;
+    let token = access_token(&client, "admin", "read:api");
+
+    let (status, body) = get_json(&client, &token, "/api/thermal/status").await;
+    assert_eq!(status, Status::Ok);
+    let regulators = body.unwrap();
+    let regulators = regulators.as_array().unwrap();
+    assert_eq!(regulators.len(), 1);
+
+    let regulator = &regulators[0];
+    assert_eq!(regulator["id"], "mock_cell");
+    assert_eq!(regulator["name"], "Mock cell");
+    assert_eq!(regulator["status"], "Running");
+    assert_eq!(regulator["fault"], Value::Null);
+    assert!((regulator["setpoint_celsius"].as_f64().unwrap() - 30.0).abs() < 1e-6);
+    let temperature = regulator["temperature_celsius"].as_f64().unwrap();
+    assert!((20.0..30.0).contains(&temperature), "{}", temperature);
+    // Below the setpoint the regulator heats
+    assert!(regulator["control_output_percent"].as_f64().unwrap() > 0.0);
+    assert!(
+        (regulator["setpoint_limits"]["max_celsius"]
+            .as_f64()
+            .unwrap()
+            - 80.0)
+            .abs()
+            < 1e-6
+    );
+
+    // The historical data endpoint is unchanged
+    let (status, body) = get_json(&client, &token, "/api/thermal?steps=0").await;
+    assert_eq!(status, Status::Ok);
+    assert!(body.unwrap()["data"]["mock_cell"].is_array());
+
+    daemon.stop().await.unwrap();
+}
+
+#[rocket::async_test]
+async fn test_thermal_regulator_by_id() {
+    let (client, mut daemon) = start_mock_system().await;
+    let token = access_token(&client, "admin", "read:api");
+
+    let (status, body) = get_json(&client, &token, "/api/thermal/mock_cell").await;
+    assert_eq!(status, Status::Ok);
+    let regulator = body.unwrap();
+    assert_eq!(regulator["id"], "mock_cell");
+    assert!(regulator["temperature_celsius"].is_f64());
+
+    // Static routes still take precedence over the regulator identifier
+    let (status, body) = get_json(&client, &token, "/api/thermal/regulators").await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body.unwrap(), json!(["mock_cell"]));
+
+    let (status, _) = get_json(&client, &token, "/api/thermal/unknown_cell").await;
+    assert_eq!(status, Status::NotFound);
+
+    daemon.stop().await.unwrap();
+}
+
+#[rocket::async_test]
+async fn test_setpoint_change_takes_effect() {
+    let (client, mut daemon) = start_mock_system().await;
+    let token = access_token(&client, "admin", "read:api write:api");
+
+    let (status, body) = put_setpoint(&client, &token, "mock_cell", 42.5).await;
+    assert_eq!(status, Status::Ok);
+    assert!((body.unwrap()["setpoint_celsius"].as_f64().unwrap() - 42.5).abs() < 1e-6);
+
+    // The loop applies the new setpoint at its next cycle
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let (status, body) = get_json(&client, &token, "/api/thermal/temperatures").await;
+    assert_eq!(status, Status::Ok);
+    let temperatures = body.unwrap();
+    assert!(
+        (temperatures["mock_cell"]["setpoint_celsius"]
+            .as_f64()
+            .unwrap()
+            - 42.5)
+            .abs()
+            < 1e-6
+    );
+
+    let (_, body) = get_json(&client, &token, "/api/thermal/mock_cell").await;
+    assert!((body.unwrap()["setpoint_celsius"].as_f64().unwrap() - 42.5).abs() < 1e-6);
+
+    daemon.stop().await.unwrap();
+}
+
+#[rocket::async_test]
+async fn test_setpoint_validation_and_auth() {
+    let (client, mut daemon) = start_mock_system().await;
+    let token = access_token(&client, "admin", "read:api write:api");
+
+    // Above the 80 °C safety limit
+    let (status, _) = put_setpoint(&client, &token, "mock_cell", 95.0).await;
+    assert_eq!(status, Status::UnprocessableEntity);
+    let (status, _) = put_setpoint(&client, &token, "unknown_cell", 30.0).await;
+    assert_eq!(status, Status::NotFound);
+
+    // The rejected request left the setpoint unchanged
+    let (_, body) = get_json(&client, &token, "/api/thermal/mock_cell").await;
+    assert!((body.unwrap()["setpoint_celsius"].as_f64().unwrap() - 30.0).abs() < 1e-6);
+
+    // A read-only token cannot change the setpoint
+    let read_token = access_token(&client, "admin", "read:api");
+    let (status, _) = put_setpoint(&client, &read_token, "mock_cell", 35.0).await;
+    assert_eq!(status, Status::Forbidden);
+
+    let response = client.get("/api/thermal/status").dispatch().await;
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    daemon.stop().await.unwrap();
+}
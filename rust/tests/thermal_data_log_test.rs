@@ -0,0 +1,164 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Tests for the thermal regulation data log
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_control_loop_writes_csv_log`] | A running regulator appends one CSV row per cycle with plausible telemetry |
+//! | [`test_json_log_rows`] | JSON rows are one object per line and read back unchanged |
+//! | [`test_csv_log_rotation`] | Rotated CSV files are kept up to `max_files` and each starts with the header |
+
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rust_photoacoustic::config::thermal_regulation::{
+    I2CBusConfig, ThermalDataLogConfig, ThermalDataLogFormat, ThermalRegulatorConfig,
+};
+use rust_photoacoustic::thermal_regulation::create_shared_thermal_state;
+use rust_photoacoustic::thermal_regulation::daemon::ThermalRegulatorDaemon;
+use rust_photoacoustic::thermal_regulation::data_log::{
+    ThermalDataLogger, ThermalLogRecord, CSV_HEADER,
+};
+
+mod common;
+use common::{BUS_YAML, REGULATOR_YAML};
+
+fn data_log_config(path: &std::path::Path, format: ThermalDataLogFormat) -> ThermalDataLogConfig {
+    ThermalDataLogConfig {
+        path: path.to_string_lossy().into_owned(),
+        format,
+        max_size: 10 * 1024 * 1024,
+        max_files: 5,
+    }
+}
+
+fn record(temperature_celsius: f64, fault: Option<&str>) -> ThermalLogRecord {
+    ThermalLogRecord::now(
+        "mock_cell",
+        temperature_celsius,
+        30.0,
+        42.5,
+        fault.map(str::to_string),
+    )
+}
+
+fn read_csv(path: &std::path::Path) -> Vec<ThermalLogRecord> {
+    csv::Reader::from_path(path)
+        .unwrap()
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_control_loop_writes_csv_log() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let log_path = temp_dir.path().join("thermal/mock_cell.csv");
+
+    let bus_config: I2CBusConfig = serde_yml::from_str(BUS_YAML).unwrap();
+    let mut regulator_config: ThermalRegulatorConfig = serde_yml::from_str(REGULATOR_YAML).unwrap();
+    regulator_config.data_log = Some(data_log_config(&log_path, ThermalDataLogFormat::Csv));
+
+    let mut daemon = ThermalRegulatorDaemon::new(
+        regulator_config,
+        bus_config,
+        create_shared_thermal_state(),
+        Arc::new(AtomicBool::new(true)),
+    )
+    .await
+    .unwrap();
+    daemon.start().unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    daemon.stop().await.unwrap();
+
+    let content = std::fs::read_to_string(&log_path).unwrap();
+    assert!(content.starts_with(CSV_HEADER), "{}", content);
+
+    let rows = read_csv(&log_path);
+    assert!(rows.len() >= 3, "{} rows", rows.len());
+    for row in &rows {
+        assert_eq!(row.regulator_id, "mock_cell");
+        assert!((row.setpoint_celsius - 30.0).abs() < 1e-6);
+        // The mock cell starts at room temperature and barely moves in 0.5 s
+        assert!(
+            (20.0..30.0).contains(&row.temperature_celsius),
+            "temperature {}",
+            row.temperature_celsius
+        );
+        // Below the setpoint the regulator heats
+        assert!(
+            row.control_output > 0.0 && row.control_output <= 100.0,
+            "output {}",
+            row.control_output
+        );
+        assert_eq!(row.fault, None);
+    }
+    assert!(rows.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+}
+
+#[test]
+fn test_json_log_rows() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let log_path = temp_dir.path().join("mock_cell.jsonl");
+    let mut logger =
+        ThermalDataLogger::open(&data_log_config(&log_path, ThermalDataLogFormat::Json)).unwrap();
+
+    let records = [
+        record(25.0, None),
+        record(
+            26.5,
+            Some("Safety shutdown: fault input 'lid, open' active"),
+        ),
+    ];
+    for record in &records {
+        logger.log(record).unwrap();
+    }
+
+    let content = std::fs::read_to_string(&log_path).unwrap();
+    let rows: Vec<ThermalLogRecord> = content
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(rows, records);
+}
+
+#[test]
+fn test_csv_log_rotation() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let log_path = temp_dir.path().join("mock_cell.csv");
+    let mut config = data_log_config(&log_path, ThermalDataLogFormat::Csv);
+    config.max_size = 300;
+    config.max_files = 2;
+
+    let mut logger = ThermalDataLogger::open(&config).unwrap();
+    for i in 0..20 {
+        logger
+            .log(&record(25.0 + i as f64, Some("overcurrent, 12.00 A")))
+            .unwrap();
+    }
+
+    let rotated = |index: usize| temp_dir.path().join(format!("mock_cell.csv.{}", index));
+    assert!(rotated(2).exists());
+    assert!(!rotated(3).exists());
+
+    let mut total_rows = 0;
+    for path in [rotated(2), rotated(1), log_path.clone()] {
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with(CSV_HEADER), "{}", content);
+        assert!(content.len() <= config.max_size as usize);
+        let rows = read_csv(&path);
+        assert!(rows
+            .iter()
+            .all(|row| row.fault.as_deref() == Some("overcurrent, 12.00 A")));
+        total_rows += rows.len();
+    }
+    // The last row is always in the current file
+    assert_eq!(
+        read_csv(&log_path).last().unwrap().temperature_celsius,
+        44.0
+    );
+    assert!(total_rows < 20);
+}
@@ -0,0 +1,141 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Tests for the thermal regulation safety inputs
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_digital_inputs_polarity`] | Digital inputs are read from the CAT9555 input pins with their configured polarity |
+//! | [`test_fault_input_triggers_shutdown`] | An active fault input forces the control output to zero |
+//! | [`test_current_sense_reading`] | The H-Bridge current is read from the current-sense ADC channel |
+//! | [`test_overcurrent_triggers_shutdown`] | A current above the threshold forces the control output to zero |
+
+use rust_photoacoustic::config::thermal_regulation::{I2CBusConfig, ThermalRegulatorConfig};
+use rust_photoacoustic::thermal_regulation::daemon::{check_fault_inputs, check_overcurrent};
+use rust_photoacoustic::thermal_regulation::{
+    MockL298NThermalRegulationDriver, ThermalRegulationDriver,
+};
+
+mod common;
+use common::{BUS_YAML, REGULATOR_YAML};
+
+const GPIO_ADDRESS: u8 = 0x20;
+
+/// Safety inputs added to the shared [`REGULATOR_YAML`]
+const SAFETY_YAML: &str = r#"
+digital_inputs:
+  - name: over_temperature
+    address: 0x20
+    pin: 8
+    active_low: true
+    fault: true
+  - name: lid_closed
+    address: 0x20
+    pin: 3
+current_sense:
+  adc_address: 0x48
+  adc_channel: 2
+  shunt_resistance_ohm: 0.01
+  amplifier_gain: 20.0
+  overcurrent_threshold_a: 5.0
+"#;
+
+/// Overcurrent threshold of [`SAFETY_YAML`] in amps
+const OVERCURRENT_THRESHOLD_A: f64 = 5.0;
+
+fn mock_driver() -> MockL298NThermalRegulationDriver {
+    let bus_config: I2CBusConfig = serde_yml::from_str(BUS_YAML).unwrap();
+    let regulator_config: ThermalRegulatorConfig =
+        serde_yml::from_str(&format!("{}{}", REGULATOR_YAML, SAFETY_YAML)).unwrap();
+    MockL298NThermalRegulationDriver::new(&bus_config, &regulator_config).unwrap()
+}
+
+#[tokio::test]
+async fn test_digital_inputs_polarity() {
+    let mut driver = mock_driver();
+
+    // Pulled-up pins: the active-low fault is inactive, the active-high input active
+    let inputs = driver.read_digital_inputs().await.unwrap();
+    assert_eq!(inputs.len(), 2);
+    assert_eq!(inputs[0].name, "over_temperature");
+    assert!(!inputs[0].active);
+    assert!(inputs[0].fault);
+    assert_eq!(inputs[1].name, "lid_closed");
+    assert!(inputs[1].active);
+    assert!(!inputs[1].fault);
+
+    driver.set_input_pin(GPIO_ADDRESS, 8, false).unwrap();
+    driver.set_input_pin(GPIO_ADDRESS, 3, false).unwrap();
+    let inputs = driver.read_digital_inputs().await.unwrap();
+    assert!(inputs[0].active);
+    assert!(!inputs[1].active);
+}
+
+#[tokio::test]
+async fn test_fault_input_triggers_shutdown() {
+    let mut driver = mock_driver();
+    driver.apply_control_output(60.0).await.unwrap();
+
+    // A non-fault input does not stop the regulator
+    driver.set_input_pin(GPIO_ADDRESS, 3, false).unwrap();
+    assert_eq!(check_fault_inputs(&mut driver).await.unwrap(), None);
+    assert_eq!(driver.get_current_control_output(), 60.0);
+
+    // The over-temperature comparator pulls its pin low
+    driver.set_input_pin(GPIO_ADDRESS, 8, false).unwrap();
+    let fault = check_fault_inputs(&mut driver)
+        .await
+        .unwrap()
+        .expect("fault input detected");
+    assert_eq!(fault.name, "over_temperature");
+    assert_eq!(driver.get_current_control_output(), 0.0);
+}
+
+#[tokio::test]
+async fn test_current_sense_reading() {
+    let mut driver = mock_driver();
+
+    // No current flows while the H-Bridge is disabled
+    let current = driver
+        .read_current()
+        .await
+        .unwrap()
+        .expect("current sensed");
+    assert!(current.abs() < 0.01, "current {} A", current);
+
+    // 60W heater on 24V: 2.5 A at full power, 1.25 A at half power
+    driver.apply_control_output(50.0).await.unwrap();
+    let current = driver.read_current().await.unwrap().unwrap();
+    assert!((current - 1.25).abs() < 0.01, "current {} A", current);
+
+    // Cooling drives the same load in reverse
+    driver.apply_control_output(-50.0).await.unwrap();
+    let current = driver.read_current().await.unwrap().unwrap();
+    assert!((current - 1.25).abs() < 0.01, "current {} A", current);
+}
+
+#[tokio::test]
+async fn test_overcurrent_triggers_shutdown() {
+    let mut driver = mock_driver();
+    driver.apply_control_output(50.0).await.unwrap();
+
+    // Nominal load current stays below the threshold
+    assert_eq!(
+        check_overcurrent(&mut driver, OVERCURRENT_THRESHOLD_A)
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(driver.get_current_control_output(), 50.0);
+
+    // A shorted load draws 12 A at half power
+    driver.set_load_resistance(1.0).unwrap();
+    let current = check_overcurrent(&mut driver, OVERCURRENT_THRESHOLD_A)
+        .await
+        .unwrap()
+        .expect("overcurrent detected");
+    assert!((current - 12.0).abs() < 0.05, "current {} A", current);
+    assert_eq!(driver.get_current_control_output(), 0.0);
+    assert!(driver.read_current().await.unwrap().unwrap().abs() < 0.01);
+}
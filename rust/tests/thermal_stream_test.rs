@@ -0,0 +1,257 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the `/api/stream/thermal` Server-Sent Events endpoint
+//!
+//! The server is built with a [`SharedThermalState`] fed either by a mock
+//! regulator or directly by the test, while the event stream is read through
+//! the local client:
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_thermal_stream_follows_regulation`] | Each regulation cycle of a running regulator is pushed as a `thermal` event, including setpoint changes |
+//! | [`test_thermal_stream_coalesces_updates`] | Cycles faster than the minimum interval only emit the latest temperature |
+//! | [`test_thermal_stream_requires_token`] | The stream is refused without a valid token |
+
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rocket::http::{Header, Status};
+use rocket::local::asynchronous::{Client, LocalResponse};
+use rust_photoacoustic::config::thermal_regulation::{I2CBusConfig, ThermalRegulatorConfig};
+use rust_photoacoustic::config::Config;
+use rust_photoacoustic::thermal_regulation::create_shared_thermal_state;
+use rust_photoacoustic::thermal_regulation::daemon::ThermalRegulatorDaemon;
+use rust_photoacoustic::thermal_regulation::shared_state::{
+    CurrentPidParams, PidComponents, SharedThermalState,
+};
+use serde_json::Value;
+use tokio::io::AsyncReadExt;
+use tokio::sync::RwLock;
+use tokio::time::timeout;
+
+mod common;
+use common::{access_token, test_figment, BUS_YAML, REGULATOR_YAML, TEST_HMAC_SECRET};
+
+async fn build_test_client(thermal_state: SharedThermalState, interval_ms: u64) -> Client {
+    let mut config = Config::default();
+    config.visualization.hmac_secret = TEST_HMAC_SECRET.to_string();
+    config.visualization.thermal_stream_interval_ms = interval_ms;
+
+    let rocket = rust_photoacoustic::visualization::server::build_rocket(
+        test_figment(),
+        Arc::new(RwLock::new(config)),
+        None,
+        None,
+        None,
+        Some(thermal_state),
+        None,
+    )
+    .await;
+    Client::tracked(rocket)
+        .await
+        .expect("valid rocket instance")
+}
+
+async fn open_stream<'c>(client: &'c Client, token: &str) -> SseReader<'c> {
+    let response = client
+        .get("/api/stream/thermal")
+        .header(Header::new("Authorization", format!("Bearer {}", token)))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    SseReader::new(response)
+}
+
+/// Record a regulation cycle of `regulator_id` at `temperature_celsius`
+async fn record_cycle(
+    thermal_state: &SharedThermalState,
+    regulator_id: &str,
+    temperature_celsius: f64,
+) {
+    let error = 30.0 - temperature_celsius;
+    thermal_state
+        .write()
+        .await
+        .update_regulator_data(
+            regulator_id,
+            temperature_celsius,
+            error,
+            30.0,
+            PidComponents {
+                proportional: error,
+                integral: 0.0,
+                derivative: 0.0,
+                feedforward: 0.0,
+                error,
+            },
+        )
+        .unwrap();
+}
+
+/// Incremental reader of the SSE events of a streamed response
+struct SseReader<'c> {
+    response: LocalResponse<'c>,
+    buffer: String,
+}
+
+impl<'c> SseReader<'c> {
+    fn new(response: LocalResponse<'c>) -> Self {
+        Self {
+            response,
+            buffer: String::new(),
+        }
+    }
+
+    /// Wait for the next named event and return its name and JSON data
+    async fn next_event(&mut self) -> (String, Value) {
+        loop {
+            while let Some(end) = self.buffer.find("\n\n") {
+                let block: String = self.buffer.drain(..end + 2).collect();
+                let mut name = None;
+                let mut data = String::new();
+                for line in block.lines() {
+                    if let Some(value) = line.strip_prefix("event:") {
+                        name = Some(value.trim().to_string());
+                    } else if let Some(value) = line.strip_prefix("data:") {
+                        data.push_str(value.trim());
+                    }
+                }
+                // Comments (heartbeats) have neither a name nor data
+                if let Some(name) = name {
+                    return (name, serde_json::from_str(&data).expect("JSON event data"));
+                }
+            }
+
+            let mut chunk = [0u8; 4096];
+            let read = timeout(Duration::from_secs(5), self.response.read(&mut chunk))
+                .await
+                .expect("event received in time")
+                .expect("readable stream");
+            assert!(read > 0, "event stream ended unexpectedly");
+            self.buffer
+                .push_str(std::str::from_utf8(&chunk[..read]).expect("UTF-8 stream"));
+        }
+    }
+}
+
+#[rocket::async_test]
+async fn test_thermal_stream_follows_regulation() {
+    let thermal_state = create_shared_thermal_state();
+    let bus_config: I2CBusConfig = serde_yml::from_str(BUS_YAML).unwrap();
+    let regulator_config: ThermalRegulatorConfig = serde_yml::from_str(REGULATOR_YAML).unwrap();
+    let mut daemon = ThermalRegulatorDaemon::new(
+        regulator_config,
+        bus_config,
+        thermal_state.clone(),
+        Arc::new(AtomicBool::new(true)),
+    )
+    .await
+    .unwrap();
+    daemon.start().unwrap();
+
+    // The stream checks for new cycles faster than the 20 Hz regulation loop
+    let client = build_test_client(thermal_state.clone(), 10).await;
+    let token = access_token(&client, "admin", "read:api");
+    let mut events = open_stream(&client, &token).await;
+
+    let mut last_sample = 0;
+    for _ in 0..5 {
+        let (name, data) = events.next_event().await;
+        assert_eq!(name, "thermal");
+        assert_eq!(data["regulator_id"], "mock_cell");
+        assert_eq!(data["status"], "Running");
+        let sample = data["sample"].as_u64().unwrap();
+        assert!(
+            sample > last_sample,
+            "sample {} after {}",
+            sample,
+            last_sample
+        );
+        last_sample = sample;
+        assert!((data["setpoint_celsius"].as_f64().unwrap() - 30.0).abs() < 1e-6);
+        let temperature = data["temperature_celsius"].as_f64().unwrap();
+        assert!((20.0..30.0).contains(&temperature), "{}", temperature);
+        // Below the setpoint the regulator heats
+        assert!(data["control_output_percent"].as_f64().unwrap() > 0.0);
+    }
+
+    // A setpoint change shows up in the following cycles
+    thermal_state
+        .write()
+        .await
+        .request_setpoint("mock_cell", 35.0)
+        .unwrap();
+    let mut applied = false;
+    for _ in 0..20 {
+        let (_, data) = events.next_event().await;
+        if (data["setpoint_celsius"].as_f64().unwrap() - 35.0).abs() < 1e-6 {
+            applied = true;
+            break;
+        }
+    }
+    assert!(applied, "setpoint change not streamed");
+
+    daemon.stop().await.unwrap();
+}
+
+#[rocket::async_test]
+async fn test_thermal_stream_coalesces_updates() {
+    let thermal_state = create_shared_thermal_state();
+    thermal_state
+        .write()
+        .await
+        .initialize_regulator(
+            "cell".to_string(),
+            "Cell".to_string(),
+            CurrentPidParams {
+                kp: 1.0,
+                ki: 0.0,
+                kd: 0.0,
+                setpoint_celsius: 30.0,
+                output_min: -100.0,
+                output_max: 100.0,
+            },
+        )
+        .unwrap();
+    record_cycle(&thermal_state, "cell", 25.0).await;
+
+    let client = build_test_client(thermal_state.clone(), 500).await;
+    let token = access_token(&client, "admin", "read:api");
+    let mut events = open_stream(&client, &token).await;
+
+    // The current telemetry is sent on connection
+    let (name, data) = events.next_event().await;
+    assert_eq!(name, "thermal");
+    assert_eq!(data["regulator_id"], "cell");
+    assert_eq!(data["sample"], 1);
+    assert_eq!(data["temperature_celsius"], 25.0);
+
+    // Several cycles within one interval only emit the last one
+    for step in 1..=5 {
+        record_cycle(&thermal_state, "cell", 25.0 + step as f64).await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let (name, data) = events.next_event().await;
+    assert_eq!(name, "thermal");
+    assert_eq!(data["sample"], 6);
+    assert_eq!(data["temperature_celsius"], 30.0);
+    assert_eq!(data["control_output_percent"], 0.0);
+}
+
+#[rocket::async_test]
+async fn test_thermal_stream_requires_token() {
+    let client = build_test_client(create_shared_thermal_state(), 20).await;
+
+    let response = client.get("/api/stream/thermal").dispatch().await;
+    assert_eq!(response.status(), Status::Unauthorized);
+
+    let response = client
+        .get("/api/stream/thermal")
+        .header(Header::new("Authorization", "Bearer invalid"))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Unauthorized);
+}
@@ -0,0 +1,292 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Tests for the coordinated control of adjacent thermal zones
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_master_slave_follows_master_output`] | A slave applies the output of the master, which regulates alone |
+//! | [`test_master_slave_fallback_without_master`] | A slave regulates its own temperature while the master has no reading |
+//! | [`test_averaged_setpoint_control`] | Averaged zones regulate the mean error of the group |
+//! | [`test_zone_readings_skip_stopped_zones`] | Only running zones with a data point are read from the shared state |
+//! | [`test_coordination_reduces_oscillation`] | Both modes damp the oscillation of two coupled zones compared to independent loops |
+
+use std::collections::HashMap;
+
+use rust_photoacoustic::config::thermal_regulation::{
+    ThermalZoneGroupConfig, ZoneCoordinationMode,
+};
+use rust_photoacoustic::thermal_regulation::coordination::{coordinate, ZoneControl, ZoneReading};
+use rust_photoacoustic::thermal_regulation::daemon::PidController;
+use rust_photoacoustic::thermal_regulation::shared_state::{
+    CurrentPidParams, PidComponents, RegulatorStatus, SharedThermalRegulationState,
+};
+use rust_photoacoustic::thermal_regulation::simulation::{ThermalPlant, ThermalPlantParameters};
+
+/// Regulation period of the simulated zones in seconds
+const DT: f64 = 0.5;
+/// Setpoint of every simulated zone in °C
+const SETPOINT: f64 = 35.0;
+/// Ambient temperature in °C
+const AMBIENT: f64 = 25.0;
+/// Heat loss of each zone to the ambient in W/K
+const LOSS_W_PER_K: f64 = 0.5;
+/// Heat exchanged between the two zones in W/K
+const COUPLING_W_PER_K: f64 = 1.0;
+/// Heater power at 100 % output in Watts
+const MAX_POWER_W: f64 = 20.0;
+
+fn group(mode: ZoneCoordinationMode, master: Option<&str>) -> ThermalZoneGroupConfig {
+    ThermalZoneGroupConfig {
+        id: "cells".to_string(),
+        mode,
+        zones: vec!["cell_a".to_string(), "cell_b".to_string()],
+        master: master.map(str::to_string),
+    }
+}
+
+fn reading(temperature_celsius: f64, control_output_percent: f64) -> ZoneReading {
+    ZoneReading {
+        temperature_celsius,
+        setpoint_celsius: SETPOINT,
+        control_output_percent,
+    }
+}
+
+fn pid_params() -> CurrentPidParams {
+    CurrentPidParams {
+        kp: 20.0,
+        ki: 0.8,
+        kd: 0.0,
+        setpoint_celsius: SETPOINT,
+        output_min: -100.0,
+        output_max: 100.0,
+    }
+}
+
+fn shared_state(zones: &[&str]) -> SharedThermalRegulationState {
+    let mut state = SharedThermalRegulationState::new();
+    for zone in zones {
+        state
+            .initialize_regulator(zone.to_string(), zone.to_string(), pid_params())
+            .unwrap();
+    }
+    state
+}
+
+fn publish(state: &mut SharedThermalRegulationState, zone: &str, reading: ZoneReading) {
+    state
+        .update_regulator_data(
+            zone,
+            reading.temperature_celsius,
+            reading.control_output_percent,
+            reading.setpoint_celsius,
+            PidComponents {
+                proportional: 0.0,
+                integral: 0.0,
+                derivative: 0.0,
+                feedforward: 0.0,
+                error: reading.setpoint_celsius - reading.temperature_celsius,
+            },
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_master_slave_follows_master_output() {
+    let group = group(ZoneCoordinationMode::MasterSlave, Some("cell_a"));
+    let readings = HashMap::from([
+        ("cell_a".to_string(), reading(34.0, 42.0)),
+        ("cell_b".to_string(), reading(36.0, -10.0)),
+    ]);
+
+    assert_eq!(
+        coordinate(&group, "cell_a", 34.5, SETPOINT, &readings),
+        ZoneControl::Regulate {
+            process_variable: 34.5
+        }
+    );
+    assert_eq!(
+        coordinate(&group, "cell_b", 36.5, SETPOINT, &readings),
+        ZoneControl::Follow {
+            control_output_percent: 42.0
+        }
+    );
+}
+
+#[test]
+fn test_master_slave_fallback_without_master() {
+    let group = group(ZoneCoordinationMode::MasterSlave, Some("cell_a"));
+    let readings = HashMap::from([("cell_b".to_string(), reading(36.0, -10.0))]);
+
+    assert_eq!(
+        coordinate(&group, "cell_b", 36.5, SETPOINT, &readings),
+        ZoneControl::Regulate {
+            process_variable: 36.5
+        }
+    );
+}
+
+#[test]
+fn test_averaged_setpoint_control() {
+    let group = group(ZoneCoordinationMode::AveragedSetpoint, None);
+
+    // Alone, a zone regulates its own temperature
+    assert_eq!(
+        coordinate(&group, "cell_a", 34.0, SETPOINT, &HashMap::new()),
+        ZoneControl::Regulate {
+            process_variable: 34.0
+        }
+    );
+
+    // The published reading of the zone itself is replaced by the current one
+    let readings = HashMap::from([
+        ("cell_a".to_string(), reading(30.0, 0.0)),
+        ("cell_b".to_string(), reading(37.0, 0.0)),
+        ("other_cell".to_string(), reading(0.0, 0.0)),
+    ]);
+    let ZoneControl::Regulate { process_variable } =
+        coordinate(&group, "cell_a", 34.0, SETPOINT, &readings)
+    else {
+        panic!("averaged zones always regulate");
+    };
+    // Mean temperature 35.5 °C: 0.5 °C above the mean setpoint
+    assert!(
+        (process_variable - 35.5).abs() < 1e-9,
+        "{}",
+        process_variable
+    );
+}
+
+#[test]
+fn test_zone_readings_skip_stopped_zones() {
+    let mut state = shared_state(&["cell_a", "cell_b", "cell_c"]);
+    let zones = ["cell_a", "cell_b", "cell_c"].map(str::to_string);
+
+    // Initializing regulators have no reading
+    assert!(state.zone_readings(&zones).is_empty());
+
+    publish(&mut state, "cell_a", reading(34.0, 10.0));
+    publish(&mut state, "cell_a", reading(34.2, 12.0));
+    publish(&mut state, "cell_b", reading(35.0, 5.0));
+    publish(&mut state, "cell_c", reading(36.0, 0.0));
+    state
+        .update_regulator_status(
+            "cell_b",
+            RegulatorStatus::Error {
+                message: "sensor".to_string(),
+            },
+        )
+        .unwrap();
+
+    let readings = state.zone_readings(&zones[..2]);
+    assert_eq!(readings.len(), 1);
+    assert_eq!(readings["cell_a"], reading(34.2, 12.0));
+}
+
+/// Peak-to-peak temperature of each zone once settled, for two coupled zones
+///
+/// Both zones are 500 J/K bodies losing 0.5 W/K to the ambient and exchanging
+/// 1 W/K with each other. Zone B has a longer actuator deadtime and a slower
+/// sensor than zone A, which makes its independent loop oscillate and drive
+/// the temperature of zone A.
+fn settled_peak_to_peak(group: Option<&ThermalZoneGroupConfig>) -> [f64; 2] {
+    let zones = ["cell_a", "cell_b"];
+    let zone_ids = zones.map(str::to_string);
+    let mut plants = [(2.0, 5.0), (10.0, 20.0)].map(|(deadtime, sensor_time_constant)| {
+        ThermalPlant::new(ThermalPlantParameters {
+            thermal_mass_j_per_k: 500.0,
+            heat_transfer_w_per_k: LOSS_W_PER_K + COUPLING_W_PER_K,
+            ambient_temperature_c: AMBIENT,
+            actuator_deadtime_s: deadtime,
+            sensor_time_constant_s: Some(sensor_time_constant),
+            heating_efficiency: 1.0,
+            cooling_efficiency: 1.0,
+            max_cooling_below_ambient_k: None,
+        })
+    });
+    let mut pids =
+        [(); 2].map(|_| PidController::new(20.0, 0.8, 0.0, SETPOINT, 1000.0, -100.0, 100.0));
+    let mut state = shared_state(&zones);
+
+    let mut ranges = [(f64::INFINITY, f64::NEG_INFINITY); 2];
+    for step in 1..=8000 {
+        for (index, zone) in zones.iter().enumerate() {
+            let temperature = plants[index].temperature();
+            let control = match group {
+                Some(group) => coordinate(
+                    group,
+                    zone,
+                    temperature,
+                    SETPOINT,
+                    &state.zone_readings(&zone_ids),
+                ),
+                None => ZoneControl::Regulate {
+                    process_variable: temperature,
+                },
+            };
+            let output = match control {
+                ZoneControl::Regulate { process_variable } => {
+                    pids[index].update_with_dt(process_variable, DT)
+                }
+                ZoneControl::Follow {
+                    control_output_percent,
+                } => {
+                    pids[index].reset();
+                    pids[index].follow(control_output_percent, temperature)
+                }
+            };
+            publish(
+                &mut state,
+                zone,
+                reading(temperature, output.control_output),
+            );
+            plants[index].set_power(output.control_output / 100.0 * MAX_POWER_W);
+        }
+
+        // Each zone sees the other one as part of its surroundings
+        let bodies = [plants[0].body_temperature(), plants[1].body_temperature()];
+        for (index, plant) in plants.iter_mut().enumerate() {
+            plant.set_ambient_temperature(
+                (LOSS_W_PER_K * AMBIENT + COUPLING_W_PER_K * bodies[1 - index])
+                    / (LOSS_W_PER_K + COUPLING_W_PER_K),
+            );
+            plant.step(DT);
+        }
+
+        if step as f64 * DT >= 3000.0 {
+            for (range, plant) in ranges.iter_mut().zip(&plants) {
+                range.0 = range.0.min(plant.temperature());
+                range.1 = range.1.max(plant.temperature());
+                assert!(
+                    (plant.temperature() - SETPOINT).abs() < 1.0,
+                    "temperature {} °C",
+                    plant.temperature()
+                );
+            }
+        }
+    }
+    ranges.map(|(min, max)| max - min)
+}
+
+#[test]
+fn test_coordination_reduces_oscillation() {
+    let independent = settled_peak_to_peak(None);
+    let independent_max = independent[0].max(independent[1]);
+    assert!(independent_max > 1.0, "independent {:?}", independent);
+
+    for group in [
+        group(ZoneCoordinationMode::MasterSlave, Some("cell_a")),
+        group(ZoneCoordinationMode::AveragedSetpoint, None),
+    ] {
+        let coordinated = settled_peak_to_peak(Some(&group));
+        assert!(
+            coordinated[0].max(coordinated[1]) < independent_max / 4.0,
+            "{:?}: coordinated {:?}, independent {:?}",
+            group.mode,
+            coordinated,
+            independent
+        );
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Tests for the token inspection of the `create_token` binary
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_inspect_minted_token`] | A freshly minted HS256 or RS256 token is valid, its claims and permissions are decoded |
+//! | [`test_inspect_tampered_token`] | A token whose claims were altered or signed with another key has an invalid signature |
+//! | [`test_inspect_expired_token`] | An expired token is flagged as expired, not as forged |
+//! | [`test_inspect_malformed_token`] | Strings that are not JWT tokens are rejected |
+
+use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+use base64::Engine;
+use rust_photoacoustic::config::Config;
+use rust_photoacoustic::utility::jwt_token::{
+    ConfigLoader, JwtAlgorithm, TokenCreationError, TokenCreationParams, TokenCreator,
+    TokenInspector, TokenStatus,
+};
+use std::time::Duration;
+
+mod common;
+use common::TEST_HMAC_SECRET;
+
+fn config_loader(hmac_secret: &str, clock_skew_seconds: u64) -> ConfigLoader {
+    let mut config = Config::default();
+    config.visualization.hmac_secret = hmac_secret.to_string();
+    config.access.clock_skew_seconds = clock_skew_seconds;
+    ConfigLoader::from_config(&config).unwrap()
+}
+
+fn mint(config_loader: &ConfigLoader, algorithm: JwtAlgorithm, duration_seconds: u64) -> String {
+    TokenCreator::new(config_loader)
+        .unwrap()
+        .create_token(&TokenCreationParams {
+            user_id: "admin".to_string(),
+            client_id: "LaserSmartClient".to_string(),
+            algorithm,
+            duration_seconds,
+        })
+        .expect("token minted")
+        .token
+}
+
+#[test]
+fn test_inspect_minted_token() {
+    let config_loader = config_loader(TEST_HMAC_SECRET, 60);
+    let inspector = TokenInspector::new(&config_loader).unwrap();
+
+    for algorithm in [JwtAlgorithm::HS256, JwtAlgorithm::RS256] {
+        let name = algorithm.as_str();
+        let token = mint(&config_loader, algorithm, 3600);
+        let inspection = inspector.inspect(&token).expect("token decoded");
+
+        assert_eq!(inspection.status, TokenStatus::Valid, "{}", name);
+        assert_eq!(inspection.header["alg"], name);
+        assert_eq!(inspection.claims["sub"], "admin");
+        assert_eq!(inspection.claims["aud"], "LaserSmartClient");
+        assert!(inspection.permissions.contains(&"admin:api".to_string()));
+        let remaining = inspection.expires_at.expect("expiry decoded") - chrono::Utc::now();
+        assert!(remaining.num_seconds() > 3500 && remaining.num_seconds() <= 3600);
+    }
+}
+
+#[test]
+fn test_inspect_tampered_token() {
+    let config_loader = config_loader(TEST_HMAC_SECRET, 60);
+    let inspector = TokenInspector::new(&config_loader).unwrap();
+    let token = mint(&config_loader, JwtAlgorithm::HS256, 3600);
+
+    // Replace the subject, keeping the original signature
+    let parts: Vec<&str> = token.split('.').collect();
+    let mut claims: serde_json::Value =
+        serde_json::from_slice(&BASE64_URL_SAFE_NO_PAD.decode(parts[1]).unwrap()).unwrap();
+    claims["sub"] = "mallory".into();
+    let forged = format!(
+        "{}.{}.{}",
+        parts[0],
+        BASE64_URL_SAFE_NO_PAD.encode(claims.to_string()),
+        parts[2]
+    );
+    let inspection = inspector.inspect(&forged).expect("forged token decoded");
+    assert!(
+        matches!(inspection.status, TokenStatus::InvalidSignature { .. }),
+        "{:?}",
+        inspection.status
+    );
+    assert!(!inspection.status.is_valid());
+    // The claims are still shown
+    assert_eq!(inspection.claims["sub"], "mallory");
+
+    // A token signed with another secret is not accepted either
+    let other_key = mint(
+        &config_loader("another-hmac-secret", 60),
+        JwtAlgorithm::HS256,
+        3600,
+    );
+    let inspection = inspector.inspect(&other_key).unwrap();
+    assert!(matches!(
+        inspection.status,
+        TokenStatus::InvalidSignature { .. }
+    ));
+}
+
+#[test]
+fn test_inspect_expired_token() {
+    let config_loader = config_loader(TEST_HMAC_SECRET, 0);
+    let inspector = TokenInspector::new(&config_loader).unwrap();
+    let token = mint(&config_loader, JwtAlgorithm::HS256, 1);
+
+    std::thread::sleep(Duration::from_millis(2100));
+    let inspection = inspector.inspect(&token).unwrap();
+    assert_eq!(inspection.status, TokenStatus::Expired);
+    assert!(inspection.expires_at.unwrap() < chrono::Utc::now());
+}
+
+#[test]
+fn test_inspect_malformed_token() {
+    let inspector = TokenInspector::new(&config_loader(TEST_HMAC_SECRET, 60)).unwrap();
+
+    for token in ["", "not-a-token", "a.b.c", "eyJhbGciOiJIUzI1NiJ9.%%%.sig"] {
+        assert!(
+            matches!(
+                inspector.inspect(token),
+                Err(TokenCreationError::MalformedToken { .. })
+            ),
+            "{}",
+            token
+        );
+    }
+}
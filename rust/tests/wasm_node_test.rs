@@ -0,0 +1,316 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the WebAssembly plugin node
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_wasm_plugin_doubles_signal`] | A doubling plugin is applied to single and dual channel frames, buffers grow with the frames |
+//! | [`test_wasm_timeout_interrupts_call`] | A plugin stuck in a loop is interrupted, counted and the next frame is processed |
+//! | [`test_wasm_deadline_does_not_leak`] | Calls made right after the deadline of the previous one elapsed are never interrupted |
+//! | [`test_wasm_memory_limit`] | A frame exceeding the memory limit fails without affecting smaller frames |
+//! | [`test_wasm_rejects_invalid_modules`] | Modules requiring imports or lacking an export are rejected, `module_path` keeps the previous module |
+//! | [`test_wasm_node_from_config`] | The node is created from its graph parameters and requires `module_path` |
+
+#![cfg(feature = "wasm-node")]
+
+use anyhow::Result;
+use rust_photoacoustic::acquisition::AudioFrame;
+use rust_photoacoustic::processing::nodes::{
+    ProcessingData, ProcessingMetadata, ProcessingNode, WasmNode, WasmNodeConfig,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+/// Bump allocator growing the memory on demand, `0` once it cannot grow
+const ALLOC: &str = r#"
+  (global $next (mut i32) (i32.const 1024))
+  (func (export "alloc") (param $size i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $next))
+    (global.set $next (i32.add (global.get $next) (local.get $size)))
+    (block $done
+      (loop $grow
+        (br_if $done (i32.le_u (global.get $next)
+                               (i32.mul (memory.size) (i32.const 65536))))
+        (if (i32.eq (memory.grow (i32.const 1)) (i32.const -1))
+          (then (return (i32.const 0))))
+        (br $grow)))
+    (local.get $ptr))
+"#;
+
+/// Writes `channel_a * 2` to the output
+const DOUBLE: &str = r#"
+  (func (export "process")
+    (param $a i32) (param $b i32) (param $len i32) (param $out i32) (result i32)
+    (local $i i32)
+    (block $end
+      (loop $next_sample
+        (br_if $end (i32.ge_u (local.get $i) (local.get $len)))
+        (f32.store
+          (i32.add (local.get $out) (i32.shl (local.get $i) (i32.const 2)))
+          (f32.mul
+            (f32.load (i32.add (local.get $a) (i32.shl (local.get $i) (i32.const 2))))
+            (f32.const 2)))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $next_sample)))
+    (local.get $len))
+"#;
+
+/// Loops forever on frames of three samples, copies channel A otherwise
+const STUCK_ON_THREE_SAMPLES: &str = r#"
+  (func (export "process")
+    (param $a i32) (param $b i32) (param $len i32) (param $out i32) (result i32)
+    (if (i32.eq (local.get $len) (i32.const 3))
+      (then (loop $forever (br $forever))))
+    (memory.copy (local.get $out) (local.get $a) (i32.shl (local.get $len) (i32.const 2)))
+    (local.get $len))
+"#;
+
+fn write_module(dir: &TempDir, name: &str, body: &str) -> PathBuf {
+    let module_path = dir.path().join(name);
+    let module = format!("(module\n  (memory (export \"memory\") 1)\n{}\n)", body);
+    std::fs::write(&module_path, module).expect("Failed to write test module");
+    module_path
+}
+
+fn wasm_node(module_path: PathBuf) -> WasmNode {
+    WasmNode::new(
+        "wasm".to_string(),
+        WasmNodeConfig {
+            module_path,
+            ..Default::default()
+        },
+    )
+    .expect("Failed to create WebAssembly node")
+}
+
+fn single_channel(samples: Vec<f32>, frame_number: u64) -> ProcessingData {
+    ProcessingData::SingleChannel {
+        samples,
+        sample_rate: 48000,
+        timestamp: 1000,
+        frame_number,
+    }
+}
+
+#[test]
+fn test_wasm_plugin_doubles_signal() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut node = wasm_node(write_module(
+        &dir,
+        "double.wat",
+        &format!("{}{}", ALLOC, DOUBLE),
+    ));
+    assert_eq!(node.node_type(), "wasm");
+
+    assert_eq!(
+        node.process(single_channel(vec![0.5, -0.25, 1.5], 1))?,
+        single_channel(vec![1.0, -0.5, 3.0], 1)
+    );
+
+    let dual = ProcessingData::DualChannel {
+        channel_a: vec![0.1, 0.2],
+        channel_b: vec![9.0, 9.0],
+        sample_rate: 44100,
+        timestamp: 2000,
+        frame_number: 2,
+    };
+    assert_eq!(node.output_type(&dual), Some("SingleChannel".to_string()));
+    assert_eq!(
+        node.process(dual)?,
+        ProcessingData::SingleChannel {
+            samples: vec![0.2, 0.4],
+            sample_rate: 44100,
+            timestamp: 2000,
+            frame_number: 2,
+        }
+    );
+
+    // A frame larger than the first ones needs new buffers, beyond the first page
+    let channel_a: Vec<f32> = (0..20_000).map(|i| i as f32).collect();
+    let output = node.process(ProcessingData::AudioFrame(AudioFrame {
+        channel_a: channel_a.clone(),
+        channel_b: vec![0.0; 20_000],
+        sample_rate: 48000,
+        timestamp: 3000,
+        frame_number: 3,
+    }))?;
+    let expected: Vec<f32> = channel_a.iter().map(|s| s * 2.0).collect();
+    assert_eq!(
+        output,
+        ProcessingData::SingleChannel {
+            samples: expected,
+            sample_rate: 48000,
+            timestamp: 3000,
+            frame_number: 3,
+        }
+    );
+
+    let result = ProcessingData::PhotoacousticResult {
+        signal: vec![1.0],
+        metadata: ProcessingMetadata {
+            original_frame_number: 4,
+            original_timestamp: 4000,
+            sample_rate: 48000,
+            processing_steps: vec![],
+            processing_latency_us: 0,
+            snr_db: None,
+        },
+    };
+    assert!(!node.accepts_input(&result));
+    assert!(node.process(result).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_wasm_timeout_interrupts_call() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut node = WasmNode::new(
+        "wasm".to_string(),
+        WasmNodeConfig {
+            module_path: write_module(
+                &dir,
+                "stuck.wat",
+                &format!("{}{}", ALLOC, STUCK_ON_THREE_SAMPLES),
+            ),
+            timeout_ms: 200,
+            ..Default::default()
+        },
+    )?;
+
+    let start = Instant::now();
+    assert!(node
+        .process(single_channel(vec![0.5, 0.5, 0.5], 1))
+        .is_err());
+    assert!(start.elapsed() < Duration::from_secs(10));
+    assert_eq!(node.timeout_count(), 1);
+
+    assert_eq!(
+        node.process(single_channel(vec![0.5, 0.25], 2))?,
+        single_channel(vec![0.5, 0.25], 2)
+    );
+    assert_eq!(ProcessingNode::timeout_count(&node), 1);
+    Ok(())
+}
+
+#[test]
+fn test_wasm_deadline_does_not_leak() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut node = WasmNode::new(
+        "wasm".to_string(),
+        WasmNodeConfig {
+            module_path: write_module(
+                &dir,
+                "stuck.wat",
+                &format!("{}{}", ALLOC, STUCK_ON_THREE_SAMPLES),
+            ),
+            timeout_ms: 20,
+            ..Default::default()
+        },
+    )?;
+
+    // Each frame starts once the deadline of the previous one has elapsed
+    for frame_number in 1..=50 {
+        assert_eq!(
+            node.process(single_channel(vec![0.5, 0.25], frame_number))?,
+            single_channel(vec![0.5, 0.25], frame_number)
+        );
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    assert_eq!(node.timeout_count(), 0);
+
+    // The watchdog still interrupts a late call
+    assert!(node
+        .process(single_channel(vec![0.5, 0.5, 0.5], 51))
+        .is_err());
+    assert_eq!(node.timeout_count(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_wasm_memory_limit() -> Result<()> {
+    let dir = TempDir::new()?;
+    let mut node = WasmNode::new(
+        "wasm".to_string(),
+        WasmNodeConfig {
+            module_path: write_module(&dir, "double.wat", &format!("{}{}", ALLOC, DOUBLE)),
+            memory_limit_mb: 1,
+            ..Default::default()
+        },
+    )?;
+
+    // Three buffers of 1 MiB cannot fit in a 1 MiB memory
+    let error = node
+        .process(single_channel(vec![1.0; 256 * 1024], 1))
+        .expect_err("the frame must exceed the memory limit");
+    assert!(error.to_string().contains("allocate"), "{}", error);
+
+    node.reset();
+    assert_eq!(
+        node.process(single_channel(vec![1.0; 16], 2))?,
+        single_channel(vec![2.0; 16], 2)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_wasm_rejects_invalid_modules() -> Result<()> {
+    let dir = TempDir::new()?;
+    let double = write_module(&dir, "double.wat", &format!("{}{}", ALLOC, DOUBLE));
+    let mut node = wasm_node(double.clone());
+    assert!(node.supports_hot_reload());
+    node.reload()?;
+
+    let host_import = dir.path().join("import.wat");
+    std::fs::write(
+        &host_import,
+        format!(
+            "(module\n  (import \"env\" \"log\" (func (param i32)))\n  (memory (export \"memory\") 1)\n{}{}\n)",
+            ALLOC, DOUBLE
+        ),
+    )?;
+    let invalid = [
+        host_import,
+        write_module(&dir, "no_process.wat", ALLOC),
+        write_module(&dir, "no_alloc.wat", DOUBLE),
+        write_module(&dir, "syntax.wat", "(func"),
+        dir.path().join("missing.wasm"),
+    ];
+    for module_path in invalid {
+        assert!(node
+            .update_config(&json!({ "module_path": module_path }))
+            .is_err());
+        assert_eq!(node.config().module_path, double);
+        assert_eq!(
+            node.process(single_channel(vec![1.0], 1))?,
+            single_channel(vec![2.0], 1)
+        );
+    }
+
+    assert!(node.update_config(&json!({ "timeout_ms": 50 }))?);
+    assert_eq!(node.config().timeout_ms, 50);
+    assert!(!node.update_config(&json!({ "memory_limit_mb": 8 }))?);
+    Ok(())
+}
+
+#[test]
+fn test_wasm_node_from_config() -> Result<()> {
+    let config: HashMap<String, serde_json::Value> = serde_json::from_value(json!({
+        "module_path": "doubler.wasm",
+        "memory_limit_mb": 16,
+        "timeout_ms": 100
+    }))?;
+    let node = WasmNode::from_config("wasm_plugin".to_string(), config)?;
+    assert_eq!(node.node_id(), "wasm_plugin");
+    assert_eq!(node.config().module_path, PathBuf::from("doubler.wasm"));
+    assert_eq!(node.config().memory_limit_mb, 16);
+    assert_eq!(node.config().timeout_ms, 100);
+
+    assert!(WasmNode::from_config("wasm".to_string(), HashMap::new()).is_err());
+    Ok(())
+}
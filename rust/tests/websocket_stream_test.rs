@@ -0,0 +1,140 @@
+// Copyright (c) 2025 Ronan LE MEILLAT, SCTG Development
+// This file is part of the rust-photoacoustic project and is licensed under the
+// SCTG Development Non-Commercial License v1.0 (see LICENSE.md for details).
+
+//! Integration tests for the `/ws/stream` WebSocket audio endpoint
+//!
+//! The server is launched on a local port with a [`SharedAudioStream`]; frames
+//! are published on the stream once a client is connected:
+//!
+//! | Test | What it verifies |
+//! |---|---|
+//! | [`test_websocket_receives_frames`] | An authenticated client receives a sequence of frames for its channel |
+//! | [`test_websocket_requires_token`] | The upgrade is refused without a valid token |
+
+use futures::{SinkExt, StreamExt};
+use rust_photoacoustic::acquisition::{AudioFrame, SharedAudioStream};
+use rust_photoacoustic::config::Config;
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio::sync::RwLock;
+use tokio::time::{sleep, timeout};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+mod common;
+use common::{rocket_access_token, test_figment, TEST_HMAC_SECRET};
+
+/// Each test launches its own server so they can run in parallel
+const TEST_PORT: u16 = 8096;
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Launch the server on `port` with `stream` and return an access token for `admin`
+async fn launch_server(stream: Arc<SharedAudioStream>, port: u16) -> String {
+    let mut config = Config::default();
+    config.visualization.hmac_secret = TEST_HMAC_SECRET.to_string();
+
+    let rocket = rust_photoacoustic::visualization::server::build_rocket(
+        test_figment().merge(("port", port)),
+        Arc::new(RwLock::new(config)),
+        Some(stream),
+        None,
+        None,
+        None,
+        None,
+    )
+    .await;
+
+    let token = rocket_access_token(&rocket, "admin", "read:api");
+
+    tokio::spawn(async move {
+        let _ = rocket.launch().await;
+    });
+    // Give the server time to start up
+    sleep(std::time::Duration::from_millis(500)).await;
+
+    token
+}
+
+/// Wait for the next JSON text message, skipping control frames
+async fn next_json(socket: &mut Socket) -> Value {
+    loop {
+        let message = timeout(std::time::Duration::from_secs(5), socket.next())
+            .await
+            .expect("message received in time")
+            .expect("stream still open")
+            .expect("valid WebSocket message");
+        if let Message::Text(text) = message {
+            return serde_json::from_str(&text).expect("JSON message");
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_websocket_receives_frames() {
+    let stream = Arc::new(SharedAudioStream::new(16));
+    let token = launch_server(stream.clone(), TEST_PORT).await;
+
+    let url = format!(
+        "ws://127.0.0.1:{}/ws/stream?channel=differential&access_token={}",
+        TEST_PORT, token
+    );
+    let (mut socket, _) = connect_async(url).await.expect("WebSocket upgrade");
+
+    for frame_number in 1..=3 {
+        let frame = AudioFrame::new(
+            vec![1.0, 2.0, frame_number as f32],
+            vec![0.5, 0.5, 0.5],
+            48000,
+            frame_number,
+        );
+        stream.publish(frame).await.unwrap();
+    }
+
+    for frame_number in 1..=3u64 {
+        let message = next_json(&mut socket).await;
+        assert_eq!(message["channel"], "differential");
+        assert_eq!(message["frame_number"], frame_number);
+        assert_eq!(message["sample_rate"], 48000);
+        assert_eq!(message["dropped_frames"], 0);
+        assert_eq!(
+            message["samples"],
+            serde_json::json!([0.5, 1.5, frame_number as f32 - 0.5])
+        );
+    }
+
+    // Switch to channel B
+    socket
+        .send(Message::Text(r#"{"channel": "b"}"#.to_string()))
+        .await
+        .unwrap();
+    sleep(std::time::Duration::from_millis(100)).await;
+    stream
+        .publish(AudioFrame::new(vec![1.0], vec![0.25], 48000, 4))
+        .await
+        .unwrap();
+
+    let message = next_json(&mut socket).await;
+    assert_eq!(message["channel"], "b");
+    assert_eq!(message["frame_number"], 4);
+    assert_eq!(message["samples"], serde_json::json!([0.25]));
+
+    socket.close(None).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_websocket_requires_token() {
+    let stream = Arc::new(SharedAudioStream::new(16));
+    let token = launch_server(stream, TEST_PORT + 1).await;
+    let base = format!("ws://127.0.0.1:{}/ws/stream", TEST_PORT + 1);
+
+    assert!(connect_async(base.clone()).await.is_err());
+    assert!(connect_async(format!("{}?access_token=invalid", base))
+        .await
+        .is_err());
+    assert!(connect_async(format!("{}?access_token={}", base, token))
+        .await
+        .is_ok());
+}
@@ -137,6 +137,24 @@ export interface StreamStats {
   sample_rate: number;
   /** Whether the stream has dual channels (true) or is mono (false) */
   dual_channel: boolean;
+  /** Number of frames the stream buffers for its consumers */
+  buffer_capacity: number;
+  /** Frames queued for the slowest consumer after the last publish */
+  buffer_fill: number;
+  /** Fill level of the buffer averaged over the recent frames, from 0.0 to 1.0 */
+  average_fill_ratio: number;
+  /** Estimated delay in milliseconds between the capture of a frame and its read by the slowest consumer */
+  estimated_latency_ms: number;
+  /** Frames published while the buffer was full */
+  overruns: number;
+  /** Reads for which a consumer waited longer than two frame durations */
+  underruns: number;
+  /** Share of underruns among the recent reads, from 0.0 to 1.0 */
+  underrun_ratio: number;
+  /** The buffer is chronically near full: the producer outpaces the slowest consumer */
+  near_full: boolean;
+  /** The consumers chronically wait for frames: the producer is late */
+  near_empty: boolean;
 }
 
 /**
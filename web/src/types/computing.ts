@@ -22,6 +22,39 @@ export interface PeakResultResponse {
   timestamp: string; // unix timestamp format
 }
 
+/**
+ * Time delay between the channels from a cross-correlation node
+ *
+ * A positive lag means that channel B lags behind channel A.
+ */
+export interface TimeDelayResultResponse {
+  /** Lag in samples, fractional when interpolated */
+  lag_samples: number;
+
+  /** Lag in seconds */
+  lag_seconds: number;
+
+  /** Normalized cross-correlation at the peak (-1 to 1) */
+  peak_correlation: number;
+
+  /** Timestamp when this result was generated */
+  timestamp: string; // unix timestamp format
+}
+
+/**
+ * Concentration from a concentration or Kalman concentration node
+ */
+export interface ConcentrationResultResponse {
+  /** Concentration in parts per million */
+  concentration_ppm: number;
+
+  /** Variance of the concentration in ppm², set by filtering nodes (optional) */
+  concentration_variance?: number | null;
+
+  /** Timestamp when this result was generated */
+  timestamp: string; // unix timestamp format
+}
+
 /**
  * Complete computing response from the API
  *
@@ -33,6 +66,12 @@ export interface ComputingResponse {
   /** Peak results from multiple nodes, keyed by node ID */
   peak_results: Record<string, PeakResultResponse>;
 
+  /** Time delays between the channels from cross-correlation nodes, keyed by node ID */
+  time_delay_results?: Record<string, TimeDelayResultResponse>;
+
+  /** Concentrations from concentration nodes, keyed by node ID */
+  concentration_results?: Record<string, ConcentrationResultResponse>;
+
   /** Legacy fields for backward compatibility */
 
   /** Legacy peak frequency field */
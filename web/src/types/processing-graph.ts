@@ -25,6 +25,9 @@ export interface NodeStatistics {
 
   /** Maximum processing time observed in nanoseconds */
   worst_processing_time: number;
+
+  /** Number of calls interrupted by the node's execution timeout (Python nodes) */
+  timeouts?: number;
 }
 
 /**
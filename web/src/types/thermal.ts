@@ -13,6 +13,8 @@ export interface PidComponents {
   integral: number;
   /** Derivative term value */
   derivative: number;
+  /** Feedforward term value */
+  feedforward: number;
   /** Error value (setpoint - process_variable) */
   error: number;
 }
@@ -49,6 +51,70 @@ export interface CurrentTemperatureInfo {
   status: string;
 }
 
+/**
+ * Allowed setpoint range for a regulator
+ */
+export interface SetpointLimits {
+  /** Lowest accepted setpoint in degrees Celsius */
+  min_celsius: number;
+  /** Highest accepted setpoint in degrees Celsius */
+  max_celsius: number;
+}
+
+/**
+ * Current state of a thermal regulator
+ */
+export interface RegulatorState {
+  /** Regulator identifier */
+  id: string;
+  /** Human-readable name of the regulator */
+  name: string;
+  /** Whether the regulator is enabled in the configuration */
+  enabled: boolean;
+  /** Current status of the regulator */
+  status: string;
+  /** Last measured temperature in degrees Celsius, null before the first cycle */
+  temperature_celsius: number | null;
+  /** Current setpoint in degrees Celsius */
+  setpoint_celsius: number;
+  /** Last control output in percent, null before the first cycle */
+  control_output_percent: number | null;
+  /** Error message while the regulator is in error or shut down */
+  fault: string | null;
+  /** Allowed range for the setpoint */
+  setpoint_limits: SetpointLimits | null;
+  /** Last update timestamp (Unix seconds) */
+  last_update: number;
+}
+
+/**
+ * Telemetry of a regulator pushed by the `/api/stream/thermal` event stream
+ */
+export interface ThermalTelemetryEvent {
+  /** Regulator identifier */
+  regulator_id: string;
+  /** Number of regulation cycles recorded so far; a gap means coalesced cycles */
+  sample: number;
+  /** Timestamp of the regulation cycle (Unix seconds) */
+  timestamp: number;
+  /** Measured temperature in degrees Celsius */
+  temperature_celsius: number;
+  /** Setpoint in degrees Celsius */
+  setpoint_celsius: number;
+  /** Control output in percent */
+  control_output_percent: number;
+  /** Current status of the regulator */
+  status: string;
+}
+
+/**
+ * Setpoint change request
+ */
+export interface SetpointRequest {
+  /** New setpoint in degrees Celsius */
+  setpoint_celsius: number;
+}
+
 /**
  * Pagination information for thermal data responses
  */